@@ -19,9 +19,11 @@ import kotlinx.coroutines.flow.asStateFlow
 import kotlinx.coroutines.launch
 import kotlinx.coroutines.withContext
 import uniffi.paperback.ConfigManagerFfi
+import uniffi.paperback.DocumentException
 import uniffi.paperback.DocumentSession
 import uniffi.paperback.HeadingTreeFfi
 import uniffi.paperback.LinkListFfi
+import uniffi.paperback.ParserErrorCode
 import uniffi.paperback.SegmentDirectionFfi
 import uniffi.paperback.SegmentTypeFfi
 import java.io.File
@@ -449,7 +451,7 @@ class MainScreenViewModel(
 				val docKey = config.getDocKey(uriString)
 				val savedPosition = config.getDocumentPosition(uriString)
 				val password = providedPassword ?: config.getDocumentPassword(uriString)
-				val session = DocumentSession.newFfi(absolutePath, password, "", false)
+				val session = DocumentSession.newFfi(absolutePath, password, "", false, true, true, false)
 				if (providedPassword != null) {
 					config.setDocumentPassword(uriString, providedPassword)
 					config.flush()
@@ -472,8 +474,7 @@ class MainScreenViewModel(
 					savedPosition = savedPosition
 				)
 			} catch (e: Exception) {
-				val msg = e.message ?: ""
-				if (msg.contains("[password_required]")) {
+				if (e is DocumentException.ParseException && e.code == ParserErrorCode.PASSWORD_REQUIRED) {
 					withContext(Dispatchers.Main) {
 						_passwordPromptUri.value = uri
 					}
@@ -60,6 +60,10 @@ fun getSegmentTypeName(type: SegmentTypeFfi): String =
 		SegmentTypeFfi.IMAGE -> t("Image")
 		// TRANSLATORS: Name of the "figure" reading/navigation unit
 		SegmentTypeFfi.FIGURE -> t("Figure")
+		// TRANSLATORS: Name of the "block quote" reading/navigation unit
+		SegmentTypeFfi.BLOCK_QUOTE -> t("Block Quote")
+		// TRANSLATORS: Name of the "code block" reading/navigation unit
+		SegmentTypeFfi.CODE_BLOCK -> t("Code Block")
 	}
 
 @OptIn(ExperimentalMaterial3Api::class)
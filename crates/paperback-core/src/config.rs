@@ -1,39 +1,273 @@
 use std::{
 	cell::{Cell, RefCell},
 	cmp::Ordering,
-	collections::HashMap,
-	fs,
+	collections::{BTreeMap, HashMap, HashSet},
+	ffi::OsString,
+	fs, io, mem,
 	path::{Path, PathBuf},
+	thread,
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 
-use crate::types::DocumentListItem;
+use crate::{
+	document::{LineLayout, TextDirection},
+	parser::clipboard::is_clipboard_path,
+	types::{DocumentListItem, DocumentSort, DocumentSortKey},
+};
 
-const CONFIG_VERSION: u32 = 4;
+const CONFIG_VERSION: u32 = 5;
+/// Version of the Backup All Data format, raised when a change would make older versions misread it.
+const BACKUP_VERSION: u32 = 1;
 const DEFAULT_RECENT_DOCUMENTS_TO_SHOW: i64 = 25;
 const MAX_RECENT_DOCUMENTS_TO_SHOW: usize = 100;
-
-#[derive(Clone, Debug, Default)]
+/// Extra attempts at writing the config file before falling back to the pending file, waiting
+/// twice as long before each one.
+const FLUSH_RETRIES: u32 = 2;
+const FLUSH_RETRY_DELAY: Duration = Duration::from_millis(100);
+/// Document sections kept before startup prunes the least recently used ones holding nothing worth
+/// keeping; overridden by the "max_document_sections" app setting, 0 turning pruning off.
+const DEFAULT_MAX_DOCUMENT_SECTIONS: i32 = 2000;
+/// Days since a document was last read or changed before a section with a saved position may be
+/// pruned because its file is gone.
+const MISSING_DOCUMENT_PRUNE_DAYS: i64 = 30;
+/// App setting holding the path of the document being parsed, left behind if the parser crashes.
+const RESTORING_KEY: &str = "restoring";
+/// App settings about this machine or this run rather than the reader's preferences, which Backup
+/// All Data leaves out and restoring a backup never overwrites.
+const MACHINE_LOCAL_APP_KEYS: [&str; 6] =
+	["session_running", "active_document", "library_folder", "dictionary_path", "update_channel", RESTORING_KEY];
+
+#[derive(Serialize, Clone, Debug, Default)]
 pub struct Bookmark {
 	pub start: i64,
 	pub end: i64,
 	pub note: String,
+	/// User-chosen kind of annotation (e.g. "Quote", "To-do"); empty when uncategorized.
+	pub category: String,
+}
+
+/// Accumulated reading activity for a document.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReadingStats {
+	/// Total time the document was read while focused, excluding idle periods.
+	#[serde(default)]
+	pub seconds_read: u64,
+	/// Number of times the document was opened.
+	#[serde(default)]
+	pub sessions: u32,
+	/// Unix timestamp (seconds) of the last time the document was read, or 0 if never.
+	#[serde(default)]
+	pub last_read: i64,
+}
+
+impl ReadingStats {
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.seconds_read == 0 && self.sessions == 0 && self.last_read == 0
+	}
+
+	/// The UTC date of `last_read` formatted as `YYYY-MM-DD`, or `None` if the document was never read.
+	#[must_use]
+	pub fn last_read_date(&self) -> Option<String> {
+		if self.last_read <= 0 {
+			return None;
+		}
+		let (year, month, day) = utc_date(self.last_read);
+		Some(format!("{year:04}-{month:02}-{day:02}"))
+	}
+}
+
+/// The UTC `(year, month, day)` of a Unix timestamp in seconds.
+pub(crate) fn utc_date(seconds: i64) -> (i64, i64, i64) {
+	// Civil-from-days conversion (proleptic Gregorian calendar), see
+	// https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+	let days = seconds.div_euclid(86_400) + 719_468;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days.rem_euclid(146_097);
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+	let year = year_of_era + era * 400 + i64::from(month <= 2);
+	(year, month, day)
+}
+
+/// Writes `contents` to `path`, trying again up to `retries` times with a growing pause in between.
+fn write_with_retries(path: &Path, contents: &str, retries: u32) -> io::Result<()> {
+	let mut delay = FLUSH_RETRY_DELAY;
+	let mut attempt = 0;
+	loop {
+		match fs::write(path, contents) {
+			Err(_) if attempt < retries => {
+				thread::sleep(delay);
+				delay *= 2;
+				attempt += 1;
+			}
+			result => return result,
+		}
+	}
+}
+
+/// Folds the changes saved to the pending file back into the config. Each document keeps whichever
+/// copy was changed last (the pending one on a tie); app settings, recent documents and the rest
+/// come from whichever file was written last.
+fn merge_pending(mut config: ConfigData, pending: ConfigData, pending_is_newer: bool) -> ConfigData {
+	for (key, doc) in pending.documents {
+		match config.documents.get(&key) {
+			Some(existing) if existing.modified > doc.modified => {}
+			_ => {
+				config.documents.insert(key, doc);
+			}
+		}
+	}
+	for (path, key) in pending.path_hashes {
+		config.path_hashes.entry(path).or_insert(key);
+	}
+	if pending_is_newer {
+		config.app = pending.app;
+		config.recent_documents = pending.recent_documents;
+		config.opened_documents = pending.opened_documents;
+		config.find_history = pending.find_history;
+		config.profiles = pending.profiles;
+		config.pronunciation_rules = pending.pronunciation_rules;
+	}
+	config
+}
+
+/// Keys of the document sections that hold nothing worth keeping, least recently used first: the
+/// document isn't open or in the recent list, has no bookmarks, notes, quick slots or pronunciation
+/// rules, and either was never read past the start or its file has been gone for a while.
+fn prunable_document_keys(data: &ConfigData, now: i64) -> Vec<String> {
+	let listed_paths: HashSet<&str> =
+		data.recent_documents.iter().chain(&data.opened_documents).map(String::as_str).collect();
+	let listed_keys: HashSet<&str> =
+		listed_paths.iter().filter_map(|path| data.path_hashes.get(*path)).map(String::as_str).collect();
+	let mut prunable: Vec<(i64, &String)> = data
+		.documents
+		.iter()
+		.filter(|(key, doc)| {
+			if doc.opened
+				|| !doc.bookmarks.is_empty()
+				|| !doc.pronunciation_rules.is_empty()
+				|| !doc.notes.is_empty()
+				|| !doc.quick_slots.is_empty()
+				|| listed_keys.contains(key.as_str())
+				|| listed_paths.contains(doc.path.as_str())
+			{
+				return false;
+			}
+			let idle_days = (now - doc.modified.max(doc.reading_stats.last_read)) / 86_400;
+			doc.last_position == 0 || (idle_days > MISSING_DOCUMENT_PRUNE_DAYS && document_is_missing(&doc.path))
+		})
+		.map(|(key, doc)| (doc.modified.max(doc.reading_stats.last_read), key))
+		.collect();
+	prunable.sort();
+	prunable.into_iter().map(|(_, key)| key.clone()).collect()
+}
+
+/// Whether a document's file is gone. Pasted clipboard text never outlives its session; other
+/// pseudo-paths, such as web addresses, can be opened again and count as present.
+fn document_is_missing(path: &str) -> bool {
+	is_clipboard_path(path) || (!path.contains("://") && !Path::new(path).exists())
+}
+
+/// Drops the given document sections along with the path hashes pointing at them.
+fn remove_document_sections(data: &mut ConfigData, keys: &[String]) {
+	let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+	data.documents.retain(|key, _| !keys.contains(key.as_str()));
+	data.path_hashes.retain(|_, key| !keys.contains(key.as_str()));
+}
+
+fn unix_now() -> i64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| i64::try_from(elapsed.as_secs()).unwrap_or(0))
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct NavigationHistory {
-	pub positions: Vec<i64>,
+	pub entries: Vec<SavedHistoryEntry>,
 	pub index: usize,
 }
 
+/// A navigation history entry as saved between sessions: its offset, where that fell in the
+/// document as a percentage, and the text starting there. If the document changes before it's
+/// reopened, the text finds the entry again, and the percentage stands in when it can't.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(from = "SavedHistoryEntryFormat")]
+pub struct SavedHistoryEntry {
+	pub offset: i64,
+	pub percent: f64,
+	/// Empty for entries saved before anchors were, which are restored at their offset.
+	pub anchor: String,
+}
+
+impl SavedHistoryEntry {
+	/// An entry from a history saved as bare offsets.
+	#[must_use]
+	pub const fn from_offset(offset: i64) -> Self {
+		Self { offset, percent: 0.0, anchor: String::new() }
+	}
+}
+
+/// The saved forms of a history entry: version 1 wrote a bare offset, version 2 an object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedHistoryEntryFormat {
+	V1(i64),
+	V2 {
+		offset: i64,
+		#[serde(default)]
+		percent: f64,
+		#[serde(default)]
+		anchor: String,
+	},
+}
+
+impl From<SavedHistoryEntryFormat> for SavedHistoryEntry {
+	fn from(format: SavedHistoryEntryFormat) -> Self {
+		match format {
+			SavedHistoryEntryFormat::V1(offset) => Self::from_offset(offset),
+			SavedHistoryEntryFormat::V2 { offset, percent, anchor } => Self { offset, percent, anchor },
+		}
+	}
+}
+
+/// Outcome of comparing a document's local state with its `.paperback` sidecar when auto-syncing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SidecarSync {
+	/// No sidecar, or neither side changed since the last sync.
+	UpToDate,
+	/// Only the sidecar changed; import it.
+	SidecarNewer,
+	/// Only the local copy changed; the next sync writes it out.
+	LocalNewer,
+	/// Both changed since the last sync, so the user has to pick one.
+	Conflict { local_position: i64, sidecar_position: i64 },
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct FindSettings {
 	pub match_case: bool,
 	pub whole_word: bool,
 	pub use_regex: bool,
+	pub all_documents: bool,
+	/// Whether every match is highlighted once Find lands on one.
+	pub highlight_all: bool,
+	pub scope: FindScope,
+}
+
+/// What Find looks through, chosen with "Search in" in the Find dialog.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FindScope {
+	/// The document's text.
+	#[default]
+	Text,
+	/// The text of headings and table of contents entries, to find a chapter by its title.
+	Headings,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -79,6 +313,24 @@ pub struct StoredBookmark {
 	pub end: i64,
 	#[serde(default)]
 	pub note: String,
+	/// Absent in configs and sidecars written before version 5, which read back as uncategorized.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub category: String,
+}
+
+/// A replacement applied to text before it's spoken, such as "et al." read as "and others". See
+/// [`PronunciationRules`](crate::reader_core::pronunciation::PronunciationRules).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PronunciationRule {
+	pub pattern: String,
+	#[serde(default)]
+	pub replacement: String,
+	/// Whether `pattern` is a regular expression; `replacement` may then use `$1` and so on.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub regex: bool,
+	/// Only match where the pattern isn't part of a longer word.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub whole_word: bool,
 }
 
 #[allow(clippy::struct_excessive_bools)]
@@ -164,6 +416,10 @@ pub struct AppSettings {
 	pub line_spacing: i64,
 	#[serde(default)]
 	pub hotkey: HotkeyConfig,
+	/// User-assigned menu shortcuts, keyed by menu command ID. An empty value removes
+	/// the default shortcut; commands that aren't listed keep their built-in one.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub shortcuts: BTreeMap<String, String>,
 	/// Pass-through storage for host-specific settings (e.g. desktop UI preferences).
 	/// Keys written here are preserved on read/write so host consumers can store their
 	/// own fields alongside the generic ones without conflict.
@@ -197,6 +453,7 @@ impl Default for AppSettings {
 			paragraph_spacing: 0,
 			line_spacing: 0,
 			hotkey: HotkeyConfig::default(),
+			shortcuts: BTreeMap::new(),
 			extra: HashMap::new(),
 		}
 	}
@@ -207,18 +464,115 @@ pub struct DocumentConfig {
 	pub path: String,
 	#[serde(default)]
 	pub last_position: i64,
+	/// Furthest the reader has got in the document, which going back to re-read doesn't lower.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub furthest_position: i64,
+	/// Offset of the first character in view when the document was last closed, so the caret comes
+	/// back at the same place in the window. An offset rather than a line, since wrapping can change.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub view_top: i64,
 	#[serde(default)]
-	pub navigation_history: Vec<i64>,
+	pub navigation_history: Vec<SavedHistoryEntry>,
 	#[serde(default)]
 	pub navigation_history_index: usize,
 	#[serde(default)]
 	pub bookmarks: Vec<StoredBookmark>,
+	/// Positions saved in the ten quick slots, keyed `quick_slot_0` to `quick_slot_9`. Unlike
+	/// bookmarks they have no range or note and are overwritten without asking.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub quick_slots: BTreeMap<String, i64>,
 	#[serde(default, skip_serializing_if = "String::is_empty")]
 	pub format: String,
+	/// Entry opened last time when the document is an archive holding several readable files.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub archive_entry: String,
 	#[serde(default, skip_serializing_if = "String::is_empty")]
 	pub password: String,
+	/// Encoding chosen for a plain text file, by its WHATWG label; empty to detect it.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub encoding: String,
 	#[serde(default)]
 	pub opened: bool,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub language: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub language_override: String,
+	/// Text direction the reader chose for the document ("ltr" or "rtl"); empty to detect it.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub text_direction: String,
+	/// Line layout the reader chose ("wrap" or "preformatted"); empty to detect it.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub line_layout: String,
+	#[serde(default)]
+	pub disable_column_detection: bool,
+	/// Whether headings are no longer guessed from font sizes in a PDF without an outline.
+	#[serde(default)]
+	pub disable_heading_detection: bool,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub pronunciation_rules: Vec<PronunciationRule>,
+	/// Free-form notes on the whole document, kept like the inside cover of a paperback.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub notes: String,
+	/// Whether running headers and footers are removed, when the reader chose; unset leaves it to
+	/// the format.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub strip_running_lines: Option<bool>,
+	#[serde(default, skip_serializing_if = "ReadingStats::is_empty")]
+	pub reading_stats: ReadingStats,
+	/// Title and author as of the last open, shown in the All Documents list.
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub title: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub author: String,
+	/// Length of the document text when it was last opened, to tell whether a saved position still
+	/// points at the same place.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub content_length: i64,
+	/// Text at `last_position`, to find it again when a parser change shifts the offsets.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub position_anchor: Option<PositionAnchor>,
+	/// Size of the file in bytes when it was last opened, to recognize it after it moves.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub file_size: i64,
+	/// Unix time of the last change to the position or bookmarks.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub modified: i64,
+	/// `modified` as of the last sidecar sync, so a change on both machines can be told apart from one.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub synced: i64,
+	/// Unix time of the last change to anything in the section, which restoring a backup goes by.
+	#[serde(default, skip_serializing_if = "is_zero")]
+	pub updated: i64,
+}
+
+impl DocumentConfig {
+	/// When the section last changed. Sections saved before `updated` was kept fall back to `modified`.
+	#[must_use]
+	pub fn last_changed(&self) -> i64 {
+		self.updated.max(self.modified)
+	}
+}
+
+/// The text a saved position points at and how far into the document it was, so the position
+/// survives the document being extracted slightly differently, see
+/// [`crate::reader_core::resolve_position_anchor`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PositionAnchor {
+	/// The first characters of text from the position on.
+	#[serde(default)]
+	pub snippet: String,
+	/// Percentage of the document's characters before the position.
+	#[serde(default)]
+	pub percent: f64,
+}
+
+fn quick_slot_key(slot: u8) -> String {
+	format!("quick_slot_{slot}")
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero(value: &i64) -> bool {
+	*value == 0
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -226,9 +580,36 @@ struct SidecarData {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	last_position: Option<i64>,
 	#[serde(default, skip_serializing_if = "Option::is_none")]
+	furthest_position: Option<i64>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
 	format: Option<String>,
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	bookmarks: Vec<StoredBookmark>,
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	quick_slots: BTreeMap<String, i64>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pronunciation_rules: Vec<PronunciationRule>,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	notes: String,
+	/// Absent in sidecars written before auto-sync; treated as older than any local change.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	modified: Option<i64>,
+}
+
+/// A named snapshot of the settings that change how a document reads, switched from the Tools menu.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReadingProfile {
+	#[serde(default)]
+	pub word_wrap: bool,
+	/// Reading font size in points; 0 keeps the platform default size.
+	#[serde(default)]
+	pub font_point_size: i32,
+	#[serde(default)]
+	pub navigation_wrap: bool,
+	#[serde(default)]
+	pub context_announcements: bool,
+	#[serde(default)]
+	pub normalize_typography: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -246,6 +627,11 @@ pub struct ConfigData {
 	pub documents: HashMap<String, DocumentConfig>,
 	#[serde(default)]
 	pub path_hashes: HashMap<String, String>,
+	#[serde(default)]
+	pub profiles: BTreeMap<String, ReadingProfile>,
+	/// Rules for every document; a document's own rules take precedence.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub pronunciation_rules: Vec<PronunciationRule>,
 }
 
 impl Default for ConfigData {
@@ -258,15 +644,106 @@ impl Default for ConfigData {
 			find_history: Vec::new(),
 			documents: HashMap::new(),
 			path_hashes: HashMap::new(),
+			profiles: BTreeMap::new(),
+			pronunciation_rules: Vec::new(),
 		}
 	}
 }
 
+/// Everything Backup All Data saves: every document section and the settings that aren't tied to
+/// one machine, written as JSON so it doesn't depend on the config file's format.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct BackupData {
+	version: u32,
+	#[serde(default)]
+	app: AppSettings,
+	#[serde(default)]
+	profiles: BTreeMap<String, ReadingProfile>,
+	#[serde(default)]
+	pronunciation_rules: Vec<PronunciationRule>,
+	/// Document sections keyed by the document's path rather than its hash, which depends on the file
+	/// and is worked out again on the machine restoring it.
+	#[serde(default)]
+	documents: BTreeMap<String, DocumentConfig>,
+}
+
+/// How many document sections restoring a backup took, and how many it left alone because the
+/// section here had changed more recently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RestoreSummary {
+	pub restored: usize,
+	pub kept: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+	#[error("{0}")]
+	Io(#[from] io::Error),
+	#[error("not a Paperback backup: {0}")]
+	Invalid(#[from] serde_json::Error),
+	/// Written by a newer version of Paperback, in a format this one doesn't know.
+	#[error("unsupported backup version {0}")]
+	UnsupportedVersion(u32),
+}
+
+/// Merges a backup into the config. A document keeps whichever copy changed last, the one here on a
+/// tie; app settings from the backup are laid over the ones here, keeping this machine's own, and
+/// reading profiles and global pronunciation rules are added.
+fn merge_backup(data: &mut ConfigData, backup: BackupData, keys: &HashMap<String, String>) -> RestoreSummary {
+	let mut summary = RestoreSummary::default();
+	for (path, mut doc) in backup.documents {
+		let Some(key) = keys.get(&path) else { continue };
+		let existing = data.documents.get(key);
+		if existing.is_some_and(|existing| existing.last_changed() >= doc.last_changed()) {
+			summary.kept += 1;
+			continue;
+		}
+		// Whether it's open and when it last synced with its sidecar belong to this machine.
+		doc.opened = existing.is_some_and(|existing| existing.opened);
+		doc.synced = existing.map_or(0, |existing| existing.synced);
+		doc.path.clone_from(&path);
+		data.documents.insert(key.clone(), doc);
+		data.path_hashes.insert(path, key.clone());
+		summary.restored += 1;
+	}
+	let mut app = backup.app;
+	let mut extra = mem::take(&mut data.app.extra);
+	extra.extend(machine_independent(app.extra));
+	app.extra = extra;
+	let mut shortcuts = mem::take(&mut data.app.shortcuts);
+	shortcuts.append(&mut app.shortcuts);
+	app.shortcuts = shortcuts;
+	data.app = app;
+	data.profiles.extend(backup.profiles);
+	for rule in backup.pronunciation_rules {
+		if !data.pronunciation_rules.contains(&rule) {
+			data.pronunciation_rules.push(rule);
+		}
+	}
+	summary
+}
+
+/// `extra` app settings without the ones in [`MACHINE_LOCAL_APP_KEYS`].
+fn machine_independent(mut extra: HashMap<String, toml::Value>) -> HashMap<String, toml::Value> {
+	extra.retain(|key, _| !MACHINE_LOCAL_APP_KEYS.contains(&key.as_str()));
+	extra
+}
+
 pub struct ConfigManager {
 	data: RefCell<ConfigData>,
 	config_path: PathBuf,
 	dirty: Cell<bool>,
 	initialized: bool,
+	app_read_only: Cell<bool>,
+	/// Where changes go when the config file can't be written, e.g. on a share that went read-only.
+	pending_path: Option<PathBuf>,
+	/// Set once the pending file has been written, until the config file is writable again.
+	wrote_pending: Cell<bool>,
+	/// The pending file's path the first time it's used, until the UI takes it to warn the user.
+	fallback_notice: RefCell<Option<PathBuf>>,
+	/// Bumped on every change to any document's bookmarks, so copies kept for caret tracking know
+	/// when to refresh.
+	bookmarks_revision: Cell<u64>,
 }
 
 impl Default for ConfigManager {
@@ -283,11 +760,37 @@ impl ConfigManager {
 			config_path: PathBuf::new(),
 			dirty: Cell::new(false),
 			initialized: false,
+			app_read_only: Cell::new(false),
+			pending_path: None,
+			wrote_pending: Cell::new(false),
+			fallback_notice: RefCell::new(None),
+			bookmarks_revision: Cell::new(0),
 		}
 	}
 
+	/// Sets the file changes are saved to while the config file can't be written. Call before
+	/// [`Self::initialize`], which merges a pending file left by an earlier session.
+	pub fn set_pending_path(&mut self, path: PathBuf) {
+		self.pending_path = Some(path);
+	}
+
+	/// The pending file's path, once, after changes first had to be saved there instead of the
+	/// config file.
+	#[must_use]
+	pub fn bookmarks_revision(&self) -> u64 {
+		self.bookmarks_revision.get()
+	}
+
+	fn bookmarks_changed(&self) {
+		self.bookmarks_revision.set(self.bookmarks_revision.get().wrapping_add(1));
+	}
+
+	pub fn take_fallback_notice(&self) -> Option<PathBuf> {
+		self.fallback_notice.borrow_mut().take()
+	}
+
 	pub fn initialize(&mut self, config_path: PathBuf) -> bool {
-		let (data, needs_save) = if config_path.exists() {
+		let (mut data, mut needs_save) = if config_path.exists() {
 			match fs::read_to_string(&config_path).ok().and_then(|s| toml::from_str::<ConfigData>(&s).ok()) {
 				Some(d) => (d, false),
 				None => (ConfigData::default(), true),
@@ -295,10 +798,34 @@ impl ConfigManager {
 		} else {
 			(ConfigData::default(), true)
 		};
+		let pending = self.pending_path.as_deref().filter(|path| path.exists());
+		if let Some(pending_path) = pending {
+			match fs::read_to_string(pending_path).ok().and_then(|s| toml::from_str::<ConfigData>(&s).ok()) {
+				Some(pending_data) => {
+					let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+					let pending_is_newer = match (modified(pending_path), modified(&config_path)) {
+						(Some(pending_time), Some(config_time)) => pending_time >= config_time,
+						_ => true,
+					};
+					tracing::info!(path = %pending_path.display(), "merging changes saved while the config was read-only");
+					data = merge_pending(data, pending_data, pending_is_newer);
+					self.wrote_pending.set(true);
+					needs_save = true;
+				}
+				None => tracing::warn!(path = %pending_path.display(), "ignoring unreadable pending config"),
+			}
+		}
+		if data.version < CONFIG_VERSION {
+			// Older versions only lack fields that deserialize to their defaults (e.g. bookmark
+			// categories), so migrating is just stamping the current version.
+			data.version = CONFIG_VERSION;
+			needs_save = true;
+		}
 
 		self.config_path = config_path;
 		self.initialized = true;
 		*self.data.borrow_mut() = data;
+		needs_save |= self.enforce_document_section_limit();
 
 		if needs_save {
 			self.dirty.set(true);
@@ -308,6 +835,55 @@ impl ConfigManager {
 		true
 	}
 
+	/// Startup housekeeping: with more document sections than "max_document_sections", prunes the
+	/// least recently used of those holding nothing worth keeping until back under the limit.
+	/// Returns whether any were removed.
+	fn enforce_document_section_limit(&self) -> bool {
+		let limit =
+			usize::try_from(self.get_app_int("max_document_sections", DEFAULT_MAX_DOCUMENT_SECTIONS)).unwrap_or(0);
+		let mut data = self.data.borrow_mut();
+		if limit == 0 || data.documents.len() <= limit {
+			return false;
+		}
+		let excess = data.documents.len() - limit;
+		let mut keys = prunable_document_keys(&data, unix_now());
+		keys.truncate(excess);
+		if keys.is_empty() {
+			return false;
+		}
+		tracing::info!(count = keys.len(), "pruning unused document sections");
+		remove_document_sections(&mut data, &keys);
+		true
+	}
+
+	/// How many document sections [`Self::compact_documents`] would remove.
+	#[must_use]
+	pub fn compactable_document_count(&self) -> usize {
+		if !self.initialized {
+			return 0;
+		}
+		prunable_document_keys(&self.data.borrow(), unix_now()).len()
+	}
+
+	/// Removes every document section holding nothing worth keeping: ones not open or recent, with
+	/// no bookmarks, notes or pronunciation rules, and either never read past the start or for a
+	/// file gone for over a month. Returns how many were removed.
+	pub fn compact_documents(&self) -> usize {
+		if !self.initialized {
+			return 0;
+		}
+		let removed = {
+			let mut data = self.data.borrow_mut();
+			let keys = prunable_document_keys(&data, unix_now());
+			remove_document_sections(&mut data, &keys);
+			keys.len()
+		};
+		if removed > 0 {
+			self.dirty.set(true);
+		}
+		removed
+	}
+
 	pub fn refresh_document_hash(&self, path: &str) {
 		if !self.initialized {
 			return;
@@ -388,14 +964,74 @@ impl ConfigManager {
 		new_key
 	}
 
+	/// Keeps app-level data (settings, recent and opened documents, find history) as it is on disk
+	/// when flushing, for a second running instance that must not overwrite the first one's. Changes
+	/// still apply in memory, and per-document data is saved as usual.
+	pub fn set_app_read_only(&self, read_only: bool) {
+		self.app_read_only.set(read_only);
+	}
+
+	/// Whether this is a second instance keeping app-level data as the first one saved it.
+	#[must_use]
+	pub fn is_app_read_only(&self) -> bool {
+		self.app_read_only.get()
+	}
+
 	pub fn flush(&self) {
 		if !self.initialized || !self.dirty.get() {
 			return;
 		}
 		let data = self.data.borrow();
-		if let Ok(s) = toml::to_string_pretty(&*data) {
-			let _ = fs::write(&self.config_path, s);
-			self.dirty.set(false);
+		// A document the other instance changed since this one loaded it keeps the other's copy,
+		// unless this instance changed it later still.
+		let merged = self.app_read_only.get().then(|| {
+			let on_disk = fs::read_to_string(&self.config_path)
+				.ok()
+				.and_then(|s| toml::from_str::<ConfigData>(&s).ok())
+				.unwrap_or_default();
+			let mut merged = merge_pending(on_disk, data.clone(), false);
+			merged.version = data.version;
+			merged
+		});
+		let Ok(s) = toml::to_string_pretty(merged.as_ref().unwrap_or(&*data)) else {
+			return;
+		};
+		// Once changes are going to the pending file, the config file gets a single try per flush so a
+		// share that stays read-only doesn't stall the UI with retries every time.
+		let retries = if self.wrote_pending.get() { 0 } else { FLUSH_RETRIES };
+		match write_with_retries(&self.config_path, &s, retries) {
+			Ok(()) => {
+				self.dirty.set(false);
+				if self.wrote_pending.replace(false)
+					&& let Some(pending_path) = &self.pending_path
+				{
+					let _ = fs::remove_file(pending_path);
+				}
+			}
+			Err(error) => {
+				tracing::warn!(path = %self.config_path.display(), %error, "failed to save config");
+				self.write_pending(&s);
+			}
+		}
+	}
+
+	/// Saves the whole config to the pending file so the changes survive until the config file is
+	/// writable again. Stays dirty when that fails too, so the next flush tries again.
+	fn write_pending(&self, contents: &str) {
+		let Some(pending_path) = &self.pending_path else {
+			return;
+		};
+		if let Some(dir) = pending_path.parent() {
+			let _ = fs::create_dir_all(dir);
+		}
+		match fs::write(pending_path, contents) {
+			Ok(()) => {
+				self.dirty.set(false);
+				if !self.wrote_pending.replace(true) {
+					*self.fallback_notice.borrow_mut() = Some(pending_path.clone());
+				}
+			}
+			Err(error) => tracing::warn!(path = %pending_path.display(), %error, "failed to save pending config"),
 		}
 	}
 
@@ -494,6 +1130,68 @@ impl ConfigManager {
 		self.dirty.set(true);
 	}
 
+	/// Names of the saved reading profiles, in alphabetical order.
+	pub fn reading_profile_names(&self) -> Vec<String> {
+		if !self.initialized {
+			return Vec::new();
+		}
+		self.data.borrow().profiles.keys().cloned().collect()
+	}
+
+	/// The profile last switched to or saved, or an empty string when none is active.
+	pub fn active_reading_profile(&self) -> String {
+		self.get_app_string("active_reading_profile", "")
+	}
+
+	/// Snapshot of the current values of every setting a profile covers.
+	pub fn current_reading_profile(&self) -> ReadingProfile {
+		ReadingProfile {
+			word_wrap: self.get_app_bool("word_wrap", false),
+			font_point_size: self.get_readability_font().point_size,
+			navigation_wrap: self.get_app_bool("navigation_wrap", false),
+			context_announcements: self.get_app_bool("context_announcements", false),
+			normalize_typography: self.get_app_bool("normalize_typography", false),
+		}
+	}
+
+	/// Saves the current settings under `name`, replacing any profile with that name, and marks it active.
+	pub fn save_reading_profile(&self, name: &str) {
+		if !self.initialized {
+			return;
+		}
+		let profile = self.current_reading_profile();
+		self.data.borrow_mut().profiles.insert(name.to_string(), profile);
+		self.set_app_string("active_reading_profile", name);
+	}
+
+	pub fn delete_reading_profile(&self, name: &str) -> bool {
+		if !self.initialized || self.data.borrow_mut().profiles.remove(name).is_none() {
+			return false;
+		}
+		if self.active_reading_profile() == name {
+			self.set_app_string("active_reading_profile", "");
+		}
+		self.dirty.set(true);
+		true
+	}
+
+	/// Writes the settings stored in profile `name` and marks it active. Per-document data is untouched.
+	pub fn apply_reading_profile(&self, name: &str) -> Option<ReadingProfile> {
+		if !self.initialized {
+			return None;
+		}
+		let profile = self.data.borrow().profiles.get(name).cloned()?;
+		self.set_app_bool("word_wrap", profile.word_wrap);
+		self.set_app_bool("navigation_wrap", profile.navigation_wrap);
+		self.set_app_bool("context_announcements", profile.context_announcements);
+		self.set_app_bool("normalize_typography", profile.normalize_typography);
+		let mut font = self.get_readability_font();
+		font.point_size = profile.font_point_size;
+		self.set_readability_font(&font);
+		self.set_app_string("active_reading_profile", name);
+		Some(profile)
+	}
+
 	pub fn get_readability_font(&self) -> ReadabilityFont {
 		if !self.initialized {
 			return ReadabilityFont::default();
@@ -625,6 +1323,36 @@ impl ConfigManager {
 		self.dirty.set(true);
 	}
 
+	pub fn get_shortcuts(&self) -> BTreeMap<String, String> {
+		if !self.initialized {
+			return BTreeMap::new();
+		}
+		self.data.borrow().app.shortcuts.clone()
+	}
+
+	pub fn set_shortcuts(&self, shortcuts: &BTreeMap<String, String>) {
+		if !self.initialized {
+			return;
+		}
+		self.data.borrow_mut().app.shortcuts.clone_from(shortcuts);
+		self.dirty.set(true);
+	}
+
+	pub fn get_global_pronunciation_rules(&self) -> Vec<PronunciationRule> {
+		if !self.initialized {
+			return Vec::new();
+		}
+		self.data.borrow().pronunciation_rules.clone()
+	}
+
+	pub fn set_global_pronunciation_rules(&self, rules: &[PronunciationRule]) {
+		if !self.initialized {
+			return;
+		}
+		self.data.borrow_mut().pronunciation_rules = rules.to_vec();
+		self.dirty.set(true);
+	}
+
 	pub fn add_recent_document(&self, path: &str) {
 		if !self.initialized {
 			return;
@@ -684,8 +1412,41 @@ impl ConfigManager {
 		self.data.borrow().opened_documents.clone()
 	}
 
+	/// The open documents to restore at startup. A document whose parse never finished is left out
+	/// until the user decides what to do with it.
 	pub fn get_opened_documents_existing(&self) -> Vec<String> {
-		self.get_opened_documents().into_iter().filter(|path| Path::new(path).exists()).collect()
+		let unfinished = self.unfinished_document();
+		// Clipboard documents only live as long as the session that pasted them.
+		self.get_opened_documents()
+			.into_iter()
+			.filter(|path| !is_clipboard_path(path) && Path::new(path).exists())
+			.filter(|path| unfinished.as_ref() != Some(path))
+			.collect()
+	}
+
+	/// Records that `path` is about to be parsed and saves it straight away, so a parser crash leaves
+	/// the marker behind for the next start to find.
+	pub fn begin_parsing_document(&self, path: &str) {
+		self.set_app_string(RESTORING_KEY, path);
+		self.flush();
+	}
+
+	/// Clears the marker left by [`Self::begin_parsing_document`] once parsing is over, whether or not
+	/// the document opened.
+	pub fn finish_parsing_document(&self) {
+		if !self.initialized {
+			return;
+		}
+		if self.data.borrow_mut().app.extra.remove(RESTORING_KEY).is_some() {
+			self.dirty.set(true);
+			self.flush();
+		}
+	}
+
+	/// The document a previous run started parsing but never finished, most likely because the parser
+	/// crashed on it.
+	pub fn unfinished_document(&self) -> Option<String> {
+		Some(self.get_app_string(RESTORING_KEY, "")).filter(|path| !path.is_empty())
 	}
 
 	pub fn get_find_settings(&self) -> FindSettings {
@@ -693,6 +1454,12 @@ impl ConfigManager {
 			match_case: self.get_app_bool("find_match_case", false),
 			whole_word: self.get_app_bool("find_whole_word", false),
 			use_regex: self.get_app_bool("find_use_regex", false),
+			all_documents: self.get_app_bool("find_all_documents", false),
+			highlight_all: self.get_app_bool("find_highlight_all", false),
+			scope: match self.get_app_string("find_scope", "text").as_str() {
+				"headings" => FindScope::Headings,
+				_ => FindScope::Text,
+			},
 		}
 	}
 
@@ -700,6 +1467,13 @@ impl ConfigManager {
 		self.set_app_bool("find_match_case", settings.match_case);
 		self.set_app_bool("find_whole_word", settings.whole_word);
 		self.set_app_bool("find_use_regex", settings.use_regex);
+		self.set_app_bool("find_all_documents", settings.all_documents);
+		self.set_app_bool("find_highlight_all", settings.highlight_all);
+		let scope = match settings.scope {
+			FindScope::Text => "text",
+			FindScope::Headings => "headings",
+		};
+		self.set_app_string("find_scope", scope);
 	}
 
 	pub fn get_find_history(&self) -> Vec<String> {
@@ -737,27 +1511,45 @@ impl ConfigManager {
 		{
 			let key = self.get_doc_key(path);
 			let mut data = self.data.borrow_mut();
-			Self::doc_entry_mut(&mut data, key, path).last_position = position;
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if doc.last_position != position {
+				doc.last_position = position;
+				doc.position_anchor = None;
+				doc.modified = unix_now();
+			}
 		}
 		self.dirty.set(true);
 	}
 
-	#[must_use]
-	pub fn get_document_position(&self, path: &str) -> i64 {
+	/// Remembers which part of the document was in view; see [`DocumentConfig::view_top`].
+	pub fn set_document_view_top(&self, path: &str, position: i64) {
 		if !self.initialized {
-			return 0;
+			return;
 		}
-		let key = self.get_doc_key(path);
-		self.data.borrow().documents.get(&key).map_or(0, |d| d.last_position)
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if doc.view_top == position {
+				return;
+			}
+			doc.view_top = position;
+		}
+		self.dirty.set(true);
 	}
 
 	#[must_use]
-	pub fn get_validated_document_position(&self, path: &str, max_position: i64) -> i64 {
-		let saved = self.get_document_position(path);
-		if saved > 0 && saved <= max_position { saved } else { -1 }
+	pub fn get_document_view_top(&self, path: &str) -> i64 {
+		if !self.initialized {
+			return 0;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map_or(0, |d| d.view_top)
 	}
 
-	pub fn set_navigation_history(&self, path: &str, history: &[i64], history_index: usize) {
+	/// Records how far the reader has got. Only ever moves forward; see
+	/// [`crate::session::DocumentSession::track_furthest`] for when a position counts as read.
+	pub fn set_document_furthest_position(&self, path: &str, position: i64) {
 		if !self.initialized {
 			return;
 		}
@@ -765,20 +1557,195 @@ impl ConfigManager {
 			let key = self.get_doc_key(path);
 			let mut data = self.data.borrow_mut();
 			let doc = Self::doc_entry_mut(&mut data, key, path);
-			doc.navigation_history = history.to_vec();
-			doc.navigation_history_index = history_index;
+			if position <= doc.furthest_position {
+				return;
+			}
+			doc.furthest_position = position;
+			doc.modified = unix_now();
 		}
 		self.dirty.set(true);
 	}
 
-	pub fn get_navigation_history(&self, path: &str) -> NavigationHistory {
-		let mut nav = NavigationHistory::default();
+	/// The furthest position read, which is at least the saved position, so documents read before it
+	/// was tracked start from where they were left.
+	#[must_use]
+	pub fn get_document_furthest_position(&self, path: &str) -> i64 {
+		if !self.initialized {
+			return 0;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map_or(0, |d| d.furthest_position.max(d.last_position))
+	}
+
+	/// How far through the document the furthest position read is as a percentage, `None` until its
+	/// length has been recorded.
+	#[must_use]
+	pub fn get_document_furthest_percent(&self, path: &str) -> Option<i32> {
+		if !self.initialized {
+			return None;
+		}
+		let key = self.get_doc_key(path);
+		let data = self.data.borrow();
+		let doc = data.documents.get(&key).filter(|doc| doc.content_length > 0)?;
+		let furthest = doc.furthest_position.max(doc.last_position).clamp(0, doc.content_length);
+		Some(i32::try_from(furthest * 100 / doc.content_length).unwrap_or(100))
+	}
+
+	/// Stores the text at the saved position. Call it after [`Self::set_document_position`], which
+	/// forgets the anchor whenever the position changes.
+	pub fn set_document_anchor(&self, path: &str, anchor: PositionAnchor) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if doc.position_anchor.as_ref() == Some(&anchor) {
+				return;
+			}
+			doc.position_anchor = Some(anchor);
+		}
+		self.dirty.set(true);
+	}
+
+	/// The anchor of the saved position; `None` for positions saved before anchors existed.
+	#[must_use]
+	pub fn get_document_anchor(&self, path: &str) -> Option<PositionAnchor> {
+		if !self.initialized {
+			return None;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).and_then(|doc| doc.position_anchor.clone())
+	}
+
+	#[must_use]
+	pub fn get_document_position(&self, path: &str) -> i64 {
+		if !self.initialized {
+			return 0;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map_or(0, |d| d.last_position)
+	}
+
+	/// Where reading stopped last time, clamped to `content_length`, and whether the document's length
+	/// has changed since then so the spot may have shifted. `None` when no position was saved.
+	#[must_use]
+	pub fn get_last_read_position(&self, path: &str, content_length: i64) -> Option<(i64, bool)> {
+		if !self.initialized {
+			return None;
+		}
+		let key = self.get_doc_key(path);
+		let data = self.data.borrow();
+		let doc = data.documents.get(&key)?;
+		if doc.last_position <= 0 {
+			return None;
+		}
+		let approximate = doc.content_length != 0 && doc.content_length != content_length;
+		Some((doc.last_position.min(content_length), approximate))
+	}
+
+	pub fn set_document_content_length(&self, path: &str, content_length: i64) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let entry = Self::doc_entry_mut(&mut data, key, path);
+			if entry.content_length == content_length {
+				return;
+			}
+			entry.content_length = content_length;
+		}
+		self.dirty.set(true);
+	}
+
+	/// Records the title and author the parser found, for the All Documents list. Only marks the
+	/// config dirty when they changed since the last open.
+	pub fn set_document_metadata(&self, path: &str, title: &str, author: &str) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let entry = Self::doc_entry_mut(&mut data, key, path);
+			if entry.title == title && entry.author == author {
+				return;
+			}
+			entry.title = title.to_string();
+			entry.author = author.to_string();
+		}
+		self.dirty.set(true);
+	}
+
+	/// The stored title and author, and how far through the document the saved position is as a
+	/// percentage (`None` until its length has been recorded).
+	#[must_use]
+	pub fn get_document_library_info(&self, path: &str) -> (String, String, Option<i32>) {
+		if !self.initialized {
+			return (String::new(), String::new(), None);
+		}
+		let key = self.get_doc_key(path);
+		let data = self.data.borrow();
+		let Some(doc) = data.documents.get(&key) else {
+			return (String::new(), String::new(), None);
+		};
+		let progress = (doc.content_length > 0).then(|| {
+			let percent = doc.last_position.clamp(0, doc.content_length) * 100 / doc.content_length;
+			i32::try_from(percent).unwrap_or(100)
+		});
+		(doc.title.clone(), doc.author.clone(), progress)
+	}
+
+	/// How the All Documents list is sorted. Configs from before sortable columns only had the
+	/// "sort by last read" checkbox, which maps to newest-first by last read.
+	#[must_use]
+	pub fn get_document_sort(&self) -> DocumentSort {
+		let stored = self.get_app_string("document_sort", "");
+		if let Some(key) = DocumentSortKey::parse(&stored) {
+			return DocumentSort { key, descending: self.get_app_bool("document_sort_descending", false) };
+		}
+		if self.get_app_bool("sort_documents_by_last_read", false) {
+			return DocumentSort { key: DocumentSortKey::LastRead, descending: true };
+		}
+		DocumentSort::default()
+	}
+
+	pub fn set_document_sort(&self, sort: DocumentSort) {
+		self.set_app_string("document_sort", sort.key.as_str());
+		self.set_app_bool("document_sort_descending", sort.descending);
+	}
+
+	#[must_use]
+	pub fn get_validated_document_position(&self, path: &str, max_position: i64) -> i64 {
+		let saved = self.get_document_position(path);
+		if saved > 0 && saved <= max_position { saved } else { -1 }
+	}
+
+	pub fn set_navigation_history(&self, path: &str, history: &[SavedHistoryEntry], history_index: usize) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			doc.navigation_history = history.to_vec();
+			doc.navigation_history_index = history_index;
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_navigation_history(&self, path: &str) -> NavigationHistory {
+		let mut nav = NavigationHistory::default();
 		if !self.initialized {
 			return nav;
 		}
 		let key = self.get_doc_key(path);
 		if let Some(doc) = self.data.borrow().documents.get(&key) {
-			nav.positions = doc.navigation_history.clone();
+			nav.entries = doc.navigation_history.clone();
 			nav.index = doc.navigation_history_index;
 		}
 		nav
@@ -809,31 +1776,85 @@ impl ConfigManager {
 			}
 			data.documents.remove(&key);
 		}
+		self.bookmarks_changed();
 		self.dirty.set(true);
 	}
 
-	pub fn rename_document_path(&self, old_path: &str, new_path: &str) {
-		if !self.initialized {
+	/// Moves everything remembered about a document to the path it was moved or renamed to, and
+	/// updates the recent and opened lists. When the new path already has a section of its own, the
+	/// more recently changed one wins and the other is dropped, so one file never ends up with two.
+	pub fn rename_document(&self, old_path: &str, new_path: &str) {
+		if !self.initialized || old_path == new_path {
 			return;
 		}
-		let mut data = self.data.borrow_mut();
-		for p in &mut data.recent_documents {
-			if p == old_path {
-				*p = new_path.to_string();
+		{
+			let mut data = self.data.borrow_mut();
+			let data = &mut *data;
+			let old_key = Self::find_doc_key(data, old_path);
+			let new_key = Self::find_doc_key(data, new_path);
+			let kept_key = match (old_key, new_key) {
+				(Some(old_key), Some(new_key)) if old_key != new_key => {
+					let modified = |key: &str| data.documents.get(key).map_or(0, |doc| doc.modified);
+					let (kept, dropped) =
+						if modified(&new_key) > modified(&old_key) { (new_key, old_key) } else { (old_key, new_key) };
+					data.documents.remove(&dropped);
+					for key in data.path_hashes.values_mut() {
+						if *key == dropped {
+							key.clone_from(&kept);
+						}
+					}
+					Some(kept)
+				}
+				(Some(key), _) | (None, Some(key)) => Some(key),
+				(None, None) => None,
+			};
+			data.path_hashes.remove(old_path);
+			if let Some(key) = kept_key {
+				if let Some(doc) = data.documents.get_mut(&key) {
+					doc.path = new_path.to_string();
+				}
+				data.path_hashes.insert(new_path.to_string(), key);
 			}
+			rename_in_list(&mut data.recent_documents, old_path, new_path);
+			rename_in_list(&mut data.opened_documents, old_path, new_path);
 		}
-		for p in &mut data.opened_documents {
-			if p == old_path {
-				*p = new_path.to_string();
-			}
+		self.bookmarks_changed();
+		self.dirty.set(true);
+	}
+
+	/// Looks in `library` and its subfolders for documents whose remembered file no longer exists,
+	/// matching on file name and size. Only documents with exactly one candidate are returned, as
+	/// (old path, new path) pairs ready for [`Self::rename_document`].
+	#[must_use]
+	pub fn find_moved_documents(&self, library: &Path) -> Vec<(String, String)> {
+		if !self.initialized {
+			return Vec::new();
 		}
-		if let Some(doc_key) = data.path_hashes.remove(old_path) {
-			data.path_hashes.insert(new_path.to_string(), doc_key.clone());
-			if let Some(doc) = data.documents.get_mut(&doc_key) {
-				doc.path = new_path.to_string();
-			}
+		let missing: Vec<(String, u64)> = self
+			.data
+			.borrow()
+			.documents
+			.values()
+			.filter(|doc| !doc.path.is_empty() && !is_clipboard_path(&doc.path) && !Path::new(&doc.path).exists())
+			.filter_map(|doc| Some((doc.path.clone(), u64::try_from(doc.file_size).ok().filter(|&size| size > 0)?)))
+			.collect();
+		if missing.is_empty() {
+			return Vec::new();
 		}
-		self.dirty.set(true);
+		let mut candidates = HashMap::new();
+		collect_library_files(library, &mut candidates);
+		let mut moved: Vec<(String, String)> = missing
+			.into_iter()
+			.filter_map(|(path, size)| {
+				let name = Path::new(&path).file_name()?.to_os_string();
+				match candidates.get(&(name, size))?.as_slice() {
+					[found] => Some((path, found.to_string_lossy().into_owned())),
+					_ => None,
+				}
+			})
+			.collect();
+		moved.sort();
+		moved
 	}
 
 	pub fn get_all_documents(&self) -> Vec<String> {
@@ -854,9 +1875,11 @@ impl ConfigManager {
 			if doc.bookmarks.iter().any(|bm| bm.start == start && bm.end == end) {
 				return;
 			}
-			doc.bookmarks.push(StoredBookmark { start, end, note: note.to_string() });
+			doc.bookmarks.push(StoredBookmark { start, end, note: note.to_string(), category: String::new() });
 			doc.bookmarks.sort_by_key(|a| a.start);
+			doc.modified = unix_now();
 		}
+		self.bookmarks_changed();
 		self.dirty.set(true);
 	}
 
@@ -870,6 +1893,8 @@ impl ConfigManager {
 			let doc = Self::doc_entry_mut(&mut data, key, path);
 			if let Some(idx) = doc.bookmarks.iter().position(|bm| bm.start == start && bm.end == end) {
 				doc.bookmarks.remove(idx);
+				doc.modified = unix_now();
+				self.bookmarks_changed();
 			}
 		}
 		self.dirty.set(true);
@@ -891,13 +1916,45 @@ impl ConfigManager {
 			let key = self.get_doc_key(path);
 			let mut data = self.data.borrow_mut();
 			let doc = Self::doc_entry_mut(&mut data, key, path);
-			if let Some(bm) = doc.bookmarks.iter_mut().find(|bm| bm.start == start && bm.end == end) {
+			if let Some(bm) = doc.bookmarks.iter_mut().find(|bm| bm.start == start && bm.end == end)
+				&& bm.note != note
+			{
 				bm.note = note.to_string();
+				doc.modified = unix_now();
+				self.bookmarks_changed();
+			}
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn update_bookmark_category(&self, path: &str, start: i64, end: i64, category: &str) {
+		if !self.initialized {
+			return;
+		}
+		let category = category.trim();
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if let Some(bm) = doc.bookmarks.iter_mut().find(|bm| bm.start == start && bm.end == end)
+				&& bm.category != category
+			{
+				bm.category = category.to_string();
+				doc.modified = unix_now();
 			}
 		}
 		self.dirty.set(true);
 	}
 
+	/// Distinct non-empty categories used by the document's bookmarks, sorted case-insensitively.
+	pub fn get_bookmark_categories(&self, path: &str) -> Vec<String> {
+		let mut categories: Vec<String> =
+			self.get_bookmarks(path).into_iter().map(|bm| bm.category).filter(|c| !c.is_empty()).collect();
+		categories.sort_by_key(|c| c.to_lowercase());
+		categories.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+		categories
+	}
+
 	pub fn get_bookmarks(&self, path: &str) -> Vec<Bookmark> {
 		if !self.initialized {
 			return Vec::new();
@@ -907,123 +1964,696 @@ impl ConfigManager {
 			.documents
 			.get(&self.get_doc_key(path))
 			.map(|d| {
-				d.bookmarks.iter().map(|bm| Bookmark { start: bm.start, end: bm.end, note: bm.note.clone() }).collect()
+				d.bookmarks
+					.iter()
+					.map(|bm| Bookmark {
+						start: bm.start,
+						end: bm.end,
+						note: bm.note.clone(),
+						category: bm.category.clone(),
+					})
+					.collect()
 			})
 			.unwrap_or_default()
 	}
 
-	pub fn set_document_format(&self, path: &str, format: &str) {
+	pub fn get_document_pronunciation_rules(&self, path: &str) -> Vec<PronunciationRule> {
+		if !self.initialized {
+			return Vec::new();
+		}
+		self.data
+			.borrow()
+			.documents
+			.get(&self.get_doc_key(path))
+			.map(|d| d.pronunciation_rules.clone())
+			.unwrap_or_default()
+	}
+
+	/// Replaces the document's own rules. They travel in its `.paperback` sidecar, so this counts as
+	/// a change to sync.
+	pub fn set_document_pronunciation_rules(&self, path: &str, rules: &[PronunciationRule]) {
 		if !self.initialized {
 			return;
 		}
 		{
 			let key = self.get_doc_key(path);
 			let mut data = self.data.borrow_mut();
-			Self::doc_entry_mut(&mut data, key, path).format = format.to_string();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if doc.pronunciation_rules == rules {
+				return;
+			}
+			doc.pronunciation_rules = rules.to_vec();
+			doc.modified = unix_now();
 		}
 		self.dirty.set(true);
 	}
 
-	pub fn get_document_format(&self, path: &str) -> String {
+	pub fn get_document_notes(&self, path: &str) -> String {
 		if !self.initialized {
 			return String::new();
 		}
 		let key = self.get_doc_key(path);
-		self.data.borrow().documents.get(&key).map(|d| d.format.clone()).unwrap_or_default()
+		self.data.borrow().documents.get(&key).map(|d| d.notes.clone()).unwrap_or_default()
 	}
 
-	pub fn set_document_password(&self, path: &str, password: &str) {
+	/// Saves `position` in quick slot `slot` (0 to 9), replacing whatever it held.
+	pub fn set_document_quick_slot(&self, path: &str, slot: u8, position: i64) {
 		if !self.initialized {
 			return;
 		}
 		{
 			let key = self.get_doc_key(path);
 			let mut data = self.data.borrow_mut();
-			Self::doc_entry_mut(&mut data, key, path).password = password.to_string();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			doc.quick_slots.insert(quick_slot_key(slot), position);
+			doc.modified = unix_now();
 		}
 		self.dirty.set(true);
 	}
 
-	pub fn get_document_password(&self, path: &str) -> String {
+	#[must_use]
+	pub fn get_document_quick_slot(&self, path: &str, slot: u8) -> Option<i64> {
 		if !self.initialized {
-			return String::new();
+			return None;
 		}
 		let key = self.get_doc_key(path);
-		self.data.borrow().documents.get(&key).map(|d| d.password.clone()).unwrap_or_default()
+		self.data.borrow().documents.get(&key).and_then(|d| d.quick_slots.get(&quick_slot_key(slot)).copied())
 	}
 
-	/// Import document settings from a `.paperback` sidecar file if it exists.
-	pub fn import_document_settings(&self, path: &str) {
-		let import_path = Path::new(path).with_extension("paperback");
-		if let Some(import_path_str) = import_path.to_str()
-			&& import_path.exists()
+	/// Replaces the document's notes page. Like bookmarks, it travels in the `.paperback` sidecar.
+	pub fn set_document_notes(&self, path: &str, notes: &str) {
+		if !self.initialized {
+			return;
+		}
 		{
-			self.import_settings_from_file(path, import_path_str);
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if doc.notes == notes {
+				return;
+			}
+			doc.notes = notes.to_string();
+			doc.modified = unix_now();
 		}
+		self.dirty.set(true);
 	}
 
-	/// Import document settings from a specified TOML sidecar file.
-	pub fn import_settings_from_file(&self, doc_path: &str, import_path: &str) {
-		if !self.initialized || !Path::new(import_path).exists() {
+	/// The rules that apply to the document: its own, then the global ones.
+	pub fn get_pronunciation_rules(&self, path: &str) -> Vec<PronunciationRule> {
+		let mut rules = self.get_document_pronunciation_rules(path);
+		rules.extend(self.get_global_pronunciation_rules());
+		rules
+	}
+
+	pub fn set_document_format(&self, path: &str, format: &str) {
+		if !self.initialized {
 			return;
 		}
-		let Ok(content) = fs::read_to_string(import_path) else { return };
-		let Ok(sidecar) = toml::from_str::<SidecarData>(&content) else { return };
-		if let Some(pos) = sidecar.last_position {
-			self.set_document_position(doc_path, pos);
-		}
-		if let Some(format) = sidecar.format {
-			self.set_document_format(doc_path, &format);
-		}
-		if !sidecar.bookmarks.is_empty() {
-			let key = self.get_doc_key(doc_path);
+		{
+			let key = self.get_doc_key(path);
 			let mut data = self.data.borrow_mut();
-			Self::doc_entry_mut(&mut data, key, doc_path).bookmarks = sidecar.bookmarks;
-			self.dirty.set(true);
+			Self::doc_entry_mut(&mut data, key, path).format = format.to_string();
 		}
+		self.dirty.set(true);
 	}
 
-	/// Export document settings to a `.paperback` sidecar TOML file.
-	pub fn export_document_settings(&self, doc_path: &str, export_path: &str) {
+	pub fn get_document_format(&self, path: &str) -> String {
+		if !self.initialized {
+			return String::new();
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map(|d| d.format.clone()).unwrap_or_default()
+	}
+
+	pub fn set_document_archive_entry(&self, path: &str, entry: &str) {
 		if !self.initialized {
 			return;
 		}
-		let key = self.get_doc_key(doc_path);
-		let data = self.data.borrow();
-		let doc = data.documents.get(&key);
-		let sidecar = SidecarData {
-			last_position: doc.map(|d| d.last_position).filter(|&p| p > 0),
-			format: doc.and_then(|d| if d.format.is_empty() { None } else { Some(d.format.clone()) }),
-			bookmarks: doc.map(|d| d.bookmarks.clone()).unwrap_or_default(),
-		};
-		if let Ok(s) = toml::to_string_pretty(&sidecar) {
-			let _ = fs::write(export_path, s);
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).archive_entry = entry.to_string();
 		}
+		self.dirty.set(true);
 	}
 
-	fn doc_entry_mut<'a>(data: &'a mut ConfigData, key: String, path: &str) -> &'a mut DocumentConfig {
-		let entry = data.documents.entry(key).or_default();
-		if entry.path.is_empty() {
-			entry.path = path.to_string();
+	pub fn get_document_archive_entry(&self, path: &str) -> String {
+		if !self.initialized {
+			return String::new();
 		}
-		entry
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map(|d| d.archive_entry.clone()).unwrap_or_default()
 	}
-}
 
-impl Drop for ConfigManager {
-	fn drop(&mut self) {
+	pub fn set_document_language(&self, path: &str, language: &str) {
 		if !self.initialized {
 			return;
 		}
-		self.flush();
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let entry = Self::doc_entry_mut(&mut data, key, path);
+			if entry.language == language {
+				return;
+			}
+			entry.language = language.to_string();
+		}
+		self.dirty.set(true);
 	}
-}
-
-pub fn get_sorted_document_list(config: &ConfigManager, open_paths: &[String], filter: &str) -> Vec<DocumentListItem> {
-	use crate::types::{DocumentListItem, DocumentListStatus};
 
-	let recent_docs = config.get_recent_documents();
-	let all_docs = config.get_all_documents();
+	pub fn set_document_language_override(&self, path: &str, language: &str) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).language_override = language.to_string();
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_language_override(&self, path: &str) -> String {
+		if !self.initialized {
+			return String::new();
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map(|d| d.language_override.clone()).unwrap_or_default()
+	}
+
+	/// Returns the user's language override for a document, falling back to the detected language.
+	pub fn get_document_language(&self, path: &str) -> String {
+		if !self.initialized {
+			return String::new();
+		}
+		let key = self.get_doc_key(path);
+		self.data
+			.borrow()
+			.documents
+			.get(&key)
+			.map(|d| if d.language_override.is_empty() { d.language.clone() } else { d.language_override.clone() })
+			.unwrap_or_default()
+	}
+
+	/// Sets the text direction the reader chose for a document, or `None` to go back to detecting it.
+	pub fn set_document_text_direction(&self, path: &str, direction: Option<TextDirection>) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).text_direction =
+				direction.map(TextDirection::key).unwrap_or_default().to_string();
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_text_direction(&self, path: &str) -> Option<TextDirection> {
+		if !self.initialized {
+			return None;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).and_then(|d| TextDirection::from_key(&d.text_direction))
+	}
+
+	pub fn set_document_line_layout(&self, path: &str, layout: Option<LineLayout>) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).line_layout =
+				layout.map(LineLayout::key).unwrap_or_default().to_string();
+		}
+		self.dirty.set(true);
+	}
+
+	/// The line layout chosen for this document, or `None` when it's left to detection.
+	pub fn get_document_line_layout(&self, path: &str) -> Option<LineLayout> {
+		if !self.initialized {
+			return None;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).and_then(|d| LineLayout::from_key(&d.line_layout))
+	}
+
+	pub fn set_document_column_detection(&self, path: &str, enabled: bool) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).disable_column_detection = !enabled;
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_column_detection(&self, path: &str) -> bool {
+		if !self.initialized {
+			return true;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).is_none_or(|d| !d.disable_column_detection)
+	}
+
+	pub fn set_document_heading_detection(&self, path: &str, enabled: bool) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).disable_heading_detection = !enabled;
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_heading_detection(&self, path: &str) -> bool {
+		if !self.initialized {
+			return true;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).is_none_or(|d| !d.disable_heading_detection)
+	}
+
+	pub fn set_document_strip_running_lines(&self, path: &str, enabled: bool) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).strip_running_lines = Some(enabled);
+		}
+		self.dirty.set(true);
+	}
+
+	/// Whether running headers and footers are removed from this document, or `None` when the
+	/// reader never chose and the format's default applies.
+	pub fn get_document_strip_running_lines(&self, path: &str) -> Option<bool> {
+		if !self.initialized {
+			return None;
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).and_then(|d| d.strip_running_lines)
+	}
+
+	/// Counts a new reading session for a document and marks it as read now.
+	pub fn record_document_session(&self, path: &str) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, path);
+			if let Ok(metadata) = fs::metadata(path) {
+				doc.file_size = i64::try_from(metadata.len()).unwrap_or(0);
+			}
+			doc.reading_stats.sessions = doc.reading_stats.sessions.saturating_add(1);
+			doc.reading_stats.last_read = unix_now();
+		}
+		self.dirty.set(true);
+	}
+
+	/// Adds reading time to a document and marks it as read now. Like other setters this only marks
+	/// the config dirty, so callers can batch updates until the next flush.
+	pub fn add_document_reading_time(&self, path: &str, seconds: u64) {
+		if !self.initialized || seconds == 0 {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			let stats = &mut Self::doc_entry_mut(&mut data, key, path).reading_stats;
+			stats.seconds_read = stats.seconds_read.saturating_add(seconds);
+			stats.last_read = unix_now();
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_reading_stats(&self, path: &str) -> ReadingStats {
+		if !self.initialized {
+			return ReadingStats::default();
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map(|d| d.reading_stats).unwrap_or_default()
+	}
+
+	pub fn set_document_password(&self, path: &str, password: &str) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).password = password.to_string();
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_password(&self, path: &str) -> String {
+		if !self.initialized {
+			return String::new();
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map(|d| d.password.clone()).unwrap_or_default()
+	}
+
+	pub fn set_document_encoding(&self, path: &str, encoding: &str) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, path).encoding = encoding.to_string();
+		}
+		self.dirty.set(true);
+	}
+
+	pub fn get_document_encoding(&self, path: &str) -> String {
+		if !self.initialized {
+			return String::new();
+		}
+		let key = self.get_doc_key(path);
+		self.data.borrow().documents.get(&key).map(|d| d.encoding.clone()).unwrap_or_default()
+	}
+
+	/// Import document settings from a `.paperback` sidecar file if it exists.
+	pub fn import_document_settings(&self, path: &str) {
+		let import_path = sidecar_path(path);
+		if let Some(import_path_str) = import_path.to_str()
+			&& import_path.exists()
+		{
+			self.import_settings_from_file(path, import_path_str);
+		}
+	}
+
+	/// Import document settings from a specified TOML sidecar file.
+	pub fn import_settings_from_file(&self, doc_path: &str, import_path: &str) {
+		if !self.initialized || !Path::new(import_path).exists() {
+			return;
+		}
+		let Ok(content) = fs::read_to_string(import_path) else { return };
+		let Ok(sidecar) = toml::from_str::<SidecarData>(&content) else { return };
+		if let Some(pos) = sidecar.last_position {
+			self.set_document_position(doc_path, pos);
+		}
+		if let Some(pos) = sidecar.furthest_position {
+			self.set_document_furthest_position(doc_path, pos);
+		}
+		if let Some(format) = sidecar.format {
+			self.set_document_format(doc_path, &format);
+		}
+		if !sidecar.bookmarks.is_empty() {
+			let key = self.get_doc_key(doc_path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, doc_path);
+			doc.bookmarks = sidecar.bookmarks;
+			doc.modified = unix_now();
+			self.bookmarks_changed();
+			self.dirty.set(true);
+		}
+		if !sidecar.quick_slots.is_empty() {
+			let key = self.get_doc_key(doc_path);
+			let mut data = self.data.borrow_mut();
+			let doc = Self::doc_entry_mut(&mut data, key, doc_path);
+			doc.quick_slots = sidecar.quick_slots;
+			doc.modified = unix_now();
+			self.dirty.set(true);
+		}
+		if !sidecar.pronunciation_rules.is_empty() {
+			self.set_document_pronunciation_rules(doc_path, &sidecar.pronunciation_rules);
+		}
+		if !sidecar.notes.is_empty() {
+			self.set_document_notes(doc_path, &sidecar.notes);
+		}
+	}
+
+	/// Export document settings to a `.paperback` sidecar TOML file.
+	pub fn export_document_settings(&self, doc_path: &str, export_path: &str) {
+		if !self.initialized {
+			return;
+		}
+		let key = self.get_doc_key(doc_path);
+		let data = self.data.borrow();
+		let doc = data.documents.get(&key);
+		let sidecar = SidecarData {
+			last_position: doc.map(|d| d.last_position).filter(|&p| p > 0),
+			furthest_position: doc.map(|d| d.furthest_position).filter(|&p| p > 0),
+			format: doc.and_then(|d| if d.format.is_empty() { None } else { Some(d.format.clone()) }),
+			bookmarks: doc.map(|d| d.bookmarks.clone()).unwrap_or_default(),
+			quick_slots: doc.map(|d| d.quick_slots.clone()).unwrap_or_default(),
+			pronunciation_rules: doc.map(|d| d.pronunciation_rules.clone()).unwrap_or_default(),
+			notes: doc.map(|d| d.notes.clone()).unwrap_or_default(),
+			modified: doc.map(|d| d.modified).filter(|&m| m > 0),
+		};
+		if let Ok(s) = toml::to_string_pretty(&sidecar) {
+			let _ = fs::write(export_path, s);
+		}
+	}
+
+	/// Compares the document's position and bookmarks with its `.paperback` sidecar by their
+	/// modification times, relative to the last sync.
+	#[must_use]
+	pub fn compare_sidecar(&self, doc_path: &str) -> SidecarSync {
+		let Some(sidecar) = read_sidecar(&sidecar_path(doc_path)) else {
+			return SidecarSync::UpToDate;
+		};
+		if !self.initialized {
+			return SidecarSync::UpToDate;
+		}
+		let key = self.get_doc_key(doc_path);
+		let data = self.data.borrow();
+		let doc = data.documents.get(&key);
+		let local = doc.map_or(0, |d| d.modified);
+		let synced = doc.map_or(0, |d| d.synced);
+		let remote = sidecar.modified.unwrap_or(0);
+		if remote == 0 && local == 0 {
+			// A sidecar from before auto-sync: only worth importing if there's nothing here yet.
+			let has_local_data =
+				doc.is_some_and(|d| d.last_position > 0 || !d.bookmarks.is_empty() || !d.notes.is_empty());
+			return if has_local_data { SidecarSync::LocalNewer } else { SidecarSync::SidecarNewer };
+		}
+		if remote == local {
+			return SidecarSync::UpToDate;
+		}
+		if local > synced && remote > synced {
+			return SidecarSync::Conflict {
+				local_position: doc.map_or(0, |d| d.last_position),
+				sidecar_position: sidecar.last_position.unwrap_or(0),
+			};
+		}
+		if remote > local { SidecarSync::SidecarNewer } else { SidecarSync::LocalNewer }
+	}
+
+	/// Replaces the local position and bookmarks with the sidecar's and records it as synced.
+	pub fn apply_sidecar(&self, doc_path: &str) {
+		let path = sidecar_path(doc_path);
+		let Some(sidecar) = read_sidecar(&path) else { return };
+		self.import_settings_from_file(doc_path, &path.to_string_lossy());
+		if !self.initialized {
+			return;
+		}
+		let remote = sidecar.modified.unwrap_or(0);
+		let key = self.get_doc_key(doc_path);
+		let mut data = self.data.borrow_mut();
+		let doc = Self::doc_entry_mut(&mut data, key, doc_path);
+		doc.modified = remote;
+		doc.synced = remote;
+		self.dirty.set(true);
+	}
+
+	/// Resolves a conflict in favour of this machine: the local state becomes the newest and is
+	/// written over the sidecar.
+	pub fn keep_local_over_sidecar(&self, doc_path: &str) {
+		if !self.initialized {
+			return;
+		}
+		{
+			let key = self.get_doc_key(doc_path);
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, doc_path).modified = unix_now();
+		}
+		self.dirty.set(true);
+		self.write_sidecar_if_changed(doc_path);
+	}
+
+	/// Writes the document's `.paperback` sidecar next to it if the position or bookmarks changed
+	/// since the last sync, or if the sidecar is missing. Returns whether a file was written.
+	pub fn write_sidecar_if_changed(&self, doc_path: &str) -> bool {
+		if !self.initialized {
+			return false;
+		}
+		let path = sidecar_path(doc_path);
+		let key = self.get_doc_key(doc_path);
+		let modified = {
+			let data = self.data.borrow();
+			let Some(doc) = data.documents.get(&key) else { return false };
+			if doc.modified == doc.synced && path.exists() {
+				return false;
+			}
+			doc.modified
+		};
+		self.export_document_settings(doc_path, &path.to_string_lossy());
+		{
+			let mut data = self.data.borrow_mut();
+			Self::doc_entry_mut(&mut data, key, doc_path).synced = modified;
+		}
+		self.dirty.set(true);
+		true
+	}
+
+	/// Every document section, keyed by path, and the settings that aren't tied to this machine, as
+	/// the JSON that Backup All Data writes.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the settings can't be serialized.
+	pub fn backup_json(&self) -> Result<String, BackupError> {
+		let data = self.data.borrow();
+		let mut app = data.app.clone();
+		app.extra = machine_independent(app.extra);
+		let backup = BackupData {
+			version: BACKUP_VERSION,
+			app,
+			profiles: data.profiles.clone(),
+			pronunciation_rules: data.pronunciation_rules.clone(),
+			documents: data
+				.documents
+				.values()
+				.filter(|doc| !doc.path.is_empty() && !is_clipboard_path(&doc.path))
+				.map(|doc| (doc.path.clone(), doc.clone()))
+				.collect(),
+		};
+		Ok(serde_json::to_string_pretty(&backup)?)
+	}
+
+	/// Writes [`Self::backup_json`] to `backup_path`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be written.
+	pub fn backup_to_file(&self, backup_path: &Path) -> Result<(), BackupError> {
+		let json = self.backup_json()?;
+		fs::write(backup_path, json)?;
+		Ok(())
+	}
+
+	/// Merges a backup written by [`Self::backup_json`] into the config and saves it. A document keeps
+	/// whichever copy changed last, so restoring an old backup doesn't undo reading done since.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `json` isn't a backup, or is one from a newer version of Paperback.
+	pub fn restore_backup_json(&self, json: &str) -> Result<RestoreSummary, BackupError> {
+		let backup: BackupData = serde_json::from_str(json)?;
+		if backup.version > BACKUP_VERSION {
+			return Err(BackupError::UnsupportedVersion(backup.version));
+		}
+		if !self.initialized {
+			return Ok(RestoreSummary::default());
+		}
+		let keys: HashMap<String, String> = backup
+			.documents
+			.keys()
+			.filter(|path| !path.is_empty())
+			.map(|path| (path.clone(), self.get_doc_key(path)))
+			.collect();
+		let summary = merge_backup(&mut self.data.borrow_mut(), backup, &keys);
+		self.bookmarks_changed();
+		self.dirty.set(true);
+		self.flush();
+		Ok(summary)
+	}
+
+	/// Restores the backup saved in `backup_path`, see [`Self::restore_backup_json`].
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be read or isn't a backup this version can restore.
+	pub fn restore_backup_from_file(&self, backup_path: &Path) -> Result<RestoreSummary, BackupError> {
+		let json = fs::read_to_string(backup_path)?;
+		self.restore_backup_json(&json)
+	}
+
+	/// The section stored for `path`, by its remembered key or, failing that, by the path recorded in it.
+	fn find_doc_key(data: &ConfigData, path: &str) -> Option<String> {
+		data.path_hashes
+			.get(path)
+			.filter(|key| data.documents.contains_key(*key))
+			.cloned()
+			.or_else(|| data.documents.iter().find(|(_, doc)| doc.path == path).map(|(key, _)| key.clone()))
+	}
+
+	/// The section to change for a document, created if it has none. Every change to a section comes
+	/// through here, which stamps it as updated.
+	fn doc_entry_mut<'a>(data: &'a mut ConfigData, key: String, path: &str) -> &'a mut DocumentConfig {
+		let entry = data.documents.entry(key).or_default();
+		if entry.path.is_empty() {
+			entry.path = path.to_string();
+		}
+		entry.updated = unix_now();
+		entry
+	}
+}
+
+fn rename_in_list(list: &mut Vec<String>, old_path: &str, new_path: &str) {
+	for path in list.iter_mut() {
+		if path == old_path {
+			*path = new_path.to_string();
+		}
+	}
+	let mut seen = false;
+	list.retain(|path| path != new_path || !std::mem::replace(&mut seen, true));
+}
+
+/// Indexes the files under `dir` by name and size, skipping anything that can't be read.
+fn collect_library_files(dir: &Path, files: &mut HashMap<(OsString, u64), Vec<PathBuf>>) {
+	let Ok(entries) = fs::read_dir(dir) else {
+		return;
+	};
+	for entry in entries.flatten() {
+		let Ok(file_type) = entry.file_type() else {
+			continue;
+		};
+		if file_type.is_dir() {
+			collect_library_files(&entry.path(), files);
+		} else if file_type.is_file() {
+			if let Ok(metadata) = entry.metadata() {
+				files.entry((entry.file_name(), metadata.len())).or_default().push(entry.path());
+			}
+		}
+	}
+}
+
+fn sidecar_path(doc_path: &str) -> PathBuf {
+	Path::new(doc_path).with_extension("paperback")
+}
+
+fn read_sidecar(path: &Path) -> Option<SidecarData> {
+	let content = fs::read_to_string(path).ok()?;
+	toml::from_str(&content).ok()
+}
+
+impl Drop for ConfigManager {
+	fn drop(&mut self) {
+		if !self.initialized {
+			return;
+		}
+		self.flush();
+	}
+}
+
+pub fn get_sorted_document_list(config: &ConfigManager, open_paths: &[String], filter: &str) -> Vec<DocumentListItem> {
+	use crate::types::{DocumentListItem, DocumentListStatus};
+
+	let recent_docs = config.get_recent_documents();
+	let all_docs = config.get_all_documents();
 	let mut doc_paths: Vec<String> = Vec::new();
 	for path in &recent_docs {
 		if !doc_paths.contains(path) {
@@ -1044,12 +2674,15 @@ pub fn get_sorted_document_list(config: &ConfigManager, open_paths: &[String], f
 	});
 	doc_paths.extend(rest);
 	let filter_lower = filter.to_lowercase();
-	doc_paths
+	let mut items: Vec<DocumentListItem> = doc_paths
 		.into_iter()
 		.filter_map(|path| {
 			let path_obj = Path::new(&path);
 			let filename = path_obj.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
-			if !filter.is_empty() && !filename.to_lowercase().contains(&filter_lower) {
+			let (title, author, progress) = config.get_document_library_info(&path);
+			if !filter.is_empty()
+				&& ![&filename, &title, &author].iter().any(|field| field.to_lowercase().contains(&filter_lower))
+			{
 				return None;
 			}
 			let status = if !path_obj.exists() {
@@ -1059,9 +2692,34 @@ pub fn get_sorted_document_list(config: &ConfigManager, open_paths: &[String], f
 			} else {
 				DocumentListStatus::Closed
 			};
-			Some(DocumentListItem { path, filename, status })
+			let furthest = config.get_document_furthest_percent(&path);
+			let reading_stats = config.get_document_reading_stats(&path);
+			Some(DocumentListItem { path, filename, title, author, progress, furthest, status, reading_stats })
 		})
-		.collect()
+		.collect();
+	let sort = config.get_document_sort();
+	// The sort is stable, so ties keep the recent-first order built above.
+	items.sort_by(|a, b| {
+		let ordering = compare_document_list_items(a, b, sort.key);
+		if sort.descending { ordering.reverse() } else { ordering }
+	});
+	items
+}
+
+fn compare_document_list_items(a: &DocumentListItem, b: &DocumentListItem, key: DocumentSortKey) -> Ordering {
+	let text = |value: &String| value.to_lowercase();
+	// Documents not opened since titles were recorded sort by file name among the titled ones.
+	let title = |item: &DocumentListItem| text(if item.title.is_empty() { &item.filename } else { &item.title });
+	match key {
+		DocumentSortKey::Recent => Ordering::Equal,
+		DocumentSortKey::FileName => text(&a.filename).cmp(&text(&b.filename)),
+		DocumentSortKey::Title => title(a).cmp(&title(b)),
+		DocumentSortKey::Author => text(&a.author).cmp(&text(&b.author)).then_with(|| title(a).cmp(&title(b))),
+		DocumentSortKey::Progress => a.progress.cmp(&b.progress),
+		DocumentSortKey::Status => (a.status as u8).cmp(&(b.status as u8)),
+		DocumentSortKey::Path => text(&a.path).cmp(&text(&b.path)),
+		DocumentSortKey::LastRead => a.reading_stats.last_read.cmp(&b.reading_stats.last_read),
+	}
 }
 
 #[must_use]
@@ -1147,4 +2805,815 @@ mod tests {
 		config.set_app_bool("render_tables_inline", true);
 		assert!(config.get_app_bool("render_tables_inline", true));
 	}
+
+	#[test]
+	fn find_scope_round_trips() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert_eq!(config.get_find_settings().scope, FindScope::Text);
+		config.set_find_settings(FindSettings { scope: FindScope::Headings, ..FindSettings::default() });
+		assert_eq!(config.get_find_settings().scope, FindScope::Headings);
+	}
+
+	#[test]
+	fn parsing_marker_is_saved_until_parsing_finishes() {
+		let path = std::env::temp_dir().join(format!("paperback_restoring_{}.toml", std::process::id()));
+		let _ = fs::remove_file(&path);
+		let mut config = ConfigManager::new();
+		config.initialize(path.clone());
+		assert_eq!(config.unfinished_document(), None);
+		config.begin_parsing_document("broken.pdf");
+		assert_eq!(config.unfinished_document().as_deref(), Some("broken.pdf"));
+		let mut reloaded = ConfigManager::new();
+		reloaded.initialize(path.clone());
+		assert_eq!(reloaded.unfinished_document().as_deref(), Some("broken.pdf"));
+		config.finish_parsing_document();
+		assert_eq!(config.unfinished_document(), None);
+		let mut reloaded = ConfigManager::new();
+		reloaded.initialize(path.clone());
+		let _ = fs::remove_file(&path);
+		assert_eq!(reloaded.unfinished_document(), None);
+	}
+
+	#[test]
+	fn unfinished_document_is_not_restored() {
+		let dir = std::env::temp_dir().join(format!("paperback_unfinished_{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let good = dir.join("good.txt");
+		let broken = dir.join("broken.txt");
+		fs::write(&good, "fine").unwrap();
+		fs::write(&broken, "crashes").unwrap();
+		let (good, broken) = (good.to_string_lossy().to_string(), broken.to_string_lossy().to_string());
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		config.add_opened_document(&good);
+		config.add_opened_document(&broken);
+		config.begin_parsing_document(&broken);
+		assert_eq!(config.get_opened_documents_existing(), [good.clone()]);
+		assert_eq!(config.get_opened_documents(), [good.clone(), broken.clone()]);
+		config.finish_parsing_document();
+		let _ = fs::remove_dir_all(&dir);
+		assert_eq!(config.get_opened_documents(), [good, broken]);
+	}
+
+	#[test]
+	fn shortcuts_survive_a_save_and_reload() {
+		let path = std::env::temp_dir().join(format!("paperback_shortcuts_{}.toml", std::process::id()));
+		let _ = fs::remove_file(&path);
+		let mut config = ConfigManager::new();
+		config.initialize(path.clone());
+		let mut shortcuts = BTreeMap::new();
+		shortcuts.insert("5241".to_string(), "F6".to_string());
+		shortcuts.insert("5240".to_string(), String::new());
+		config.set_shortcuts(&shortcuts);
+		config.flush();
+		let mut reloaded = ConfigManager::new();
+		reloaded.initialize(path.clone());
+		assert_eq!(reloaded.get_shortcuts(), shortcuts);
+		let _ = fs::remove_file(&path);
+	}
+
+	#[test]
+	fn navigation_history_reads_bare_offsets_and_round_trips_anchors() {
+		let old: DocumentConfig =
+			toml::from_str("path = \"a.txt\"\nnavigation_history = [10, 250]\nnavigation_history_index = 1\n").unwrap();
+		assert_eq!(old.navigation_history, [SavedHistoryEntry::from_offset(10), SavedHistoryEntry::from_offset(250)]);
+		let entry = SavedHistoryEntry { offset: 250, percent: 12.5, anchor: "Call me Ishmael.".to_string() };
+		let doc = DocumentConfig { navigation_history: vec![entry.clone()], ..old };
+		let reloaded: DocumentConfig = toml::from_str(&toml::to_string(&doc).unwrap()).unwrap();
+		assert_eq!(reloaded.navigation_history, [entry]);
+		assert_eq!(reloaded.navigation_history_index, 1);
+	}
+
+	fn doc_with(position: i64, modified: i64) -> DocumentConfig {
+		DocumentConfig { last_position: position, modified, ..DocumentConfig::default() }
+	}
+
+	#[test]
+	fn merge_pending_keeps_the_newer_copy_of_each_document() {
+		let mut config = ConfigData::default();
+		config.documents.insert("doc_a".to_string(), doc_with(10, 200));
+		config.documents.insert("doc_b".to_string(), doc_with(20, 100));
+		config.recent_documents = vec!["old.txt".to_string()];
+		let mut pending = ConfigData::default();
+		pending.documents.insert("doc_a".to_string(), doc_with(11, 150));
+		pending.documents.insert("doc_b".to_string(), doc_with(21, 300));
+		pending.documents.insert("doc_c".to_string(), doc_with(30, 50));
+		pending.recent_documents = vec!["new.txt".to_string()];
+		let merged = merge_pending(config.clone(), pending.clone(), true);
+		assert_eq!(merged.documents["doc_a"].last_position, 10);
+		assert_eq!(merged.documents["doc_b"].last_position, 21);
+		assert_eq!(merged.documents["doc_c"].last_position, 30);
+		assert_eq!(merged.recent_documents, vec!["new.txt".to_string()]);
+		let merged = merge_pending(config, pending, false);
+		assert_eq!(merged.documents["doc_b"].last_position, 21);
+		assert_eq!(merged.recent_documents, vec!["old.txt".to_string()]);
+	}
+
+	#[test]
+	fn flush_falls_back_to_the_pending_file_and_merges_it_back() {
+		let dir = std::env::temp_dir().join(format!("paperback_pending_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("Paperback.toml");
+		let pending_path = dir.join("local").join("pending.toml");
+		let mut config = ConfigManager::new();
+		config.set_pending_path(pending_path.clone());
+		config.initialize(path.clone());
+		// Permissions don't stop root, so stand a directory in the config file's place to make
+		// every write to it fail.
+		fs::remove_file(&path).unwrap();
+		fs::create_dir(&path).unwrap();
+		config.set_document_position("book.txt", 42);
+		config.flush();
+		assert!(pending_path.exists());
+		assert_eq!(config.take_fallback_notice(), Some(pending_path.clone()));
+		config.set_document_position("book.txt", 43);
+		config.flush();
+		assert_eq!(config.take_fallback_notice(), None);
+		fs::remove_dir(&path).unwrap();
+		let mut reloaded = ConfigManager::new();
+		reloaded.set_pending_path(pending_path.clone());
+		reloaded.initialize(path.clone());
+		assert_eq!(reloaded.get_document_position("book.txt"), 43);
+		assert!(path.is_file());
+		assert!(!pending_path.exists());
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn read_only_app_data_is_left_as_the_other_instance_saved_it() {
+		let path = std::env::temp_dir().join(format!("paperback_read_only_{}.toml", std::process::id()));
+		let _ = fs::remove_file(&path);
+		let mut first = ConfigManager::new();
+		first.initialize(path.clone());
+		let mut second = ConfigManager::new();
+		second.initialize(path.clone());
+		second.set_app_read_only(true);
+		first.set_app_bool("word_wrap", true);
+		first.add_recent_document("first.txt");
+		first.flush();
+		second.set_app_bool("word_wrap", false);
+		second.add_recent_document("second.txt");
+		second.set_document_position("second.txt", 42);
+		second.flush();
+		let mut reloaded = ConfigManager::new();
+		reloaded.initialize(path.clone());
+		let _ = fs::remove_file(&path);
+		assert!(reloaded.get_app_bool("word_wrap", false));
+		assert_eq!(reloaded.data.borrow().recent_documents, vec!["first.txt".to_string()]);
+		assert_eq!(reloaded.get_document_position("second.txt"), 42);
+	}
+
+	#[test]
+	fn read_only_flush_keeps_documents_the_other_instance_changed_later() {
+		let path = std::env::temp_dir().join(format!("paperback_read_only_docs_{}.toml", std::process::id()));
+		let _ = fs::remove_file(&path);
+		let mut first = ConfigManager::new();
+		first.initialize(path.clone());
+		first.set_document_position("book.txt", 10);
+		first.flush();
+		let mut second = ConfigManager::new();
+		second.initialize(path.clone());
+		second.set_app_read_only(true);
+		first.set_document_position("book.txt", 20);
+		let key = first.get_doc_key("book.txt");
+		first.data.borrow_mut().documents.get_mut(&key).unwrap().modified += 10;
+		first.flush();
+		second.set_document_position("other.txt", 5);
+		second.flush();
+		let mut reloaded = ConfigManager::new();
+		reloaded.initialize(path.clone());
+		let _ = fs::remove_file(&path);
+		assert_eq!(reloaded.get_document_position("book.txt"), 20);
+		assert_eq!(reloaded.get_document_position("other.txt"), 5);
+	}
+
+	#[test]
+	fn reading_profiles_switch_app_settings_but_not_documents() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		config.set_document_position("book.txt", 42);
+		config.set_app_bool("word_wrap", true);
+		config.set_app_bool("context_announcements", true);
+		config.save_reading_profile("Commute");
+		config.set_app_bool("word_wrap", false);
+		config.set_app_bool("context_announcements", false);
+		config.set_app_bool("navigation_wrap", true);
+		config.save_reading_profile("Study");
+		assert_eq!(config.reading_profile_names(), ["Commute", "Study"]);
+		assert_eq!(config.active_reading_profile(), "Study");
+
+		let commute = config.apply_reading_profile("Commute").unwrap();
+		assert!(commute.word_wrap);
+		assert!(config.get_app_bool("word_wrap", false));
+		assert!(config.get_app_bool("context_announcements", false));
+		assert!(!config.get_app_bool("navigation_wrap", false));
+		assert_eq!(config.active_reading_profile(), "Commute");
+		assert_eq!(config.get_document_position("book.txt"), 42);
+
+		assert!(config.delete_reading_profile("Commute"));
+		assert!(!config.delete_reading_profile("Commute"));
+		assert_eq!(config.active_reading_profile(), "");
+		assert!(config.apply_reading_profile("Commute").is_none());
+	}
+
+	#[test]
+	fn last_read_position_is_clamped_and_flagged_after_the_document_changes() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert_eq!(config.get_last_read_position("book.txt", 100), None);
+		config.set_document_position("book.txt", 80);
+		assert_eq!(config.get_last_read_position("book.txt", 100), Some((80, false)));
+		config.set_document_content_length("book.txt", 100);
+		assert_eq!(config.get_last_read_position("book.txt", 100), Some((80, false)));
+		assert_eq!(config.get_last_read_position("book.txt", 50), Some((50, true)));
+	}
+
+	#[test]
+	fn document_list_shows_metadata_and_sorts_by_column() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		config.set_document_position("b.epub", 50);
+		config.set_document_content_length("b.epub", 200);
+		config.set_document_metadata("b.epub", "Zebra Tales", "Alice Walker");
+		config.set_document_position("a.epub", 10);
+		config.set_document_metadata("a.epub", "Moby Dick", "Herman Melville");
+		config.set_document_position("c.txt", 0);
+		let list = get_sorted_document_list(&config, &[], "");
+		assert_eq!(list.iter().map(|item| item.filename.as_str()).collect::<Vec<_>>(), ["a.epub", "b.epub", "c.txt"]);
+		assert_eq!(list[1].title, "Zebra Tales");
+		assert_eq!(list[1].progress, Some(25));
+		assert_eq!(list[0].progress, None);
+		assert!(list[2].title.is_empty());
+		config.set_document_sort(DocumentSort { key: DocumentSortKey::Title, descending: true });
+		let list = get_sorted_document_list(&config, &[], "");
+		// c.txt has no title yet, so it sorts by its file name.
+		assert_eq!(list.iter().map(|item| item.filename.as_str()).collect::<Vec<_>>(), ["b.epub", "a.epub", "c.txt"]);
+		let list = get_sorted_document_list(&config, &[], "melville");
+		assert_eq!(list.len(), 1);
+		assert_eq!(list[0].filename, "a.epub");
+	}
+
+	#[test]
+	fn legacy_last_read_checkbox_maps_to_a_document_sort() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert_eq!(config.get_document_sort(), DocumentSort::default());
+		config.set_app_bool("sort_documents_by_last_read", true);
+		assert_eq!(config.get_document_sort(), DocumentSort { key: DocumentSortKey::LastRead, descending: true });
+		config.set_document_sort(DocumentSort { key: DocumentSortKey::Author, descending: false });
+		assert_eq!(config.get_document_sort(), DocumentSort { key: DocumentSortKey::Author, descending: false });
+	}
+
+	#[test]
+	fn document_language_override_takes_precedence() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert_eq!(config.get_document_language("book.txt"), "");
+		config.set_document_language("book.txt", "fr");
+		assert_eq!(config.get_document_language("book.txt"), "fr");
+		config.set_document_language_override("book.txt", "it");
+		assert_eq!(config.get_document_language("book.txt"), "it");
+		assert_eq!(config.get_document_language_override("book.txt"), "it");
+		config.set_document_language_override("book.txt", "");
+		assert_eq!(config.get_document_language("book.txt"), "fr");
+	}
+
+	#[test]
+	fn document_text_direction_override_round_trips() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert_eq!(config.get_document_text_direction("book.txt"), None);
+		config.set_document_text_direction("book.txt", Some(TextDirection::RightToLeft));
+		assert_eq!(config.get_document_text_direction("book.txt"), Some(TextDirection::RightToLeft));
+		config.set_document_text_direction("book.txt", None);
+		assert_eq!(config.get_document_text_direction("book.txt"), None);
+	}
+
+	#[test]
+	fn reading_stats_accumulate_per_document() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert!(config.get_document_reading_stats("book.epub").is_empty());
+		config.record_document_session("book.epub");
+		config.record_document_session("book.epub");
+		config.add_document_reading_time("book.epub", 90);
+		config.add_document_reading_time("book.epub", 30);
+		let stats = config.get_document_reading_stats("book.epub");
+		assert_eq!(stats.sessions, 2);
+		assert_eq!(stats.seconds_read, 120);
+		assert!(stats.last_read > 0);
+		assert!(config.get_document_reading_stats("other.epub").is_empty());
+	}
+
+	#[test]
+	fn reading_stats_last_read_date_is_formatted_in_utc() {
+		assert_eq!(ReadingStats::default().last_read_date(), None);
+		let stats = ReadingStats { last_read: 1_714_694_400, ..ReadingStats::default() };
+		assert_eq!(stats.last_read_date().as_deref(), Some("2024-05-03"));
+		let stats = ReadingStats { last_read: 951_825_600, ..ReadingStats::default() };
+		assert_eq!(stats.last_read_date().as_deref(), Some("2000-02-29"));
+	}
+
+	#[test]
+	fn document_column_detection_defaults_to_enabled() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		assert!(config.get_document_column_detection("paper.pdf"));
+		config.set_document_column_detection("paper.pdf", false);
+		assert!(!config.get_document_column_detection("paper.pdf"));
+		assert!(config.get_document_column_detection("other.pdf"));
+	}
+
+	#[test]
+	fn document_heading_detection_can_be_turned_off_per_document() {
+		let config = initialized_config();
+		assert!(config.get_document_heading_detection("book.pdf"));
+		config.set_document_heading_detection("book.pdf", false);
+		assert!(!config.get_document_heading_detection("book.pdf"));
+		assert!(config.get_document_heading_detection("other.pdf"));
+		config.set_document_heading_detection("book.pdf", true);
+		assert!(config.get_document_heading_detection("book.pdf"));
+	}
+
+	fn sidecar_test_doc(name: &str) -> String {
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos());
+		let dir = std::env::temp_dir().join(format!("paperback_sidecar_{name}_{}_{nanos}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let doc = dir.join("book.epub");
+		fs::write(&doc, name).unwrap();
+		doc.to_string_lossy().into_owned()
+	}
+
+	fn initialized_config() -> ConfigManager {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		config
+	}
+
+	#[test]
+	fn legacy_sidecar_without_modified_is_imported_into_fresh_config() {
+		let doc = sidecar_test_doc("legacy");
+		fs::write(sidecar_path(&doc), "last_position = 42\n").unwrap();
+		let config = initialized_config();
+		assert_eq!(config.compare_sidecar(&doc), SidecarSync::SidecarNewer);
+		config.apply_sidecar(&doc);
+		assert_eq!(config.get_document_position(&doc), 42);
+		config.set_document_position(&doc, 50);
+		assert_eq!(config.compare_sidecar(&doc), SidecarSync::LocalNewer);
+	}
+
+	#[test]
+	fn bookmark_categories_round_trip_through_sidecar_and_old_entries_stay_uncategorized() {
+		let doc = sidecar_test_doc("categories");
+		fs::write(sidecar_path(&doc), "[[bookmarks]]\nstart = 5\nend = 5\nnote = \"old\"\n").unwrap();
+		let config = initialized_config();
+		config.apply_sidecar(&doc);
+		assert_eq!(config.get_bookmarks(&doc)[0].category, "");
+		config.add_bookmark(&doc, 20, 30, "");
+		config.update_bookmark_category(&doc, 20, 30, "Quote");
+		config.update_bookmark_category(&doc, 5, 5, "question");
+		assert_eq!(config.get_bookmark_categories(&doc), vec!["question".to_string(), "Quote".to_string()]);
+		let export = sidecar_path(&doc).with_extension("export");
+		config.export_document_settings(&doc, &export.to_string_lossy());
+		let other = initialized_config();
+		other.import_settings_from_file(&doc, &export.to_string_lossy());
+		let categories: Vec<String> = other.get_bookmarks(&doc).into_iter().map(|bm| bm.category).collect();
+		assert_eq!(categories, vec!["question".to_string(), "Quote".to_string()]);
+	}
+
+	#[test]
+	fn bookmarks_revision_moves_only_when_bookmarks_change() {
+		let doc = sidecar_test_doc("revision");
+		fs::write(sidecar_path(&doc), "[[bookmarks]]\nstart = 5\nend = 5\n").unwrap();
+		let config = initialized_config();
+		let mut revision = config.bookmarks_revision();
+		let mut changed = |config: &ConfigManager| {
+			let moved = config.bookmarks_revision() != revision;
+			revision = config.bookmarks_revision();
+			moved
+		};
+		config.toggle_bookmark(&doc, 10, 20, "");
+		assert!(changed(&config));
+		config.add_bookmark(&doc, 10, 20, "");
+		assert!(!changed(&config), "adding an existing bookmark changes nothing");
+		config.update_bookmark_note(&doc, 10, 20, "note");
+		assert!(changed(&config));
+		config.update_bookmark_category(&doc, 10, 20, "Quote");
+		assert!(!changed(&config));
+		config.remove_bookmark(&doc, 10, 20);
+		assert!(changed(&config));
+		config.remove_bookmark(&doc, 10, 20);
+		assert!(!changed(&config));
+		config.apply_sidecar(&doc);
+		assert!(changed(&config));
+	}
+
+	#[test]
+	fn pronunciation_rules_travel_in_the_sidecar_and_document_rules_come_first() {
+		let doc = sidecar_test_doc("pronunciation");
+		let config = initialized_config();
+		let rule = |pattern: &str, replacement: &str| PronunciationRule {
+			pattern: pattern.to_string(),
+			replacement: replacement.to_string(),
+			..PronunciationRule::default()
+		};
+		config.set_global_pronunciation_rules(&[rule("Fig.", "Figure")]);
+		config.set_document_pronunciation_rules(&doc, &[rule("et al.", "and others")]);
+		let patterns: Vec<String> = config.get_pronunciation_rules(&doc).into_iter().map(|r| r.pattern).collect();
+		assert_eq!(patterns, vec!["et al.".to_string(), "Fig.".to_string()]);
+		let export = sidecar_path(&doc).with_extension("export");
+		config.export_document_settings(&doc, &export.to_string_lossy());
+		let other = initialized_config();
+		other.import_settings_from_file(&doc, &export.to_string_lossy());
+		assert_eq!(other.get_document_pronunciation_rules(&doc), vec![rule("et al.", "and others")]);
+		assert!(other.get_global_pronunciation_rules().is_empty());
+	}
+
+	#[test]
+	fn furthest_position_only_moves_forward_and_travels_in_the_sidecar() {
+		let doc = sidecar_test_doc("furthest");
+		let config = initialized_config();
+		config.set_document_position(&doc, 40);
+		assert_eq!(config.get_document_furthest_position(&doc), 40);
+		config.set_document_furthest_position(&doc, 300);
+		config.set_document_furthest_position(&doc, 200);
+		config.set_document_position(&doc, 10);
+		assert_eq!(config.get_document_furthest_position(&doc), 300);
+		config.set_document_content_length(&doc, 400);
+		assert_eq!(config.get_document_furthest_percent(&doc), Some(75));
+		let export = sidecar_path(&doc).with_extension("export");
+		config.export_document_settings(&doc, &export.to_string_lossy());
+		let other = initialized_config();
+		other.import_settings_from_file(&doc, &export.to_string_lossy());
+		assert_eq!(other.get_document_position(&doc), 10);
+		assert_eq!(other.get_document_furthest_position(&doc), 300);
+	}
+
+	#[test]
+	fn quick_slots_are_overwritten_and_travel_in_the_sidecar() {
+		let doc = sidecar_test_doc("quick_slots");
+		let config = initialized_config();
+		assert_eq!(config.get_document_quick_slot(&doc, 3), None);
+		config.set_document_quick_slot(&doc, 3, 120);
+		config.set_document_quick_slot(&doc, 3, 80);
+		config.set_document_quick_slot(&doc, 0, 5);
+		assert_eq!(config.get_document_quick_slot(&doc, 3), Some(80));
+		assert!(config.get_bookmarks(&doc).is_empty());
+		let export = sidecar_path(&doc).with_extension("export");
+		config.export_document_settings(&doc, &export.to_string_lossy());
+		assert!(fs::read_to_string(&export).unwrap().contains("quick_slot_3 = 80"));
+		let other = initialized_config();
+		other.import_settings_from_file(&doc, &export.to_string_lossy());
+		assert_eq!(other.get_document_quick_slot(&doc, 0), Some(5));
+		assert_eq!(other.get_document_quick_slot(&doc, 3), Some(80));
+	}
+
+	#[test]
+	fn view_top_is_remembered_but_stays_on_this_machine() {
+		let doc = sidecar_test_doc("view_top");
+		let config = initialized_config();
+		assert_eq!(config.get_document_view_top(&doc), 0);
+		config.set_document_view_top(&doc, 4_200);
+		assert_eq!(config.get_document_view_top(&doc), 4_200);
+		let export = sidecar_path(&doc).with_extension("export");
+		config.export_document_settings(&doc, &export.to_string_lossy());
+		let other = initialized_config();
+		other.import_settings_from_file(&doc, &export.to_string_lossy());
+		assert_eq!(other.get_document_view_top(&doc), 0);
+	}
+
+	#[test]
+	fn document_notes_travel_in_the_sidecar() {
+		let doc = sidecar_test_doc("document_notes");
+		let config = initialized_config();
+		let notes = "Characters:\n- Ishmael, \"call me\" [narrator]\n\tKeep an eye on chapter 42.";
+		config.set_document_notes(&doc, notes);
+		assert_eq!(config.get_document_notes(&doc), notes);
+		let export = sidecar_path(&doc).with_extension("export");
+		config.export_document_settings(&doc, &export.to_string_lossy());
+		let other = initialized_config();
+		other.import_settings_from_file(&doc, &export.to_string_lossy());
+		assert_eq!(other.get_document_notes(&doc), notes);
+	}
+
+	#[test]
+	fn configs_from_older_versions_are_migrated_on_load() {
+		let path = std::env::temp_dir().join(format!("paperback_migrate_{}.toml", std::process::id()));
+		let doc = "[documents.doc_a]\npath = \"a.txt\"\n[[documents.doc_a.bookmarks]]\nstart = 1\nend = 2\n";
+		fs::write(&path, format!("version = 4\n{doc}")).unwrap();
+		let mut config = ConfigManager::new();
+		config.initialize(path.clone());
+		assert_eq!(config.data.borrow().version, CONFIG_VERSION);
+		assert_eq!(config.data.borrow().documents["doc_a"].bookmarks[0].category, "");
+		assert!(fs::read_to_string(&path).unwrap().contains(&format!("version = {CONFIG_VERSION}")));
+		let _ = fs::remove_file(&path);
+	}
+
+	#[test]
+	fn sidecar_round_trips_between_machines() {
+		let doc = sidecar_test_doc("round_trip");
+		let first = initialized_config();
+		first.set_document_position(&doc, 120);
+		assert!(first.write_sidecar_if_changed(&doc));
+		assert!(!first.write_sidecar_if_changed(&doc));
+		assert_eq!(first.compare_sidecar(&doc), SidecarSync::UpToDate);
+		let second = initialized_config();
+		assert_eq!(second.compare_sidecar(&doc), SidecarSync::SidecarNewer);
+		second.apply_sidecar(&doc);
+		assert_eq!(second.get_document_position(&doc), 120);
+		assert_eq!(second.compare_sidecar(&doc), SidecarSync::UpToDate);
+	}
+
+	#[test]
+	fn sidecar_changed_on_both_sides_is_a_conflict() {
+		let doc = sidecar_test_doc("conflict");
+		let config = initialized_config();
+		config.set_document_position(&doc, 10);
+		assert!(config.write_sidecar_if_changed(&doc));
+		let key = config.get_doc_key(&doc);
+		let synced = config.data.borrow().documents[&key].synced;
+		config.data.borrow_mut().documents.get_mut(&key).unwrap().modified = synced + 10;
+		fs::write(sidecar_path(&doc), format!("last_position = 99\nmodified = {}\n", synced + 20)).unwrap();
+		assert_eq!(config.compare_sidecar(&doc), SidecarSync::Conflict { local_position: 10, sidecar_position: 99 });
+		config.keep_local_over_sidecar(&doc);
+		assert_eq!(config.compare_sidecar(&doc), SidecarSync::UpToDate);
+	}
+
+	fn moved_test_dir(name: &str) -> PathBuf {
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos());
+		let dir = std::env::temp_dir().join(format!("paperback_moved_{name}_{}_{nanos}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	fn path_string(path: &Path) -> String {
+		path.to_string_lossy().into_owned()
+	}
+
+	#[test]
+	fn rename_document_moves_section_and_lists() {
+		let dir = moved_test_dir("rename");
+		let old = path_string(&dir.join("book.epub"));
+		let new = path_string(&dir.join("renamed.epub"));
+		fs::write(&old, "moved book").unwrap();
+		let config = initialized_config();
+		config.set_document_position(&old, 120);
+		config.add_bookmark(&old, 5, 10, "note");
+		config.add_recent_document(&old);
+		config.add_opened_document(&old);
+		fs::rename(&old, &new).unwrap();
+		config.rename_document(&old, &new);
+		assert_eq!(config.get_document_position(&new), 120);
+		assert_eq!(config.get_bookmarks(&new).len(), 1);
+		assert_eq!(config.get_recent_documents(), vec![new.clone()]);
+		assert_eq!(config.get_opened_documents(), vec![new.clone()]);
+		assert_eq!(config.get_all_documents(), vec![new]);
+		assert!(!config.data.borrow().path_hashes.contains_key(&old));
+	}
+
+	#[test]
+	fn rename_document_onto_path_with_its_own_section_keeps_one() {
+		let dir = moved_test_dir("collide");
+		let old = path_string(&dir.join("old.epub"));
+		let new = path_string(&dir.join("new.epub"));
+		fs::write(&old, "old copy").unwrap();
+		fs::write(&new, "new copy").unwrap();
+		let config = initialized_config();
+		config.set_document_position(&old, 300);
+		config.set_document_position(&new, 7);
+		config.add_recent_document(&new);
+		config.add_recent_document(&old);
+		config.rename_document(&old, &new);
+		assert_eq!(config.get_document_position(&new), 300);
+		assert_eq!(config.get_all_documents(), vec![new.clone()]);
+		assert_eq!(config.get_recent_documents(), vec![new.clone()]);
+		let data = config.data.borrow();
+		assert_eq!(data.documents.len(), 1);
+		assert!(data.path_hashes.values().all(|key| data.documents.contains_key(key)));
+	}
+
+	#[test]
+	fn rename_document_prefers_the_more_recently_changed_section() {
+		let dir = moved_test_dir("newer");
+		let old = path_string(&dir.join("old.epub"));
+		let new = path_string(&dir.join("new.epub"));
+		fs::write(&old, "old copy").unwrap();
+		fs::write(&new, "new copy").unwrap();
+		let config = initialized_config();
+		config.set_document_position(&old, 300);
+		config.set_document_position(&new, 7);
+		let new_key = config.get_doc_key(&new);
+		config.data.borrow_mut().documents.get_mut(&new_key).unwrap().modified += 10;
+		config.rename_document(&old, &new);
+		assert_eq!(config.get_document_position(&new), 7);
+		assert_eq!(config.get_all_documents(), vec![new]);
+	}
+
+	#[test]
+	fn find_moved_documents_matches_unique_name_and_size() {
+		let dir = moved_test_dir("library");
+		let library = dir.join("library");
+		fs::create_dir_all(library.join("nested")).unwrap();
+		fs::create_dir_all(library.join("a")).unwrap();
+		fs::create_dir_all(library.join("b")).unwrap();
+		let moved = path_string(&dir.join("moved.epub"));
+		let twin = path_string(&dir.join("twin.epub"));
+		fs::write(&moved, "moved contents").unwrap();
+		fs::write(&twin, "twin contents").unwrap();
+		let config = initialized_config();
+		config.record_document_session(&moved);
+		config.record_document_session(&twin);
+		let target = library.join("nested").join("moved.epub");
+		fs::rename(&moved, &target).unwrap();
+		fs::copy(&twin, library.join("a").join("twin.epub")).unwrap();
+		fs::rename(&twin, library.join("b").join("twin.epub")).unwrap();
+		assert_eq!(config.find_moved_documents(&library), vec![(moved, path_string(&target))]);
+	}
+
+	#[test]
+	fn document_anchor_is_forgotten_when_the_position_changes() {
+		let config = initialized_config();
+		config.set_document_position("anchored.epub", 40);
+		let anchor = PositionAnchor { snippet: "the text".to_string(), percent: 12.5 };
+		config.set_document_anchor("anchored.epub", anchor.clone());
+		config.set_document_position("anchored.epub", 40);
+		assert_eq!(config.get_document_anchor("anchored.epub"), Some(anchor));
+		config.set_document_position("anchored.epub", 41);
+		assert_eq!(config.get_document_anchor("anchored.epub"), None);
+	}
+
+	#[test]
+	fn document_without_anchor_key_loads() {
+		let doc: DocumentConfig = toml::from_str("path = \"a.epub\"\nlast_position = 5\n").unwrap();
+		assert_eq!(doc.position_anchor, None);
+		let serialized = toml::to_string(&doc).unwrap();
+		assert!(!serialized.contains("position_anchor"));
+	}
+
+	fn section(path: &str, position: i64, modified: i64) -> DocumentConfig {
+		DocumentConfig { path: path.to_string(), last_position: position, modified, ..DocumentConfig::default() }
+	}
+
+	#[test]
+	fn compaction_keeps_documents_with_bookmarks_or_recent_use() {
+		let now = unix_now();
+		let long_ago = now - (MISSING_DOCUMENT_PRUNE_DAYS + 5) * 86_400;
+		let mut data = ConfigData::default();
+		let mut bookmarked = section("gone/bookmarked.epub", 0, long_ago);
+		bookmarked.bookmarks.push(StoredBookmark { start: 1, end: 1, note: "Note".to_string(), ..Default::default() });
+		data.documents.insert("doc_bookmarked".to_string(), bookmarked);
+		let mut slotted = section("gone/slotted.epub", 0, long_ago);
+		slotted.quick_slots.insert(quick_slot_key(1), 40);
+		data.documents.insert("doc_slotted".to_string(), slotted);
+		data.documents.insert("doc_recent".to_string(), section("gone/recent.epub", 0, long_ago));
+		data.recent_documents.push("gone/recent.epub".to_string());
+		data.documents.insert("doc_unread".to_string(), section("gone/unread.epub", 0, now));
+		data.documents.insert("doc_missing".to_string(), section("gone/missing.epub", 500, long_ago));
+		data.documents.insert("doc_fresh".to_string(), section("gone/fresh.epub", 500, now));
+		data.documents.insert("doc_clipboard".to_string(), section("clipboard://2020-01-01-00-00", 20, long_ago));
+		data.path_hashes.insert("gone/unread.epub".to_string(), "doc_unread".to_string());
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		*config.data.borrow_mut() = data;
+		assert_eq!(config.compactable_document_count(), 3);
+		assert_eq!(config.compact_documents(), 3);
+		let data = config.data.borrow();
+		let mut kept: Vec<&str> = data.documents.keys().map(String::as_str).collect();
+		kept.sort_unstable();
+		assert_eq!(kept, ["doc_bookmarked", "doc_fresh", "doc_recent", "doc_slotted"]);
+		assert!(data.path_hashes.is_empty());
+	}
+
+	#[test]
+	fn startup_prunes_a_bloated_config_down_to_the_section_limit() {
+		let dir = std::env::temp_dir().join(format!("paperback_compact_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("Paperback.toml");
+		let mut data = ConfigData::default();
+		for index in 0..10_000_i64 {
+			let mut doc = section(&dir.join(format!("temp-{index}.html")).to_string_lossy(), 0, index);
+			if index % 1000 == 0 {
+				doc.bookmarks.push(StoredBookmark { start: index, end: index, ..Default::default() });
+			}
+			data.documents.insert(format!("doc_{index}"), doc);
+		}
+		fs::write(&path, toml::to_string_pretty(&data).unwrap()).unwrap();
+		let bloated_size = fs::metadata(&path).unwrap().len();
+		let mut config = ConfigManager::new();
+		config.initialize(path.clone());
+		let startup_size = fs::metadata(&path).unwrap().len();
+		assert_eq!(config.data.borrow().documents.len(), usize::try_from(DEFAULT_MAX_DOCUMENT_SECTIONS).unwrap());
+		assert!(startup_size * 4 < bloated_size, "{startup_size} bytes left of {bloated_size}");
+		// The oldest sections go first, so the newest unprotected ones are what's left.
+		assert!(config.data.borrow().documents.contains_key("doc_9999"));
+		assert!(!config.data.borrow().documents.contains_key("doc_1"));
+		config.compact_documents();
+		config.flush();
+		let compacted: ConfigData = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+		let mut kept: Vec<&String> = compacted.documents.keys().collect();
+		kept.sort_unstable();
+		assert_eq!(kept.len(), 10);
+		assert!(kept.iter().all(|key| compacted.documents[*key].bookmarks.len() == 1));
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn backup_round_trips_every_document_section() {
+		let mut source = ConfigManager::new();
+		source.initialized = true;
+		let notes = "Chapter 1: the \"start\"\r\nkey = value\n[section]\ttab\\backslash\u{0}\u{feff} café 🙂\n";
+		source.set_document_position("/books/a.epub", 120);
+		source.set_document_notes("/books/a.epub", notes);
+		source.add_bookmark("/books/a.epub", 5, 9, "line one\nline two: done");
+		source.update_bookmark_category("/books/a.epub", 5, 9, "Quote");
+		source.set_document_quick_slot("/books/a.epub", 3, 77);
+		source.add_document_reading_time("/books/a.epub", 90);
+		source.set_document_position("C:\\books\\b.txt", 4);
+		source.set_app_bool("word_wrap", true);
+		let json = source.backup_json().unwrap();
+
+		let mut restored = ConfigManager::new();
+		restored.initialized = true;
+		let summary = restored.restore_backup_json(&json).unwrap();
+		assert_eq!(summary, RestoreSummary { restored: 2, kept: 0 });
+		assert_eq!(restored.get_document_position("/books/a.epub"), 120);
+		assert_eq!(restored.get_document_notes("/books/a.epub"), notes);
+		let bookmarks = restored.get_bookmarks("/books/a.epub");
+		assert_eq!(bookmarks.len(), 1);
+		assert_eq!(bookmarks[0].note, "line one\nline two: done");
+		assert_eq!(bookmarks[0].category, "Quote");
+		assert_eq!(restored.get_document_quick_slot("/books/a.epub", 3), Some(77));
+		assert_eq!(restored.get_document_reading_stats("/books/a.epub").seconds_read, 90);
+		assert_eq!(restored.get_document_position("C:\\books\\b.txt"), 4);
+		assert!(restored.get_app_bool("word_wrap", false));
+		assert_eq!(restored.backup_json().unwrap(), json);
+	}
+
+	#[test]
+	fn restoring_a_backup_keeps_the_more_recently_changed_copy() {
+		let mut source = ConfigManager::new();
+		source.initialized = true;
+		source.set_document_position("old.epub", 10);
+		source.set_document_position("new.epub", 20);
+		let mut backup: BackupData = serde_json::from_str(&source.backup_json().unwrap()).unwrap();
+		for (path, changed) in [("old.epub", 100), ("new.epub", 300)] {
+			let doc = backup.documents.get_mut(path).unwrap();
+			doc.updated = changed;
+			doc.modified = changed;
+		}
+
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		config.set_document_position("old.epub", 11);
+		config.set_document_position("new.epub", 21);
+		config.set_document_opened("new.epub", true);
+		for doc in config.data.borrow_mut().documents.values_mut() {
+			doc.updated = 200;
+			doc.modified = 200;
+		}
+		let summary = config.restore_backup_json(&serde_json::to_string(&backup).unwrap()).unwrap();
+		assert_eq!(summary, RestoreSummary { restored: 1, kept: 1 });
+		assert_eq!(config.get_document_position("old.epub"), 11);
+		assert_eq!(config.get_document_position("new.epub"), 20);
+		let key = config.get_doc_key("new.epub");
+		assert!(config.data.borrow().documents[&key].opened);
+	}
+
+	#[test]
+	fn restoring_a_backup_keeps_this_machines_own_settings() {
+		let mut source = ConfigManager::new();
+		source.initialized = true;
+		source.set_app_string("library_folder", "/home/reader/books");
+		source.set_app_bool("session_running", true);
+		source.set_app_bool("word_wrap", true);
+		source.set_app_string("speech_voice", "backup voice");
+		let json = source.backup_json().unwrap();
+		assert!(!json.contains("library_folder"));
+		assert!(!json.contains("session_running"));
+
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		config.set_app_string("library_folder", "D:\\Books");
+		config.set_app_bool("session_running", true);
+		config.set_app_string("local_only", "kept");
+		config.set_app_string("speech_voice", "local voice");
+		config.restore_backup_json(&json).unwrap();
+		assert_eq!(config.get_app_string("library_folder", ""), "D:\\Books");
+		assert!(config.get_app_bool("session_running", false));
+		assert_eq!(config.get_app_string("local_only", ""), "kept");
+		assert_eq!(config.get_app_string("speech_voice", ""), "backup voice");
+		assert!(config.get_app_bool("word_wrap", false));
+	}
+
+	#[test]
+	fn backups_from_a_newer_version_are_refused() {
+		let mut config = ConfigManager::new();
+		config.initialized = true;
+		let json = format!(r#"{{"version": {}, "documents": {{}}}}"#, BACKUP_VERSION + 1);
+		assert!(matches!(config.restore_backup_json(&json), Err(BackupError::UnsupportedVersion(_))));
+		assert!(matches!(config.restore_backup_json("[section]\nkey = value"), Err(BackupError::Invalid(_))));
+	}
 }
@@ -1,12 +1,24 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range, sync::Arc};
 
 use bitflags::bitflags;
 
 use crate::{
 	types::HeadingInfo,
-	util::text::{display_len, is_space_like},
+	util::{
+		language,
+		text::{
+			OffsetMap, WordCount, ch_width, count_words, display_len, display_to_byte_index, is_space_like,
+			normalize_newlines, normalize_typography, remove_ranges,
+		},
+	},
 };
 
+pub mod page_loader;
+pub mod running_lines;
+
+use page_loader::PageLoader;
+use running_lines::RunningLines;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(i32)]
 pub enum MarkerType {
@@ -29,6 +41,13 @@ pub enum MarkerType {
 	Bold = 16,
 	Italic = 17,
 	Underline = 18,
+	BlockQuote = 19,
+	CodeBlock = 20,
+	/// A footnote or endnote body, such as an EPUB `aside` typed as a footnote.
+	Note = 21,
+	/// A highlight or note another application left in the file, such as a PDF annotation. `text`
+	/// is its note, `reference` its author and `level` the 1-based page it was made on.
+	Annotation = 22,
 }
 
 impl From<MarkerType> for i32 {
@@ -70,6 +89,10 @@ impl TryFrom<i32> for MarkerType {
 			16 => Ok(Self::Bold),
 			17 => Ok(Self::Italic),
 			18 => Ok(Self::Underline),
+			19 => Ok(Self::BlockQuote),
+			20 => Ok(Self::CodeBlock),
+			21 => Ok(Self::Note),
+			22 => Ok(Self::Annotation),
 			_ => Err(()),
 		}
 	}
@@ -124,6 +147,9 @@ pub struct DocumentBuffer {
 	content_char_count: usize,
 	newline_char_positions: Vec<usize>,
 	char_to_byte_map: Vec<usize>,
+	/// Char index and display offset of every character wider than one display unit (astral-plane
+	/// characters where the text control counts UTF-16 code units). Always empty on GTK.
+	wide_chars: Vec<(usize, usize)>,
 }
 
 impl DocumentBuffer {
@@ -136,6 +162,7 @@ impl DocumentBuffer {
 			content_char_count: 0,
 			newline_char_positions: Vec::new(),
 			char_to_byte_map: Vec::new(),
+			wide_chars: Vec::new(),
 		}
 	}
 
@@ -145,10 +172,13 @@ impl DocumentBuffer {
 		let mut char_count = 0usize;
 		let mut newline_char_positions = Vec::new();
 		let mut char_to_byte_map = Vec::with_capacity(content.len().min(1024));
+		let mut wide_chars = Vec::new();
 		for (byte_idx, c) in content.char_indices() {
 			char_to_byte_map.push(byte_idx);
 			if c == '\n' {
 				newline_char_positions.push(char_count);
+			} else if ch_width(c) > 1 {
+				wide_chars.push((char_count, char_count + wide_chars.len()));
 			}
 			char_count += 1;
 		}
@@ -160,9 +190,18 @@ impl DocumentBuffer {
 			content_char_count: char_count,
 			newline_char_positions,
 			char_to_byte_map,
+			wide_chars,
 		}
 	}
 
+	/// An empty buffer whose text will be appended after `position` units of existing content, so
+	/// that markers added to it carry their final offsets. Only `current_position` accounts for the
+	/// content before it; the index lookups cover this buffer's own text.
+	#[must_use]
+	pub fn continuing_at(position: usize) -> Self {
+		Self { content_display_len: position, ..Self::new() }
+	}
+
 	pub fn add_marker(&mut self, marker: Marker) {
 		self.markers.push(marker);
 	}
@@ -181,6 +220,8 @@ impl DocumentBuffer {
 			self.char_to_byte_map.push(start_byte + byte_idx);
 			if c == '\n' {
 				self.newline_char_positions.push(base + count);
+			} else if ch_width(c) > 1 {
+				self.wide_chars.push((base + count, base + count + self.wide_chars.len()));
 			}
 			count += 1;
 		}
@@ -200,6 +241,36 @@ impl DocumentBuffer {
 		self.char_to_byte_map.binary_search(&byte_index).unwrap_or_else(|idx| idx)
 	}
 
+	/// Char index of the character at display offset `display` (the unit of marker positions and caret
+	/// offsets). An offset inside a surrogate pair resolves to that character; offsets past the end
+	/// clamp to the char count.
+	#[must_use]
+	pub fn char_index_for_display(&self, display: usize) -> usize {
+		// Every wide character that ends at or before `display` accounts for one extra unit.
+		let wide_before = self.wide_chars.partition_point(|&(_, start)| start + 2 <= display);
+		let char_index = match self.wide_chars.get(wide_before) {
+			Some(&(wide, start)) if start + 1 == display => wide,
+			_ => display - wide_before,
+		};
+		char_index.min(self.content_char_count)
+	}
+
+	#[must_use]
+	pub fn display_index_for_char(&self, char_index: usize) -> usize {
+		let char_index = char_index.min(self.content_char_count);
+		char_index + self.wide_chars.partition_point(|&(c, _)| c < char_index)
+	}
+
+	#[must_use]
+	pub fn byte_index_for_display(&self, display: usize) -> usize {
+		self.byte_index_for_char(self.char_index_for_display(display))
+	}
+
+	#[must_use]
+	pub fn display_index_for_byte(&self, byte_index: usize) -> usize {
+		self.display_index_for_char(self.char_index_for_byte(byte_index))
+	}
+
 	#[must_use]
 	pub const fn current_position(&self) -> usize {
 		self.content_display_len
@@ -237,9 +308,15 @@ impl TocItem {
 	}
 }
 
+/// Han and Hiragana characters a reader gets through in the time one alphabetic word takes.
+const IDEOGRAPHS_PER_READING_WORD: usize = 2;
+
 #[derive(Debug, Clone, Default)]
 pub struct DocumentStats {
 	pub word_count: usize,
+	/// `word_count` with Chinese and Japanese characters weighted down to match the reading speed,
+	/// which is given in alphabetic words per minute; reading-time estimates divide this instead.
+	pub reading_words: usize,
 	pub line_count: usize,
 	pub char_count: usize,
 	pub char_count_no_whitespace: usize,
@@ -250,12 +327,199 @@ impl DocumentStats {
 	pub fn from_text(text: &str) -> Self {
 		let char_count = text.chars().count();
 		let line_count = text.lines().count();
-		let word_count = text.split_whitespace().count();
+		let WordCount { words, ideographic } = count_words(text);
+		let reading_words = words - ideographic + ideographic.div_ceil(IDEOGRAPHS_PER_READING_WORD);
 		let char_count_no_whitespace = text.chars().filter(|c| !is_space_like(*c)).count();
-		Self { word_count, line_count, char_count, char_count_no_whitespace }
+		Self { word_count: words, reading_words, line_count, char_count, char_count_no_whitespace }
+	}
+}
+
+/// Descriptive metadata a format can carry beyond title and author, in the order Document Info
+/// lists it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MetadataField {
+	Series,
+	Publisher,
+	Date,
+	Language,
+	Subject,
+	Identifier,
+}
+
+impl MetadataField {
+	/// Stable name used by the FFI and IPC consumers.
+	#[must_use]
+	pub const fn key(self) -> &'static str {
+		match self {
+			Self::Series => "series",
+			Self::Publisher => "publisher",
+			Self::Date => "date",
+			Self::Language => "language",
+			Self::Subject => "subject",
+			Self::Identifier => "identifier",
+		}
+	}
+}
+
+/// Direction a document's paragraphs run in, which decides which edge of the view they start at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+	#[default]
+	LeftToRight,
+	RightToLeft,
+}
+
+impl TextDirection {
+	/// Stable name used in the configuration file.
+	#[must_use]
+	pub const fn key(self) -> &'static str {
+		match self {
+			Self::LeftToRight => "ltr",
+			Self::RightToLeft => "rtl",
+		}
+	}
+
+	#[must_use]
+	pub fn from_key(key: &str) -> Option<Self> {
+		match key {
+			"ltr" => Some(Self::LeftToRight),
+			"rtl" => Some(Self::RightToLeft),
+			_ => None,
+		}
 	}
 }
 
+/// Whether a document's lines wrap at the edge of the view or are shown as broken in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineLayout {
+	#[default]
+	Wrapped,
+	/// The line breaks are part of the text, as in poetry and scripts, so lines are never wrapped.
+	Preformatted,
+}
+
+/// Share of the sampled lines, in percent, that must be short for a document to be preformatted.
+const PREFORMATTED_SHORT_LINE_PERCENT: usize = 70;
+/// Non-blank lines [`LineLayout::detect`] looks at, spread evenly over the document.
+const LINE_LAYOUT_SAMPLE_LINES: usize = 2_000;
+/// Fewer non-blank lines than this say too little about the layout to leave the global setting.
+const LINE_LAYOUT_MIN_LINES: usize = 8;
+
+impl LineLayout {
+	/// Stable name used in the configuration file.
+	#[must_use]
+	pub const fn key(self) -> &'static str {
+		match self {
+			Self::Wrapped => "wrap",
+			Self::Preformatted => "preformatted",
+		}
+	}
+
+	#[must_use]
+	pub fn from_key(key: &str) -> Option<Self> {
+		match key {
+			"wrap" => Some(Self::Wrapped),
+			"preformatted" => Some(Self::Preformatted),
+			_ => None,
+		}
+	}
+
+	/// Guesses whether `text` relies on its own line breaks: it does when more than 70% of a sample
+	/// of its non-blank lines are shorter than `max_line_length` characters.
+	#[must_use]
+	pub fn detect(text: &str, max_line_length: usize) -> Self {
+		let lines: Vec<&str> = text.lines().map(str::trim_end).filter(|line| !line.trim_start().is_empty()).collect();
+		if lines.len() < LINE_LAYOUT_MIN_LINES {
+			return Self::Wrapped;
+		}
+		let step = lines.len().div_ceil(LINE_LAYOUT_SAMPLE_LINES);
+		let (mut sampled, mut short) = (0, 0);
+		for line in lines.iter().step_by(step) {
+			sampled += 1;
+			if line.chars().count() < max_line_length {
+				short += 1;
+			}
+		}
+		if short * 100 > sampled * PREFORMATTED_SHORT_LINE_PERCENT { Self::Preformatted } else { Self::Wrapped }
+	}
+}
+
+/// The kind of a structural landmark such as the preface or the index, as named by an EPUB
+/// `landmarks` nav, an EPUB 2 guide or an `epub:type`/`role` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandmarkKind {
+	Cover,
+	TitlePage,
+	Toc,
+	Foreword,
+	Preface,
+	Introduction,
+	BodyMatter,
+	Appendix,
+	Acknowledgments,
+	Endnotes,
+	Glossary,
+	Bibliography,
+	Index,
+}
+
+impl LandmarkKind {
+	/// Reads a whitespace-separated `epub:type`, DPUB-ARIA `role` (`doc-` prefixed) or guide `type`
+	/// value, returning the first token that names a landmark.
+	#[must_use]
+	pub fn from_semantic_type(value: &str) -> Option<Self> {
+		value.split_ascii_whitespace().find_map(|token| {
+			let token = token.to_ascii_lowercase();
+			let token = token.strip_prefix("doc-").unwrap_or(&token);
+			match token {
+				"cover" => Some(Self::Cover),
+				"titlepage" | "title-page" => Some(Self::TitlePage),
+				"toc" => Some(Self::Toc),
+				"foreword" => Some(Self::Foreword),
+				"preface" => Some(Self::Preface),
+				"introduction" => Some(Self::Introduction),
+				"bodymatter" | "text" => Some(Self::BodyMatter),
+				"appendix" => Some(Self::Appendix),
+				"acknowledgments" | "acknowledgements" => Some(Self::Acknowledgments),
+				"endnotes" | "rearnotes" | "notes" => Some(Self::Endnotes),
+				"glossary" => Some(Self::Glossary),
+				"bibliography" => Some(Self::Bibliography),
+				"index" => Some(Self::Index),
+				_ => None,
+			}
+		})
+	}
+
+	/// Stable name used by the FFI and IPC consumers.
+	#[must_use]
+	pub const fn key(self) -> &'static str {
+		match self {
+			Self::Cover => "cover",
+			Self::TitlePage => "titlepage",
+			Self::Toc => "toc",
+			Self::Foreword => "foreword",
+			Self::Preface => "preface",
+			Self::Introduction => "introduction",
+			Self::BodyMatter => "bodymatter",
+			Self::Appendix => "appendix",
+			Self::Acknowledgments => "acknowledgments",
+			Self::Endnotes => "endnotes",
+			Self::Glossary => "glossary",
+			Self::Bibliography => "bibliography",
+			Self::Index => "index",
+		}
+	}
+}
+
+/// A landmark resolved to a position in the text. `title` is the label the file gives it, empty
+/// when it only marks the element with its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Landmark {
+	pub kind: LandmarkKind,
+	pub title: String,
+	pub offset: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct Document {
 	pub title: String,
@@ -266,6 +530,28 @@ pub struct Document {
 	pub spine_items: Vec<String>,
 	pub manifest_items: HashMap<String, String>,
 	pub stats: DocumentStats,
+	/// ISO 639-1 code of the detected dominant language, empty when unknown.
+	pub language: String,
+	/// Detected direction of the text, left to right unless it is mostly Hebrew, Arabic or the like.
+	pub direction: TextDirection,
+	/// Flat keyword index (e.g. a CHM `.hhk` file), empty when the format has none.
+	pub index_items: Vec<TocItem>,
+	/// Whether typographic characters were rewritten for speech after parsing.
+	pub typography_normalized: bool,
+	/// Extracts the pages a lazily loaded document has not read yet; `None` once every page is in
+	/// the buffer.
+	pub page_loader: Option<Arc<PageLoader>>,
+	/// Running headers and footers removed after parsing, or `None` when none were.
+	pub running_lines: Option<RunningLines>,
+	/// Metadata read from the file (OPF, FB2 description, PDF Info), one entry per value.
+	pub metadata: Vec<(MetadataField, String)>,
+	/// The printed page number of every page, such as "xi" for a preface page, when the file
+	/// labels its pages; empty when it doesn't. Includes pages a lazily loaded PDF hasn't read yet.
+	pub page_labels: Vec<String>,
+	/// Landmarks such as the preface, bibliography and index, in the order the file lists them.
+	pub landmarks: Vec<Landmark>,
+	/// Whether the file was damaged and only the parts that could be recovered were read.
+	pub damaged: bool,
 }
 
 impl Document {
@@ -280,6 +566,16 @@ impl Document {
 			spine_items: Vec::new(),
 			manifest_items: HashMap::new(),
 			stats: DocumentStats::default(),
+			language: String::new(),
+			direction: TextDirection::LeftToRight,
+			index_items: Vec::new(),
+			typography_normalized: false,
+			page_loader: None,
+			running_lines: None,
+			metadata: Vec::new(),
+			page_labels: Vec::new(),
+			landmarks: Vec::new(),
+			damaged: false,
 		}
 	}
 
@@ -295,13 +591,194 @@ impl Document {
 		self
 	}
 
+	/// Records a metadata value, ignoring blank values and ones already recorded for the field.
+	pub fn add_metadata(&mut self, field: MetadataField, value: &str) {
+		let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+		if value.is_empty() || self.metadata.iter().any(|(f, v)| *f == field && *v == value) {
+			return;
+		}
+		self.metadata.push((field, value));
+	}
+
+	/// Metadata grouped by field in display order, with the values of repeated fields (several
+	/// identifiers or subjects) joined by semicolons.
+	#[must_use]
+	pub fn metadata_entries(&self) -> Vec<(MetadataField, String)> {
+		let mut entries: Vec<(MetadataField, String)> = Vec::new();
+		let mut metadata = self.metadata.clone();
+		metadata.sort_by_key(|(field, _)| *field);
+		for (field, value) in metadata {
+			match entries.last_mut() {
+				Some((last, joined)) if *last == field => {
+					joined.push_str("; ");
+					joined.push_str(&value);
+				}
+				_ => entries.push((field, value)),
+			}
+		}
+		entries
+	}
+
+	/// Records a landmark, ignoring one of the same kind already recorded at the same offset.
+	pub fn add_landmark(&mut self, kind: LandmarkKind, title: &str, offset: usize) {
+		let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+		if self.landmarks.iter().any(|landmark| landmark.kind == kind && landmark.offset == offset) {
+			return;
+		}
+		self.landmarks.push(Landmark { kind, title, offset });
+	}
+
 	pub fn set_buffer(&mut self, buffer: DocumentBuffer) {
 		self.buffer = buffer;
 	}
 
+	/// Rewrites CRLF and lone CR line endings as `\n` and moves every offset to match, so line
+	/// numbers agree wherever they're counted.
+	pub fn normalize_newlines(&mut self) {
+		if !self.buffer.content.contains('\r') {
+			return;
+		}
+		let (content, map) = normalize_newlines(&self.buffer.content);
+		self.replace_content(content, &map);
+	}
+
+	/// Puts `notice` on a line of its own before the text, moving everything else down.
+	pub fn prepend_notice(&mut self, notice: &str) {
+		let line = format!("{notice}\n");
+		let map = OffsetMap::insertion(0, display_len(&line));
+		let content = line + &self.buffer.content;
+		self.replace_content(content, &map);
+	}
+
+	/// Replaces quotes, dashes, ellipses and special spaces with plain equivalents and moves every
+	/// offset recorded against the old text (markers, TOC, index and anchors) to match.
+	pub fn normalize_typography(&mut self) {
+		let (content, map) = normalize_typography(&self.buffer.content);
+		if content == self.buffer.content {
+			return;
+		}
+		self.typography_normalized = true;
+		self.replace_content(content, &map);
+	}
+
+	/// Removes lines repeated at the top or bottom of most pages, such as the book's title and the
+	/// page numbers, and moves every offset to match.
+	pub fn strip_running_lines(&mut self) {
+		let mut page_starts: Vec<usize> = self
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::PageBreak)
+			.map(|marker| marker.position)
+			.collect();
+		page_starts.sort_unstable();
+		page_starts.dedup();
+		let Some(mut running_lines) = RunningLines::detect(&self.buffer.content, &page_starts) else {
+			return;
+		};
+		let content = running_lines.strip(&self.buffer.content, &page_starts);
+		self.replace_content(content, running_lines.offset_map());
+		self.running_lines = Some(running_lines);
+	}
+
+	/// Removes the `"[Image]"` placeholders left for images without a description, along with the
+	/// line break after any that stood on a line of their own or the space that separated one from
+	/// the surrounding words, and moves every offset to match.
+	pub fn remove_unlabeled_images(&mut self) {
+		let content = &self.buffer.content;
+		let mut ranges: Vec<Range<usize>> = self
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::Image && marker.text.is_empty() && marker.length > 0)
+			.map(|marker| {
+				let start = marker.position;
+				let end = start + marker.length;
+				let before = content[..display_to_byte_index(content, start)].chars().next_back();
+				let after = content[display_to_byte_index(content, end)..].chars().next();
+				let line_start = matches!(before, None | Some('\n'));
+				let eat_next = match after {
+					Some('\n') => line_start,
+					Some(' ') => line_start || before == Some(' '),
+					_ => false,
+				};
+				if eat_next { start..end + 1 } else { start..end }
+			})
+			.collect();
+		if ranges.is_empty() {
+			return;
+		}
+		ranges.sort_by_key(|range| range.start);
+		ranges.dedup_by_key(|range| range.start);
+		self.buffer.markers.retain(|marker| !(marker.mtype == MarkerType::Image && marker.text.is_empty()));
+		let (content, map) = remove_ranges(content, &ranges);
+		self.replace_content(content, &map);
+	}
+
+	/// Swaps in a rewritten copy of the text, moving markers, TOC, index, landmarks and anchors
+	/// through `map`.
+	fn replace_content(&mut self, content: String, map: &OffsetMap) {
+		let markers = std::mem::take(&mut self.buffer.markers);
+		let mut buffer = DocumentBuffer::with_content(content);
+		for mut marker in markers {
+			remap_marker(&mut marker, map);
+			buffer.add_marker(marker);
+		}
+		self.buffer = buffer;
+		if map.is_identity() {
+			return;
+		}
+		remap_toc_offsets(&mut self.toc_items, map);
+		remap_toc_offsets(&mut self.index_items, map);
+		for landmark in &mut self.landmarks {
+			landmark.offset = map.map(landmark.offset);
+		}
+		for offset in self.id_positions.values_mut() {
+			*offset = map.map(*offset);
+		}
+	}
+
 	pub fn compute_stats(&mut self) {
 		self.stats = DocumentStats::from_text(&self.buffer.content);
 	}
+
+	/// Detects the dominant language from the text, falling back to the language the file declares
+	/// when the text is too short or mixed to tell.
+	pub fn detect_language(&mut self) {
+		let declared = || {
+			self.metadata
+				.iter()
+				.filter(|(field, _)| *field == MetadataField::Language)
+				.find_map(|(_, tag)| language::language_from_tag(tag))
+		};
+		self.language =
+			language::detect_language(&self.buffer.content).or_else(declared).unwrap_or_default().to_string();
+	}
+
+	/// Detects whether the text runs right to left, falling back to the direction of the language the
+	/// file declares when the text has no letters to go by.
+	pub fn detect_direction(&mut self) {
+		let declared = || {
+			self.metadata
+				.iter()
+				.filter(|(field, _)| *field == MetadataField::Language)
+				.find_map(|(_, tag)| language::direction_of_tag(tag))
+		};
+		self.direction = language::detect_direction(&self.buffer.content).or_else(declared).unwrap_or_default();
+	}
+}
+
+fn remap_marker(marker: &mut Marker, map: &OffsetMap) {
+	let end = map.map(marker.position + marker.length);
+	marker.position = map.map(marker.position);
+	marker.length = end - marker.position;
+}
+
+fn remap_toc_offsets(items: &mut [TocItem], map: &OffsetMap) {
+	for item in items {
+		item.offset = map.map(item.offset);
+		remap_toc_offsets(&mut item.children, map);
+	}
 }
 
 impl Default for Document {
@@ -338,16 +815,142 @@ pub struct ContainerSpan {
 	pub mtype: MarkerType,
 }
 
+/// The structural region a position falls in: the innermost list, table, block quote or code block
+/// around it, and within a list the item holding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructureRegion {
+	pub span: ContainerSpan,
+	/// Number of items when the region is a list, 0 otherwise.
+	pub item_count: i32,
+	/// 1-based index of the list item holding the position, 0 when it is not inside an item.
+	pub item_index: i32,
+}
+
+/// How the structural region changed between two caret positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureTransition {
+	Entered(StructureRegion),
+	/// Left a region of this type, either to the surrounding text or to an enclosing region.
+	Left(MarkerType),
+}
+
+const fn is_structure_marker(marker_type: MarkerType) -> bool {
+	matches!(marker_type, MarkerType::List | MarkerType::Table | MarkerType::BlockQuote | MarkerType::CodeBlock)
+}
+
+/// Compares the regions before and after a caret move. Moving between items of the same list is
+/// not a transition; moving out of a nested region into the one around it is reported as leaving.
+#[must_use]
+pub fn structure_transition(
+	previous: Option<StructureRegion>,
+	current: Option<StructureRegion>,
+) -> Option<StructureTransition> {
+	match (previous, current) {
+		(Some(prev), Some(cur)) if prev.span == cur.span => None,
+		(Some(prev), Some(cur)) if cur.span.start <= prev.span.start && prev.span.end <= cur.span.end => {
+			Some(StructureTransition::Left(prev.span.mtype))
+		}
+		(_, Some(cur)) => Some(StructureTransition::Entered(cur)),
+		(Some(prev), None) => Some(StructureTransition::Left(prev.span.mtype)),
+		(None, None) => None,
+	}
+}
+
+/// The text cut into non-overlapping segments, each tagged with the innermost region covering it,
+/// so finding the region at a position is a binary search.
+#[derive(Debug, Clone, Default)]
+struct StructureIndex {
+	starts: Vec<usize>,
+	regions: Vec<Option<StructureRegion>>,
+}
+
+struct OpenInterval {
+	end: usize,
+	/// `None` for a list item.
+	region: Option<StructureRegion>,
+	/// Items seen so far, for a list; the item's own index, for an item.
+	items: i32,
+}
+
+impl StructureIndex {
+	/// Sweeps the region and list item markers (which must carry lengths) in position order.
+	fn build(markers: &[Marker]) -> Self {
+		let mut intervals: Vec<&Marker> = markers
+			.iter()
+			.filter(|m| m.length > 0 && (is_structure_marker(m.mtype) || m.mtype == MarkerType::ListItem))
+			.collect();
+		// Outer intervals first; a list item sharing its list's exact span comes after the list.
+		intervals.sort_by(|a, b| {
+			a.position
+				.cmp(&b.position)
+				.then_with(|| b.length.cmp(&a.length))
+				.then_with(|| (a.mtype == MarkerType::ListItem).cmp(&(b.mtype == MarkerType::ListItem)))
+		});
+		let mut boundaries: Vec<usize> = intervals.iter().flat_map(|m| [m.position, m.position + m.length]).collect();
+		boundaries.sort_unstable();
+		boundaries.dedup();
+		let mut index = Self::default();
+		let mut open: Vec<OpenInterval> = Vec::new();
+		let mut next = 0;
+		for boundary in boundaries {
+			open.retain(|interval| interval.end > boundary);
+			while let Some(marker) = intervals.get(next).filter(|m| m.position <= boundary) {
+				next += 1;
+				let end = marker.position + marker.length;
+				if marker.mtype == MarkerType::ListItem {
+					let item_index = open
+						.iter_mut()
+						.rev()
+						.find(|interval| interval.region.is_some())
+						.filter(|interval| interval.region.is_some_and(|r| r.span.mtype == MarkerType::List))
+						.map_or(0, |list| {
+							list.items += 1;
+							list.items
+						});
+					open.push(OpenInterval { end, region: None, items: item_index });
+				} else {
+					let span = ContainerSpan { start: marker.position, end, mtype: marker.mtype };
+					let item_count = if marker.mtype == MarkerType::List { marker.level } else { 0 };
+					let region = StructureRegion { span, item_count, item_index: 0 };
+					open.push(OpenInterval { end, region: Some(region), items: 0 });
+				}
+			}
+			let region = Self::innermost(&open);
+			if index.regions.last() != Some(&region) {
+				index.starts.push(boundary);
+				index.regions.push(region);
+			}
+		}
+		index
+	}
+
+	fn innermost(open: &[OpenInterval]) -> Option<StructureRegion> {
+		let container = open.iter().rposition(|interval| interval.region.is_some())?;
+		let mut region = open[container].region?;
+		if let Some(item) = open[container + 1..].iter().rev().find(|interval| interval.region.is_none()) {
+			region.item_index = item.items;
+		}
+		Some(region)
+	}
+
+	fn region_at(&self, position: usize) -> Option<StructureRegion> {
+		let segment = self.starts.partition_point(|&start| start <= position).checked_sub(1)?;
+		self.regions[segment]
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct DocumentHandle {
 	doc: Document,
+	structure: StructureIndex,
 }
 
 impl DocumentHandle {
 	#[must_use]
 	pub fn new(mut doc: Document) -> Self {
 		doc.buffer.markers.sort_by_key(|m| m.position);
-		Self { doc }
+		let structure = StructureIndex::build(&doc.buffer.markers);
+		Self { doc, structure }
 	}
 
 	#[must_use]
@@ -355,6 +958,17 @@ impl DocumentHandle {
 		&self.doc
 	}
 
+	/// The document text, borrowed rather than copied, since a book can run to many megabytes.
+	#[must_use]
+	pub fn content_ref(&self) -> &str {
+		&self.doc.buffer.content
+	}
+
+	#[must_use]
+	pub fn index_items(&self) -> &[TocItem] {
+		&self.doc.index_items
+	}
+
 	fn markers_by_type(&self, marker_type: MarkerType) -> impl Iterator<Item = (usize, &Marker)> {
 		self.doc.buffer.markers.iter().enumerate().filter(move |(_, m)| m.mtype == marker_type)
 	}
@@ -431,6 +1045,89 @@ impl DocumentHandle {
 			.min_by(|a, b| b.start.cmp(&a.start).then_with(|| a.end.cmp(&b.end)))
 	}
 
+	/// The innermost list, table, block quote or code block around `position`. Looked up in an
+	/// index built when the handle is created, so it is cheap enough to call on every caret move.
+	#[must_use]
+	pub fn structure_region(&self, position: usize) -> Option<StructureRegion> {
+		self.structure.region_at(position)
+	}
+
+	/// Whether every page is in the buffer. Only lazily loaded PDFs start out partially loaded.
+	#[must_use]
+	pub const fn is_fully_loaded(&self) -> bool {
+		self.doc.page_loader.is_none()
+	}
+
+	/// The document's page count, including pages that have not been extracted yet.
+	#[must_use]
+	pub fn total_pages(&self) -> usize {
+		self.doc
+			.page_loader
+			.as_ref()
+			.map_or_else(|| self.count_markers_by_type(MarkerType::PageBreak), |loader| loader.total_pages())
+	}
+
+	/// Pages whose text is already in the buffer.
+	#[must_use]
+	pub fn loaded_pages(&self) -> usize {
+		self.count_markers_by_type(MarkerType::PageBreak)
+	}
+
+	/// Whether pages that were asked for are still being extracted.
+	#[must_use]
+	pub fn is_loading_pages(&self) -> bool {
+		self.doc.page_loader.as_ref().is_some_and(|loader| loader.is_busy())
+	}
+
+	/// Asks the background loader for the pages in `pages`, and every page before them, since pages
+	/// are appended in order. Returns immediately; [`Self::apply_loaded_pages`] picks them up.
+	pub fn ensure_pages_loaded(&self, pages: Range<usize>) {
+		if let Some(loader) = &self.doc.page_loader {
+			loader.request(pages.end);
+		}
+	}
+
+	/// Like [`Self::ensure_pages_loaded`], but blocks until the pages are extracted and appended.
+	/// Returns the text added to the end of the content.
+	pub fn load_pages_now(&mut self, pages: Range<usize>) -> Option<String> {
+		if let Some(loader) = &self.doc.page_loader {
+			loader.wait_for(pages.end);
+		}
+		self.apply_loaded_pages()
+	}
+
+	/// Appends the pages extracted since the last call in one step, so content never shows up
+	/// without its markers. Returns the appended text, or `None` when nothing new arrived.
+	pub fn apply_loaded_pages(&mut self) -> Option<String> {
+		let loader = self.doc.page_loader.as_ref()?;
+		// Read before taking, so a page finished in between is never left behind.
+		let finished = loader.extracted_pages() >= loader.total_pages();
+		let batches = loader.take_ready();
+		if batches.is_empty() && !finished {
+			return None;
+		}
+		let mut appended = String::new();
+		for mut batch in batches {
+			if let Some(running_lines) = &mut self.doc.running_lines {
+				running_lines.strip_batch(&mut batch);
+			}
+			self.doc.buffer.append(&batch.text);
+			self.doc.buffer.markers.extend(batch.markers);
+			self.doc.id_positions.extend(batch.id_positions);
+			if let Some(toc_items) = batch.toc_items {
+				self.doc.toc_items = toc_items;
+			}
+			appended.push_str(&batch.text);
+		}
+		self.doc.buffer.markers.sort_by_key(|m| m.position);
+		self.structure = StructureIndex::build(&self.doc.buffer.markers);
+		if finished {
+			self.doc.page_loader = None;
+			self.doc.compute_stats();
+		}
+		(!appended.is_empty()).then_some(appended)
+	}
+
 	#[must_use]
 	pub fn next_heading_marker_index(&self, position: i64, level: Option<i32>) -> Option<usize> {
 		let heading_markers = self.heading_markers(level);
@@ -545,6 +1242,10 @@ bitflags! {
 	}
 }
 
+pub const DEFAULT_MAX_LINKED_FILES: usize = 500;
+/// Headings a PDF without an outline may get from font sizes before the smallest ones are dropped.
+pub const DEFAULT_MAX_DETECTED_HEADINGS: usize = 500;
+
 #[derive(Debug, Clone)]
 pub struct ParserContext {
 	pub file_path: String,
@@ -553,12 +1254,61 @@ pub struct ParserContext {
 	/// When `true`, parsers emit each table's full tab-separated rendering inline; when `false`,
 	/// they emit a `"[Table]: <first row>"` placeholder. Threaded into each parser at parse time.
 	pub render_tables_inline: bool,
+	/// When `true`, PDF pages whose text sits in side-by-side columns are reflowed column by
+	/// column instead of in pdfium's row-interleaved stream order.
+	pub detect_columns: bool,
+	/// When `true`, a PDF with neither an outline nor tags gets headings from lines set in
+	/// noticeably larger type than its body text.
+	pub detect_headings: bool,
+	/// Upper bound on the headings found that way; see [`DEFAULT_MAX_DETECTED_HEADINGS`].
+	pub max_detected_headings: usize,
+	/// When `true`, presentation parsers append each slide's speaker notes after the slide body.
+	pub include_speaker_notes: bool,
+	/// When `true`, Word documents show each reviewer comment inline, in brackets, where it is anchored.
+	pub include_comments: bool,
+	/// When `true`, typographic quotes, dashes and spaces are replaced with plain ones after parsing.
+	pub normalize_typography: bool,
+	/// When `true`, images without alt text are left out instead of being shown as `"[Image]"`.
+	pub skip_unlabeled_images: bool,
+	/// Upper bound on the number of files a multi-file HTML book may pull in by following links.
+	pub max_linked_files: usize,
+	/// Name of the entry to open when `file_path` is an archive holding several readable documents.
+	pub archive_entry: Option<String>,
+	/// When set, PDFs with more pages than this extract only that many up front and leave the rest
+	/// to a [`PageLoader`] that reads them as they are needed.
+	pub lazy_page_limit: Option<usize>,
+	/// Whether running headers and footers are removed after parsing. `None` leaves it to the
+	/// format: on for PDFs, off for everything else.
+	pub strip_running_lines: Option<bool>,
+	/// Encoding plain text files are read in, by its WHATWG label, instead of the detected one.
+	pub encoding: Option<String>,
+	/// Web address `file_path` was downloaded from. Bookmarks and other saved state are kept under
+	/// it rather than under the downloaded copy.
+	pub source_url: Option<String>,
 }
 
 impl ParserContext {
 	#[must_use]
 	pub const fn new(file_path: String) -> Self {
-		Self { file_path, password: None, forced_extension: None, render_tables_inline: true }
+		Self {
+			file_path,
+			password: None,
+			forced_extension: None,
+			render_tables_inline: true,
+			detect_columns: true,
+			detect_headings: true,
+			max_detected_headings: DEFAULT_MAX_DETECTED_HEADINGS,
+			include_speaker_notes: true,
+			include_comments: false,
+			normalize_typography: false,
+			skip_unlabeled_images: false,
+			max_linked_files: DEFAULT_MAX_LINKED_FILES,
+			archive_entry: None,
+			lazy_page_limit: None,
+			strip_running_lines: None,
+			encoding: None,
+			source_url: None,
+		}
 	}
 
 	#[must_use]
@@ -578,10 +1328,90 @@ impl ParserContext {
 		self.render_tables_inline = value;
 		self
 	}
+
+	#[must_use]
+	pub const fn with_detect_columns(mut self, value: bool) -> Self {
+		self.detect_columns = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_detect_headings(mut self, value: bool) -> Self {
+		self.detect_headings = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_max_detected_headings(mut self, value: usize) -> Self {
+		self.max_detected_headings = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_include_speaker_notes(mut self, value: bool) -> Self {
+		self.include_speaker_notes = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_include_comments(mut self, value: bool) -> Self {
+		self.include_comments = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_normalize_typography(mut self, value: bool) -> Self {
+		self.normalize_typography = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_skip_unlabeled_images(mut self, value: bool) -> Self {
+		self.skip_unlabeled_images = value;
+		self
+	}
+
+	#[must_use]
+	pub const fn with_max_linked_files(mut self, value: usize) -> Self {
+		self.max_linked_files = value;
+		self
+	}
+
+	#[must_use]
+	pub fn with_archive_entry(mut self, entry: String) -> Self {
+		self.archive_entry = Some(entry);
+		self
+	}
+
+	#[must_use]
+	pub const fn with_lazy_page_limit(mut self, pages: usize) -> Self {
+		self.lazy_page_limit = Some(pages);
+		self
+	}
+
+	#[must_use]
+	pub const fn with_strip_running_lines(mut self, value: bool) -> Self {
+		self.strip_running_lines = Some(value);
+		self
+	}
+
+	#[must_use]
+	pub fn with_encoding(mut self, encoding: String) -> Self {
+		self.encoding = Some(encoding);
+		self
+	}
+
+	#[must_use]
+	pub fn with_source_url(mut self, url: String) -> Self {
+		self.source_url = Some(url);
+		self
+	}
 }
 
 #[cfg(test)]
 mod tests {
+	use rstest::rstest;
+
 	use super::*;
 
 	fn sample_handle() -> DocumentHandle {
@@ -616,6 +1446,122 @@ mod tests {
 		DocumentHandle::new(doc)
 	}
 
+	#[test]
+	fn remove_unlabeled_images_drops_placeholders_and_moves_markers() {
+		let mut buffer = DocumentBuffer::with_content("See [Image] here [Image: cat]\n[Image]\nEnd".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Image, 4).with_length(7));
+		buffer.add_marker(Marker::new(MarkerType::Image, 17).with_text("cat".to_string()).with_length(12));
+		buffer.add_marker(Marker::new(MarkerType::Image, 30).with_length(7));
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 38).with_level(1).with_text("End".to_string()));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		doc.id_positions.insert("end".to_string(), 38);
+		doc.remove_unlabeled_images();
+		assert_eq!(doc.buffer.content, "See here [Image: cat]\nEnd");
+		let images: Vec<_> = doc.buffer.markers.iter().filter(|m| m.mtype == MarkerType::Image).collect();
+		assert_eq!(images.len(), 1);
+		assert_eq!((images[0].position, images[0].text.as_str()), (9, "cat"));
+		let heading = doc.buffer.markers.iter().find(|m| m.mtype == MarkerType::Heading1).unwrap();
+		assert_eq!(heading.position, 22);
+		assert_eq!(doc.id_positions["end"], 22);
+	}
+
+	#[test]
+	fn normalize_typography_moves_markers_toc_and_anchors() {
+		let mut buffer = DocumentBuffer::with_content("One\u{2014}two\nThree\u{2026}\nFour".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 8).with_level(1).with_length(6));
+		buffer.add_marker(Marker::new(MarkerType::Link, 15).with_length(4));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		doc.toc_items.push(TocItem::new("Three".to_string(), String::new(), 8));
+		doc.id_positions.insert("four".to_string(), 15);
+		doc.normalize_typography();
+		assert!(doc.typography_normalized);
+		assert_eq!(doc.buffer.content, "One - two\nThree...\nFour");
+		assert_eq!((doc.buffer.markers[0].position, doc.buffer.markers[0].length), (10, 8));
+		assert_eq!(doc.buffer.markers[1].position, 19);
+		assert_eq!(doc.toc_items[0].offset, 10);
+		assert_eq!(doc.id_positions["four"], 19);
+	}
+
+	#[test]
+	fn lazily_loaded_pages_are_appended_with_their_markers() {
+		let mut buffer = DocumentBuffer::with_content("Page 1\n".to_string());
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, 0));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		doc.page_loader = Some(Arc::new(PageLoader::spawn(1, 3, || {
+			let mut offset = 7;
+			move |page: usize| {
+				let text = format!("Page {}\n", page + 1);
+				let mut batch = page_loader::PageBatch {
+					markers: vec![Marker::new(MarkerType::PageBreak, offset)],
+					id_positions: vec![(format!("page_{page}"), offset)],
+					..page_loader::PageBatch::default()
+				};
+				if page == 2 {
+					batch.toc_items = Some(vec![TocItem::new("Last".to_string(), String::new(), offset)]);
+				}
+				offset += text.len();
+				batch.text = text;
+				batch
+			}
+		})));
+		let mut handle = DocumentHandle::new(doc);
+		assert!(!handle.is_fully_loaded());
+		assert_eq!((handle.loaded_pages(), handle.total_pages()), (1, 3));
+		assert_eq!(handle.load_pages_now(1..2).as_deref(), Some("Page 2\n"));
+		assert_eq!(handle.page_index(8), Some(1));
+		assert_eq!(handle.load_pages_now(2..3).as_deref(), Some("Page 3\n"));
+		assert!(handle.is_fully_loaded());
+		assert_eq!(handle.document().buffer.content, "Page 1\nPage 2\nPage 3\n");
+		assert_eq!(handle.get_marker_position_by_index(MarkerType::PageBreak, 2), Some(14));
+		assert_eq!(handle.document().id_positions.get("page_2"), Some(&14));
+		assert_eq!(handle.document().toc_items[0].offset, 14);
+		assert_eq!(handle.total_pages(), 3);
+	}
+
+	#[test]
+	fn running_lines_are_stripped_from_parsed_and_later_pages() {
+		let mut buffer = DocumentBuffer::new();
+		for page in ["Book title\nAlpha.\n", "Book title\nBravo.\n", "Book title\nCharlie.\n"] {
+			buffer.add_marker(Marker::new(MarkerType::PageBreak, buffer.current_position()));
+			buffer.append(page);
+		}
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 29).with_level(1).with_length(6));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		doc.toc_items.push(TocItem::new("Bravo".to_string(), String::new(), 29));
+		doc.page_loader = Some(Arc::new(PageLoader::spawn(3, 4, || {
+			|_| page_loader::PageBatch {
+				text: "Book title\nDelta.\n".to_string(),
+				markers: vec![Marker::new(MarkerType::PageBreak, 56)],
+				id_positions: vec![("delta".to_string(), 67)],
+				toc_items: Some(vec![TocItem::new("Delta".to_string(), String::new(), 67)]),
+			}
+		})));
+		doc.strip_running_lines();
+		assert_eq!(doc.buffer.content, "Alpha.\nBravo.\nCharlie.\n");
+		assert_eq!(doc.running_lines.as_ref().map(|lines| lines.headers.clone()), Some(vec!["Book title".to_string()]));
+		assert_eq!((doc.buffer.markers[3].position, doc.buffer.markers[3].length), (7, 6));
+		assert_eq!(doc.toc_items[0].offset, 7);
+		let mut handle = DocumentHandle::new(doc);
+		assert_eq!(handle.load_pages_now(3..4).as_deref(), Some("Delta.\n"));
+		assert_eq!(handle.document().buffer.content, "Alpha.\nBravo.\nCharlie.\nDelta.\n");
+		assert_eq!(handle.get_marker_position_by_index(MarkerType::PageBreak, 3), Some(23));
+		assert_eq!(handle.document().id_positions.get("delta"), Some(&23));
+		assert_eq!(handle.document().toc_items[0].offset, 23);
+	}
+
+	#[test]
+	fn normalize_typography_leaves_plain_documents_alone() {
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content("Plain - text...".to_string()));
+		doc.normalize_typography();
+		assert!(!doc.typography_normalized);
+		assert_eq!(doc.buffer.content, "Plain - text...");
+	}
+
 	#[test]
 	fn enclosing_container_finds_list_and_table_spans() {
 		let handle = container_handle();
@@ -649,6 +1595,56 @@ mod tests {
 		assert!(handle.enclosing_container(150).is_none());
 	}
 
+	fn structure_handle() -> DocumentHandle {
+		let mut buffer = DocumentBuffer::new();
+		buffer.append(&"x".repeat(100));
+		// A three-item list [10, 40) whose second item [20, 30) holds a one-item list [25, 30),
+		// then a block quote [50, 60).
+		buffer.add_marker(Marker::new(MarkerType::List, 10).with_level(3).with_length(30));
+		buffer.add_marker(Marker::new(MarkerType::ListItem, 10).with_level(1).with_length(10));
+		buffer.add_marker(Marker::new(MarkerType::ListItem, 20).with_level(1).with_length(10));
+		buffer.add_marker(Marker::new(MarkerType::List, 25).with_level(1).with_length(5));
+		buffer.add_marker(Marker::new(MarkerType::ListItem, 25).with_level(2).with_length(5));
+		buffer.add_marker(Marker::new(MarkerType::ListItem, 30).with_level(1).with_length(10));
+		buffer.add_marker(Marker::new(MarkerType::BlockQuote, 50).with_length(10));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		DocumentHandle::new(doc)
+	}
+
+	#[test]
+	fn structure_region_reports_list_and_item_position() {
+		let handle = structure_handle();
+		assert_eq!(handle.structure_region(5), None);
+		let first = handle.structure_region(12).unwrap();
+		assert_eq!((first.span.start, first.span.end, first.item_count, first.item_index), (10, 40, 3, 1));
+		assert_eq!(handle.structure_region(22).unwrap().item_index, 2);
+		let nested = handle.structure_region(26).unwrap();
+		assert_eq!((nested.span.start, nested.item_count, nested.item_index), (25, 1, 1));
+		assert_eq!(handle.structure_region(35).unwrap().item_index, 3);
+		assert_eq!(handle.structure_region(40), None);
+		assert_eq!(handle.structure_region(55).unwrap().span.mtype, MarkerType::BlockQuote);
+		assert_eq!(handle.structure_region(60), None);
+	}
+
+	#[test]
+	fn structure_transition_reports_entering_and_leaving() {
+		let handle = structure_handle();
+		let region = |position| handle.structure_region(position);
+		assert_eq!(
+			structure_transition(region(5), region(12)),
+			Some(StructureTransition::Entered(region(12).unwrap()))
+		);
+		assert_eq!(structure_transition(region(12), region(22)), None);
+		assert_eq!(
+			structure_transition(region(22), region(26)),
+			Some(StructureTransition::Entered(region(26).unwrap()))
+		);
+		assert_eq!(structure_transition(region(26), region(35)), Some(StructureTransition::Left(MarkerType::List)));
+		assert_eq!(structure_transition(region(35), region(45)), Some(StructureTransition::Left(MarkerType::List)));
+		assert_eq!(structure_transition(region(45), region(5)), None);
+	}
+
 	#[test]
 	fn marker_type_round_trip_for_all_known_values() {
 		for raw in 0..=18 {
@@ -682,6 +1678,25 @@ mod tests {
 		assert_eq!(buffer.current_position(), 5);
 	}
 
+	#[test]
+	fn document_buffer_maps_display_offsets_across_wide_characters() {
+		let text = "a\u{1F600}e\u{0301}\u{4E2D}\u{1F468}b";
+		let mut appended = DocumentBuffer::new();
+		appended.append("a\u{1F600}e\u{0301}");
+		appended.append("\u{4E2D}\u{1F468}b");
+		for buffer in [DocumentBuffer::with_content(text.to_string()), appended] {
+			assert_eq!(buffer.current_position(), display_len(text));
+			for (char_index, (byte_index, _)) in text.char_indices().enumerate() {
+				let display = display_len(&text[..byte_index]);
+				assert_eq!(buffer.display_index_for_char(char_index), display);
+				assert_eq!(buffer.char_index_for_display(display), char_index);
+				assert_eq!(buffer.byte_index_for_display(display), byte_index);
+				assert_eq!(buffer.display_index_for_byte(byte_index), display);
+			}
+			assert_eq!(buffer.byte_index_for_display(buffer.current_position() + 3), text.len());
+		}
+	}
+
 	#[test]
 	fn document_stats_counts_words_lines_and_chars() {
 		let stats = DocumentStats::from_text("a b\nc");
@@ -700,6 +1715,108 @@ mod tests {
 		assert_eq!(doc.stats.line_count, 1);
 	}
 
+	#[rstest]
+	#[case::english("It's 3.14 o'clock, and the U.S. team won -- again!", 9)]
+	#[case::french("L'homme qu'il a vu aujourd'hui n'est pas là.", 8)]
+	#[case::chinese("我们今天去北京。", 7)]
+	#[case::japanese_mixed("私はコーヒーが好きです。Paperbackで読む。", 12)]
+	#[case::hyphenated("My mother-in-law's state-of-the-art, well-known bric-a-brac - all of it.", 8)]
+	#[case::punctuation_only("— … !? -- « »", 0)]
+	fn document_stats_counts_words_by_script(#[case] text: &str, #[case] words: usize) {
+		assert_eq!(DocumentStats::from_text(text).word_count, words);
+	}
+
+	const POEM: &str = "Because I could not stop for Death –\nHe kindly stopped for me –\nThe Carriage held but just Ourselves –\nAnd Immortality.\n\nWe slowly drove – He knew no haste\nAnd I had put away\nMy labor and my leisure too,\nFor His Civility –\n";
+	const PROSE: &str = "It is a truth universally acknowledged, that a single man in possession of a good fortune, must be in want of a wife.";
+
+	#[test]
+	fn line_layout_detects_poetry_as_preformatted() {
+		assert_eq!(LineLayout::detect(&POEM.repeat(3), 80), LineLayout::Preformatted);
+		assert_eq!(LineLayout::detect(&POEM.repeat(3), 20), LineLayout::Wrapped);
+	}
+
+	#[test]
+	fn line_layout_leaves_prose_and_short_documents_wrapped() {
+		let prose = format!("Chapter 1\n\n{}", [PROSE; 20].join("\n\n"));
+		assert_eq!(LineLayout::detect(&prose, 80), LineLayout::Wrapped);
+		assert_eq!(LineLayout::detect("A title\nBy someone\n", 80), LineLayout::Wrapped);
+		assert_eq!(LineLayout::detect("", 80), LineLayout::Wrapped);
+	}
+
+	#[test]
+	fn line_layout_follows_the_bulk_of_a_mixed_document() {
+		let verse_with_preface = format!("{}\n{}", [PROSE; 3].join("\n"), POEM.repeat(4));
+		assert_eq!(LineLayout::detect(&verse_with_preface, 80), LineLayout::Preformatted);
+		let prose_with_epigraph = format!("{POEM}\n{}", [PROSE; 12].join("\n"));
+		assert_eq!(LineLayout::detect(&prose_with_epigraph, 80), LineLayout::Wrapped);
+		assert_eq!(LineLayout::from_key(LineLayout::Preformatted.key()), Some(LineLayout::Preformatted));
+	}
+
+	#[test]
+	fn reading_words_weigh_ideographs_lighter() {
+		let stats = DocumentStats::from_text("我们今天去北京 and more");
+		assert_eq!((stats.word_count, stats.reading_words), (9, 6));
+	}
+
+	#[test]
+	fn metadata_entries_group_repeated_fields_in_display_order() {
+		let mut doc = Document::new();
+		doc.add_metadata(MetadataField::Identifier, "urn:uuid:1234");
+		doc.add_metadata(MetadataField::Language, "en");
+		doc.add_metadata(MetadataField::Identifier, " 978-0-00-000000-0 ");
+		doc.add_metadata(MetadataField::Identifier, "urn:uuid:1234");
+		doc.add_metadata(MetadataField::Publisher, "   ");
+		doc.add_metadata(MetadataField::Series, "Discworld, book 3");
+		assert_eq!(
+			doc.metadata_entries(),
+			vec![
+				(MetadataField::Series, "Discworld, book 3".to_string()),
+				(MetadataField::Language, "en".to_string()),
+				(MetadataField::Identifier, "urn:uuid:1234; 978-0-00-000000-0".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn landmark_kinds_read_epub_types_aria_roles_and_guide_types() {
+		assert_eq!(LandmarkKind::from_semantic_type("frontmatter preface"), Some(LandmarkKind::Preface));
+		assert_eq!(LandmarkKind::from_semantic_type("doc-bibliography"), Some(LandmarkKind::Bibliography));
+		assert_eq!(LandmarkKind::from_semantic_type("acknowledgements"), Some(LandmarkKind::Acknowledgments));
+		assert_eq!(LandmarkKind::from_semantic_type("title-page"), Some(LandmarkKind::TitlePage));
+		assert_eq!(LandmarkKind::from_semantic_type("footnote noteref"), None);
+	}
+
+	#[test]
+	fn add_landmark_skips_duplicates_at_the_same_offset() {
+		let mut doc = Document::new();
+		doc.add_landmark(LandmarkKind::Index, " Index\n", 40);
+		doc.add_landmark(LandmarkKind::Index, "", 40);
+		doc.add_landmark(LandmarkKind::Glossary, "", 40);
+		assert_eq!(doc.landmarks.len(), 2);
+		assert_eq!(doc.landmarks[0].title, "Index");
+	}
+
+	#[test]
+	fn detect_language_falls_back_to_the_declared_language() {
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content("Kurz.".to_string()));
+		doc.add_metadata(MetadataField::Language, "de-AT");
+		doc.detect_language();
+		assert_eq!(doc.language, "de");
+	}
+
+	#[test]
+	fn detect_direction_falls_back_to_the_declared_language() {
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content("1948 - 2024".to_string()));
+		doc.add_metadata(MetadataField::Language, "he");
+		doc.detect_direction();
+		assert_eq!(doc.direction, TextDirection::RightToLeft);
+		doc.set_buffer(DocumentBuffer::with_content("Shalom, world.".to_string()));
+		doc.detect_direction();
+		assert_eq!(doc.direction, TextDirection::LeftToRight);
+	}
+
 	#[test]
 	fn heading_marker_helper_matches_heading_types_only() {
 		assert!(is_heading_marker(MarkerType::Heading1));
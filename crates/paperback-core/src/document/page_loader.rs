@@ -0,0 +1,171 @@
+use std::{
+	mem,
+	sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+	thread,
+};
+
+use super::{Marker, TocItem};
+
+/// Text and markers for one page extracted after the document was opened. Marker offsets are
+/// already final: they assume the batch is appended right after every batch before it.
+#[derive(Debug, Clone, Default)]
+pub struct PageBatch {
+	pub text: String,
+	pub markers: Vec<Marker>,
+	pub id_positions: Vec<(String, usize)>,
+	/// Table of contents for the whole document, sent with the last page once every offset is known.
+	pub toc_items: Option<Vec<TocItem>>,
+}
+
+#[derive(Debug, Default)]
+struct LoadState {
+	/// Pages the reader needs, counted from the start of the document.
+	requested: usize,
+	/// Pages extracted so far, including those already taken.
+	extracted: usize,
+	ready: Vec<PageBatch>,
+	cancelled: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+	state: Mutex<LoadState>,
+	changed: Condvar,
+}
+
+/// Extracts the remaining pages of a lazily loaded document on a background thread, but only as
+/// far as has been requested, so a reader who never leaves the first chapter never pays for the
+/// rest of the book.
+#[derive(Debug)]
+pub struct PageLoader {
+	shared: Arc<Shared>,
+	total_pages: usize,
+}
+
+impl PageLoader {
+	/// Starts the loader thread for a document whose first `loaded_pages` of `total_pages` are
+	/// already in memory. `open` runs once on that thread and returns the function extracting a
+	/// page by index, so it can hold handles that must not cross threads.
+	#[must_use]
+	pub fn spawn<O, F>(loaded_pages: usize, total_pages: usize, open: O) -> Self
+	where
+		O: FnOnce() -> F + Send + 'static,
+		F: FnMut(usize) -> PageBatch,
+	{
+		let shared = Arc::new(Shared {
+			state: Mutex::new(LoadState { requested: loaded_pages, extracted: loaded_pages, ..LoadState::default() }),
+			changed: Condvar::new(),
+		});
+		let worker = Arc::clone(&shared);
+		thread::spawn(move || {
+			let mut extract = open();
+			loop {
+				let page = {
+					let mut state = lock(&worker.state);
+					while !state.cancelled && state.extracted >= state.requested {
+						state = worker.changed.wait(state).unwrap_or_else(PoisonError::into_inner);
+					}
+					if state.cancelled {
+						return;
+					}
+					state.extracted
+				};
+				let batch = extract(page);
+				let mut state = lock(&worker.state);
+				state.ready.push(batch);
+				state.extracted += 1;
+				worker.changed.notify_all();
+				if state.extracted >= total_pages {
+					return;
+				}
+			}
+		});
+		Self { shared, total_pages }
+	}
+
+	#[must_use]
+	pub const fn total_pages(&self) -> usize {
+		self.total_pages
+	}
+
+	/// Pages extracted so far, whether or not they have been taken yet.
+	#[must_use]
+	pub fn extracted_pages(&self) -> usize {
+		lock(&self.shared.state).extracted
+	}
+
+	/// Whether the loader is still working towards an outstanding request.
+	#[must_use]
+	pub fn is_busy(&self) -> bool {
+		let state = lock(&self.shared.state);
+		state.extracted < state.requested
+	}
+
+	/// Asks for every page before `pages` to be extracted. Returns immediately.
+	pub fn request(&self, pages: usize) {
+		let mut state = lock(&self.shared.state);
+		let pages = pages.min(self.total_pages);
+		if pages > state.requested {
+			state.requested = pages;
+			self.shared.changed.notify_all();
+		}
+	}
+
+	/// Requests every page before `pages` and blocks until they have all been extracted.
+	pub fn wait_for(&self, pages: usize) {
+		self.request(pages);
+		let pages = pages.min(self.total_pages);
+		let mut state = lock(&self.shared.state);
+		while state.extracted < pages {
+			state = self.shared.changed.wait(state).unwrap_or_else(PoisonError::into_inner);
+		}
+	}
+
+	/// Removes and returns the pages extracted since the last call, in document order.
+	#[must_use]
+	pub fn take_ready(&self) -> Vec<PageBatch> {
+		mem::take(&mut lock(&self.shared.state).ready)
+	}
+}
+
+impl Drop for PageLoader {
+	fn drop(&mut self) {
+		lock(&self.shared.state).cancelled = true;
+		self.shared.changed.notify_all();
+	}
+}
+
+fn lock(state: &Mutex<LoadState>) -> MutexGuard<'_, LoadState> {
+	state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn numbered_loader(loaded: usize, total: usize) -> PageLoader {
+		PageLoader::spawn(loaded, total, || {
+			|page: usize| PageBatch { text: format!("Page {page}\n"), ..PageBatch::default() }
+		})
+	}
+
+	#[test]
+	fn loader_extracts_only_what_is_requested() {
+		let loader = numbered_loader(2, 10);
+		assert!(!loader.is_busy());
+		assert!(loader.take_ready().is_empty());
+		loader.wait_for(4);
+		let texts: Vec<String> = loader.take_ready().into_iter().map(|batch| batch.text).collect();
+		assert_eq!(texts, ["Page 2\n", "Page 3\n"]);
+		assert_eq!(loader.extracted_pages(), 4);
+	}
+
+	#[test]
+	fn requests_past_the_end_stop_at_the_last_page() {
+		let loader = numbered_loader(0, 3);
+		loader.wait_for(100);
+		assert_eq!(loader.take_ready().len(), 3);
+		assert_eq!(loader.extracted_pages(), loader.total_pages());
+		assert!(!loader.is_busy());
+	}
+}
@@ -0,0 +1,310 @@
+use std::{
+	collections::{HashMap, HashSet},
+	ops::Range,
+};
+
+use super::{MarkerType, page_loader::PageBatch, remap_marker, remap_toc_offsets};
+use crate::util::text::{OffsetMap, display_len, remove_ranges};
+
+/// Share of pages a line has to appear on to count as a running header or footer.
+const MIN_PAGE_SHARE: f64 = 0.6;
+/// With fewer pages than this, a repeated line is as likely to be a coincidence.
+const MIN_PAGES: usize = 3;
+/// How many lines at the top and at the bottom of a page can be running lines.
+const LINES_PER_EDGE: usize = 2;
+/// Stands in for the page or chapter number in a running line shown to the reader.
+const NUMBER_PLACEHOLDER: &str = "N";
+
+/// Headers and footers repeated on most pages, such as a book's title and its page numbers, that
+/// were removed from the text. Kept with the document so pages loaded later lose the same lines.
+#[derive(Debug, Clone, Default)]
+pub struct RunningLines {
+	/// Removed headers as shown to the reader, with the parts that change from page to page
+	/// written as "N".
+	pub headers: Vec<String>,
+	pub footers: Vec<String>,
+	header_keys: HashSet<String>,
+	footer_keys: HashSet<String>,
+	/// Maps offsets in the text as extracted to offsets in the stripped text.
+	map: OffsetMap,
+	/// Display length of the text stripped so far, as extracted and after stripping.
+	source_len: usize,
+	stripped_len: usize,
+}
+
+impl RunningLines {
+	/// Looks for lines that repeat at the top or bottom of at least 60% of the pages starting at
+	/// `page_starts`, allowing numbers to differ. Returns `None` when there are none.
+	#[must_use]
+	pub fn detect(text: &str, page_starts: &[usize]) -> Option<Self> {
+		let pages: Vec<Vec<PageLine<'_>>> =
+			page_lines(text, page_starts).into_iter().filter(|lines| !lines.is_empty()).collect();
+		if pages.len() < MIN_PAGES {
+			return None;
+		}
+		let headers = repeated_lines(pages.iter().map(|lines| head(lines)), pages.len());
+		// On short pages the same lines are at both edges; report them once, as headers.
+		let footers: Vec<(String, String)> = repeated_lines(pages.iter().map(|lines| tail(lines)), pages.len())
+			.into_iter()
+			.filter(|(key, _)| !headers.iter().any(|(header_key, _)| header_key == key))
+			.collect();
+		if headers.is_empty() && footers.is_empty() {
+			return None;
+		}
+		let (header_keys, headers) = headers.into_iter().unzip();
+		let (footer_keys, footers) = footers.into_iter().unzip();
+		Some(Self { headers, footers, header_keys, footer_keys, ..Self::default() })
+	}
+
+	/// Removes the running lines from `text`, which continues the text stripped so far, and returns
+	/// what is left. `page_starts` are offsets into `text`.
+	pub fn strip(&mut self, text: &str, page_starts: &[usize]) -> String {
+		let mut ranges = Vec::new();
+		for lines in page_lines(text, page_starts) {
+			for (index, line) in lines.iter().enumerate() {
+				let at_top = index < LINES_PER_EDGE;
+				let at_bottom = index + LINES_PER_EDGE >= lines.len();
+				if !at_top && !at_bottom {
+					continue;
+				}
+				let key = line_key(line.text);
+				if (at_top && self.header_keys.contains(&key)) || (at_bottom && self.footer_keys.contains(&key)) {
+					ranges.push(line.range.clone());
+				}
+			}
+		}
+		let (stripped, map) = remove_ranges(text, &ranges);
+		self.map.append(&map, self.source_len, self.stripped_len);
+		self.source_len += display_len(text);
+		self.stripped_len += display_len(&stripped);
+		stripped
+	}
+
+	/// Maps every offset stripped so far from the extracted text to the stripped text.
+	#[must_use]
+	pub const fn offset_map(&self) -> &OffsetMap {
+		&self.map
+	}
+
+	/// Strips a batch of lazily loaded pages and moves its offsets, which assume nothing before it
+	/// was removed, to match.
+	pub fn strip_batch(&mut self, batch: &mut PageBatch) {
+		let base = self.source_len;
+		let mut page_starts: Vec<usize> = batch
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::PageBreak)
+			.map(|marker| marker.position.saturating_sub(base))
+			.collect();
+		page_starts.sort_unstable();
+		batch.text = self.strip(&batch.text, &page_starts);
+		for marker in &mut batch.markers {
+			remap_marker(marker, &self.map);
+		}
+		for (_, offset) in &mut batch.id_positions {
+			*offset = self.map.map(*offset);
+		}
+		if let Some(toc_items) = &mut batch.toc_items {
+			remap_toc_offsets(toc_items, &self.map);
+		}
+	}
+}
+
+/// A non-blank line of a page. `range` covers its display units, including the line break.
+struct PageLine<'a> {
+	text: &'a str,
+	range: Range<usize>,
+}
+
+/// The non-blank lines of each page, in order. Text before the first page is left out.
+fn page_lines<'a>(text: &'a str, page_starts: &[usize]) -> Vec<Vec<PageLine<'a>>> {
+	let mut pages: Vec<Vec<PageLine<'a>>> = page_starts.iter().map(|_| Vec::new()).collect();
+	let mut offset = 0;
+	for raw_line in text.split_inclusive('\n') {
+		let start = offset;
+		offset += display_len(raw_line);
+		let line = raw_line.trim_end_matches(['\n', '\r']);
+		if line.trim().is_empty() {
+			continue;
+		}
+		let Some(page) = page_starts.partition_point(|&page_start| page_start <= start).checked_sub(1) else {
+			continue;
+		};
+		pages[page].push(PageLine { text: line, range: start..offset });
+	}
+	pages
+}
+
+fn head<'a, 'b>(lines: &'b [PageLine<'a>]) -> &'b [PageLine<'a>] {
+	&lines[..lines.len().min(LINES_PER_EDGE)]
+}
+
+fn tail<'a, 'b>(lines: &'b [PageLine<'a>]) -> &'b [PageLine<'a>] {
+	&lines[lines.len().saturating_sub(LINES_PER_EDGE)..]
+}
+
+/// A line with numbers in the same place, as it first appeared and how often.
+struct Candidate {
+	words: Vec<String>,
+	/// Which words differed from the first appearance on some page.
+	varies: Vec<bool>,
+	pages: usize,
+	first_seen: usize,
+}
+
+/// Counts the lines at one edge of each page and keeps those on enough pages. Returns each one's
+/// matching key and how it is shown to the reader, in the order they were first seen.
+fn repeated_lines<'a, 'b: 'a>(
+	edges: impl Iterator<Item = &'a [PageLine<'b>]>,
+	page_count: usize,
+) -> Vec<(String, String)> {
+	let mut candidates: HashMap<String, Candidate> = HashMap::new();
+	for (page, lines) in edges.enumerate() {
+		let mut seen_on_page = HashSet::new();
+		for line in lines {
+			let key = line_key(line.text);
+			if !seen_on_page.insert(key.clone()) {
+				continue;
+			}
+			let words: Vec<String> = line.text.split_whitespace().map(str::to_string).collect();
+			let candidate = candidates.entry(key).or_insert_with(|| Candidate {
+				varies: vec![false; words.len()],
+				words: words.clone(),
+				pages: 0,
+				first_seen: page,
+			});
+			candidate.pages += 1;
+			for ((varies, first), word) in candidate.varies.iter_mut().zip(&candidate.words).zip(&words) {
+				*varies |= first != word;
+			}
+		}
+	}
+	#[allow(clippy::cast_precision_loss)]
+	let needed = (page_count as f64 * MIN_PAGE_SHARE).ceil();
+	#[allow(clippy::cast_precision_loss)]
+	let mut repeated: Vec<(String, Candidate)> =
+		candidates.into_iter().filter(|(_, candidate)| candidate.pages as f64 >= needed).collect();
+	repeated.sort_by_key(|(_, candidate)| candidate.first_seen);
+	repeated.into_iter().map(|(key, candidate)| (key, display_pattern(&candidate))).collect()
+}
+
+fn display_pattern(candidate: &Candidate) -> String {
+	candidate
+		.words
+		.iter()
+		.zip(&candidate.varies)
+		.map(|(word, &varies)| if varies { replace_number(word, NUMBER_PLACEHOLDER) } else { word.clone() })
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// What lines are compared by: case and spacing are ignored, and every number, Arabic or Roman,
+/// matches any other.
+fn line_key(line: &str) -> String {
+	line.split_whitespace().map(|word| replace_number(&word.to_lowercase(), "#")).collect::<Vec<_>>().join(" ")
+}
+
+/// Replaces the number in `word`, keeping any punctuation around it. Words that aren't numbers are
+/// returned as they are.
+fn replace_number(word: &str, placeholder: &str) -> String {
+	let core = word.trim_matches(|ch: char| !ch.is_alphanumeric());
+	if !is_number(core) {
+		return word.to_string();
+	}
+	let start = word.len() - word.trim_start_matches(|ch: char| !ch.is_alphanumeric()).len();
+	format!("{}{placeholder}{}", &word[..start], &word[start + core.len()..])
+}
+
+fn is_number(word: &str) -> bool {
+	if word.is_empty() {
+		return false;
+	}
+	if word.chars().all(|ch| ch.is_ascii_digit()) {
+		return true;
+	}
+	// Front matter is usually numbered in Roman numerals, in either case but never mixed.
+	let same_case = word.chars().all(|ch| ch.is_ascii_lowercase()) || word.chars().all(|ch| ch.is_ascii_uppercase());
+	same_case && word.len() <= 8 && roman::from(&word.to_ascii_uppercase()).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Joins pages into one text and returns it with the offset each page starts at.
+	fn paged(pages: &[String]) -> (String, Vec<usize>) {
+		let mut text = String::new();
+		let mut starts = Vec::new();
+		for page in pages {
+			starts.push(display_len(&text));
+			text.push_str(page);
+		}
+		(text, starts)
+	}
+
+	const WORDS: [&str; 8] = ["Alpha", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel"];
+
+	#[test]
+	fn running_lines_with_changing_page_numbers_are_removed() {
+		let pages: Vec<String> = (1_usize..=6)
+			.map(|page| {
+				let chapter = page.div_ceil(2);
+				let word = WORDS[page];
+				format!("Hands that see — Chapter {chapter}\n{word} begins here.\nThen {word} ends.\n{page}\n")
+			})
+			.collect();
+		let (text, starts) = paged(&pages);
+		let mut lines = RunningLines::detect(&text, &starts).expect("running lines");
+		assert_eq!(lines.headers, ["Hands that see — Chapter N"]);
+		assert_eq!(lines.footers, ["N"]);
+		let stripped = lines.strip(&text, &starts);
+		assert!(stripped.starts_with("Bravo begins here.\nThen Bravo ends.\nCharlie begins here.\n"));
+		assert!(!stripped.contains("Chapter"));
+		let second_page_body = display_len(&text[..text.find("Charlie").unwrap()]);
+		assert_eq!(lines.offset_map().map(second_page_body), display_len("Bravo begins here.\nThen Bravo ends.\n"));
+	}
+
+	#[test]
+	fn roman_numerals_in_front_matter_count_as_page_numbers() {
+		let numbers = ["iii", "iv", "v", "vi", "1", "2", "3", "4"];
+		let pages: Vec<String> = numbers
+			.iter()
+			.zip(WORDS)
+			.map(|(number, word)| format!("{word} opens the page.\nIt goes on about {word}.\n- {number} -\n"))
+			.collect();
+		let (text, starts) = paged(&pages);
+		let mut lines = RunningLines::detect(&text, &starts).expect("running lines");
+		assert!(lines.headers.is_empty());
+		assert_eq!(lines.footers, ["- N -"]);
+		let stripped = lines.strip(&text, &starts);
+		assert!(!stripped.contains("- iv -"));
+		assert!(!stripped.contains("- 2 -"));
+		assert!(stripped.contains("It goes on about Hotel.\n"));
+	}
+
+	#[test]
+	fn pages_without_repetition_are_left_alone() {
+		let pages: Vec<String> =
+			["The sea was calm.\nGulls cried.\n", "Night fell.\nStars came out.\n", "Morning.\nA ship!\n"]
+				.iter()
+				.map(ToString::to_string)
+				.collect();
+		let (text, starts) = paged(&pages);
+		assert!(RunningLines::detect(&text, &starts).is_none());
+	}
+
+	#[test]
+	fn lines_repeated_in_the_body_are_kept() {
+		let pages: Vec<String> = WORDS[..4]
+			.iter()
+			.map(|word| format!("Title\n{word} one.\nRefrain\n{word} two.\nLast of {word}.\n"))
+			.collect();
+		let (text, starts) = paged(&pages);
+		let mut lines = RunningLines::detect(&text, &starts).expect("running lines");
+		assert_eq!(lines.headers, ["Title"]);
+		assert!(lines.footers.is_empty());
+		let stripped = lines.strip(&text, &starts);
+		assert_eq!(stripped.matches("Refrain").count(), 4);
+		assert!(!stripped.contains("Title"));
+	}
+}
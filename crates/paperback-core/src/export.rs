@@ -2,7 +2,9 @@ pub mod epub_direct;
 pub mod html;
 pub mod markdown;
 
-use crate::document::DocumentHandle;
+use std::borrow::Cow;
+
+use crate::document::{Document, DocumentBuffer, DocumentHandle};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportFormat {
@@ -11,11 +13,60 @@ pub enum ExportFormat {
 	Markdown,
 }
 
+/// The line ending text files are written with on this system.
+pub const PLATFORM_NEWLINE: &str = if cfg!(windows) { "\r\n" } else { "\n" };
+
+/// The document in `format`. Plain text is the document's own, borrowed rather than copied.
 #[must_use]
-pub fn render(doc: &DocumentHandle, format: ExportFormat) -> String {
+pub fn render(doc: &DocumentHandle, format: ExportFormat) -> Cow<'_, str> {
 	match format {
-		ExportFormat::Text => doc.document().buffer.content.clone(),
-		ExportFormat::Html => html::render(doc),
-		ExportFormat::Markdown => markdown::render(doc.document()),
+		ExportFormat::Text => Cow::Borrowed(doc.content_ref()),
+		ExportFormat::Html => Cow::Owned(html::render(doc)),
+		ExportFormat::Markdown => Cow::Owned(markdown::render(doc.document())),
+	}
+}
+
+/// The text from display offset `start` to `end` in `format`, as a document of its own titled
+/// `title`. Plain text is borrowed from the document like [`render`].
+#[must_use]
+pub fn render_range<'a>(
+	doc: &'a DocumentHandle,
+	format: ExportFormat,
+	start: usize,
+	end: usize,
+	title: &str,
+) -> Cow<'a, str> {
+	let buffer = &doc.document().buffer;
+	let (start_byte, end_byte) = (buffer.byte_index_for_display(start), buffer.byte_index_for_display(end));
+	if format == ExportFormat::Text {
+		return Cow::Borrowed(&buffer.content[start_byte..end_byte.max(start_byte)]);
+	}
+	let section = range_document(doc.document(), start, end, title);
+	Cow::Owned(render(&section, format).into_owned())
+}
+
+/// A copy of the text from display offset `start` to `end` with the markers and link targets that
+/// fall inside it, moved to count from the start of the range. Markers are cut off at its end.
+fn range_document(document: &Document, start: usize, end: usize, title: &str) -> DocumentHandle {
+	let buffer = &document.buffer;
+	let end = end.max(start);
+	let text = &buffer.content[buffer.byte_index_for_display(start)..buffer.byte_index_for_display(end)];
+	let mut range_buffer = DocumentBuffer::with_content(text.to_string());
+	for marker in buffer.markers.iter().filter(|marker| (start..end).contains(&marker.position)) {
+		let mut marker = marker.clone();
+		marker.position -= start;
+		marker.length = marker.length.min(end - start - marker.position);
+		range_buffer.add_marker(marker);
 	}
+	let mut range_doc = Document::new().with_title(title.to_string()).with_author(document.author.clone());
+	range_doc.id_positions = document
+		.id_positions
+		.iter()
+		.filter(|&(_, &position)| (start..end).contains(&position))
+		.map(|(id, &position)| (id.clone(), position - start))
+		.collect();
+	range_doc.language.clone_from(&document.language);
+	range_doc.direction = document.direction;
+	range_doc.set_buffer(range_buffer);
+	DocumentHandle::new(range_doc)
 }
@@ -193,7 +193,8 @@ pub fn render(doc: &DocumentHandle) -> String {
 				events.push(Ev { pos: end, kind: Ek::BlockClose("</ul>") });
 			}
 			MarkerType::ListItem => {
-				let end = effective_end(marker.length);
+				// An item's length spans any list nested in it; close the <li> at the end of its own line.
+				let end = newline_from(pos);
 				events.push(Ev { pos, kind: Ek::BlockOpen("<li>") });
 				events.push(Ev { pos: end, kind: Ek::BlockClose("</li>") });
 			}
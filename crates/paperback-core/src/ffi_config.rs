@@ -49,6 +49,22 @@ impl ConfigManagerFfi {
 		self.inner.lock().unwrap().get_document_position(&path)
 	}
 
+	pub fn set_document_furthest_position(&self, path: String, position: i64) {
+		self.inner.lock().unwrap().set_document_furthest_position(&path, position);
+	}
+
+	pub fn get_document_furthest_position(&self, path: String) -> i64 {
+		self.inner.lock().unwrap().get_document_furthest_position(&path)
+	}
+
+	pub fn set_document_quick_slot(&self, path: String, slot: u8, position: i64) {
+		self.inner.lock().unwrap().set_document_quick_slot(&path, slot, position);
+	}
+
+	pub fn get_document_quick_slot(&self, path: String, slot: u8) -> Option<i64> {
+		self.inner.lock().unwrap().get_document_quick_slot(&path, slot)
+	}
+
 	pub fn set_document_password(&self, path: String, password: String) {
 		self.inner.lock().unwrap().set_document_password(&path, &password);
 	}
@@ -86,7 +102,7 @@ impl ConfigManagerFfi {
 	}
 
 	pub fn rename_document_path(&self, old_path: String, new_path: String) {
-		self.inner.lock().unwrap().rename_document_path(&old_path, &new_path);
+		self.inner.lock().unwrap().rename_document(&old_path, &new_path);
 	}
 
 	pub fn get_supported_extensions(&self) -> Vec<String> {
@@ -0,0 +1,246 @@
+//! Line-delimited JSON protocol that lets external tools (screen reader add-ons, scripts) query
+//! and drive the reader.
+//!
+//! Each request is one JSON object on its own line, tagged by `command`:
+//!
+//! ```text
+//! {"command":"get_state"}
+//! {"command":"get_toc"}
+//! {"command":"get_bookmarks"}
+//! {"command":"goto","offset":1200}
+//! {"command":"open","path":"/home/me/book.epub"}
+//! ```
+//!
+//! Every request gets exactly one line back: `{"ok":true,...}` with the requested data, or
+//! `{"ok":false,"error":"..."}`. The transport (named pipe or local socket) belongs to the app;
+//! this module only speaks the protocol over any reader/writer pair.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{config::Bookmark, session::TocEntry};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ApiRequest {
+	GetState,
+	GetToc,
+	GetBookmarks,
+	Goto { offset: i64 },
+	Open { path: String },
+}
+
+/// The active document as reported by `get_state`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocumentState {
+	pub path: String,
+	pub title: String,
+	pub caret: i64,
+	pub percent: i32,
+}
+
+#[derive(Debug, Clone)]
+pub enum ApiResponse {
+	State(DocumentState),
+	Toc(Vec<TocEntry>),
+	Bookmarks(Vec<Bookmark>),
+	Done,
+	Error(String),
+}
+
+impl ApiResponse {
+	#[must_use]
+	pub fn to_json(&self) -> String {
+		let value = match self {
+			Self::State(state) => json!({ "ok": true, "state": state }),
+			Self::Toc(entries) => json!({ "ok": true, "toc": entries }),
+			Self::Bookmarks(bookmarks) => json!({ "ok": true, "bookmarks": bookmarks }),
+			Self::Done => json!({ "ok": true }),
+			Self::Error(message) => json!({ "ok": false, "error": message }),
+		};
+		value.to_string()
+	}
+}
+
+/// What the protocol needs from whoever owns the open documents.
+pub trait ApiHost {
+	/// `None` when no document is open.
+	fn state(&self) -> Option<DocumentState>;
+	fn toc(&self) -> Option<Vec<TocEntry>>;
+	fn bookmarks(&self) -> Option<Vec<Bookmark>>;
+	/// Moves the caret in the active document. Returns `false` when no document is open.
+	fn goto(&mut self, offset: i64) -> bool;
+	/// Opens `path` and makes it the active document.
+	///
+	/// # Errors
+	///
+	/// Returns a message describing why the document could not be opened.
+	fn open(&mut self, path: &str) -> Result<(), String>;
+}
+
+const NO_DOCUMENT: &str = "no document is open";
+
+pub fn dispatch(host: &mut dyn ApiHost, request: ApiRequest) -> ApiResponse {
+	let missing = || ApiResponse::Error(NO_DOCUMENT.to_string());
+	match request {
+		ApiRequest::GetState => host.state().map_or_else(missing, ApiResponse::State),
+		ApiRequest::GetToc => host.toc().map_or_else(missing, ApiResponse::Toc),
+		ApiRequest::GetBookmarks => host.bookmarks().map_or_else(missing, ApiResponse::Bookmarks),
+		ApiRequest::Goto { offset } if offset < 0 => ApiResponse::Error("offset must not be negative".to_string()),
+		ApiRequest::Goto { offset } => {
+			if host.goto(offset) {
+				ApiResponse::Done
+			} else {
+				missing()
+			}
+		}
+		ApiRequest::Open { path } => host.open(&path).map_or_else(ApiResponse::Error, |()| ApiResponse::Done),
+	}
+}
+
+/// Answers one request line with one response line (without the trailing newline).
+pub fn handle_line(line: &str, handle: &mut impl FnMut(ApiRequest) -> ApiResponse) -> String {
+	let response = match serde_json::from_str::<Value>(line) {
+		Err(err) => ApiResponse::Error(format!("invalid JSON: {err}")),
+		Ok(value) => match ApiRequest::deserialize(value) {
+			Ok(request) => handle(request),
+			Err(err) => ApiResponse::Error(format!("invalid request: {err}")),
+		},
+	};
+	response.to_json()
+}
+
+/// Serves one client until it disconnects, answering each request line as it arrives.
+///
+/// `handle` runs on the calling thread, so a GUI host passes a closure that marshals the request to
+/// its UI thread and waits for the answer.
+///
+/// # Errors
+///
+/// Returns an error if reading from or writing to the connection fails.
+pub fn serve_connection(
+	reader: impl Read,
+	mut writer: impl Write,
+	mut handle: impl FnMut(ApiRequest) -> ApiResponse,
+) -> io::Result<()> {
+	for line in BufReader::new(reader).lines() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+		let mut response = handle_line(&line, &mut handle);
+		response.push('\n');
+		writer.write_all(response.as_bytes())?;
+		writer.flush()?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, fs};
+
+	use super::*;
+	use crate::{config::ConfigManager, session::DocumentSession};
+
+	/// Stands in for the app: one open session plus the config holding its bookmarks.
+	struct HeadlessHost {
+		config: ConfigManager,
+		document: Option<(String, DocumentSession)>,
+		caret: i64,
+	}
+
+	impl ApiHost for HeadlessHost {
+		fn state(&self) -> Option<DocumentState> {
+			let (path, session) = self.document.as_ref()?;
+			Some(DocumentState {
+				path: path.clone(),
+				title: session.title(),
+				caret: self.caret,
+				percent: session.get_status_info(self.caret).percentage,
+			})
+		}
+
+		fn toc(&self) -> Option<Vec<TocEntry>> {
+			self.document.as_ref().map(|(_, session)| session.get_toc())
+		}
+
+		fn bookmarks(&self) -> Option<Vec<Bookmark>> {
+			self.document.as_ref().map(|(path, _)| self.config.get_bookmarks(path))
+		}
+
+		fn goto(&mut self, offset: i64) -> bool {
+			if self.document.is_none() {
+				return false;
+			}
+			self.caret = offset;
+			true
+		}
+
+		fn open(&mut self, path: &str) -> Result<(), String> {
+			let session = DocumentSession::new(path, "", "", true, true, true, false).map_err(|err| err.to_string())?;
+			self.document = Some((path.to_string(), session));
+			self.caret = 0;
+			Ok(())
+		}
+	}
+
+	fn responses(lines: &[&str], host: &mut HeadlessHost) -> Vec<Value> {
+		let input = lines.iter().map(|line| format!("{line}\n")).collect::<String>();
+		let mut output = Vec::new();
+		serve_connection(input.as_bytes(), &mut output, |request| dispatch(host, request)).unwrap();
+		String::from_utf8(output).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+	}
+
+	#[test]
+	fn requests_parse_from_tagged_json() {
+		assert_eq!(serde_json::from_str::<ApiRequest>(r#"{"command":"get_state"}"#).unwrap(), ApiRequest::GetState);
+		assert_eq!(
+			serde_json::from_str::<ApiRequest>(r#"{"command":"goto","offset":12}"#).unwrap(),
+			ApiRequest::Goto { offset: 12 }
+		);
+		assert!(serde_json::from_str::<ApiRequest>(r#"{"command":"delete_everything"}"#).is_err());
+	}
+
+	#[test]
+	fn server_answers_every_command_against_a_headless_session() {
+		let dir = env::temp_dir().join(format!("paperback_ipc_api_{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let book = dir.join("book.md");
+		fs::write(&book, "# One\n\nFirst chapter.\n\n# Two\n\nSecond chapter.\n").unwrap();
+		let book = book.to_string_lossy().into_owned();
+		let mut config = ConfigManager::new();
+		config.initialize(dir.join("config.toml"));
+		config.add_bookmark(&book, 3, 8, "note");
+		let mut host = HeadlessHost { config, document: None, caret: 0 };
+		let open = format!(r#"{{"command":"open","path":{}}}"#, serde_json::to_string(&book).unwrap());
+		let replies = responses(
+			&[
+				r#"{"command":"get_state"}"#,
+				&open,
+				r#"{"command":"goto","offset":10}"#,
+				r#"{"command":"get_state"}"#,
+				r#"{"command":"get_toc"}"#,
+				r#"{"command":"get_bookmarks"}"#,
+				r#"{"command":"goto","offset":-1}"#,
+				"not json",
+			],
+			&mut host,
+		);
+		assert_eq!(replies.len(), 8);
+		assert_eq!(replies[0]["ok"], false);
+		assert_eq!(replies[1]["ok"], true);
+		assert_eq!(replies[2]["ok"], true);
+		assert_eq!(replies[3]["state"]["path"], book.as_str());
+		assert_eq!(replies[3]["state"]["caret"], 10);
+		let toc = replies[4]["toc"].as_array().unwrap();
+		assert_eq!(toc.len(), 2);
+		assert_eq!(toc[1]["title"], "Two");
+		assert_eq!(replies[5]["bookmarks"][0]["note"], "note");
+		assert_eq!(replies[6]["ok"], false);
+		assert_eq!(replies[7]["ok"], false);
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
@@ -4,6 +4,7 @@ pub mod config;
 pub mod document;
 pub mod export;
 pub mod ffi_config;
+pub mod ipc_api;
 pub mod parser;
 pub mod reader_core;
 pub mod session;
@@ -13,10 +14,11 @@ pub mod version;
 
 pub use crate::{
 	ffi_config::ConfigManagerFfi,
+	parser::error::ParserErrorCode,
 	session::{
-		DocumentError, DocumentSession, DocumentStatsFfi, HeadingTreeFfi, HeadingTreeItemFfi, LineMarker, LinkAction,
-		LinkActivationResult, LinkListFfi, LinkListItemFfi, MarkerTypeFfi, SearchOptionsFfi, SearchResultFfi,
-		SegmentDirectionFfi, SegmentTypeFfi, StatusInfo, TextSegmentFfi, TocEntry,
+		DocumentError, DocumentSession, DocumentStatsFfi, HeadingTreeFfi, HeadingTreeItemFfi, LandmarkFfi, LineMarker,
+		LinkAction, LinkActivationResult, LinkListFfi, LinkListItemFfi, LinkPreview, MarkerTypeFfi, MetadataEntryFfi,
+		SearchOptionsFfi, SearchResultFfi, SegmentDirectionFfi, SegmentTypeFfi, StatusInfo, TextSegmentFfi, TocEntry,
 	},
 };
 
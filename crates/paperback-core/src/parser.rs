@@ -1,5 +1,6 @@
 use std::{
 	collections::{BTreeSet, HashMap},
+	fs,
 	path::Path,
 	string::String,
 	sync::OnceLock,
@@ -9,13 +10,21 @@ use anyhow::Result;
 
 use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags},
+	parser::error::ParserError,
 	t,
-	types::{FormatInfo, HeadingInfo, ImageInfo, LinkInfo, ListInfo, ListItemInfo, SeparatorInfo, TableInfo},
+	types::{
+		BlockInfo, FormatInfo, HeadingInfo, ImageInfo, LandmarkInfo, LinkInfo, ListInfo, ListItemInfo, SeparatorInfo,
+		TableInfo,
+	},
+	util::{text::display_len, zip::is_damaged_zip},
 };
 
+pub mod archive;
 pub mod chm;
+pub mod clipboard;
 pub mod daisy;
 pub mod epub;
+pub mod error;
 pub mod fb2;
 pub mod html;
 pub mod html_to_text;
@@ -38,6 +47,16 @@ pub trait Parser: Send + Sync {
 	fn name(&self) -> &str;
 	fn extensions(&self) -> &[&str];
 	fn supported_flags(&self) -> ParserFlags;
+	/// Formats that can only be picked from Open As, not matched by a file's extension, are left
+	/// out of file filters and the supported-extension list.
+	fn open_as_only(&self) -> bool {
+		false
+	}
+	/// Parsers that decode the file's bytes as text themselves honour the encoding chosen for a
+	/// document; the rest ignore it.
+	fn reads_encoding(&self) -> bool {
+		false
+	}
 	/// Parse a document from the given context.
 	///
 	/// # Errors
@@ -46,9 +65,21 @@ pub trait Parser: Send + Sync {
 	fn parse(&self, context: &ParserContext) -> Result<Document>;
 }
 
+#[derive(Clone, Debug)]
 pub struct ParserInfo {
 	pub name: String,
 	pub extensions: Vec<String>,
+	pub reads_encoding: bool,
+}
+
+impl ParserInfo {
+	fn from_parser(parser: &dyn Parser) -> Self {
+		Self {
+			name: parser.name().to_string(),
+			extensions: parser.extensions().iter().map(|s| (*s).to_string()).collect(),
+			reads_encoding: parser.reads_encoding(),
+		}
+	}
 }
 
 pub struct ParserRegistry {
@@ -80,13 +111,15 @@ impl ParserRegistry {
 
 	#[must_use]
 	pub fn all_parsers(&self) -> Vec<ParserInfo> {
-		self.parsers
-			.values()
-			.map(|p| ParserInfo {
-				name: p.name().to_string(),
-				extensions: p.extensions().iter().map(|s| (*s).to_string()).collect(),
-			})
-			.collect()
+		self.parsers.values().filter(|p| !p.open_as_only()).map(|p| ParserInfo::from_parser(&**p)).collect()
+	}
+
+	/// Every registered parser, including the Open As-only ones, sorted by name.
+	#[must_use]
+	pub fn available_parsers(&self) -> Vec<ParserInfo> {
+		let mut parsers: Vec<_> = self.parsers.values().map(|p| ParserInfo::from_parser(&**p)).collect();
+		parsers.sort_by(|a, b| a.name.cmp(&b.name));
+		parsers
 	}
 
 	pub fn global() -> &'static Self {
@@ -99,6 +132,7 @@ impl ParserRegistry {
 			registry.register(epub::EpubParser);
 			registry.register(fb2::Fb2Parser);
 			registry.register(html::HtmlParser);
+			registry.register(html::HtmlBookParser);
 
 			registry.register(pdf::PdfParser);
 			registry.register(markdown::MarkdownParser);
@@ -110,6 +144,8 @@ impl ParserRegistry {
 			registry.register(powerpoint::PowerpointParser);
 			registry.register(rtf::RtfParser);
 			registry.register(text::TextParser);
+			registry.register(text::TextPartsParser);
+			registry.register(archive::ArchiveParser);
 			registry
 		})
 	}
@@ -119,60 +155,105 @@ impl ParserRegistry {
 ///
 /// # Errors
 ///
-/// Returns an error if:
+/// Returns a [`ParserError`] if:
 /// - No file extension is found
 /// - No parser is available for the file extension
-/// - The parser fails to parse the file
-pub fn parse_document(context: &ParserContext) -> Result<Document> {
+/// - Every parser for the extension fails (classified from the last failure)
+pub fn parse_document(context: &ParserContext) -> Result<Document, ParserError> {
+	let doc = parse_unprocessed(context)?;
+	Ok(finish_document(doc, context))
+}
+
+/// The post-processing every parsed document gets, whichever parser produced it.
+fn finish_document(mut doc: Document, context: &ParserContext) -> Document {
+	doc.normalize_newlines();
+	if doc.damaged {
+		// TRANSLATORS: Line shown at the top of a document whose file was damaged and only partly recovered
+		doc.prepend_notice(&t("This file appears damaged; content may be incomplete."));
+	}
+	if context.strip_running_lines.unwrap_or_else(|| context_extension(context).eq_ignore_ascii_case("pdf")) {
+		doc.strip_running_lines();
+	}
+	if context.skip_unlabeled_images {
+		doc.remove_unlabeled_images();
+	}
+	if context.normalize_typography {
+		doc.normalize_typography();
+	}
+	doc.compute_stats();
+	doc.detect_language();
+	doc.detect_direction();
+	doc
+}
+
+/// Runs the parsers registered for the context's extension without the shared post-processing, so
+/// a container parser (see [`archive`]) can delegate to the parser for an inner file.
+pub(crate) fn parse_unprocessed(context: &ParserContext) -> Result<Document, ParserError> {
 	let path = Path::new(&context.file_path);
-	let extension = context.forced_extension.as_ref().map_or_else(
-		|| {
-			path.extension().and_then(|e| e.to_str()).ok_or_else(|| {
-				// TRANSLATORS: Error shown when a file has no extension to determine its format; {} is the file path
-				anyhow::anyhow!(t("No file extension found for: {}").replace("{}", &context.file_path))
-			})
-		},
-		|ext| Ok(ext.as_str()),
-	)?;
+	let extension = match context.forced_extension.as_deref() {
+		Some(ext) => ext,
+		None => path.extension().and_then(|e| e.to_str()).ok_or_else(|| {
+			// TRANSLATORS: Error shown when a file has no extension to determine its format; {} is the file path
+			ParserError::NoExtension(t("No file extension found for: {}").replace("{}", &context.file_path))
+		})?,
+	};
 	let parsers = ParserRegistry::global().get_parsers_for_extension(extension);
 	if parsers.is_empty() {
 		// TRANSLATORS: Error shown when no parser supports a file's extension; {} is the extension (without the leading dot)
-		return Err(anyhow::anyhow!(t("No parser found for extension: .{}").replace("{}", extension)));
+		return Err(ParserError::UnsupportedFormat(t("No parser found for extension: .{}").replace("{}", extension)));
+	}
+	if fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.len() == 0) {
+		// TRANSLATORS: Error shown when the file being opened has no content at all (zero bytes)
+		return Err(ParserError::EmptyFile(t("The file is empty.")));
 	}
 	let mut last_error = None;
 	for parser in parsers {
 		match parser.parse(context) {
-			Ok(mut doc) => {
-				doc.compute_stats();
-				return Ok(doc);
-			}
+			Ok(doc) => return Ok(doc),
 			Err(e) => {
-				if e.to_string().starts_with(PASSWORD_REQUIRED_ERROR_PREFIX) {
-					return Err(e);
+				let error = ParserError::classify(&e);
+				if matches!(error, ParserError::PasswordRequired(_)) {
+					return Err(error);
 				}
-				last_error = Some(e);
+				last_error = Some(error);
 			}
 		}
 	}
-	Err(last_error.unwrap_or_else(|| {
+	let error = last_error.unwrap_or_else(|| {
 		// TRANSLATORS: Error shown when every parser for a file's extension failed; {} is the extension (without the leading dot)
-		anyhow::anyhow!(t("All parsers failed for extension: .{}").replace("{}", extension))
-	}))
+		ParserError::Other(t("All parsers failed for extension: .{}").replace("{}", extension))
+	});
+	// What was salvaged from a cut-off archive may fail to parse in any number of ways; the damage is
+	// the cause worth reporting.
+	if matches!(error, ParserError::Xml { .. } | ParserError::Io(_) | ParserError::Other(_)) && is_damaged_zip(path) {
+		return Err(ParserError::CorruptArchive(error.to_string()));
+	}
+	Err(error)
+}
+
+/// The forced extension if there is one, otherwise the file's own, or an empty string.
+fn context_extension(context: &ParserContext) -> &str {
+	context
+		.forced_extension
+		.as_deref()
+		.unwrap_or_else(|| Path::new(&context.file_path).extension().and_then(|e| e.to_str()).unwrap_or(""))
 }
 
 #[must_use]
 pub fn get_parser_flags_for_context(context: &ParserContext) -> ParserFlags {
-	let path = Path::new(&context.file_path);
-	let extension = context
-		.forced_extension
-		.as_ref()
-		.map_or_else(|| path.extension().and_then(|e| e.to_str()).unwrap_or(""), |ext| ext.as_str());
 	ParserRegistry::global()
-		.get_parsers_for_extension(extension)
+		.get_parsers_for_extension(context_extension(context))
 		.iter()
 		.fold(ParserFlags::NONE, |acc, p| acc | p.supported_flags())
 }
 
+/// The parsers Open As offers. A parser is forced by its first extension, which is what
+/// [`ParserContext::with_forced_extension`] expects.
+#[must_use]
+pub fn get_available_parsers() -> Vec<ParserInfo> {
+	ParserRegistry::global().available_parsers()
+}
+
 #[must_use]
 pub fn parser_supports_extension(extension: &str) -> bool {
 	if extension.is_empty() {
@@ -195,8 +276,10 @@ where
 #[must_use]
 pub fn build_file_filter_string() -> String {
 	let parsers = ParserRegistry::global().all_parsers();
+	// TRANSLATORS: File type choice in the Open dialog that shows every file
+	let all_files = format!("{} (*.*)|*.*", t("All Files"));
 	if parsers.is_empty() {
-		return "All Files (*.*)|*.*".to_string();
+		return all_files;
 	}
 	let mut all_extensions = BTreeSet::new();
 	for parser in &parsers {
@@ -209,7 +292,9 @@ pub fn build_file_filter_string() -> String {
 	let mut parts = String::new();
 	let all_ext_part = join_extensions(all_extensions.iter().map(String::as_str));
 	if !all_ext_part.is_empty() {
-		parts.push_str("All Supported Files (");
+		// TRANSLATORS: File type choice in the Open dialog that shows every document Paperback can open
+		parts.push_str(&t("All Supported Files"));
+		parts.push_str(" (");
 		parts.push_str(&all_ext_part);
 		parts.push_str(")|");
 		parts.push_str(&all_ext_part);
@@ -233,7 +318,7 @@ pub fn build_file_filter_string() -> String {
 	// On macOS, NSOpenPanel treats *.*  as "allow everything", which disables
 	// filtering for all groups.  Only add the catch-all on other platforms.
 	#[cfg(not(target_os = "macos"))]
-	parts.push_str("All Files (*.*)|*.*");
+	parts.push_str(&all_files);
 	#[cfg(target_os = "macos")]
 	{
 		// Remove the trailing '|' left by the last per-parser entry.
@@ -244,6 +329,36 @@ pub fn build_file_filter_string() -> String {
 	parts
 }
 
+/// The text that stands in for an image: `"[Image: a cat sleeping]"`, or `"[Image]"` when it has no
+/// description.
+#[must_use]
+pub fn image_text(description: &str) -> String {
+	// TRANSLATORS: Label inserted before a figure or image's description, e.g. "[Figure: a cat sleeping]"
+	let label = t("Image");
+	if description.is_empty() { format!("[{label}]") } else { format!("[{label}: {description}]") }
+}
+
+/// The text that stands in for an uncaptioned figure with a label, e.g. `"[Figure: sales by year]"`.
+#[must_use]
+pub fn figure_text(description: &str) -> String {
+	// TRANSLATORS: Label inserted before a figure or image's description, e.g. "[Figure: a cat sleeping]"
+	let label = t("Figure");
+	format!("[{label}: {description}]")
+}
+
+/// A book's place in its series for Document Info, e.g. `"Discworld, book 3"`, or just the series
+/// name when the number isn't known. Calibre writes whole numbers as `3.0`; the fraction is dropped.
+#[must_use]
+pub fn series_text(name: &str, number: &str) -> String {
+	let number = number.trim();
+	let number = number.strip_suffix(".0").unwrap_or(number);
+	if number.is_empty() {
+		return name.trim().to_string();
+	}
+	// TRANSLATORS: A book's place in a series, shown in Document Info; the first {} is the series name, the second its number in the series
+	t("{}, book {}").replacen("{}", name.trim(), 1).replacen("{}", number, 1)
+}
+
 pub trait ConverterOutput {
 	fn get_headings(&self) -> &[HeadingInfo];
 	fn get_links(&self) -> &[LinkInfo];
@@ -251,6 +366,9 @@ pub trait ConverterOutput {
 	fn get_figures(&self) -> &[ImageInfo];
 	fn get_tables(&self) -> &[TableInfo];
 	fn get_separators(&self) -> &[SeparatorInfo];
+	fn get_block_quotes(&self) -> &[BlockInfo];
+	fn get_code_blocks(&self) -> &[BlockInfo];
+	fn get_notes(&self) -> &[BlockInfo];
 	fn get_lists(&self) -> &[ListInfo];
 	fn get_list_items(&self) -> &[ListItemInfo];
 	fn get_bolds(&self) -> &[FormatInfo];
@@ -279,7 +397,11 @@ fn add_links(buffer: &mut DocumentBuffer, converter: &dyn ConverterOutput, offse
 
 fn add_images(buffer: &mut DocumentBuffer, converter: &dyn ConverterOutput, offset: usize) {
 	for image in converter.get_images() {
-		buffer.add_marker(Marker::new(MarkerType::Image, offset + image.offset).with_text(image.alt_text.clone()));
+		buffer.add_marker(
+			Marker::new(MarkerType::Image, offset + image.offset)
+				.with_text(image.alt_text.clone())
+				.with_length(display_len(&image_text(&image.alt_text))),
+		);
 	}
 }
 
@@ -314,7 +436,30 @@ fn add_tables_separators_lists(buffer: &mut DocumentBuffer, converter: &dyn Conv
 		buffer.add_marker(
 			Marker::new(MarkerType::ListItem, offset + list_item.offset)
 				.with_text(list_item.text.clone())
-				.with_level(list_item.level),
+				.with_level(list_item.level)
+				.with_length(list_item.length),
+		);
+	}
+}
+
+fn add_blocks(buffer: &mut DocumentBuffer, converter: &dyn ConverterOutput, offset: usize) {
+	for quote in converter.get_block_quotes() {
+		buffer.add_marker(
+			Marker::new(MarkerType::BlockQuote, offset + quote.offset)
+				.with_text(quote.text.clone())
+				.with_length(quote.length),
+		);
+	}
+	for code in converter.get_code_blocks() {
+		buffer.add_marker(
+			Marker::new(MarkerType::CodeBlock, offset + code.offset)
+				.with_text(code.text.clone())
+				.with_length(code.length),
+		);
+	}
+	for note in converter.get_notes() {
+		buffer.add_marker(
+			Marker::new(MarkerType::Note, offset + note.offset).with_text(note.text.clone()).with_length(note.length),
 		);
 	}
 }
@@ -339,6 +484,7 @@ pub fn add_converter_markers(buffer: &mut DocumentBuffer, converter: &dyn Conver
 	add_images(buffer, converter, offset);
 	add_figures(buffer, converter, offset);
 	add_tables_separators_lists(buffer, converter, offset);
+	add_blocks(buffer, converter, offset);
 	add_formatting(buffer, converter, offset);
 }
 
@@ -352,23 +498,89 @@ pub fn add_converter_markers_excluding_links(
 	add_images(buffer, converter, offset);
 	add_figures(buffer, converter, offset);
 	add_tables_separators_lists(buffer, converter, offset);
+	add_blocks(buffer, converter, offset);
 	add_formatting(buffer, converter, offset);
 }
 
+/// Records landmarks found in converted text starting at `offset`. A landmark of the same kind as
+/// the one before it is dropped, since books split into files often repeat the type (such as
+/// `bodymatter`) on every file of the part.
+pub fn add_converter_landmarks(doc: &mut Document, landmarks: &[LandmarkInfo], offset: usize) {
+	for landmark in landmarks {
+		if doc.landmarks.last().is_some_and(|last| last.kind == landmark.kind) {
+			continue;
+		}
+		doc.add_landmark(landmark.kind, "", offset + landmark.offset);
+	}
+}
+
+/// Whether an `epub:type` or DPUB-ARIA `role` value marks a single footnote or endnote, as opposed
+/// to a section collecting them.
+#[must_use]
+pub fn is_note_semantic_type(value: &str) -> bool {
+	value.split_ascii_whitespace().any(|token| {
+		let token = token.to_ascii_lowercase();
+		matches!(token.strip_prefix("doc-").unwrap_or(&token), "footnote" | "endnote" | "rearnote" | "note")
+	})
+}
+
 #[must_use]
 pub fn is_external_url(url: &str) -> bool {
 	let lower = url.to_ascii_lowercase();
 	lower.starts_with("http:") || lower.starts_with("https:") || lower.starts_with("mailto:")
 }
 
+/// Page-break marker text for a presentation slide: "Slide N: Title", "Slide N", or
+/// "Slide N (no text)" when the slide has nothing readable (e.g. it only holds images).
+#[must_use]
+pub fn slide_marker_text(number: usize, title: &str, has_text: bool) -> String {
+	let number = number.to_string();
+	if !has_text {
+		// TRANSLATORS: Marker text for a presentation slide that has no text (e.g. only images); %d is the slide number
+		return t("Slide %d (no text)").replacen("%d", &number, 1);
+	}
+	let title = title.trim();
+	if title.is_empty() {
+		// TRANSLATORS: Marker text for a presentation slide without a title; %d is the slide number
+		t("Slide %d").replacen("%d", &number, 1)
+	} else {
+		// TRANSLATORS: Marker text for a presentation slide; %d is the slide number, %s is the slide title
+		t("Slide %d: %s").replacen("%d", &number, 1).replacen("%s", title, 1)
+	}
+}
+
+/// Appends a slide's speaker notes under a "Notes:" line. Does nothing when `notes` is empty.
+pub fn append_speaker_notes(buffer: &mut DocumentBuffer, notes: &str) {
+	let notes = notes.trim();
+	if notes.is_empty() {
+		return;
+	}
+	if !buffer.content.is_empty() && !buffer.content.ends_with('\n') {
+		buffer.append("\n");
+	}
+	// TRANSLATORS: Line introducing the speaker notes of a presentation slide
+	buffer.append(&t("Notes:"));
+	buffer.append("\n");
+	buffer.append(notes);
+	buffer.append("\n");
+}
+
 #[cfg(test)]
 mod tests {
-	use std::iter;
+	use std::{
+		env, fs, iter,
+		path::PathBuf,
+		time::{SystemTime, UNIX_EPOCH},
+	};
 
 	use rstest::rstest;
 
 	use super::*;
-	use crate::types::{FormatInfo, HeadingInfo, LinkInfo, ListInfo, ListItemInfo, SeparatorInfo, TableInfo};
+	use crate::{
+		parser::error::ParserErrorCode,
+		types::{BlockInfo, FormatInfo, HeadingInfo, LinkInfo, ListInfo, ListItemInfo, SeparatorInfo, TableInfo},
+		util::zip::testing::zip_bytes,
+	};
 
 	struct MockConverter {
 		headings: Vec<HeadingInfo>,
@@ -377,6 +589,9 @@ mod tests {
 		figures: Vec<ImageInfo>,
 		tables: Vec<TableInfo>,
 		separators: Vec<SeparatorInfo>,
+		block_quotes: Vec<BlockInfo>,
+		code_blocks: Vec<BlockInfo>,
+		notes: Vec<BlockInfo>,
 		lists: Vec<ListInfo>,
 		list_items: Vec<ListItemInfo>,
 		bolds: Vec<FormatInfo>,
@@ -409,6 +624,18 @@ mod tests {
 			&self.separators
 		}
 
+		fn get_block_quotes(&self) -> &[BlockInfo] {
+			&self.block_quotes
+		}
+
+		fn get_code_blocks(&self) -> &[BlockInfo] {
+			&self.code_blocks
+		}
+
+		fn get_notes(&self) -> &[BlockInfo] {
+			&self.notes
+		}
+
 		fn get_lists(&self) -> &[ListInfo] {
 			&self.lists
 		}
@@ -442,8 +669,11 @@ mod tests {
 				length: 11,
 			}],
 			separators: vec![SeparatorInfo { offset: 4, length: 7 }],
+			block_quotes: vec![],
+			code_blocks: vec![],
+			notes: vec![],
 			lists: vec![ListInfo { offset: 5, item_count: 3, length: 4 }],
-			list_items: vec![ListItemInfo { offset: 6, level: 1, text: "Item".to_string() }],
+			list_items: vec![ListItemInfo { offset: 6, level: 1, text: "Item".to_string(), length: 5 }],
 			bolds: vec![],
 			italics: vec![],
 			underlines: vec![],
@@ -500,6 +730,18 @@ mod tests {
 		assert!(filter.ends_with("All Files (*.*)|*.*"));
 	}
 
+	#[test]
+	fn available_parsers_include_open_as_only_formats_in_name_order() {
+		let parsers = get_available_parsers();
+		let names: Vec<&str> = parsers.iter().map(|p| p.name.as_str()).collect();
+		assert!(names.is_sorted());
+		assert!(parsers.iter().all(|p| !p.extensions.is_empty()));
+		let parts = parsers.iter().find(|p| p.extensions.first().map(String::as_str) == Some(text::TEXT_PARTS_FORMAT));
+		assert!(parts.is_some_and(|p| p.reads_encoding));
+		let epub = parsers.iter().find(|p| p.extensions.iter().any(|ext| ext == "epub"));
+		assert!(epub.is_some_and(|p| !p.reads_encoding));
+	}
+
 	#[test]
 	fn add_converter_markers_transfers_all_marker_types_with_offset() {
 		let converter = sample_converter();
@@ -520,6 +762,7 @@ mod tests {
 		assert_eq!(buffer.markers[4].level, 3);
 		assert_eq!(buffer.markers[5].mtype, MarkerType::ListItem);
 		assert_eq!(buffer.markers[5].level, 1);
+		assert_eq!(buffer.markers[5].length, 5);
 	}
 
 	#[test]
@@ -531,6 +774,23 @@ mod tests {
 		assert!(buffer.markers.iter().all(|marker| marker.mtype != MarkerType::Link));
 	}
 
+	#[test]
+	fn add_converter_markers_transfers_block_quotes_and_code_blocks() {
+		let mut converter = sample_converter();
+		converter.block_quotes = vec![BlockInfo { offset: 7, text: "Quote".to_string(), length: 6 }];
+		converter.code_blocks = vec![BlockInfo { offset: 8, text: "let x = 1;".to_string(), length: 11 }];
+		let mut buffer = DocumentBuffer::new();
+		add_converter_markers_excluding_links(&mut buffer, &converter, 10);
+		let quote = buffer.markers.iter().find(|m| m.mtype == MarkerType::BlockQuote).expect("block quote marker");
+		assert_eq!(quote.position, 17);
+		assert_eq!(quote.text, "Quote");
+		assert_eq!(quote.length, 6);
+		let code = buffer.markers.iter().find(|m| m.mtype == MarkerType::CodeBlock).expect("code block marker");
+		assert_eq!(code.position, 18);
+		assert_eq!(code.text, "let x = 1;");
+		assert_eq!(code.length, 11);
+	}
+
 	#[test]
 	fn add_converter_markers_handles_empty_converter_output() {
 		let converter = MockConverter {
@@ -540,6 +800,9 @@ mod tests {
 			figures: vec![],
 			tables: vec![],
 			separators: vec![],
+			block_quotes: vec![],
+			code_blocks: vec![],
+			notes: vec![],
 			lists: vec![],
 			list_items: vec![],
 			bolds: vec![],
@@ -565,6 +828,116 @@ mod tests {
 		assert!(err.to_string().contains("No parser found for extension"));
 	}
 
+	fn write_temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let path = env::temp_dir().join(format!("paperback_parser_error_{nanos}_{name}"));
+		fs::write(&path, bytes).unwrap();
+		path
+	}
+
+	fn parse_bytes(name: &str, bytes: &[u8]) -> Result<Document, ParserError> {
+		let path = write_temp_file(name, bytes);
+		let result = parse_document(&ParserContext::new(path.to_string_lossy().to_string()));
+		let _ = fs::remove_file(&path);
+		result
+	}
+
+	fn parse_error_for(name: &str, bytes: &[u8]) -> ParserError {
+		parse_bytes(name, bytes).expect_err("expected parse failure")
+	}
+
+	#[test]
+	fn truncated_epub_is_reported_as_corrupt_archive() {
+		let container = r#"<container><rootfiles><rootfile full-path="content.opf"/></rootfiles></container>"#;
+		let epub = zip_bytes(&[("mimetype", "application/epub+zip"), ("META-INF/container.xml", container)]);
+		let err = parse_error_for("truncated.epub", &epub[..epub.len() / 2]);
+		assert_eq!(err.code(), ParserErrorCode::CorruptArchive);
+	}
+
+	const DAMAGED_NOTICE: &str = "This file appears damaged; content may be incomplete.\n";
+
+	fn sample_epub() -> Vec<u8> {
+		let container = r#"<container><rootfiles><rootfile full-path="content.opf"/></rootfiles></container>"#;
+		let package = concat!(
+			r#"<package xmlns="http://www.idpf.org/2007/opf" version="3.0"><manifest>"#,
+			r#"<item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>"#,
+			r#"<item id="c2" href="c2.xhtml" media-type="application/xhtml+xml"/>"#,
+			r#"</manifest><spine><itemref idref="c1"/><itemref idref="c2"/></spine></package>"#
+		);
+		let chapter = |title: &str| {
+			let paragraphs = iter::repeat_n(format!("<p>{title} goes on at some length.</p>"), 40).collect::<String>();
+			format!(r#"<html xmlns="http://www.w3.org/1999/xhtml"><body><h1>{title}</h1>{paragraphs}</body></html>"#)
+		};
+		let (first, second) = (chapter("First chapter"), chapter("Second chapter"));
+		zip_bytes(&[
+			("mimetype", "application/epub+zip"),
+			("META-INF/container.xml", container),
+			("content.opf", package),
+			("c1.xhtml", first.as_str()),
+			("c2.xhtml", second.as_str()),
+		])
+	}
+
+	#[test]
+	fn empty_file_is_reported_as_empty() {
+		for name in ["empty.epub", "empty.txt", "empty.docx"] {
+			let err = parse_error_for(name, &[]);
+			assert_eq!(err, ParserError::EmptyFile("The file is empty.".to_string()), "{name}");
+		}
+	}
+
+	#[test]
+	fn epub_missing_its_central_directory_opens_with_a_warning() {
+		let epub = sample_epub();
+		let doc = parse_bytes("cut.epub", &epub[..epub.len() - 30]).expect("salvaged document");
+		assert!(doc.buffer.content.starts_with(DAMAGED_NOTICE));
+		assert!(doc.buffer.content.contains("Second chapter goes on"));
+		assert!(doc.toc_items.iter().all(|item| item.offset >= DAMAGED_NOTICE.len()));
+	}
+
+	#[test]
+	fn epub_truncated_anywhere_fails_cleanly_or_opens_partially() {
+		let epub = sample_epub();
+		let mut state = 0x2545_f491_u32;
+		let cuts = iter::repeat_with(|| {
+			state ^= state << 13;
+			state ^= state >> 17;
+			state ^= state << 5;
+			usize::try_from(state).unwrap() % epub.len()
+		});
+		for cut in [0, 1, 4, 30].into_iter().chain(cuts.take(60)) {
+			match parse_bytes("cut.epub", &epub[..cut]) {
+				Ok(doc) => assert!(doc.buffer.content.starts_with(DAMAGED_NOTICE), "cut at {cut}"),
+				Err(err) => assert!(
+					matches!(err.code(), ParserErrorCode::CorruptArchive | ParserErrorCode::EmptyFile),
+					"cut at {cut}: {err:?}"
+				),
+			}
+		}
+	}
+
+	#[test]
+	fn drm_protected_epub_is_reported_as_encrypted() {
+		let encryption = concat!(
+			r#"<encryption><EncryptedData><EncryptionMethod Algorithm="http://www.w3.org/2001/04/xmlenc#aes128-cbc"/>"#,
+			r#"<CipherData><CipherReference URI="OEBPS/chapter1.xhtml"/></CipherData></EncryptedData></encryption>"#
+		);
+		let epub = zip_bytes(&[("mimetype", "application/epub+zip"), ("META-INF/encryption.xml", encryption)]);
+		assert_eq!(parse_error_for("drm.epub", &epub).code(), ParserErrorCode::Encrypted);
+	}
+
+	#[test]
+	fn drm_pdf_is_reported_as_encrypted() {
+		let pdf = b"%PDF-1.6\n1 0 obj\n<< /Filter /EBX_HANDLER /V 4 >>\nendobj\ntrailer\n<< /Encrypt 1 0 R >>\n%%EOF\n";
+		assert_eq!(parse_error_for("drm.pdf", pdf).code(), ParserErrorCode::Encrypted);
+	}
+
+	#[test]
+	fn binary_garbage_docx_is_reported_as_corrupt_archive() {
+		let garbage: Vec<u8> = (0..2048u32).map(|i| u8::try_from((i * 7919 + 13) % 251).unwrap()).collect();
+		assert_eq!(parse_error_for("garbage.docx", &garbage).code(), ParserErrorCode::CorruptArchive);
+	}
+
 	#[test]
 	fn get_parser_flags_for_context_returns_none_for_unknown_extension() {
 		let context = ParserContext::new("doc.unknown_ext".to_string());
@@ -592,6 +965,9 @@ mod tests {
 				length: 7, // display-unit field — must appear as marker length
 			}],
 			separators: vec![],
+			block_quotes: vec![],
+			code_blocks: vec![],
+			notes: vec![],
 			lists: vec![],
 			list_items: vec![],
 			bolds: vec![],
@@ -607,4 +983,26 @@ mod tests {
 		assert_eq!(table_marker.length, 7, "marker length must equal table length, not byte length");
 		assert_eq!(table_marker.reference, "<table/>", "marker reference must be the table HTML");
 	}
+
+	#[rstest]
+	#[case(12, "Results", true, "Slide 12: Results")]
+	#[case(3, "  ", true, "Slide 3")]
+	#[case(4, "Photos", false, "Slide 4 (no text)")]
+	fn slide_marker_text_describes_slide(
+		#[case] number: usize,
+		#[case] title: &str,
+		#[case] has_text: bool,
+		#[case] expected: &str,
+	) {
+		assert_eq!(slide_marker_text(number, title, has_text), expected);
+	}
+
+	#[test]
+	fn append_speaker_notes_adds_notes_block_after_slide_text() {
+		let mut buffer = DocumentBuffer::new();
+		buffer.append("Body");
+		append_speaker_notes(&mut buffer, "  Say hello  ");
+		append_speaker_notes(&mut buffer, "   ");
+		assert_eq!(buffer.content, "Body\nNotes:\nSay hello\n");
+	}
 }
@@ -0,0 +1,144 @@
+use std::{
+	env, fs,
+	fs::File,
+	path::Path,
+	process,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+	document::{Document, ParserContext, ParserFlags},
+	parser::{Parser, error::ParserError, parse_unprocessed, parser_supports_extension},
+	t,
+	util::zip::{OpenedZip, extract_zip_entry_to_file, open_zip},
+};
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "tif", "tiff"];
+/// Archives inside the archive are not opened; one level of nesting is all we handle.
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "cbz"];
+
+/// Opens a document packed inside a ZIP archive. Registered after the DAISY and Word parsers so
+/// zipped DAISY books and bundles of .docx files keep their dedicated handling.
+pub struct ArchiveParser;
+
+impl Parser for ArchiveParser {
+	fn name(&self) -> &'static str {
+		"Archives"
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&["zip", "cbz"]
+	}
+
+	fn supported_flags(&self) -> ParserFlags {
+		ParserFlags::NONE
+	}
+
+	fn parse(&self, context: &ParserContext) -> Result<Document> {
+		let file = File::open(&context.file_path)
+			.with_context(|| format!("Failed to open archive '{}'", context.file_path))?;
+		let OpenedZip { mut archive, salvaged } =
+			open_zip(file).with_context(|| format!("Failed to read archive '{}'", context.file_path))?;
+		let names: Vec<String> = archive.file_names().map(String::from).collect();
+		let candidates = readable_entries(&names);
+		let entry = match (&context.archive_entry, candidates.as_slice()) {
+			(Some(entry), _) if candidates.contains(entry) => entry.clone(),
+			(_, [only]) => only.clone(),
+			(_, []) if names.iter().any(|name| has_extension(name, IMAGE_EXTENSIONS)) => {
+				anyhow::bail!(ParserError::UnsupportedFormat(
+					// TRANSLATORS: Error shown when a ZIP or CBZ archive only holds images, such as a comic book
+					t("This archive only contains images, such as a comic book, which can't be read as text.")
+				));
+			}
+			(_, []) => {
+				anyhow::bail!(ParserError::UnsupportedFormat(
+					// TRANSLATORS: Error shown when a ZIP archive holds no file in a format Paperback can open
+					t("The archive doesn't contain any documents Paperback can open.")
+				));
+			}
+			_ => anyhow::bail!(ParserError::ArchiveEntryRequired(candidates)),
+		};
+		let file_name =
+			Path::new(&entry).file_name().map_or_else(|| entry.clone(), |n| n.to_string_lossy().into_owned());
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+		let dir = env::temp_dir().join(format!("paperback_archive_{}_{nanos}", process::id()));
+		let extracted = dir.join(file_name);
+		let result = extract_zip_entry_to_file(&mut archive, &entry, &extracted).and_then(|()| {
+			let mut inner = context.clone();
+			inner.file_path = extracted.to_string_lossy().into_owned();
+			inner.forced_extension = None;
+			inner.archive_entry = None;
+			// The extracted copy is deleted below, so there is nothing for a page loader to reopen.
+			inner.lazy_page_limit = None;
+			parse_unprocessed(&inner).map_err(anyhow::Error::new)
+		});
+		let _ = fs::remove_dir_all(&dir);
+		let mut doc =
+			result.with_context(|| format!("Failed to open '{entry}' from archive '{}'", context.file_path))?;
+		doc.damaged |= salvaged;
+		Ok(doc)
+	}
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+	Path::new(name)
+		.extension()
+		.and_then(|ext| ext.to_str())
+		.is_some_and(|ext| extensions.iter().any(|known| ext.eq_ignore_ascii_case(known)))
+}
+
+/// Entries that one of the registered parsers can open, sorted by name. Directories, macOS
+/// resource forks and nested archives are skipped.
+fn readable_entries(names: &[String]) -> Vec<String> {
+	let mut entries: Vec<String> = names
+		.iter()
+		.filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+		.filter(|name| !has_extension(name, ARCHIVE_EXTENSIONS))
+		.filter(|name| Path::new(name).extension().and_then(|ext| ext.to_str()).is_some_and(parser_supports_extension))
+		.cloned()
+		.collect();
+	entries.sort();
+	entries
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		parser::{error::ParserErrorCode, parse_document},
+		util::zip::testing::TempZip,
+	};
+
+	#[test]
+	fn readable_entries_skip_folders_resource_forks_and_nested_archives() {
+		let names =
+			["b.txt", "a/", "a/story.md", "__MACOSX/a/._story.md", "inner.zip", "cover.jpg"].map(String::from).to_vec();
+		assert_eq!(readable_entries(&names), vec!["a/story.md".to_string(), "b.txt".to_string()]);
+	}
+
+	#[test]
+	fn single_document_in_zip_opens_directly() {
+		let zip = TempZip::new("single.zip", &[("notes/readme.txt", "Hello from inside."), ("cover.png", "x")]);
+		let doc = parse_document(&ParserContext::new(zip.path())).unwrap();
+		assert!(doc.buffer.content.contains("Hello from inside."));
+	}
+
+	#[test]
+	fn several_documents_require_a_choice() {
+		let zip = TempZip::new("many.zip", &[("ch2.txt", "Two"), ("ch1.txt", "One")]);
+		let err = parse_document(&ParserContext::new(zip.path())).unwrap_err();
+		assert_eq!(err, ParserError::ArchiveEntryRequired(vec!["ch1.txt".to_string(), "ch2.txt".to_string()]));
+		let doc = parse_document(&ParserContext::new(zip.path()).with_archive_entry("ch2.txt".to_string())).unwrap();
+		assert_eq!(doc.buffer.content.trim(), "Two");
+	}
+
+	#[test]
+	fn image_only_cbz_is_rejected() {
+		let zip = TempZip::new("comic.cbz", &[("001.jpg", "x"), ("002.jpg", "y")]);
+		let err = parse_document(&ParserContext::new(zip.path())).unwrap_err();
+		assert_eq!(err.code(), ParserErrorCode::UnsupportedFormat);
+		assert!(err.to_string().contains("only contains images"));
+	}
+}
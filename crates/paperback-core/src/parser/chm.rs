@@ -1,6 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+	collections::{HashMap, HashSet},
+	str,
+};
 
 use anyhow::{Context, Result};
+use encoding_rs::Encoding;
 use libchm::{ChmFile, EntryCategory, EntrySel};
 use scraper::{ElementRef, Html, Selector};
 
@@ -12,7 +16,7 @@ use crate::{
 		is_external_url,
 		util::path::extract_title_from_path,
 	},
-	util::encoding::convert_to_utf8,
+	util::encoding::convert_html_to_utf8,
 };
 
 pub struct ChmParser;
@@ -37,21 +41,36 @@ impl Parser for ChmParser {
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
 		let mut chm = ChmFile::open(&context.file_path)
 			.with_context(|| format!("Failed to open CHM file: {}", context.file_path))?;
+		let system =
+			chm.find("/#SYSTEM").and_then(|e| chm.read(&e)).map(|data| parse_system_data(&data)).unwrap_or_default();
 		let mut html_files = Vec::new();
 		let mut hhc_file = String::new();
+		let mut hhk_file = String::new();
+		let mut entry_paths = HashMap::new();
 		for entry in chm.entries(EntrySel::ALL)? {
+			entry_paths.insert(normalize_path(&entry.path), entry.path.clone());
 			let lower_path = entry.path.to_lowercase();
 			if lower_path.contains(".hhc") && (hhc_file.is_empty() || lower_path.contains("index.hhc")) {
 				hhc_file.clone_from(&entry.path);
 			}
+			if lower_path.ends_with(".hhk") && hhk_file.is_empty() {
+				hhk_file.clone_from(&entry.path);
+			}
 			if (lower_path.contains(".htm") || lower_path.contains(".html")) && entry.category != EntryCategory::Special
 			{
 				html_files.push(entry.path);
 			}
 		}
 		html_files.sort();
-		let title = parse_system_file(&mut chm).unwrap_or_else(|| extract_title_from_path(&context.file_path));
-		let mut toc_items = if hhc_file.is_empty() { Vec::new() } else { parse_hhc_file(&mut chm, &hhc_file)? };
+		// The #SYSTEM file names the compiled contents and index files; prefer those over guessing.
+		for (declared, fallback) in [(&system.contents_file, &mut hhc_file), (&system.index_file, &mut hhk_file)] {
+			if let Some(actual) = declared.as_deref().and_then(|declared| entry_paths.get(&normalize_path(declared))) {
+				fallback.clone_from(actual);
+			}
+		}
+		let title = system.title().unwrap_or_else(|| extract_title_from_path(&context.file_path));
+		let mut toc_items =
+			if hhc_file.is_empty() { Vec::new() } else { parse_sitemap_file(&mut chm, &hhc_file, system.encoding)? };
 		let ordered_files = build_ordered_file_list(&html_files, &toc_items);
 		let mut buffer = DocumentBuffer::new();
 		let mut id_positions = HashMap::new();
@@ -62,7 +81,7 @@ impl Parser for ChmParser {
 			if content_bytes.is_empty() {
 				continue;
 			}
-			let utf8_content = convert_to_utf8(&content_bytes);
+			let utf8_content = convert_html_to_utf8(&content_bytes, system.encoding);
 			let mut converter = HtmlToText::with_render_tables_inline(context.render_tables_inline);
 			if !converter.convert(&utf8_content, HtmlSourceMode::NativeHtml) {
 				continue;
@@ -97,59 +116,146 @@ impl Parser for ChmParser {
 			}
 		}
 		calculate_toc_offsets(&mut toc_items, &file_positions, &id_positions);
+		let toc_items = prune_unresolved_items(toc_items);
+		let mut index_items = Vec::new();
+		if !hhk_file.is_empty() {
+			// A broken index shouldn't prevent the book itself from opening.
+			let mut keywords = parse_sitemap_file(&mut chm, &hhk_file, system.encoding).unwrap_or_default();
+			calculate_toc_offsets(&mut keywords, &file_positions, &id_positions);
+			flatten_index_items(keywords, &mut index_items);
+		}
 		let mut document = Document::new().with_title(title);
 		document.set_buffer(buffer);
 		document.id_positions = id_positions;
 		document.toc_items = toc_items;
+		document.index_items = index_items;
 		Ok(document)
 	}
 }
 
-fn parse_system_file(chm: &mut ChmFile) -> Option<String> {
-	let content = chm.find("/#SYSTEM").and_then(|e| chm.read(&e)).ok()?;
-	if content.len() < 4 {
-		return None;
+/// The parts of a CHM's `#SYSTEM` file that the parser cares about.
+#[derive(Default)]
+struct ChmSystemInfo {
+	contents_file: Option<String>,
+	index_file: Option<String>,
+	title: Option<Vec<u8>>,
+	/// Legacy code page implied by the help file's locale, used for undeclared topic encodings.
+	encoding: Option<&'static Encoding>,
+}
+
+impl ChmSystemInfo {
+	fn title(&self) -> Option<String> {
+		let bytes = self.title.as_deref()?;
+		let title = match self.encoding {
+			Some(encoding) if str::from_utf8(bytes).is_err() => {
+				encoding.decode_without_bom_handling(bytes).0.into_owned()
+			}
+			_ => String::from_utf8_lossy(bytes).into_owned(),
+		};
+		let title = title.trim();
+		(!title.is_empty()).then(|| title.to_string())
 	}
+}
+
+fn parse_system_data(content: &[u8]) -> ChmSystemInfo {
+	let mut info = ChmSystemInfo::default();
 	let read_le16 = |data: &[u8], offset: usize| -> u16 { u16::from_le_bytes([data[offset], data[offset + 1]]) };
+	let strip_nul = |bytes: &[u8]| -> Vec<u8> {
+		let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+		bytes[..end].to_vec()
+	};
 	let mut index = 4;
 	while index + 4 <= content.len() {
-		let code = read_le16(&content, index);
-		let length = read_le16(&content, index + 2) as usize;
+		let code = read_le16(content, index);
+		let length = read_le16(content, index + 2) as usize;
 		if index + 4 + length > content.len() {
 			break;
 		}
-		// Code 3 is the title.
-		if code == 3 && length > 0 {
-			let title_bytes = &content[index + 4..index + 4 + length];
-			let title_bytes =
-				if title_bytes.last() == Some(&0) { &title_bytes[..title_bytes.len() - 1] } else { title_bytes };
-			let title = String::from_utf8_lossy(title_bytes).to_string();
-			if !title.trim().is_empty() {
-				return Some(title);
+		let data = &content[index + 4..index + 4 + length];
+		match code {
+			0 if length > 0 => info.contents_file = Some(String::from_utf8_lossy(&strip_nul(data)).into_owned()),
+			1 if length > 0 => info.index_file = Some(String::from_utf8_lossy(&strip_nul(data)).into_owned()),
+			3 if length > 0 => info.title = Some(strip_nul(data)),
+			// Code 4 starts with the help file's LCID.
+			4 if length >= 4 => {
+				info.encoding = encoding_for_lcid(u32::from_le_bytes([data[0], data[1], data[2], data[3]]));
 			}
+			_ => {}
 		}
 		index += 4 + length;
 	}
-	None
+	info.contents_file = info.contents_file.filter(|file| !file.trim().is_empty());
+	info.index_file = info.index_file.filter(|file| !file.trim().is_empty());
+	info
 }
 
-fn parse_hhc_file(chm: &mut ChmFile, hhc_path: &str) -> Result<Vec<TocItem>> {
-	let content_bytes = chm
-		.find(hhc_path)
-		.and_then(|e| chm.read(&e))
-		.with_context(|| format!("Failed to read .hhc file: {hhc_path}"))?;
+/// Maps a Windows locale ID to the ANSI code page HTML Help Workshop would have compiled its topics in.
+fn encoding_for_lcid(lcid: u32) -> Option<&'static Encoding> {
+	let encoding = match lcid & 0x3FF {
+		0x02 | 0x19 | 0x22 | 0x23 | 0x2F | 0x40 | 0x43 | 0x44 | 0x50 => encoding_rs::WINDOWS_1251,
+		0x1A if lcid == 0x0C1A || lcid == 0x1C1A => encoding_rs::WINDOWS_1251,
+		0x05 | 0x0E | 0x15 | 0x18 | 0x1A | 0x1B | 0x1C | 0x24 => encoding_rs::WINDOWS_1250,
+		0x08 => encoding_rs::WINDOWS_1253,
+		0x1F | 0x2C => encoding_rs::WINDOWS_1254,
+		0x0D => encoding_rs::WINDOWS_1255,
+		0x01 | 0x20 | 0x29 => encoding_rs::WINDOWS_1256,
+		0x25..=0x27 => encoding_rs::WINDOWS_1257,
+		0x2A => encoding_rs::WINDOWS_1258,
+		0x1E => encoding_rs::WINDOWS_874,
+		0x11 => encoding_rs::SHIFT_JIS,
+		0x12 => encoding_rs::EUC_KR,
+		0x04 if matches!(lcid, 0x0404 | 0x0C04 | 0x1404) => encoding_rs::BIG5,
+		0x04 => encoding_rs::GBK,
+		_ => return None,
+	};
+	Some(encoding)
+}
+
+/// Parses an HTML Help sitemap (`.hhc` contents or `.hhk` index) into a tree of items with unresolved offsets.
+fn parse_sitemap_file(chm: &mut ChmFile, path: &str, encoding: Option<&'static Encoding>) -> Result<Vec<TocItem>> {
+	let content_bytes =
+		chm.find(path).and_then(|e| chm.read(&e)).with_context(|| format!("Failed to read sitemap file: {path}"))?;
 	if content_bytes.is_empty() {
 		return Ok(Vec::new());
 	}
-	let content = convert_to_utf8(&content_bytes);
-	let document = Html::parse_document(&content);
+	Ok(parse_sitemap(&convert_html_to_utf8(&content_bytes, encoding)))
+}
+
+fn parse_sitemap(content: &str) -> Vec<TocItem> {
+	let document = Html::parse_document(content);
 	let body_selector = Selector::parse("body").unwrap();
 	let Some(body) = document.select(&body_selector).next() else {
-		return Ok(Vec::new());
+		return Vec::new();
 	};
 	let mut toc_items = Vec::new();
 	parse_hhc_node(body, &mut toc_items);
-	Ok(toc_items)
+	toc_items
+}
+
+/// Drops entries whose topic isn't in the archive, hoisting any resolvable children into their place.
+/// A missing parent that still has children keeps its place and points at its first child.
+fn prune_unresolved_items(items: Vec<TocItem>) -> Vec<TocItem> {
+	let mut result = Vec::new();
+	for mut item in items {
+		item.children = prune_unresolved_items(item.children);
+		if item.offset != usize::MAX {
+			result.push(item);
+		} else if let Some(first) = item.children.first() {
+			item.offset = first.offset;
+			result.push(item);
+		}
+	}
+	result
+}
+
+fn flatten_index_items(items: Vec<TocItem>, output: &mut Vec<TocItem>) {
+	for mut item in items {
+		let children = std::mem::take(&mut item.children);
+		if item.offset != usize::MAX {
+			output.push(item);
+		}
+		flatten_index_items(children, output);
+	}
 }
 
 fn parse_hhc_node(node: ElementRef, items: &mut Vec<TocItem>) {
@@ -182,8 +288,9 @@ fn parse_hhc_node(node: ElementRef, items: &mut Vec<TocItem>) {
 							let param_name = param.value().attr("name").unwrap_or("").to_lowercase();
 							let param_value = param.value().attr("value").unwrap_or("");
 							match param_name.as_str() {
-								"name" => name = param_value.to_string(),
-								"local" => local = param_value.to_string(),
+								// Index entries repeat Name/Local per topic; the first pair is the keyword's own.
+								"name" if name.is_empty() => name = param_value.to_string(),
+								"local" if local.is_empty() => local = param_value.to_string(),
 								_ => {}
 							}
 						}
@@ -342,3 +449,68 @@ fn calculate_offset_from_reference(
 	}
 	file_positions.get(&normalized_path).copied().unwrap_or(usize::MAX)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn system_entry(code: u16, data: &[u8]) -> Vec<u8> {
+		let mut entry = code.to_le_bytes().to_vec();
+		entry.extend_from_slice(&u16::try_from(data.len()).unwrap().to_le_bytes());
+		entry.extend_from_slice(data);
+		entry
+	}
+
+	#[test]
+	fn system_data_yields_sitemaps_title_and_locale_encoding() {
+		let mut data = vec![3, 0, 0, 0];
+		data.extend(system_entry(0, b"Contents.hhc\0"));
+		data.extend(system_entry(1, b"Index.hhk\0"));
+		data.extend(system_entry(3, b"\xD1\xEF\xF0\xE0\xE2\xEA\xE0\0"));
+		data.extend(system_entry(4, &[0x19, 0x04, 0, 0, 0, 0, 0, 0]));
+		let info = parse_system_data(&data);
+		assert_eq!(info.contents_file.as_deref(), Some("Contents.hhc"));
+		assert_eq!(info.index_file.as_deref(), Some("Index.hhk"));
+		assert_eq!(info.encoding, Some(encoding_rs::WINDOWS_1251));
+		assert_eq!(info.title().as_deref(), Some("Справка"));
+	}
+
+	#[test]
+	fn missing_topics_are_pruned_from_the_toc() {
+		let sitemap = concat!(
+			"<html><body><ul>",
+			r#"<li><object type="text/sitemap"><param name="Name" value="Intro"><param name="Local" value="intro.htm"></object>"#,
+			r#"<li><object type="text/sitemap"><param name="Name" value="Gone"><param name="Local" value="gone.htm"></object>"#,
+			r#"<ul><li><object type="text/sitemap"><param name="Name" value="Usage"><param name="Local" value="usage.htm#top"></object></ul>"#,
+			r#"<li><object type="text/sitemap"><param name="Name" value="Lost"><param name="Local" value="lost.htm"></object>"#,
+			"</ul></body></html>"
+		);
+		let mut items = parse_sitemap(sitemap);
+		let file_positions = HashMap::from([("/intro.htm".to_string(), 0), ("/usage.htm".to_string(), 40)]);
+		let id_positions = HashMap::from([("/usage.htm#top".to_string(), 42)]);
+		calculate_toc_offsets(&mut items, &file_positions, &id_positions);
+		let items = prune_unresolved_items(items);
+		assert_eq!(items.len(), 2);
+		assert_eq!((items[0].name.as_str(), items[0].offset), ("Intro", 0));
+		assert_eq!((items[1].name.as_str(), items[1].offset), ("Gone", 42));
+		assert_eq!((items[1].children[0].name.as_str(), items[1].children[0].offset), ("Usage", 42));
+	}
+
+	#[test]
+	fn index_keywords_flatten_and_use_first_name() {
+		let sitemap = concat!(
+			"<html><body><ul>",
+			r#"<li><object type="text/sitemap"><param name="Name" value="alpha"><param name="Name" value="Alpha topic"><param name="Local" value="a.htm"></object>"#,
+			r#"<ul><li><object type="text/sitemap"><param name="Name" value="beta"><param name="Local" value="b.htm"></object></ul>"#,
+			"</ul></body></html>"
+		);
+		let mut keywords = parse_sitemap(sitemap);
+		let file_positions = HashMap::from([("/a.htm".to_string(), 5), ("/b.htm".to_string(), 9)]);
+		calculate_toc_offsets(&mut keywords, &file_positions, &HashMap::new());
+		let mut flat = Vec::new();
+		flatten_index_items(keywords, &mut flat);
+		let names: Vec<_> = flat.iter().map(|item| (item.name.as_str(), item.offset)).collect();
+		assert_eq!(names, [("alpha", 5), ("beta", 9)]);
+		assert!(flat.iter().all(|item| item.children.is_empty()));
+	}
+}
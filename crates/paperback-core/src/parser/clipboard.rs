@@ -0,0 +1,105 @@
+use crate::{
+	config::utc_date,
+	document::{Document, DocumentBuffer, ParserContext, ParserFlags},
+	parser::{
+		Parser,
+		error::ParserError,
+		finish_document,
+		html::{HtmlParser, document_from_converter},
+		html_to_text::{HtmlSourceMode, HtmlToText},
+		text::TextParser,
+	},
+	t,
+	util::text::remove_soft_hyphens,
+};
+
+/// Prefix of the pseudo-paths clipboard documents are opened under, so their positions and
+/// bookmarks can be stored like any other document's for as long as they are open.
+pub const CLIPBOARD_PATH_PREFIX: &str = "clipboard://";
+const TITLE_MAX_CHARS: usize = 80;
+
+#[must_use]
+pub fn is_clipboard_path(path: &str) -> bool {
+	path.starts_with(CLIPBOARD_PATH_PREFIX)
+}
+
+/// The pseudo-path for text pasted at `unix_seconds`, e.g. `clipboard://2024-05-03-14-22` (UTC).
+#[must_use]
+pub fn clipboard_path(unix_seconds: i64) -> String {
+	let (year, month, day) = utc_date(unix_seconds);
+	let seconds_of_day = unix_seconds.rem_euclid(86_400);
+	let hour = seconds_of_day / 3600;
+	let minute = seconds_of_day % 3600 / 60;
+	format!("{CLIPBOARD_PATH_PREFIX}{year:04}-{month:02}-{day:02}-{hour:02}-{minute:02}")
+}
+
+/// Flags of the parser clipboard contents go through: HTML when there is an HTML flavor, otherwise
+/// plain text.
+#[must_use]
+pub fn clipboard_parser_flags(has_html: bool) -> ParserFlags {
+	if has_html { HtmlParser.supported_flags() } else { TextParser.supported_flags() }
+}
+
+/// Builds a document from clipboard contents, preferring the HTML flavor so headings, lists and
+/// links survive. The title is the first non-blank line of the result.
+///
+/// # Errors
+///
+/// Returns [`ParserError::Other`] when the clipboard holds no readable text.
+pub fn parse_clipboard(context: &ParserContext, text: &str, html: Option<&str>) -> Result<Document, ParserError> {
+	let mut converter = HtmlToText::with_render_tables_inline(context.render_tables_inline);
+	let mut doc = match html {
+		Some(html)
+			if converter.convert(html, HtmlSourceMode::NativeHtml) && !converter.get_text().trim().is_empty() =>
+		{
+			document_from_converter(&converter)
+		}
+		_ => {
+			let mut doc = Document::new();
+			doc.set_buffer(DocumentBuffer::with_content(remove_soft_hyphens(text)));
+			doc
+		}
+	};
+	let Some(first_line) = doc.buffer.content.lines().map(str::trim).find(|line| !line.is_empty()) else {
+		// TRANSLATORS: Error shown when creating a document from the clipboard while it holds no text
+		return Err(ParserError::Other(t("The clipboard does not contain any text.")));
+	};
+	doc.title = first_line
+		.char_indices()
+		.nth(TITLE_MAX_CHARS)
+		.map_or_else(|| first_line.to_string(), |(idx, _)| format!("{}\u{2026}", first_line[..idx].trim_end()));
+	Ok(finish_document(doc, context))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::document::MarkerType;
+
+	#[test]
+	fn clipboard_paths_are_stamped_with_the_utc_minute() {
+		// 2024-05-03 14:22:59 UTC
+		let path = clipboard_path(1_714_746_179);
+		assert_eq!(path, "clipboard://2024-05-03-14-22");
+		assert!(is_clipboard_path(&path));
+		assert!(!is_clipboard_path("/home/reader/book.epub"));
+	}
+
+	#[test]
+	fn html_flavor_is_preferred_and_keeps_headings() {
+		let context = ParserContext::new(clipboard_path(0));
+		let html = "<h1>Article</h1><p>Body text.</p>";
+		let doc = parse_clipboard(&context, "Article\nBody text.", Some(html)).unwrap();
+		assert_eq!(doc.title, "Article");
+		assert!(doc.buffer.markers.iter().any(|marker| marker.mtype == MarkerType::Heading1));
+	}
+
+	#[test]
+	fn plain_text_is_titled_by_its_first_line() {
+		let context = ParserContext::new(clipboard_path(0));
+		let doc = parse_clipboard(&context, "\n  First line  \nSecond line", None).unwrap();
+		assert_eq!(doc.title, "First line");
+		assert!(doc.buffer.content.contains("Second line"));
+		assert!(parse_clipboard(&context, " \n ", None).is_err());
+	}
+}
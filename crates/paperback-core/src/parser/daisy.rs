@@ -7,7 +7,6 @@ use std::{
 
 use anyhow::{Context, Result};
 use roxmltree::{Document as XmlDocument, Node, NodeType, ParsingOptions};
-use zip::ZipArchive;
 
 use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, TocItem},
@@ -18,7 +17,7 @@ use crate::{
 		xml_to_text::XmlToText,
 	},
 	t,
-	util::zip::read_zip_entry_by_name_with_password,
+	util::zip::{OpenedZip, open_zip, read_zip_entry_by_name_with_password},
 };
 
 pub struct DaisyParser;
@@ -55,7 +54,7 @@ impl Parser for DaisyParser {
 				.unwrap_or(false);
 		if is_zip {
 			let file = File::open(path).context("Failed to open zip file")?;
-			let mut archive = ZipArchive::new(BufReader::new(file)).context("Failed to read zip archive")?;
+			let OpenedZip { mut archive, salvaged } = open_zip(file).context("Failed to read zip archive")?;
 			let opf_path = archive
 				.file_names()
 				.find(|n| Path::new(n).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("opf")))
@@ -127,6 +126,7 @@ impl Parser for DaisyParser {
 						buffer,
 						toc_items,
 						id_positions: converter.get_id_positions().clone(),
+						damaged: salvaged,
 						..Document::default()
 					});
 				}
@@ -170,6 +170,7 @@ impl Parser for DaisyParser {
 						buffer,
 						toc_items,
 						id_positions: converter.get_id_positions().clone(),
+						damaged: salvaged,
 						..Document::default()
 					});
 				}
@@ -1,7 +1,7 @@
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	fs::File,
-	io::{BufReader, Read, Seek},
+	io::{Read, Seek},
 	path::{Component, Path, PathBuf},
 };
 
@@ -10,19 +10,29 @@ use roxmltree::{Document as XmlDocument, Node, NodeType, ParsingOptions};
 use zip::ZipArchive;
 
 use crate::{
-	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, TocItem},
+	document::{
+		Document, DocumentBuffer, Landmark, LandmarkKind, Marker, MarkerType, MetadataField, ParserContext,
+		ParserFlags, TocItem,
+	},
 	parser::{
-		ConverterOutput, Parser, add_converter_markers_excluding_links,
+		ConverterOutput, Parser, add_converter_landmarks, add_converter_markers_excluding_links,
+		error::ParserError,
 		html_to_text::{HtmlSourceMode, HtmlToText},
-		is_external_url,
-		util::path::extract_title_from_path,
+		is_external_url, series_text,
+		util::{
+			page_breaks::{PageBreakRules, push_page_break},
+			path::extract_title_from_path,
+		},
 		xml_to_text::XmlToText,
 	},
 	t,
-	types::{FormatInfo, HeadingInfo, ImageInfo, LinkInfo, ListInfo, ListItemInfo, SeparatorInfo, TableInfo},
+	types::{
+		BlockInfo, FormatInfo, HeadingInfo, ImageInfo, LandmarkInfo, LinkInfo, ListInfo, ListItemInfo, PageBreakInfo,
+		SeparatorInfo, TableInfo,
+	},
 	util::{
-		text::{collapse_whitespace, trim_string, url_decode},
-		zip::read_zip_entry_by_name,
+		text::{collapse_whitespace, display_len, trim_string, url_decode},
+		zip::{OpenedZip, open_zip, read_zip_entry_by_name},
 	},
 };
 
@@ -34,11 +44,16 @@ struct SectionContent {
 	figures: Vec<ImageInfo>,
 	tables: Vec<TableInfo>,
 	separators: Vec<SeparatorInfo>,
+	block_quotes: Vec<BlockInfo>,
+	code_blocks: Vec<BlockInfo>,
+	notes: Vec<BlockInfo>,
 	lists: Vec<ListInfo>,
 	list_items: Vec<ListItemInfo>,
 	bolds: Vec<FormatInfo>,
 	italics: Vec<FormatInfo>,
 	underlines: Vec<FormatInfo>,
+	landmarks: Vec<LandmarkInfo>,
+	page_breaks: Vec<PageBreakInfo>,
 	id_positions: HashMap<String, usize>,
 }
 
@@ -61,6 +76,15 @@ impl ConverterOutput for SectionContent {
 	fn get_separators(&self) -> &[SeparatorInfo] {
 		&self.separators
 	}
+	fn get_block_quotes(&self) -> &[BlockInfo] {
+		&self.block_quotes
+	}
+	fn get_code_blocks(&self) -> &[BlockInfo] {
+		&self.code_blocks
+	}
+	fn get_notes(&self) -> &[BlockInfo] {
+		&self.notes
+	}
 	fn get_lists(&self) -> &[ListInfo] {
 		&self.lists
 	}
@@ -89,12 +113,31 @@ struct ManifestItem {
 	path: String,
 	media_type: String,
 	properties: Vec<String>,
+	fallback: Option<String>,
+}
+
+struct SpineItem {
+	idref: String,
+	linear: bool,
+}
+
+/// An EPUB 2 `<guide>` reference whose type names a landmark; `href` is relative to the OPF.
+struct GuideReference {
+	kind: LandmarkKind,
+	title: String,
+	href: String,
 }
 
 struct SpineConversionResult {
 	buffer: DocumentBuffer,
 	id_positions: HashMap<String, usize>,
 	sections: Vec<SectionMeta>,
+	/// Landmarks marked by `epub:type` or `role` attributes in the text, at buffer offsets.
+	landmarks: Vec<LandmarkInfo>,
+	/// Pages marked in the text by `pagebreak` spans and forced CSS breaks, at buffer offsets.
+	page_breaks: Vec<PageBreakInfo>,
+	/// Manifest ids of the converted items, in the order their sections appear in the buffer.
+	reading_order: Vec<String>,
 	conversion_errors: Vec<String>,
 }
 
@@ -121,8 +164,12 @@ impl Parser for EpubParser {
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
 		let file = File::open(&context.file_path)
 			.with_context(|| format!("Failed to open EPUB file '{}'", context.file_path))?;
-		let mut archive = ZipArchive::new(BufReader::new(file))
-			.with_context(|| format!("Failed to read EPUB as zip '{}'", context.file_path))?;
+		let OpenedZip { mut archive, salvaged } =
+			open_zip(file).with_context(|| format!("Failed to read EPUB as zip '{}'", context.file_path))?;
+		if is_drm_protected(&mut archive) {
+			// TRANSLATORS: Error shown when an EPUB's content is encrypted with DRM (e.g. Adobe ADEPT)
+			return Err(ParserError::Encrypted(t("This EPUB is protected by DRM and can't be opened.")).into());
+		}
 		let container_path = find_container_path(&mut archive)?;
 		let opf_content = read_zip_entry_by_name(&mut archive, &container_path)?;
 		let opf_dir = Path::new(&container_path).parent().unwrap_or_else(|| Path::new("")).to_path_buf();
@@ -136,7 +183,7 @@ impl Parser for EpubParser {
 			.find(|n| n.node_type() == NodeType::Element && n.tag_name().name() == "package")
 			// TRANSLATORS: Error shown when an EPUB's OPF document has no <package> element
 			.ok_or_else(|| anyhow::anyhow!(t("OPF package element missing")))?;
-		let (manifest, spine, nav_path, ncx_path, metadata) = parse_package(package_node, &opf_dir);
+		let (manifest, spine, nav_path, ncx_path, guide, metadata) = parse_package(package_node, &opf_dir);
 		let mut conversion = convert_spine_items(&mut archive, &manifest, &spine, context.render_tables_inline);
 		if conversion.sections.is_empty() {
 			let reason = if conversion.conversion_errors.is_empty() {
@@ -168,36 +215,64 @@ impl Parser for EpubParser {
 			&conversion.sections,
 			&conversion.id_positions,
 		);
-		for page in page_items {
-			conversion.buffer.add_marker(Marker::new(MarkerType::PageBreak, page.offset).with_text(page.name));
-		}
+		add_page_markers(&mut conversion.buffer, page_items, &conversion.page_breaks);
+		let landmarks = build_epub_landmarks(
+			&mut archive,
+			nav_path.as_deref(),
+			&guide,
+			&container_path,
+			&conversion.sections,
+			&conversion.id_positions,
+		);
 		let manifest_items: HashMap<String, String> =
 			manifest.values().map(|item| (item.id.clone(), item.path.clone())).collect();
 		let mut document = Document::new().with_title(title).with_author(author);
+		for (field, value) in &metadata.fields {
+			document.add_metadata(*field, value);
+		}
 		document.set_buffer(conversion.buffer);
 		document.id_positions = conversion.id_positions;
-		document.spine_items = spine;
+		document.spine_items = conversion.reading_order;
 		document.manifest_items = manifest_items;
 		document.toc_items = toc_items;
+		document.damaged = salvaged;
+		if landmarks.is_empty() {
+			add_converter_landmarks(&mut document, &conversion.landmarks, 0);
+		} else {
+			for landmark in landmarks {
+				document.add_landmark(landmark.kind, &landmark.title, landmark.offset);
+			}
+		}
 		Ok(document)
 	}
 }
 
+/// Converts the spine into one buffer. Linear items come first, in spine order; items marked
+/// `linear="no"` (covers, inline tables of contents and the like) follow under a separator so links
+/// into them still resolve without them interrupting the book. Items that aren't XHTML are replaced
+/// by their manifest fallback, and images and fonts without one are skipped.
 fn convert_spine_items<R: Read + Seek>(
 	archive: &mut ZipArchive<R>,
 	manifest: &HashMap<String, ManifestItem>,
-	spine: &[String],
+	spine: &[SpineItem],
 	render_tables_inline: bool,
 ) -> SpineConversionResult {
 	let mut buffer = DocumentBuffer::new();
 	let mut id_positions = HashMap::new();
 	let mut sections = Vec::new();
+	let mut landmarks = Vec::new();
+	let mut page_breaks = Vec::new();
+	let mut reading_order = Vec::new();
 	let mut conversion_errors = Vec::new();
-	for (idx, idref) in spine.iter().enumerate() {
-		let Some(item) = manifest.get(idref) else {
-			conversion_errors.push(format!("missing manifest item for {idref}"));
+	let mut auxiliary_started = false;
+	let page_break_rules = load_page_break_rules(archive, manifest);
+	let ordered = spine.iter().filter(|item| item.linear).chain(spine.iter().filter(|item| !item.linear));
+	for spine_item in ordered {
+		if !manifest.contains_key(&spine_item.idref) {
+			conversion_errors.push(format!("missing manifest item for {}", spine_item.idref));
 			continue;
-		};
+		}
+		let Some(item) = resolve_spine_item(manifest, &spine_item.idref) else { continue };
 		let section_data = match read_zip_entry_by_name(archive, &item.path) {
 			Ok(v) => v,
 			Err(err) => {
@@ -205,15 +280,21 @@ fn convert_spine_items<R: Read + Seek>(
 				continue;
 			}
 		};
-		let section_start = buffer.current_position();
-		let section_label = format!("Section {}", idx + 1);
-		buffer.add_marker(
-			Marker::new(MarkerType::SectionBreak, section_start)
-				.with_text(section_label)
-				.with_reference(item.path.clone()),
-		);
-		match convert_section(&section_data, render_tables_inline) {
+		match convert_section(&section_data, render_tables_inline, &page_break_rules) {
 			Ok(section) => {
+				if !spine_item.linear && !auxiliary_started {
+					auxiliary_started = true;
+					if !buffer.content.is_empty() {
+						append_separator(&mut buffer);
+					}
+				}
+				let section_start = buffer.current_position();
+				let section_label = format!("Section {}", sections.len() + 1);
+				buffer.add_marker(
+					Marker::new(MarkerType::SectionBreak, section_start)
+						.with_text(section_label)
+						.with_reference(item.path.clone()),
+				);
 				for (id, relative) in &section.id_positions {
 					let absolute = section_start + relative;
 					// Keep the first occurrence for bare ids to avoid later sections overwriting earlier ones.
@@ -221,6 +302,15 @@ fn convert_spine_items<R: Read + Seek>(
 					id_positions.insert(format!("{}#{id}", item.path), absolute);
 				}
 				add_converter_markers_excluding_links(&mut buffer, &section, section_start);
+				landmarks.extend(
+					section
+						.landmarks
+						.iter()
+						.map(|landmark| LandmarkInfo { offset: section_start + landmark.offset, kind: landmark.kind }),
+				);
+				for page in &section.page_breaks {
+					push_page_break(&mut page_breaks, section_start + page.offset, page.text.clone());
+				}
 				for link in &section.links {
 					let resolved = resolve_href(&item.path, &link.reference);
 					buffer.add_marker(
@@ -237,13 +327,86 @@ fn convert_spine_items<R: Read + Seek>(
 				}
 				let section_end = buffer.current_position();
 				sections.push(SectionMeta { path: item.path.clone(), start: section_start, end: section_end });
+				reading_order.push(item.id.clone());
 			}
 			Err(err) => {
 				conversion_errors.push(format!("{} ({err})", item.path));
 			}
 		}
 	}
-	SpineConversionResult { buffer, id_positions, sections, conversion_errors }
+	SpineConversionResult { buffer, id_positions, sections, landmarks, page_breaks, reading_order, conversion_errors }
+}
+
+/// The page-break rules of every stylesheet in the manifest. Books rarely style a class one way in
+/// one file and another way in the next, so one set of rules serves the whole spine.
+fn load_page_break_rules<R: Read + Seek>(
+	archive: &mut ZipArchive<R>,
+	manifest: &HashMap<String, ManifestItem>,
+) -> PageBreakRules {
+	let mut rules = PageBreakRules::default();
+	for item in manifest.values().filter(|item| item.media_type == "text/css") {
+		if let Ok(css) = read_zip_entry_by_name(archive, &item.path) {
+			rules.extend_from_css(&css);
+		}
+	}
+	rules
+}
+
+/// Adds the book's pages. A page list in the navigation document gives the printed pages, so then
+/// the text only adds labelled `pagebreak` spans the list leaves out; forced CSS breaks, which
+/// don't stand for printed pages, would only throw the numbering off. Without a page list, the
+/// spans and forced breaks are the pages.
+fn add_page_markers(buffer: &mut DocumentBuffer, page_list: Vec<TocItem>, page_breaks: &[PageBreakInfo]) {
+	let listed: HashSet<usize> = page_list.iter().map(|page| page.offset).collect();
+	let has_page_list = !listed.is_empty();
+	for page in page_list {
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, page.offset).with_text(page.name));
+	}
+	for page in page_breaks {
+		if listed.contains(&page.offset) || (has_page_list && page.text.is_empty()) {
+			continue;
+		}
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, page.offset).with_text(page.text.clone()));
+	}
+}
+
+fn append_separator(buffer: &mut DocumentBuffer) {
+	let line = "----------------------------------------";
+	buffer.add_marker(
+		Marker::new(MarkerType::Separator, buffer.current_position())
+			.with_text("Separator".to_string())
+			.with_length(display_len(line)),
+	);
+	buffer.append(line);
+	buffer.append("\n");
+}
+
+fn is_content_document(media_type: &str) -> bool {
+	matches!(media_type, "application/xhtml+xml" | "text/html")
+}
+
+fn is_image_or_font(media_type: &str) -> bool {
+	media_type.starts_with("image/")
+		|| media_type.starts_with("font/")
+		|| media_type.starts_with("application/font-")
+		|| media_type.starts_with("application/x-font-")
+		|| media_type == "application/vnd.ms-opentype"
+}
+
+/// Follows the manifest fallback chain from `idref` until it reaches an XHTML item. When the chain
+/// runs out, the last item is still tried unless it is an image or a font.
+fn resolve_spine_item<'a>(manifest: &'a HashMap<String, ManifestItem>, idref: &str) -> Option<&'a ManifestItem> {
+	let mut item = manifest.get(idref)?;
+	let mut visited = HashSet::from([item.id.as_str()]);
+	while !is_content_document(&item.media_type) {
+		let Some(next) =
+			item.fallback.as_deref().filter(|id| visited.insert(*id)).and_then(|fallback| manifest.get(fallback))
+		else {
+			break;
+		};
+		item = next;
+	}
+	(!is_image_or_font(&item.media_type)).then_some(item)
 }
 
 fn build_epub_toc<R: Read + Seek>(
@@ -264,6 +427,26 @@ fn build_epub_toc<R: Read + Seek>(
 	}
 }
 
+/// Font obfuscation algorithms: only embedded fonts are scrambled, the text is still readable.
+const FONT_OBFUSCATION_ALGORITHMS: [&str; 2] = ["http://www.idpf.org/2008/embedding", "http://ns.adobe.com/pdf/enc#RC"];
+
+/// Whether the book's content documents are encrypted (Adobe `rights.xml`, or an `encryption.xml`
+/// entry using anything other than font obfuscation).
+fn is_drm_protected<R: Read + Seek>(archive: &mut ZipArchive<R>) -> bool {
+	if archive.by_name("META-INF/rights.xml").is_ok() {
+		return true;
+	}
+	let Ok(encryption_xml) = read_zip_entry_by_name(archive, "META-INF/encryption.xml") else {
+		return false;
+	};
+	let Ok(doc) = XmlDocument::parse(&encryption_xml) else {
+		return false;
+	};
+	doc.descendants().filter(|node| node.is_element() && node.tag_name().name() == "EncryptionMethod").any(|method| {
+		method.attribute("Algorithm").is_some_and(|algorithm| !FONT_OBFUSCATION_ALGORITHMS.contains(&algorithm))
+	})
+}
+
 fn find_container_path<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<String> {
 	let container_xml = read_zip_entry_by_name(archive, "META-INF/container.xml")
 		.context("Failed to read META-INF/container.xml in EPUB")?;
@@ -287,17 +470,27 @@ fn find_container_path<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<St
 struct PackageMetadata {
 	title: Option<String>,
 	author: Option<String>,
+	fields: Vec<(MetadataField, String)>,
 }
 
-type PackageParts = (HashMap<String, ManifestItem>, Vec<String>, Option<String>, Option<String>, PackageMetadata);
+type PackageParts = (
+	HashMap<String, ManifestItem>,
+	Vec<SpineItem>,
+	Option<String>,
+	Option<String>,
+	Vec<GuideReference>,
+	PackageMetadata,
+);
 
 fn parse_package(package: Node<'_, '_>, opf_dir: &Path) -> PackageParts {
 	let mut manifest = HashMap::new();
 	let mut spine = Vec::new();
 	let mut nav_path = None;
 	let mut ncx_path = None;
+	let mut guide = Vec::new();
 	let mut title = None;
 	let mut author = None;
+	let mut fields = Vec::new();
 	for child in package.children() {
 		if child.node_type() != NodeType::Element {
 			continue;
@@ -312,6 +505,7 @@ fn parse_package(package: Node<'_, '_>, opf_dir: &Path) -> PackageParts {
 					.descendants()
 					.find(|n| n.node_type() == NodeType::Element && n.tag_name().name().eq_ignore_ascii_case("creator"))
 					.and_then(|n| n.text().map(str::to_string));
+				fields = read_metadata_fields(child);
 			}
 			"manifest" => {
 				for item in
@@ -320,12 +514,13 @@ fn parse_package(package: Node<'_, '_>, opf_dir: &Path) -> PackageParts {
 					let Some(id) = item.attribute("id") else { continue };
 					let Some(href) = item.attribute("href") else { continue };
 					let media_type = item.attribute("media-type").unwrap_or("").to_string();
+					let fallback = item.attribute("fallback").map(str::to_string);
 					let properties = item
 						.attribute("properties")
 						.map_or_else(Vec::new, |v| v.split_whitespace().map(ToString::to_string).collect());
 					let resolved = normalize_path(&opf_dir.join(url_decode(href)));
 					let manifest_item =
-						ManifestItem { id: id.to_string(), path: resolved.clone(), media_type, properties };
+						ManifestItem { id: id.to_string(), path: resolved.clone(), media_type, properties, fallback };
 					if manifest_item.properties.iter().any(|p| p == "nav") {
 						nav_path = Some(resolved.clone());
 					}
@@ -345,18 +540,99 @@ fn parse_package(package: Node<'_, '_>, opf_dir: &Path) -> PackageParts {
 					child.children().filter(|n| n.node_type() == NodeType::Element && n.tag_name().name() == "itemref")
 				{
 					if let Some(idref) = itemref.attribute("idref") {
-						spine.push(idref.to_string());
+						let linear = itemref.attribute("linear").is_none_or(|v| !v.trim().eq_ignore_ascii_case("no"));
+						spine.push(SpineItem { idref: idref.to_string(), linear });
 					}
 				}
 			}
+			"guide" => {
+				for reference in child
+					.children()
+					.filter(|n| n.node_type() == NodeType::Element && n.tag_name().name() == "reference")
+				{
+					let Some(href) = reference.attribute("href") else { continue };
+					let Some(kind) = reference.attribute("type").and_then(LandmarkKind::from_semantic_type) else {
+						continue;
+					};
+					let title = reference.attribute("title").unwrap_or("").to_string();
+					guide.push(GuideReference { kind, title, href: href.to_string() });
+				}
+			}
 			_ => {}
 		}
 	}
-	(manifest, spine, nav_path, ncx_path, PackageMetadata { title, author })
+	(manifest, spine, nav_path, ncx_path, guide, PackageMetadata { title, author, fields })
 }
 
-fn convert_section(content: &str, render_tables_inline: bool) -> Result<SectionContent> {
-	let mut xml_converter = XmlToText::with_render_tables_inline(render_tables_inline);
+/// Reads the Dublin Core elements Document Info shows, plus the series from calibre's
+/// `calibre:series` meta or an EPUB 3 `belongs-to-collection` with its `group-position`.
+fn read_metadata_fields(metadata: Node<'_, '_>) -> Vec<(MetadataField, String)> {
+	let mut fields = Vec::new();
+	let mut series = None;
+	let mut series_index = String::new();
+	let mut collection: Option<(Option<&str>, String)> = None;
+	let mut positions: HashMap<&str, String> = HashMap::new();
+	for node in metadata.children().filter(|n| n.node_type() == NodeType::Element) {
+		let text = node.text().unwrap_or("").trim().to_string();
+		let field = match node.tag_name().name().to_ascii_lowercase().as_str() {
+			"language" => MetadataField::Language,
+			"publisher" => MetadataField::Publisher,
+			"subject" => MetadataField::Subject,
+			"date" => {
+				// Dates are often full timestamps (2011-03-04T00:00:00+00:00); the day is enough.
+				let day = text.split('T').next().unwrap_or_default().to_string();
+				fields.push((MetadataField::Date, day));
+				continue;
+			}
+			"identifier" => {
+				let scheme = node.attributes().find(|a| a.name() == "scheme").map(|a| a.value().trim());
+				let value = match scheme {
+					Some(scheme) if !scheme.is_empty() && !text.to_lowercase().contains(&scheme.to_lowercase()) => {
+						format!("{scheme}: {text}")
+					}
+					_ => text,
+				};
+				fields.push((MetadataField::Identifier, value));
+				continue;
+			}
+			"meta" => {
+				match (node.attribute("name"), node.attribute("property")) {
+					(Some("calibre:series"), _) => series = node.attribute("content").map(str::to_string),
+					(Some("calibre:series_index"), _) => {
+						series_index = node.attribute("content").unwrap_or("").to_string();
+					}
+					(_, Some("belongs-to-collection")) if collection.is_none() => {
+						collection = Some((node.attribute("id"), text));
+					}
+					(_, Some("group-position")) => {
+						if let Some(target) = node.attribute("refines") {
+							positions.insert(target.trim_start_matches('#'), text);
+						}
+					}
+					_ => {}
+				}
+				continue;
+			}
+			_ => continue,
+		};
+		fields.push((field, text));
+	}
+	if let Some(name) = series {
+		fields.push((MetadataField::Series, series_text(&name, &series_index)));
+	} else if let Some((id, name)) = collection {
+		let position = id.and_then(|id| positions.get(id)).map_or("", String::as_str);
+		fields.push((MetadataField::Series, series_text(&name, position)));
+	}
+	fields
+}
+
+fn convert_section(
+	content: &str,
+	render_tables_inline: bool,
+	page_break_rules: &PageBreakRules,
+) -> Result<SectionContent> {
+	let mut xml_converter =
+		XmlToText::with_render_tables_inline(render_tables_inline).with_page_break_rules(page_break_rules.clone());
 	if xml_converter.convert(content) {
 		return Ok(SectionContent {
 			text: xml_converter.get_text(),
@@ -366,15 +642,21 @@ fn convert_section(content: &str, render_tables_inline: bool) -> Result<SectionC
 			figures: xml_converter.get_figures().to_vec(),
 			tables: xml_converter.get_tables().to_vec(),
 			separators: xml_converter.get_separators().to_vec(),
+			block_quotes: xml_converter.get_block_quotes().to_vec(),
+			code_blocks: xml_converter.get_code_blocks().to_vec(),
+			notes: xml_converter.get_notes().to_vec(),
 			lists: xml_converter.get_lists().to_vec(),
 			list_items: xml_converter.get_list_items().to_vec(),
 			bolds: xml_converter.get_bolds().to_vec(),
 			italics: xml_converter.get_italics().to_vec(),
 			underlines: xml_converter.get_underlines().to_vec(),
+			landmarks: xml_converter.get_landmarks().to_vec(),
+			page_breaks: xml_converter.get_page_breaks().to_vec(),
 			id_positions: xml_converter.get_id_positions().clone(),
 		});
 	}
-	let mut html_converter = HtmlToText::with_render_tables_inline(render_tables_inline);
+	let mut html_converter =
+		HtmlToText::with_render_tables_inline(render_tables_inline).with_page_break_rules(page_break_rules.clone());
 	if html_converter.convert(content, HtmlSourceMode::NativeHtml) {
 		return Ok(SectionContent {
 			text: html_converter.get_text(),
@@ -384,11 +666,16 @@ fn convert_section(content: &str, render_tables_inline: bool) -> Result<SectionC
 			figures: html_converter.get_figures().to_vec(),
 			tables: html_converter.get_tables().to_vec(),
 			separators: html_converter.get_separators().to_vec(),
+			block_quotes: html_converter.get_block_quotes().to_vec(),
+			code_blocks: html_converter.get_code_blocks().to_vec(),
+			notes: html_converter.get_notes().to_vec(),
 			lists: html_converter.get_lists().to_vec(),
 			list_items: html_converter.get_list_items().to_vec(),
 			bolds: html_converter.get_bolds().to_vec(),
 			italics: html_converter.get_italics().to_vec(),
 			underlines: html_converter.get_underlines().to_vec(),
+			landmarks: html_converter.get_landmarks().to_vec(),
+			page_breaks: html_converter.get_page_breaks().to_vec(),
 			id_positions: html_converter.get_id_positions().clone(),
 		});
 	}
@@ -457,23 +744,7 @@ fn build_toc_from_nav_document<R: Read + Seek>(
 	let nav_doc =
 		XmlDocument::parse_with_options(&nav_content, ParsingOptions { allow_dtd: true, ..ParsingOptions::default() })
 			.ok()?;
-	let nav_node = nav_doc.descendants().find(|node| {
-		if node.node_type() != NodeType::Element || node.tag_name().name() != "nav" {
-			return false;
-		}
-		node.attributes().any(|attr| {
-			let attr_name = attr.name();
-			let matches_name = attr_name.eq_ignore_ascii_case("epub:type")
-				|| attr_name.eq_ignore_ascii_case("type")
-				|| attr_name.eq_ignore_ascii_case("role");
-			matches_name
-				&& attr
-					.value()
-					.split_ascii_whitespace()
-					.any(|part| part.eq_ignore_ascii_case("toc") || part.eq_ignore_ascii_case("doc-toc"))
-		})
-	});
-	let nav_node = nav_node?;
+	let nav_node = find_typed_nav(&nav_doc, &["toc", "doc-toc"])?;
 	let mut items = Vec::new();
 	for child in nav_node.children() {
 		if child.node_type() != NodeType::Element {
@@ -495,6 +766,29 @@ fn build_toc_from_nav_document<R: Read + Seek>(
 	if items.is_empty() { None } else { Some(items) }
 }
 
+/// The values of the element's `epub:type` and `role` attributes.
+fn semantic_types<'a>(node: Node<'a, '_>) -> impl Iterator<Item = &'a str> {
+	node.attributes()
+		.filter(|attr| {
+			let attr_name = attr.name();
+			attr_name.eq_ignore_ascii_case("epub:type")
+				|| attr_name.eq_ignore_ascii_case("type")
+				|| attr_name.eq_ignore_ascii_case("role")
+		})
+		.map(|attr| attr.value())
+}
+
+/// The first `<nav>` whose `epub:type` or `role` includes one of `types`.
+fn find_typed_nav<'a, 'input>(nav_doc: &'a XmlDocument<'input>, types: &[&str]) -> Option<Node<'a, 'input>> {
+	nav_doc.descendants().find(|node| {
+		node.node_type() == NodeType::Element
+			&& node.tag_name().name() == "nav"
+			&& semantic_types(*node).any(|value| {
+				value.split_ascii_whitespace().any(|part| types.iter().any(|wanted| part.eq_ignore_ascii_case(wanted)))
+			})
+	})
+}
+
 fn parse_nav_list(
 	list_node: Node<'_, '_>,
 	current_path: &str,
@@ -686,23 +980,7 @@ fn build_pages_from_nav_document<R: Read + Seek>(
 	let nav_doc =
 		XmlDocument::parse_with_options(&nav_content, ParsingOptions { allow_dtd: true, ..ParsingOptions::default() })
 			.ok()?;
-	let nav_node = nav_doc.descendants().find(|node| {
-		if node.node_type() != NodeType::Element || node.tag_name().name() != "nav" {
-			return false;
-		}
-		node.attributes().any(|attr| {
-			let attr_name = attr.name();
-			let matches_name = attr_name.eq_ignore_ascii_case("epub:type")
-				|| attr_name.eq_ignore_ascii_case("type")
-				|| attr_name.eq_ignore_ascii_case("role");
-			matches_name
-				&& attr
-					.value()
-					.split_ascii_whitespace()
-					.any(|part| part.eq_ignore_ascii_case("page-list") || part.eq_ignore_ascii_case("doc-pagelist"))
-		})
-	});
-	let nav_node = nav_node?;
+	let nav_node = find_typed_nav(&nav_doc, &["page-list", "doc-pagelist"])?;
 	let mut items = Vec::new();
 	for child in nav_node.children() {
 		if child.node_type() != NodeType::Element {
@@ -747,3 +1025,255 @@ fn build_pages_from_ncx<R: Read + Seek>(
 	}
 	if items.is_empty() { None } else { Some(items) }
 }
+
+/// Landmarks from the EPUB 3 `landmarks` nav, falling back to the EPUB 2 guide, each resolved to
+/// an offset like a TOC entry. Entries pointing outside the converted spine are dropped.
+fn build_epub_landmarks<R: Read + Seek>(
+	archive: &mut ZipArchive<R>,
+	nav_path: Option<&str>,
+	guide: &[GuideReference],
+	opf_path: &str,
+	sections: &[SectionMeta],
+	id_positions: &HashMap<String, usize>,
+) -> Vec<Landmark> {
+	let from_nav = nav_path
+		.and_then(|nav_path| build_landmarks_from_nav_document(archive, nav_path, sections, id_positions))
+		.unwrap_or_default();
+	if !from_nav.is_empty() {
+		return from_nav;
+	}
+	guide
+		.iter()
+		.filter_map(|entry| {
+			let reference = resolve_href(opf_path, &entry.href);
+			is_in_spine(&reference, sections).then(|| Landmark {
+				kind: entry.kind,
+				title: entry.title.clone(),
+				offset: compute_nav_offset(&reference, sections, id_positions),
+			})
+		})
+		.collect()
+}
+
+fn build_landmarks_from_nav_document<R: Read + Seek>(
+	archive: &mut ZipArchive<R>,
+	nav_path: &str,
+	sections: &[SectionMeta],
+	id_positions: &HashMap<String, usize>,
+) -> Option<Vec<Landmark>> {
+	let nav_content = read_zip_entry_by_name(archive, nav_path).ok()?;
+	let nav_doc =
+		XmlDocument::parse_with_options(&nav_content, ParsingOptions { allow_dtd: true, ..ParsingOptions::default() })
+			.ok()?;
+	let nav_node = find_typed_nav(&nav_doc, &["landmarks"])?;
+	let landmarks = nav_node
+		.descendants()
+		.filter(|node| node.node_type() == NodeType::Element && node.tag_name().name() == "a")
+		.filter_map(|link| {
+			let kind = semantic_types(link).find_map(LandmarkKind::from_semantic_type)?;
+			let reference = resolve_href(nav_path, link.attribute("href")?);
+			is_in_spine(&reference, sections).then(|| Landmark {
+				kind,
+				title: extract_link_text(link),
+				offset: compute_nav_offset(&reference, sections, id_positions),
+			})
+		})
+		.collect();
+	Some(landmarks)
+}
+
+fn is_in_spine(reference: &str, sections: &[SectionMeta]) -> bool {
+	let (path_part, _) = split_href(reference);
+	sections.iter().any(|section| section.path == path_part)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::zip::testing::TempZip;
+
+	const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+<rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+
+	const PACKAGE: &str = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Quirks</dc:title></metadata>
+<manifest>
+<item id="toc" href="toc.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+<item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+<item id="ch2-dtb" href="ch2.dtb" media-type="application/x-dtbook+xml" fallback="ch2"/>
+<item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+<item id="plate" href="plate.png" media-type="image/png"/>
+</manifest>
+<spine>
+<itemref idref="toc" linear="no"/>
+<itemref idref="ch1"/>
+<itemref idref="plate"/>
+<itemref idref="ch2-dtb"/>
+</spine>
+</package>"#;
+
+	fn xhtml(body: &str) -> String {
+		format!(r#"<?xml version="1.0"?><html xmlns="http://www.w3.org/1999/xhtml"><body>{body}</body></html>"#)
+	}
+
+	#[test]
+	fn package_metadata_reads_dublin_core_and_series() {
+		let opf = r#"<package xmlns="http://www.idpf.org/2007/opf" xmlns:opf="http://www.idpf.org/2007/opf">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:title>Equal Rites</dc:title>
+<dc:language>en-GB</dc:language>
+<dc:date opf:event="publication">1987-01-15T00:00:00+00:00</dc:date>
+<dc:publisher>Gollancz</dc:publisher>
+<dc:identifier opf:scheme="ISBN">9780575039506</dc:identifier>
+<dc:identifier>urn:uuid:0c1d</dc:identifier>
+<meta name="calibre:series" content="Discworld"/>
+<meta name="calibre:series_index" content="3.0"/>
+</metadata>
+</package>"#;
+		let xml = XmlDocument::parse(opf).unwrap();
+		let (.., metadata) = parse_package(xml.root_element(), Path::new(""));
+		assert_eq!(metadata.title.as_deref(), Some("Equal Rites"));
+		assert_eq!(
+			metadata.fields,
+			vec![
+				(MetadataField::Language, "en-GB".to_string()),
+				(MetadataField::Date, "1987-01-15".to_string()),
+				(MetadataField::Publisher, "Gollancz".to_string()),
+				(MetadataField::Identifier, "ISBN: 9780575039506".to_string()),
+				(MetadataField::Identifier, "urn:uuid:0c1d".to_string()),
+				(MetadataField::Series, "Discworld, book 3".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn epub3_collections_give_the_series() {
+		let opf = r##"<package xmlns="http://www.idpf.org/2007/opf"><metadata>
+<meta property="belongs-to-collection" id="c1">The Expanse</meta>
+<meta refines="#c1" property="group-position">2</meta>
+</metadata></package>"##;
+		let xml = XmlDocument::parse(opf).unwrap();
+		let (.., metadata) = parse_package(xml.root_element(), Path::new(""));
+		assert_eq!(metadata.fields, vec![(MetadataField::Series, "The Expanse, book 2".to_string())]);
+	}
+
+	#[test]
+	fn non_linear_items_follow_the_book_and_fallbacks_replace_foreign_items() {
+		let nav = r#"<nav xmlns:epub="http://www.idpf.org/2007/ops" epub:type="toc" id="contents"><ol><li><a href="ch1.xhtml">Chapter One</a></li><li><a href="ch2.xhtml">Chapter Two</a></li></ol></nav>"#;
+		let epub = TempZip::new(
+			"book.epub",
+			&[
+				("mimetype", "application/epub+zip".to_string()),
+				("META-INF/container.xml", CONTAINER.to_string()),
+				("OEBPS/content.opf", PACKAGE.to_string()),
+				("OEBPS/toc.xhtml", xhtml(nav)),
+				("OEBPS/ch1.xhtml", xhtml("<p>The first chapter begins.</p>")),
+				("OEBPS/ch2.dtb", "<dtbook><book>Not XHTML</book></dtbook>".to_string()),
+				("OEBPS/ch2.xhtml", xhtml("<p>The second chapter follows.</p>")),
+				("OEBPS/plate.png", "not really a png".to_string()),
+			],
+		);
+		let doc = EpubParser.parse(&ParserContext::new(epub.path())).unwrap();
+		let content = &doc.buffer.content;
+		let first = content.find("The first chapter begins.").unwrap();
+		let second = content.find("The second chapter follows.").unwrap();
+		let separator = content.find("----------").unwrap();
+		let contents = content.find("Chapter One").unwrap();
+		assert!(first < second && second < separator && separator < contents);
+		assert!(!content.contains("Not XHTML"));
+		assert!(!content.contains("not really a png"));
+		assert_eq!(doc.spine_items, vec!["ch1".to_string(), "ch2".to_string(), "toc".to_string()]);
+		let section_starts: Vec<usize> = doc
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::SectionBreak)
+			.map(|marker| marker.position)
+			.collect();
+		assert_eq!(section_starts.len(), 3);
+		assert!(doc.id_positions.get("OEBPS/toc.xhtml#contents").is_some_and(|&pos| pos >= section_starts[2]));
+	}
+
+	#[test]
+	fn without_a_page_list_pages_come_from_stylesheets_and_pagebreak_spans() {
+		let package = PACKAGE.replace(
+			r#"<item id="plate""#,
+			r#"<item id="css" href="style.css" media-type="text/css"/><item id="plate""#,
+		);
+		let nav = r#"<nav xmlns:epub="http://www.idpf.org/2007/ops" epub:type="toc"><ol><li><a href="ch1.xhtml">One</a></li></ol></nav>"#;
+		let chapter_one = r#"<h1 class="chapter">Chapter One</h1><p xmlns:epub="http://www.idpf.org/2007/ops">Middle<span epub:type="pagebreak" title="ii"/> text.</p>"#;
+		let epub = TempZip::new(
+			"book.epub",
+			&[
+				("mimetype", "application/epub+zip".to_string()),
+				("META-INF/container.xml", CONTAINER.to_string()),
+				("OEBPS/content.opf", package),
+				("OEBPS/toc.xhtml", xhtml(nav)),
+				("OEBPS/ch1.xhtml", xhtml(chapter_one)),
+				("OEBPS/ch2.dtb", "<dtbook/>".to_string()),
+				("OEBPS/ch2.xhtml", xhtml(r#"<h1 class="chapter">Chapter Two</h1><p>Closing.</p>"#)),
+				("OEBPS/style.css", "/* print */ h1.chapter { page-break-before: always; }".to_string()),
+			],
+		);
+		let doc = EpubParser.parse(&ParserContext::new(epub.path())).unwrap();
+		let content = &doc.buffer.content;
+		let pages: Vec<(usize, &str)> = doc
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::PageBreak)
+			.map(|marker| (marker.position, marker.text.as_str()))
+			.collect();
+		assert_eq!(
+			pages,
+			[
+				(content.find("Chapter One").unwrap(), ""),
+				(content.find(" text.").unwrap(), "ii"),
+				(content.find("Chapter Two").unwrap(), "")
+			]
+		);
+	}
+
+	#[test]
+	fn landmarks_come_from_the_nav_and_fall_back_to_epub_type_attributes() {
+		let nav = r#"<nav xmlns:epub="http://www.idpf.org/2007/ops" epub:type="toc"><ol><li><a href="ch1.xhtml">Chapter One</a></li></ol></nav>
+<nav xmlns:epub="http://www.idpf.org/2007/ops" epub:type="landmarks"><ol>
+<li><a epub:type="bodymatter" href="ch1.xhtml">Start</a></li>
+<li><a epub:type="bibliography" href="ch2.xhtml#works">Works Cited</a></li>
+<li><a epub:type="index" href="missing.xhtml">Index</a></li>
+</ol></nav>"#;
+		let chapter_two = r#"<p>Intro.</p><section xmlns:epub="http://www.idpf.org/2007/ops" id="works" epub:type="bibliography"><p>Pratchett, T.</p></section>"#;
+		let entries = |nav: String| {
+			vec![
+				("mimetype", "application/epub+zip".to_string()),
+				("META-INF/container.xml", CONTAINER.to_string()),
+				("OEBPS/content.opf", PACKAGE.to_string()),
+				("OEBPS/toc.xhtml", nav),
+				("OEBPS/ch1.xhtml", xhtml("<p>The first chapter begins.</p>")),
+				("OEBPS/ch2.dtb", "<dtbook/>".to_string()),
+				("OEBPS/ch2.xhtml", xhtml(chapter_two)),
+			]
+		};
+		let epub = TempZip::new("book.epub", &entries(xhtml(nav)));
+		let doc = EpubParser.parse(&ParserContext::new(epub.path())).unwrap();
+		let works = doc.id_positions["OEBPS/ch2.xhtml#works"];
+		let kinds: Vec<(LandmarkKind, &str)> =
+			doc.landmarks.iter().map(|landmark| (landmark.kind, landmark.title.as_str())).collect();
+		assert_eq!(kinds, vec![(LandmarkKind::BodyMatter, "Start"), (LandmarkKind::Bibliography, "Works Cited")]);
+		assert_eq!(doc.landmarks[0].offset, 0);
+		assert_eq!(doc.landmarks[1].offset, works);
+		let epub = TempZip::new(
+			"book.epub",
+			&entries(xhtml(
+				r#"<nav xmlns:epub="http://www.idpf.org/2007/ops" epub:type="toc"><ol><li><a href="ch1.xhtml">One</a></li></ol></nav>"#,
+			)),
+		);
+		let doc = EpubParser.parse(&ParserContext::new(epub.path())).unwrap();
+		let bibliography = doc.landmarks.iter().find(|landmark| landmark.kind == LandmarkKind::Bibliography).unwrap();
+		assert_eq!(bibliography.offset, doc.id_positions["OEBPS/ch2.xhtml#works"]);
+		assert!(bibliography.title.is_empty());
+	}
+}
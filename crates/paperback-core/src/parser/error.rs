@@ -0,0 +1,174 @@
+use std::io;
+
+use zip::result::ZipError;
+
+use crate::parser::PASSWORD_REQUIRED_ERROR_PREFIX;
+
+/// Category of a [`ParserError`], exposed over FFI so front ends can pick a message without
+/// inspecting error text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParserErrorCode {
+	NoExtension,
+	UnsupportedFormat,
+	PasswordRequired,
+	Encrypted,
+	Io,
+	EmptyFile,
+	CorruptArchive,
+	Xml,
+	UnsupportedVariant,
+	ArchiveEntryRequired,
+	Other,
+}
+
+impl ParserErrorCode {
+	#[must_use]
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::NoExtension => "no_extension",
+			Self::UnsupportedFormat => "unsupported_format",
+			Self::PasswordRequired => "password_required",
+			Self::Encrypted => "encrypted",
+			Self::Io => "io",
+			Self::EmptyFile => "empty_file",
+			Self::CorruptArchive => "corrupt_archive",
+			Self::Xml => "xml",
+			Self::UnsupportedVariant => "unsupported_variant",
+			Self::ArchiveEntryRequired => "archive_entry_required",
+			Self::Other => "other",
+		}
+	}
+}
+
+/// Why a document could not be opened.
+///
+/// Parsers keep returning `anyhow` errors internally; [`parse_document`](super::parse_document)
+/// classifies the final failure with [`ParserError::classify`]. A parser that knows exactly what
+/// went wrong can return a `ParserError` itself (e.g. `bail!(ParserError::Encrypted(..))`) and it
+/// is passed through unchanged. Each variant carries the underlying cause for bug reports.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParserError {
+	#[error("{0}")]
+	NoExtension(String),
+	#[error("{0}")]
+	UnsupportedFormat(String),
+	#[error("{0}")]
+	PasswordRequired(String),
+	/// Protected by DRM or an encryption scheme that no password can unlock here.
+	#[error("{0}")]
+	Encrypted(String),
+	#[error("{0}")]
+	Io(String),
+	/// The file has no bytes at all, such as a download that never started.
+	#[error("{0}")]
+	EmptyFile(String),
+	/// The ZIP (or other) container is truncated or not an archive at all.
+	#[error("{0}")]
+	CorruptArchive(String),
+	/// Malformed XML; `line` and `column` are 1-based positions in the offending part.
+	#[error("{message}")]
+	Xml { message: String, line: u32, column: u32 },
+	/// A recognised format using a feature or sub-format the parser doesn't handle.
+	#[error("{0}")]
+	UnsupportedVariant(String),
+	/// An archive holds several readable documents; the caller picks one of these entry names and
+	/// retries with [`ParserContext::with_archive_entry`](crate::document::ParserContext::with_archive_entry).
+	#[error("{}", .0.join(", "))]
+	ArchiveEntryRequired(Vec<String>),
+	#[error("{0}")]
+	Other(String),
+}
+
+impl ParserError {
+	/// Classifies a parser failure by its cause chain: an explicit `ParserError` anywhere in the
+	/// chain wins, then the password sentinel, then I/O, ZIP and XML errors.
+	#[must_use]
+	pub fn classify(err: &anyhow::Error) -> Self {
+		if let Some(parser_error) = err.chain().find_map(|cause| cause.downcast_ref::<Self>()) {
+			return parser_error.clone();
+		}
+		let summary = err.to_string();
+		if let Some(message) = summary.strip_prefix(PASSWORD_REQUIRED_ERROR_PREFIX) {
+			return Self::PasswordRequired(message.trim().to_string());
+		}
+		let details = format!("{err:#}");
+		for cause in err.chain() {
+			if cause.downcast_ref::<ZipError>().is_some() {
+				return Self::CorruptArchive(details);
+			}
+			if let Some(xml_error) = cause.downcast_ref::<roxmltree::Error>() {
+				let pos = xml_error.pos();
+				return Self::Xml { message: details, line: pos.row, column: pos.col };
+			}
+			if cause.downcast_ref::<io::Error>().is_some() {
+				return Self::Io(details);
+			}
+		}
+		Self::Other(details)
+	}
+
+	#[must_use]
+	pub const fn code(&self) -> ParserErrorCode {
+		match self {
+			Self::NoExtension(_) => ParserErrorCode::NoExtension,
+			Self::UnsupportedFormat(_) => ParserErrorCode::UnsupportedFormat,
+			Self::PasswordRequired(_) => ParserErrorCode::PasswordRequired,
+			Self::Encrypted(_) => ParserErrorCode::Encrypted,
+			Self::Io(_) => ParserErrorCode::Io,
+			Self::EmptyFile(_) => ParserErrorCode::EmptyFile,
+			Self::CorruptArchive(_) => ParserErrorCode::CorruptArchive,
+			Self::Xml { .. } => ParserErrorCode::Xml,
+			Self::UnsupportedVariant(_) => ParserErrorCode::UnsupportedVariant,
+			Self::ArchiveEntryRequired(_) => ParserErrorCode::ArchiveEntryRequired,
+			Self::Other(_) => ParserErrorCode::Other,
+		}
+	}
+}
+
+impl From<anyhow::Error> for ParserError {
+	fn from(err: anyhow::Error) -> Self {
+		Self::classify(&err)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{self, Cursor};
+
+	use anyhow::Context;
+	use zip::ZipArchive;
+
+	use super::*;
+
+	#[test]
+	fn classify_recognises_password_sentinel() {
+		let err = anyhow::anyhow!("{PASSWORD_REQUIRED_ERROR_PREFIX} File is encrypted");
+		assert_eq!(ParserError::classify(&err), ParserError::PasswordRequired("File is encrypted".to_string()));
+	}
+
+	#[test]
+	fn classify_reports_xml_position() {
+		let xml_error = roxmltree::Document::parse("<root>\n  <open></root>").unwrap_err();
+		let err = anyhow::Error::new(xml_error).context("Failed to parse OPF document");
+		let classified = ParserError::classify(&err);
+		assert_eq!(classified.code(), ParserErrorCode::Xml);
+		let ParserError::Xml { message, line, .. } = classified else { unreachable!() };
+		assert_eq!(line, 2);
+		assert!(message.starts_with("Failed to parse OPF document: "));
+	}
+
+	#[test]
+	fn classify_prefers_archive_over_io_cause() {
+		let zip_error = ZipArchive::new(Cursor::new(b"not a zip".to_vec())).unwrap_err();
+		let err = anyhow::Error::new(zip_error).context("Failed to read EPUB as zip");
+		assert_eq!(ParserError::classify(&err).code(), ParserErrorCode::CorruptArchive);
+		let err = anyhow::Error::new(io::Error::from(io::ErrorKind::NotFound)).context("Failed to open");
+		assert_eq!(ParserError::classify(&err).code(), ParserErrorCode::Io);
+	}
+
+	#[test]
+	fn classify_passes_explicit_parser_errors_through() {
+		let err = anyhow::Error::new(ParserError::Encrypted("DRM".to_string())).context("outer");
+		assert_eq!(ParserError::classify(&err), ParserError::Encrypted("DRM".to_string()));
+	}
+}
@@ -4,16 +4,22 @@ use anyhow::{Context, Result};
 use roxmltree::{Document as XmlDocument, Node, NodeType};
 
 use crate::{
-	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags},
+	document::{Document, DocumentBuffer, Marker, MarkerType, MetadataField, ParserContext, ParserFlags, TocItem},
 	parser::{
-		Parser, add_converter_markers,
+		Parser, add_converter_markers, series_text,
 		util::xml::{collect_element_text, find_child_element},
 		xml_to_text::XmlToText,
 	},
 	t,
+	util::text::collapse_whitespace,
 };
 
-type Metadata = (String, String);
+#[derive(Default)]
+struct Metadata {
+	title: String,
+	author: String,
+	fields: Vec<(MetadataField, String)>,
+}
 
 pub struct Fb2Parser;
 
@@ -37,9 +43,9 @@ impl Parser for Fb2Parser {
 		if let Some(pos) = xml_content.rfind(CLOSING_TAG) {
 			xml_content.truncate(pos + CLOSING_TAG.len());
 		}
-		let (xml_content, (title, author)) = clean_fb2(&xml_content).unwrap_or_else(|| {
-			let (title, author) = extract_metadata(&xml_content);
-			(xml_content, (title, author))
+		let (xml_content, metadata) = clean_fb2(&xml_content).unwrap_or_else(|| {
+			let metadata = extract_metadata(&xml_content);
+			(xml_content, metadata)
 		});
 		let mut converter = XmlToText::with_render_tables_inline(context.render_tables_inline);
 		if !converter.convert(&xml_content) {
@@ -53,8 +59,14 @@ impl Parser for Fb2Parser {
 			buffer.add_marker(Marker::new(MarkerType::SectionBreak, *offset));
 		}
 		let id_positions: HashMap<String, usize> = converter.get_id_positions().clone();
-		let mut document = Document::new().with_title(title).with_author(author);
+		let mut document = Document::new().with_title(metadata.title).with_author(metadata.author);
+		for (field, value) in &metadata.fields {
+			document.add_metadata(*field, value);
+		}
 		document.set_buffer(buffer);
+		if let Some(notes) = build_notes_toc(&xml_content, &id_positions) {
+			document.toc_items.push(notes);
+		}
 		document.id_positions = id_positions;
 		Ok(document)
 	}
@@ -142,8 +154,7 @@ fn escape_xml(s: &str) -> String {
 }
 
 fn extract_metadata(xml_content: &str) -> Metadata {
-	XmlDocument::parse(xml_content)
-		.map_or_else(|_| (String::new(), String::new()), |doc| extract_metadata_from_doc(&doc))
+	XmlDocument::parse(xml_content).map_or_else(|_| Metadata::default(), |doc| extract_metadata_from_doc(&doc))
 }
 
 fn extract_metadata_from_doc(doc: &XmlDocument<'_>) -> Metadata {
@@ -169,7 +180,74 @@ fn extract_metadata_from_doc(doc: &XmlDocument<'_>) -> Metadata {
 		}
 		author = author.trim().to_string();
 	}
-	(title, author)
+	let fields = find_element_by_path(doc.root(), &["FictionBook", "description"]).map(read_description_fields);
+	Metadata { title, author, fields: fields.unwrap_or_default() }
+}
+
+/// Reads the language, genres, date and series from `<title-info>` and the publisher, year and
+/// ISBN from `<publish-info>`.
+fn read_description_fields(description: Node<'_, '_>) -> Vec<(MetadataField, String)> {
+	let mut fields = Vec::new();
+	let text = |node: Node<'_, '_>| collapse_whitespace(&collect_element_text(node)).trim().to_string();
+	if let Some(title_info) = find_child_element(description, "title-info") {
+		for node in title_info.children().filter(|n| n.node_type() == NodeType::Element) {
+			match node.tag_name().name() {
+				"lang" => fields.push((MetadataField::Language, text(node))),
+				"genre" => fields.push((MetadataField::Subject, text(node))),
+				"date" => {
+					let date = node.attribute("value").map_or_else(|| text(node), str::to_string);
+					fields.push((MetadataField::Date, date));
+				}
+				"sequence" => {
+					if let Some(name) = node.attribute("name") {
+						fields.push((MetadataField::Series, series_text(name, node.attribute("number").unwrap_or(""))));
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+	if let Some(publish_info) = find_child_element(description, "publish-info") {
+		for node in publish_info.children().filter(|n| n.node_type() == NodeType::Element) {
+			match node.tag_name().name() {
+				"publisher" => fields.push((MetadataField::Publisher, text(node))),
+				"year" if !fields.iter().any(|(field, _)| *field == MetadataField::Date) => {
+					fields.push((MetadataField::Date, text(node)));
+				}
+				"isbn" => fields.push((MetadataField::Identifier, format!("ISBN: {}", text(node)))),
+				_ => {}
+			}
+		}
+	}
+	fields
+}
+
+/// Builds a "Notes" TOC node whose children are the sections of the FB2 notes body
+/// (`<body name="notes">`), so footnotes can be browsed from the table of contents.
+fn build_notes_toc(xml_content: &str, id_positions: &HashMap<String, usize>) -> Option<TocItem> {
+	let doc = XmlDocument::parse(xml_content).ok()?;
+	let notes_body = doc.descendants().find(|node| {
+		node.tag_name().name() == "body"
+			&& node.attribute("name").is_some_and(|name| name == "notes" || name == "comments")
+	})?;
+	let children: Vec<TocItem> = notes_body
+		.children()
+		.filter(|child| child.is_element() && child.tag_name().name() == "section")
+		.filter_map(|section| {
+			let id = section.attribute("id")?;
+			let offset = *id_positions.get(id)?;
+			let name = find_child_element(section, "title")
+				.map(|title| collapse_whitespace(&collect_element_text(title)).trim().to_string())
+				.filter(|name| !name.is_empty())
+				.unwrap_or_else(|| id.to_string());
+			Some(TocItem::new(name, id.to_string(), offset))
+		})
+		.collect();
+	let offset = children.first()?.offset;
+	// TRANSLATORS: Table of contents entry grouping the footnotes of an FB2 (FictionBook) book
+	let mut notes = TocItem::new(t("Notes"), String::new(), offset);
+	notes.children = children;
+	Some(notes)
 }
 
 fn find_element_by_path<'a, 'input>(node: Node<'a, 'input>, path: &[&str]) -> Option<Node<'a, 'input>> {
@@ -191,3 +269,84 @@ fn find_element_by_path<'a, 'input>(node: Node<'a, 'input>, path: &[&str]) -> Op
 	}
 	None
 }
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		env,
+		time::{SystemTime, UNIX_EPOCH},
+	};
+
+	use super::*;
+	use crate::{document::DocumentHandle, reader_core::resolve_link};
+
+	const SAMPLE_FB2: &str = concat!(
+		r#"<?xml version="1.0" encoding="utf-8"?>"#,
+		r#"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">"#,
+		"<description><title-info><book-title>Verses</book-title>",
+		"<author><first-name>Ann</first-name><last-name>Poet</last-name></author>",
+		r#"<genre>poetry</genre><lang>en</lang><sequence name="Collected Verse" number="2"/>"#,
+		r##"<coverpage><image l:href="#cover.jpg"/></coverpage></title-info>"##,
+		"<publish-info><publisher>Small Press</publisher><year>2019</year><isbn>978-1-2345</isbn></publish-info>",
+		"</description>",
+		"<body><section><title><p>Chapter One</p></title>",
+		"<epigraph><p>Brevity is the soul of wit.</p><text-author>Polonius</text-author></epigraph>",
+		r##"<poem><stanza><v>First line</v><v>Second line<a l:href="#n1" type="note">[1]</a></v></stanza>"##,
+		"<stanza><v>Third line</v></stanza></poem></section></body>",
+		r#"<body name="notes"><section id="n1"><title><p>1</p></title><p>A footnote.</p></section></body>"#,
+		r#"<binary id="cover.jpg" content-type="image/jpeg">QUJDRA==</binary>"#,
+		"</FictionBook>"
+	);
+
+	fn parse_sample() -> Document {
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let path = env::temp_dir().join(format!("paperback_fb2_test_{nanos}.fb2"));
+		fs::write(&path, SAMPLE_FB2).unwrap();
+		let context = ParserContext::new(path.to_string_lossy().to_string());
+		let document = Fb2Parser.parse(&context).expect("parse fb2");
+		let _ = fs::remove_file(&path);
+		document
+	}
+
+	#[test]
+	fn poems_epigraphs_and_notes_are_laid_out_as_lines() {
+		let document = parse_sample();
+		assert_eq!(document.title, "Verses");
+		assert_eq!(document.author, "Ann Poet");
+		assert_eq!(
+			document.metadata_entries(),
+			vec![
+				(MetadataField::Series, "Collected Verse, book 2".to_string()),
+				(MetadataField::Publisher, "Small Press".to_string()),
+				(MetadataField::Date, "2019".to_string()),
+				(MetadataField::Language, "en".to_string()),
+				(MetadataField::Subject, "poetry".to_string()),
+				(MetadataField::Identifier, "ISBN: 978-1-2345".to_string()),
+			]
+		);
+		assert_eq!(
+			document.buffer.content,
+			"Chapter One\n\nBrevity is the soul of wit.\n\u{2014} Polonius\nFirst line\nSecond line[1]\n\nThird line\n1\nA footnote."
+		);
+	}
+
+	#[test]
+	fn note_reference_resolves_to_note_section() {
+		let document = parse_sample();
+		let content = document.buffer.content.clone();
+		let note_offset = content[..content.find("1\nA footnote.").unwrap()].chars().count();
+		assert!(
+			document.buffer.markers.iter().any(|marker| marker.mtype == MarkerType::Link && marker.reference == "#n1")
+		);
+		assert_eq!(document.toc_items.len(), 1);
+		assert_eq!(document.toc_items[0].name, "Notes");
+		assert_eq!(document.toc_items[0].children.len(), 1);
+		assert_eq!(document.toc_items[0].children[0].name, "1");
+		assert_eq!(document.toc_items[0].children[0].offset, note_offset);
+		let handle = DocumentHandle::new(document);
+		let link = resolve_link(&handle, "#n1", 0);
+		assert!(link.found);
+		assert!(!link.is_external);
+		assert_eq!(link.offset, note_offset);
+	}
+}
@@ -1,18 +1,30 @@
-use std::fs;
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	fs,
+	path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 
 use crate::{
-	document::{Document, DocumentBuffer, ParserContext, ParserFlags},
+	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, TocItem},
 	parser::{
-		Parser, add_converter_markers,
+		Parser, add_converter_landmarks, add_converter_markers, add_converter_markers_excluding_links,
 		html_to_text::{HtmlSourceMode, HtmlToText},
+		is_external_url,
 		util::{path::extract_title_from_path, toc::build_toc_from_headings},
 	},
 	t,
-	util::encoding::convert_to_utf8,
+	types::LandmarkInfo,
+	util::{encoding::decode_text, text::url_decode},
 };
 
+/// Forced format used by the Open As dialog to read a folder or index page as one book.
+pub const HTML_BOOK_FORMAT: &str = "htmlbook";
+
+const HTML_EXTENSIONS: [&str; 3] = ["htm", "html", "xhtml"];
+const INDEX_FILE_NAMES: [&str; 3] = ["index.html", "index.htm", "index.xhtml"];
+
 pub struct HtmlParser;
 
 impl Parser for HtmlParser {
@@ -21,41 +33,398 @@ impl Parser for HtmlParser {
 	}
 
 	fn extensions(&self) -> &[&str] {
-		&["htm", "html", "xhtml"]
+		&HTML_EXTENSIONS
+	}
+
+	fn reads_encoding(&self) -> bool {
+		true
 	}
 
 	fn supported_flags(&self) -> ParserFlags {
-		ParserFlags::SUPPORTS_TOC | ParserFlags::SUPPORTS_LISTS
+		ParserFlags::SUPPORTS_TOC
+			| ParserFlags::SUPPORTS_LISTS
+			| ParserFlags::SUPPORTS_SECTIONS
+			| ParserFlags::SUPPORTS_PAGES
 	}
 
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
+		let path = Path::new(&context.file_path);
+		if path.is_dir() {
+			return parse_html_book(context, path);
+		}
 		let bytes = fs::read(&context.file_path)
 			.with_context(|| format!("Failed to open HTML file '{}'", context.file_path))?;
 		if bytes.is_empty() {
 			// TRANSLATORS: Error shown when an HTML file has no content; {} is the file path
 			anyhow::bail!(t("HTML file is empty: {}").replace("{}", &context.file_path));
 		}
-		let html_content = convert_to_utf8(&bytes);
+		let html_content = decode_text(&bytes, context.encoding.as_deref());
 		let mut converter = HtmlToText::with_render_tables_inline(context.render_tables_inline);
 		if !converter.convert(&html_content, HtmlSourceMode::NativeHtml) {
 			// TRANSLATORS: Error shown when an HTML file fails to convert to plain text; {} is the file path
 			anyhow::bail!(t("Failed to convert HTML to text: {}").replace("{}", &context.file_path));
 		}
-		let extracted_title = converter.get_title();
-		let title = if extracted_title.is_empty() {
-			extract_title_from_path(&context.file_path)
+		if is_book_index(path, &converter) {
+			return parse_html_book(context, path);
+		}
+		let mut doc = document_from_converter(&converter);
+		if doc.title.is_empty() {
+			doc.title = extract_title_from_path(&context.file_path);
+		}
+		Ok(doc)
+	}
+}
+
+/// Builds a document from converted HTML, titled by its `<title>` (empty when there is none).
+pub(crate) fn document_from_converter(converter: &HtmlToText) -> Document {
+	let mut buffer = DocumentBuffer::with_content(converter.get_text());
+	add_converter_markers(&mut buffer, converter, 0);
+	add_page_breaks(&mut buffer, converter, 0);
+	let mut doc = Document::new().with_title(converter.get_title().to_string());
+	doc.set_buffer(buffer);
+	doc.toc_items = build_toc_from_headings(converter.get_headings());
+	doc.id_positions = converter.get_id_positions().clone();
+	add_converter_landmarks(&mut doc, converter.get_landmarks(), 0);
+	doc
+}
+
+/// Adds the pages the converted HTML marks with `pagebreak` spans and forced CSS breaks.
+fn add_page_breaks(buffer: &mut DocumentBuffer, converter: &HtmlToText, offset: usize) {
+	for page in converter.get_page_breaks() {
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, offset + page.offset).with_text(page.text.clone()));
+	}
+}
+
+/// Reads an index page and the chapter files it links to as a single document.
+pub struct HtmlBookParser;
+
+impl Parser for HtmlBookParser {
+	fn name(&self) -> &'static str {
+		"HTML Books"
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&[HTML_BOOK_FORMAT]
+	}
+
+	fn reads_encoding(&self) -> bool {
+		true
+	}
+
+	fn supported_flags(&self) -> ParserFlags {
+		ParserFlags::SUPPORTS_TOC
+			| ParserFlags::SUPPORTS_LISTS
+			| ParserFlags::SUPPORTS_SECTIONS
+			| ParserFlags::SUPPORTS_PAGES
+	}
+
+	fn open_as_only(&self) -> bool {
+		true
+	}
+
+	fn parse(&self, context: &ParserContext) -> Result<Document> {
+		parse_html_book(context, Path::new(&context.file_path))
+	}
+}
+
+/// An `index.html` that links to at least one other local HTML file next to it.
+fn is_book_index(path: &Path, converter: &HtmlToText) -> bool {
+	let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+		return false;
+	};
+	if !INDEX_FILE_NAMES.iter().any(|index| index.eq_ignore_ascii_case(file_name)) {
+		return false;
+	}
+	let root = path.parent().unwrap_or_else(|| Path::new(""));
+	converter.get_links().iter().any(|link| {
+		book_file_target(file_name, &link.reference)
+			.is_some_and(|target| target != file_name && root.join(&target).is_file())
+	})
+}
+
+fn parse_html_book(context: &ParserContext, start: &Path) -> Result<Document> {
+	let (root, index) = book_root_and_index(start)?;
+	let max_files = context.max_linked_files.max(1);
+	let encoding = context.encoding.as_deref();
+	let mut queue = VecDeque::from([index.clone()]);
+	let mut seen = HashSet::from([index]);
+	let mut buffer = DocumentBuffer::new();
+	let mut id_positions = HashMap::new();
+	let mut toc_items = Vec::new();
+	let mut landmarks = Vec::new();
+	let mut book_title = None;
+	let mut skipped = 0usize;
+	while let Some(relative) = queue.pop_front() {
+		let Ok(bytes) = fs::read(root.join(&relative)) else {
+			continue;
+		};
+		let mut converter = HtmlToText::with_render_tables_inline(context.render_tables_inline);
+		if bytes.is_empty() || !converter.convert(&decode_text(&bytes, encoding), HtmlSourceMode::NativeHtml) {
+			continue;
+		}
+		for link in converter.get_links() {
+			let Some(target) = book_file_target(&relative, &link.reference) else {
+				continue;
+			};
+			if seen.contains(&target) || !root.join(&target).is_file() {
+				continue;
+			}
+			if seen.len() >= max_files {
+				skipped += 1;
+				continue;
+			}
+			seen.insert(target.clone());
+			queue.push_back(target);
+		}
+		let section_start = buffer.current_position();
+		let chapter_title = chapter_title(&converter, &relative);
+		if book_title.is_none() && !converter.get_title().is_empty() {
+			book_title = Some(converter.get_title().to_string());
+		}
+		buffer.add_marker(
+			Marker::new(MarkerType::SectionBreak, section_start)
+				.with_text(chapter_title.clone())
+				.with_reference(relative.clone()),
+		);
+		// Fragment-less links to a chapter resolve through its bare path.
+		id_positions.insert(relative.clone(), section_start);
+		for (id, offset) in converter.get_id_positions() {
+			let absolute = section_start + offset;
+			id_positions.entry(id.clone()).or_insert(absolute);
+			id_positions.insert(format!("{relative}#{id}"), absolute);
+		}
+		add_converter_markers_excluding_links(&mut buffer, &converter, section_start);
+		add_page_breaks(&mut buffer, &converter, section_start);
+		landmarks.extend(
+			converter
+				.get_landmarks()
+				.iter()
+				.map(|landmark| LandmarkInfo { offset: section_start + landmark.offset, kind: landmark.kind }),
+		);
+		for link in converter.get_links() {
+			let reference = resolve_book_href(&relative, &link.reference).unwrap_or_else(|| link.reference.clone());
+			buffer.add_marker(
+				Marker::new(MarkerType::Link, section_start + link.offset)
+					.with_text(link.text.clone())
+					.with_reference(reference),
+			);
+		}
+		buffer.append(&converter.get_text());
+		if !buffer.content.ends_with('\n') {
+			buffer.append("\n");
+		}
+		toc_items.push(TocItem::new(chapter_title, relative, section_start));
+	}
+	if skipped > 0 {
+		tracing::warn!(skipped, max_files, "HTML book file limit reached; remaining linked files were not read");
+	}
+	if toc_items.is_empty() {
+		// TRANSLATORS: Error shown when none of the files of a multi-file HTML book could be read; {} is the path
+		anyhow::bail!(t("HTML book has no readable files: {}").replace("{}", &context.file_path));
+	}
+	let title = book_title.unwrap_or_else(|| {
+		let name = if start.is_dir() { start.to_string_lossy() } else { root.to_string_lossy() };
+		extract_title_from_path(name.trim_end_matches(['/', '\\']))
+	});
+	let mut doc = Document::new().with_title(title);
+	doc.set_buffer(buffer);
+	doc.toc_items = toc_items;
+	doc.id_positions = id_positions;
+	add_converter_landmarks(&mut doc, &landmarks, 0);
+	Ok(doc)
+}
+
+/// Splits the opened path into the book's folder and the index file relative to it.
+fn book_root_and_index(start: &Path) -> Result<(PathBuf, String)> {
+	if start.is_dir() {
+		let index = INDEX_FILE_NAMES.iter().find(|name| start.join(name).is_file()).ok_or_else(|| {
+			// TRANSLATORS: Error shown when a folder opened as an HTML book has no index page; {} is the folder path
+			anyhow::anyhow!(t("No index.html found in folder: {}").replace("{}", &start.display().to_string()))
+		})?;
+		return Ok((start.to_path_buf(), (*index).to_string()));
+	}
+	let root = start.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+	let index = start.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+	Ok((root, index))
+}
+
+fn chapter_title(converter: &HtmlToText, relative: &str) -> String {
+	let title = converter.get_title().trim();
+	if !title.is_empty() {
+		return title.to_string();
+	}
+	converter
+		.get_headings()
+		.iter()
+		.find(|heading| heading.level == 1 && !heading.text.trim().is_empty())
+		.map_or_else(|| extract_title_from_path(relative), |heading| heading.text.trim().to_string())
+}
+
+/// The book-relative HTML file a link points to, if it's a local chapter worth following.
+fn book_file_target(current: &str, href: &str) -> Option<String> {
+	let resolved = resolve_book_href(current, href)?;
+	let file = resolved.split('#').next().unwrap_or_default();
+	let extension = Path::new(file).extension()?.to_str()?;
+	HTML_EXTENSIONS.iter().any(|ext| ext.eq_ignore_ascii_case(extension)).then(|| file.to_string())
+}
+
+/// Resolves `href` against the book-relative path of the file containing it.
+///
+/// Returns `None` for remote or non-file links and for paths that climb out of the book's
+/// folder, so the crawler never leaves the local tree.
+fn resolve_book_href(current: &str, href: &str) -> Option<String> {
+	let href = href.trim();
+	if href.is_empty()
+		|| is_external_url(href)
+		|| href.contains("://")
+		|| href.to_ascii_lowercase().starts_with("file:")
+	{
+		return None;
+	}
+	let (path_part, fragment) = href.split_once('#').map_or((href, None), |(path, fragment)| (path, Some(fragment)));
+	let path_part = path_part.split('?').next().unwrap_or_default();
+	if path_part.contains(':') {
+		return None;
+	}
+	let resolved = if path_part.is_empty() {
+		current.to_string()
+	} else {
+		let decoded = url_decode(path_part).replace('\\', "/");
+		let mut parts: Vec<&str> = if decoded.starts_with('/') {
+			Vec::new()
 		} else {
-			extracted_title.to_string()
+			current.split('/').filter(|part| !part.is_empty()).collect()
 		};
-		let text = converter.get_text();
-		let mut buffer = DocumentBuffer::with_content(text);
-		let id_positions = converter.get_id_positions().clone();
-		add_converter_markers(&mut buffer, &converter, 0);
-		let toc_items = build_toc_from_headings(converter.get_headings());
-		let mut doc = Document::new().with_title(title);
-		doc.set_buffer(buffer);
-		doc.toc_items = toc_items;
-		doc.id_positions = id_positions;
-		Ok(doc)
+		if !decoded.starts_with('/') {
+			parts.pop();
+		}
+		for part in decoded.split('/') {
+			match part {
+				".." => {
+					parts.pop()?;
+				}
+				"." | "" => {}
+				part => parts.push(part),
+			}
+		}
+		parts.join("/")
+	};
+	match fragment {
+		Some(fragment) if !fragment.is_empty() => Some(format!("{resolved}#{fragment}")),
+		_ => Some(resolved),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use super::*;
+
+	fn write_book(name: &str, files: &[(&str, &str)]) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("paperback_html_book_{name}_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		for (file, content) in files {
+			let path = dir.join(file);
+			fs::create_dir_all(path.parent().unwrap()).unwrap();
+			fs::write(path, content).unwrap();
+		}
+		dir
+	}
+
+	#[test]
+	fn resolve_book_href_stays_inside_the_book() {
+		assert_eq!(resolve_book_href("index.html", "ch1.html").as_deref(), Some("ch1.html"));
+		assert_eq!(resolve_book_href("text/ch1.html", "ch2.html#a").as_deref(), Some("text/ch2.html#a"));
+		assert_eq!(resolve_book_href("text/ch1.html", "#note").as_deref(), Some("text/ch1.html#note"));
+		assert_eq!(resolve_book_href("text/ch1.html", "../index.html").as_deref(), Some("index.html"));
+		assert_eq!(resolve_book_href("text/ch1.html", "my%20file.html").as_deref(), Some("text/my file.html"));
+		assert_eq!(resolve_book_href("index.html", "../outside.html"), None);
+		assert_eq!(resolve_book_href("index.html", "https://example.com/ch1.html"), None);
+		assert_eq!(resolve_book_href("index.html", "mailto:someone@example.com"), None);
+	}
+
+	#[test]
+	fn index_with_chapters_is_read_as_one_book() {
+		let dir = write_book(
+			"basic",
+			&[
+				(
+					"index.html",
+					"<html><head><title>My Book</title></head><body><a href=\"ch1.html\">One</a> <a href=\"ch2.html\">Two</a> <a href=\"https://example.com/ch3.html\">Web</a></body></html>",
+				),
+				(
+					"ch1.html",
+					"<html><body><h1>Chapter One</h1><p>First.</p><a href=\"ch2.html#end\">Jump</a><a href=\"index.html\">Home</a></body></html>",
+				),
+				("ch2.html", "<html><body><h1>Chapter Two</h1><p id=\"end\">Second.</p></body></html>"),
+			],
+		);
+		let context = ParserContext::new(dir.join("index.html").to_string_lossy().into_owned());
+		let doc = HtmlParser.parse(&context).unwrap();
+		assert_eq!(doc.title, "My Book");
+		let names: Vec<&str> = doc.toc_items.iter().map(|item| item.name.as_str()).collect();
+		assert_eq!(names, ["My Book", "Chapter One", "Chapter Two"]);
+		let sections = doc.buffer.markers.iter().filter(|m| m.mtype == MarkerType::SectionBreak).count();
+		assert_eq!(sections, 3);
+		let chapter_two = doc.toc_items[2].offset;
+		assert_eq!(doc.id_positions.get("ch2.html"), Some(&chapter_two));
+		let end = doc.id_positions["ch2.html#end"];
+		assert!(doc.buffer.content[end..].starts_with("Second."));
+		assert!(doc.buffer.markers.iter().any(|m| m.mtype == MarkerType::Link && m.reference == "ch2.html#end"));
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn crawl_respects_the_file_cap_and_cycles() {
+		let dir = write_book(
+			"cap",
+			&[
+				("index.html", "<html><body><a href=\"a.html\">A</a><a href=\"b.html\">B</a></body></html>"),
+				(
+					"a.html",
+					"<html><body><h1>A</h1><a href=\"index.html\">Back</a><a href=\"b.html\">B</a></body></html>",
+				),
+				("b.html", "<html><body><h1>B</h1><a href=\"a.html\">A</a></body></html>"),
+			],
+		);
+		let path = dir.join("index.html").to_string_lossy().into_owned();
+		let all = HtmlParser.parse(&ParserContext::new(path.clone())).unwrap();
+		assert_eq!(all.toc_items.len(), 3);
+		let capped = HtmlParser.parse(&ParserContext::new(path).with_max_linked_files(2)).unwrap();
+		assert_eq!(capped.toc_items.len(), 2);
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn folder_opened_as_book_starts_at_index() {
+		let dir = write_book(
+			"folder",
+			&[
+				("index.htm", "<html><body><a href=\"part.htm\">Part</a></body></html>"),
+				("part.htm", "<html><head><title>Part</title></head><body>Body</body></html>"),
+			],
+		);
+		let context = ParserContext::new(dir.to_string_lossy().into_owned());
+		let doc = HtmlBookParser.parse(&context).unwrap();
+		assert_eq!(doc.toc_items.len(), 2);
+		assert_eq!(doc.toc_items[1].name, "Part");
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn plain_page_with_local_links_stays_single_document() {
+		let dir = write_book(
+			"single",
+			&[
+				("page.html", "<html><body><a href=\"other.html\">Other</a></body></html>"),
+				("other.html", "<html><body>Other</body></html>"),
+			],
+		);
+		let context = ParserContext::new(dir.join("page.html").to_string_lossy().into_owned());
+		let doc = HtmlParser.parse(&context).unwrap();
+		assert!(doc.toc_items.is_empty());
+		assert!(!doc.buffer.markers.iter().any(|m| m.mtype == MarkerType::SectionBreak));
+		let _ = fs::remove_dir_all(&dir);
 	}
 }
@@ -5,12 +5,19 @@ use ego_tree::NodeRef;
 use scraper::{ElementRef, Html, Node, node};
 
 use crate::{
+	document::LandmarkKind,
 	parser::{
-		ConverterOutput,
-		table_text::{push_finalized_line, table_render_bundle},
+		ConverterOutput, figure_text, image_text, is_note_semantic_type,
+		table_text::{align_tsv_columns, display_lines_and_length, push_finalized_line, table_render_bundle},
+		util::{
+			blocks::BlockTracker,
+			page_breaks::{ForcedBreaks, PageBreakRules, is_page_break_semantic_type, push_page_break},
+		},
+	},
+	types::{
+		BlockInfo, FormatInfo, HeadingInfo, ImageInfo, LandmarkInfo, LinkInfo, ListInfo, ListItemInfo, PageBreakInfo,
+		SeparatorInfo, TableInfo,
 	},
-	t,
-	types::{FormatInfo, HeadingInfo, ImageInfo, LinkInfo, ListInfo, ListItemInfo, SeparatorInfo, TableInfo},
 	util::text::{collapse_whitespace, display_len, format_list_item, remove_soft_hyphens, trim_string},
 };
 
@@ -53,8 +60,15 @@ pub struct HtmlToText {
 	figures: Vec<ImageInfo>,
 	tables: Vec<TableInfo>,
 	separators: Vec<SeparatorInfo>,
+	block_quotes: BlockTracker,
+	code_blocks: BlockTracker,
+	notes: BlockTracker,
+	/// Whether each open `<aside>` is a footnote or endnote, so the matching close ends its note.
+	open_asides: Vec<bool>,
 	lists: Vec<ListInfo>,
 	list_items: Vec<ListItemInfo>,
+	landmarks: Vec<LandmarkInfo>,
+	page_breaks: Vec<PageBreakInfo>,
 	title: String,
 	preserve_whitespace_depth: usize,
 	flags: ProcessingFlags,
@@ -66,6 +80,9 @@ pub struct HtmlToText {
 	/// `None` marks an open list that was not recorded (no direct `<li>`), keeping the stack
 	/// balanced with the start/close handlers so list lengths are set on the right entries.
 	open_lists: Vec<Option<usize>>,
+	/// Indices into `list_items` for currently open `<li>` elements, so each item's length is set
+	/// when it closes.
+	open_list_items: Vec<usize>,
 	link_start_pos: usize,
 	bolds: Vec<FormatInfo>,
 	italics: Vec<FormatInfo>,
@@ -78,6 +95,9 @@ pub struct HtmlToText {
 	/// When `true`, tables are emitted as their full tab-separated rendering; otherwise as a
 	/// `"[Table]: <first row>"` placeholder. A config flag, not parse state: it survives `clear()`.
 	render_tables_inline: bool,
+	/// Page-break rules from the stylesheets the document links to. Like `render_tables_inline`, it
+	/// survives `clear()`.
+	page_break_rules: PageBreakRules,
 }
 
 impl HtmlToText {
@@ -93,8 +113,14 @@ impl HtmlToText {
 			figures: Vec::new(),
 			tables: Vec::new(),
 			separators: Vec::new(),
+			block_quotes: BlockTracker::default(),
+			code_blocks: BlockTracker::default(),
+			notes: BlockTracker::default(),
+			open_asides: Vec::new(),
 			lists: Vec::new(),
 			list_items: Vec::new(),
+			landmarks: Vec::new(),
+			page_breaks: Vec::new(),
 			title: String::new(),
 			preserve_whitespace_depth: 0,
 			flags: ProcessingFlags::empty(),
@@ -103,6 +129,7 @@ impl HtmlToText {
 			list_style_stack: Vec::new(),
 			list_level: 0,
 			open_lists: Vec::new(),
+			open_list_items: Vec::new(),
 			link_start_pos: 0,
 			bolds: Vec::new(),
 			italics: Vec::new(),
@@ -113,6 +140,7 @@ impl HtmlToText {
 			source_mode: HtmlSourceMode::NativeHtml,
 			cached_char_length: 0,
 			render_tables_inline: false,
+			page_break_rules: PageBreakRules::default(),
 		}
 	}
 
@@ -124,6 +152,13 @@ impl HtmlToText {
 		Self { render_tables_inline, ..Self::new() }
 	}
 
+	/// Starts a new page wherever `rules` force a break, as well as where `style` attributes do.
+	#[must_use]
+	pub fn with_page_break_rules(mut self, rules: PageBreakRules) -> Self {
+		self.page_break_rules = rules;
+		self
+	}
+
 	pub fn convert(&mut self, html_content: &str, mode: HtmlSourceMode) -> bool {
 		self.clear();
 		self.source_mode = mode;
@@ -131,6 +166,7 @@ impl HtmlToText {
 		let root = document.tree.root();
 		self.process_node(root, &document);
 		self.finalize_current_line();
+		self.drop_trailing_page_break();
 		true
 	}
 
@@ -174,11 +210,37 @@ impl HtmlToText {
 		&self.list_items
 	}
 
+	#[must_use]
+	pub fn get_landmarks(&self) -> &[LandmarkInfo] {
+		&self.landmarks
+	}
+
+	/// Page breaks from `pagebreak` spans and CSS forced breaks, labelled by the span's title.
+	#[must_use]
+	pub fn get_page_breaks(&self) -> &[PageBreakInfo] {
+		&self.page_breaks
+	}
+
 	#[must_use]
 	pub const fn get_id_positions(&self) -> &HashMap<String, usize> {
 		&self.id_positions
 	}
 
+	#[must_use]
+	pub fn get_block_quotes(&self) -> &[BlockInfo] {
+		self.block_quotes.blocks()
+	}
+
+	#[must_use]
+	pub fn get_code_blocks(&self) -> &[BlockInfo] {
+		self.code_blocks.blocks()
+	}
+
+	#[must_use]
+	pub fn get_notes(&self) -> &[BlockInfo] {
+		self.notes.blocks()
+	}
+
 	#[must_use]
 	pub fn get_bolds(&self) -> &[FormatInfo] {
 		&self.bolds
@@ -204,8 +266,14 @@ impl HtmlToText {
 		self.figures.clear();
 		self.tables.clear();
 		self.separators.clear();
+		self.block_quotes.clear();
+		self.code_blocks.clear();
+		self.notes.clear();
+		self.open_asides.clear();
 		self.lists.clear();
 		self.list_items.clear();
+		self.landmarks.clear();
+		self.page_breaks.clear();
 		self.title.clear();
 		self.preserve_whitespace_depth = 0;
 		self.flags = ProcessingFlags::empty();
@@ -214,6 +282,7 @@ impl HtmlToText {
 		self.list_style_stack.clear();
 		self.list_level = 0;
 		self.open_lists.clear();
+		self.open_list_items.clear();
 		self.link_start_pos = 0;
 		self.bolds.clear();
 		self.italics.clear();
@@ -237,6 +306,10 @@ impl HtmlToText {
 		match node.value() {
 			Node::Element(element) => {
 				let tag_name = element.name();
+				let breaks = self.forced_page_breaks(tag_name, element);
+				if breaks.before {
+					self.add_forced_page_break(tag_name);
+				}
 				if tag_name == "table" {
 					if self.flags.contains(ProcessingFlags::IN_BODY)
 						&& let Some(id) = element.attr("id").or_else(|| element.attr("name"))
@@ -244,17 +317,23 @@ impl HtmlToText {
 						self.id_positions.insert(id.to_string(), self.get_current_text_position());
 					}
 					self.handle_table(node, document);
+					if breaks.after {
+						self.add_forced_page_break(tag_name);
+					}
 					return;
 				}
 				self.handle_element_opening(tag_name, node, document);
 				self.handle_list_item(tag_name, node, document);
 				self.handle_list_start(tag_name, node);
 				self.handle_heading(tag_name, node, document);
-				if matches!(tag_name, "script" | "style" | "noscript" | "iframe" | "object" | "embed") {
+				if matches!(tag_name, "script" | "style" | "noscript" | "iframe" | "object" | "embed" | "svg") {
 					return;
 				}
 				self.process_element_children(node, document, tag_name);
 				self.handle_element_closing(tag_name);
+				if breaks.after {
+					self.add_forced_page_break(tag_name);
+				}
 			}
 			Node::Text(text) => {
 				self.handle_text_node(text);
@@ -268,6 +347,30 @@ impl HtmlToText {
 		}
 	}
 
+	fn forced_page_breaks(&self, tag_name: &str, element: &node::Element) -> ForcedBreaks {
+		if !self.flags.contains(ProcessingFlags::IN_BODY) || tag_name == "body" {
+			return ForcedBreaks::default();
+		}
+		let classes = element.attr("class").unwrap_or_default();
+		self.page_break_rules.breaks_for(tag_name, classes, element.attr("style").unwrap_or_default())
+	}
+
+	/// A forced break after the last element would start a page with nothing on it.
+	fn drop_trailing_page_break(&mut self) {
+		let end = self.get_current_text_position();
+		if self.page_breaks.last().is_some_and(|last| last.offset >= end && last.text.is_empty()) {
+			self.page_breaks.pop();
+		}
+	}
+
+	/// Starts a page at the current position, on a line of its own for a block element.
+	fn add_forced_page_break(&mut self, tag_name: &str) {
+		if Self::is_block_element(tag_name) {
+			self.finalize_current_line();
+		}
+		push_page_break(&mut self.page_breaks, self.get_current_text_position(), String::new());
+	}
+
 	fn handle_table(&mut self, node: NodeRef<'_, Node>, document: &Html) {
 		self.finalize_current_line();
 		let table_html = Self::serialize_node(node, document);
@@ -275,7 +378,12 @@ impl HtmlToText {
 		// Emit the table's on-screen text via the shared helper instead of recursing children to
 		// emit one cell per line. The helper output may contain tabs and span multiple lines; push
 		// each line verbatim so tab separators and empty cells survive whitespace collapsing.
-		let render = table_render_bundle(&table_html, self.render_tables_inline);
+		let mut render = table_render_bundle(&table_html, self.render_tables_inline);
+		if self.source_mode == HtmlSourceMode::Markdown && self.render_tables_inline {
+			// Pipe tables are read as plain text in the source, so line the columns up the same way.
+			(render.lines, render.display_length) =
+				display_lines_and_length(&align_tsv_columns(&render.lines.join("\n")));
+		}
 		for line in render.lines {
 			self.push_finalized_line(line);
 		}
@@ -298,44 +406,40 @@ impl HtmlToText {
 
 	fn handle_element_opening(&mut self, tag_name: &str, node: NodeRef<'_, Node>, document: &Html) {
 		if let Node::Element(element) = node.value() {
+			if (self.flags.contains(ProcessingFlags::IN_BODY) || tag_name == "body")
+				&& let Some(kind) = element
+					.attr("epub:type")
+					.or_else(|| element.attr("role"))
+					.and_then(LandmarkKind::from_semantic_type)
+			{
+				self.landmarks.push(LandmarkInfo { offset: self.get_current_text_position(), kind });
+			}
 			if self.flags.contains(ProcessingFlags::IN_BODY) {
 				if let Some(id) = element.attr("id").or_else(|| element.attr("name")) {
 					self.id_positions.insert(id.to_string(), self.get_current_text_position());
 				}
-				if tag_name == "img" || tag_name == "image" || tag_name == "figure" {
-					let mut description = element
+				if element.attr("epub:type").or_else(|| element.attr("role")).is_some_and(is_page_break_semantic_type) {
+					let label = element
+						.attr("title")
+						.or_else(|| element.attr("aria-label"))
+						.map_or_else(|| Self::collect_text(node), str::to_string);
+					let offset = self.get_current_text_position();
+					push_page_break(&mut self.page_breaks, offset, trim_string(&collapse_whitespace(&label)));
+				}
+				if tag_name == "figure" {
+					self.handle_figure(element, node);
+				} else if matches!(tag_name, "img" | "image" | "svg") {
+					let description = element
 						.attr("alt")
 						.or_else(|| element.attr("aria-label"))
 						.or_else(|| element.attr("aria-description"))
 						.or_else(|| element.attr("title"))
 						.map(collapse_whitespace)
+						.or_else(|| (tag_name == "svg").then(|| Self::svg_title(node)))
 						.unwrap_or_default();
-
-					if description.is_empty() && tag_name == "figure" {
-						for child in node.children() {
-							if let Node::Element(child_elem) = child.value()
-								&& child_elem.name() == "figcaption"
-							{
-								description = collapse_whitespace(&Self::collect_text(child));
-								break;
-							}
-						}
-					}
-
-					if !description.is_empty() {
-						let is_figure = tag_name == "figure";
-						// TRANSLATORS: Label inserted before a figure or image's description, e.g. "[Figure: a cat sleeping]"
-						let label = if is_figure { t("Figure") } else { t("Image") };
-						let image_text = format!("[{label}: {description}]");
-						let offset = self.get_current_text_position();
-						self.current_line.push_str(&image_text);
-						let info = ImageInfo { offset, alt_text: description };
-						if is_figure {
-							self.figures.push(info);
-						} else {
-							self.images.push(info);
-						}
-					}
+					let offset = self.get_current_text_position();
+					self.current_line.push_str(&image_text(&description));
+					self.images.push(ImageInfo { offset, alt_text: description });
 				}
 			}
 			if tag_name == "a" && !self.flags.contains(ProcessingFlags::IN_LINK) {
@@ -360,7 +464,24 @@ impl HtmlToText {
 			self.flags.insert(ProcessingFlags::IN_BODY);
 		} else if tag_name == "pre" {
 			self.finalize_current_line();
+			if self.flags.contains(ProcessingFlags::IN_BODY) {
+				self.code_blocks.open(self.get_current_text_position(), self.lines.len());
+			}
 			self.start_preserve_whitespace();
+		} else if tag_name == "blockquote" && self.flags.contains(ProcessingFlags::IN_BODY) {
+			self.finalize_current_line();
+			self.block_quotes.open(self.get_current_text_position(), self.lines.len());
+		} else if tag_name == "aside" && self.flags.contains(ProcessingFlags::IN_BODY) {
+			self.finalize_current_line();
+			let is_note = node
+				.value()
+				.as_element()
+				.and_then(|element| element.attr("epub:type").or_else(|| element.attr("role")))
+				.is_some_and(is_note_semantic_type);
+			if is_note {
+				self.notes.open(self.get_current_text_position(), self.lines.len());
+			}
+			self.open_asides.push(is_note);
 		} else if tag_name == "hr" && self.flags.contains(ProcessingFlags::IN_BODY) {
 			self.finalize_current_line();
 			let offset = self.get_current_text_position();
@@ -380,10 +501,12 @@ impl HtmlToText {
 		if tag_name == "li" {
 			self.finalize_current_line();
 			let li_text = Self::get_element_text(node, document);
+			self.open_list_items.push(self.list_items.len());
 			self.list_items.push(ListItemInfo {
 				offset: self.get_current_text_position(),
 				level: self.list_level,
 				text: li_text,
+				length: 0,
 			});
 			for _ in 0..self.list_level {
 				self.current_line.push_str("  ");
@@ -517,8 +640,24 @@ impl HtmlToText {
 				self.finalize_current_line();
 			}
 			self.stop_preserve_whitespace();
+			let end = self.get_current_text_position();
+			self.code_blocks.close(&self.lines, end);
 		} else if Self::is_block_element(tag_name) {
 			self.finalize_current_line();
+			if tag_name == "blockquote" {
+				let end = self.get_current_text_position();
+				self.block_quotes.close(&self.lines, end);
+			} else if tag_name == "aside" {
+				if self.open_asides.pop() == Some(true) {
+					let end = self.get_current_text_position();
+					self.notes.close(&self.lines, end);
+				}
+			} else if tag_name == "li"
+				&& let Some(open) = self.open_list_items.pop()
+			{
+				let offset = self.list_items[open].offset;
+				self.list_items[open].length = self.get_current_text_position().saturating_sub(offset);
+			}
 		}
 		if tag_name == "b" || tag_name == "strong" {
 			if let Some(start) = self.open_bolds.pop() {
@@ -576,6 +715,38 @@ impl HtmlToText {
 		}
 	}
 
+	/// Puts a figure on a line of its own and records where it starts. A figure with a caption
+	/// leaves the caption to follow its image as ordinary text; one without gets a label instead.
+	fn handle_figure(&mut self, element: &node::Element, node: NodeRef<'_, Node>) {
+		self.finalize_current_line();
+		let caption = node
+			.children()
+			.find(|child| matches!(child.value(), Node::Element(child_elem) if child_elem.name() == "figcaption"))
+			.map(|child| collapse_whitespace(&Self::collect_text(child)))
+			.unwrap_or_default();
+		let label = element
+			.attr("aria-label")
+			.or_else(|| element.attr("aria-description"))
+			.or_else(|| element.attr("title"))
+			.map(collapse_whitespace)
+			.unwrap_or_default();
+		let offset = self.get_current_text_position();
+		if caption.is_empty() && !label.is_empty() {
+			self.current_line.push_str(&figure_text(&label));
+			self.finalize_current_line();
+		}
+		let description = if label.is_empty() { caption } else { label };
+		self.figures.push(ImageInfo { offset, alt_text: description });
+	}
+
+	/// An inline SVG's accessible name comes from its `<title>` child when it has no label attribute.
+	fn svg_title(node: NodeRef<'_, Node>) -> String {
+		node.children()
+			.find(|child| matches!(child.value(), Node::Element(child_elem) if child_elem.name() == "title"))
+			.map(|child| collapse_whitespace(&Self::collect_text(child)))
+			.unwrap_or_default()
+	}
+
 	fn get_element_text(node: NodeRef<'_, Node>, _document: &Html) -> String {
 		Self::collect_text(node)
 	}
@@ -693,6 +864,15 @@ impl ConverterOutput for HtmlToText {
 	fn get_separators(&self) -> &[SeparatorInfo] {
 		&self.separators
 	}
+	fn get_block_quotes(&self) -> &[BlockInfo] {
+		self.block_quotes.blocks()
+	}
+	fn get_code_blocks(&self) -> &[BlockInfo] {
+		self.code_blocks.blocks()
+	}
+	fn get_notes(&self) -> &[BlockInfo] {
+		self.notes.blocks()
+	}
 	fn get_lists(&self) -> &[ListInfo] {
 		&self.lists
 	}
@@ -767,6 +947,29 @@ mod tests {
 		assert_eq!(table_marker.length, expected_display_length);
 	}
 
+	#[test]
+	fn forced_breaks_and_pagebreak_spans_start_pages() {
+		let html = concat!(
+			"<html><body><p>Front</p>",
+			r#"<h1 style="page-break-before: always">One</h1>"#,
+			r#"<p>Text <span epub:type="pagebreak" title="2"></span>more</p>"#,
+			r#"<div class="chapter"><span role="doc-pagebreak" aria-label="3"></span><p>Two</p></div>"#,
+			r#"<p style="break-after: page">Last</p>"#,
+			"</body></html>"
+		);
+		let rules = PageBreakRules::parse(".chapter { break-before: page }");
+		let mut converter = HtmlToText::new().with_page_break_rules(rules);
+		assert!(converter.convert(html, HtmlSourceMode::NativeHtml));
+		let text = converter.get_text();
+		assert_eq!(text, "Front\nOne\nText more\nTwo\nLast");
+		let pages: Vec<(&str, &str)> = converter
+			.get_page_breaks()
+			.iter()
+			.map(|page| (text[page.offset..].lines().next().unwrap(), page.text.as_str()))
+			.collect();
+		assert_eq!(pages, [("One", ""), ("more", "2"), ("Two", "3")]);
+	}
+
 	#[test]
 	fn test_title_and_text() {
 		let html = "<html><head><title>  Hello   World </title></head><body><p>Hi</p></body></html>";
@@ -776,6 +979,29 @@ mod tests {
 		assert_eq!(converter.get_text(), "Hi");
 	}
 
+	#[test]
+	fn images_figures_and_svg_titles_are_shown_inline() {
+		let html = concat!(
+			r#"<html><body><p>A <img alt="cat"> and <img src="x.png"></p>"#,
+			r#"<figure><img alt="Chart"><figcaption>Sales by year</figcaption></figure>"#,
+			"<svg><title>Logo</title><text>ignored</text></svg>",
+			"</body></html>"
+		);
+		let mut converter = HtmlToText::new();
+		assert!(converter.convert(html, HtmlSourceMode::NativeHtml));
+		let text = converter.get_text();
+		let lines: Vec<&str> = text.lines().collect();
+		assert_eq!(lines, ["A [Image: cat] and [Image]", "[Image: Chart]", "Sales by year", "[Image: Logo]"]);
+		assert_eq!(converter.get_title(), "", "an SVG title is not the document title");
+		let images: Vec<&str> = converter.get_images().iter().map(|image| image.alt_text.as_str()).collect();
+		assert_eq!(images, ["cat", "", "Chart", "Logo"]);
+		assert_eq!(converter.get_images()[0].offset, 2);
+		let figures = converter.get_figures();
+		assert_eq!(figures.len(), 1);
+		assert_eq!(figures[0].alt_text, "Sales by year");
+		assert_eq!(figures[0].offset, converter.get_images()[2].offset);
+	}
+
 	#[test]
 	fn test_link_collection() {
 		let html = "<html><body><a href=\"https://example.com\">Hello   world</a></body></html>";
@@ -873,6 +1099,10 @@ mod tests {
 		assert_eq!(items[0].level, 1);
 		assert_eq!(items[0].text, "First");
 		assert_eq!(items[1].text, "Second");
+		// Each item runs up to the next one, and the last one stays within the list.
+		assert_eq!(items[0].offset + items[0].length, items[1].offset);
+		assert!(items[1].length > 0);
+		assert!(items[1].offset + items[1].length <= lists[0].offset + lists[0].length);
 	}
 
 	#[test]
@@ -910,6 +1140,42 @@ mod tests {
 		assert_eq!(converter.get_separators().len(), 1);
 	}
 
+	#[test]
+	fn blockquote_and_pre_produce_blocks() {
+		let html = concat!(
+			"<html><body><p>Intro</p>",
+			"<blockquote><p>Outer quote</p><blockquote><p>Inner</p></blockquote></blockquote>",
+			"<pre>fn main() {}\n</pre>",
+			"</body></html>"
+		);
+		let mut converter = HtmlToText::new();
+		assert!(converter.convert(html, HtmlSourceMode::NativeHtml));
+		let quotes = converter.get_block_quotes();
+		assert_eq!(quotes.len(), 1, "nested quotes count once");
+		assert_eq!(quotes[0].offset, 6);
+		assert_eq!(quotes[0].text, "Outer quote");
+		let code = converter.get_code_blocks();
+		assert_eq!(code.len(), 1);
+		assert_eq!(code[0].text, "fn main() {}");
+		assert!(converter.get_text()[code[0].offset..].starts_with("fn main()"));
+	}
+
+	#[test]
+	fn footnote_asides_are_recorded_as_notes() {
+		let html = concat!(
+			"<html><body><p>Text</p>",
+			"<aside role=\"doc-footnote\"><p>A note.</p></aside>",
+			"<aside epub:type=\"sidebar\"><p>Aside</p></aside>",
+			"</body></html>"
+		);
+		let mut converter = HtmlToText::new();
+		assert!(converter.convert(html, HtmlSourceMode::NativeHtml));
+		let notes = converter.get_notes();
+		assert_eq!(notes.len(), 1);
+		assert_eq!(notes[0].offset, 5);
+		assert_eq!(notes[0].text, "A note.");
+	}
+
 	#[test]
 	fn nested_ul_increments_list_level() {
 		let html = "<html><body><ul><li>Outer<ul><li>Inner</li></ul></li></ul></body></html>";
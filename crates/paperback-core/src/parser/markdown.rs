@@ -1,7 +1,7 @@
-use std::fs;
+use std::{collections::HashMap, fs};
 
 use anyhow::{Context, Result};
-use pulldown_cmark::{Event, Options, Parser as MarkdownParserImpl, Tag, html::push_html};
+use pulldown_cmark::{Event, Options, Parser as MarkdownParserImpl, Tag, TagEnd, html::push_html};
 
 use crate::{
 	document::{Document, DocumentBuffer, ParserContext, ParserFlags},
@@ -11,7 +11,7 @@ use crate::{
 		util::{path::extract_title_from_path, toc::build_toc_from_headings},
 	},
 	t,
-	util::encoding::convert_to_utf8,
+	util::encoding::decode_text,
 };
 
 /// Converts Markdown to HTML with an empty `<span id="pb-block-N"></span>` before each block.
@@ -19,27 +19,145 @@ use crate::{
 /// The anchors produce no text but give every block a stable id, so a position
 /// in the converted text can be mapped back to a `#fragment` when the document
 /// is shown in a web view.
+///
+/// GitHub-flavored extensions are rendered for reading as text: task list items
+/// start with `[ ]` or `[x]`, headings get slugified ids so `#section` links
+/// resolve, and footnote references link to their definitions, which are moved
+/// to the end of the document after a separator.
 #[must_use]
 pub fn markdown_to_html(markdown_text: &str) -> String {
-	let mut options = Options::empty();
-	options.insert(Options::ENABLE_TABLES);
-	let parser = MarkdownParserImpl::new_ext(markdown_text, options);
+	let parser = MarkdownParserImpl::new_ext(markdown_text, markdown_options());
 	let mut block_counter = 0usize;
-	let events = parser.flat_map(|event| {
-		let anchor = match &event {
-			Event::Start(Tag::Paragraph | Tag::Heading { .. } | Tag::Item | Tag::BlockQuote(_) | Tag::CodeBlock(_)) => {
-				block_counter += 1;
-				Some(Event::Html(format!("<span id=\"pb-block-{block_counter}\"></span>").into()))
-			}
-			_ => None,
-		};
-		anchor.into_iter().chain(std::iter::once(event))
-	});
+	let mut events: Vec<Event<'_>> = parser
+		.flat_map(|event| {
+			let anchor = match &event {
+				Event::Start(
+					Tag::Paragraph | Tag::Heading { .. } | Tag::Item | Tag::BlockQuote(_) | Tag::CodeBlock(_),
+				) => {
+					block_counter += 1;
+					Some(Event::Html(format!("<span id=\"pb-block-{block_counter}\"></span>").into()))
+				}
+				_ => None,
+			};
+			let event = match event {
+				Event::TaskListMarker(checked) => Event::Text(if checked { "[x] " } else { "[ ] " }.into()),
+				event => event,
+			};
+			anchor.into_iter().chain(std::iter::once(event))
+		})
+		.collect();
+	assign_heading_ids(&mut events);
+	let events = move_footnotes_to_end(events);
 	let mut html_content = String::new();
-	push_html(&mut html_content, events);
+	push_html(&mut html_content, events.into_iter());
 	html_content
 }
 
+fn markdown_options() -> Options {
+	Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_FOOTNOTES
+}
+
+/// Gives every heading without an explicit id a GitHub-style slug of its text,
+/// numbering repeats as `slug-1`, `slug-2`, and so on.
+fn assign_heading_ids(events: &mut [Event<'_>]) {
+	let mut seen: HashMap<String, usize> = HashMap::new();
+	let headings: Vec<usize> = events
+		.iter()
+		.enumerate()
+		.filter(|(_, event)| matches!(event, Event::Start(Tag::Heading { id: None, .. })))
+		.map(|(index, _)| index)
+		.collect();
+	for index in headings {
+		let mut heading_text = String::new();
+		for event in &events[index + 1..] {
+			match event {
+				Event::End(TagEnd::Heading(_)) => break,
+				Event::Text(text) | Event::Code(text) => heading_text.push_str(text),
+				_ => {}
+			}
+		}
+		let slug = heading_slug(&heading_text);
+		if slug.is_empty() {
+			continue;
+		}
+		let count = seen.entry(slug.clone()).or_insert(0);
+		let unique = if *count == 0 { slug } else { format!("{slug}-{count}") };
+		*count += 1;
+		if let Event::Start(Tag::Heading { id, .. }) = &mut events[index] {
+			*id = Some(unique.into());
+		}
+	}
+}
+
+fn heading_slug(text: &str) -> String {
+	text.trim()
+		.chars()
+		.filter_map(|c| match c {
+			' ' => Some('-'),
+			'-' | '_' => Some(c),
+			c if c.is_alphanumeric() => Some(c),
+			_ => None,
+		})
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+/// Replaces footnote references with numbered links and appends the definitions,
+/// numbered in order of first reference, after a separator at the end.
+fn move_footnotes_to_end(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+	let mut numbers: HashMap<String, usize> = HashMap::new();
+	for event in &events {
+		if let Event::FootnoteReference(label) = event {
+			let next = numbers.len() + 1;
+			numbers.entry(label.to_lowercase()).or_insert(next);
+		}
+	}
+	let mut body = Vec::with_capacity(events.len());
+	let mut definitions: Vec<(usize, Vec<Event<'_>>)> = Vec::new();
+	let mut events = events.into_iter();
+	while let Some(event) = events.next() {
+		match event {
+			Event::FootnoteReference(label) => {
+				let number = numbers[&label.to_lowercase()];
+				body.push(Event::InlineHtml(format!("<a href=\"#footnote-{number}\">[{number}]</a>").into()));
+			}
+			Event::Start(Tag::FootnoteDefinition(label)) => {
+				let next = numbers.len() + 1;
+				let number = *numbers.entry(label.to_lowercase()).or_insert(next);
+				let mut contents: Vec<Event<'_>> = events
+					.by_ref()
+					.take_while(|event| !matches!(event, Event::End(TagEnd::FootnoteDefinition)))
+					.map(|event| match event {
+						Event::FootnoteReference(label) => {
+							let number = numbers.get(&label.to_lowercase()).copied().unwrap_or(number);
+							Event::InlineHtml(format!("<a href=\"#footnote-{number}\">[{number}]</a>").into())
+						}
+						event => event,
+					})
+					.collect();
+				let label_at = contents
+					.iter()
+					.position(|event| matches!(event, Event::Start(Tag::Paragraph)))
+					.map_or(0, |index| index + 1);
+				contents.insert(label_at, Event::Text(format!("[{number}] ").into()));
+				definitions.push((number, contents));
+			}
+			event => body.push(event),
+		}
+	}
+	if definitions.is_empty() {
+		return body;
+	}
+	definitions.sort_by_key(|(number, _)| *number);
+	body.push(Event::Rule);
+	for (number, contents) in definitions {
+		body.push(Event::Html(format!("<div id=\"footnote-{number}\">").into()));
+		body.extend(contents);
+		body.push(Event::Html("</div>".into()));
+	}
+	body
+}
+
 /// Returns the byte offset in `markdown_text` where the 1-based `block_index`
 /// block begins, using the same block numbering as [`markdown_to_html`].
 ///
@@ -47,9 +165,7 @@ pub fn markdown_to_html(markdown_text: &str) -> String {
 /// location in the original Markdown source.
 #[must_use]
 pub fn block_source_offset(markdown_text: &str, block_index: usize) -> Option<usize> {
-	let mut options = Options::empty();
-	options.insert(Options::ENABLE_TABLES);
-	let parser = MarkdownParserImpl::new_ext(markdown_text, options).into_offset_iter();
+	let parser = MarkdownParserImpl::new_ext(markdown_text, markdown_options()).into_offset_iter();
 	let mut block_counter = 0usize;
 	for (event, range) in parser {
 		if matches!(
@@ -70,6 +186,7 @@ pub struct MarkdownParser;
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::document::{Marker, MarkerType};
 
 	#[test]
 	fn markdown_to_html_injects_block_anchors() {
@@ -104,6 +221,64 @@ mod tests {
 		assert_eq!(ids.get("pb-block-3"), Some(&text.find("Second").unwrap()), "ids: {ids:?} text: {text:?}");
 		assert!(!text.contains("pb-block"), "anchors must not leak into text: {text:?}");
 	}
+
+	fn markers_of(doc: &Document, mtype: MarkerType) -> Vec<&Marker> {
+		doc.buffer.markers.iter().filter(|marker| marker.mtype == mtype).collect()
+	}
+
+	#[test]
+	fn pipe_tables_are_column_aligned_with_a_table_marker() {
+		let doc = markdown_document("| Name | Age |\n|---|---|\n| Alice | 30 |\n| Bo | 7 |\n", true).unwrap();
+		let text = &doc.buffer.content;
+		let table = "Name   Age\nAlice  30\nBo     7\n";
+		let start = text.find(table).unwrap_or_else(|| panic!("aligned table missing: {text:?}"));
+		let tables = markers_of(&doc, MarkerType::Table);
+		assert_eq!(tables.len(), 1);
+		assert_eq!(tables[0].position, start);
+		assert_eq!(tables[0].length, table.len());
+	}
+
+	#[test]
+	fn task_list_items_keep_their_checkboxes_and_list_item_markers() {
+		let doc = markdown_document("- [ ] Buy milk\n- [x] Walk the dog\n", true).unwrap();
+		let text = &doc.buffer.content;
+		let items = markers_of(&doc, MarkerType::ListItem);
+		assert_eq!(items.len(), 2, "text: {text:?}");
+		let first_line = |position: usize| text[position..].lines().next().unwrap_or_default();
+		assert!(first_line(items[0].position).ends_with("[ ] Buy milk"), "text: {text:?}");
+		assert!(first_line(items[1].position).ends_with("[x] Walk the dog"), "text: {text:?}");
+	}
+
+	#[test]
+	fn headings_register_slugified_ids_for_fragment_links() {
+		let source = "# Getting Started!\n\nSee [setup](#getting-started).\n\n## Notes\n\nOne.\n\n## Notes\n\nTwo.\n";
+		let doc = markdown_document(source, true).unwrap();
+		let text = &doc.buffer.content;
+		let ids = &doc.id_positions;
+		assert_eq!(ids.get("getting-started"), Some(&text.find("Getting Started!").unwrap()), "ids: {ids:?}");
+		assert_eq!(ids.get("notes"), Some(&text.find("Notes").unwrap()), "ids: {ids:?}");
+		assert_eq!(ids.get("notes-1"), Some(&text.rfind("Notes").unwrap()), "ids: {ids:?}");
+		let links = markers_of(&doc, MarkerType::Link);
+		assert_eq!(links.len(), 1);
+		assert_eq!(links[0].reference, "#getting-started");
+	}
+
+	#[test]
+	fn footnotes_link_to_definitions_appended_at_the_end() {
+		let source = "A claim[^src] here.\n\n[^src]: The source.\n\nMore text.\n";
+		let doc = markdown_document(source, true).unwrap();
+		let text = &doc.buffer.content;
+		let links = markers_of(&doc, MarkerType::Link);
+		assert_eq!(links.len(), 1, "text: {text:?}");
+		assert_eq!(links[0].position, text.find("[1]").unwrap());
+		assert_eq!(links[0].text, "[1]");
+		assert_eq!(links[0].reference, "#footnote-1");
+		let definition = text.find("[1] The source.").unwrap_or_else(|| panic!("definition missing: {text:?}"));
+		assert!(definition > text.find("More text.").unwrap(), "text: {text:?}");
+		assert_eq!(doc.id_positions.get("footnote-1"), Some(&definition));
+		let separators = markers_of(&doc, MarkerType::Separator);
+		assert!(separators.iter().any(|marker| marker.position < definition), "text: {text:?}");
+	}
 }
 
 impl Parser for MarkdownParser {
@@ -115,6 +290,10 @@ impl Parser for MarkdownParser {
 		&["md", "markdown", "mdx", "mdown", "mdwn", "mkd", "mkdn", "mkdown", "ronn"]
 	}
 
+	fn reads_encoding(&self) -> bool {
+		true
+	}
+
 	fn supported_flags(&self) -> ParserFlags {
 		ParserFlags::SUPPORTS_TOC | ParserFlags::SUPPORTS_LISTS
 	}
@@ -122,23 +301,26 @@ impl Parser for MarkdownParser {
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
 		let bytes = fs::read(&context.file_path)
 			.with_context(|| format!("Failed to open Markdown file '{}'", context.file_path))?;
-		let markdown_content = convert_to_utf8(&bytes);
-		let html_content = markdown_to_html(&markdown_content);
-		let mut converter = HtmlToText::with_render_tables_inline(context.render_tables_inline);
-		if !converter.convert(&html_content, HtmlSourceMode::Markdown) {
+		let markdown_content = decode_text(&bytes, context.encoding.as_deref());
+		let Some(doc) = markdown_document(&markdown_content, context.render_tables_inline) else {
 			// TRANSLATORS: Error shown when a Markdown file fails to convert to plain text; {} is the file path
 			anyhow::bail!(t("Failed to convert Markdown to text: {}").replace("{}", &context.file_path));
-		}
-		let title = extract_title_from_path(&context.file_path);
-		let text = converter.get_text();
-		let mut buffer = DocumentBuffer::with_content(text);
-		let id_positions = converter.get_id_positions().clone();
-		add_converter_markers(&mut buffer, &converter, 0);
-		let toc_items = build_toc_from_headings(converter.get_headings());
-		let mut doc = Document::new().with_title(title);
-		doc.set_buffer(buffer);
-		doc.toc_items = toc_items;
-		doc.id_positions = id_positions;
-		Ok(doc)
+		};
+		Ok(doc.with_title(extract_title_from_path(&context.file_path)))
+	}
+}
+
+fn markdown_document(markdown_content: &str, render_tables_inline: bool) -> Option<Document> {
+	let html_content = markdown_to_html(markdown_content);
+	let mut converter = HtmlToText::with_render_tables_inline(render_tables_inline);
+	if !converter.convert(&html_content, HtmlSourceMode::Markdown) {
+		return None;
 	}
+	let mut buffer = DocumentBuffer::with_content(converter.get_text());
+	add_converter_markers(&mut buffer, &converter, 0);
+	let mut doc = Document::new();
+	doc.set_buffer(buffer);
+	doc.toc_items = build_toc_from_headings(converter.get_headings());
+	doc.id_positions = converter.get_id_positions().clone();
+	Some(doc)
 }
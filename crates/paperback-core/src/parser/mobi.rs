@@ -12,6 +12,7 @@ use crate::{
 	document::{Document, DocumentBuffer, ParserContext, ParserFlags, TocItem},
 	parser::{
 		Parser, add_converter_markers,
+		error::ParserError,
 		html_to_text::{HtmlSourceMode, HtmlToText},
 		util::{path::extract_title_from_path, toc::build_toc_from_headings},
 	},
@@ -69,6 +70,11 @@ impl Parser for MobiParser {
 			anyhow::bail!(t("Invalid Record 0"));
 		}
 		let compression = u16::from_be_bytes([rec0[0], rec0[1]]);
+		// PalmDOC header: a non-zero encryption type means the text records are DRM-protected.
+		if u16::from_be_bytes([rec0[12], rec0[13]]) != 0 {
+			// TRANSLATORS: Error shown when a MOBI/AZW book is protected by DRM
+			return Err(ParserError::Encrypted(t("This book is protected by DRM and can't be opened.")).into());
+		}
 		let mobi_header_offset = 16;
 		if mobi_header_offset + 8 > rec0.len() {
 			// TRANSLATORS: Error shown when a MOBI file is missing its MOBI header
@@ -269,8 +275,13 @@ impl Parser for MobiParser {
 						content.extend_from_slice(&decoded);
 					}
 				}
-				// TRANSLATORS: Error shown when a MOBI file uses an unrecognized compression mode; {} is the numeric mode value
-				other => anyhow::bail!(t("Unsupported compression mode ({})").replace("{}", &other.to_string())),
+				other => {
+					return Err(ParserError::UnsupportedVariant(
+						// TRANSLATORS: Error shown when a MOBI file uses an unrecognized compression mode; {} is the numeric mode value
+						t("Unsupported compression mode ({})").replace("{}", &other.to_string()),
+					)
+					.into());
+				}
 			}
 		}
 
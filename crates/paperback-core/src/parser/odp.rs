@@ -1,18 +1,17 @@
-use std::{collections::HashMap, fs, fs::File, io::BufReader};
+use std::{collections::HashMap, fs, fs::File};
 
 use anyhow::{Context, Result};
 use roxmltree::{Document as XmlDocument, Node, NodeType};
-use zip::ZipArchive;
 
 use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags},
 	parser::{
-		Parser,
+		Parser, append_speaker_notes, slide_marker_text,
 		util::{path::extract_title_from_path, xml::collect_element_text},
 	},
 	t,
 	types::LinkInfo,
-	util::zip::read_zip_entry_by_name,
+	util::zip::{OpenedZip, open_zip, read_zip_entry_by_name},
 };
 
 pub struct OdpParser;
@@ -33,8 +32,8 @@ impl Parser for OdpParser {
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
 		let file = File::open(&context.file_path)
 			.with_context(|| format!("Failed to open ODP file '{}'", context.file_path))?;
-		let mut archive = ZipArchive::new(BufReader::new(file))
-			.with_context(|| format!("Failed to read ODP as zip '{}'", context.file_path))?;
+		let OpenedZip { mut archive, salvaged } =
+			open_zip(file).with_context(|| format!("Failed to read ODP as zip '{}'", context.file_path))?;
 		let content_str = read_zip_entry_by_name(&mut archive, "content.xml")
 			.context("ODP file does not contain content.xml or it is empty")?;
 		let xml_doc = XmlDocument::parse(&content_str).context("Invalid ODP content.xml")?;
@@ -45,29 +44,12 @@ impl Parser for OdpParser {
 			// TRANSLATORS: Error shown when an ODP presentation file has no pages/slides
 			anyhow::bail!(t("ODP file does not contain any pages"));
 		}
-		for (index, page_node) in pages.iter().enumerate() {
-			let slide_start = buffer.current_position();
-			let mut links = Vec::new();
-			let slide_text = get_page_text(*page_node, &mut links, slide_start);
-			if !slide_text.trim().is_empty() {
-				buffer.append(&slide_text);
-				if !buffer.content.ends_with('\n') {
-					buffer.append("\n");
-				}
-				buffer.add_marker(
-					Marker::new(MarkerType::PageBreak, slide_start).with_text(format!("Slide {}", index + 1)),
-				);
-				for link in links {
-					buffer.add_marker(
-						Marker::new(MarkerType::Link, link.offset).with_text(link.text).with_reference(link.reference),
-					);
-				}
-			}
-		}
+		append_pages(&mut buffer, &pages, context.include_speaker_notes);
 		let title = extract_title_from_path(&context.file_path);
 		let mut document = Document::new().with_title(title);
 		document.set_buffer(buffer);
 		document.id_positions = id_positions;
+		document.damaged = salvaged;
 		Ok(document)
 	}
 }
@@ -98,25 +80,7 @@ impl Parser for FodpParser {
 			// TRANSLATORS: Error shown when a flat-XML ODP presentation file has no pages/slides
 			anyhow::bail!(t("FODP file does not contain any pages"));
 		}
-		for (index, page_node) in pages.iter().enumerate() {
-			let slide_start = buffer.current_position();
-			let mut links = Vec::new();
-			let slide_text = get_page_text(*page_node, &mut links, slide_start);
-			if !slide_text.trim().is_empty() {
-				buffer.append(&slide_text);
-				if !buffer.content.ends_with('\n') {
-					buffer.append("\n");
-				}
-				buffer.add_marker(
-					Marker::new(MarkerType::PageBreak, slide_start).with_text(format!("Slide {}", index + 1)),
-				);
-				for link in links {
-					buffer.add_marker(
-						Marker::new(MarkerType::Link, link.offset).with_text(link.text).with_reference(link.reference),
-					);
-				}
-			}
-		}
+		append_pages(&mut buffer, &pages, context.include_speaker_notes);
 		let title = extract_title_from_path(&context.file_path);
 		let mut document = Document::new().with_title(title);
 		document.set_buffer(buffer);
@@ -140,6 +104,72 @@ fn collect_pages<'a, 'input>(node: Node<'a, 'input>, pages: &mut Vec<Node<'a, 'i
 	}
 }
 
+/// Appends every page (slide) to `buffer` with a page-break marker carrying the slide title, followed
+/// by its presenter notes when `include_speaker_notes` is set.
+fn append_pages(buffer: &mut DocumentBuffer, pages: &[Node], include_speaker_notes: bool) {
+	for (index, page_node) in pages.iter().enumerate() {
+		let slide_start = buffer.current_position();
+		let mut links = Vec::new();
+		let slide_text = get_page_text(*page_node, &mut links, slide_start);
+		let has_text = !slide_text.trim().is_empty();
+		if has_text {
+			buffer.append(&slide_text);
+			if !buffer.content.ends_with('\n') {
+				buffer.append("\n");
+			}
+		}
+		if include_speaker_notes {
+			append_speaker_notes(buffer, &get_page_notes(*page_node));
+		}
+		if !has_text && buffer.current_position() == slide_start {
+			// Keep image-only slides reachable: give the marker a line of its own.
+			buffer.append("\n");
+		}
+		let title = get_page_title(*page_node);
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, slide_start).with_text(slide_marker_text(
+			index + 1,
+			&title,
+			has_text,
+		)));
+		for link in links {
+			buffer.add_marker(
+				Marker::new(MarkerType::Link, link.offset).with_text(link.text).with_reference(link.reference),
+			);
+		}
+	}
+}
+
+/// Text of the page's title frame (`presentation:class="title"`), if it has one.
+fn get_page_title(page_node: Node) -> String {
+	page_node
+		.descendants()
+		.find(|node| {
+			node.is_element()
+				&& node.tag_name().name() == "frame"
+				&& node.attributes().any(|attr| attr.name() == "class" && attr.value() == "title")
+		})
+		.map(collect_element_text)
+		.unwrap_or_default()
+}
+
+/// Paragraphs of the page's `<presentation:notes>` element, one per line.
+fn get_page_notes(page_node: Node) -> String {
+	let Some(notes) = page_node.children().find(|child| is_notes_node(*child)) else {
+		return String::new();
+	};
+	notes
+		.descendants()
+		.filter(|node| node.is_element() && node.tag_name().name() == "p")
+		.map(collect_element_text)
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+fn is_notes_node(node: Node) -> bool {
+	node.is_element() && node.tag_name().name() == "notes"
+}
+
 fn get_page_text(page_node: Node, links: &mut Vec<LinkInfo>, slide_start: usize) -> String {
 	let mut text = String::new();
 	traverse_page(page_node, &mut text, links, slide_start);
@@ -149,6 +179,10 @@ fn get_page_text(page_node: Node, links: &mut Vec<LinkInfo>, slide_start: usize)
 fn traverse_page(node: Node, text: &mut String, links: &mut Vec<LinkInfo>, slide_start: usize) {
 	if node.node_type() == NodeType::Element {
 		let tag_name = node.tag_name().name();
+		if tag_name == "notes" {
+			// Presenter notes are appended after the slide body, not inline.
+			return;
+		}
 		if tag_name == "a" {
 			if let Some(href) = node.attribute("href") {
 				let link_offset = slide_start + text.len();
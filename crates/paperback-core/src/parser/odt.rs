@@ -1,8 +1,7 @@
-use std::{collections::HashMap, fs, fs::File, io::BufReader};
+use std::{collections::HashMap, fs, fs::File};
 
 use anyhow::{Context, Result};
 use roxmltree::{Document as XmlDocument, Node, NodeType};
-use zip::ZipArchive;
 
 use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, format_marker_types},
@@ -12,10 +11,10 @@ use crate::{
 		util::{
 			path::extract_title_from_path,
 			toc::{build_toc_from_buffer, heading_level_to_marker_type},
-			xml::collect_element_text,
+			xml::{attribute_by_local_name, collect_element_text},
 		},
 	},
-	util::zip::read_zip_entry_by_name,
+	util::zip::{OpenedZip, open_zip, read_zip_entry_by_name},
 };
 
 pub struct OdtParser;
@@ -36,20 +35,34 @@ impl Parser for OdtParser {
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
 		let file = File::open(&context.file_path)
 			.with_context(|| format!("Failed to open ODT file '{}'", context.file_path))?;
-		let mut archive = ZipArchive::new(BufReader::new(file))
-			.with_context(|| format!("Failed to read ODT as zip '{}'", context.file_path))?;
+		let OpenedZip { mut archive, salvaged } =
+			open_zip(file).with_context(|| format!("Failed to read ODT as zip '{}'", context.file_path))?;
 		let content_str = read_zip_entry_by_name(&mut archive, "content.xml")
 			.context("ODT file does not contain content.xml or it is empty")?;
 		let xml_doc = XmlDocument::parse(&content_str).context("Invalid ODT content.xml")?;
+		// Named styles such as "Heading 2" live in styles.xml; a file without it still opens.
+		let styles_str = read_zip_entry_by_name(&mut archive, "styles.xml").unwrap_or_default();
+		let styles_doc = XmlDocument::parse(&styles_str).ok();
+		let mut style_roots = vec![xml_doc.root()];
+		style_roots.extend(styles_doc.as_ref().map(XmlDocument::root));
 		let format_style_map = build_odt_format_style_map(xml_doc.root());
+		let heading_style_map = build_odt_heading_style_map(&style_roots);
 		let mut buffer = DocumentBuffer::new();
 		let mut id_positions = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut id_positions, context.render_tables_inline, &format_style_map);
+		traverse(
+			xml_doc.root(),
+			&mut buffer,
+			&mut id_positions,
+			context.render_tables_inline,
+			&format_style_map,
+			&heading_style_map,
+		);
 		let title = extract_title_from_path(&context.file_path);
 		let toc_items = build_toc_from_buffer(&buffer);
 		let mut document = Document::new().with_title(title);
 		document.set_buffer(buffer);
 		document.id_positions = id_positions;
+		document.damaged = salvaged;
 		document.toc_items = toc_items;
 		Ok(document)
 	}
@@ -75,9 +88,17 @@ impl Parser for FodtParser {
 			.with_context(|| format!("Failed to open FODT file '{}'", context.file_path))?;
 		let xml_doc = XmlDocument::parse(&content_str).context("Invalid FODT document")?;
 		let format_style_map = build_odt_format_style_map(xml_doc.root());
+		let heading_style_map = build_odt_heading_style_map(&[xml_doc.root()]);
 		let mut buffer = DocumentBuffer::new();
 		let mut id_positions = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut id_positions, context.render_tables_inline, &format_style_map);
+		traverse(
+			xml_doc.root(),
+			&mut buffer,
+			&mut id_positions,
+			context.render_tables_inline,
+			&format_style_map,
+			&heading_style_map,
+		);
 		let title = extract_title_from_path(&context.file_path);
 		let toc_items = build_toc_from_buffer(&buffer);
 		let mut document = Document::new().with_title(title);
@@ -121,17 +142,72 @@ fn build_odt_format_style_map(root: Node) -> HashMap<String, (bool, bool, bool)>
 	map
 }
 
+/// Builds a paragraph style-name → heading level map from every `<style:style
+/// style:family="paragraph">` under `roots` (content.xml and styles.xml). LibreOffice often writes
+/// a heading as a `<text:p>` whose automatic style (say "P7") has "Heading 2" as its parent, so each
+/// style's parent chain is followed until a style with a level turns up: either a
+/// `style:default-outline-level` attribute or one of the built-in "Heading N" styles.
+fn build_odt_heading_style_map(roots: &[Node]) -> HashMap<String, i32> {
+	let mut styles: HashMap<&str, (Option<&str>, Option<i32>)> = HashMap::new();
+	for root in roots {
+		for node in root.descendants() {
+			if !node.is_element()
+				|| node.tag_name().name() != "style"
+				|| attribute_by_local_name(node, "family") != Some("paragraph")
+			{
+				continue;
+			}
+			let Some(name) = attribute_by_local_name(node, "name") else { continue };
+			let level = attribute_by_local_name(node, "default-outline-level")
+				.and_then(|value| value.parse::<i32>().ok())
+				.or_else(|| heading_style_level(name))
+				.or_else(|| attribute_by_local_name(node, "display-name").and_then(heading_style_level))
+				.filter(|level| (1..=10).contains(level));
+			styles.insert(name, (attribute_by_local_name(node, "parent-style-name"), level));
+		}
+	}
+	let mut map = HashMap::new();
+	for &name in styles.keys() {
+		let mut current = Some(name);
+		// The hop limit stops a parent cycle in a damaged file from looping forever.
+		for _ in 0..styles.len() {
+			let Some((parent, level)) = current.and_then(|style| styles.get(style)) else { break };
+			if let Some(level) = level {
+				map.insert(name.to_string(), *level);
+				break;
+			}
+			current = *parent;
+		}
+	}
+	map
+}
+
+/// The level of a built-in heading style, whose internal name is "Heading_20_N" ("_20_" being an
+/// encoded space) and whose display name is "Heading N".
+fn heading_style_level(name: &str) -> Option<i32> {
+	name.strip_prefix("Heading_20_").or_else(|| name.strip_prefix("Heading ")).and_then(|n| n.parse().ok())
+}
+
 fn traverse(
 	node: Node,
 	buffer: &mut DocumentBuffer,
 	id_positions: &mut HashMap<String, usize>,
 	render_tables_inline: bool,
 	format_style_map: &HashMap<String, (bool, bool, bool)>,
+	heading_style_map: &HashMap<String, i32>,
 ) {
 	if node.node_type() == NodeType::Element {
 		let tag_name = node.tag_name().name();
-		if tag_name == "h" {
-			let level = node.attribute("outline-level").and_then(|s| s.parse::<i32>().ok()).unwrap_or(1);
+		// Some exporters put `text:outline-level` on a plain paragraph instead of using `text:h`.
+		let outline_level = attribute_by_local_name(node, "outline-level").and_then(|s| s.parse::<i32>().ok());
+		let style_level =
+			attribute_by_local_name(node, "style-name").and_then(|style| heading_style_map.get(style).copied());
+		let heading_level = match tag_name {
+			"h" => Some(outline_level.or(style_level).unwrap_or(1)),
+			"p" => outline_level.or(style_level),
+			_ => None,
+		};
+		if let Some(level) = heading_level {
 			let heading_offset = buffer.current_position();
 			let heading_text = collect_element_text(node);
 			if !heading_text.is_empty() {
@@ -143,7 +219,7 @@ fn traverse(
 			return; // Don't traverse children, we already got the text
 		}
 		if tag_name == "p" {
-			traverse_children(node, buffer, id_positions, render_tables_inline, format_style_map);
+			traverse_children(node, buffer, id_positions, render_tables_inline, format_style_map, heading_style_map);
 			buffer.append("\n");
 			return;
 		}
@@ -171,7 +247,7 @@ fn traverse(
 			&& (bold || italic || underline)
 		{
 			let start = buffer.current_position();
-			traverse_children(node, buffer, id_positions, render_tables_inline, format_style_map);
+			traverse_children(node, buffer, id_positions, render_tables_inline, format_style_map, heading_style_map);
 			let end = buffer.current_position();
 			if end > start {
 				for kind in format_marker_types(bold, italic, underline) {
@@ -190,7 +266,7 @@ fn traverse(
 		}
 		return;
 	}
-	traverse_children(node, buffer, id_positions, render_tables_inline, format_style_map);
+	traverse_children(node, buffer, id_positions, render_tables_inline, format_style_map, heading_style_map);
 }
 
 fn traverse_children(
@@ -199,9 +275,10 @@ fn traverse_children(
 	id_positions: &mut HashMap<String, usize>,
 	render_tables_inline: bool,
 	format_style_map: &HashMap<String, (bool, bool, bool)>,
+	heading_style_map: &HashMap<String, i32>,
 ) {
 	for child in node.children() {
-		traverse(child, buffer, id_positions, render_tables_inline, format_style_map);
+		traverse(child, buffer, id_positions, render_tables_inline, format_style_map, heading_style_map);
 	}
 }
 
@@ -276,10 +353,11 @@ mod tests {
 
 	use roxmltree::Document as XmlDocument;
 
-	use super::{build_odt_format_style_map, traverse};
+	use super::{build_odt_format_style_map, build_odt_heading_style_map, traverse};
 	use crate::{
-		document::{DocumentBuffer, MarkerType},
-		util::text::display_len,
+		document::{DocumentBuffer, MarkerType, ParserContext},
+		parser::parse_document,
+		util::{text::display_len, zip::testing::TempZip},
 	};
 
 	/// OFF mode: an ODT table emits a `"[Table]: <first row>"` placeholder. The second cell holds a
@@ -292,7 +370,7 @@ mod tests {
 		let mut buffer = DocumentBuffer::new();
 		let mut id_positions = HashMap::new();
 		let format_style_map = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut id_positions, false, &format_style_map);
+		traverse(xml_doc.root(), &mut buffer, &mut id_positions, false, &format_style_map, &HashMap::new());
 
 		assert_eq!(buffer.content, "[Table]: Kop \u{1D11E}\n");
 		let table_marker = buffer.markers.iter().find(|m| m.mtype == MarkerType::Table).expect("Table marker");
@@ -313,7 +391,7 @@ mod tests {
 		for inline in [false, true] {
 			let mut buffer = DocumentBuffer::new();
 			let mut id_positions = HashMap::new();
-			traverse(xml_doc.root(), &mut buffer, &mut id_positions, inline, &format_style_map);
+			traverse(xml_doc.root(), &mut buffer, &mut id_positions, inline, &format_style_map, &HashMap::new());
 			let table_marker = buffer.markers.iter().find(|m| m.mtype == MarkerType::Table).expect("Table marker");
 			assert_eq!(
 				id_positions.get("anchor1"),
@@ -331,7 +409,7 @@ mod tests {
 		let mut buffer = DocumentBuffer::new();
 		let mut id_positions = HashMap::new();
 		let format_style_map = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut id_positions, true, &format_style_map);
+		traverse(xml_doc.root(), &mut buffer, &mut id_positions, true, &format_style_map, &HashMap::new());
 
 		assert_eq!(buffer.content, "Kop\t\u{1D11E}\n");
 		let table_marker = buffer.markers.iter().find(|m| m.mtype == MarkerType::Table).expect("Table marker");
@@ -351,12 +429,80 @@ mod tests {
 	fn traverse_fixture(xml: &str) -> DocumentBuffer {
 		let xml_doc = XmlDocument::parse(xml).expect("valid xml");
 		let format_style_map = build_odt_format_style_map(xml_doc.root());
+		let heading_style_map = build_odt_heading_style_map(&[xml_doc.root()]);
 		let mut buffer = DocumentBuffer::new();
 		let mut id_positions = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut id_positions, false, &format_style_map);
+		traverse(xml_doc.root(), &mut buffer, &mut id_positions, false, &format_style_map, &heading_style_map);
 		buffer
 	}
 
+	fn heading_levels(buffer: &DocumentBuffer) -> Vec<(String, i32)> {
+		buffer.markers.iter().filter(|m| m.level > 0).map(|m| (m.text.clone(), m.level)).collect()
+	}
+
+	const STYLES_XML: &str = r#"<office:document-styles xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"><office:styles>
+<style:style style:name="Heading" style:family="paragraph" style:parent-style-name="Standard"/>
+<style:style style:name="Heading_20_1" style:display-name="Heading 1" style:family="paragraph" style:parent-style-name="Heading" style:default-outline-level="1"/>
+<style:style style:name="Heading_20_2" style:display-name="Heading 2" style:family="paragraph" style:parent-style-name="Heading" style:default-outline-level="2"/>
+<style:style style:name="Kapitel" style:family="paragraph" style:parent-style-name="Heading" style:default-outline-level="3"/>
+<style:style style:name="Text_20_body" style:display-name="Text body" style:family="paragraph" style:parent-style-name="Standard"/>
+</office:styles></office:document-styles>"#;
+
+	/// The shape LibreOffice saves: direct formatting on a heading turns it into a `text:p` with an
+	/// automatic style whose parent is the real heading style, defined over in styles.xml.
+	const CONTENT_XML: &str = r##"<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><office:automatic-styles><style:style style:name="P1" style:family="paragraph" style:parent-style-name="Heading_20_1"><style:text-properties fo:color="#000080" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0"/></style:style><style:style style:name="P7" style:family="paragraph" style:parent-style-name="Heading_20_2"/><style:style style:name="P8" style:family="paragraph" style:parent-style-name="Kapitel"/><style:style style:name="P9" style:family="paragraph" style:parent-style-name="Text_20_body"/></office:automatic-styles><office:body><office:text><text:p text:style-name="P1">The Voyage</text:p><text:p text:style-name="P9">It began at sea.</text:p><text:p text:style-name="P7">Departure</text:p><text:p text:style-name="P8">Der Hafen</text:p><text:h text:style-name="Heading_20_2" text:outline-level="2">Arrival</text:h><text:p text:style-name="Text_20_body" text:outline-level="4">Exported aside</text:p></office:text></office:body></office:document-content>"##;
+
+	#[test]
+	fn odt_paragraphs_with_heading_styles_become_headings() {
+		let odt = TempZip::new(
+			"voyage.odt",
+			&[
+				("mimetype", "application/vnd.oasis.opendocument.text"),
+				("styles.xml", STYLES_XML),
+				("content.xml", CONTENT_XML),
+			],
+		);
+		let doc = parse_document(&ParserContext::new(odt.path())).unwrap();
+		assert_eq!(doc.buffer.content, "The Voyage\nIt began at sea.\nDeparture\nDer Hafen\nArrival\nExported aside\n");
+		assert_eq!(
+			heading_levels(&doc.buffer),
+			vec![
+				("The Voyage".to_string(), 1),
+				("Departure".to_string(), 2),
+				("Der Hafen".to_string(), 3),
+				("Arrival".to_string(), 2),
+				("Exported aside".to_string(), 4),
+			]
+		);
+		let departure = doc.buffer.markers.iter().find(|m| m.text == "Departure").unwrap();
+		assert_eq!(departure.mtype, MarkerType::Heading2);
+		assert_eq!(departure.position, display_len("The Voyage\nIt began at sea.\n"));
+		let toc: Vec<&str> = doc.toc_items.iter().map(|item| item.name.as_str()).collect();
+		assert_eq!(toc, ["The Voyage"]);
+		let chapter = &doc.toc_items[0];
+		assert_eq!(
+			chapter.children.iter().map(|item| item.name.as_str()).collect::<Vec<_>>(),
+			["Departure", "Arrival"]
+		);
+	}
+
+	#[test]
+	fn odt_text_h_headings_and_plain_paragraphs_are_unchanged() {
+		let xml = "<document><automatic-styles><style family=\"paragraph\" name=\"P1\" parent-style-name=\"Standard\"/></automatic-styles><h outline-level=\"3\">Deep</h><h>Default</h><p style-name=\"P1\">Body</p><p style-name=\"Missing\">More</p></document>";
+		let buffer = traverse_fixture(xml);
+
+		assert_eq!(buffer.content, "Deep\nDefault\nBody\nMore\n");
+		assert_eq!(heading_levels(&buffer), vec![("Deep".to_string(), 3), ("Default".to_string(), 1)]);
+	}
+
+	/// A style that is its own grandparent must not hang the resolver.
+	#[test]
+	fn odt_heading_style_cycles_resolve_to_nothing() {
+		let xml = "<document><automatic-styles><style family=\"paragraph\" name=\"A\" parent-style-name=\"B\"/><style family=\"paragraph\" name=\"B\" parent-style-name=\"A\"/></automatic-styles><p style-name=\"A\">Loop</p></document>";
+		let xml_doc = XmlDocument::parse(xml).expect("valid xml");
+		assert!(build_odt_heading_style_map(&[xml_doc.root()]).is_empty());
+	}
+
 	#[test]
 	fn odt_span_bold_style_adds_bold_marker() {
 		let xml = span_fixture("font-weight", "bold", "bold text");
@@ -1,15 +1,24 @@
 use std::{
 	collections::{HashMap, HashSet},
+	ffi::CString,
+	fs::{self, File},
+	io::{Read, Seek, SeekFrom},
 	mem,
+	ops::Range,
+	sync::Arc,
 };
 
 use anyhow::{Result, anyhow};
 use pdfium::{PdfiumDocument, PdfiumError, PdfiumTextPage, lib};
 
 use crate::{
-	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, TocItem},
+	document::{
+		Document, DocumentBuffer, Marker, MarkerType, MetadataField, ParserContext, ParserFlags, TocItem,
+		page_loader::{PageBatch, PageLoader},
+	},
 	parser::{
 		PASSWORD_REQUIRED_ERROR_PREFIX, Parser,
+		error::ParserError,
 		table_text::{display_lines_and_length, html_table_to_display},
 		util::{bidi, path::extract_title_from_path},
 	},
@@ -24,6 +33,15 @@ use crate::{
 /// structure tree is treated as unreliable and plain extraction is used instead.
 const MIN_MCID_COVERAGE: f64 = 0.5;
 
+/// How much larger than the body text a line must be set to count as a heading.
+const HEADING_FONT_RATIO: f64 = 1.2;
+/// pdfium's font weight at and above which a glyph counts as bold (400 is regular).
+const BOLD_FONT_WEIGHT: i32 = 600;
+/// Sizes closer than this, in points, are treated as one heading level.
+const HEADING_SIZE_TOLERANCE: f64 = 0.5;
+/// Heading levels guessed from font sizes; anything smaller than the third size joins it.
+const MAX_DETECTED_HEADING_LEVELS: usize = 3;
+
 pub struct PdfParser;
 
 impl Parser for PdfParser {
@@ -40,298 +58,671 @@ impl Parser for PdfParser {
 	}
 
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
-		let render_tables_inline = context.render_tables_inline;
+		// A DRM handler often fails in pdfium as a password error, so catch it before the user is asked
+		// for a password that can never work.
+		if let Some(handler) = drm_security_handler(&context.file_path) {
+			// TRANSLATORS: Error shown when a PDF is protected by DRM rather than a plain password; {} is the name of the DRM scheme
+			let message = t("This PDF is protected by DRM ({}) and can't be opened.").replace("{}", &handler);
+			return Err(ParserError::Encrypted(message).into());
+		}
 		let document =
 			PdfiumDocument::new_from_path(&context.file_path, context.password.as_deref()).map_err(map_load_error)?;
-		let mut buffer = DocumentBuffer::new();
-		let mut page_offsets = Vec::new();
-		let mut id_positions = HashMap::new();
-		let mut page_lines_info: Vec<Vec<(usize, String)>> = Vec::new();
 		let page_count = document.page_count();
-		let mut any_tags_processed = false;
-		let mut flat_toc_items = Vec::new();
-		let mut has_any_text = false;
-		let mut has_any_images = false;
-		let mut detected_heading_positions: Vec<(usize, String)> = Vec::new();
-		for page_index in 0..page_count {
-			let marker_position = buffer.current_position();
-			page_offsets.push(marker_position);
-			id_positions.insert(format!("page_{page_index}"), marker_position);
-			buffer.add_marker(
-				Marker::new(MarkerType::PageBreak, marker_position).with_text(format!("Page {}", page_index + 1)),
-			);
-			let Ok(page) = document.page(page_index) else {
-				page_lines_info.push(Vec::new());
-				continue;
-			};
-			let Ok(text_page) = page.text() else {
-				page_lines_info.push(Vec::new());
-				continue;
-			};
-			let page_start_offset = buffer.current_position();
-			let mut page_display_text = String::new();
-			let mut current_lines_info = Vec::new();
-			let mut tags_processed = false;
-			if let Some(struct_tree) = page.struct_tree() {
-				let child_count = struct_tree.count_children();
-				if child_count > 0 {
-					let mut mcid_to_text: HashMap<i32, String> = HashMap::new();
-					let mut real_char_count: usize = 0;
-					let mut mcid_char_count: usize = 0;
-					if let Ok(char_count) = text_page.char_count() {
-						let mut current_mcid = -1;
-						// Chars of the current marked-content run with their pdfium index, so RTL
-						// runs can be reordered visual→logical per run.
-						let mut current_chars: Vec<(char, i32)> = Vec::new();
-						for i in 0..char_count {
-							let unicode = text_page.get_unicode(i);
-							if let Some(ch) = char::from_u32(unicode) {
-								if (ch.is_control() && !matches!(ch, '\n' | '\r' | '\t')) || ch == '\u{00AD}' {
-									continue;
-								}
-								let is_generated = text_page.is_generated(i).unwrap_or(false);
-								let mut char_mcid = -1;
-								if !is_generated && let Ok(obj) = text_page.get_text_object(i) {
-									char_mcid = obj.get_marked_content_id();
-								}
-								if !is_generated && !ch.is_whitespace() {
-									real_char_count += 1;
-									if char_mcid >= 0 {
-										mcid_char_count += 1;
-									}
+		let total_pages = usize::try_from(page_count).unwrap_or(0);
+		let outline = read_outline(&document);
+		let page_labels = read_page_labels(&document, page_count);
+		let mut extraction = PdfExtraction::new(&outline, page_labels.clone());
+		// Pages loaded later are placed at offsets that assume the text before them is final, which
+		// typography normalization would break. An outline is read whole so the table of contents and
+		// its heading markers are there from the start rather than after the last page.
+		let lazy_limit = context
+			.lazy_page_limit
+			.filter(|&limit| total_pages > limit && !context.normalize_typography && outline.is_empty());
+		let first_pages = lazy_limit.map_or(page_count, |limit| i32::try_from(limit).unwrap_or(page_count));
+		for page_index in 0..first_pages {
+			extraction.extract_page(&document, page_index, context);
+		}
+		let mut doc = Document::new();
+		doc.page_labels = page_labels;
+		doc.title = metadata_value(&document, "Title").unwrap_or_else(|| extract_title_from_path(&context.file_path));
+		doc.author = metadata_value(&document, "Author").unwrap_or_default();
+		for (field, key) in [(MetadataField::Subject, "Subject"), (MetadataField::Subject, "Keywords")] {
+			if let Some(value) = metadata_value(&document, key) {
+				doc.add_metadata(field, &value);
+			}
+		}
+		if let Some(date) = metadata_value(&document, "CreationDate").and_then(|value| info_date(&value)) {
+			doc.add_metadata(MetadataField::Date, &date);
+		}
+		if let Some(loaded_pages) = lazy_limit {
+			let position = extraction.buffer.current_position();
+			doc.set_buffer(mem::replace(&mut extraction.buffer, DocumentBuffer::continuing_at(position)));
+			doc.id_positions = mem::take(&mut extraction.id_positions);
+			let loader = spawn_page_loader(context, extraction, loaded_pages, total_pages);
+			doc.page_loader = Some(Arc::new(loader));
+		} else {
+			doc.toc_items = extraction.finish(outline, context);
+			doc.set_buffer(extraction.buffer);
+			doc.id_positions = extraction.id_positions;
+		}
+		Ok(doc)
+	}
+}
+
+/// Everything gathered while reading pages in order, kept between pages so a large PDF can be
+/// read a few pages at a time.
+#[derive(Default)]
+struct PdfExtraction {
+	buffer: DocumentBuffer,
+	page_offsets: Vec<usize>,
+	id_positions: HashMap<String, usize>,
+	/// Paragraph offsets and text per page, kept only for pages an outline entry points at.
+	page_lines_info: Vec<Vec<(usize, String)>>,
+	outline_pages: HashSet<usize>,
+	any_tags_processed: bool,
+	flat_toc_items: Vec<(u32, TocItem)>,
+	has_any_text: bool,
+	has_any_images: bool,
+	/// Lines set larger than the text around them, on pages read without tags.
+	heading_candidates: Vec<HeadingCandidate>,
+	/// Characters of untagged text per font size in tenths of a point, to find the body size.
+	font_size_chars: HashMap<u32, usize>,
+	/// Printed page labels, empty when the PDF doesn't have them.
+	page_labels: Vec<String>,
+	/// Annotations whose place in the text couldn't be found, listed after the last page.
+	unplaced_annotations: Vec<UnplacedAnnotation>,
+}
+
+/// A highlight or note made in another application that couldn't be matched to the page's text.
+struct UnplacedAnnotation {
+	page_index: i32,
+	author: String,
+	note: String,
+	/// The text pdfium reports under a highlight, shown when the highlight has no note.
+	quoted: String,
+}
+
+impl PdfExtraction {
+	fn new(outline: &[OutlineEntry], page_labels: Vec<String>) -> Self {
+		Self {
+			outline_pages: outline.iter().filter_map(|entry| entry.page_index).collect(),
+			page_labels,
+			..Self::default()
+		}
+	}
+
+	fn begin_page(&mut self, page_index: i32) {
+		let marker_position = self.buffer.current_position();
+		self.page_offsets.push(marker_position);
+		self.id_positions.insert(format!("page_{page_index}"), marker_position);
+		let label = self.page_label(page_index);
+		self.buffer.add_marker(Marker::new(MarkerType::PageBreak, marker_position).with_text(format!("Page {label}")));
+	}
+
+	/// The printed label of a page, or its number when the PDF doesn't label it.
+	fn page_label(&self, page_index: i32) -> String {
+		usize::try_from(page_index)
+			.ok()
+			.and_then(|index| self.page_labels.get(index))
+			.filter(|label| !label.is_empty())
+			.map_or_else(|| (page_index + 1).to_string(), Clone::clone)
+	}
+
+	#[allow(clippy::too_many_lines)]
+	fn extract_page(&mut self, document: &PdfiumDocument, page_index: i32, context: &ParserContext) {
+		self.begin_page(page_index);
+		let Ok(page) = document.page(page_index) else {
+			self.page_lines_info.push(Vec::new());
+			return;
+		};
+		let Ok(text_page) = page.text() else {
+			self.page_lines_info.push(Vec::new());
+			return;
+		};
+		let page_start_offset = self.buffer.current_position();
+		let mut page_display_text = String::new();
+		let mut current_lines_info = Vec::new();
+		let mut tags_processed = false;
+		if let Some(struct_tree) = page.struct_tree() {
+			let child_count = struct_tree.count_children();
+			if child_count > 0 {
+				let mut mcid_to_text: HashMap<i32, String> = HashMap::new();
+				let mut real_char_count: usize = 0;
+				let mut mcid_char_count: usize = 0;
+				if let Ok(char_count) = text_page.char_count() {
+					let mut current_mcid = -1;
+					// Chars of the current marked-content run with their pdfium index, so RTL
+					// runs can be reordered visual→logical per run.
+					let mut current_chars: Vec<(char, i32)> = Vec::new();
+					for i in 0..char_count {
+						let unicode = text_page.get_unicode(i);
+						if let Some(ch) = char::from_u32(unicode) {
+							if (ch.is_control() && !matches!(ch, '\n' | '\r' | '\t')) || ch == '\u{00AD}' {
+								continue;
+							}
+							let is_generated = text_page.is_generated(i).unwrap_or(false);
+							let mut char_mcid = -1;
+							if !is_generated && let Ok(obj) = text_page.get_text_object(i) {
+								char_mcid = obj.get_marked_content_id();
+							}
+							if !is_generated && !ch.is_whitespace() {
+								real_char_count += 1;
+								if char_mcid >= 0 {
+									mcid_char_count += 1;
 								}
-								if char_mcid >= 0 && char_mcid != current_mcid {
-									if current_mcid >= 0 && !current_chars.is_empty() {
-										mcid_to_text
-											.entry(current_mcid)
-											.or_default()
-											.push_str(&reorder_run(&text_page, &current_chars));
-									}
-									current_chars.clear();
-									current_mcid = char_mcid;
+							}
+							if char_mcid >= 0 && char_mcid != current_mcid {
+								if current_mcid >= 0 && !current_chars.is_empty() {
+									mcid_to_text
+										.entry(current_mcid)
+										.or_default()
+										.push_str(&reorder_run(&text_page, &current_chars));
 								}
-								current_chars.push((ch, i));
+								current_chars.clear();
+								current_mcid = char_mcid;
 							}
-						}
-						if current_mcid >= 0 && !current_chars.is_empty() {
-							mcid_to_text
-								.entry(current_mcid)
-								.or_default()
-								.push_str(&reorder_run(&text_page, &current_chars));
+							current_chars.push((ch, i));
 						}
 					}
-					let coverage =
-						if real_char_count > 0 { mcid_char_count as f64 / real_char_count as f64 } else { 1.0 };
-					if coverage >= MIN_MCID_COVERAGE {
-						let mut current_block = String::new();
-						for i in 0..child_count {
-							if let Ok(child) = struct_tree.child(i) {
-								process_struct_element(
-									&child,
-									&mcid_to_text,
-									&mut buffer,
-									&mut page_display_text,
-									&mut current_block,
-									&mut current_lines_info,
-									&mut flat_toc_items,
-									render_tables_inline,
-								);
-							}
+					if current_mcid >= 0 && !current_chars.is_empty() {
+						mcid_to_text
+							.entry(current_mcid)
+							.or_default()
+							.push_str(&reorder_run(&text_page, &current_chars));
+					}
+				}
+				let coverage = if real_char_count > 0 { mcid_char_count as f64 / real_char_count as f64 } else { 1.0 };
+				if coverage >= MIN_MCID_COVERAGE {
+					let mut current_block = String::new();
+					for i in 0..child_count {
+						if let Ok(child) = struct_tree.child(i) {
+							process_struct_element(
+								&child,
+								&mcid_to_text,
+								&mut self.buffer,
+								&mut page_display_text,
+								&mut current_block,
+								&mut current_lines_info,
+								&mut self.flat_toc_items,
+								context.render_tables_inline,
+							);
 						}
-						flush_block(&mut current_block, &mut buffer, &mut page_display_text, &mut current_lines_info);
-						tags_processed = true;
-						any_tags_processed = true;
 					}
+					flush_block(&mut current_block, &mut self.buffer, &mut page_display_text, &mut current_lines_info);
+					tags_processed = true;
+					self.any_tags_processed = true;
 				}
 			}
-			if tags_processed {
-				has_any_text = true;
-			} else {
-				let line_infos = extract_text_lines(&text_page);
-				let body_size = median_line_font_size(&line_infos);
-				let paragraphs = join_paragraphs(&line_infos, body_size);
-				if !paragraphs.is_empty() {
-					has_any_text = true;
+		}
+		if tags_processed {
+			self.has_any_text = true;
+		} else {
+			let line_infos = reading_order_lines(extract_text_lines(&text_page, context.detect_columns));
+			let body_size = median_line_font_size(&line_infos);
+			let page_min_size = self.count_font_sizes(&line_infos);
+			let paragraphs = join_paragraphs(&line_infos, body_size);
+			if !paragraphs.is_empty() {
+				self.has_any_text = true;
+			}
+			for (text, heading_style) in &paragraphs {
+				let current_offset = self.buffer.current_position();
+				if let Some(style) = heading_style {
+					self.heading_candidates.push(HeadingCandidate {
+						offset: current_offset,
+						text: text.clone(),
+						style: *style,
+						page_min_size,
+					});
 				}
-				for (text, is_heading) in &paragraphs {
-					let current_offset = buffer.current_position();
-					if *is_heading {
-						detected_heading_positions.push((current_offset, text.clone()));
-					}
-					current_lines_info.push((current_offset, text.clone()));
-					buffer.append(text);
-					buffer.append("\n");
-					page_display_text.push_str(text);
-					page_display_text.push('\n');
+				current_lines_info.push((current_offset, text.clone()));
+				self.buffer.append(text);
+				self.buffer.append("\n");
+				page_display_text.push_str(text);
+				page_display_text.push('\n');
+			}
+		}
+		// Check for image objects on this page
+		if !self.has_any_images {
+			let obj_count = lib().FPDFPage_CountObjects(&page);
+			for i in 0..obj_count {
+				if let Ok(obj) = lib().FPDFPage_GetObject(&page, i)
+					&& lib().FPDFPageObj_GetType(&obj) == pdfium::pdfium_constants::FPDF_PAGEOBJ_IMAGE
+				{
+					self.has_any_images = true;
+					break;
 				}
 			}
-			// Check for image objects on this page
-			if !has_any_images {
-				let obj_count = lib().FPDFPage_CountObjects(&page);
-				for i in 0..obj_count {
-					if let Ok(obj) = lib().FPDFPage_GetObject(&page, i)
-						&& lib().FPDFPageObj_GetType(&obj) == pdfium::pdfium_constants::FPDF_PAGEOBJ_IMAGE
-					{
-						has_any_images = true;
-						break;
+		}
+		// Load implicit web links
+		if let Ok(links) = text_page.load_web_links() {
+			let count = lib().FPDFLink_CountWebLinks(&links);
+			let mut last_search_pos = 0;
+			for i in 0..count {
+				let mut start = 0;
+				let mut char_count = 0;
+				if lib().FPDFLink_GetTextRange(&links, i, &mut start, &mut char_count).is_ok() {
+					let link_text = sanitize_pdf_text(&text_page.extract(start, char_count));
+					let trimmed_link = trim_string(&collapse_whitespace(&link_text));
+					if trimmed_link.is_empty() {
+						continue;
+					}
+					let mut url_buffer = vec![0u16; 2048];
+					let len = lib().FPDFLink_GetURL(&links, i, &mut url_buffer[0], 2048);
+					if len > 0 {
+						let url = String::from_utf16_lossy(&url_buffer[..(len as usize - 1)]);
+						if let Some(pos) = page_display_text[last_search_pos..].find(&trimmed_link) {
+							let text_before = &page_display_text[last_search_pos..last_search_pos + pos];
+							let marker_pos = page_start_offset
+								+ display_len(&page_display_text[..last_search_pos])
+								+ display_len(text_before);
+							let link_len = display_len(&trimmed_link);
+							self.buffer.add_marker(
+								Marker::new(MarkerType::Link, marker_pos)
+									.with_text(trimmed_link.clone())
+									.with_reference(url)
+									.with_length(link_len),
+							);
+							last_search_pos += pos + trimmed_link.len();
+						}
 					}
 				}
 			}
-			// Load implicit web links
-			if let Ok(links) = text_page.load_web_links() {
-				let count = lib().FPDFLink_CountWebLinks(&links);
-				let mut last_search_pos = 0;
-				for i in 0..count {
-					let mut start = 0;
-					let mut char_count = 0;
-					if lib().FPDFLink_GetTextRange(&links, i, &mut start, &mut char_count).is_ok() {
-						let link_text = sanitize_pdf_text(&text_page.extract(start, char_count));
-						let trimmed_link = trim_string(&collapse_whitespace(&link_text));
+		}
+		// Load explicit annotations (internal and external links)
+		let annot_count = lib().FPDFPage_GetAnnotCount(&page);
+		let mut last_search_pos = 0;
+		for i in 0..annot_count {
+			let annot_result = lib().FPDFPage_GetAnnot(&page, i);
+			if let Ok(annot) = annot_result
+				&& lib().FPDFAnnot_GetSubtype(&annot) == pdfium::pdfium_constants::FPDF_ANNOT_LINK
+			{
+				let mut rect = pdfium::pdfium_types::FS_RECTF { left: 0.0, top: 0.0, right: 0.0, bottom: 0.0 };
+				if lib().FPDFAnnot_GetRect(&annot, &mut rect).is_ok() {
+					let mut text_buffer = vec![0u16; 2048];
+					let len = lib().FPDFText_GetBoundedText(
+						&text_page,
+						f64::from(rect.left),
+						f64::from(rect.top),
+						f64::from(rect.right),
+						f64::from(rect.bottom),
+						&mut text_buffer[0],
+						2048,
+					);
+					if len > 0 {
+						let text = sanitize_pdf_text(&String::from_utf16_lossy(&text_buffer[..(len as usize - 1)]));
+						let trimmed_link = trim_string(&collapse_whitespace(&text));
 						if trimmed_link.is_empty() {
 							continue;
 						}
-						let mut url_buffer = vec![0u16; 2048];
-						let len = lib().FPDFLink_GetURL(&links, i, &mut url_buffer[0], 2048);
-						if len > 0 {
-							let url = String::from_utf16_lossy(&url_buffer[..(len as usize - 1)]);
-							if let Some(pos) = page_display_text[last_search_pos..].find(&trimmed_link) {
-								let text_before = &page_display_text[last_search_pos..last_search_pos + pos];
-								let marker_pos = page_start_offset
-									+ display_len(&page_display_text[..last_search_pos])
-									+ display_len(text_before);
-								let link_len = display_len(&trimmed_link);
-								buffer.add_marker(
-									Marker::new(MarkerType::Link, marker_pos)
-										.with_text(trimmed_link.clone())
-										.with_reference(url)
-										.with_length(link_len),
-								);
-								last_search_pos += pos + trimmed_link.len();
-							}
-						}
-					}
-				}
-			}
-			// Load explicit annotations (internal and external links)
-			let annot_count = lib().FPDFPage_GetAnnotCount(&page);
-			let mut last_search_pos = 0;
-			for i in 0..annot_count {
-				let annot_result = lib().FPDFPage_GetAnnot(&page, i);
-				if let Ok(annot) = annot_result
-					&& lib().FPDFAnnot_GetSubtype(&annot) == pdfium::pdfium_constants::FPDF_ANNOT_LINK
-				{
-					let mut rect = pdfium::pdfium_types::FS_RECTF { left: 0.0, top: 0.0, right: 0.0, bottom: 0.0 };
-					if lib().FPDFAnnot_GetRect(&annot, &mut rect).is_ok() {
-						let mut text_buffer = vec![0u16; 2048];
-						let len = lib().FPDFText_GetBoundedText(
-							&text_page,
-							f64::from(rect.left),
-							f64::from(rect.top),
-							f64::from(rect.right),
-							f64::from(rect.bottom),
-							&mut text_buffer[0],
-							2048,
-						);
-						if len > 0 {
-							let text = sanitize_pdf_text(&String::from_utf16_lossy(&text_buffer[..(len as usize - 1)]));
-							let trimmed_link = trim_string(&collapse_whitespace(&text));
-							if trimmed_link.is_empty() {
-								continue;
-							}
-							let mut url = String::new();
-							let link_result = lib().FPDFAnnot_GetLink(&annot);
-							if let Ok(link) = link_result {
-								let action_result = lib().FPDFLink_GetAction(&link);
-								if let Ok(action) = action_result {
-									let action_type = lib().FPDFAction_GetType(&action);
-									// PDFACTION_URI is 3
-									if action_type == 3 {
-										let mut uri_buffer = vec![0u8; 2048];
-										let uri_len = lib().FPDFAction_GetURIPath(
-											&document,
-											&action,
-											Some(&mut uri_buffer),
-											2048,
-										);
-										if uri_len > 0 {
-											url = String::from_utf8_lossy(&uri_buffer[..(uri_len as usize - 1)])
-												.to_string();
-										}
+						let mut url = String::new();
+						let link_result = lib().FPDFAnnot_GetLink(&annot);
+						if let Ok(link) = link_result {
+							let action_result = lib().FPDFLink_GetAction(&link);
+							if let Ok(action) = action_result {
+								let action_type = lib().FPDFAction_GetType(&action);
+								// PDFACTION_URI is 3
+								if action_type == 3 {
+									let mut uri_buffer = vec![0u8; 2048];
+									let uri_len =
+										lib().FPDFAction_GetURIPath(document, &action, Some(&mut uri_buffer), 2048);
+									if uri_len > 0 {
+										url =
+											String::from_utf8_lossy(&uri_buffer[..(uri_len as usize - 1)]).to_string();
 									}
 								}
-								if url.is_empty() {
-									let dest_result = lib().FPDFLink_GetDest(&document, &link);
-									let dest = dest_result.ok().or_else(|| {
-										lib()
-											.FPDFLink_GetAction(&link)
-											.ok()
-											.and_then(|action| lib().FPDFAction_GetDest(&document, &action).ok())
-									});
-									if let Some(dest) = dest {
-										let dest_page = lib().FPDFDest_GetDestPageIndex(&document, &dest);
-										if dest_page >= 0 {
-											url = format!("#page_{dest_page}");
-										}
+							}
+							if url.is_empty() {
+								let dest_result = lib().FPDFLink_GetDest(document, &link);
+								let dest = dest_result.ok().or_else(|| {
+									lib()
+										.FPDFLink_GetAction(&link)
+										.ok()
+										.and_then(|action| lib().FPDFAction_GetDest(document, &action).ok())
+								});
+								if let Some(dest) = dest {
+									let dest_page = lib().FPDFDest_GetDestPageIndex(document, &dest);
+									if dest_page >= 0 {
+										url = format!("#page_{dest_page}");
 									}
 								}
 							}
-							if !url.is_empty()
-								&& let Some(pos) = page_display_text[last_search_pos..].find(&trimmed_link)
-							{
-								let text_before = &page_display_text[last_search_pos..last_search_pos + pos];
-								let marker_pos = page_start_offset
-									+ display_len(&page_display_text[..last_search_pos])
-									+ display_len(text_before);
-								let link_len = display_len(&trimmed_link);
-								buffer.add_marker(
-									Marker::new(MarkerType::Link, marker_pos)
-										.with_text(trimmed_link.clone())
-										.with_reference(url)
-										.with_length(link_len),
-								);
-								last_search_pos += pos + trimmed_link.len();
-							}
+						}
+						if !url.is_empty()
+							&& let Some(pos) = page_display_text[last_search_pos..].find(&trimmed_link)
+						{
+							let text_before = &page_display_text[last_search_pos..last_search_pos + pos];
+							let marker_pos = page_start_offset
+								+ display_len(&page_display_text[..last_search_pos])
+								+ display_len(text_before);
+							let link_len = display_len(&trimmed_link);
+							self.buffer.add_marker(
+								Marker::new(MarkerType::Link, marker_pos)
+									.with_text(trimmed_link.clone())
+									.with_reference(url)
+									.with_length(link_len),
+							);
+							last_search_pos += pos + trimmed_link.len();
 						}
 					}
 				}
 			}
-			page_lines_info.push(current_lines_info);
 		}
-		if !has_any_text && has_any_images {
-			let marker_position = buffer.current_position();
-			buffer.add_marker(Marker::new(MarkerType::PageBreak, marker_position).with_text(String::new()));
+		// Highlights and notes made in other applications, placed by the text under or beside them.
+		let mut page_notes: Vec<String> = Vec::new();
+		for i in 0..annot_count {
+			let Ok(annot) = lib().FPDFPage_GetAnnot(&page, i) else {
+				continue;
+			};
+			let subtype = lib().FPDFAnnot_GetSubtype(&annot);
+			let is_highlight = subtype == pdfium::pdfium_constants::FPDF_ANNOT_HIGHLIGHT;
+			let is_popup = subtype == pdfium::pdfium_constants::FPDF_ANNOT_POPUP;
+			if !is_highlight && !is_popup && subtype != pdfium::pdfium_constants::FPDF_ANNOT_TEXT {
+				continue;
+			}
+			let string_value = |key: &str| {
+				let Ok(key) = CString::new(key) else {
+					return String::new();
+				};
+				let mut probe = 0u16;
+				let needed = lib().FPDFAnnot_GetStringValue(&annot, &key, &mut probe, 0);
+				let units = usize::try_from(needed).unwrap_or(0) / 2;
+				if units <= 1 {
+					return String::new();
+				}
+				let mut buffer = vec![0u16; units];
+				lib().FPDFAnnot_GetStringValue(&annot, &key, &mut buffer[0], needed);
+				trim_string(&sanitize_pdf_text(&String::from_utf16_lossy(&buffer[..units - 1])))
+			};
+			let note = string_value("Contents");
+			// A popup mostly repeats the note of the annotation it opens from.
+			if is_popup && (note.is_empty() || page_notes.contains(&note)) {
+				continue;
+			}
+			let author = string_value("T");
+			let bounded_text = |left: f32, top: f32, right: f32, bottom: f32| {
+				let mut text_buffer = vec![0u16; 4096];
+				let len = lib().FPDFText_GetBoundedText(
+					&text_page,
+					f64::from(left),
+					f64::from(top),
+					f64::from(right),
+					f64::from(bottom),
+					&mut text_buffer[0],
+					4096,
+				);
+				let len = usize::try_from(len).unwrap_or(0).saturating_sub(1);
+				sanitize_pdf_text(&String::from_utf16_lossy(&text_buffer[..len]))
+			};
+			// Where on the page the highlight starts, to tell apart repeats of the text it covers.
+			let mut start_char = None;
+			let covered = if is_highlight {
+				let quads = lib().FPDFAnnot_CountAttachmentPoints(&annot);
+				let mut parts = Vec::new();
+				for quad_index in 0..quads {
+					let mut quad = pdfium::pdfium_types::FS_QUADPOINTSF {
+						x1: 0.0,
+						y1: 0.0,
+						x2: 0.0,
+						y2: 0.0,
+						x3: 0.0,
+						y3: 0.0,
+						x4: 0.0,
+						y4: 0.0,
+					};
+					if lib().FPDFAnnot_GetAttachmentPoints(&annot, quad_index, &mut quad).is_ok() {
+						let xs = [quad.x1, quad.x2, quad.x3, quad.x4];
+						let ys = [quad.y1, quad.y2, quad.y3, quad.y4];
+						let (left, right) =
+							(xs.into_iter().fold(f32::MAX, f32::min), xs.into_iter().fold(f32::MIN, f32::max));
+						let (bottom, top) =
+							(ys.into_iter().fold(f32::MAX, f32::min), ys.into_iter().fold(f32::MIN, f32::max));
+						if start_char.is_none() {
+							let half_height = f64::from(top - bottom) / 2.0;
+							let index = lib().FPDFText_GetCharIndexAtPos(
+								&text_page,
+								f64::from(left) + half_height,
+								f64::from(bottom) + half_height,
+								half_height,
+								half_height,
+							);
+							start_char = (index >= 0).then_some(index);
+						}
+						parts.push(bounded_text(left, top, right, bottom));
+					}
+				}
+				parts.join(" ")
+			} else {
+				let mut rect = pdfium::pdfium_types::FS_RECTF { left: 0.0, top: 0.0, right: 0.0, bottom: 0.0 };
+				if lib().FPDFAnnot_GetRect(&annot, &mut rect).is_ok() {
+					// A note's icon usually sits in the margin, so take the first line across the
+					// whole page at its height.
+					let band = bounded_text(0.0, rect.top, lib().FPDF_GetPageWidthF(&page), rect.bottom);
+					band.lines().find(|line| !line.trim().is_empty()).unwrap_or_default().to_string()
+				} else {
+					String::new()
+				}
+			};
+			let covered = trim_string(&collapse_whitespace(&covered));
+			page_notes.push(note.clone());
+			let found = match start_char {
+				Some(index) => find_loosely_near(&page_display_text, &covered, loose_chars_before(&text_page, index)),
+				None => find_loosely(&page_display_text, &covered),
+			};
+			let Some(range) = found else {
+				let quoted = if is_highlight { covered } else { String::new() };
+				self.unplaced_annotations.push(UnplacedAnnotation { page_index, author, note, quoted });
+				continue;
+			};
+			let position = page_start_offset + display_len(&page_display_text[..range.start]);
+			let length = if is_highlight { display_len(&page_display_text[range]) } else { 0 };
+			self.buffer.add_marker(
+				Marker::new(MarkerType::Annotation, position)
+					.with_text(note)
+					.with_reference(author)
+					.with_level(page_index + 1)
+					.with_length(length),
+			);
+		}
+		let keep_lines = usize::try_from(page_index).is_ok_and(|page| self.outline_pages.contains(&page));
+		self.page_lines_info.push(if keep_lines { current_lines_info } else { Vec::new() });
+	}
+
+	/// Lists the annotations whose text couldn't be found after the last page, one line each with
+	/// its page, so they can still be read and navigated to.
+	fn append_unplaced_annotations(&mut self) {
+		if self.unplaced_annotations.is_empty() {
+			return;
+		}
+		// TRANSLATORS: Line added after the last page of a PDF, above the annotations whose place in the text couldn't be found
+		self.buffer.append(&t("Annotations not found in the text:"));
+		self.buffer.append("\n");
+		for annotation in mem::take(&mut self.unplaced_annotations) {
+			let shown = if annotation.note.is_empty() { &annotation.quoted } else { &annotation.note };
+			let about = [annotation.author.as_str(), shown.as_str()]
+				.into_iter()
+				.filter(|part| !part.is_empty())
+				.collect::<Vec<_>>()
+				.join(": ");
+			// TRANSLATORS: An annotation listed after the last page of a PDF; the first {} is its page label, the second its author and note
+			let template = t("Page {}: {}");
+			let line = template.replacen("{}", &self.page_label(annotation.page_index), 1).replacen("{}", &about, 1);
+			let position = self.buffer.current_position();
+			self.buffer.append(&line);
+			self.buffer.append("\n");
+			self.buffer.add_marker(
+				Marker::new(MarkerType::Annotation, position)
+					.with_text(annotation.note)
+					.with_reference(annotation.author)
+					.with_level(annotation.page_index + 1)
+					.with_length(display_len(&line)),
+			);
+		}
+	}
+
+	/// Adds each line's characters to the document's font size tally and returns the page's
+	/// smallest font size.
+	fn count_font_sizes(&mut self, lines: &[TextLine]) -> f64 {
+		let mut smallest = f64::INFINITY;
+		for line in lines.iter().filter(|line| line.font_size > 0.0) {
+			let chars = line.text.chars().filter(|ch| !ch.is_whitespace()).count();
+			if chars == 0 {
+				continue;
+			}
+			*self.font_size_chars.entry(font_size_key(line.font_size)).or_default() += chars;
+			smallest = smallest.min(line.font_size);
+		}
+		smallest
+	}
+
+	/// The font size most of the untagged text is set in, or 0 when there is none.
+	fn body_font_size(&self) -> f64 {
+		self.font_size_chars
+			.iter()
+			.max_by_key(|&(size, chars)| (*chars, std::cmp::Reverse(*size)))
+			.map_or(0.0, |(size, _)| f64::from(*size) / 10.0)
+	}
+
+	/// Adds what can only be worked out once every page has been read: the notice for image-only
+	/// PDFs, the annotations that couldn't be placed in the text, and the table of contents with
+	/// heading markers for its entries.
+	fn finish(&mut self, outline: Vec<OutlineEntry>, context: &ParserContext) -> Vec<TocItem> {
+		if !self.has_any_text && self.has_any_images {
+			let marker_position = self.buffer.current_position();
+			self.buffer.add_marker(Marker::new(MarkerType::PageBreak, marker_position).with_text(String::new()));
 			// TRANSLATORS: Notice inserted into the extracted text when a PDF has images but no text layer at all
-			buffer.append(&t("This PDF contains images only, with no extractable text. You may need to run it through OCR software to read its contents."));
-			buffer.append("\n");
+			self.buffer.append(&t("This PDF contains images only, with no extractable text. You may need to run it through OCR software to read its contents."));
+			self.buffer.append("\n");
 		}
-		let title = metadata_value(&document, "Title").unwrap_or_else(|| extract_title_from_path(&context.file_path));
-		let author = metadata_value(&document, "Author").unwrap_or_default();
-		let mut toc_items = extract_toc(&document, &page_offsets, &page_lines_info);
-		if any_tags_processed {
+		self.append_unplaced_annotations();
+		let (mut toc_items, skipped) = outline_to_toc(outline, &self.page_offsets, &self.page_lines_info);
+		if skipped > 0 {
+			tracing::warn!(skipped, "skipped PDF outline entries with unresolvable or out-of-range destinations");
+		}
+		if self.any_tags_processed {
 			if toc_items.is_empty() {
-				toc_items = build_toc_tree(flat_toc_items);
-			} else if flat_toc_items.is_empty() {
-				add_heading_markers(&mut buffer, &toc_items, 1);
-			}
-		} else if toc_items.is_empty() && !detected_heading_positions.is_empty() {
-			for (pos, text) in &detected_heading_positions {
-				buffer.add_marker(Marker::new(MarkerType::Heading1, *pos).with_text(text.clone()).with_level(1));
+				toc_items = build_toc_tree(mem::take(&mut self.flat_toc_items));
+			} else if self.flat_toc_items.is_empty() {
+				add_heading_markers(&mut self.buffer, &toc_items, 1);
 			}
-			toc_items = detected_heading_positions
-				.into_iter()
-				.map(|(pos, text)| TocItem::new(text, String::new(), pos))
-				.collect();
+		} else if toc_items.is_empty() && context.detect_headings && !self.heading_candidates.is_empty() {
+			let body_size = self.body_font_size();
+			let candidates = mem::take(&mut self.heading_candidates);
+			toc_items = build_toc_tree(classify_headings(candidates, body_size, context.max_detected_headings));
+			add_heading_markers(&mut self.buffer, &toc_items, 1);
 		} else {
-			add_heading_markers(&mut buffer, &toc_items, 1);
+			add_heading_markers(&mut self.buffer, &toc_items, 1);
 		}
-		let mut doc = Document::new();
-		doc.set_buffer(buffer);
-		doc.title = title;
-		doc.author = author;
-		doc.toc_items = toc_items;
-		doc.id_positions = id_positions;
-		Ok(doc)
+		toc_items
+	}
+}
+
+/// Hands the pages after the first `loaded_pages` of a document without an outline to a background
+/// loader. The loader reopens the file on its own thread so no pdfium handle crosses threads, and
+/// sends each page's text with markers already at their final offsets.
+fn spawn_page_loader(
+	context: &ParserContext,
+	mut extraction: PdfExtraction,
+	loaded_pages: usize,
+	total_pages: usize,
+) -> PageLoader {
+	let context = context.clone();
+	PageLoader::spawn(loaded_pages, total_pages, move || {
+		let document = PdfiumDocument::new_from_path(&context.file_path, context.password.as_deref()).ok();
+		move |page: usize| {
+			let page_index = i32::try_from(page).unwrap_or(i32::MAX);
+			if let Some(document) = &document {
+				extraction.extract_page(document, page_index, &context);
+			} else {
+				extraction.begin_page(page_index);
+				extraction.page_lines_info.push(Vec::new());
+			}
+			// Documents with an outline are never loaded lazily, so there is none to map here.
+			let toc_items = (page + 1 == total_pages).then(|| extraction.finish(Vec::new(), &context));
+			let position = extraction.buffer.current_position();
+			let extracted = mem::replace(&mut extraction.buffer, DocumentBuffer::continuing_at(position));
+			PageBatch {
+				text: extracted.content,
+				markers: extracted.markers,
+				id_positions: extraction.id_positions.drain().collect(),
+				toc_items,
+			}
+		}
+	})
+}
+
+/// The type a heading line is set in.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct HeadingStyle {
+	font_size: f64,
+	bold: bool,
+}
+
+/// A paragraph that might be a heading, kept until the whole document's body size is known.
+#[derive(Clone, Debug)]
+struct HeadingCandidate {
+	offset: usize,
+	text: String,
+	style: HeadingStyle,
+	/// Smallest font size on the candidate's page; a title page has nothing at body size.
+	page_min_size: f64,
+}
+
+/// Font sizes are positive and far below `u32::MAX` tenths of a point, so the cast only rounds.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn font_size_key(font_size: f64) -> u32 {
+	(font_size * 10.0).round() as u32
+}
+
+/// Turns heading candidates into levelled TOC entries. Only lines set at least
+/// [`HEADING_FONT_RATIO`] times the body size count, and not on pages where every line does
+/// (title pages). Sizes within [`HEADING_SIZE_TOLERANCE`] of each other share a level, bold
+/// ranking above regular at the same size, and the largest sizes become levels 1 to 3. When
+/// there are more than `max_headings`, the deepest levels are dropped first.
+fn classify_headings(candidates: Vec<HeadingCandidate>, body_size: f64, max_headings: usize) -> Vec<(u32, TocItem)> {
+	if body_size <= 0.0 || max_headings == 0 {
+		return Vec::new();
 	}
+	let threshold = body_size * HEADING_FONT_RATIO;
+	let headings: Vec<HeadingCandidate> = candidates
+		.into_iter()
+		.filter(|candidate| candidate.style.font_size >= threshold && candidate.page_min_size < threshold)
+		.collect();
+	let mut styles: Vec<HeadingStyle> = headings.iter().map(|candidate| candidate.style).collect();
+	styles.sort_by(|a, b| {
+		b.font_size.partial_cmp(&a.font_size).unwrap_or(std::cmp::Ordering::Equal).then(b.bold.cmp(&a.bold))
+	});
+	// Each level is named by its largest style; a smaller size, or regular after bold, starts the next.
+	let mut levels: Vec<HeadingStyle> = Vec::new();
+	for style in styles {
+		let starts_level = levels.last().is_none_or(|level| {
+			level.font_size - style.font_size > HEADING_SIZE_TOLERANCE || (level.bold && !style.bold)
+		});
+		if starts_level {
+			levels.push(style);
+		}
+	}
+	levels.truncate(MAX_DETECTED_HEADING_LEVELS);
+	let level_of = |style: HeadingStyle| {
+		let index = levels
+			.iter()
+			.position(|level| {
+				style.font_size >= level.font_size - HEADING_SIZE_TOLERANCE && (style.bold || !level.bold)
+			})
+			.unwrap_or(levels.len().saturating_sub(1));
+		u32::try_from(index + 1).unwrap_or(1)
+	};
+	let mut levelled: Vec<(u32, HeadingCandidate)> =
+		headings.into_iter().map(|candidate| (level_of(candidate.style), candidate)).collect();
+	let mut deepest = u32::try_from(levels.len()).unwrap_or(1);
+	while levelled.len() > max_headings && deepest > 1 {
+		levelled.retain(|(level, _)| *level < deepest);
+		deepest -= 1;
+	}
+	levelled.truncate(max_headings);
+	levelled
+		.into_iter()
+		.map(|(level, candidate)| (level, TocItem::new(candidate.text, String::new(), candidate.offset)))
+		.collect()
 }
 
 fn add_heading_markers(buffer: &mut DocumentBuffer, items: &[TocItem], level: i32) {
@@ -359,14 +750,52 @@ fn is_cjk(c: char) -> bool {
 	(0xAC00..=0xD7AF).contains(&u) // Hangul
 }
 
+/// Finds `needle` in `haystack` as if neither had whitespace or hyphens, since the text pdfium
+/// reports for an area breaks and hyphenates its lines differently from the paragraphs the page
+/// was joined into. Returns the byte range in `haystack` from the first matched character to the
+/// last, or `None` when `needle` has nothing to match.
+fn find_loosely(haystack: &str, needle: &str) -> Option<Range<usize>> {
+	find_loosely_near(haystack, needle, 0)
+}
+
+/// Like [`find_loosely`], but of several matches takes the one starting closest to the `near`th
+/// character that isn't whitespace or a hyphen, so a highlight lands on its own copy of a phrase the
+/// page repeats.
+fn find_loosely_near(haystack: &str, needle: &str, near: usize) -> Option<Range<usize>> {
+	let wanted: Vec<char> = needle.chars().filter(|&ch| !is_loosely_skipped(ch)).collect();
+	if wanted.is_empty() {
+		return None;
+	}
+	let kept: Vec<(usize, char)> = haystack.char_indices().filter(|&(_, ch)| !is_loosely_skipped(ch)).collect();
+	kept.windows(wanted.len())
+		.enumerate()
+		.filter(|(_, window)| window.iter().map(|&(_, ch)| ch).eq(wanted.iter().copied()))
+		.min_by_key(|&(start, _)| start.abs_diff(near))
+		.map(|(_, window)| {
+			let (last, ch) = window[window.len() - 1];
+			window[0].0..last + ch.len_utf8()
+		})
+}
+
+const fn is_loosely_skipped(ch: char) -> bool {
+	ch.is_whitespace() || ch == '-'
+}
+
+/// How many of the page's characters before pdfium's character `index` [`find_loosely_near`] would
+/// count, which places that character in the extracted page text closely enough to pick a match.
+fn loose_chars_before(text_page: &PdfiumTextPage, index: i32) -> usize {
+	(0..index)
+		.filter_map(|i| char::from_u32(text_page.get_unicode(i)))
+		.filter(|&ch| !ch.is_control() && ch != '\u{00AD}' && !is_loosely_skipped(ch))
+		.count()
+}
+
 fn sanitize_pdf_text(input: &str) -> String {
 	input.chars().filter(|&ch| (!ch.is_control() || matches!(ch, '\n' | '\r' | '\t')) && ch != '\u{00AD}').collect()
 }
 
 fn char_x_origin(text_page: &PdfiumTextPage, i: i32) -> f32 {
-	let (mut x, mut y) = (0.0, 0.0);
-	let _ = text_page.get_char_origin(i, &mut x, &mut y);
-	x as f32
+	char_origin(text_page, i).0 as f32
 }
 
 /// Assemble one run of `(char, pdfium index)` pairs into text, reordering
@@ -381,40 +810,235 @@ fn reorder_run(text_page: &PdfiumTextPage, chars: &[(char, i32)]) -> String {
 	bidi::reorder_line(&with_origin)
 }
 
-fn extract_text_lines(text_page: &PdfiumTextPage) -> Vec<(String, f64)> {
+/// A run of glyphs on one visual line, with its horizontal extent and baseline in page units.
+#[derive(Clone, Debug, Default)]
+struct LineSegment {
+	text: String,
+	font_size: f64,
+	bold: bool,
+	left: f64,
+	right: f64,
+	baseline: f64,
+}
+
+impl LineSegment {
+	fn text_line(&self) -> TextLine {
+		TextLine { text: self.text.clone(), font_size: self.font_size, bold: self.bold }
+	}
+}
+
+/// One line of a page's text stream. `segments` splits it wherever consecutive glyphs are far
+/// apart horizontally, which is where side-by-side columns meet; it is empty when positions
+/// weren't requested.
+#[derive(Clone, Debug, Default)]
+struct PageLine {
+	text: String,
+	font_size: f64,
+	bold: bool,
+	segments: Vec<LineSegment>,
+}
+
+/// A line in reading order with the type it is set in; an empty one marks a paragraph gap.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct TextLine {
+	text: String,
+	font_size: f64,
+	bold: bool,
+}
+
+struct Glyph {
+	ch: char,
+	index: i32,
+	size: f64,
+	bold: bool,
+	origin: Option<(f64, f64)>,
+}
+
+fn char_origin(text_page: &PdfiumTextPage, i: i32) -> (f64, f64) {
+	let (mut x, mut y) = (0.0, 0.0);
+	let _ = text_page.get_char_origin(i, &mut x, &mut y);
+	(x, y)
+}
+
+fn extract_text_lines(text_page: &PdfiumTextPage, with_positions: bool) -> Vec<PageLine> {
 	let Ok(char_count) = text_page.char_count() else {
 		let raw = sanitize_pdf_text(&text_page.full()).replace('\r', "");
-		return raw.lines().map(|l| (l.to_string(), 0.0)).collect();
+		return raw.lines().map(|l| PageLine { text: l.to_string(), ..PageLine::default() }).collect();
 	};
-	let mut result: Vec<(String, f64)> = Vec::new();
-	// Chars of the current visual line with their pdfium index, so each line can be
-	// reordered visual→logical (handles RTL scripts) before paragraph joining.
-	let mut current_chars: Vec<(char, i32)> = Vec::new();
-	let mut current_sizes: Vec<f64> = Vec::new();
+	let mut result = Vec::new();
+	let mut glyphs: Vec<Glyph> = Vec::new();
+	// pdfium reports a hyphen that breaks a word across lines as U+0002; remember it so the
+	// line keeps its trailing "-" and `join_paragraphs` can rejoin the word.
+	let mut pending_hyphen: Option<i32> = None;
 	for i in 0..char_count {
 		let unicode = text_page.get_unicode(i);
 		let Some(ch) = char::from_u32(unicode) else { continue };
 		if ch == '\n' || ch == '\r' {
-			let size = sorted_median(&mut current_sizes);
-			result.push((reorder_run(text_page, &mem::take(&mut current_chars)), size));
-			current_sizes.clear();
-		} else if (ch.is_control() && !matches!(ch, '\t')) || ch == '\u{00AD}' {
+			if let Some(index) = pending_hyphen.take() {
+				glyphs.push(Glyph { ch: '-', index, size: 0.0, bold: false, origin: None });
+			}
+			result.push(build_page_line(text_page, &mem::take(&mut glyphs)));
+		} else if ch == '\u{0002}' || ch == '\u{00AD}' {
+			pending_hyphen = Some(i);
+		} else if ch.is_control() && ch != '\t' {
 			continue;
 		} else {
+			if !ch.is_whitespace() {
+				pending_hyphen = None;
+			}
 			let size = text_page.get_font_size(i);
-			if size > 0.0 {
-				current_sizes.push(size);
+			let bold = lib().FPDFText_GetFontWeight(text_page, i) >= BOLD_FONT_WEIGHT;
+			let origin = with_positions.then(|| char_origin(text_page, i));
+			glyphs.push(Glyph { ch, index: i, size, bold, origin });
+		}
+	}
+	if let Some(index) = pending_hyphen {
+		glyphs.push(Glyph { ch: '-', index, size: 0.0, bold: false, origin: None });
+	}
+	if !glyphs.is_empty() {
+		result.push(build_page_line(text_page, &glyphs));
+	}
+	result
+}
+
+/// Assembles a line's glyphs into text (reordering RTL runs) and, when glyph positions are
+/// known, into segments split at wide horizontal gaps or baseline jumps.
+fn build_page_line(text_page: &PdfiumTextPage, glyphs: &[Glyph]) -> PageLine {
+	const SEGMENT_GAP_FACTOR: f64 = 2.5;
+	let font_size = glyph_font_size(glyphs);
+	let bold = glyphs_bold(glyphs);
+	let chars: Vec<(char, i32)> = glyphs.iter().map(|glyph| (glyph.ch, glyph.index)).collect();
+	let text = reorder_run(text_page, &chars);
+	let mut segments = Vec::new();
+	let mut start = 0;
+	let mut last_origin: Option<(f64, f64)> = None;
+	for (i, glyph) in glyphs.iter().enumerate() {
+		let Some((x, y)) = glyph.origin else { continue };
+		if glyph.ch.is_whitespace() {
+			continue;
+		}
+		if let Some((last_x, last_y)) = last_origin {
+			let scale = if glyph.size > 0.0 { glyph.size } else { font_size.max(1.0) };
+			if x - last_x > scale * SEGMENT_GAP_FACTOR || (y - last_y).abs() > scale {
+				segments.extend(build_segment(text_page, &glyphs[start..i]));
+				start = i;
 			}
-			current_chars.push((ch, i));
 		}
+		last_origin = Some((x, y));
+	}
+	if last_origin.is_some() {
+		segments.extend(build_segment(text_page, &glyphs[start..]));
 	}
-	if !current_chars.is_empty() {
-		let size = sorted_median(&mut current_sizes);
-		result.push((reorder_run(text_page, &current_chars), size));
+	PageLine { text, font_size, bold, segments }
+}
+
+fn build_segment(text_page: &PdfiumTextPage, glyphs: &[Glyph]) -> Option<LineSegment> {
+	let font_size = glyph_font_size(glyphs);
+	let positioned: Vec<(f64, f64, f64)> = glyphs
+		.iter()
+		.filter(|glyph| !glyph.ch.is_whitespace())
+		.filter_map(|glyph| glyph.origin.map(|(x, y)| (x, y, glyph.size)))
+		.collect();
+	let &(_, baseline, _) = positioned.first()?;
+	let left = positioned.iter().map(|&(x, _, _)| x).fold(f64::INFINITY, f64::min);
+	// Origins mark where a glyph starts; half an em is a reasonable stand-in for its advance.
+	let right = positioned.iter().map(|&(x, _, size)| x + size * 0.5).fold(f64::NEG_INFINITY, f64::max);
+	let chars: Vec<(char, i32)> = glyphs.iter().map(|glyph| (glyph.ch, glyph.index)).collect();
+	Some(LineSegment {
+		text: reorder_run(text_page, &chars),
+		font_size,
+		bold: glyphs_bold(glyphs),
+		left,
+		right,
+		baseline,
+	})
+}
+
+fn glyph_font_size(glyphs: &[Glyph]) -> f64 {
+	let mut sizes: Vec<f64> = glyphs.iter().map(|glyph| glyph.size).filter(|size| *size > 0.0).collect();
+	sorted_median(&mut sizes)
+}
+
+/// Whether most of the visible glyphs are bold.
+fn glyphs_bold(glyphs: &[Glyph]) -> bool {
+	let visible = glyphs.iter().filter(|glyph| !glyph.ch.is_whitespace());
+	let (bold, total) =
+		visible.fold((0usize, 0usize), |(bold, total), glyph| (bold + usize::from(glyph.bold), total + 1));
+	bold * 2 > total
+}
+
+/// Puts a page's lines into reading order. When the page's segments split cleanly into two
+/// side-by-side columns, each horizontal band is emitted left column first, then right, with
+/// full-width lines (titles, running headers) kept in place between bands. Otherwise the
+/// lines are returned in pdfium's stream order.
+fn reading_order_lines(lines: Vec<PageLine>) -> Vec<TextLine> {
+	const PARAGRAPH_GAP_FACTOR: f64 = 1.8;
+	let segments: Vec<&LineSegment> =
+		lines.iter().flat_map(|line| &line.segments).filter(|segment| !segment.text.trim().is_empty()).collect();
+	let Some(gutter) = find_column_gutter(&segments) else {
+		return lines
+			.into_iter()
+			.map(|line| TextLine { text: line.text, font_size: line.font_size, bold: line.bold })
+			.collect();
+	};
+	let mut ordered = segments;
+	// PDF y grows upwards, so the top of the page has the largest baseline.
+	ordered.sort_by(|a, b| b.baseline.partial_cmp(&a.baseline).unwrap_or(std::cmp::Ordering::Equal));
+	let mut result = Vec::new();
+	let mut left_column: Vec<&LineSegment> = Vec::new();
+	let mut right_column: Vec<&LineSegment> = Vec::new();
+	let flush = |column: &mut Vec<&LineSegment>, result: &mut Vec<TextLine>| {
+		let mut previous: Option<&LineSegment> = None;
+		for segment in column.drain(..) {
+			if let Some(previous) = previous
+				&& previous.baseline - segment.baseline > segment.font_size.max(1.0) * PARAGRAPH_GAP_FACTOR
+			{
+				result.push(TextLine::default());
+			}
+			result.push(segment.text_line());
+			previous = Some(segment);
+		}
+	};
+	for segment in ordered {
+		if segment.right <= gutter {
+			left_column.push(segment);
+		} else if segment.left > gutter {
+			right_column.push(segment);
+		} else {
+			flush(&mut left_column, &mut result);
+			flush(&mut right_column, &mut result);
+			result.push(segment.text_line());
+		}
 	}
+	flush(&mut left_column, &mut result);
+	flush(&mut right_column, &mut result);
 	result
 }
 
+/// Finds the x coordinate separating two text columns: the right edge of the left column that
+/// leaves the most balanced split, provided both sides have a few lines and little text
+/// straddles it.
+fn find_column_gutter(segments: &[&LineSegment]) -> Option<f64> {
+	const MIN_COLUMN_LINES: usize = 3;
+	const MAX_SPANNING_PERCENT: usize = 20;
+	let mut best: Option<(usize, f64)> = None;
+	for candidate in segments {
+		let gutter = candidate.right;
+		let left = segments.iter().filter(|segment| segment.right <= gutter).count();
+		let right = segments.iter().filter(|segment| segment.left > gutter).count();
+		let spanning = segments.len() - left - right;
+		if left < MIN_COLUMN_LINES || right < MIN_COLUMN_LINES || spanning * 100 > segments.len() * MAX_SPANNING_PERCENT
+		{
+			continue;
+		}
+		let balance = left.min(right);
+		if best.is_none_or(|(best_balance, _)| balance > best_balance) {
+			best = Some((balance, gutter));
+		}
+	}
+	best.map(|(_, gutter)| gutter)
+}
+
 fn sorted_median(values: &mut Vec<f64>) -> f64 {
 	if values.is_empty() {
 		return 0.0;
@@ -423,26 +1047,32 @@ fn sorted_median(values: &mut Vec<f64>) -> f64 {
 	values[values.len() / 2]
 }
 
-fn median_line_font_size(line_infos: &[(String, f64)]) -> f64 {
+fn median_line_font_size(line_infos: &[TextLine]) -> f64 {
 	let mut sizes: Vec<f64> = line_infos
 		.iter()
-		.filter(|(text, size)| !text.trim().is_empty() && *size > 0.0)
-		.map(|(_, size)| *size)
+		.filter(|line| !line.text.trim().is_empty() && line.font_size > 0.0)
+		.map(|line| line.font_size)
 		.collect();
 	sorted_median(&mut sizes)
 }
 
-fn join_paragraphs(raw_lines: &[(String, f64)], body_font_size: f64) -> Vec<(String, bool)> {
-	const HEADING_FONT_RATIO: f64 = 1.2;
+/// Joins a page's lines into paragraphs. A line set noticeably larger than `body_font_size`
+/// stands alone and carries its style, as a candidate heading.
+fn join_paragraphs(raw_lines: &[TextLine], body_font_size: f64) -> Vec<(String, Option<HeadingStyle>)> {
+	const HEADING_MIN_LEN: usize = 3;
 	const HEADING_MAX_LEN: usize = 150;
 	let heading_threshold = if body_font_size > 0.0 { body_font_size * HEADING_FONT_RATIO } else { f64::INFINITY };
-	let lines: Vec<(String, bool)> = raw_lines
+	let lines: Vec<(String, Option<HeadingStyle>)> = raw_lines
 		.iter()
-		.map(|(text, size)| {
-			let trimmed = trim_string(&collapse_whitespace(text));
+		.map(|line| {
+			let trimmed = trim_string(&collapse_whitespace(&line.text));
 			let len = display_len(&trimmed);
-			let is_heading_line = *size >= heading_threshold && len > 0 && len <= HEADING_MAX_LEN;
-			(trimmed, is_heading_line)
+			// A lone drop cap or page number in display type isn't a heading.
+			let is_heading_line = line.font_size >= heading_threshold
+				&& (HEADING_MIN_LEN..=HEADING_MAX_LEN).contains(&len)
+				&& trimmed.chars().any(char::is_alphabetic);
+			let style = is_heading_line.then_some(HeadingStyle { font_size: line.font_size, bold: line.bold });
+			(trimmed, style)
 		})
 		.collect();
 	let mut max_len = 0usize;
@@ -453,16 +1083,15 @@ fn join_paragraphs(raw_lines: &[(String, f64)], body_font_size: f64) -> Vec<(Str
 		}
 	}
 	let short_line_threshold = (max_len as f32 * 0.75) as usize;
-	let mut paragraphs: Vec<(String, bool)> = Vec::new();
+	let mut paragraphs: Vec<(String, Option<HeadingStyle>)> = Vec::new();
 	let mut current_paragraph = String::new();
-	let mut current_is_heading = false;
+	let mut current_heading: Option<HeadingStyle> = None;
 	let mut last_line_len = 0usize;
 	let mut last_line_ends_with_punctuation = false;
-	for (line, is_heading_line) in &lines {
+	for (line, heading_line) in &lines {
 		if line.is_empty() {
 			if !current_paragraph.is_empty() {
-				paragraphs.push((mem::take(&mut current_paragraph), current_is_heading));
-				current_is_heading = false;
+				paragraphs.push((mem::take(&mut current_paragraph), current_heading.take()));
 			}
 			last_line_len = 0;
 			last_line_ends_with_punctuation = false;
@@ -475,7 +1104,7 @@ fn join_paragraphs(raw_lines: &[(String, f64)], body_font_size: f64) -> Vec<(Str
 		let len = display_len(line);
 		if current_paragraph.is_empty() {
 			current_paragraph = line.clone();
-			current_is_heading = *is_heading_line;
+			current_heading = *heading_line;
 		} else {
 			let mut is_numbered = false;
 			let mut chars = line.chars();
@@ -494,7 +1123,7 @@ fn join_paragraphs(raw_lines: &[(String, f64)], body_font_size: f64) -> Vec<(Str
 				}
 				is_numbered = found_space;
 			}
-			let break_paragraph = if *is_heading_line || current_is_heading {
+			let break_paragraph = if heading_line.is_some() || current_heading.is_some() {
 				true
 			} else if is_list_item || is_numbered {
 				true
@@ -504,13 +1133,17 @@ fn join_paragraphs(raw_lines: &[(String, f64)], body_font_size: f64) -> Vec<(Str
 				last_line_len < short_line_threshold && (starts_with_uppercase || !starts_with_alpha)
 			};
 			if break_paragraph {
-				paragraphs.push((mem::take(&mut current_paragraph), current_is_heading));
+				paragraphs.push((mem::take(&mut current_paragraph), current_heading));
 				current_paragraph = line.clone();
-				current_is_heading = *is_heading_line;
+				current_heading = *heading_line;
 			} else {
 				let last_char = current_paragraph.chars().last().unwrap_or(' ');
 				if current_paragraph.ends_with('-') {
-					current_paragraph.pop();
+					// Only a lowercase continuation means the hyphen split a word ("remem-" / "bers");
+					// otherwise it is part of a compound ("Jean-" / "Paul") and stays.
+					if first_char.is_some_and(char::is_lowercase) {
+						current_paragraph.pop();
+					}
 					current_paragraph.push_str(line);
 				} else if is_cjk(last_char) && line.chars().next().is_some_and(is_cjk) {
 					current_paragraph.push_str(line);
@@ -533,7 +1166,7 @@ fn join_paragraphs(raw_lines: &[(String, f64)], body_font_size: f64) -> Vec<(Str
 			|| line.ends_with('：');
 	}
 	if !current_paragraph.is_empty() {
-		paragraphs.push((current_paragraph, current_is_heading));
+		paragraphs.push((current_paragraph, current_heading));
 	}
 	paragraphs
 }
@@ -549,23 +1182,122 @@ fn map_load_error(err: PdfiumError) -> anyhow::Error {
 	}
 }
 
+/// Returns the security handler name if the PDF at `path` is encrypted with anything other than the
+/// standard password handler. The trailer lives at the end of the file (or near the start when
+/// linearized), so only those regions are scanned before reading the whole file.
+fn drm_security_handler(path: &str) -> Option<String> {
+	const PROBE_LEN: u64 = 64 * 1024;
+	let mut file = File::open(path).ok()?;
+	let len = file.metadata().ok()?.len();
+	let mut probe = Vec::new();
+	file.by_ref().take(PROBE_LEN).read_to_end(&mut probe).ok()?;
+	if len > PROBE_LEN {
+		file.seek(SeekFrom::Start(len.saturating_sub(PROBE_LEN).max(PROBE_LEN))).ok()?;
+		file.read_to_end(&mut probe).ok()?;
+	}
+	find_all(&probe, b"/Encrypt").next()?;
+	let handler = pdf_security_handler(&fs::read(path).ok()?)?;
+	(handler != "Standard").then_some(handler)
+}
+
+/// Name of the security handler declared by a PDF's `/Encrypt` dictionary: `Standard` for
+/// ordinary passwords, something like `EBX_HANDLER` or `FOPN_fLock` for DRM schemes.
+fn pdf_security_handler(bytes: &[u8]) -> Option<String> {
+	const ENCRYPT_KEY: &[u8] = b"/Encrypt";
+	// The trailer comes last, and `/EncryptMetadata` must not be mistaken for the key.
+	let encrypt_end = find_all(bytes, ENCRYPT_KEY)
+		.map(|pos| pos + ENCRYPT_KEY.len())
+		.filter(|&end| bytes.get(end).is_none_or(|byte| !byte.is_ascii_alphanumeric()))
+		.last()?;
+	let value = trim_pdf_whitespace(&bytes[encrypt_end..]);
+	let dictionary = if value.starts_with(b"<<") {
+		value
+	} else {
+		let reference: Vec<&[u8]> =
+			value.split(u8::is_ascii_whitespace).filter(|part| !part.is_empty()).take(3).collect();
+		let [object, generation, b"R"] = reference.as_slice() else {
+			return None;
+		};
+		let header = [*object, b" ", *generation, b" obj"].concat();
+		let start = find_all(bytes, &header).find(|&pos| pos == 0 || !bytes[pos - 1].is_ascii_digit())?;
+		&bytes[start + header.len()..]
+	};
+	let dictionary = &dictionary[..find_all(dictionary, b"endobj").next().unwrap_or(dictionary.len()).min(4096)];
+	let filter_end = find_all(dictionary, b"/Filter").next()? + b"/Filter".len();
+	let name = trim_pdf_whitespace(&dictionary[filter_end..]).strip_prefix(b"/")?;
+	let name_len =
+		name.iter().position(|byte| byte.is_ascii_whitespace() || b"/<>[]()".contains(byte)).unwrap_or(name.len());
+	(name_len > 0).then(|| String::from_utf8_lossy(&name[..name_len]).into_owned())
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+	haystack.windows(needle.len()).enumerate().filter(move |(_, window)| *window == needle).map(|(pos, _)| pos)
+}
+
+fn trim_pdf_whitespace(bytes: &[u8]) -> &[u8] {
+	let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(bytes.len());
+	&bytes[start..]
+}
+
 fn metadata_value(document: &PdfiumDocument, key: &str) -> Option<String> {
 	document.metadata_value(key).ok().map(|value| trim_string(&value)).filter(|value| !value.is_empty())
 }
 
-fn extract_toc(
-	document: &PdfiumDocument,
-	page_offsets: &[usize],
-	page_lines_info: &[Vec<(usize, String)>],
-) -> Vec<TocItem> {
+/// Turns an Info dictionary date (`D:20190314093000+01'00'`) into `2019-03-14`, or the year and
+/// month alone when that is all it gives.
+fn info_date(value: &str) -> Option<String> {
+	let digits: String =
+		value.trim().trim_start_matches("D:").chars().take_while(char::is_ascii_digit).take(8).collect();
+	match digits.len() {
+		8 => Some(format!("{}-{}-{}", &digits[..4], &digits[4..6], &digits[6..])),
+		6 => Some(format!("{}-{}", &digits[..4], &digits[4..])),
+		4 => Some(digits),
+		_ => None,
+	}
+}
+
+/// The printed label of each page ("i", "ii", ..., "1", "2") from the PDF's page label tree, or
+/// nothing when it has none or its labels just count the pages from 1.
+fn read_page_labels(document: &PdfiumDocument, page_count: i32) -> Vec<String> {
+	meaningful_page_labels((0..page_count).map(|index| page_label(document, index)).collect())
+}
+
+fn page_label(document: &PdfiumDocument, page_index: i32) -> String {
+	let mut buffer = vec![0u8; 256];
+	// The label comes back as UTF-16LE with a two-byte terminator; the length is 0 without one.
+	let len = lib().FPDF_GetPageLabel(document, page_index, Some(&mut buffer), 256);
+	let len = usize::try_from(len).unwrap_or(0).min(buffer.len());
+	if len <= 2 {
+		return String::new();
+	}
+	let units: Vec<u16> =
+		buffer[..len - 2].chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+	trim_string(&String::from_utf16_lossy(&units))
+}
+
+/// `labels`, unless they add nothing over the page numbers, in which case pages keep being called
+/// by number exactly as for a PDF without labels.
+fn meaningful_page_labels(labels: Vec<String>) -> Vec<String> {
+	let counts_pages =
+		labels.iter().enumerate().all(|(index, label)| label.is_empty() || *label == (index + 1).to_string());
+	if counts_pages { Vec::new() } else { labels }
+}
+
+/// One outline (bookmark) entry as read from the PDF, before it is mapped onto the extracted text.
+struct OutlineEntry {
+	level: u32,
+	title: String,
+	/// `None` when the destination (e.g. a named destination) couldn't be resolved to a page.
+	page_index: Option<usize>,
+}
+
+/// Reads the outline (bookmarks) up front, so only the pages it points at need their paragraphs kept
+/// for matching entry titles.
+fn read_outline(document: &PdfiumDocument) -> Vec<OutlineEntry> {
 	let Ok(bookmarks) = document.toc(16) else {
 		return Vec::new();
 	};
-	if bookmarks.is_empty() {
-		return Vec::new();
-	}
-	let mut items = Vec::<(u32, TocItem)>::new();
-	let mut used_offsets = HashSet::new();
+	let mut entries = Vec::new();
 	for bookmark in &bookmarks {
 		let Some(level) = bookmark.level() else {
 			continue;
@@ -573,20 +1305,36 @@ fn extract_toc(
 		let Ok(raw_title) = bookmark.title() else {
 			continue;
 		};
-		let title = trim_string(&collapse_whitespace(&raw_title));
+		let page_index = bookmark
+			.dest(document)
+			.ok()
+			.and_then(|dest| dest.index(document))
+			.and_then(|index| usize::try_from(index).ok());
+		entries.push(OutlineEntry { level, title: raw_title, page_index });
+	}
+	entries
+}
+
+/// Maps outline entries onto offsets in the extracted text and nests them by level. Returns the
+/// tree and the number of entries skipped because their destination page is unknown or was not
+/// extracted.
+fn outline_to_toc(
+	entries: Vec<OutlineEntry>,
+	page_offsets: &[usize],
+	page_lines_info: &[Vec<(usize, String)>],
+) -> (Vec<TocItem>, usize) {
+	let mut items = Vec::<(u32, TocItem)>::new();
+	let mut used_offsets = HashSet::new();
+	let mut skipped = 0;
+	for entry in entries {
+		let title = trim_string(&collapse_whitespace(&entry.title));
 		if title.is_empty() {
 			continue;
 		}
-		let Ok(dest) = bookmark.dest(document) else {
-			continue;
-		};
-		let Some(page_index) = dest.index(document) else {
-			continue;
-		};
-		let Ok(page_index) = usize::try_from(page_index) else {
-			continue;
-		};
-		let Some(&page_start_offset) = page_offsets.get(page_index) else {
+		let Some((page_index, &page_start_offset)) =
+			entry.page_index.and_then(|index| page_offsets.get(index).map(|offset| (index, offset)))
+		else {
+			skipped += 1;
 			continue;
 		};
 		let mut actual_offset = page_start_offset;
@@ -616,9 +1364,9 @@ fn extract_toc(
 			actual_offset += 1;
 		}
 		used_offsets.insert(actual_offset);
-		items.push((level, TocItem::new(actual_title, String::new(), actual_offset)));
+		items.push((entry.level, TocItem::new(actual_title, String::new(), actual_offset)));
 	}
-	build_toc_tree(items)
+	(build_toc_tree(items), skipped)
 }
 
 fn build_toc_tree(flat_items: Vec<(u32, TocItem)>) -> Vec<TocItem> {
@@ -881,8 +1629,156 @@ fn append_pdf_table_to_buffer(
 
 #[cfg(test)]
 mod tests {
-	use super::{append_pdf_table_to_buffer, join_paragraphs, sanitize_pdf_text};
-	use crate::document::{DocumentBuffer, MarkerType};
+	use super::{
+		HeadingCandidate, HeadingStyle, LineSegment, OutlineEntry, PageLine, PdfExtraction, PdfParser, TextLine,
+		UnplacedAnnotation, append_pdf_table_to_buffer, classify_headings, find_loosely, find_loosely_near, info_date,
+		join_paragraphs, meaningful_page_labels, outline_to_toc, pdf_security_handler, reading_order_lines,
+		sanitize_pdf_text,
+	};
+	use crate::{
+		document::{DocumentBuffer, MarkerType, ParserContext},
+		parser::Parser,
+	};
+
+	fn labels(labels: &[&str]) -> Vec<String> {
+		labels.iter().map(ToString::to_string).collect()
+	}
+
+	/// Front matter numbered i–iii, then arabic numbering that starts at 5 (a `/St 5` range).
+	#[test]
+	fn page_breaks_are_named_after_printed_page_labels() {
+		let front_matter_then_offset = labels(&["i", "ii", "iii", "5", "6"]);
+		assert_eq!(meaningful_page_labels(front_matter_then_offset.clone()), front_matter_then_offset);
+		let mut extraction = PdfExtraction::new(&[], front_matter_then_offset);
+		for page_index in 0..5 {
+			extraction.begin_page(page_index);
+		}
+		let names: Vec<&str> = extraction.buffer.markers.iter().map(|marker| marker.text.as_str()).collect();
+		assert_eq!(names, ["Page i", "Page ii", "Page iii", "Page 5", "Page 6"]);
+	}
+
+	#[test]
+	fn highlighted_text_is_found_across_line_breaks_and_hyphens() {
+		let page = "The commit-\nment was made in good faith. Both parties agree.\n";
+		let found = |needle| find_loosely(page, needle).map(|range| &page[range]);
+		assert_eq!(found("commitment was\r\nmade"), Some("commit-\nment was made"));
+		assert_eq!(found("Both  parties"), Some("Both parties"));
+		assert_eq!(found("agree. "), Some("agree."));
+		assert_eq!(found("disagree"), None);
+		assert_eq!(found(" \r\n"), None);
+	}
+
+	#[test]
+	fn highlights_of_repeated_text_are_placed_by_their_position() {
+		let page = "Note well. The first part.\nNote well. The second part.\n";
+		let found = |near| find_loosely_near(page, "Note well.", near).map(|range| range.start);
+		assert_eq!(found(0), Some(0));
+		assert_eq!(found(19), Some(27));
+		assert_eq!(found(100), Some(27));
+		assert_eq!(find_loosely_near(page, "missing", 19), None);
+	}
+
+	#[test]
+	fn unplaced_annotations_are_listed_after_the_last_page() {
+		let mut extraction = PdfExtraction::new(&[], labels(&["iv", "v"]));
+		extraction.begin_page(0);
+		extraction.buffer.append("Body text.\n");
+		extraction.unplaced_annotations.push(UnplacedAnnotation {
+			page_index: 1,
+			author: "Ada".to_string(),
+			note: String::new(),
+			quoted: "a figure caption".to_string(),
+		});
+		extraction.unplaced_annotations.push(UnplacedAnnotation {
+			page_index: 0,
+			author: String::new(),
+			note: "Check the date".to_string(),
+			quoted: String::new(),
+		});
+		extraction.finish(Vec::new(), &ParserContext::new(String::new()));
+		assert_eq!(
+			extraction.buffer.content,
+			"Body text.\nAnnotations not found in the text:\nPage v: Ada: a figure caption\nPage iv: Check the date\n"
+		);
+		let annotations: Vec<(usize, usize, &str, &str, i32)> = extraction
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::Annotation)
+			.map(|marker| {
+				(marker.position, marker.length, marker.text.as_str(), marker.reference.as_str(), marker.level)
+			})
+			.collect();
+		assert_eq!(annotations, [(46, 29, "", "Ada", 2), (76, 23, "Check the date", "", 1)]);
+	}
+
+	#[test]
+	fn labels_that_only_count_pages_are_dropped() {
+		assert!(meaningful_page_labels(labels(&["1", "2", "3"])).is_empty());
+		assert!(meaningful_page_labels(labels(&["", "2", ""])).is_empty());
+		let mut extraction = PdfExtraction::new(&[], Vec::new());
+		extraction.begin_page(0);
+		assert_eq!(extraction.buffer.markers[0].text, "Page 1");
+	}
+
+	/// Builds a stream line from `(text, left, right, baseline)` segments, as pdfium reports them.
+	fn page_line(segments: &[(&str, f64, f64, f64)]) -> PageLine {
+		let segments: Vec<LineSegment> = segments
+			.iter()
+			.map(|&(text, left, right, baseline)| LineSegment {
+				text: text.to_string(),
+				font_size: 10.0,
+				bold: false,
+				left,
+				right,
+				baseline,
+			})
+			.collect();
+		let text = segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join("   ");
+		PageLine { text, font_size: 10.0, bold: false, segments }
+	}
+
+	/// A synthetic two-column page whose text stream interleaves the columns row by row, with a
+	/// full-width title above them and a footer below.
+	fn two_column_page() -> Vec<PageLine> {
+		vec![
+			page_line(&[("A Study of Columns", 150.0, 460.0, 760.0)]),
+			page_line(&[("Left one", 72.0, 290.0, 720.0), ("Right one", 320.0, 540.0, 720.0)]),
+			page_line(&[("left two", 72.0, 288.0, 708.0), ("right two", 320.0, 538.0, 708.0)]),
+			page_line(&[("left three", 72.0, 285.0, 696.0), ("right three", 320.0, 536.0, 696.0)]),
+			page_line(&[("Left four.", 72.0, 200.0, 684.0), ("right four.", 320.0, 420.0, 684.0)]),
+			page_line(&[("Page footer", 250.0, 360.0, 40.0)]),
+		]
+	}
+
+	fn texts(lines: &[TextLine]) -> Vec<&str> {
+		lines.iter().map(|line| line.text.as_str()).collect()
+	}
+
+	fn text_lines(lines: &[(&str, f64)]) -> Vec<TextLine> {
+		lines.iter().map(|&(text, font_size)| TextLine { text: text.to_string(), font_size, bold: false }).collect()
+	}
+
+	#[test]
+	fn pdf_security_handler_follows_indirect_encrypt_reference() {
+		let pdf = b"%PDF-1.6\n11 0 obj\n<< /Filter /Standard >>\nendobj\n1 0 obj\n<< /Filter /EBX_HANDLER /V 4 /EncryptMetadata false >>\nendobj\ntrailer\n<< /Root 2 0 R /Encrypt 1 0 R >>\n%%EOF";
+		assert_eq!(pdf_security_handler(pdf).as_deref(), Some("EBX_HANDLER"));
+	}
+
+	#[test]
+	fn pdf_security_handler_reads_inline_dictionary_and_ignores_unencrypted_files() {
+		let pdf = b"%PDF-1.4\ntrailer\n<< /Encrypt << /Filter/Standard /V 2 >> >>\n%%EOF";
+		assert_eq!(pdf_security_handler(pdf).as_deref(), Some("Standard"));
+		assert_eq!(pdf_security_handler(b"%PDF-1.4\ntrailer\n<< /Root 1 0 R >>\n%%EOF"), None);
+	}
+
+	#[test]
+	fn info_dates_are_shown_as_calendar_dates() {
+		assert_eq!(info_date("D:20190314093000+01'00'").as_deref(), Some("2019-03-14"));
+		assert_eq!(info_date("D:201903").as_deref(), Some("2019-03"));
+		assert_eq!(info_date("2019").as_deref(), Some("2019"));
+		assert_eq!(info_date("March 2019"), None);
+	}
 
 	#[test]
 	fn sanitize_pdf_text_strips_control_chars_and_soft_hyphens() {
@@ -892,23 +1788,134 @@ mod tests {
 
 	#[test]
 	fn join_paragraphs_merges_continuation_lines() {
-		let lines = vec![("The suggestion appears here.".to_string(), 12.0), ("And here.".to_string(), 12.0)];
+		let lines = text_lines(&[("The suggestion appears here.", 12.0), ("And here.", 12.0)]);
 		let result = join_paragraphs(&lines, 12.0);
 		assert_eq!(result.len(), 1);
 		assert_eq!(result[0].0, "The suggestion appears here. And here.");
-		assert!(!result[0].1);
+		assert_eq!(result[0].1, None);
 	}
 
 	#[test]
 	fn join_paragraphs_flags_large_font_lines_as_headings() {
-		let lines =
-			vec![("Chapter One".to_string(), 18.0), ("This is the body text of the document.".to_string(), 12.0)];
+		let mut lines = text_lines(&[("Chapter One", 18.0), ("This is the body text of the document.", 12.0)]);
+		lines[0].bold = true;
 		let result = join_paragraphs(&lines, 12.0);
 		assert_eq!(result.len(), 2);
 		assert_eq!(result[0].0, "Chapter One");
-		assert!(result[0].1);
+		assert_eq!(result[0].1, Some(HeadingStyle { font_size: 18.0, bold: true }));
 		assert_eq!(result[1].0, "This is the body text of the document.");
-		assert!(!result[1].1);
+		assert_eq!(result[1].1, None);
+	}
+
+	#[test]
+	fn join_paragraphs_ignores_large_lines_too_short_to_be_headings() {
+		let lines = text_lines(&[("T", 36.0), ("12", 18.0), ("he story begins on a quiet morning.", 12.0)]);
+		let result = join_paragraphs(&lines, 12.0);
+		assert!(result.iter().all(|(_, heading)| heading.is_none()), "{result:?}");
+	}
+
+	fn candidate(offset: usize, text: &str, font_size: f64, bold: bool) -> HeadingCandidate {
+		HeadingCandidate {
+			offset,
+			text: text.to_string(),
+			style: HeadingStyle { font_size, bold },
+			page_min_size: 11.0,
+		}
+	}
+
+	fn levels(headings: &[(u32, crate::document::TocItem)]) -> Vec<(u32, &str)> {
+		headings.iter().map(|(level, item)| (*level, item.name.as_str())).collect()
+	}
+
+	#[test]
+	fn heading_sizes_cluster_into_three_levels() {
+		let candidates = vec![
+			candidate(0, "Part One", 24.0, true),
+			candidate(10, "Chapter 1", 16.0, false),
+			candidate(20, "A Section", 13.5, true),
+			candidate(30, "Chapter 2", 16.2, false),
+			candidate(40, "A Smaller Section", 13.4, false),
+			candidate(50, "Part Two", 23.8, true),
+			candidate(60, "Tiny", 12.0, true),
+		];
+		let headings = classify_headings(candidates, 11.0, 500);
+		assert_eq!(
+			levels(&headings),
+			[
+				(1, "Part One"),
+				(2, "Chapter 1"),
+				(3, "A Section"),
+				(2, "Chapter 2"),
+				(3, "A Smaller Section"),
+				(1, "Part Two"),
+			]
+		);
+		assert_eq!(headings[1].1.offset, 10);
+	}
+
+	#[test]
+	fn bold_ranks_above_regular_at_the_same_size() {
+		let candidates = vec![candidate(0, "Chapter", 16.0, true), candidate(10, "Section", 16.0, false)];
+		assert_eq!(levels(&classify_headings(candidates, 11.0, 500)), [(1, "Chapter"), (2, "Section")]);
+	}
+
+	#[test]
+	fn pages_set_entirely_in_large_type_are_skipped() {
+		let mut title = candidate(0, "The Book Title", 36.0, true);
+		title.page_min_size = 24.0;
+		let candidates = vec![title, candidate(100, "Chapter 1", 24.0, true)];
+		assert_eq!(levels(&classify_headings(candidates, 11.0, 500)), [(1, "Chapter 1")]);
+	}
+
+	#[test]
+	fn the_heading_cap_drops_the_deepest_levels_first() {
+		let mut candidates = Vec::new();
+		for chapter in 0..3 {
+			candidates.push(candidate(chapter * 100, &format!("Chapter {chapter}"), 20.0, true));
+			for section in 1..5 {
+				candidates.push(candidate(chapter * 100 + section, &format!("Section {section}"), 14.0, true));
+			}
+		}
+		let headings = classify_headings(candidates.clone(), 11.0, 5);
+		assert_eq!(levels(&headings), [(1, "Chapter 0"), (1, "Chapter 1"), (1, "Chapter 2")]);
+		assert_eq!(classify_headings(candidates, 11.0, 2).len(), 2);
+	}
+
+	#[test]
+	fn no_body_text_means_no_detected_headings() {
+		assert!(classify_headings(vec![candidate(0, "Chapter 1", 24.0, true)], 0.0, 500).is_empty());
+	}
+
+	/// The fixture has a title page (36 and 24 pt), then chapters in 24 pt bold, sections in 16 pt
+	/// and subsections in 13.5 pt bold over 11 pt body text, and no outline.
+	#[test]
+	#[ignore = "needs the pdfium library"]
+	fn headings_are_detected_from_font_sizes_in_a_pdf_without_an_outline() {
+		let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/pdf/headings.pdf");
+		let doc = PdfParser.parse(&ParserContext::new(path.to_string())).unwrap();
+		let names = |items: &[crate::document::TocItem]| items.iter().map(|item| item.name.clone()).collect::<Vec<_>>();
+		assert_eq!(names(&doc.toc_items), ["Chapter One", "Chapter Two"]);
+		assert_eq!(names(&doc.toc_items[0].children), ["First Section", "Second Section"]);
+		assert_eq!(names(&doc.toc_items[0].children[0].children), ["A Subsection"]);
+		let levels: Vec<(MarkerType, &str)> = doc
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| matches!(marker.mtype, MarkerType::Heading1 | MarkerType::Heading2 | MarkerType::Heading3))
+			.map(|marker| (marker.mtype, marker.text.as_str()))
+			.collect();
+		assert_eq!(
+			levels,
+			[
+				(MarkerType::Heading1, "Chapter One"),
+				(MarkerType::Heading2, "First Section"),
+				(MarkerType::Heading3, "A Subsection"),
+				(MarkerType::Heading2, "Second Section"),
+				(MarkerType::Heading1, "Chapter Two"),
+			]
+		);
+		let disabled = PdfParser.parse(&ParserContext::new(path.to_string()).with_detect_headings(false)).unwrap();
+		assert!(disabled.toc_items.is_empty());
 	}
 
 	/// OFF mode: the PDF table helper emits a single `"[Table]: <first row>"` placeholder line and
@@ -979,4 +1986,99 @@ mod tests {
 		let table_marker = buffer.markers.iter().find(|m| m.mtype == MarkerType::Table).expect("Table marker present");
 		assert_eq!(table_marker.length, 0, "zero-length marker for empty inline table");
 	}
+
+	#[test]
+	fn two_column_page_is_read_column_by_column() {
+		let ordered = reading_order_lines(two_column_page());
+		assert_eq!(
+			texts(&ordered),
+			vec![
+				"A Study of Columns",
+				"Left one",
+				"left two",
+				"left three",
+				"Left four.",
+				"Right one",
+				"right two",
+				"right three",
+				"right four.",
+				"Page footer",
+			]
+		);
+	}
+
+	#[test]
+	fn single_column_page_keeps_stream_order() {
+		let lines = vec![
+			page_line(&[("First line of text", 72.0, 540.0, 720.0)]),
+			page_line(&[("Second line of text", 72.0, 530.0, 708.0)]),
+			page_line(&[("Short", 90.0, 140.0, 696.0)]),
+			page_line(&[("Fourth line of text", 72.0, 520.0, 684.0)]),
+		];
+		let ordered = reading_order_lines(lines);
+		assert_eq!(texts(&ordered), vec!["First line of text", "Second line of text", "Short", "Fourth line of text"]);
+	}
+
+	#[test]
+	fn lines_without_positions_keep_stream_order() {
+		let lines =
+			two_column_page().into_iter().map(|line| PageLine { segments: Vec::new(), ..line }).collect::<Vec<_>>();
+		let ordered = reading_order_lines(lines);
+		assert_eq!(ordered[1].text, "Left one   Right one");
+	}
+
+	#[test]
+	fn join_paragraphs_rejoins_hyphenated_words_only_before_lowercase() {
+		let lines = text_lines(&[
+			("The old man remem-", 12.0),
+			("bers the days when Jean-", 12.0),
+			("Paul came to visit them all.", 12.0),
+		]);
+		let result = join_paragraphs(&lines, 12.0);
+		assert_eq!(result.len(), 1);
+		assert_eq!(result[0].0, "The old man remembers the days when Jean-Paul came to visit them all.");
+	}
+
+	fn outline(level: u32, title: &str, page_index: Option<usize>) -> OutlineEntry {
+		OutlineEntry { level, title: title.to_string(), page_index }
+	}
+
+	#[test]
+	fn outline_maps_three_levels_to_page_offsets() {
+		let entries = vec![
+			outline(0, "Part One", Some(0)),
+			outline(1, "Chapter 1", Some(1)),
+			outline(2, "Section 1.1", Some(2)),
+			outline(1, "Chapter 2", Some(3)),
+			outline(0, "Part Two", Some(4)),
+		];
+		let page_offsets = [0, 100, 250, 400, 600];
+		let page_lines = vec![Vec::new(), vec![(110, "Chapter 1".to_string())], Vec::new(), Vec::new(), Vec::new()];
+		let (toc, skipped) = outline_to_toc(entries, &page_offsets, &page_lines);
+		assert_eq!(skipped, 0);
+		assert_eq!(toc.len(), 2);
+		assert_eq!(toc[0].name, "Part One");
+		assert_eq!(toc[0].offset, 0);
+		assert_eq!(toc[0].children.len(), 2);
+		assert_eq!(toc[0].children[0].name, "Chapter 1");
+		assert_eq!(toc[0].children[0].offset, 110, "offset snaps to the matching line on the page");
+		assert_eq!(toc[0].children[0].children[0].name, "Section 1.1");
+		assert_eq!(toc[0].children[0].children[0].offset, 250);
+		assert_eq!(toc[0].children[1].offset, 400);
+		assert_eq!(toc[1].name, "Part Two");
+		assert_eq!(toc[1].offset, 600);
+	}
+
+	#[test]
+	fn outline_skips_unresolved_and_out_of_range_entries() {
+		let entries = vec![
+			outline(0, "Intro", Some(0)),
+			outline(0, "Named destination", None),
+			outline(0, "Beyond extracted pages", Some(9)),
+			outline(0, "Appendix", Some(1)),
+		];
+		let (toc, skipped) = outline_to_toc(entries, &[0, 50], &[]);
+		assert_eq!(skipped, 2);
+		assert_eq!(toc.iter().map(|item| item.name.as_str()).collect::<Vec<_>>(), vec!["Intro", "Appendix"]);
+	}
 }
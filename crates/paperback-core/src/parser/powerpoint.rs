@@ -1,7 +1,7 @@
 use std::{
 	collections::HashMap,
 	fs::File,
-	io::{Cursor, Read},
+	io::{Read, Seek},
 	path::Path,
 };
 
@@ -13,18 +13,25 @@ use zip::ZipArchive;
 use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, TocItem},
 	parser::{
-		Parser,
+		Parser, append_speaker_notes,
+		error::ParserError,
+		slide_marker_text,
 		table_text::{
 			build_html_table_from_grid, display_lines_and_length, html_table_to_display, table_caption_from_html,
 		},
 		util::{
-			ooxml::read_ooxml_relationships, path::extract_title_from_path, xml::collect_text_from_tagged_elements,
+			ooxml::{find_ooxml_relationship_target, read_ooxml_relationships, resolve_ooxml_part_path},
+			path::extract_title_from_path,
+			xml::collect_text_from_tagged_elements,
 		},
 		word::try_decrypt_office_file,
 	},
 	t,
 	types::LinkInfo,
-	util::{text::display_len, zip::read_zip_entry_by_name},
+	util::{
+		text::display_len,
+		zip::{OpenedZip, open_zip_bytes, read_zip_entry_by_name},
+	},
 };
 
 /// A table found while traversing a slide. Markers are added after the slide text is appended to
@@ -81,8 +88,8 @@ fn parse_pptx(context: &ParserContext) -> Result<Document> {
 		None => std::fs::read(&context.file_path)
 			.with_context(|| format!("Failed to read PPTX file '{}'", context.file_path))?,
 	};
-	let mut archive = ZipArchive::new(Cursor::new(bytes))
-		.with_context(|| format!("Failed to read PPTX as zip '{}'", context.file_path))?;
+	let OpenedZip { mut archive, salvaged } =
+		open_zip_bytes(bytes).with_context(|| format!("Failed to read PPTX as zip '{}'", context.file_path))?;
 	let mut slides = (0..archive.len())
 		.filter_map(|i| archive.by_index(i).ok().map(|entry| entry.name().to_string()))
 		.filter(|name| {
@@ -118,41 +125,83 @@ fn parse_pptx(context: &ParserContext) -> Result<Document> {
 			&rels,
 			context.render_tables_inline,
 		);
-		if !slide_text.trim().is_empty() {
+		let has_text = !slide_text.trim().is_empty();
+		if has_text {
 			buffer.append(&slide_text);
 			if !buffer.content.ends_with('\n') {
 				buffer.append("\n");
 			}
-			if index + 1 < slides.len() {
-				buffer.append("\n");
-			}
-			buffer
-				.add_marker(Marker::new(MarkerType::PageBreak, slide_start).with_text(format!("Slide {}", index + 1)));
-			for link in links {
-				buffer.add_marker(
-					Marker::new(MarkerType::Link, link.offset).with_text(link.text).with_reference(link.reference),
-				);
-			}
-			for table in tables {
-				buffer.add_marker(
-					Marker::new(MarkerType::Table, table.offset)
-						.with_text(table.caption)
-						.with_reference(table.html)
-						.with_length(table.length),
-				);
-			}
-			let toc_name = if slide_title.is_empty() { format!("Slide {}", index + 1) } else { slide_title.clone() };
-			toc_items.push(TocItem::new(toc_name, String::new(), slide_start));
 		}
+		if context.include_speaker_notes {
+			append_speaker_notes(&mut buffer, &read_slide_notes(&mut archive, slide_base));
+		}
+		if index + 1 < slides.len() {
+			buffer.append("\n");
+		}
+		let marker_text = slide_marker_text(index + 1, &slide_title, has_text);
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, slide_start).with_text(marker_text));
+		for link in links {
+			buffer.add_marker(
+				Marker::new(MarkerType::Link, link.offset).with_text(link.text).with_reference(link.reference),
+			);
+		}
+		for table in tables {
+			buffer.add_marker(
+				Marker::new(MarkerType::Table, table.offset)
+					.with_text(table.caption)
+					.with_reference(table.html)
+					.with_length(table.length),
+			);
+		}
+		let toc_name = if slide_title.is_empty() { format!("Slide {}", index + 1) } else { slide_title };
+		toc_items.push(TocItem::new(toc_name, String::new(), slide_start));
 	}
 	let title = extract_title_from_path(&context.file_path);
 	let mut document = Document::new().with_title(title);
 	document.set_buffer(buffer);
 	document.id_positions = id_positions;
 	document.toc_items = toc_items;
+	document.damaged = salvaged;
 	Ok(document)
 }
 
+/// Reads the speaker notes of `slide_base` (e.g. `slide3.xml`) through its `notesSlide` relationship.
+fn read_slide_notes<R: Read + Seek>(archive: &mut ZipArchive<R>, slide_base: &str) -> String {
+	let rels_name = format!("ppt/slides/_rels/{slide_base}.rels");
+	let Some(target) = find_ooxml_relationship_target(archive, &rels_name, "/notesSlide") else {
+		return String::new();
+	};
+	let notes_name = resolve_ooxml_part_path("ppt/slides", &target);
+	read_zip_entry_by_name(archive, &notes_name)
+		.ok()
+		.and_then(|content| XmlDocument::parse(&content).ok().map(|doc| extract_notes_text(doc.root())))
+		.unwrap_or_default()
+}
+
+/// Collects the paragraphs of a notes slide's body placeholder, skipping the slide thumbnail and
+/// the header/footer/slide-number placeholders.
+fn extract_notes_text(root: Node) -> String {
+	root.descendants()
+		.filter(|node| {
+			node.node_type() == NodeType::Element && node.tag_name().name() == "sp" && is_notes_body_shape(*node)
+		})
+		.flat_map(|shape| {
+			shape.descendants().filter(|node| node.node_type() == NodeType::Element && node.tag_name().name() == "p")
+		})
+		.map(|paragraph| collect_text_from_tagged_elements(paragraph, "t").trim().to_string())
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+fn is_notes_body_shape(node: Node) -> bool {
+	node.descendants().any(|child| {
+		child.node_type() == NodeType::Element
+			&& child.tag_name().name() == "ph"
+			&& child.attribute("type").is_some_and(|ph_type| ph_type == "body")
+	})
+}
+
 fn parse_legacy_ppt(context: &ParserContext) -> Result<Document> {
 	let file =
 		File::open(&context.file_path).with_context(|| format!("Failed to open PPT file '{}'", context.file_path))?;
@@ -162,9 +211,10 @@ fn parse_legacy_ppt(context: &ParserContext) -> Result<Document> {
 	// Encrypted PPT files have an EncryptionInfo stream. We can detect but not decrypt them.
 	if compound.entry("/EncryptionInfo").is_ok() {
 		// TRANSLATORS: Error shown when a legacy PPT file is password-protected, which this parser cannot handle
-		anyhow::bail!(t(
-			"Password-protected PPT files are not currently supported. Try saving the file as PPTX and opening that instead."
-		));
+		return Err(ParserError::UnsupportedVariant(t(
+			"Password-protected PPT files are not currently supported. Try saving the file as PPTX and opening that instead.",
+		))
+		.into());
 	}
 
 	let ppt_document_stream = read_ppt_document_stream(&mut compound)
@@ -506,9 +556,11 @@ mod tests {
 	use rstest::rstest;
 
 	use super::{
-		display_len, extract_legacy_text, extract_slide_number, extract_slide_text, extract_slide_title,
-		is_title_shape, normalize_legacy_slide_text, parse_cstring, parse_text_bytes_atom, parse_text_chars_atom,
+		display_len, extract_legacy_text, extract_notes_text, extract_slide_number, extract_slide_text,
+		extract_slide_title, is_title_shape, normalize_legacy_slide_text, parse_cstring, parse_text_bytes_atom,
+		parse_text_chars_atom,
 	};
+	use crate::parser::util::ooxml::resolve_ooxml_part_path;
 
 	#[rstest]
 	#[case("ppt/slides/slide1.xml", 1)]
@@ -560,6 +612,32 @@ mod tests {
 		assert!(extract_slide_title(doc.root()).is_empty());
 	}
 
+	#[test]
+	fn extract_notes_text_reads_body_placeholder_only() {
+		let xml = r#"
+			<root>
+				<sp><nvSpPr><nvPr><ph type="sldImg" /></nvPr></nvSpPr></sp>
+				<sp><nvSpPr><nvPr><ph type="body" idx="1" /></nvPr></nvSpPr>
+					<txBody><p><r><t>Mention the</t></r><r><t> budget</t></r></p><p/><p><r><t>Pause</t></r></p></txBody></sp>
+				<sp><nvSpPr><nvPr><ph type="sldNum" /></nvPr></nvSpPr><txBody><p><r><t>4</t></r></p></txBody></sp>
+			</root>
+		"#;
+		let doc = XmlDocument::parse(xml).expect("xml parse");
+		assert_eq!(extract_notes_text(doc.root()), "Mention the budget\nPause");
+	}
+
+	#[rstest]
+	#[case("ppt/slides", "../notesSlides/notesSlide1.xml", "ppt/notesSlides/notesSlide1.xml")]
+	#[case("ppt/slides", "./media/image1.png", "ppt/slides/media/image1.png")]
+	#[case("ppt/slides", "/ppt/notesSlides/notesSlide2.xml", "ppt/notesSlides/notesSlide2.xml")]
+	fn resolve_ooxml_part_path_handles_relative_targets(
+		#[case] base: &str,
+		#[case] target: &str,
+		#[case] expected: &str,
+	) {
+		assert_eq!(resolve_ooxml_part_path(base, target), expected);
+	}
+
 	#[test]
 	fn extract_slide_text_collects_paragraphs_and_breaks() {
 		let xml = r#"
@@ -11,6 +11,7 @@ use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags},
 	parser::{Parser, util::path::extract_title_from_path},
 	t,
+	util::text::display_len,
 };
 
 pub struct RtfParser;
@@ -574,7 +575,7 @@ fn extract_content_from_tokens(tokens: &[Token]) -> DocumentBuffer {
 						pending_link = Some(PendingLink { url, start_position: buffer.current_position() });
 					} else if let Some(link) = pending_link.take() {
 						let display_text = text.to_string();
-						let text_len = display_text.chars().count();
+						let text_len = display_len(&display_text);
 						buffer.append(&display_text);
 						buffer.add_marker(
 							Marker::new(MarkerType::Link, link.start_position)
@@ -97,6 +97,38 @@ fn first_tsv_row_text(tsv: &str) -> String {
 	trim_string(&collapse_whitespace(&first_line))
 }
 
+/// Pad tab-separated cells with spaces so every column lines up, separating columns by two spaces.
+/// Widths are counted in characters; trailing padding on the last cell of a row is dropped.
+#[must_use]
+pub fn align_tsv_columns(tsv: &str) -> String {
+	let rows: Vec<Vec<&str>> = tsv.split('\n').map(|row| row.split('\t').collect()).collect();
+	let mut widths: Vec<usize> = Vec::new();
+	for row in &rows {
+		for (column, cell) in row.iter().enumerate() {
+			let width = cell.chars().count();
+			match widths.get_mut(column) {
+				Some(existing) => *existing = (*existing).max(width),
+				None => widths.push(width),
+			}
+		}
+	}
+	let aligned: Vec<String> = rows
+		.iter()
+		.map(|row| {
+			let mut line = String::new();
+			for (column, cell) in row.iter().enumerate() {
+				if column > 0 {
+					line.push_str("  ");
+				}
+				line.push_str(cell);
+				line.extend(std::iter::repeat_n(' ', widths[column] - cell.chars().count()));
+			}
+			line.trim_end().to_string()
+		})
+		.collect();
+	aligned.join("\n")
+}
+
 pub struct TableRenderBundle {
 	pub caption: String,
 	pub lines: Vec<String>,
@@ -224,6 +256,12 @@ fn collect_cell_text(node: ego_tree::NodeRef<'_, Node>, buffer: &mut String) {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn aligned_columns_pad_to_the_widest_cell() {
+		let aligned = align_tsv_columns("Name\tAge\nAlice\t30\nBo\t7");
+		assert_eq!(aligned, "Name   Age\nAlice  30\nBo     7");
+	}
+
 	#[test]
 	fn two_by_two_table_is_tab_and_newline_separated() {
 		let html = "<table><tr><td>a</td><td>b</td></tr><tr><td>c</td><td>d</td></tr></table>";
@@ -1,13 +1,26 @@
-use std::fs;
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 
 use crate::{
-	document::{Document, DocumentBuffer, ParserContext, ParserFlags},
+	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, TocItem},
 	parser::{Parser, util::path::extract_title_from_path},
-	util::{encoding::convert_to_utf8, text::remove_soft_hyphens},
+	t,
+	util::{
+		encoding::decode_text,
+		text::{natural_cmp, remove_soft_hyphens},
+	},
 };
 
+/// Forced format used to read a set of numbered text files (`book1.txt`, `book2.txt`, …) as one book.
+pub const TEXT_PARTS_FORMAT: &str = "txtparts";
+
+const TEXT_EXTENSIONS: [&str; 2] = ["txt", "log"];
+
 pub struct TextParser;
 
 impl Parser for TextParser {
@@ -16,7 +29,11 @@ impl Parser for TextParser {
 	}
 
 	fn extensions(&self) -> &[&str] {
-		&["txt", "log"]
+		&TEXT_EXTENSIONS
+	}
+
+	fn reads_encoding(&self) -> bool {
+		true
 	}
 
 	fn supported_flags(&self) -> ParserFlags {
@@ -24,13 +41,214 @@ impl Parser for TextParser {
 	}
 
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
-		let bytes = fs::read(&context.file_path)
-			.with_context(|| format!("Failed to open text file '{}'", context.file_path))?;
-		let utf8_content = convert_to_utf8(&bytes);
-		let processed = remove_soft_hyphens(&utf8_content);
 		let title = extract_title_from_path(&context.file_path);
 		let mut doc = Document::new().with_title(title);
-		doc.set_buffer(DocumentBuffer::with_content(processed));
+		let text = read_text_file(Path::new(&context.file_path), context.encoding.as_deref())?;
+		doc.set_buffer(DocumentBuffer::with_content(text));
+		Ok(doc)
+	}
+}
+
+/// Reads every numbered sibling of the opened text file, in numeric order, as a single document.
+pub struct TextPartsParser;
+
+impl Parser for TextPartsParser {
+	fn name(&self) -> &'static str {
+		"Multi-part Text Books"
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&[TEXT_PARTS_FORMAT]
+	}
+
+	fn reads_encoding(&self) -> bool {
+		true
+	}
+
+	fn supported_flags(&self) -> ParserFlags {
+		ParserFlags::SUPPORTS_TOC | ParserFlags::SUPPORTS_SECTIONS
+	}
+
+	fn open_as_only(&self) -> bool {
+		true
+	}
+
+	fn parse(&self, context: &ParserContext) -> Result<Document> {
+		let path = Path::new(&context.file_path);
+		let mut parts = text_parts(path);
+		if parts.is_empty() {
+			parts.push(path.to_path_buf());
+		}
+		let mut buffer = DocumentBuffer::new();
+		let mut toc_items = Vec::new();
+		let mut id_positions = HashMap::new();
+		for part in &parts {
+			let text = read_text_file(part, context.encoding.as_deref())?;
+			let name = part.file_name().map_or_else(String::new, |name| name.to_string_lossy().to_string());
+			let start = buffer.current_position();
+			buffer.add_marker(
+				Marker::new(MarkerType::SectionBreak, start).with_text(name.clone()).with_reference(name.clone()),
+			);
+			id_positions.insert(name.clone(), start);
+			toc_items.push(TocItem::new(name.clone(), name, start));
+			buffer.append(&text);
+			if !buffer.content.ends_with('\n') {
+				buffer.append("\n");
+			}
+		}
+		if toc_items.is_empty() {
+			// TRANSLATORS: Error shown when none of the files of a multi-part text book could be read; {} is the path
+			anyhow::bail!(t("Multi-part text book has no readable files: {}").replace("{}", &context.file_path));
+		}
+		let mut doc = Document::new().with_title(parts_title(&parts[0]));
+		doc.set_buffer(buffer);
+		doc.toc_items = toc_items;
+		doc.id_positions = id_positions;
 		Ok(doc)
 	}
 }
+
+/// The numbered siblings of `path` that make up one multi-part text book, first part first.
+///
+/// A part shares the opened file's extension and its name up to a trailing number, so `ch1.txt`,
+/// `ch02.txt` and `ch10.txt` belong together while `ch1a.txt` or `notes.txt` do not. Returns an
+/// empty list when `path` isn't numbered or has no numbered siblings.
+#[must_use]
+pub fn text_parts(path: &Path) -> Vec<PathBuf> {
+	let Some((base, extension)) = part_base_and_extension(path) else {
+		return Vec::new();
+	};
+	let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+	let Ok(entries) = fs::read_dir(parent) else {
+		return Vec::new();
+	};
+	let mut parts: Vec<PathBuf> = entries
+		.filter_map(Result::ok)
+		.map(|entry| entry.path())
+		.filter(|candidate| {
+			candidate.is_file()
+				&& part_base_and_extension(candidate).is_some_and(|(candidate_base, candidate_extension)| {
+					candidate_base.eq_ignore_ascii_case(&base) && candidate_extension.eq_ignore_ascii_case(&extension)
+				})
+		})
+		.collect();
+	if parts.len() < 2 {
+		return Vec::new();
+	}
+	parts.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+	parts
+}
+
+/// Splits `ch02.txt` into `("ch", "txt")`; `None` for names without a trailing number.
+fn part_base_and_extension(path: &Path) -> Option<(String, String)> {
+	let extension = path.extension()?.to_str()?;
+	if !TEXT_EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(extension)) {
+		return None;
+	}
+	let stem = path.file_stem()?.to_str()?;
+	let base = stem.trim_end_matches(|ch: char| ch.is_ascii_digit());
+	if base.len() == stem.len() {
+		return None;
+	}
+	Some((base.to_string(), extension.to_string()))
+}
+
+fn parts_title(first: &Path) -> String {
+	let title = part_base_and_extension(first)
+		.map(|(base, _)| base.trim_end_matches([' ', '_', '-', '.']).to_string())
+		.unwrap_or_default();
+	if title.is_empty() { extract_title_from_path(&first.to_string_lossy()) } else { title }
+}
+
+/// Whether documents of `format` (an extension or forced format) are read by the text parsers, and so
+/// can be reopened in another encoding.
+#[must_use]
+pub fn is_text_format(format: &str) -> bool {
+	TEXT_EXTENSIONS.iter().chain([&TEXT_PARTS_FORMAT]).any(|known| known.eq_ignore_ascii_case(format))
+}
+
+/// Reads a text file in `encoding` when one is given, otherwise in the encoding its bytes suggest.
+fn read_text_file(path: &Path, encoding: Option<&str>) -> Result<String> {
+	let bytes = fs::read(path).with_context(|| format!("Failed to open text file '{}'", path.display()))?;
+	let text = decode_text(&bytes, encoding);
+	Ok(remove_soft_hyphens(&text))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn write_parts(name: &str, files: &[(&str, &str)]) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("paperback_text_parts_{name}_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		for (file, content) in files {
+			fs::write(dir.join(file), content).unwrap();
+		}
+		dir
+	}
+
+	#[test]
+	fn numbered_siblings_are_found_in_numeric_order() {
+		let dir = write_parts(
+			"order",
+			&[
+				("book10.txt", "ten"),
+				("book2.txt", "two"),
+				("book01.txt", "one"),
+				("book1a.txt", "unrelated"),
+				("notes.txt", "unrelated"),
+				("book3.md", "unrelated"),
+			],
+		);
+		let names: Vec<String> = text_parts(&dir.join("book2.txt"))
+			.iter()
+			.map(|part| part.file_name().unwrap().to_string_lossy().to_string())
+			.collect();
+		assert_eq!(names, ["book01.txt", "book2.txt", "book10.txt"]);
+		assert!(text_parts(&dir.join("notes.txt")).is_empty());
+		let _ = fs::remove_dir_all(dir);
+	}
+
+	#[test]
+	fn lone_numbered_file_is_not_a_set() {
+		let dir = write_parts("lone", &[("chapter1.txt", "one"), ("other2.txt", "two")]);
+		assert!(text_parts(&dir.join("chapter1.txt")).is_empty());
+		let _ = fs::remove_dir_all(dir);
+	}
+
+	#[test]
+	fn parts_are_read_as_one_document_with_sections() {
+		let dir = write_parts("merge", &[("vol_2.txt", "Second part"), ("vol_1.txt", "First part\n")]);
+		let context = ParserContext::new(dir.join("vol_2.txt").to_string_lossy().into_owned());
+		let doc = TextPartsParser.parse(&context).unwrap();
+		assert_eq!(doc.title, "vol");
+		assert_eq!(doc.buffer.content, "First part\nSecond part\n");
+		let names: Vec<&str> = doc.toc_items.iter().map(|item| item.name.as_str()).collect();
+		assert_eq!(names, ["vol_1.txt", "vol_2.txt"]);
+		assert_eq!(doc.toc_items[1].offset, 11);
+		let sections = doc.buffer.markers.iter().filter(|m| m.mtype == MarkerType::SectionBreak).count();
+		assert_eq!(sections, 2);
+		let _ = fs::remove_dir_all(dir);
+	}
+
+	#[test]
+	fn chosen_encoding_overrides_detection() {
+		let dir = write_parts("encoding", &[]);
+		let path = dir.join("cyrillic.txt");
+		fs::write(&path, b"\xCF\xF0\xE8\xE2\xE5\xF2, \xEC\xE8\xF0!\r\n").unwrap();
+		let context = ParserContext::new(path.to_string_lossy().into_owned());
+		assert_eq!(TextParser.parse(&context).unwrap().buffer.content, "Привет, мир!\n");
+		let context = context.with_encoding("windows-1252".to_string());
+		assert_eq!(TextParser.parse(&context).unwrap().buffer.content, "Ïðèâåò, ìèð!\n");
+		let _ = fs::remove_dir_all(dir);
+	}
+
+	#[test]
+	fn text_formats_include_text_parts() {
+		assert!(is_text_format("txt"));
+		assert!(is_text_format("LOG"));
+		assert!(is_text_format(TEXT_PARTS_FORMAT));
+		assert!(!is_text_format("epub"));
+	}
+}
@@ -1,5 +1,7 @@
 pub mod bidi;
+pub mod blocks;
 pub mod ooxml;
+pub mod page_breaks;
 pub mod path;
 pub mod toc;
 pub mod xml;
@@ -0,0 +1,88 @@
+use crate::types::BlockInfo;
+
+/// Records the outermost occurrence of a nestable block element (block quotes,
+/// preformatted code) while a converter walks the tree. Nested openings only
+/// bump the depth, so a quote inside a quote is reported once.
+#[derive(Debug, Default)]
+pub struct BlockTracker {
+	depth: usize,
+	/// Text offset and index into the converter's finalized lines where the
+	/// outermost open block began.
+	start: Option<(usize, usize)>,
+	blocks: Vec<BlockInfo>,
+}
+
+impl BlockTracker {
+	/// Called after the converter has finalized the line preceding the block.
+	pub const fn open(&mut self, offset: usize, line_index: usize) {
+		if self.depth == 0 {
+			self.start = Some((offset, line_index));
+		}
+		self.depth += 1;
+	}
+
+	/// Called after the converter has finalized the block's last line, with the
+	/// text position that follows it. The first non-blank line emitted since
+	/// `open` becomes the block's text.
+	pub fn close(&mut self, lines: &[String], end: usize) {
+		if self.depth == 0 {
+			return;
+		}
+		self.depth -= 1;
+		if self.depth > 0 {
+			return;
+		}
+		let Some((offset, line_index)) = self.start.take() else {
+			return;
+		};
+		let Some(text) =
+			lines.get(line_index..).and_then(|emitted| emitted.iter().map(|l| l.trim()).find(|l| !l.is_empty()))
+		else {
+			return;
+		};
+		self.blocks.push(BlockInfo { offset, text: text.to_string(), length: end.saturating_sub(offset) });
+	}
+
+	#[must_use]
+	pub fn blocks(&self) -> &[BlockInfo] {
+		&self.blocks
+	}
+
+	pub fn clear(&mut self) {
+		self.depth = 0;
+		self.start = None;
+		self.blocks.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lines(items: &[&str]) -> Vec<String> {
+		items.iter().map(ToString::to_string).collect()
+	}
+
+	#[test]
+	fn nested_blocks_are_recorded_once_with_first_line() {
+		let mut tracker = BlockTracker::default();
+		tracker.open(6, 1);
+		tracker.open(6, 1);
+		tracker.close(&lines(&["Intro", "", "  Quoted  ", "Inner"]), 19);
+		assert!(tracker.blocks().is_empty());
+		tracker.close(&lines(&["Intro", "", "  Quoted  ", "Inner"]), 20);
+		assert_eq!(tracker.blocks().len(), 1);
+		assert_eq!(tracker.blocks()[0].offset, 6);
+		assert_eq!(tracker.blocks()[0].text, "Quoted");
+		assert_eq!(tracker.blocks()[0].length, 14);
+	}
+
+	#[test]
+	fn empty_block_is_skipped() {
+		let mut tracker = BlockTracker::default();
+		tracker.open(0, 0);
+		tracker.close(&lines(&[]), 0);
+		tracker.close(&lines(&[]), 0);
+		assert!(tracker.blocks().is_empty());
+	}
+}
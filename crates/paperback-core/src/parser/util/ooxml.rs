@@ -33,6 +33,42 @@ pub fn read_ooxml_relationships<R: Read + Seek>(
 	rels
 }
 
+/// Returns the target of the first relationship in `rels_path` whose `Type` ends with `type_suffix`
+/// (for example `/notesSlide`), or `None` if the part has no such relationship.
+pub fn find_ooxml_relationship_target<R: Read + Seek>(
+	archive: &mut ZipArchive<R>,
+	rels_path: &str,
+	type_suffix: &str,
+) -> Option<String> {
+	let rels_content = read_zip_entry_by_name(archive, rels_path).ok()?;
+	let rels_doc = roxmltree::Document::parse(&rels_content).ok()?;
+	rels_doc
+		.descendants()
+		.filter(|node| node.node_type() == NodeType::Element && node.tag_name().name() == "Relationship")
+		.find(|node| node.attribute("Type").is_some_and(|rel_type| rel_type.ends_with(type_suffix)))
+		.and_then(|node| node.attribute("Target"))
+		.filter(|target| !target.is_empty())
+		.map(ToString::to_string)
+}
+
+/// Resolves a relationship target relative to the folder of the part that declared it, e.g.
+/// `../notesSlides/notesSlide1.xml` from `ppt/slides` becomes `ppt/notesSlides/notesSlide1.xml`.
+#[must_use]
+pub fn resolve_ooxml_part_path(base_dir: &str, target: &str) -> String {
+	let mut parts: Vec<&str> =
+		if target.starts_with('/') { Vec::new() } else { base_dir.split('/').filter(|p| !p.is_empty()).collect() };
+	for segment in target.split('/') {
+		match segment {
+			"" | "." => {}
+			".." => {
+				parts.pop();
+			}
+			_ => parts.push(segment),
+		}
+	}
+	parts.join("/")
+}
+
 #[must_use]
 pub fn collect_ooxml_run_text(run_element: Node) -> String {
 	let mut text = String::new();
@@ -0,0 +1,210 @@
+//! Page breaks a book marks in its markup rather than with page-number elements: `pagebreak` spans,
+//! and CSS that starts an element on a new page, as books converted from print do at each chapter.
+
+use crate::types::PageBreakInfo;
+
+/// Whether CSS starts a new page before an element, after it, or both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForcedBreaks {
+	pub before: bool,
+	pub after: bool,
+}
+
+impl ForcedBreaks {
+	/// Reads `page-break-before`/`-after` and `break-before`/`-after` from a list of CSS
+	/// declarations such as a `style` attribute. A later declaration of a property wins.
+	#[must_use]
+	pub fn from_declarations(declarations: &str) -> Self {
+		let mut breaks = Self::default();
+		for declaration in declarations.split(';') {
+			let Some((property, value)) = declaration.split_once(':') else { continue };
+			let value = value.trim().trim_end_matches("!important").trim().to_ascii_lowercase();
+			match property.trim().to_ascii_lowercase().as_str() {
+				"page-break-before" => breaks.before = matches!(value.as_str(), "always" | "left" | "right"),
+				"page-break-after" => breaks.after = matches!(value.as_str(), "always" | "left" | "right"),
+				"break-before" => breaks.before = is_page_break_value(&value),
+				"break-after" => breaks.after = is_page_break_value(&value),
+				_ => {}
+			}
+		}
+		breaks
+	}
+
+	#[must_use]
+	pub const fn any(self) -> bool {
+		self.before || self.after
+	}
+
+	const fn or(self, other: Self) -> Self {
+		Self { before: self.before || other.before, after: self.after || other.after }
+	}
+}
+
+fn is_page_break_value(value: &str) -> bool {
+	matches!(value, "page" | "always" | "left" | "right" | "recto" | "verso" | "all")
+}
+
+/// Whether an `epub:type` or DPUB-ARIA `role` value marks a page break.
+#[must_use]
+pub fn is_page_break_semantic_type(value: &str) -> bool {
+	value.split_ascii_whitespace().any(|token| {
+		let token = token.to_ascii_lowercase();
+		token.strip_prefix("doc-").unwrap_or(&token) == "pagebreak"
+	})
+}
+
+/// A selector simple enough to match without a style engine: an element name, a class, or an
+/// element name with a class (`h1`, `.chapter`, `div.chapter`).
+#[derive(Debug, Clone)]
+struct SimpleSelector {
+	tag: Option<String>,
+	class: Option<String>,
+}
+
+impl SimpleSelector {
+	fn parse(selector: &str) -> Option<Self> {
+		let (tag, class) = selector.split_once('.').map_or((selector, None), |(tag, class)| (tag, Some(class)));
+		let is_name = |name: &str| name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+		if !is_name(tag) || class.is_some_and(|class| class.is_empty() || !is_name(class)) {
+			return None;
+		}
+		let tag = (!tag.is_empty()).then(|| tag.to_ascii_lowercase());
+		if tag.is_none() && class.is_none() {
+			return None;
+		}
+		Some(Self { tag, class: class.map(str::to_string) })
+	}
+
+	fn matches(&self, tag: &str, classes: &str) -> bool {
+		self.tag.as_ref().is_none_or(|expected| expected.eq_ignore_ascii_case(tag))
+			&& self
+				.class
+				.as_ref()
+				.is_none_or(|expected| classes.split_ascii_whitespace().any(|class| class == expected))
+	}
+}
+
+/// The page-break rules of a book's stylesheets that use [`SimpleSelector`]s. Rules with any other
+/// selector are left out rather than guessed at.
+#[derive(Debug, Clone, Default)]
+pub struct PageBreakRules {
+	rules: Vec<(SimpleSelector, ForcedBreaks)>,
+}
+
+impl PageBreakRules {
+	#[must_use]
+	pub fn parse(css: &str) -> Self {
+		let mut rules = Self::default();
+		rules.extend_from_css(css);
+		rules
+	}
+
+	/// Adds the page-break rules of another stylesheet.
+	pub fn extend_from_css(&mut self, css: &str) {
+		let css = strip_comments(css);
+		for block in css.split('}') {
+			let Some((head, declarations)) = block.rsplit_once('{') else { continue };
+			// A rule nested in `@media print { ... }` still applies; the at-rule prefix is dropped.
+			let selectors = head.rsplit_once('{').map_or(head, |(_, selectors)| selectors).trim();
+			if selectors.starts_with('@') {
+				continue;
+			}
+			let breaks = ForcedBreaks::from_declarations(declarations);
+			if !breaks.any() {
+				continue;
+			}
+			let parsed = selectors.split(',').filter_map(|selector| SimpleSelector::parse(selector.trim()));
+			self.rules.extend(parsed.map(|selector| (selector, breaks)));
+		}
+	}
+
+	/// The breaks forced on an element named `tag` with the `class` attribute `classes`, by the
+	/// stylesheets and by its own `style` attribute.
+	#[must_use]
+	pub fn breaks_for(&self, tag: &str, classes: &str, style: &str) -> ForcedBreaks {
+		self.rules
+			.iter()
+			.filter(|(selector, _)| selector.matches(tag, classes))
+			.fold(ForcedBreaks::from_declarations(style), |breaks, &(_, rule)| breaks.or(rule))
+	}
+}
+
+fn strip_comments(css: &str) -> String {
+	let mut stripped = String::with_capacity(css.len());
+	let mut rest = css;
+	while let Some(start) = rest.find("/*") {
+		stripped.push_str(&rest[..start]);
+		rest = rest[start + 2..].find("*/").map_or("", |end| &rest[start + 2 + end + 2..]);
+	}
+	stripped.push_str(rest);
+	stripped
+}
+
+/// Records a page break at `offset`. A break at the same place as the one before it, as when a
+/// `pagebreak` span opens a chapter that CSS also starts on a new page, only lends it a label.
+pub fn push_page_break(page_breaks: &mut Vec<PageBreakInfo>, offset: usize, text: String) {
+	if let Some(last) = page_breaks.last_mut().filter(|last| last.offset == offset) {
+		if last.text.is_empty() {
+			last.text = text;
+		}
+		return;
+	}
+	page_breaks.push(PageBreakInfo { offset, text });
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	#[rstest]
+	#[case("page-break-before: always", true, false)]
+	#[case("PAGE-BREAK-AFTER: Always !important", false, true)]
+	#[case("break-before: page; break-after: right", true, true)]
+	#[case("page-break-before: auto", false, false)]
+	#[case("break-before: column", false, false)]
+	#[case("color: red; page-break-before: always; page-break-before: avoid", false, false)]
+	fn reads_forced_breaks_from_declarations(#[case] style: &str, #[case] before: bool, #[case] after: bool) {
+		assert_eq!(ForcedBreaks::from_declarations(style), ForcedBreaks { before, after });
+	}
+
+	#[rstest]
+	#[case("pagebreak", true)]
+	#[case("doc-pagebreak", true)]
+	#[case("bodymatter pagebreak", true)]
+	#[case("pagelist", false)]
+	fn recognizes_page_break_semantics(#[case] value: &str, #[case] expected: bool) {
+		assert_eq!(is_page_break_semantic_type(value), expected);
+	}
+
+	#[test]
+	fn stylesheet_rules_match_simple_selectors_only() {
+		let rules = PageBreakRules::parse(
+			"/* chapters */ h1, div.chapter { page-break-before: always }\n\
+			 .end { break-after: page; }\n\
+			 section > h2 { page-break-before: always }\n\
+			 @media print { h3 { break-before: page } }\n\
+			 p { margin: 0 }",
+		);
+		let before = ForcedBreaks { before: true, after: false };
+		assert_eq!(rules.breaks_for("h1", "", ""), before);
+		assert_eq!(rules.breaks_for("div", "intro chapter", ""), before);
+		assert_eq!(rules.breaks_for("p", "chapter", ""), ForcedBreaks::default());
+		assert_eq!(rules.breaks_for("p", "end", ""), ForcedBreaks { before: false, after: true });
+		assert_eq!(rules.breaks_for("h2", "", ""), ForcedBreaks::default());
+		assert_eq!(rules.breaks_for("h3", "", ""), before);
+		assert!(rules.breaks_for("p", "", "page-break-after: always").after);
+	}
+
+	#[test]
+	fn a_break_at_the_same_place_only_adds_its_label() {
+		let mut page_breaks = Vec::new();
+		push_page_break(&mut page_breaks, 10, String::new());
+		push_page_break(&mut page_breaks, 10, "12".to_string());
+		push_page_break(&mut page_breaks, 10, "13".to_string());
+		push_page_break(&mut page_breaks, 40, String::new());
+		let pages: Vec<_> = page_breaks.iter().map(|page| (page.offset, page.text.as_str())).collect();
+		assert_eq!(pages, [(10, "12"), (40, "")]);
+	}
+}
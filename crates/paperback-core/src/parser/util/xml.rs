@@ -42,6 +42,12 @@ pub fn find_child_element<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Opt
 	node.children().find(|child| child.node_type() == NodeType::Element && child.tag_name().name() == name)
 }
 
+/// Looks up an attribute by its local name whatever its namespace, so `w:id` matches `"id"`.
+#[must_use]
+pub fn attribute_by_local_name<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+	node.attributes().find(|attr| attr.name() == name).map(|attr| attr.value())
+}
+
 #[cfg(test)]
 mod tests {
 	use roxmltree::Document;
@@ -94,4 +100,14 @@ mod tests {
 		let doc = Document::parse(xml).unwrap();
 		assert!(find_child_element(doc.root_element(), "target").is_none());
 	}
+
+	#[test]
+	fn attribute_by_local_name_ignores_namespace_prefix() {
+		let xml = r#"<w:r xmlns:w="urn:w" w:id="7" plain="x"/>"#;
+		let doc = Document::parse(xml).unwrap();
+		let node = doc.root_element();
+		assert_eq!(attribute_by_local_name(node, "id"), Some("7"));
+		assert_eq!(attribute_by_local_name(node, "plain"), Some("x"));
+		assert_eq!(attribute_by_local_name(node, "missing"), None);
+	}
 }
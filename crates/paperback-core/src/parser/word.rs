@@ -1,7 +1,7 @@
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	fs::File,
-	io::{BufReader, Cursor, Read, Seek},
+	io::{Cursor, Read, Seek},
 	path::Path,
 };
 
@@ -15,18 +15,22 @@ use zip::ZipArchive;
 use crate::{
 	document::{Document, DocumentBuffer, Marker, MarkerType, ParserContext, ParserFlags, format_marker_types},
 	parser::{
-		PASSWORD_REQUIRED_ERROR_PREFIX, Parser,
+		PASSWORD_REQUIRED_ERROR_PREFIX, Parser, image_text,
 		table_text::{build_html_table_from_grid, html_table_to_display, table_caption_from_html},
 		util::{
 			ooxml::{collect_ooxml_run_text, read_ooxml_relationships},
 			path::extract_title_from_path,
 			toc::{build_toc_from_buffer, heading_level_to_marker_type},
-			xml::find_child_element,
+			xml::{attribute_by_local_name, find_child_element},
 		},
 	},
 	t,
 	types::HeadingInfo,
-	util::{encoding::convert_to_utf8, text::display_len, zip::read_zip_entry_by_name},
+	util::{
+		encoding::convert_to_utf8,
+		text::{collapse_whitespace, display_len, format_list_item},
+		zip::{OpenedZip, open_zip, open_zip_bytes, read_zip_entry_by_name},
+	},
 };
 
 const FIB_MAGIC_DOC: u16 = 0xA5EC;
@@ -49,7 +53,7 @@ impl Parser for WordParser {
 	}
 
 	fn supported_flags(&self) -> ParserFlags {
-		ParserFlags::SUPPORTS_TOC | ParserFlags::SUPPORTS_SECTIONS
+		ParserFlags::SUPPORTS_TOC | ParserFlags::SUPPORTS_SECTIONS | ParserFlags::SUPPORTS_LISTS
 	}
 
 	fn parse(&self, context: &ParserContext) -> Result<Document> {
@@ -92,8 +96,8 @@ impl Parser for WordParser {
 fn parse_word_zip(context: &ParserContext, render_tables_inline: bool) -> Result<Document> {
 	let file =
 		File::open(&context.file_path).with_context(|| format!("Failed to open ZIP file '{}'", context.file_path))?;
-	let mut archive = ZipArchive::new(BufReader::new(file))
-		.with_context(|| format!("Failed to read ZIP archive '{}'", context.file_path))?;
+	let OpenedZip { mut archive, mut salvaged } =
+		open_zip(file).with_context(|| format!("Failed to read ZIP archive '{}'", context.file_path))?;
 
 	let mut docx_names: Vec<String> =
 		archive.file_names().filter(|name| name.to_ascii_lowercase().ends_with(".docx")).map(String::from).collect();
@@ -120,8 +124,9 @@ fn parse_word_zip(context: &ParserContext, render_tables_inline: bool) -> Result
 			buffer.add_marker(Marker::new(MarkerType::SectionBreak, buffer.current_position()));
 		}
 
-		let mut inner_archive = ZipArchive::new(Cursor::new(inner_file_data))
+		let OpenedZip { archive: mut inner_archive, salvaged: inner_salvaged } = open_zip_bytes(inner_file_data)
 			.with_context(|| format!("Failed to parse inner DOCX '{docx_name}' as zip"))?;
+		salvaged |= inner_salvaged;
 
 		parse_ooxml_from_archive(
 			&mut inner_archive,
@@ -129,6 +134,7 @@ fn parse_word_zip(context: &ParserContext, render_tables_inline: bool) -> Result
 			&mut id_positions,
 			&mut headings,
 			render_tables_inline,
+			context.include_comments,
 		)
 		.with_context(|| format!("Failed to parse DOCX contents of '{docx_name}'"))?;
 	}
@@ -139,23 +145,32 @@ fn parse_word_zip(context: &ParserContext, render_tables_inline: bool) -> Result
 	document.set_buffer(buffer);
 	document.id_positions = id_positions;
 	document.toc_items = toc_items;
+	document.damaged = salvaged;
 	Ok(document)
 }
 
 fn parse_ooxml_doc(context: &ParserContext, render_tables_inline: bool) -> Result<Document> {
 	let bytes = load_ooxml_bytes(&context.file_path, context.password.as_deref())?;
-	let mut archive = ZipArchive::new(Cursor::new(bytes))
-		.with_context(|| format!("Failed to read DOCX as zip '{}'", context.file_path))?;
+	let OpenedZip { mut archive, salvaged } =
+		open_zip_bytes(bytes).with_context(|| format!("Failed to read DOCX as zip '{}'", context.file_path))?;
 	let mut buffer = DocumentBuffer::new();
 	let mut id_positions = HashMap::new();
 	let mut headings = Vec::new();
-	parse_ooxml_from_archive(&mut archive, &mut buffer, &mut id_positions, &mut headings, render_tables_inline)?;
+	parse_ooxml_from_archive(
+		&mut archive,
+		&mut buffer,
+		&mut id_positions,
+		&mut headings,
+		render_tables_inline,
+		context.include_comments,
+	)?;
 	let title = extract_title_from_path(&context.file_path);
 	let toc_items = build_toc_from_buffer(&buffer);
 	let mut document = Document::new().with_title(title);
 	document.set_buffer(buffer);
 	document.id_positions = id_positions;
 	document.toc_items = toc_items;
+	document.damaged = salvaged;
 	Ok(document)
 }
 
@@ -173,15 +188,534 @@ pub fn parse_ooxml_from_archive<R: Read + Seek>(
 	id_positions: &mut HashMap<String, usize>,
 	headings: &mut Vec<HeadingInfo>,
 	render_tables_inline: bool,
+	include_comments: bool,
 ) -> Result<()> {
-	let style_heading_map = build_style_heading_map(archive);
-	let rels = read_ooxml_relationships(archive, "word/_rels/document.xml.rels");
+	let ctx = OoxmlContext {
+		rels: read_ooxml_relationships(archive, "word/_rels/document.xml.rels"),
+		style_heading_map: build_style_heading_map(archive),
+		numbering: read_list_definitions(archive),
+		comments: if include_comments { read_comments(archive) } else { HashMap::new() },
+		render_tables_inline,
+	};
 	let doc_content = read_zip_entry_by_name(archive, "word/document.xml")?;
 	let doc_xml = XmlDocument::parse(&doc_content).context("Failed to parse word/document.xml")?;
-	traverse(doc_xml.root(), buffer, headings, id_positions, &rels, &style_heading_map, render_tables_inline);
+	// Several documents can share one buffer (a ZIP of .docx files), so later ones scope their note ids.
+	let key_scope = if buffer.content.is_empty() { String::new() } else { format!("{}-", buffer.current_position()) };
+	let mut notes = NoteTracker { key_scope, ..NoteTracker::default() };
+	if let Ok(settings) = read_zip_entry_by_name(archive, "word/settings.xml")
+		&& let Ok(settings_xml) = XmlDocument::parse(&settings)
+	{
+		notes.apply_numbering(settings_xml.root());
+	}
+	notes.apply_numbering(doc_xml.root());
+	let mut lists = ListTracker::default();
+	traverse(doc_xml.root(), buffer, headings, id_positions, &ctx, &mut notes, &mut lists);
+	lists.finish(buffer);
+	append_notes(archive, buffer, headings, id_positions, &ctx, &mut notes, &mut lists);
+	lists.finish(buffer);
 	Ok(())
 }
 
+/// Relationships, styles and options shared by every paragraph of one DOCX document.
+#[derive(Default)]
+struct OoxmlContext {
+	rels: HashMap<String, String>,
+	style_heading_map: HashMap<String, i32>,
+	numbering: ListDefinitions,
+	/// Comment id → (author, text). Empty unless comments are shown inline.
+	comments: HashMap<String, (String, String)>,
+	render_tables_inline: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum NoteKind {
+	Footnote,
+	Endnote,
+}
+
+impl NoteKind {
+	const fn name(self) -> &'static str {
+		match self {
+			Self::Footnote => "footnote",
+			Self::Endnote => "endnote",
+		}
+	}
+
+	const fn part_name(self) -> &'static str {
+		match self {
+			Self::Footnote => "word/footnotes.xml",
+			Self::Endnote => "word/endnotes.xml",
+		}
+	}
+
+	fn heading(self) -> String {
+		match self {
+			// TRANSLATORS: Heading above the footnotes gathered at the end of a Word document
+			Self::Footnote => t("Footnotes"),
+			// TRANSLATORS: Heading above the endnotes gathered at the end of a Word document
+			Self::Endnote => t("Endnotes"),
+		}
+	}
+}
+
+/// Word's automatic numbering for one kind of note: a [`format_list_item`] style and the first number.
+#[derive(Clone, Copy, Debug)]
+struct NoteNumbering {
+	format: &'static str,
+	start: i32,
+}
+
+#[derive(Debug)]
+struct NoteReference {
+	kind: NoteKind,
+	id: String,
+	label: String,
+	/// `id_positions` key of the note itself.
+	note_key: String,
+	/// `id_positions` key of the reference mark in the body, for the link back.
+	reference_key: String,
+}
+
+/// Footnote and endnote references in body order, numbered the way Word numbers them.
+#[derive(Debug)]
+struct NoteTracker {
+	footnote_numbering: NoteNumbering,
+	endnote_numbering: NoteNumbering,
+	key_scope: String,
+	references: Vec<NoteReference>,
+	/// Index of the note being written; its `footnoteRef`/`endnoteRef` mark links back to the reference.
+	current: Option<usize>,
+}
+
+impl Default for NoteTracker {
+	fn default() -> Self {
+		Self {
+			footnote_numbering: NoteNumbering { format: "1", start: 1 },
+			endnote_numbering: NoteNumbering { format: "i", start: 1 },
+			key_scope: String::new(),
+			references: Vec::new(),
+			current: None,
+		}
+	}
+}
+
+impl NoteTracker {
+	/// Applies the `numFmt`/`numStart` of every `footnotePr`/`endnotePr` under `root`, so document
+	/// section settings override `settings.xml` when applied after it.
+	fn apply_numbering(&mut self, root: Node) {
+		for node in root.descendants() {
+			if node.node_type() != NodeType::Element {
+				continue;
+			}
+			let numbering = match node.tag_name().name() {
+				"footnotePr" => &mut self.footnote_numbering,
+				"endnotePr" => &mut self.endnote_numbering,
+				_ => continue,
+			};
+			if let Some(format) = find_child_element(node, "numFmt").and_then(|n| attribute_by_local_name(n, "val")) {
+				numbering.format = list_item_style(format);
+			}
+			if let Some(start) = find_child_element(node, "numStart")
+				.and_then(|n| attribute_by_local_name(n, "val"))
+				.and_then(|val| val.parse().ok())
+			{
+				numbering.start = start;
+			}
+		}
+	}
+
+	fn add_reference(&mut self, kind: NoteKind, id: &str) -> &NoteReference {
+		let numbering = match kind {
+			NoteKind::Footnote => self.footnote_numbering,
+			NoteKind::Endnote => self.endnote_numbering,
+		};
+		let count = self.references.iter().filter(|reference| reference.kind == kind).count();
+		let number = numbering.start.saturating_add(i32::try_from(count).unwrap_or(i32::MAX));
+		let name = kind.name();
+		let scope = &self.key_scope;
+		self.references.push(NoteReference {
+			kind,
+			id: id.to_string(),
+			label: format_list_item(number, numbering.format),
+			note_key: format!("{name}-{scope}{id}"),
+			reference_key: format!("{name}-ref-{scope}{id}"),
+		});
+		&self.references[self.references.len() - 1]
+	}
+}
+
+/// Word allows nine list levels, `w:ilvl` 0 through 8.
+const MAX_LIST_LEVELS: usize = 9;
+
+/// The `format_list_item` style for a `w:numFmt` value; formats without one count in decimal.
+fn list_item_style(num_fmt: &str) -> &'static str {
+	match num_fmt {
+		"lowerRoman" => "i",
+		"upperRoman" => "I",
+		"lowerLetter" => "a",
+		"upperLetter" => "A",
+		_ => "1",
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LevelFormat {
+	Number(&'static str),
+	Bullet,
+	/// `numFmt="none"`: the level still counts, but its number is never shown.
+	Hidden,
+}
+
+#[derive(Clone)]
+struct ListLevel {
+	format: LevelFormat,
+	/// The `w:lvlText` template, where `%1` through `%9` stand for the numbers of levels 1 to 9.
+	text: String,
+	start: i32,
+	/// Numbering a level shallower than this one restarts it; `w:lvlRestart` can lower it.
+	restart_below: usize,
+}
+
+impl ListLevel {
+	fn new(level: usize) -> Self {
+		Self { format: LevelFormat::Number("1"), text: format!("%{}.", level + 1), start: 1, restart_below: level }
+	}
+
+	/// Reads a `w:lvl`, keeping this level's values for anything it leaves out.
+	fn read(mut self, lvl: Node, level: usize) -> Self {
+		let value = |name| find_child_element(lvl, name).and_then(|node| attribute_by_local_name(node, "val"));
+		if let Some(format) = value("numFmt") {
+			self.format = match format {
+				"bullet" => LevelFormat::Bullet,
+				"none" => LevelFormat::Hidden,
+				_ => LevelFormat::Number(list_item_style(format)),
+			};
+		}
+		if let Some(text) = value("lvlText") {
+			self.text = text.to_string();
+		}
+		if let Some(start) = value("start").and_then(|val| val.parse().ok()) {
+			self.start = start;
+		}
+		if let Some(restart) = value("lvlRestart").and_then(|val| val.parse::<usize>().ok()) {
+			self.restart_below = restart.min(level);
+		}
+		self
+	}
+}
+
+/// A `w:num`: the abstract list it numbers, and the levels it overrides for its own paragraphs.
+#[derive(Default)]
+struct ListInstance {
+	abstract_id: String,
+	start_overrides: HashMap<usize, i32>,
+	level_overrides: HashMap<usize, ListLevel>,
+}
+
+/// The lists defined in `word/numbering.xml`, and the numbering paragraph styles apply.
+#[derive(Default)]
+struct ListDefinitions {
+	abstract_levels: HashMap<String, Vec<ListLevel>>,
+	instances: HashMap<String, ListInstance>,
+	/// Style id → (`numId`, `ilvl`) from the style's `w:numPr`.
+	style_numbering: HashMap<String, (String, Option<usize>)>,
+}
+
+fn read_list_definitions<R: Read + Seek>(archive: &mut ZipArchive<R>) -> ListDefinitions {
+	let mut definitions = ListDefinitions::default();
+	if let Ok(content) = read_zip_entry_by_name(archive, "word/numbering.xml")
+		&& let Ok(xml) = XmlDocument::parse(&content)
+	{
+		for node in xml.root().descendants().filter(Node::is_element) {
+			match node.tag_name().name() {
+				"abstractNum" => {
+					let Some(id) = attribute_by_local_name(node, "abstractNumId") else { continue };
+					let mut levels: Vec<ListLevel> = (0..MAX_LIST_LEVELS).map(ListLevel::new).collect();
+					for lvl in node.children().filter(|child| child.is_element() && child.tag_name().name() == "lvl") {
+						if let Some(level) = list_level_index(lvl) {
+							levels[level] = levels[level].clone().read(lvl, level);
+						}
+					}
+					definitions.abstract_levels.insert(id.to_string(), levels);
+				}
+				"num" => {
+					let Some(id) = attribute_by_local_name(node, "numId") else { continue };
+					let mut instance = ListInstance::default();
+					for child in node.children().filter(Node::is_element) {
+						match child.tag_name().name() {
+							"abstractNumId" => {
+								instance.abstract_id =
+									attribute_by_local_name(child, "val").unwrap_or_default().to_string();
+							}
+							"lvlOverride" => {
+								let Some(level) = list_level_index(child) else { continue };
+								if let Some(start) = find_child_element(child, "startOverride")
+									.and_then(|node| attribute_by_local_name(node, "val"))
+									.and_then(|val| val.parse().ok())
+								{
+									instance.start_overrides.insert(level, start);
+								}
+								if let Some(lvl) = find_child_element(child, "lvl") {
+									instance.level_overrides.insert(level, ListLevel::new(level).read(lvl, level));
+								}
+							}
+							_ => {}
+						}
+					}
+					definitions.instances.insert(id.to_string(), instance);
+				}
+				_ => {}
+			}
+		}
+	}
+	if let Ok(content) = read_zip_entry_by_name(archive, "word/styles.xml")
+		&& let Ok(xml) = XmlDocument::parse(&content)
+	{
+		for style in xml.root().descendants().filter(|node| node.is_element() && node.tag_name().name() == "style") {
+			if let Some(style_id) = attribute_by_local_name(style, "styleId")
+				&& let Some(num_pr) = find_child_element(style, "pPr").and_then(|ppr| find_child_element(ppr, "numPr"))
+				&& let Some(num_id) =
+					find_child_element(num_pr, "numId").and_then(|node| attribute_by_local_name(node, "val"))
+			{
+				let level = find_child_element(num_pr, "ilvl").and_then(|node| attribute_by_local_name(node, "val"));
+				definitions
+					.style_numbering
+					.insert(style_id.to_string(), (num_id.to_string(), level.and_then(|val| val.parse().ok())));
+			}
+		}
+	}
+	definitions
+}
+
+/// The `w:ilvl` attribute of a `w:lvl` or `w:lvlOverride`, if it names one of the nine levels.
+fn list_level_index(node: Node) -> Option<usize> {
+	attribute_by_local_name(node, "ilvl").and_then(|val| val.parse().ok()).filter(|level| *level < MAX_LIST_LEVELS)
+}
+
+/// The list (`numId`) and level a paragraph is numbered with, from its own `w:numPr` or its style's.
+fn paragraph_numbering(ppr: Node, definitions: &ListDefinitions) -> Option<(String, usize)> {
+	let num_pr = find_child_element(ppr, "numPr");
+	let style = find_child_element(ppr, "pStyle")
+		.and_then(|node| attribute_by_local_name(node, "val"))
+		.and_then(|id| definitions.style_numbering.get(id));
+	let value = |name| {
+		num_pr.and_then(|num_pr| find_child_element(num_pr, name)).and_then(|node| attribute_by_local_name(node, "val"))
+	};
+	let num_id = value("numId").or_else(|| style.map(|(num_id, _)| num_id.as_str()))?;
+	// `numId` 0 is how a paragraph opts out of the numbering its style would give it.
+	if num_id == "0" {
+		return None;
+	}
+	let level =
+		value("ilvl").and_then(|val| val.parse().ok()).or_else(|| style.and_then(|(_, level)| *level)).unwrap_or(0);
+	Some((num_id.to_string(), level.min(MAX_LIST_LEVELS - 1)))
+}
+
+/// What a bullet level's `w:lvlText` shows. Word draws most bullets from symbol fonts, whose
+/// characters sit in the Private Use Area and mean nothing in other fonts.
+fn bullet_symbol(text: &str) -> String {
+	match text {
+		"\u{F0A7}" => "▪".to_string(),
+		"o" => "◦".to_string(),
+		_ if text.trim().is_empty() || text.chars().any(|ch| ('\u{E000}'..='\u{F8FF}').contains(&ch)) => {
+			"•".to_string()
+		}
+		_ => text.to_string(),
+	}
+}
+
+struct ListEntry {
+	level: usize,
+	num_id: String,
+	start: usize,
+	text: String,
+}
+
+/// Numbers list paragraphs as they are read, and turns each run of them into List and ListItem
+/// markers once it ends.
+#[derive(Default)]
+struct ListTracker {
+	/// Abstract list id → the last number given at each level. Lists sharing an abstract list
+	/// continue each other's numbering, as they do in Word.
+	counters: HashMap<String, [Option<i32>; MAX_LIST_LEVELS]>,
+	/// `numId`s already used, so a start override only restarts the list at its first paragraph.
+	started: HashSet<String>,
+	items: Vec<ListEntry>,
+}
+
+impl ListTracker {
+	/// Counts a paragraph at `level` of list `num_id` and returns the number or bullet it shows.
+	fn number(&mut self, definitions: &ListDefinitions, num_id: &str, level: usize) -> Option<String> {
+		let instance = definitions.instances.get(num_id)?;
+		let levels = definitions.abstract_levels.get(&instance.abstract_id)?;
+		let definition = |level: usize| instance.level_overrides.get(&level).unwrap_or(&levels[level]);
+		let start = |level: usize| instance.start_overrides.get(&level).copied().unwrap_or(definition(level).start);
+		let counters = self.counters.entry(instance.abstract_id.clone()).or_default();
+		if self.started.insert(num_id.to_string()) {
+			for &level in instance.start_overrides.keys() {
+				counters[level] = None;
+			}
+		}
+		counters[level] = Some(counters[level].map_or_else(|| start(level), |number| number.saturating_add(1)));
+		for deeper in level + 1..MAX_LIST_LEVELS {
+			if level < definition(deeper).restart_below {
+				counters[deeper] = None;
+			}
+		}
+		let current = definition(level);
+		if current.format == LevelFormat::Bullet {
+			return Some(bullet_symbol(&current.text));
+		}
+		let mut label = String::new();
+		let mut chars = current.text.chars().peekable();
+		while let Some(ch) = chars.next() {
+			if ch == '%'
+				&& let Some(shown) =
+					chars.peek().and_then(|digit| digit.to_digit(10)).filter(|digit| (1..=9).contains(digit))
+			{
+				chars.next();
+				let shown = usize::try_from(shown - 1).unwrap_or(0);
+				if let LevelFormat::Number(style) = definition(shown).format {
+					label.push_str(&format_list_item(counters[shown].unwrap_or_else(|| start(shown)), style));
+				}
+			} else {
+				label.push(ch);
+			}
+		}
+		Some(label)
+	}
+
+	/// Ends the current run of list paragraphs at the buffer's end, adding a List marker for each
+	/// list in it (a new one starts at every deeper level or change of `numId`) and a ListItem
+	/// marker for each paragraph, which runs until the next item at the same or a shallower level.
+	fn finish(&mut self, buffer: &mut DocumentBuffer) {
+		let items = std::mem::take(&mut self.items);
+		let end = buffer.current_position();
+		let count = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+		// (level, numId, start, item count) of each list still open, outermost first.
+		let mut open: Vec<(usize, &str, usize, usize)> = Vec::new();
+		for (index, item) in items.iter().enumerate() {
+			while let Some(&(level, num_id, start, item_count)) = open.last() {
+				if level < item.level || (level == item.level && num_id == item.num_id) {
+					break;
+				}
+				buffer.add_marker(
+					Marker::new(MarkerType::List, start).with_level(count(item_count)).with_length(item.start - start),
+				);
+				open.pop();
+			}
+			match open.last_mut() {
+				Some((level, _, _, item_count)) if *level == item.level => *item_count += 1,
+				_ => open.push((item.level, &item.num_id, item.start, 1)),
+			}
+			let item_end =
+				items[index + 1..].iter().find(|next| next.level <= item.level).map_or(end, |next| next.start);
+			buffer.add_marker(
+				Marker::new(MarkerType::ListItem, item.start)
+					.with_text(item.text.clone())
+					.with_level(count(item.level + 1))
+					.with_length(item_end - item.start),
+			);
+		}
+		for (_, _, start, item_count) in open.into_iter().rev() {
+			buffer.add_marker(
+				Marker::new(MarkerType::List, start).with_level(count(item_count)).with_length(end - start),
+			);
+		}
+	}
+}
+
+/// Reads `word/comments.xml` into comment id → (author, text).
+fn read_comments<R: Read + Seek>(archive: &mut ZipArchive<R>) -> HashMap<String, (String, String)> {
+	let mut comments = HashMap::new();
+	let Ok(content) = read_zip_entry_by_name(archive, "word/comments.xml") else {
+		return comments;
+	};
+	let Ok(xml) = XmlDocument::parse(&content) else {
+		return comments;
+	};
+	for node in xml.root().descendants() {
+		if node.node_type() != NodeType::Element || node.tag_name().name() != "comment" {
+			continue;
+		}
+		let Some(id) = attribute_by_local_name(node, "id") else { continue };
+		let author = attribute_by_local_name(node, "author").unwrap_or_default().trim().to_string();
+		let paragraphs: Vec<String> = node
+			.descendants()
+			.filter(|p| p.node_type() == NodeType::Element && p.tag_name().name() == "p")
+			.map(|p| {
+				p.children()
+					.filter(|r| r.node_type() == NodeType::Element && r.tag_name().name() == "r")
+					.map(collect_ooxml_run_text)
+					.collect::<String>()
+					.trim()
+					.to_string()
+			})
+			.filter(|text| !text.is_empty())
+			.collect();
+		if !paragraphs.is_empty() {
+			comments.insert(id.to_string(), (author, paragraphs.join(" ")));
+		}
+	}
+	comments
+}
+
+fn comment_annotation(author: &str, text: &str) -> String {
+	let annotation = if author.is_empty() {
+		// TRANSLATORS: A Word reviewer comment shown inline in brackets; {} is the comment text
+		t("Comment: {}").replace("{}", text)
+	} else {
+		// TRANSLATORS: A Word reviewer comment shown inline in brackets; the first {} is the commenter's name and the second the comment text
+		t("Comment by {}: {}").replacen("{}", author, 1).replacen("{}", text, 1)
+	};
+	format!(" [{annotation}]")
+}
+
+/// Appends the referenced footnotes, then endnotes, under their own headings in reference order.
+/// Each note's position is recorded so the body's reference links resolve to it.
+fn append_notes<R: Read + Seek>(
+	archive: &mut ZipArchive<R>,
+	buffer: &mut DocumentBuffer,
+	headings: &mut Vec<HeadingInfo>,
+	id_positions: &mut HashMap<String, usize>,
+	ctx: &OoxmlContext,
+	notes: &mut NoteTracker,
+	lists: &mut ListTracker,
+) {
+	for kind in [NoteKind::Footnote, NoteKind::Endnote] {
+		let indices: Vec<usize> =
+			notes.references.iter().enumerate().filter(|(_, r)| r.kind == kind).map(|(index, _)| index).collect();
+		if indices.is_empty() {
+			continue;
+		}
+		let Ok(content) = read_zip_entry_by_name(archive, kind.part_name()) else { continue };
+		let Ok(xml) = XmlDocument::parse(&content) else { continue };
+		let note_nodes: HashMap<&str, Node> = xml
+			.root()
+			.descendants()
+			.filter(|node| node.node_type() == NodeType::Element && node.tag_name().name() == kind.name())
+			.filter_map(|node| attribute_by_local_name(node, "id").map(|id| (id, node)))
+			.collect();
+		lists.finish(buffer);
+		let heading_start = buffer.current_position();
+		let heading_text = kind.heading();
+		buffer.append(&heading_text);
+		buffer.append("\n");
+		buffer
+			.add_marker(Marker::new(MarkerType::Heading1, heading_start).with_text(heading_text.clone()).with_level(1));
+		headings.push(HeadingInfo { offset: heading_start, level: 1, text: heading_text });
+		for index in indices {
+			let Some(node) = note_nodes.get(notes.references[index].id.as_str()) else { continue };
+			id_positions.insert(notes.references[index].note_key.clone(), buffer.current_position());
+			notes.current = Some(index);
+			for child in node.children() {
+				traverse(child, buffer, headings, id_positions, ctx, notes, lists);
+			}
+			notes.current = None;
+		}
+	}
+}
+
 /// Reads `word/styles.xml` and returns a map of style ID → heading level (1–9).
 /// Detects headings via `<w:name w:val="heading N"/>` (the canonical semantic name
 /// Word assigns regardless of locale) or a fallback `<w:outlineLvl>` in the style's pPr.
@@ -531,9 +1065,9 @@ fn traverse(
 	buffer: &mut DocumentBuffer,
 	headings: &mut Vec<HeadingInfo>,
 	id_positions: &mut HashMap<String, usize>,
-	rels: &HashMap<String, String>,
-	style_heading_map: &HashMap<String, i32>,
-	render_tables_inline: bool,
+	ctx: &OoxmlContext,
+	notes: &mut NoteTracker,
+	lists: &mut ListTracker,
 ) {
 	if node.node_type() == NodeType::Element {
 		let tag_name = node.tag_name().name();
@@ -541,15 +1075,16 @@ fn traverse(
 			id_positions.insert(id.to_string(), buffer.current_position());
 		}
 		if tag_name == "p" {
-			process_paragraph(node, buffer, headings, id_positions, rels, style_heading_map);
+			process_paragraph(node, buffer, headings, id_positions, ctx, notes, lists);
 			return;
 		} else if tag_name == "tbl" {
-			process_table(node, buffer, rels, render_tables_inline);
+			lists.finish(buffer);
+			process_table(node, buffer, &ctx.rels, ctx.render_tables_inline);
 			return;
 		}
 	}
 	for child in node.children() {
-		traverse(child, buffer, headings, id_positions, rels, style_heading_map, render_tables_inline);
+		traverse(child, buffer, headings, id_positions, ctx, notes, lists);
 	}
 }
 
@@ -604,22 +1139,36 @@ fn process_paragraph(
 	buffer: &mut DocumentBuffer,
 	headings: &mut Vec<HeadingInfo>,
 	id_positions: &mut HashMap<String, usize>,
-	rels: &HashMap<String, String>,
-	style_heading_map: &HashMap<String, i32>,
+	ctx: &OoxmlContext,
+	notes: &mut NoteTracker,
+	lists: &mut ListTracker,
 ) {
-	let paragraph_start = buffer.current_position();
+	let line_start = buffer.current_position();
+	let list_item = find_child_element(element, "pPr")
+		.and_then(|ppr| paragraph_numbering(ppr, &ctx.numbering))
+		.and_then(|(num_id, level)| Some((lists.number(&ctx.numbering, &num_id, level)?, num_id, level)));
+	// The number and its indent go ahead of the text, so everything in the paragraph starts after them.
+	let prefix = list_item.as_ref().map_or_else(String::new, |(label, _, level)| {
+		let indent = "  ".repeat(*level);
+		if label.is_empty() { indent } else { format!("{indent}{label} ") }
+	});
+	let paragraph_start = line_start + display_len(&prefix);
 	let mut paragraph_text = String::new();
 	let mut para_display_len = 0usize;
 	let mut heading_level = 0;
 	let mut is_paragraph_style_heading = false;
 	let mut format_spans: Vec<(MarkerType, usize, usize)> = Vec::new();
+	// Note reference links and their anchors are placed after the leading whitespace is trimmed.
+	let mut note_links: Vec<(usize, String, String)> = Vec::new();
+	let mut note_anchors: Vec<(String, usize)> = Vec::new();
+	let mut images: Vec<(usize, String)> = Vec::new();
 	for child in element.children() {
 		if child.node_type() != NodeType::Element {
 			continue;
 		}
 		let tag_name = child.tag_name().name();
 		if tag_name == "pPr" {
-			heading_level = get_paragraph_heading_level(child, style_heading_map);
+			heading_level = get_paragraph_heading_level(child, &ctx.style_heading_map);
 			if heading_level > 0 {
 				is_paragraph_style_heading = true;
 			}
@@ -628,7 +1177,7 @@ fn process_paragraph(
 				id_positions.insert(name.to_string(), paragraph_start + paragraph_text.len());
 			}
 		} else if tag_name == "hyperlink" {
-			para_display_len += process_hyperlink(child, &mut paragraph_text, buffer, rels, paragraph_start);
+			para_display_len += process_hyperlink(child, &mut paragraph_text, buffer, &ctx.rels, paragraph_start);
 		} else if tag_name == "r" {
 			if heading_level == 0
 				&& let Some(rpr_node) = find_child_element(child, "rPr")
@@ -654,6 +1203,48 @@ fn process_paragraph(
 					}
 				}
 			}
+			for run_child in child.children() {
+				if run_child.node_type() != NodeType::Element {
+					continue;
+				}
+				let offset = paragraph_start + para_display_len;
+				let inserted = match run_child.tag_name().name() {
+					tag @ ("footnoteReference" | "endnoteReference") => {
+						let kind = if tag == "footnoteReference" { NoteKind::Footnote } else { NoteKind::Endnote };
+						let Some(id) = attribute_by_local_name(run_child, "id") else { continue };
+						let reference = notes.add_reference(kind, id);
+						let mark = format!("[{}]", reference.label);
+						note_anchors.push((reference.reference_key.clone(), offset));
+						note_links.push((offset, mark.clone(), format!("#{}", reference.note_key)));
+						mark
+					}
+					"footnoteRef" | "endnoteRef" => {
+						let Some(reference) = notes.current.take().and_then(|index| notes.references.get(index)) else {
+							continue;
+						};
+						let mark = format!("[{}]", reference.label);
+						note_links.push((offset, mark.clone(), format!("#{}", reference.reference_key)));
+						mark
+					}
+					"commentReference" => {
+						let Some((author, text)) =
+							attribute_by_local_name(run_child, "id").and_then(|id| ctx.comments.get(id))
+						else {
+							continue;
+						};
+						comment_annotation(author, text)
+					}
+					"drawing" | "pict" => {
+						let description = picture_description(run_child);
+						let text = image_text(&description);
+						images.push((offset, description));
+						text
+					}
+					_ => continue,
+				};
+				paragraph_text.push_str(&inserted);
+				para_display_len += display_len(&inserted);
+			}
 			let run_text = collect_ooxml_run_text(child);
 			if !run_text.is_empty() {
 				let run_start = paragraph_start + para_display_len;
@@ -673,8 +1264,17 @@ fn process_paragraph(
 		}
 	}
 	let trimmed = paragraph_text.trim();
+	// A numbered heading is kept out of list navigation, and ends any list before it.
+	let list_item = list_item.filter(|_| heading_level == 0);
+	if list_item.is_none() {
+		lists.finish(buffer);
+	}
+	buffer.append(&prefix);
 	buffer.append(trimmed);
 	buffer.append("\n");
+	if let Some((_, num_id, level)) = list_item {
+		lists.items.push(ListEntry { level, num_id, start: line_start, text: trimmed.to_string() });
+	}
 	let leading_trim = display_len(&paragraph_text) - display_len(paragraph_text.trim_start());
 	for (kind, start, end) in format_spans {
 		let adj_start = start.saturating_sub(leading_trim);
@@ -683,19 +1283,54 @@ fn process_paragraph(
 			buffer.add_marker(Marker::new(kind, adj_start).with_length(adj_end - adj_start));
 		}
 	}
+	for (offset, text, target) in note_links {
+		buffer.add_marker(
+			Marker::new(MarkerType::Link, offset.saturating_sub(leading_trim)).with_text(text).with_reference(target),
+		);
+	}
+	for (key, offset) in note_anchors {
+		id_positions.insert(key, offset.saturating_sub(leading_trim));
+	}
+	for (offset, description) in images {
+		let length = display_len(&image_text(&description));
+		buffer.add_marker(
+			Marker::new(MarkerType::Image, offset.saturating_sub(leading_trim))
+				.with_text(description)
+				.with_length(length),
+		);
+	}
 	if heading_level > 0 && !trimmed.is_empty() {
 		let heading_text =
 			if is_paragraph_style_heading { trimmed.to_string() } else { extract_heading_text(element, heading_level) };
 		if !heading_text.is_empty() {
+			let heading_text = format!("{}{heading_text}", prefix.trim_start());
 			let marker_type = heading_level_to_marker_type(heading_level);
 			buffer.add_marker(
-				Marker::new(marker_type, paragraph_start).with_text(heading_text.clone()).with_level(heading_level),
+				Marker::new(marker_type, line_start).with_text(heading_text.clone()).with_level(heading_level),
 			);
-			headings.push(HeadingInfo { offset: paragraph_start, level: heading_level, text: heading_text });
+			headings.push(HeadingInfo { offset: line_start, level: heading_level, text: heading_text });
 		}
 	}
 }
 
+/// The alt text of an inline picture: the drawing's `wp:docPr` description or title, or for a
+/// legacy VML picture the shape's `alt` or the image's `o:title`.
+fn picture_description(picture: Node) -> String {
+	picture
+		.descendants()
+		.filter(|node| node.is_element())
+		.flat_map(|node| match node.tag_name().name() {
+			"docPr" => vec![attribute_by_local_name(node, "descr"), attribute_by_local_name(node, "title")],
+			"shape" => vec![attribute_by_local_name(node, "alt")],
+			"imagedata" => vec![attribute_by_local_name(node, "title")],
+			_ => Vec::new(),
+		})
+		.flatten()
+		.find(|value| !value.trim().is_empty())
+		.map(collapse_whitespace)
+		.unwrap_or_default()
+}
+
 /// Appends the hyperlink's display text to `paragraph_text`, records a Link
 /// marker, and returns the number of display units appended.
 fn process_hyperlink(
@@ -907,10 +1542,15 @@ mod tests {
 
 	use roxmltree::Document as XmlDocument;
 
-	use super::{looks_like_text_content, normalize_doc_text, parse_doc_clx, parse_doc_piece_table, traverse};
+	use super::{
+		ListTracker, NoteTracker, OoxmlContext, looks_like_text_content, normalize_doc_text, parse_doc_clx,
+		parse_doc_piece_table, traverse,
+	};
 	use crate::{
-		document::{DocumentBuffer, MarkerType},
-		util::text::display_len,
+		document::{DocumentBuffer, DocumentHandle, Marker, MarkerType, ParserContext},
+		parser::parse_document,
+		reader_core::resolve_link,
+		util::{text::display_len, zip::testing::TempZip},
 	};
 
 	#[test]
@@ -980,8 +1620,16 @@ mod tests {
 		let mut buffer = DocumentBuffer::new();
 		let mut headings = Vec::new();
 		let mut id_positions = HashMap::new();
-		let rels = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut headings, &mut id_positions, &rels, &HashMap::new(), false);
+		let ctx = OoxmlContext::default();
+		traverse(
+			xml_doc.root(),
+			&mut buffer,
+			&mut headings,
+			&mut id_positions,
+			&ctx,
+			&mut NoteTracker::default(),
+			&mut ListTracker::default(),
+		);
 		assert_eq!(buffer.content, "[Table]: Kop \u{1D11E}\n");
 		let table_marker = buffer.markers.iter().find(|m| m.mtype == MarkerType::Table).expect("Table marker");
 		assert_eq!(table_marker.text, "Kop \u{1D11E}", "marker caption is the first-row text, no prefix");
@@ -992,7 +1640,16 @@ mod tests {
 		let mut buffer = DocumentBuffer::new();
 		let mut headings = Vec::new();
 		let mut id_positions = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut headings, &mut id_positions, &rels, &HashMap::new(), true);
+		let ctx = OoxmlContext { render_tables_inline: true, ..OoxmlContext::default() };
+		traverse(
+			xml_doc.root(),
+			&mut buffer,
+			&mut headings,
+			&mut id_positions,
+			&ctx,
+			&mut NoteTracker::default(),
+			&mut ListTracker::default(),
+		);
 		assert_eq!(buffer.content, "Kop\t\u{1D11E}\n");
 		let table_marker = buffer.markers.iter().find(|m| m.mtype == MarkerType::Table).expect("Table marker");
 		assert_eq!(table_marker.length, display_len("Kop\t\u{1D11E}") + 1, "marker length spans the TSV");
@@ -1006,8 +1663,16 @@ mod tests {
 		let mut buffer = DocumentBuffer::new();
 		let mut headings = Vec::new();
 		let mut id_positions = HashMap::new();
-		let rels = HashMap::new();
-		traverse(xml_doc.root(), &mut buffer, &mut headings, &mut id_positions, &rels, &HashMap::new(), false);
+		let ctx = OoxmlContext::default();
+		traverse(
+			xml_doc.root(),
+			&mut buffer,
+			&mut headings,
+			&mut id_positions,
+			&ctx,
+			&mut NoteTracker::default(),
+			&mut ListTracker::default(),
+		);
 		buffer
 	}
 
@@ -1019,6 +1684,27 @@ mod tests {
 		assert_eq!(marker.length, display_len("bold"));
 	}
 
+	#[test]
+	fn pictures_are_shown_with_their_description() {
+		let buffer = parse_run_props(concat!(
+			r#"<document><body><p><r><t>See </t></r><r><drawing><inline>"#,
+			r#"<docPr id="1" name="Picture 1" descr="A red  barn"/></inline></drawing></r></p>"#,
+			r#"<p><r><drawing><inline><docPr id="2" name="Picture 2" descr=""/></inline></drawing></r></p>"#,
+			"</body></document>"
+		));
+		assert_eq!(
+			buffer.content,
+			"See [Image: A red barn]
+[Image]
+"
+		);
+		let images: Vec<_> = buffer.markers.iter().filter(|m| m.mtype == MarkerType::Image).collect();
+		assert_eq!(images.len(), 2);
+		assert_eq!((images[0].position, images[0].text.as_str()), (4, "A red barn"));
+		assert_eq!(images[0].length, display_len("[Image: A red barn]"));
+		assert_eq!((images[1].position, images[1].text.as_str()), (24, ""));
+	}
+
 	#[test]
 	fn run_italic_property_emits_italic_marker() {
 		let buffer = parse_run_props(r"<document><body><p><r><rPr><i/></rPr><t>italic</t></r></p></body></document>");
@@ -1107,4 +1793,131 @@ mod tests {
 		assert_eq!(marker.position, 0, "Bold marker must point at the start of the trimmed content");
 		assert_eq!(marker.length, display_len("bold"));
 	}
+
+	const W_NS: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+	/// Footnote ids 2 and 5 are numbered 1 and 2 by their order in the text; endnotes use Word's
+	/// default lower-case Roman numerals. The non-BMP clef keeps the offsets honest in display units.
+	#[test]
+	fn footnotes_endnotes_and_comments_link_both_ways() {
+		let document = format!(
+			r#"<w:document {W_NS}><w:body>
+			<w:p><w:r><w:t>&#x1D11E; claim</w:t></w:r><w:r><w:footnoteReference w:id="2"/></w:r><w:r><w:t xml:space="preserve"> and more</w:t></w:r><w:r><w:endnoteReference w:id="1"/></w:r><w:commentRangeStart w:id="0"/><w:r><w:t xml:space="preserve"> disputed</w:t></w:r><w:commentRangeEnd w:id="0"/><w:r><w:commentReference w:id="0"/></w:r></w:p>
+			<w:p><w:r><w:t>Second</w:t></w:r><w:r><w:footnoteReference w:id="5"/></w:r></w:p>
+			</w:body></w:document>"#
+		);
+		let footnotes = format!(
+			r#"<w:footnotes {W_NS}>
+			<w:footnote w:type="separator" w:id="-1"><w:p><w:r><w:separator/></w:r></w:p></w:footnote>
+			<w:footnote w:id="2"><w:p><w:r><w:footnoteRef/></w:r><w:r><w:t xml:space="preserve"> First source.</w:t></w:r></w:p></w:footnote>
+			<w:footnote w:id="5"><w:p><w:r><w:footnoteRef/></w:r><w:r><w:t xml:space="preserve"> Second source.</w:t></w:r></w:p></w:footnote>
+			</w:footnotes>"#
+		);
+		let endnotes = format!(
+			r#"<w:endnotes {W_NS}><w:endnote w:id="1"><w:p><w:r><w:endnoteRef/></w:r><w:r><w:t xml:space="preserve"> An endnote.</w:t></w:r></w:p></w:endnote></w:endnotes>"#
+		);
+		let comments = format!(
+			r#"<w:comments {W_NS}><w:comment w:id="0" w:author="Ada"><w:p><w:r><w:annotationRef/></w:r><w:r><w:t>Check this.</w:t></w:r></w:p></w:comment></w:comments>"#
+		);
+		let docx = TempZip::new(
+			"notes.docx",
+			&[
+				("word/document.xml", document),
+				("word/footnotes.xml", footnotes),
+				("word/endnotes.xml", endnotes),
+				("word/comments.xml", comments),
+			],
+		);
+		let without_comments = parse_document(&ParserContext::new(docx.path())).unwrap();
+		let doc = parse_document(&ParserContext::new(docx.path()).with_include_comments(true)).unwrap();
+		assert!(!without_comments.buffer.content.contains("Comment"));
+		let content = doc.buffer.content.clone();
+		assert_eq!(
+			content,
+			"\u{1D11E} claim[1] and more[i] disputed [Comment by Ada: Check this.]\nSecond[2]\nFootnotes\n[1] First source.\n[2] Second source.\nEndnotes\n[i] An endnote.\n"
+		);
+		let offset_of = |needle: &str| display_len(&content[..content.find(needle).unwrap()]);
+		let handle = DocumentHandle::new(doc);
+		for (reference, note) in [("[1] and", "[1] First"), ("[2]\n", "[2] Second"), ("[i] disputed", "[i] An")] {
+			let (reference, note) = (offset_of(reference), offset_of(note));
+			let markers = &handle.document().buffer.markers;
+			let forward = markers.iter().find(|m| m.mtype == MarkerType::Link && m.position == reference).unwrap();
+			let back = markers.iter().find(|m| m.mtype == MarkerType::Link && m.position == note).unwrap();
+			assert_eq!(forward.text, back.text);
+			let to_note = resolve_link(&handle, &forward.reference, 0);
+			assert!(to_note.found);
+			assert_eq!(to_note.offset, note);
+			let to_reference = resolve_link(&handle, &back.reference, 0);
+			assert!(to_reference.found);
+			assert_eq!(to_reference.offset, reference);
+		}
+	}
+
+	/// Numbers continue across the interrupting paragraph, a `startOverride` restarts them, and
+	/// each deeper level or new `numId` starts a list of its own.
+	#[test]
+	fn lists_are_numbered_and_nested_from_numbering_xml() {
+		let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/word/lists.docx");
+		let doc = parse_document(&ParserContext::new(path.to_string())).unwrap();
+		let content = doc.buffer.content.as_str();
+		assert_eq!(
+			content,
+			"Terms\n1. Definitions\n  1.1. Scope\n    1.1.a Goods\n    1.1.b Services\n  1.2. Exclusions\n2. Payment\nAn interrupting paragraph.\n3. Termination\n  3.1. Notice\n1. Fresh start\nLetters:\na) alpha\n  (i) first\n  (ii) second\n    1. deep\nb) beta\nBullets:\n• Milk\n  ◦ Whole\n    ▪ Organic\n"
+		);
+		let covered = |marker: &Marker| content.chars().skip(marker.position).take(marker.length).collect::<String>();
+		let mut markers: Vec<&Marker> = doc.buffer.markers.iter().collect();
+		markers.sort_by_key(|marker| marker.position);
+		let items: Vec<(i32, &str)> = markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::ListItem)
+			.map(|marker| (marker.level, marker.text.as_str()))
+			.collect();
+		assert_eq!(
+			items,
+			[
+				(1, "Definitions"),
+				(2, "Scope"),
+				(3, "Goods"),
+				(3, "Services"),
+				(2, "Exclusions"),
+				(1, "Payment"),
+				(1, "Termination"),
+				(2, "Notice"),
+				(1, "Fresh start"),
+				(1, "alpha"),
+				(2, "first"),
+				(2, "second"),
+				(3, "deep"),
+				(1, "beta"),
+				(1, "Milk"),
+				(2, "Whole"),
+				(3, "Organic"),
+			]
+		);
+		let definitions = markers.iter().find(|marker| marker.text == "Definitions").unwrap();
+		assert_eq!(
+			covered(definitions),
+			"1. Definitions\n  1.1. Scope\n    1.1.a Goods\n    1.1.b Services\n  1.2. Exclusions\n"
+		);
+		let lists: Vec<(i32, String)> = markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::List)
+			.map(|marker| (marker.level, covered(marker)))
+			.collect();
+		let expected = [
+			(2, "1. Definitions\n  1.1. Scope\n    1.1.a Goods\n    1.1.b Services\n  1.2. Exclusions\n2. Payment\n"),
+			(2, "  1.1. Scope\n    1.1.a Goods\n    1.1.b Services\n  1.2. Exclusions\n"),
+			(2, "    1.1.a Goods\n    1.1.b Services\n"),
+			(1, "3. Termination\n  3.1. Notice\n"),
+			(1, "  3.1. Notice\n"),
+			(1, "1. Fresh start\n"),
+			(2, "a) alpha\n  (i) first\n  (ii) second\n    1. deep\nb) beta\n"),
+			(2, "  (i) first\n  (ii) second\n    1. deep\n"),
+			(1, "    1. deep\n"),
+			(1, "• Milk\n  ◦ Whole\n    ▪ Organic\n"),
+			(1, "  ◦ Whole\n    ▪ Organic\n"),
+			(1, "    ▪ Organic\n"),
+		];
+		assert_eq!(lists, expected.map(|(count, text)| (count, text.to_string())));
+	}
 }
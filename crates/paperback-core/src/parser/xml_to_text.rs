@@ -3,18 +3,25 @@ use std::{collections::HashMap, mem};
 use roxmltree::{Document, Node, NodeType, ParsingOptions};
 
 use crate::{
+	document::LandmarkKind,
 	parser::{
-		ConverterOutput,
+		ConverterOutput, figure_text, image_text, is_note_semantic_type,
 		table_text::{push_finalized_line, table_render_bundle},
-		util::xml::collect_element_text,
+		util::{
+			blocks::BlockTracker,
+			page_breaks::{ForcedBreaks, PageBreakRules, is_page_break_semantic_type, push_page_break},
+			xml::collect_element_text,
+		},
 	},
-	t,
 	types::{
-		FormatInfo, HeadingInfo, ImageInfo, LinkInfo, ListInfo, ListItemInfo, PageBreakInfo, SeparatorInfo, TableInfo,
+		BlockInfo, FormatInfo, HeadingInfo, ImageInfo, LandmarkInfo, LinkInfo, ListInfo, ListItemInfo, PageBreakInfo,
+		SeparatorInfo, TableInfo,
 	},
 	util::text::{collapse_whitespace, display_len, format_list_item, remove_soft_hyphens, trim_string},
 };
 
+const EPUB_OPS_NAMESPACE: &str = "http://www.idpf.org/2007/ops";
+
 #[derive(Clone)]
 struct ListStyle {
 	ordered: bool,
@@ -39,7 +46,13 @@ pub struct XmlToText {
 	figures: Vec<ImageInfo>,
 	tables: Vec<TableInfo>,
 	separators: Vec<SeparatorInfo>,
+	block_quotes: BlockTracker,
+	code_blocks: BlockTracker,
+	notes: BlockTracker,
+	/// Whether each open `<aside>` is a footnote or endnote, so the matching close ends its note.
+	open_asides: Vec<bool>,
 	page_breaks: Vec<PageBreakInfo>,
+	landmarks: Vec<LandmarkInfo>,
 	lists: Vec<ListInfo>,
 	list_items: Vec<ListItemInfo>,
 	section_offsets: Vec<usize>,
@@ -53,6 +66,9 @@ pub struct XmlToText {
 	/// `None` marks an open list that was not recorded (no direct `<li>`), keeping the stack
 	/// balanced with the start/close handlers so list lengths are set on the right entries.
 	open_lists: Vec<Option<usize>>,
+	/// Indices into `list_items` for currently open `<li>` elements, so each item's length is set
+	/// when it closes.
+	open_list_items: Vec<usize>,
 	bolds: Vec<FormatInfo>,
 	italics: Vec<FormatInfo>,
 	underlines: Vec<FormatInfo>,
@@ -63,6 +79,9 @@ pub struct XmlToText {
 	/// When `true`, tables are emitted as their full tab-separated rendering; otherwise as a
 	/// `"[Table]: <first row>"` placeholder. A config flag, not parse state: it survives `clear()`.
 	render_tables_inline: bool,
+	/// Page-break rules from the stylesheets the document links to. Like `render_tables_inline`, it
+	/// survives `clear()`.
+	page_break_rules: PageBreakRules,
 }
 
 impl XmlToText {
@@ -79,6 +98,13 @@ impl XmlToText {
 		Self { render_tables_inline, ..Self::default() }
 	}
 
+	/// Starts a new page wherever `rules` force a break, as well as where `style` attributes do.
+	#[must_use]
+	pub fn with_page_break_rules(mut self, rules: PageBreakRules) -> Self {
+		self.page_break_rules = rules;
+		self
+	}
+
 	pub fn convert(&mut self, xml_content: &str) -> bool {
 		self.clear();
 		let options = ParsingOptions { allow_dtd: true, ..ParsingOptions::default() };
@@ -89,6 +115,7 @@ impl XmlToText {
 			self.process_node(child);
 		}
 		self.finalize_current_line();
+		self.drop_trailing_page_break();
 		true
 	}
 
@@ -134,6 +161,11 @@ impl XmlToText {
 		&self.page_breaks
 	}
 
+	#[must_use]
+	pub fn get_landmarks(&self) -> &[LandmarkInfo] {
+		&self.landmarks
+	}
+
 	#[must_use]
 	pub fn get_tables(&self) -> &[TableInfo] {
 		&self.tables
@@ -144,6 +176,21 @@ impl XmlToText {
 		&self.separators
 	}
 
+	#[must_use]
+	pub fn get_block_quotes(&self) -> &[BlockInfo] {
+		self.block_quotes.blocks()
+	}
+
+	#[must_use]
+	pub fn get_code_blocks(&self) -> &[BlockInfo] {
+		self.code_blocks.blocks()
+	}
+
+	#[must_use]
+	pub fn get_notes(&self) -> &[BlockInfo] {
+		self.notes.blocks()
+	}
+
 	#[must_use]
 	pub fn get_lists(&self) -> &[ListInfo] {
 		&self.lists
@@ -184,7 +231,12 @@ impl XmlToText {
 		self.figures.clear();
 		self.tables.clear();
 		self.separators.clear();
+		self.block_quotes.clear();
+		self.code_blocks.clear();
+		self.notes.clear();
+		self.open_asides.clear();
 		self.page_breaks.clear();
+		self.landmarks.clear();
 		self.lists.clear();
 		self.list_items.clear();
 		self.section_offsets.clear();
@@ -194,6 +246,7 @@ impl XmlToText {
 		self.cached_char_length = 0;
 		self.list_style_stack.clear();
 		self.open_lists.clear();
+		self.open_list_items.clear();
 		self.bolds.clear();
 		self.italics.clear();
 		self.underlines.clear();
@@ -214,9 +267,13 @@ impl XmlToText {
 				{
 					self.watched_byte_offset = Some(node.range().start);
 				}
+				let breaks = self.forced_page_breaks(tag_name, node);
+				if breaks.before {
+					self.add_forced_page_break(tag_name);
+				}
 				let skip_children = self.handle_element_opening_xml(tag_name, node);
 				self.handle_heading_xml(tag_name, node);
-				(Some(tag_name), skip_children)
+				(Some((tag_name, breaks.after)), skip_children)
 			}
 			NodeType::Text => {
 				self.process_text_node(node);
@@ -229,8 +286,35 @@ impl XmlToText {
 				self.process_node(child);
 			}
 		}
-		if let Some(tag_name) = tag_name {
+		if let Some((tag_name, break_after)) = tag_name {
 			self.handle_element_closing_xml(tag_name);
+			if break_after {
+				self.add_forced_page_break(tag_name);
+			}
+		}
+	}
+
+	fn forced_page_breaks(&self, tag_name: &str, node: Node<'_, '_>) -> ForcedBreaks {
+		if !self.in_body || Self::tag_is(tag_name, "body") {
+			return ForcedBreaks::default();
+		}
+		let classes = node.attribute("class").unwrap_or_default();
+		self.page_break_rules.breaks_for(tag_name, classes, node.attribute("style").unwrap_or_default())
+	}
+
+	/// Starts a page at the current position, on a line of its own for a block element.
+	fn add_forced_page_break(&mut self, tag_name: &str) {
+		if Self::is_block_element(tag_name) {
+			self.finalize_current_line();
+		}
+		push_page_break(&mut self.page_breaks, self.get_current_text_position(), String::new());
+	}
+
+	/// A forced break after the last element would start a page with nothing on it.
+	fn drop_trailing_page_break(&mut self) {
+		let end = self.get_current_text_position();
+		if self.page_breaks.last().is_some_and(|last| last.offset >= end && last.text.is_empty()) {
+			self.page_breaks.pop();
 		}
 	}
 
@@ -260,7 +344,7 @@ impl XmlToText {
 		if Self::tag_is(tag_name, "a") {
 			let link_text = collect_element_text(node);
 			if !link_text.is_empty() {
-				let href = node.attribute("href").unwrap_or("").to_string();
+				let href = Self::href_attribute(node).unwrap_or("").to_string();
 				let processed_link_text = collapse_whitespace(&link_text);
 				let link_offset = self.get_current_text_position();
 				self.current_line.push_str(&processed_link_text);
@@ -276,11 +360,37 @@ impl XmlToText {
 			self.in_body = true;
 		} else if Self::tag_is(tag_name, "pre") {
 			self.finalize_current_line();
+			if self.in_body {
+				self.code_blocks.open(self.get_current_text_position(), self.lines.len());
+			}
 			self.start_preserve_whitespace();
+		} else if Self::tag_is(tag_name, "blockquote") && self.in_body {
+			self.finalize_current_line();
+			self.block_quotes.open(self.get_current_text_position(), self.lines.len());
+		} else if Self::tag_is(tag_name, "aside") && self.in_body {
+			self.finalize_current_line();
+			let is_note = Self::semantic_type(node).is_some_and(is_note_semantic_type);
+			if is_note {
+				self.notes.open(self.get_current_text_position(), self.lines.len());
+			}
+			self.open_asides.push(is_note);
 		} else if Self::tag_is(tag_name, "code") {
 			self.start_preserve_whitespace();
 		} else if Self::tag_is(tag_name, "br") {
 			self.finalize_current_line();
+		} else if Self::tag_is(tag_name, "epigraph") {
+			self.finalize_current_line();
+			if self.in_body && !self.lines.is_empty() {
+				self.push_finalized_line(String::new());
+			}
+		} else if Self::tag_is(tag_name, "stanza") {
+			self.finalize_current_line();
+			if node.prev_sibling_element().is_some_and(|prev| Self::tag_is(prev.tag_name().name(), "stanza")) {
+				self.push_finalized_line(String::new());
+			}
+		} else if Self::tag_is(tag_name, "text-author") {
+			self.finalize_current_line();
+			self.current_line.push_str("\u{2014} ");
 		} else if Self::tag_is(tag_name, "li") {
 			self.handle_list_item_xml(node);
 		} else if Self::tag_is(tag_name, "ul") || Self::tag_is(tag_name, "ol") || Self::tag_is(tag_name, "list") {
@@ -296,43 +406,67 @@ impl XmlToText {
 			if let Some(id) = node.attribute("id").or_else(|| node.attribute("name")) {
 				self.id_positions.insert(id.to_string(), self.get_current_text_position());
 			}
-			if Self::tag_is(tag_name, "img") || Self::tag_is(tag_name, "image") || Self::tag_is(tag_name, "figure") {
-				let mut description = node
+			if Self::semantic_type(node).is_some_and(is_page_break_semantic_type) {
+				let label = node
+					.attribute("title")
+					.or_else(|| node.attribute("aria-label"))
+					.map_or_else(|| collect_element_text(node), str::to_string);
+				let offset = self.get_current_text_position();
+				push_page_break(&mut self.page_breaks, offset, trim_string(&collapse_whitespace(&label)));
+			}
+			if let Some(kind) = Self::semantic_type(node).and_then(LandmarkKind::from_semantic_type) {
+				self.landmarks.push(LandmarkInfo { offset: self.get_current_text_position(), kind });
+			}
+			if Self::tag_is(tag_name, "figure") {
+				self.handle_figure_xml(node);
+			} else if Self::tag_is(tag_name, "img") || Self::tag_is(tag_name, "image") || Self::tag_is(tag_name, "svg")
+			{
+				let is_svg = Self::tag_is(tag_name, "svg");
+				let description = node
 					.attribute("alt")
 					.or_else(|| node.attribute("aria-label"))
 					.or_else(|| node.attribute("aria-description"))
 					.or_else(|| node.attribute("title"))
 					.map(collapse_whitespace)
+					.or_else(|| is_svg.then(|| Self::child_text(node, "title")))
 					.unwrap_or_default();
-
-				if description.is_empty() && Self::tag_is(tag_name, "figure") {
-					for child in node.children() {
-						if child.is_element() && Self::tag_is(child.tag_name().name(), "figcaption") {
-							description = collapse_whitespace(&collect_element_text(child));
-							break;
-						}
-					}
-				}
-
-				if !description.is_empty() {
-					let is_figure = Self::tag_is(tag_name, "figure");
-					// TRANSLATORS: Label inserted before a figure or image's description, e.g. "[Figure: a cat sleeping]"
-					let label = if is_figure { t("Figure") } else { t("Image") };
-					let image_text = format!("[{label}: {description}]");
-					let offset = self.get_current_text_position();
-					self.current_line.push_str(&image_text);
-					let info = ImageInfo { offset, alt_text: description };
-					if is_figure {
-						self.figures.push(info);
-					} else {
-						self.images.push(info);
-					}
-				}
+				let offset = self.get_current_text_position();
+				self.current_line.push_str(&image_text(&description));
+				self.images.push(ImageInfo { offset, alt_text: description });
+				skip_children |= is_svg;
 			}
 		}
 		skip_children
 	}
 
+	/// Puts a figure on a line of its own and records where it starts. A figure with a caption
+	/// leaves the caption to follow its image as ordinary text; one without gets a label instead.
+	fn handle_figure_xml(&mut self, node: Node<'_, '_>) {
+		self.finalize_current_line();
+		let caption = Self::child_text(node, "figcaption");
+		let label = node
+			.attribute("aria-label")
+			.or_else(|| node.attribute("aria-description"))
+			.or_else(|| node.attribute("title"))
+			.map(collapse_whitespace)
+			.unwrap_or_default();
+		let offset = self.get_current_text_position();
+		if caption.is_empty() && !label.is_empty() {
+			self.current_line.push_str(&figure_text(&label));
+			self.finalize_current_line();
+		}
+		let description = if label.is_empty() { caption } else { label };
+		self.figures.push(ImageInfo { offset, alt_text: description });
+	}
+
+	/// The collapsed text of the first child element named `tag_name`, or an empty string.
+	fn child_text(node: Node<'_, '_>, tag_name: &str) -> String {
+		node.children()
+			.find(|child| child.is_element() && Self::tag_is(child.tag_name().name(), tag_name))
+			.map(|child| collapse_whitespace(&collect_element_text(child)))
+			.unwrap_or_default()
+	}
+
 	fn handle_table_xml(&mut self, node: Node<'_, '_>) {
 		self.finalize_current_line();
 		let table_xml = node.document().input_text()[node.range()].to_string();
@@ -364,10 +498,12 @@ impl XmlToText {
 	fn handle_list_item_xml(&mut self, node: Node<'_, '_>) {
 		self.finalize_current_line();
 		let li_text = collect_element_text(node);
+		self.open_list_items.push(self.list_items.len());
 		self.list_items.push(ListItemInfo {
 			offset: self.get_current_text_position(),
 			level: self.list_level,
 			text: li_text,
+			length: 0,
 		});
 		let indent = usize::try_from(self.list_level).unwrap_or(0) * 2;
 		self.current_line.push_str(&" ".repeat(indent));
@@ -447,10 +583,26 @@ impl XmlToText {
 		if is_pre {
 			self.finalize_current_line();
 			self.stop_preserve_whitespace();
+			let end = self.get_current_text_position();
+			self.code_blocks.close(&self.lines, end);
 		} else {
 			if Self::is_block_element(tag_name) {
 				self.finalize_current_line();
 			}
+			if Self::tag_is(tag_name, "blockquote") {
+				let end = self.get_current_text_position();
+				self.block_quotes.close(&self.lines, end);
+			} else if Self::tag_is(tag_name, "aside") {
+				if self.open_asides.pop() == Some(true) {
+					let end = self.get_current_text_position();
+					self.notes.close(&self.lines, end);
+				}
+			} else if Self::tag_is(tag_name, "li")
+				&& let Some(open) = self.open_list_items.pop()
+			{
+				let offset = self.list_items[open].offset;
+				self.list_items[open].length = self.get_current_text_position().saturating_sub(offset);
+			}
 			if Self::tag_is(tag_name, "code") {
 				self.stop_preserve_whitespace();
 			} else if Self::tag_is(tag_name, "b") || Self::tag_is(tag_name, "strong") {
@@ -617,13 +769,28 @@ impl XmlToText {
 			"rearmatter",
 			"doctitle",
 			"docauthor",
+			"poem",
+			"stanza",
+			"v",
+			"epigraph",
+			"text-author",
 		]
 		.iter()
 		.any(|t| Self::tag_is(tag_name, t))
 	}
 
 	fn is_ignored_element(tag_name: &str) -> bool {
-		["script", "style", "noscript", "iframe", "object", "embed"].iter().any(|t| Self::tag_is(tag_name, t))
+		["script", "style", "noscript", "iframe", "object", "embed", "binary"].iter().any(|t| Self::tag_is(tag_name, t))
+	}
+
+	/// Returns the element's `href`, including namespaced forms such as FB2's `l:href`.
+	fn href_attribute<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+		node.attribute("href").or_else(|| node.attributes().find(|attr| attr.name() == "href").map(|attr| attr.value()))
+	}
+
+	/// Returns the element's `epub:type`, falling back to its ARIA `role`.
+	fn semantic_type<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+		node.attribute((EPUB_OPS_NAMESPACE, "type")).or_else(|| node.attribute("role"))
 	}
 
 	const fn tag_is(tag_name: &str, expected: &str) -> bool {
@@ -671,6 +838,15 @@ impl ConverterOutput for XmlToText {
 	fn get_separators(&self) -> &[SeparatorInfo] {
 		&self.separators
 	}
+	fn get_block_quotes(&self) -> &[BlockInfo] {
+		self.block_quotes.blocks()
+	}
+	fn get_code_blocks(&self) -> &[BlockInfo] {
+		self.code_blocks.blocks()
+	}
+	fn get_notes(&self) -> &[BlockInfo] {
+		self.notes.blocks()
+	}
 	fn get_lists(&self) -> &[ListInfo] {
 		&self.lists
 	}
@@ -707,6 +883,72 @@ mod tests {
 		assert_eq!(converter.get_text(), "Hello world");
 	}
 
+	#[test]
+	fn images_figures_and_svg_titles_are_shown_inline() {
+		let xml = concat!(
+			r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:svg="http://www.w3.org/2000/svg"><body>"#,
+			r#"<p>A <img alt="cat"/> and <img src="x.png"/></p>"#,
+			r#"<figure><img alt="Chart"/><figcaption>Sales by year</figcaption></figure>"#,
+			r#"<figure aria-label="Map"><img alt=""/></figure>"#,
+			"<svg:svg><svg:title>Logo</svg:title></svg:svg>",
+			"</body></html>"
+		);
+		let mut converter = XmlToText::new();
+		assert!(converter.convert(xml));
+		let text = converter.get_text();
+		let lines: Vec<&str> = text.lines().collect();
+		assert_eq!(
+			lines,
+			[
+				"A [Image: cat] and [Image]",
+				"[Image: Chart]",
+				"Sales by year",
+				"[Figure: Map]",
+				"[Image]",
+				"[Image: Logo]"
+			]
+		);
+		let figures: Vec<&str> = converter.get_figures().iter().map(|figure| figure.alt_text.as_str()).collect();
+		assert_eq!(figures, ["Sales by year", "Map"]);
+		assert_eq!(converter.get_figures()[0].offset, converter.get_images()[2].offset);
+	}
+
+	#[test]
+	fn pagebreak_spans_and_forced_breaks_start_pages() {
+		let xml = concat!(
+			r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops"><body>"#,
+			"<p>Front</p>",
+			r#"<h1 class="chapter"><span epub:type="pagebreak" id="p7" title="7"/>One</h1>"#,
+			r#"<p style="page-break-before:always">Two</p>"#,
+			"</body></html>"
+		);
+		let rules = PageBreakRules::parse("h1.chapter { page-break-before: always }");
+		let mut converter = XmlToText::new().with_page_break_rules(rules);
+		assert!(converter.convert(xml));
+		let text = converter.get_text();
+		let pages: Vec<(&str, &str)> =
+			converter.get_page_breaks().iter().map(|page| (&text[page.offset..], page.text.as_str())).collect();
+		assert_eq!(pages, [("One\nTwo", "7"), ("Two", "")]);
+	}
+
+	#[test]
+	fn footnote_asides_are_recorded_as_notes() {
+		let xml = concat!(
+			r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops"><body>"#,
+			r##"<p>Text<a epub:type="noteref" href="#n1">1</a></p>"##,
+			r#"<aside>Sidebar</aside>"#,
+			r#"<aside id="n1" epub:type="footnote"><p>First note.</p><p>More.</p></aside>"#,
+			"<p>After</p></body></html>"
+		);
+		let mut converter = XmlToText::new();
+		assert!(converter.convert(xml));
+		let notes = converter.get_notes();
+		assert_eq!(notes.len(), 1);
+		assert_eq!(notes[0].text, "First note.");
+		let text = converter.get_text();
+		assert_eq!(text[notes[0].offset..notes[0].offset + notes[0].length].trim_end(), "First note.\nMore.");
+	}
+
 	#[test]
 	fn test_heading_normalization() {
 		let xml = "<root><body><h2>  Hello \n world </h2></body></root>";
@@ -1,13 +1,35 @@
+use std::ops::Range;
+
 use bitflags::bitflags;
-use regex::RegexBuilder;
+use icu_normalizer::DecomposingNormalizerBorrowed;
+use icu_properties::{
+	CodePointMapData,
+	props::{GeneralCategory, GeneralCategoryGroup},
+};
+use regex::{Match, Regex, RegexBuilder};
 
 use crate::{
-	config::{Bookmark, ConfigManager as RustConfigManager},
-	document::{DocumentHandle, MarkerType},
-	parser::is_external_url,
+	config::{Bookmark, ConfigManager as RustConfigManager, PositionAnchor},
+	document::{DocumentBuffer, DocumentHandle, Marker, MarkerType, TocItem, is_heading_marker},
+	export::PLATFORM_NEWLINE,
+	parser::{is_external_url, util::toc::build_toc_from_buffer},
+	t,
 	types::{self as ffi, HeadingInfo},
+	util::{
+		message::{format_message, plural},
+		text::{
+			byte_to_display_index, collapse_whitespace, display_len, display_to_byte_index, ellipsize_words,
+			sentence_starts, snippet_at, url_decode,
+		},
+	},
 };
 
+pub mod bookmark_ranges;
+pub mod import_annotations;
+pub mod lookup;
+pub mod pronunciation;
+pub mod shuffle;
+
 fn heading_info(doc: &DocumentHandle, idx: i32) -> Option<HeadingInfo> {
 	doc.heading_info(idx)
 }
@@ -102,7 +124,9 @@ pub fn reader_navigate(doc: &DocumentHandle, req: &ffi::NavRequest) -> ffi::NavR
 		| NavTarget::Table
 		| NavTarget::Separator
 		| NavTarget::Image
-		| NavTarget::Figure => {
+		| NavTarget::Figure
+		| NavTarget::BlockQuote
+		| NavTarget::CodeBlock => {
 			let kind = match req.target {
 				NavTarget::List => MarkerType::List,
 				NavTarget::ListItem => MarkerType::ListItem,
@@ -111,8 +135,11 @@ pub fn reader_navigate(doc: &DocumentHandle, req: &ffi::NavRequest) -> ffi::NavR
 				NavTarget::Separator => MarkerType::Separator,
 				NavTarget::Image => MarkerType::Image,
 				NavTarget::Figure => MarkerType::Figure,
+				NavTarget::BlockQuote => MarkerType::BlockQuote,
+				NavTarget::CodeBlock => MarkerType::CodeBlock,
 				_ => unreachable!(
-					"NavTarget should only be List, ListItem, Link, Table, Separator, Image, or Figure in this branch"
+					"NavTarget should only be List, ListItem, Link, Table, Separator, Image, Figure, BlockQuote, or \
+					 CodeBlock in this branch"
 				),
 			};
 			let (idx_opt, wrapped) = select_marker_index(doc, req.position, req.wrap, req.direction, kind);
@@ -152,89 +179,608 @@ pub fn reader_container_navigate(doc: &DocumentHandle, position: i64, to_end: bo
 fn line_after(doc: &DocumentHandle, end: usize) -> usize {
 	let buffer = &doc.document().buffer;
 	let total = buffer.char_count();
-	let probe = end.min(total).saturating_sub(1);
-	buffer.newline_positions().iter().find(|&&nl| nl >= probe).map_or(total, |&nl| (nl + 1).min(total))
+	let probe = buffer.char_index_for_display(end).saturating_sub(1);
+	let next_line = buffer.newline_positions().iter().find(|&&nl| nl >= probe).map_or(total, |&nl| (nl + 1).min(total));
+	buffer.display_index_for_char(next_line)
+}
+
+/// Byte range of the line containing `byte_idx`, excluding its newline.
+fn line_bounds(content: &str, byte_idx: usize) -> (usize, usize) {
+	let start = content[..byte_idx].rfind('\n').map_or(0, |i| i + 1);
+	let end = content[byte_idx..].find('\n').map_or(content.len(), |i| byte_idx + i);
+	(start, end)
+}
+
+/// Start of the line before the one starting at `line_start` (which must be > 0).
+fn previous_line_start(content: &str, line_start: usize) -> usize {
+	content[..line_start - 1].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn is_blank_line(content: &str, line_start: usize) -> bool {
+	let (_, end) = line_bounds(content, line_start);
+	content[line_start..end].trim().is_empty()
+}
+
+/// Byte ranges of the sentences in the paragraph `start..end`, each trimmed of trailing whitespace.
+fn paragraph_sentences(content: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+	let paragraph = &content[start..end];
+	let starts = sentence_starts(paragraph);
+	starts
+		.iter()
+		.enumerate()
+		.map(|(i, &sentence_start)| {
+			let sentence_end = starts.get(i + 1).copied().unwrap_or(paragraph.len());
+			(start + sentence_start, start + paragraph[..sentence_end].trim_end().len())
+		})
+		.collect()
 }
 
+/// Structured documents emit one paragraph per line and mark block boundaries, so only marker-less
+/// text that contains blank lines (plain text, often hard-wrapped) groups consecutive lines into
+/// paragraphs.
+fn uses_blank_line_paragraphs(doc: &DocumentHandle) -> bool {
+	let buffer = &doc.document().buffer;
+	buffer.markers.is_empty() && buffer.content.lines().any(|line| line.trim().is_empty())
+}
+
+fn is_paragraph_start(content: &str, line_start: usize, blank_line_mode: bool) -> bool {
+	if is_blank_line(content, line_start) {
+		return false;
+	}
+	!blank_line_mode || line_start == 0 || is_blank_line(content, previous_line_start(content, line_start))
+}
+
+fn paragraph_end(content: &str, start: usize, blank_line_mode: bool) -> usize {
+	let (_, mut end) = line_bounds(content, start);
+	while blank_line_mode && end < content.len() && !is_blank_line(content, end + 1) {
+		end = line_bounds(content, end + 1).1;
+	}
+	end
+}
+
+/// Byte range of the paragraph holding `byte_idx`, as [`reader_paragraph_navigate`] counts them. A
+/// blank line is a range of its own, with no sentences in it.
+fn paragraph_bounds(content: &str, byte_idx: usize, blank_line_mode: bool) -> (usize, usize) {
+	let (mut start, end) = line_bounds(content, byte_idx);
+	if is_blank_line(content, start) {
+		return (start, end);
+	}
+	while !is_paragraph_start(content, start, blank_line_mode) {
+		start = previous_line_start(content, start);
+	}
+	(start, paragraph_end(content, start, blank_line_mode))
+}
+
+fn text_unit_result(doc: &DocumentHandle, range: Option<(usize, usize)>) -> ffi::NavResult {
+	let buffer = &doc.document().buffer;
+	range.map_or_else(
+		|| build_nav_result(false, false, 0, 0, String::new()),
+		|(start, end)| {
+			let text = collapse_whitespace(&buffer.content[start..end]).trim().to_string();
+			build_nav_result(true, false, buffer.display_index_for_byte(start), 0, text)
+		},
+	)
+}
+
+/// Move to the start of the next or previous sentence; `marker_text` carries its text.
+///
+/// Sentences never span paragraphs, counted as [`reader_paragraph_navigate`] does, so a sentence
+/// hard-wrapped over several lines of plain text stays whole. Like word processors, moving backwards
+/// from inside a sentence lands on that sentence's start first.
 #[must_use]
-pub fn reader_search(haystack: &str, needle: &str, start: i64, options: SearchOptions) -> i64 {
-	if needle.is_empty() {
-		return -1;
+pub fn reader_sentence_navigate(doc: &DocumentHandle, position: i64, next: bool) -> ffi::NavResult {
+	let buffer = &doc.document().buffer;
+	let content = buffer.content.as_str();
+	let blank_line_mode = uses_blank_line_paragraphs(doc);
+	let byte_idx = buffer.byte_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
+	let (mut start, mut end) = paragraph_bounds(content, byte_idx, blank_line_mode);
+	let sentences = paragraph_sentences(content, start, end);
+	let mut found = if next {
+		sentences.into_iter().find(|&(sentence_start, _)| sentence_start > byte_idx)
+	} else {
+		sentences.into_iter().rev().find(|&(sentence_start, _)| sentence_start < byte_idx)
+	};
+	while found.is_none() {
+		if next {
+			if end >= content.len() {
+				break;
+			}
+			(start, end) = paragraph_bounds(content, end + 1, blank_line_mode);
+			found = paragraph_sentences(content, start, end).into_iter().next();
+		} else {
+			if start == 0 {
+				break;
+			}
+			(start, end) = paragraph_bounds(content, previous_line_start(content, start), blank_line_mode);
+			found = paragraph_sentences(content, start, end).pop();
+		}
 	}
-	let start_utf16 = usize::try_from(start.clamp(0, i64::MAX)).unwrap_or(0);
+	text_unit_result(doc, found)
+}
 
-	let utf16_to_byte_index = |s: &str, utf16_idx: usize| -> usize {
-		let mut utf16_count = 0usize;
-		for (byte_idx, ch) in s.char_indices() {
-			let len16 = ch.len_utf16();
-			if utf16_count >= utf16_idx {
-				return byte_idx;
+/// Display range and text of the sentence containing `position`, or of the next one when the caret sits
+/// on a blank line or in the gap after a sentence.
+#[must_use]
+pub fn reader_sentence_at(doc: &DocumentHandle, position: i64) -> Option<(usize, usize, String)> {
+	let buffer = &doc.document().buffer;
+	let content = buffer.content.as_str();
+	let blank_line_mode = uses_blank_line_paragraphs(doc);
+	let byte_idx = buffer.byte_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
+	let (mut start, mut end) = paragraph_bounds(content, byte_idx, blank_line_mode);
+	let sentences = paragraph_sentences(content, start, end);
+	let mut found = sentences.iter().copied().find(|&(sentence_start, sentence_end)| {
+		sentence_start <= byte_idx && byte_idx < sentence_end.max(sentence_start + 1)
+	});
+	if found.is_none() {
+		found = sentences.into_iter().find(|&(sentence_start, _)| sentence_start > byte_idx);
+	}
+	while found.is_none() && end < content.len() {
+		(start, end) = paragraph_bounds(content, end + 1, blank_line_mode);
+		found = paragraph_sentences(content, start, end).into_iter().next();
+	}
+	let (start, end) = found?;
+	let text = collapse_whitespace(&content[start..end]).trim().to_string();
+	Some((buffer.display_index_for_byte(start), buffer.display_index_for_byte(end), text))
+}
+
+/// Move to the start of the next or previous paragraph; `marker_text` carries its text.
+///
+/// Paragraphs are lines, except in marker-less text with blank lines where they are runs of lines
+/// separated by blank lines. Moving backwards from inside a paragraph lands on its start first.
+#[must_use]
+pub fn reader_paragraph_navigate(doc: &DocumentHandle, position: i64, next: bool) -> ffi::NavResult {
+	let buffer = &doc.document().buffer;
+	let content = buffer.content.as_str();
+	let blank_line_mode = uses_blank_line_paragraphs(doc);
+	let byte_idx = buffer.byte_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
+	let (mut line_start, mut line_end) = line_bounds(content, byte_idx);
+	let target = if next {
+		loop {
+			if line_end >= content.len() {
+				break None;
+			}
+			line_start = line_end + 1;
+			line_end = line_bounds(content, line_start).1;
+			if is_paragraph_start(content, line_start, blank_line_mode) {
+				break Some(line_start);
 			}
-			utf16_count += len16;
 		}
-		s.len()
-	};
-	let byte_to_utf16_index = |s: &str, byte_idx: usize| -> usize {
-		let mut utf16_count = 0usize;
-		for (idx, ch) in s.char_indices() {
-			if idx >= byte_idx {
-				break;
+	} else {
+		loop {
+			if line_start < byte_idx && is_paragraph_start(content, line_start, blank_line_mode) {
+				break Some(line_start);
+			}
+			if line_start == 0 {
+				break None;
 			}
-			utf16_count += ch.len_utf16();
+			line_start = previous_line_start(content, line_start);
 		}
-		utf16_count
 	};
-	let start_byte = utf16_to_byte_index(haystack, start_utf16);
+	text_unit_result(doc, target.map(|start| (start, paragraph_end(content, start, blank_line_mode))))
+}
+
+/// Upper bound, in bytes, on a compiled search pattern and on its lazy DFA cache.
+const SEARCH_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Why a Find query can't be searched for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchPatternError {
+	/// The pattern matches empty text, so every position would be a zero-length hit.
+	EmptyMatch,
+	/// The pattern compiles to more than [`SEARCH_REGEX_SIZE_LIMIT`].
+	TooComplex,
+	/// Regular expression syntax error. `position` is the 0-based character offset into the query.
+	Syntax { position: Option<usize>, message: String },
+}
+
+#[must_use]
+pub fn reader_search(haystack: &str, needle: &str, start: i64, options: SearchOptions) -> i64 {
+	reader_search_match(haystack, needle, start, options).map_or(-1, |(position, _)| position)
+}
+
+/// Finds the next match (or the previous one without `FORWARD`) of `needle` from display offset
+/// `start`. Returns the match's display offset and display length, which differs from the needle's
+/// for regular expressions and case-insensitive matches that change length when folded.
+#[must_use]
+pub fn reader_search_match(haystack: &str, needle: &str, start: i64, options: SearchOptions) -> Option<(i64, i64)> {
+	if needle.is_empty() {
+		return None;
+	}
+	let re = build_search_regex(needle, options).ok()?;
+	search_with_regex(&re, haystack, start, options)
+}
+
+/// Searches from `start`, wrapping around to the other end of `haystack` once.
+///
+/// # Errors
+///
+/// Returns the reason when `needle` can't be used as a pattern.
+pub fn reader_search_with_wrap(
+	haystack: &str,
+	needle: &str,
+	start: i64,
+	options: SearchOptions,
+) -> Result<ffi::SearchResult, SearchPatternError> {
+	let not_found = ffi::SearchResult { found: false, wrapped: false, position: -1, length: 0 };
+	if needle.is_empty() {
+		return Ok(not_found);
+	}
+	let re = build_search_regex(needle, options)?;
+	if let Some((position, length)) = search_with_regex(&re, haystack, start, options) {
+		return Ok(ffi::SearchResult { found: true, wrapped: false, position, length });
+	}
+	let wrap_pos =
+		if options.contains(SearchOptions::FORWARD) { 0 } else { i64::try_from(display_len(haystack)).unwrap_or(0) };
+	if let Some((position, length)) = search_with_regex(&re, haystack, wrap_pos, options) {
+		return Ok(ffi::SearchResult { found: true, wrapped: true, position, length });
+	}
+	Ok(not_found)
+}
+
+/// Every match of `needle` in `haystack`, front to back and without overlaps, as display offset
+/// and display length. Stops after `limit` matches; the flag says whether any were left out.
+/// `FORWARD` in `options` is ignored.
+///
+/// # Errors
+///
+/// Returns the reason when `needle` can't be used as a pattern.
+pub fn reader_search_all(
+	haystack: &str,
+	needle: &str,
+	options: SearchOptions,
+	limit: usize,
+) -> Result<(Vec<(i64, i64)>, bool), SearchPatternError> {
+	if needle.is_empty() {
+		return Ok((Vec::new(), false));
+	}
+	let re = build_search_regex(needle, options)?;
+	let whole_word = options.contains(SearchOptions::WHOLE_WORD);
+	let accepts = |range: Range<usize>| !range.is_empty() && (!whole_word || is_whole_word_match(haystack, range));
+	let mut matches = Vec::new();
+	// Display offsets are counted on from the previous match rather than from the start each time.
+	let (mut byte, mut display) = (0, 0);
+	while let Some(found) = next_accepted_match(&re, haystack, byte, accepts) {
+		if matches.len() == limit {
+			return Ok((matches, true));
+		}
+		let start = display + display_len(&haystack[byte..found.start()]);
+		let length = display_len(found.as_str());
+		matches.push((i64::try_from(start).unwrap_or(i64::MAX), i64::try_from(length).unwrap_or(0)));
+		(byte, display) = (found.end(), start + length);
+	}
+	Ok((matches, false))
+}
+
+/// A heading or table of contents entry found by [`reader_search_headings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingSearchResult {
+	/// Display offset of the heading, or of the text the entry points to.
+	pub position: i64,
+	pub text: String,
+	/// Heading level, or 1 plus the nesting depth for a table of contents entry.
+	pub level: i32,
+	/// Whether the search went past one end of the document and came back round from the other.
+	pub wrapped: bool,
+}
+
+/// Finds the next heading (or the previous one without `FORWARD`) after display offset `start`
+/// whose text matches `needle`, among the heading markers and table of contents entries, wrapping
+/// around once. Where a heading and an entry share a position, the heading is found.
+///
+/// # Errors
+///
+/// Returns the reason when `needle` can't be used as a pattern.
+pub fn reader_search_headings(
+	markers: &[Marker],
+	toc_items: &[TocItem],
+	needle: &str,
+	start: i64,
+	options: SearchOptions,
+) -> Result<Option<HeadingSearchResult>, SearchPatternError> {
+	fn flatten<'a>(items: &'a [TocItem], level: i32, flat: &mut Vec<(usize, &'a str, i32)>) {
+		for item in items {
+			flat.push((item.offset, &item.name, level));
+			flatten(&item.children, level + 1, flat);
+		}
+	}
+	if needle.is_empty() {
+		return Ok(None);
+	}
+	let re = build_search_regex(needle, options)?;
+	let mut candidates: Vec<(usize, &str, i32)> = markers
+		.iter()
+		.filter(|marker| is_heading_marker(marker.mtype))
+		.map(|marker| (marker.position, marker.text.as_str(), marker.level))
+		.collect();
+	flatten(toc_items, 1, &mut candidates);
+	let match_options = options | SearchOptions::FORWARD;
+	candidates.retain(|(_, text, _)| search_with_regex(&re, text, 0, match_options).is_some());
+	candidates.sort_by_key(|&(position, _, _)| position);
+	candidates.dedup_by_key(|(position, _, _)| *position);
+	let start = usize::try_from(start).unwrap_or(0);
+	let (found, wrapped) = if options.contains(SearchOptions::FORWARD) {
+		match candidates.iter().find(|(position, _, _)| *position > start) {
+			Some(found) => (Some(found), false),
+			None => (candidates.first(), true),
+		}
+	} else {
+		match candidates.iter().rev().find(|(position, _, _)| *position < start) {
+			Some(found) => (Some(found), false),
+			None => (candidates.last(), true),
+		}
+	};
+	Ok(found.map(|&(position, text, level)| HeadingSearchResult {
+		position: i64::try_from(position).unwrap_or(i64::MAX),
+		text: text.to_string(),
+		level,
+		wrapped,
+	}))
+}
 
+/// Characters of context kept on either side of a match in a note search excerpt.
+const NOTE_EXCERPT_CONTEXT: usize = 30;
+
+/// Where a note found by [`search_notes`] is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteSource {
+	/// The note of the bookmark spanning these display offsets of the document.
+	Bookmark { start: i64, end: i64 },
+	/// The document's own notes, edited with Document Notes.
+	DocumentNotes,
+}
+
+/// A match found by [`search_notes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSearchResult {
+	pub source: NoteSource,
+	/// Display offset of the match within the note.
+	pub position: i64,
+	/// Display length of the match within the note.
+	pub length: i64,
+	/// The match and some text either side on one line, the match set off with `>>` and `<<`.
+	pub excerpt: String,
+}
+
+/// Every match of `needle` in the bookmark notes of the document at `path`, in bookmark order, then
+/// in its Document Notes. With `ignore_diacritics`, accented letters match their plain forms, so
+/// "cafe" finds "café". `FORWARD` in `options` is ignored.
+///
+/// # Errors
+///
+/// Returns the reason when `needle` can't be used as a pattern.
+pub fn search_notes(
+	manager: &RustConfigManager,
+	path: &str,
+	needle: &str,
+	options: SearchOptions,
+	ignore_diacritics: bool,
+) -> Result<Vec<NoteSearchResult>, SearchPatternError> {
+	if needle.is_empty() {
+		return Ok(Vec::new());
+	}
+	let re = if ignore_diacritics {
+		build_search_regex(&fold_diacritics(needle).0, options)?
+	} else {
+		build_search_regex(needle, options)?
+	};
+	let mut bookmarks = manager.get_bookmarks(path);
+	bookmarks.retain(|bookmark| !bookmark.note.is_empty());
+	bookmarks.sort_by_key(|bookmark| (bookmark.start, bookmark.end));
+	let notes = bookmarks
+		.into_iter()
+		.map(|bookmark| (NoteSource::Bookmark { start: bookmark.start, end: bookmark.end }, bookmark.note))
+		.chain(std::iter::once((NoteSource::DocumentNotes, manager.get_document_notes(path))));
+	let mut results = Vec::new();
+	for (source, note) in notes {
+		for range in note_matches(&re, &note, options, ignore_diacritics) {
+			results.push(NoteSearchResult {
+				source,
+				position: i64::try_from(byte_to_display_index(&note, range.start)).unwrap_or(i64::MAX),
+				length: i64::try_from(display_len(&note[range.clone()])).unwrap_or(0),
+				excerpt: note_excerpt(&note, range),
+			});
+		}
+	}
+	Ok(results)
+}
+
+/// Byte ranges of the matches of `re` in `note`. With `ignore_diacritics`, `re` is run over the
+/// folded note and its matches are mapped back, taking in any marks that trail them.
+fn note_matches(re: &Regex, note: &str, options: SearchOptions, ignore_diacritics: bool) -> Vec<Range<usize>> {
+	let whole_word = options.contains(SearchOptions::WHOLE_WORD);
+	let (folded, origins) = if ignore_diacritics { fold_diacritics(note) } else { (String::new(), Vec::new()) };
+	let text = if ignore_diacritics { folded.as_str() } else { note };
+	let accepts = |range: Range<usize>| !range.is_empty() && (!whole_word || is_whole_word_match(text, range));
+	let mut ranges = Vec::new();
+	let mut at = 0;
+	while let Some(found) = next_accepted_match(re, text, at, accepts) {
+		at = found.end();
+		ranges.push(if ignore_diacritics { origins[found.start()]..origins[found.end()] } else { found.range() });
+	}
+	ranges
+}
+
+/// `text` with the diacritics taken off its letters, so "é" and "e\u{301}" both read as "e", along
+/// with the byte offset in `text` that each byte of the result came from and a final entry for the
+/// end of `text`. Letters whose decomposition holds no marks, such as Hangul syllables, are kept whole.
+fn fold_diacritics(text: &str) -> (String, Vec<usize>) {
+	let nfd = DecomposingNormalizerBorrowed::new_nfd();
+	let mut folded = String::with_capacity(text.len());
+	let mut origins = Vec::with_capacity(text.len() + 1);
+	let mut buffer = [0; 4];
+	for (index, ch) in text.char_indices() {
+		let decomposed = nfd.normalize(ch.encode_utf8(&mut buffer));
+		let mut push = |base: char| {
+			folded.push(base);
+			origins.extend(std::iter::repeat_n(index, base.len_utf8()));
+		};
+		if decomposed.chars().any(is_mark) {
+			decomposed.chars().filter(|&c| !is_mark(c)).for_each(&mut push);
+		} else {
+			push(ch);
+		}
+	}
+	origins.push(text.len());
+	(folded, origins)
+}
+
+/// The match at byte `range` of `note` with up to [`NOTE_EXCERPT_CONTEXT`] characters either side,
+/// on one line, with `>>` and `<<` around the match and an ellipsis where the note was cut.
+fn note_excerpt(note: &str, range: Range<usize>) -> String {
+	let from = note[..range.start].char_indices().rev().nth(NOTE_EXCERPT_CONTEXT - 1).map_or(0, |(index, _)| index);
+	let to =
+		note[range.end..].char_indices().nth(NOTE_EXCERPT_CONTEXT).map_or(note.len(), |(index, _)| range.end + index);
+	let excerpt = format!("{}>>{}<<{}", &note[from..range.start], &note[range.clone()], &note[range.end..to]);
+	let excerpt = collapse_whitespace(&excerpt);
+	let excerpt = excerpt.trim();
+	let before = if from > 0 { "\u{2026}" } else { "" };
+	let after = if to < note.len() { "\u{2026}" } else { "" };
+	format!("{before}{excerpt}{after}")
+}
+
+/// Compiles a Find query, escaping it unless `REGEX` is set. `WHOLE_WORD` isn't part of the
+/// pattern; [`search_with_regex`] checks the edges of each match instead.
+///
+/// # Errors
+///
+/// Rejects syntax errors, patterns over the size limit, and patterns that match empty text.
+pub fn build_search_regex(needle: &str, options: SearchOptions) -> Result<Regex, SearchPatternError> {
 	// Build regex for search - this avoids copying/lowercasing the entire haystack
-	let escaped_needle =
-		if options.contains(SearchOptions::REGEX) { needle.to_string() } else { regex::escape(needle) };
-	let pattern =
-		if options.contains(SearchOptions::WHOLE_WORD) { format!(r"\b{escaped_needle}\b") } else { escaped_needle };
+	let ignore_case = !options.contains(SearchOptions::MATCH_CASE);
+	let pattern = if options.contains(SearchOptions::REGEX) {
+		needle.to_string()
+	} else {
+		literal_search_pattern(needle, ignore_case)
+	};
 	let mut builder = RegexBuilder::new(&pattern);
-	if !options.contains(SearchOptions::MATCH_CASE) {
+	builder.size_limit(SEARCH_REGEX_SIZE_LIMIT).dfa_size_limit(SEARCH_REGEX_SIZE_LIMIT);
+	if ignore_case {
 		builder.case_insensitive(true);
 	}
-	let Ok(re) = builder.build() else {
-		return -1;
-	};
+	let re = builder.build().map_err(|err| match err {
+		regex::Error::CompiledTooBig(_) => SearchPatternError::TooComplex,
+		err => {
+			let (position, message) = syntax_error_details(&err.to_string());
+			SearchPatternError::Syntax { position, message }
+		}
+	})?;
+	if re.is_match("") {
+		return Err(SearchPatternError::EmptyMatch);
+	}
+	Ok(re)
+}
 
-	if options.contains(SearchOptions::FORWARD) {
-		if let Some(m) = re.find(&haystack[start_byte..]) {
-			let byte_pos = start_byte + m.start();
-			let utf16_pos = byte_to_utf16_index(haystack, byte_pos);
-			return i64::try_from(utf16_pos).unwrap_or(-1);
+/// Escapes a plain Find query. Ignoring case, "ß" and "ss" also match each other, which the
+/// simple case folding `regex` applies doesn't cover.
+fn literal_search_pattern(needle: &str, ignore_case: bool) -> String {
+	if !ignore_case {
+		return regex::escape(needle);
+	}
+	let mut pattern = String::with_capacity(needle.len());
+	let mut chars = needle.chars().peekable();
+	while let Some(ch) = chars.next() {
+		match ch {
+			'\u{df}' | '\u{1e9e}' => pattern.push_str("(?:\u{df}|ss)"),
+			's' | 'S' if chars.next_if(|next| matches!(next, 's' | 'S')).is_some() => {
+				pattern.push_str("(?:ss|\u{df})");
+			}
+			_ => pattern.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
 		}
+	}
+	pattern
+}
+
+/// Runs `re` from display offset `start`, skipping zero-length matches (lookarounds and anchors
+/// can still produce them mid-text) so a hit always selects at least one character. With
+/// `WHOLE_WORD`, matches that start or end inside a word are skipped too.
+fn search_with_regex(re: &Regex, haystack: &str, start: i64, options: SearchOptions) -> Option<(i64, i64)> {
+	let start_byte = display_to_byte_index(haystack, usize::try_from(start.max(0)).unwrap_or(0));
+	let whole_word = options.contains(SearchOptions::WHOLE_WORD);
+	let accepts = |range: Range<usize>| !range.is_empty() && (!whole_word || is_whole_word_match(haystack, range));
+	let found = if options.contains(SearchOptions::FORWARD) {
+		next_accepted_match(re, haystack, start_byte, accepts)?
 	} else {
-		let mut last: Option<usize> = None;
-		let end_byte = start_byte.min(haystack.len());
-		for m in re.find_iter(&haystack[..end_byte]) {
-			last = Some(m.start());
+		let before = &haystack[..start_byte];
+		let mut last = None;
+		let mut at = 0;
+		while let Some(found) = next_accepted_match(re, before, at, accepts) {
+			at = found.end();
+			last = Some(found);
 		}
-		if let Some(pos) = last {
-			let utf16_pos = byte_to_utf16_index(haystack, pos);
-			return i64::try_from(utf16_pos).unwrap_or(-1);
+		last?
+	};
+	let position = byte_to_display_index(haystack, found.start());
+	let length = display_len(found.as_str());
+	Some((i64::try_from(position).ok()?, i64::try_from(length).ok()?))
+}
+
+/// The first match of `re` at or after byte `at` whose range `accepts` takes. A rejected match is retried
+/// one character past its start, so a candidate overlapping it isn't missed.
+fn next_accepted_match<'h>(
+	re: &Regex,
+	text: &'h str,
+	mut at: usize,
+	accepts: impl Fn(Range<usize>) -> bool,
+) -> Option<Match<'h>> {
+	loop {
+		let found = re.find_at(text, at)?;
+		if accepts(found.range()) {
+			return Some(found);
 		}
+		at = found.start() + text[found.start()..].chars().next()?.len_utf8();
 	}
-	-1
 }
 
-#[must_use]
-pub fn reader_search_with_wrap(haystack: &str, needle: &str, start: i64, options: SearchOptions) -> ffi::SearchResult {
-	let position = reader_search(haystack, needle, start, options);
-	if position >= 0 {
-		return ffi::SearchResult { found: true, wrapped: false, position };
-	}
-	let wrap_pos = if options.contains(SearchOptions::FORWARD) {
-		0
-	} else {
-		i64::try_from(haystack.encode_utf16().count()).unwrap_or(0)
+/// Whether `range` of `text` starts and ends on word boundaries.
+fn is_whole_word_match(text: &str, range: Range<usize>) -> bool {
+	is_word_boundary(text, range.start) && is_word_boundary(text, range.end)
+}
+
+/// Whether byte offset `at` separates a word character from a non-word one, in either order.
+fn is_word_boundary(text: &str, at: usize) -> bool {
+	let before = text[..at].char_indices().next_back().is_some_and(|(index, _)| is_word_char_at(text, index));
+	let after = at < text.len() && is_word_char_at(text, at);
+	before != after
+}
+
+/// Whether the character at byte `index` belongs to a word: letters, digits, combining marks and
+/// `_`, plus an apostrophe with word characters on both sides, as in "l'enfant" or "don't".
+fn is_word_char_at(text: &str, index: usize) -> bool {
+	let Some(ch) = text[index..].chars().next() else {
+		return false;
 	};
-	let wrapped_position = reader_search(haystack, needle, wrap_pos, options);
-	if wrapped_position >= 0 {
-		return ffi::SearchResult { found: true, wrapped: true, position: wrapped_position };
+	if is_word_char(ch) {
+		return true;
 	}
-	ffi::SearchResult { found: false, wrapped: false, position: -1 }
+	matches!(ch, '\'' | '\u{2019}')
+		&& text[..index].chars().next_back().is_some_and(is_word_char)
+		&& text[index + ch.len_utf8()..].chars().next().is_some_and(is_word_char)
+}
+
+fn is_word_char(ch: char) -> bool {
+	ch.is_alphanumeric() || ch == '_' || is_mark(ch)
+}
+
+/// Whether `ch` is a combining mark, such as the accent of a decomposed "é".
+fn is_mark(ch: char) -> bool {
+	GeneralCategoryGroup::Mark.contains(CodePointMapData::<GeneralCategory>::new().get(ch))
+}
+
+/// Pulls the caret column and the `error:` line out of a `regex` syntax error, whose text shows the
+/// pattern indented by four spaces with a `^` under the offending character.
+fn syntax_error_details(text: &str) -> (Option<usize>, String) {
+	let position = text.lines().find_map(|line| {
+		let trimmed = line.trim_start();
+		(!trimmed.is_empty() && trimmed.chars().all(|ch| ch == '^'))
+			.then(|| (line.len() - trimmed.len()).saturating_sub(4))
+	});
+	let message = text
+		.lines()
+		.find_map(|line| line.strip_prefix("error: "))
+		.unwrap_or_else(|| text.lines().last().unwrap_or_default())
+		.trim()
+		.to_string();
+	(position, message)
 }
 
 bitflags! {
@@ -254,11 +800,15 @@ pub fn bookmark_navigate(
 	wrap: bool,
 	next: bool,
 	notes_only: bool,
+	category: Option<&str>,
 ) -> ffi::BookmarkNavResult {
 	let mut bookmarks: Vec<Bookmark> = manager.get_bookmarks(path);
 	if notes_only {
 		bookmarks.retain(|b| !b.note.is_empty());
 	}
+	if let Some(category) = category {
+		bookmarks.retain(|b| b.category.eq_ignore_ascii_case(category));
+	}
 	if bookmarks.is_empty() {
 		return ffi::BookmarkNavResult { found: false, start: -1, note: String::new(), index: -1, wrapped: false };
 	}
@@ -297,8 +847,12 @@ pub fn get_filtered_bookmarks(
 	path: &str,
 	current_pos: i64,
 	filter: ffi::BookmarkFilterType,
+	category: Option<&str>,
 ) -> ffi::FilteredBookmarks {
 	let mut bookmarks: Vec<Bookmark> = manager.get_bookmarks(path);
+	if let Some(category) = category {
+		bookmarks.retain(|b| b.category.eq_ignore_ascii_case(category));
+	}
 	match filter {
 		ffi::BookmarkFilterType::BookmarksOnly => {
 			bookmarks.retain(|b| b.note.is_empty());
@@ -315,6 +869,7 @@ pub fn get_filtered_bookmarks(
 			start: b.start,
 			end: b.end,
 			note: b.note.clone(),
+			category: b.category.clone(),
 			is_whole_line: b.start == b.end,
 		})
 		.collect();
@@ -335,6 +890,528 @@ pub fn get_filtered_bookmarks(
 	ffi::FilteredBookmarks { items, closest_index }
 }
 
+/// Text for bookmarks copied from the bookmarks dialog, one per line: the note and the bookmarked
+/// text as the list shows them, or just the text when there is no note.
+#[must_use]
+pub fn bookmarks_clipboard_text(bookmarks: &[ffi::BookmarkDisplayEntry]) -> String {
+	bookmarks
+		.iter()
+		.map(|bookmark| {
+			if bookmark.note.is_empty() {
+				bookmark.snippet.clone()
+			} else {
+				format!("{} - {}", bookmark.note, bookmark.snippet)
+			}
+		})
+		.collect::<Vec<_>>()
+		.join(PLATFORM_NEWLINE)
+}
+
+/// Composes a "where am I?" announcement such as "Chapter 4, page 37 of 112, 33 percent, line 1204".
+///
+/// Parts that don't apply — an empty heading, or a document without pages — are left out entirely.
+/// A printed page label, where the document has one, is given ahead of the page's place in the file.
+#[must_use]
+pub fn format_location(
+	heading: &str,
+	page: Option<(usize, usize)>,
+	page_label: Option<&str>,
+	percent: i32,
+	line: i64,
+) -> String {
+	let mut parts = Vec::new();
+	let heading = collapse_whitespace(heading);
+	if !heading.trim().is_empty() {
+		parts.push(heading.trim().to_string());
+	}
+	if let Some((page, page_count)) = page.filter(|&(page, page_count)| page > 0 && page_count > 0) {
+		if let Some(label) = page_label {
+			// TRANSLATORS: Part of the location announcement for a document with printed page numbers; the first {} is the printed number (such as "xi"), the second the page's position in the file, the third the page count
+			let template = t("page {} ({} of {})");
+			parts.push(template.replacen("{}", label, 1).replacen("{}", &page.to_string(), 1).replacen(
+				"{}",
+				&page_count.to_string(),
+				1,
+			));
+		} else {
+			// TRANSLATORS: Part of the location announcement; the first %d is the current page, the second the page count
+			parts.push(t("page %d of %d").replacen("%d", &page.to_string(), 1).replacen(
+				"%d",
+				&page_count.to_string(),
+				1,
+			));
+		}
+	}
+	// TRANSLATORS: Part of the location announcement; %d is how far through the document the caret is
+	parts.push(t("%d percent").replacen("%d", &percent.to_string(), 1));
+	// TRANSLATORS: Part of the location announcement; %d is the current line number
+	parts.push(t("line %d").replacen("%d", &line.to_string(), 1));
+	parts.join(", ")
+}
+
+/// Everything the status bar template can show about the caret's place in a document.
+#[derive(Debug, Clone, Default)]
+pub struct StatusFields {
+	pub line: i64,
+	pub character: i64,
+	pub percent: i32,
+	/// Current page and page count, for documents that have pages.
+	pub page: Option<(usize, usize)>,
+	/// Printed label of the current page, which `{page}` shows instead of its number when present.
+	pub page_label: Option<String>,
+	/// The numbered headings enclosing the caret, e.g. "3 Methods > 3.2 Participants".
+	pub heading: String,
+	/// Estimated words between the caret and the end, for documents that have any words.
+	pub words_left: Option<usize>,
+	/// `words_left` weighted for reading speed like
+	/// [`DocumentStats::reading_words`](crate::document::DocumentStats::reading_words), which drives `{time_left}`.
+	pub reading_words_left: Option<usize>,
+	pub title: String,
+}
+
+/// The status bar template used until the user sets their own.
+#[must_use]
+pub fn default_status_format() -> String {
+	// TRANSLATORS: Default status bar layout. Translate the words but keep the {placeholders} exactly as they are
+	t("Line {line}, Character {char}, Reading {percent}% | {heading}")
+}
+
+/// Fills in a status bar template such as `"Page {page} of {pages} | {time_left}"`.
+///
+/// The template is split into parts at `,`, `|` and `;`. A part whose placeholders have nothing to
+/// show for this document (no pages, no heading yet) is left out together with the separator in
+/// front of it, so the result never has dangling separators. Unknown placeholders are kept as
+/// typed. `words_per_minute` drives `{time_left}`.
+#[must_use]
+pub fn format_status(template: &str, fields: &StatusFields, words_per_minute: i64) -> String {
+	// Each part with the delimiter that ends it; the last part has none.
+	let mut parts: Vec<(&str, Option<char>)> = Vec::new();
+	let mut start = 0;
+	let mut depth = 0usize;
+	for (index, ch) in template.char_indices() {
+		match ch {
+			'{' => depth += 1,
+			'}' => depth = depth.saturating_sub(1),
+			',' | '|' | ';' if depth == 0 => {
+				parts.push((&template[start..index], Some(ch)));
+				start = index + ch.len_utf8();
+			}
+			_ => {}
+		}
+	}
+	parts.push((&template[start..], None));
+	let mut output = String::new();
+	let mut previous: Option<(&str, char)> = None;
+	for (part, delimiter) in parts {
+		if let Some(rendered) = render_status_part(part.trim(), fields, words_per_minute)
+			&& !rendered.is_empty()
+		{
+			if let Some((previous_part, previous_delimiter)) = previous.filter(|_| !output.is_empty()) {
+				output.push_str(&previous_part[previous_part.trim_end().len()..]);
+				output.push(previous_delimiter);
+				output.push_str(&part[..part.len() - part.trim_start().len()]);
+			}
+			output.push_str(&rendered);
+		}
+		previous = delimiter.map(|delimiter| (part, delimiter));
+	}
+	output
+}
+
+/// One part of a status template with its placeholders filled in, or `None` when one of them has
+/// nothing to show.
+fn render_status_part(part: &str, fields: &StatusFields, words_per_minute: i64) -> Option<String> {
+	let mut rendered = String::with_capacity(part.len());
+	let mut rest = part;
+	while let Some(open) = rest.find('{') {
+		let Some(close) = rest[open..].find('}') else {
+			break;
+		};
+		rendered.push_str(&rest[..open]);
+		let name = &rest[open + 1..open + close];
+		match status_placeholder(name, fields, words_per_minute) {
+			Some(Some(value)) => rendered.push_str(&value),
+			Some(None) => return None,
+			None => rendered.push_str(&rest[open..=open + close]),
+		}
+		rest = &rest[open + close + 1..];
+	}
+	rendered.push_str(rest);
+	Some(rendered)
+}
+
+/// `None` for an unknown placeholder, `Some(None)` for a known one with nothing to show.
+fn status_placeholder(name: &str, fields: &StatusFields, words_per_minute: i64) -> Option<Option<String>> {
+	let non_empty = |text: &str| (!text.trim().is_empty()).then(|| text.trim().to_string());
+	Some(match name {
+		"line" => Some(fields.line.to_string()),
+		"char" => Some(fields.character.to_string()),
+		"percent" => Some(fields.percent.to_string()),
+		"page" => fields
+			.page
+			.filter(|&(page, _)| page > 0)
+			.map(|(page, _)| fields.page_label.clone().unwrap_or_else(|| page.to_string())),
+		"pages" => fields.page.filter(|&(_, pages)| pages > 0).map(|(_, pages)| pages.to_string()),
+		"heading" => non_empty(&fields.heading),
+		"words_left" => fields.words_left.map(|words| words.to_string()),
+		"time_left" => fields.reading_words_left.filter(|_| words_per_minute > 0).map(|words| {
+			let minutes = u64::try_from(words).unwrap_or(u64::MAX).div_ceil(words_per_minute.unsigned_abs());
+			format_time_left(minutes)
+		}),
+		"title" => non_empty(&fields.title),
+		_ => return None,
+	})
+}
+
+/// A short reading-time estimate for the status bar, e.g. "1 h 40 min" or "12 min".
+fn format_time_left(minutes: u64) -> String {
+	let (hours, minutes) = (minutes / 60, minutes % 60);
+	if hours == 0 {
+		// TRANSLATORS: Status bar reading time left when under an hour; %d is the number of minutes. Give one variant per plural form of your language, separated by |
+		return plural(&t("%d min|%d min"), minutes);
+	}
+	// TRANSLATORS: Status bar reading time left; the first %d is hours, the second minutes
+	format_message(&t("%d h %d min"), &[("%d", &hours.to_string()), ("%d", &minutes.to_string())])
+}
+
+/// Numbers headings the way a textbook outline would, e.g. `[3, 2, 1]` for "3.2.1", given their
+/// levels in document order.
+///
+/// A heading that skips levels (an H3 straight after an H1) becomes a direct child of the nearest
+/// shallower heading, so the numbering never has gaps or zero components.
+#[must_use]
+pub fn number_headings(levels: &[i32]) -> Vec<Vec<u32>> {
+	// (level, number) for each open heading, outermost first.
+	let mut open: Vec<(i32, u32)> = Vec::new();
+	levels
+		.iter()
+		.map(|&level| {
+			let mut previous_sibling = 0;
+			while open.last().is_some_and(|&(open_level, _)| open_level > level) {
+				previous_sibling = open.pop().map_or(0, |(_, number)| number);
+			}
+			match open.last_mut() {
+				Some((open_level, number)) if *open_level == level => *number += 1,
+				_ => open.push((level, previous_sibling + 1)),
+			}
+			open.iter().map(|&(_, number)| number).collect()
+		})
+		.collect()
+}
+
+/// Parses a dotted section number such as "3.2.1" (a trailing dot is allowed).
+#[must_use]
+pub fn parse_section_number(input: &str) -> Option<Vec<u32>> {
+	let input = input.trim().trim_end_matches('.');
+	if input.is_empty() {
+		return None;
+	}
+	input.split('.').map(|part| part.trim().parse::<u32>().ok().filter(|&n| n > 0)).collect()
+}
+
+#[must_use]
+pub fn format_section_number(number: &[u32]) -> String {
+	number.iter().map(ToString::to_string).collect::<Vec<_>>().join(".")
+}
+
+/// Renders a document's bookmarks and notes as Markdown, in reading order.
+///
+/// Each bookmark becomes a section headed by the nearest preceding heading, with the bookmarked text as a
+/// blockquote and its note (if any) below. Positions are display offsets into `content`; whole-line bookmarks
+/// (`start == end`) quote the line they sit on.
+#[must_use]
+pub fn notes_to_markdown(
+	title: &str,
+	author: &str,
+	content: &str,
+	markers: &[Marker],
+	bookmarks: &[Bookmark],
+	include_bookmarks_without_notes: bool,
+) -> String {
+	let line_at = |pos: usize| -> String {
+		let (start, end) = line_bounds(content, display_to_byte_index(content, pos));
+		content[start..end].to_string()
+	};
+	let mut headings: Vec<&Marker> = markers.iter().filter(|marker| is_heading_marker(marker.mtype)).collect();
+	headings.sort_by_key(|marker| marker.position);
+	let mut bookmarks: Vec<&Bookmark> =
+		bookmarks.iter().filter(|bm| include_bookmarks_without_notes || !bm.note.trim().is_empty()).collect();
+	bookmarks.sort_by_key(|bm| (bm.start, bm.end));
+	let mut md = String::new();
+	match (title.trim(), author.trim()) {
+		("", "") => {}
+		(title, "") => md.push_str(&format!("# {title}\n\n")),
+		("", author) => md.push_str(&format!("# {author}\n\n")),
+		(title, author) => md.push_str(&format!("# {title} \u{2014} {author}\n\n")),
+	}
+	for (index, bookmark) in bookmarks.into_iter().enumerate() {
+		let start = usize::try_from(bookmark.start.max(0)).unwrap_or(0);
+		let end = usize::try_from(bookmark.end.max(0)).unwrap_or(0);
+		let heading = headings
+			.iter()
+			.rev()
+			.find(|marker| marker.position <= start)
+			.map(|marker| if marker.text.trim().is_empty() { line_at(marker.position) } else { marker.text.clone() })
+			.map(|text| collapse_whitespace(&text).trim().to_string())
+			.filter(|text| !text.is_empty())
+			// TRANSLATORS: Section title in exported notes for a bookmark with no heading before it; %d is its number
+			.unwrap_or_else(|| t("Bookmark %d").replacen("%d", &(index + 1).to_string(), 1));
+		let (start_byte, end_byte) = (display_to_byte_index(content, start), display_to_byte_index(content, end));
+		let snippet = if end_byte > start_byte { content[start_byte..end_byte].to_string() } else { line_at(start) };
+		md.push_str(&format!("## {heading}\n\n"));
+		for line in snippet.trim().lines() {
+			let line = line.trim_end();
+			if line.is_empty() {
+				md.push_str(">\n");
+			} else {
+				md.push_str(&format!("> {line}\n"));
+			}
+		}
+		let note = bookmark.note.trim();
+		if !note.is_empty() {
+			md.push('\n');
+			md.push_str(note);
+			md.push('\n');
+		}
+		md.push('\n');
+	}
+	md
+}
+
+/// One entry of a document outline, see [`outline_items`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineItem {
+	pub title: String,
+	/// Nesting depth, 0 for top-level entries.
+	pub depth: usize,
+	/// Display offset the entry points to.
+	pub position: usize,
+}
+
+/// What goes into an exported outline, see [`outline_text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutlineOptions {
+	/// Write a nested Markdown list under a title heading instead of indented plain text.
+	pub markdown: bool,
+	/// Follow each entry with how far through the document it is.
+	pub include_percent: bool,
+	/// Follow each entry with the page it's on, for documents that have pages.
+	pub include_page: bool,
+}
+
+/// A document's outline in reading order: its table of contents, or the tree its headings form when
+/// it has none.
+#[must_use]
+pub fn outline_items(toc_items: &[TocItem], buffer: &DocumentBuffer) -> Vec<OutlineItem> {
+	fn flatten(items: &[TocItem], depth: usize, flat: &mut Vec<OutlineItem>) {
+		for item in items {
+			flat.push(OutlineItem { title: item.name.clone(), depth, position: item.offset });
+			flatten(&item.children, depth + 1, flat);
+		}
+	}
+	let mut flat = Vec::new();
+	if toc_items.is_empty() {
+		flatten(&build_toc_from_buffer(buffer), 0, &mut flat);
+	} else {
+		flatten(toc_items, 0, &mut flat);
+	}
+	flat
+}
+
+/// Renders an outline under the document title, indented two spaces per level, as plain text or a
+/// Markdown list. Entries without a name are called "Untitled". `locate` gives the percentage and
+/// page (`None` without pages) of a position, for the details `options` asks for.
+#[must_use]
+pub fn outline_text(
+	title: &str,
+	items: &[OutlineItem],
+	options: OutlineOptions,
+	locate: impl Fn(usize) -> (i32, Option<String>),
+) -> String {
+	let mut text = String::new();
+	let title = title.trim();
+	if !title.is_empty() {
+		text.push_str(&if options.markdown { format!("# {title}\n\n") } else { format!("{title}\n\n") });
+	}
+	let bullet = if options.markdown { "- " } else { "" };
+	for item in items {
+		let name = collapse_whitespace(&item.title).trim().to_string();
+		// TRANSLATORS: Name used in an exported outline for a table of contents entry or heading that has no text
+		let name = if name.is_empty() { t("Untitled") } else { name };
+		let mut details = Vec::new();
+		if options.include_percent || options.include_page {
+			let (percent, page) = locate(item.position);
+			if options.include_percent {
+				details.push(format!("{percent}%"));
+			}
+			if options.include_page
+				&& let Some(page) = page
+			{
+				// TRANSLATORS: Page an entry is on in an exported outline; %s is the page number or printed page label
+				details.push(t("page %s").replace("%s", &page));
+			}
+		}
+		text.push_str(&format!("{}{bullet}{name}", "  ".repeat(item.depth)));
+		if !details.is_empty() {
+			text.push_str(&format!(" ({})", details.join(", ")));
+		}
+		text.push('\n');
+	}
+	text
+}
+
+/// Characters of text kept in a [`PositionAnchor`].
+pub const POSITION_ANCHOR_CHARS: usize = 64;
+
+/// Records the text at display offset `position` and how far into the document it is.
+#[must_use]
+pub fn position_anchor(buffer: &DocumentBuffer, position: usize) -> PositionAnchor {
+	let total = buffer.char_count();
+	let start = buffer.char_index_for_display(position);
+	let end = (start + POSITION_ANCHOR_CHARS).min(total);
+	let snippet =
+		snippet_at(&buffer.content, buffer.byte_index_for_char(start), buffer.byte_index_for_char(end)).to_string();
+	#[allow(clippy::cast_precision_loss)]
+	let percent = if total == 0 { 0.0 } else { start as f64 * 100.0 / total as f64 };
+	PositionAnchor { snippet, percent }
+}
+
+/// Finds where a position saved at display offset `position` along with `anchor` is in the text as
+/// it is now. The saved offset is kept when the anchor's text is still there; otherwise the closest
+/// copy of the text within 2% of the document either side is used. When the text can't be found the
+/// saved percentage is, and the second value is `true` to say the spot is only approximate.
+#[must_use]
+pub fn resolve_position_anchor(buffer: &DocumentBuffer, position: usize, anchor: &PositionAnchor) -> (usize, bool) {
+	let total = buffer.char_count();
+	let expected = buffer.char_index_for_display(position);
+	let content = &buffer.content;
+	if anchor.snippet.is_empty() || content[buffer.byte_index_for_char(expected)..].starts_with(&anchor.snippet) {
+		return (buffer.display_index_for_char(expected), false);
+	}
+	let window = (total / 50).max(POSITION_ANCHOR_CHARS);
+	let window_start = buffer.byte_index_for_char(expected.saturating_sub(window));
+	let window_end = buffer.byte_index_for_char(expected + window + anchor.snippet.chars().count());
+	let found = content[window_start..window_end]
+		.match_indices(anchor.snippet.as_str())
+		.map(|(offset, _)| buffer.char_index_for_byte(window_start + offset))
+		.min_by_key(|&index| index.abs_diff(expected));
+	if let Some(index) = found {
+		return (buffer.display_index_for_char(index), false);
+	}
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+	let index = (anchor.percent.clamp(0.0, 100.0) * total as f64 / 100.0).round() as usize;
+	(buffer.display_index_for_char(index), true)
+}
+
+/// Layout of the plain-text export for braille embossing, see [`braille_text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrailleOptions {
+	/// Cells per line. Lines are wrapped between words to fit; 0 turns wrapping off.
+	pub line_width: usize,
+	/// Put before headings below level 1, e.g. "== ".
+	pub heading_prefix: String,
+	/// End lines with this system's line ending (CRLF on Windows) rather than `\n`.
+	pub platform_newlines: bool,
+}
+
+impl Default for BrailleOptions {
+	fn default() -> Self {
+		Self { line_width: 38, heading_prefix: "== ".to_string(), platform_newlines: false }
+	}
+}
+
+/// Renders `content` as plain text laid out the way braille translation software expects.
+///
+/// Level 1 headings become capitalized lines set off by blank lines, lower headings get
+/// `options.heading_prefix`, and each page break becomes a "[page N]" line (N counting page
+/// markers from 1) before the line it falls on. Every line is wrapped at `options.line_width`
+/// cells without splitting words, and ends in `\n` unless `options.platform_newlines` asks for
+/// this system's line ending. Only the exported copy changes, so offsets are unaffected.
+#[must_use]
+pub fn braille_text(content: &str, markers: &[Marker], options: &BrailleOptions) -> String {
+	let mut page_breaks: Vec<usize> =
+		markers.iter().filter(|marker| marker.mtype == MarkerType::PageBreak).map(|marker| marker.position).collect();
+	page_breaks.sort_unstable();
+	let mut headings: Vec<(usize, i32)> = markers
+		.iter()
+		.filter(|marker| is_heading_marker(marker.mtype))
+		.map(|marker| (marker.position, marker.level))
+		.collect();
+	headings.sort_by_key(|&(position, _)| position);
+	let mut lines: Vec<String> = Vec::new();
+	let push_blank = |lines: &mut Vec<String>| {
+		if lines.last().is_some_and(|line| !line.is_empty()) {
+			lines.push(String::new());
+		}
+	};
+	let (mut page_index, mut heading_index, mut line_start) = (0, 0, 0);
+	for line in content.split('\n') {
+		let line_end = line_start + display_len(line);
+		while page_breaks.get(page_index).is_some_and(|&position| position <= line_end) {
+			page_index += 1;
+			lines.push(format!("[page {page_index}]"));
+		}
+		let mut level = None;
+		while let Some(&(position, heading_level)) = headings.get(heading_index)
+			&& position <= line_end
+		{
+			level = level.or(Some(heading_level));
+			heading_index += 1;
+		}
+		let text = collapse_whitespace(line);
+		let text = text.trim();
+		match level {
+			_ if text.is_empty() => push_blank(&mut lines),
+			Some(level) if level <= 1 => {
+				push_blank(&mut lines);
+				lines.extend(wrap_words(&text.to_uppercase(), options.line_width));
+				lines.push(String::new());
+			}
+			Some(_) => lines.extend(wrap_words(&format!("{}{text}", options.heading_prefix), options.line_width)),
+			None => lines.extend(wrap_words(text, options.line_width)),
+		}
+		line_start = line_end + 1;
+	}
+	while page_index < page_breaks.len() {
+		page_index += 1;
+		lines.push(format!("[page {page_index}]"));
+	}
+	let newline = if options.platform_newlines { PLATFORM_NEWLINE } else { "\n" };
+	let mut text = lines.join(newline);
+	text.truncate(text.trim_end().len());
+	text.push_str(newline);
+	text
+}
+
+/// Greedily fills lines of at most `width` characters, breaking only at spaces. A word longer
+/// than `width` gets a line of its own rather than being split.
+#[must_use]
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+	if width == 0 {
+		return vec![text.to_string()];
+	}
+	let mut lines = Vec::new();
+	let mut current = String::new();
+	let mut current_len = 0;
+	for word in text.split_whitespace() {
+		let word_len = word.chars().count();
+		if current_len > 0 && current_len + 1 + word_len > width {
+			lines.push(std::mem::take(&mut current));
+			current_len = 0;
+		}
+		if current_len > 0 {
+			current.push(' ');
+			current_len += 1;
+		}
+		current.push_str(word);
+		current_len += word_len;
+	}
+	if !current.is_empty() {
+		lines.push(current);
+	}
+	lines
+}
+
 fn normalize_index(positions: &[i64], index: usize) -> usize {
 	if positions.is_empty() {
 		return 0;
@@ -421,6 +1498,36 @@ pub fn history_go_next(history: &[i64], history_index: usize, current_pos: i64,
 	HistoryNavResult { found: false, target: -1, positions, index }
 }
 
+/// Jumps to `history[entry]` without discarding anything: the current position and then the
+/// target are appended, and the target becomes the current entry, so Back returns to where the
+/// jump started.
+#[must_use]
+pub fn history_go_to(
+	history: &[i64],
+	history_index: usize,
+	entry: usize,
+	current_pos: i64,
+	max_len: usize,
+) -> HistoryNavResult {
+	let Some(&target) = history.get(entry) else {
+		return HistoryNavResult {
+			found: false,
+			target: -1,
+			positions: history.to_vec(),
+			index: normalize_index(history, history_index),
+		};
+	};
+	let mut positions = history.to_vec();
+	for position in [current_pos, target] {
+		if positions.last() != Some(&position) {
+			positions.push(position);
+		}
+	}
+	let mut index = positions.len() - 1;
+	trim_history(&mut positions, &mut index, max_len);
+	HistoryNavResult { found: true, target, positions, index }
+}
+
 #[derive(Debug, Clone)]
 pub struct LinkNavigation {
 	pub found: bool,
@@ -486,6 +1593,26 @@ pub fn nearest_fragment_before(doc: &DocumentHandle, position: usize) -> Option<
 	)
 }
 
+/// Maps the URL a web view ended up on back to a text offset through its `#fragment`.
+///
+/// The URL's file name picks the section whose scoped ids are searched first (the web view
+/// shows a temp copy of the section, so only the file name survives); otherwise the current
+/// section is used. Returns `None` when there is no fragment or it doesn't resolve.
+#[must_use]
+pub fn resolve_webview_url(doc: &DocumentHandle, url: &str, current_position: usize) -> Option<usize> {
+	let (base, fragment) = url.split_once('#')?;
+	let fragment = url_decode(fragment);
+	let file_name = base.rsplit(['/', '\\']).next().unwrap_or_default();
+	let section_path = doc
+		.document()
+		.manifest_items
+		.values()
+		.find(|path| !file_name.is_empty() && path.rsplit('/').next() == Some(file_name))
+		.cloned()
+		.or_else(|| current_section_path(doc, current_position));
+	find_fragment_offset(doc, &fragment, section_path.as_deref())
+}
+
 fn find_manifest_id_for_path(doc: &DocumentHandle, path: &str) -> Option<String> {
 	doc.document().manifest_items.iter().find_map(|(id, p)| if p == path { Some(id.clone()) } else { None })
 }
@@ -557,10 +1684,42 @@ pub fn resolve_link(doc: &DocumentHandle, href: &str, current_position: i64) ->
 	LinkNavigation { found: false, is_external: false, offset: 0, url: String::new() }
 }
 
+/// Most characters shown when previewing where an internal link leads.
+pub const LINK_PREVIEW_MAX_CHARS: usize = 1000;
+
+/// The text an internal link to `offset` leads to, for previewing it without moving the caret: the
+/// whole footnote when the target lies in one, otherwise the paragraph from `offset` to the next
+/// blank line or heading. Longer text is cut at a word boundary.
+#[must_use]
+pub fn link_preview_text(doc: &DocumentHandle, offset: usize) -> String {
+	let buffer = &doc.document().buffer;
+	let note = buffer.markers.iter().find(|marker| {
+		marker.mtype == MarkerType::Note && marker.position <= offset && offset < marker.position + marker.length
+	});
+	let (start, end) = note.map_or_else(
+		|| {
+			let next_heading = doc
+				.next_heading_marker_index(i64::try_from(offset).unwrap_or(i64::MAX), None)
+				.and_then(|index| buffer.markers.get(index))
+				.map_or(usize::MAX, |marker| marker.position);
+			(offset, next_heading)
+		},
+		|note| (note.position, note.position + note.length),
+	);
+	let start_byte = display_to_byte_index(&buffer.content, start);
+	let end_byte = display_to_byte_index(&buffer.content, end).max(start_byte);
+	let lines = buffer.content[start_byte..end_byte].lines().skip_while(|line| line.trim().is_empty());
+	let text: Vec<&str> =
+		if note.is_some() { lines.collect() } else { lines.take_while(|line| !line.trim().is_empty()).collect() };
+	ellipsize_words(&text.join("\n"), LINK_PREVIEW_MAX_CHARS)
+}
+
 #[cfg(test)]
 mod tests {
 	use std::collections::HashMap;
 
+	use rstest::rstest;
+
 	use super::*;
 	use crate::document::{Document, DocumentBuffer, DocumentHandle, Marker, MarkerType};
 
@@ -590,6 +1749,274 @@ mod tests {
 		assert_eq!(table_end.offset, 15); // also lands on "BBBB", the line after the table
 	}
 
+	#[test]
+	fn format_location_omits_parts_that_do_not_apply() {
+		assert_eq!(
+			format_location("Chapter 4 \u{2014} The Ontology of Touch", Some((37, 112)), None, 33, 1204),
+			"Chapter 4 \u{2014} The Ontology of Touch, page 37 of 112, 33 percent, line 1204"
+		);
+		assert_eq!(format_location("", None, None, 0, 1), "0 percent, line 1");
+		assert_eq!(format_location("  Intro\n", Some((0, 12)), None, 5, 3), "Intro, 5 percent, line 3");
+		assert_eq!(
+			format_location("Preface", Some((11, 300)), Some("xi"), 2, 40),
+			"Preface, page xi (11 of 300), 2 percent, line 40"
+		);
+	}
+
+	fn status_fixture() -> StatusFields {
+		StatusFields {
+			line: 12,
+			character: 340,
+			percent: 27,
+			page: Some((4, 120)),
+			page_label: None,
+			heading: "2 Methods > 2.1 Participants".to_string(),
+			words_left: Some(9_000),
+			reading_words_left: Some(9_000),
+			title: "Field Notes".to_string(),
+		}
+	}
+
+	#[test]
+	fn format_status_fills_every_placeholder() {
+		let template = "{title}: Line {line}, Character {char}, Page {page} of {pages}, {percent}% | {heading} | {words_left} words, {time_left} left";
+		assert_eq!(
+			format_status(template, &status_fixture(), 150),
+			"Field Notes: Line 12, Character 340, Page 4 of 120, 27% | 2 Methods > 2.1 Participants | 9000 words, 1 h 0 min left"
+		);
+		let fields = StatusFields { words_left: Some(250), reading_words_left: Some(250), ..status_fixture() };
+		assert_eq!(format_status("{time_left}", &fields, 150), "2 min");
+		let fields = StatusFields { reading_words_left: Some(4_500), ..status_fixture() };
+		assert_eq!(format_status("{words_left} words, {time_left} left", &fields, 150), "9000 words, 30 min left");
+		let fields = StatusFields { page_label: Some("iv".to_string()), ..status_fixture() };
+		assert_eq!(format_status("Page {page} of {pages}", &fields, 150), "Page iv of 120");
+	}
+
+	#[test]
+	fn format_status_drops_unavailable_parts_with_their_separators() {
+		let fields = StatusFields { page: None, heading: String::new(), ..status_fixture() };
+		assert_eq!(format_status("Line {line}, Page {page} of {pages}, {percent}%", &fields, 150), "Line 12, 27%");
+		assert_eq!(format_status("{heading} | Page {page} | {percent}%", &fields, 150), "27%");
+		assert_eq!(format_status("{percent}% | {heading}", &fields, 150), "27%");
+		assert_eq!(format_status("{words_left} words; {time_left} left", &fields, 0), "9000 words");
+		assert_eq!(format_status("{page} | {heading}", &fields, 150), "");
+	}
+
+	#[test]
+	fn format_status_handles_empty_documents_and_odd_templates() {
+		let empty = StatusFields { line: 1, character: 1, ..StatusFields::default() };
+		assert_eq!(format_status(&default_status_format(), &empty, 150), "Line 1, Character 1, Reading 0%");
+		assert_eq!(format_status("{words_left} | {time_left} | {title}", &empty, 150), "");
+		assert_eq!(format_status("", &empty, 150), "");
+		assert_eq!(format_status("{bogus} {line}", &empty, 150), "{bogus} 1");
+		assert_eq!(format_status("Line {line", &empty, 150), "Line {line");
+		assert_eq!(format_status(" , {line},, ", &empty, 150), "1");
+	}
+
+	#[test]
+	fn number_headings_nests_skipped_levels_under_the_nearest_heading() {
+		let numbers = number_headings(&[1, 2, 3, 3, 1, 3, 2, 1]);
+		let formatted: Vec<String> = numbers.iter().map(Vec::as_slice).map(format_section_number).collect();
+		assert_eq!(formatted, ["1", "1.1", "1.1.1", "1.1.2", "2", "2.1", "2.2", "3"]);
+	}
+
+	#[test]
+	fn parse_section_number_accepts_dotted_numbers_only() {
+		assert_eq!(parse_section_number(" 3.2.1 "), Some(vec![3, 2, 1]));
+		assert_eq!(parse_section_number("4."), Some(vec![4]));
+		assert_eq!(parse_section_number("3..1"), None);
+		assert_eq!(parse_section_number("0.1"), None);
+		assert_eq!(parse_section_number("three"), None);
+		assert_eq!(parse_section_number(""), None);
+	}
+
+	#[test]
+	fn wrap_words_never_splits_a_word() {
+		assert_eq!(wrap_words("the quick brown fox", 9), ["the quick", "brown fox"]);
+		assert_eq!(wrap_words("a extraordinarily b", 5), ["a", "extraordinarily", "b"]);
+		assert_eq!(wrap_words("no wrap", 0), ["no wrap"]);
+	}
+
+	#[test]
+	fn braille_text_formats_headings_pages_and_wraps_lines() {
+		let content =
+			"Chapter One\nIt was a dark and stormy night and the rain fell.\nThe Storm\nLightning.\nMore text";
+		let markers = vec![
+			Marker::new(MarkerType::Heading1, 0).with_level(1).with_text("Chapter One".to_string()),
+			Marker::new(MarkerType::PageBreak, 0),
+			Marker::new(MarkerType::Heading2, 62).with_level(2),
+			Marker::new(MarkerType::PageBreak, 72),
+		];
+		let options = BrailleOptions { line_width: 20, ..BrailleOptions::default() };
+		let expected = "[page 1]\n\nCHAPTER ONE\n\nIt was a dark and\nstormy night and the\nrain fell.\n== The Storm\n[page 2]\nLightning.\nMore text\n";
+		assert_eq!(braille_text(content, &markers, &options), expected);
+		let options = BrailleOptions { platform_newlines: true, ..options };
+		assert_eq!(braille_text(content, &markers, &options), expected.replace('\n', PLATFORM_NEWLINE));
+	}
+
+	#[test]
+	fn copied_bookmarks_put_each_note_before_its_text() {
+		let entry = |note: &str, snippet: &str| ffi::BookmarkDisplayEntry {
+			start: 0,
+			end: 0,
+			note: note.to_string(),
+			snippet: snippet.to_string(),
+		};
+		let bookmarks = [entry("", "Call me Ishmael."), entry("Opening", "It was the best of times")];
+		assert_eq!(
+			bookmarks_clipboard_text(&bookmarks),
+			["Call me Ishmael.", "Opening - It was the best of times"].join(PLATFORM_NEWLINE)
+		);
+		assert_eq!(bookmarks_clipboard_text(&[]), "");
+	}
+
+	#[test]
+	fn notes_to_markdown_groups_bookmarks_under_nearest_heading() {
+		let content = "Intro line\nChapter One\nIt was a dark night.\nSecond para.\n";
+		let markers = vec![Marker::new(MarkerType::Heading1, 11).with_text("Chapter One".to_string())];
+		let bookmarks = vec![
+			Bookmark { start: 44, end: 44, ..Bookmark::default() },
+			Bookmark { start: 32, end: 42, note: "Classic opener.".to_string(), ..Bookmark::default() },
+			Bookmark { start: 0, end: 0, note: "Before any heading".to_string(), ..Bookmark::default() },
+		];
+		let md = notes_to_markdown("Story", "Ann Author", content, &markers, &bookmarks, true);
+		assert_eq!(
+			md,
+			concat!(
+				"# Story \u{2014} Ann Author\n\n",
+				"## Bookmark 1\n\n> Intro line\n\nBefore any heading\n\n",
+				"## Chapter One\n\n> dark night\n\nClassic opener.\n\n",
+				"## Chapter One\n\n> Second para.\n\n",
+			)
+		);
+		let notes_only = notes_to_markdown("Story", "", content, &markers, &bookmarks, false);
+		assert!(notes_only.starts_with("# Story\n\n"));
+		assert!(!notes_only.contains("Second para."));
+		assert_eq!(notes_only.matches("## ").count(), 2);
+	}
+
+	fn toc(name: &str, offset: usize, children: Vec<TocItem>) -> TocItem {
+		TocItem { children, ..TocItem::new(name.to_string(), String::new(), offset) }
+	}
+
+	#[test]
+	fn outline_items_flatten_deeply_nested_toc_in_reading_order() {
+		let items = vec![
+			toc("Part I", 0, vec![toc("Chapter 1", 10, vec![toc("1.1", 20, vec![toc("1.1.1", 30, vec![])])])]),
+			toc("Part II", 40, vec![]),
+		];
+		let outline = outline_items(&items, &DocumentBuffer::new());
+		let flat: Vec<(&str, usize, usize)> =
+			outline.iter().map(|item| (item.title.as_str(), item.depth, item.position)).collect();
+		assert_eq!(
+			flat,
+			vec![("Part I", 0, 0), ("Chapter 1", 1, 10), ("1.1", 2, 20), ("1.1.1", 3, 30), ("Part II", 0, 40)]
+		);
+	}
+
+	#[test]
+	fn outline_items_fall_back_to_headings_without_toc() {
+		let mut buffer = DocumentBuffer::with_content("x".repeat(50));
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 0).with_text("One".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Link, 5).with_text("link".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Heading3, 10).with_text("Deep".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Heading2, 20).with_text("Middle".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 30).with_text("Two".to_string()));
+		let outline = outline_items(&[], &buffer);
+		let flat: Vec<(&str, usize)> = outline.iter().map(|item| (item.title.as_str(), item.depth)).collect();
+		assert_eq!(flat, vec![("One", 0), ("Deep", 1), ("Middle", 1), ("Two", 0)]);
+		assert!(outline_items(&[], &DocumentBuffer::with_content("plain".to_string())).is_empty());
+	}
+
+	#[test]
+	fn outline_text_renders_markdown_and_plain_text() {
+		let items = vec![
+			OutlineItem { title: "Chapter  One".to_string(), depth: 0, position: 0 },
+			OutlineItem { title: " ".to_string(), depth: 1, position: 50 },
+		];
+		let locate = |position: usize| (i32::try_from(position).unwrap(), (position > 0).then(|| "xi".to_string()));
+		let markdown = OutlineOptions { markdown: true, include_percent: true, include_page: true };
+		assert_eq!(
+			outline_text("Book", &items, markdown, locate),
+			"# Book\n\n- Chapter One (0%)\n  - Untitled (50%, page xi)\n"
+		);
+		assert_eq!(outline_text("", &items, OutlineOptions::default(), locate), "Chapter One\n  Untitled\n");
+	}
+
+	fn text_handle(content: &str) -> DocumentHandle {
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content(content.to_string()));
+		DocumentHandle::new(doc)
+	}
+
+	#[test]
+	fn reader_sentence_at_returns_the_surrounding_sentence() {
+		let handle = text_handle("Mr. Smith arrived. He sat down.\n\nIt was late.");
+		assert_eq!(reader_sentence_at(&handle, 0), Some((0, 18, "Mr. Smith arrived.".to_string())));
+		assert_eq!(reader_sentence_at(&handle, 24), Some((19, 31, "He sat down.".to_string())));
+		// From a blank line it moves on to the next sentence.
+		assert_eq!(reader_sentence_at(&handle, 32), Some((33, 45, "It was late.".to_string())));
+		assert_eq!(reader_sentence_at(&text_handle(""), 0), None);
+	}
+
+	#[test]
+	fn reader_sentence_navigate_moves_across_sentences_and_lines() {
+		let handle = text_handle("Mr. Smith arrived. He sat down.\nIt was 3.5 hours later.");
+		let next = reader_sentence_navigate(&handle, 0, true);
+		assert!(next.found);
+		assert_eq!(next.offset, 19);
+		assert_eq!(next.marker_text, "He sat down.");
+		let next_line = reader_sentence_navigate(&handle, 19, true);
+		assert_eq!(next_line.offset, 32);
+		assert_eq!(next_line.marker_text, "It was 3.5 hours later.");
+		assert!(!reader_sentence_navigate(&handle, 32, true).found);
+		// Backwards from inside a sentence lands on its start, then on the previous one.
+		assert_eq!(reader_sentence_navigate(&handle, 40, false).offset, 32);
+		assert_eq!(reader_sentence_navigate(&handle, 32, false).offset, 19);
+		assert_eq!(reader_sentence_navigate(&handle, 19, false).offset, 0);
+		assert!(!reader_sentence_navigate(&handle, 0, false).found);
+	}
+
+	#[test]
+	fn reader_sentence_navigate_keeps_hard_wrapped_sentences_whole() {
+		let handle = text_handle("The rain went on\nall night. Nobody slept.\n\nMorning came.");
+		let next = reader_sentence_navigate(&handle, 0, true);
+		assert_eq!((next.offset, next.marker_text.as_str()), (28, "Nobody slept."));
+		let back = reader_sentence_navigate(&handle, 28, false);
+		assert_eq!((back.offset, back.marker_text.as_str()), (0, "The rain went on all night."));
+		assert_eq!(reader_sentence_navigate(&handle, 28, true).offset, 43);
+		assert_eq!(reader_sentence_navigate(&handle, 43, false).offset, 28);
+		assert_eq!(reader_sentence_at(&handle, 20), Some((0, 27, "The rain went on all night.".to_string())));
+	}
+
+	#[test]
+	fn reader_paragraph_navigate_uses_lines_for_structured_documents() {
+		let mut buffer = DocumentBuffer::with_content("Title\nFirst.\n\nSecond.".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 0));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let handle = DocumentHandle::new(doc);
+		let next = reader_paragraph_navigate(&handle, 0, true);
+		assert_eq!((next.offset, next.marker_text.as_str()), (6, "First."));
+		let skip_blank = reader_paragraph_navigate(&handle, 6, true);
+		assert_eq!((skip_blank.offset, skip_blank.marker_text.as_str()), (14, "Second."));
+		assert_eq!(reader_paragraph_navigate(&handle, 14, false).offset, 6);
+	}
+
+	#[test]
+	fn reader_paragraph_navigate_groups_wrapped_plain_text() {
+		let handle = text_handle("One line\nwrapped here.\n\nTwo starts\nand ends.");
+		let next = reader_paragraph_navigate(&handle, 3, true);
+		assert!(next.found);
+		assert_eq!(next.offset, 24);
+		assert_eq!(next.marker_text, "Two starts and ends.");
+		assert!(!reader_paragraph_navigate(&handle, 24, true).found);
+		let previous = reader_paragraph_navigate(&handle, 40, false);
+		assert_eq!(previous.offset, 24);
+		let first = reader_paragraph_navigate(&handle, 24, false);
+		assert_eq!((first.offset, first.marker_text.as_str()), (0, "One line wrapped here."));
+	}
+
 	#[test]
 	fn reader_container_navigate_not_in_container() {
 		let mut buffer = DocumentBuffer::with_content("x".repeat(120));
@@ -614,7 +2041,29 @@ mod tests {
 	fn reader_search_handles_utf16_offsets() {
 		let haystack = "a😀b";
 		let options = SearchOptions::FORWARD;
-		assert_eq!(reader_search(haystack, "b", 0, options), 3);
+		assert_eq!(reader_search(haystack, "b", 0, options), i64::try_from(display_len("a😀")).unwrap());
+	}
+
+	#[rstest]
+	#[case("x 😀 caf\u{e9} \u{4e2d}\u{6587} e\u{301}t\u{e9} 😀end", "\u{4e2d}\u{6587}")]
+	#[case("x 😀 caf\u{e9} \u{4e2d}\u{6587} e\u{301}t\u{e9} 😀end", "e\u{301}t\u{e9}")]
+	#[case("x 😀 caf\u{e9} \u{4e2d}\u{6587} e\u{301}t\u{e9} 😀end", "😀end")]
+	fn reader_search_match_spans_exactly_the_query(#[case] haystack: &str, #[case] query: &str) {
+		let (position, length) = reader_search_match(haystack, query, 1, SearchOptions::FORWARD).unwrap();
+		let start = display_to_byte_index(haystack, usize::try_from(position).unwrap());
+		let end = display_to_byte_index(haystack, usize::try_from(position + length).unwrap());
+		assert_eq!(&haystack[start..end], query);
+		let (back, _) = reader_search_match(haystack, query, position + length, SearchOptions::empty()).unwrap();
+		assert_eq!(back, position);
+	}
+
+	#[test]
+	fn reader_search_match_reports_the_matched_length_for_regex() {
+		let haystack = "😀 abc123 def";
+		let (position, length) =
+			reader_search_match(haystack, "[a-z]+[0-9]+", 0, SearchOptions::FORWARD | SearchOptions::REGEX).unwrap();
+		assert_eq!(position, i64::try_from(display_len("😀 ")).unwrap());
+		assert_eq!(length, 6);
 	}
 
 	#[test]
@@ -631,7 +2080,7 @@ mod tests {
 	fn reader_search_with_wrap_wraps_forward() {
 		let haystack = "abc";
 		let options = SearchOptions::FORWARD;
-		let result = reader_search_with_wrap(haystack, "a", 1, options);
+		let result = reader_search_with_wrap(haystack, "a", 1, options).unwrap();
 		assert!(result.found);
 		assert!(result.wrapped);
 		assert_eq!(result.position, 0);
@@ -682,6 +2131,63 @@ mod tests {
 		assert_eq!(reader_search(haystack, "(", 0, options), -1);
 	}
 
+	#[rstest]
+	#[case("a*")]
+	#[case("(foo)?")]
+	#[case("^")]
+	fn reader_search_rejects_patterns_that_match_empty_text(#[case] pattern: &str) {
+		let options = SearchOptions::FORWARD | SearchOptions::REGEX;
+		assert_eq!(
+			reader_search_with_wrap("aaa foo", pattern, 0, options).unwrap_err(),
+			SearchPatternError::EmptyMatch
+		);
+	}
+
+	#[test]
+	fn reader_search_skips_zero_length_matches_mid_text() {
+		let options = SearchOptions::FORWARD | SearchOptions::REGEX;
+		// `\b` matches empty text at every word edge; only "ab" may be selected.
+		assert_eq!(reader_search_match("xb ab", r"ab|\b", 0, options), Some((3, 2)));
+		assert_eq!(reader_search_match("ab xb", r"ab|\b", 5, SearchOptions::REGEX), Some((0, 2)));
+	}
+
+	#[test]
+	fn reader_search_reports_syntax_error_position() {
+		let options = SearchOptions::FORWARD | SearchOptions::REGEX;
+		let Err(SearchPatternError::Syntax { position, message }) = reader_search_with_wrap("abc", "ab[c", 0, options)
+		else {
+			panic!("expected a syntax error");
+		};
+		assert_eq!(position, Some(2));
+		assert!(!message.is_empty());
+		let whole_word = options | SearchOptions::WHOLE_WORD;
+		let Err(SearchPatternError::Syntax { position, .. }) = reader_search_with_wrap("abc", "ab[c", 0, whole_word)
+		else {
+			panic!("expected a syntax error");
+		};
+		assert_eq!(position, Some(2));
+	}
+
+	#[test]
+	fn reader_search_inline_case_flags_override_match_case() {
+		let regex = SearchOptions::FORWARD | SearchOptions::REGEX;
+		assert_eq!(reader_search("Hello hello", "(?i)HELLO", 0, regex | SearchOptions::MATCH_CASE), 0);
+		assert_eq!(reader_search("Hello hello", "(?-i)hello", 0, regex), 6);
+		assert_eq!(reader_search("Hello hello", "HELLO", 0, regex | SearchOptions::MATCH_CASE), -1);
+	}
+
+	#[test]
+	fn reader_search_handles_pathological_patterns() {
+		let haystack = "a".repeat(10_000);
+		let options = SearchOptions::FORWARD | SearchOptions::REGEX;
+		let result = reader_search_with_wrap(&haystack, "(a*)*b", 0, options).unwrap();
+		assert!(!result.found);
+		assert_eq!(
+			reader_search_with_wrap(&haystack, "a{1000}{1000}", 0, options).unwrap_err(),
+			SearchPatternError::TooComplex
+		);
+	}
+
 	#[test]
 	fn reader_search_whole_word_positive_case() {
 		let haystack = "alpha beta gamma";
@@ -689,6 +2195,206 @@ mod tests {
 		assert_eq!(reader_search(haystack, "beta", 0, options), 6);
 	}
 
+	#[rstest]
+	#[case::french_ligature("L'œuvre d'art", "Œuvre", SearchOptions::empty(), 2)]
+	#[case::french_ligature_match_case("L'œuvre d'art", "Œuvre", SearchOptions::MATCH_CASE, -1)]
+	#[case::french_elision("L'œuvre d'art, l'enfant", "enfant", SearchOptions::empty(), 17)]
+	#[case::french_elision_whole_word("L'œuvre d'art, l'enfant", "enfant", SearchOptions::WHOLE_WORD, -1)]
+	#[case::french_elided_word("L'œuvre d'art, l\u{2019}Enfant", "l\u{2019}enfant", SearchOptions::WHOLE_WORD, 15)]
+	#[case::french_accent_whole_word("cafe\u{301} cafe", "cafe", SearchOptions::WHOLE_WORD, 6)]
+	#[case::german_eszett("Die STRASSE", "straße", SearchOptions::empty(), 4)]
+	#[case::german_eszett_match_case("Die STRASSE", "straße", SearchOptions::MATCH_CASE, -1)]
+	#[case::german_whole_word("Straßenbahn und Straße", "straße", SearchOptions::WHOLE_WORD, 16)]
+	#[case::greek_final_sigma("ΛΟΓΟΣ και λογος", "λογος", SearchOptions::empty(), 0)]
+	#[case::greek_match_case("ΛΟΓΟΣ και λογος", "λογος", SearchOptions::MATCH_CASE, 10)]
+	#[case::greek_partial_word("λόγοι και λόγος", "λόγο", SearchOptions::empty(), 0)]
+	#[case::greek_partial_word_whole_word("λόγοι και λόγος", "λόγο", SearchOptions::WHOLE_WORD, -1)]
+	#[case::greek_whole_word("λόγοι και λόγος", "ΛΌΓΟΣ", SearchOptions::WHOLE_WORD, 10)]
+	#[case::cyrillic("Мировой мир", "мир", SearchOptions::empty(), 0)]
+	#[case::cyrillic_whole_word("Мировой мир", "МИР", SearchOptions::WHOLE_WORD, 8)]
+	#[case::cyrillic_match_case("Мировой мир", "Мир", SearchOptions::WHOLE_WORD | SearchOptions::MATCH_CASE, -1)]
+	fn reader_search_handles_non_ascii_scripts(
+		#[case] haystack: &str,
+		#[case] query: &str,
+		#[case] options: SearchOptions,
+		#[case] expected: i64,
+	) {
+		assert_eq!(reader_search(haystack, query, 0, options | SearchOptions::FORWARD), expected);
+	}
+
+	#[test]
+	fn reader_search_match_length_follows_the_matched_text() {
+		let haystack = "Die Straße, die STRASSE";
+		let options = SearchOptions::FORWARD | SearchOptions::WHOLE_WORD;
+		assert_eq!(reader_search_match(haystack, "strasse", 0, options), Some((4, 6)));
+		assert_eq!(reader_search_match(haystack, "straße", 5, options), Some((16, 7)));
+	}
+
+	#[test]
+	fn reader_search_whole_word_treats_inner_apostrophes_as_part_of_the_word() {
+		let options = SearchOptions::FORWARD | SearchOptions::WHOLE_WORD;
+		assert_eq!(reader_search("don't stop, don", "don", 0, options), 12);
+		assert_eq!(reader_search("the dogs' bowl", "dogs", 0, options), 4);
+		assert_eq!(reader_search("'quoted' text", "quoted", 0, options), 1);
+	}
+
+	#[test]
+	fn reader_search_all_lists_every_match_in_display_offsets() {
+		let haystack = "Cat \u{1F408} cat, concatenate CAT";
+		// The cat emoji takes two display units where the control counts UTF-16.
+		let shift = i64::try_from(display_len("\u{1F408}")).unwrap();
+		let (matches, truncated) = reader_search_all(haystack, "cat", SearchOptions::empty(), 100).unwrap();
+		assert_eq!(matches, vec![(0, 3), (5 + shift, 3), (13 + shift, 3), (22 + shift, 3)]);
+		assert!(!truncated);
+		let whole_word = SearchOptions::MATCH_CASE | SearchOptions::WHOLE_WORD;
+		assert_eq!(reader_search_all(haystack, "cat", whole_word, 100).unwrap(), (vec![(5 + shift, 3)], false));
+		let (first_two, truncated) = reader_search_all(haystack, "cat", SearchOptions::empty(), 2).unwrap();
+		assert_eq!((first_two, truncated), (vec![(0, 3), (5 + shift, 3)], true));
+		assert_eq!(reader_search_all(haystack, "", SearchOptions::empty(), 100).unwrap(), (Vec::new(), false));
+		assert_eq!(reader_search_all("aaaa", "aa", SearchOptions::empty(), 100).unwrap().0, vec![(0, 2), (2, 2)]);
+		assert!(reader_search_all(haystack, "a*", SearchOptions::REGEX, 100).is_err());
+	}
+
+	fn heading_search_fixture() -> (Vec<Marker>, Vec<TocItem>) {
+		let markers = vec![
+			Marker::new(MarkerType::Heading1, 0).with_text("The Voyage Out".to_string()).with_level(1),
+			Marker::new(MarkerType::Link, 20).with_text("voyage notes".to_string()),
+			Marker::new(MarkerType::Heading2, 40).with_text("Storms at sea".to_string()).with_level(2),
+			Marker::new(MarkerType::Heading2, 90).with_text("The Return Voyage".to_string()).with_level(2),
+		];
+		let mut part = TocItem::new("Part One: Departure".to_string(), String::new(), 0);
+		part.children.push(TocItem::new("A voyage begins".to_string(), String::new(), 60));
+		part.children.push(TocItem::new("The Return Voyage".to_string(), String::new(), 90));
+		(markers, vec![part])
+	}
+
+	#[test]
+	fn heading_search_cycles_through_matching_headings_and_toc_entries() {
+		let (markers, toc) = heading_search_fixture();
+		let search = |start, options| {
+			reader_search_headings(&markers, &toc, "voyage", start, options)
+				.unwrap()
+				.map(|found| (found.position, found.text, found.level, found.wrapped))
+		};
+		let forward = SearchOptions::FORWARD;
+		assert_eq!(search(0, forward), Some((60, "A voyage begins".to_string(), 2, false)));
+		assert_eq!(search(60, forward), Some((90, "The Return Voyage".to_string(), 2, false)));
+		assert_eq!(search(90, forward), Some((0, "The Voyage Out".to_string(), 1, true)));
+		assert_eq!(search(60, SearchOptions::empty()), Some((0, "The Voyage Out".to_string(), 1, false)));
+		assert_eq!(search(0, SearchOptions::empty()), Some((90, "The Return Voyage".to_string(), 2, true)));
+	}
+
+	#[rstest]
+	#[case("voyage", SearchOptions::MATCH_CASE, Some(60))]
+	#[case("sea", SearchOptions::WHOLE_WORD, Some(40))]
+	#[case("se", SearchOptions::WHOLE_WORD, None)]
+	#[case(r"^Part \w+:", SearchOptions::REGEX, Some(0))]
+	#[case("notes", SearchOptions::empty(), None)]
+	#[case("", SearchOptions::empty(), None)]
+	fn heading_search_applies_find_options(
+		#[case] needle: &str,
+		#[case] options: SearchOptions,
+		#[case] expected: Option<i64>,
+	) {
+		let (markers, toc) = heading_search_fixture();
+		let found = reader_search_headings(&markers, &toc, needle, 20, options | SearchOptions::FORWARD).unwrap();
+		assert_eq!(found.map(|found| found.position), expected);
+	}
+
+	#[test]
+	fn heading_search_rejects_invalid_patterns() {
+		let (markers, toc) = heading_search_fixture();
+		assert!(reader_search_headings(&markers, &toc, "(", 0, SearchOptions::REGEX).is_err());
+	}
+
+	/// A config in a fresh temporary directory, removed along with it when dropped.
+	struct NotesConfig {
+		config: RustConfigManager,
+		dir: std::path::PathBuf,
+	}
+
+	impl Drop for NotesConfig {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_dir_all(&self.dir);
+		}
+	}
+
+	fn notes_config() -> NotesConfig {
+		static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+		let id = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+		let dir = std::env::temp_dir().join(format!("paperback_note_search_{}_{id}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let mut config = RustConfigManager::new();
+		config.initialize(dir.join("config.toml"));
+		config.add_bookmark("book.txt", 120, 130, "Ahab's obsession with the whale, again.");
+		config.add_bookmark("book.txt", 40, 50, "The whale is named here.");
+		config.add_bookmark("book.txt", 80, 80, "");
+		config.set_document_notes("book.txt", "Themes:\nthe whale as fate;\nthe crew.");
+		NotesConfig { config, dir }
+	}
+
+	#[test]
+	fn note_search_covers_bookmark_notes_in_order_then_document_notes() {
+		let notes = notes_config();
+		let results = search_notes(&notes.config, "book.txt", "whale", SearchOptions::empty(), false).unwrap();
+		let found: Vec<_> = results.iter().map(|result| (result.source, result.position, result.length)).collect();
+		assert_eq!(
+			found,
+			[
+				(NoteSource::Bookmark { start: 40, end: 50 }, 4, 5),
+				(NoteSource::Bookmark { start: 120, end: 130 }, 26, 5),
+				(NoteSource::DocumentNotes, 12, 5),
+			]
+		);
+		assert_eq!(results[0].excerpt, "The >>whale<< is named here.");
+		assert_eq!(results[2].excerpt, "Themes: the >>whale<< as fate; the crew.");
+		assert!(search_notes(&notes.config, "book.txt", "Whale", SearchOptions::MATCH_CASE, false).unwrap().is_empty());
+		assert!(search_notes(&notes.config, "other.txt", "whale", SearchOptions::empty(), false).unwrap().is_empty());
+		assert!(search_notes(&notes.config, "book.txt", "(", SearchOptions::REGEX, false).is_err());
+	}
+
+	#[test]
+	fn note_search_cuts_long_notes_around_the_match() {
+		let notes = notes_config();
+		let note = format!("{}needle{}", "a b ".repeat(20), " c d".repeat(20));
+		notes.config.add_bookmark("long.txt", 0, 0, &note);
+		let results = search_notes(&notes.config, "long.txt", "needle", SearchOptions::empty(), false).unwrap();
+		let excerpt = &results[0].excerpt;
+		assert!(excerpt.starts_with('\u{2026}') && excerpt.ends_with('\u{2026}'));
+		assert!(excerpt.contains(" a b >>needle<< c d "));
+		assert_eq!(excerpt.chars().count(), 2 * NOTE_EXCERPT_CONTEXT + "needle".len() + 6);
+	}
+
+	#[rstest]
+	#[case::precomposed("Caf\u{e9} noir", "cafe", SearchOptions::empty(), Some((0, 4)))]
+	#[case::decomposed_mark_is_taken_along("cafe\u{301} noir", "cafe", SearchOptions::WHOLE_WORD, Some((0, 5)))]
+	#[case::accented_needle("resume", "r\u{e9}sum\u{e9}", SearchOptions::empty(), Some((0, 6)))]
+	#[case::greek_tonos("λόγος", "λογος", SearchOptions::empty(), Some((0, 5)))]
+	#[case::match_case_still_applies("\u{c9}t\u{e9}", "ete", SearchOptions::MATCH_CASE, None)]
+	#[case::hangul_is_not_split("\u{d55c}\u{ad6d}", "\u{d55c}", SearchOptions::empty(), Some((0, 1)))]
+	fn note_search_can_ignore_diacritics(
+		#[case] note: &str,
+		#[case] needle: &str,
+		#[case] options: SearchOptions,
+		#[case] expected: Option<(i64, i64)>,
+	) {
+		let notes = notes_config();
+		notes.config.add_bookmark("accents.txt", 0, 0, note);
+		let results = search_notes(&notes.config, "accents.txt", needle, options, true).unwrap();
+		assert_eq!(results.first().map(|result| (result.position, result.length)), expected);
+	}
+
+	#[test]
+	fn note_search_matches_diacritics_exactly_unless_asked_not_to() {
+		let notes = notes_config();
+		notes.config.add_bookmark("accents.txt", 0, 0, "Caf\u{e9} noir");
+		assert!(search_notes(&notes.config, "accents.txt", "cafe", SearchOptions::empty(), false).unwrap().is_empty());
+		assert_eq!(
+			search_notes(&notes.config, "accents.txt", "caf\u{e9}", SearchOptions::empty(), false).unwrap().len(),
+			1
+		);
+	}
+
 	#[test]
 	fn reader_search_clamps_negative_start_to_zero() {
 		let haystack = "abc";
@@ -732,6 +2438,20 @@ mod tests {
 		assert_eq!(result.index, 1);
 	}
 
+	#[test]
+	fn history_go_to_appends_instead_of_truncating() {
+		let history = vec![10, 20, 30, 40];
+		let result = history_go_to(&history, 1, 0, 25, 10);
+		assert!(result.found);
+		assert_eq!(result.target, 10);
+		assert_eq!(result.positions, vec![10, 20, 30, 40, 25, 10]);
+		assert_eq!(result.index, 5);
+		let missing = history_go_to(&history, 1, 9, 25, 10);
+		assert!(!missing.found);
+		assert_eq!(missing.positions, history);
+		assert_eq!(missing.index, 1);
+	}
+
 	fn sample_link_doc_handle() -> DocumentHandle {
 		let mut buffer = DocumentBuffer::with_content("x".repeat(220));
 		buffer.add_marker(Marker::new(MarkerType::SectionBreak, 0));
@@ -803,6 +2523,25 @@ mod tests {
 		assert_eq!(result.offset, 100);
 	}
 
+	#[test]
+	fn resolve_webview_url_maps_fragment_through_section_file_name() {
+		let doc = sample_link_doc_handle();
+		assert_eq!(resolve_webview_url(&doc, "file:///tmp/paperback_x/chapter2.xhtml#target", 10), Some(120));
+		assert_eq!(resolve_webview_url(&doc, "file:///tmp/paperback_x/chapter1.xhtml#intro", 150), Some(10));
+		assert_eq!(resolve_webview_url(&doc, "file:///C:/Temp/book.html#glob%61l", 0), Some(180));
+	}
+
+	#[test]
+	fn resolve_webview_url_ignores_missing_or_unknown_fragments() {
+		let doc = sample_link_doc_handle();
+		assert_eq!(resolve_webview_url(&doc, "file:///tmp/paperback_x/chapter2.xhtml", 150), None);
+		assert_eq!(resolve_webview_url(&doc, "file:///tmp/paperback_x/chapter2.xhtml#", 150), None);
+		assert_eq!(
+			resolve_webview_url(&doc, "file:///tmp/paperback_x/chapter2.xhtml#paperback-reading-pos", 150),
+			None
+		);
+	}
+
 	#[test]
 	fn resolve_link_returns_not_found_for_unknown_targets() {
 		let doc = sample_link_doc_handle();
@@ -810,6 +2549,32 @@ mod tests {
 		assert!(!result.found);
 	}
 
+	#[test]
+	fn link_preview_text_stops_at_blank_line_or_heading() {
+		// "Intro"(0-4) \n "Para one"(6-13) \n "goes on"(15-21) \n\n "Later"(24-28) \n "Heading"(30-36) \n "Text"(38-41).
+		let mut buffer = DocumentBuffer::with_content("Intro\nPara one\ngoes on\n\nLater\nHeading\nText".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Heading2, 30).with_level(2));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let doc = DocumentHandle::new(doc);
+		assert_eq!(link_preview_text(&doc, 6), "Para one\ngoes on");
+		assert_eq!(link_preview_text(&doc, 24), "Later");
+		assert_eq!(link_preview_text(&doc, 30), "Heading\nText");
+	}
+
+	#[test]
+	fn link_preview_text_prefers_the_enclosing_note() {
+		// "Body"(0-3) \n "1. First"(5-12) \n "continued"(14-22) \n "2. Second"(24-32).
+		let mut buffer = DocumentBuffer::with_content("Body\n1. First\ncontinued\n2. Second".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Note, 5).with_length(19));
+		buffer.add_marker(Marker::new(MarkerType::Note, 24).with_length(9));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let doc = DocumentHandle::new(doc);
+		assert_eq!(link_preview_text(&doc, 8), "1. First\ncontinued");
+		assert_eq!(link_preview_text(&doc, 24), "2. Second");
+	}
+
 	fn sample_reading_pos_doc_handle() -> DocumentHandle {
 		let mut buffer = DocumentBuffer::with_content("x".repeat(220));
 		buffer.add_marker(Marker::new(MarkerType::SectionBreak, 0));
@@ -878,4 +2643,64 @@ mod tests {
 		assert_eq!(nearest_fragment_before(&doc, 45), Some("top".to_string()));
 		assert_eq!(nearest_fragment_before(&doc, 5), None);
 	}
+
+	fn anchored_text() -> String {
+		(0..40).map(|n| format!("Sentence number {n} of the book goes here. ")).collect()
+	}
+
+	fn anchor_for(content: &str, needle: &str) -> (usize, PositionAnchor) {
+		let buffer = DocumentBuffer::with_content(content.to_string());
+		let position = buffer.display_index_for_byte(content.find(needle).unwrap());
+		(position, position_anchor(&buffer, position))
+	}
+
+	#[test]
+	fn position_anchor_keeps_an_unchanged_offset() {
+		let content = anchored_text();
+		let (position, anchor) = anchor_for(&content, "Sentence number 20 ");
+		assert_eq!(anchor.snippet.chars().count(), POSITION_ANCHOR_CHARS);
+		let buffer = DocumentBuffer::with_content(content);
+		assert_eq!(resolve_position_anchor(&buffer, position, &anchor), (position, false));
+	}
+
+	#[test]
+	fn position_anchor_follows_text_inserted_before_it() {
+		let content = anchored_text();
+		let (position, anchor) = anchor_for(&content, "Sentence number 20 ");
+		let shifted = content.replacen("number 3 of", "number 3, with some words added, of", 1);
+		let buffer = DocumentBuffer::with_content(shifted.clone());
+		let expected = buffer.display_index_for_byte(shifted.find("Sentence number 20 ").unwrap());
+		assert_ne!(expected, position);
+		assert_eq!(resolve_position_anchor(&buffer, position, &anchor), (expected, false));
+	}
+
+	#[test]
+	fn position_anchor_follows_text_deleted_before_it() {
+		let content = anchored_text();
+		let (position, anchor) = anchor_for(&content, "Sentence number 20 ");
+		let shifted = content.replacen("Sentence number 5 of the book goes here. ", "", 1);
+		let buffer = DocumentBuffer::with_content(shifted.clone());
+		let expected = buffer.display_index_for_byte(shifted.find("Sentence number 20 ").unwrap());
+		assert_eq!(resolve_position_anchor(&buffer, position, &anchor), (expected, false));
+	}
+
+	#[test]
+	fn position_anchor_falls_back_to_the_percentage() {
+		let content = anchored_text();
+		let (position, anchor) = anchor_for(&content, "Sentence number 20 ");
+		// Every sentence loses a character, so the percentage still lands on the same one.
+		let rewritten = content.replace("goes here", "is found");
+		let buffer = DocumentBuffer::with_content(rewritten.clone());
+		let expected = buffer.display_index_for_byte(rewritten.find("Sentence number 20 ").unwrap());
+		assert_eq!(resolve_position_anchor(&buffer, position, &anchor), (expected, true));
+	}
+
+	#[test]
+	fn position_anchor_near_the_end_is_shorter() {
+		let content = "Short text.".to_string();
+		let (position, anchor) = anchor_for(&content, "text");
+		assert_eq!(anchor.snippet, "text.");
+		let buffer = DocumentBuffer::with_content(format!("A {content}"));
+		assert_eq!(resolve_position_anchor(&buffer, position, &anchor), (position + 2, false));
+	}
 }
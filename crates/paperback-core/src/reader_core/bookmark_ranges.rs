@@ -0,0 +1,137 @@
+//! The bookmarks of one document laid out for caret tracking: which bookmark the caret is in, found
+//! by binary search, so reading into a bookmark can be noticed without scanning them all.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::config::Bookmark;
+
+/// A document's bookmarks cut into the spans between their edges, each span knowing the innermost
+/// bookmark covering it.
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkRanges {
+	bookmarks: Vec<Bookmark>,
+	/// Every position where a bookmark starts or ends, sorted and without repeats.
+	edges: Vec<i64>,
+	/// Innermost bookmark from each edge up to the next one; the last edge always maps to `None`.
+	innermost: Vec<Option<usize>>,
+}
+
+/// The end of a bookmark as a half-open range. A bookmark without a range covers just its position.
+fn range_end(bookmark: &Bookmark) -> i64 {
+	bookmark.end.max(bookmark.start.saturating_add(1))
+}
+
+impl BookmarkRanges {
+	#[must_use]
+	pub fn new(mut bookmarks: Vec<Bookmark>) -> Self {
+		bookmarks.sort_by_key(|bm| (bm.start, Reverse(range_end(bm))));
+		let mut edges: Vec<i64> = bookmarks.iter().flat_map(|bm| [bm.start, range_end(bm)]).collect();
+		edges.sort_unstable();
+		edges.dedup();
+		// Sweeps the edges with the bookmarks open at each one, the latest started (and of those the
+		// shortest) on top; the ones that have ended are only dropped once they reach the top.
+		let mut open = BinaryHeap::new();
+		let mut next = 0;
+		let mut innermost = Vec::with_capacity(edges.len());
+		for &edge in &edges {
+			while let Some(bookmark) = bookmarks.get(next).filter(|bm| bm.start <= edge) {
+				open.push((bookmark.start, Reverse(range_end(bookmark)), Reverse(next)));
+				next += 1;
+			}
+			while open.peek().is_some_and(|&(_, Reverse(end), _)| end <= edge) {
+				open.pop();
+			}
+			innermost.push(open.peek().map(|&(_, _, Reverse(index))| index));
+		}
+		Self { bookmarks, edges, innermost }
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.bookmarks.is_empty()
+	}
+
+	/// The innermost bookmark holding `position`: of overlapping ones, the one starting last.
+	#[must_use]
+	pub fn at(&self, position: i64) -> Option<&Bookmark> {
+		let span = self.edges.partition_point(|&edge| edge <= position).checked_sub(1)?;
+		self.innermost[span].map(|index| &self.bookmarks[index])
+	}
+
+	/// The bookmark the caret entered moving from `from` to `to`, if the innermost one holding `to`
+	/// didn't already hold `from`. Moving within a bookmark, or back out to one that holds it, is not
+	/// entering anything.
+	#[must_use]
+	pub fn entered(&self, from: i64, to: i64) -> Option<&Bookmark> {
+		self.at(to).filter(|bm| !(bm.start <= from && from < range_end(bm)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn bookmark(start: i64, end: i64, note: &str) -> Bookmark {
+		Bookmark { start, end, note: note.to_string(), category: String::new() }
+	}
+
+	fn note_at(ranges: &BookmarkRanges, position: i64) -> Option<&str> {
+		ranges.at(position).map(|bm| bm.note.as_str())
+	}
+
+	fn note_entered(ranges: &BookmarkRanges, from: i64, to: i64) -> Option<&str> {
+		ranges.entered(from, to).map(|bm| bm.note.as_str())
+	}
+
+	#[test]
+	fn finds_the_bookmark_holding_a_position() {
+		let ranges = BookmarkRanges::new(vec![bookmark(30, 40, "b"), bookmark(10, 20, "a"), bookmark(50, 50, "point")]);
+		assert_eq!(note_at(&ranges, 9), None);
+		assert_eq!(note_at(&ranges, 10), Some("a"));
+		assert_eq!(note_at(&ranges, 19), Some("a"));
+		assert_eq!(note_at(&ranges, 20), None);
+		assert_eq!(note_at(&ranges, 35), Some("b"));
+		assert_eq!(note_at(&ranges, 50), Some("point"));
+		assert_eq!(note_at(&ranges, 51), None);
+		assert!(BookmarkRanges::default().at(0).is_none());
+	}
+
+	#[test]
+	fn entering_is_reported_once_per_bookmark() {
+		let ranges = BookmarkRanges::new(vec![bookmark(10, 20, "a")]);
+		assert_eq!(note_entered(&ranges, 5, 10), Some("a"));
+		assert_eq!(note_entered(&ranges, 10, 15), None);
+		assert_eq!(note_entered(&ranges, 15, 25), None);
+		assert_eq!(note_entered(&ranges, 25, 12), Some("a"));
+		assert_eq!(note_entered(&ranges, 12, 12), None);
+	}
+
+	#[test]
+	fn adjacent_bookmarks_are_each_entered() {
+		let ranges = BookmarkRanges::new(vec![bookmark(0, 10, "first"), bookmark(10, 20, "second")]);
+		assert_eq!(note_entered(&ranges, 9, 10), Some("second"));
+		assert_eq!(note_entered(&ranges, 10, 9), Some("first"));
+		assert_eq!(note_entered(&ranges, 19, 20), None);
+	}
+
+	#[test]
+	fn overlapping_bookmarks_report_the_innermost_newly_entered() {
+		let ranges =
+			BookmarkRanges::new(vec![bookmark(0, 30, "outer"), bookmark(10, 20, "inner"), bookmark(25, 40, "tail")]);
+		assert_eq!(note_entered(&ranges, 5, 10), Some("inner"));
+		assert_eq!(note_entered(&ranges, 15, 20), None, "back out into a bookmark the caret never left");
+		assert_eq!(note_entered(&ranges, 20, 25), Some("tail"));
+		assert_eq!(note_entered(&ranges, 25, 30), None);
+		assert_eq!(note_at(&ranges, 35), Some("tail"));
+		assert_eq!(note_entered(&ranges, 35, 22), Some("outer"));
+		assert_eq!(note_entered(&ranges, 45, 15), Some("inner"));
+	}
+
+	#[test]
+	fn bookmarks_sharing_a_start_prefer_the_shorter() {
+		let ranges = BookmarkRanges::new(vec![bookmark(10, 30, "long"), bookmark(10, 10, "point")]);
+		assert_eq!(note_entered(&ranges, 5, 10), Some("point"));
+		assert_eq!(note_entered(&ranges, 10, 11), None, "the caret was already in both");
+		assert_eq!(note_entered(&ranges, 35, 11), Some("long"));
+	}
+}
@@ -0,0 +1,400 @@
+//! Bookmarks and highlights exported by other reading apps.
+//!
+//! Each importer reads the app's own position scheme, turns it into a rough position in the open
+//! document, and then looks for the entry's highlighted text near that spot, since the apps
+//! count paragraphs and bytes differently from how this document was laid out.
+
+use std::path::Path;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+	document::{DocumentHandle, MarkerType},
+	util::text::{byte_to_display_index, display_len, display_to_byte_index},
+};
+
+/// How far, in display units, highlighted text may be found from the position its app recorded
+/// and still count as the same place.
+const ANCHOR_TOLERANCE: usize = 5000;
+/// Characters of an entry's text kept when listing it as unplaced.
+const SUMMARY_TEXT_LEN: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationFormat {
+	/// Calibre viewer's "Export annotations" JSON (highlights and bookmarks).
+	Calibre,
+	/// Moon+ Reader `.po` position records, one `time*chapter@split#offset:percent%` per line.
+	MoonReader,
+}
+
+impl AnnotationFormat {
+	#[must_use]
+	pub fn from_path(path: &str) -> Option<Self> {
+		let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+		match extension.as_str() {
+			"calibre_highlights" | "json" => Some(Self::Calibre),
+			"po" => Some(Self::MoonReader),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedAnnotation {
+	pub start: i64,
+	pub end: i64,
+	pub note: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationImport {
+	pub annotations: Vec<ImportedAnnotation>,
+	/// Short descriptions of the entries that couldn't be found in this document.
+	pub unplaced: Vec<String>,
+}
+
+/// Where inside its section an app says an entry is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionOffset {
+	/// Block-level element index (roughly a paragraph) and character offset inside it.
+	Paragraph {
+		index: usize,
+		chars: usize,
+	},
+	/// Byte offset from the start of the section text.
+	Bytes(usize),
+	Unknown,
+}
+
+#[derive(Debug, Clone)]
+struct RawAnnotation {
+	section_index: Option<usize>,
+	section_name: String,
+	offset: SectionOffset,
+	/// Fraction of the whole book, when the app records one.
+	fraction: Option<f64>,
+	text: String,
+	note: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CalibreExport {
+	Wrapped {
+		#[serde(alias = "annotations")]
+		highlights: Vec<CalibreEntry>,
+	},
+	List(Vec<CalibreEntry>),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CalibreEntry {
+	#[serde(rename = "type")]
+	kind: String,
+	highlighted_text: String,
+	notes: String,
+	title: String,
+	start_cfi: String,
+	spine_index: Option<usize>,
+	spine_name: String,
+	/// Bookmark position, e.g. `epubcfi(/6/8!/4/2/2:0)`.
+	pos: String,
+}
+
+/// Reads an export in `format` and places its entries in `doc`. `None` when the content isn't
+/// in that format at all.
+#[must_use]
+pub fn import_annotations(doc: &DocumentHandle, format: AnnotationFormat, content: &str) -> Option<AnnotationImport> {
+	let raw = match format {
+		AnnotationFormat::Calibre => parse_calibre(content)?,
+		AnnotationFormat::MoonReader => parse_moon_reader(content)?,
+	};
+	let sections = section_starts(doc);
+	let text = &doc.document().buffer.content;
+	let mut import = AnnotationImport::default();
+	for entry in raw {
+		match place(text, &sections, &entry) {
+			Some((start, end)) => import.annotations.push(ImportedAnnotation {
+				start: i64::try_from(start).unwrap_or(i64::MAX),
+				end: i64::try_from(end).unwrap_or(i64::MAX),
+				note: entry.note,
+			}),
+			None => import.unplaced.push(describe(&entry)),
+		}
+	}
+	Some(import)
+}
+
+fn parse_calibre(content: &str) -> Option<Vec<RawAnnotation>> {
+	let entries = match serde_json::from_str::<CalibreExport>(content).ok()? {
+		CalibreExport::Wrapped { highlights } => highlights,
+		CalibreExport::List(entries) => entries,
+	};
+	Some(
+		entries
+			.into_iter()
+			.filter(|entry| entry.kind.is_empty() || entry.kind == "highlight" || entry.kind == "bookmark")
+			.map(|entry| {
+				if entry.kind == "bookmark" {
+					let (spine, path) =
+						entry.pos.trim_start_matches("epubcfi(").trim_end_matches(')').split_once('!').unzip();
+					// The part before `!` walks the package: /6 is the spine, then one even step per item.
+					let section_index =
+						spine.and_then(|spine| cfi_steps(spine).get(1).map(|step| (step / 2).saturating_sub(1)));
+					// After `!` the path starts at <html>; its first step is <body>.
+					let offset = path.map_or(SectionOffset::Unknown, cfi_offset);
+					RawAnnotation {
+						section_index,
+						section_name: String::new(),
+						offset,
+						fraction: None,
+						text: String::new(),
+						note: entry.title,
+					}
+				} else {
+					RawAnnotation {
+						section_index: entry.spine_index,
+						section_name: entry.spine_name,
+						offset: cfi_offset(&entry.start_cfi),
+						fraction: None,
+						text: entry.highlighted_text,
+						note: entry.notes,
+					}
+				}
+			})
+			.collect(),
+	)
+}
+
+fn cfi_steps(path: &str) -> Vec<usize> {
+	path.split('/').filter_map(|step| step.split(['[', ':']).next()?.parse().ok()).collect()
+}
+
+/// Reads a CFI path whose first step is `<body>`: the step after it picks the block element, and
+/// the `:n` suffix is the character offset.
+fn cfi_offset(path: &str) -> SectionOffset {
+	let Some(&block_step) = cfi_steps(path).get(1) else {
+		return SectionOffset::Unknown;
+	};
+	let chars = path.rsplit_once(':').and_then(|(_, chars)| chars.parse().ok()).unwrap_or(0);
+	SectionOffset::Paragraph { index: (block_step / 2).saturating_sub(1), chars }
+}
+
+fn parse_moon_reader(content: &str) -> Option<Vec<RawAnnotation>> {
+	let line_pattern = Regex::new(r"^\d+\*(\d+)@\d+#(\d+):([\d.]+)%").ok()?;
+	let entries: Vec<RawAnnotation> = content
+		.lines()
+		.filter_map(|line| {
+			let captures = line_pattern.captures(line.trim())?;
+			Some(RawAnnotation {
+				section_index: captures[1].parse().ok(),
+				section_name: String::new(),
+				offset: captures[2].parse().map_or(SectionOffset::Unknown, SectionOffset::Bytes),
+				fraction: captures[3].parse::<f64>().ok().map(|percent| percent / 100.0),
+				text: String::new(),
+				note: String::new(),
+			})
+		})
+		.collect();
+	if entries.is_empty() { None } else { Some(entries) }
+}
+
+/// Section start offsets paired with their source file, in document order.
+fn section_starts(doc: &DocumentHandle) -> Vec<(usize, String)> {
+	doc.document()
+		.buffer
+		.markers
+		.iter()
+		.filter(|marker| marker.mtype == MarkerType::SectionBreak)
+		.map(|marker| (marker.position, marker.reference.clone()))
+		.collect()
+}
+
+/// The display range `entry` covers in `text`, or `None` when it can't be found there.
+fn place(text: &str, sections: &[(usize, String)], entry: &RawAnnotation) -> Option<(usize, usize)> {
+	let claimed = claimed_position(text, sections, entry);
+	let snippet = entry.text.split_whitespace().collect::<Vec<_>>().join(" ");
+	if snippet.is_empty() {
+		return claimed.map(|position| (position, position));
+	}
+	// Only the first line is searched for: the document may break the rest differently.
+	let probe = entry.text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or(&snippet);
+	let probe = probe.split_whitespace().collect::<Vec<_>>().join(" ");
+	let start = nearest_occurrence(text, &probe, claimed.unwrap_or(0))?;
+	if claimed.is_some_and(|claimed| start.abs_diff(claimed) > ANCHOR_TOLERANCE) {
+		return None;
+	}
+	Some((start, (start + display_len(&snippet)).min(display_len(text))))
+}
+
+fn claimed_position(text: &str, sections: &[(usize, String)], entry: &RawAnnotation) -> Option<usize> {
+	let total = display_len(text);
+	let section = if entry.section_name.is_empty() {
+		None
+	} else {
+		sections.iter().position(|(_, reference)| {
+			!reference.is_empty()
+				&& (reference.ends_with(&entry.section_name) || entry.section_name.ends_with(reference))
+		})
+	}
+	.or_else(|| entry.section_index.filter(|&index| index < sections.len()))
+	.or_else(|| (sections.is_empty() && entry.section_index.unwrap_or(0) == 0).then_some(usize::MAX));
+	let Some(section) = section else {
+		return entry.fraction.map(|fraction| position_from_fraction(fraction, total));
+	};
+	// `usize::MAX` stands for a document without section breaks, read as one section.
+	let (start, end) = if section == usize::MAX {
+		(0, total)
+	} else {
+		(sections[section].0, sections.get(section + 1).map_or(total, |(next, _)| *next))
+	};
+	let start_byte = display_to_byte_index(text, start);
+	let end_byte = display_to_byte_index(text, end).max(start_byte);
+	let section_text = &text[start_byte..end_byte];
+	let within = match entry.offset {
+		SectionOffset::Paragraph { index, chars } => paragraph_start(section_text, index)? + chars,
+		SectionOffset::Bytes(bytes) => byte_to_display_index(section_text, floor_char_boundary(section_text, bytes)),
+		SectionOffset::Unknown => 0,
+	};
+	Some((start + within).min(end))
+}
+
+fn position_from_fraction(fraction: f64, total: usize) -> usize {
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+	let position = (fraction.clamp(0.0, 1.0) * total as f64) as usize;
+	position.min(total)
+}
+
+/// Display offset of the start of the `index`th non-empty line of `section`.
+fn paragraph_start(section: &str, index: usize) -> Option<usize> {
+	let mut offset = 0;
+	let mut seen = 0;
+	for line in section.split('\n') {
+		if !line.trim().is_empty() {
+			if seen == index {
+				return Some(offset);
+			}
+			seen += 1;
+		}
+		offset += display_len(line) + 1;
+	}
+	None
+}
+
+fn floor_char_boundary(text: &str, byte: usize) -> usize {
+	let mut byte = byte.min(text.len());
+	while !text.is_char_boundary(byte) {
+		byte -= 1;
+	}
+	byte
+}
+
+/// Display offset of the occurrence of `needle` closest to `near`.
+fn nearest_occurrence(text: &str, needle: &str, near: usize) -> Option<usize> {
+	let mut best: Option<usize> = None;
+	let mut display = 0;
+	let mut last_byte = 0;
+	for (byte, _) in text.match_indices(needle) {
+		display += display_len(&text[last_byte..byte]);
+		last_byte = byte;
+		if best.is_none_or(|best| display.abs_diff(near) < best.abs_diff(near)) {
+			best = Some(display);
+		}
+		if display > near {
+			break;
+		}
+	}
+	best
+}
+
+fn describe(entry: &RawAnnotation) -> String {
+	let text = if entry.text.trim().is_empty() { &entry.note } else { &entry.text };
+	let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+	if !text.is_empty() {
+		let mut chars = text.chars();
+		let short: String = chars.by_ref().take(SUMMARY_TEXT_LEN).collect();
+		return if chars.next().is_some() { format!("{short}…") } else { short };
+	}
+	match (entry.section_index, entry.fraction) {
+		(Some(section), _) => format!("#{}", section + 1),
+		(None, Some(fraction)) => format!("{:.1}%", fraction * 100.0),
+		(None, None) => String::from("?"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::document::{Document, DocumentBuffer, Marker};
+
+	fn two_section_handle() -> DocumentHandle {
+		let mut buffer = DocumentBuffer::new();
+		buffer.add_marker(Marker::new(MarkerType::SectionBreak, 0).with_reference("text/one.xhtml".to_string()));
+		buffer.append("Call me Ishmael.\nSome years ago.\n");
+		let second = buffer.current_position();
+		buffer.add_marker(Marker::new(MarkerType::SectionBreak, second).with_reference("text/two.xhtml".to_string()));
+		buffer.append("It was the best of times.\nIt was the worst of times.\n");
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		DocumentHandle::new(doc)
+	}
+
+	#[test]
+	fn calibre_highlights_are_found_by_their_text_near_the_claimed_paragraph() {
+		let handle = two_section_handle();
+		let export = r#"{"type": "calibre_highlights", "version": 1, "highlights": [
+			{"type": "highlight", "highlighted_text": "worst of times", "notes": "Dickens", "spine_index": 1,
+			 "spine_name": "text/two.xhtml", "start_cfi": "/2/4/1:11"},
+			{"type": "highlight", "highlighted_text": "not in this book", "spine_index": 0, "start_cfi": "/2/2:0"}
+		]}"#;
+		let import = import_annotations(&handle, AnnotationFormat::Calibre, export).unwrap();
+		assert_eq!(import.annotations.len(), 1);
+		let text = &handle.document().buffer.content;
+		let start = text.find("worst of times").unwrap();
+		let expected_start = i64::try_from(display_len(&text[..start])).unwrap();
+		assert_eq!(
+			import.annotations[0],
+			ImportedAnnotation { start: expected_start, end: expected_start + 14, note: "Dickens".to_string() }
+		);
+		assert_eq!(import.unplaced, ["not in this book"]);
+	}
+
+	#[test]
+	fn calibre_bookmarks_use_the_spine_step_of_their_cfi() {
+		let handle = two_section_handle();
+		let export =
+			r#"[{"type": "bookmark", "title": "Opening", "pos": "epubcfi(/6/4!/4/4/1:3)", "pos_type": "epubcfi"}]"#;
+		let import = import_annotations(&handle, AnnotationFormat::Calibre, export).unwrap();
+		let second_line =
+			i64::try_from(display_len("Call me Ishmael.\nSome years ago.\nIt was the best of times.\n")).unwrap();
+		assert_eq!(import.annotations[0].start, second_line + 3);
+		assert_eq!(import.annotations[0].note, "Opening");
+	}
+
+	#[test]
+	fn moon_reader_positions_use_chapter_byte_offsets() {
+		let handle = two_section_handle();
+		let import = import_annotations(
+			&handle,
+			AnnotationFormat::MoonReader,
+			"1703471012345*1@0#7:60.0%\n1703471012345*9@0#0:0%",
+		)
+		.unwrap();
+		let second = i64::try_from(display_len("Call me Ishmael.\nSome years ago.\n")).unwrap();
+		assert_eq!(import.annotations.len(), 2);
+		assert_eq!(import.annotations[0].start, second + 7);
+		// Chapter 9 doesn't exist, so the recorded percentage places it.
+		assert_eq!(import.annotations[1].start, 0);
+		assert!(import.unplaced.is_empty());
+		assert!(import_annotations(&handle, AnnotationFormat::MoonReader, "not a position").is_none());
+	}
+
+	#[test]
+	fn format_is_picked_by_extension() {
+		assert_eq!(AnnotationFormat::from_path("book.calibre_highlights"), Some(AnnotationFormat::Calibre));
+		assert_eq!(AnnotationFormat::from_path("C:\\Moon\\book.epub.PO"), Some(AnnotationFormat::MoonReader));
+		assert_eq!(AnnotationFormat::from_path("book.paperback"), None);
+	}
+}
@@ -0,0 +1,125 @@
+//! Word lookup in an offline dictionary, with a web page as the fallback.
+//!
+//! Dictionaries are plain text with one `word<TAB>definition` entry per line, as written by
+//! StarDict's tab-file tools and most TSV glossary exports. Escaped `\n` sequences in a definition
+//! become line breaks, and a word listed more than once keeps all of its definitions.
+
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex, PoisonError},
+};
+
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+
+/// Looked up when the reader hasn't chosen another page.
+pub const DEFAULT_LOOKUP_URL: &str = "https://en.wiktionary.org/wiki/{word}";
+
+/// The loaded dictionary, kept until a different file is configured.
+static DICTIONARY: Mutex<Option<(PathBuf, Arc<Dictionary>)>> = Mutex::new(None);
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+	entries: HashMap<String, String>,
+}
+
+impl Dictionary {
+	/// Reads a tab-separated dictionary file.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file can't be read.
+	pub fn load(path: &Path) -> io::Result<Self> {
+		let bytes = fs::read(path)?;
+		Ok(Self::parse(&String::from_utf8_lossy(&bytes)))
+	}
+
+	#[must_use]
+	pub fn parse(contents: &str) -> Self {
+		let mut entries: HashMap<String, String> = HashMap::new();
+		for line in contents.lines() {
+			let Some((word, definition)) = line.split_once('\t') else {
+				continue;
+			};
+			let (word, definition) = (normalize_word(word), definition.trim().replace("\\n", "\n"));
+			if word.is_empty() || definition.is_empty() {
+				continue;
+			}
+			entries
+				.entry(word)
+				.and_modify(|existing| {
+					existing.push_str("\n\n");
+					existing.push_str(&definition);
+				})
+				.or_insert(definition);
+		}
+		Self { entries }
+	}
+
+	/// The definition of `word`, ignoring case and surrounding punctuation.
+	#[must_use]
+	pub fn lookup(&self, word: &str) -> Option<&str> {
+		self.entries.get(&normalize_word(word)).map(String::as_str)
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+fn normalize_word(word: &str) -> String {
+	word.trim_matches(|ch: char| !ch.is_alphanumeric()).to_lowercase()
+}
+
+/// The dictionary at `path`, read on first use and reused until another file is asked for.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read.
+pub fn cached_dictionary(path: &Path) -> io::Result<Arc<Dictionary>> {
+	let mut cache = DICTIONARY.lock().unwrap_or_else(PoisonError::into_inner);
+	if let Some((cached_path, dictionary)) = cache.as_ref()
+		&& cached_path == path
+	{
+		return Ok(Arc::clone(dictionary));
+	}
+	let dictionary = Arc::new(Dictionary::load(path)?);
+	*cache = Some((path.to_path_buf(), Arc::clone(&dictionary)));
+	Ok(dictionary)
+}
+
+/// Fills `{word}` in a lookup URL template, percent-encoding the text. Multi-word selections are
+/// passed through whole.
+#[must_use]
+pub fn lookup_url(template: &str, text: &str) -> String {
+	let encoded = utf8_percent_encode(text.trim(), NON_ALPHANUMERIC).to_string();
+	template.replace("{word}", &encoded)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tab_separated_entries_are_found_ignoring_case_and_punctuation() {
+		let dictionary = Dictionary::parse(
+			"apple\tA round fruit.\\nGrows on trees.\nno tab on this line\nApple\tA record label.\n\tempty word\n",
+		);
+		assert_eq!(dictionary.len(), 1);
+		assert_eq!(dictionary.lookup("\"APPLE,\""), Some("A round fruit.\nGrows on trees.\n\nA record label."));
+		assert_eq!(dictionary.lookup("pear"), None);
+	}
+
+	#[test]
+	fn lookup_url_encodes_the_whole_selection() {
+		assert_eq!(lookup_url(DEFAULT_LOOKUP_URL, "café"), "https://en.wiktionary.org/wiki/caf%C3%A9");
+		assert_eq!(lookup_url("https://example.com/?q={word}", " ad hoc "), "https://example.com/?q=ad%20hoc");
+	}
+}
@@ -0,0 +1,177 @@
+//! Pronunciation rules: replacements applied to text on its way to speech, such as "et al." read
+//! as "and others". They never touch the document itself, so offsets and Find are unaffected.
+//!
+//! Wherever several rules match, the one that starts first wins, and among those the longest
+//! match, so a rule for "et al." is used before one for "al." can see the text. Matches of the same
+//! length go to the earlier rule, which puts a document's own rules ahead of the global ones.
+
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::config::PronunciationRule;
+
+struct CompiledRule {
+	regex: Regex,
+	replacement: String,
+	/// Whether `replacement` may refer to capture groups, which plain rules can't have.
+	expand: bool,
+	whole_word: bool,
+}
+
+#[derive(Default)]
+pub struct PronunciationRules {
+	rules: Vec<CompiledRule>,
+}
+
+impl PronunciationRules {
+	/// Compiles `rules` in order, skipping empty patterns and invalid regular expressions.
+	#[must_use]
+	pub fn new(rules: &[PronunciationRule]) -> Self {
+		let rules = rules
+			.iter()
+			.filter(|rule| !rule.pattern.is_empty())
+			.filter_map(|rule| {
+				let regex = compile(rule).ok()?;
+				Some(CompiledRule {
+					regex,
+					replacement: rule.replacement.clone(),
+					expand: rule.regex,
+					whole_word: rule.whole_word,
+				})
+			})
+			.collect();
+		Self { rules }
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+
+	/// `text` with every rule applied. Replacements aren't matched again.
+	#[must_use]
+	pub fn apply(&self, text: &str) -> String {
+		if self.rules.is_empty() {
+			return text.to_string();
+		}
+		let mut output = String::with_capacity(text.len());
+		let mut pos = 0;
+		// The next match of each rule at or after `pos`, or `None` once it has no more.
+		let mut next: Vec<Option<Range<usize>>> = self.rules.iter().map(|rule| find_from(rule, text, 0)).collect();
+		loop {
+			let mut best: Option<(usize, Range<usize>)> = None;
+			for (index, rule) in self.rules.iter().enumerate() {
+				if next[index].as_ref().is_some_and(|found| found.start < pos) {
+					next[index] = find_from(rule, text, pos);
+				}
+				let Some(found) = next[index].clone() else {
+					continue;
+				};
+				let better = best.as_ref().is_none_or(|(_, current)| {
+					found.start < current.start || (found.start == current.start && found.len() > current.len())
+				});
+				if better {
+					best = Some((index, found));
+				}
+			}
+			let Some((index, found)) = best else {
+				break;
+			};
+			output.push_str(&text[pos..found.start]);
+			let rule = &self.rules[index];
+			if rule.expand
+				&& let Some(captures) = rule.regex.captures_at(text, found.start)
+			{
+				captures.expand(&rule.replacement, &mut output);
+			} else {
+				output.push_str(&rule.replacement);
+			}
+			pos = found.end;
+		}
+		output.push_str(&text[pos..]);
+		output
+	}
+}
+
+/// Checks that a rule's pattern can be used, for the rule editor.
+///
+/// # Errors
+///
+/// Returns the regular expression error if the pattern is an invalid one.
+pub fn validate(rule: &PronunciationRule) -> Result<(), regex::Error> {
+	compile(rule).map(|_| ())
+}
+
+fn compile(rule: &PronunciationRule) -> Result<Regex, regex::Error> {
+	if rule.regex { Regex::new(&rule.pattern) } else { Regex::new(&regex::escape(&rule.pattern)) }
+}
+
+/// The first non-empty match of `rule` starting at or after `from`, honouring whole-word rules.
+fn find_from(rule: &CompiledRule, text: &str, mut from: usize) -> Option<Range<usize>> {
+	while from <= text.len() {
+		let found = rule.regex.find_at(text, from)?;
+		if !found.is_empty() && (!rule.whole_word || is_whole_word(text, found.range())) {
+			return Some(found.range());
+		}
+		from = found.start() + text[found.start()..].chars().next().map_or(1, char::len_utf8);
+	}
+	None
+}
+
+/// Whether `range` doesn't run into a word on either side. An edge that isn't a letter or digit,
+/// like the period of "Fig.", needs nothing after it.
+fn is_whole_word(text: &str, range: Range<usize>) -> bool {
+	let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+	let matched = &text[range.clone()];
+	let open_start =
+		!matched.chars().next().is_some_and(is_word) || !text[..range.start].chars().next_back().is_some_and(is_word);
+	let open_end =
+		!matched.chars().next_back().is_some_and(is_word) || !text[range.end..].chars().next().is_some_and(is_word);
+	open_start && open_end
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn rule(pattern: &str, replacement: &str) -> PronunciationRule {
+		PronunciationRule {
+			pattern: pattern.to_string(),
+			replacement: replacement.to_string(),
+			..PronunciationRule::default()
+		}
+	}
+
+	#[test]
+	fn longest_match_wins_and_earlier_rules_break_ties() {
+		let rules = PronunciationRules::new(&[
+			rule("al.", "ALL"),
+			rule("et al.", "and others"),
+			rule("Fig.", "Figure"),
+			rule("Fig.", "Figurine"),
+		]);
+		assert_eq!(rules.apply("Smith et al. (Fig. 3) and al."), "Smith and others (Figure 3) and ALL");
+	}
+
+	#[test]
+	fn whole_word_rules_skip_matches_inside_words() {
+		let rules = PronunciationRules::new(&[
+			PronunciationRule { whole_word: true, ..rule("cm", "centimetres") },
+			PronunciationRule { whole_word: true, ..rule("Fig.", "Figure") },
+		]);
+		assert_eq!(rules.apply("5 cm, acme, Fig. 2, ConFig."), "5 centimetres, acme, Figure 2, ConFig.");
+	}
+
+	#[test]
+	fn regex_rules_expand_captures_and_invalid_ones_are_skipped() {
+		let rules = PronunciationRules::new(&[
+			PronunciationRule { regex: true, ..rule(r"(\d+)\s?km/h", "$1 kilometres per hour") },
+			PronunciationRule { regex: true, ..rule("(unclosed", "never") },
+			rule("", "nothing"),
+		]);
+		assert_eq!(rules.apply("at 80km/h (unclosed"), "at 80 kilometres per hour (unclosed");
+		assert!(validate(&PronunciationRule { regex: true, ..rule("(unclosed", "") }).is_err());
+		assert!(validate(&rule("(unclosed", "")).is_ok());
+	}
+}
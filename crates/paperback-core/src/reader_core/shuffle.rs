@@ -0,0 +1,171 @@
+//! Random section picks for review reading: a single jump to another section, or a shuffled
+//! order that visits every section once before any repeats.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// SplitMix64, which is plenty for picking sections and keeps a seed reproducible in tests.
+#[derive(Debug, Clone)]
+struct Rng {
+	state: u64,
+}
+
+impl Rng {
+	fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// A uniform index below `bound`, which must not be 0.
+	fn below(&mut self, bound: usize) -> usize {
+		let bound = u64::try_from(bound).unwrap_or(u64::MAX);
+		// Rejecting the top partial range keeps every index equally likely.
+		let zone = u64::MAX - u64::MAX % bound;
+		loop {
+			let value = self.next_u64();
+			if value < zone {
+				return usize::try_from(value % bound).unwrap_or(0);
+			}
+		}
+	}
+}
+
+/// Picks sections at random for one document.
+#[derive(Debug, Clone)]
+pub struct SectionShuffle {
+	rng: Rng,
+	/// Sections not yet visited this round, taken from the end.
+	remaining: Vec<usize>,
+	/// Section count the round was dealt for; a different count starts a new round.
+	dealt_for: usize,
+}
+
+impl Default for SectionShuffle {
+	fn default() -> Self {
+		let seed = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |elapsed| (elapsed.as_secs() << 30) ^ u64::from(elapsed.subsec_nanos()));
+		Self::with_seed(seed)
+	}
+}
+
+impl SectionShuffle {
+	#[must_use]
+	pub const fn with_seed(seed: u64) -> Self {
+		Self { rng: Rng { state: seed }, remaining: Vec::new(), dealt_for: 0 }
+	}
+
+	/// A section other than `current`, each equally likely. `None` with fewer than two sections.
+	pub fn random(&mut self, count: usize, current: Option<usize>) -> Option<usize> {
+		if count < 2 {
+			return None;
+		}
+		match current.filter(|&current| current < count) {
+			Some(current) => {
+				let pick = self.rng.below(count - 1);
+				Some(if pick >= current { pick + 1 } else { pick })
+			}
+			None => Some(self.rng.below(count)),
+		}
+	}
+
+	/// The next section of the shuffled order. Each round visits every section but `current` once,
+	/// in a fresh random order; a new round starts when one is used up or the count changes.
+	/// `None` with fewer than two sections.
+	pub fn next(&mut self, count: usize, current: Option<usize>) -> Option<usize> {
+		if count < 2 {
+			return None;
+		}
+		loop {
+			if self.dealt_for != count || self.remaining.is_empty() {
+				self.deal(count, current);
+			}
+			let pick = self.remaining.pop()?;
+			if Some(pick) != current {
+				return Some(pick);
+			}
+		}
+	}
+
+	/// Starts the order over, as when shuffling is turned back on.
+	pub fn reset(&mut self) {
+		self.remaining.clear();
+		self.dealt_for = 0;
+	}
+
+	fn deal(&mut self, count: usize, current: Option<usize>) {
+		self.remaining = (0..count).filter(|&index| Some(index) != current).collect();
+		// Fisher-Yates.
+		for index in (1..self.remaining.len()).rev() {
+			let other = self.rng.below(index + 1);
+			self.remaining.swap(index, other);
+		}
+		self.dealt_for = count;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::*;
+
+	#[test]
+	fn random_never_picks_the_current_section() {
+		let mut shuffle = SectionShuffle::with_seed(7);
+		let mut seen = HashSet::new();
+		for _ in 0..200 {
+			let pick = shuffle.random(4, Some(2)).unwrap();
+			assert_ne!(pick, 2);
+			seen.insert(pick);
+		}
+		assert_eq!(seen, HashSet::from([0, 1, 3]));
+	}
+
+	#[test]
+	fn random_is_uniform_enough() {
+		let mut shuffle = SectionShuffle::with_seed(42);
+		let mut counts = [0usize; 5];
+		for _ in 0..5000 {
+			counts[shuffle.random(5, None).unwrap()] += 1;
+		}
+		assert!(counts.iter().all(|&count| (850..1150).contains(&count)), "{counts:?}");
+	}
+
+	#[test]
+	fn fewer_than_two_sections_have_nothing_to_pick() {
+		let mut shuffle = SectionShuffle::with_seed(1);
+		assert_eq!(shuffle.random(1, Some(0)), None);
+		assert_eq!(shuffle.next(0, None), None);
+	}
+
+	#[test]
+	fn next_visits_every_other_section_once_per_round() {
+		let mut shuffle = SectionShuffle::with_seed(3);
+		let round: Vec<usize> = (0..9).map(|_| shuffle.next(10, Some(4)).unwrap()).collect();
+		let unique: HashSet<usize> = round.iter().copied().collect();
+		assert_eq!(unique.len(), 9);
+		assert!(!unique.contains(&4));
+		assert_ne!(round, (0..10).filter(|&index| index != 4).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn same_seed_gives_the_same_order() {
+		let mut first = SectionShuffle::with_seed(99);
+		let mut second = SectionShuffle::with_seed(99);
+		for _ in 0..20 {
+			assert_eq!(first.next(6, None), second.next(6, None));
+		}
+	}
+
+	#[test]
+	fn a_changed_section_count_starts_a_new_round() {
+		let mut shuffle = SectionShuffle::with_seed(5);
+		shuffle.next(3, None);
+		for _ in 0..10 {
+			assert!(shuffle.next(2, None).unwrap() < 2);
+		}
+	}
+}
@@ -1,28 +1,63 @@
 use std::{
+	collections::BTreeSet,
 	fs::{self, File},
 	io::{self, BufReader, Write},
 	path::Path,
+	time::{Duration, Instant},
 };
 
 use base64::Engine;
+use serde::Serialize;
 use zip::ZipArchive;
 
 use crate::{
-	config::{ConfigManager, compute_document_hash},
-	document::{self, DocumentHandle, MarkerType, ParserContext, ParserFlags},
-	export::{ExportFormat, render},
-	parser,
+	config::{ConfigManager, PositionAnchor, SavedHistoryEntry, compute_document_hash},
+	document::{
+		self, DocumentHandle, Landmark, LineLayout, Marker, MarkerType, MetadataField, ParserContext, ParserFlags,
+		TextDirection,
+	},
+	export::{self, ExportFormat, render},
+	parser::{
+		self,
+		error::{ParserError, ParserErrorCode},
+	},
 	reader_core::{
-		SearchOptions, bookmark_navigate, encode_url_fragment, history_go_next, history_go_previous,
-		nearest_fragment_before, reader_container_navigate, reader_navigate, reader_search_with_wrap,
-		record_history_position, resolve_link,
+		BrailleOptions, HeadingSearchResult, HistoryNavResult, OutlineOptions, SearchOptions, SearchPatternError,
+		StatusFields, bookmark_navigate, braille_text, encode_url_fragment, format_location, format_section_number,
+		history_go_next, history_go_previous, history_go_to,
+		import_annotations::{self, AnnotationFormat, AnnotationImport},
+		link_preview_text, nearest_fragment_before, notes_to_markdown, number_headings, outline_items, outline_text,
+		parse_section_number, position_anchor, reader_container_navigate, reader_navigate, reader_paragraph_navigate,
+		reader_search_headings, reader_search_with_wrap, reader_sentence_at, reader_sentence_navigate,
+		record_history_position, resolve_link, resolve_position_anchor, resolve_webview_url,
+		shuffle::SectionShuffle,
 	},
 	types::{self as ffi, NavDirection, NavTarget},
-	util::{encoding::convert_to_utf8, zip as zip_utils},
+	util::{
+		encoding::convert_to_utf8,
+		text::{self, display_len},
+		zip as zip_utils,
+	},
 };
 
 const MAX_HISTORY_LEN: usize = 10;
+/// How many pages past the caret a lazily loaded document keeps extracted ahead of the reader.
+const PAGE_LOOKAHEAD: usize = 10;
 const HISTORY_DISTANCE_THRESHOLD: i64 = 300;
+/// Characters of text saved with each history entry to find it again after the document changes.
+const HISTORY_ANCHOR_CHARS: usize = 40;
+/// How far, in display units, a saved history entry's text is looked for on either side of its offset.
+const HISTORY_ANCHOR_WINDOW: usize = 2000;
+/// How long the caret has to stay past the furthest position read before it counts, so a search hit
+/// or link far ahead that the reader leaves straight away isn't taken as read.
+const FURTHEST_READ_DWELL: Duration = Duration::from_secs(5);
+
+/// How far the reader has got, and since when the caret has been beyond that.
+#[derive(Debug, Default)]
+struct FurthestRead {
+	position: i64,
+	ahead_since: Option<Instant>,
+}
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct SearchOptionsFfi {
@@ -115,6 +150,15 @@ pub struct LinkActivationResult {
 	pub url: String,
 }
 
+/// What a link leads to, shown without following it. `url` is set for external links, whose
+/// `text` is the address itself; otherwise `text` is taken from the link's target.
+#[derive(Debug, Clone)]
+pub struct LinkPreview {
+	pub title: String,
+	pub text: String,
+	pub url: String,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LinkAction {
 	Internal,
@@ -137,6 +181,8 @@ pub enum SegmentTypeFfi {
 	Separator,
 	Image,
 	Figure,
+	BlockQuote,
+	CodeBlock,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -155,13 +201,19 @@ pub struct TextSegmentFfi {
 
 #[derive(Debug, thiserror::Error)]
 pub enum DocumentError {
-	#[error("Parse error: {0}")]
-	ParseError(String),
+	#[error("Parse error: {details}")]
+	ParseError { code: ParserErrorCode, details: String },
 }
 
 impl From<String> for DocumentError {
 	fn from(s: String) -> Self {
-		Self::ParseError(s)
+		Self::ParseError { code: ParserErrorCode::Other, details: s }
+	}
+}
+
+impl From<ParserError> for DocumentError {
+	fn from(err: ParserError) -> Self {
+		Self::ParseError { code: err.code(), details: err.to_string() }
 	}
 }
 
@@ -174,10 +226,16 @@ impl LinkActivationResult {
 pub struct DocumentSession {
 	handle: DocumentHandle,
 	file_path: String,
+	/// Web address the file was downloaded from, when it was.
+	source_url: Option<String>,
 	history: Vec<i64>,
 	history_index: usize,
 	parser_flags: ParserFlags,
 	last_stable_position: Option<i64>,
+	section_shuffle: SectionShuffle,
+	/// Whether Next Section follows a shuffled order instead of reading order.
+	shuffle_sections: bool,
+	furthest: FurthestRead,
 }
 
 #[derive(Copy, Clone)]
@@ -189,7 +247,7 @@ struct NavigateParams {
 	level_filter: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TocEntry {
 	pub title: String,
 	pub position: i64,
@@ -217,6 +275,10 @@ pub enum MarkerTypeFfi {
 	Bold,
 	Italic,
 	Underline,
+	BlockQuote,
+	CodeBlock,
+	Note,
+	Annotation,
 }
 
 impl From<MarkerType> for MarkerTypeFfi {
@@ -241,6 +303,64 @@ impl From<MarkerType> for MarkerTypeFfi {
 			MarkerType::Bold => Self::Bold,
 			MarkerType::Italic => Self::Italic,
 			MarkerType::Underline => Self::Underline,
+			MarkerType::BlockQuote => Self::BlockQuote,
+			MarkerType::CodeBlock => Self::CodeBlock,
+			MarkerType::Note => Self::Note,
+			MarkerType::Annotation => Self::Annotation,
+		}
+	}
+}
+
+/// One navigation history entry as shown in the history browser.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+	pub offset: i64,
+	pub percentage: i32,
+	/// The nearest heading at or before the entry.
+	pub heading: String,
+	/// The line the entry falls on.
+	pub snippet: String,
+	/// False when the offset is past the end of the document, e.g. after it was reloaded shorter.
+	pub available: bool,
+}
+
+/// The section Export Current Section and Copy Current Section work on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionRange {
+	/// Display offset of the section's start.
+	pub start: i64,
+	/// Display offset just past the section's end.
+	pub end: i64,
+	/// The section's first heading, or the document's title when it has none.
+	pub title: String,
+	/// True when the document has no sections, so the range is the whole document.
+	pub whole_document: bool,
+}
+
+/// A highlight or note another application left in the document's file, such as a PDF annotation.
+/// Read-only: it stays out of the document's bookmarks unless the user converts it to one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalAnnotation {
+	/// Display offset of the annotated text, or of the line a note is beside.
+	pub start: i64,
+	/// Display offset just past the annotated text; the same as `start` for a note.
+	pub end: i64,
+	pub note: String,
+	/// Who made the annotation, empty when the file doesn't say.
+	pub author: String,
+	/// The 1-based page the annotation was made on.
+	pub page: i32,
+}
+
+impl ExternalAnnotation {
+	/// The note a bookmark converted from this annotation gets: the note, after its author when
+	/// the file names one.
+	#[must_use]
+	pub fn bookmark_note(&self) -> String {
+		if self.note.is_empty() || self.author.is_empty() {
+			self.note.clone()
+		} else {
+			format!("{}: {}", self.author, self.note)
 		}
 	}
 }
@@ -255,6 +375,24 @@ pub struct LineMarker {
 	pub length: i64,
 }
 
+/// One line of Document Info metadata; `key` is the stable name of the field (see
+/// [`MetadataField::key`]).
+#[derive(Debug, Clone)]
+pub struct MetadataEntryFfi {
+	pub key: String,
+	pub value: String,
+}
+
+/// A landmark for the FFI; `kind` is the stable name of its type (see [`LandmarkKind::key`]).
+///
+/// [`LandmarkKind::key`]: document::LandmarkKind::key
+#[derive(Debug, Clone)]
+pub struct LandmarkFfi {
+	pub kind: String,
+	pub title: String,
+	pub position: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct DocumentStatsFfi {
 	pub word_count: i64,
@@ -268,6 +406,7 @@ pub struct HeadingTreeItemFfi {
 	pub offset: i64,
 	pub text: String,
 	pub parent_index: i32,
+	pub level: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -291,13 +430,16 @@ pub struct LinkListFfi {
 impl DocumentSession {
 	/// # Errors
 	///
-	/// Returns an error if the document cannot be parsed.
+	/// Returns a [`ParserError`] describing why the document cannot be parsed.
 	pub fn new(
 		file_path: &str,
 		password: &str,
 		forced_extension: &str,
 		render_tables_inline: bool,
-	) -> Result<Self, String> {
+		detect_columns: bool,
+		include_speaker_notes: bool,
+		normalize_typography: bool,
+	) -> Result<Self, ParserError> {
 		let mut context = ParserContext::new(file_path.to_string());
 		if !password.is_empty() {
 			context = context.with_password(password.to_string());
@@ -305,26 +447,97 @@ impl DocumentSession {
 		if !forced_extension.is_empty() {
 			context = context.with_forced_extension(forced_extension.to_string());
 		}
-		context = context.with_render_tables_inline(render_tables_inline);
-		let parser_flags = parser::get_parser_flags_for_context(&context);
-		let doc = parser::parse_document(&context).map_err(|e| e.to_string())?;
+		context = context
+			.with_render_tables_inline(render_tables_inline)
+			.with_detect_columns(detect_columns)
+			.with_include_speaker_notes(include_speaker_notes)
+			.with_normalize_typography(normalize_typography);
+		Self::from_context(&context)
+	}
+
+	/// Opens the document described by a fully built parser context.
+	///
+	/// # Errors
+	///
+	/// Returns a [`ParserError`] describing why the document cannot be parsed.
+	pub fn from_context(context: &ParserContext) -> Result<Self, ParserError> {
+		let parser_flags = parser::get_parser_flags_for_context(context);
+		let doc = parser::parse_document(context)?;
 		Ok(Self {
 			handle: DocumentHandle::new(doc),
-			file_path: file_path.to_string(),
+			file_path: context.file_path.clone(),
+			source_url: context.source_url.clone(),
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		})
 	}
 
+	/// Opens text copied to the clipboard as a document under the context's (pseudo) path, reading
+	/// the HTML flavor instead when there is one.
+	///
+	/// # Errors
+	///
+	/// Returns a [`ParserError`] when the clipboard holds no readable text.
+	pub fn from_clipboard(context: &ParserContext, text: &str, html: Option<&str>) -> Result<Self, ParserError> {
+		let doc = parser::clipboard::parse_clipboard(context, text, html)?;
+		Ok(Self {
+			handle: DocumentHandle::new(doc),
+			file_path: context.file_path.clone(),
+			source_url: context.source_url.clone(),
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: parser::clipboard::clipboard_parser_flags(html.is_some()),
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		})
+	}
+
+	/// An empty stand-in titled `title` for a document that hasn't been parsed yet, so it can have a
+	/// tab before it's read.
+	#[must_use]
+	pub fn placeholder(file_path: &str, title: &str) -> Self {
+		let mut doc = document::Document::new();
+		doc.title = title.to_string();
+		Self {
+			handle: DocumentHandle::new(doc),
+			file_path: file_path.to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		}
+	}
+
 	pub fn new_ffi(
 		file_path: String,
 		password: String,
 		forced_extension: String,
 		render_tables_inline: bool,
+		detect_columns: bool,
+		include_speaker_notes: bool,
+		normalize_typography: bool,
 	) -> Result<Self, DocumentError> {
-		Self::new(&file_path, &password, &forced_extension, render_tables_inline).map_err(DocumentError::ParseError)
+		Self::new(
+			&file_path,
+			&password,
+			&forced_extension,
+			render_tables_inline,
+			detect_columns,
+			include_speaker_notes,
+			normalize_typography,
+		)
+		.map_err(DocumentError::from)
 	}
 
 	/// The parsed document handle backing this session.
@@ -338,6 +551,13 @@ impl DocumentSession {
 		&self.file_path
 	}
 
+	/// The path saved state such as bookmarks is kept under: the web address a downloaded document
+	/// came from, otherwise its file path.
+	#[must_use]
+	pub fn document_path(&self) -> &str {
+		self.source_url.as_deref().unwrap_or(&self.file_path)
+	}
+
 	#[must_use]
 	pub fn title(&self) -> String {
 		self.handle.document().title.clone()
@@ -348,9 +568,135 @@ impl DocumentSession {
 		self.handle.document().author.clone()
 	}
 
+	/// ISO 639-1 code of the document's detected language, or an empty string when unknown.
+	#[must_use]
+	pub fn language(&self) -> String {
+		self.handle.document().language.clone()
+	}
+
+	/// Direction detected for the document's text.
+	#[must_use]
+	pub fn text_direction(&self) -> TextDirection {
+		self.handle.document().direction
+	}
+
+	/// Whether the document's lines look like they should keep their breaks; see [`LineLayout::detect`].
+	#[must_use]
+	pub fn detect_line_layout(&self, max_line_length: usize) -> LineLayout {
+		LineLayout::detect(&self.handle.document().buffer.content, max_line_length)
+	}
+
+	/// Series, publisher, date, language, subjects and identifiers read from the file, in display
+	/// order, with repeated fields joined into one value.
+	#[must_use]
+	pub fn document_metadata(&self) -> Vec<(MetadataField, String)> {
+		self.handle.document().metadata_entries()
+	}
+
+	/// The preface, bibliography, index and other landmarks the file marks, in reading order.
+	#[must_use]
+	pub fn landmarks(&self) -> Vec<Landmark> {
+		let mut landmarks = self.handle.document().landmarks.clone();
+		landmarks.sort_by_key(|landmark| landmark.offset);
+		landmarks
+	}
+
+	/// Whether quotes, dashes and special spaces were replaced with plain characters at parse time.
+	#[must_use]
+	pub fn typography_normalized(&self) -> bool {
+		self.handle.document().typography_normalized
+	}
+
+	/// Running headers removed at parse time, with page numbers written as "N".
+	#[must_use]
+	pub fn running_headers(&self) -> Vec<String> {
+		self.handle.document().running_lines.as_ref().map(|lines| lines.headers.clone()).unwrap_or_default()
+	}
+
+	/// Running footers removed at parse time, with page numbers written as "N".
+	#[must_use]
+	pub fn running_footers(&self) -> Vec<String> {
+		self.handle.document().running_lines.as_ref().map(|lines| lines.footers.clone()).unwrap_or_default()
+	}
+
+	/// The document text. While a lazily loaded PDF is still being read this is only the text of the
+	/// pages extracted so far, and it grows as [`Self::apply_loaded_pages`] appends more.
+	#[must_use]
+	pub fn content_ref(&self) -> &str {
+		self.handle.content_ref()
+	}
+
+	/// A copy of [`Self::content_ref`], for the bindings, which can't borrow from the session.
 	#[must_use]
 	pub fn content(&self) -> String {
-		self.handle.document().buffer.content.clone()
+		self.content_ref().to_string()
+	}
+
+	/// Length of `content()` in display units, the unit of every position in the document, so it can
+	/// be set beside a caret offset. The buffer counts it as the text is built and grows it as pages
+	/// load, so this never rescans a large book.
+	#[must_use]
+	pub fn char_length(&self) -> usize {
+		self.handle.document().buffer.current_position()
+	}
+
+	/// Whether every page is in `content()`.
+	#[must_use]
+	pub const fn is_fully_loaded(&self) -> bool {
+		self.handle.is_fully_loaded()
+	}
+
+	/// Whether pages the reader is heading towards are still being extracted.
+	#[must_use]
+	pub fn is_loading_pages(&self) -> bool {
+		self.handle.is_loading_pages()
+	}
+
+	#[must_use]
+	pub fn loaded_page_count(&self) -> usize {
+		self.handle.loaded_pages()
+	}
+
+	/// Starts extracting the next pages in the background once the caret gets close to the end of
+	/// the loaded text.
+	pub fn request_pages_near(&self, position: i64) {
+		if self.handle.is_fully_loaded() {
+			return;
+		}
+		let page = usize::try_from(self.current_page(position)).unwrap_or(0);
+		if page + PAGE_LOOKAHEAD > self.handle.loaded_pages() {
+			self.handle.ensure_pages_loaded(page..page + PAGE_LOOKAHEAD);
+		}
+	}
+
+	/// Appends the pages extracted since the last call. Returns the text added to `content()`.
+	pub fn apply_loaded_pages(&mut self) -> Option<String> {
+		self.handle.apply_loaded_pages()
+	}
+
+	/// Loads every page up to and including `page_number` (1-based) before returning, for jumps
+	/// past the loaded text. Returns the text added to `content()`.
+	pub fn load_through_page(&mut self, page_number: i32) -> Option<String> {
+		let pages = usize::try_from(page_number).unwrap_or(0);
+		self.handle.load_pages_now(0..pages)
+	}
+
+	/// Loads pages until `position` falls inside the loaded text or the document is complete.
+	pub fn load_through_position(&mut self, position: i64) -> Option<String> {
+		let position = usize::try_from(position.max(0)).unwrap_or(0);
+		let mut appended = String::new();
+		while !self.handle.is_fully_loaded() && self.handle.document().buffer.current_position() <= position {
+			let pages = self.handle.loaded_pages() + PAGE_LOOKAHEAD;
+			if let Some(text) = self.handle.load_pages_now(0..pages) {
+				appended.push_str(&text);
+			}
+		}
+		(!appended.is_empty()).then_some(appended)
+	}
+
+	/// Loads every remaining page before returning.
+	pub fn load_all_pages(&mut self) -> Option<String> {
+		self.handle.load_pages_now(0..usize::MAX)
 	}
 
 	#[must_use]
@@ -368,6 +714,63 @@ impl DocumentSession {
 		self.history_index = index.min(self.history.len().saturating_sub(1));
 	}
 
+	/// The history in the form it's saved in, with each entry's percentage and anchor text.
+	#[must_use]
+	pub fn saved_history(&self) -> (Vec<SavedHistoryEntry>, usize) {
+		let buf = &self.handle.document().buffer;
+		let document_len = buf.current_position();
+		let entries = self
+			.history
+			.iter()
+			.map(|&offset| {
+				let display = usize::try_from(offset).unwrap_or(0).min(document_len);
+				#[allow(clippy::cast_precision_loss)]
+				let percent = if document_len == 0 { 0.0 } else { display as f64 * 100.0 / document_len as f64 };
+				let start = buf.byte_index_for_display(display);
+				let end = buf.content[start..]
+					.char_indices()
+					.nth(HISTORY_ANCHOR_CHARS)
+					.map_or(buf.content.len(), |(i, _)| start + i);
+				let anchor = text::snippet_at(&buf.content, start, end).to_string();
+				SavedHistoryEntry { offset, percent, anchor }
+			})
+			.collect();
+		(entries, self.history_index)
+	}
+
+	/// Restores a saved history. An entry whose anchor text is still near its offset goes to where
+	/// the text now is; one whose text moved away or is gone goes to the same percentage of the
+	/// document instead, so a changed file doesn't send Go Back to an unrelated spot mid-word.
+	pub fn restore_history(&mut self, entries: &[SavedHistoryEntry], index: usize) {
+		let positions: Vec<i64> = entries.iter().map(|entry| self.resolve_saved_history_entry(entry)).collect();
+		self.set_history(&positions, index);
+	}
+
+	fn resolve_saved_history_entry(&self, entry: &SavedHistoryEntry) -> i64 {
+		let buf = &self.handle.document().buffer;
+		let document_len = buf.current_position();
+		let offset = usize::try_from(entry.offset).unwrap_or(0);
+		// Entries saved as bare offsets have nothing to check, and text past what a lazily loaded
+		// document has extracted so far can't be checked yet.
+		if entry.anchor.is_empty() || (offset > document_len && !self.is_fully_loaded()) {
+			return entry.offset;
+		}
+		let window_start = buf.byte_index_for_display(offset.saturating_sub(HISTORY_ANCHOR_WINDOW));
+		let window_end = buf.byte_index_for_display(offset + HISTORY_ANCHOR_WINDOW + display_len(&entry.anchor));
+		let center = buf.byte_index_for_display(offset);
+		let nearest = buf.content[window_start..window_end]
+			.match_indices(entry.anchor.as_str())
+			.map(|(found, _)| window_start + found)
+			.min_by_key(|&found| found.abs_diff(center));
+		if let Some(found) = nearest {
+			return i64::try_from(buf.display_index_for_byte(found)).unwrap_or(entry.offset);
+		}
+		#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+		let fallback = (entry.percent.clamp(0.0, 100.0) / 100.0 * document_len as f64).round() as usize;
+		let fallback = buf.display_index_for_byte(buf.byte_index_for_display(fallback));
+		i64::try_from(fallback).unwrap_or(entry.offset)
+	}
+
 	pub fn check_and_record_history(&mut self, new_position: i64) {
 		if let Some(last_pos) = self.last_stable_position {
 			let distance = (new_position - last_pos).abs();
@@ -384,6 +787,32 @@ impl DocumentSession {
 		self.last_stable_position = Some(position);
 	}
 
+	/// Starts from the furthest position saved for the document.
+	pub const fn set_furthest_position(&mut self, position: i64) {
+		self.furthest = FurthestRead { position, ahead_since: None };
+	}
+
+	#[must_use]
+	pub const fn furthest_position(&self) -> i64 {
+		self.furthest.position
+	}
+
+	/// Notes the caret at `position`. Once it has stayed past the furthest position read for
+	/// [`FURTHEST_READ_DWELL`], wherever it is then becomes the furthest position, which is returned
+	/// so it can be saved. Going back to or before it starts the wait over.
+	pub fn track_furthest(&mut self, position: i64, now: Instant) -> Option<i64> {
+		if position <= self.furthest.position {
+			self.furthest.ahead_since = None;
+			return None;
+		}
+		let since = *self.furthest.ahead_since.get_or_insert(now);
+		if now.saturating_duration_since(since) < FURTHEST_READ_DWELL {
+			return None;
+		}
+		self.set_furthest_position(position);
+		Some(position)
+	}
+
 	const fn nav_direction(next: bool) -> NavDirection {
 		if next { NavDirection::Next } else { NavDirection::Previous }
 	}
@@ -436,6 +865,79 @@ impl DocumentSession {
 		)
 	}
 
+	/// Start offsets of the document's sections, as Random Section, shuffled reading and the current
+	/// section exports see them: the section breaks, or the top-level headings of a document without any.
+	fn section_starts(&self) -> Vec<usize> {
+		let markers = &self.handle.document().buffer.markers;
+		let of_type = |mtype: MarkerType| -> Vec<usize> {
+			markers.iter().filter(|marker| marker.mtype == mtype).map(|marker| marker.position).collect()
+		};
+		let sections = if self.parser_flags.contains(ParserFlags::SUPPORTS_SECTIONS) {
+			of_type(MarkerType::SectionBreak)
+		} else {
+			Vec::new()
+		};
+		if sections.is_empty() { of_type(MarkerType::Heading1) } else { sections }
+	}
+
+	fn shuffle_result(&self, targets: &[usize], index: usize) -> NavigationResult {
+		let offset = i64::try_from(targets[index]).unwrap_or(0);
+		let mut result = NavigationResult::not_found();
+		result.found = true;
+		result.offset = offset;
+		result.marker_index = i32::try_from(index).unwrap_or(-1);
+		result.marker_text = self
+			.handle
+			.document()
+			.buffer
+			.markers
+			.iter()
+			.find(|marker| marker.position == targets[index] && !marker.text.is_empty())
+			.map(|marker| marker.text.clone())
+			.unwrap_or_default();
+		self.fill_marker_text_if_empty(&mut result);
+		result
+	}
+
+	fn shuffle_pick(
+		&mut self,
+		position: i64,
+		pick: impl FnOnce(&mut SectionShuffle, usize, Option<usize>) -> Option<usize>,
+	) -> NavigationResult {
+		let targets = self.section_starts();
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		let current = targets.iter().rposition(|&offset| offset <= pos);
+		match pick(&mut self.section_shuffle, targets.len(), current) {
+			Some(index) => self.shuffle_result(&targets, index),
+			None => NavigationResult::not_supported(),
+		}
+	}
+
+	/// Jumps to a section other than the one holding `position`, each equally likely. Reports
+	/// `not_supported` when the document has fewer than two sections.
+	pub fn random_section(&mut self, position: i64) -> NavigationResult {
+		self.shuffle_pick(position, SectionShuffle::random)
+	}
+
+	/// The next section of the shuffled order, visiting every section once before any repeats.
+	/// Reports `not_supported` when the document has fewer than two sections.
+	pub fn shuffle_next_section(&mut self, position: i64) -> NavigationResult {
+		self.shuffle_pick(position, SectionShuffle::next)
+	}
+
+	#[must_use]
+	pub const fn section_shuffle_enabled(&self) -> bool {
+		self.shuffle_sections
+	}
+
+	/// Turns shuffled section reading on or off; turning it on starts a fresh order.
+	pub fn set_section_shuffle(&mut self, enabled: bool) {
+		if enabled && !self.shuffle_sections {
+			self.section_shuffle.reset();
+		}
+		self.shuffle_sections = enabled;
+	}
+
 	#[must_use]
 	pub fn navigate_heading(&self, position: i64, wrap: bool, next: bool, level: i32) -> NavigationResult {
 		let is_supported = self.has_headings(if level > 0 { Some(level) } else { None });
@@ -522,6 +1024,27 @@ impl DocumentSession {
 		NavigationResult::from_nav_result(&result)
 	}
 
+	/// Reads bookmarks and highlights exported by Calibre or Moon+ Reader and adds the ones found in
+	/// this document as bookmarks; ranges that are already bookmarked are left alone. `None` when
+	/// the file can't be read or isn't a format those apps export.
+	pub fn import_annotations(&self, config: &ConfigManager, import_path: &str) -> Option<AnnotationImport> {
+		let format = AnnotationFormat::from_path(import_path)?;
+		let content = fs::read_to_string(import_path).ok()?;
+		let import = import_annotations::import_annotations(&self.handle, format, &content)?;
+		for annotation in &import.annotations {
+			config.add_bookmark(self.document_path(), annotation.start, annotation.end, &annotation.note);
+		}
+		Some(import)
+	}
+
+	/// The structural region (list, table, block quote or code block) the caret is in, for
+	/// announcing when arrowing moves into or out of one.
+	#[must_use]
+	pub fn structure_region(&self, position: i64) -> Option<document::StructureRegion> {
+		let pos_usize = usize::try_from(position.max(0)).unwrap_or(0);
+		self.handle.structure_region(pos_usize)
+	}
+
 	#[must_use]
 	pub fn navigate_separator(&self, position: i64, wrap: bool, next: bool) -> NavigationResult {
 		let is_supported = self.has_marker(MarkerType::Separator);
@@ -558,6 +1081,65 @@ impl DocumentSession {
 		)
 	}
 
+	#[must_use]
+	pub fn navigate_block_quote(&self, position: i64, wrap: bool, next: bool) -> NavigationResult {
+		let is_supported = self.has_marker(MarkerType::BlockQuote);
+		self.navigate_with_post(
+			NavigateParams { position, wrap, next, target: NavTarget::BlockQuote, level_filter: 0 },
+			is_supported,
+			|s, nav_result| {
+				s.fill_marker_text_if_empty(nav_result);
+			},
+		)
+	}
+
+	#[must_use]
+	pub fn navigate_code_block(&self, position: i64, wrap: bool, next: bool) -> NavigationResult {
+		let is_supported = self.has_marker(MarkerType::CodeBlock);
+		self.navigate_with_post(
+			NavigateParams { position, wrap, next, target: NavTarget::CodeBlock, level_filter: 0 },
+			is_supported,
+			|s, nav_result| {
+				s.fill_marker_text_if_empty(nav_result);
+			},
+		)
+	}
+
+	#[must_use]
+	pub fn next_sentence(&self, position: i64) -> NavigationResult {
+		NavigationResult::from_nav_result(&reader_sentence_navigate(&self.handle, position, true))
+	}
+
+	#[must_use]
+	pub fn previous_sentence(&self, position: i64) -> NavigationResult {
+		NavigationResult::from_nav_result(&reader_sentence_navigate(&self.handle, position, false))
+	}
+
+	/// The word at or just before `position`, or an empty string between words.
+	#[must_use]
+	pub fn word_at(&self, position: i64) -> String {
+		let buffer = &self.handle.document().buffer;
+		let byte_idx = buffer.byte_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
+		text::word_at(&buffer.content, byte_idx).map(|range| buffer.content[range].to_string()).unwrap_or_default()
+	}
+
+	/// Start, end and text of the sentence at `position`, for selecting it in the text control.
+	#[must_use]
+	pub fn sentence_at(&self, position: i64) -> Option<(i64, i64, String)> {
+		let (start, end, text) = reader_sentence_at(&self.handle, position)?;
+		Some((i64::try_from(start).ok()?, i64::try_from(end).ok()?, text))
+	}
+
+	#[must_use]
+	pub fn next_paragraph(&self, position: i64) -> NavigationResult {
+		NavigationResult::from_nav_result(&reader_paragraph_navigate(&self.handle, position, true))
+	}
+
+	#[must_use]
+	pub fn previous_paragraph(&self, position: i64) -> NavigationResult {
+		NavigationResult::from_nav_result(&reader_paragraph_navigate(&self.handle, position, false))
+	}
+
 	fn navigate_bookmark_inner(
 		&self,
 		config: &ConfigManager,
@@ -565,8 +1147,9 @@ impl DocumentSession {
 		wrap: bool,
 		next: bool,
 		notes_only: bool,
+		category: Option<&str>,
 	) -> NavigationResult {
-		let result = bookmark_navigate(config, &self.file_path, position, wrap, next, notes_only);
+		let result = bookmark_navigate(config, self.document_path(), position, wrap, next, notes_only, category);
 		if result.found {
 			NavigationResult {
 				found: true,
@@ -584,12 +1167,76 @@ impl DocumentSession {
 
 	#[must_use]
 	pub fn navigate_bookmark(&self, config: &ConfigManager, position: i64, wrap: bool, next: bool) -> NavigationResult {
-		self.navigate_bookmark_inner(config, position, wrap, next, false)
+		self.navigate_bookmark_inner(config, position, wrap, next, false, None)
+	}
+
+	/// Like [`Self::navigate_bookmark`], but only visits bookmarks in `category`.
+	#[must_use]
+	pub fn navigate_bookmark_in_category(
+		&self,
+		config: &ConfigManager,
+		position: i64,
+		wrap: bool,
+		next: bool,
+		category: &str,
+	) -> NavigationResult {
+		self.navigate_bookmark_inner(config, position, wrap, next, false, Some(category))
 	}
 
 	#[must_use]
 	pub fn navigate_note(&self, config: &ConfigManager, position: i64, wrap: bool, next: bool) -> NavigationResult {
-		self.navigate_bookmark_inner(config, position, wrap, next, true)
+		self.navigate_bookmark_inner(config, position, wrap, next, true, None)
+	}
+
+	/// Highlights and notes other applications left in the file, in document order.
+	#[must_use]
+	pub fn external_annotations(&self) -> Vec<ExternalAnnotation> {
+		self.handle
+			.document()
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| marker.mtype == MarkerType::Annotation)
+			.map(|marker| ExternalAnnotation {
+				start: i64::try_from(marker.position).unwrap_or(i64::MAX),
+				end: i64::try_from(marker.position + marker.length).unwrap_or(i64::MAX),
+				note: marker.text.clone(),
+				author: marker.reference.clone(),
+				page: marker.level,
+			})
+			.collect()
+	}
+
+	/// Moves to the next or previous external annotation. `marker_index` is its index in
+	/// [`Self::external_annotations`] and `marker_text` its note.
+	#[must_use]
+	pub fn navigate_annotation(&self, position: i64, wrap: bool, next: bool) -> NavigationResult {
+		let annotations = self.external_annotations();
+		let find = |from: i64| {
+			if next {
+				annotations.iter().position(|annotation| annotation.start > from)
+			} else {
+				annotations.iter().rposition(|annotation| annotation.start < from)
+			}
+		};
+		let (index, wrapped) = match find(position) {
+			Some(index) => (index, false),
+			None if wrap => match find(if next { -1 } else { i64::MAX }) {
+				Some(index) => (index, true),
+				None => return NavigationResult::not_found(),
+			},
+			None => return NavigationResult::not_found(),
+		};
+		let annotation = &annotations[index];
+		NavigationResult {
+			found: true,
+			wrapped,
+			offset: annotation.start,
+			marker_text: annotation.note.clone(),
+			marker_level: annotation.page,
+			marker_index: i32::try_from(index).unwrap_or(-1),
+			not_supported: false,
+		}
 	}
 
 	#[must_use]
@@ -598,7 +1245,7 @@ impl DocumentSession {
 		config: &ConfigManager,
 		position: i64,
 	) -> ffi::BookmarkDisplayAtPosition {
-		let bookmark = config.get_bookmarks(&self.file_path).into_iter().find(|bm| bm.start == position);
+		let bookmark = config.get_bookmarks(self.document_path()).into_iter().find(|bm| bm.start == position);
 		let Some(bookmark) = bookmark else {
 			return ffi::BookmarkDisplayAtPosition { found: false, note: String::new(), snippet: String::new() };
 		};
@@ -629,6 +1276,82 @@ impl DocumentSession {
 		ffi::LinkList { items, closest_index }
 	}
 
+	/// Tables and lists in document order, for the Elements dialog's structure view. Nested lists
+	/// each get their own entry.
+	#[must_use]
+	pub fn structure_list(&self, position: i64) -> ffi::StructureList {
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		let mut closest_index = -1;
+		let mut items = Vec::new();
+		for marker in &self.handle.document().buffer.markers {
+			let kind = match marker.mtype {
+				MarkerType::Table => ffi::StructureKind::Table,
+				MarkerType::List => ffi::StructureKind::List,
+				_ => continue,
+			};
+			let text = if marker.text.is_empty() {
+				self.get_line_text(i64::try_from(marker.position).unwrap_or(0))
+			} else {
+				marker.text.clone()
+			};
+			if marker.position <= pos {
+				closest_index = i32::try_from(items.len()).unwrap_or(-1);
+			}
+			items.push(ffi::StructureListItem { offset: marker.position, text, kind });
+		}
+		ffi::StructureList { items, closest_index }
+	}
+
+	/// Figures and the images outside them, in document order, for the Elements dialog's figures
+	/// view and the figure count in Document Info. An image that a figure wraps is listed once, as
+	/// the figure.
+	#[must_use]
+	pub fn figure_list(&self, position: i64) -> ffi::StructureList {
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		let markers = &self.handle.document().buffer.markers;
+		let figure_offsets: BTreeSet<usize> =
+			markers.iter().filter(|marker| marker.mtype == MarkerType::Figure).map(|marker| marker.position).collect();
+		let mut closest_index = -1;
+		let mut items = Vec::new();
+		for marker in markers {
+			let kind = match marker.mtype {
+				MarkerType::Figure => ffi::StructureKind::Figure,
+				MarkerType::Image if !figure_offsets.contains(&marker.position) => ffi::StructureKind::Image,
+				_ => continue,
+			};
+			let text = if marker.text.is_empty() {
+				self.get_line_text(i64::try_from(marker.position).unwrap_or(0))
+			} else {
+				marker.text.clone()
+			};
+			if marker.position <= pos {
+				closest_index = i32::try_from(items.len()).unwrap_or(-1);
+			}
+			items.push(ffi::StructureListItem { offset: marker.position, text, kind });
+		}
+		ffi::StructureList { items, closest_index }
+	}
+
+	/// Heading and link markers, in document order, for exposing the outline to assistive technology.
+	#[must_use]
+	pub fn get_structure_markers(&self) -> Vec<LineMarker> {
+		self.handle
+			.document()
+			.buffer
+			.markers
+			.iter()
+			.filter(|m| document::is_heading_marker(m.mtype) || m.mtype == MarkerType::Link)
+			.map(|m| LineMarker {
+				mtype: m.mtype.into(),
+				position: i64::try_from(m.position).unwrap_or(0),
+				text: m.text.clone(),
+				reference: m.reference.clone(),
+				level: m.level,
+				length: i64::try_from(m.length).unwrap_or(0),
+			})
+			.collect()
+	}
+
 	#[must_use]
 	pub fn get_formatting_markers(&self) -> Vec<LineMarker> {
 		self.handle
@@ -671,7 +1394,7 @@ impl DocumentSession {
 			} else {
 				marker.text.clone()
 			};
-			items.push(ffi::HeadingTreeItem { offset: marker.position, text, parent_index });
+			items.push(ffi::HeadingTreeItem { offset: marker.position, text, parent_index, level });
 			item_stack.push((level, current_index));
 			if marker.position <= pos {
 				let dist = pos - marker.position;
@@ -695,6 +1418,7 @@ impl DocumentSession {
 					offset: i64::try_from(i.offset).unwrap_or(i64::MAX),
 					text: i.text,
 					parent_index: i.parent_index,
+					level: i.level,
 				})
 				.collect(),
 			closest_index: tree.closest_index,
@@ -723,6 +1447,10 @@ impl DocumentSession {
 		} else {
 			history_go_previous(&self.history, self.history_index, current_pos, MAX_HISTORY_LEN)
 		};
+		self.apply_history_result(result)
+	}
+
+	fn apply_history_result(&mut self, result: HistoryNavResult) -> NavigationResult {
 		self.history = result.positions;
 		self.history_index = result.index;
 		if result.found {
@@ -748,28 +1476,65 @@ impl DocumentSession {
 		self.history_navigate(current_pos, true)
 	}
 
+	/// Jumps to the history entry at `entry` (oldest first), keeping the forward history intact.
+	/// Entries past the end of the document are not jumped to.
+	pub fn history_go_to(&mut self, current_pos: i64, entry: usize) -> NavigationResult {
+		let document_len = self.handle.document().buffer.current_position();
+		let available =
+			self.history.get(entry).is_some_and(|&offset| usize::try_from(offset).is_ok_and(|o| o <= document_len));
+		if !available {
+			return NavigationResult::not_found();
+		}
+		let result = history_go_to(&self.history, self.history_index, entry, current_pos, MAX_HISTORY_LEN);
+		self.apply_history_result(result)
+	}
+
+	/// Describes each navigation history entry, oldest first, for the history browser.
 	#[must_use]
-	pub fn activate_link(&self, position: i64) -> LinkActivationResult {
+	pub fn history_entries(&self) -> Vec<HistoryEntry> {
+		let document_len = self.handle.document().buffer.current_position();
+		self.history
+			.iter()
+			.map(|&offset| {
+				let available = usize::try_from(offset).is_ok_and(|o| o <= document_len);
+				if !available {
+					return HistoryEntry {
+						offset,
+						percentage: 0,
+						heading: String::new(),
+						snippet: String::new(),
+						available,
+					};
+				}
+				HistoryEntry {
+					offset,
+					percentage: self.get_status_info(offset).percentage,
+					heading: self.heading_at(offset),
+					snippet: self.get_line_text(offset).trim().to_string(),
+					available,
+				}
+			})
+			.collect()
+	}
+
+	/// The link whose text the caret at `position` is on, if it has a target.
+	fn link_at(&self, position: i64) -> Option<&Marker> {
 		let pos_usize = usize::try_from(position.max(0)).unwrap_or(0);
-		let href = {
-			let link_index = self.handle.current_marker_index(pos_usize, MarkerType::Link);
-			let Some(link_index) = link_index else {
-				return LinkActivationResult::not_found();
-			};
-			let Some(marker) = self.handle.document().buffer.markers.get(link_index) else {
-				return LinkActivationResult::not_found();
-			};
-			let link_end = marker.position + marker.text.chars().count();
-			if pos_usize < marker.position || pos_usize > link_end {
-				return LinkActivationResult::not_found();
-			}
-			if marker.reference.is_empty() {
-				return LinkActivationResult::not_found();
-			}
-			// Clone the href so we can drop the borrow on self.handle.
-			marker.reference.clone()
+		let link_index = self.handle.current_marker_index(pos_usize, MarkerType::Link)?;
+		let marker = self.handle.document().buffer.markers.get(link_index)?;
+		let link_end = marker.position + display_len(&marker.text);
+		if pos_usize < marker.position || pos_usize > link_end || marker.reference.is_empty() {
+			return None;
+		}
+		Some(marker)
+	}
+
+	#[must_use]
+	pub fn activate_link(&self, position: i64) -> LinkActivationResult {
+		let Some(marker) = self.link_at(position) else {
+			return LinkActivationResult::not_found();
 		};
-		let resolution = resolve_link(&self.handle, &href, position);
+		let resolution = resolve_link(&self.handle, &marker.reference, position);
 		if !resolution.found {
 			LinkActivationResult::not_found()
 		} else if resolution.is_external {
@@ -789,6 +1554,47 @@ impl DocumentSession {
 		self.activate_link(position)
 	}
 
+	/// Previews the link at `position` without moving there. Returns `None` when the caret isn't
+	/// on a link or its target can't be found.
+	#[must_use]
+	pub fn link_preview(&self, position: i64) -> Option<LinkPreview> {
+		let marker = self.link_at(position)?;
+		let resolution = resolve_link(&self.handle, &marker.reference, position);
+		if !resolution.found {
+			return None;
+		}
+		let title = marker.text.trim().to_string();
+		if resolution.is_external {
+			return Some(LinkPreview { title, text: resolution.url.clone(), url: resolution.url });
+		}
+		Some(LinkPreview { title, text: link_preview_text(&self.handle, resolution.offset), url: String::new() })
+	}
+
+	#[must_use]
+	pub fn link_preview_ffi(&self, position: i64) -> Option<LinkPreview> {
+		self.link_preview(position)
+	}
+
+	#[must_use]
+	pub fn document_metadata_ffi(&self) -> Vec<MetadataEntryFfi> {
+		self.document_metadata()
+			.into_iter()
+			.map(|(field, value)| MetadataEntryFfi { key: field.key().to_string(), value })
+			.collect()
+	}
+
+	#[must_use]
+	pub fn document_landmarks(&self) -> Vec<LandmarkFfi> {
+		self.landmarks()
+			.into_iter()
+			.map(|landmark| LandmarkFfi {
+				kind: landmark.kind.key().to_string(),
+				title: landmark.title,
+				position: i64::try_from(landmark.offset).unwrap_or(0),
+			})
+			.collect()
+	}
+
 	#[must_use]
 	pub fn get_stats_ffi(&self) -> DocumentStatsFfi {
 		let s = self.stats();
@@ -855,6 +1661,14 @@ impl DocumentSession {
 			supported.push(SegmentTypeFfi::Figure);
 		}
 
+		if self.has_marker(MarkerType::BlockQuote) {
+			supported.push(SegmentTypeFfi::BlockQuote);
+		}
+
+		if self.has_marker(MarkerType::CodeBlock) {
+			supported.push(SegmentTypeFfi::CodeBlock);
+		}
+
 		supported
 	}
 
@@ -874,9 +1688,26 @@ impl DocumentSession {
 			search_options.insert(SearchOptions::FORWARD);
 		}
 
-		let result =
-			reader_search_with_wrap(&self.handle.document().buffer.content, &query, start_position, search_options);
-		SearchResultFfi { found: result.found, wrapped: result.wrapped, position: result.position }
+		reader_search_with_wrap(&self.handle.document().buffer.content, &query, start_position, search_options).map_or(
+			SearchResultFfi { found: false, wrapped: false, position: -1 },
+			|result| SearchResultFfi { found: result.found, wrapped: result.wrapped, position: result.position },
+		)
+	}
+
+	/// Finds the next heading or table of contents entry from `start` whose text matches `query`; see
+	/// [`reader_search_headings`].
+	///
+	/// # Errors
+	///
+	/// Returns the reason when `query` can't be used as a pattern.
+	pub fn search_headings(
+		&self,
+		query: &str,
+		start: i64,
+		options: SearchOptions,
+	) -> Result<Option<HeadingSearchResult>, SearchPatternError> {
+		let doc = self.handle.document();
+		reader_search_headings(&doc.buffer.markers, &doc.toc_items, query, start, options)
 	}
 
 	#[must_use]
@@ -899,6 +1730,11 @@ impl DocumentSession {
 		i32::try_from(self.page_count()).unwrap_or(0)
 	}
 
+	#[must_use]
+	pub fn page_for_input_ffi(&self, input: String) -> Option<i32> {
+		self.page_for_input(&input)
+	}
+
 	#[must_use]
 	pub fn page_offset_ffi(&self, page: i32) -> i64 {
 		self.page_offset(page)
@@ -998,6 +1834,14 @@ impl DocumentSession {
 		nearest_fragment_before(&self.handle, pos).map(|id| encode_url_fragment(&id))
 	}
 
+	/// Returns the text position of the element a web view URL's `#fragment` points at, so the
+	/// caret can follow internal links clicked in the web view. `None` leaves the caret alone.
+	#[must_use]
+	pub fn webview_url_position(&self, url: &str, position: i64) -> Option<i64> {
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		resolve_webview_url(&self.handle, url, pos).and_then(|offset| i64::try_from(offset).ok())
+	}
+
 	/// Returns true when the document's underlying source can be shown as text.
 	#[must_use]
 	pub fn source_view_available(&self) -> bool {
@@ -1028,7 +1872,7 @@ impl DocumentSession {
 		Some(SourceView { path: output_path.to_string_lossy().to_string(), caret: i64::try_from(caret).unwrap_or(0) })
 	}
 
-	/// Returns `(source_text, caret_char_offset, file_name)` for the document at
+	/// Returns `(source_text, caret_display_offset, file_name)` for the document at
 	/// `position`. The caret is mapped into the returned source text.
 	fn source_content_for_position(&self, position: i64) -> Option<(String, usize, String)> {
 		let pos = usize::try_from(position.max(0)).unwrap_or(0);
@@ -1060,7 +1904,7 @@ impl DocumentSession {
 	fn xml_caret(content: &str, relative: usize) -> usize {
 		parser::xml_to_text::XmlToText::new()
 			.find_anchor_byte_offset(content, relative)
-			.and_then(|byte| Some(content.get(..byte)?.chars().count()))
+			.and_then(|byte| Some(display_len(content.get(..byte)?)))
 			.unwrap_or(0)
 	}
 
@@ -1070,7 +1914,7 @@ impl DocumentSession {
 		nearest_fragment_before(&self.handle, pos)
 			.and_then(|id| id.strip_prefix("pb-block-").and_then(|n| n.parse::<usize>().ok()))
 			.and_then(|index| parser::markdown::block_source_offset(content, index))
-			.and_then(|byte| Some(content.get(..byte)?.chars().count()))
+			.and_then(|byte| Some(display_len(content.get(..byte)?)))
 			.unwrap_or(0)
 	}
 
@@ -1101,6 +1945,55 @@ impl DocumentSession {
 		Ok(())
 	}
 
+	/// Exports the text of `section`, with its formatting, to a file.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the file cannot be written.
+	pub fn export_section_as(&self, output_path: &str, format: ExportFormat, section: &SectionRange) -> io::Result<()> {
+		let start = usize::try_from(section.start.max(0)).unwrap_or(0);
+		let end = usize::try_from(section.end.max(0)).unwrap_or(0);
+		fs::write(output_path, export::render_range(&self.handle, format, start, end, &section.title).as_bytes())
+	}
+
+	/// Writes the document to `output_path` as plain text laid out for braille embossing.
+	pub fn export_braille(&self, output_path: &str, options: &BrailleOptions) -> io::Result<()> {
+		let doc = self.handle.document();
+		fs::write(output_path, braille_text(&doc.buffer.content, &doc.buffer.markers, options))
+	}
+
+	/// Writes the document's table of contents, or its headings when it has none, to `output_path` as
+	/// an indented outline.
+	pub fn export_outline(&self, output_path: &str, options: OutlineOptions) -> io::Result<()> {
+		let doc = self.handle.document();
+		let items = outline_items(&doc.toc_items, &doc.buffer);
+		let text = outline_text(&doc.title, &items, options, |position| {
+			let position = i64::try_from(position).unwrap_or(0);
+			let page = self.current_page(position);
+			(self.get_status_info(position).percentage, (page > 0).then(|| self.page_name(page)))
+		});
+		fs::write(output_path, text)
+	}
+
+	/// Writes this document's bookmarks and notes to `output_path` as Markdown.
+	pub fn export_notes_markdown(
+		&self,
+		config: &ConfigManager,
+		output_path: &str,
+		include_bookmarks_without_notes: bool,
+	) -> io::Result<()> {
+		let doc = self.handle.document();
+		let markdown = notes_to_markdown(
+			&doc.title,
+			&doc.author,
+			&doc.buffer.content,
+			&doc.buffer.markers,
+			&config.get_bookmarks(self.document_path()),
+			include_bookmarks_without_notes,
+		);
+		fs::write(output_path, markdown)
+	}
+
 	#[must_use]
 	pub fn get_text_segment(
 		&self,
@@ -1119,6 +2012,8 @@ impl DocumentSession {
 			SegmentTypeFfi::Separator => Some(NavTarget::Separator),
 			SegmentTypeFfi::Image => Some(NavTarget::Image),
 			SegmentTypeFfi::Figure => Some(NavTarget::Figure),
+			SegmentTypeFfi::BlockQuote => Some(NavTarget::BlockQuote),
+			SegmentTypeFfi::CodeBlock => Some(NavTarget::CodeBlock),
 			_ => None,
 		};
 
@@ -1135,21 +2030,16 @@ impl DocumentSession {
 				let mut end_pos = offset;
 
 				if text.trim().is_empty() {
-					let content = &self.handle.document().buffer.content;
-					let total_chars = self.handle.document().buffer.char_count();
-					let start_pos_char = usize::try_from(offset.max(0)).unwrap_or(0).min(total_chars);
-					let byte_idx = self.handle.document().buffer.byte_index_for_char(start_pos_char);
+					let buffer = &self.handle.document().buffer;
+					let byte_idx = buffer.byte_index_for_display(usize::try_from(offset.max(0)).unwrap_or(0));
 
 					let (start_byte, end_byte) =
-						self.find_paragraph_boundaries(content, byte_idx, SegmentDirectionFfi::Current);
-					text = content[start_byte..end_byte].trim().to_string();
-					let start_char = self.handle.document().buffer.char_index_for_byte(start_byte) as i64;
-					let end_char = self.handle.document().buffer.char_index_for_byte(end_byte) as i64;
-
-					offset = start_char;
-					end_pos = end_char;
+						self.find_paragraph_boundaries(&buffer.content, byte_idx, SegmentDirectionFfi::Current);
+					text = buffer.content[start_byte..end_byte].trim().to_string();
+					offset = buffer.display_index_for_byte(start_byte) as i64;
+					end_pos = buffer.display_index_for_byte(end_byte) as i64;
 				} else {
-					end_pos += i64::try_from(text.chars().count()).unwrap_or(0);
+					end_pos += i64::try_from(display_len(&text)).unwrap_or(0);
 				}
 
 				return TextSegmentFfi { text, start_pos: offset, end_pos };
@@ -1157,34 +2047,28 @@ impl DocumentSession {
 			return TextSegmentFfi { text: String::new(), start_pos: position, end_pos: position };
 		}
 
-		let content = &self.handle.document().buffer.content;
-		let total_chars = self.handle.document().buffer.char_count();
-		let start_pos_char = usize::try_from(position.max(0)).unwrap_or(0).min(total_chars);
-		let byte_idx = self.handle.document().buffer.byte_index_for_char(start_pos_char);
+		let buffer = &self.handle.document().buffer;
+		let content = &buffer.content;
+		let byte_idx = buffer.byte_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
 
 		let (start_byte, end_byte) = if matches!(segment_type, SegmentTypeFfi::Line) {
-			let line_num = self.line_from_position(start_pos_char as i64);
+			let line_num = self.line_from_position(position);
 			let target_line = match direction {
 				SegmentDirectionFfi::Previous => (line_num - 1).max(1),
 				SegmentDirectionFfi::Next => line_num + 1,
 				SegmentDirectionFfi::Current => line_num,
 			};
-			let start_char_idx = usize::try_from(self.position_from_line(target_line)).unwrap_or(0);
-			let end_char_idx = usize::try_from(self.position_from_line(target_line + 1)).unwrap_or(0);
-
-			let sb = self.handle.document().buffer.byte_index_for_char(start_char_idx);
-			let eb = self.handle.document().buffer.byte_index_for_char(end_char_idx);
-			(sb, eb)
+			let line_start = usize::try_from(self.position_from_line(target_line)).unwrap_or(0);
+			let line_end = usize::try_from(self.position_from_line(target_line + 1)).unwrap_or(0);
+			(buffer.byte_index_for_display(line_start), buffer.byte_index_for_display(line_end))
 		} else {
 			self.find_paragraph_boundaries(content, byte_idx, direction)
 		};
 		let text = content[start_byte..end_byte].trim().to_string();
-		let start_char = self.handle.document().buffer.char_index_for_byte(start_byte);
-		let end_char = self.handle.document().buffer.char_index_for_byte(end_byte);
 		TextSegmentFfi {
 			text,
-			start_pos: i64::try_from(start_char).unwrap_or(0),
-			end_pos: i64::try_from(end_char).unwrap_or(0),
+			start_pos: i64::try_from(buffer.display_index_for_byte(start_byte)).unwrap_or(0),
+			end_pos: i64::try_from(buffer.display_index_for_byte(end_byte)).unwrap_or(0),
 		}
 	}
 
@@ -1225,7 +2109,7 @@ impl DocumentSession {
 	pub fn get_status_info(&self, position: i64) -> StatusInfo {
 		let buf = &self.handle.document().buffer;
 		let total_chars = buf.char_count();
-		let pos = usize::try_from(position.max(0)).unwrap_or(0).min(total_chars);
+		let pos = buf.char_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
 		let line_number = buf.newline_positions().partition_point(|&p| p < pos) + 1;
 		let character_number = pos + 1;
 		let percentage = if total_chars > 0 { (pos * 100) / total_chars } else { 0 };
@@ -1236,15 +2120,191 @@ impl DocumentSession {
 		}
 	}
 
+	/// What the status bar template can show for `position`; see [`reader_core::format_status`].
+	#[must_use]
+	pub fn status_fields(&self, position: i64) -> StatusFields {
+		let status = self.get_status_info(position);
+		let document = self.handle.document();
+		let total_chars = document.buffer.char_count();
+		let pos = document.buffer.char_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
+		let remaining = |count: usize| {
+			(count > 0 && total_chars > 0).then(|| count.saturating_mul(total_chars.saturating_sub(pos)) / total_chars)
+		};
+		let words_left = remaining(document.stats.word_count);
+		let reading_words_left = remaining(document.stats.reading_words);
+		let page_count = self.page_count();
+		StatusFields {
+			line: status.line_number,
+			character: status.character_number,
+			percent: status.percentage,
+			page: (page_count > 0).then(|| (usize::try_from(self.current_page(position)).unwrap_or(0), page_count)),
+			page_label: self.page_label(self.current_page(position)),
+			heading: self.heading_breadcrumb(position),
+			words_left,
+			reading_words_left,
+			title: document.title.clone(),
+		}
+	}
+
+	/// Describes where `position` is: the heading breadcrumb, page, percentage and line.
+	#[must_use]
+	pub fn location_announcement(&self, position: i64) -> String {
+		let heading = self.heading_breadcrumb(position);
+		let page_count = self.page_count();
+		let page = (page_count > 0).then(|| (usize::try_from(self.current_page(position)).unwrap_or(0), page_count));
+		let page_label = self.page_label(self.current_page(position));
+		let status = self.get_status_info(position);
+		format_location(&heading, page, page_label.as_deref(), status.percentage, status.line_number)
+	}
+
+	/// Text of the last heading at or before `position`, or an empty string when there is none.
+	#[must_use]
+	pub fn heading_at(&self, position: i64) -> String {
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		self.handle
+			.document()
+			.buffer
+			.markers
+			.iter()
+			.take_while(|marker| marker.position <= pos)
+			.filter(|marker| document::is_heading_marker(marker.mtype))
+			.last()
+			.map(|marker| self.heading_text(marker))
+			.unwrap_or_default()
+	}
+
+	/// Text of the last level 1 or 2 heading at or before `position`, the chapter shown in the window
+	/// title, or an empty string when there is none.
+	#[must_use]
+	pub fn section_title_at(&self, position: i64) -> String {
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		self.handle
+			.document()
+			.buffer
+			.markers
+			.iter()
+			.take_while(|marker| marker.position <= pos)
+			.filter(|marker| document::is_heading_marker(marker.mtype) && marker.level <= 2)
+			.last()
+			.map(|marker| self.heading_text(marker))
+			.unwrap_or_default()
+	}
+
+	/// The section holding `position`: from the section start at or before it up to the next one.
+	/// Text before the first section start counts as a section of its own, and a document without
+	/// sections gives the whole document.
+	#[must_use]
+	pub fn current_section_range(&self, position: i64) -> SectionRange {
+		let doc = self.handle.document();
+		let total = doc.buffer.current_position();
+		let pos = usize::try_from(position.max(0)).unwrap_or(0).min(total);
+		let starts: Vec<usize> = self.section_starts().into_iter().filter(|&start| start < total).collect();
+		let start = starts.iter().rev().copied().find(|&start| start <= pos).unwrap_or(0);
+		let end = starts.iter().copied().find(|&start| start > pos).unwrap_or(total);
+		let title = doc
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| (start..end).contains(&marker.position) && document::is_heading_marker(marker.mtype))
+			.map(|marker| self.heading_text(marker).trim().to_string())
+			.find(|text| !text.is_empty())
+			.unwrap_or_else(|| doc.title.trim().to_string());
+		SectionRange {
+			start: i64::try_from(start).unwrap_or(0),
+			end: i64::try_from(end).unwrap_or(i64::MAX),
+			title,
+			whole_document: starts.is_empty(),
+		}
+	}
+
+	/// The numbered chain of headings enclosing `position`, e.g.
+	/// "3 Methods > 3.2 Participants > 3.2.1 Recruitment", or an empty string before the first heading.
+	#[must_use]
+	pub fn heading_breadcrumb(&self, position: i64) -> String {
+		let pos = usize::try_from(position.max(0)).unwrap_or(0);
+		let headings = self.heading_markers();
+		let levels: Vec<i32> = headings.iter().map(|marker| marker.level).collect();
+		let mut chain: Vec<(Vec<u32>, &document::Marker)> = Vec::new();
+		for (number, marker) in number_headings(&levels).into_iter().zip(&headings) {
+			if marker.position > pos {
+				break;
+			}
+			chain.truncate(number.len() - 1);
+			chain.push((number, marker));
+		}
+		chain
+			.iter()
+			.map(|(number, marker)| format!("{} {}", format_section_number(number), self.heading_text(marker)))
+			.collect::<Vec<_>>()
+			.join(" > ")
+	}
+
+	/// Position of the heading numbered `number` (e.g. "3.2.1") in the outline built from heading
+	/// levels, or `None` when the number is malformed or no heading has it.
+	#[must_use]
+	pub fn section_number_position(&self, number: &str) -> Option<i64> {
+		let target = parse_section_number(number)?;
+		let headings = self.heading_markers();
+		let levels: Vec<i32> = headings.iter().map(|marker| marker.level).collect();
+		number_headings(&levels)
+			.iter()
+			.position(|candidate| *candidate == target)
+			.and_then(|index| i64::try_from(headings[index].position).ok())
+	}
+
+	fn heading_markers(&self) -> Vec<&document::Marker> {
+		self.handle
+			.document()
+			.buffer
+			.markers
+			.iter()
+			.filter(|marker| document::is_heading_marker(marker.mtype))
+			.collect()
+	}
+
+	fn heading_text(&self, marker: &document::Marker) -> String {
+		if marker.text.is_empty() {
+			self.get_line_text(i64::try_from(marker.position).unwrap_or(0))
+		} else {
+			marker.text.clone()
+		}
+	}
+
+	/// `position` kept within the document. Positions are offsets into the document's text, which
+	/// don't depend on how a control wraps its lines, so they can be carried from one control to another.
+	#[must_use]
+	pub fn clamp_position(&self, position: i64) -> i64 {
+		let buf = &self.handle.document().buffer;
+		let end = i64::try_from(buf.display_index_for_char(buf.char_count())).unwrap_or(0);
+		position.clamp(0, end)
+	}
+
+	/// The text at `position`, saved with it so it can be found again if the offsets shift.
+	#[must_use]
+	pub fn position_anchor(&self, position: i64) -> PositionAnchor {
+		position_anchor(&self.handle.document().buffer, usize::try_from(position).unwrap_or(0))
+	}
+
+	/// Where a position saved along with `anchor` is now, and whether it could only be estimated from
+	/// the saved percentage.
+	#[must_use]
+	pub fn resolve_position_anchor(&self, position: i64, anchor: &PositionAnchor) -> (i64, bool) {
+		let (position, approximate) =
+			resolve_position_anchor(&self.handle.document().buffer, usize::try_from(position).unwrap_or(0), anchor);
+		(i64::try_from(position).unwrap_or(0), approximate)
+	}
+
 	#[must_use]
 	pub fn position_from_percent(&self, percent: i32) -> i64 {
-		let total_chars = i64::try_from(self.handle.document().buffer.char_count()).unwrap_or(0);
+		let buf = &self.handle.document().buffer;
+		let total_chars = i64::try_from(buf.char_count()).unwrap_or(0);
 		let percent = i64::from(percent.clamp(0, 100));
 		if total_chars == 0 {
 			return 0;
 		}
 		// Ceiling division: (percent * total_chars + 99) / 100
-		(percent * total_chars + 99) / 100
+		let char_index = usize::try_from((percent * total_chars + 99) / 100).unwrap_or(0);
+		i64::try_from(buf.display_index_for_char(char_index)).unwrap_or(0)
 	}
 
 	#[must_use]
@@ -1263,24 +2323,24 @@ impl DocumentSession {
 		let target_newlines = usize::try_from(line - 1).unwrap_or(0);
 		let newlines = buf.newline_positions();
 		if target_newlines <= newlines.len() {
-			i64::try_from(newlines[target_newlines - 1] + 1).unwrap_or(0)
+			i64::try_from(buf.display_index_for_char(newlines[target_newlines - 1] + 1)).unwrap_or(0)
 		} else {
-			i64::try_from(buf.char_count()).unwrap_or(0)
+			i64::try_from(buf.current_position()).unwrap_or(0)
 		}
 	}
 
 	#[must_use]
 	pub fn line_from_position(&self, position: i64) -> i64 {
 		let buf = &self.handle.document().buffer;
-		let total_chars = buf.char_count();
-		let pos = usize::try_from(position.max(0)).unwrap_or(0).min(total_chars);
+		let pos = buf.char_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
 		let line_number = buf.newline_positions().partition_point(|&p| p < pos) + 1;
 		i64::try_from(line_number).unwrap_or(1)
 	}
 
+	/// Number of pages, including any a lazily loaded PDF has not extracted yet.
 	#[must_use]
 	pub fn page_count(&self) -> usize {
-		self.handle.count_markers_by_type(MarkerType::PageBreak)
+		self.handle.total_pages()
 	}
 
 	#[must_use]
@@ -1289,6 +2349,42 @@ impl DocumentSession {
 		self.handle.page_index(pos).map_or(0, |idx| idx + 1)
 	}
 
+	/// The printed label of page `page_number` (1-based), such as "xi", or `None` when the document
+	/// doesn't label its pages.
+	#[must_use]
+	pub fn page_label(&self, page_number: i32) -> Option<String> {
+		let index = usize::try_from(page_number - 1).ok()?;
+		self.handle.document().page_labels.get(index).filter(|label| !label.is_empty()).cloned()
+	}
+
+	/// The printed page labels, one per page, or empty when the document doesn't label its pages.
+	#[must_use]
+	pub fn page_labels(&self) -> &[String] {
+		&self.handle.document().page_labels
+	}
+
+	/// Page `page_number` as it's read out: its label where the document has one, otherwise the number.
+	#[must_use]
+	pub fn page_name(&self, page_number: i32) -> String {
+		self.page_label(page_number).unwrap_or_else(|| page_number.to_string())
+	}
+
+	/// The page (1-based) typed into Go to Page. A printed label such as "xi" or "12" wins, so page
+	/// numbers match the ones on the page; anything else is taken as a page count from the start.
+	#[must_use]
+	pub fn page_for_input(&self, input: &str) -> Option<i32> {
+		let input = input.trim();
+		if input.is_empty() {
+			return None;
+		}
+		let labelled = self.handle.document().page_labels.iter().position(|label| label.eq_ignore_ascii_case(input));
+		if let Some(index) = labelled {
+			return i32::try_from(index + 1).ok();
+		}
+		let page = input.parse::<i32>().ok()?;
+		(1..=i32::try_from(self.page_count()).unwrap_or(i32::MAX)).contains(&page).then_some(page)
+	}
+
 	#[must_use]
 	pub fn page_offset(&self, page_number: i32) -> i64 {
 		let index = page_number - 1;
@@ -1300,37 +2396,23 @@ impl DocumentSession {
 			.map_or(-1, |offset| i64::try_from(offset).unwrap_or(-1))
 	}
 
-	/// Returns the text between two positions (start inclusive, end exclusive).
+	/// Returns the text between two display positions (start inclusive, end exclusive).
 	#[must_use]
 	pub fn get_text_range(&self, start: i64, end: i64) -> String {
-		let total_chars = self.handle.document().buffer.char_count();
-		let start_pos = usize::try_from(start.max(0)).unwrap_or(0).min(total_chars);
-		let end_pos = usize::try_from(end.max(0)).unwrap_or(0).min(total_chars);
-		if start_pos >= end_pos {
+		let buf = &self.handle.document().buffer;
+		let start_byte = buf.byte_index_for_display(usize::try_from(start.max(0)).unwrap_or(0));
+		let end_byte = buf.byte_index_for_display(usize::try_from(end.max(0)).unwrap_or(0));
+		if start_byte >= end_byte {
 			return String::new();
 		}
-
-		let start_byte = self.handle.document().buffer.byte_index_for_char(start_pos);
-		let end_byte = self.handle.document().buffer.byte_index_for_char(end_pos);
-
-		self.handle.document().buffer.content[start_byte..end_byte].to_string()
+		text::snippet_at(&buf.content, start_byte, end_byte).to_string()
 	}
 
 	#[must_use]
 	pub fn get_line_text(&self, position: i64) -> String {
 		let buf = &self.handle.document().buffer;
-		let total_chars = buf.char_count();
-		let pos = usize::try_from(position.max(0)).unwrap_or(0).min(total_chars);
-		let newlines = buf.newline_positions();
-		let line_start = match newlines.partition_point(|&p| p < pos) {
-			0 => 0,
-			idx => newlines[idx - 1] + 1,
-		};
-
-		let start_byte = buf.byte_index_for_char(line_start);
-		let line_end_byte = buf.content[start_byte..].find('\n').map_or(buf.content.len(), |i| start_byte + i);
-
-		buf.content[start_byte..line_end_byte].to_string()
+		let pos = buf.byte_index_for_display(usize::try_from(position.max(0)).unwrap_or(0));
+		text::line_at(&buf.content, pos).to_string()
 	}
 
 	#[must_use]
@@ -1401,9 +2483,40 @@ impl DocumentSession {
 
 #[cfg(test)]
 mod tests {
+	use std::env;
+
 	use super::*;
 	use crate::document::{Document, DocumentBuffer, Marker};
 
+	#[test]
+	fn furthest_position_moves_once_the_caret_stays_ahead() {
+		let mut session = sample_session(ParserFlags::NONE);
+		session.set_furthest_position(5);
+		let start = Instant::now();
+		let at = |secs| start + Duration::from_secs(secs);
+		assert_eq!(session.track_furthest(8, at(0)), None);
+		assert_eq!(session.track_furthest(10, at(3)), None);
+		assert_eq!(session.track_furthest(12, at(5)), Some(12));
+		assert_eq!(session.furthest_position(), 12);
+		assert_eq!(session.track_furthest(2, at(6)), None);
+		assert_eq!(session.furthest_position(), 12);
+	}
+
+	#[test]
+	fn furthest_position_ignores_a_jump_ahead_left_straight_away() {
+		let mut session = sample_session(ParserFlags::NONE);
+		session.set_furthest_position(5);
+		let start = Instant::now();
+		let at = |secs| start + Duration::from_secs(secs);
+		// A search hit far ahead, then straight back to where the reader was.
+		assert_eq!(session.track_furthest(15, at(0)), None);
+		assert_eq!(session.track_furthest(4, at(2)), None);
+		// Going past again restarts the wait rather than counting from the earlier jump.
+		assert_eq!(session.track_furthest(15, at(6)), None);
+		assert_eq!(session.track_furthest(15, at(10)), None);
+		assert_eq!(session.track_furthest(15, at(11)), Some(15));
+	}
+
 	fn sample_session(parser_flags: ParserFlags) -> DocumentSession {
 		let mut buffer = DocumentBuffer::with_content("line1\nline2\nline3".to_string());
 		buffer.add_marker(Marker::new(MarkerType::SectionBreak, 0).with_reference("chapter1.xhtml".to_string()));
@@ -1429,10 +2542,14 @@ mod tests {
 		DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		}
 	}
 
@@ -1446,6 +2563,63 @@ mod tests {
 		assert!(not_supported.not_supported);
 	}
 
+	#[test]
+	fn location_announcement_combines_heading_page_and_progress() {
+		let session = sample_session(ParserFlags::SUPPORTS_PAGES);
+		assert_eq!(session.location_announcement(13), "1 H1, page 2 of 2, 76 percent, line 3");
+	}
+
+	#[test]
+	fn heading_breadcrumb_and_section_numbers_follow_the_outline() {
+		let mut buffer = DocumentBuffer::with_content("Methods\nParticipants\nRecruitment\nText\n".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 0).with_level(1).with_text("Methods".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Heading3, 8).with_level(3));
+		buffer.add_marker(Marker::new(MarkerType::Heading4, 21).with_level(4).with_text("Recruitment".to_string()));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let session = DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "paper.html".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		};
+		assert_eq!(session.heading_breadcrumb(35), "1 Methods > 1.1 Participants > 1.1.1 Recruitment");
+		assert_eq!(session.heading_breadcrumb(10), "1 Methods > 1.1 Participants");
+		assert_eq!(session.section_number_position("1.1.1"), Some(21));
+		assert_eq!(session.section_number_position("1.1"), Some(8));
+		assert_eq!(session.section_number_position("2"), None);
+		assert_eq!(session.section_title_at(35), "Methods");
+	}
+
+	#[test]
+	fn section_title_is_the_nearest_level_one_or_two_heading() {
+		let session = sample_session(ParserFlags::NONE);
+		assert_eq!(session.section_title_at(13), "H1");
+		let mut buffer = DocumentBuffer::with_content(
+			"Intro
+Part
+Chapter
+Section
+"
+			.to_string(),
+		);
+		buffer.add_marker(Marker::new(MarkerType::Heading1, 6).with_level(1).with_text("Part".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Heading2, 11).with_level(2).with_text("Chapter".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Heading3, 19).with_level(3).with_text("Section".to_string()));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let session = DocumentSession { handle: DocumentHandle::new(doc), ..sample_session(ParserFlags::NONE) };
+		assert_eq!(session.section_title_at(2), "");
+		assert_eq!(session.section_title_at(8), "Part");
+		assert_eq!(session.section_title_at(25), "Chapter");
+	}
+
 	#[test]
 	fn link_activation_result_not_found_defaults() {
 		let result = LinkActivationResult::not_found();
@@ -1485,6 +2659,250 @@ mod tests {
 		assert_eq!(index, 1);
 	}
 
+	fn sectioned_session(mtype: MarkerType, starts: &[usize], parser_flags: ParserFlags) -> DocumentSession {
+		let mut buffer = DocumentBuffer::with_content("One\nTwo\nThree\nFour\n".to_string());
+		for &start in starts {
+			buffer.add_marker(Marker::new(mtype, start).with_level(1));
+		}
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "book.epub".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::with_seed(11),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		}
+	}
+
+	#[test]
+	fn random_section_leaves_the_current_section() {
+		let mut session = sectioned_session(MarkerType::SectionBreak, &[0, 4, 8, 14], ParserFlags::SUPPORTS_SECTIONS);
+		for _ in 0..50 {
+			let result = session.random_section(5);
+			assert!(result.found);
+			assert_ne!(result.offset, 4);
+			assert!(!result.marker_text.is_empty());
+		}
+	}
+
+	#[test]
+	fn shuffled_sections_visit_every_other_section_before_repeating() {
+		let mut session = sectioned_session(MarkerType::SectionBreak, &[0, 4, 8, 14], ParserFlags::SUPPORTS_SECTIONS);
+		session.set_section_shuffle(true);
+		assert!(session.section_shuffle_enabled());
+		let visited: BTreeSet<i64> = (0..3).map(|_| session.shuffle_next_section(0).offset).collect();
+		assert_eq!(visited, BTreeSet::from([4, 8, 14]));
+	}
+
+	#[test]
+	fn random_section_falls_back_to_top_level_headings() {
+		let mut session = sectioned_session(MarkerType::Heading1, &[0, 8], ParserFlags::NONE);
+		assert_eq!(session.random_section(0).offset, 8);
+	}
+
+	#[test]
+	fn random_section_needs_two_sections() {
+		let mut session = sectioned_session(MarkerType::SectionBreak, &[0], ParserFlags::SUPPORTS_SECTIONS);
+		assert!(session.random_section(0).not_supported);
+		assert!(session.shuffle_next_section(0).not_supported);
+	}
+
+	#[test]
+	fn current_section_runs_to_the_next_section_start() {
+		let session = sectioned_session(MarkerType::SectionBreak, &[0, 4, 8, 14], ParserFlags::SUPPORTS_SECTIONS);
+		let range = |position| {
+			let section = session.current_section_range(position);
+			(section.start, section.end, section.whole_document)
+		};
+		assert_eq!(range(0), (0, 4, false));
+		assert_eq!(range(5), (4, 8, false));
+		assert_eq!(range(8), (8, 14, false));
+		assert_eq!(range(16), (14, 19, false));
+		assert_eq!(range(999), (14, 19, false));
+	}
+
+	#[test]
+	fn text_before_the_first_heading_is_a_section_of_its_own() {
+		let session = sectioned_session(MarkerType::Heading1, &[4, 14], ParserFlags::NONE);
+		let first = session.current_section_range(2);
+		assert_eq!((first.start, first.end, first.whole_document), (0, 4, false));
+		let second = session.current_section_range(9);
+		assert_eq!((second.start, second.end, second.title.as_str()), (4, 14, "Two"));
+	}
+
+	#[test]
+	fn a_document_without_sections_is_one_section() {
+		let session = sectioned_session(MarkerType::SectionBreak, &[], ParserFlags::SUPPORTS_SECTIONS);
+		let section = session.current_section_range(6);
+		assert_eq!(section, SectionRange { start: 0, end: 19, title: String::new(), whole_document: true });
+	}
+
+	fn annotated_session() -> DocumentSession {
+		let mut session = sectioned_session(MarkerType::SectionBreak, &[], ParserFlags::NONE);
+		let mut doc = Document::new();
+		let mut buffer = DocumentBuffer::with_content("One\nTwo\nThree\nFour\n".to_string());
+		buffer.add_marker(
+			Marker::new(MarkerType::Annotation, 8)
+				.with_text("Check this".to_string())
+				.with_reference("Ada".to_string())
+				.with_level(2)
+				.with_length(5),
+		);
+		buffer.add_marker(Marker::new(MarkerType::Annotation, 4).with_level(1));
+		doc.set_buffer(buffer);
+		session.handle = DocumentHandle::new(doc);
+		session
+	}
+
+	#[test]
+	fn external_annotations_are_listed_in_document_order() {
+		let session = annotated_session();
+		let annotations = session.external_annotations();
+		assert_eq!(
+			annotations,
+			[
+				ExternalAnnotation { start: 4, end: 4, note: String::new(), author: String::new(), page: 1 },
+				ExternalAnnotation {
+					start: 8,
+					end: 13,
+					note: "Check this".to_string(),
+					author: "Ada".to_string(),
+					page: 2
+				},
+			]
+		);
+		assert_eq!(annotations[0].bookmark_note(), "");
+		assert_eq!(annotations[1].bookmark_note(), "Ada: Check this");
+	}
+
+	#[test]
+	fn annotation_navigation_moves_between_annotations() {
+		let session = annotated_session();
+		let landing = |position, next, wrap| {
+			let result = session.navigate_annotation(position, wrap, next);
+			result.found.then_some((result.offset, result.wrapped))
+		};
+		assert_eq!(landing(0, true, false), Some((4, false)));
+		assert_eq!(landing(4, true, false), Some((8, false)));
+		assert_eq!(landing(8, true, false), None);
+		assert_eq!(landing(8, true, true), Some((4, true)));
+		assert_eq!(landing(8, false, false), Some((4, false)));
+		assert_eq!(landing(4, false, true), Some((8, true)));
+	}
+
+	#[test]
+	fn section_export_rebases_the_markers_inside_it() {
+		let session = sectioned_session(MarkerType::Heading1, &[4, 8], ParserFlags::NONE);
+		let section = session.current_section_range(5);
+		let (start, end) = (usize::try_from(section.start).unwrap(), usize::try_from(section.end).unwrap());
+		assert_eq!(export::render_range(&session.handle, ExportFormat::Text, start, end, &section.title), "Two\n");
+		let markdown = export::render_range(&session.handle, ExportFormat::Markdown, start, end, &section.title);
+		assert!(markdown.starts_with("# Two"), "{markdown}");
+		assert!(!markdown.contains("One") && !markdown.contains("Three"), "{markdown}");
+		let html = export::render_range(&session.handle, ExportFormat::Html, start, end, &section.title);
+		assert!(html.contains("<title>Two</title>") && html.contains("<h1>Two"), "{html}");
+		assert!(!html.contains("Three"), "{html}");
+	}
+
+	fn text_session(content: &str) -> DocumentSession {
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content(content.to_string()));
+		DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "notes.txt".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		}
+	}
+
+	/// Five pages labelled i–iii and then 5, 6, as a roman front matter range followed by arabic
+	/// numbering that starts at 5.
+	fn labelled_pages_session() -> DocumentSession {
+		let mut buffer = DocumentBuffer::with_content("a\nb\nc\nd\ne\n".to_string());
+		for page in 0..5 {
+			buffer.add_marker(Marker::new(MarkerType::PageBreak, page * 2));
+		}
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		doc.page_labels = ["i", "ii", "iii", "5", "6"].map(String::from).to_vec();
+		DocumentSession { handle: DocumentHandle::new(doc), ..text_session("") }
+	}
+
+	#[test]
+	fn go_to_page_accepts_printed_labels_and_page_numbers() {
+		let session = labelled_pages_session();
+		assert_eq!(session.page_for_input("iii"), Some(3));
+		assert_eq!(session.page_for_input(" II "), Some(2));
+		assert_eq!(session.page_for_input("5"), Some(4), "a printed number beats the page count");
+		assert_eq!(session.page_for_input("2"), Some(2), "numbers that aren't labels count pages");
+		assert_eq!(session.page_for_input("7"), None);
+		assert_eq!(session.page_for_input("xx"), None);
+		assert_eq!(session.page_name(4), "5");
+		assert_eq!(session.status_fields(7).page_label.as_deref(), Some("5"));
+		assert_eq!(session.location_announcement(2), "page ii (2 of 5), 20 percent, line 2");
+
+		let unlabelled = sample_session(ParserFlags::NONE);
+		assert_eq!(unlabelled.page_label(1), None);
+		assert_eq!(unlabelled.page_name(2), "2");
+		assert_eq!(unlabelled.page_for_input("2"), Some(2));
+	}
+
+	#[test]
+	fn restored_history_follows_moved_text_and_falls_back_to_the_percentage() {
+		let before = "Alpha beta gamma.\nThe whale surfaced at dawn, and the crew watched.\nEnd of the line here.\n";
+		let offset_in =
+			|text: &str, needle: &str| i64::try_from(display_len(&text[..text.find(needle).unwrap()])).unwrap();
+		let mut session = text_session(before);
+		session.set_history(&[offset_in(before, "The whale"), offset_in(before, "End of")], 1);
+		let (mut saved, index) = session.saved_history();
+		assert_eq!(saved[0].anchor, "The whale surfaced at dawn, and the crew");
+		saved.push(SavedHistoryEntry::from_offset(3));
+
+		let after = "A new preface.\nAlpha beta gamma.\nThe whale surfaced at dawn, and the crew watched.\nEverything after this changed.\n";
+		let mut reopened = text_session(after);
+		reopened.restore_history(&saved, index);
+		let (history, index) = reopened.get_history();
+		assert_eq!(index, 1);
+		assert_eq!(history[0], offset_in(after, "The whale"), "the anchor text is found where it moved to");
+		#[allow(clippy::cast_precision_loss)]
+		let document_len = display_len(after) as f64;
+		#[allow(clippy::cast_possible_truncation)]
+		let expected = (saved[1].percent / 100.0 * document_len).round() as i64;
+		assert_eq!(history[1], expected, "text that's gone falls back to the same percentage");
+		assert_eq!(history[2], 3, "entries saved as bare offsets are kept as they were");
+	}
+
+	#[test]
+	fn history_entries_describe_positions_and_flag_missing_ones() {
+		let mut session = sample_session(ParserFlags::NONE);
+		session.set_history(&[6, 500], 1);
+		let entries = session.history_entries();
+		assert_eq!(entries.len(), 2);
+		assert!(entries[0].available);
+		assert_eq!(entries[0].heading, "H1");
+		assert_eq!(entries[0].snippet, "line2");
+		assert!(!entries[1].available);
+		assert!(!session.history_go_to(12, 1).found);
+		let result = session.history_go_to(12, 0);
+		assert!(result.found);
+		assert_eq!(result.offset, 6);
+		let (history, index) = session.get_history();
+		assert_eq!(history, &[6, 500, 12, 6]);
+		assert_eq!(index, 3);
+	}
+
 	#[test]
 	fn nav_helpers_build_expected_request() {
 		assert_eq!(DocumentSession::nav_direction(true), NavDirection::Next);
@@ -1569,6 +2987,41 @@ mod tests {
 		assert!(!session.has_headings(Some(99)));
 	}
 
+	#[test]
+	fn get_structure_markers_returns_headings_and_links() {
+		let mut buffer = DocumentBuffer::with_content("Intro\nsee docs\nline3".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Heading2, 0).with_level(2).with_text("Intro".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Bold, 6).with_length(3));
+		buffer.add_marker(
+			Marker::new(MarkerType::Link, 10).with_length(4).with_reference("https://example.com".to_string()),
+		);
+		buffer.add_marker(Marker::new(MarkerType::PageBreak, 15));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let session = DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "book.epub".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		};
+
+		let markers = session.get_structure_markers();
+
+		assert_eq!(markers.len(), 2);
+		assert_eq!(markers[0].mtype, MarkerTypeFfi::Heading2);
+		assert_eq!(markers[0].level, 2);
+		assert_eq!(markers[1].mtype, MarkerTypeFfi::Link);
+		assert_eq!(markers[1].position, 10);
+		assert_eq!(markers[1].length, 4);
+		assert_eq!(markers[1].reference, "https://example.com");
+	}
+
 	#[test]
 	fn get_formatting_markers_returns_only_bold_italic_underline_markers() {
 		let mut buffer = DocumentBuffer::with_content("line1\nline2\nline3".to_string());
@@ -1581,10 +3034,14 @@ mod tests {
 		let session = DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 
 		let markers = session.get_formatting_markers();
@@ -1626,6 +3083,16 @@ mod tests {
 		assert_eq!(result.url, "https://example.com");
 	}
 
+	#[test]
+	fn link_preview_shows_external_address_and_ignores_plain_text() {
+		let session = sample_session(ParserFlags::NONE);
+		assert!(session.link_preview(2).is_none());
+		let preview = session.link_preview(7).expect("link preview");
+		assert_eq!(preview.title, "line2");
+		assert_eq!(preview.text, "https://example.com");
+		assert_eq!(preview.url, "https://example.com");
+	}
+
 	#[test]
 	fn link_list_reports_closest_index_and_text() {
 		let session = sample_session(ParserFlags::NONE);
@@ -1636,6 +3103,90 @@ mod tests {
 		assert_eq!(list.closest_index, 0);
 	}
 
+	#[test]
+	fn structure_list_collects_tables_and_lists_in_order() {
+		let session = sample_session(ParserFlags::NONE);
+		let list = session.structure_list(7);
+		let items: Vec<(usize, &str, ffi::StructureKind)> =
+			list.items.iter().map(|item| (item.offset, item.text.as_str(), item.kind)).collect();
+		assert_eq!(items, [(6, "line2", ffi::StructureKind::List), (12, "line3", ffi::StructureKind::Table)]);
+		assert_eq!(list.closest_index, 0);
+	}
+
+	#[test]
+	fn status_fields_describe_position_and_leave_out_missing_data() {
+		let session = sample_session(ParserFlags::NONE);
+		let fields = session.status_fields(8);
+		assert_eq!((fields.line, fields.character, fields.percent), (2, 9, 47));
+		assert_eq!(fields.page, Some((2, 2)));
+		assert_eq!(fields.heading, "1 H1");
+		assert_eq!(fields.title, "Title");
+		assert_eq!(fields.words_left, None, "stats were never computed for the fixture");
+
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content("one two three four".to_string()));
+		doc.compute_stats();
+		let session = DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "notes.txt".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		};
+		let fields = session.status_fields(9);
+		assert_eq!((fields.words_left, fields.reading_words_left), (Some(2), Some(2)));
+		assert_eq!(fields.page, None);
+		assert!(fields.heading.is_empty());
+
+		let session = DocumentSession {
+			handle: DocumentHandle::new(Document::new()),
+			file_path: "empty.txt".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		};
+		let fields = session.status_fields(0);
+		assert_eq!((fields.line, fields.character, fields.percent), (1, 1, 0));
+		assert_eq!((fields.page, fields.words_left), (None, None));
+	}
+
+	#[test]
+	fn figure_list_lists_figures_once_and_loose_images() {
+		let mut buffer = DocumentBuffer::with_content("[Image: Chart]\nSales\nSee [Image]".to_string());
+		buffer.add_marker(Marker::new(MarkerType::Figure, 0).with_text("Sales".to_string()));
+		buffer.add_marker(Marker::new(MarkerType::Image, 0).with_text("Chart".to_string()).with_length(14));
+		buffer.add_marker(Marker::new(MarkerType::Image, 25).with_length(7));
+		let mut doc = Document::new();
+		doc.set_buffer(buffer);
+		let session = DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "book.epub".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		};
+		let list = session.figure_list(3);
+		let items: Vec<(usize, &str, ffi::StructureKind)> =
+			list.items.iter().map(|item| (item.offset, item.text.as_str(), item.kind)).collect();
+		assert_eq!(items, [(0, "Sales", ffi::StructureKind::Figure), (25, "See [Image]", ffi::StructureKind::Image)]);
+		assert_eq!(list.closest_index, 0);
+	}
+
 	#[test]
 	fn heading_tree_builds_parent_links_and_closest_index() {
 		let mut buffer = DocumentBuffer::with_content("a\nb\nc".to_string());
@@ -1647,16 +3198,21 @@ mod tests {
 		let session = DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		let tree = session.heading_tree(3);
 		assert_eq!(tree.items.len(), 3);
 		assert_eq!(tree.items[0].parent_index, -1);
 		assert_eq!(tree.items[1].parent_index, 0);
 		assert_eq!(tree.items[2].parent_index, -1);
+		assert_eq!(tree.items[1].level, 2);
 		assert_eq!(tree.closest_index, 1);
 	}
 
@@ -1684,10 +3240,14 @@ mod tests {
 		let session = DocumentSession {
 			handle: sample_session(ParserFlags::NONE).handle.clone(),
 			file_path: "C:\\docs\\chapter.md".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		assert!(session.webview_target_path(0, "C:\\temp").is_none());
 	}
@@ -1703,10 +3263,14 @@ mod tests {
 		let session = DocumentSession {
 			handle: sample_session(ParserFlags::NONE).handle.clone(),
 			file_path: "C:\\docs\\chapter.txt".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		assert_eq!(session.extract_resource("anything", "out.file").ok(), Some(false));
 	}
@@ -1715,10 +3279,14 @@ mod tests {
 		DocumentSession {
 			handle: sample_session(ParserFlags::NONE).handle.clone(),
 			file_path: file_path.to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		}
 	}
 
@@ -1740,6 +3308,15 @@ mod tests {
 		assert!(!session_with_path("plain.txt").source_view_available());
 	}
 
+	#[test]
+	fn downloaded_documents_keep_saved_state_under_their_url() {
+		let mut session = session_with_path("cache/book.epub");
+		assert_eq!(session.document_path(), "cache/book.epub");
+		session.source_url = Some("https://example.com/book.epub".to_string());
+		assert_eq!(session.document_path(), "https://example.com/book.epub");
+		assert_eq!(session.file_path(), "cache/book.epub");
+	}
+
 	#[test]
 	fn view_source_returns_none_for_unsupported_format() {
 		let dir = unique_temp_dir();
@@ -1773,6 +3350,32 @@ mod tests {
 		let _ = fs::remove_dir_all(&dir);
 	}
 
+	#[test]
+	fn crlf_cr_and_mixed_line_endings_count_the_same_lines() {
+		let dir = unique_temp_dir();
+		fs::create_dir_all(&dir).unwrap();
+		for (name, text) in [
+			("crlf.txt", "first\r\nsecond\r\nthird"),
+			("cr.txt", "first\rsecond\rthird"),
+			("mixed.txt", "first\r\nsecond\rthird"),
+		] {
+			let path = dir.join(name);
+			fs::write(&path, text).unwrap();
+			let session =
+				DocumentSession::new(&path.to_string_lossy(), "", "", false, true, true, false).expect("open text");
+			assert_eq!(session.content(), "first\nsecond\nthird", "{name}");
+			assert_eq!(session.get_stats_ffi().line_count, 3, "{name}");
+			assert_eq!(session.line_count(), 3, "{name}");
+			for (line, expected) in [(1, "first"), (2, "second"), (3, "third")] {
+				let position = session.position_from_line(line);
+				assert_eq!(session.line_from_position(position), line, "{name}");
+				assert_eq!(session.get_status_info(position).line_number, line, "{name}");
+				assert_eq!(session.get_line_text(position), expected, "{name}");
+			}
+		}
+		fs::remove_dir_all(&dir).ok();
+	}
+
 	#[test]
 	fn view_source_for_markdown_maps_caret_to_current_block() {
 		let dir = unique_temp_dir();
@@ -1781,7 +3384,8 @@ mod tests {
 		let src = dir.join("notes.md");
 		fs::write(&src, md.as_bytes()).unwrap();
 		// A real session populates id_positions with pb-block-N anchors.
-		let session = DocumentSession::new(&src.to_string_lossy(), "", "", false).expect("open markdown");
+		let session =
+			DocumentSession::new(&src.to_string_lossy(), "", "", false, true, true, false).expect("open markdown");
 
 		let rendered = session.content();
 		let pos = i64::try_from(rendered.find("Second").expect("second block rendered")).unwrap();
@@ -1852,6 +3456,25 @@ mod tests {
 		assert!(!session.navigate_note(&config, 0, false, true).found);
 	}
 
+	#[test]
+	fn navigate_bookmark_in_category_skips_other_categories() {
+		let session = sample_session(ParserFlags::NONE);
+		let dir = env::temp_dir().join(format!("paperback_bookmark_category_{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let mut config = ConfigManager::new();
+		config.initialize(dir.join("config.toml"));
+		for (position, category) in [(2, "Quote"), (7, "To-do"), (13, "Quote")] {
+			config.add_bookmark(&session.file_path, position, position, "");
+			config.update_bookmark_category(&session.file_path, position, position, category);
+		}
+		assert_eq!(session.navigate_bookmark(&config, 2, false, true).offset, 7);
+		assert_eq!(session.navigate_bookmark_in_category(&config, 2, false, true, "quote").offset, 13);
+		let wrapped = session.navigate_bookmark_in_category(&config, 13, true, true, "Quote");
+		assert!(wrapped.wrapped);
+		assert_eq!(wrapped.offset, 2);
+		let _ = fs::remove_dir_all(&dir);
+	}
+
 	#[test]
 	fn bookmark_display_at_position_returns_not_found_without_data() {
 		let session = sample_session(ParserFlags::NONE);
@@ -1871,10 +3494,14 @@ mod tests {
 		let session = DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		assert!(session.get_current_section_path(0).is_none());
 	}
@@ -1884,10 +3511,14 @@ mod tests {
 		let session = DocumentSession {
 			handle: sample_session(ParserFlags::NONE).handle.clone(),
 			file_path: "C:\\path\\does\\not\\exist.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		assert!(session.extract_resource("x", "y").is_err());
 	}
@@ -1911,10 +3542,14 @@ mod tests {
 		DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		}
 	}
 
@@ -1948,16 +3583,111 @@ mod tests {
 		let session = DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		// Position 5 is within [0, 6) by display length but would be outside [0, 1) by char count.
 		assert_eq!(session.get_table_at_position(5).as_deref(), Some("<table/>"));
 		assert!(session.get_table_at_position(6).is_none());
 	}
 
+	fn wide_text_session() -> (DocumentSession, &'static str) {
+		let text =
+			"Intro \u{1F600} line\nCaf\u{e9} e\u{301}t\u{e9} \u{4E2D}\u{6587}\u{1F468}\u{200D}\u{1F469} end\nlast";
+		let mut doc = Document::new();
+		doc.set_buffer(DocumentBuffer::with_content(text.to_string()));
+		let session = DocumentSession {
+			handle: DocumentHandle::new(doc),
+			file_path: "wide.txt".to_string(),
+			source_url: None,
+			history: Vec::new(),
+			history_index: 0,
+			parser_flags: ParserFlags::NONE,
+			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
+		};
+		(session, text)
+	}
+
+	#[test]
+	fn char_length_counts_display_units_not_bytes() {
+		let (session, text) = wide_text_session();
+		assert_eq!(session.char_length(), display_len(text));
+		assert!(session.char_length() < text.len());
+		assert_eq!(text_session("").char_length(), 0);
+	}
+
+	fn display_range(text: &str, selected: &str) -> (i64, i64) {
+		let byte = text.find(selected).unwrap();
+		let start = i64::try_from(display_len(&text[..byte])).unwrap();
+		(start, start + i64::try_from(display_len(selected)).unwrap())
+	}
+
+	#[test]
+	fn positions_survive_being_carried_to_a_rewrapped_control() {
+		let (session, text) = wide_text_session();
+		let (start, end) = display_range(text, "\u{4E2D}\u{6587}\u{1F468}\u{200D}\u{1F469}");
+		let (start, end) = (session.clamp_position(start), session.clamp_position(end));
+		assert_eq!(session.get_text_range(start, end), "\u{4E2D}\u{6587}\u{1F468}\u{200D}\u{1F469}");
+		assert_eq!(session.line_from_position(start), 2);
+		assert_eq!(session.clamp_position(-3), 0);
+		assert_eq!(session.clamp_position(i64::MAX), i64::try_from(display_len(text)).unwrap());
+	}
+
+	#[test]
+	fn bookmark_on_wide_text_round_trips_to_the_same_selection() {
+		let (session, text) = wide_text_session();
+		let dir = env::temp_dir().join(format!("paperback_wide_bookmark_{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let mut config = ConfigManager::new();
+		config.initialize(dir.join("config.toml"));
+		for selected in ["e\u{301}t\u{e9}", "\u{4E2D}\u{6587}\u{1F468}\u{200D}\u{1F469}", "\u{1F600} line"] {
+			let (start, end) = display_range(text, selected);
+			config.add_bookmark(&session.file_path, start, end, "");
+			let bookmark = config.get_bookmarks(&session.file_path).into_iter().find(|b| b.start == start).unwrap();
+			assert_eq!(bookmark.end, end);
+			assert_eq!(session.get_text_range(bookmark.start, bookmark.end), selected);
+		}
+		let (start, _) = display_range(text, "\u{4E2D}");
+		assert_eq!(
+			session.get_line_text(start),
+			"Caf\u{e9} e\u{301}t\u{e9} \u{4E2D}\u{6587}\u{1F468}\u{200D}\u{1F469} end"
+		);
+		assert_eq!(session.line_from_position(start), 2);
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn find_on_wide_text_selects_exactly_the_query() {
+		let (session, text) = wide_text_session();
+		let options = SearchOptionsFfi { match_case: false, whole_word: false, regex: false, forward: true };
+		for query in ["e\u{301}t\u{e9}", "\u{6587}\u{1F468}", "end", "last"] {
+			let result = session.search_ffi(query.to_string(), 0, options);
+			let (start, end) = display_range(text, query);
+			assert!(result.found);
+			assert_eq!(result.position, start);
+			assert_eq!(session.get_text_range(result.position, end), query);
+		}
+	}
+
+	#[test]
+	fn line_positions_are_display_offsets() {
+		let (session, text) = wide_text_session();
+		let (second_line, _) = display_range(text, "Caf");
+		let (third_line, _) = display_range(text, "last");
+		assert_eq!(session.position_from_line(2), second_line);
+		assert_eq!(session.position_from_line(3), third_line);
+		assert_eq!(session.position_from_line(9), i64::try_from(display_len(text)).unwrap());
+	}
+
 	#[test]
 	fn activate_link_returns_not_found_when_reference_missing() {
 		let mut buffer = DocumentBuffer::with_content("line1\nline2".to_string());
@@ -1967,13 +3697,26 @@ mod tests {
 		let session = DocumentSession {
 			handle: DocumentHandle::new(doc),
 			file_path: "book.epub".to_string(),
+			source_url: None,
 			history: Vec::new(),
 			history_index: 0,
 			parser_flags: ParserFlags::NONE,
 			last_stable_position: None,
+			section_shuffle: SectionShuffle::default(),
+			shuffle_sections: false,
+			furthest: FurthestRead::default(),
 		};
 		let result = session.activate_link(7);
 		assert!(!result.found);
 		assert_eq!(result.action, LinkAction::NotFound);
 	}
+
+	#[test]
+	fn placeholder_is_an_empty_document_with_the_known_title() {
+		let session = DocumentSession::placeholder("book.epub", "Moby Dick");
+		assert_eq!(session.title(), "Moby Dick");
+		assert!(session.content().is_empty());
+		assert_eq!(session.page_count(), 0);
+		assert!(session.is_fully_loaded());
+	}
 }
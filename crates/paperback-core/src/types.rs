@@ -1,3 +1,5 @@
+use crate::{config::ReadingStats, document::LandmarkKind};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NavDirection {
 	Next,
@@ -16,6 +18,8 @@ pub enum NavTarget {
 	Separator,
 	Image,
 	Figure,
+	BlockQuote,
+	CodeBlock,
 }
 
 #[derive(Debug, Clone)]
@@ -57,6 +61,8 @@ pub struct ListItemInfo {
 	pub offset: usize,
 	pub level: i32,
 	pub text: String,
+	/// Display-unit span of the item, including any list nested inside it.
+	pub length: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +80,22 @@ pub struct SeparatorInfo {
 	pub length: usize,
 }
 
+/// Start of a block quote or code block; `text` is its first non-blank line.
+#[derive(Debug, Clone)]
+pub struct BlockInfo {
+	pub offset: usize,
+	pub text: String,
+	/// Display-unit span of the block.
+	pub length: usize,
+}
+
+/// An element marked as a landmark by its `epub:type` or `role` attribute.
+#[derive(Debug, Clone)]
+pub struct LandmarkInfo {
+	pub offset: usize,
+	pub kind: LandmarkKind,
+}
+
 #[derive(Debug, Clone)]
 pub struct PageBreakInfo {
 	pub offset: usize,
@@ -103,6 +125,8 @@ pub struct SearchResult {
 	pub found: bool,
 	pub wrapped: bool,
 	pub position: i64,
+	/// Display length of the matched text.
+	pub length: i64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -126,6 +150,7 @@ pub struct BookmarkDisplayItem {
 	pub start: i64,
 	pub end: i64,
 	pub note: String,
+	pub category: String,
 	pub is_whole_line: bool,
 }
 
@@ -159,6 +184,7 @@ pub struct HeadingTreeItem {
 	pub offset: usize,
 	pub text: String,
 	pub parent_index: i32,
+	pub level: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -167,10 +193,67 @@ pub struct HeadingTree {
 	pub closest_index: i32,
 }
 
+impl HeadingTree {
+	/// Keeps only headings of `level`, plus their ancestors so the filtered tree still shows where each
+	/// heading sits. Parent links are renumbered; the closest item becomes the last kept heading at or
+	/// before the original closest one.
+	#[must_use]
+	pub fn filtered_by_level(&self, level: i32) -> Self {
+		let mut keep = vec![false; self.items.len()];
+		for (index, item) in self.items.iter().enumerate() {
+			if item.level != level {
+				continue;
+			}
+			keep[index] = true;
+			let mut parent = usize::try_from(item.parent_index).ok();
+			while let Some(parent_index) = parent.filter(|&p| p < keep.len() && !keep[p]) {
+				keep[parent_index] = true;
+				parent = usize::try_from(self.items[parent_index].parent_index).ok();
+			}
+		}
+		let mut new_indices = vec![-1i32; self.items.len()];
+		let mut items = Vec::new();
+		for (index, item) in self.items.iter().enumerate().filter(|(index, _)| keep[*index]) {
+			new_indices[index] = i32::try_from(items.len()).unwrap_or(-1);
+			let parent_index = usize::try_from(item.parent_index).ok().map_or(-1, |parent| new_indices[parent]);
+			items.push(HeadingTreeItem { parent_index, ..item.clone() });
+		}
+		let closest_index = usize::try_from(self.closest_index)
+			.ok()
+			.and_then(|closest| new_indices.iter().take(closest + 1).rev().find(|&&index| index >= 0).copied())
+			.unwrap_or(-1);
+		Self { items, closest_index }
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureKind {
+	Table,
+	List,
+	Image,
+	Figure,
+}
+
+#[derive(Debug, Clone)]
+pub struct StructureListItem {
+	pub offset: usize,
+	pub text: String,
+	pub kind: StructureKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct StructureList {
+	pub items: Vec<StructureListItem>,
+	pub closest_index: i32,
+}
+
 #[derive(Debug, Clone)]
 pub struct BookmarkDisplayEntry {
 	pub start: i64,
 	pub end: i64,
+	pub note: String,
+	/// The bookmarked text, or its whole line for a line bookmark.
+	pub snippet: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -184,5 +267,109 @@ pub enum DocumentListStatus {
 pub struct DocumentListItem {
 	pub path: String,
 	pub filename: String,
+	/// Title and author recorded the last time the document was opened; empty before that.
+	pub title: String,
+	pub author: String,
+	/// Percent of the document read, or `None` when its length isn't known yet.
+	pub progress: Option<i32>,
+	/// Percent of the document reached at the furthest, or `None` when its length isn't known yet.
+	pub furthest: Option<i32>,
 	pub status: DocumentListStatus,
+	pub reading_stats: ReadingStats,
+}
+
+/// Column the All Documents list is sorted by. `Recent` keeps recently opened documents first,
+/// then the rest by file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocumentSortKey {
+	#[default]
+	Recent,
+	FileName,
+	Title,
+	Author,
+	Progress,
+	Status,
+	Path,
+	LastRead,
+}
+
+impl DocumentSortKey {
+	pub const ALL: [Self; 8] = [
+		Self::Recent,
+		Self::FileName,
+		Self::Title,
+		Self::Author,
+		Self::Progress,
+		Self::Status,
+		Self::Path,
+		Self::LastRead,
+	];
+
+	#[must_use]
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Recent => "recent",
+			Self::FileName => "file_name",
+			Self::Title => "title",
+			Self::Author => "author",
+			Self::Progress => "progress",
+			Self::Status => "status",
+			Self::Path => "path",
+			Self::LastRead => "last_read",
+		}
+	}
+
+	#[must_use]
+	pub fn parse(value: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|key| key.as_str() == value)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DocumentSort {
+	pub key: DocumentSortKey,
+	pub descending: bool,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn heading(text: &str, level: i32, parent_index: i32) -> HeadingTreeItem {
+		HeadingTreeItem { offset: 0, text: text.to_string(), parent_index, level }
+	}
+
+	#[test]
+	fn filtered_by_level_keeps_ancestors_and_renumbers_parents() {
+		let tree = HeadingTree {
+			items: vec![
+				heading("Part", 1, -1),
+				heading("Chapter", 2, 0),
+				heading("Methods", 3, 1),
+				heading("Other chapter", 2, 0),
+				heading("Results", 3, 3),
+			],
+			closest_index: 3,
+		};
+		let filtered = tree.filtered_by_level(3);
+		let texts: Vec<&str> = filtered.items.iter().map(|item| item.text.as_str()).collect();
+		assert_eq!(texts, ["Part", "Chapter", "Methods", "Other chapter", "Results"]);
+		let filtered = tree.filtered_by_level(2);
+		let parents: Vec<i32> = filtered.items.iter().map(|item| item.parent_index).collect();
+		assert_eq!(parents, [-1, 0, 0]);
+		assert_eq!(filtered.closest_index, 2);
+	}
+
+	#[test]
+	fn filtered_by_level_moves_closest_to_previous_kept_heading() {
+		let tree = HeadingTree {
+			items: vec![heading("One", 1, -1), heading("Sub", 2, 0), heading("Two", 1, -1)],
+			closest_index: 1,
+		};
+		let filtered = tree.filtered_by_level(1);
+		assert_eq!(filtered.items.len(), 2);
+		assert_eq!(filtered.items[1].parent_index, -1);
+		assert_eq!(filtered.closest_index, 0);
+		assert_eq!(tree.filtered_by_level(4).closest_index, -1);
+	}
 }
@@ -1,3 +1,6 @@
 pub mod encoding;
+pub mod language;
+pub mod message;
+pub mod path;
 pub mod text;
 pub mod zip;
@@ -1,6 +1,33 @@
 use std::str;
 
-use encoding_rs::{UTF_16BE, UTF_16LE, WINDOWS_1252};
+use encoding_rs::{Encoding, SHIFT_JIS, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1250, WINDOWS_1251, WINDOWS_1252};
+
+/// Encodings offered for reading a plain text file, by their WHATWG names.
+pub const TEXT_ENCODINGS: [&str; 20] = [
+	"UTF-8",
+	"UTF-16LE",
+	"UTF-16BE",
+	"windows-1250",
+	"windows-1251",
+	"windows-1252",
+	"windows-1253",
+	"windows-1254",
+	"windows-1255",
+	"windows-1256",
+	"windows-1257",
+	"windows-874",
+	"IBM866",
+	"KOI8-R",
+	"KOI8-U",
+	"ISO-8859-2",
+	"Shift_JIS",
+	"EUC-JP",
+	"gb18030",
+	"Big5",
+];
+
+/// Fewer bytes than this say too little about which bytes are the high halves of UTF-16 units.
+const UTF16_SNIFF_MIN_BYTES: usize = 16;
 
 #[must_use]
 pub fn convert_to_utf8(input: &[u8]) -> String {
@@ -47,6 +74,9 @@ pub fn convert_to_utf8(input: &[u8]) -> String {
 			return decoded.to_string();
 		}
 	}
+	if let Some(encoding) = detect_encoding(input) {
+		return encoding.decode_without_bom_handling(input).0.into_owned();
+	}
 	// Windows-1252
 	let (decoded, _, _) = WINDOWS_1252.decode(input);
 	if decoded.chars().any(|c| !c.is_control() || c.is_whitespace()) {
@@ -56,6 +86,139 @@ pub fn convert_to_utf8(input: &[u8]) -> String {
 	String::from_utf8_lossy(input).to_string()
 }
 
+/// Decodes `input` as the encoding named by `label`, dropping a BOM that belongs to it. `None` when
+/// the label names no encoding.
+#[must_use]
+pub fn decode_with_label(input: &[u8], label: &str) -> Option<String> {
+	let encoding = Encoding::for_label(label.as_bytes())?;
+	Some(encoding.decode_with_bom_removal(input).0.into_owned())
+}
+
+/// Decodes `input` as the encoding chosen for a document, or detects it when none was chosen or
+/// the label names no encoding.
+#[must_use]
+pub fn decode_text(input: &[u8], label: Option<&str>) -> String {
+	label.and_then(|label| decode_with_label(input, label)).unwrap_or_else(|| convert_to_utf8(input))
+}
+
+/// Guesses the encoding of text that is neither UTF-8 nor marked with a BOM: Shift_JIS, UTF-16
+/// without a BOM, or the Cyrillic or Central European Windows code page. `None` leaves it to the
+/// Windows-1252 fallback.
+fn detect_encoding(input: &[u8]) -> Option<&'static Encoding> {
+	if decode_cleanly(input, SHIFT_JIS).is_some_and(|text| text.chars().any(is_kana)) {
+		return Some(SHIFT_JIS);
+	}
+	if let Some(encoding) = utf16_by_byte_pattern(input) {
+		return Some(encoding);
+	}
+	if looks_cyrillic(input) {
+		return Some(WINDOWS_1251);
+	}
+	let letters = |encoding| {
+		decode_cleanly(input, encoding).map(|text| text.chars().filter(|c| !c.is_ascii() && c.is_alphabetic()).count())
+	};
+	// Central European text has letters where Windows-1252 has symbols or nothing at all.
+	let central_european = letters(WINDOWS_1250)?;
+	(central_european > letters(WINDOWS_1252).unwrap_or(0)).then_some(WINDOWS_1250)
+}
+
+/// `input` decoded as `encoding`, if that goes without errors and gives no control characters other
+/// than line breaks and tabs, nor private use characters.
+fn decode_cleanly(input: &[u8], encoding: &'static Encoding) -> Option<String> {
+	let (text, had_errors) = encoding.decode_without_bom_handling(input);
+	let unexpected = |c: char| {
+		(c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0C')) || ('\u{E000}'..='\u{F8FF}').contains(&c)
+	};
+	(!had_errors && !text.chars().any(unexpected)).then(|| text.into_owned())
+}
+
+/// Hiragana or full-width katakana, which Japanese text is rarely without.
+fn is_kana(c: char) -> bool {
+	('\u{3040}'..='\u{30FF}').contains(&c)
+}
+
+/// Recognizes UTF-16 without a BOM in text with few or no ASCII characters. The high byte of each
+/// unit names the Unicode block of a character, so in text of one script a few values make up
+/// nearly all of them, while the low bytes vary as much as the letters do.
+fn utf16_by_byte_pattern(input: &[u8]) -> Option<&'static Encoding> {
+	if input.len() < UTF16_SNIFF_MIN_BYTES {
+		return None;
+	}
+	let even = top_byte_share(input.iter().step_by(2));
+	let odd = top_byte_share(input.iter().skip(1).step_by(2));
+	let encoding = if odd >= 0.9 && even < 0.6 {
+		UTF_16LE
+	} else if even >= 0.9 && odd < 0.6 {
+		UTF_16BE
+	} else {
+		return None;
+	};
+	decode_cleanly(input, encoding).map(|_| encoding)
+}
+
+/// The share of `bytes` taken by its three most common values.
+fn top_byte_share<'a>(bytes: impl Iterator<Item = &'a u8>) -> f64 {
+	let mut counts = [0usize; 256];
+	let mut total = 0usize;
+	for &byte in bytes {
+		counts[usize::from(byte)] += 1;
+		total += 1;
+	}
+	if total == 0 {
+		return 0.0;
+	}
+	counts.sort_unstable_by(|a, b| b.cmp(a));
+	#[allow(clippy::cast_precision_loss)]
+	let share = (counts[0] + counts[1] + counts[2]) as f64 / total as f64;
+	share
+}
+
+/// Whether `input` reads as Windows-1251 Cyrillic: at least two words with letters beyond ASCII,
+/// nearly all of them made of Cyrillic letters only. Western European text read this way gives
+/// words that mix Latin and Cyrillic letters.
+fn looks_cyrillic(input: &[u8]) -> bool {
+	let Some(text) = decode_cleanly(input, WINDOWS_1251) else {
+		return false;
+	};
+	let words: Vec<&str> =
+		text.split(|c: char| !c.is_alphabetic()).filter(|word| word.chars().any(|c| !c.is_ascii())).collect();
+	let cyrillic = words.iter().filter(|word| word.chars().all(|c| ('\u{0400}'..='\u{04FF}').contains(&c))).count();
+	words.len() >= 2 && cyrillic * 5 >= words.len() * 4
+}
+
+/// Decodes HTML bytes, honouring a BOM, valid UTF-8, a `<meta>` charset declaration and finally
+/// `fallback` (typically derived from the container's locale) before the generic heuristics.
+#[must_use]
+pub fn convert_html_to_utf8(input: &[u8], fallback: Option<&'static Encoding>) -> String {
+	if Encoding::for_bom(input).is_some() || str::from_utf8(input).is_ok() {
+		return convert_to_utf8(input);
+	}
+	match sniff_html_charset(input).filter(|encoding| *encoding != UTF_8).or(fallback) {
+		Some(encoding) => encoding.decode_without_bom_handling(input).0.into_owned(),
+		None => convert_to_utf8(input),
+	}
+}
+
+/// Finds the encoding declared by `<meta charset=...>` or `<meta http-equiv ... content="...; charset=...">`
+/// within the first few kilobytes of an HTML document.
+#[must_use]
+pub fn sniff_html_charset(input: &[u8]) -> Option<&'static Encoding> {
+	const SNIFF_LIMIT: usize = 4096;
+	let head = String::from_utf8_lossy(&input[..input.len().min(SNIFF_LIMIT)]).to_ascii_lowercase();
+	let mut rest = head.as_str();
+	while let Some(pos) = rest.find("charset") {
+		rest = &rest[pos + "charset".len()..];
+		let Some(value) = rest.trim_start().strip_prefix('=') else { continue };
+		let value = value.trim_start().trim_start_matches(['"', '\'']);
+		let end =
+			value.find(|c: char| matches!(c, '"' | '\'' | ';' | '>' | '/') || c.is_whitespace()).unwrap_or(value.len());
+		if let Some(encoding) = Encoding::for_label(value[..end].as_bytes()) {
+			return Some(encoding);
+		}
+	}
+	None
+}
+
 fn decode_utf32_le(input: &[u8]) -> String {
 	input
 		.chunks_exact(4)
@@ -164,6 +327,72 @@ mod tests {
 		assert_eq!(looks_like_utf16(input), expected);
 	}
 
+	#[rstest]
+	#[case(b"<meta charset=\"windows-1251\">", Some(encoding_rs::WINDOWS_1251))]
+	#[case(b"<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=koi8-r\">", Some(encoding_rs::KOI8_R))]
+	#[case(b"<meta charset=bogus>", None)]
+	#[case(b"<p>no declaration</p>", None)]
+	fn test_sniff_html_charset(#[case] input: &[u8], #[case] expected: Option<&'static Encoding>) {
+		assert_eq!(sniff_html_charset(input), expected);
+	}
+
+	#[test]
+	fn test_convert_html_to_utf8_prefers_declared_charset_then_fallback() {
+		let declared = b"<meta charset=\"windows-1251\"><p>\xCF\xF0\xE8\xE2\xE5\xF2</p>";
+		assert!(convert_html_to_utf8(declared, None).contains("Привет"));
+		let undeclared = b"<p>\xCF\xF0\xE8\xE2\xE5\xF2</p>";
+		assert_eq!(convert_html_to_utf8(undeclared, Some(encoding_rs::WINDOWS_1251)), "<p>Привет</p>");
+		assert_eq!(convert_html_to_utf8(b"<p>caf\xE9</p>", None), "<p>café</p>");
+		assert_eq!(convert_html_to_utf8("<p>Привет</p>".as_bytes(), Some(WINDOWS_1252)), "<p>Привет</p>");
+	}
+
+	#[rstest]
+	#[case::utf16le_without_bom(
+		&include_bytes!("../../tests/fixtures/encoding/utf-16le.txt")[..],
+		"Широкая электрификация южных губерний даст мощный толчок подъёму сельского хозяйства.\n"
+	)]
+	#[case::windows_1251(
+		&include_bytes!("../../tests/fixtures/encoding/windows-1251.txt")[..],
+		"Съешь же ещё этих мягких французских булок, да выпей чаю.\n"
+	)]
+	#[case::windows_1250(
+		&include_bytes!("../../tests/fixtures/encoding/windows-1250.txt")[..],
+		"Zażółć gęślą jaźń. Pchnąć w tę łódź jeża lub ośm skrzyń fig.\n"
+	)]
+	#[case::shift_jis(
+		&include_bytes!("../../tests/fixtures/encoding/shift_jis.txt")[..],
+		"いろはにほへと ちりぬるを。日本語のテキストです。\n"
+	)]
+	fn test_convert_to_utf8_detects_legacy_encodings(#[case] input: &[u8], #[case] expected: &str) {
+		assert_eq!(convert_to_utf8(input), expected);
+	}
+
+	#[test]
+	fn test_western_text_stays_windows_1252() {
+		let french = b"Voil\xE0, o\xF9 est le gar\xE7on? Il a \xE9t\xE9 \xE0 la fen\xEAtre.";
+		assert_eq!(convert_to_utf8(french), "Voilà, où est le garçon? Il a été à la fenêtre.");
+		let german = b"Gr\xF6\xDFe und \xDCberma\xDF f\xFCr Stra\xDFe";
+		assert_eq!(convert_to_utf8(german), "Größe und Übermaß für Straße");
+	}
+
+	#[test]
+	fn test_decode_with_label_overrides_detection() {
+		let bytes = include_bytes!("../../tests/fixtures/encoding/windows-1251.txt");
+		assert_eq!(decode_with_label(bytes, "windows-1251").unwrap(), convert_to_utf8(bytes));
+		assert_ne!(decode_with_label(bytes, "windows-1252").unwrap(), convert_to_utf8(bytes));
+		assert_eq!(decode_with_label(b"\xFF\xFEH\x00i\x00", "UTF-16LE").unwrap(), "Hi");
+		assert!(decode_with_label(b"text", "no-such-encoding").is_none());
+		assert!(TEXT_ENCODINGS.iter().all(|label| Encoding::for_label(label.as_bytes()).is_some()));
+	}
+
+	#[test]
+	fn test_decode_text_falls_back_to_detection() {
+		let bytes = include_bytes!("../../tests/fixtures/encoding/windows-1251.txt");
+		assert_eq!(decode_text(bytes, None), convert_to_utf8(bytes));
+		assert_eq!(decode_text(bytes, Some("no-such-encoding")), convert_to_utf8(bytes));
+		assert_eq!(decode_text(b"\xE0", Some("windows-1252")), "à");
+	}
+
 	#[test]
 	fn test_convert_to_utf8_falls_back_to_lossy_when_no_viable_decode() {
 		let input = b"\x81\x8D";
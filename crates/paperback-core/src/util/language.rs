@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+
+use icu_properties::{CodePointMapData, props::BidiClass};
+
+use crate::{document::TextDirection, t};
+
+/// Documents shorter than this are too small to classify reliably.
+pub const MIN_DETECTION_CHARS: usize = 500;
+/// Only the start of a document is sampled; that is plenty for a stable guess.
+const SAMPLE_CHARS: usize = 20_000;
+/// The best profile must account for at least this share (in percent) of the sample's trigrams.
+const MIN_PROFILE_HIT_PERCENT: u64 = 8;
+
+/// Languages `detect_language` can report, in the order they are offered to the user.
+pub const DETECTABLE_LANGUAGES: &[&str] = &[
+	"ar", "bg", "cs", "de", "el", "en", "es", "fa", "fi", "fr", "he", "hi", "it", "ja", "ko", "nl", "pl", "pt", "ru",
+	"sr", "sv", "th", "tr", "uk", "zh",
+];
+
+/// The most frequent word-boundary-padded trigrams of each Latin-script language.
+const LATIN_PROFILES: &[(&str, [&str; 30])] = &[
+	(
+		"en",
+		[
+			" th", "the", "he ", " an", "and", "nd ", " of", "of ", " to", "to ", "ing", "ng ", " in", "in ", "ed ",
+			" wa", "was", "as ", "hat", "tha", "at ", " he", "his", "is ", " it", "it ", "er ", "for", " fo", "ou ",
+		],
+	),
+	(
+		"fr",
+		[
+			" de", "de ", "es ", " le", "le ", "ent", "nt ", " la", "la ", " et", "et ", " qu", "que", "ue ", "les",
+			" pa", "ait", "ais", " un", "un ", "des", " co", "ous", "our", "ans", " da", "dan", " so", "ne ", " pl",
+		],
+	),
+	(
+		"de",
+		[
+			"en ", "er ", " de", "der", "die", " di", "ie ", "ch ", "ein", " ei", "ich", " un", "und", "nd ", "sch",
+			"che", "den", "cht", "ung", " ge", " zu", "nde", "gen", "das", " da", "ine", " si", "sie", "ist", " is",
+		],
+	),
+	(
+		"es",
+		[
+			" de", "de ", "os ", " la", "la ", "el ", " el", "que", " qu", "ue ", " en", "en ", "as ", "ado", "do ",
+			" lo", "los", " se", " co", "con", "ón ", "ión", "ent", "nte", "por", " po", " su", "una", " un", "ara",
+		],
+	),
+	(
+		"it",
+		[
+			" di", "di ", " la", "la ", "che", " ch", "he ", " il", "il ", "ell", "lla", "del", " de", "to ", "re ",
+			" co", "ent", "one", "ion", "per", " pe", "ato", "tto", "gli", " gl", "no ", "ere", "nte", " e ", "non",
+		],
+	),
+	(
+		"pt",
+		[
+			" de", "de ", "os ", "do ", " do", "que", " qu", "ue ", "da ", " da", " a ", "ão ", "ção", " co", "com",
+			"ent", "nte", " o ", " pa", "par", "ara", "em ", " se", "mos", "ado", "uma", " um", "um ", "não", " nã",
+		],
+	),
+	(
+		"nl",
+		[
+			"en ", " de", "de ", "an ", "het", " he", "et ", "van", " va", "een", " ee", "der", "ing", "ijk", "aar",
+			"ver", " ve", "oor", "cht", " ge", "sch", "nde", "ond", " op", "op ", "dat", " da", "ij ", "zij", " zi",
+		],
+	),
+	(
+		"pl",
+		[
+			"ie ", "nie", " ni", "ch ", " pr", "prz", "rze", "owa", "ego", "go ", " po", "ani", "ści", "że ", " że",
+			"dzi", " si", "się", "ię ", " w ", " na", "na ", "ych", "cie", "nia", "wie", "ej ", " do", "kie", "ać ",
+		],
+	),
+	(
+		"cs",
+		[
+			" pr", "pro", " po", " na", "na ", "ní ", " se", "se ", "ost", " je", "je ", "ch ", " ne", "ého", "ter",
+			" st", "sti", "že ", " ve", "ení", "ých", "ova", "ým ", "pře", "ře ", "ně ", "ou ", "ho ", " za", "val",
+		],
+	),
+	(
+		"fi",
+		[
+			"en ", "an ", "in ", "ta ", "sa ", "ist", "lla", "sta", "tä ", "ssa", " ja", "ja ", "kan", " ka", "ise",
+			"tai", "all", "een", "ell", "ais", "ett", "oli", " ol", "ksi", "äll", "nen", "ine", "ään", " ku", "tti",
+		],
+	),
+	(
+		"sv",
+		[
+			"en ", " oc", "och", "ch ", "er ", "ar ", " de", "det", "et ", "för", " fö", "att", " at", "tt ", "an ",
+			"som", " so", "om ", "ade", " sk", "nde", "den", "ing", " me", " va", "var", "ten", "and", " ha", "han",
+		],
+	),
+	(
+		"tr",
+		[
+			"lar", "ler", " bi", "bir", "ir ", "in ", "an ", "eri", "ını", "ara", "ın ", "da ", "de ", "nda", " ve",
+			"ve ", "ini", "yor", "ile", " il", "arı", "ına", "ak ", "dı ", "ası", "sın", "ğı ", "lan", "rak", "en ",
+		],
+	),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Script {
+	Latin,
+	Greek,
+	Cyrillic,
+	Hebrew,
+	Arabic,
+	Devanagari,
+	Thai,
+	Hangul,
+	Kana,
+	Han,
+}
+
+const fn script_of(ch: char) -> Option<Script> {
+	match ch {
+		'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => Some(Script::Latin),
+		'\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Some(Script::Greek),
+		'\u{0400}'..='\u{052F}' => Some(Script::Cyrillic),
+		'\u{0590}'..='\u{05FF}' => Some(Script::Hebrew),
+		'\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' => Some(Script::Arabic),
+		'\u{0900}'..='\u{097F}' => Some(Script::Devanagari),
+		'\u{0E00}'..='\u{0E7F}' => Some(Script::Thai),
+		'\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => Some(Script::Hangul),
+		'\u{3040}'..='\u{30FF}' => Some(Script::Kana),
+		'\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' => Some(Script::Han),
+		_ => None,
+	}
+}
+
+/// Guesses the dominant language of `text`, returning its ISO 639-1 code.
+///
+/// Only the first ~20k characters are examined. Returns `None` for documents shorter than
+/// [`MIN_DETECTION_CHARS`] or when no language stands out.
+#[must_use]
+pub fn detect_language(text: &str) -> Option<&'static str> {
+	let sample_end = text.char_indices().nth(SAMPLE_CHARS).map_or(text.len(), |(index, _)| index);
+	let sample = &text[..sample_end];
+	if sample.chars().count() < MIN_DETECTION_CHARS {
+		return None;
+	}
+	let mut scripts: HashMap<Script, usize> = HashMap::new();
+	for ch in sample.chars() {
+		if let Some(script) = script_of(ch) {
+			*scripts.entry(script).or_insert(0) += 1;
+		}
+	}
+	let count = |script| scripts.get(&script).copied().unwrap_or(0);
+	let (dominant, _) = scripts
+		.iter()
+		.map(|(script, count)| (*script, *count))
+		.max_by_key(|(script, count)| (*count, *script == Script::Latin))?;
+	match dominant {
+		Script::Latin => detect_latin_language(sample),
+		Script::Cyrillic => Some(detect_cyrillic_language(sample)),
+		Script::Arabic => {
+			let persian = sample.chars().filter(|ch| matches!(ch, 'پ' | 'چ' | 'ژ' | 'گ' | 'ی')).count();
+			Some(if persian * 50 > count(Script::Arabic) { "fa" } else { "ar" })
+		}
+		Script::Han | Script::Kana => {
+			let kana = count(Script::Kana);
+			Some(if kana * 10 > kana + count(Script::Han) { "ja" } else { "zh" })
+		}
+		Script::Greek => Some("el"),
+		Script::Hebrew => Some("he"),
+		Script::Devanagari => Some("hi"),
+		Script::Thai => Some("th"),
+		Script::Hangul => Some("ko"),
+	}
+}
+
+fn detect_cyrillic_language(sample: &str) -> &'static str {
+	let count_of = |letters: &[char]| {
+		sample.chars().filter(|ch| letters.contains(&ch.to_lowercase().next().unwrap_or(*ch))).count()
+	};
+	let letters = sample.chars().filter(|ch| script_of(*ch) == Some(Script::Cyrillic)).count();
+	let serbian = count_of(&['ђ', 'ћ', 'љ', 'њ', 'џ', 'ј']);
+	let ukrainian = count_of(&['і', 'ї', 'є', 'ґ']);
+	let russian_only = count_of(&['ы', 'э', 'ё']);
+	let hard_sign = count_of(&['ъ']);
+	if serbian * 100 > letters {
+		"sr"
+	} else if ukrainian * 100 > letters {
+		"uk"
+	} else if russian_only == 0 && hard_sign * 200 > letters {
+		"bg"
+	} else {
+		"ru"
+	}
+}
+
+fn detect_latin_language(sample: &str) -> Option<&'static str> {
+	let trigrams = count_trigrams(sample);
+	let total: u64 = trigrams.values().sum();
+	if total == 0 {
+		return None;
+	}
+	let (code, hits) = LATIN_PROFILES
+		.iter()
+		.map(|(code, profile)| {
+			let hits: u64 = profile.iter().filter_map(|trigram| trigrams.get(*trigram)).sum();
+			(*code, hits)
+		})
+		.max_by_key(|(_, hits)| *hits)?;
+	(hits * 100 >= total * MIN_PROFILE_HIT_PERCENT).then_some(code)
+}
+
+/// Counts trigrams of lowercased words, each padded with a space on both sides.
+fn count_trigrams(sample: &str) -> HashMap<String, u64> {
+	let mut trigrams = HashMap::new();
+	let mut word: Vec<char> = vec![' '];
+	let mut flush = |word: &mut Vec<char>| {
+		if word.len() > 1 {
+			word.push(' ');
+			for window in word.windows(3) {
+				*trigrams.entry(window.iter().collect()).or_insert(0) += 1;
+			}
+		}
+		word.truncate(1);
+	};
+	for ch in sample.chars() {
+		if ch.is_alphabetic() {
+			word.extend(ch.to_lowercase());
+		} else {
+			flush(&mut word);
+		}
+	}
+	flush(&mut word);
+	trigrams
+}
+
+/// Maps a language tag declared by a file (`en-US`, `fr`, `ger`) to the matching code in
+/// [`DETECTABLE_LANGUAGES`], or `None` when it names another language.
+#[must_use]
+pub fn language_from_tag(tag: &str) -> Option<&'static str> {
+	let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+	let primary = match primary.as_str() {
+		"ara" => "ar",
+		"ces" | "cze" => "cs",
+		"deu" | "ger" => "de",
+		"eng" => "en",
+		"fra" | "fre" => "fr",
+		"ita" => "it",
+		"jpn" => "ja",
+		"nld" | "dut" => "nl",
+		"pol" => "pl",
+		"por" => "pt",
+		"rus" => "ru",
+		"spa" => "es",
+		"swe" => "sv",
+		"ukr" => "uk",
+		"zho" | "chi" => "zh",
+		other => other,
+	};
+	DETECTABLE_LANGUAGES.iter().copied().find(|code| *code == primary)
+}
+
+/// Decides whether `text` runs right to left by the first strong character of each paragraph in
+/// its first ~20k characters, as the Unicode bidi algorithm would per paragraph, and taking the
+/// direction most paragraphs start with. A Hebrew novel quoting English lines still reads right to
+/// left. Returns `None` when no paragraph has a strong character, or as many start each way.
+#[must_use]
+pub fn detect_direction(text: &str) -> Option<TextDirection> {
+	let sample_end = text.char_indices().nth(SAMPLE_CHARS).map_or(text.len(), |(index, _)| index);
+	let bidi = CodePointMapData::<BidiClass>::new();
+	let (mut ltr, mut rtl) = (0usize, 0usize);
+	for paragraph in text[..sample_end].split('\n') {
+		let first_strong = paragraph.chars().find_map(|ch| match bidi.get(ch) {
+			BidiClass::LeftToRight => Some(TextDirection::LeftToRight),
+			BidiClass::RightToLeft | BidiClass::ArabicLetter => Some(TextDirection::RightToLeft),
+			_ => None,
+		});
+		match first_strong {
+			Some(TextDirection::LeftToRight) => ltr += 1,
+			Some(TextDirection::RightToLeft) => rtl += 1,
+			None => {}
+		}
+	}
+	match rtl.cmp(&ltr) {
+		std::cmp::Ordering::Greater => Some(TextDirection::RightToLeft),
+		std::cmp::Ordering::Less => Some(TextDirection::LeftToRight),
+		std::cmp::Ordering::Equal => None,
+	}
+}
+
+/// The direction of the language a file declares (`he`, `ar-EG`, `az-Arab`), or `None` for an
+/// empty or undetermined tag.
+#[must_use]
+pub fn direction_of_tag(tag: &str) -> Option<TextDirection> {
+	let lowered = tag.trim().to_ascii_lowercase();
+	let mut subtags = lowered.split(['-', '_']);
+	let primary = subtags.next().filter(|primary| !primary.is_empty() && *primary != "und")?;
+	let rtl_script = subtags.any(|subtag| matches!(subtag, "arab" | "hebr" | "syrc" | "thaa" | "nkoo" | "adlm"));
+	let rtl_language = matches!(
+		primary,
+		"ar" | "ara"
+			| "he" | "heb"
+			| "iw" | "fa"
+			| "fas" | "per"
+			| "ur" | "urd"
+			| "yi" | "yid"
+			| "ps" | "pus"
+			| "sd" | "snd"
+			| "ug" | "uig"
+			| "dv" | "div"
+			| "ckb" | "syr"
+			| "arc"
+	);
+	Some(if rtl_language || rtl_script { TextDirection::RightToLeft } else { TextDirection::LeftToRight })
+}
+
+/// Returns the translated display name of a language code reported by `detect_language`.
+#[must_use]
+pub fn language_name(code: &str) -> String {
+	match code {
+		// TRANSLATORS: Name of a document language
+		"ar" => t("Arabic"),
+		// TRANSLATORS: Name of a document language
+		"bg" => t("Bulgarian"),
+		// TRANSLATORS: Name of a document language
+		"cs" => t("Czech"),
+		// TRANSLATORS: Name of a document language
+		"de" => t("German"),
+		// TRANSLATORS: Name of a document language
+		"el" => t("Greek"),
+		// TRANSLATORS: Name of a document language
+		"en" => t("English"),
+		// TRANSLATORS: Name of a document language
+		"es" => t("Spanish"),
+		// TRANSLATORS: Name of a document language
+		"fa" => t("Persian"),
+		// TRANSLATORS: Name of a document language
+		"fi" => t("Finnish"),
+		// TRANSLATORS: Name of a document language
+		"fr" => t("French"),
+		// TRANSLATORS: Name of a document language
+		"he" => t("Hebrew"),
+		// TRANSLATORS: Name of a document language
+		"hi" => t("Hindi"),
+		// TRANSLATORS: Name of a document language
+		"it" => t("Italian"),
+		// TRANSLATORS: Name of a document language
+		"ja" => t("Japanese"),
+		// TRANSLATORS: Name of a document language
+		"ko" => t("Korean"),
+		// TRANSLATORS: Name of a document language
+		"nl" => t("Dutch"),
+		// TRANSLATORS: Name of a document language
+		"pl" => t("Polish"),
+		// TRANSLATORS: Name of a document language
+		"pt" => t("Portuguese"),
+		// TRANSLATORS: Name of a document language
+		"ru" => t("Russian"),
+		// TRANSLATORS: Name of a document language
+		"sr" => t("Serbian"),
+		// TRANSLATORS: Name of a document language
+		"sv" => t("Swedish"),
+		// TRANSLATORS: Name of a document language
+		"th" => t("Thai"),
+		// TRANSLATORS: Name of a document language
+		"tr" => t("Turkish"),
+		// TRANSLATORS: Name of a document language
+		"uk" => t("Ukrainian"),
+		// TRANSLATORS: Name of a document language
+		"zh" => t("Chinese"),
+		_ => code.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	#[rstest]
+	#[case("en", "It was the best of times and the worst of times, for he had seen what the king was thinking of. ")]
+	#[case("fr", "Il était une fois une petite fille qui vivait dans les bois avec sa mère et ses deux sœurs. ")]
+	#[case("de", "Es war einmal ein König, der hatte drei Töchter, und die jüngste war die schönste von allen. ")]
+	#[case("es", "En un lugar de la Mancha, de cuyo nombre no quiero acordarme, vivía un hidalgo de los de lanza. ")]
+	#[case(
+		"it",
+		"Nel mezzo del cammin di nostra vita mi ritrovai per una selva oscura, che la diritta via era smarrita. "
+	)]
+	#[case("ru", "Все счастливые семьи похожи друг на друга, каждая несчастливая семья несчастлива по-своему. ")]
+	#[case(
+		"ja",
+		"吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。何でも薄暗いじめじめした所で泣いていた事だけは記憶している。"
+	)]
+	fn detects_language_of_long_samples(#[case] expected: &str, #[case] sentence: &str) {
+		let text = sentence.repeat(MIN_DETECTION_CHARS / sentence.chars().count() + 1);
+		assert_eq!(detect_language(&text), Some(expected));
+	}
+
+	#[rstest]
+	#[case("en-US", Some("en"))]
+	#[case("FR", Some("fr"))]
+	#[case("ger", Some("de"))]
+	#[case("pt_BR", Some("pt"))]
+	#[case("und", None)]
+	#[case("", None)]
+	fn declared_language_tags_map_to_detectable_codes(#[case] tag: &str, #[case] expected: Option<&str>) {
+		assert_eq!(language_from_tag(tag), expected);
+	}
+
+	#[test]
+	fn short_documents_are_not_classified() {
+		let text = "It was the best of times and the worst of times. ".repeat(5);
+		assert!(text.chars().count() < MIN_DETECTION_CHARS);
+		assert_eq!(detect_language(&text), None);
+	}
+
+	#[test]
+	fn text_without_letters_is_not_classified() {
+		assert_eq!(detect_language(&"1234 5678 - ".repeat(100)), None);
+	}
+
+	#[rstest]
+	#[case::hebrew("בראשית ברא אלהים את השמים ואת הארץ.\nוהארץ היתה תהו ובהו.", Some(TextDirection::RightToLeft))]
+	#[case::arabic("في البدء كان الكلمة.\nوكان الكلمة عند الله.", Some(TextDirection::RightToLeft))]
+	#[case::latin("In the beginning was the Word.\nAnd the Word was with God.", Some(TextDirection::LeftToRight))]
+	#[case::hebrew_quoting_english(
+		"הוא אמר: Hello there.\nשלום לכולם.\n\"To be or not to be\", כתב שייקספיר.\nסוף.",
+		Some(TextDirection::RightToLeft)
+	)]
+	#[case::english_quoting_arabic(
+		"The word السلام means peace.\nIt is a common greeting.\nسلام",
+		Some(TextDirection::LeftToRight)
+	)]
+	#[case::numbers_only("1234\n5678 - 90", None)]
+	#[case::even_split("Shalom.\nשלום.", None)]
+	fn detects_direction_by_first_strong_character(#[case] text: &str, #[case] expected: Option<TextDirection>) {
+		assert_eq!(detect_direction(text), expected);
+	}
+
+	#[test]
+	fn paragraphs_starting_with_digits_go_by_their_first_letter() {
+		assert_eq!(detect_direction("1. פרק ראשון\n2. פרק שני\n3. Appendix"), Some(TextDirection::RightToLeft));
+	}
+
+	#[rstest]
+	#[case("he", Some(TextDirection::RightToLeft))]
+	#[case("ar-EG", Some(TextDirection::RightToLeft))]
+	#[case("fas", Some(TextDirection::RightToLeft))]
+	#[case("az-Arab", Some(TextDirection::RightToLeft))]
+	#[case("en-US", Some(TextDirection::LeftToRight))]
+	#[case("und", None)]
+	#[case("", None)]
+	fn declared_language_tags_give_a_direction(#[case] tag: &str, #[case] expected: Option<TextDirection>) {
+		assert_eq!(direction_of_tag(tag), expected);
+	}
+
+	#[test]
+	fn every_detectable_language_has_a_name() {
+		for code in DETECTABLE_LANGUAGES {
+			assert_ne!(language_name(code), *code);
+		}
+	}
+}
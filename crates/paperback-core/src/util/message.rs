@@ -0,0 +1,429 @@
+//! Filling in translated messages.
+//!
+//! Messages with a count carry every plural variant in one string, separated by `|` and ordered
+//! the way the catalog's `Plural-Forms` header numbers them: English has `"%d minute|%d minutes"`,
+//! Polish three variants. A translation whose variant count doesn't match its header, such as one
+//! made before the message had variants or one not translated yet, is read with the English rule.
+//!
+//! Large numbers are written with their digits grouped the way the catalog's language groups them.
+
+use std::cell::RefCell;
+
+use crate::t;
+
+/// Separates the plural variants of a message.
+const VARIANT_SEPARATOR: char = '|';
+
+/// A `Plural-Forms` rule: how many variants a language has and which one a count takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluralForms {
+	count: usize,
+	rule: Expr,
+}
+
+impl PluralForms {
+	/// One variant for 1, another for everything else.
+	#[must_use]
+	pub fn english() -> Self {
+		Self { count: 2, rule: Expr::Binary(BinaryOp::Ne, Box::new(Expr::N), Box::new(Expr::Number(1))) }
+	}
+
+	/// Reads the `Plural-Forms` line of a catalog header, e.g.
+	/// `Plural-Forms: nplurals=2; plural=(n > 1);`. `None` when the header has none or it can't be parsed.
+	#[must_use]
+	pub fn from_header(header: &str) -> Option<Self> {
+		let line = header.lines().find_map(|line| line.trim().strip_prefix("Plural-Forms:"))?;
+		let mut count = None;
+		let mut rule = None;
+		for field in line.split(';') {
+			let Some((key, value)) = field.split_once('=') else {
+				continue;
+			};
+			match key.trim() {
+				"nplurals" => count = value.trim().parse::<usize>().ok().filter(|&count| count > 0),
+				"plural" => rule = Parser::parse(value),
+				_ => {}
+			}
+		}
+		Some(Self { count: count?, rule: rule? })
+	}
+
+	/// Number of variants a message has in this language.
+	#[must_use]
+	pub const fn count(&self) -> usize {
+		self.count
+	}
+
+	/// Which variant `n` takes, clamped to the variants there are.
+	#[must_use]
+	pub fn index(&self, n: u64) -> usize {
+		usize::try_from(self.rule.eval(n)).unwrap_or(usize::MAX).min(self.count - 1)
+	}
+}
+
+/// Picks the variant of `message` for `count` under `forms` and fills `%d` in with `count`.
+#[must_use]
+pub fn select_plural(message: &str, count: u64, forms: &PluralForms) -> String {
+	fill_plural(message, count, forms, &count.to_string())
+}
+
+fn fill_plural(message: &str, count: u64, forms: &PluralForms, count_text: &str) -> String {
+	let variants: Vec<&str> = message.split(VARIANT_SEPARATOR).collect();
+	let index = if variants.len() == forms.count() {
+		forms.index(count)
+	} else {
+		PluralForms::english().index(count).min(variants.len() - 1)
+	};
+	format_message(variants[index], &[("%d", count_text)])
+}
+
+/// The separator a language puts between groups of three digits: a period in German or Spanish,
+/// a no-break space in French or Russian, and a comma otherwise. `language` is a catalog code
+/// such as `pt_BR` or `fr`.
+#[must_use]
+pub fn digit_group_separator(language: &str) -> &'static str {
+	let primary = language.split(['_', '-']).next().unwrap_or_default().to_ascii_lowercase();
+	match primary.as_str() {
+		"bs" | "da" | "de" | "el" | "es" | "hr" | "id" | "it" | "nl" | "pt" | "ro" | "sl" | "sr" | "tr" | "vi" => ".",
+		"be" | "bg" | "cs" | "et" | "fi" | "fr" | "hu" | "lt" | "lv" | "nb" | "no" | "pl" | "ru" | "sk" | "sv"
+		| "uk" => "\u{a0}",
+		_ => ",",
+	}
+}
+
+/// Writes `n` with `separator` between each group of three digits, counting from the right.
+#[must_use]
+pub fn group_digits(n: u64, separator: &str) -> String {
+	let digits = n.to_string();
+	let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+	for (index, digit) in digits.chars().enumerate() {
+		if index > 0 && (digits.len() - index) % 3 == 0 {
+			grouped.push_str(separator);
+		}
+		grouped.push(digit);
+	}
+	grouped
+}
+
+/// Writes `n` with its digits grouped the way the current catalog's language does, for counts shown
+/// in the interface such as words, lines and characters.
+#[must_use]
+pub fn format_number(n: u64) -> String {
+	// The translation of the empty message is the catalog header, which names its language.
+	let header = t("");
+	group_digits(n, digit_group_separator(header_language(&header)))
+}
+
+/// The `Language:` field of a catalog header; empty, and so English, without one.
+fn header_language(header: &str) -> &str {
+	header.lines().find_map(|line| line.trim().strip_prefix("Language:")).map_or("", str::trim)
+}
+
+/// Picks the variant of an already translated `message` for `count`, by the rule in the current
+/// catalog's header, and fills `%d` in with `count`, its digits grouped for the catalog's language.
+#[must_use]
+pub fn plural(message: &str, count: u64) -> String {
+	thread_local! {
+		static FORMS: RefCell<Option<(String, PluralForms)>> = const { RefCell::new(None) };
+	}
+	// The translation of the empty message is the catalog header.
+	let header = t("");
+	FORMS.with_borrow_mut(|cached| {
+		let forms = match cached.take() {
+			Some((cached_header, forms)) if cached_header == header => forms,
+			_ => PluralForms::from_header(&header).unwrap_or_else(PluralForms::english),
+		};
+		let count_text = group_digits(count, digit_group_separator(header_language(&header)));
+		let text = fill_plural(message, count, &forms, &count_text);
+		*cached = Some((header, forms));
+		text
+	})
+}
+
+/// Replaces the placeholders in `template` in a single pass, so text filled in for one placeholder
+/// is never mistaken for another. A placeholder that appears several times, like the two `%d` of
+/// `"%d h %d min"`, takes its arguments in order; extra occurrences are left as they are.
+#[must_use]
+pub fn format_message(template: &str, args: &[(&str, &str)]) -> String {
+	let mut used = vec![false; args.len()];
+	let mut output = String::with_capacity(template.len());
+	let mut rest = template;
+	'scan: while let Some(ch) = rest.chars().next() {
+		for (index, (placeholder, value)) in args.iter().enumerate() {
+			if !used[index] && !placeholder.is_empty() && rest.starts_with(placeholder) {
+				used[index] = true;
+				output.push_str(value);
+				rest = &rest[placeholder.len()..];
+				continue 'scan;
+			}
+		}
+		output.push(ch);
+		rest = &rest[ch.len_utf8()..];
+	}
+	output
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+	Or,
+	And,
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+	Add,
+	Sub,
+	Mul,
+	Div,
+	Rem,
+}
+
+/// A C expression over `n`, as gettext writes plural rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+	N,
+	Number(u64),
+	Not(Box<Self>),
+	Binary(BinaryOp, Box<Self>, Box<Self>),
+	Conditional(Box<Self>, Box<Self>, Box<Self>),
+}
+
+impl Expr {
+	fn eval(&self, n: u64) -> u64 {
+		match self {
+			Self::N => n,
+			Self::Number(value) => *value,
+			Self::Not(operand) => u64::from(operand.eval(n) == 0),
+			Self::Conditional(condition, then, otherwise) => {
+				if condition.eval(n) == 0 {
+					otherwise.eval(n)
+				} else {
+					then.eval(n)
+				}
+			}
+			Self::Binary(op, left, right) => {
+				let (left, right) = (left.eval(n), right.eval(n));
+				match op {
+					BinaryOp::Or => u64::from(left != 0 || right != 0),
+					BinaryOp::And => u64::from(left != 0 && right != 0),
+					BinaryOp::Eq => u64::from(left == right),
+					BinaryOp::Ne => u64::from(left != right),
+					BinaryOp::Lt => u64::from(left < right),
+					BinaryOp::Le => u64::from(left <= right),
+					BinaryOp::Gt => u64::from(left > right),
+					BinaryOp::Ge => u64::from(left >= right),
+					BinaryOp::Add => left.wrapping_add(right),
+					BinaryOp::Sub => left.wrapping_sub(right),
+					BinaryOp::Mul => left.wrapping_mul(right),
+					BinaryOp::Div => left.checked_div(right).unwrap_or(0),
+					BinaryOp::Rem => left.checked_rem(right).unwrap_or(0),
+				}
+			}
+		}
+	}
+}
+
+/// Recursive descent over a plural rule, lowest precedence first: `?:`, `||`, `&&`, equality,
+/// comparison, additive, multiplicative, then `!`, parentheses, `n` and numbers.
+struct Parser<'a> {
+	rest: &'a str,
+}
+
+/// Operators of each binary precedence level, lowest first. Longer operators come before their
+/// prefixes so `<=` isn't read as `<`.
+const BINARY_LEVELS: [&[(&str, BinaryOp)]; 6] = [
+	&[("||", BinaryOp::Or)],
+	&[("&&", BinaryOp::And)],
+	&[("==", BinaryOp::Eq), ("!=", BinaryOp::Ne)],
+	&[("<=", BinaryOp::Le), (">=", BinaryOp::Ge), ("<", BinaryOp::Lt), (">", BinaryOp::Gt)],
+	&[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+	&[("*", BinaryOp::Mul), ("/", BinaryOp::Div), ("%", BinaryOp::Rem)],
+];
+
+impl<'a> Parser<'a> {
+	fn parse(source: &'a str) -> Option<Expr> {
+		let mut parser = Self { rest: source };
+		let expr = parser.conditional()?;
+		parser.rest.trim().is_empty().then_some(expr)
+	}
+
+	fn eat(&mut self, token: &str) -> bool {
+		let trimmed = self.rest.trim_start();
+		if let Some(rest) = trimmed.strip_prefix(token) {
+			self.rest = rest;
+			true
+		} else {
+			false
+		}
+	}
+
+	fn conditional(&mut self) -> Option<Expr> {
+		let condition = self.binary(0)?;
+		if !self.eat("?") {
+			return Some(condition);
+		}
+		let then = self.conditional()?;
+		if !self.eat(":") {
+			return None;
+		}
+		let otherwise = self.conditional()?;
+		Some(Expr::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)))
+	}
+
+	fn binary(&mut self, level: usize) -> Option<Expr> {
+		let Some(operators) = BINARY_LEVELS.get(level) else {
+			return self.unary();
+		};
+		let mut left = self.binary(level + 1)?;
+		'operators: loop {
+			for &(token, op) in *operators {
+				if self.eat(token) {
+					let right = self.binary(level + 1)?;
+					left = Expr::Binary(op, Box::new(left), Box::new(right));
+					continue 'operators;
+				}
+			}
+			return Some(left);
+		}
+	}
+
+	fn unary(&mut self) -> Option<Expr> {
+		if self.eat("!") {
+			return Some(Expr::Not(Box::new(self.unary()?)));
+		}
+		if self.eat("(") {
+			let inner = self.conditional()?;
+			return self.eat(")").then_some(inner);
+		}
+		if self.eat("n") {
+			return Some(Expr::N);
+		}
+		let trimmed = self.rest.trim_start();
+		let digits = trimmed.len() - trimmed.trim_start_matches(|ch: char| ch.is_ascii_digit()).len();
+		let value = trimmed[..digits].parse().ok()?;
+		self.rest = &trimmed[digits..];
+		Some(Expr::Number(value))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use rstest::rstest;
+
+	use super::*;
+
+	const POLISH_HEADER: &str = "Language: pl\nPlural-Forms: nplurals=3; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && \
+	                             (n%100<12 || n%100>14) ? 1 : 2);\n";
+	const FRENCH_HEADER: &str = "Language: fr\nPlural-Forms: nplurals=2; plural=(n > 1);\n";
+
+	/// A stand-in for a loaded catalog: its header and translations.
+	struct MockCatalog {
+		forms: PluralForms,
+		messages: HashMap<&'static str, &'static str>,
+	}
+
+	impl MockCatalog {
+		fn new(header: &str, messages: &[(&'static str, &'static str)]) -> Self {
+			let forms = PluralForms::from_header(header).unwrap_or_else(PluralForms::english);
+			Self { forms, messages: messages.iter().copied().collect() }
+		}
+
+		fn plural(&self, message: &str, count: u64) -> String {
+			select_plural(self.messages.get(message).copied().unwrap_or(message), count, &self.forms)
+		}
+	}
+
+	const MINUTES: &str = "%d minute|%d minutes";
+
+	#[rstest]
+	#[case(0, "0 minutes")]
+	#[case(1, "1 minute")]
+	#[case(2, "2 minutes")]
+	fn english_has_one_and_many(#[case] count: u64, #[case] expected: &str) {
+		let catalog = MockCatalog::new("", &[]);
+		assert_eq!(catalog.plural(MINUTES, count), expected);
+	}
+
+	#[rstest]
+	#[case(0, "0 minute")]
+	#[case(1, "1 minute")]
+	#[case(2, "2 minutes")]
+	fn french_counts_zero_as_singular(#[case] count: u64, #[case] expected: &str) {
+		let catalog = MockCatalog::new(FRENCH_HEADER, &[(MINUTES, "%d minute|%d minutes")]);
+		assert_eq!(catalog.plural(MINUTES, count), expected);
+	}
+
+	#[rstest]
+	#[case(1, "1 minuta")]
+	#[case(2, "2 minuty")]
+	#[case(4, "4 minuty")]
+	#[case(5, "5 minut")]
+	#[case(12, "12 minut")]
+	#[case(22, "22 minuty")]
+	#[case(25, "25 minut")]
+	#[case(112, "112 minut")]
+	fn polish_has_three_forms(#[case] count: u64, #[case] expected: &str) {
+		let catalog = MockCatalog::new(POLISH_HEADER, &[(MINUTES, "%d minuta|%d minuty|%d minut")]);
+		assert_eq!(catalog.plural(MINUTES, count), expected);
+	}
+
+	#[test]
+	fn untranslated_and_older_messages_fall_back_to_english_rules() {
+		let catalog = MockCatalog::new(POLISH_HEADER, &[("%d word|%d words", "%d słów")]);
+		assert_eq!(catalog.plural(MINUTES, 5), "5 minutes");
+		assert_eq!(catalog.plural(MINUTES, 1), "1 minute");
+		assert_eq!(catalog.plural("%d word|%d words", 1), "1 słów");
+	}
+
+	#[test]
+	fn headers_without_plural_forms_are_rejected() {
+		assert_eq!(PluralForms::from_header("Language: de\n"), None);
+		assert_eq!(PluralForms::from_header("Plural-Forms: nplurals=2; plural=(n >;\n"), None);
+		let japanese = PluralForms::from_header("Plural-Forms: nplurals=1; plural=0;\n").unwrap();
+		assert_eq!(japanese.index(7), 0);
+	}
+
+	#[rstest]
+	#[case("en", 1_234_567, "1,234,567")]
+	#[case("zh_CN", 45_000, "45,000")]
+	#[case("de", 1_234_567, "1.234.567")]
+	#[case("pt_BR", 10_000, "10.000")]
+	#[case("fr", 1_234_567, "1\u{a0}234\u{a0}567")]
+	#[case("pl", 25_000, "25\u{a0}000")]
+	#[case("ru", 999, "999")]
+	#[case("", 1000, "1,000")]
+	fn digits_are_grouped_by_language(#[case] language: &str, #[case] n: u64, #[case] expected: &str) {
+		assert_eq!(group_digits(n, digit_group_separator(language)), expected);
+	}
+
+	#[rstest]
+	#[case(0, "0")]
+	#[case(12, "12")]
+	#[case(123, "123")]
+	#[case(1234, "1,234")]
+	#[case(123_456, "123,456")]
+	#[case(u64::MAX, "18,446,744,073,709,551,615")]
+	fn groups_are_counted_from_the_right(#[case] n: u64, #[case] expected: &str) {
+		assert_eq!(group_digits(n, ","), expected);
+	}
+
+	#[test]
+	fn catalog_headers_name_their_language() {
+		assert_eq!(header_language(POLISH_HEADER), "pl");
+		assert_eq!(header_language("Content-Type: text/plain\n"), "");
+	}
+
+	#[test]
+	fn format_message_substitutes_in_one_pass() {
+		assert_eq!(
+			format_message("%s Heading level %d", &[("%s", "Costs: 5%d"), ("%d", "2")]),
+			"Costs: 5%d Heading level 2"
+		);
+		assert_eq!(format_message("%d h %d min", &[("%d", "1"), ("%d", "40")]), "1 h 40 min");
+		assert_eq!(format_message("Seite %s: %d", &[("%d", "3"), ("%s", "Intro")]), "Seite Intro: 3");
+	}
+}
@@ -0,0 +1,95 @@
+use std::{fs, path::Path};
+
+/// A key naming the file `path` points to, so two spellings of the same document (relative or
+/// absolute, through a symlink, with `\\?\` or mixed separators, or in another case on Windows)
+/// compare equal. Paths that don't exist are still made absolute and normalized; pseudo-paths
+/// such as web addresses are returned unchanged.
+#[must_use]
+pub fn document_path_key(path: &Path) -> String {
+	let text = path.to_string_lossy();
+	if text.contains("://") {
+		return text.into_owned();
+	}
+	let resolved = fs::canonicalize(path).or_else(|_| std::path::absolute(path)).unwrap_or_else(|_| path.to_path_buf());
+	normalize_path_text(&resolved.to_string_lossy(), cfg!(windows))
+}
+
+/// Normalizes a path without touching the file system: separators become `/`, verbatim (`\\?\`)
+/// prefixes are dropped, `.` and `..` segments are resolved and repeated or trailing separators
+/// are removed. `case_insensitive` also lowercases it, as Windows compares paths.
+#[must_use]
+pub fn normalize_path_text(path: &str, case_insensitive: bool) -> String {
+	let unified = path.replace('\\', "/");
+	let unified = if let Some(share) = unified.strip_prefix("//?/UNC/") {
+		format!("//{share}")
+	} else {
+		unified.strip_prefix("//?/").map_or(unified.clone(), ToString::to_string)
+	};
+	let (root, rest) = if let Some(rest) = unified.strip_prefix("//") {
+		("//", rest)
+	} else if let Some(rest) = unified.strip_prefix('/') {
+		("/", rest)
+	} else {
+		("", unified.as_str())
+	};
+	let is_drive = |segment: &str| segment.len() == 2 && segment.ends_with(':');
+	let relative = root.is_empty() && !rest.split('/').next().is_some_and(is_drive);
+	let mut segments: Vec<&str> = Vec::new();
+	for segment in rest.split('/') {
+		match segment {
+			"" | "." => {}
+			".." => match segments.last() {
+				Some(&last) if last != ".." && !is_drive(last) => {
+					segments.pop();
+				}
+				_ if relative => segments.push(".."),
+				_ => {}
+			},
+			_ => segments.push(segment),
+		}
+	}
+	let normalized = format!("{root}{}", segments.join("/"));
+	if case_insensitive { normalized.to_lowercase() } else { normalized }
+}
+
+#[cfg(test)]
+mod tests {
+	use std::env;
+
+	use super::*;
+
+	#[test]
+	fn case_and_separator_differences_are_ignored_when_case_insensitive() {
+		assert_eq!(normalize_path_text(r"C:\Books\a.epub", true), normalize_path_text("c:/books/A.EPUB", true));
+		assert_ne!(normalize_path_text(r"C:\Books\a.epub", false), normalize_path_text("c:/books/A.EPUB", false));
+		assert_eq!(normalize_path_text(r"C:\Books\\a.epub\", false), "C:/Books/a.epub");
+	}
+
+	#[test]
+	fn verbatim_and_unc_prefixes_are_dropped() {
+		assert_eq!(normalize_path_text(r"\\?\C:\Books\a.epub", false), "C:/Books/a.epub");
+		assert_eq!(normalize_path_text(r"\\?\UNC\server\share\a.epub", false), "//server/share/a.epub");
+		assert_eq!(normalize_path_text(r"\\server\share\a.epub", false), "//server/share/a.epub");
+	}
+
+	#[test]
+	fn dot_segments_are_resolved() {
+		assert_eq!(normalize_path_text("/home/me/./old/../a.epub", false), "/home/me/a.epub");
+		assert_eq!(normalize_path_text(r"C:\..\Books\a.epub", false), "C:/Books/a.epub");
+		assert_eq!(normalize_path_text("books/../../a.epub", false), "../a.epub");
+	}
+
+	#[test]
+	fn relative_paths_match_their_absolute_form() {
+		let absolute = env::current_dir().unwrap().join("paperback-missing-book.epub");
+		assert_eq!(
+			document_path_key(Path::new("paperback-missing-dir/../paperback-missing-book.epub")),
+			document_path_key(&absolute)
+		);
+	}
+
+	#[test]
+	fn pseudo_paths_are_kept_as_is() {
+		assert_eq!(document_path_key(Path::new("https://example.com/Book.epub")), "https://example.com/Book.epub");
+	}
+}
@@ -1,5 +1,12 @@
+use std::{cmp::Ordering, ops::Range};
+
+use icu_properties::{
+	CodePointMapData,
+	props::{GeneralCategory, GeneralCategoryGroup, Script},
+};
 use pulldown_cmark::{Event, Parser, TagEnd};
 use roman::to;
+use unicode_segmentation::GraphemeCursor;
 
 #[must_use]
 pub fn markdown_to_text(markdown: &str) -> String {
@@ -27,11 +34,186 @@ pub fn remove_soft_hyphens(input: &str) -> String {
 	input.replace("\u{00AD}", "")
 }
 
+/// Maps offsets in a text to offsets in a rewritten copy of it, for the spans whose length changed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OffsetMap {
+	/// Changed spans as (old start, old end, new start, new end), in display units and in order.
+	edits: Vec<(usize, usize, usize, usize)>,
+}
+
+impl OffsetMap {
+	/// The map for `len` display units inserted at `at`; an offset at the insertion point moves past
+	/// the inserted text.
+	#[must_use]
+	pub fn insertion(at: usize, len: usize) -> Self {
+		Self { edits: vec![(at, at, at, at + len)] }
+	}
+
+	#[must_use]
+	pub fn is_identity(&self) -> bool {
+		self.edits.is_empty()
+	}
+
+	/// An offset inside a rewritten span lands at the same distance into its replacement, clamped
+	/// to the replacement's end.
+	#[must_use]
+	pub fn map(&self, offset: usize) -> usize {
+		let idx = self.edits.partition_point(|&(_, old_end, _, _)| old_end <= offset);
+		if let Some(&(old_start, _, new_start, new_end)) = self.edits.get(idx)
+			&& old_start < offset
+		{
+			return (new_start + offset - old_start).min(new_end);
+		}
+		idx.checked_sub(1).map_or(offset, |prev| {
+			let (_, old_end, _, new_end) = self.edits[prev];
+			offset - old_end + new_end
+		})
+	}
+
+	/// Adds the edits of `other`, a map over text that follows everything this map covers, starting
+	/// at `old_base` in the old text and `new_base` in the new one.
+	pub fn append(&mut self, other: &Self, old_base: usize, new_base: usize) {
+		self.edits.extend(other.edits.iter().map(|&(old_start, old_end, new_start, new_end)| {
+			(old_start + old_base, old_end + old_base, new_start + new_base, new_end + new_base)
+		}));
+	}
+}
+
+/// Deletes `ranges` (display units, in order and not overlapping) from `input`. Returns the new
+/// text and the map from old to new offsets; an offset inside a deleted range maps to where the
+/// range was.
+#[must_use]
+pub fn remove_ranges(input: &str, ranges: &[Range<usize>]) -> (String, OffsetMap) {
+	let mut output = String::with_capacity(input.len());
+	let mut map = OffsetMap::default();
+	let mut removed = 0;
+	for range in ranges.iter().filter(|range| !range.is_empty()) {
+		let new_start = range.start - removed;
+		map.edits.push((range.start, range.end, new_start, new_start));
+		removed += range.len();
+	}
+	let mut pending = ranges.iter().filter(|range| !range.is_empty()).peekable();
+	let mut old_offset = 0;
+	for ch in input.chars() {
+		while pending.peek().is_some_and(|range| range.end <= old_offset) {
+			pending.next();
+		}
+		if !pending.peek().is_some_and(|range| range.start <= old_offset) {
+			output.push(ch);
+		}
+		old_offset += ch_width(ch);
+	}
+	(output, map)
+}
+
+/// Rewrites typographic characters that speech synthesizers tend to mangle: curly quotes become
+/// straight ones, en and em dashes become a spaced hyphen, the ellipsis character becomes three
+/// periods, non-breaking spaces become plain spaces, and soft hyphens are dropped.
+///
+/// Returns the new text and the map from old to new offsets, so markers computed against the
+/// original text can be moved.
+#[must_use]
+pub fn normalize_typography(input: &str) -> (String, OffsetMap) {
+	let mut output = String::with_capacity(input.len());
+	let mut map = OffsetMap::default();
+	let mut old_offset = 0;
+	let mut new_offset = 0;
+	let mut chars = input.chars().peekable();
+	while let Some(ch) = chars.next() {
+		let replacement = match ch {
+			'\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => "'".to_string(),
+			'\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => "\"".to_string(),
+			'\u{00A0}' | '\u{202F}' | '\u{2007}' => " ".to_string(),
+			'\u{00AD}' => String::new(),
+			'\u{2026}' => "...".to_string(),
+			'\u{2013}' | '\u{2014}' => {
+				let mut dash = String::new();
+				if output.chars().next_back().is_some_and(|prev| !prev.is_whitespace()) {
+					dash.push(' ');
+				}
+				dash.push('-');
+				if chars.peek().is_some_and(|&next| !is_space_like(next)) {
+					dash.push(' ');
+				}
+				dash
+			}
+			_ => {
+				output.push(ch);
+				old_offset += ch_width(ch);
+				new_offset += ch_width(ch);
+				continue;
+			}
+		};
+		let old_len = ch_width(ch);
+		let new_len = display_len(&replacement);
+		if old_len != new_len {
+			map.edits.push((old_offset, old_offset + old_len, new_offset, new_offset + new_len));
+		}
+		output.push_str(&replacement);
+		old_offset += old_len;
+		new_offset += new_len;
+	}
+	(output, map)
+}
+
+/// Turns Windows (`\r\n`) and old Mac (lone `\r`) line endings into `\n`, so every line-based
+/// feature counts the same lines.
+///
+/// Returns the new text and the map from old to new offsets, like [`normalize_typography`].
+#[must_use]
+pub fn normalize_newlines(input: &str) -> (String, OffsetMap) {
+	let mut map = OffsetMap::default();
+	if !input.contains('\r') {
+		return (input.to_string(), map);
+	}
+	let mut output = String::with_capacity(input.len());
+	let mut old_offset = 0;
+	let mut new_offset = 0;
+	let mut chars = input.chars().peekable();
+	while let Some(ch) = chars.next() {
+		if ch != '\r' {
+			output.push(ch);
+			old_offset += ch_width(ch);
+			new_offset += ch_width(ch);
+			continue;
+		}
+		output.push('\n');
+		if chars.next_if_eq(&'\n').is_some() {
+			map.edits.push((old_offset, old_offset + 2, new_offset, new_offset + 1));
+			old_offset += 2;
+		} else {
+			old_offset += 1;
+		}
+		new_offset += 1;
+	}
+	(output, map)
+}
+
 #[must_use]
 pub fn url_decode(input: &str) -> String {
 	percent_encoding::percent_decode_str(input).decode_utf8_lossy().into_owned()
 }
 
+/// Shortens `text` to at most `max_chars` characters plus an ellipsis, cutting at the last word
+/// boundary that fits. A single word longer than that is cut mid-word.
+#[must_use]
+pub fn ellipsize_words(text: &str, max_chars: usize) -> String {
+	let text = text.trim();
+	let Some((cut, _)) = text.char_indices().nth(max_chars) else {
+		return text.to_string();
+	};
+	let head = snippet_at(text, 0, cut);
+	if head.len() == text.len() {
+		return text.to_string();
+	}
+	let cut = head.len();
+	let head = match head.rfind(char::is_whitespace) {
+		Some(space) if !text[cut..].starts_with(char::is_whitespace) => &head[..space],
+		_ => head,
+	};
+	format!("{}\u{2026}", head.trim_end())
+}
+
 #[must_use]
 pub fn collapse_whitespace(input: &str) -> String {
 	if input.is_empty() {
@@ -62,6 +244,47 @@ pub fn collapse_whitespace(input: &str) -> String {
 	result
 }
 
+/// Compares strings so runs of digits sort by value ("part2" before "part10") and case is ignored.
+/// Strings that only differ in case or zero padding fall back to a plain comparison.
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+	let mut left = a.chars().peekable();
+	let mut right = b.chars().peekable();
+	loop {
+		match (left.peek().copied(), right.peek().copied()) {
+			(None, None) => return a.cmp(b),
+			(None, Some(_)) => return Ordering::Less,
+			(Some(_), None) => return Ordering::Greater,
+			(Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+				let l_digits = take_digits(&mut left);
+				let r_digits = take_digits(&mut right);
+				let l_value = l_digits.trim_start_matches('0');
+				let r_value = r_digits.trim_start_matches('0');
+				let ordering = l_value.len().cmp(&r_value.len()).then_with(|| l_value.cmp(r_value));
+				if ordering != Ordering::Equal {
+					return ordering;
+				}
+			}
+			(Some(l), Some(r)) => {
+				let ordering = l.to_lowercase().cmp(r.to_lowercase());
+				if ordering != Ordering::Equal {
+					return ordering;
+				}
+				left.next();
+				right.next();
+			}
+		}
+	}
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+	let mut digits = String::new();
+	while let Some(ch) = chars.next_if(char::is_ascii_digit) {
+		digits.push(ch);
+	}
+	digits
+}
+
 #[must_use]
 pub fn trim_string(s: &str) -> String {
 	s.trim_matches(is_space_like).to_string()
@@ -81,11 +304,264 @@ pub const fn ch_width(ch: char) -> usize {
 	if cfg!(any(windows, target_os = "macos")) { ch.len_utf16() } else { 1 }
 }
 
+/// Byte index of the character at display offset `display`. An offset that falls inside a
+/// character (the second half of a surrogate pair) resolves to that character's start; offsets
+/// past the end clamp to `s.len()`.
+#[must_use]
+pub fn display_to_byte_index(s: &str, display: usize) -> usize {
+	let mut units = 0usize;
+	for (byte_idx, ch) in s.char_indices() {
+		units += ch_width(ch);
+		if units > display {
+			return byte_idx;
+		}
+	}
+	s.len()
+}
+
+/// Display offset of byte index `byte_idx`, which must lie on a char boundary.
+#[must_use]
+pub fn byte_to_display_index(s: &str, byte_idx: usize) -> usize {
+	s.get(..byte_idx).map_or_else(|| display_len(s), display_len)
+}
+
+/// Start of the grapheme cluster holding byte `byte_idx`, clamped to the text.
+fn cluster_floor(text: &str, byte_idx: usize) -> usize {
+	let mut idx = byte_idx.min(text.len());
+	while !text.is_char_boundary(idx) {
+		idx -= 1;
+	}
+	let mut cursor = GraphemeCursor::new(idx, text.len(), true);
+	if cursor.is_boundary(text, 0).unwrap_or(true) {
+		return idx;
+	}
+	cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(0)
+}
+
+/// End of the grapheme cluster holding byte `byte_idx`, or `byte_idx` itself on a boundary.
+fn cluster_ceil(text: &str, byte_idx: usize) -> usize {
+	let start = cluster_floor(text, byte_idx);
+	if start == byte_idx.min(text.len()) {
+		return start;
+	}
+	GraphemeCursor::new(start, text.len(), true).next_boundary(text, 0).ok().flatten().unwrap_or(text.len())
+}
+
+/// The text from byte `start` to byte `end`, widened to whole grapheme clusters so a snippet never
+/// starts on a lone combining mark or stops partway through an emoji. Offsets past the end clamp to
+/// it, and an `end` before `start` gives an empty snippet.
+#[must_use]
+pub fn snippet_at(text: &str, start: usize, end: usize) -> &str {
+	let start = cluster_floor(text, start);
+	let end = cluster_ceil(text, end).max(start);
+	&text[start..end]
+}
+
+/// The line holding byte `pos`, without its line break. A position on a line break belongs to the
+/// line the break ends, and positions past the end give the last line.
+#[must_use]
+pub fn line_at(text: &str, pos: usize) -> &str {
+	let pos = cluster_floor(text, pos);
+	let start = text[..pos].rfind('\n').map_or(0, |idx| idx + 1);
+	let end = text[pos..].find('\n').map_or(text.len(), |idx| pos + idx);
+	let line = &text[start..end];
+	line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Byte range of the word at `byte_idx`, or of the word ending right before it when the caret sits
+/// just after one. Apostrophes and hyphens with letters on both sides count as part of the word, so
+/// "don't" and "well-known" are looked up whole.
+#[must_use]
+pub fn word_at(text: &str, byte_idx: usize) -> Option<Range<usize>> {
+	let mut idx = byte_idx.min(text.len());
+	while !text.is_char_boundary(idx) {
+		idx -= 1;
+	}
+	let line_start = text[..idx].rfind('\n').map_or(0, |pos| pos + 1);
+	let line_end = text[idx..].find('\n').map_or(text.len(), |pos| idx + pos);
+	let chars: Vec<(usize, char)> =
+		text[line_start..line_end].char_indices().map(|(pos, ch)| (line_start + pos, ch)).collect();
+	let in_word = |i: usize| {
+		let ch = chars[i].1;
+		ch.is_alphanumeric()
+			|| (matches!(ch, '\'' | '\u{2019}' | '-')
+				&& i > 0 && i + 1 < chars.len()
+				&& chars[i - 1].1.is_alphanumeric()
+				&& chars[i + 1].1.is_alphanumeric())
+	};
+	let mut pos = chars.partition_point(|&(byte, _)| byte < idx);
+	if pos == chars.len() || !in_word(pos) {
+		if pos > 0 && in_word(pos - 1) {
+			pos -= 1;
+		} else {
+			return None;
+		}
+	}
+	let mut start = pos;
+	while start > 0 && in_word(start - 1) {
+		start -= 1;
+	}
+	let mut end = pos + 1;
+	while end < chars.len() && in_word(end) {
+		end += 1;
+	}
+	Some(chars[start].0..chars.get(end).map_or(line_end, |&(byte, _)| byte))
+}
+
+/// Words found by [`count_words`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WordCount {
+	pub words: usize,
+	/// How many of `words` are single Han or Hiragana characters, which are read faster than words
+	/// of alphabetic scripts.
+	pub ideographic: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WordClass {
+	Ideograph,
+	Katakana,
+	Letter,
+	Joiner,
+	NumberJoiner,
+	Other,
+}
+
+/// Counts words in a single pass, roughly following UAX #29 word boundaries.
+///
+/// Runs of letters, digits and combining marks make one word, and an apostrophe, hyphen or full stop
+/// between two of them joins rather than splits, so "mother-in-law", "l'homme" and "3.14" are one
+/// word each, as is "1,000". Chinese and Japanese are written without spaces, so every Han and
+/// Hiragana character counts on its own while a Katakana run counts once. Punctuation standing
+/// alone is not a word.
+#[must_use]
+pub fn count_words(text: &str) -> WordCount {
+	let scripts = CodePointMapData::<Script>::new();
+	let categories = CodePointMapData::<GeneralCategory>::new();
+	let classify = |ch: char| match ch {
+		'\'' | '\u{2019}' | '-' | '\u{2010}' | '\u{2011}' | '.' => WordClass::Joiner,
+		',' => WordClass::NumberJoiner,
+		'\u{30FC}' | '\u{FF70}' => WordClass::Katakana,
+		_ if ch.is_ascii_alphanumeric() => WordClass::Letter,
+		_ if ch.is_ascii() => WordClass::Other,
+		_ => match scripts.get(ch) {
+			Script::Han | Script::Hiragana => WordClass::Ideograph,
+			Script::Katakana => WordClass::Katakana,
+			_ if ch.is_alphanumeric() || GeneralCategoryGroup::Mark.contains(categories.get(ch)) => WordClass::Letter,
+			_ => WordClass::Other,
+		},
+	};
+	let mut count = WordCount::default();
+	let mut open: Option<WordClass> = None;
+	let mut joiner: Option<WordClass> = None;
+	let mut after_digit = false;
+	for ch in text.chars() {
+		match classify(ch) {
+			WordClass::Ideograph => {
+				count.words += 1;
+				count.ideographic += 1;
+				open = None;
+			}
+			WordClass::Joiner if open.is_some() && joiner.is_none() => {
+				joiner = Some(WordClass::Joiner);
+				continue;
+			}
+			WordClass::NumberJoiner if after_digit && joiner.is_none() => {
+				joiner = Some(WordClass::NumberJoiner);
+				continue;
+			}
+			class @ (WordClass::Katakana | WordClass::Letter) => {
+				let continues = open == Some(class) && (joiner != Some(WordClass::NumberJoiner) || ch.is_numeric());
+				if !continues {
+					count.words += 1;
+					open = Some(class);
+				}
+			}
+			WordClass::Joiner | WordClass::NumberJoiner | WordClass::Other => open = None,
+		}
+		joiner = None;
+		after_digit = ch.is_numeric();
+	}
+	count
+}
+
 #[must_use]
 pub const fn is_space_like(ch: char) -> bool {
 	ch.is_whitespace() || matches!(ch, '\u{00A0}' | '\u{200B}')
 }
 
+/// Lowercased abbreviations whose trailing period does not end a sentence. Ones that are also words
+/// ending real sentences, like "no" or the months, are left out; "No." before a number is handled on
+/// its own.
+const SENTENCE_ABBREVIATIONS: &[&str] = &[
+	"mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "etc", "e.g", "i.e", "cf", "al", "approx", "vol",
+	"fig", "ch", "pp", "inc", "ltd",
+];
+
+const fn is_sentence_terminator(ch: char) -> bool {
+	matches!(ch, '.' | '!' | '?' | '\u{2026}' | '\u{3002}' | '\u{FF01}' | '\u{FF1F}')
+}
+
+const fn is_sentence_closer(ch: char) -> bool {
+	matches!(ch, '"' | '\'' | ')' | ']' | '\u{201D}' | '\u{2019}' | '\u{00BB}')
+}
+
+/// Whether the word ending right before a period is an abbreviation or a single-letter initial, given
+/// the text `before` the period and the text `after` it.
+fn ends_with_abbreviation(before: &str, after: &str) -> bool {
+	let word = before.rsplit(char::is_whitespace).next().unwrap_or_default();
+	let word = word.trim_start_matches(|ch: char| !ch.is_alphanumeric());
+	let mut chars = word.chars();
+	if let (Some(first), None) = (chars.next(), chars.next()) {
+		return first.is_alphabetic();
+	}
+	let word = word.to_lowercase();
+	if word == "no" {
+		return after.trim_start().starts_with(|ch: char| ch.is_ascii_digit());
+	}
+	SENTENCE_ABBREVIATIONS.contains(&word.as_str())
+}
+
+/// Returns the byte offsets at which sentences start in `text`.
+///
+/// A sentence ends at `.`, `!`, `?` or an ellipsis (plus any closing quotes or brackets) followed by
+/// whitespace, so decimal numbers never split. Periods after common abbreviations ("Mr.", "e.g.")
+/// and single-letter initials, or before a lowercase word, are not treated as sentence ends. CJK
+/// full stops end a sentence even without trailing whitespace.
+#[must_use]
+pub fn sentence_starts(text: &str) -> Vec<usize> {
+	let mut starts = Vec::new();
+	let Some(first) = text.find(|ch: char| !ch.is_whitespace()) else {
+		return starts;
+	};
+	starts.push(first);
+	let mut chars = text.char_indices().peekable();
+	while let Some((idx, ch)) = chars.next() {
+		if !is_sentence_terminator(ch) || (ch == '.' && ends_with_abbreviation(&text[..idx], &text[idx + 1..])) {
+			continue;
+		}
+		let mut end = idx + ch.len_utf8();
+		while let Some(&(next_idx, next)) = chars.peek() {
+			if !is_sentence_terminator(next) && !is_sentence_closer(next) {
+				break;
+			}
+			end = next_idx + next.len_utf8();
+			chars.next();
+		}
+		let rest = &text[end..];
+		let is_cjk = matches!(ch, '\u{3002}' | '\u{FF01}' | '\u{FF1F}');
+		if !is_cjk && !rest.starts_with(char::is_whitespace) {
+			continue;
+		}
+		if let Some(offset) = rest.find(|ch: char| !ch.is_whitespace())
+			&& !rest[offset..].starts_with(char::is_lowercase)
+		{
+			starts.push(end + offset);
+		}
+	}
+	starts
+}
+
 #[must_use]
 pub fn format_list_item(number: i32, list_type: &str) -> String {
 	match list_type {
@@ -115,9 +591,119 @@ fn to_alpha(mut n: i32, uppercase: bool) -> String {
 #[cfg(test)]
 mod tests {
 	use rstest::rstest;
+	use unicode_segmentation::UnicodeSegmentation;
 
 	use super::*;
 
+	const CLUSTERED_TEXTS: [&str; 4] = [
+		"Family: 👩‍👩‍👧‍👦 and 🏳️‍🌈 flags 🇳🇿🇯🇵 end",
+		"नमस्ते क्षत्रिय श्री\nहिन्दी",
+		"e\u{301}te\u{301} cafe\u{301}\r\nde\u{301}ja\u{300}",
+		"🧑🏽‍💻க்ஷ👍🏿\n\nபெண்",
+	];
+
+	/// Byte offsets that start a grapheme cluster, plus the end of the text.
+	fn cluster_boundaries(text: &str) -> Vec<usize> {
+		text.grapheme_indices(true).map(|(idx, _)| idx).chain([text.len()]).collect()
+	}
+
+	/// Pseudo-random offsets up to a little past the end of `text`, the same on every run.
+	fn random_offsets(text: &str, count: usize) -> Vec<usize> {
+		let mut state = 0x2545_f491_4f6c_dd1d_u64 ^ u64::try_from(text.len()).unwrap();
+		(0..count)
+			.map(|_| {
+				state ^= state << 13;
+				state ^= state >> 7;
+				state ^= state << 17;
+				usize::try_from(state % u64::try_from(text.len() + 8).unwrap()).unwrap()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn snippets_start_and_end_on_cluster_boundaries() {
+		for text in CLUSTERED_TEXTS {
+			let boundaries = cluster_boundaries(text);
+			let offsets = random_offsets(text, 400);
+			for pair in offsets.chunks(2) {
+				let (start, end) = (pair[0], pair[1]);
+				let snippet = snippet_at(text, start, end);
+				let snippet_start = snippet.as_ptr().addr() - text.as_ptr().addr();
+				let snippet_end = snippet_start + snippet.len();
+				assert!(boundaries.contains(&snippet_start), "{text:?} {start}..{end} starts mid-cluster");
+				assert!(boundaries.contains(&snippet_end), "{text:?} {start}..{end} ends mid-cluster");
+				if start < end {
+					assert!(snippet_start <= start.min(text.len()) && snippet_end >= end.min(text.len()));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn lines_start_and_end_on_cluster_boundaries() {
+		for text in CLUSTERED_TEXTS {
+			let boundaries = cluster_boundaries(text);
+			for pos in random_offsets(text, 200) {
+				let line = line_at(text, pos);
+				let line_start = line.as_ptr().addr() - text.as_ptr().addr();
+				assert!(boundaries.contains(&line_start), "{text:?} line at {pos} starts mid-cluster");
+				assert!(boundaries.contains(&(line_start + line.len())), "{text:?} line at {pos} ends mid-cluster");
+				assert!(!line.contains('\n') && !line.ends_with('\r'));
+			}
+		}
+	}
+
+	#[rstest]
+	#[case("ab👩‍👩‍👧cd", 3, 6, "👩‍👩‍👧")]
+	#[case("cafe\u{301}s", 0, 4, "cafe\u{301}")]
+	#[case("cafe\u{301}s", 4, 7, "e\u{301}s")]
+	#[case("abc", 2, 99, "c")]
+	#[case("abc", 99, 120, "")]
+	#[case("abc", 2, 1, "")]
+	fn snippet_at_widens_to_whole_clusters(
+		#[case] text: &str,
+		#[case] start: usize,
+		#[case] end: usize,
+		#[case] expected: &str,
+	) {
+		assert_eq!(snippet_at(text, start, end), expected);
+	}
+
+	#[rstest]
+	#[case("one\ntwo\nthree", 5, "two")]
+	#[case("one\ntwo\nthree", 3, "one")]
+	#[case("one\ntwo\nthree", 999, "three")]
+	#[case("one\r\ntwo", 1, "one")]
+	#[case("", 4, "")]
+	fn line_at_finds_the_line_around_a_position(#[case] text: &str, #[case] pos: usize, #[case] expected: &str) {
+		assert_eq!(line_at(text, pos), expected);
+	}
+
+	#[rstest]
+	#[case("The cat sat.", 5, Some("cat"))]
+	#[case("The cat sat.", 7, Some("cat"))]
+	#[case("The cat sat.", 0, Some("The"))]
+	#[case("I don't know", 4, Some("don't"))]
+	#[case("a well-known fact", 3, Some("well-known"))]
+	#[case("end -- start", 4, None)]
+	#[case("first\nsecond", 6, Some("second"))]
+	#[case("naïve café", 9, Some("café"))]
+	#[case("", 0, None)]
+	fn word_at_finds_the_word_around_the_caret(
+		#[case] text: &str,
+		#[case] byte_idx: usize,
+		#[case] word: Option<&str>,
+	) {
+		assert_eq!(word_at(text, byte_idx).map(|range| &text[range]), word);
+	}
+
+	#[test]
+	fn natural_cmp_orders_numbers_by_value() {
+		let mut names = vec!["book10.txt", "book2.txt", "Book1.txt", "book02a.txt", "book002.txt", "book01.txt"];
+		names.sort_by(|a, b| natural_cmp(a, b));
+		assert_eq!(names, ["Book1.txt", "book01.txt", "book002.txt", "book2.txt", "book02a.txt", "book10.txt"]);
+	}
+
 	#[rstest]
 	#[case("hel\u{00AD}lo", "hello")]
 	#[case("no hyphens", "no hyphens")]
@@ -126,6 +712,99 @@ mod tests {
 		assert_eq!(remove_soft_hyphens(input), expected);
 	}
 
+	#[rstest]
+	#[case("\u{201C}Hi,\u{201D} she said.", "\"Hi,\" she said.")]
+	#[case("it\u{2019}s \u{2018}quoted\u{2019}", "it's 'quoted'")]
+	#[case("\u{201E}low\u{201F} \u{201A}single\u{201B}", "\"low\" 'single'")]
+	#[case("wait\u{2014}what", "wait - what")]
+	#[case("wait \u{2014} what", "wait - what")]
+	#[case("1990\u{2013}2000", "1990 - 2000")]
+	#[case("\u{2014}start", "- start")]
+	#[case("end\u{2014}", "end -")]
+	#[case("line\n\u{2014}dialogue", "line\n- dialogue")]
+	#[case("and so\u{2026}", "and so...")]
+	#[case("10\u{00A0}km, 5\u{202F}%, 3\u{2007}4", "10 km, 5 %, 3 4")]
+	#[case("hy\u{00AD}phen", "hyphen")]
+	#[case("plain - text...", "plain - text...")]
+	fn test_normalize_typography(#[case] input: &str, #[case] expected: &str) {
+		assert_eq!(normalize_typography(input).0, expected);
+	}
+
+	#[test]
+	fn normalize_typography_maps_offsets_past_changed_spans() {
+		let input = "a\u{2014}b\u{2026} c\u{00AD}d e";
+		let (output, map) = normalize_typography(input);
+		assert_eq!(output, "a - b... cd e");
+		// Every character that survives keeps pointing at itself.
+		for (old, new) in [(0, 0), (2, 4), (4, 8), (5, 9), (7, 10), (8, 11), (9, 12)] {
+			assert_eq!(output.chars().nth(new), input.chars().nth(old), "offset {old}");
+			assert_eq!(map.map(old), new, "offset {old}");
+		}
+		assert_eq!(map.map(display_len(input)), display_len(&output));
+		// Offsets pointing at a replaced character land on its replacement.
+		assert_eq!(map.map(1), 1);
+		assert_eq!(map.map(3), 5);
+		assert_eq!(map.map(6), 10);
+	}
+
+	#[rstest]
+	#[case("Short", "Short")]
+	#[case("The Adventure of the Speckled Band", "The Adventure of\u{2026}")]
+	#[case("The Adventure of the", "The Adventure of\u{2026}")]
+	#[case("Supercalifragilistic", "Supercalifragilis\u{2026}")]
+	fn test_ellipsize_words(#[case] input: &str, #[case] expected: &str) {
+		assert_eq!(ellipsize_words(input, 17), expected);
+	}
+
+	#[rstest]
+	#[case("a\r\nb\r\n", "a\nb\n")]
+	#[case("a\rb\r", "a\nb\n")]
+	#[case("a\r\nb\rc\nd\r\r\ne", "a\nb\nc\nd\n\ne")]
+	#[case("no carriage returns\n", "no carriage returns\n")]
+	fn test_normalize_newlines(#[case] input: &str, #[case] expected: &str) {
+		assert_eq!(normalize_newlines(input).0, expected);
+	}
+
+	#[test]
+	fn normalize_newlines_maps_offsets_past_crlf_pairs() {
+		let (output, map) = normalize_newlines("ab\r\ncd\re\r\nf");
+		assert_eq!(output, "ab\ncd\ne\nf");
+		assert_eq!(map.map(0), 0);
+		assert_eq!(map.map(4), 3);
+		assert_eq!(map.map(7), 6);
+		assert_eq!(map.map(10), 8);
+		assert_eq!(map.map(11), 9);
+	}
+
+	#[test]
+	fn normalize_typography_without_length_changes_keeps_offsets() {
+		let (output, map) = normalize_typography("\u{201C}quote\u{201D}\u{00A0}here");
+		assert_eq!(output, "\"quote\" here");
+		assert!(map.is_identity());
+		assert_eq!(map.map(7), 7);
+	}
+
+	#[test]
+	fn remove_ranges_maps_offsets_around_deleted_text() {
+		let (output, map) = remove_ranges("head\nbody\nfoot\nmore", &[0..5, 10..15]);
+		assert_eq!(output, "body\nmore");
+		assert_eq!(map.map(5), 0);
+		assert_eq!(map.map(7), 2);
+		assert_eq!(map.map(12), 5);
+		assert_eq!(map.map(15), 5);
+		assert_eq!(map.map(19), 9);
+	}
+
+	#[test]
+	fn appended_maps_shift_into_place() {
+		let (_, mut map) = remove_ranges("ab\ncd", &[0..3]);
+		let (_, tail) = remove_ranges("ef\ngh", &[3..5]);
+		map.append(&tail, 5, 2);
+		assert_eq!(map.map(4), 1);
+		assert_eq!(map.map(7), 4);
+		assert_eq!(map.map(10), 5);
+	}
+
 	#[rstest]
 	#[case("hello%20world", "hello world")]
 	#[case("test%2Fpath", "test/path")]
@@ -273,4 +952,51 @@ mod tests {
 	fn display_len_plain_newline_counts_as_one_unit() {
 		assert_eq!(display_len("\n"), 1);
 	}
+
+	#[rstest]
+	#[case("a\u{1F600}b")]
+	#[case("e\u{0301}te\u{0301}")]
+	#[case("\u{4E2D}\u{6587}\u{1F468}\u{200D}\u{1F469}x")]
+	fn display_and_byte_indices_round_trip(#[case] text: &str) {
+		for (byte_idx, _) in text.char_indices() {
+			assert_eq!(display_to_byte_index(text, byte_to_display_index(text, byte_idx)), byte_idx);
+		}
+		assert_eq!(display_to_byte_index(text, display_len(text)), text.len());
+		assert_eq!(byte_to_display_index(text, text.len()), display_len(text));
+	}
+
+	#[test]
+	fn display_offset_inside_a_surrogate_pair_resolves_to_the_character() {
+		let text = "a\u{1F600}b";
+		let inside = display_len("a") + ch_width('\u{1F600}') - 1;
+		assert_eq!(display_to_byte_index(text, inside), 1);
+		assert_eq!(display_to_byte_index(text, display_len("a\u{1F600}")), 5);
+	}
+
+	#[rstest]
+	#[case("One. Two! Three? Four", vec!["One. ", "Two! ", "Three? ", "Four"])]
+	#[case("Mr. Smith met Dr. Jones.", vec!["Mr. Smith met Dr. Jones."])]
+	#[case("Use tools, e.g. hammers. Then rest.", vec!["Use tools, e.g. hammers. ", "Then rest."])]
+	#[case("Pi is 3.14 exactly. Yes.", vec!["Pi is 3.14 exactly. ", "Yes."])]
+	#[case("J. R. R. Tolkien wrote it. Really.", vec!["J. R. R. Tolkien wrote it. ", "Really."])]
+	#[case("\"Stop!\" she said. Fine.", vec!["\"Stop!\" she said. ", "Fine."])]
+	#[case("Wait... What?", vec!["Wait... ", "What?"])]
+	#[case("今天好。明天见。", vec!["今天好。", "明天见。"])]
+	#[case("See No. 5 below. I said no. Then left.", vec!["See No. 5 below. ", "I said no. ", "Then left."])]
+	#[case("It ended in Dec. The next began.", vec!["It ended in Dec. ", "The next began."])]
+	fn test_sentence_starts(#[case] input: &str, #[case] expected: Vec<&str>) {
+		let starts = sentence_starts(input);
+		let sentences: Vec<&str> = starts
+			.iter()
+			.enumerate()
+			.map(|(i, &start)| &input[start..starts.get(i + 1).copied().unwrap_or(input.len())])
+			.collect();
+		assert_eq!(sentences, expected);
+	}
+
+	#[test]
+	fn test_sentence_starts_skips_leading_whitespace() {
+		assert_eq!(sentence_starts("  Hello."), vec![2]);
+		assert!(sentence_starts("   ").is_empty());
+	}
 }
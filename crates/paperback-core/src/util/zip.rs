@@ -1,14 +1,175 @@
 use std::{
+	collections::HashSet,
 	fs::{self, File},
-	io::{self, Read, Seek},
+	io::{self, BufReader, Cursor, Read, Seek, SeekFrom, Write},
 	path::Path,
 };
 
 use anyhow::{Context, Result};
-use zip::{ZipArchive, result::ZipError};
+use flate2::read::DeflateDecoder;
+use zip::{
+	CompressionMethod, ZipArchive, ZipWriter,
+	result::{ZipError, ZipResult},
+	write::FileOptions,
+};
 
 use crate::{parser::PASSWORD_REQUIRED_ERROR_PREFIX, t};
 
+const LOCAL_HEADER_SIGNATURE: &[u8] = b"PK\x03\x04";
+const LOCAL_HEADER_LEN: usize = 30;
+const FLAG_ENCRYPTED: u16 = 0x0001;
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+/// What a ZIP archive is read from: the file itself, or an in-memory copy (a decrypted Office
+/// file, or the entries salvaged from a damaged archive).
+pub enum ZipSource {
+	File(BufReader<File>),
+	Memory(Cursor<Vec<u8>>),
+}
+
+impl Read for ZipSource {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		match self {
+			Self::File(reader) => reader.read(buf),
+			Self::Memory(cursor) => cursor.read(buf),
+		}
+	}
+}
+
+impl Seek for ZipSource {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		match self {
+			Self::File(reader) => reader.seek(pos),
+			Self::Memory(cursor) => cursor.seek(pos),
+		}
+	}
+}
+
+pub struct OpenedZip {
+	pub archive: ZipArchive<ZipSource>,
+	/// Whether the central directory couldn't be read, so the entries were recovered from their
+	/// local headers and some may be missing or cut short.
+	pub salvaged: bool,
+}
+
+/// Opens a ZIP archive, falling back to [`salvage_zip`] when it is truncated or its central
+/// directory is damaged.
+pub fn open_zip(file: File) -> ZipResult<OpenedZip> {
+	let error = match ZipArchive::new(ZipSource::File(BufReader::new(file.try_clone()?))) {
+		Ok(archive) => return Ok(OpenedZip { archive, salvaged: false }),
+		Err(error) => error,
+	};
+	let mut reader = BufReader::new(file);
+	let mut bytes = Vec::new();
+	reader.seek(SeekFrom::Start(0))?;
+	reader.read_to_end(&mut bytes)?;
+	salvage_zip(&bytes, error)
+}
+
+/// Like [`open_zip`], for an archive already in memory.
+pub fn open_zip_bytes(bytes: Vec<u8>) -> ZipResult<OpenedZip> {
+	if let Err(error) = ZipArchive::new(Cursor::new(bytes.as_slice())) {
+		return salvage_zip(&bytes, error);
+	}
+	Ok(OpenedZip { archive: ZipArchive::new(ZipSource::Memory(Cursor::new(bytes)))?, salvaged: false })
+}
+
+/// Whether `path` starts like a ZIP archive but can't be opened as one, as happens when a download
+/// is cut short.
+pub fn is_damaged_zip(path: &Path) -> bool {
+	let Ok(mut file) = File::open(path) else {
+		return false;
+	};
+	let mut header = [0; 4];
+	file.read_exact(&mut header).is_ok()
+		&& header.as_slice() == LOCAL_HEADER_SIGNATURE
+		&& file.rewind().is_ok()
+		&& ZipArchive::new(BufReader::new(file)).is_err()
+}
+
+/// Rebuilds an archive from the entries whose local headers survive in `bytes`, keeping whatever
+/// part of a cut-off entry can still be decompressed. Returns `error` when nothing is recoverable,
+/// so a file that was never an archive is still reported as one that couldn't be read.
+fn salvage_zip(bytes: &[u8], error: ZipError) -> ZipResult<OpenedZip> {
+	let entries = salvage_entries(bytes);
+	if entries.is_empty() {
+		return Err(error);
+	}
+	let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+	let options = FileOptions::<()>::default().compression_method(CompressionMethod::Stored);
+	for (name, data) in entries {
+		writer.start_file(name, options)?;
+		writer.write_all(&data)?;
+	}
+	let archive = ZipArchive::new(ZipSource::Memory(writer.finish()?))?;
+	Ok(OpenedZip { archive, salvaged: true })
+}
+
+fn salvage_entries(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+	let read_u16 = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+	let read_u32 = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+	let mut entries = Vec::new();
+	let mut seen = HashSet::new();
+	let mut pos = 0;
+	while let Some(start) = find_local_header(bytes, pos) {
+		pos = start + LOCAL_HEADER_SIGNATURE.len();
+		if bytes.len() < start + LOCAL_HEADER_LEN {
+			break;
+		}
+		let flags = read_u16(start + 6);
+		let method = read_u16(start + 8);
+		let compressed_size = usize::try_from(read_u32(start + 18)).unwrap_or(usize::MAX);
+		let name_len = usize::from(read_u16(start + 26));
+		let extra_len = usize::from(read_u16(start + 28));
+		let name_start = start + LOCAL_HEADER_LEN;
+		let data_start = name_start + name_len + extra_len;
+		let (Some(name), Some(data)) = (bytes.get(name_start..name_start + name_len), bytes.get(data_start..)) else {
+			break;
+		};
+		let known_size = flags & FLAG_DATA_DESCRIPTOR == 0;
+		let data = if known_size { &data[..compressed_size.min(data.len())] } else { data };
+		let skipped = if known_size { data.len() } else { 0 };
+		let (contents, consumed) = match method {
+			_ if flags & FLAG_ENCRYPTED != 0 => (None, skipped),
+			METHOD_STORED if known_size => (Some(data.to_vec()), data.len()),
+			METHOD_DEFLATED => {
+				let (contents, consumed) = inflate_partial(data);
+				(Some(contents), consumed)
+			}
+			_ => (None, skipped),
+		};
+		pos = pos.max(data_start + consumed);
+		let name = String::from_utf8_lossy(name).into_owned();
+		if let Some(contents) = contents
+			&& !name.ends_with('/')
+			&& seen.insert(name.clone())
+		{
+			entries.push((name, contents));
+		}
+	}
+	entries
+}
+
+fn find_local_header(bytes: &[u8], from: usize) -> Option<usize> {
+	let offset =
+		bytes.get(from..)?.windows(LOCAL_HEADER_SIGNATURE.len()).position(|window| window == LOCAL_HEADER_SIGNATURE)?;
+	Some(from + offset)
+}
+
+/// Decompresses as much of a deflate stream as is there, returning the output and how many input
+/// bytes it used.
+fn inflate_partial(data: &[u8]) -> (Vec<u8>, usize) {
+	let mut decoder = DeflateDecoder::new(data);
+	let mut output = Vec::new();
+	let mut chunk = [0u8; 8192];
+	while let Ok(read @ 1..) = decoder.read(&mut chunk) {
+		output.extend_from_slice(&chunk[..read]);
+	}
+	(output, usize::try_from(decoder.total_in()).unwrap_or(data.len()))
+}
+
 pub fn read_zip_entry_by_name<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<String> {
 	read_zip_entry_by_name_with_password(archive, name, None)
 }
@@ -60,31 +221,112 @@ pub fn extract_zip_entry_to_file<R: Read + Seek>(
 	Ok(())
 }
 
+/// Extracts every file in the archive at `path` into `dest`, keeping the folders inside it.
+/// `before_write` is given each destination before it is written, so a caller can move a file
+/// that is in use out of the way. Entries whose names would land outside `dest` are skipped.
+/// Returns how many files were written.
+///
+/// # Errors
+///
+/// Returns an error if the archive can't be read or a file can't be written.
+pub fn extract_zip_to_dir(
+	path: &Path,
+	dest: &Path,
+	mut before_write: impl FnMut(&Path) -> io::Result<()>,
+) -> Result<usize> {
+	let file = File::open(path).with_context(|| format!("Failed to open '{}'", path.display()))?;
+	let mut archive = ZipArchive::new(BufReader::new(file))?;
+	let mut written = 0;
+	for index in 0..archive.len() {
+		let mut entry = archive.by_index(index)?;
+		let Some(relative) = entry.enclosed_name() else {
+			continue;
+		};
+		let output_path = dest.join(relative);
+		if entry.is_dir() {
+			fs::create_dir_all(&output_path)
+				.with_context(|| format!("Failed to create directory '{}'", output_path.display()))?;
+			continue;
+		}
+		if let Some(parent) = output_path.parent() {
+			fs::create_dir_all(parent).with_context(|| format!("Failed to create directory '{}'", parent.display()))?;
+		}
+		before_write(&output_path).with_context(|| format!("Failed to replace '{}'", output_path.display()))?;
+		let mut out_file =
+			File::create(&output_path).with_context(|| format!("Failed to create file '{}'", output_path.display()))?;
+		io::copy(&mut entry, &mut out_file).with_context(|| format!("Failed to extract '{}'", entry.name()))?;
+		written += 1;
+	}
+	Ok(written)
+}
+
+/// Zip fixtures shared by the tests of the archive-based parsers.
 #[cfg(test)]
-mod tests {
+pub(crate) mod testing {
 	use std::{
-		env,
+		env, fs,
 		io::{Cursor, Write},
 		path::PathBuf,
-		time::{SystemTime, UNIX_EPOCH},
+		process,
+		sync::atomic::{AtomicUsize, Ordering},
 	};
 
 	use zip::{ZipWriter, write::FileOptions};
 
-	use super::*;
-
-	fn build_test_archive() -> ZipArchive<Cursor<Vec<u8>>> {
+	/// Builds a zip in memory holding `entries`, each a name and its text.
+	pub(crate) fn zip_bytes<S: AsRef<str>>(entries: &[(&str, S)]) -> Vec<u8> {
 		let mut cursor = Cursor::new(Vec::new());
-		{
-			let mut writer = ZipWriter::new(&mut cursor);
-			writer.start_file("foo.txt", FileOptions::<()>::default()).expect("start file");
-			writer.write_all(b"hello world").expect("write file");
-			writer.start_file("nested/bar.txt", FileOptions::<()>::default()).expect("start file");
-			writer.write_all(b"nested").expect("write file");
-			writer.finish().expect("finish zip");
+		let mut writer = ZipWriter::new(&mut cursor);
+		for (name, content) in entries {
+			writer.start_file(*name, FileOptions::<()>::default()).expect("start file");
+			writer.write_all(content.as_ref().as_bytes()).expect("write file");
 		}
-		cursor.set_position(0);
-		ZipArchive::new(cursor).expect("open zip")
+		writer.finish().expect("finish zip");
+		cursor.into_inner()
+	}
+
+	/// A zip written to the temp folder, removed again when dropped so a failing assertion doesn't
+	/// leave it behind.
+	pub(crate) struct TempZip {
+		path: PathBuf,
+	}
+
+	impl TempZip {
+		/// Writes `entries` to a zip in the temp folder whose name ends with `name`, e.g. "book.epub".
+		pub(crate) fn new<S: AsRef<str>>(name: &str, entries: &[(&str, S)]) -> Self {
+			static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+			let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+			let path = env::temp_dir().join(format!("paperback_zip_test_{}_{id}_{name}", process::id()));
+			fs::write(&path, zip_bytes(entries)).expect("write zip");
+			Self { path }
+		}
+
+		/// The zip's path, as `ParserContext::new` takes it.
+		pub(crate) fn path(&self) -> String {
+			self.path.to_string_lossy().into_owned()
+		}
+	}
+
+	impl Drop for TempZip {
+		fn drop(&mut self) {
+			let _ = fs::remove_file(&self.path);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		env,
+		path::PathBuf,
+		time::{SystemTime, UNIX_EPOCH},
+	};
+
+	use super::{testing::zip_bytes, *};
+
+	fn build_test_archive() -> ZipArchive<Cursor<Vec<u8>>> {
+		let bytes = zip_bytes(&[("foo.txt", "hello world"), ("nested/bar.txt", "nested")]);
+		ZipArchive::new(Cursor::new(bytes)).expect("open zip")
 	}
 
 	fn unique_temp_path(suffix: &str) -> PathBuf {
@@ -137,6 +379,42 @@ mod tests {
 		assert_eq!(contents, "hello world");
 	}
 
+	#[test]
+	fn extract_zip_to_dir_writes_every_file_and_skips_escaping_names() {
+		let bytes = zip_bytes(&[("paperback.exe", "new exe"), ("sounds/page.wav", "wav"), ("../evil.txt", "x")]);
+		let archive_path = unique_temp_path("update.zip");
+		let dest = archive_path.with_file_name("app");
+		fs::create_dir_all(&dest).expect("create dest");
+		fs::write(&archive_path, bytes).expect("write archive");
+		fs::write(dest.join("paperback.exe"), "old exe").expect("seed exe");
+		let mut replaced = Vec::new();
+		let written = extract_zip_to_dir(&archive_path, &dest, |path| {
+			replaced.push(path.strip_prefix(&dest).unwrap().to_path_buf());
+			Ok(())
+		})
+		.expect("extract");
+		assert_eq!(written, 2);
+		assert_eq!(replaced, [PathBuf::from("paperback.exe"), PathBuf::from("sounds/page.wav")]);
+		assert_eq!(fs::read_to_string(dest.join("paperback.exe")).expect("read exe"), "new exe");
+		assert_eq!(fs::read_to_string(dest.join("sounds/page.wav")).expect("read wav"), "wav");
+		assert!(!dest.parent().unwrap().join("evil.txt").exists());
+		let _ = fs::remove_dir_all(archive_path.parent().unwrap());
+	}
+
+	#[test]
+	fn open_zip_bytes_salvages_entries_before_the_cut() {
+		let bytes = build_test_archive().into_inner().into_inner();
+		let OpenedZip { mut archive, salvaged } = open_zip_bytes(bytes[..bytes.len() - 40].to_vec()).expect("salvage");
+		assert!(salvaged);
+		assert_eq!(read_zip_entry_by_name(&mut archive, "foo.txt").expect("read entry"), "hello world");
+		assert_eq!(read_zip_entry_by_name(&mut archive, "nested/bar.txt").expect("read entry"), "nested");
+	}
+
+	#[test]
+	fn open_zip_bytes_keeps_the_error_when_nothing_is_recoverable() {
+		assert!(open_zip_bytes(b"not a zip at all".to_vec()).is_err());
+	}
+
 	#[test]
 	fn read_zip_entry_by_name_reads_nested_entry() {
 		let mut archive = build_test_archive();
@@ -0,0 +1,86 @@
+//! A parser crash on one document must not stop the next run from starting: the marker written
+//! before parsing survives the crash, and the document is kept out of the restored session.
+
+use std::{
+	fs, panic,
+	path::{Path, PathBuf},
+	thread,
+};
+
+use paperback_core::{config::ConfigManager, session::DocumentSession};
+
+struct TempDir(PathBuf);
+
+impl TempDir {
+	fn create(name: &str) -> Self {
+		let dir = std::env::temp_dir().join(format!("paperback_{name}_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		Self(dir)
+	}
+
+	fn file(&self, name: &str, contents: &str) -> String {
+		let path = self.0.join(name);
+		fs::write(&path, contents).unwrap();
+		path.to_string_lossy().to_string()
+	}
+}
+
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		let _ = fs::remove_dir_all(&self.0);
+	}
+}
+
+fn load_config(path: &Path) -> ConfigManager {
+	let mut config = ConfigManager::new();
+	config.initialize(path.to_path_buf());
+	config
+}
+
+fn open(path: &str) -> DocumentSession {
+	DocumentSession::new(path, "", "", true, false, false, false).unwrap()
+}
+
+#[test]
+fn a_crash_while_parsing_keeps_the_document_out_of_the_next_session() {
+	let dir = TempDir::create("crash_recovery");
+	let config_path = dir.0.join("Paperback.toml");
+	let good = dir.file("good.txt", "A book that opens.");
+	let broken = dir.file("broken.txt", "A book the parser crashes on.");
+
+	let run = {
+		let (config_path, good, broken) = (config_path.clone(), good.clone(), broken.clone());
+		thread::spawn(move || {
+			let config = load_config(&config_path);
+			config.add_opened_document(&good);
+			config.add_opened_document(&broken);
+			config.begin_parsing_document(&good);
+			open(&good);
+			config.finish_parsing_document();
+			config.begin_parsing_document(&broken);
+			let _session = open(&broken);
+			// What a parser crash looks like from here: the run ends before parsing is marked finished.
+			std::mem::forget(config);
+			panic!("parser crashed");
+		})
+	};
+	let previous_hook = panic::take_hook();
+	panic::set_hook(Box::new(|_| {}));
+	assert!(run.join().is_err());
+	panic::set_hook(previous_hook);
+
+	let config = load_config(&config_path);
+	assert_eq!(config.unfinished_document().as_deref(), Some(broken.as_str()));
+	assert_eq!(config.get_opened_documents_existing(), [good.clone()]);
+	assert_eq!(config.get_opened_documents(), [good.clone(), broken.clone()]);
+
+	// Trying it again goes through the marker like any other open, and clears it once it opens.
+	config.begin_parsing_document(&broken);
+	assert!(!open(&broken).content_ref().is_empty());
+	config.finish_parsing_document();
+	drop(config);
+	let config = load_config(&config_path);
+	assert_eq!(config.unfinished_document(), None);
+	assert_eq!(config.get_opened_documents_existing(), [good, broken]);
+}
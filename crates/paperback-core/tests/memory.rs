@@ -0,0 +1,101 @@
+//! Guards against copying a book's text where borrowing it will do. Lives in its own test binary so
+//! the counting allocator sees only what these tests allocate.
+
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	cell::Cell,
+	fs,
+	path::PathBuf,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+use paperback_core::{
+	export::{self, ExportFormat},
+	session::DocumentSession,
+};
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+	static COUNTING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn counting() -> bool {
+	COUNTING.try_with(Cell::get).unwrap_or(false)
+}
+
+// SAFETY: every call is forwarded to the system allocator unchanged; only counters are updated.
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		if counting() {
+			ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+			LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+		}
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		if counting() {
+			LIVE.fetch_sub(layout.size().min(LIVE.load(Ordering::Relaxed)), Ordering::Relaxed);
+		}
+		unsafe { System.dealloc(ptr, layout) }
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		if counting() {
+			ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+			LIVE.fetch_add(new_size, Ordering::Relaxed);
+			LIVE.fetch_sub(layout.size().min(LIVE.load(Ordering::Relaxed)), Ordering::Relaxed);
+		}
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Bytes allocated, and bytes still live, while `f` runs on this thread.
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, usize) {
+	ALLOCATED.store(0, Ordering::Relaxed);
+	LIVE.store(0, Ordering::Relaxed);
+	COUNTING.with(|flag| flag.set(true));
+	let value = f();
+	COUNTING.with(|flag| flag.set(false));
+	(value, ALLOCATED.load(Ordering::Relaxed), LIVE.load(Ordering::Relaxed))
+}
+
+const FIXTURE_SIZE: usize = 20 * 1024 * 1024;
+
+struct LargeTextFile(PathBuf);
+
+impl LargeTextFile {
+	fn create() -> Self {
+		let line = "The quick brown fox jumps over the lazy dog, and the dog does not seem to mind at all.\n";
+		let path = std::env::temp_dir().join(format!("paperback-memory-{}.txt", std::process::id()));
+		fs::write(&path, line.repeat(FIXTURE_SIZE / line.len())).unwrap();
+		Self(path)
+	}
+}
+
+impl Drop for LargeTextFile {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.0);
+	}
+}
+
+#[test]
+fn large_book_text_is_shared_not_copied() {
+	let file = LargeTextFile::create();
+	let (session, _, retained) =
+		measure(|| DocumentSession::new(file.0.to_str().unwrap(), "", "", true, false, false, false).unwrap());
+	assert!(retained < FIXTURE_SIZE * 3, "opening a {FIXTURE_SIZE} byte book kept {retained} bytes");
+	let (len, allocated, _) = measure(|| session.content_ref().len());
+	assert!(len > 0);
+	assert_eq!(allocated, 0, "borrowing the content allocated {allocated} bytes");
+	let (len, allocated, _) = measure(|| export::render(session.handle(), ExportFormat::Text).len());
+	assert_eq!(len, session.content_ref().len());
+	assert_eq!(allocated, 0, "exporting as text allocated {allocated} bytes");
+}
@@ -2,7 +2,11 @@
 ///
 /// This module owns:
 /// - `UpdateChannel` — the desktop auto-update channel selector.
+/// - `ColorScheme` — which colors the reading view uses, plus the custom text color.
+/// - `SleepAction` — what happens when the sleep timer runs out.
 /// - `config_toml_path()` — Windows/installer-aware path resolution for the TOML config file.
+/// - `pending_config_path()` — local fallback for changes made while the config file is read-only.
+/// - `url_cache_dir()` — where documents opened from web addresses are downloaded to.
 /// - `get_update_channel` / `set_update_channel` — typed helpers wrapping the generic string API.
 use std::{
 	env,
@@ -49,6 +53,169 @@ pub fn set_update_channel(config: &ConfigManager, channel: UpdateChannel) {
 	config.set_app_string("update_channel", &channel.to_string());
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorScheme {
+	#[default]
+	System,
+	Light,
+	Dark,
+	Custom,
+}
+
+impl ColorScheme {
+	pub const ALL: [Self; 4] = [Self::System, Self::Light, Self::Dark, Self::Custom];
+}
+
+impl Display for ColorScheme {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::System => write!(f, "system"),
+			Self::Light => write!(f, "light"),
+			Self::Dark => write!(f, "dark"),
+			Self::Custom => write!(f, "custom"),
+		}
+	}
+}
+
+impl FromStr for ColorScheme {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"system" => Ok(Self::System),
+			"light" => Ok(Self::Light),
+			"dark" => Ok(Self::Dark),
+			"custom" => Ok(Self::Custom),
+			_ => Err(()),
+		}
+	}
+}
+
+pub fn get_color_scheme(config: &ConfigManager) -> ColorScheme {
+	config.get_app_string("color_scheme", "").parse().unwrap_or_else(|()| {
+		// Configs from before color schemes existed only had a custom background color.
+		if config.get_bg_color() >= 0 { ColorScheme::Custom } else { ColorScheme::System }
+	})
+}
+
+pub fn set_color_scheme(config: &ConfigManager, scheme: ColorScheme) {
+	config.set_app_string("color_scheme", &scheme.to_string());
+}
+
+/// Custom text color as `0xRRGGBB`, or -1 to use the font color.
+pub fn get_text_color(config: &ConfigManager) -> i32 {
+	config.get_app_int("text_color", -1)
+}
+
+pub fn set_text_color(config: &ConfigManager, color: i32) {
+	config.set_app_int("text_color", color);
+}
+
+/// What the sleep timer does when it runs out, after saving every open document's position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SleepAction {
+	#[default]
+	Exit,
+	Minimize,
+}
+
+impl SleepAction {
+	pub const ALL: [Self; 2] = [Self::Exit, Self::Minimize];
+}
+
+impl Display for SleepAction {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Exit => write!(f, "exit"),
+			Self::Minimize => write!(f, "minimize"),
+		}
+	}
+}
+
+impl FromStr for SleepAction {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"exit" => Ok(Self::Exit),
+			"minimize" => Ok(Self::Minimize),
+			_ => Err(()),
+		}
+	}
+}
+
+pub fn get_sleep_action(config: &ConfigManager) -> SleepAction {
+	config.get_app_string("sleep_action", "exit").parse().unwrap_or_default()
+}
+
+pub fn set_sleep_action(config: &ConfigManager, action: SleepAction) {
+	config.set_app_string("sleep_action", &action.to_string());
+}
+
+/// Default autosave interval for open documents' positions, in seconds.
+pub const DEFAULT_AUTOSAVE_INTERVAL_SECS: i32 = 30;
+
+/// Seconds between autosaves of open documents' positions; 0 disables the timer.
+pub fn get_autosave_interval(config: &ConfigManager) -> i32 {
+	config.get_app_int("autosave_interval", DEFAULT_AUTOSAVE_INTERVAL_SECS).max(0)
+}
+
+pub fn set_autosave_interval(config: &ConfigManager, seconds: i32) {
+	config.set_app_int("autosave_interval", seconds.max(0));
+}
+
+/// Whether each document's position and bookmarks are kept in sync with a `.paperback` sidecar next
+/// to it, so they follow the document to other machines through a synced folder.
+pub fn is_auto_sync_sidecar_enabled(config: &ConfigManager) -> bool {
+	config.get_app_bool("auto_sync_sidecar", false)
+}
+
+/// Writes the document's sidecar if auto-sync is on and its position or bookmarks changed since the
+/// last sync. Call after saving the position; the caller flushes.
+pub fn auto_sync_sidecar(config: &ConfigManager, doc_path: &str) {
+	// Clipboard text and downloaded documents have no folder of their own to keep a sidecar in.
+	if is_auto_sync_sidecar_enabled(config) && Path::new(doc_path).is_file() {
+		config.write_sidecar_if_changed(doc_path);
+	}
+}
+
+/// Category last picked in the Jump to Bookmark filter, or empty when the filter isn't a category.
+pub fn set_selected_bookmark_category(config: &ConfigManager, category: &str) {
+	config.set_app_string("bookmark_category", category);
+}
+
+/// Whether Next/Previous Bookmark stay within the selected bookmark category.
+pub fn is_bookmark_category_navigation_enabled(config: &ConfigManager) -> bool {
+	config.get_app_bool("bookmark_category_navigation", false)
+}
+
+pub fn set_bookmark_category_navigation_enabled(config: &ConfigManager, enabled: bool) {
+	config.set_app_bool("bookmark_category_navigation", enabled);
+}
+
+/// The category Next/Previous Bookmark are limited to, if the toggle is on and a category is picked.
+pub fn bookmark_navigation_category(config: &ConfigManager) -> Option<String> {
+	if !is_bookmark_category_navigation_enabled(config) {
+		return None;
+	}
+	Some(config.get_app_string("bookmark_category", "")).filter(|category| !category.is_empty())
+}
+
+/// Marks this run as in progress and returns whether the previous run never reached
+/// [`mark_session_ended`], i.e. it crashed or was killed.
+pub fn mark_session_started(config: &ConfigManager) -> bool {
+	let crashed = config.get_app_bool("session_running", false);
+	config.set_app_bool("session_running", true);
+	config.flush();
+	crashed
+}
+
+/// Clears the running flag on an orderly exit.
+pub fn mark_session_ended(config: &ConfigManager) {
+	config.set_app_bool("session_running", false);
+	config.flush();
+}
+
 /// Returns the directory where Paperback stores its config and log files.
 ///
 /// On macOS app bundles: `~/Library/Application Support/Paperback/`.
@@ -78,6 +245,31 @@ pub fn config_toml_path() -> PathBuf {
 	config_dir().join("Paperback.toml")
 }
 
+/// Returns the folder documents opened from web addresses are downloaded into, next to the config file.
+pub fn url_cache_dir() -> PathBuf {
+	config_dir().join("url_cache")
+}
+
+/// Returns where changes are saved while `Paperback.toml` can't be written, on a local disk so a
+/// read-only share or portable drive doesn't take them down with it.
+///
+/// On Windows: `%LOCALAPPDATA%\Paperback\pending.toml`.
+/// On macOS: `~/Library/Caches/Paperback/pending.toml`.
+/// Otherwise: `$XDG_STATE_HOME/paperback/pending.toml`, falling back to `~/.local/state`.
+pub fn pending_config_path() -> PathBuf {
+	let local_dir = if cfg!(target_os = "windows") {
+		env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("Paperback"))
+	} else if cfg!(target_os = "macos") {
+		env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Caches/Paperback"))
+	} else {
+		env::var_os("XDG_STATE_HOME")
+			.map(PathBuf::from)
+			.or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+			.map(|dir| dir.join("paperback"))
+	};
+	local_dir.unwrap_or_else(|| env::temp_dir().join("Paperback")).join("pending.toml")
+}
+
 #[cfg(target_os = "macos")]
 fn is_app_bundle(exe_dir: &Path) -> bool {
 	exe_dir.components().any(|c| c.as_os_str().to_string_lossy().ends_with(".app"))
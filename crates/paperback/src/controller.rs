@@ -0,0 +1,732 @@
+//! Go menu navigation and what it announces, apart from the widgets so it can be tested: a
+//! [`ReaderController`] moves through a [`DocumentSession`] and reports through a [`Caret`] and an
+//! [`Announcer`], which the window implements over the text control and the live region.
+
+use paperback_core::{
+	config::ConfigManager,
+	session::{DocumentSession, NavigationResult},
+	util::text::snippet_at,
+};
+use patois::t;
+
+use crate::{config_ext::bookmark_navigation_category, translation_manager::format_message};
+
+/// Where the reader's caret is, and moving it there and into view.
+pub trait Caret {
+	fn position(&self) -> i64;
+	fn set_position(&mut self, position: i64);
+}
+
+/// Speaks messages to the reader.
+pub trait Announcer {
+	fn announce(&mut self, message: &str);
+}
+
+#[derive(Clone, Copy)]
+pub enum MarkerNavTarget {
+	Section,
+	Page,
+	Heading(i32),
+	Link,
+	Table,
+	Separator,
+	List,
+	ListItem,
+	Image,
+	Figure,
+	BlockQuote,
+	CodeBlock,
+}
+
+#[derive(Clone, Copy)]
+pub enum TextUnit {
+	Sentence,
+	Paragraph,
+}
+
+enum NavFoundFormat {
+	TextOnly,
+	TextWithLevel,
+	PageFormat,
+	LinkFormat,
+	ImageFormat,
+	BlockQuoteFormat,
+	CodeBlockFormat,
+}
+
+struct NavAnnouncements {
+	not_supported: String,
+	not_found_next: String,
+	not_found_prev: String,
+	format: NavFoundFormat,
+}
+
+fn nav_announcements(target: MarkerNavTarget) -> NavAnnouncements {
+	match target {
+		MarkerNavTarget::Section => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no sections to navigate
+			not_supported: t("No sections."),
+			// TRANSLATORS: Announced when there is no next section from the current position
+			not_found_next: t("No next section"),
+			// TRANSLATORS: Announced when there is no previous section from the current position
+			not_found_prev: t("No previous section"),
+			format: NavFoundFormat::TextOnly,
+		},
+		MarkerNavTarget::Heading(level) => {
+			if level > 0 {
+				// TRANSLATORS: Announced when the document has no headings at the given level; %d is the heading level number
+				let no_headings = t("No headings at level %d.");
+				// TRANSLATORS: Announced when there is no next heading at the given level; %d is the heading level number
+				let no_next = t("No next heading at level %d.");
+				// TRANSLATORS: Announced when there is no previous heading at the given level; %d is the heading level number
+				let no_prev = t("No previous heading at level %d.");
+				NavAnnouncements {
+					not_supported: no_headings.replacen("%d", &level.to_string(), 1),
+					not_found_next: no_next.replacen("%d", &level.to_string(), 1),
+					not_found_prev: no_prev.replacen("%d", &level.to_string(), 1),
+					format: NavFoundFormat::TextWithLevel,
+				}
+			} else {
+				NavAnnouncements {
+					// TRANSLATORS: Announced when the document has no headings at all (no level filter applied)
+					not_supported: t("No headings."),
+					// TRANSLATORS: Announced when there is no next heading (no level filter applied)
+					not_found_next: t("No next heading."),
+					// TRANSLATORS: Announced when there is no previous heading (no level filter applied)
+					not_found_prev: t("No previous heading."),
+					format: NavFoundFormat::TextWithLevel,
+				}
+			}
+		}
+		MarkerNavTarget::Page => NavAnnouncements {
+			// TRANSLATORS: Announced when "Go to Page" is used on a document that has no page numbers
+			not_supported: t("No pages."),
+			// TRANSLATORS: Announced when there is no next page from the current position
+			not_found_next: t("No next page."),
+			// TRANSLATORS: Announced when there is no previous page from the current position
+			not_found_prev: t("No previous page."),
+			format: NavFoundFormat::PageFormat,
+		},
+		MarkerNavTarget::Link => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no links to navigate
+			not_supported: t("No links."),
+			// TRANSLATORS: Announced when there is no next link from the current position
+			not_found_next: t("No next link."),
+			// TRANSLATORS: Announced when there is no previous link from the current position
+			not_found_prev: t("No previous link."),
+			format: NavFoundFormat::LinkFormat,
+		},
+		MarkerNavTarget::List => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no lists to navigate
+			not_supported: t("No lists."),
+			// TRANSLATORS: Announced when there is no next list from the current position
+			not_found_next: t("No next list."),
+			// TRANSLATORS: Announced when there is no previous list from the current position
+			not_found_prev: t("No previous list."),
+			format: NavFoundFormat::TextOnly,
+		},
+		MarkerNavTarget::ListItem => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no list items to navigate
+			not_supported: t("No list items."),
+			// TRANSLATORS: Announced when there is no next list item from the current position
+			not_found_next: t("No next list item."),
+			// TRANSLATORS: Announced when there is no previous list item from the current position
+			not_found_prev: t("No previous list item."),
+			format: NavFoundFormat::TextOnly,
+		},
+		MarkerNavTarget::Table => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no tables to navigate
+			not_supported: t("No tables."),
+			// TRANSLATORS: Announced when there is no next table from the current position
+			not_found_next: t("No next table."),
+			// TRANSLATORS: Announced when there is no previous table from the current position
+			not_found_prev: t("No previous table."),
+			format: NavFoundFormat::TextOnly,
+		},
+		MarkerNavTarget::Separator => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no separators to navigate
+			not_supported: t("No separators."),
+			// TRANSLATORS: Announced when there is no next separator from the current position
+			not_found_next: t("No next separator."),
+			// TRANSLATORS: Announced when there is no previous separator from the current position
+			not_found_prev: t("No previous separator."),
+			format: NavFoundFormat::TextOnly,
+		},
+		MarkerNavTarget::Image => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no images to navigate
+			not_supported: t("No images."),
+			// TRANSLATORS: Announced when there is no next image from the current position
+			not_found_next: t("No next image."),
+			// TRANSLATORS: Announced when there is no previous image from the current position
+			not_found_prev: t("No previous image."),
+			format: NavFoundFormat::ImageFormat,
+		},
+		MarkerNavTarget::Figure => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no figures to navigate
+			not_supported: t("No figures."),
+			// TRANSLATORS: Announced when there is no next figure from the current position
+			not_found_next: t("No next figure."),
+			// TRANSLATORS: Announced when there is no previous figure from the current position
+			not_found_prev: t("No previous figure."),
+			format: NavFoundFormat::ImageFormat,
+		},
+		MarkerNavTarget::BlockQuote => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no block quotes to navigate
+			not_supported: t("No block quotes."),
+			// TRANSLATORS: Announced when there is no next block quote from the current position
+			not_found_next: t("No more block quotes."),
+			// TRANSLATORS: Announced when there is no previous block quote from the current position
+			not_found_prev: t("No previous block quote."),
+			format: NavFoundFormat::BlockQuoteFormat,
+		},
+		MarkerNavTarget::CodeBlock => NavAnnouncements {
+			// TRANSLATORS: Announced when the document has no code blocks to navigate
+			not_supported: t("No code blocks."),
+			// TRANSLATORS: Announced when there is no next code block from the current position
+			not_found_next: t("No more code blocks."),
+			// TRANSLATORS: Announced when there is no previous code block from the current position
+			not_found_prev: t("No previous code block."),
+			format: NavFoundFormat::CodeBlockFormat,
+		},
+	}
+}
+
+/// Whether navigating `next`ward reaches `first` before `second`: a hit found without wrapping
+/// comes first, then the one nearer the caret. A bookmark wins a tie.
+fn lands_first(first: &NavigationResult, second: &NavigationResult, next: bool) -> bool {
+	let order = |result: &NavigationResult| (result.wrapped, if next { result.offset } else { -result.offset });
+	order(first) < order(second)
+}
+
+fn wrap_prefix(wrapped: bool, next: bool) -> String {
+	if !wrapped {
+		return String::new();
+	}
+	// TRANSLATORS: Prefix announced when navigation wraps around past the end/start of the document; the trailing space is significant
+	if next { t("Wrapping to start. ") } else { t("Wrapping to end. ") }
+}
+
+fn format_nav_found_message(
+	format: &NavFoundFormat,
+	context_text: &str,
+	context_index: i32,
+	page_name: Option<&str>,
+) -> String {
+	match format {
+		NavFoundFormat::TextOnly | NavFoundFormat::ImageFormat => context_text.to_string(),
+		NavFoundFormat::TextWithLevel => {
+			// TRANSLATORS: Announcement when landing on a heading; %s is the heading text, %d is the heading level number
+			let template = t("%s Heading level %d");
+			format_message(&template, &[("%s", context_text), ("%d", &context_index.to_string())])
+		}
+		NavFoundFormat::PageFormat => {
+			// TRANSLATORS: Announcement when landing on a page; %d is the page number, %s is the page text
+			let template = t("Page %d: %s");
+			let page_text = page_name.map_or_else(|| (context_index + 1).to_string(), str::to_string);
+			format_message(&template, &[("%d", &page_text), ("%s", context_text)])
+		}
+		// TRANSLATORS: Suffix appended after a link's text when announcing navigation to a link; the leading space is significant
+		NavFoundFormat::LinkFormat => format!("{context_text}{}", t(" link")),
+		// TRANSLATORS: Announcement when landing on a block quote; {} is the quote's first line
+		NavFoundFormat::BlockQuoteFormat => t("Block quote: {}").replace("{}", context_text),
+		// TRANSLATORS: Announcement when landing on a code block; {} is the block's first line
+		NavFoundFormat::CodeBlockFormat => t("Code block: {}").replace("{}", context_text),
+	}
+}
+
+/// Longest sentence or paragraph text announced after moving to it, in characters.
+const MAX_TEXT_UNIT_ANNOUNCEMENT_CHARS: usize = 200;
+
+pub fn truncate_for_announcement(text: &str) -> String {
+	let Some((cut, _)) = text.char_indices().nth(MAX_TEXT_UNIT_ANNOUNCEMENT_CHARS) else {
+		return text.to_string();
+	};
+	let head = snippet_at(text, 0, cut);
+	if head.len() == text.len() {
+		return text.to_string();
+	}
+	format!("{}\u{2026}", head.trim_end())
+}
+
+fn find_marker(
+	session: &DocumentSession,
+	target: MarkerNavTarget,
+	position: i64,
+	wrap: bool,
+	next: bool,
+) -> NavigationResult {
+	match target {
+		MarkerNavTarget::Section => session.navigate_section(position, wrap, next),
+		MarkerNavTarget::Page => session.navigate_page(position, wrap, next),
+		MarkerNavTarget::Heading(level) => session.navigate_heading(position, wrap, next, level),
+		MarkerNavTarget::Link => session.navigate_link(position, wrap, next),
+		MarkerNavTarget::Table => session.navigate_table(position, wrap, next),
+		MarkerNavTarget::Separator => session.navigate_separator(position, wrap, next),
+		MarkerNavTarget::List => session.navigate_list(position, wrap, next),
+		MarkerNavTarget::ListItem => session.navigate_list_item(position, wrap, next),
+		MarkerNavTarget::Image => session.navigate_image(position, wrap, next),
+		MarkerNavTarget::Figure => session.navigate_figure(position, wrap, next),
+		MarkerNavTarget::BlockQuote => session.navigate_block_quote(position, wrap, next),
+		MarkerNavTarget::CodeBlock => session.navigate_code_block(position, wrap, next),
+	}
+}
+
+fn find_text_unit(session: &DocumentSession, unit: TextUnit, position: i64, next: bool) -> NavigationResult {
+	match (unit, next) {
+		(TextUnit::Sentence, true) => session.next_sentence(position),
+		(TextUnit::Sentence, false) => session.previous_sentence(position),
+		(TextUnit::Paragraph, true) => session.next_paragraph(position),
+		(TextUnit::Paragraph, false) => session.previous_paragraph(position),
+	}
+}
+
+/// Whether the loaded text has a `target` after `position` without wrapping, so a partly loaded
+/// document can read on before a forward move gives up or wraps.
+pub fn marker_ahead(session: &DocumentSession, target: MarkerNavTarget, position: i64) -> bool {
+	find_marker(session, target, position, false, true).found
+}
+
+/// Like [`marker_ahead`], for the next sentence or paragraph.
+pub fn text_unit_ahead(session: &DocumentSession, unit: TextUnit, position: i64) -> bool {
+	find_text_unit(session, unit, position, true).found
+}
+
+fn announce_not_enough_sections(announcer: &mut dyn Announcer) {
+	// TRANSLATORS: Announced by Random Section and shuffled Next Section when the document has fewer than two sections
+	announcer.announce(&t("Not enough sections."));
+}
+
+/// Navigates one document for the Go menu. Each move returns whether the caret moved; moves that
+/// jump through the document are recorded in the session's history, which the caller then saves.
+pub struct ReaderController<'a> {
+	session: &'a mut DocumentSession,
+	caret: &'a mut dyn Caret,
+	announcer: &'a mut dyn Announcer,
+}
+
+impl<'a> ReaderController<'a> {
+	pub fn new(session: &'a mut DocumentSession, caret: &'a mut dyn Caret, announcer: &'a mut dyn Announcer) -> Self {
+		Self { session, caret, announcer }
+	}
+
+	/// Moves to the next or previous marker of `target`'s kind, announcing it and, with
+	/// `with_location`, the full location (heading, page, percent, line) after it. Next Section
+	/// follows the shuffled order when section shuffle is on.
+	pub fn navigate_marker(&mut self, target: MarkerNavTarget, next: bool, wrap: bool, with_location: bool) -> bool {
+		let current_pos = self.caret.position();
+		let session = &mut *self.session;
+		let shuffled = matches!(target, MarkerNavTarget::Section) && next && session.section_shuffle_enabled();
+		let result = if shuffled {
+			session.shuffle_next_section(current_pos)
+		} else {
+			find_marker(session, target, current_pos, wrap, next)
+		};
+		if shuffled && result.not_supported {
+			announce_not_enough_sections(&mut *self.announcer);
+			return false;
+		}
+		self.apply_marker_result(&result, target, next, with_location)
+	}
+
+	/// Jumps to a section other than the current one, chosen at random. Announces "Not enough
+	/// sections." when the document has fewer than two.
+	pub fn random_section(&mut self, with_location: bool) -> bool {
+		let result = self.session.random_section(self.caret.position());
+		if result.not_supported {
+			announce_not_enough_sections(&mut *self.announcer);
+			return false;
+		}
+		self.apply_marker_result(&result, MarkerNavTarget::Section, true, with_location)
+	}
+
+	/// Moves the caret to a marker navigation hit and announces it, or says why there was none.
+	fn apply_marker_result(
+		&mut self,
+		result: &NavigationResult,
+		target: MarkerNavTarget,
+		next: bool,
+		with_location: bool,
+	) -> bool {
+		let ann = nav_announcements(target);
+		if result.not_supported {
+			self.announcer.announce(&ann.not_supported);
+			return false;
+		}
+		if !result.found {
+			self.announcer.announce(if next { &ann.not_found_next } else { &ann.not_found_prev });
+			return false;
+		}
+		let mut context_text = result.marker_text.clone();
+		if context_text.is_empty() {
+			context_text = self.session.get_line_text(result.offset);
+		}
+		let context_index = match target {
+			MarkerNavTarget::Heading(_) => result.marker_level,
+			MarkerNavTarget::Page | MarkerNavTarget::Image | MarkerNavTarget::Figure => result.marker_index,
+			_ => 0,
+		};
+		// Pages are called by their printed label where the document has them.
+		let page_name =
+			matches!(target, MarkerNavTarget::Page).then(|| self.session.page_name(result.marker_index + 1));
+		let found = format_nav_found_message(&ann.format, &context_text, context_index, page_name.as_deref());
+		let mut message = format!("{}{found}", wrap_prefix(result.wrapped, next));
+		if with_location {
+			message =
+				format!("{}. {}", message.trim_end_matches('.'), self.session.location_announcement(result.offset));
+		}
+		self.announcer.announce(&message);
+		self.land(result.offset);
+		true
+	}
+
+	/// Jumps to the start of the list or table the caret is in, or just past its end with `to_end`.
+	/// Announces "Not in a container." when the caret is not inside one.
+	pub fn navigate_container(&mut self, to_end: bool) -> bool {
+		let result = self.session.navigate_container(self.caret.position(), to_end);
+		if result.not_supported {
+			// TRANSLATORS: Announced when the document has no containers (lists/tables) to navigate
+			self.announcer.announce(&t("No containers."));
+			return false;
+		}
+		if !result.found {
+			// TRANSLATORS: Announced when the caret is not currently inside a container (list/table)
+			self.announcer.announce(&t("Not in a container."));
+			return false;
+		}
+		let line = self.session.get_line_text(result.offset);
+		let message = if line.trim().is_empty() {
+			// TRANSLATORS: Announced when jumping to the start/end of the container (list/table) the caret is inside, and the target line is blank
+			if to_end { t("Past end of container.") } else { t("Start of container.") }
+		} else {
+			line
+		};
+		self.announcer.announce(&message);
+		self.land(result.offset);
+		true
+	}
+
+	/// Moves to the next or previous sentence or paragraph and announces its (truncated) text.
+	pub fn navigate_text_unit(&mut self, unit: TextUnit, next: bool) -> bool {
+		let result = find_text_unit(self.session, unit, self.caret.position(), next);
+		if !result.found {
+			let message = match (unit, next) {
+				// TRANSLATORS: Announced when there is no next sentence from the current position
+				(TextUnit::Sentence, true) => t("No next sentence."),
+				// TRANSLATORS: Announced when there is no previous sentence from the current position
+				(TextUnit::Sentence, false) => t("No previous sentence."),
+				// TRANSLATORS: Announced when there is no next paragraph from the current position
+				(TextUnit::Paragraph, true) => t("No next paragraph."),
+				// TRANSLATORS: Announced when there is no previous paragraph from the current position
+				(TextUnit::Paragraph, false) => t("No previous paragraph."),
+			};
+			self.announcer.announce(&message);
+			return false;
+		}
+		self.announcer.announce(&truncate_for_announcement(&result.marker_text));
+		self.land(result.offset);
+		true
+	}
+
+	/// Moves forward or back through the positions the caret jumped from.
+	pub fn navigate_history(&mut self, forward: bool) -> bool {
+		let current_pos = self.caret.position();
+		let result = if forward {
+			self.session.history_go_forward(current_pos)
+		} else {
+			self.session.history_go_back(current_pos)
+		};
+		if !result.found {
+			// TRANSLATORS: Announced when there is no next/previous position in the caret position history
+			let message = if forward { t("No next position.") } else { t("No previous position.") };
+			self.announcer.announce(&message);
+			return false;
+		}
+		// TRANSLATORS: Announced when moving forward/backward through the caret position history
+		let message = if forward { t("Navigated to next position.") } else { t("Navigated to previous position.") };
+		self.announcer.announce(&message);
+		self.caret.set_position(result.offset);
+		self.session.set_stable_position(result.offset);
+		true
+	}
+
+	/// Moves to the next or previous bookmark, or note with `notes_only`, keeping to the bookmark
+	/// category chosen for navigation. Without a category, annotations made in other applications
+	/// are visited along with the bookmarks. Returns whether the bookmark or annotation landed on
+	/// has a note, or `None` when the caret didn't move.
+	pub fn navigate_bookmark(
+		&mut self,
+		config: &ConfigManager,
+		next: bool,
+		notes_only: bool,
+		wrap: bool,
+	) -> Option<bool> {
+		let current_pos = self.caret.position();
+		let category = if notes_only { None } else { bookmark_navigation_category(config) };
+		let result = match &category {
+			_ if notes_only => self.session.navigate_note(config, current_pos, wrap, next),
+			Some(category) => self.session.navigate_bookmark_in_category(config, current_pos, wrap, next, category),
+			None => self.session.navigate_bookmark(config, current_pos, wrap, next),
+		};
+		let with_annotations = !notes_only && category.is_none();
+		let annotation = with_annotations
+			.then(|| self.session.navigate_annotation(current_pos, wrap, next))
+			.filter(|annotation| annotation.found && (!result.found || lands_first(annotation, &result, next)));
+		if let Some(annotation) = annotation {
+			return Some(self.land_on_annotation(&annotation, next));
+		}
+		if !result.found {
+			let mut bookmarks = config.get_bookmarks(self.session.document_path());
+			if let Some(category) = &category {
+				bookmarks.retain(|bm| bm.category.eq_ignore_ascii_case(category));
+			}
+			let has_items = if notes_only {
+				bookmarks.iter().any(|bm| !bm.note.is_empty())
+			} else {
+				!bookmarks.is_empty() || (with_annotations && !self.session.external_annotations().is_empty())
+			};
+			let message = if !has_items {
+				// TRANSLATORS: Announced when there are no bookmarks/notes at all to navigate to
+				if notes_only { t("No notes.") } else { t("No bookmarks.") }
+			} else if next {
+				// TRANSLATORS: Announced when there is no next bookmark/note from the current position
+				if notes_only { t("No next note.") } else { t("No next bookmark.") }
+			} else if notes_only {
+				// TRANSLATORS: Announced when there is no previous note from the current position
+				t("No previous note.")
+			} else {
+				// TRANSLATORS: Announced when there is no previous bookmark from the current position
+				t("No previous bookmark.")
+			};
+			self.announcer.announce(&message);
+			return None;
+		}
+		let note_text = &result.marker_text;
+		let line_text = self.session.get_line_text(result.offset);
+		let content_text = if note_text.is_empty() { line_text } else { format!("{note_text}, {line_text}") };
+		// TRANSLATORS: Announcement when landing on a bookmark; %s is the bookmark/line text, %d is the bookmark's 1-based index
+		let bookmark_text = t("%s - Bookmark %d").replacen("%s", &content_text, 1).replacen(
+			"%d",
+			&(result.marker_index + 1).to_string(),
+			1,
+		);
+		self.announcer.announce(&format!("{}{bookmark_text}", wrap_prefix(result.wrapped, next)));
+		self.land(result.offset);
+		Some(!note_text.is_empty())
+	}
+
+	/// Announces the annotation `result` found, by its author, note and the text it marks, and
+	/// moves there. Returns whether it has a note.
+	fn land_on_annotation(&mut self, result: &NavigationResult, next: bool) -> bool {
+		let annotation = usize::try_from(result.marker_index)
+			.ok()
+			.and_then(|index| self.session.external_annotations().into_iter().nth(index));
+		let (author, text) = match annotation {
+			Some(annotation) if annotation.end > annotation.start => {
+				(annotation.author, self.session.get_text_range(annotation.start, annotation.end))
+			}
+			Some(annotation) => (annotation.author, self.session.get_line_text(result.offset)),
+			None => (String::new(), self.session.get_line_text(result.offset)),
+		};
+		let about = [author.as_str(), result.marker_text.as_str(), text.trim()]
+			.into_iter()
+			.filter(|part| !part.is_empty())
+			.collect::<Vec<_>>()
+			.join(", ");
+		// TRANSLATORS: Announcement when landing on a highlight or note made in another application; %s is its author, note and text
+		let message = t("Annotation: %s").replace("%s", &about);
+		self.announcer.announce(&format!("{}{message}", wrap_prefix(result.wrapped, next)));
+		self.land(result.offset);
+		!result.marker_text.is_empty()
+	}
+
+	/// Puts the caret at `offset` and records the jump in history.
+	fn land(&mut self, offset: i64) {
+		self.caret.set_position(offset);
+		self.session.check_and_record_history(offset);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use paperback_core::document::ParserContext;
+	use rstest::rstest;
+
+	use super::*;
+
+	#[derive(Default)]
+	struct FakeCaret(i64);
+
+	impl Caret for FakeCaret {
+		fn position(&self) -> i64 {
+			self.0
+		}
+
+		fn set_position(&mut self, position: i64) {
+			self.0 = position;
+		}
+	}
+
+	#[derive(Default)]
+	struct FakeAnnouncer(Vec<String>);
+
+	impl Announcer for FakeAnnouncer {
+		fn announce(&mut self, message: &str) {
+			self.0.push(message.to_string());
+		}
+	}
+
+	/// Enough text between the headings that jumping over it is a jump worth remembering.
+	fn filler() -> String {
+		"Filler text that keeps the headings far apart. ".repeat(10)
+	}
+
+	fn session() -> DocumentSession {
+		let html = format!(
+			"<h1>Intro</h1><p>{0}</p><h2>Chapter One</h2><p>See <a href=\"https://example.com\">the site</a>.</p>\
+			 <p>{0}</p><h2>Chapter Two</h2><p>First sentence. Second sentence.</p>",
+			filler()
+		);
+		DocumentSession::from_clipboard(&ParserContext::new("fixture.html".to_string()), "", Some(&html)).unwrap()
+	}
+
+	fn offset_of(session: &DocumentSession, text: &str) -> i64 {
+		i64::try_from(session.content().find(text).unwrap()).unwrap()
+	}
+
+	/// Runs `navigate` with the caret at `from`, returning whether it moved, where the caret ended up
+	/// and what was announced.
+	fn drive(
+		session: &mut DocumentSession,
+		from: i64,
+		navigate: impl FnOnce(&mut ReaderController<'_>) -> bool,
+	) -> (bool, i64, Vec<String>) {
+		let mut caret = FakeCaret(from);
+		let mut announcer = FakeAnnouncer::default();
+		let moved = navigate(&mut ReaderController::new(session, &mut caret, &mut announcer));
+		(moved, caret.0, announcer.0)
+	}
+
+	#[rstest]
+	#[case::next_heading(MarkerNavTarget::Heading(0), true, "Chapter One Heading level 2")]
+	#[case::next_heading_at_level(MarkerNavTarget::Heading(2), true, "Chapter One Heading level 2")]
+	#[case::next_link(MarkerNavTarget::Link, true, "the site link")]
+	#[case::previous_heading(MarkerNavTarget::Heading(0), false, "Intro Heading level 1")]
+	fn marker_navigation_announces_what_it_lands_on(
+		#[case] target: MarkerNavTarget,
+		#[case] next: bool,
+		#[case] expected: &str,
+	) {
+		let mut session = session();
+		let from = offset_of(&session, "Filler");
+		let (moved, caret, announced) =
+			drive(&mut session, from, |nav| nav.navigate_marker(target, next, false, false));
+		assert!(moved);
+		assert_eq!(announced, [expected]);
+		let landed = expected.trim_end_matches(" link").split(" Heading").next().unwrap();
+		assert_eq!(caret, offset_of(&session, landed));
+	}
+
+	#[test]
+	fn marker_navigation_says_when_there_is_nothing_further() {
+		let mut session = session();
+		let from = offset_of(&session, "Second sentence");
+		let (moved, caret, announced) =
+			drive(&mut session, from, |nav| nav.navigate_marker(MarkerNavTarget::Heading(0), true, false, false));
+		assert!(!moved);
+		assert_eq!(caret, from);
+		assert_eq!(announced, ["No next heading."]);
+		let (_, _, announced) =
+			drive(&mut session, from, |nav| nav.navigate_marker(MarkerNavTarget::Heading(3), true, false, false));
+		assert_eq!(announced, ["No headings at level 3."]);
+		let (_, _, announced) =
+			drive(&mut session, from, |nav| nav.navigate_marker(MarkerNavTarget::Table, false, false, false));
+		assert_eq!(announced, ["No tables."]);
+	}
+
+	#[test]
+	fn wrapping_is_announced_before_the_heading() {
+		let mut session = session();
+		let from = offset_of(&session, "Second sentence");
+		let (moved, caret, announced) =
+			drive(&mut session, from, |nav| nav.navigate_marker(MarkerNavTarget::Heading(0), true, true, false));
+		assert!(moved);
+		assert_eq!(caret, 0);
+		assert_eq!(announced, ["Wrapping to start. Intro Heading level 1"]);
+		let (_, _, announced) =
+			drive(&mut session, 0, |nav| nav.navigate_marker(MarkerNavTarget::Heading(0), false, true, false));
+		assert_eq!(announced, ["Wrapping to end. Chapter Two Heading level 2"]);
+	}
+
+	#[test]
+	fn found_messages_follow_each_format() {
+		let page = format_nav_found_message(&NavFoundFormat::PageFormat, "Contents", 4, None);
+		assert_eq!(page, "Page 5: Contents");
+		let named_page = format_nav_found_message(&NavFoundFormat::PageFormat, "Preface", 0, Some("iv"));
+		assert_eq!(named_page, "Page iv: Preface");
+		assert_eq!(format_nav_found_message(&NavFoundFormat::BlockQuoteFormat, "Quoth", 0, None), "Block quote: Quoth");
+		assert_eq!(
+			format_nav_found_message(&NavFoundFormat::CodeBlockFormat, "fn main()", 0, None),
+			"Code block: fn main()"
+		);
+		assert_eq!(wrap_prefix(false, true), "");
+	}
+
+	#[test]
+	fn long_jumps_are_recorded_in_history_and_can_be_retraced() {
+		let mut session = session();
+		session.set_stable_position(0);
+		let (moved, _, _) =
+			drive(&mut session, 0, |nav| nav.navigate_marker(MarkerNavTarget::Heading(2), true, false, false));
+		assert!(moved);
+		assert_eq!(session.get_history().0, [0]);
+		let chapter_one = offset_of(&session, "Chapter One");
+		let (moved, caret, announced) = drive(&mut session, chapter_one, |nav| nav.navigate_history(false));
+		assert!(moved);
+		assert_eq!(caret, 0);
+		assert_eq!(announced, ["Navigated to previous position."]);
+		let (moved, _, announced) = drive(&mut session, 0, |nav| nav.navigate_history(false));
+		assert!(!moved);
+		assert_eq!(announced, ["No previous position."]);
+	}
+
+	#[test]
+	fn short_moves_and_misses_leave_history_alone() {
+		let mut session = session();
+		let start = offset_of(&session, "First sentence");
+		session.set_stable_position(start);
+		let (moved, caret, announced) =
+			drive(&mut session, start, |nav| nav.navigate_text_unit(TextUnit::Sentence, true));
+		assert!(moved);
+		assert_eq!(caret, offset_of(&session, "Second sentence"));
+		assert_eq!(announced, ["Second sentence."]);
+		let (moved, _, _) =
+			drive(&mut session, caret, |nav| nav.navigate_marker(MarkerNavTarget::List, true, false, false));
+		assert!(!moved);
+		assert!(session.get_history().0.is_empty());
+	}
+
+	#[test]
+	fn text_units_are_truncated_for_speech() {
+		let long = "word ".repeat(100);
+		let truncated = truncate_for_announcement(&long);
+		assert_eq!(truncated.chars().count(), MAX_TEXT_UNIT_ANNOUNCEMENT_CHARS);
+		assert!(truncated.ends_with("word\u{2026}"));
+	}
+
+	#[test]
+	fn the_nearer_hit_lands_first_and_a_wrapped_one_last() {
+		let hit = |offset, wrapped| NavigationResult {
+			found: true,
+			wrapped,
+			offset,
+			marker_text: String::new(),
+			marker_level: 0,
+			marker_index: 0,
+			not_supported: false,
+		};
+		assert!(lands_first(&hit(10, false), &hit(20, false), true));
+		assert!(lands_first(&hit(20, false), &hit(10, false), false));
+		assert!(lands_first(&hit(90, false), &hit(5, true), true));
+		assert!(!lands_first(&hit(10, false), &hit(10, false), true));
+	}
+}
@@ -0,0 +1,291 @@
+use std::{
+	collections::HashSet,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use paperback_core::{
+	document::{ParserContext, is_heading_marker},
+	export::ExportFormat,
+	parser,
+	reader_core::OutlineOptions,
+	session::DocumentSession,
+};
+
+/// First argument that switches Paperback to converting documents without opening a window.
+pub const CONVERT_FLAG: &str = "--convert";
+
+const USAGE: &str = "usage: paperback --convert <input>... -o <output> [--format txt|html|md] [--toc]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+	Text,
+	Html,
+	Markdown,
+}
+
+impl Format {
+	fn parse(name: &str) -> Option<Self> {
+		match name.to_ascii_lowercase().as_str() {
+			"txt" | "text" => Some(Self::Text),
+			"html" | "htm" => Some(Self::Html),
+			"md" | "markdown" => Some(Self::Markdown),
+			_ => None,
+		}
+	}
+
+	const fn extension(self) -> &'static str {
+		match self {
+			Self::Text => "txt",
+			Self::Html => "html",
+			Self::Markdown => "md",
+		}
+	}
+
+	const fn export_format(self) -> ExportFormat {
+		match self {
+			Self::Text => ExportFormat::Text,
+			Self::Html => ExportFormat::Html,
+			Self::Markdown => ExportFormat::Markdown,
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ConvertArgs {
+	inputs: Vec<PathBuf>,
+	output: PathBuf,
+	format: Format,
+	toc: bool,
+}
+
+/// Runs `paperback --convert` with the arguments after the flag, printing what was written to
+/// stdout and what failed to stderr. Returns the process exit code: 0 when every document was
+/// converted, 1 when any failed and 2 for bad arguments.
+pub fn run(args: &[String]) -> i32 {
+	let args = match parse_args(args) {
+		Ok(args) => args,
+		Err(message) => {
+			eprintln!("paperback: {message}");
+			eprintln!("{USAGE}");
+			return 2;
+		}
+	};
+	let mut failed = false;
+	let mut inputs = Vec::new();
+	for input in &args.inputs {
+		let matches = expand_input(input);
+		if matches.is_empty() {
+			eprintln!("paperback: {}: no such file", input.display());
+			failed = true;
+		}
+		inputs.extend(matches);
+	}
+	let to_dir = inputs.len() > 1 || args.output.is_dir();
+	if to_dir && let Err(err) = fs::create_dir_all(&args.output) {
+		eprintln!("paperback: failed to create {}: {err}", args.output.display());
+		return 1;
+	}
+	let mut used_names = HashSet::new();
+	for input in &inputs {
+		let output =
+			if to_dir { output_in_dir(&args.output, input, args.format, &mut used_names) } else { args.output.clone() };
+		match convert_file(input, &output, args.format, args.toc) {
+			Ok(summary) => println!("{} -> {}: {summary}", input.display(), output.display()),
+			Err(message) => {
+				eprintln!("paperback: {}: {message}", input.display());
+				failed = true;
+			}
+		}
+	}
+	i32::from(failed)
+}
+
+fn parse_args(args: &[String]) -> Result<ConvertArgs, String> {
+	let mut inputs = Vec::new();
+	let mut output = None;
+	let mut format = Format::Text;
+	let mut toc = false;
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-o" | "--output" => {
+				output = Some(PathBuf::from(args.next().ok_or_else(|| format!("{arg} needs a path"))?));
+			}
+			"-f" | "--format" => {
+				let name = args.next().ok_or_else(|| format!("{arg} needs txt, html or md"))?;
+				format = Format::parse(name).ok_or_else(|| format!("unknown format: {name}"))?;
+			}
+			"--toc" => toc = true,
+			_ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("unknown option: {arg}")),
+			_ => inputs.push(PathBuf::from(arg)),
+		}
+	}
+	if inputs.is_empty() {
+		return Err("no input documents".to_string());
+	}
+	let output = output.ok_or("missing -o <output>")?;
+	if toc && format == Format::Html {
+		return Err("--toc writes txt or md".to_string());
+	}
+	Ok(ConvertArgs { inputs, output, format, toc })
+}
+
+/// The files an input names. A `*` or `?` in the file name is matched against the files in its folder,
+/// as Windows shells pass patterns through unexpanded.
+fn expand_input(input: &Path) -> Vec<PathBuf> {
+	let Some(pattern) = input.file_name().map(|name| name.to_string_lossy().into_owned()) else {
+		return Vec::new();
+	};
+	if !pattern.contains(['*', '?']) {
+		return if input.is_file() { vec![input.to_path_buf()] } else { Vec::new() };
+	}
+	let dir = input.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+	let Ok(entries) = fs::read_dir(dir) else {
+		return Vec::new();
+	};
+	let mut matches: Vec<PathBuf> = entries
+		.flatten()
+		.filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+		.filter(|entry| wildcard_match(&pattern, &entry.file_name().to_string_lossy()))
+		.map(|entry| dir.join(entry.file_name()))
+		.collect();
+	matches.sort();
+	matches
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of characters and `?` for one.
+/// Letters are compared without case, like file names on Windows.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+	let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+	let name: Vec<char> = name.to_lowercase().chars().collect();
+	let (mut p, mut n) = (0, 0);
+	let mut backtrack = None;
+	while n < name.len() {
+		if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+			p += 1;
+			n += 1;
+		} else if p < pattern.len() && pattern[p] == '*' {
+			backtrack = Some((p, n));
+			p += 1;
+		} else if let Some((star, matched)) = backtrack {
+			p = star + 1;
+			n = matched + 1;
+			backtrack = Some((star, n));
+		} else {
+			return false;
+		}
+	}
+	pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Where `input` is written in the output folder: its name with the format's extension, numbered
+/// when an earlier input of the same run already took that name.
+fn output_in_dir(dir: &Path, input: &Path, format: Format, used: &mut HashSet<PathBuf>) -> PathBuf {
+	let stem = input.file_stem().map_or_else(|| "document".to_string(), |stem| stem.to_string_lossy().into_owned());
+	let ext = format.extension();
+	let mut path = dir.join(format!("{stem}.{ext}"));
+	let mut number = 2;
+	while !used.insert(path.clone()) {
+		path = dir.join(format!("{stem} ({number}).{ext}"));
+		number += 1;
+	}
+	path
+}
+
+/// Converts one document, returning its word and heading counts for the summary line.
+fn convert_file(input: &Path, output: &Path, format: Format, toc: bool) -> Result<String, String> {
+	let ext = input.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+	if !parser::parser_supports_extension(ext) {
+		return Err(format!("unsupported file format: .{ext}"));
+	}
+	let context = ParserContext::new(input.to_string_lossy().into_owned()).with_render_tables_inline(true);
+	let session = DocumentSession::from_context(&context).map_err(|err| format!("failed to parse: {err}"))?;
+	let output_str = output.to_string_lossy();
+	let written = if toc {
+		session
+			.export_outline(&output_str, OutlineOptions { markdown: format == Format::Markdown, ..Default::default() })
+	} else {
+		session.export_as(&output_str, format.export_format())
+	};
+	written.map_err(|err| format!("failed to write {}: {err}", output.display()))?;
+	let doc = session.handle().document();
+	let headings = doc.buffer.markers.iter().filter(|marker| is_heading_marker(marker.mtype)).count();
+	Ok(format!("{} words, {headings} headings", doc.stats.word_count))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(list: &[&str]) -> Vec<String> {
+		list.iter().map(ToString::to_string).collect()
+	}
+
+	fn temp_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("paperback_convert_{name}_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	#[test]
+	fn parse_args_reads_inputs_output_and_options() {
+		let parsed = parse_args(&args(&["a.epub", "b.pdf", "-o", "out", "--format", "md", "--toc"])).unwrap();
+		assert_eq!(parsed.inputs, vec![PathBuf::from("a.epub"), PathBuf::from("b.pdf")]);
+		assert_eq!(parsed.output, PathBuf::from("out"));
+		assert_eq!(parsed.format, Format::Markdown);
+		assert!(parsed.toc);
+	}
+
+	#[test]
+	fn parse_args_rejects_bad_usage() {
+		assert!(parse_args(&args(&["-o", "out.txt"])).is_err());
+		assert!(parse_args(&args(&["a.epub"])).is_err());
+		assert!(parse_args(&args(&["a.epub", "-o", "out", "--format", "rtf"])).is_err());
+		assert!(parse_args(&args(&["a.epub", "-o", "out", "--format", "html", "--toc"])).is_err());
+		assert!(parse_args(&args(&["a.epub", "-o", "out", "--bogus"])).is_err());
+	}
+
+	#[test]
+	fn wildcard_match_handles_stars_and_question_marks() {
+		assert!(wildcard_match("*.epub", "Book.EPUB"));
+		assert!(wildcard_match("ch??.txt", "ch01.txt"));
+		assert!(wildcard_match("*a*b*", "xxaxxbxx"));
+		assert!(!wildcard_match("*.epub", "book.pdf"));
+		assert!(!wildcard_match("ch?.txt", "ch01.txt"));
+	}
+
+	#[test]
+	fn output_in_dir_numbers_clashing_names() {
+		let mut used = HashSet::new();
+		let dir = Path::new("out");
+		assert_eq!(output_in_dir(dir, Path::new("a/book.epub"), Format::Text, &mut used), dir.join("book.txt"));
+		assert_eq!(output_in_dir(dir, Path::new("b/book.pdf"), Format::Text, &mut used), dir.join("book (2).txt"));
+	}
+
+	#[test]
+	fn run_converts_matching_files_into_a_folder() {
+		let dir = temp_dir("batch");
+		fs::write(dir.join("one.txt"), "First book.").unwrap();
+		fs::write(dir.join("two.txt"), "Second book here.").unwrap();
+		let out = dir.join("out");
+		let pattern = dir.join("*.txt").to_string_lossy().into_owned();
+		let out_str = out.to_string_lossy().into_owned();
+		assert_eq!(run(&args(&[pattern.as_str(), "-o", out_str.as_str()])), 0);
+		assert_eq!(fs::read_to_string(out.join("one.txt")).unwrap().trim(), "First book.");
+		assert_eq!(fs::read_to_string(out.join("two.txt")).unwrap().trim(), "Second book here.");
+	}
+
+	#[test]
+	fn run_fails_on_missing_and_unsupported_inputs() {
+		let dir = temp_dir("failures");
+		fs::write(dir.join("data.xyz"), "data").unwrap();
+		let out = dir.join("out.txt").to_string_lossy().into_owned();
+		let unsupported = dir.join("data.xyz").to_string_lossy().into_owned();
+		let missing = dir.join("missing.epub").to_string_lossy().into_owned();
+		assert_eq!(run(&args(&[unsupported.as_str(), "-o", out.as_str()])), 1);
+		assert_eq!(run(&args(&[missing.as_str(), "-o", out.as_str()])), 1);
+		assert_eq!(run(&args(&["-o", out.as_str()])), 2);
+	}
+}
@@ -54,6 +54,13 @@ pub fn named_pipe_path() -> String {
 	format!(r"\\.\pipe\paperback_{user}")
 }
 
+/// Duplex pipe for the external tools API, scoped to the current user like `named_pipe_path`.
+#[cfg(windows)]
+pub fn api_pipe_path() -> String {
+	let user = env::var("USERNAME").unwrap_or_else(|_| "user".to_string());
+	format!(r"\\.\pipe\paperback_api_{user}")
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -7,7 +7,7 @@
 use std::fs;
 
 use base64::{Engine, engine::general_purpose::STANDARD};
-use paperback_core::config::{ConfigData, DocumentConfig, StoredBookmark};
+use paperback_core::config::{ConfigData, DocumentConfig, SavedHistoryEntry, StoredBookmark};
 use toml::Value as TomlValue;
 use wxdragon::config::{Config, ConfigStyle};
 
@@ -137,7 +137,11 @@ fn read_ini(ini_path: &std::path::Path) -> ConfigData {
 		doc.password = config.read_string("password", "");
 		let history_str = config.read_string("navigation_history", "");
 		if !history_str.is_empty() {
-			doc.navigation_history = history_str.split(',').filter_map(|t| t.trim().parse::<i64>().ok()).collect();
+			doc.navigation_history = history_str
+				.split(',')
+				.filter_map(|t| t.trim().parse::<i64>().ok())
+				.map(SavedHistoryEntry::from_offset)
+				.collect();
 		}
 		let history_index = config.read_long("navigation_history_index", 0);
 		doc.navigation_history_index = usize::try_from(history_index).unwrap_or(0);
@@ -154,10 +158,15 @@ fn read_ini(ini_path: &std::path::Path) -> ConfigData {
 					let end_str = parts.next().unwrap_or_default();
 					let note_str = parts.next().unwrap_or_default();
 					if let (Ok(start), Ok(end)) = (start_str.parse::<i64>(), end_str.parse::<i64>()) {
-						doc.bookmarks.push(StoredBookmark { start, end, note: decode_note(note_str) });
+						doc.bookmarks.push(StoredBookmark {
+							start,
+							end,
+							note: decode_note(note_str),
+							category: String::new(),
+						});
 					}
 				} else if let Ok(pos) = trimmed.parse::<i64>() {
-					doc.bookmarks.push(StoredBookmark { start: pos, end: pos, note: String::new() });
+					doc.bookmarks.push(StoredBookmark { start: pos, end: pos, ..StoredBookmark::default() });
 				}
 			}
 			doc.bookmarks.sort_by_key(|a| a.start);
@@ -5,23 +5,34 @@ patois::embed_domain!();
 patois::embed_wx_translations!();
 
 mod config_ext;
+mod controller;
+mod convert;
 mod ipc;
 mod legacy_config;
 mod logging;
+mod shell;
 mod translation_manager;
 mod ui;
 
-use std::{env, fs, io};
+use std::{env, fs, io, process};
 
 use paperback_core::{set_pdfium_library_path, version};
 use ui::PaperbackApp;
 use wxdragon::prelude::{Appearance, set_appearance};
 
 fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+	if args.first().is_some_and(|arg| arg == convert::CONVERT_FLAG) {
+		attach_parent_console();
+		set_pdfium_path_from_exe();
+		process::exit(convert::run(&args[1..]));
+	}
 	let _log_guard = logging::init(&config_ext::config_dir());
 	tracing::info!(version = env!("CARGO_PKG_VERSION"), commit = version::COMMIT_HASH, "starting");
 	set_pdfium_path_from_exe();
 	cleanup_legacy_files();
+	#[cfg(target_os = "windows")]
+	ui::remove_replaced_files();
 
 	// When running in dev via `cargo run`, make sure the app gets a proper menu bar on Mac OS.
 	// Binaries not inside an app bundle are essentially treated as background processes of Terminal.app, which leads to all sorts of nastiness.
@@ -52,6 +63,18 @@ fn promote_unbundled_to_regular_app() {
 	}
 }
 
+/// Sends `--convert` output to the console Paperback was started from. As a GUI program on Windows
+/// it doesn't get one of its own.
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+	use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+	let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(target_os = "windows"))]
+const fn attach_parent_console() {}
+
 fn set_pdfium_path_from_exe() {
 	if let Ok(exe) = env::current_exe()
 		&& let Some(dir) = exe.parent()
@@ -0,0 +1,136 @@
+#[cfg(not(target_os = "windows"))]
+use std::process::Command;
+use std::{io, path::Path};
+
+/// Opens the system file manager at the folder holding `path`, with the file itself selected where
+/// the platform allows it.
+///
+/// # Errors
+///
+/// Returns an error if no file manager could be started.
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+	let path = shell_path(&path.to_string_lossy());
+	reveal(&path)
+}
+
+#[cfg(target_os = "windows")]
+fn reveal(path: &str) -> io::Result<()> {
+	use std::{os::windows::process::CommandExt, process::Command};
+
+	use windows::{
+		Win32::UI::Shell::{ILCreateFromPathW, ILFree, SHOpenFolderAndSelectItems},
+		core::HSTRING,
+	};
+
+	let wide = HSTRING::from(path);
+	let pidl = unsafe { ILCreateFromPathW(&wide) };
+	if !pidl.is_null() {
+		let result = unsafe { SHOpenFolderAndSelectItems(pidl, None, 0) };
+		unsafe { ILFree(Some(pidl.cast_const())) };
+		if result.is_ok() {
+			return Ok(());
+		}
+	}
+	// Explorer splits its command line itself, so the argument is passed through as written.
+	// It exits with 1 even when it succeeds, so only failing to start it counts.
+	Command::new("explorer").raw_arg(explorer_select_arg(path)).spawn().map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &str) -> io::Result<()> {
+	run(Command::new("open").arg("-R").arg(path))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn reveal(path: &str) -> io::Result<()> {
+	// File managers that implement the freedesktop interface (Nautilus, Dolphin, Nemo, Thunar...)
+	// select the file; anything else just gets the folder opened.
+	let show_items = Command::new("dbus-send")
+		.args([
+			"--session",
+			"--print-reply",
+			"--reply-timeout=2000",
+			"--dest=org.freedesktop.FileManager1",
+			"--type=method_call",
+			"/org/freedesktop/FileManager1",
+			"org.freedesktop.FileManager1.ShowItems",
+		])
+		.arg(format!("array:string:{}", file_uri(path)))
+		.arg("string:")
+		.output();
+	if show_items.is_ok_and(|output| output.status.success()) {
+		return Ok(());
+	}
+	let parent = Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("/"));
+	run(Command::new("xdg-open").arg(parent))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run(command: &mut Command) -> io::Result<()> {
+	let status = command.status()?;
+	if status.success() { Ok(()) } else { Err(io::Error::other(format!("file manager exited with {status}"))) }
+}
+
+/// `path` in the form file managers accept. Windows writes long and canonicalized paths with an
+/// extended-length prefix that Explorer rejects: `\\?\C:\book.epub` becomes `C:\book.epub` and
+/// `\\?\UNC\server\share\book.epub` becomes `\\server\share\book.epub`. Other paths are unchanged.
+fn shell_path(path: &str) -> String {
+	if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+		format!(r"\\{rest}")
+	} else if let Some(rest) = path.strip_prefix(r"\\?\") {
+		rest.to_string()
+	} else {
+		path.to_string()
+	}
+}
+
+/// The Explorer argument selecting `path`, quoted so commas and spaces in it survive.
+#[cfg(any(target_os = "windows", test))]
+fn explorer_select_arg(path: &str) -> String {
+	format!("/select,\"{path}\"")
+}
+
+/// A `file://` URI for an absolute path, percent-encoding everything but unreserved characters and
+/// slashes.
+#[cfg(any(not(any(target_os = "windows", target_os = "macos")), test))]
+fn file_uri(path: &str) -> String {
+	let mut uri = String::from("file://");
+	for byte in path.bytes() {
+		if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+			uri.push(char::from(byte));
+		} else {
+			uri.push_str(&format!("%{byte:02X}"));
+		}
+	}
+	uri
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	#[rstest]
+	#[case(r"C:\Books\moby.epub", r"C:\Books\moby.epub")]
+	#[case(r"\\?\C:\Books\moby.epub", r"C:\Books\moby.epub")]
+	#[case(r"\\?\UNC\server\share\moby.epub", r"\\server\share\moby.epub")]
+	#[case(r"\\server\share\moby.epub", r"\\server\share\moby.epub")]
+	#[case("/home/me/moby.epub", "/home/me/moby.epub")]
+	fn shell_path_strips_the_extended_length_prefix(#[case] input: &str, #[case] expected: &str) {
+		assert_eq!(shell_path(input), expected);
+	}
+
+	#[test]
+	fn explorer_argument_quotes_the_path() {
+		assert_eq!(
+			explorer_select_arg(r"\\server\share\Tolkien, J.R.R\book.epub"),
+			r#"/select,"\\server\share\Tolkien, J.R.R\book.epub""#
+		);
+	}
+
+	#[test]
+	fn file_uri_percent_encodes_spaces_and_non_ascii() {
+		assert_eq!(file_uri("/home/me/My Books/Çà.epub"), "file:///home/me/My%20Books/%C3%87%C3%A0.epub");
+	}
+}
@@ -1,10 +1,24 @@
-use std::sync::{Mutex, OnceLock};
+use std::{
+	cell::RefCell,
+	rc::Rc,
+	sync::{Mutex, OnceLock},
+};
 
+pub use paperback_core::util::message::{format_message, format_number, plural};
 pub use patois::LanguageInfo;
 use patois::ui::WxTranslationManager;
 
 use crate::WxStdCatalogLoader;
 
+/// Base codes of the languages written right to left.
+const RIGHT_TO_LEFT_LANGUAGES: [&str; 10] = ["ar", "ckb", "dv", "fa", "he", "ps", "sd", "ug", "ur", "yi"];
+
+thread_local! {
+	/// Called after the UI language changes. The windows they refresh aren't `Send`, so they live
+	/// on the UI thread beside the manager rather than inside it.
+	static LANGUAGE_OBSERVERS: RefCell<Vec<Rc<dyn Fn()>>> = const { RefCell::new(Vec::new()) };
+}
+
 /// Thin app-side wrapper around `patois::ui::WxTranslationManager`: owns the singleton
 /// lifecycle (a concern of this app, not something patois should manage) and the
 /// paperback-specific logging around it.
@@ -64,6 +78,36 @@ impl TranslationManager {
 	}
 }
 
+/// Runs `observer` each time the UI language changes, so whatever it shows can fetch its strings
+/// again.
+pub fn on_language_changed(observer: impl Fn() + 'static) {
+	LANGUAGE_OBSERVERS.with(|observers| observers.borrow_mut().push(Rc::new(observer)));
+}
+
+/// Switches the UI language and tells every observer when it changed. They run once the manager is
+/// unlocked again, so they can ask it for the new language.
+pub fn switch_language(language_code: &str) -> bool {
+	let switched = TranslationManager::instance().lock().unwrap().set_language(language_code);
+	if switched {
+		notify_language_changed();
+	}
+	switched
+}
+
+fn notify_language_changed() {
+	// A copy, so an observer can subscribe another without the list being borrowed.
+	let observers = LANGUAGE_OBSERVERS.with(|observers| observers.borrow().clone());
+	for observer in observers {
+		observer();
+	}
+}
+
+/// Whether a language is written right to left, going by its base code, so "ar_EG" counts as Arabic.
+pub fn is_right_to_left(language_code: &str) -> bool {
+	let base = language_code.split(['_', '-']).next().unwrap_or(language_code);
+	RIGHT_TO_LEFT_LANGUAGES.iter().any(|code| base.eq_ignore_ascii_case(code))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -91,6 +135,28 @@ mod tests {
 		assert!(!manager.is_language_available("xx"));
 	}
 
+	#[test]
+	fn observers_run_in_order_each_time_the_language_changes() {
+		let calls = Rc::new(RefCell::new(Vec::new()));
+		for name in ["menus", "find dialog"] {
+			let calls = Rc::clone(&calls);
+			on_language_changed(move || calls.borrow_mut().push(name));
+		}
+		notify_language_changed();
+		notify_language_changed();
+		assert_eq!(*calls.borrow(), ["menus", "find dialog", "menus", "find dialog"]);
+	}
+
+	#[test]
+	fn right_to_left_languages_are_recognised_by_their_base_code() {
+		assert!(is_right_to_left("ar"));
+		assert!(is_right_to_left("he_IL"));
+		assert!(is_right_to_left("FA-ir"));
+		assert!(!is_right_to_left("en"));
+		assert!(!is_right_to_left("pt_br"));
+		assert!(!is_right_to_left("arn"));
+	}
+
 	/// Confirms `patois::embed_wx_translations!()` (invoked in `main.rs`) actually embedded
 	/// real wxstd catalogs restricted to paperback's own shipped languages, without needing
 	/// a visible window — mirrors the `wxdragon`/`patois` upstream headless test pattern.
@@ -7,11 +7,21 @@ mod main_window;
 mod menu;
 mod menu_ids;
 mod navigation;
+#[cfg(target_os = "windows")]
+mod reading_structure;
 mod rtf_write;
+mod shortcuts;
+mod sleep_timer;
 mod sounds;
+mod speech;
 mod status;
 #[cfg(target_os = "windows")]
 mod tray;
+#[cfg(target_os = "windows")]
+mod update;
+mod url_download;
 
 pub use app::PaperbackApp;
 pub use main_window::MainWindow;
+#[cfg(target_os = "windows")]
+pub use update::remove_replaced_files;
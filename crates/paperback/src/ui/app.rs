@@ -1,6 +1,6 @@
 use std::{
 	env,
-	path::Path,
+	path::{Path, PathBuf},
 	process,
 	rc::Rc,
 	sync::{
@@ -8,15 +8,19 @@ use std::{
 		atomic::{AtomicUsize, Ordering},
 	},
 };
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use std::{sync::mpsc, time::Duration};
 
 use paperback_core::config::ConfigManager;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use paperback_core::ipc_api::{ApiRequest, ApiResponse};
 use wxdragon::prelude::*;
 
-use super::MainWindow;
+use super::{MainWindow, url_download};
 #[cfg(any(target_os = "linux", target_os = "windows", test))]
 use crate::ipc::IPC_COMMAND_TOGGLE_VISIBILITY;
 use crate::{
-	config_ext::{config_toml_path, get_update_channel},
+	config_ext::{config_toml_path, get_update_channel, pending_config_path},
 	ipc::{IPC_COMMAND_ACTIVATE, IpcCommand, SINGLE_INSTANCE_NAME, normalize_cli_path},
 	legacy_config::migrate_if_needed,
 	translation_manager::TranslationManager,
@@ -35,6 +39,7 @@ impl PaperbackApp {
 	pub fn new(_app: App) -> Self {
 		migrate_if_needed();
 		let mut config = ConfigManager::new();
+		config.set_pending_path(pending_config_path());
 		let _ = config.initialize(config_toml_path());
 		{
 			let mut translations = TranslationManager::instance().lock().unwrap();
@@ -44,20 +49,31 @@ impl PaperbackApp {
 				translations.set_language(&preferred_language);
 			}
 		}
-		let config = Rc::new(Mutex::new(config));
 		let single_instance_checker = SingleInstanceChecker::new(SINGLE_INSTANCE_NAME, None);
-		if let Some(checker) = single_instance_checker.as_ref()
-			&& checker.is_another_running()
-		{
-			let cmd = ipc_command_from_cli();
-			tracing::info!(command = ?cmd, "another instance is running, forwarding command and exiting");
-			send_ipc_command(cmd);
-			process::exit(0);
+		let another_running = single_instance_checker.as_ref().is_some_and(SingleInstanceChecker::is_another_running);
+		if another_running {
+			if config.get_app_bool("single_instance", true) {
+				let commands = ipc_commands_from_cli();
+				tracing::info!(commands = ?commands, "another instance is running, forwarding commands and exiting");
+				for command in commands {
+					send_ipc_command(command);
+				}
+				process::exit(0);
+			}
+			// Both instances share one config file; leave app settings, recent documents and the
+			// like to the first so they aren't overwritten with this instance's stale copy.
+			tracing::info!("another instance is running, not saving app settings from this one");
+			config.set_app_read_only(true);
 		}
+		let config = Rc::new(Mutex::new(config));
 		let main_window = Rc::new(MainWindow::new(Rc::clone(&config)));
 		MAIN_WINDOW_PTR.store(Rc::as_ptr(&main_window) as usize, Ordering::SeqCst);
 		set_top_window(main_window.frame());
-		let pipe_server = start_pipe_server(&Rc::clone(&main_window));
+		// The first instance owns the pipes; this one can't receive files or API requests.
+		let pipe_server = if another_running { PipeServer {} } else { start_pipe_server(&Rc::clone(&main_window)) };
+		if !another_running && config.lock().unwrap().get_app_bool("enable_ipc_api", false) {
+			start_api_server();
+		}
 		main_window.show();
 		#[cfg(target_os = "macos")]
 		_app.on_reopen_app(|| {
@@ -95,7 +111,11 @@ impl PaperbackApp {
 }
 
 fn open_from_command_line(main_window: &MainWindow) {
-	if let Some(path) = env::args().nth(1) {
+	for path in env::args().skip(1) {
+		if url_download::is_url(&path) {
+			main_window.open_file(Path::new(&path));
+			continue;
+		}
 		let normalized = normalize_cli_path(Path::new(&path));
 		tracing::info!(path = %normalized.display(), "opening file from command line");
 		main_window.open_file(&normalized);
@@ -110,12 +130,17 @@ pub fn main_window_from_ptr() -> Option<&'static MainWindow> {
 	unsafe { (ptr as *const MainWindow).as_ref() }
 }
 
-fn ipc_command_from_cli() -> IpcCommand {
-	if let Some(path) = env::args().nth(1) {
-		let normalized = normalize_cli_path(Path::new(&path));
-		return IpcCommand::OpenFile(normalized);
-	}
-	IpcCommand::Activate
+/// Every file named on the command line, followed by a request to bring the window forward.
+fn ipc_commands_from_cli() -> Vec<IpcCommand> {
+	env::args()
+		.skip(1)
+		.map(|path| {
+			let path =
+				if url_download::is_url(&path) { PathBuf::from(path) } else { normalize_cli_path(Path::new(&path)) };
+			IpcCommand::OpenFile(path)
+		})
+		.chain([IpcCommand::Activate])
+		.collect()
 }
 
 // Replaces wxWidgets DDE which has no access controls; any process in the
@@ -125,18 +150,32 @@ fn ipc_command_from_cli() -> IpcCommand {
 // different users on the same machine never share a pipe.
 #[cfg(windows)]
 mod pipe {
-	use std::{ffi::OsStr, os::windows::ffi::OsStrExt as _};
+	use std::{
+		ffi::OsStr,
+		io::{self, Read, Write},
+		os::windows::ffi::OsStrExt as _,
+	};
 
+	use paperback_core::ipc_api::{self, ApiRequest, ApiResponse};
 	use windows::{
 		Win32::{
-			Foundation::{CloseHandle, ERROR_PIPE_CONNECTED, HANDLE},
+			Foundation::{CloseHandle, ERROR_BROKEN_PIPE, ERROR_PIPE_CONNECTED, HANDLE, HLOCAL, LocalFree},
+			Security::{
+				Authorization::{
+					ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+				},
+				GetTokenInformation, PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES, TOKEN_QUERY, TOKEN_USER, TokenUser,
+			},
 			Storage::FileSystem::{
 				CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_MODE, OPEN_EXISTING,
 				ReadFile, WriteFile,
 			},
-			System::Pipes::{ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, NAMED_PIPE_MODE, WaitNamedPipeW},
+			System::{
+				Pipes::{ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, NAMED_PIPE_MODE, WaitNamedPipeW},
+				Threading::{GetCurrentProcess, OpenProcessToken},
+			},
 		},
-		core::PCWSTR,
+		core::{PCWSTR, PWSTR},
 	};
 
 	const BUF: usize = 4096;
@@ -144,6 +183,8 @@ mod pipe {
 	// Raw pipe constants (Windows SDK values; windows-rs doesn't export all of these
 	// without a larger feature matrix).
 	const PIPE_ACCESS_INBOUND: u32 = 0x0000_0001;
+	const PIPE_ACCESS_DUPLEX: u32 = 0x0000_0003;
+	const PIPE_REJECT_REMOTE_CLIENTS: u32 = 0x0000_0008;
 	const PIPE_FLAG_FIRST_INSTANCE: u32 = 0x0008_0000; // FILE_FLAG_FIRST_PIPE_INSTANCE
 	const PIPE_UNLIMITED_INSTANCES: u32 = 255;
 
@@ -193,6 +234,127 @@ mod pipe {
 		});
 	}
 
+	/// The SID of the user this process runs as, in its string form ("S-1-5-21-...").
+	fn current_user_sid() -> Option<String> {
+		let mut token = HANDLE::default();
+		unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &raw mut token) }.ok()?;
+		let mut needed = 0u32;
+		let _ = unsafe { GetTokenInformation(token, TokenUser, None, 0, &raw mut needed) };
+		// u64s so the buffer is aligned for the TOKEN_USER written into it.
+		let mut buffer = vec![0u64; usize::try_from(needed).unwrap_or(0).div_ceil(8)];
+		let queried =
+			unsafe { GetTokenInformation(token, TokenUser, Some(buffer.as_mut_ptr().cast()), needed, &raw mut needed) };
+		let _ = unsafe { CloseHandle(token) };
+		queried.ok()?;
+		let user = unsafe { &*buffer.as_ptr().cast::<TOKEN_USER>() };
+		let mut string_sid = PWSTR::null();
+		unsafe { ConvertSidToStringSidW(user.User.Sid, &raw mut string_sid) }.ok()?;
+		let sid = unsafe { string_sid.to_string() }.ok();
+		let _ = unsafe { LocalFree(Some(HLOCAL(string_sid.0.cast()))) };
+		sid
+	}
+
+	/// A security descriptor whose DACL grants access to the current user and nobody else.
+	struct CurrentUserOnly(PSECURITY_DESCRIPTOR);
+
+	impl CurrentUserOnly {
+		fn new() -> Option<Self> {
+			// Protected, so nothing is inherited: one entry allowing the user full access.
+			let sddl = wide_nul(&format!("D:P(A;;GA;;;{})", current_user_sid()?));
+			let mut descriptor = PSECURITY_DESCRIPTOR::default();
+			unsafe {
+				ConvertStringSecurityDescriptorToSecurityDescriptorW(
+					PCWSTR(sddl.as_ptr()),
+					SDDL_REVISION_1,
+					&raw mut descriptor,
+					None,
+				)
+			}
+			.ok()?;
+			Some(Self(descriptor))
+		}
+	}
+
+	impl Drop for CurrentUserOnly {
+		fn drop(&mut self) {
+			let _ = unsafe { LocalFree(Some(HLOCAL(self.0.0))) };
+		}
+	}
+
+	/// Create the duplex pipe for the external tools API. Remote clients are refused outright, and
+	/// the pipe's DACL lets only the current user open it, so nobody else on the machine can send
+	/// requests. The default DACL would let everyone read from it as well.
+	pub fn try_create_api_server(pipe_name: &str) -> Option<HANDLE> {
+		let name = wide_nul(pipe_name);
+		let security = CurrentUserOnly::new()?;
+		let attributes = SECURITY_ATTRIBUTES {
+			nLength: u32::try_from(size_of::<SECURITY_ATTRIBUTES>()).unwrap_or(0),
+			lpSecurityDescriptor: security.0.0,
+			bInheritHandle: false.into(),
+		};
+		let handle = unsafe {
+			CreateNamedPipeW(
+				PCWSTR(name.as_ptr()),
+				FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_DUPLEX | PIPE_FLAG_FIRST_INSTANCE),
+				NAMED_PIPE_MODE(PIPE_REJECT_REMOTE_CLIENTS),
+				1,
+				BUF as u32,
+				BUF as u32,
+				0,
+				Some(&raw const attributes),
+			)
+		};
+		if handle.is_invalid() { None } else { Some(handle) }
+	}
+
+	/// One connected client of the API pipe.
+	#[derive(Clone, Copy)]
+	struct PipeConnection(HANDLE);
+
+	impl Read for PipeConnection {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			let mut n = 0u32;
+			match unsafe { ReadFile(self.0, Some(buf), Some(&raw mut n), None) } {
+				Ok(()) => Ok(n as usize),
+				// The client closing its end is the normal way a session ends.
+				Err(err) if err.code() == ERROR_BROKEN_PIPE.to_hresult() => Ok(0),
+				Err(err) => Err(io::Error::other(err)),
+			}
+		}
+	}
+
+	impl Write for PipeConnection {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			let mut n = 0u32;
+			unsafe { WriteFile(self.0, Some(buf), Some(&raw mut n), None) }.map_err(io::Error::other)?;
+			Ok(n as usize)
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	/// Serve API clients one at a time until the process exits.
+	pub fn serve_api_loop(handle: HANDLE, on_request: impl Fn(ApiRequest) -> ApiResponse + Send + 'static) {
+		let raw = handle.0 as usize;
+		std::thread::spawn(move || {
+			let h = HANDLE(raw as *mut _);
+			loop {
+				let conn = unsafe { ConnectNamedPipe(h, None) };
+				let ready =
+					conn.is_ok() || unsafe { windows::Win32::Foundation::GetLastError() } == ERROR_PIPE_CONNECTED;
+				if ready {
+					let connection = PipeConnection(h);
+					if let Err(err) = ipc_api::serve_connection(connection, connection, &on_request) {
+						tracing::warn!(error = %err, "API connection failed");
+					}
+				}
+				let _ = unsafe { DisconnectNamedPipe(h) };
+			}
+		});
+	}
+
 	pub fn send(pipe_name: &str, payload: &str) {
 		let name = wide_nul(pipe_name);
 		// Allow up to 2 s for the server to become ready.
@@ -222,10 +384,15 @@ mod pipe {
 mod pipe_unix {
 	use std::{
 		io::{Read, Write},
-		os::unix::net::{UnixListener, UnixStream},
+		os::unix::{
+			fs::PermissionsExt as _,
+			net::{UnixListener, UnixStream},
+		},
 		path::PathBuf,
 	};
 
+	use paperback_core::ipc_api::{self, ApiRequest, ApiResponse};
+
 	pub fn socket_path() -> Option<PathBuf> {
 		let dir = std::env::var("XDG_RUNTIME_DIR").ok()?;
 		let user = std::env::var("USER").unwrap_or_else(|_| "user".to_string());
@@ -256,6 +423,32 @@ mod pipe_unix {
 		});
 	}
 
+	pub fn api_socket_path() -> Option<PathBuf> {
+		let dir = std::env::var("XDG_RUNTIME_DIR").ok()?;
+		let user = std::env::var("USER").unwrap_or_else(|_| "user".to_string());
+		Some(std::path::Path::new(&dir).join(format!("paperback-api-{user}.sock")))
+	}
+
+	/// Create the API socket, readable and writable by the owner only.
+	pub fn try_create_api_server() -> Option<UnixListener> {
+		let path = api_socket_path()?;
+		let _ = std::fs::remove_file(&path);
+		let listener = UnixListener::bind(&path).ok()?;
+		std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).ok()?;
+		Some(listener)
+	}
+
+	/// Serve API clients one at a time until the process exits.
+	pub fn serve_api_loop(listener: UnixListener, on_request: impl Fn(ApiRequest) -> ApiResponse + Send + 'static) {
+		std::thread::spawn(move || {
+			for stream in listener.incoming().flatten() {
+				if let Err(err) = ipc_api::serve_connection(&stream, &stream, &on_request) {
+					tracing::warn!(error = %err, "API connection failed");
+				}
+			}
+		});
+	}
+
 	pub fn send(payload: &str) {
 		let Some(path) = socket_path() else { return };
 		if let Ok(mut stream) = UnixStream::connect(&path) {
@@ -323,6 +516,53 @@ fn start_pipe_server(main_window: &Rc<MainWindow>) -> PipeServer {
 	PipeServer {}
 }
 
+/// How long an API client waits for the UI thread before giving up, e.g. while a modal dialog is open.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+const API_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs `request` on the UI thread and waits for the answer. Called from the API server thread.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn answer_on_ui_thread(request: ApiRequest) -> ApiResponse {
+	let (sender, receiver) = mpsc::channel();
+	wxdragon::call_after(Box::new(move || {
+		let response = main_window_from_ptr().map_or_else(
+			|| ApiResponse::Error("Paperback is shutting down".to_string()),
+			|window| window.handle_api_request(request),
+		);
+		let _ = sender.send(response);
+	}));
+	wxdragon::wake_up_idle();
+	receiver
+		.recv_timeout(API_REQUEST_TIMEOUT)
+		.unwrap_or_else(|_| ApiResponse::Error("timed out waiting for Paperback".to_string()))
+}
+
+/// Starts the external tools API. Off unless the `enable_ipc_api` app setting is on.
+fn start_api_server() {
+	#[cfg(windows)]
+	{
+		use crate::ipc::api_pipe_path;
+		let name = api_pipe_path();
+		if let Some(handle) = pipe::try_create_api_server(&name) {
+			tracing::info!(pipe = %name, "API server started");
+			pipe::serve_api_loop(handle, answer_on_ui_thread);
+		} else {
+			tracing::error!(pipe = %name, "failed to create API server");
+		}
+	}
+	#[cfg(target_os = "linux")]
+	{
+		if let Some(listener) = pipe_unix::try_create_api_server() {
+			if let Some(path) = pipe_unix::api_socket_path() {
+				tracing::info!(socket = %path.display(), "API server started");
+			}
+			pipe_unix::serve_api_loop(listener, answer_on_ui_thread);
+		} else {
+			tracing::warn!("could not create the API socket; external tools API unavailable");
+		}
+	}
+}
+
 fn send_ipc_command(command: IpcCommand) {
 	tracing::debug!(command = ?command, "sending IPC command to existing instance");
 	let payload = match &command {
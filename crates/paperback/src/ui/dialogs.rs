@@ -7,30 +7,62 @@ mod about;
 pub use about::show_about_dialog;
 mod all_documents;
 pub use all_documents::show_all_documents_dialog;
+mod archive_entry;
+pub use archive_entry::show_archive_entry_dialog;
 mod bookmark;
 pub use bookmark::show_bookmark_dialog;
 mod document_info;
-pub use document_info::show_document_info_dialog;
+pub use document_info::{DocumentInfo, DocumentInfoOptions, show_document_info_dialog};
+mod document_notes;
+pub use document_notes::show_document_notes_dialog;
+#[cfg(target_os = "windows")]
+mod download_progress;
+#[cfg(target_os = "windows")]
+pub use download_progress::{DownloadProgress, show_download_progress_dialog};
 mod elements;
 pub use elements::show_elements_dialog;
+mod encoding;
+pub use encoding::show_encoding_dialog;
+mod export_braille;
+pub use export_braille::show_export_braille_dialog;
+mod export_notes;
+pub use export_notes::show_export_notes_dialog;
+mod export_outline;
+pub use export_outline::show_export_outline_dialog;
 mod go_to_line;
 pub use go_to_line::show_go_to_line_dialog;
 mod go_to_page;
 pub use go_to_page::show_go_to_page_dialog;
 mod go_to_percent;
 pub use go_to_percent::show_go_to_percent_dialog;
+mod go_to_section_number;
+pub use go_to_section_number::show_go_to_section_number_dialog;
+mod history;
+pub use history::show_history_dialog;
+mod landmarks;
+pub use landmarks::show_landmarks_dialog;
+mod load_error;
+pub use load_error::show_load_error_dialog;
 mod note_entry;
-pub use note_entry::show_note_entry_dialog;
+pub use note_entry::{BookmarkNoteEntry, show_note_entry_dialog};
 mod open_as;
-pub use open_as::show_open_as_dialog;
+pub use open_as::{OpenAsChoice, show_open_as_dialog};
 mod options;
 pub use options::show_options_dialog;
+mod pronunciation;
+pub use pronunciation::{PronunciationRuleSets, show_pronunciation_rules_dialog};
+mod search_notes;
+pub use search_notes::show_search_notes_dialog;
 mod sleep_timer;
-pub use sleep_timer::show_sleep_timer_dialog;
+pub use sleep_timer::{show_sleep_timer_dialog, show_sleep_timer_warning};
+mod text_parts;
+pub use text_parts::{TextPartsChoice, show_text_parts_dialog};
 mod toc;
 pub use toc::show_toc_dialog;
+mod unfinished_document;
+pub use unfinished_document::{UnfinishedDocumentChoice, show_unfinished_document_dialog};
 mod view_note;
-pub use view_note::show_view_note_dialog;
+pub use view_note::{show_link_preview_dialog, show_text_dialog, show_view_note_dialog};
 mod web_view;
 pub use web_view::{ACTIVE_WEB_VIEW, show_web_view_dialog};
 mod word_count;
@@ -1,15 +1,44 @@
 use std::{path::Path, rc::Rc, sync::Mutex};
 
-use paperback_core::{config::ConfigManager, parser::build_file_filter_string, types::DocumentListStatus};
+use paperback_core::{
+	config::ConfigManager,
+	parser::build_file_filter_string,
+	types::{DocumentListStatus, DocumentSort, DocumentSortKey},
+};
 use patois::t;
 use wxdragon::prelude::*;
 
 const DIALOG_PADDING: i32 = 10;
-const RECENT_DOCS_LIST_WIDTH: i32 = 800;
+const RECENT_DOCS_LIST_WIDTH: i32 = 1000;
 const RECENT_DOCS_LIST_HEIGHT: i32 = 600;
-const RECENT_DOCS_FILENAME_WIDTH: i32 = 250;
-const RECENT_DOCS_STATUS_WIDTH: i32 = 100;
-const RECENT_DOCS_PATH_WIDTH: i32 = 450;
+const RECENT_DOCS_FILENAME_WIDTH: i32 = 200;
+const RECENT_DOCS_TITLE_WIDTH: i32 = 200;
+const RECENT_DOCS_AUTHOR_WIDTH: i32 = 150;
+const RECENT_DOCS_PROGRESS_WIDTH: i32 = 80;
+const RECENT_DOCS_STATUS_WIDTH: i32 = 90;
+const RECENT_DOCS_PATH_WIDTH: i32 = 300;
+const RECENT_DOCS_LAST_READ_WIDTH: i32 = 110;
+const COLUMN_TITLE: i32 = 1;
+const COLUMN_AUTHOR: i32 = 2;
+const COLUMN_PROGRESS: i32 = 3;
+const COLUMN_STATUS: i32 = 4;
+const COLUMN_PATH: i32 = 5;
+const COLUMN_LAST_READ: i32 = 6;
+/// What clicking each column header sorts by, in column order.
+const COLUMN_SORT_KEYS: [DocumentSortKey; 7] = [
+	DocumentSortKey::FileName,
+	DocumentSortKey::Title,
+	DocumentSortKey::Author,
+	DocumentSortKey::Progress,
+	DocumentSortKey::Status,
+	DocumentSortKey::Path,
+	DocumentSortKey::LastRead,
+];
+/// Sort menu IDs: one per `DocumentSortKey::ALL` entry from here, then the descending toggle.
+const ID_SORT_FIRST: i32 = 20_000;
+const ID_SORT_DESCENDING: i32 = ID_SORT_FIRST + 100;
+const WXK_F10: i32 = 349;
+const WXK_WINDOWS_MENU: i32 = 395;
 const KEY_DELETE: i32 = 127;
 const KEY_NUMPAD_DELETE: i32 = 330;
 const KEY_RETURN: i32 = 13;
@@ -101,7 +130,18 @@ pub fn show_all_documents_dialog(
 		Rc::clone(config),
 		Rc::clone(&open_paths),
 	);
-	bind_all_documents_keys(doc_list, &open_action, &remove_action);
+	let refresh_action = make_all_documents_refresh_action(
+		search_ctrl,
+		doc_list,
+		open_button,
+		locate_button,
+		remove_button,
+		clear_all_button,
+		Rc::clone(config),
+		Rc::clone(&open_paths),
+	);
+	let sort_menu_action = bind_all_documents_sorting(dialog, doc_list, Rc::clone(config), &refresh_action);
+	bind_all_documents_keys(doc_list, &open_action, &remove_action, &sort_menu_action);
 	bind_all_documents_layout(
 		dialog,
 		AllDocumentsLayout {
@@ -154,10 +194,18 @@ fn build_all_documents_list(dialog: Dialog) -> ListCtrl {
 		.build();
 	// TRANSLATORS: Column header for the document filename in the All Documents list
 	doc_list.insert_column(0, &t("File Name"), ListColumnFormat::Left, RECENT_DOCS_FILENAME_WIDTH);
+	// TRANSLATORS: Column header for the document title in the All Documents list
+	doc_list.insert_column(1, &t("Title"), ListColumnFormat::Left, RECENT_DOCS_TITLE_WIDTH);
+	// TRANSLATORS: Column header for the document author in the All Documents list
+	doc_list.insert_column(2, &t("Author"), ListColumnFormat::Left, RECENT_DOCS_AUTHOR_WIDTH);
+	// TRANSLATORS: Column header for how much of a document has been read, as a percentage, in the All Documents list
+	doc_list.insert_column(3, &t("Progress"), ListColumnFormat::Left, RECENT_DOCS_PROGRESS_WIDTH);
 	// TRANSLATORS: Column header for the document status (e.g. Open, Closed, Missing) in the All Documents list
-	doc_list.insert_column(1, &t("Status"), ListColumnFormat::Left, RECENT_DOCS_STATUS_WIDTH);
+	doc_list.insert_column(4, &t("Status"), ListColumnFormat::Left, RECENT_DOCS_STATUS_WIDTH);
 	// TRANSLATORS: Column header for the file path in the All Documents list
-	doc_list.insert_column(2, &t("Path"), ListColumnFormat::Left, RECENT_DOCS_PATH_WIDTH);
+	doc_list.insert_column(5, &t("Path"), ListColumnFormat::Left, RECENT_DOCS_PATH_WIDTH);
+	// TRANSLATORS: Column header for the date a document was last read in the All Documents list
+	doc_list.insert_column(6, &t("Last Read"), ListColumnFormat::Left, RECENT_DOCS_LAST_READ_WIDTH);
 	doc_list
 }
 
@@ -373,8 +421,144 @@ fn bind_all_documents_search(
 	});
 }
 
-fn bind_all_documents_keys(list: ListCtrl, open_action: &Rc<dyn Fn()>, remove_action: &Rc<dyn Fn()>) {
+fn make_all_documents_refresh_action(
+	search_ctrl: TextCtrl,
+	list: ListCtrl,
+	open_button: Button,
+	locate_button: Button,
+	remove_button: Button,
+	clear_button: Button,
+	config: Rc<Mutex<ConfigManager>>,
+	open_paths: Rc<Vec<String>>,
+) -> Rc<dyn Fn()> {
+	Rc::new(move || {
+		let filter = search_ctrl.get_value();
+		populate_document_list(&DocumentListParams {
+			list,
+			open_button,
+			locate_button,
+			remove_button,
+			clear_all_button: clear_button,
+			config: &config,
+			open_paths: open_paths.as_ref(),
+			filter: &filter,
+			selection: None,
+		});
+	})
+}
+
+/// Clicking a column header sorts by it, and clicking it again reverses the order. Header clicks
+/// aren't reachable from the keyboard everywhere, so the same choices are in a context menu; the
+/// returned action opens it.
+fn bind_all_documents_sorting(
+	dialog: Dialog,
+	list: ListCtrl,
+	config: Rc<Mutex<ConfigManager>>,
+	refresh_action: &Rc<dyn Fn()>,
+) -> Rc<dyn Fn()> {
+	let current_sort = {
+		let config = Rc::clone(&config);
+		move || config.lock().unwrap().get_document_sort()
+	};
+	let apply_sort = {
+		let refresh_action = Rc::clone(refresh_action);
+		Rc::new(move |sort: DocumentSort| {
+			{
+				let cfg = config.lock().unwrap();
+				cfg.set_document_sort(sort);
+				cfg.flush();
+			}
+			refresh_action();
+		})
+	};
+	list.on_column_clicked({
+		let apply_sort = Rc::clone(&apply_sort);
+		let current_sort = current_sort.clone();
+		move |event| {
+			if let Some(&key) = usize::try_from(event.get_column()).ok().and_then(|column| COLUMN_SORT_KEYS.get(column))
+			{
+				apply_sort(toggled_document_sort(current_sort(), key));
+			}
+		}
+	});
+	dialog.on_menu({
+		let current_sort = current_sort.clone();
+		move |event| {
+			let id = event.get_id();
+			let sort = current_sort();
+			if id == ID_SORT_DESCENDING {
+				apply_sort(DocumentSort { descending: !sort.descending, ..sort });
+			} else if let Some(key) = DocumentSortKey::ALL.into_iter().find(|&key| sort_menu_id(key) == id) {
+				apply_sort(DocumentSort { key, ..sort });
+			}
+		}
+	});
+	let sort_menu_action: Rc<dyn Fn()> = Rc::new(move || show_sort_menu(list, current_sort()));
+	list.bind_internal(EventType::RIGHT_UP, {
+		let sort_menu_action = Rc::clone(&sort_menu_action);
+		move |event| {
+			event.skip(true);
+			sort_menu_action();
+		}
+	});
+	sort_menu_action
+}
+
+fn toggled_document_sort(current: DocumentSort, key: DocumentSortKey) -> DocumentSort {
+	if current.key == key {
+		DocumentSort { key, descending: !current.descending }
+	} else {
+		DocumentSort { key, descending: false }
+	}
+}
+
+fn sort_menu_id(key: DocumentSortKey) -> i32 {
+	let index = DocumentSortKey::ALL.iter().position(|&candidate| candidate == key).unwrap_or(0);
+	ID_SORT_FIRST + i32::try_from(index).unwrap_or(0)
+}
+
+fn sort_key_label(key: DocumentSortKey) -> String {
+	match key {
+		// TRANSLATORS: All Documents sort menu item that puts recently opened documents first
+		DocumentSortKey::Recent => t("&Recently opened"),
+		// TRANSLATORS: All Documents sort menu item
+		DocumentSortKey::FileName => t("&File name"),
+		// TRANSLATORS: All Documents sort menu item
+		DocumentSortKey::Title => t("&Title"),
+		// TRANSLATORS: All Documents sort menu item
+		DocumentSortKey::Author => t("&Author"),
+		// TRANSLATORS: All Documents sort menu item that orders documents by how much of them has been read
+		DocumentSortKey::Progress => t("&Progress"),
+		// TRANSLATORS: All Documents sort menu item
+		DocumentSortKey::Status => t("&Status"),
+		// TRANSLATORS: All Documents sort menu item
+		DocumentSortKey::Path => t("Pat&h"),
+		// TRANSLATORS: All Documents sort menu item
+		DocumentSortKey::LastRead => t("&Last read"),
+	}
+}
+
+fn show_sort_menu(list: ListCtrl, sort: DocumentSort) {
+	let mut menu = Menu::builder().build();
+	for key in DocumentSortKey::ALL {
+		menu.append(sort_menu_id(key), &sort_key_label(key), "", ItemKind::Radio);
+	}
+	menu.check_item(sort_menu_id(sort.key), true);
+	menu.append_separator();
+	// TRANSLATORS: Checkable All Documents sort menu item that reverses the sort order
+	menu.append(ID_SORT_DESCENDING, &t("&Descending"), "", ItemKind::Check);
+	menu.check_item(ID_SORT_DESCENDING, sort.descending);
+	list.popup_menu(&mut menu, None);
+}
+
+fn bind_all_documents_keys(
+	list: ListCtrl,
+	open_action: &Rc<dyn Fn()>,
+	remove_action: &Rc<dyn Fn()>,
+	sort_menu_action: &Rc<dyn Fn()>,
+) {
 	let remove_action_for_keys = Rc::clone(remove_action);
+	let sort_menu_action_for_keys = Rc::clone(sort_menu_action);
 	let open_action_for_keys = Rc::clone(open_action);
 	let list_for_keys = list;
 	list.bind_internal(EventType::KEY_DOWN, move |event| {
@@ -389,6 +573,11 @@ fn bind_all_documents_keys(list: ListCtrl, open_action: &Rc<dyn Fn()>, remove_ac
 				event.skip(false);
 				return;
 			}
+			if (key == WXK_F10 && event.shift_down()) || key == WXK_WINDOWS_MENU {
+				sort_menu_action_for_keys();
+				event.skip(false);
+				return;
+			}
 			if key == i32::from(b'A') && event.control_down() {
 				list_for_keys.set_item_state(-1, ListItemState::Selected, ListItemState::Selected);
 				event.skip(false);
@@ -507,8 +696,19 @@ fn populate_document_list(params: &DocumentListParams<'_>) {
 			// TRANSLATORS: Status of a document whose file could not be found on disk
 			DocumentListStatus::Missing => t("Missing"),
 		};
-		list.set_item_text_by_column(index, 1, &status);
-		list.set_item_text_by_column(index, 2, &item.path);
+		let progress = match (item.progress, item.furthest) {
+			(Some(percent), Some(furthest)) if furthest > percent => {
+				// TRANSLATORS: Progress column in All Documents when the reader has gone back to re-read; the first {} is the current percentage, the second the furthest one reached
+				t("{}% (furthest {}%)").replacen("{}", &percent.to_string(), 1).replacen("{}", &furthest.to_string(), 1)
+			}
+			(progress, _) => progress.map(|percent| format!("{percent}%")).unwrap_or_default(),
+		};
+		list.set_item_text_by_column(index, COLUMN_TITLE, &item.title);
+		list.set_item_text_by_column(index, COLUMN_AUTHOR, &item.author);
+		list.set_item_text_by_column(index, COLUMN_PROGRESS, &progress);
+		list.set_item_text_by_column(index, COLUMN_STATUS, &status);
+		list.set_item_text_by_column(index, COLUMN_PATH, &item.path);
+		list.set_item_text_by_column(index, COLUMN_LAST_READ, &item.reading_stats.last_read_date().unwrap_or_default());
 	}
 	if list.get_item_count() > 0 {
 		let mut select_index = selection.unwrap_or(0);
@@ -538,13 +738,17 @@ fn update_open_button_for_index(list: ListCtrl, open_button: Button, index: i32)
 		open_button.enable(false);
 		return;
 	}
-	let status = list.get_item_text(i64::from(index), 1);
+	let status = list.get_item_text(i64::from(index), COLUMN_STATUS);
 	open_button.enable(status != t("Missing"));
 }
 
 fn update_locate_button(list: ListCtrl, locate_button: Button) {
 	let indices = get_selected_indices(list);
-	let enabled = if indices.len() == 1 { list.get_item_text(i64::from(indices[0]), 1) == t("Missing") } else { false };
+	let enabled = if indices.len() == 1 {
+		list.get_item_text(i64::from(indices[0]), COLUMN_STATUS) == t("Missing")
+	} else {
+		false
+	};
 	locate_button.enable(enabled);
 }
 
@@ -583,7 +787,7 @@ fn bind_all_documents_locate(
 		};
 		{
 			let cfg = config.lock().unwrap();
-			cfg.rename_document_path(&old_path, &new_path);
+			cfg.rename_document(&old_path, &new_path);
 			cfg.flush();
 		}
 		let filter = search_ctrl.get_value();
@@ -630,7 +834,7 @@ fn get_path_for_index(list: ListCtrl, index: i32) -> Option<String> {
 	{
 		return Some(path.clone());
 	}
-	let path = list.get_item_text(i64::from(index), 2);
+	let path = list.get_item_text(i64::from(index), COLUMN_PATH);
 	if path.is_empty() { None } else { Some(path) }
 }
 
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// Asks which document to open from an archive holding several. Returns the chosen entry name.
+pub fn show_archive_entry_dialog(parent: &dyn WxWidget, path: &Path, entries: &[String]) -> Option<String> {
+	// TRANSLATORS: Title of the dialog for picking which document to open from a ZIP archive
+	let title = t("Open from Archive");
+	let dialog = Dialog::builder(parent, &title).build();
+	// TRANSLATORS: Prompt in the Open from Archive dialog; {} is the archive's file name
+	let message_template = t("{} contains several documents. Which one would you like to open?");
+	let file_name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string());
+	let message = message_template.replace("{}", &file_name);
+	let label = StaticText::builder(&dialog).with_label(&message).build();
+	let entry_list = ListBox::builder(&dialog).build();
+	for entry in entries {
+		entry_list.append(entry);
+	}
+	if !entries.is_empty() {
+		entry_list.set_selection(0, true);
+	}
+	// TRANSLATORS: Label for the confirmation button
+	let ok_label = t("OK");
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&ok_label).build();
+	ok_button.set_default();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_label = t("Cancel");
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&cancel_label).build();
+	let dialog_for_ok = dialog;
+	ok_button.on_click(move |_| {
+		dialog_for_ok.end_modal(ID_OK);
+	});
+	let dialog_for_cancel = dialog;
+	cancel_button.on_click(move |_| {
+		dialog_for_cancel.end_modal(ID_CANCEL);
+	});
+	let dialog_for_double_click = dialog;
+	entry_list.on_item_double_clicked(move |_| {
+		dialog_for_double_click.end_modal(ID_OK);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&label, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add(
+		&entry_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	entry_list.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let index = usize::try_from(entry_list.get_selection()?).ok()?;
+	entries.get(index).cloned()
+}
@@ -1,5 +1,6 @@
 use std::{
 	cell::{Cell, RefCell},
+	collections::{HashMap, HashSet},
 	rc::Rc,
 	sync::Mutex,
 };
@@ -7,17 +8,22 @@ use std::{
 use paperback_core::{
 	config::ConfigManager,
 	reader_core,
-	session::DocumentSession,
-	types::{BookmarkDisplayEntry, BookmarkFilterType},
+	session::{DocumentSession, ExternalAnnotation},
+	types::{BookmarkDisplayEntry, BookmarkFilterType, FilteredBookmarks},
 };
 use patois::t;
-use wxdragon::prelude::*;
+use wxdragon::{clipboard::Clipboard, prelude::*};
 
-use super::show_note_entry_dialog;
+use super::{BookmarkNoteEntry, show_note_entry_dialog};
+use crate::{config_ext::set_selected_bookmark_category, translation_manager::plural};
 
 const DIALOG_PADDING: i32 = 10;
 const KEY_DELETE: i32 = 127;
 const KEY_NUMPAD_DELETE: i32 = 330;
+const BOOKMARK_LIST_WIDTH: i32 = 500;
+const BOOKMARK_LIST_HEIGHT: i32 = 300;
+/// Filter choices before the per-category entries: All, Bookmarks, Notes.
+const FIXED_FILTER_COUNT: usize = 3;
 
 pub struct BookmarkDialogResult {
 	pub start: i64,
@@ -30,8 +36,10 @@ pub fn show_bookmark_dialog(
 	current_pos: i64,
 	initial_filter: BookmarkFilterType,
 ) -> Option<BookmarkDialogResult> {
-	let file_path = session.file_path().to_string();
-	let content = Rc::new(session.content());
+	let file_path = session.document_path().to_string();
+	let annotations = Rc::new(session.external_annotations());
+	let snippets = Rc::new(bookmark_snippets(session, config, &file_path, &annotations));
+	let categories = Rc::new(config.lock().unwrap().get_bookmark_categories(&file_path));
 	// TRANSLATORS: Title of the Jump to Bookmark dialog
 	let dialog = Dialog::builder(parent, &t("Jump to Bookmark")).build();
 	let BookmarkDialogUi {
@@ -40,29 +48,33 @@ pub fn show_bookmark_dialog(
 		bookmark_list,
 		edit_button,
 		delete_button,
+		copy_button,
+		convert_button,
 		jump_button,
 		cancel_button,
-	} = build_bookmark_dialog_ui(dialog, initial_filter);
-	let state = build_bookmark_dialog_state(jump_button, delete_button, edit_button);
+	} = build_bookmark_dialog_ui(dialog, initial_filter, &categories, !annotations.is_empty());
+	let buttons = BookmarkButtons {
+		edit: edit_button,
+		delete: delete_button,
+		copy: copy_button,
+		convert: convert_button,
+		jump: jump_button,
+	};
+	let state = build_bookmark_dialog_state(bookmark_list, buttons);
 	let repopulate = build_bookmark_repopulate(BookmarkRepopulateParams {
 		list: bookmark_list,
 		config: Rc::clone(config),
 		file_path: file_path.clone(),
-		content: Rc::clone(&content),
+		snippets,
 		entries: Rc::clone(&state.entries),
-		selected_start: Rc::clone(&state.selected_start),
-		selected_end: Rc::clone(&state.selected_end),
 		filter_choice,
-		set_buttons_enabled: Rc::clone(&state.set_buttons_enabled),
+		categories: Rc::clone(&categories),
+		annotations: Rc::clone(&annotations),
+		read_only: Rc::clone(&state.read_only),
+		sync_selection: Rc::clone(&state.sync_selection),
 	});
 	repopulate(current_pos);
-	bind_bookmark_selection(BookmarkSelectionParams {
-		list: bookmark_list,
-		entries: Rc::clone(&state.entries),
-		selected_start: Rc::clone(&state.selected_start),
-		selected_end: Rc::clone(&state.selected_end),
-		set_buttons_enabled: Rc::clone(&state.set_buttons_enabled),
-	});
+	bind_bookmark_selection(bookmark_list, &state.sync_selection);
 	bind_bookmark_jump(dialog, jump_button, &state.selected_start);
 	bind_bookmark_actions(BookmarkDialogActions {
 		dialog,
@@ -70,23 +82,20 @@ pub fn show_bookmark_dialog(
 		bookmark_list,
 		edit_button,
 		delete_button,
+		copy_button,
+		convert_button,
 		cancel_button,
 		repopulate: Rc::clone(&repopulate),
+		entries: Rc::clone(&state.entries),
 		selected_start: Rc::clone(&state.selected_start),
 		selected_end: Rc::clone(&state.selected_end),
+		read_only: Rc::clone(&state.read_only),
 		config: Rc::clone(config),
+		categories,
 		file_path,
 		current_pos,
 	});
-	finalize_bookmark_dialog_layout(
-		dialog,
-		filter_sizer,
-		bookmark_list,
-		edit_button,
-		delete_button,
-		jump_button,
-		cancel_button,
-	);
+	finalize_bookmark_dialog_layout(dialog, filter_sizer, bookmark_list, buttons, cancel_button);
 	if dialog.show_modal() != ID_OK {
 		return None;
 	}
@@ -97,56 +106,76 @@ pub fn show_bookmark_dialog(
 struct BookmarkDialogUi {
 	filter_choice: Choice,
 	filter_sizer: BoxSizer,
-	bookmark_list: ListBox,
+	bookmark_list: ListCtrl,
 	edit_button: Button,
 	delete_button: Button,
+	copy_button: Button,
+	convert_button: Button,
 	jump_button: Button,
 	cancel_button: Button,
 }
 
+#[derive(Clone, Copy)]
+struct BookmarkButtons {
+	edit: Button,
+	delete: Button,
+	copy: Button,
+	convert: Button,
+	jump: Button,
+}
+
 struct BookmarkDialogState {
 	entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>,
+	/// The bookmark Jump and Edit Note act on, or -1 unless exactly one is selected.
 	selected_start: Rc<Cell<i64>>,
 	selected_end: Rc<Cell<i64>>,
-	set_buttons_enabled: Rc<dyn Fn(bool)>,
+	/// Whether the list shows the file's annotations, which can't be edited or deleted.
+	read_only: Rc<Cell<bool>>,
+	/// Reads the list's selection back into the fields above and enables the buttons to match.
+	sync_selection: Rc<dyn Fn()>,
 }
 
 struct BookmarkRepopulateParams {
-	list: ListBox,
+	list: ListCtrl,
 	config: Rc<Mutex<ConfigManager>>,
 	file_path: String,
-	content: Rc<String>,
+	/// Text of each bookmark by its range, see [`bookmark_snippets`].
+	snippets: Rc<HashMap<(i64, i64), String>>,
 	entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>,
-	selected_start: Rc<Cell<i64>>,
-	selected_end: Rc<Cell<i64>>,
 	filter_choice: Choice,
-	set_buttons_enabled: Rc<dyn Fn(bool)>,
-}
-
-struct BookmarkSelectionParams {
-	list: ListBox,
-	entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>,
-	selected_start: Rc<Cell<i64>>,
-	selected_end: Rc<Cell<i64>>,
-	set_buttons_enabled: Rc<dyn Fn(bool)>,
+	categories: Rc<Vec<String>>,
+	/// Annotations made in other applications, listed by the filter after the categories.
+	annotations: Rc<Vec<ExternalAnnotation>>,
+	read_only: Rc<Cell<bool>>,
+	sync_selection: Rc<dyn Fn()>,
 }
 
 struct BookmarkDialogActions {
 	dialog: Dialog,
 	filter_choice: Choice,
-	bookmark_list: ListBox,
+	bookmark_list: ListCtrl,
 	edit_button: Button,
 	delete_button: Button,
+	copy_button: Button,
+	convert_button: Button,
 	cancel_button: Button,
 	repopulate: Rc<dyn Fn(i64)>,
+	entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>,
 	selected_start: Rc<Cell<i64>>,
 	selected_end: Rc<Cell<i64>>,
+	read_only: Rc<Cell<bool>>,
 	config: Rc<Mutex<ConfigManager>>,
+	categories: Rc<Vec<String>>,
 	file_path: String,
 	current_pos: i64,
 }
 
-fn build_bookmark_dialog_ui(dialog: Dialog, initial_filter: BookmarkFilterType) -> BookmarkDialogUi {
+fn build_bookmark_dialog_ui(
+	dialog: Dialog,
+	initial_filter: BookmarkFilterType,
+	categories: &[String],
+	has_annotations: bool,
+) -> BookmarkDialogUi {
 	// TRANSLATORS: Label for the bookmark filter dropdown
 	let filter_label_text = t("&Filter:");
 	let filter_label = StaticText::builder(&dialog).with_label(&filter_label_text).build();
@@ -157,6 +186,14 @@ fn build_bookmark_dialog_ui(dialog: Dialog, initial_filter: BookmarkFilterType)
 	filter_choice.append(&t("Bookmarks"));
 	// TRANSLATORS: Option in the filter dropdown to show notes only
 	filter_choice.append(&t("Notes"));
+	for category in categories {
+		// TRANSLATORS: Option in the bookmark filter dropdown showing one category; {} is the category name
+		filter_choice.append(&t("Category: {}").replace("{}", category));
+	}
+	if has_annotations {
+		// TRANSLATORS: Option in the bookmark filter dropdown showing the highlights and notes made in other applications, such as PDF annotations
+		filter_choice.append(&t("Annotations"));
+	}
 	let initial_index = match initial_filter {
 		BookmarkFilterType::BookmarksOnly => 1,
 		BookmarkFilterType::NotesOnly => 2,
@@ -168,7 +205,12 @@ fn build_bookmark_dialog_ui(dialog: Dialog, initial_filter: BookmarkFilterType)
 	let filter_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	filter_sizer.add(&filter_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 6);
 	filter_sizer.add(&filter_choice, 1, SizerFlag::Expand, 0);
-	let bookmark_list = ListBox::builder(&dialog).build();
+	let bookmark_list = ListCtrl::builder(&dialog)
+		.with_style(ListCtrlStyle::Report)
+		.with_size(Size::new(BOOKMARK_LIST_WIDTH, BOOKMARK_LIST_HEIGHT))
+		.build();
+	// TRANSLATORS: Column header in the bookmarks list, which shows each bookmark's note and text
+	bookmark_list.insert_column(0, &t("Bookmark"), ListColumnFormat::Left, BOOKMARK_LIST_WIDTH - 30);
 	// TRANSLATORS: Label for the bookmark/note list (used only as an accessibility label on macOS)
 	let list_label_text = t("&Bookmarks:");
 	let _list_label = StaticText::builder(&dialog).with_label(&list_label_text).build();
@@ -177,6 +219,11 @@ fn build_bookmark_dialog_ui(dialog: Dialog, initial_filter: BookmarkFilterType)
 	// TRANSLATORS: Label for the button to edit a note
 	let edit_button = Button::builder(&dialog).with_label(&t("&Edit Note")).build();
 	let delete_button = Button::builder(&dialog).with_label(&t("&Delete")).build();
+	// TRANSLATORS: Label for the button that copies the selected bookmarks' notes and text to the clipboard
+	let copy_button = Button::builder(&dialog).with_label(&t("C&opy")).build();
+	// TRANSLATORS: Label for the button that adds the selected annotations from the file as bookmarks
+	let convert_button = Button::builder(&dialog).with_label(&t("Con&vert to Bookmark")).build();
+	convert_button.show(has_annotations);
 	// TRANSLATORS: Label for the button to jump to the selected bookmark
 	let jump_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("&Jump")).build();
 	// TRANSLATORS: Label for the button to cancel the action
@@ -189,25 +236,87 @@ fn build_bookmark_dialog_ui(dialog: Dialog, initial_filter: BookmarkFilterType)
 		bookmark_list,
 		edit_button,
 		delete_button,
+		copy_button,
+		convert_button,
 		jump_button,
 		cancel_button,
 	}
 }
 
-fn build_bookmark_dialog_state(jump_button: Button, delete_button: Button, edit_button: Button) -> BookmarkDialogState {
+/// Jump and Edit Note act on a single bookmark, so they are only enabled while exactly one is
+/// selected; Delete and Copy take the whole selection. Annotations can be copied or converted to
+/// bookmarks, but not edited or deleted.
+fn build_bookmark_dialog_state(list: ListCtrl, buttons: BookmarkButtons) -> BookmarkDialogState {
 	let entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>> = Rc::new(RefCell::new(Vec::new()));
 	let selected_start = Rc::new(Cell::new(-1i64));
 	let selected_end = Rc::new(Cell::new(-1i64));
-	let jump_button_for_state = jump_button;
-	let delete_button_for_state = delete_button;
-	let edit_button_for_state = edit_button;
-	let set_buttons_enabled = Rc::new(move |enabled: bool| {
-		jump_button_for_state.enable(enabled);
-		delete_button_for_state.enable(enabled);
-		edit_button_for_state.enable(enabled);
+	let read_only = Rc::new(Cell::new(false));
+	let entries_for_state = Rc::clone(&entries);
+	let selected_start_for_state = Rc::clone(&selected_start);
+	let selected_end_for_state = Rc::clone(&selected_end);
+	let read_only_for_state = Rc::clone(&read_only);
+	let sync_selection = Rc::new(move || {
+		let selected = selected_indices(list);
+		let single = match selected.as_slice() {
+			[index] => entries_for_state.borrow().get(*index).map(|entry| (entry.start, entry.end)),
+			_ => None,
+		};
+		let (start, end) = single.unwrap_or((-1, -1));
+		selected_start_for_state.set(start);
+		selected_end_for_state.set(end);
+		let read_only = read_only_for_state.get();
+		buttons.jump.enable(single.is_some());
+		buttons.edit.enable(single.is_some() && !read_only);
+		buttons.delete.enable(!selected.is_empty() && !read_only);
+		buttons.copy.enable(!selected.is_empty());
+		buttons.convert.enable(!selected.is_empty() && read_only);
 	});
-	set_buttons_enabled(false);
-	BookmarkDialogState { entries, selected_start, selected_end, set_buttons_enabled }
+	sync_selection();
+	BookmarkDialogState { entries, selected_start, selected_end, read_only, sync_selection }
+}
+
+fn selected_indices(list: ListCtrl) -> Vec<usize> {
+	let mut indices = Vec::new();
+	let mut next = list.get_first_selected_item();
+	while let Ok(index) = usize::try_from(next) {
+		indices.push(index);
+		next = list.get_next_item(i64::from(next), ListNextItemFlag::All, ListItemState::Selected);
+	}
+	indices
+}
+
+fn focused_index(list: ListCtrl) -> Option<usize> {
+	usize::try_from(list.get_next_item(-1, ListNextItemFlag::All, ListItemState::Focused)).ok()
+}
+
+fn selected_entries(list: ListCtrl, entries: &RefCell<Vec<BookmarkDisplayEntry>>) -> Vec<BookmarkDisplayEntry> {
+	let entries = entries.borrow();
+	selected_indices(list).into_iter().filter_map(|index| entries.get(index).cloned()).collect()
+}
+
+/// The text of every bookmark and annotation in the document, by range: the marked text, or the
+/// whole line for one at a single position. Taken once when the dialog opens, so it doesn't hold a
+/// copy of the whole document.
+fn bookmark_snippets(
+	session: &DocumentSession,
+	config: &Rc<Mutex<ConfigManager>>,
+	file_path: &str,
+	annotations: &[ExternalAnnotation],
+) -> HashMap<(i64, i64), String> {
+	let bookmarks = {
+		let cfg = config.lock().unwrap();
+		reader_core::get_filtered_bookmarks(&cfg, file_path, 0, BookmarkFilterType::All, None)
+	};
+	bookmarks
+		.items
+		.into_iter()
+		.map(|item| (item.start, item.end))
+		.chain(annotations.iter().map(|annotation| (annotation.start, annotation.end)))
+		.map(|(start, end)| {
+			let snippet = if start == end { session.get_line_text(start) } else { session.get_text_range(start, end) };
+			((start, end), snippet)
+		})
+		.collect()
 }
 
 fn build_bookmark_repopulate(params: BookmarkRepopulateParams) -> Rc<dyn Fn(i64)> {
@@ -215,12 +324,13 @@ fn build_bookmark_repopulate(params: BookmarkRepopulateParams) -> Rc<dyn Fn(i64)
 		list,
 		config,
 		file_path,
-		content,
+		snippets,
 		entries,
-		selected_start,
-		selected_end,
 		filter_choice,
-		set_buttons_enabled,
+		categories,
+		annotations,
+		read_only,
+		sync_selection,
 	} = params;
 	Rc::new(move |pos: i64| {
 		let filter_index = filter_choice.get_selection().unwrap_or(0);
@@ -229,95 +339,133 @@ fn build_bookmark_repopulate(params: BookmarkRepopulateParams) -> Rc<dyn Fn(i64)
 			2 => BookmarkFilterType::NotesOnly,
 			_ => BookmarkFilterType::All,
 		};
-		let content_for_snippet = Rc::clone(&content);
-		let get_text_range = move |start: i64, end: i64| -> String {
-			let content = content_for_snippet.as_str();
-			let total_chars = content.chars().count();
-			let start_pos = usize::try_from(start.max(0)).unwrap_or(0).min(total_chars);
-			let end_pos = usize::try_from(end.max(0)).unwrap_or(0).min(total_chars);
-			if start_pos >= end_pos {
-				return String::new();
-			}
-			content.chars().skip(start_pos).take(end_pos - start_pos).collect()
-		};
-		let content_for_line = Rc::clone(&content);
-		let get_line_text = move |position: i64| -> String {
-			let content = content_for_line.as_str();
-			let total_chars = content.chars().count();
-			let pos = usize::try_from(position.max(0)).unwrap_or(0).min(total_chars);
-			let line_start =
-				content.chars().take(pos).collect::<Vec<_>>().iter().rposition(|&c| c == '\n').map_or(0, |idx| idx + 1);
-			let chars_after_start: String = content.chars().skip(line_start).collect();
-			let line_end = chars_after_start.find('\n').map_or(chars_after_start.len(), |idx| idx);
-			chars_after_start.chars().take(line_end).collect()
+		let category = usize::try_from(filter_index)
+			.ok()
+			.and_then(|index| index.checked_sub(FIXED_FILTER_COUNT))
+			.and_then(|index| categories.get(index));
+		// Selection is kept by start offset, since a refresh can add, drop or reorder rows.
+		let (previous_selected, previous_focus) = {
+			let entries_ref = entries.borrow();
+			let start_at = |index: usize| entries_ref.get(index).map(|entry| entry.start);
+			let selected: HashSet<i64> = selected_indices(list).into_iter().filter_map(start_at).collect();
+			(selected, focused_index(list).and_then(start_at))
 		};
-		let previous_selected = selected_start.get();
-		list.clear();
+		list.delete_all_items();
 		entries.borrow_mut().clear();
-		let filtered = {
-			let cfg = config.lock().unwrap();
-			reader_core::get_filtered_bookmarks(&cfg, &file_path, pos, filter)
+		let showing_annotations = !annotations.is_empty()
+			&& usize::try_from(filter_index).is_ok_and(|index| index == FIXED_FILTER_COUNT + categories.len());
+		read_only.set(showing_annotations);
+		let closest_index = if showing_annotations {
+			fill_annotation_list(list, &annotations, &snippets, &entries, pos)
+		} else {
+			let filtered = {
+				let cfg = config.lock().unwrap();
+				reader_core::get_filtered_bookmarks(&cfg, &file_path, pos, filter, category.map(String::as_str))
+			};
+			fill_bookmark_list(list, filtered, &snippets, &entries)
 		};
-		for item in filtered.items {
-			let snippet =
-				if item.is_whole_line { get_line_text(item.start) } else { get_text_range(item.start, item.end) };
-			let mut snippet = snippet.trim().to_string();
-			if snippet.trim().is_empty() {
-				// TRANSLATORS: Placeholder text shown in the bookmarks list when the bookmark text range is empty or blank
-				snippet = t("blank");
-			}
-			let display = if item.note.is_empty() { snippet.clone() } else { format!("{} - {}", item.note, snippet) };
-			entries.borrow_mut().push(BookmarkDisplayEntry { start: item.start, end: item.end });
-			list.append(&display);
-		}
-		selected_start.set(-1);
-		selected_end.set(-1);
-		set_buttons_enabled(false);
-		let entries_ref = entries.borrow();
-		if previous_selected >= 0
-			&& let Some((idx, entry)) =
-				entries_ref.iter().enumerate().find(|(_, entry)| entry.start == previous_selected)
-		{
-			if let Ok(idx_u32) = u32::try_from(idx) {
-				list.set_selection(idx_u32, true);
+		let (restored, focus) = {
+			let entries_ref = entries.borrow();
+			let restored: Vec<usize> = entries_ref
+				.iter()
+				.enumerate()
+				.filter(|(_, entry)| previous_selected.contains(&entry.start))
+				.map(|(index, _)| index)
+				.collect();
+			let focus = previous_focus
+				.and_then(|start| restored.iter().copied().find(|&index| entries_ref[index].start == start))
+				.or_else(|| restored.first().copied())
+				.or_else(|| usize::try_from(closest_index).ok().filter(|&index| index < entries_ref.len()));
+			(restored, focus)
+		};
+		for &index in &restored {
+			if let Ok(index) = i64::try_from(index) {
+				list.set_item_state(index, ListItemState::Selected, ListItemState::Selected);
 			}
-			selected_start.set(entry.start);
-			selected_end.set(entry.end);
-			set_buttons_enabled(true);
-			return;
 		}
-		if filtered.closest_index >= 0
-			&& let Ok(idx) = usize::try_from(filtered.closest_index)
-			&& let Some(entry) = entries_ref.get(idx)
-		{
-			if let Ok(idx_u32) = u32::try_from(idx) {
-				list.set_selection(idx_u32, true);
-			}
-			selected_start.set(entry.start);
-			selected_end.set(entry.end);
-			set_buttons_enabled(true);
+		if let Some(index) = focus.and_then(|index| i64::try_from(index).ok()) {
+			let state = if restored.is_empty() {
+				ListItemState::Selected | ListItemState::Focused
+			} else {
+				ListItemState::Focused
+			};
+			list.set_item_state(index, state, state);
+			list.ensure_visible(index);
 		}
+		sync_selection();
 	})
 }
 
-fn bind_bookmark_selection(params: BookmarkSelectionParams) {
-	let BookmarkSelectionParams { list, entries, selected_start, selected_end, set_buttons_enabled } = params;
-	list.on_selection_changed(move |event| {
-		let selection = event.get_selection().unwrap_or(-1);
-		if selection >= 0 {
-			let entries_ref = entries.borrow();
-			if let Ok(index) = usize::try_from(selection)
-				&& let Some(entry) = entries_ref.get(index)
-			{
-				selected_start.set(entry.start);
-				selected_end.set(entry.end);
-				set_buttons_enabled(true);
-				return;
-			}
+/// Lists the bookmarks `filtered` holds and returns the index of the one nearest the caret.
+fn fill_bookmark_list(
+	list: ListCtrl,
+	filtered: FilteredBookmarks,
+	snippets: &HashMap<(i64, i64), String>,
+	entries: &RefCell<Vec<BookmarkDisplayEntry>>,
+) -> i32 {
+	for item in filtered.items {
+		let mut snippet = snippets.get(&(item.start, item.end)).map_or("", |snippet| snippet.trim()).to_string();
+		if snippet.trim().is_empty() {
+			// TRANSLATORS: Placeholder text shown in the bookmarks list when the bookmark text range is empty or blank
+			snippet = t("blank");
+		}
+		let mut display = if item.note.is_empty() { snippet.clone() } else { format!("{} - {}", item.note, snippet) };
+		if !item.category.is_empty() {
+			display = format!("[{}] {display}", item.category);
+		}
+		let index = i64::from(list.get_item_count());
+		list.insert_item(index, &display, None);
+		entries.borrow_mut().push(BookmarkDisplayEntry { start: item.start, end: item.end, note: item.note, snippet });
+	}
+	filtered.closest_index
+}
+
+/// Lists the file's annotations, each after its author, and returns the index of the one nearest
+/// the caret at `pos`.
+fn fill_annotation_list(
+	list: ListCtrl,
+	annotations: &[ExternalAnnotation],
+	snippets: &HashMap<(i64, i64), String>,
+	entries: &RefCell<Vec<BookmarkDisplayEntry>>,
+	pos: i64,
+) -> i32 {
+	for annotation in annotations {
+		let mut snippet =
+			snippets.get(&(annotation.start, annotation.end)).map_or("", |snippet| snippet.trim()).to_string();
+		if snippet.is_empty() {
+			// TRANSLATORS: Placeholder text shown in the bookmarks list when the bookmark text range is empty or blank
+			snippet = t("blank");
+		}
+		let mut display =
+			if annotation.note.is_empty() { snippet.clone() } else { format!("{} - {}", annotation.note, snippet) };
+		if !annotation.author.is_empty() {
+			display = format!("[{}] {display}", annotation.author);
 		}
-		selected_start.set(-1);
-		selected_end.set(-1);
-		set_buttons_enabled(false);
+		let index = i64::from(list.get_item_count());
+		list.insert_item(index, &display, None);
+		entries.borrow_mut().push(BookmarkDisplayEntry {
+			start: annotation.start,
+			end: annotation.end,
+			note: annotation.bookmark_note(),
+			snippet,
+		});
+	}
+	annotations
+		.iter()
+		.enumerate()
+		.min_by_key(|(_, annotation)| (annotation.start - pos).abs())
+		.and_then(|(index, _)| i32::try_from(index).ok())
+		.unwrap_or(-1)
+}
+
+fn bind_bookmark_selection(list: ListCtrl, sync_selection: &Rc<dyn Fn()>) {
+	let sync_for_select = Rc::clone(sync_selection);
+	list.on_item_selected(move |_| {
+		sync_for_select();
+	});
+	let sync_for_deselect = Rc::clone(sync_selection);
+	list.on_item_deselected(move |_| {
+		sync_for_deselect();
 	});
 }
 
@@ -348,25 +496,54 @@ fn bind_bookmark_actions(actions: BookmarkDialogActions) {
 		bookmark_list,
 		edit_button,
 		delete_button,
+		copy_button,
+		convert_button,
 		cancel_button,
 		repopulate,
+		entries,
 		selected_start,
 		selected_end,
+		read_only,
 		config,
+		categories,
 		file_path,
 		current_pos,
 	} = actions;
 	bind_bookmark_cancel(dialog, cancel_button);
-	bind_bookmark_filter(filter_choice, Rc::clone(&repopulate), current_pos);
-	bind_bookmark_delete(
-		delete_button,
-		Rc::clone(&repopulate),
-		Rc::clone(&selected_start),
-		Rc::clone(&selected_end),
-		Rc::clone(&config),
-		file_path.clone(),
+	bind_bookmark_filter(BookmarkFilterParams {
+		filter_choice,
+		repopulate: Rc::clone(&repopulate),
+		config: Rc::clone(&config),
+		categories: Rc::clone(&categories),
 		current_pos,
-	);
+	});
+	let delete_action = make_bookmark_delete_action(BookmarkDeleteParams {
+		dialog,
+		list: bookmark_list,
+		entries: Rc::clone(&entries),
+		repopulate: Rc::clone(&repopulate),
+		read_only: Rc::clone(&read_only),
+		config: Rc::clone(&config),
+		file_path: file_path.clone(),
+		current_pos,
+	});
+	let delete_action_for_button = Rc::clone(&delete_action);
+	delete_button.on_click(move |_| {
+		delete_action_for_button();
+	});
+	let copy_action = make_bookmark_copy_action(bookmark_list, Rc::clone(&entries));
+	let copy_action_for_button = Rc::clone(&copy_action);
+	copy_button.on_click(move |_| {
+		copy_action_for_button();
+	});
+	bind_annotation_convert(AnnotationConvertParams {
+		dialog,
+		list: bookmark_list,
+		convert_button,
+		entries: Rc::clone(&entries),
+		config: Rc::clone(&config),
+		file_path: file_path.clone(),
+	});
 	bind_bookmark_edit(BookmarkEditParams {
 		dialog,
 		edit_button,
@@ -374,19 +551,12 @@ fn bind_bookmark_actions(actions: BookmarkDialogActions) {
 		selected_start: Rc::clone(&selected_start),
 		selected_end: Rc::clone(&selected_end),
 		config: Rc::clone(&config),
+		categories: Rc::clone(&categories),
 		file_path: file_path.clone(),
 		current_pos,
 	});
-	bind_bookmark_key_actions(
-		bookmark_list,
-		Rc::clone(&repopulate),
-		Rc::clone(&selected_start),
-		Rc::clone(&selected_end),
-		Rc::clone(&config),
-		file_path,
-		current_pos,
-	);
-	bind_bookmark_double_click(bookmark_list, dialog, selected_start);
+	bind_bookmark_key_actions(bookmark_list, delete_action, copy_action);
+	bind_bookmark_activate(bookmark_list, dialog, selected_start);
 }
 
 fn bind_bookmark_cancel(dialog: Dialog, cancel_button: Button) {
@@ -395,33 +565,114 @@ fn bind_bookmark_cancel(dialog: Dialog, cancel_button: Button) {
 	});
 }
 
-fn bind_bookmark_filter(filter_choice: Choice, repopulate: Rc<dyn Fn(i64)>, current_pos: i64) {
+struct BookmarkFilterParams {
+	filter_choice: Choice,
+	repopulate: Rc<dyn Fn(i64)>,
+	config: Rc<Mutex<ConfigManager>>,
+	categories: Rc<Vec<String>>,
+	current_pos: i64,
+}
+
+fn bind_bookmark_filter(params: BookmarkFilterParams) {
+	let BookmarkFilterParams { filter_choice, repopulate, config, categories, current_pos } = params;
 	filter_choice.on_selection_changed(move |_event| {
+		let category = filter_choice
+			.get_selection()
+			.and_then(|index| usize::try_from(index).ok())
+			.and_then(|index| index.checked_sub(FIXED_FILTER_COUNT))
+			.and_then(|index| categories.get(index))
+			.map_or("", String::as_str);
+		set_selected_bookmark_category(&config.lock().unwrap(), category);
 		repopulate(current_pos);
 	});
 }
 
-fn bind_bookmark_delete(
-	delete_button: Button,
+struct BookmarkDeleteParams {
+	dialog: Dialog,
+	list: ListCtrl,
+	entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>,
 	repopulate: Rc<dyn Fn(i64)>,
-	selected_start: Rc<Cell<i64>>,
-	selected_end: Rc<Cell<i64>>,
+	read_only: Rc<Cell<bool>>,
 	config: Rc<Mutex<ConfigManager>>,
 	file_path: String,
 	current_pos: i64,
-) {
-	delete_button.on_click(move |_| {
-		let start = selected_start.get();
-		let end = selected_end.get();
-		if start < 0 {
+}
+
+/// Removes every selected bookmark, asking once first when there is more than one.
+fn make_bookmark_delete_action(params: BookmarkDeleteParams) -> Rc<dyn Fn()> {
+	let BookmarkDeleteParams { dialog, list, entries, repopulate, read_only, config, file_path, current_pos } = params;
+	Rc::new(move || {
+		let selected = selected_entries(list, &entries);
+		if selected.is_empty() || read_only.get() {
 			return;
 		}
+		if selected.len() > 1 {
+			// TRANSLATORS: Confirmation before deleting several bookmarks at once; %d is how many are selected. Give one variant per plural form of your language, separated by |
+			let message = plural(
+				&t("Delete the %d selected bookmark?|Delete the %d selected bookmarks?"),
+				u64::try_from(selected.len()).unwrap_or(u64::MAX),
+			);
+			// TRANSLATORS: Title of the confirmation shown before deleting several bookmarks
+			let confirm = MessageDialog::builder(&dialog, &message, &t("Delete Bookmarks"))
+				.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+				.build();
+			if confirm.show_modal() != ID_YES {
+				return;
+			}
+		}
 		{
 			let cfg = config.lock().unwrap();
-			cfg.remove_bookmark(&file_path, start, end);
+			for entry in &selected {
+				cfg.remove_bookmark(&file_path, entry.start, entry.end);
+			}
 			cfg.flush();
 		}
 		repopulate(current_pos);
+	})
+}
+
+fn make_bookmark_copy_action(list: ListCtrl, entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>) -> Rc<dyn Fn()> {
+	Rc::new(move || {
+		let selected = selected_entries(list, &entries);
+		if !selected.is_empty() {
+			Clipboard::get().set_text(&reader_core::bookmarks_clipboard_text(&selected));
+		}
+	})
+}
+
+struct AnnotationConvertParams {
+	dialog: Dialog,
+	list: ListCtrl,
+	convert_button: Button,
+	entries: Rc<RefCell<Vec<BookmarkDisplayEntry>>>,
+	config: Rc<Mutex<ConfigManager>>,
+	file_path: String,
+}
+
+/// Adds each selected annotation as a bookmark over the same text, noted with its author and note.
+/// The annotations stay listed, since they still belong to the file.
+fn bind_annotation_convert(params: AnnotationConvertParams) {
+	let AnnotationConvertParams { dialog, list, convert_button, entries, config, file_path } = params;
+	convert_button.on_click(move |_| {
+		let selected = selected_entries(list, &entries);
+		if selected.is_empty() {
+			return;
+		}
+		{
+			let cfg = config.lock().unwrap();
+			for entry in &selected {
+				cfg.add_bookmark(&file_path, entry.start, entry.end, &entry.note);
+			}
+			cfg.flush();
+		}
+		// TRANSLATORS: Shown after converting annotations to bookmarks; %d is how many. Give one variant per plural form of your language, separated by |
+		let template = t("Added %d bookmark.|Added %d bookmarks.");
+		let message = plural(&template, u64::try_from(selected.len()).unwrap_or(u64::MAX));
+		// TRANSLATORS: Title of the message shown after converting annotations to bookmarks
+		MessageDialog::builder(&dialog, &message, &t("Convert to Bookmark"))
+			.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconInformation | MessageDialogStyle::Centre)
+			.build()
+			.show_modal();
 	});
 }
 
@@ -432,6 +683,7 @@ struct BookmarkEditParams {
 	selected_start: Rc<Cell<i64>>,
 	selected_end: Rc<Cell<i64>>,
 	config: Rc<Mutex<ConfigManager>>,
+	categories: Rc<Vec<String>>,
 	file_path: String,
 	current_pos: i64,
 }
@@ -444,6 +696,7 @@ fn bind_bookmark_edit(params: BookmarkEditParams) {
 		selected_start,
 		selected_end,
 		config,
+		categories,
 		file_path,
 		current_pos,
 	} = params;
@@ -453,55 +706,52 @@ fn bind_bookmark_edit(params: BookmarkEditParams) {
 		if start < 0 {
 			return;
 		}
-		let existing_note = {
+		let existing = {
 			let cfg = config.lock().unwrap();
 			cfg.get_bookmarks(&file_path)
 				.into_iter()
 				.find(|bm| bm.start == start && bm.end == end)
-				.map(|bm| bm.note)
-				.unwrap_or_default()
+				.map(|bm| BookmarkNoteEntry { note: bm.note, category: bm.category })
+				.unwrap_or_else(|| BookmarkNoteEntry { note: String::new(), category: String::new() })
 		};
-		let Some(note) = show_note_entry_dialog(
+		let Some(entry) = show_note_entry_dialog(
 			&dialog,
 			// TRANSLATORS: Title of the Bookmark Note editor dialog
 			&t("Bookmark Note"),
 			// TRANSLATORS: Label/prompt in the Note editor dialog
 			&t("Edit bookmark note:"),
-			&existing_note,
+			&existing,
+			&categories,
 		) else {
 			return;
 		};
 		{
 			let cfg = config.lock().unwrap();
-			cfg.update_bookmark_note(&file_path, start, end, &note);
+			cfg.update_bookmark_note(&file_path, start, end, &entry.note);
+			cfg.update_bookmark_category(&file_path, start, end, &entry.category);
 			cfg.flush();
 		}
 		repopulate(current_pos);
 	});
 }
 
-fn bind_bookmark_key_actions(
-	bookmark_list: ListBox,
-	repopulate: Rc<dyn Fn(i64)>,
-	selected_start: Rc<Cell<i64>>,
-	selected_end: Rc<Cell<i64>>,
-	config: Rc<Mutex<ConfigManager>>,
-	file_path: String,
-	current_pos: i64,
-) {
+/// Shift+arrows extend the selection natively; Ctrl+A selects every bookmark, Ctrl+C copies the
+/// selection and Delete removes it.
+fn bind_bookmark_key_actions(bookmark_list: ListCtrl, delete_action: Rc<dyn Fn()>, copy_action: Rc<dyn Fn()>) {
 	bookmark_list.bind_internal(EventType::KEY_DOWN, move |event| {
 		let key = event.get_key_code().unwrap_or(0);
 		if key == KEY_DELETE || key == KEY_NUMPAD_DELETE {
-			let start = selected_start.get();
-			let end = selected_end.get();
-			if start >= 0 {
-				{
-					let cfg = config.lock().unwrap();
-					cfg.remove_bookmark(&file_path, start, end);
-					cfg.flush();
-				}
-				repopulate(current_pos);
-			}
+			delete_action();
+			event.skip(false);
+			return;
+		}
+		if key == i32::from(b'A') && event.control_down() {
+			bookmark_list.set_item_state(-1, ListItemState::Selected, ListItemState::Selected);
+			event.skip(false);
+			return;
+		}
+		if key == i32::from(b'C') && event.control_down() {
+			copy_action();
 			event.skip(false);
 			return;
 		}
@@ -509,8 +759,8 @@ fn bind_bookmark_key_actions(
 	});
 }
 
-fn bind_bookmark_double_click(bookmark_list: ListBox, dialog: Dialog, selected_start: Rc<Cell<i64>>) {
-	bookmark_list.on_item_double_clicked(move |_| {
+fn bind_bookmark_activate(bookmark_list: ListCtrl, dialog: Dialog, selected_start: Rc<Cell<i64>>) {
+	bookmark_list.on_item_activated(move |_| {
 		if selected_start.get() >= 0 {
 			dialog.end_modal(ID_OK);
 		}
@@ -520,16 +770,16 @@ fn bind_bookmark_double_click(bookmark_list: ListBox, dialog: Dialog, selected_s
 fn finalize_bookmark_dialog_layout(
 	dialog: Dialog,
 	filter_sizer: BoxSizer,
-	bookmark_list: ListBox,
-	edit_button: Button,
-	delete_button: Button,
-	jump_button: Button,
+	bookmark_list: ListCtrl,
+	buttons: BookmarkButtons,
 	cancel_button: Button,
 ) {
 	let action_sizer = BoxSizer::builder(Orientation::Horizontal).build();
-	action_sizer.add(&edit_button, 0, SizerFlag::Right, DIALOG_PADDING);
-	action_sizer.add(&delete_button, 0, SizerFlag::Right, DIALOG_PADDING);
-	action_sizer.add(&jump_button, 0, SizerFlag::Right, DIALOG_PADDING);
+	action_sizer.add(&buttons.edit, 0, SizerFlag::Right, DIALOG_PADDING);
+	action_sizer.add(&buttons.delete, 0, SizerFlag::Right, DIALOG_PADDING);
+	action_sizer.add(&buttons.copy, 0, SizerFlag::Right, DIALOG_PADDING);
+	action_sizer.add(&buttons.convert, 0, SizerFlag::Right, DIALOG_PADDING);
+	action_sizer.add(&buttons.jump, 0, SizerFlag::Right, DIALOG_PADDING);
 	action_sizer.add(&cancel_button, 0, SizerFlag::Right, DIALOG_PADDING);
 	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
 	content_sizer.add_sizer(&filter_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
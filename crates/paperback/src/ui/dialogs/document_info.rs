@@ -1,15 +1,100 @@
 use std::{fmt::Write, path::Path};
 
-use paperback_core::document::DocumentStats;
+use paperback_core::{
+	config::ReadingStats,
+	document::{DocumentStats, MetadataField},
+	util::language::{DETECTABLE_LANGUAGES, language_from_tag, language_name},
+};
 use patois::t;
 use wxdragon::prelude::*;
 
 use super::DIALOG_PADDING;
+use crate::translation_manager::format_number;
 
 const DOC_INFO_WIDTH: i32 = 600;
 const DOC_INFO_HEIGHT: i32 = 400;
 
-pub fn show_document_info_dialog(parent: &Frame, path: &Path, title: &str, author: &str, stats: &DocumentStats) {
+/// Per-document settings that can be changed from the Document Info dialog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentInfoOptions {
+	/// Language code chosen by the user; empty to use the detected language.
+	pub language_override: String,
+	/// Whether PDF column detection is enabled, or `None` for documents it doesn't apply to.
+	pub detect_columns: Option<bool>,
+	/// Whether headings are guessed from font sizes when a PDF has no outline, or `None` for
+	/// documents it doesn't apply to.
+	pub detect_headings: Option<bool>,
+	/// Whether lines repeated at the top or bottom of most pages are removed.
+	pub strip_running_lines: bool,
+}
+
+/// Read-only facts shown in the Document Info dialog.
+#[derive(Clone, Copy)]
+pub struct DocumentInfo<'a> {
+	pub path: &'a Path,
+	pub title: &'a str,
+	pub author: &'a str,
+	/// Series, publisher, date and so on read from the file, grouped by field.
+	pub metadata: &'a [(MetadataField, String)],
+	pub stats: &'a DocumentStats,
+	/// Length of the text in the same units as caret positions.
+	pub char_length: usize,
+	pub figure_count: usize,
+	pub detected_language: &'a str,
+	pub reading_stats: ReadingStats,
+	/// How far through the document the reader has got at the furthest, `None` until its length is known.
+	pub furthest_percent: Option<i32>,
+	/// Characters in the document's notes page, 0 when it has none.
+	pub notes_length: usize,
+	pub typography_normalized: bool,
+	pub running_headers: &'a [String],
+	pub running_footers: &'a [String],
+	/// Whether the document keeps its own line breaks rather than wrapping, as for poetry or code.
+	pub preformatted: bool,
+}
+
+fn metadata_label(field: MetadataField) -> String {
+	match field {
+		// TRANSLATORS: Label in Document Info for the series a book belongs to and its number in it
+		MetadataField::Series => t("Series:"),
+		// TRANSLATORS: Label in Document Info for the document's publisher
+		MetadataField::Publisher => t("Publisher:"),
+		// TRANSLATORS: Label in Document Info for the date the document was published or created
+		MetadataField::Date => t("Date:"),
+		// TRANSLATORS: Label in Document Info for the language the file itself says it is written in, which can differ from the detected language
+		MetadataField::Language => t("Declared language:"),
+		// TRANSLATORS: Label in Document Info for the document's subjects, genres or keywords
+		MetadataField::Subject => t("Subjects:"),
+		// TRANSLATORS: Label in Document Info for identifiers such as the ISBN
+		MetadataField::Identifier => t("Identifiers:"),
+	}
+}
+
+/// Shows the document's metadata and statistics. Returns the updated per-document options if the
+/// user changed any of them.
+pub fn show_document_info_dialog(
+	parent: &Frame,
+	document: &DocumentInfo<'_>,
+	options: &DocumentInfoOptions,
+) -> Option<DocumentInfoOptions> {
+	let DocumentInfo {
+		path,
+		title,
+		author,
+		metadata,
+		stats,
+		char_length,
+		figure_count,
+		detected_language,
+		reading_stats,
+		furthest_percent,
+		notes_length,
+		typography_normalized,
+		running_headers,
+		running_footers,
+		preformatted,
+	} = *document;
+	let language_override = options.language_override.as_str();
 	// TRANSLATORS: Title of the Document Info dialog
 	let dialog_title = t("Document Info");
 	let dialog = Dialog::builder(parent, &dialog_title).build();
@@ -24,6 +109,8 @@ pub fn show_document_info_dialog(parent: &Frame, path: &Path, title: &str, autho
 	let title_label = t("Title:");
 	// TRANSLATORS: Label for the document's author
 	let author_label = t("Author:");
+	// TRANSLATORS: Label for the document's language
+	let language_label = t("Language:");
 	// TRANSLATORS: Label for the number of words in the document
 	let words_label = t("Words:");
 	// TRANSLATORS: Label for the number of lines in the document
@@ -32,6 +119,8 @@ pub fn show_document_info_dialog(parent: &Frame, path: &Path, title: &str, autho
 	let characters_label = t("Characters:");
 	// TRANSLATORS: Label for the number of characters in the document excluding space characters
 	let characters_no_spaces_label = t("Characters (excluding spaces):");
+	// TRANSLATORS: Label for the number of figures and images in the document
+	let figures_label = t("Figures and images:");
 	let mut info = String::new();
 	let _ = writeln!(info, "{path_label} {}", path.display());
 	if !title.is_empty() {
@@ -40,15 +129,100 @@ pub fn show_document_info_dialog(parent: &Frame, path: &Path, title: &str, autho
 	if !author.is_empty() {
 		let _ = writeln!(info, "{author_label} {author}");
 	}
-	let _ = writeln!(info, "{} {}", words_label, stats.word_count);
-	let _ = writeln!(info, "{lines_label} {}", stats.line_count);
-	let _ = writeln!(info, "{characters_label} {}", stats.char_count);
-	let _ = writeln!(info, "{characters_no_spaces_label} {}", stats.char_count_no_whitespace);
+	for (field, value) in metadata {
+		let value = match field {
+			MetadataField::Language => language_from_tag(value).map_or_else(|| value.clone(), language_name),
+			_ => value.clone(),
+		};
+		let _ = writeln!(info, "{} {value}", metadata_label(*field));
+	}
+	let language = if language_override.is_empty() { detected_language } else { language_override };
+	if !language.is_empty() {
+		let _ = writeln!(info, "{language_label} {}", language_name(language));
+	}
+	let _ = writeln!(info, "{words_label} {}", format_count(stats.word_count));
+	let _ = writeln!(info, "{lines_label} {}", format_count(stats.line_count));
+	let _ = writeln!(info, "{characters_label} {}", format_count(char_length));
+	let _ = writeln!(info, "{characters_no_spaces_label} {}", format_count(stats.char_count_no_whitespace));
+	let _ = writeln!(info, "{figures_label} {}", format_count(figure_count));
+	if !reading_stats.is_empty() {
+		let _ = writeln!(info, "{}", format_reading_stats(&reading_stats));
+	}
+	if let Some(percent) = furthest_percent.filter(|&percent| percent > 0) {
+		// TRANSLATORS: Line in the Document Info dialog saying how far into the document the reader has ever got; {} is a percentage
+		let _ = writeln!(info, "{}", t("Furthest read: {}%").replace("{}", &percent.to_string()));
+	}
+	if notes_length > 0 {
+		// TRANSLATORS: Line in the Document Info dialog when the document has notes from Tools > Document Notes; {} is their length in characters
+		let _ = writeln!(info, "{}", t("Notes: {} characters").replace("{}", &format_count(notes_length)));
+	}
+	if typography_normalized {
+		// TRANSLATORS: Note in the Document Info dialog when curly quotes, dashes and similar characters were replaced with plain ones
+		let _ = writeln!(info, "{}", t("Quotes, dashes and ellipses were replaced with plain characters for speech."));
+	}
+	if preformatted {
+		// TRANSLATORS: Line in the Document Info dialog when the document keeps its own line breaks instead of wrapping, as for poetry or code
+		let _ = writeln!(info, "{}", t("Layout: preformatted"));
+	}
+	for header in running_headers {
+		// TRANSLATORS: Line in the Document Info dialog naming a header repeated on every page that was removed from the text; {} is the header, with page numbers shown as N
+		let _ = writeln!(info, "{}", t("Removed running header: '{}'").replace("{}", header));
+	}
+	for footer in running_footers {
+		// TRANSLATORS: Line in the Document Info dialog naming a footer repeated on every page that was removed from the text; {} is the footer, with page numbers shown as N
+		let _ = writeln!(info, "{}", t("Removed running footer: '{}'").replace("{}", footer));
+	}
 	info_ctrl.set_value(&info);
+	// TRANSLATORS: Label for the dropdown that overrides a document's detected language
+	let language_choice_label_text = t("Document &language:");
+	let language_choice_label = StaticText::builder(&dialog).with_label(&language_choice_label_text).build();
+	let language_choice = Choice::builder(&dialog).build();
+	let automatic_label = if detected_language.is_empty() {
+		// TRANSLATORS: Language dropdown option used when no language could be detected for the document
+		t("Automatic (unknown)")
+	} else {
+		// TRANSLATORS: Language dropdown option that uses the detected language; {} is the language name
+		t("Automatic ({})").replace("{}", &language_name(detected_language))
+	};
+	language_choice.append(&automatic_label);
+	for code in DETECTABLE_LANGUAGES {
+		language_choice.append(&language_name(code));
+	}
+	let initial_index =
+		DETECTABLE_LANGUAGES.iter().position(|code| *code == language_override).map_or(0, |index| index + 1);
+	language_choice.set_selection(u32::try_from(initial_index).unwrap_or(0));
+	#[cfg(target_os = "macos")]
+	language_choice.set_accessibility_label(language_choice_label_text.replace('&', "").trim_end_matches(':').trim());
+	let language_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	language_sizer.add(&language_choice_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	language_sizer.add(&language_choice, 1, SizerFlag::Expand, 0);
+	let columns_check = options.detect_columns.map(|detect_columns| {
+		// TRANSLATORS: Checkbox in the Document Info dialog for PDFs; when checked, pages laid out in side-by-side columns are read column by column
+		let check = CheckBox::builder(&dialog).with_label(&t("Read multi-column pages column by &column")).build();
+		check.set_value(detect_columns);
+		check
+	});
+	let headings_check = options.detect_headings.map(|detect_headings| {
+		// TRANSLATORS: Checkbox in the Document Info dialog for PDFs; when checked, a PDF without a table of contents gets headings from lines set in larger type
+		let check = CheckBox::builder(&dialog).with_label(&t("Detect headings from font &sizes")).build();
+		check.set_value(detect_headings);
+		check
+	});
+	// TRANSLATORS: Checkbox in the Document Info dialog; when checked, lines repeated at the top or bottom of most pages, such as the book title and page numbers, are left out of the text
+	let running_lines_check = CheckBox::builder(&dialog).with_label(&t("Remove running &headers and footers")).build();
+	running_lines_check.set_value(options.strip_running_lines);
 	let ok_label = t("Close");
 	let ok_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&ok_label).build();
 	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
 	content_sizer.add(&info_ctrl, 1, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&language_sizer, 0, SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right, DIALOG_PADDING);
+	if let Some(check) = &columns_check {
+		content_sizer.add(check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Top, DIALOG_PADDING);
+	}
+	if let Some(check) = &headings_check {
+		content_sizer.add(check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Top, DIALOG_PADDING);
+	}
+	content_sizer.add(&running_lines_check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Top, DIALOG_PADDING);
 	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	button_sizer.add_stretch_spacer(1);
 	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
@@ -56,4 +230,38 @@ pub fn show_document_info_dialog(parent: &Frame, path: &Path, title: &str, autho
 	dialog.set_sizer_and_fit(content_sizer, true);
 	dialog.centre();
 	dialog.show_modal();
+	let selected = language_choice
+		.get_selection()
+		.and_then(|index| usize::try_from(index).ok())
+		.and_then(|index| index.checked_sub(1))
+		.and_then(|index| DETECTABLE_LANGUAGES.get(index))
+		.map_or("", |code| *code);
+	let updated = DocumentInfoOptions {
+		language_override: selected.to_string(),
+		detect_columns: columns_check.map(|check| check.is_checked()),
+		detect_headings: headings_check.map(|check| check.is_checked()),
+		strip_running_lines: running_lines_check.is_checked(),
+	};
+	(updated != *options).then_some(updated)
+}
+
+fn format_count(count: usize) -> String {
+	format_number(u64::try_from(count).unwrap_or(u64::MAX))
+}
+
+fn format_reading_stats(reading_stats: &ReadingStats) -> String {
+	let hours = reading_stats.seconds_read / 3600;
+	let minutes = (reading_stats.seconds_read % 3600) / 60;
+	let time = if hours > 0 {
+		// TRANSLATORS: Time spent reading a document in hours and minutes, e.g. "4 h 12 m"
+		t("%d h %d m").replacen("%d", &hours.to_string(), 1).replacen("%d", &minutes.to_string(), 1)
+	} else {
+		// TRANSLATORS: Time spent reading a document in minutes, e.g. "12 m"
+		t("%d m").replacen("%d", &minutes.to_string(), 1)
+	};
+	// TRANSLATORS: Reading statistics in the Document Info dialog; the first %s is the time read (e.g. "4 h 12 m"), %d is the number of times the document was opened, and the last %s is the date it was last read
+	t("Time read: %s over %d sessions, last read %s")
+		.replacen("%s", &time, 1)
+		.replacen("%d", &reading_stats.sessions.to_string(), 1)
+		.replacen("%s", &reading_stats.last_read_date().unwrap_or_default(), 1)
 }
@@ -0,0 +1,85 @@
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::{DIALOG_PADDING, KEY_RETURN};
+
+fn character_count_label(text: &str) -> String {
+	// TRANSLATORS: Shown under the Document Notes editor; {} is the number of characters typed
+	t("{} characters").replace("{}", &text.chars().count().to_string())
+}
+
+/// Edits the free-form notes kept for a whole document. Enter closes the dialog and Shift+Enter
+/// starts a new line, as in the bookmark note dialog. `selection` picks out a stretch of the notes,
+/// such as a Search Notes match, to scroll to; otherwise the caret starts at the end. Returns the text
+/// as it stood when the dialog closed, however it was closed, so nothing typed is lost.
+pub fn show_document_notes_dialog(
+	parent: &Frame,
+	document_title: &str,
+	notes: &str,
+	selection: Option<(i64, i64)>,
+) -> String {
+	// TRANSLATORS: Title of the Document Notes dialog; {} is the document's title
+	let title = t("Notes for {}").replace("{}", document_title);
+	let dialog =
+		Dialog::builder(parent, &title).with_style(DialogStyle::DefaultDialogStyle | DialogStyle::ResizeBorder).build();
+	// TRANSLATORS: Label for the text box holding a document's free-form notes
+	let notes_label_text = t("&Notes:");
+	let notes_label = StaticText::builder(&dialog).with_label(&notes_label_text).build();
+	let notes_ctrl = TextCtrl::builder(&dialog)
+		.with_value(notes)
+		.with_style(TextCtrlStyle::MultiLine)
+		.with_size(Size::new(500, 300))
+		.build();
+	#[cfg(target_os = "macos")]
+	notes_ctrl.set_accessibility_label(notes_label_text.replace('&', "").trim_end_matches(':').trim());
+	let count_label = StaticText::builder(&dialog).with_label(&character_count_label(notes)).build();
+	// TRANSLATORS: Label for the button that closes the Document Notes dialog, keeping the notes
+	let close_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Close")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	let dialog_for_close = dialog;
+	close_button.on_click(move |_| {
+		dialog_for_close.end_modal(ID_CANCEL);
+	});
+	notes_ctrl.on_text_updated(move |_| {
+		count_label.set_label(&character_count_label(&notes_ctrl.get_value()));
+	});
+	let dialog_for_key = dialog;
+	notes_ctrl.bind_internal(EventType::KEY_DOWN, move |event| {
+		if let Some(key) = event.get_key_code()
+			&& key == KEY_RETURN
+		{
+			if event.shift_down() {
+				event.skip(true);
+			} else {
+				dialog_for_key.end_modal(ID_CANCEL);
+				event.skip(false);
+			}
+			return;
+		}
+		event.skip(true);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&notes_label, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add(
+		&notes_ctrl,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add(&count_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&close_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	notes_ctrl.set_focus();
+	if let Some((start, end)) = selection {
+		notes_ctrl.set_selection(start, end);
+		notes_ctrl.show_position(start);
+	} else {
+		notes_ctrl.set_insertion_point(notes_ctrl.get_last_position());
+	}
+	dialog.show_modal();
+	notes_ctrl.get_value()
+}
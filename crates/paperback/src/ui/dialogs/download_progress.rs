@@ -0,0 +1,116 @@
+use std::{
+	cell::Cell,
+	path::PathBuf,
+	rc::Rc,
+	sync::{
+		Arc, Mutex, PoisonError,
+		atomic::{AtomicBool, Ordering},
+	},
+};
+
+use patois::t;
+use wxdragon::{prelude::*, timer::Timer};
+
+use super::DIALOG_PADDING;
+use crate::ui::{
+	speech,
+	url_download::{DownloadError, format_size},
+};
+
+/// How often the dialog looks at how far the download has got.
+const POLL_INTERVAL_MS: i32 = 200;
+
+/// How far a download running on another thread has got. The thread fills in `result` as it ends.
+#[derive(Default)]
+pub struct DownloadProgress {
+	pub received: u64,
+	pub total: Option<u64>,
+	pub result: Option<Result<PathBuf, DownloadError>>,
+}
+
+/// Shows a download running on another thread until it ends, announcing each tenth of the way.
+/// Cancel, Escape and closing the dialog set `cancel` and wait for the download to stop, so the
+/// file it was writing is gone by the time this returns. Returns how the download ended.
+pub fn show_download_progress_dialog(
+	parent: &Frame,
+	title: &str,
+	message: &str,
+	progress: &Arc<Mutex<DownloadProgress>>,
+	cancel: &Arc<AtomicBool>,
+) -> Result<PathBuf, DownloadError> {
+	let dialog = Dialog::builder(parent, title).build();
+	let message_label = StaticText::builder(&dialog).with_label(message).build();
+	let gauge = Gauge::builder(&dialog).with_range(100).with_size(Size::new(300, -1)).build();
+	let size_label = StaticText::builder(&dialog).with_label("").build();
+	let announcer = StaticText::builder(&dialog).with_label("").with_size(Size::new(0, 0)).build();
+	announcer.show(false);
+	let _ = live_region::set_live_region(&announcer);
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	let request_cancel = {
+		let cancel = Arc::clone(cancel);
+		Rc::new(move || {
+			cancel.store(true, Ordering::SeqCst);
+			cancel_button.enable(false);
+			// TRANSLATORS: Shown in a download's progress dialog while it stops after Cancel was pressed
+			size_label.set_label(&t("Cancelling…"));
+		})
+	};
+	let cancel_for_button = Rc::clone(&request_cancel);
+	cancel_button.on_click(move |_| cancel_for_button());
+	dialog.on_close(move |event| {
+		request_cancel();
+		event.skip(false);
+	});
+	let timer = Timer::new(&dialog);
+	let announced = Cell::new(0);
+	let ended = Cell::new(false);
+	let progress_for_tick = Arc::clone(progress);
+	let cancel_for_tick = Arc::clone(cancel);
+	timer.on_tick(move |_| {
+		let state = progress_for_tick.lock().unwrap_or_else(PoisonError::into_inner);
+		if state.result.is_some() {
+			if !ended.replace(true) {
+				dialog.end_modal(ID_OK);
+			}
+			return;
+		}
+		let Some(total) = state.total.filter(|&total| total > 0) else {
+			gauge.pulse();
+			return;
+		};
+		let percent = state.received.min(total) * 100 / total;
+		gauge.set_value(i32::try_from(percent).unwrap_or(100));
+		if cancel_for_tick.load(Ordering::SeqCst) {
+			return;
+		}
+		// TRANSLATORS: Shown under a download's progress bar; the first %s is how much has arrived, the second the whole size, e.g. "3.2 MB of 10.5 MB"
+		let sizes =
+			t("%s of %s").replacen("%s", &format_size(state.received), 1).replacen("%s", &format_size(total), 1);
+		size_label.set_label(&sizes);
+		let tenth = percent / 10;
+		if tenth > announced.get() {
+			announced.set(tenth);
+			// TRANSLATORS: Announced by screen readers every 10 percent of a download; %d is the percentage
+			speech::announce(announcer, &t("%d percent downloaded").replace("%d", &(tenth * 10).to_string()));
+		}
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&message_label, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add(&gauge, 0, SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right, DIALOG_PADDING);
+	content_sizer.add(&size_label, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	cancel_button.set_focus();
+	timer.start(POLL_INTERVAL_MS, false);
+	dialog.show_modal();
+	timer.stop();
+	dialog.destroy();
+	let result = progress.lock().unwrap_or_else(PoisonError::into_inner).result.take();
+	result.unwrap_or(Err(DownloadError::Cancelled))
+}
@@ -1,11 +1,20 @@
 use std::{cell::Cell, rc::Rc};
 #[cfg(not(target_os = "windows"))]
-use std::{collections::HashMap, ffi::c_void};
+use std::{cell::RefCell, collections::HashMap, ffi::c_void};
 
-use paperback_core::session::DocumentSession;
+use paperback_core::{
+	session::DocumentSession,
+	types::{HeadingTree, HeadingTreeItem, StructureKind},
+};
 use patois::t;
 use wxdragon::prelude::*;
 
+const VIEW_HEADINGS: u32 = 0;
+const VIEW_LINKS: u32 = 1;
+const VIEW_STRUCTURE: u32 = 2;
+const VIEW_FIGURES: u32 = 3;
+const MAX_HEADING_LEVEL: i32 = 6;
+
 pub fn show_elements_dialog(parent: &Frame, session: &DocumentSession, current_pos: i64) -> Option<i64> {
 	#[cfg(not(target_os = "windows"))]
 	return show_elements_dialog_dv(parent, session, current_pos);
@@ -13,44 +22,102 @@ pub fn show_elements_dialog(parent: &Frame, session: &DocumentSession, current_p
 	return show_elements_dialog_wx(parent, session, current_pos);
 }
 
+/// Everything the dialog lists, gathered once up front so the level filter can rebuild the headings
+/// tree without going back to the session.
+struct ElementsData {
+	headings: Rc<HeadingTree>,
+	links: FlatItems,
+	structure: FlatItems,
+	figures: FlatItems,
+}
+
+struct FlatItems {
+	labels: Vec<String>,
+	offsets: Vec<i64>,
+	closest_index: i32,
+}
+
+impl ElementsData {
+	fn load(session: &DocumentSession, current_pos: i64) -> Self {
+		let link_data = session.link_list(current_pos);
+		let links = FlatItems {
+			offsets: link_data.items.iter().map(|item| i64::try_from(item.offset).unwrap_or(i64::MAX)).collect(),
+			labels: link_data.items.into_iter().map(|item| item.text).collect(),
+			closest_index: link_data.closest_index,
+		};
+		let structure_data = session.structure_list(current_pos);
+		let structure = FlatItems {
+			offsets: structure_data.items.iter().map(|item| i64::try_from(item.offset).unwrap_or(i64::MAX)).collect(),
+			labels: structure_data.items.iter().map(|item| structure_label(item.kind, &item.text)).collect(),
+			closest_index: structure_data.closest_index,
+		};
+		let figure_data = session.figure_list(current_pos);
+		let figures = FlatItems {
+			offsets: figure_data.items.iter().map(|item| i64::try_from(item.offset).unwrap_or(i64::MAX)).collect(),
+			labels: figure_data.items.iter().map(|item| structure_label(item.kind, &item.text)).collect(),
+			closest_index: figure_data.closest_index,
+		};
+		Self { headings: Rc::new(session.heading_tree(current_pos)), links, structure, figures }
+	}
+}
+
 // ── DataViewTreeCtrl implementation (Linux + macOS) ───────────────────────────
 
 #[cfg(not(target_os = "windows"))]
 struct ElementsDialogUiDv {
 	content_sizer: BoxSizer,
 	view_choice: Choice,
+	level_label: StaticText,
+	level_choice: Choice,
 	headings_tree: DataViewTreeCtrl,
 	links_list: ListBox,
+	structure_list: ListBox,
+	figures_list: ListBox,
 }
 
+#[cfg(not(target_os = "windows"))]
+type HeadingOffsets = Rc<RefCell<HashMap<usize, i64>>>;
+
 #[cfg(not(target_os = "windows"))]
 fn show_elements_dialog_dv(parent: &Frame, session: &DocumentSession, current_pos: i64) -> Option<i64> {
 	// TRANSLATORS: Title of the Elements dialog
 	let dialog = Dialog::builder(parent, &t("Elements")).build();
-	let ElementsDialogUiDv { content_sizer, view_choice, headings_tree, links_list } =
-		build_elements_dialog_ui_dv(dialog);
-	let (selected_offset, item_offsets, link_offsets) =
-		populate_elements_dialog_dv(session, current_pos, headings_tree, links_list);
-	let item_offsets = Rc::new(item_offsets);
-	bind_elements_view_toggle_dv(view_choice, headings_tree, links_list, dialog);
-	bind_elements_activation_dv(dialog, headings_tree, links_list, &item_offsets, &link_offsets, &selected_offset);
+	let data = ElementsData::load(session, current_pos);
+	let ui = build_elements_dialog_ui_dv(dialog, &data);
+	let selected_offset = Rc::new(Cell::new(-1i64));
+	let item_offsets: HeadingOffsets = Rc::new(RefCell::new(HashMap::new()));
+	fill_headings_tree_dv(ui.headings_tree, &data.headings, &item_offsets);
+	let link_offsets = populate_flat_list(ui.links_list, &data.links);
+	let structure_offsets = populate_flat_list(ui.structure_list, &data.structure);
+	let figure_offsets = populate_flat_list(ui.figures_list, &data.figures);
+	bind_elements_view_toggle_dv(&ui, dialog);
+	bind_level_filter_dv(ui.level_choice, ui.headings_tree, &data.headings, &item_offsets);
+	bind_elements_activation_dv(dialog, ui.headings_tree, &item_offsets, &selected_offset);
+	bind_flat_list_activation(dialog, ui.links_list, &link_offsets, &selected_offset);
+	bind_flat_list_activation(dialog, ui.structure_list, &structure_offsets, &selected_offset);
+	bind_flat_list_activation(dialog, ui.figures_list, &figure_offsets, &selected_offset);
 	let (ok_button, cancel_button) = build_elements_buttons(dialog);
-	bind_elements_ok_action_dv(
-		dialog,
-		view_choice,
-		headings_tree,
-		links_list,
-		&item_offsets,
-		&link_offsets,
-		&selected_offset,
-		ok_button,
-	);
-	finalize_elements_layout(dialog, content_sizer, ok_button, cancel_button);
-	if view_choice.get_selection().unwrap_or(0) == 0 {
-		headings_tree.set_focus();
-	} else {
-		links_list.set_focus();
-	}
+	let view_choice = ui.view_choice;
+	let headings_tree = ui.headings_tree;
+	let links_list = ui.links_list;
+	let structure_list = ui.structure_list;
+	let figures_list = ui.figures_list;
+	let offsets_for_ok = Rc::clone(&item_offsets);
+	let selected_for_ok = Rc::clone(&selected_offset);
+	ok_button.on_click(move |_| {
+		let offset = match view_choice.get_selection().unwrap_or(VIEW_HEADINGS) {
+			VIEW_LINKS => flat_list_selected_offset(links_list, &link_offsets),
+			VIEW_STRUCTURE => flat_list_selected_offset(structure_list, &structure_offsets),
+			VIEW_FIGURES => flat_list_selected_offset(figures_list, &figure_offsets),
+			_ => headings_tree.get_selection().and_then(|item| heading_offset_dv(&item, &offsets_for_ok)),
+		};
+		if let Some(offset) = offset {
+			selected_for_ok.set(offset);
+			dialog.end_modal(wxdragon::id::ID_OK);
+		}
+	});
+	finalize_elements_layout(dialog, ui.content_sizer, ok_button, cancel_button);
+	headings_tree.set_focus();
 	if dialog.show_modal() == wxdragon::id::ID_OK {
 		let offset = selected_offset.get();
 		if offset >= 0 { Some(offset) } else { None }
@@ -60,23 +127,10 @@ fn show_elements_dialog_dv(parent: &Frame, session: &DocumentSession, current_po
 }
 
 #[cfg(not(target_os = "windows"))]
-fn build_elements_dialog_ui_dv(dialog: Dialog) -> ElementsDialogUiDv {
+fn build_elements_dialog_ui_dv(dialog: Dialog, data: &ElementsData) -> ElementsDialogUiDv {
 	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
-	let choice_sizer = BoxSizer::builder(Orientation::Horizontal).build();
-	// TRANSLATORS: Label for the view selection dropdown in the Elements dialog
-	let choice_label_text = t("&View:");
-	let choice_label = StaticText::builder(&dialog).with_label(&choice_label_text).build();
-	let view_choice = Choice::builder(&dialog).build();
-	// TRANSLATORS: Choice option in the view dropdown to show headings list
-	view_choice.append(&t("Headings"));
-	// TRANSLATORS: Choice option in the view dropdown to show links list
-	view_choice.append(&t("Links"));
-	view_choice.set_selection(0);
-	#[cfg(target_os = "macos")]
-	view_choice.set_accessibility_label(choice_label_text.replace('&', "").trim_end_matches(':').trim());
-	choice_sizer.add(&choice_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, super::DIALOG_PADDING);
-	choice_sizer.add(&view_choice, 1, SizerFlag::Expand, 0);
-	content_sizer.add_sizer(&choice_sizer, 0, SizerFlag::Expand | SizerFlag::All, super::DIALOG_PADDING);
+	let view_choice = build_view_choice(dialog, content_sizer, data);
+	let (level_label, level_choice) = build_level_choice(dialog, content_sizer);
 	let headings_tree = DataViewTreeCtrl::builder(&dialog).with_size(Size::new(400, 500)).build();
 	content_sizer.add(
 		&headings_tree,
@@ -92,19 +146,39 @@ fn build_elements_dialog_ui_dv(dialog: Dialog) -> ElementsDialogUiDv {
 		super::DIALOG_PADDING,
 	);
 	links_list.show(false);
-	ElementsDialogUiDv { content_sizer, view_choice, headings_tree, links_list }
+	let structure_list = ListBox::builder(&dialog).build();
+	content_sizer.add(
+		&structure_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		super::DIALOG_PADDING,
+	);
+	structure_list.show(false);
+	let figures_list = ListBox::builder(&dialog).build();
+	content_sizer.add(
+		&figures_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		super::DIALOG_PADDING,
+	);
+	figures_list.show(false);
+	ElementsDialogUiDv {
+		content_sizer,
+		view_choice,
+		level_label,
+		level_choice,
+		headings_tree,
+		links_list,
+		structure_list,
+		figures_list,
+	}
 }
 
 #[cfg(not(target_os = "windows"))]
-fn populate_elements_dialog_dv(
-	session: &DocumentSession,
-	current_pos: i64,
-	headings_tree: DataViewTreeCtrl,
-	links_list: ListBox,
-) -> (Rc<Cell<i64>>, HashMap<usize, i64>, Rc<Vec<i64>>) {
-	let selected_offset = Rc::new(Cell::new(-1i64));
-	let mut item_offsets: HashMap<usize, i64> = HashMap::new();
-	let tree_data = session.heading_tree(current_pos);
+fn fill_headings_tree_dv(headings_tree: DataViewTreeCtrl, tree_data: &HeadingTree, item_offsets: &HeadingOffsets) {
+	headings_tree.delete_all_items();
+	let mut item_offsets = item_offsets.borrow_mut();
+	item_offsets.clear();
 	// Precompute which items have children so we can use append_container vs append_item.
 	let has_children_vec: Vec<bool> = (0..tree_data.items.len())
 		.map(|i| {
@@ -119,8 +193,7 @@ fn populate_elements_dialog_dv(
 		} else {
 			&root
 		};
-		// TRANSLATORS: Placeholder text shown in the elements list when a document element has no text content
-		let display_text = if item.text.is_empty() { t("Untitled") } else { item.text.clone() };
+		let display_text = heading_label(item);
 		let offset = i64::try_from(item.offset).unwrap_or(i64::MAX);
 		let node = if has_children_vec[current_idx] {
 			headings_tree.append_container(parent, &display_text, -1, -1)
@@ -145,114 +218,72 @@ fn populate_elements_dialog_dv(
 			headings_tree.ensure_visible(item);
 		}
 	}
-	let link_data = session.link_list(current_pos);
-	let mut link_offsets = Vec::new();
-	for item in link_data.items {
-		links_list.append(&item.text);
-		link_offsets.push(i64::try_from(item.offset).unwrap_or(i64::MAX));
-	}
-	if !link_offsets.is_empty() {
-		let idx = if link_data.closest_index >= 0 { link_data.closest_index } else { 0 };
-		if let Ok(idx_u32) = u32::try_from(idx) {
-			links_list.set_selection(idx_u32, true);
-		}
-	}
-	(selected_offset, item_offsets, Rc::new(link_offsets))
 }
 
 #[cfg(not(target_os = "windows"))]
-fn bind_elements_view_toggle_dv(
-	view_choice: Choice,
-	headings_tree: DataViewTreeCtrl,
-	links_list: ListBox,
-	dialog: Dialog,
-) {
+fn heading_offset_dv(item: &DataViewItem, item_offsets: &HeadingOffsets) -> Option<i64> {
+	let id_ptr = item.get_id::<c_void>()?;
+	item_offsets.borrow().get(&(id_ptr as usize)).copied()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn bind_elements_view_toggle_dv(ui: &ElementsDialogUiDv, dialog: Dialog) {
+	let view_choice = ui.view_choice;
+	let level_label = ui.level_label;
+	let level_choice = ui.level_choice;
+	let headings_tree = ui.headings_tree;
+	let links_list = ui.links_list;
+	let structure_list = ui.structure_list;
+	let figures_list = ui.figures_list;
 	view_choice.on_selection_changed(move |_| {
-		let selection = view_choice.get_selection().unwrap_or(0);
-		if selection == 0 {
-			headings_tree.show(true);
-			links_list.show(false);
-			headings_tree.set_focus();
-		} else {
-			headings_tree.show(false);
-			links_list.show(true);
-			links_list.set_focus();
+		let selection = view_choice.get_selection().unwrap_or(VIEW_HEADINGS);
+		let show_headings = selection == VIEW_HEADINGS;
+		level_label.show(show_headings);
+		level_choice.show(show_headings);
+		headings_tree.show(show_headings);
+		links_list.show(selection == VIEW_LINKS);
+		structure_list.show(selection == VIEW_STRUCTURE);
+		figures_list.show(selection == VIEW_FIGURES);
+		match selection {
+			VIEW_LINKS => links_list.set_focus(),
+			VIEW_STRUCTURE => structure_list.set_focus(),
+			VIEW_FIGURES => figures_list.set_focus(),
+			_ => headings_tree.set_focus(),
 		}
 		dialog.layout();
 	});
 }
 
 #[cfg(not(target_os = "windows"))]
-fn bind_elements_activation_dv(
-	dialog: Dialog,
+fn bind_level_filter_dv(
+	level_choice: Choice,
 	headings_tree: DataViewTreeCtrl,
-	links_list: ListBox,
-	item_offsets: &Rc<HashMap<usize, i64>>,
-	link_offsets: &Rc<Vec<i64>>,
-	selected_offset: &Rc<Cell<i64>>,
+	headings: &Rc<HeadingTree>,
+	item_offsets: &HeadingOffsets,
 ) {
-	let offsets_for_tree = Rc::clone(item_offsets);
-	let selected_for_tree = Rc::clone(selected_offset);
-	let dialog_for_tree = dialog;
-	headings_tree.on_item_activated(move |event| {
-		if let Some(item) = event.get_item() {
-			if let Some(id_ptr) = item.get_id::<c_void>() {
-				if let Some(&offset) = offsets_for_tree.get(&(id_ptr as usize)) {
-					selected_for_tree.set(offset);
-					dialog_for_tree.end_modal(wxdragon::id::ID_OK);
-				}
-			}
-		}
-	});
-	let offsets_for_list = Rc::clone(link_offsets);
-	let selected_for_list = Rc::clone(selected_offset);
-	let dialog_for_list = dialog;
-	links_list.on_item_double_clicked(move |event| {
-		let selection = event.get_selection().unwrap_or(-1);
-		if selection >= 0 {
-			if let Ok(index) = usize::try_from(selection) {
-				if let Some(offset) = offsets_for_list.get(index) {
-					selected_for_list.set(*offset);
-					dialog_for_list.end_modal(wxdragon::id::ID_OK);
-				}
-			}
-		}
+	let headings = Rc::clone(headings);
+	let item_offsets = Rc::clone(item_offsets);
+	level_choice.on_selection_changed(move |_| {
+		let filtered = filter_headings(&headings, level_choice.get_selection().unwrap_or(0));
+		fill_headings_tree_dv(headings_tree, &filtered, &item_offsets);
 	});
 }
 
 #[cfg(not(target_os = "windows"))]
-fn bind_elements_ok_action_dv(
+fn bind_elements_activation_dv(
 	dialog: Dialog,
-	view_choice: Choice,
 	headings_tree: DataViewTreeCtrl,
-	links_list: ListBox,
-	item_offsets: &Rc<HashMap<usize, i64>>,
-	link_offsets: &Rc<Vec<i64>>,
+	item_offsets: &HeadingOffsets,
 	selected_offset: &Rc<Cell<i64>>,
-	ok_button: Button,
 ) {
-	let offsets_for_ok = Rc::clone(item_offsets);
-	let link_offsets_for_ok = Rc::clone(link_offsets);
-	let selected_for_ok = Rc::clone(selected_offset);
-	let dialog_for_ok = dialog;
-	ok_button.on_click(move |_| {
-		let selection = view_choice.get_selection().unwrap_or(0);
-		if selection == 0 {
-			if let Some(item) = headings_tree.get_selection() {
-				if let Some(id_ptr) = item.get_id::<c_void>() {
-					if let Some(&offset) = offsets_for_ok.get(&(id_ptr as usize)) {
-						selected_for_ok.set(offset);
-						dialog_for_ok.end_modal(wxdragon::id::ID_OK);
-					}
-				}
-			}
-		} else if let Some(idx) = links_list.get_selection() {
-			if let Ok(index) = usize::try_from(idx) {
-				if let Some(offset) = link_offsets_for_ok.get(index) {
-					selected_for_ok.set(*offset);
-					dialog_for_ok.end_modal(wxdragon::id::ID_OK);
-				}
-			}
+	let offsets_for_tree = Rc::clone(item_offsets);
+	let selected_for_tree = Rc::clone(selected_offset);
+	headings_tree.on_item_activated(move |event| {
+		if let Some(item) = event.get_item()
+			&& let Some(offset) = heading_offset_dv(&item, &offsets_for_tree)
+		{
+			selected_for_tree.set(offset);
+			dialog.end_modal(wxdragon::id::ID_OK);
 		}
 	});
 }
@@ -263,25 +294,51 @@ fn bind_elements_ok_action_dv(
 struct ElementsDialogUi {
 	content_sizer: BoxSizer,
 	view_choice: Choice,
+	level_label: StaticText,
+	level_choice: Choice,
 	headings_tree: TreeCtrl,
 	links_list: ListBox,
+	structure_list: ListBox,
+	figures_list: ListBox,
 }
 
 #[cfg(target_os = "windows")]
 fn show_elements_dialog_wx(parent: &Frame, session: &DocumentSession, current_pos: i64) -> Option<i64> {
 	let dialog = Dialog::builder(parent, &t("Elements")).build();
-	let ElementsDialogUi { content_sizer, view_choice, headings_tree, links_list } = build_elements_dialog_ui(dialog);
-	let (selected_offset, link_offsets) = populate_elements_dialog(session, current_pos, headings_tree, links_list);
-	bind_elements_view_toggle(view_choice, headings_tree, links_list, dialog);
-	bind_elements_activation(dialog, headings_tree, links_list, &selected_offset, &link_offsets);
+	let data = ElementsData::load(session, current_pos);
+	let ui = build_elements_dialog_ui(dialog, &data);
+	let selected_offset = Rc::new(Cell::new(-1i64));
+	fill_headings_tree(ui.headings_tree, &data.headings);
+	let link_offsets = populate_flat_list(ui.links_list, &data.links);
+	let structure_offsets = populate_flat_list(ui.structure_list, &data.structure);
+	let figure_offsets = populate_flat_list(ui.figures_list, &data.figures);
+	bind_elements_view_toggle(&ui, dialog);
+	bind_level_filter(ui.level_choice, ui.headings_tree, &data.headings);
+	bind_elements_activation(dialog, ui.headings_tree, &selected_offset);
+	bind_flat_list_activation(dialog, ui.links_list, &link_offsets, &selected_offset);
+	bind_flat_list_activation(dialog, ui.structure_list, &structure_offsets, &selected_offset);
+	bind_flat_list_activation(dialog, ui.figures_list, &figure_offsets, &selected_offset);
 	let (ok_button, cancel_button) = build_elements_buttons(dialog);
-	bind_elements_ok_action(dialog, view_choice, headings_tree, links_list, &link_offsets, &selected_offset, ok_button);
-	finalize_elements_layout(dialog, content_sizer, ok_button, cancel_button);
-	if view_choice.get_selection().unwrap_or(0) == 0 {
-		headings_tree.set_focus();
-	} else {
-		links_list.set_focus();
-	}
+	let view_choice = ui.view_choice;
+	let headings_tree = ui.headings_tree;
+	let links_list = ui.links_list;
+	let structure_list = ui.structure_list;
+	let figures_list = ui.figures_list;
+	let selected_for_ok = Rc::clone(&selected_offset);
+	ok_button.on_click(move |_| {
+		let offset = match view_choice.get_selection().unwrap_or(VIEW_HEADINGS) {
+			VIEW_LINKS => flat_list_selected_offset(links_list, &link_offsets),
+			VIEW_STRUCTURE => flat_list_selected_offset(structure_list, &structure_offsets),
+			VIEW_FIGURES => flat_list_selected_offset(figures_list, &figure_offsets),
+			_ => headings_tree.get_selection().and_then(|item| heading_offset(headings_tree, &item)),
+		};
+		if let Some(offset) = offset {
+			selected_for_ok.set(offset);
+			dialog.end_modal(ID_OK);
+		}
+	});
+	finalize_elements_layout(dialog, ui.content_sizer, ok_button, cancel_button);
+	headings_tree.set_focus();
 	if dialog.show_modal() == ID_OK {
 		let offset = selected_offset.get();
 		if offset >= 0 { Some(offset) } else { None }
@@ -291,23 +348,10 @@ fn show_elements_dialog_wx(parent: &Frame, session: &DocumentSession, current_po
 }
 
 #[cfg(target_os = "windows")]
-fn build_elements_dialog_ui(dialog: Dialog) -> ElementsDialogUi {
+fn build_elements_dialog_ui(dialog: Dialog, data: &ElementsData) -> ElementsDialogUi {
 	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
-	let choice_sizer = BoxSizer::builder(Orientation::Horizontal).build();
-	// TRANSLATORS: Label for the view selection dropdown in the Elements dialog
-	let choice_label_text = t("&View:");
-	let choice_label = StaticText::builder(&dialog).with_label(&choice_label_text).build();
-	let view_choice = Choice::builder(&dialog).build();
-	// TRANSLATORS: Choice option in the view dropdown to show headings list
-	view_choice.append(&t("Headings"));
-	// TRANSLATORS: Choice option in the view dropdown to show links list
-	view_choice.append(&t("Links"));
-	view_choice.set_selection(0);
-	#[cfg(target_os = "macos")]
-	view_choice.set_accessibility_label(choice_label_text.replace('&', "").trim_end_matches(':').trim());
-	choice_sizer.add(&choice_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, super::DIALOG_PADDING);
-	choice_sizer.add(&view_choice, 1, SizerFlag::Expand, 0);
-	content_sizer.add_sizer(&choice_sizer, 0, SizerFlag::Expand | SizerFlag::All, super::DIALOG_PADDING);
+	let view_choice = build_view_choice(dialog, content_sizer, data);
+	let (level_label, level_choice) = build_level_choice(dialog, content_sizer);
 	let headings_sizer = BoxSizer::builder(Orientation::Vertical).build();
 	let headings_tree = TreeCtrl::builder(&dialog)
 		.with_style(TreeCtrlStyle::Default | TreeCtrlStyle::HideRoot)
@@ -330,23 +374,43 @@ fn build_elements_dialog_ui(dialog: Dialog) -> ElementsDialogUi {
 		super::DIALOG_PADDING,
 	);
 	links_list.show(false);
-	ElementsDialogUi { content_sizer, view_choice, headings_tree, links_list }
+	let structure_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	let structure_list = ListBox::builder(&dialog).build();
+	structure_sizer.add(&structure_list, 1, SizerFlag::Expand, 0);
+	content_sizer.add_sizer(
+		&structure_sizer,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		super::DIALOG_PADDING,
+	);
+	structure_list.show(false);
+	let figures_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	let figures_list = ListBox::builder(&dialog).build();
+	figures_sizer.add(&figures_list, 1, SizerFlag::Expand, 0);
+	content_sizer.add_sizer(
+		&figures_sizer,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		super::DIALOG_PADDING,
+	);
+	figures_list.show(false);
+	ElementsDialogUi {
+		content_sizer,
+		view_choice,
+		level_label,
+		level_choice,
+		headings_tree,
+		links_list,
+		structure_list,
+		figures_list,
+	}
 }
 
 #[cfg(target_os = "windows")]
-fn populate_elements_dialog(
-	session: &DocumentSession,
-	current_pos: i64,
-	headings_tree: TreeCtrl,
-	links_list: ListBox,
-) -> (Rc<Cell<i64>>, Rc<Vec<i64>>) {
-	let selected_offset = Rc::new(Cell::new(-1i64));
+fn fill_headings_tree(headings_tree: TreeCtrl, tree_data: &HeadingTree) {
+	headings_tree.delete_all_items();
 	let root = headings_tree.add_root("Root", None, None).unwrap();
-	let tree_data = session.heading_tree(current_pos);
-	let mut item_ids: Vec<TreeItemId> = Vec::new();
-	if !tree_data.items.is_empty() {
-		item_ids.reserve(tree_data.items.len());
-	}
+	let mut item_ids: Vec<TreeItemId> = Vec::with_capacity(tree_data.items.len());
 	for item in &tree_data.items {
 		let parent_id = if item.parent_index >= 0 {
 			usize::try_from(item.parent_index)
@@ -356,7 +420,7 @@ fn populate_elements_dialog(
 		} else {
 			root.clone()
 		};
-		let display_text = if item.text.is_empty() { t("Untitled") } else { item.text.clone() };
+		let display_text = heading_label(item);
 		let offset = i64::try_from(item.offset).unwrap_or(i64::MAX);
 		if let Some(id) = headings_tree.append_item_with_data(&parent_id, &display_text, offset, None, None) {
 			item_ids.push(id);
@@ -376,111 +440,176 @@ fn populate_elements_dialog(
 		headings_tree.select_item(&first_child);
 		headings_tree.ensure_visible(&first_child);
 	}
-	let link_data = session.link_list(current_pos);
-	let mut link_offsets = Vec::new();
-	for item in link_data.items {
-		links_list.append(&item.text);
-		link_offsets.push(i64::try_from(item.offset).unwrap_or(i64::MAX));
-	}
-	if !link_offsets.is_empty() {
-		let idx = if link_data.closest_index >= 0 { link_data.closest_index } else { 0 };
-		if let Ok(idx_u32) = u32::try_from(idx) {
-			links_list.set_selection(idx_u32, true);
-		}
-	}
-	(selected_offset, Rc::new(link_offsets))
 }
 
 #[cfg(target_os = "windows")]
-fn bind_elements_view_toggle(view_choice: Choice, headings_tree: TreeCtrl, links_list: ListBox, dialog: Dialog) {
-	let headings_tree_for_choice = headings_tree;
-	let links_list_for_choice = links_list;
-	let dialog_for_layout = dialog;
+fn heading_offset(headings_tree: TreeCtrl, item: &TreeItemId) -> Option<i64> {
+	let data = headings_tree.get_custom_data(item)?;
+	data.downcast_ref::<i64>().copied()
+}
+
+#[cfg(target_os = "windows")]
+fn bind_elements_view_toggle(ui: &ElementsDialogUi, dialog: Dialog) {
+	let view_choice = ui.view_choice;
+	let level_label = ui.level_label;
+	let level_choice = ui.level_choice;
+	let headings_tree = ui.headings_tree;
+	let links_list = ui.links_list;
+	let structure_list = ui.structure_list;
+	let figures_list = ui.figures_list;
 	view_choice.on_selection_changed(move |_| {
-		let selection = view_choice.get_selection().unwrap_or(0);
-		if selection == 0 {
-			headings_tree_for_choice.show(true);
-			links_list_for_choice.show(false);
-			headings_tree_for_choice.set_focus();
-		} else {
-			headings_tree_for_choice.show(false);
-			links_list_for_choice.show(true);
-			links_list_for_choice.set_focus();
+		let selection = view_choice.get_selection().unwrap_or(VIEW_HEADINGS);
+		let show_headings = selection == VIEW_HEADINGS;
+		level_label.show(show_headings);
+		level_choice.show(show_headings);
+		headings_tree.show(show_headings);
+		links_list.show(selection == VIEW_LINKS);
+		structure_list.show(selection == VIEW_STRUCTURE);
+		figures_list.show(selection == VIEW_FIGURES);
+		match selection {
+			VIEW_LINKS => links_list.set_focus(),
+			VIEW_STRUCTURE => structure_list.set_focus(),
+			VIEW_FIGURES => figures_list.set_focus(),
+			_ => headings_tree.set_focus(),
 		}
-		dialog_for_layout.layout();
+		dialog.layout();
 	});
 }
 
 #[cfg(target_os = "windows")]
-fn bind_elements_activation(
-	dialog: Dialog,
-	headings_tree: TreeCtrl,
-	links_list: ListBox,
-	selected_offset: &Rc<Cell<i64>>,
-	link_offsets: &Rc<Vec<i64>>,
-) {
+fn bind_level_filter(level_choice: Choice, headings_tree: TreeCtrl, headings: &Rc<HeadingTree>) {
+	let headings = Rc::clone(headings);
+	level_choice.on_selection_changed(move |_| {
+		let filtered = filter_headings(&headings, level_choice.get_selection().unwrap_or(0));
+		fill_headings_tree(headings_tree, &filtered);
+	});
+}
+
+#[cfg(target_os = "windows")]
+fn bind_elements_activation(dialog: Dialog, headings_tree: TreeCtrl, selected_offset: &Rc<Cell<i64>>) {
 	let selected_offset_for_tree = Rc::clone(selected_offset);
-	let tree_for_activate = headings_tree;
-	let dialog_for_tree = dialog;
 	headings_tree.on_item_activated(move |event| {
 		if let Some(item) = event.get_item()
-			&& let Some(data) = tree_for_activate.get_custom_data(&item)
-			&& let Some(offset) = data.downcast_ref::<i64>()
+			&& let Some(offset) = heading_offset(headings_tree, &item)
 		{
-			selected_offset_for_tree.set(*offset);
-			dialog_for_tree.end_modal(ID_OK);
+			selected_offset_for_tree.set(offset);
+			dialog.end_modal(ID_OK);
 		}
 	});
-	let selected_offset_for_list = Rc::clone(selected_offset);
-	let offsets_for_list = Rc::clone(link_offsets);
-	let dialog_for_list = dialog;
-	links_list.on_item_double_clicked(move |event| {
+}
+
+// ── Shared helpers ─────────────────────────────────────────────────────────────
+
+fn build_view_choice(dialog: Dialog, content_sizer: BoxSizer, data: &ElementsData) -> Choice {
+	let choice_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	// TRANSLATORS: Label for the view selection dropdown in the Elements dialog
+	let choice_label_text = t("&View:");
+	let choice_label = StaticText::builder(&dialog).with_label(&choice_label_text).build();
+	let view_choice = Choice::builder(&dialog).build();
+	// TRANSLATORS: Choice option in the view dropdown to show headings list; {} is the number of headings
+	view_choice.append(&t("Headings ({})").replace("{}", &data.headings.items.len().to_string()));
+	// TRANSLATORS: Choice option in the view dropdown to show links list; {} is the number of links
+	view_choice.append(&t("Links ({})").replace("{}", &data.links.labels.len().to_string()));
+	// TRANSLATORS: Choice option in the view dropdown to show tables and lists; {} is the number of them
+	view_choice.append(&t("Tables & Lists ({})").replace("{}", &data.structure.labels.len().to_string()));
+	// TRANSLATORS: Choice option in the view dropdown to show figures and images; {} is the number of them
+	view_choice.append(&t("Figures ({})").replace("{}", &data.figures.labels.len().to_string()));
+	view_choice.set_selection(VIEW_HEADINGS);
+	#[cfg(target_os = "macos")]
+	view_choice.set_accessibility_label(choice_label_text.replace('&', "").trim_end_matches(':').trim());
+	choice_sizer.add(&choice_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, super::DIALOG_PADDING);
+	choice_sizer.add(&view_choice, 1, SizerFlag::Expand, 0);
+	content_sizer.add_sizer(&choice_sizer, 0, SizerFlag::Expand | SizerFlag::All, super::DIALOG_PADDING);
+	view_choice
+}
+
+fn build_level_choice(dialog: Dialog, content_sizer: BoxSizer) -> (StaticText, Choice) {
+	let level_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	// TRANSLATORS: Label for the heading level filter in the Elements dialog
+	let level_label_text = t("&Level:");
+	let level_label = StaticText::builder(&dialog).with_label(&level_label_text).build();
+	let level_choice = Choice::builder(&dialog).build();
+	// TRANSLATORS: Heading level filter option in the Elements dialog that shows headings of every level
+	level_choice.append(&t("All levels"));
+	for level in 1..=MAX_HEADING_LEVEL {
+		// TRANSLATORS: Heading level filter option in the Elements dialog; %d is the heading level (1-6)
+		level_choice.append(&t("Level %d").replacen("%d", &level.to_string(), 1));
+	}
+	level_choice.set_selection(0);
+	#[cfg(target_os = "macos")]
+	level_choice.set_accessibility_label(level_label_text.replace('&', "").trim_end_matches(':').trim());
+	level_sizer.add(&level_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, super::DIALOG_PADDING);
+	level_sizer.add(&level_choice, 1, SizerFlag::Expand, 0);
+	content_sizer.add_sizer(
+		&level_sizer,
+		0,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		super::DIALOG_PADDING,
+	);
+	(level_label, level_choice)
+}
+
+/// Applies the level filter selection: index 0 is "All levels", otherwise the heading level itself.
+fn filter_headings(headings: &HeadingTree, selection: u32) -> HeadingTree {
+	match i32::try_from(selection) {
+		Ok(level) if (1..=MAX_HEADING_LEVEL).contains(&level) => headings.filtered_by_level(level),
+		_ => headings.clone(),
+	}
+}
+
+fn heading_label(item: &HeadingTreeItem) -> String {
+	// TRANSLATORS: Placeholder text shown in the elements list when a document element has no text content
+	let text = if item.text.is_empty() { t("Untitled") } else { item.text.clone() };
+	format!("[H{}] {text}", item.level)
+}
+
+fn structure_label(kind: StructureKind, text: &str) -> String {
+	let label = match kind {
+		// TRANSLATORS: Prefix for a table in the Elements dialog's Tables & Lists view; {} is the table's first line
+		StructureKind::Table => t("Table: {}"),
+		// TRANSLATORS: Prefix for a list in the Elements dialog's Tables & Lists view; {} is the list's first line
+		StructureKind::List => t("List: {}"),
+		// TRANSLATORS: Prefix for an image in the Elements dialog's Figures view; {} is its description
+		StructureKind::Image => t("Image: {}"),
+		// TRANSLATORS: Prefix for a figure in the Elements dialog's Figures view; {} is its caption or description
+		StructureKind::Figure => t("Figure: {}"),
+	};
+	label.replace("{}", text.trim())
+}
+
+fn populate_flat_list(list: ListBox, items: &FlatItems) -> Rc<Vec<i64>> {
+	for label in &items.labels {
+		list.append(label);
+	}
+	if !items.offsets.is_empty() {
+		let idx = if items.closest_index >= 0 { items.closest_index } else { 0 };
+		if let Ok(idx_u32) = u32::try_from(idx) {
+			list.set_selection(idx_u32, true);
+		}
+	}
+	Rc::new(items.offsets.clone())
+}
+
+fn flat_list_selected_offset(list: ListBox, offsets: &[i64]) -> Option<i64> {
+	let index = usize::try_from(list.get_selection()?).ok()?;
+	offsets.get(index).copied()
+}
+
+fn bind_flat_list_activation(dialog: Dialog, list: ListBox, offsets: &Rc<Vec<i64>>, selected_offset: &Rc<Cell<i64>>) {
+	let offsets_for_list = Rc::clone(offsets);
+	let selected_for_list = Rc::clone(selected_offset);
+	list.on_item_double_clicked(move |event| {
 		let selection = event.get_selection().unwrap_or(-1);
 		if selection >= 0
 			&& let Ok(index) = usize::try_from(selection)
 			&& let Some(offset) = offsets_for_list.get(index)
 		{
-			selected_offset_for_list.set(*offset);
-			dialog_for_list.end_modal(ID_OK);
-		}
-	});
-}
-
-#[cfg(target_os = "windows")]
-fn bind_elements_ok_action(
-	dialog: Dialog,
-	view_choice: Choice,
-	headings_tree: TreeCtrl,
-	links_list: ListBox,
-	link_offsets: &Rc<Vec<i64>>,
-	selected_offset: &Rc<Cell<i64>>,
-	ok_button: Button,
-) {
-	let offsets_for_ok = Rc::clone(link_offsets);
-	let selected_offset_for_ok = Rc::clone(selected_offset);
-	let dialog_for_ok = dialog;
-	ok_button.on_click(move |_| {
-		let selection = view_choice.get_selection().unwrap_or(0);
-		if selection == 0 {
-			if let Some(item) = headings_tree.get_selection()
-				&& let Some(data) = headings_tree.get_custom_data(&item)
-				&& let Some(offset) = data.downcast_ref::<i64>()
-			{
-				selected_offset_for_ok.set(*offset);
-				dialog_for_ok.end_modal(ID_OK);
-			}
-		} else if let Some(idx) = links_list.get_selection()
-			&& let Ok(index) = usize::try_from(idx)
-			&& let Some(offset) = offsets_for_ok.get(index)
-		{
-			selected_offset_for_ok.set(*offset);
-			dialog_for_ok.end_modal(ID_OK);
+			selected_for_list.set(*offset);
+			dialog.end_modal(ID_OK);
 		}
 	});
 }
 
-// ── Shared helpers ─────────────────────────────────────────────────────────────
-
 fn build_elements_buttons(dialog: Dialog) -> (Button, Button) {
 	// TRANSLATORS: Label for the confirmation button
 	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
@@ -0,0 +1,65 @@
+use paperback_core::util::encoding::TEXT_ENCODINGS;
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// Asks which character encoding to read a text document with. `current` is the saved choice, empty
+/// when the encoding is detected. Returns the new choice, empty for automatic detection.
+pub fn show_encoding_dialog(parent: &dyn WxWidget, current: &str) -> Option<String> {
+	// TRANSLATORS: Title of the dialog for choosing the character encoding of a text document
+	let title = t("Encoding");
+	let dialog = Dialog::builder(parent, &title).build();
+	// TRANSLATORS: Label above the list of character encodings in the Encoding dialog
+	let label_text = t("Read this document as:");
+	let label = StaticText::builder(&dialog).with_label(&label_text).build();
+	let encoding_list = ListBox::builder(&dialog).build();
+	// TRANSLATORS: First entry in the Encoding dialog, letting Paperback guess the encoding
+	encoding_list.append(&t("Detect automatically"));
+	for encoding in TEXT_ENCODINGS {
+		encoding_list.append(encoding);
+	}
+	let selected =
+		TEXT_ENCODINGS.iter().position(|encoding| encoding.eq_ignore_ascii_case(current)).map_or(0, |i| i + 1);
+	encoding_list.set_selection(u32::try_from(selected).unwrap_or(0), true);
+	// TRANSLATORS: Label for the confirmation button
+	let ok_label = t("OK");
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&ok_label).build();
+	ok_button.set_default();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_label = t("Cancel");
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&cancel_label).build();
+	let dialog_for_ok = dialog;
+	ok_button.on_click(move |_| {
+		dialog_for_ok.end_modal(ID_OK);
+	});
+	let dialog_for_cancel = dialog;
+	cancel_button.on_click(move |_| {
+		dialog_for_cancel.end_modal(ID_CANCEL);
+	});
+	let dialog_for_double_click = dialog;
+	encoding_list.on_item_double_clicked(move |_| {
+		dialog_for_double_click.end_modal(ID_OK);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&label, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add(
+		&encoding_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	encoding_list.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let index = usize::try_from(encoding_list.get_selection()?).ok()?;
+	if index == 0 { Some(String::new()) } else { TEXT_ENCODINGS.get(index - 1).map(ToString::to_string) }
+}
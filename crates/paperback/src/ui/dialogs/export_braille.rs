@@ -0,0 +1,70 @@
+use paperback_core::reader_core::BrailleOptions;
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+const MIN_LINE_WIDTH: i32 = 10;
+const MAX_LINE_WIDTH: i32 = 200;
+
+/// Asks for the line width, heading prefix and line endings of a braille export, starting from `initial`.
+/// Returns `None` if the user cancelled.
+pub fn show_export_braille_dialog(parent: &Frame, initial: &BrailleOptions) -> Option<BrailleOptions> {
+	// TRANSLATORS: Title of the dialog shown before exporting a document for braille embossing
+	let dialog = Dialog::builder(parent, &t("Export for Braille")).build();
+	// TRANSLATORS: Label for the number of braille cells per line in the Export for Braille dialog
+	let width_label_text = t("&Cells per line:");
+	let width_label = StaticText::builder(&dialog).with_label(&width_label_text).build();
+	let width_ctrl = SpinCtrl::builder(&dialog).with_range(MIN_LINE_WIDTH, MAX_LINE_WIDTH).build();
+	width_ctrl
+		.set_value(i32::try_from(initial.line_width).unwrap_or(MAX_LINE_WIDTH).clamp(MIN_LINE_WIDTH, MAX_LINE_WIDTH));
+	#[cfg(target_os = "macos")]
+	width_ctrl.set_accessibility_label(width_label_text.replace('&', "").trim_end_matches(':').trim());
+	// TRANSLATORS: Label for the text put before headings below level 1 in the Export for Braille dialog
+	let prefix_label_text = t("&Prefix for lower headings:");
+	let prefix_label = StaticText::builder(&dialog).with_label(&prefix_label_text).build();
+	let prefix_ctrl = TextCtrl::builder(&dialog).with_size(Size::new(100, -1)).build();
+	prefix_ctrl.set_value(&initial.heading_prefix);
+	#[cfg(target_os = "macos")]
+	prefix_ctrl.set_accessibility_label(prefix_label_text.replace('&', "").trim_end_matches(':').trim());
+	// TRANSLATORS: Checkbox in the Export for Braille dialog to end lines with the operating system's line ending (CR LF on Windows) instead of a bare line feed
+	let newlines_check = CheckBox::builder(&dialog).with_label(&t("Use this system's line &endings")).build();
+	newlines_check.set_value(initial.platform_newlines);
+	let width_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	width_sizer.add(&width_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 5);
+	width_sizer.add(&width_ctrl, 1, SizerFlag::Expand, 0);
+	let prefix_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	prefix_sizer.add(&prefix_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 5);
+	prefix_sizer.add(&prefix_ctrl, 1, SizerFlag::Expand, 0);
+	// TRANSLATORS: Label for the confirmation button
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	ok_button.set_default();
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add_sizer(&width_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(
+		&prefix_sizer,
+		0,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	content_sizer.add(&newlines_check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	width_ctrl.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	Some(BrailleOptions {
+		line_width: usize::try_from(width_ctrl.value().clamp(MIN_LINE_WIDTH, MAX_LINE_WIDTH)).unwrap_or(38),
+		heading_prefix: prefix_ctrl.get_value(),
+		platform_newlines: newlines_check.is_checked(),
+	})
+}
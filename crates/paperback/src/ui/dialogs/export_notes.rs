@@ -0,0 +1,33 @@
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// Asks which bookmarks to include before exporting notes. Returns whether bookmarks without a note should be
+/// exported as bare quotes, or `None` if the user cancelled.
+pub fn show_export_notes_dialog(parent: &Frame) -> Option<bool> {
+	// TRANSLATORS: Title of the dialog shown before exporting bookmarks and notes to Markdown
+	let dialog = Dialog::builder(parent, &t("Export Notes")).build();
+	let include_check = CheckBox::builder(&dialog)
+		// TRANSLATORS: Checkbox in the Export Notes dialog; when checked, bookmarks that have no note are exported too
+		.with_label(&t("Include &bookmarks without notes"))
+		.build();
+	include_check.set_value(true);
+	// TRANSLATORS: Label for the confirmation button
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	ok_button.set_default();
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&include_check, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	include_check.set_focus();
+	if dialog.show_modal() == ID_OK { Some(include_check.is_checked()) } else { None }
+}
@@ -0,0 +1,47 @@
+use paperback_core::reader_core::OutlineOptions;
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// Asks what to add to each entry of an exported outline. Returns `initial` with the choices made, or
+/// `None` if the user cancelled. Whether it's Markdown is left to the file name picked afterwards.
+pub fn show_export_outline_dialog(parent: &Frame, initial: OutlineOptions) -> Option<OutlineOptions> {
+	// TRANSLATORS: Title of the dialog shown before exporting the document outline
+	let dialog = Dialog::builder(parent, &t("Export Outline")).build();
+	let percent_check = CheckBox::builder(&dialog)
+		// TRANSLATORS: Checkbox in the Export Outline dialog; when checked, each entry is followed by how far through the document it is
+		.with_label(&t("Include &percentage"))
+		.build();
+	percent_check.set_value(initial.include_percent);
+	let page_check = CheckBox::builder(&dialog)
+		// TRANSLATORS: Checkbox in the Export Outline dialog; when checked, each entry is followed by the page it's on
+		.with_label(&t("Include p&age numbers"))
+		.build();
+	page_check.set_value(initial.include_page);
+	// TRANSLATORS: Label for the confirmation button
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	ok_button.set_default();
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&percent_check, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add(&page_check, 0, SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	percent_check.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	Some(OutlineOptions {
+		include_percent: percent_check.is_checked(),
+		include_page: page_check.is_checked(),
+		..initial
+	})
+}
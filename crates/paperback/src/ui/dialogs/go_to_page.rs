@@ -3,8 +3,13 @@ use wxdragon::prelude::*;
 
 use super::DIALOG_PADDING;
 
-pub fn show_go_to_page_dialog(parent: &Frame, current_page: i32, max_page: i32) -> Option<i32> {
+/// Asks for a page and returns what was entered, a page number or, for a document that labels its
+/// pages (`labels` non-empty), a printed page label such as "xi" or a number.
+pub fn show_go_to_page_dialog(parent: &Frame, current_page: i32, max_page: i32, labels: &[String]) -> Option<String> {
 	let max_page = max_page.max(1);
+	if !labels.is_empty() {
+		return show_go_to_page_label_dialog(parent, current_page, max_page, labels);
+	}
 	// TRANSLATORS: Title of the Go to page dialog
 	let dialog_title = t("Go to page");
 	let dialog = Dialog::builder(parent, &dialog_title).build();
@@ -56,5 +61,54 @@ pub fn show_go_to_page_dialog(parent: &Frame, current_page: i32, max_page: i32)
 	dialog.set_sizer_and_fit(content_sizer, true);
 	dialog.centre();
 	page_ctrl.set_focus();
-	if dialog.show_modal() == ID_OK { Some(page_ctrl.value().clamp(1, max_page)) } else { None }
+	if dialog.show_modal() == ID_OK { Some(page_ctrl.value().clamp(1, max_page).to_string()) } else { None }
+}
+
+fn show_go_to_page_label_dialog(parent: &Frame, current_page: i32, max_page: i32, labels: &[String]) -> Option<String> {
+	// TRANSLATORS: Title of the Go to page dialog
+	let dialog = Dialog::builder(parent, &t("Go to page")).build();
+	let current = current_page.clamp(1, max_page);
+	let current_label = usize::try_from(current - 1)
+		.ok()
+		.and_then(|index| labels.get(index))
+		.filter(|label| !label.is_empty())
+		.cloned()
+		.unwrap_or_else(|| current.to_string());
+	// TRANSLATORS: Prompt of the Go to page dialog for a document with printed page numbers. %s is the current page's printed number (such as "xi"), the %d placeholders are its position in the file and the page count.
+	let label_template = t("Go to page (%s, %d of %d):");
+	let label_text = label_template.replacen("%s", &current_label, 1).replacen("%d", &current.to_string(), 1).replacen(
+		"%d",
+		&max_page.to_string(),
+		1,
+	);
+	let label = StaticText::builder(&dialog).with_label(&label_text).build();
+	let page_combo = ComboBox::builder(&dialog).with_style(ComboBoxStyle::ProcessEnter).build();
+	for page_label in labels.iter().filter(|page_label| !page_label.is_empty()) {
+		page_combo.append(page_label);
+	}
+	page_combo.set_value(&current_label);
+	page_combo.bind_internal(EventType::TEXT_ENTER, move |event| {
+		event.skip(false);
+		dialog.end_modal(ID_OK);
+	});
+	let page_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	page_sizer.add(&label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 5);
+	page_sizer.add(&page_combo, 1, SizerFlag::Expand, 0);
+	// TRANSLATORS: Label for the button that jumps to the entered position (a line, page, or percentage, depending on the dialog)
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("Go")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	dialog.set_affirmative_id(ID_OK);
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add_sizer(&page_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	page_combo.set_focus();
+	if dialog.show_modal() == ID_OK { Some(page_combo.get_value().trim().to_string()) } else { None }
 }
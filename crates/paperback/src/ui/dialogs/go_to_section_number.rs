@@ -0,0 +1,41 @@
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// Asks for a dotted section number such as "3.2.1". Returns the text as entered.
+pub fn show_go_to_section_number_dialog(parent: &Frame) -> Option<String> {
+	// TRANSLATORS: Title of the Go to Section Number dialog
+	let dialog_title = t("Go to Section Number");
+	let dialog = Dialog::builder(parent, &dialog_title).build();
+	// TRANSLATORS: Label for the input field where users enter a dotted section number such as 3.2.1
+	let label_text = t("&Section number:");
+	let label = StaticText::builder(&dialog).with_label(&label_text).build();
+	let number_ctrl = TextCtrl::builder(&dialog).with_size(Size::new(150, -1)).build();
+	#[cfg(target_os = "macos")]
+	number_ctrl.set_accessibility_label(label_text.replace('&', "").trim_end_matches(':').trim());
+	let number_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	number_sizer.add(&label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 5);
+	number_sizer.add(&number_ctrl, 1, SizerFlag::Expand, 0);
+	// TRANSLATORS: Label for the button that jumps to the entered position (a line, page, or percentage, depending on the dialog)
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("Go")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	ok_button.set_default();
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add_sizer(&number_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	number_ctrl.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let number = number_ctrl.get_value().trim().to_string();
+	(!number.is_empty()).then_some(number)
+}
@@ -0,0 +1,120 @@
+use paperback_core::{session::HistoryEntry, util::text::snippet_at};
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+const MAX_SNIPPET_CHARS: usize = 80;
+
+/// Lists the navigation history newest first, with the current entry preselected. Returns the
+/// index of the chosen entry in `entries` (oldest first). Entries past the end of the document are
+/// listed but cannot be jumped to.
+pub fn show_history_dialog(parent: &Frame, entries: &[HistoryEntry], current_index: usize) -> Option<usize> {
+	// TRANSLATORS: Title of the dialog listing the navigation history
+	let title = t("History");
+	let dialog = Dialog::builder(parent, &title).build();
+	// TRANSLATORS: Label for the list of navigation history entries
+	let list_label_text = t("&Positions, newest first:");
+	let list_label = StaticText::builder(&dialog).with_label(&list_label_text).build();
+	let history_list = ListBox::builder(&dialog).build();
+	#[cfg(target_os = "macos")]
+	history_list.set_accessibility_label(list_label_text.replace('&', "").trim_end_matches(':').trim());
+	// Row i of the list shows entries[order[i]].
+	let order: Vec<usize> = (0..entries.len()).rev().collect();
+	for &index in &order {
+		history_list.append(&entry_label(&entries[index], index == current_index));
+	}
+	let available: Vec<bool> = order.iter().map(|&index| entries[index].available).collect();
+	// TRANSLATORS: Label for the button that jumps to the selected history entry
+	let jump_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("&Jump")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	jump_button.set_default();
+	let selected_row_available =
+		move |row: i32| usize::try_from(row).ok().and_then(|row| available.get(row).copied()).unwrap_or(false);
+	if let Some(row) = order.iter().position(|&index| index == current_index)
+		&& let Ok(row) = u32::try_from(row)
+	{
+		history_list.set_selection(row, true);
+	}
+	jump_button.enable(selected_row_available(history_list.get_selection().unwrap_or(-1)));
+	let jump_button_for_selection = jump_button;
+	let available_for_selection = selected_row_available.clone();
+	history_list.on_selection_changed(move |event| {
+		jump_button_for_selection.enable(available_for_selection(event.get_selection().unwrap_or(-1)));
+	});
+	let dialog_for_jump = dialog;
+	let list_for_jump = history_list;
+	let available_for_jump = selected_row_available.clone();
+	jump_button.on_click(move |_| {
+		if available_for_jump(list_for_jump.get_selection().unwrap_or(-1)) {
+			dialog_for_jump.end_modal(ID_OK);
+		}
+	});
+	let dialog_for_cancel = dialog;
+	cancel_button.on_click(move |_| {
+		dialog_for_cancel.end_modal(ID_CANCEL);
+	});
+	let dialog_for_double_click = dialog;
+	let list_for_double_click = history_list;
+	history_list.on_item_double_clicked(move |_| {
+		if selected_row_available(list_for_double_click.get_selection().unwrap_or(-1)) {
+			dialog_for_double_click.end_modal(ID_OK);
+		}
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&list_label, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add(
+		&history_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&jump_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	history_list.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let row = usize::try_from(history_list.get_selection()?).ok()?;
+	order.get(row).copied().filter(|&index| entries[index].available)
+}
+
+fn entry_label(entry: &HistoryEntry, current: bool) -> String {
+	let mut label = if entry.available {
+		// TRANSLATORS: Percentage shown for a history entry; {} is the number
+		let mut parts = vec![t("{}%").replace("{}", &entry.percentage.to_string())];
+		if !entry.heading.is_empty() {
+			parts.push(entry.heading.clone());
+		}
+		if !entry.snippet.is_empty() {
+			parts.push(truncate_snippet(&entry.snippet));
+		}
+		parts.join(", ")
+	} else {
+		// TRANSLATORS: Shown in the history list for a position past the end of the document, e.g. after it was reloaded shorter
+		t("Unavailable (past the end of the document)")
+	};
+	if current {
+		// TRANSLATORS: Appended to the history entry the reader is currently at
+		label = format!("{label} {}", t("(current)"));
+	}
+	label
+}
+
+fn truncate_snippet(text: &str) -> String {
+	let Some((cut, _)) = text.char_indices().nth(MAX_SNIPPET_CHARS) else {
+		return text.to_string();
+	};
+	let head = snippet_at(text, 0, cut);
+	if head.len() == text.len() {
+		return text.to_string();
+	}
+	format!("{}\u{2026}", head.trim_end())
+}
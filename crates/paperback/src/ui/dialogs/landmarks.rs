@@ -0,0 +1,105 @@
+use paperback_core::document::{Landmark, LandmarkKind};
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+fn kind_label(kind: LandmarkKind) -> String {
+	match kind {
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a book's cover
+		LandmarkKind::Cover => t("Cover"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a book's title page
+		LandmarkKind::TitlePage => t("Title page"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a table of contents printed in the book
+		LandmarkKind::Toc => t("Table of contents"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a foreword
+		LandmarkKind::Foreword => t("Foreword"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a preface
+		LandmarkKind::Preface => t("Preface"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for an introduction
+		LandmarkKind::Introduction => t("Introduction"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for where the main text of a book begins
+		LandmarkKind::BodyMatter => t("Start of content"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for an appendix
+		LandmarkKind::Appendix => t("Appendix"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for the acknowledgments
+		LandmarkKind::Acknowledgments => t("Acknowledgments"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for the notes collected at the end of a book or chapter
+		LandmarkKind::Endnotes => t("Notes"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a glossary
+		LandmarkKind::Glossary => t("Glossary"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a bibliography or list of references
+		LandmarkKind::Bibliography => t("Bibliography"),
+		// TRANSLATORS: Landmark type in the Landmarks dialog for a book's index
+		LandmarkKind::Index => t("Index"),
+	}
+}
+
+fn landmark_label(landmark: &Landmark) -> String {
+	let kind = kind_label(landmark.kind);
+	if landmark.title.is_empty() || landmark.title.eq_ignore_ascii_case(&kind) {
+		kind
+	} else {
+		// TRANSLATORS: A row of the Landmarks dialog; the first {} is the landmark type (e.g. "Bibliography"), the second the title the book gives it
+		t("{}: {}").replacen("{}", &kind, 1).replacen("{}", &landmark.title, 1)
+	}
+}
+
+/// Lists the document's landmarks in reading order, with the one at or before the caret
+/// preselected. Returns the index of the chosen landmark in `landmarks`.
+pub fn show_landmarks_dialog(parent: &Frame, landmarks: &[Landmark], position: usize) -> Option<usize> {
+	// TRANSLATORS: Title of the dialog listing a book's landmarks, such as the preface, bibliography and index
+	let title = t("Landmarks");
+	let dialog = Dialog::builder(parent, &title).build();
+	// TRANSLATORS: Label for the list of landmarks
+	let list_label_text = t("&Landmarks:");
+	let list_label = StaticText::builder(&dialog).with_label(&list_label_text).build();
+	let landmark_list = ListBox::builder(&dialog).build();
+	#[cfg(target_os = "macos")]
+	landmark_list.set_accessibility_label(list_label_text.replace('&', "").trim_end_matches(':').trim());
+	for landmark in landmarks {
+		landmark_list.append(&landmark_label(landmark));
+	}
+	let current = landmarks.iter().rposition(|landmark| landmark.offset <= position).unwrap_or(0);
+	if let Ok(row) = u32::try_from(current) {
+		landmark_list.set_selection(row, true);
+	}
+	// TRANSLATORS: Label for the button that jumps to the selected landmark
+	let jump_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("&Jump")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	jump_button.set_default();
+	let dialog_for_jump = dialog;
+	jump_button.on_click(move |_| {
+		dialog_for_jump.end_modal(ID_OK);
+	});
+	let dialog_for_cancel = dialog;
+	cancel_button.on_click(move |_| {
+		dialog_for_cancel.end_modal(ID_CANCEL);
+	});
+	let dialog_for_double_click = dialog;
+	landmark_list.on_item_double_clicked(move |_| {
+		dialog_for_double_click.end_modal(ID_OK);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&list_label, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add(
+		&landmark_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&jump_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	landmark_list.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	usize::try_from(landmark_list.get_selection()?).ok().filter(|&index| index < landmarks.len())
+}
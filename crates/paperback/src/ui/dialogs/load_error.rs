@@ -0,0 +1,44 @@
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// Error dialog for a document that failed to open. Shows a short explanation; the Details button
+/// reveals the file path and underlying cause in a read-only field that can be copied into a bug report.
+pub fn show_load_error_dialog(parent: &dyn WxWidget, summary: &str, details: &str) {
+	// TRANSLATORS: Title of the dialog shown when a document fails to open
+	let dialog = Dialog::builder(parent, &t("Error")).build();
+	let summary_label = StaticText::builder(&dialog).with_label(summary).build();
+	let details_ctrl = TextCtrl::builder(&dialog)
+		.with_value(details)
+		.with_style(TextCtrlStyle::MultiLine | TextCtrlStyle::ReadOnly)
+		.with_size(Size::new(450, 120))
+		.build();
+	details_ctrl.show(false);
+	// TRANSLATORS: Button in the document load error dialog that reveals the file path and technical error details
+	let details_button = Button::builder(&dialog).with_label(&t("&Details...")).build();
+	// TRANSLATORS: Label for the confirmation button
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
+	dialog.set_affirmative_id(ID_OK);
+	dialog.set_escape_id(ID_OK);
+	ok_button.set_default();
+	let dialog_for_details = dialog;
+	details_button.on_click(move |_| {
+		details_ctrl.show(true);
+		dialog_for_details.layout();
+		dialog_for_details.fit();
+		details_ctrl.set_focus();
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&summary_label, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add(&details_ctrl, 1, SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add(&details_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	ok_button.set_focus();
+	dialog.show_modal();
+}
@@ -3,19 +3,60 @@ use wxdragon::prelude::*;
 
 use super::{DIALOG_PADDING, KEY_RETURN};
 
+/// A bookmark's note and category as entered in [`show_note_entry_dialog`].
+pub struct BookmarkNoteEntry {
+	pub note: String,
+	/// Empty when the bookmark has no category.
+	pub category: String,
+}
+
+/// Categories offered for every document, before any the user has typed.
+fn default_bookmark_categories() -> Vec<String> {
+	vec![
+		// TRANSLATORS: Built-in bookmark category for quotations
+		t("Quote"),
+		// TRANSLATORS: Built-in bookmark category for open questions
+		t("Question"),
+		// TRANSLATORS: Built-in bookmark category for things to follow up on
+		t("To-do"),
+	]
+}
+
+/// Edits a bookmark's note and category. The category box lists the built-in categories plus
+/// `categories` already used in the document; any other text typed into it becomes a new category.
 pub fn show_note_entry_dialog(
 	parent: &dyn WxWidget,
 	title: &str,
 	message: &str,
-	existing_note: &str,
-) -> Option<String> {
+	existing: &BookmarkNoteEntry,
+	categories: &[String],
+) -> Option<BookmarkNoteEntry> {
 	let dialog = Dialog::builder(parent, title).build();
 	let message_label = StaticText::builder(&dialog).with_label(message).build();
 	let note_ctrl = TextCtrl::builder(&dialog)
-		.with_value(existing_note)
+		.with_value(&existing.note)
 		.with_style(TextCtrlStyle::MultiLine)
 		.with_size(Size::new(400, 200))
 		.build();
+	// TRANSLATORS: Label for the category box in the bookmark note dialog
+	let category_label_text = t("&Category:");
+	let category_label = StaticText::builder(&dialog).with_label(&category_label_text).build();
+	let category_combo = ComboBox::builder(&dialog).build();
+	// TRANSLATORS: Category box choice for a bookmark without a category
+	let no_category = t("None");
+	category_combo.append(&no_category);
+	let mut choices = default_bookmark_categories();
+	for name in categories {
+		if !choices.iter().any(|choice| choice.eq_ignore_ascii_case(name)) {
+			choices.push(name.clone());
+		}
+	}
+	for choice in &choices {
+		category_combo.append(choice);
+	}
+	category_combo.set_value(if existing.category.is_empty() { &no_category } else { &existing.category });
+	#[cfg(target_os = "macos")]
+	category_combo.set_accessibility_label(category_label_text.replace('&', "").trim_end_matches(':').trim());
 	// TRANSLATORS: Label for the confirmation button
 	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
 	// TRANSLATORS: Label for the cancellation button
@@ -53,6 +94,15 @@ pub fn show_note_entry_dialog(
 		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
 		DIALOG_PADDING,
 	);
+	let category_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	category_sizer.add(&category_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	category_sizer.add(&category_combo, 1, SizerFlag::Expand, 0);
+	content_sizer.add_sizer(
+		&category_sizer,
+		0,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
 	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	button_sizer.add_stretch_spacer(1);
 	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
@@ -61,5 +111,10 @@ pub fn show_note_entry_dialog(
 	dialog.set_sizer_and_fit(content_sizer, true);
 	dialog.centre();
 	note_ctrl.set_focus();
-	if dialog.show_modal() == ID_OK { Some(note_ctrl.get_value()) } else { None }
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let category = category_combo.get_value().trim().to_string();
+	let category = if category == no_category { String::new() } else { category };
+	Some(BookmarkNoteEntry { note: note_ctrl.get_value(), category })
 }
@@ -1,34 +1,95 @@
-use std::path::Path;
+use std::{path::Path, rc::Rc};
 
+use paperback_core::{
+	parser::{ParserInfo, get_available_parsers},
+	util::encoding::TEXT_ENCODINGS,
+};
 use patois::t;
 use wxdragon::prelude::*;
 
 use super::DIALOG_PADDING;
 
-pub fn show_open_as_dialog(parent: &Frame, path: &Path) -> Option<String> {
+/// The format a file of unknown type is forced to, and the encoding it is read as (empty to detect
+/// it).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpenAsChoice {
+	pub format: String,
+	pub encoding: String,
+}
+
+/// Asks which parser to force for `path`. When a previous choice failed to open the file, `error`
+/// says why and that choice is selected again.
+pub fn show_open_as_dialog(
+	parent: &dyn WxWidget,
+	path: &Path,
+	previous: Option<&OpenAsChoice>,
+	error: Option<&str>,
+) -> Option<OpenAsChoice> {
+	let parsers: Rc<Vec<ParserInfo>> =
+		Rc::new(get_available_parsers().into_iter().filter(|parser| !parser.extensions.is_empty()).collect());
 	// TRANSLATORS: Title of the Open As dialog
 	let title = t("Open As");
 	let dialog = Dialog::builder(parent, &title).build();
-	// TRANSLATORS: Prompt template informing the user that no parser was found for their file. The {} placeholder is replaced with the file path.
-	let message_template = t("No suitable parser was found for {}.\nHow would you like to open this file?");
-	let message = message_template.replace("{}", &path.display().to_string());
+	let message = if let Some(error) = error {
+		// TRANSLATORS: Shown in the Open As dialog when the format picked earlier couldn't open the file. The first {} is the file path, the second the reason it failed.
+		let template = t("{} couldn't be opened in the chosen format:\n{}\nWould you like to try another format?");
+		template.replacen("{}", &path.display().to_string(), 1).replacen("{}", error, 1)
+	} else {
+		// TRANSLATORS: Prompt template informing the user that no parser was found for their file. The {} placeholder is replaced with the file path.
+		let template = t("No suitable parser was found for {}.\nHow would you like to open this file?");
+		template.replace("{}", &path.display().to_string())
+	};
 	let label = StaticText::builder(&dialog).with_label(&message).build();
 	// TRANSLATORS: Label for the format selection dropdown
 	let format_label_text = t("Open &as:");
 	let format_label = StaticText::builder(&dialog).with_label(&format_label_text).build();
 	let format_combo = Choice::builder(&dialog).build();
-	// TRANSLATORS: Choice option to open a file as plain text
-	format_combo.append(&t("Plain Text"));
-	// TRANSLATORS: Choice option to open a file as HTML
-	format_combo.append(&t("HTML"));
-	// TRANSLATORS: Choice option to open a file as Markdown
-	format_combo.append(&t("Markdown"));
-	format_combo.set_selection(0);
+	for parser in parsers.iter() {
+		format_combo.append(&parser.name);
+	}
+	let previous_format = previous.map_or("txt", |choice| choice.format.as_str());
+	let selected = parsers
+		.iter()
+		.position(|parser| parser.extensions.first().is_some_and(|ext| ext.eq_ignore_ascii_case(previous_format)))
+		.unwrap_or(0);
+	format_combo.set_selection(u32::try_from(selected).unwrap_or(0));
 	#[cfg(target_os = "macos")]
 	format_combo.set_accessibility_label(format_label_text.replace('&', "").trim_end_matches(':').trim());
+	// TRANSLATORS: Label for the dropdown in the Open As dialog choosing the character encoding of text and HTML files
+	let encoding_label_text = t("&Encoding:");
+	let encoding_label = StaticText::builder(&dialog).with_label(&encoding_label_text).build();
+	let encoding_combo = Choice::builder(&dialog).build();
+	// TRANSLATORS: First entry in the Open As dialog's encoding dropdown, letting Paperback guess the encoding
+	encoding_combo.append(&t("Detect automatically"));
+	for encoding in TEXT_ENCODINGS {
+		encoding_combo.append(encoding);
+	}
+	let previous_encoding = previous.map_or("", |choice| choice.encoding.as_str());
+	let selected_encoding = TEXT_ENCODINGS
+		.iter()
+		.position(|encoding| encoding.eq_ignore_ascii_case(previous_encoding))
+		.map_or(0, |i| i + 1);
+	encoding_combo.set_selection(u32::try_from(selected_encoding).unwrap_or(0));
+	#[cfg(target_os = "macos")]
+	encoding_combo.set_accessibility_label(encoding_label_text.replace('&', "").trim_end_matches(':').trim());
+	let selected_parser = {
+		let parsers = Rc::clone(&parsers);
+		move || format_combo.get_selection().and_then(|i| parsers.get(usize::try_from(i).ok()?)).cloned()
+	};
+	let sync_encoding = {
+		let selected_parser = selected_parser.clone();
+		move || {
+			let reads_encoding = selected_parser().is_some_and(|parser| parser.reads_encoding);
+			encoding_label.enable(reads_encoding);
+			encoding_combo.enable(reads_encoding);
+		}
+	};
+	sync_encoding();
+	format_combo.on_selection_changed(move |_| sync_encoding());
 	// TRANSLATORS: Label for the confirmation button
 	let ok_label = t("OK");
-	let ok_button = Button::builder(&dialog).with_label(&ok_label).build();
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&ok_label).build();
+	ok_button.set_default();
 	// TRANSLATORS: Label for the cancellation button
 	let cancel_label = t("Cancel");
 	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&cancel_label).build();
@@ -46,6 +107,10 @@ pub fn show_open_as_dialog(parent: &Frame, path: &Path) -> Option<String> {
 	format_sizer.add(&format_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
 	format_sizer.add(&format_combo, 1, SizerFlag::Expand, 0);
 	content_sizer.add_sizer(&format_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING / 2);
+	let encoding_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	encoding_sizer.add(&encoding_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	encoding_sizer.add(&encoding_combo, 1, SizerFlag::Expand, 0);
+	content_sizer.add_sizer(&encoding_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING / 2);
 	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	button_sizer.add_stretch_spacer(1);
 	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
@@ -57,11 +122,16 @@ pub fn show_open_as_dialog(parent: &Frame, path: &Path) -> Option<String> {
 	if dialog.show_modal() != ID_OK {
 		return None;
 	}
-	let selection = format_combo.get_selection();
-	let format = match selection {
-		Some(1) => "html",
-		Some(2) => "md",
-		_ => "txt",
+	let parser = selected_parser()?;
+	let format = parser.extensions.first()?.clone();
+	let encoding = if parser.reads_encoding {
+		encoding_combo
+			.get_selection()
+			.and_then(|i| usize::try_from(i).ok()?.checked_sub(1))
+			.and_then(|i| TEXT_ENCODINGS.get(i))
+			.map_or_else(String::new, ToString::to_string)
+	} else {
+		String::new()
 	};
-	Some(format.to_string())
+	Some(OpenAsChoice { format, encoding })
 }
@@ -1,60 +1,111 @@
 use std::{
 	cell::{Cell, RefCell},
+	collections::BTreeMap,
 	fmt::Write,
 	mem,
 	rc::Rc,
 };
 
-use paperback_core::config::{ConfigManager, HotkeyConfig, ReadabilityFont};
+use paperback_core::{
+	config::{ConfigManager, HotkeyConfig, ReadabilityFont},
+	reader_core::{StatusFields, format_status, lookup},
+};
 use patois::{t, ui::populate_language_choice};
 #[cfg(target_os = "windows")]
 use wxdragon::accessible::AccRole;
-use wxdragon::prelude::*;
+use wxdragon::{event::WindowEventData, prelude::*};
 
 use super::DIALOG_PADDING;
 use crate::{
-	config_ext::{UpdateChannel, get_update_channel},
+	config_ext::{
+		ColorScheme, UpdateChannel, get_autosave_interval, get_color_scheme, get_text_color, get_update_channel,
+		is_auto_sync_sidecar_enabled,
+	},
 	translation_manager::TranslationManager,
+	ui::{
+		shortcuts::{self, ShortcutCommand},
+		status,
+	},
 };
 
 #[derive(Clone, Debug)]
 pub struct OptionsDialogResult {
 	pub restore_previous_documents: bool,
+	pub auto_sync_sidecar: bool,
+	pub relink_moved_documents: bool,
+	pub library_folder: String,
 	pub word_wrap: bool,
 	pub render_tables_inline: bool,
+	pub include_speaker_notes: bool,
+	pub include_comments: bool,
+	pub skip_unlabeled_images: bool,
+	pub normalize_typography: bool,
 	pub minimize_to_tray: bool,
 	pub start_maximized: bool,
+	pub single_instance: bool,
 	pub compact_go_menu: bool,
 	pub navigation_wrap: bool,
 	pub check_for_updates_on_startup: bool,
 	pub bookmark_sounds: bool,
+	pub announce_bookmarks: bool,
+	pub announce_location_on_navigation: bool,
+	pub context_announcements: bool,
+	pub find_loads_all_pages: bool,
+	pub show_section_in_title: bool,
 	pub recent_documents_to_show: i32,
+	pub autosave_interval: i32,
 	pub reading_speed_wpm: i32,
+	pub status_format: String,
+	pub dictionary_path: String,
+	pub lookup_url: String,
 	pub language: String,
 	pub update_channel: UpdateChannel,
 	pub hotkey: HotkeyConfig,
 	pub readability_font: ReadabilityFont,
 	pub line_spacing: i32,
 	pub bg_color: i32,
+	pub color_scheme: ColorScheme,
+	pub text_color: i32,
 	pub text_alignment: i32,
 	pub letter_spacing: i32,
 	pub paragraph_spacing: i32,
+	/// Shortcut overrides by menu ID; commands not listed use their default shortcut.
+	pub shortcuts: BTreeMap<i32, String>,
+	/// Whether Compact Configuration was confirmed, to drop unused document sections on saving.
+	pub compact_configuration: bool,
 }
 
 struct OptionsDialogUi {
 	dialog: Dialog,
 	notebook: Notebook,
 	restore_docs_check: CheckBox,
+	auto_sync_sidecar_check: CheckBox,
+	relink_moved_check: CheckBox,
+	library_folder_ctrl: TextCtrl,
 	word_wrap_check: CheckBox,
 	render_tables_inline_check: CheckBox,
+	speaker_notes_check: CheckBox,
+	comments_check: CheckBox,
+	skip_unlabeled_images_check: CheckBox,
+	normalize_typography_check: CheckBox,
 	minimize_to_tray_check: CheckBox,
 	start_maximized_check: CheckBox,
+	single_instance_check: CheckBox,
 	compact_go_menu_check: CheckBox,
 	navigation_wrap_check: CheckBox,
 	check_for_updates_check: CheckBox,
 	bookmark_sounds_check: CheckBox,
+	announce_bookmarks_check: CheckBox,
+	announce_location_check: CheckBox,
+	context_announcements_check: CheckBox,
+	find_loads_all_pages_check: CheckBox,
+	show_section_in_title_check: CheckBox,
 	recent_docs_ctrl: SpinCtrl,
+	autosave_interval_ctrl: SpinCtrl,
 	reading_speed_ctrl: SpinCtrl,
+	status_format_ctrl: TextCtrl,
+	dictionary_path_ctrl: TextCtrl,
+	lookup_url_ctrl: TextCtrl,
 	language_combo: Choice,
 	update_channel_combo: Choice,
 	language_codes: Vec<String>,
@@ -65,9 +116,13 @@ struct OptionsDialogUi {
 	readability_font: Rc<RefCell<ReadabilityFont>>,
 	line_spacing_ctrl: Choice,
 	bg_color: Rc<Cell<i32>>,
+	color_scheme_ctrl: Choice,
+	text_color: Rc<Cell<i32>>,
 	text_alignment_ctrl: Choice,
 	letter_spacing_ctrl: Choice,
 	paragraph_spacing_ctrl: Choice,
+	shortcuts: Rc<RefCell<BTreeMap<i32, String>>>,
+	compact_configuration: Rc<Cell<bool>>,
 }
 
 pub fn show_options_dialog(parent: &Frame, config: &ConfigManager) -> Option<OptionsDialogResult> {
@@ -84,30 +139,56 @@ pub fn show_options_dialog(parent: &Frame, config: &ConfigManager) -> Option<Opt
 	let readability_font = ui.readability_font.borrow().clone();
 	let line_spacing = ui.line_spacing_ctrl.get_selection().unwrap_or(0) as i32;
 	let bg_color = ui.bg_color.get();
+	let color_scheme = ui
+		.color_scheme_ctrl
+		.get_selection()
+		.and_then(|index| ColorScheme::ALL.get(index as usize).copied())
+		.unwrap_or_default();
 	let text_alignment = ui.text_alignment_ctrl.get_selection().unwrap_or(0) as i32;
 	let letter_spacing = ui.letter_spacing_ctrl.get_selection().unwrap_or(0) as i32;
 	let paragraph_spacing = ui.paragraph_spacing_ctrl.get_selection().unwrap_or(0) as i32;
 	Some(OptionsDialogResult {
 		restore_previous_documents: ui.restore_docs_check.is_checked(),
+		auto_sync_sidecar: ui.auto_sync_sidecar_check.is_checked(),
+		relink_moved_documents: ui.relink_moved_check.is_checked(),
+		library_folder: ui.library_folder_ctrl.get_value().trim().to_string(),
 		word_wrap: ui.word_wrap_check.is_checked(),
 		render_tables_inline: ui.render_tables_inline_check.is_checked(),
+		include_speaker_notes: ui.speaker_notes_check.is_checked(),
+		include_comments: ui.comments_check.is_checked(),
+		skip_unlabeled_images: ui.skip_unlabeled_images_check.is_checked(),
+		normalize_typography: ui.normalize_typography_check.is_checked(),
 		minimize_to_tray: ui.minimize_to_tray_check.is_checked(),
 		start_maximized: ui.start_maximized_check.is_checked(),
+		single_instance: ui.single_instance_check.is_checked(),
 		compact_go_menu: ui.compact_go_menu_check.is_checked(),
 		navigation_wrap: ui.navigation_wrap_check.is_checked(),
 		check_for_updates_on_startup: ui.check_for_updates_check.is_checked(),
 		bookmark_sounds: ui.bookmark_sounds_check.is_checked(),
+		announce_bookmarks: ui.announce_bookmarks_check.is_checked(),
+		announce_location_on_navigation: ui.announce_location_check.is_checked(),
+		context_announcements: ui.context_announcements_check.is_checked(),
+		find_loads_all_pages: ui.find_loads_all_pages_check.is_checked(),
+		show_section_in_title: ui.show_section_in_title_check.is_checked(),
 		recent_documents_to_show: ui.recent_docs_ctrl.value(),
+		autosave_interval: ui.autosave_interval_ctrl.value(),
 		reading_speed_wpm: ui.reading_speed_ctrl.value(),
+		status_format: ui.status_format_ctrl.get_value(),
+		dictionary_path: ui.dictionary_path_ctrl.get_value().trim().to_string(),
+		lookup_url: ui.lookup_url_ctrl.get_value().trim().to_string(),
 		language,
 		update_channel,
 		hotkey: ui.hotkey.borrow().clone(),
 		readability_font,
 		line_spacing,
 		bg_color,
+		color_scheme,
+		text_color: ui.text_color.get(),
 		text_alignment,
 		letter_spacing,
 		paragraph_spacing,
+		shortcuts: ui.shortcuts.borrow().clone(),
+		compact_configuration: ui.compact_configuration.get(),
 	})
 }
 
@@ -124,15 +205,40 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	let restore_docs_check =
 		// TRANSLATORS: Option to restore documents that were open when the app was last closed
 		CheckBox::builder(&general_panel).with_label(&t("&Restore previously opened documents on startup")).build();
+	let auto_sync_sidecar_check =
+		// TRANSLATORS: Option to keep each document's reading position and bookmarks in a .paperback file next to it, so they follow the document across computers (e.g. in a synced folder)
+		CheckBox::builder(&general_panel).with_label(&t("S&ync reading position with .paperback files")).build();
+	let relink_moved_check =
+		// TRANSLATORS: Option to look in the library folder at startup for documents that were moved or renamed, and offer to point their saved positions and bookmarks at the new place
+		CheckBox::builder(&general_panel).with_label(&t("Find &moved documents in the library folder on startup")).build();
+	let library_folder_label =
+		// TRANSLATORS: Label for the folder searched, with its subfolders, for documents that were moved
+		StaticText::builder(&general_panel).with_label(&t("Library &folder:")).build();
+	let library_folder_ctrl = TextCtrl::builder(&general_panel).with_size(Size::new(280, -1)).build();
 	// TRANSLATORS: Option to toggle word wrapping of text
 	let word_wrap_check = CheckBox::builder(&readability_panel).with_label(&t("&Word wrap")).build();
 	let render_tables_inline_check =
 		// TRANSLATORS: Option to render tables inline rather than showing a placeholder link
 		CheckBox::builder(&readability_panel).with_label(&t("Render tables &inline")).build();
+	let speaker_notes_check =
+		// TRANSLATORS: Option to include the speaker notes of PowerPoint/OpenDocument slides after each slide's text
+		CheckBox::builder(&readability_panel).with_label(&t("Include speaker &notes in presentations")).build();
+	let comments_check =
+		// TRANSLATORS: Option to show the reviewer comments of Word documents inline, in brackets, where they are attached
+		CheckBox::builder(&readability_panel).with_label(&t("Include &comments in Word documents")).build();
+	let skip_unlabeled_images_check =
+		// TRANSLATORS: Option to leave out images that have no description instead of showing "[Image]" for them
+		CheckBox::builder(&readability_panel).with_label(&t("Skip images without &alt text")).build();
+	let normalize_typography_check =
+		// TRANSLATORS: Option to replace curly quotes, long dashes and ellipsis characters with plain ones so speech synthesizers read them cleanly
+		CheckBox::builder(&readability_panel).with_label(&t("Use plain &quotes, dashes and ellipses for speech")).build();
 	// TRANSLATORS: Option to minimize the app window to the system tray instead of the taskbar
 	let minimize_to_tray_check = CheckBox::builder(&general_panel).with_label(&t("&Minimize to system tray")).build();
 	// TRANSLATORS: Option to start the app maximized
 	let start_maximized_check = CheckBox::builder(&general_panel).with_label(&t("&Start maximized")).build();
+	let single_instance_check =
+		// TRANSLATORS: Option to open files in the already running Paperback window instead of starting a second copy; takes effect the next time Paperback starts
+		CheckBox::builder(&general_panel).with_label(&t("Allow only one running &instance")).build();
 	// TRANSLATORS: Option to show a compact Go navigation menu in the menu bar
 	let compact_go_menu_check = CheckBox::builder(&reading_panel).with_label(&t("Show compact &go menu")).build();
 	// TRANSLATORS: Option to wrap navigation around to the beginning/end when navigating elements
@@ -140,19 +246,58 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	let bookmark_sounds_check =
 		// TRANSLATORS: Option to play sound effects when bookmarks or notes are encountered
 		CheckBox::builder(&reading_panel).with_label(&t("Play &sounds on bookmarks and notes")).build();
+	let announce_bookmarks_check =
+		// TRANSLATORS: Option to speak "Bookmark:" and the bookmark's note or text when the arrow keys move the caret into a bookmark
+		CheckBox::builder(&reading_panel).with_label(&t("Announce boo&kmarks when arrowing into them")).build();
+	let announce_location_check =
+		// TRANSLATORS: Option to speak the heading, page and percentage after each Go menu navigation
+		CheckBox::builder(&reading_panel).with_label(&t("Announce &location after navigating")).build();
+	let context_announcements_check =
+		// TRANSLATORS: Option to speak "List with 3 items", "Table", "Out of list" and so on when the arrow keys move into or out of a list, table, block quote or code block
+		CheckBox::builder(&reading_panel).with_label(&t("Announce lists and &tables when arrowing into them")).build();
+	let find_loads_all_pages_check =
+		// TRANSLATORS: Option to read every page of a large PDF before Find searches it; when off, Find only searches the pages loaded so far
+		CheckBox::builder(&reading_panel).with_label(&t("Load all &pages of large PDFs before searching")).build();
+	let show_section_in_title_check =
+		// TRANSLATORS: Option to show the heading of the chapter the caret is in after the document title in the window title and the tab label
+		CheckBox::builder(&reading_panel).with_label(&t("Show the current &chapter in the title bar and tab")).build();
 	let check_for_updates_check =
 		// TRANSLATORS: Option to check for app updates automatically on startup
 		CheckBox::builder(&general_panel).with_label(&t("Check for &updates on startup")).build();
 	// TRANSLATORS: Button label to open the hotkey customization dialog
 	let hotkey_button = Button::builder(&general_panel).with_label(&t("Customize &Window Hotkey...")).build();
+	// TRANSLATORS: Button label to remove saved data for documents that have nothing worth keeping
+	let compact_button = Button::builder(&general_panel).with_label(&t("Compact &Configuration...")).build();
 	let option_padding = 5;
 	general_sizer.add(&restore_docs_check, 0, SizerFlag::All, option_padding);
+	general_sizer.add(&auto_sync_sidecar_check, 0, SizerFlag::All, option_padding);
+	general_sizer.add(&relink_moved_check, 0, SizerFlag::All, option_padding);
+	let library_folder_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	library_folder_sizer.add(
+		&library_folder_label,
+		0,
+		SizerFlag::AlignCenterVertical | SizerFlag::Right,
+		DIALOG_PADDING,
+	);
+	library_folder_sizer.add(&library_folder_ctrl, 1, SizerFlag::AlignCenterVertical, 0);
+	general_sizer.add_sizer(&library_folder_sizer, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
 	general_sizer.add(&start_maximized_check, 0, SizerFlag::All, option_padding);
+	general_sizer.add(&single_instance_check, 0, SizerFlag::All, option_padding);
 	#[cfg(not(target_os = "macos"))]
 	general_sizer.add(&minimize_to_tray_check, 0, SizerFlag::All, option_padding);
 	general_sizer.add(&check_for_updates_check, 0, SizerFlag::All, option_padding);
 	general_sizer.add(&hotkey_button, 0, SizerFlag::All, option_padding);
-	for check in [&navigation_wrap_check, &compact_go_menu_check, &bookmark_sounds_check] {
+	general_sizer.add(&compact_button, 0, SizerFlag::All, option_padding);
+	for check in [
+		&navigation_wrap_check,
+		&compact_go_menu_check,
+		&bookmark_sounds_check,
+		&announce_bookmarks_check,
+		&announce_location_check,
+		&context_announcements_check,
+		&find_loads_all_pages_check,
+		&show_section_in_title_check,
+	] {
 		reading_sizer.add(check, 0, SizerFlag::All, option_padding);
 	}
 	let reading_speed_label =
@@ -163,6 +308,52 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	reading_speed_sizer.add(&reading_speed_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
 	reading_speed_sizer.add(&reading_speed_ctrl, 0, SizerFlag::AlignCenterVertical, 0);
 	reading_sizer.add_sizer(&reading_speed_sizer, 0, SizerFlag::All, option_padding);
+	// TRANSLATORS: Label/header for the Status bar options section
+	let status_group_box = StaticBox::builder(&reading_panel).with_label(&t("Status bar")).build();
+	let status_group_sizer = StaticBoxSizerBuilder::new_with_box(&status_group_box, Orientation::Vertical).build();
+	let status_format_label =
+		// TRANSLATORS: Label for the text field holding the status bar template, e.g. "Line {line}, Reading {percent}%"
+		StaticText::builder(&reading_panel).with_label(&t("Status bar &format:")).build();
+	let status_format_ctrl = TextCtrl::builder(&reading_panel).with_size(Size::new(360, -1)).build();
+	let status_format_help = StaticText::builder(&reading_panel)
+		// TRANSLATORS: Help text listing the placeholders the status bar template understands; the words in braces must not be translated
+		.with_label(&t(
+			"Placeholders: {line}, {char}, {percent}, {page}, {pages}, {heading}, {words_left}, {time_left}, {title}",
+		))
+		.build();
+	let status_preview_label = StaticText::builder(&reading_panel).with_label("").build();
+	status_group_sizer.add(&status_format_label, 0, SizerFlag::All, option_padding);
+	status_group_sizer.add(&status_format_ctrl, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
+	status_group_sizer.add(&status_format_help, 0, SizerFlag::All, option_padding);
+	status_group_sizer.add(&status_preview_label, 0, SizerFlag::All, option_padding);
+	reading_sizer.add_sizer(&status_group_sizer, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
+	// TRANSLATORS: Label/header for the word lookup options section
+	let dictionary_group_box = StaticBox::builder(&reading_panel).with_label(&t("Look up word")).build();
+	let dictionary_group_sizer =
+		StaticBoxSizerBuilder::new_with_box(&dictionary_group_box, Orientation::Vertical).build();
+	let dictionary_path_label =
+		// TRANSLATORS: Label for the path of an offline tab-separated dictionary file; leave empty to look words up online
+		StaticText::builder(&reading_panel).with_label(&t("Offline &dictionary file (empty to look up online):")).build();
+	let dictionary_path_ctrl = TextCtrl::builder(&reading_panel).with_size(Size::new(280, -1)).build();
+	// TRANSLATORS: Button to choose an offline dictionary file
+	let browse_dictionary_button = Button::builder(&reading_panel).with_label(&t("&Browse...")).build();
+	let dictionary_path_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	dictionary_path_sizer.add(
+		&dictionary_path_ctrl,
+		1,
+		SizerFlag::AlignCenterVertical | SizerFlag::Right,
+		DIALOG_PADDING,
+	);
+	dictionary_path_sizer.add(&browse_dictionary_button, 0, SizerFlag::AlignCenterVertical, 0);
+	let lookup_url_label =
+		// TRANSLATORS: Label for the web address opened to look up a word; {word} is replaced with the word and must not be translated
+		StaticText::builder(&reading_panel).with_label(&t("Online lookup &address ({word} is replaced):")).build();
+	let lookup_url_ctrl = TextCtrl::builder(&reading_panel).with_size(Size::new(360, -1)).build();
+	dictionary_group_sizer.add(&dictionary_path_label, 0, SizerFlag::All, option_padding);
+	dictionary_group_sizer.add_sizer(&dictionary_path_sizer, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
+	dictionary_group_sizer.add(&lookup_url_label, 0, SizerFlag::All, option_padding);
+	dictionary_group_sizer.add(&lookup_url_ctrl, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
+	reading_sizer.add_sizer(&dictionary_group_sizer, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
 	let max_recent_docs = 100;
 	let recent_docs_label =
 		// TRANSLATORS: Label for the number of recently opened documents to keep in history
@@ -172,6 +363,20 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	recent_docs_sizer.add(&recent_docs_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
 	recent_docs_sizer.add(&recent_docs_ctrl, 0, SizerFlag::AlignCenterVertical, 0);
 	general_sizer.add_sizer(&recent_docs_sizer, 0, SizerFlag::All, option_padding);
+	let max_autosave_interval = 3600;
+	let autosave_interval_label =
+		// TRANSLATORS: Label for how often the reading positions of open documents are saved, in seconds; 0 turns autosave off
+		StaticText::builder(&general_panel).with_label(&t("&Autosave positions every (seconds, 0 to disable):")).build();
+	let autosave_interval_ctrl = SpinCtrl::builder(&general_panel).with_range(0, max_autosave_interval).build();
+	let autosave_interval_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	autosave_interval_sizer.add(
+		&autosave_interval_label,
+		0,
+		SizerFlag::AlignCenterVertical | SizerFlag::Right,
+		DIALOG_PADDING,
+	);
+	autosave_interval_sizer.add(&autosave_interval_ctrl, 0, SizerFlag::AlignCenterVertical, 0);
+	general_sizer.add_sizer(&autosave_interval_sizer, 0, SizerFlag::All, option_padding);
 	// TRANSLATORS: Label for the language selection dropdown
 	let language_label_text = t("&Language:");
 	let language_label = StaticText::builder(&general_panel).with_label(&language_label_text).build();
@@ -212,14 +417,38 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	font_group_sizer.add(&choose_font_button, 0, SizerFlag::All, option_padding);
 	font_group_sizer.add(&reset_font_button, 0, SizerFlag::All, option_padding);
 	readability_sizer.add_sizer(&font_group_sizer, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
-	// TRANSLATORS: Label/header for the Background Color options section
-	let bg_group_box = StaticBox::builder(&readability_panel).with_label(&t("Background Color")).build();
+	// TRANSLATORS: Label/header for the Colors options section
+	let bg_group_box = StaticBox::builder(&readability_panel).with_label(&t("Colors")).build();
 	let bg_group_sizer = StaticBoxSizerBuilder::new_with_box(&bg_group_box, Orientation::Vertical).build();
+	// TRANSLATORS: Label for the color scheme dropdown
+	let color_scheme_label_text = t("Color &scheme:");
+	let color_scheme_label = StaticText::builder(&readability_panel).with_label(&color_scheme_label_text).build();
+	let color_scheme_ctrl = Choice::builder(&readability_panel).build();
+	// TRANSLATORS: Color scheme option that follows the operating system's colors
+	color_scheme_ctrl.append(&t("System colors"));
+	// TRANSLATORS: Color scheme option with dark text on a light background
+	color_scheme_ctrl.append(&t("Light"));
+	// TRANSLATORS: Color scheme option with light text on a dark background
+	color_scheme_ctrl.append(&t("Dark"));
+	// TRANSLATORS: Color scheme option using the text and background colors chosen below
+	color_scheme_ctrl.append(&t("Custom"));
+	#[cfg(target_os = "macos")]
+	color_scheme_ctrl.set_accessibility_label(color_scheme_label_text.replace('&', "").trim_end_matches(':').trim());
+
+	let color_scheme_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	color_scheme_sizer.add(&color_scheme_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	color_scheme_sizer.add(&color_scheme_ctrl, 0, SizerFlag::AlignCenterVertical, 0);
+	let text_color_label = StaticText::builder(&readability_panel).with_label("").build();
+	// TRANSLATORS: Button label to pick a custom text color
+	let choose_text_color_button = Button::builder(&readability_panel).with_label(&t("Choose &Text Color...")).build();
 	let bg_color_label = StaticText::builder(&readability_panel).with_label("").build();
 	// TRANSLATORS: Button label to pick a background color
 	let choose_bg_button = Button::builder(&readability_panel).with_label(&t("Choose &Background Color...")).build();
 	// TRANSLATORS: Button label to restore background color to default values
 	let reset_bg_button = Button::builder(&readability_panel).with_label(&t("Reset to &Default Background")).build();
+	bg_group_sizer.add_sizer(&color_scheme_sizer, 0, SizerFlag::All, option_padding);
+	bg_group_sizer.add(&text_color_label, 0, SizerFlag::All, option_padding);
+	bg_group_sizer.add(&choose_text_color_button, 0, SizerFlag::All, option_padding);
 	bg_group_sizer.add(&bg_color_label, 0, SizerFlag::All, option_padding);
 	bg_group_sizer.add(&choose_bg_button, 0, SizerFlag::All, option_padding);
 	bg_group_sizer.add(&reset_bg_button, 0, SizerFlag::All, option_padding);
@@ -311,6 +540,10 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	text_alignment_sizer.add(&text_alignment_ctrl, 0, SizerFlag::AlignCenterVertical, 0);
 	readability_sizer.add(&word_wrap_check, 0, SizerFlag::All, option_padding);
 	readability_sizer.add(&render_tables_inline_check, 0, SizerFlag::All, option_padding);
+	readability_sizer.add(&speaker_notes_check, 0, SizerFlag::All, option_padding);
+	readability_sizer.add(&comments_check, 0, SizerFlag::All, option_padding);
+	readability_sizer.add(&skip_unlabeled_images_check, 0, SizerFlag::All, option_padding);
+	readability_sizer.add(&normalize_typography_check, 0, SizerFlag::All, option_padding);
 	readability_sizer.add_sizer(&line_spacing_sizer, 0, SizerFlag::All, option_padding);
 	readability_sizer.add_sizer(&paragraph_spacing_sizer, 0, SizerFlag::All, option_padding);
 	readability_sizer.add_sizer(&letter_spacing_sizer, 0, SizerFlag::All, option_padding);
@@ -318,35 +551,85 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	readability_panel.set_sizer(readability_sizer, true);
 	general_panel.set_sizer(general_sizer, true);
 	reading_panel.set_sizer(reading_sizer, true);
+	let (keyboard_panel, shortcut_overrides) = build_keyboard_page(&notebook, dialog);
 	// TRANSLATORS: Tab label for the General options panel
 	let general_label = t("General");
 	// TRANSLATORS: Tab label for the Reading options panel
 	let reading_label = t("Reading");
 	// TRANSLATORS: Tab label for the Readability options panel
 	let readability_label = t("Readability");
+	// TRANSLATORS: Tab label for the Keyboard options panel, where menu shortcuts are customized
+	let keyboard_label = t("Keyboard");
 	general_panel.set_accessibility_label(&general_label);
 	reading_panel.set_accessibility_label(&reading_label);
 	readability_panel.set_accessibility_label(&readability_label);
+	keyboard_panel.set_accessibility_label(&keyboard_label);
 	#[cfg(target_os = "windows")]
 	{
 		general_panel.set_accessibility_role(AccRole::PropertyPage);
 		reading_panel.set_accessibility_role(AccRole::PropertyPage);
 		readability_panel.set_accessibility_role(AccRole::PropertyPage);
+		keyboard_panel.set_accessibility_role(AccRole::PropertyPage);
 	}
 	notebook.add_page(&general_panel, &general_label, true, None);
 	notebook.add_page(&reading_panel, &reading_label, false, None);
 	notebook.add_page(&readability_panel, &readability_label, false, None);
+	notebook.add_page(&keyboard_panel, &keyboard_label, false, None);
 	restore_docs_check.set_value(config.get_app_bool("restore_previous_documents", true));
+	auto_sync_sidecar_check.set_value(is_auto_sync_sidecar_enabled(config));
+	relink_moved_check.set_value(config.get_app_bool("relink_moved_documents", false));
+	library_folder_ctrl.set_value(&config.get_app_string("library_folder", ""));
 	word_wrap_check.set_value(config.get_app_bool("word_wrap", false));
 	render_tables_inline_check.set_value(config.get_app_bool("render_tables_inline", true));
+	speaker_notes_check.set_value(config.get_app_bool("include_speaker_notes", true));
+	comments_check.set_value(config.get_app_bool("include_comments", false));
+	skip_unlabeled_images_check.set_value(config.get_app_bool("skip_unlabeled_images", false));
+	normalize_typography_check.set_value(config.get_app_bool("normalize_typography", false));
 	minimize_to_tray_check.set_value(config.get_app_bool("minimize_to_tray", false));
 	start_maximized_check.set_value(config.get_app_bool("start_maximized", false));
+	single_instance_check.set_value(config.get_app_bool("single_instance", true));
 	compact_go_menu_check.set_value(config.get_app_bool("compact_go_menu", true));
 	navigation_wrap_check.set_value(config.get_app_bool("navigation_wrap", false));
 	bookmark_sounds_check.set_value(config.get_app_bool("bookmark_sounds", true));
+	announce_bookmarks_check.set_value(config.get_app_bool("announce_bookmarks", true));
+	announce_location_check.set_value(config.get_app_bool("announce_location_on_navigation", false));
+	context_announcements_check.set_value(config.get_app_bool("context_announcements", false));
+	find_loads_all_pages_check.set_value(config.get_app_bool("find_loads_all_pages", true));
+	show_section_in_title_check.set_value(config.get_app_bool("show_section_in_title", false));
 	check_for_updates_check.set_value(config.get_app_bool("check_for_updates_on_startup", true));
 	recent_docs_ctrl.set_value(config.get_app_int("recent_documents_to_show", 25).clamp(0, max_recent_docs));
+	autosave_interval_ctrl.set_value(get_autosave_interval(config).min(max_autosave_interval));
 	reading_speed_ctrl.set_value(config.get_app_int("reading_speed_wpm", 150).clamp(1, 2000));
+	status_format_ctrl.set_value(&status::status_format(config));
+	let update_status_preview = move || {
+		let preview = format_status(
+			&status_format_ctrl.get_value(),
+			&status_preview_fields(),
+			i64::from(reading_speed_ctrl.value()),
+		);
+		// TRANSLATORS: Prefix of the live preview of the status bar template in the Options dialog
+		status_preview_label.set_label(&format!("{} {preview}", t("Preview:")));
+	};
+	update_status_preview();
+	status_format_ctrl.on_text_updated(move |_| update_status_preview());
+	dictionary_path_ctrl.set_value(&config.get_app_string("dictionary_path", ""));
+	lookup_url_ctrl.set_value(&config.get_app_string("lookup_url", lookup::DEFAULT_LOOKUP_URL));
+	let dictionary_dialog_parent = dialog;
+	browse_dictionary_button.on_click(move |_| {
+		// TRANSLATORS: File filter shown when choosing an offline dictionary file
+		let wildcard = t("Dictionary files (*.txt;*.tsv;*.tab)|*.txt;*.tsv;*.tab|All files (*.*)|*.*");
+		let file_dialog = FileDialog::builder(&dictionary_dialog_parent)
+			// TRANSLATORS: Title of the file dialog for choosing an offline dictionary
+			.with_message(&t("Choose dictionary file"))
+			.with_wildcard(&wildcard)
+			.with_style(FileDialogStyle::Open | FileDialogStyle::FileMustExist)
+			.build();
+		if file_dialog.show_modal() == ID_OK
+			&& let Some(path) = file_dialog.get_path()
+		{
+			dictionary_path_ctrl.set_value(&path);
+		}
+	});
 	let stored_language = config.get_app_string("language", "");
 	let current_language = if stored_language.is_empty() {
 		TranslationManager::instance().lock().unwrap().current_language()
@@ -371,6 +654,32 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 			*hotkey_state.borrow_mut() = updated;
 		}
 	});
+	let compactable = config.compactable_document_count();
+	let compact_configuration = Rc::new(Cell::new(false));
+	let compact_state = Rc::clone(&compact_configuration);
+	let compact_dialog_parent = dialog;
+	compact_button.on_click(move |_| {
+		// TRANSLATORS: Title of the dialogs shown by the Compact Configuration button in Options
+		let title = t("Compact Configuration");
+		if compactable == 0 {
+			// TRANSLATORS: Shown by Compact Configuration when every saved document entry is still in use
+			let message = t("There are no unused document entries to remove.");
+			MessageDialog::builder(&compact_dialog_parent, &message, &title)
+				.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconInformation | MessageDialogStyle::Centre)
+				.build()
+				.show_modal();
+			return;
+		}
+		// TRANSLATORS: Confirmation for Compact Configuration; %d is how many saved document entries will be removed
+		let message = t("%d saved document entries are unused: the documents aren't open or recent, have no bookmarks or notes, and were never read past the start or no longer exist. Remove them when the options are saved? Bookmarks and notes are never removed.")
+			.replace("%d", &compactable.to_string());
+		let confirm = MessageDialog::builder(&compact_dialog_parent, &message, &title)
+			.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+			.build();
+		if confirm.show_modal() == ID_YES {
+			compact_state.set(true);
+		}
+	});
 	let initial_font = config.get_readability_font();
 	font_preview_label.set_label(&font_description(&initial_font));
 	let readability_font = Rc::new(RefCell::new(initial_font));
@@ -380,8 +689,14 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 	letter_spacing_ctrl.set_selection(config.get_letter_spacing().clamp(0, 2) as u32);
 	text_alignment_ctrl.set_selection(config.get_text_alignment().clamp(0, 3) as u32);
 	let stored_bg = config.get_bg_color();
-	bg_color_label.set_label(&color_description(stored_bg));
+	bg_color_label.set_label(&bg_color_description(stored_bg));
 	let bg_color = Rc::new(Cell::new(stored_bg));
+	let stored_scheme = get_color_scheme(config);
+	let scheme_index = ColorScheme::ALL.iter().position(|scheme| *scheme == stored_scheme).unwrap_or(0);
+	color_scheme_ctrl.set_selection(u32::try_from(scheme_index).unwrap_or(0));
+	let stored_text_color = get_text_color(config);
+	text_color_label.set_label(&text_color_description(stored_text_color));
+	let text_color = Rc::new(Cell::new(stored_text_color));
 	let font_state = Rc::clone(&readability_font);
 	let preview_label = font_preview_label;
 	let dialog_ref = dialog;
@@ -399,37 +714,33 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 		preview_label_reset.set_label(&font_description(&default_font));
 		*font_state_reset.borrow_mut() = default_font;
 	});
+	let custom_scheme_index = u32::try_from(ColorScheme::ALL.len() - 1).unwrap_or(0);
 	let bg_state = Rc::clone(&bg_color);
 	let bg_label = bg_color_label;
 	let dialog_for_bg = dialog_ref;
+	let scheme_ctrl_for_bg = color_scheme_ctrl;
 	choose_bg_button.on_click(move |_| {
-		let current = bg_state.get();
-		let initial = if current >= 0 {
-			let r = ((current >> 16) & 0xFF) as u8;
-			let g = ((current >> 8) & 0xFF) as u8;
-			let b = (current & 0xFF) as u8;
-			Some(Colour::rgb(r, g, b))
-		} else {
-			None
-		};
-		let mut dlg = ColourDialog::builder(&dialog_for_bg);
-		if let Some(c) = initial {
-			dlg = dlg.with_initial_colour(c);
-		}
-		let dlg = dlg.build();
-		if dlg.show_modal() == ID_OK
-			&& let Some(c) = dlg.get_colour()
-		{
-			let packed = (i32::from(c.r) << 16) | (i32::from(c.g) << 8) | i32::from(c.b);
+		if let Some(packed) = pick_colour(dialog_for_bg, bg_state.get()) {
 			bg_state.set(packed);
-			bg_label.set_label(&color_description(packed));
+			bg_label.set_label(&bg_color_description(packed));
+			scheme_ctrl_for_bg.set_selection(custom_scheme_index);
+		}
+	});
+	let text_color_state = Rc::clone(&text_color);
+	let text_label = text_color_label;
+	let scheme_ctrl_for_text = color_scheme_ctrl;
+	choose_text_color_button.on_click(move |_| {
+		if let Some(packed) = pick_colour(dialog_ref, text_color_state.get()) {
+			text_color_state.set(packed);
+			text_label.set_label(&text_color_description(packed));
+			scheme_ctrl_for_text.set_selection(custom_scheme_index);
 		}
 	});
 	let bg_state_reset = Rc::clone(&bg_color);
 	let bg_label_reset = bg_label;
 	reset_bg_button.on_click(move |_| {
 		bg_state_reset.set(-1);
-		bg_label_reset.set_label(&color_description(-1));
+		bg_label_reset.set_label(&bg_color_description(-1));
 	});
 	// TRANSLATORS: Label for the confirmation button
 	let ok_button = Button::builder(&dialog_ref).with_id(ID_OK).with_label(&t("OK")).build();
@@ -440,16 +751,33 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 		dialog: dialog_ref,
 		notebook,
 		restore_docs_check,
+		auto_sync_sidecar_check,
+		relink_moved_check,
+		library_folder_ctrl,
 		word_wrap_check,
 		render_tables_inline_check,
+		speaker_notes_check,
+		comments_check,
+		skip_unlabeled_images_check,
+		normalize_typography_check,
 		minimize_to_tray_check,
 		start_maximized_check,
+		single_instance_check,
 		compact_go_menu_check,
 		navigation_wrap_check,
 		check_for_updates_check,
 		bookmark_sounds_check,
+		announce_bookmarks_check,
+		announce_location_check,
+		context_announcements_check,
+		find_loads_all_pages_check,
+		show_section_in_title_check,
 		recent_docs_ctrl,
+		autosave_interval_ctrl,
 		reading_speed_ctrl,
+		status_format_ctrl,
+		dictionary_path_ctrl,
+		lookup_url_ctrl,
 		language_combo,
 		update_channel_combo,
 		language_codes,
@@ -460,9 +788,175 @@ fn build_options_dialog_ui(parent: &Frame, config: &ConfigManager) -> OptionsDia
 		readability_font,
 		line_spacing_ctrl,
 		bg_color,
+		color_scheme_ctrl,
+		text_color,
 		text_alignment_ctrl,
 		letter_spacing_ctrl,
 		paragraph_spacing_ctrl,
+		shortcuts: shortcut_overrides,
+		compact_configuration,
+	}
+}
+
+fn build_keyboard_page(notebook: &Notebook, dialog: Dialog) -> (Panel, Rc<RefCell<BTreeMap<i32, String>>>) {
+	let option_padding = 5;
+	let panel = Panel::builder(notebook).build();
+	let sizer = BoxSizer::builder(Orientation::Vertical).build();
+	// TRANSLATORS: Label for the list of menu commands on the Keyboard options page
+	let commands_label_text = t("&Commands:");
+	let commands_label = StaticText::builder(&panel).with_label(&commands_label_text).build();
+	let commands_list = ListBox::builder(&panel).build();
+	commands_list.set_accessibility_label(commands_label_text.replace('&', "").trim_end_matches(':').trim());
+	// TRANSLATORS: Label for the field that captures a new shortcut; the user presses the key combination while it has focus
+	let shortcut_label_text = t("&Shortcut (press the new key combination):");
+	let shortcut_label = StaticText::builder(&panel).with_label(&shortcut_label_text).build();
+	let shortcut_field = TextCtrl::builder(&panel).build();
+	// TRANSLATORS: Button label to assign the captured shortcut to the selected command
+	let assign_button = Button::builder(&panel).with_label(&t("&Assign")).build();
+	// TRANSLATORS: Button label to remove the shortcut from the selected command
+	let remove_button = Button::builder(&panel).with_label(&t("&Remove Shortcut")).build();
+	// TRANSLATORS: Button label to reset every command to its built-in shortcut
+	let defaults_button = Button::builder(&panel).with_label(&t("Restore &Defaults")).build();
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add(&assign_button, 0, SizerFlag::Right, DIALOG_PADDING);
+	button_sizer.add(&remove_button, 0, SizerFlag::Right, DIALOG_PADDING);
+	button_sizer.add(&defaults_button, 0, SizerFlag::Right, DIALOG_PADDING);
+	sizer.add(&commands_label, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Top, option_padding);
+	sizer.add(&commands_list, 1, SizerFlag::Expand | SizerFlag::All, option_padding);
+	sizer.add(&shortcut_label, 0, SizerFlag::Left | SizerFlag::Right, option_padding);
+	sizer.add(&shortcut_field, 0, SizerFlag::Expand | SizerFlag::All, option_padding);
+	sizer.add_sizer(&button_sizer, 0, SizerFlag::All, option_padding);
+	panel.set_sizer(sizer, true);
+
+	let commands = Rc::new(shortcuts::commands());
+	let overrides = Rc::new(RefCell::new(shortcuts::overrides()));
+	let refresh = {
+		let commands = Rc::clone(&commands);
+		let overrides = Rc::clone(&overrides);
+		Rc::new(move || {
+			let selection = commands_list.get_selection();
+			commands_list.clear();
+			let overrides = overrides.borrow();
+			for command in commands.iter() {
+				commands_list.append(&shortcut_row_label(command, &effective_shortcut(command, &overrides)));
+			}
+			if let Some(index) = selection.or_else(|| (!commands.is_empty()).then_some(0)) {
+				commands_list.set_selection(index, true);
+			}
+		})
+	};
+	refresh();
+	let selected_command = {
+		let commands = Rc::clone(&commands);
+		move || usize::try_from(commands_list.get_selection()?).ok().and_then(|index| commands.get(index).cloned())
+	};
+	let selected_for_list = selected_command.clone();
+	let overrides_for_list = Rc::clone(&overrides);
+	commands_list.on_selection_changed(move |_| {
+		if let Some(command) = selected_for_list() {
+			shortcut_field.set_value(&effective_shortcut(&command, &overrides_for_list.borrow()));
+		}
+	});
+	if let Some(command) = selected_command() {
+		shortcut_field.set_value(&effective_shortcut(&command, &overrides.borrow()));
+	}
+	shortcut_field.on_key_down(move |event| {
+		if let WindowEventData::Keyboard(kbd) = &event
+			&& let Some(key) = kbd.get_key_code()
+			&& let Some(accel) =
+				shortcuts::accelerator_from_key(key, kbd.control_down(), kbd.alt_down(), kbd.shift_down())
+		{
+			shortcut_field.set_value(&accel);
+			kbd.event.skip(false);
+			return;
+		}
+		event.skip(true);
+	});
+	let selected_for_assign = selected_command.clone();
+	let commands_for_assign = Rc::clone(&commands);
+	let overrides_for_assign = Rc::clone(&overrides);
+	let refresh_for_assign = Rc::clone(&refresh);
+	assign_button.on_click(move |_| {
+		let Some(command) = selected_for_assign() else {
+			return;
+		};
+		let accel = shortcut_field.get_value().trim().to_string();
+		let bindings: Vec<(i32, String)> = {
+			let overrides = overrides_for_assign.borrow();
+			commands_for_assign.iter().map(|c| (c.id, effective_shortcut(c, &overrides))).collect()
+		};
+		if let Some(other_id) = shortcuts::find_conflict(&bindings, command.id, &accel) {
+			let other_name = commands_for_assign.iter().find(|c| c.id == other_id).map_or("", |c| c.name.as_str());
+			// TRANSLATORS: Warning shown when a shortcut is already used; the first {} is the shortcut, the second is the command that uses it
+			let message = t("{} is already assigned to {}. Assign it to this command instead?")
+				.replacen("{}", &accel, 1)
+				.replacen("{}", other_name, 1);
+			// TRANSLATORS: Title of the dialog warning about a shortcut used by two commands
+			let title = t("Shortcut Conflict");
+			let confirm = MessageDialog::builder(&dialog, &message, &title)
+				.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconWarning | MessageDialogStyle::Centre)
+				.build();
+			if confirm.show_modal() != ID_YES {
+				return;
+			}
+			overrides_for_assign.borrow_mut().insert(other_id, String::new());
+		}
+		overrides_for_assign.borrow_mut().insert(command.id, accel);
+		refresh_for_assign();
+	});
+	let selected_for_remove = selected_command;
+	let overrides_for_remove = Rc::clone(&overrides);
+	let refresh_for_remove = Rc::clone(&refresh);
+	remove_button.on_click(move |_| {
+		if let Some(command) = selected_for_remove() {
+			overrides_for_remove.borrow_mut().insert(command.id, String::new());
+			shortcut_field.set_value("");
+			refresh_for_remove();
+		}
+	});
+	let commands_for_defaults = Rc::clone(&commands);
+	let overrides_for_defaults = Rc::clone(&overrides);
+	defaults_button.on_click(move |_| {
+		overrides_for_defaults.borrow_mut().clear();
+		refresh();
+		if let Some(index) = commands_list.get_selection()
+			&& let Some(command) = usize::try_from(index).ok().and_then(|index| commands_for_defaults.get(index))
+		{
+			shortcut_field.set_value(&command.default_shortcut);
+		}
+	});
+	(panel, overrides)
+}
+
+/// The shortcut a command will have once the dialog is accepted.
+fn effective_shortcut(command: &ShortcutCommand, overrides: &BTreeMap<i32, String>) -> String {
+	overrides.get(&command.id).map_or_else(|| command.default_shortcut.clone(), Clone::clone)
+}
+
+fn shortcut_row_label(command: &ShortcutCommand, shortcut: &str) -> String {
+	if shortcut.is_empty() {
+		// TRANSLATORS: Entry in the Keyboard options list for a command without a shortcut; {} is the command name
+		t("{}: no shortcut").replace("{}", &command.name)
+	} else {
+		// TRANSLATORS: Entry in the Keyboard options list; the first {} is the command name, the second is its shortcut
+		t("{}: {}").replacen("{}", &command.name, 1).replacen("{}", shortcut, 1)
+	}
+}
+
+/// Made-up position used to preview the status bar template.
+fn status_preview_fields() -> StatusFields {
+	StatusFields {
+		line: 12,
+		character: 340,
+		percent: 27,
+		page: Some((4, 120)),
+		page_label: None,
+		// TRANSLATORS: Sample heading path shown in the status bar preview of the Options dialog
+		heading: t("2 Methods > 2.1 Participants"),
+		words_left: Some(9000),
+		reading_words_left: Some(9000),
+		// TRANSLATORS: Sample document title shown in the status bar preview of the Options dialog
+		title: t("Sample Book"),
 	}
 }
 
@@ -483,16 +977,49 @@ fn resolve_options_language(ui: &OptionsDialogUi) -> String {
 		.unwrap_or_else(|| ui.current_language.clone())
 }
 
-fn color_description(color: i32) -> String {
+fn bg_color_description(color: i32) -> String {
 	if color < 0 {
 		// TRANSLATORS: Description text shown when the background color is set to default
 		t("Background: Default")
 	} else {
-		let r = ((color >> 16) & 0xFF) as u8;
-		let g = ((color >> 8) & 0xFF) as u8;
-		let b = (color & 0xFF) as u8;
-		format!("#{r:02X}{g:02X}{b:02X}")
+		// TRANSLATORS: Description of the chosen background color; {} is a hex color such as #1E1E1E
+		t("Background: {}").replace("{}", &hex_color(color))
+	}
+}
+
+fn text_color_description(color: i32) -> String {
+	if color < 0 {
+		// TRANSLATORS: Description text shown when no custom text color is chosen, so the font color is used
+		t("Text: Default")
+	} else {
+		// TRANSLATORS: Description of the chosen text color; {} is a hex color such as #FFFF00
+		t("Text: {}").replace("{}", &hex_color(color))
+	}
+}
+
+fn hex_color(color: i32) -> String {
+	let r = (color >> 16) & 0xFF;
+	let g = (color >> 8) & 0xFF;
+	let b = color & 0xFF;
+	format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+/// Shows the system color picker starting at `current` (a packed `0xRRGGBB`, or -1 for none) and returns the
+/// packed choice.
+fn pick_colour(parent: Dialog, current: i32) -> Option<i32> {
+	let mut dlg = ColourDialog::builder(&parent);
+	if current >= 0 {
+		let r = ((current >> 16) & 0xFF) as u8;
+		let g = ((current >> 8) & 0xFF) as u8;
+		let b = (current & 0xFF) as u8;
+		dlg = dlg.with_initial_colour(Colour::rgb(r, g, b));
+	}
+	let dlg = dlg.build();
+	if dlg.show_modal() != ID_OK {
+		return None;
 	}
+	let c = dlg.get_colour()?;
+	Some((i32::from(c.r) << 16) | (i32::from(c.g) << 8) | i32::from(c.b))
 }
 
 fn font_description(rf: &ReadabilityFont) -> String {
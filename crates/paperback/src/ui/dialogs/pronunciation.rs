@@ -0,0 +1,253 @@
+use std::{cell::RefCell, rc::Rc};
+
+use paperback_core::{config::PronunciationRule, reader_core::pronunciation};
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// The rule lists edited in [`show_pronunciation_rules_dialog`].
+pub struct PronunciationRuleSets {
+	/// Rules for the open document, or `None` when no document is open.
+	pub document: Option<Vec<PronunciationRule>>,
+	pub global: Vec<PronunciationRule>,
+}
+
+/// Lists the pronunciation rules of the open document and those for all documents, with buttons to
+/// add, edit and delete them. Returns the edited lists, or `None` if the user cancelled.
+pub fn show_pronunciation_rules_dialog(
+	parent: &Frame,
+	initial: PronunciationRuleSets,
+) -> Option<PronunciationRuleSets> {
+	// TRANSLATORS: Title of the dialog for editing pronunciation rules
+	let dialog = Dialog::builder(parent, &t("Pronunciation Rules")).build();
+	// TRANSLATORS: Label for the choice between the open document's pronunciation rules and the rules for every document
+	let scope_label_text = t("&Rules for:");
+	let scope_label = StaticText::builder(&dialog).with_label(&scope_label_text).build();
+	let scope_choice = Choice::builder(&dialog).build();
+	let has_document = initial.document.is_some();
+	if has_document {
+		// TRANSLATORS: Choice in the Pronunciation Rules dialog for the rules of the open document
+		scope_choice.append(&t("This document"));
+	}
+	// TRANSLATORS: Choice in the Pronunciation Rules dialog for the rules used in every document
+	scope_choice.append(&t("All documents"));
+	scope_choice.set_selection(0);
+	#[cfg(target_os = "macos")]
+	scope_choice.set_accessibility_label(scope_label_text.replace('&', "").trim_end_matches(':').trim());
+	let rule_list = ListBox::builder(&dialog).with_size(Size::new(420, 220)).build();
+	// TRANSLATORS: Label for the list of pronunciation rules (used only as an accessibility label on macOS)
+	let list_label_text = t("R&ules:");
+	let _list_label = StaticText::builder(&dialog).with_label(&list_label_text).build();
+	#[cfg(target_os = "macos")]
+	rule_list.set_accessibility_label(list_label_text.replace('&', "").trim_end_matches(':').trim());
+	// TRANSLATORS: Button to add a pronunciation rule
+	let add_button = Button::builder(&dialog).with_label(&t("&Add...")).build();
+	// TRANSLATORS: Button to edit the selected pronunciation rule
+	let edit_button = Button::builder(&dialog).with_label(&t("&Edit...")).build();
+	let delete_button = Button::builder(&dialog).with_label(&t("&Delete")).build();
+	// TRANSLATORS: Label for the confirmation button
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	ok_button.set_default();
+
+	let sets: Rc<RefCell<[Vec<PronunciationRule>; 2]>> =
+		Rc::new(RefCell::new([initial.document.unwrap_or_default(), initial.global]));
+	let scope_index = move || {
+		let selection = usize::try_from(scope_choice.get_selection().unwrap_or(0)).unwrap_or(0);
+		if has_document { selection.min(1) } else { 1 }
+	};
+	let selected_rule = move || usize::try_from(rule_list.get_selection().unwrap_or(-1)).ok();
+	let update_buttons = move || {
+		let has_selection = selected_rule().is_some();
+		edit_button.enable(has_selection);
+		delete_button.enable(has_selection);
+	};
+	let sets_for_fill = Rc::clone(&sets);
+	let fill_list = Rc::new(move |select: Option<usize>| {
+		rule_list.clear();
+		let sets = sets_for_fill.borrow();
+		let rules = &sets[scope_index()];
+		for rule in rules {
+			rule_list.append(&rule_label(rule));
+		}
+		if let Some(index) = select.filter(|&index| index < rules.len())
+			&& let Ok(index) = u32::try_from(index)
+		{
+			rule_list.set_selection(index, true);
+		}
+		update_buttons();
+	});
+	fill_list(Some(0));
+	let fill_for_scope = Rc::clone(&fill_list);
+	scope_choice.on_selection_changed(move |_| fill_for_scope(Some(0)));
+	rule_list.on_selection_changed(move |_| update_buttons());
+	let sets_for_add = Rc::clone(&sets);
+	let fill_for_add = Rc::clone(&fill_list);
+	add_button.on_click(move |_| {
+		// TRANSLATORS: Title of the dialog for adding a pronunciation rule
+		if let Some(rule) = show_rule_dialog(&dialog, &t("Add Rule"), &PronunciationRule::default()) {
+			let index = {
+				let mut sets = sets_for_add.borrow_mut();
+				let rules = &mut sets[scope_index()];
+				rules.push(rule);
+				rules.len() - 1
+			};
+			fill_for_add(Some(index));
+		}
+		rule_list.set_focus();
+	});
+	let sets_for_edit = Rc::clone(&sets);
+	let fill_for_edit = Rc::clone(&fill_list);
+	let edit_selected = Rc::new(move || {
+		let Some(index) = selected_rule() else {
+			return;
+		};
+		let Some(existing) = sets_for_edit.borrow()[scope_index()].get(index).cloned() else {
+			return;
+		};
+		// TRANSLATORS: Title of the dialog for editing a pronunciation rule
+		if let Some(rule) = show_rule_dialog(&dialog, &t("Edit Rule"), &existing) {
+			sets_for_edit.borrow_mut()[scope_index()][index] = rule;
+			fill_for_edit(Some(index));
+		}
+		rule_list.set_focus();
+	});
+	let edit_for_button = Rc::clone(&edit_selected);
+	edit_button.on_click(move |_| edit_for_button());
+	rule_list.on_item_double_clicked(move |_| edit_selected());
+	let sets_for_delete = Rc::clone(&sets);
+	delete_button.on_click(move |_| {
+		let Some(index) = selected_rule() else {
+			return;
+		};
+		let next = {
+			let mut sets = sets_for_delete.borrow_mut();
+			let rules = &mut sets[scope_index()];
+			if index >= rules.len() {
+				return;
+			}
+			rules.remove(index);
+			index.min(rules.len().saturating_sub(1))
+		};
+		fill_list(Some(next));
+		rule_list.set_focus();
+	});
+
+	let scope_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	scope_sizer.add(&scope_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	scope_sizer.add(&scope_choice, 1, SizerFlag::Expand, 0);
+	let rule_buttons_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	rule_buttons_sizer.add(&add_button, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	rule_buttons_sizer.add(&edit_button, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	rule_buttons_sizer.add(&delete_button, 0, SizerFlag::AlignCenterVertical, 0);
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add_sizer(&scope_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add(
+		&rule_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	content_sizer.add_sizer(
+		&rule_buttons_sizer,
+		0,
+		SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	rule_list.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let [document, global] = sets.take();
+	Some(PronunciationRuleSets { document: has_document.then_some(document), global })
+}
+
+fn rule_label(rule: &PronunciationRule) -> String {
+	let mut label = format!("{} \u{2192} {}", rule.pattern, rule.replacement);
+	if rule.regex {
+		// TRANSLATORS: Appended to a pronunciation rule whose pattern is a regular expression
+		label = format!("{label} {}", t("(regular expression)"));
+	}
+	if rule.whole_word {
+		// TRANSLATORS: Appended to a pronunciation rule that only matches whole words
+		label = format!("{label} {}", t("(whole word)"));
+	}
+	label
+}
+
+/// Edits one rule, refusing to close on an empty pattern or an invalid regular expression.
+fn show_rule_dialog(parent: &dyn WxWidget, title: &str, existing: &PronunciationRule) -> Option<PronunciationRule> {
+	let dialog = Dialog::builder(parent, title).build();
+	// TRANSLATORS: Label for the text a pronunciation rule looks for, e.g. "et al."
+	let pattern_label = StaticText::builder(&dialog).with_label(&t("&Text to replace:")).build();
+	let pattern_ctrl = TextCtrl::builder(&dialog).with_value(&existing.pattern).with_size(Size::new(300, -1)).build();
+	// TRANSLATORS: Label for what a pronunciation rule's text is spoken as, e.g. "and others"
+	let replacement_label = StaticText::builder(&dialog).with_label(&t("&Speak as:")).build();
+	let replacement_ctrl =
+		TextCtrl::builder(&dialog).with_value(&existing.replacement).with_size(Size::new(300, -1)).build();
+	// TRANSLATORS: Checkbox making a pronunciation rule's text a regular expression
+	let regex_check = CheckBox::builder(&dialog).with_label(&t("Regular e&xpression")).build();
+	regex_check.set_value(existing.regex);
+	// TRANSLATORS: Checkbox limiting a pronunciation rule to whole words
+	let whole_word_check = CheckBox::builder(&dialog).with_label(&t("&Whole word only")).build();
+	whole_word_check.set_value(existing.whole_word);
+	// TRANSLATORS: Label for the confirmation button
+	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	ok_button.set_default();
+	let rule = move || PronunciationRule {
+		pattern: pattern_ctrl.get_value(),
+		replacement: replacement_ctrl.get_value(),
+		regex: regex_check.is_checked(),
+		whole_word: whole_word_check.is_checked(),
+	};
+	ok_button.on_click(move |_| {
+		let candidate = rule();
+		if candidate.pattern.is_empty() {
+			pattern_ctrl.set_focus();
+			return;
+		}
+		if let Err(error) = pronunciation::validate(&candidate) {
+			// TRANSLATORS: Error shown when a pronunciation rule's regular expression is invalid; {} is the error
+			let message = t("The regular expression isn't valid:\n{}").replace("{}", &error.to_string());
+			MessageDialog::builder(&dialog, &message, &t("Error"))
+				.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
+				.build()
+				.show_modal();
+			pattern_ctrl.set_focus();
+			return;
+		}
+		dialog.end_modal(ID_OK);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	for (label, ctrl) in [(&pattern_label, &pattern_ctrl), (&replacement_label, &replacement_ctrl)] {
+		content_sizer.add(label, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Top, DIALOG_PADDING);
+		content_sizer.add(ctrl, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	}
+	content_sizer.add(&regex_check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom, DIALOG_PADDING);
+	content_sizer.add(&whole_word_check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	pattern_ctrl.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	Some(rule())
+}
@@ -0,0 +1,159 @@
+use std::{cell::RefCell, rc::Rc};
+
+use paperback_core::reader_core::{NoteSearchResult, NoteSource, SearchOptions};
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::{DIALOG_PADDING, KEY_NUMPAD_ENTER, KEY_RETURN};
+use crate::translation_manager::plural;
+
+/// Searches the bookmark notes and Document Notes of a document, listing each match with the text
+/// around it. `search` is given the query, its Find options and whether to ignore diacritics, and
+/// returns the matches or the message to show when the query can't be searched for. Returns the
+/// match chosen to go to.
+pub fn show_search_notes_dialog(
+	parent: &Frame,
+	initial_options: SearchOptions,
+	search: impl Fn(&str, SearchOptions, bool) -> Result<Vec<NoteSearchResult>, String> + 'static,
+) -> Option<NoteSearchResult> {
+	// TRANSLATORS: Title of the dialog that searches a document's bookmark notes and Document Notes
+	let title = t("Search Notes");
+	let dialog =
+		Dialog::builder(parent, &title).with_style(DialogStyle::DefaultDialogStyle | DialogStyle::ResizeBorder).build();
+	// TRANSLATORS: Label for the text box holding what to look for in the notes
+	let query_label_text = t("Search &for:");
+	let query_label = StaticText::builder(&dialog).with_label(&query_label_text).build();
+	let query_ctrl = TextCtrl::builder(&dialog).build();
+	#[cfg(target_os = "macos")]
+	query_ctrl.set_accessibility_label(query_label_text.replace('&', "").trim_end_matches(':').trim());
+	// TRANSLATORS: Checkbox in the Search Notes dialog
+	let match_case = CheckBox::builder(&dialog).with_label(&t("&Match case")).build();
+	match_case.set_value(initial_options.contains(SearchOptions::MATCH_CASE));
+	// TRANSLATORS: Checkbox in the Search Notes dialog
+	let whole_word = CheckBox::builder(&dialog).with_label(&t("Match &whole word")).build();
+	whole_word.set_value(initial_options.contains(SearchOptions::WHOLE_WORD));
+	// TRANSLATORS: Checkbox in the Search Notes dialog
+	let use_regex = CheckBox::builder(&dialog).with_label(&t("Use &regular expressions")).build();
+	use_regex.set_value(initial_options.contains(SearchOptions::REGEX));
+	// TRANSLATORS: Checkbox in the Search Notes dialog that lets "cafe" find "café"
+	let ignore_diacritics = CheckBox::builder(&dialog).with_label(&t("&Ignore accents and diacritics")).build();
+	// TRANSLATORS: Label for the button that searches the notes
+	let search_button = Button::builder(&dialog).with_label(&t("&Search")).build();
+	// TRANSLATORS: Label for the list of notes matching a Search Notes query
+	let results_label_text = t("&Results:");
+	let results_label = StaticText::builder(&dialog).with_label(&results_label_text).build();
+	let results_list = ListBox::builder(&dialog).with_size(Size::new(500, 200)).build();
+	#[cfg(target_os = "macos")]
+	results_list.set_accessibility_label(results_label_text.replace('&', "").trim_end_matches(':').trim());
+	let status_label = StaticText::builder(&dialog).with_label("").build();
+	// TRANSLATORS: Label for the button that goes to the selected note search result
+	let go_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("&Go To")).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&t("Cancel")).build();
+	dialog.set_escape_id(ID_CANCEL);
+	go_button.enable(false);
+	let results: Rc<RefCell<Vec<NoteSearchResult>>> = Rc::new(RefCell::new(Vec::new()));
+	let run_search = {
+		let results = Rc::clone(&results);
+		move || {
+			let mut options = SearchOptions::FORWARD;
+			options.set(SearchOptions::MATCH_CASE, match_case.get_value());
+			options.set(SearchOptions::WHOLE_WORD, whole_word.get_value());
+			options.set(SearchOptions::REGEX, use_regex.get_value());
+			results_list.clear();
+			let found = search(&query_ctrl.get_value(), options, ignore_diacritics.get_value());
+			let status = match &found {
+				Ok(found) if found.is_empty() => {
+					// TRANSLATORS: Shown in the Search Notes dialog when no note matches
+					t("No notes match.")
+				}
+				Ok(found) => {
+					// TRANSLATORS: Shown in the Search Notes dialog after searching; %d is the number of matches. Give one variant per plural form of your language, separated by |
+					plural(&t("%d match|%d matches"), u64::try_from(found.len()).unwrap_or(u64::MAX))
+				}
+				Err(message) => message.clone(),
+			};
+			let found = found.unwrap_or_default();
+			for result in &found {
+				results_list.append(&result_label(result));
+			}
+			status_label.set_label(&status);
+			go_button.enable(!found.is_empty());
+			if found.is_empty() {
+				query_ctrl.set_focus();
+			} else {
+				results_list.set_selection(0, true);
+				results_list.set_focus();
+			}
+			*results.borrow_mut() = found;
+		}
+	};
+	search_button.on_click(move |_| run_search());
+	// Enter in the query searches, as the default button; in the results it goes to the selected one.
+	search_button.set_default();
+	let dialog_for_key = dialog;
+	results_list.bind_internal(EventType::KEY_DOWN, move |event| {
+		if let Some(key) = event.get_key_code()
+			&& (key == KEY_RETURN || key == KEY_NUMPAD_ENTER)
+			&& results_list.get_selection().is_some_and(|row| row >= 0)
+		{
+			dialog_for_key.end_modal(ID_OK);
+			event.skip(false);
+			return;
+		}
+		event.skip(true);
+	});
+	let dialog_for_go = dialog;
+	go_button.on_click(move |_| {
+		if results_list.get_selection().is_some_and(|row| row >= 0) {
+			dialog_for_go.end_modal(ID_OK);
+		}
+	});
+	let dialog_for_cancel = dialog;
+	cancel_button.on_click(move |_| {
+		dialog_for_cancel.end_modal(ID_CANCEL);
+	});
+	let dialog_for_double_click = dialog;
+	results_list.on_item_double_clicked(move |_| {
+		dialog_for_double_click.end_modal(ID_OK);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	let query_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	query_sizer.add(&query_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::All, DIALOG_PADDING / 2);
+	query_sizer.add(&query_ctrl, 1, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING / 2);
+	query_sizer.add(&search_button, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add_sizer(&query_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING / 2);
+	for check in [&match_case, &whole_word, &use_regex, &ignore_diacritics] {
+		content_sizer.add(check, 0, SizerFlag::Left | SizerFlag::Right | SizerFlag::Top, DIALOG_PADDING);
+	}
+	content_sizer.add(&results_label, 0, SizerFlag::All, DIALOG_PADDING / 2);
+	content_sizer.add(
+		&results_list,
+		1,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
+	content_sizer.add(&status_label, 0, SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&go_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	query_ctrl.set_focus();
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let row = usize::try_from(results_list.get_selection()?).ok()?;
+	results.borrow().get(row).cloned()
+}
+
+fn result_label(result: &NoteSearchResult) -> String {
+	match result.source {
+		// TRANSLATORS: A Search Notes result found in a bookmark's note; {} is the note text around the match
+		NoteSource::Bookmark { .. } => t("Bookmark: {}").replace("{}", &result.excerpt),
+		// TRANSLATORS: A Search Notes result found in the Document Notes; {} is the note text around the match
+		NoteSource::DocumentNotes => t("Document notes: {}").replace("{}", &result.excerpt),
+	}
+}
@@ -2,8 +2,14 @@ use patois::t;
 use wxdragon::prelude::*;
 
 use super::DIALOG_PADDING;
+use crate::config_ext::SleepAction;
 
-pub fn show_sleep_timer_dialog(parent: &Frame, initial_duration: i32) -> Option<i32> {
+/// Asks for the timer length in minutes and what to do when it runs out.
+pub fn show_sleep_timer_dialog(
+	parent: &Frame,
+	initial_duration: i32,
+	initial_action: SleepAction,
+) -> Option<(i32, SleepAction)> {
 	// TRANSLATORS: Title of the Sleep Timer dialog
 	let dialog = Dialog::builder(parent, &t("Sleep Timer")).build();
 	// TRANSLATORS: Label for the input field where users enter the number of minutes for the sleep timer.
@@ -21,6 +27,18 @@ pub fn show_sleep_timer_dialog(parent: &Frame, initial_duration: i32) -> Option<
 	let input_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	input_sizer.add(&label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 5);
 	input_sizer.add(&input_ctrl, 1, SizerFlag::Expand, 0);
+	// TRANSLATORS: Label for the choice of what Paperback does when the sleep timer runs out
+	let action_label = StaticText::builder(&dialog).with_label(&t("&When time is up:")).build();
+	let action_choice = Choice::builder(&dialog).build();
+	// TRANSLATORS: Sleep timer choice that closes Paperback when time is up
+	action_choice.append(&t("Close Paperback"));
+	// TRANSLATORS: Sleep timer choice that minimizes Paperback instead of closing it when time is up
+	action_choice.append(&t("Minimize Paperback"));
+	let action_index = SleepAction::ALL.iter().position(|&action| action == initial_action).unwrap_or(0);
+	action_choice.set_selection(u32::try_from(action_index).unwrap_or(0));
+	let action_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	action_sizer.add(&action_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, 5);
+	action_sizer.add(&action_choice, 1, SizerFlag::Expand, 0);
 	// TRANSLATORS: Label for the confirmation button
 	let ok_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("OK")).build();
 	// TRANSLATORS: Label for the cancellation button
@@ -29,6 +47,12 @@ pub fn show_sleep_timer_dialog(parent: &Frame, initial_duration: i32) -> Option<
 	ok_button.set_default();
 	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
 	content_sizer.add_sizer(&input_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(
+		&action_sizer,
+		0,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
 	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	button_sizer.add_stretch_spacer(1);
 	button_sizer.add(&ok_button, 0, SizerFlag::All, DIALOG_PADDING);
@@ -37,5 +61,58 @@ pub fn show_sleep_timer_dialog(parent: &Frame, initial_duration: i32) -> Option<
 	dialog.set_sizer_and_fit(content_sizer, true);
 	dialog.centre();
 	input_ctrl.set_focus();
-	if dialog.show_modal() == ID_OK { Some(input_ctrl.value()) } else { None }
+	if dialog.show_modal() != ID_OK {
+		return None;
+	}
+	let action = action_choice
+		.get_selection()
+		.and_then(|index| SleepAction::ALL.get(index as usize).copied())
+		.unwrap_or_default();
+	Some((input_ctrl.value(), action))
+}
+
+/// Shows the non-modal warning offered shortly before the sleep timer runs out. Either button runs
+/// its callback and hides the dialog; the caller owns it and destroys it once the timer is done.
+pub fn show_sleep_timer_warning(
+	parent: &Frame,
+	on_extend: impl Fn() + 'static,
+	on_stop: impl Fn() + 'static,
+) -> Dialog {
+	// TRANSLATORS: Title of the Sleep Timer dialog
+	let dialog = Dialog::builder(parent, &t("Sleep Timer")).build();
+	// TRANSLATORS: Message in the dialog shown shortly before the sleep timer runs out
+	let message = StaticText::builder(&dialog).with_label(&t("The sleep timer ends in 2 minutes.")).build();
+	// TRANSLATORS: Button that adds 10 minutes to a sleep timer that is about to run out
+	let extend_button = Button::builder(&dialog).with_label(&t("&Extend 10 minutes")).build();
+	// TRANSLATORS: Button that cancels a sleep timer that is about to run out
+	let stop_button = Button::builder(&dialog).with_label(&t("&Stop timer")).build();
+	extend_button.set_default();
+	let dialog_for_extend = dialog;
+	extend_button.on_click(move |_| {
+		on_extend();
+		dialog_for_extend.show(false);
+	});
+	let dialog_for_stop = dialog;
+	stop_button.on_click(move |_| {
+		on_stop();
+		dialog_for_stop.show(false);
+	});
+	// Dismissing the warning leaves the timer running.
+	let dialog_for_close = dialog;
+	dialog.on_close(move |event| {
+		dialog_for_close.show(false);
+		event.skip(false);
+	});
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&message, 0, SizerFlag::All, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&extend_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&stop_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	dialog.show(true);
+	extend_button.set_focus();
+	dialog
 }
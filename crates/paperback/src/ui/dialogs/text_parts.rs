@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+/// What to do with a numbered text file that has numbered siblings.
+pub struct TextPartsChoice {
+	pub merge: bool,
+	pub remember: bool,
+}
+
+/// Asks whether to open every part of a multi-part text book or only the chosen file.
+pub fn show_text_parts_dialog(parent: &dyn WxWidget, path: &Path, part_count: usize) -> Option<TextPartsChoice> {
+	// TRANSLATORS: Title of the dialog offering to open a set of numbered text files as one book
+	let title = t("Multi-part Book");
+	let dialog = Dialog::builder(parent, &title).build();
+	// TRANSLATORS: Prompt shown when opening a numbered text file; the first {} is the file name, the second the number of parts found
+	let message_template =
+		t("{} looks like one part of a book split across {} numbered files. Open all parts as one document?");
+	let file_name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string());
+	let message = message_template.replacen("{}", &file_name, 1).replacen("{}", &part_count.to_string(), 1);
+	let label = StaticText::builder(&dialog).with_label(&message).build();
+	// TRANSLATORS: Checkbox in the multi-part book dialog that stops it from asking again
+	let remember_check = CheckBox::builder(&dialog).with_label(&t("&Remember my choice")).build();
+	// TRANSLATORS: Button that opens every numbered part as a single document
+	let all_label = t("Open &All Parts");
+	let all_button = Button::builder(&dialog).with_id(ID_YES).with_label(&all_label).build();
+	all_button.set_default();
+	// TRANSLATORS: Button that opens only the file that was chosen
+	let single_label = t("Open &This Part Only");
+	let single_button = Button::builder(&dialog).with_id(ID_NO).with_label(&single_label).build();
+	// TRANSLATORS: Label for the cancellation button
+	let cancel_label = t("Cancel");
+	let cancel_button = Button::builder(&dialog).with_id(ID_CANCEL).with_label(&cancel_label).build();
+	let dialog_for_all = dialog;
+	all_button.on_click(move |_| {
+		dialog_for_all.end_modal(ID_YES);
+	});
+	let dialog_for_single = dialog;
+	single_button.on_click(move |_| {
+		dialog_for_single.end_modal(ID_NO);
+	});
+	let dialog_for_cancel = dialog;
+	cancel_button.on_click(move |_| {
+		dialog_for_cancel.end_modal(ID_CANCEL);
+	});
+	dialog.set_escape_id(ID_CANCEL);
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&label, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add(&remember_check, 0, SizerFlag::Left | SizerFlag::Right, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&all_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&single_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&cancel_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	all_button.set_focus();
+	match dialog.show_modal() {
+		ID_YES => Some(TextPartsChoice { merge: true, remember: remember_check.is_checked() }),
+		ID_NO => Some(TextPartsChoice { merge: false, remember: remember_check.is_checked() }),
+		_ => None,
+	}
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use patois::t;
+use wxdragon::prelude::*;
+
+use super::DIALOG_PADDING;
+
+const ID_OPEN_FOLDER: i32 = 20_000;
+
+/// What to do with a document Paperback crashed while opening during the previous run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnfinishedDocumentChoice {
+	TryAgain,
+	RemoveFromSession,
+	OpenContainingFolder,
+}
+
+/// Names the document the previous run crashed while opening and asks what to do with it.
+/// Returns `None` when the dialog is dismissed, leaving the question for the next start.
+pub fn show_unfinished_document_dialog(parent: &dyn WxWidget, path: &Path) -> Option<UnfinishedDocumentChoice> {
+	// TRANSLATORS: Title of the dialog shown at startup when Paperback crashed while opening a document
+	let title = t("Document not restored");
+	let dialog = Dialog::builder(parent, &title).build();
+	// TRANSLATORS: Message shown at startup when Paperback crashed while opening a document last time; {} is the document's path
+	let message_template = t(
+		"Paperback closed unexpectedly while opening {} last time, so it wasn't opened again. What would you like to do with it?",
+	);
+	let message = message_template.replace("{}", &path.to_string_lossy());
+	let label = StaticText::builder(&dialog).with_label(&message).build();
+	// TRANSLATORS: Button that opens a document again after Paperback crashed while opening it
+	let try_label = t("&Try Again");
+	let try_button = Button::builder(&dialog).with_id(ID_YES).with_label(&try_label).build();
+	// TRANSLATORS: Button that stops a document Paperback crashed on from being reopened at startup
+	let remove_label = t("&Remove from Session");
+	let remove_button = Button::builder(&dialog).with_id(ID_NO).with_label(&remove_label).build();
+	remove_button.set_default();
+	// TRANSLATORS: Button that shows the folder holding a document Paperback crashed on in the file manager
+	let folder_label = t("Open Containing &Folder");
+	let folder_button = Button::builder(&dialog).with_id(ID_OPEN_FOLDER).with_label(&folder_label).build();
+	let dialog_for_try = dialog;
+	try_button.on_click(move |_| {
+		dialog_for_try.end_modal(ID_YES);
+	});
+	let dialog_for_remove = dialog;
+	remove_button.on_click(move |_| {
+		dialog_for_remove.end_modal(ID_NO);
+	});
+	let dialog_for_folder = dialog;
+	folder_button.on_click(move |_| {
+		dialog_for_folder.end_modal(ID_OPEN_FOLDER);
+	});
+	dialog.set_escape_id(ID_CANCEL);
+	let content_sizer = BoxSizer::builder(Orientation::Vertical).build();
+	content_sizer.add(&label, 0, SizerFlag::All, DIALOG_PADDING);
+	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	button_sizer.add_stretch_spacer(1);
+	button_sizer.add(&try_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&remove_button, 0, SizerFlag::All, DIALOG_PADDING);
+	button_sizer.add(&folder_button, 0, SizerFlag::All, DIALOG_PADDING);
+	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
+	dialog.set_sizer_and_fit(content_sizer, true);
+	dialog.centre();
+	remove_button.set_focus();
+	match dialog.show_modal() {
+		ID_YES => Some(UnfinishedDocumentChoice::TryAgain),
+		ID_NO => Some(UnfinishedDocumentChoice::RemoveFromSession),
+		ID_OPEN_FOLDER => Some(UnfinishedDocumentChoice::OpenContainingFolder),
+		_ => None,
+	}
+}
@@ -2,15 +2,38 @@ use patois::t;
 use wxdragon::prelude::*;
 
 use super::DIALOG_PADDING;
+use crate::ui::document_manager::{ReadingColors, apply_reading_colors};
 
-pub fn show_view_note_dialog(parent: &dyn WxWidget, note_text: &str) {
+pub fn show_view_note_dialog(parent: &dyn WxWidget, note_text: &str, colors: ReadingColors) {
 	// TRANSLATORS: Title of the View Note dialog
-	let dialog = Dialog::builder(parent, &t("View Note")).build();
+	show_text_dialog(parent, &t("View Note"), note_text, colors);
+}
+
+/// Shows read-only text, such as a note or a dictionary definition, in the reading colors.
+pub fn show_text_dialog(parent: &dyn WxWidget, title: &str, text: &str, colors: ReadingColors) {
+	show_text_dialog_with_url(parent, title, text, None, colors);
+}
+
+/// Shows where a link leads without following it: the target's text, or for a web link its address
+/// with a button to open it in the browser.
+pub fn show_link_preview_dialog(
+	parent: &dyn WxWidget,
+	title: &str,
+	text: &str,
+	url: Option<&str>,
+	colors: ReadingColors,
+) {
+	show_text_dialog_with_url(parent, title, text, url, colors);
+}
+
+fn show_text_dialog_with_url(parent: &dyn WxWidget, title: &str, text: &str, url: Option<&str>, colors: ReadingColors) {
+	let dialog = Dialog::builder(parent, title).build();
 	let note_ctrl = TextCtrl::builder(&dialog)
-		.with_value(note_text)
+		.with_value(text)
 		.with_style(TextCtrlStyle::MultiLine | TextCtrlStyle::ReadOnly | TextCtrlStyle::Rich2)
 		.with_size(Size::new(400, 200))
 		.build();
+	apply_reading_colors(&note_ctrl, colors);
 	let close_button = Button::builder(&dialog).with_id(ID_OK).with_label(&t("Close")).build();
 	dialog.set_affirmative_id(ID_OK);
 	let dialog_for_close = dialog;
@@ -21,6 +44,17 @@ pub fn show_view_note_dialog(parent: &dyn WxWidget, note_text: &str) {
 	content_sizer.add(&note_ctrl, 1, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
 	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	button_sizer.add_stretch_spacer(1);
+	if let Some(url) = url {
+		// TRANSLATORS: Button in the link preview that opens a web link in the default browser
+		let open_button = Button::builder(&dialog).with_label(&t("&Open in browser")).build();
+		let url = url.to_string();
+		let dialog_for_open = dialog;
+		open_button.on_click(move |_| {
+			launch_default_browser(&url, BrowserLaunchFlags::Default);
+			dialog_for_open.end_modal(ID_OK);
+		});
+		button_sizer.add(&open_button, 0, SizerFlag::All, DIALOG_PADDING);
+	}
 	button_sizer.add(&close_button, 0, SizerFlag::All, DIALOG_PADDING);
 	content_sizer.add_sizer(&button_sizer, 0, SizerFlag::Expand, 0);
 	dialog.set_sizer_and_fit(content_sizer, true);
@@ -2,19 +2,21 @@ use patois::t;
 use wxdragon::{event::WebViewEvents, prelude::*, widgets::WebView};
 
 type NavigationHandler = Box<dyn Fn(&str) -> bool>;
-use std::cell::Cell;
+use std::{borrow::Cow, cell::Cell};
 
 thread_local! {
 	pub static ACTIVE_WEB_VIEW: Cell<Option<Dialog>> = const { Cell::new(None) };
 }
 
+/// Shows the web view modally and returns the URL it was showing when closed, so callers can
+/// follow internal links the user clicked.
 pub fn show_web_view_dialog(
 	parent: &Frame,
 	title: &str,
 	url_or_content: &str,
 	is_url: bool,
 	navigation_handler: Option<NavigationHandler>,
-) {
+) -> String {
 	let dialog = Dialog::builder(parent, title)
 		.with_style(DialogStyle::DefaultDialogStyle | DialogStyle::ResizeBorder)
 		.with_size(800, 600)
@@ -41,10 +43,12 @@ pub fn show_web_view_dialog(
 	if is_url {
 		web_view.load_url(url_or_content);
 	} else {
-		let full_html = if url_or_content.to_lowercase().contains("<html") {
-			url_or_content.to_string()
+		// Checked without lowercasing a copy of what may be a whole book.
+		let is_full_page = url_or_content.as_bytes().windows(5).any(|window| window.eq_ignore_ascii_case(b"<html"));
+		let full_html = if is_full_page {
+			Cow::Borrowed(url_or_content)
 		} else {
-			format!("<html><head><title>{title}</title></head><body>{url_or_content}</body></html>")
+			Cow::Owned(format!("<html><head><title>{title}</title></head><body>{url_or_content}</body></html>"))
 		};
 		web_view.set_page(&full_html, "");
 	}
@@ -73,6 +77,8 @@ pub fn show_web_view_dialog(
 	dialog.set_sizer(sizer, true);
 	dialog.centre();
 	dialog.show_modal();
+	let final_url = web_view.get_current_url();
 
 	ACTIVE_WEB_VIEW.with(|v| v.set(None));
+	final_url
 }
@@ -1,35 +1,29 @@
+use paperback_core::document::DocumentStats;
 use patois::t;
 use wxdragon::prelude::*;
 
-fn format_reading_time(word_count: usize, wpm: i32) -> String {
+use crate::translation_manager::plural;
+
+fn format_reading_time(reading_words: usize, wpm: i32) -> String {
 	if wpm <= 0 {
 		return String::new();
 	}
-	let total_seconds = (word_count as f64 / f64::from(wpm) * 60.0).round() as u64;
+	let total_seconds = (reading_words as f64 / f64::from(wpm) * 60.0).round() as u64;
 	let hours = total_seconds / 3600;
 	let minutes = (total_seconds % 3600) / 60;
 	let seconds = total_seconds % 60;
 	let mut parts: Vec<String> = Vec::new();
-	if hours == 1 {
-		// TRANSLATORS: Singular label for 1 hour duration
-		parts.push(t("1 hour"));
-	} else if hours > 1 {
-		// TRANSLATORS: Plural label for hours duration (e.g. "2 hours")
-		parts.push(format!("{} {}", hours, t("hours")));
+	if hours > 0 {
+		// TRANSLATORS: Hours part of a duration, e.g. "2 hours"; %d is the number of hours. Give one variant per plural form of your language, separated by |
+		parts.push(plural(&t("%d hour|%d hours"), hours));
 	}
-	if minutes == 1 {
-		// TRANSLATORS: Singular label for 1 minute duration
-		parts.push(t("1 minute"));
-	} else if minutes > 1 {
-		// TRANSLATORS: Plural label for minutes duration (e.g. "2 minutes")
-		parts.push(format!("{} {}", minutes, t("minutes")));
+	if minutes > 0 {
+		// TRANSLATORS: Minutes part of a duration, e.g. "5 minutes"; %d is the number of minutes. Give one variant per plural form of your language, separated by |
+		parts.push(plural(&t("%d minute|%d minutes"), minutes));
 	}
-	if seconds == 1 {
-		// TRANSLATORS: Singular label for 1 second duration
-		parts.push(t("1 second"));
-	} else if seconds > 1 || total_seconds == 0 {
-		// TRANSLATORS: Plural label for seconds duration (e.g. "2 seconds")
-		parts.push(format!("{} {}", seconds, t("seconds")));
+	if seconds > 0 || total_seconds == 0 {
+		// TRANSLATORS: Seconds part of a duration, e.g. "30 seconds"; %d is the number of seconds. Give one variant per plural form of your language, separated by |
+		parts.push(plural(&t("%d second|%d seconds"), seconds));
 	}
 	let time_str = parts.join(", ");
 	// TRANSLATORS: Prompt showing estimated reading time. The {} placeholder is replaced with a formatted duration like "1 hour, 5 minutes".
@@ -37,16 +31,16 @@ fn format_reading_time(word_count: usize, wpm: i32) -> String {
 	template.replace("{}", &time_str)
 }
 
-pub fn show_word_count_dialog(parent: &Frame, word_count: usize, reading_speed_wpm: i32, is_selection: bool) {
+pub fn show_word_count_dialog(parent: &Frame, stats: &DocumentStats, reading_speed_wpm: i32, is_selection: bool) {
 	let words_template = if is_selection {
-		// TRANSLATORS: Message template for selection word count. The {} placeholder is replaced with the number of words.
-		t("The selection contains {} words.")
+		// TRANSLATORS: Message for the selection's word count; %d is the number of words. Give one variant per plural form of your language, separated by |
+		t("The selection contains %d word.|The selection contains %d words.")
 	} else {
-		// TRANSLATORS: Message template for document word count. The {} placeholder is replaced with the number of words.
-		t("This document contains {} words.")
+		// TRANSLATORS: Message for the document's word count; %d is the number of words. Give one variant per plural form of your language, separated by |
+		t("This document contains %d word.|This document contains %d words.")
 	};
-	let mut msg = words_template.replace("{}", &word_count.to_string());
-	let reading_time = format_reading_time(word_count, reading_speed_wpm);
+	let mut msg = plural(&words_template, u64::try_from(stats.word_count).unwrap_or(u64::MAX));
+	let reading_time = format_reading_time(stats.reading_words, reading_speed_wpm);
 	if !reading_time.is_empty() {
 		msg.push('\n');
 		msg.push_str(&reading_time);
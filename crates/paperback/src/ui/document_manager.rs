@@ -1,19 +1,29 @@
-#[cfg(target_os = "linux")]
-use std::collections::HashMap;
 #[cfg(target_os = "windows")]
 use std::ptr::{addr_of_mut, copy_nonoverlapping};
 use std::{
-	cell::Cell,
+	cell::{Cell, Ref, RefCell},
+	collections::HashMap,
 	path::{Path, PathBuf},
 	rc::Rc,
-	sync::{Mutex, atomic::Ordering},
-	time::Instant,
+	sync::Mutex,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use paperback_core::{
-	config::{ConfigManager, ReadabilityFont},
-	parser::PASSWORD_REQUIRED_ERROR_PREFIX,
+	config::{Bookmark, ConfigManager, ReadabilityFont, SidecarSync},
+	document::{
+		DEFAULT_MAX_DETECTED_HEADINGS, LineLayout, MarkerType, ParserContext, StructureRegion, StructureTransition,
+		TextDirection, structure_transition,
+	},
+	parser::{
+		clipboard::{clipboard_path, is_clipboard_path},
+		error::ParserError,
+		parser_supports_extension,
+		text::{TEXT_PARTS_FORMAT, text_parts},
+	},
+	reader_core::bookmark_ranges::BookmarkRanges,
 	session::DocumentSession,
+	util::{path::document_path_key, text::ellipsize_words},
 };
 use patois::t;
 use wxdragon::{
@@ -22,11 +32,19 @@ use wxdragon::{
 	prelude::*,
 };
 
+#[cfg(target_os = "windows")]
+use super::reading_structure;
 #[cfg(target_os = "windows")]
 use super::rtf_write::{self, RtfFontInfo};
 use super::{
-	main_window::{SLEEP_TIMER_DURATION_MINUTES, SLEEP_TIMER_START_MS},
-	menu_ids, status,
+	dialogs::{
+		OpenAsChoice, show_archive_entry_dialog, show_load_error_dialog, show_open_as_dialog, show_text_parts_dialog,
+	},
+	find::{self, FindHighlights},
+	menu_ids, speech, status, url_download,
+};
+use crate::config_ext::{
+	ColorScheme, auto_sync_sidecar, get_color_scheme, get_text_color, is_auto_sync_sidecar_enabled,
 };
 
 pub struct DocumentTab {
@@ -35,6 +53,81 @@ pub struct DocumentTab {
 	pub session: DocumentSession,
 	pub file_path: PathBuf,
 	pub track: bool,
+	/// Position restored from the previous session, and whether the document changed length since.
+	pub last_read: Option<(i64, bool)>,
+	/// Section shown in the tab label and window title, empty when none is.
+	pub shown_section: RefCell<String>,
+	/// Whether the document, restored from the previous session, hasn't been parsed yet. Its session
+	/// is an empty placeholder until `DocumentManager::load_pending_tab` reads it.
+	pub pending: bool,
+	/// Matches painted by Find's "Highlight all matches", kept to repaint a rebuilt control.
+	pub find_highlights: FindHighlights,
+	/// Layout detected from the document's lines when it was parsed.
+	pub line_layout: LineLayout,
+	/// Whether the text control wraps lines, which only rebuilding it can change.
+	pub word_wrap: bool,
+	/// The document's bookmarks laid out for caret tracking, as of `bookmarks_revision`.
+	bookmark_ranges: RefCell<BookmarkRanges>,
+	bookmarks_revision: Cell<Option<u64>>,
+}
+
+impl DocumentTab {
+	/// The document's bookmarks laid out for caret tracking, rebuilt first if any bookmark changed
+	/// since they were last read.
+	fn bookmark_ranges(&self, config: &ConfigManager) -> Ref<'_, BookmarkRanges> {
+		let revision = config.bookmarks_revision();
+		if self.bookmarks_revision.get() != Some(revision) {
+			*self.bookmark_ranges.borrow_mut() =
+				BookmarkRanges::new(config.get_bookmarks(&self.file_path.to_string_lossy()));
+			self.bookmarks_revision.set(Some(revision));
+		}
+		self.bookmark_ranges.borrow()
+	}
+
+	/// Saves `position` as where reading stopped, along with the text there once the whole document
+	/// is loaded, so the spot can be found again if a later version extracts the text differently.
+	pub fn save_position(&self, config: &ConfigManager, position: i64) {
+		let path_str = self.file_path.to_string_lossy();
+		config.set_document_position(&path_str, position);
+		if self.session.is_fully_loaded() {
+			config.set_document_anchor(&path_str, self.session.position_anchor(position));
+		}
+	}
+
+	/// Appends the pages the background loader has read since the last call.
+	pub fn sync_loaded_pages(&mut self) -> bool {
+		let text = self.session.apply_loaded_pages();
+		self.append_loaded_text(text)
+	}
+
+	/// Reads every page up to and including `page` before returning.
+	pub fn load_through_page(&mut self, page: i32) {
+		let text = self.session.load_through_page(page);
+		self.append_loaded_text(text);
+	}
+
+	/// Reads pages until `position` is inside the loaded text.
+	pub fn load_through_position(&mut self, position: i64) {
+		let text = self.session.load_through_position(position);
+		self.append_loaded_text(text);
+	}
+
+	pub fn load_all_pages(&mut self) {
+		let text = self.session.load_all_pages();
+		self.append_loaded_text(text);
+	}
+
+	/// Appending moves the caret to the end, so the selection is put back afterwards.
+	fn append_loaded_text(&self, text: Option<String>) -> bool {
+		let Some(text) = text else {
+			return false;
+		};
+		let (start, end) = self.text_ctrl.get_selection();
+		self.text_ctrl.append_text(&text);
+		self.text_ctrl.set_selection(start, end);
+		self.text_ctrl.show_position(start);
+		true
+	}
 }
 
 pub fn title_or_filename(title: String, path: &Path) -> String {
@@ -49,7 +142,56 @@ pub fn display_title(tab: &DocumentTab) -> String {
 	title_or_filename(tab.session.title(), &tab.file_path)
 }
 
+fn window_title(tab: &DocumentTab, section: &str) -> String {
+	let title = if section.is_empty() {
+		display_title(tab)
+	} else {
+		// TRANSLATORS: Document title followed by the chapter the caret is in, in the window title; the first {} is the book, the second the chapter
+		t("{} \u{2014} {}").replacen("{}", &display_title(tab), 1).replacen("{}", section, 1)
+	};
+	// TRANSLATORS: Window title when a document is open; {} is the document title
+	t("Paperback - {}").replace("{}", &title)
+}
+
+fn tab_label(tab: &DocumentTab, section: &str) -> String {
+	if section.is_empty() {
+		return display_title(tab);
+	}
+	format!("{} \u{00B7} {section}", ellipsize_words(&display_title(tab), MAX_SECTION_TITLE_CHARS))
+}
+
 const POSITION_SAVE_INTERVAL_SECS: u64 = 3;
+/// Headings (and book titles next to them in a tab label) are shortened to about this many characters.
+const MAX_SECTION_TITLE_CHARS: usize = 40;
+/// Longest stretch of a bookmark's note or text spoken when the caret moves into it.
+const BOOKMARK_SNIPPET_CHARS: usize = 120;
+/// Reading time stops accumulating once there has been no caret movement or key press for this long.
+const READING_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Minimum gap between two structure announcements, so holding an arrow key through a run of short
+/// lists does not queue up a stream of speech.
+const STRUCTURE_ANNOUNCEMENT_INTERVAL: Duration = Duration::from_millis(400);
+/// PDFs longer than this open after reading only their first pages; the rest are read in the
+/// background as the caret gets near them.
+const LAZY_PDF_PAGE_LIMIT: usize = 50;
+/// Pages read per step when a whole PDF is loaded with progress in the status bar.
+const PAGE_LOAD_STEP: usize = 25;
+/// Auto-scroll speed in lines per minute: the default, the `+`/`-` step, and the allowed range.
+const AUTO_SCROLL_DEFAULT_SPEED: i32 = 30;
+const AUTO_SCROLL_SPEED_STEP: i32 = 5;
+const AUTO_SCROLL_MIN_SPEED: i32 = 5;
+const AUTO_SCROLL_MAX_SPEED: i32 = 300;
+/// Line length below which most of a document's lines must fall for it to be shown preformatted.
+const DEFAULT_PREFORMATTED_LINE_LENGTH: i32 = 80;
+const KEY_PLUS: i32 = 43;
+const KEY_MINUS: i32 = 45;
+/// Key codes seen on key up for `+` and `-`: the characters themselves, `=` (shifted to `+` on
+/// many layouts) and the numpad keys.
+const AUTO_SCROLL_SPEED_KEYS: [i32; 5] = [KEY_PLUS, KEY_MINUS, 61, 388, 390];
+const WXK_SHIFT: i32 = 306;
+const WXK_ALT: i32 = 307;
+const WXK_CONTROL: i32 = 308;
+/// End, Home, Left, Up, Right, Down, Page Up and Page Down.
+const CARET_MOVEMENT_KEYS: [i32; 8] = [312, 313, 314, 315, 316, 317, 366, 367];
 const WXK_F10: i32 = 349;
 const WXK_WINDOWS_MENU: i32 = 395;
 #[cfg(target_os = "windows")]
@@ -57,6 +199,51 @@ const WXK_UP: i32 = 315;
 #[cfg(target_os = "windows")]
 const WXK_DOWN: i32 = 317;
 
+/// Measures how long the focused document is actually being read. Time between two activity
+/// events counts in full up to `READING_IDLE_TIMEOUT`, so a document left open overnight only
+/// gains a few minutes.
+#[derive(Default)]
+struct ReadingClock {
+	path: Option<PathBuf>,
+	last_activity: Option<Instant>,
+	pending: Duration,
+}
+
+impl ReadingClock {
+	/// Starts timing `path`, returning the time accumulated for the previously timed document.
+	fn start(&mut self, path: &Path, now: Instant) -> Option<(PathBuf, Duration)> {
+		if self.path.as_deref() == Some(path) {
+			self.touch(now);
+			return None;
+		}
+		let previous = self.stop(now);
+		self.path = Some(path.to_path_buf());
+		self.last_activity = Some(now);
+		previous
+	}
+
+	fn touch(&mut self, now: Instant) {
+		if let Some(last) = self.last_activity {
+			self.pending += now.saturating_duration_since(last).min(READING_IDLE_TIMEOUT);
+			self.last_activity = Some(now);
+		}
+	}
+
+	/// Takes the time accumulated so far without stopping the clock.
+	fn take(&mut self, now: Instant) -> Option<(PathBuf, Duration)> {
+		self.touch(now);
+		let path = self.path.clone()?;
+		Some((path, std::mem::take(&mut self.pending)))
+	}
+
+	fn stop(&mut self, now: Instant) -> Option<(PathBuf, Duration)> {
+		let taken = self.take(now);
+		self.path = None;
+		self.last_activity = None;
+		taken
+	}
+}
+
 pub struct DocumentManager {
 	frame: Frame,
 	notebook: Notebook,
@@ -65,10 +252,19 @@ pub struct DocumentManager {
 	live_region_label: StaticText,
 	last_position_save: Cell<Option<Instant>>,
 	last_sound_position: Cell<Option<i64>>,
+	/// Region the caret was last announced in (or silently moved to), for context announcements.
+	announced_structure: Cell<Option<StructureRegion>>,
+	last_structure_announcement: Cell<Option<Instant>>,
+	/// Whether auto-scroll is running. The timer driving it lives in `MainWindow` and stops itself
+	/// on the next tick once this is cleared.
+	auto_scrolling: Cell<bool>,
 	preferred_column: Cell<Option<i64>>,
 	recently_closed: Vec<PathBuf>,
+	reading_clock: RefCell<ReadingClock>,
+	/// Caret position last written by `autosave_positions`, per document, so unchanged tabs are skipped.
+	autosaved_positions: RefCell<HashMap<PathBuf, i64>>,
 	#[cfg(target_os = "linux")]
-	navigation_key_map: Rc<HashMap<(i32, bool), i32>>,
+	navigation_key_map: Rc<RefCell<HashMap<(i32, bool), i32>>>,
 }
 
 impl DocumentManager {
@@ -86,28 +282,67 @@ impl DocumentManager {
 			live_region_label,
 			last_position_save: Cell::new(None),
 			last_sound_position: Cell::new(None),
+			announced_structure: Cell::new(None),
+			last_structure_announcement: Cell::new(None),
+			auto_scrolling: Cell::new(false),
 			preferred_column: Cell::new(None),
 			recently_closed: Vec::new(),
+			reading_clock: RefCell::new(ReadingClock::default()),
+			autosaved_positions: RefCell::new(HashMap::new()),
 			#[cfg(target_os = "linux")]
-			navigation_key_map: Rc::new(build_navigation_key_map()),
+			navigation_key_map: Rc::new(RefCell::new(build_navigation_key_map())),
 		}
 	}
 
 	pub fn open_file(&mut self, self_rc: &Rc<Mutex<Self>>, path: &Path) -> bool {
-		self.open_file_impl(self_rc, path, true, false, None)
-	}
-
-	pub fn open_file_restore(&mut self, self_rc: &Rc<Mutex<Self>>, path: &Path) -> bool {
-		self.open_file_impl(self_rc, path, true, true, None)
+		self.open_file_impl(self_rc, path, true, None)
 	}
 
 	pub fn open_help_file(&mut self, self_rc: &Rc<Mutex<Self>>, path: &Path) -> bool {
-		self.open_file_impl(self_rc, path, false, false, None)
+		self.open_file_impl(self_rc, path, false, None)
 	}
 
 	/// Opens a synthetic source-view document (untracked) with an explicit tab title.
 	pub fn open_source_file(&mut self, self_rc: &Rc<Mutex<Self>>, path: &Path, title: &str) -> bool {
-		self.open_file_impl(self_rc, path, false, false, Some(title))
+		self.open_file_impl(self_rc, path, false, Some(title))
+	}
+
+	/// Opens clipboard contents as a new document under a `clipboard://` pseudo-path, so positions
+	/// and bookmarks work as usual while it stays open.
+	pub fn open_clipboard(&mut self, self_rc: &Rc<Mutex<Self>>, text: &str, html: Option<&str>) -> bool {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(0));
+		let base_path = clipboard_path(now);
+		let mut path_str = base_path.clone();
+		let mut suffix = 2;
+		while self.find_tab_by_path(Path::new(&path_str)).is_some() {
+			path_str = format!("{base_path}-{suffix}");
+			suffix += 1;
+		}
+		let context = document_parser_context(&self.config.lock().unwrap(), &path_str);
+		tracing::info!(path = %path_str, "opening clipboard contents");
+		match DocumentSession::from_clipboard(&context, text, html) {
+			Ok(session) => self.add_session_tab(self_rc, Path::new(&path_str), session, "", true, None),
+			Err(err) => {
+				show_error_dialog(&self.notebook, &err.to_string(), &t("Error"));
+				false
+			}
+		}
+	}
+
+	/// Opens the downloaded copy of `url` under the address itself, so bookmarks and the reading
+	/// position carry over when the link is downloaded again.
+	pub fn open_url(&mut self, self_rc: &Rc<Mutex<Self>>, url: &str) -> bool {
+		let path = Path::new(url);
+		if let Some(index) = self.find_tab_by_path(path) {
+			self.focus_open_tab(index);
+			return true;
+		}
+		let context = document_parser_context(&self.config.lock().unwrap(), url);
+		tracing::info!(url = %url, file = %context.file_path, "opening downloaded document");
+		let Some((session, password)) = self.parse_session(path, context) else {
+			return false;
+		};
+		self.add_session_tab(self_rc, path, session, &password, true, None)
 	}
 
 	fn open_file_impl(
@@ -115,7 +350,6 @@ impl DocumentManager {
 		self_rc: &Rc<Mutex<Self>>,
 		path: &Path,
 		track: bool,
-		is_restore: bool,
 		title_override: Option<&str>,
 	) -> bool {
 		if !path.exists() {
@@ -126,13 +360,24 @@ impl DocumentManager {
 			show_error_dialog(&self.notebook, &message, &t("Error"));
 			return false;
 		}
+		// Checked before the text parts prompt too, so reopening a document never asks about it.
 		if let Some(index) = self.find_tab_by_path(path) {
-			self.notebook.set_selection(index);
+			self.focus_open_tab(index);
+			return true;
+		}
+		let parts_path = if track { self.resolve_text_parts(path) } else { Some(path.to_path_buf()) };
+		let Some(parts_path) = parts_path else {
+			return false;
+		};
+		let path = parts_path.as_path();
+		if let Some(index) = self.find_tab_by_path(path) {
+			self.focus_open_tab(index);
 			return true;
 		}
 
 		let import_path = path.with_extension("paperback");
-		if !is_restore && import_path.exists() {
+		let auto_sync = track && is_auto_sync_sidecar_enabled(&self.config.lock().unwrap());
+		if !auto_sync && import_path.exists() {
 			// TRANSLATORS: Prompt asking whether to import a document's previously saved settings and bookmarks found alongside it
 			let message = t("A .paperback file was found for this document. Would you like to import it?");
 			// TRANSLATORS: Title of the dialog prompting to import a document's saved settings and bookmarks
@@ -146,48 +391,113 @@ impl DocumentManager {
 			}
 		}
 
-		let (password, forced_extension, render_tables_inline) = {
+		let path_str = path.to_string_lossy().to_string();
+		let context = {
 			let config = self.config.lock().unwrap();
-			let path_str = path.to_string_lossy();
 			config.refresh_document_hash(&path_str);
-			let forced_extension = config.get_document_format(&path_str);
-			let password = config.get_document_password(&path_str);
-			let render_tables_inline = config.get_app_bool("render_tables_inline", true);
-			drop(config);
-			(password, forced_extension, render_tables_inline)
+			document_parser_context(&config, &path_str)
 		};
-		let path_str = path.to_string_lossy().to_string();
 		tracing::info!(path = %path.display(), "opening document");
-		match DocumentSession::new(&path_str, &password, &forced_extension, render_tables_inline) {
-			Ok(session) => self.add_session_tab(self_rc, path, session, &password, track, title_override),
-			Err(err) => {
-				if err.starts_with(PASSWORD_REQUIRED_ERROR_PREFIX) {
-					let config = self.config.lock().unwrap();
-					config.set_document_password(&path_str, "");
-					drop(config);
-					let password = prompt_for_password(&self.notebook);
-					let Some(password) = password else {
+		let Some((session, password)) = self.parse_session(path, context) else {
+			return false;
+		};
+		self.add_session_tab(self_rc, path, session, &password, track, title_override)
+	}
+
+	/// Parses the document at `path`, asking for a password or archive entry when the parser needs
+	/// one. Returns the session and the password it was opened with, or `None` once the user has been
+	/// told why it couldn't be opened.
+	fn parse_session(&self, path: &Path, mut context: ParserContext) -> Option<(DocumentSession, String)> {
+		let path_str = path.to_string_lossy().to_string();
+		let mut asked_password = false;
+		let mut asked_entry = false;
+		loop {
+			match self.parse_marked(&path_str, &context) {
+				Ok(session) => {
+					let password = context.password.clone().unwrap_or_default();
+					return Some((session, password));
+				}
+				Err(ParserError::PasswordRequired(_)) if !asked_password => {
+					asked_password = true;
+					self.config.lock().unwrap().set_document_password(&path_str, "");
+					let Some(password) = prompt_for_password(&self.notebook) else {
 						// TRANSLATORS: Error shown when the user dismisses the password prompt for an encrypted document without entering one
 						show_error_dialog(&self.notebook, &t("Password is required."), &t("Error"));
-						return false;
+						return None;
 					};
-					match DocumentSession::new(&path_str, &password, &forced_extension, render_tables_inline) {
-						Ok(session) => self.add_session_tab(self_rc, path, session, &password, track, title_override),
-						Err(retry_error) => {
-							tracing::error!(path = %path.display(), error = %retry_error, "failed to open document");
-							let message = build_document_load_error_message(path, &retry_error);
-							show_error_dialog(&self.notebook, &message, &t("Error"));
-							false
-						}
-					}
-				} else {
+					context = context.with_password(password);
+				}
+				Err(ParserError::ArchiveEntryRequired(entries)) if !asked_entry => {
+					asked_entry = true;
+					let entry = show_archive_entry_dialog(&self.notebook, path, &entries)?;
+					self.config.lock().unwrap().set_document_archive_entry(&path_str, &entry);
+					context = context.with_archive_entry(entry);
+				}
+				Err(err) if opened_as_unknown_type(path, &context, &err) => {
+					tracing::warn!(path = %path.display(), error = %err, "forced format failed to open document");
+					let (summary, _) = document_load_error_text(path, &err);
+					let previous = OpenAsChoice {
+						format: context.forced_extension.clone().unwrap_or_default(),
+						encoding: context.encoding.clone().unwrap_or_default(),
+					};
+					let choice = show_open_as_dialog(&self.notebook, path, Some(&previous), Some(&summary))?;
+					let config = self.config.lock().unwrap();
+					config.set_document_format(&path_str, &choice.format);
+					config.set_document_encoding(&path_str, &choice.encoding);
+					drop(config);
+					context.encoding = Some(choice.encoding).filter(|encoding| !encoding.is_empty());
+					context = context.with_forced_extension(choice.format);
+				}
+				Err(err) => {
 					tracing::error!(path = %path.display(), error = %err, "failed to open document");
-					let message = build_document_load_error_message(path, &err);
-					show_error_dialog(&self.notebook, &message, &t("Error"));
-					false
+					show_document_load_error(&self.notebook, path, &err);
+					return None;
+				}
+			}
+		}
+	}
+
+	/// Parses the document once, without asking the user anything.
+	fn parse_marked(&self, path_str: &str, context: &ParserContext) -> Result<DocumentSession, ParserError> {
+		// Marked on disk first, so a parser crash on this document doesn't reopen it at every start.
+		self.config.lock().unwrap().begin_parsing_document(path_str);
+		let result = DocumentSession::from_context(context);
+		self.config.lock().unwrap().finish_parsing_document();
+		result
+	}
+
+	/// Offers to open a numbered text file together with its numbered siblings, keyed on the first
+	/// part. Returns the path to open, or `None` if the user cancelled. A per-document format chosen
+	/// through Open As (or an earlier merge) is respected without asking.
+	fn resolve_text_parts(&self, path: &Path) -> Option<PathBuf> {
+		let parts = text_parts(path);
+		let Some(first) = parts.first() else {
+			return Some(path.to_path_buf());
+		};
+		let mode = {
+			let config = self.config.lock().unwrap();
+			if !config.get_document_format(&path.to_string_lossy()).is_empty() {
+				return Some(path.to_path_buf());
+			}
+			config.get_app_string("text_parts_mode", "ask")
+		};
+		let merge = match mode.as_str() {
+			"always" => true,
+			"never" => false,
+			_ => {
+				let choice = show_text_parts_dialog(&self.notebook, path, parts.len())?;
+				if choice.remember {
+					let mode = if choice.merge { "always" } else { "never" };
+					self.config.lock().unwrap().set_app_string("text_parts_mode", mode);
 				}
+				choice.merge
 			}
+		};
+		if !merge {
+			return Some(path.to_path_buf());
 		}
+		self.config.lock().unwrap().set_document_format(&first.to_string_lossy(), TEXT_PARTS_FORMAT);
+		Some(first.clone())
 	}
 
 	pub fn add_session_tab(
@@ -200,14 +510,139 @@ impl DocumentManager {
 		title_override: Option<&str>,
 	) -> bool {
 		if let Some(index) = self.find_tab_by_path(path) {
-			self.notebook.set_selection(index);
+			self.focus_open_tab(index);
 			return true;
 		}
 		let title = title_override.map_or_else(|| title_or_filename(session.title(), path), ToString::to_string);
+		let (line_layout, word_wrap) = {
+			let config = self.config.lock().unwrap();
+			let line_layout = detect_line_layout(&config, &session);
+			(line_layout, word_wrap_for(&config, path, line_layout))
+		};
+		let (panel, text_ctrl) = self.build_tab_page(self_rc, word_wrap);
+		fill_tab_text(text_ctrl, &session, &self.config.lock().unwrap());
+		self.notebook.add_page(&panel, &title, true, None);
+		self.tabs.push(DocumentTab {
+			panel,
+			text_ctrl,
+			session,
+			file_path: path.to_path_buf(),
+			track,
+			last_read: None,
+			shown_section: RefCell::new(String::new()),
+			pending: false,
+			find_highlights: FindHighlights::default(),
+			line_layout,
+			word_wrap,
+			bookmark_ranges: RefCell::default(),
+			bookmarks_revision: Cell::new(None),
+		});
+		self.finish_opening_tab(self.tabs.len() - 1, password);
+		true
+	}
+
+	/// Adds a tab for a document restored from the previous session without parsing it, labelled
+	/// with the title it was last opened under. It's read by `load_pending_tab` when first shown.
+	pub fn add_pending_tab(&mut self, self_rc: &Rc<Mutex<Self>>, path: &Path) {
+		if self.find_tab_by_path(path).is_some() {
+			return;
+		}
+		let path_str = path.to_string_lossy().to_string();
+		let (stored_title, word_wrap) = {
+			let config = self.config.lock().unwrap();
+			let (stored_title, _, _) = config.get_document_library_info(&path_str);
+			(stored_title, word_wrap_for(&config, path, LineLayout::Wrapped))
+		};
+		let (panel, text_ctrl) = self.build_tab_page(self_rc, word_wrap);
+		self.notebook.add_page(&panel, &title_or_filename(stored_title.clone(), path), false, None);
+		self.tabs.push(DocumentTab {
+			panel,
+			text_ctrl,
+			session: DocumentSession::placeholder(&path_str, &stored_title),
+			file_path: path.to_path_buf(),
+			track: true,
+			last_read: None,
+			shown_section: RefCell::new(String::new()),
+			pending: true,
+			find_highlights: FindHighlights::default(),
+			line_layout: LineLayout::Wrapped,
+			word_wrap,
+			bookmark_ranges: RefCell::default(),
+			bookmarks_revision: Cell::new(None),
+		});
+	}
+
+	/// Parses the document of a tab added by `add_pending_tab` and fills the tab in place. A document
+	/// that can't be opened loses its tab. Returns whether the tab now holds its document.
+	pub fn load_pending_tab(&mut self, self_rc: &Rc<Mutex<Self>>, index: usize) -> bool {
+		let Some(tab) = self.tabs.get(index) else {
+			return false;
+		};
+		if !tab.pending {
+			return true;
+		}
+		let path = tab.file_path.clone();
+		let context = self.restored_document_context(&path);
+		tracing::info!(path = %path.display(), "opening restored document");
+		let Some((session, password)) = self.parse_session(&path, context) else {
+			self.discard_tab(index);
+			return false;
+		};
+		self.fill_pending_tab(self_rc, index, session, &password);
+		true
+	}
+
+	fn restored_document_context(&self, path: &Path) -> ParserContext {
+		let path_str = path.to_string_lossy().to_string();
+		let config = self.config.lock().unwrap();
+		config.refresh_document_hash(&path_str);
+		document_parser_context(&config, &path_str)
+	}
+
+	/// Puts a restored document's parsed `session` into its pending tab at `index`.
+	fn fill_pending_tab(&mut self, self_rc: &Rc<Mutex<Self>>, index: usize, session: DocumentSession, password: &str) {
+		let path = self.tabs[index].file_path.clone();
+		let (line_layout, word_wrap) = {
+			let config = self.config.lock().unwrap();
+			let line_layout = detect_line_layout(&config, &session);
+			(line_layout, word_wrap_for(&config, &path, line_layout))
+		};
+		if word_wrap != self.tabs[index].word_wrap {
+			// The control is still empty, so it can simply be swapped for one that wraps the right way.
+			let old_ctrl = self.tabs[index].text_ctrl;
+			self.tabs[index].text_ctrl = self.build_tab_ctrl(self.tabs[index].panel, self_rc, word_wrap);
+			old_ctrl.destroy();
+		}
+		let tab = &mut self.tabs[index];
+		tab.line_layout = line_layout;
+		tab.word_wrap = word_wrap;
+		fill_tab_text(tab.text_ctrl, &session, &self.config.lock().unwrap());
+		tab.session = session;
+		tab.pending = false;
+		self.notebook.set_page_text(index, &display_title(tab));
+		self.finish_opening_tab(index, password);
+	}
+
+	/// Parses the active document if it's still pending. The active tab moves on when it can't be
+	/// opened, so this goes on until it lands on a parsed document or none is left.
+	pub fn load_active_tab(&mut self, self_rc: &Rc<Mutex<Self>>) {
+		while let Some(index) = self.active_tab_index()
+			&& self.tabs.get(index).is_some_and(|tab| tab.pending)
+		{
+			self.load_pending_tab(self_rc, index);
+		}
+	}
+
+	/// Builds the panel and text control of a new tab, styled with the reading settings.
+	fn build_tab_page(&self, self_rc: &Rc<Mutex<Self>>, word_wrap: bool) -> (Panel, TextCtrl) {
 		let panel = Panel::builder(&self.notebook).build();
+		let text_ctrl = self.build_tab_ctrl(panel, self_rc, word_wrap);
+		(panel, text_ctrl)
+	}
+
+	/// Builds the text control filling a tab's `panel`, in place of any it had before.
+	fn build_tab_ctrl(&self, panel: Panel, self_rc: &Rc<Mutex<Self>>, word_wrap: bool) -> TextCtrl {
 		let config = self.config.lock().unwrap();
-		let mut session = session;
-		let word_wrap = config.get_app_bool("word_wrap", false);
 		#[cfg(target_os = "linux")]
 		let text_ctrl = Self::build_text_ctrl(panel, word_wrap, self_rc, self.frame, Rc::clone(&self.navigation_key_map));
 		#[cfg(not(target_os = "linux"))]
@@ -216,48 +651,112 @@ impl DocumentManager {
 		if let Some(font) = build_font_from_readability(&rf) {
 			text_ctrl.set_font(&font);
 		}
-		apply_foreground_color_to_ctrl(text_ctrl, rf.color);
-		apply_bg_color_to_ctrl(text_ctrl, config.get_bg_color());
+		apply_reading_colors(&text_ctrl, ReadingColors::for_document(&config));
 		let sizer = BoxSizer::builder(Orientation::Vertical).build();
 		sizer.add(&text_ctrl, 1, SizerFlag::Expand | SizerFlag::All, 0);
 		panel.set_sizer(sizer, true);
-		let content = session.content();
-		fill_text_ctrl_with_formatting(text_ctrl, &session, &content);
-		apply_readability_format_to_ctrl(
-			text_ctrl,
-			config.get_line_spacing(),
-			config.get_paragraph_spacing(),
-			config.get_letter_spacing(),
-			config.get_text_alignment(),
-		);
-		self.notebook.add_page(&panel, &title, true, None);
+		text_ctrl
+	}
+
+	/// Restores the history and caret position of a tab whose text was just filled in, and records
+	/// the document as open.
+	fn finish_opening_tab(&mut self, tab_index: usize, password: &str) {
+		let mut config = self.config.lock().unwrap();
+		let path = self.tabs[tab_index].file_path.clone();
+		let track = self.tabs[tab_index].track;
 		let path_str = path.to_string_lossy();
 		let nav_history = config.get_navigation_history(&path_str);
-		session.set_history(&nav_history.positions, nav_history.index);
-		self.tabs.push(DocumentTab { panel, text_ctrl, session, file_path: path.to_path_buf(), track });
+		self.tabs[tab_index].session.restore_history(&nav_history.entries, nav_history.index);
+		config.set_document_language(&path_str, &self.tabs[tab_index].session.language());
 		if !password.is_empty() {
 			config.set_document_password(&path_str, password);
 		}
-		let tab_index = self.tabs.len() - 1;
+		let fully_loaded = self.tabs[tab_index].session.is_fully_loaded();
+		if !fully_loaded {
+			let saved_pos = config.get_document_position(&path_str);
+			self.tabs[tab_index].load_through_position(saved_pos);
+		}
 		let max_pos = self.tabs[tab_index].text_ctrl.get_last_position();
-		let saved_pos = config.get_validated_document_position(&path_str, max_pos);
-		let initial_pos = if saved_pos >= 0 {
-			self.tabs[tab_index].text_ctrl.set_insertion_point(saved_pos);
-			self.tabs[tab_index].text_ctrl.show_position(saved_pos);
-			saved_pos
-		} else {
-			self.tabs[tab_index].text_ctrl.set_insertion_point(0);
-			self.tabs[tab_index].text_ctrl.show_position(0);
-			0
-		};
+		let is_clipboard = is_clipboard_path(&path_str);
+		if track && !is_clipboard && !url_download::is_url(&path_str) && is_auto_sync_sidecar_enabled(&config) {
+			// The conflict prompt is modal, so don't hold the config lock across it.
+			drop(config);
+			self.sync_sidecar_on_open(&path_str, max_pos);
+			config = self.config.lock().unwrap();
+		}
+		let mut saved_pos = config.get_validated_document_position(&path_str, max_pos);
+		let mut approximate_restore = false;
+		if track {
+			// Only part of a lazily loaded PDF is in the control, so its length says nothing about edits.
+			self.tabs[tab_index].last_read = config
+				.get_last_read_position(&path_str, max_pos)
+				.map(|(position, approximate)| (position, approximate && fully_loaded));
+			if fully_loaded && let Some(anchor) = config.get_document_anchor(&path_str) {
+				let stored = config.get_document_position(&path_str);
+				let (position, approximate) = self.tabs[tab_index].session.resolve_position_anchor(stored, &anchor);
+				self.tabs[tab_index].last_read = (position > 0).then_some((position, approximate));
+				saved_pos = position;
+				approximate_restore = approximate;
+			}
+			if fully_loaded {
+				config.set_document_content_length(&path_str, max_pos);
+			}
+			let furthest = config.get_document_furthest_position(&path_str);
+			self.tabs[tab_index].session.set_furthest_position(furthest);
+			let session = &self.tabs[tab_index].session;
+			config.set_document_metadata(&path_str, &session.title(), &session.author());
+		}
+		let initial_pos = saved_pos.max(0);
+		let text_ctrl = self.tabs[tab_index].text_ctrl;
+		text_ctrl.set_insertion_point(initial_pos);
+		// The view is put back by offset, so it holds even if word wrap changed since; it's dropped
+		// when the caret had to be estimated or would end up above it.
+		let view_top = config.get_document_view_top(&path_str);
+		if track && !approximate_restore && view_top > 0 && view_top <= initial_pos {
+			self.tabs[tab_index].panel.layout();
+			scroll_position_to_top(text_ctrl, view_top);
+		}
+		text_ctrl.show_position(initial_pos);
 		self.tabs[tab_index].session.set_stable_position(initial_pos);
 		if track {
-			config.add_recent_document(&path_str);
+			// A clipboard document can't be reopened once closed, so it stays out of the recent list.
+			if !is_clipboard {
+				config.add_recent_document(&path_str);
+			}
 			config.set_document_opened(&path_str, true);
 			config.add_opened_document(&path_str);
+			config.record_document_session(&path_str);
 		}
 		config.flush();
-		true
+		drop(config);
+		// Documents parsed in the background aren't being read yet.
+		if self.active_tab_index() == Some(tab_index) {
+			self.start_reading_clock();
+			if approximate_restore {
+				// TRANSLATORS: Announced on opening a document whose text changed so much that the saved reading position could only be estimated
+				speech::announce(self.live_region_label, &t("Approximate position restored."));
+			}
+		}
+	}
+
+	/// Brings the local position and bookmarks up to date with the document's `.paperback` sidecar,
+	/// asking which to keep when both changed since the last sync.
+	fn sync_sidecar_on_open(&self, path_str: &str, max_pos: i64) {
+		let sync = self.config.lock().unwrap().compare_sidecar(path_str);
+		let use_sidecar = match sync {
+			SidecarSync::SidecarNewer => true,
+			SidecarSync::Conflict { local_position, sidecar_position } => {
+				confirm_use_sidecar_position(&self.notebook, local_position, sidecar_position, max_pos)
+			}
+			SidecarSync::UpToDate | SidecarSync::LocalNewer => return,
+		};
+		let config = self.config.lock().unwrap();
+		if use_sidecar {
+			tracing::info!(path = path_str, "applying newer .paperback sidecar");
+			config.apply_sidecar(path_str);
+		} else {
+			config.keep_local_over_sidecar(path_str);
+		}
 	}
 
 	pub fn close_document(&mut self, index: usize, save_state: bool) -> bool {
@@ -266,15 +765,32 @@ impl DocumentManager {
 		}
 		if let Some(tab) = self.tabs.get(index) {
 			tracing::info!(path = %tab.file_path.display(), "closing document");
-			self.recently_closed.push(tab.file_path.clone());
 			let path_str = tab.file_path.to_string_lossy();
+			let is_clipboard = is_clipboard_path(&path_str);
+			if !is_clipboard {
+				self.recently_closed.push(tab.file_path.clone());
+			}
+			let elapsed = {
+				let mut clock = self.reading_clock.borrow_mut();
+				if clock.path.as_deref() == Some(tab.file_path.as_path()) { clock.stop(Instant::now()) } else { None }
+			};
 			let config = self.config.lock().unwrap();
-			if save_state && tab.track {
-				let position = tab.text_ctrl.get_insertion_point();
-				config.set_document_position(&path_str, position);
-				let (history, history_index) = tab.session.get_history();
-				config.set_navigation_history(&path_str, history, history_index);
+			if let Some((path, elapsed)) = elapsed {
+				config.add_document_reading_time(&path.to_string_lossy(), elapsed.as_secs());
+			}
+			// A tab that was never parsed has nothing to save over what's already stored.
+			if save_state && tab.track && !tab.pending {
+				let position = tab.session.clamp_position(tab.text_ctrl.get_insertion_point());
+				tab.save_position(&config, position);
+				if tab.session.is_fully_loaded() {
+					config.set_document_content_length(&path_str, tab.text_ctrl.get_last_position());
+				}
+				let (history, history_index) = tab.session.saved_history();
+				config.set_navigation_history(&path_str, &history, history_index);
 				config.set_document_opened(&path_str, false);
+				if !is_clipboard {
+					auto_sync_sidecar(&config, &path_str);
+				}
 			}
 			config.remove_opened_document(&path_str);
 			config.flush();
@@ -287,9 +803,21 @@ impl DocumentManager {
 			let new_index = index.min(count - 1);
 			self.notebook.set_selection(new_index);
 		}
+		self.start_reading_clock();
 		true
 	}
 
+	/// Removes the tab of a restored document that couldn't be opened, leaving its saved state and
+	/// the active tab alone unless it was the active one.
+	fn discard_tab(&mut self, index: usize) {
+		let was_active = self.active_tab_index() == Some(index);
+		self.notebook.remove_page(index);
+		self.tabs.remove(index);
+		if was_active && !self.tabs.is_empty() {
+			self.notebook.set_selection(index.min(self.tabs.len() - 1));
+		}
+	}
+
 	pub fn active_index_after_closing(&self, index: usize) -> Option<usize> {
 		let count = self.tabs.len();
 		if index >= count || count <= 1 {
@@ -305,21 +833,97 @@ impl DocumentManager {
 		}
 	}
 
+	/// Starts timing the active document, crediting time read to the previously focused one.
+	pub fn start_reading_clock(&self) {
+		let now = Instant::now();
+		let elapsed = match self.active_tab() {
+			Some(tab) if tab.track => self.reading_clock.borrow_mut().start(&tab.file_path, now),
+			_ => self.reading_clock.borrow_mut().stop(now),
+		};
+		self.record_reading_time(elapsed);
+	}
+
+	/// Stops timing, e.g. when the app is closing. The time is written to the config but not flushed.
+	pub fn stop_reading_clock(&self) {
+		let elapsed = self.reading_clock.borrow_mut().stop(Instant::now());
+		self.record_reading_time(elapsed);
+	}
+
+	/// Writes the time read so far to the config without stopping the clock.
+	pub fn commit_reading_time(&self) {
+		let elapsed = self.reading_clock.borrow_mut().take(Instant::now());
+		self.record_reading_time(elapsed);
+	}
+
+	fn note_reading_activity(&self) {
+		self.reading_clock.borrow_mut().touch(Instant::now());
+	}
+
+	fn record_reading_time(&self, elapsed: Option<(PathBuf, Duration)>) {
+		if let Some((path, elapsed)) = elapsed
+			&& elapsed.as_secs() > 0
+		{
+			self.config.lock().unwrap().add_document_reading_time(&path.to_string_lossy(), elapsed.as_secs());
+		}
+	}
+
 	pub fn save_all_positions(&self) {
 		let config = self.config.lock().unwrap();
 		for tab in &self.tabs {
-			if !tab.track {
+			if !tab.track || tab.pending {
 				continue;
 			}
 			let position = tab.text_ctrl.get_insertion_point();
 			let path_str = tab.file_path.to_string_lossy();
-			config.set_document_position(&path_str, position);
-			let (history, history_index) = tab.session.get_history();
-			config.set_navigation_history(&path_str, history, history_index);
+			tab.save_position(&config, position);
+			if let Some(top) = first_visible_position(tab.text_ctrl) {
+				config.set_document_view_top(&path_str, top);
+			}
+			let (history, history_index) = tab.session.saved_history();
+			config.set_navigation_history(&path_str, &history, history_index);
+			auto_sync_sidecar(&config, &path_str);
 		}
 		config.flush();
 	}
 
+	/// Writes the caret position and navigation history of every tab whose caret moved since the last
+	/// autosave, so a crash loses at most one autosave interval. Flushes only if something was written.
+	pub fn autosave_positions(&self) {
+		let mut autosaved = self.autosaved_positions.borrow_mut();
+		let config = self.config.lock().unwrap();
+		let mut changed = false;
+		for tab in self.tabs.iter().filter(|tab| tab.track && !tab.pending) {
+			let position = tab.text_ctrl.get_insertion_point();
+			if autosaved.get(&tab.file_path) == Some(&position) {
+				continue;
+			}
+			let path_str = tab.file_path.to_string_lossy();
+			tab.save_position(&config, position);
+			let (history, history_index) = tab.session.saved_history();
+			config.set_navigation_history(&path_str, &history, history_index);
+			auto_sync_sidecar(&config, &path_str);
+			autosaved.insert(tab.file_path.clone(), position);
+			changed = true;
+		}
+		if changed {
+			config.flush();
+		}
+	}
+
+	/// Moves the active document's furthest read position forward once the caret has stayed past it
+	/// for a few seconds; see `DocumentSession::track_furthest`.
+	pub fn track_furthest_read(&mut self) {
+		let Some(tab) = self.active_tab_mut().filter(|tab| tab.track && !tab.pending) else {
+			return;
+		};
+		let position = tab.text_ctrl.get_insertion_point();
+		let Some(furthest) = tab.session.track_furthest(position, Instant::now()) else {
+			return;
+		};
+		let path_str = tab.file_path.to_string_lossy().to_string();
+		self.config.lock().unwrap().set_document_furthest_position(&path_str, furthest);
+	}
+
 	pub fn save_position_throttled(&self) {
 		let now = Instant::now();
 		if let Some(last_save) = self.last_position_save.get()
@@ -333,7 +937,8 @@ impl DocumentManager {
 			let position = tab.text_ctrl.get_insertion_point();
 			let path_str = tab.file_path.to_string_lossy();
 			let config = self.config.lock().unwrap();
-			config.set_document_position(&path_str, position);
+			tab.save_position(&config, position);
+			auto_sync_sidecar(&config, &path_str);
 			config.flush();
 		}
 		self.last_position_save.set(Some(now));
@@ -365,8 +970,19 @@ impl DocumentManager {
 	}
 
 	pub fn find_tab_by_path(&self, path: &Path) -> Option<usize> {
-		let target = normalized_path_key(path);
-		self.tabs.iter().position(|tab| normalized_path_key(&tab.file_path) == target)
+		let target = document_path_key(path);
+		self.tabs.iter().position(|tab| document_path_key(&tab.file_path) == target)
+	}
+
+	/// Switches to the tab of a document that was asked to be opened again, keeping the caret in
+	/// its text rather than on the notebook.
+	fn focus_open_tab(&self, index: usize) {
+		self.notebook.set_selection(index);
+		if let Some(tab) = self.tabs.get(index) {
+			tab.text_ctrl.set_focus();
+		}
+		// TRANSLATORS: Announced when opening a document that is already open in another tab, which is switched to instead
+		speech::announce(self.live_region_label, &t("Already open."));
 	}
 
 	pub fn restore_focus(&self) {
@@ -405,7 +1021,7 @@ impl DocumentManager {
 						tab.text_ctrl.show_position(result.offset);
 						tab.session.check_and_record_history(result.offset);
 						// TRANSLATORS: Announcement read by screen readers after following an internal link within the document
-						live_region::announce(self.live_region_label, &t("Navigated to internal link."));
+						speech::announce(self.live_region_label, &t("Navigated to internal link."));
 					}
 					paperback_core::session::LinkAction::External => {
 						launch_default_browser(&result.url, BrowserLaunchFlags::Default);
@@ -422,39 +1038,96 @@ impl DocumentManager {
 		})
 	}
 
-	pub fn update_status_bar(&self) {
-		let sleep_start = SLEEP_TIMER_START_MS.load(Ordering::SeqCst);
-		let sleep_duration = SLEEP_TIMER_DURATION_MINUTES.load(Ordering::SeqCst);
-		if self.tabs.is_empty() {
-			let mut status_text = t("Ready");
-			if sleep_start > 0 {
-				let remaining = status::calculate_sleep_timer_remaining(sleep_start, sleep_duration);
-				if remaining > 0 {
-					status_text = status::format_sleep_timer_status(&status_text, remaining);
+	/// Shows the chapter the caret is in after the document title, in the window title and the tab
+	/// label, when "show_section_in_title" is on. Unless `force` is set, nothing is touched until the
+	/// caret crosses into another chapter, so arrowing through a chapter doesn't churn the labels.
+	pub fn refresh_section_title(&self, force: bool) {
+		let Some(active) = self.active_tab_index() else {
+			return;
+		};
+		let show_section = self.config.lock().unwrap().get_app_bool("show_section_in_title", false);
+		if !show_section {
+			for (index, tab) in self.tabs.iter().enumerate() {
+				if !tab.shown_section.borrow().is_empty() {
+					tab.shown_section.borrow_mut().clear();
+					self.notebook.set_page_text(index, &display_title(tab));
 				}
 			}
-			self.frame.set_status_text(&status_text, 0);
+		}
+		let tab = &self.tabs[active];
+		let section = if show_section {
+			ellipsize_words(&tab.session.section_title_at(tab.text_ctrl.get_insertion_point()), MAX_SECTION_TITLE_CHARS)
+		} else {
+			String::new()
+		};
+		if !force && *tab.shown_section.borrow() == section {
+			return;
+		}
+		if show_section {
+			self.notebook.set_page_text(active, &tab_label(tab, &section));
+		}
+		self.frame.set_title(&window_title(tab, &section));
+		*tab.shown_section.borrow_mut() = section;
+	}
+
+	/// Loads the pronunciation rules of the active document, or only the global ones when none is open.
+	pub fn refresh_pronunciation_rules(&self) {
+		let path = self.active_tab().map(|tab| tab.file_path.to_string_lossy().to_string());
+		speech::load_rules(&self.config.lock().unwrap(), path.as_deref());
+	}
+
+	pub fn update_status_bar(&self) {
+		if self.tabs.is_empty() {
+			self.frame.set_status_text(&status::with_sleep_timer(status::with_download(t("Ready"))), 0);
 			return;
 		}
 		if let Some(tab) = self.active_tab() {
 			let position = tab.text_ctrl.get_insertion_point();
-			let status_info = tab.session.get_status_info(position);
-			let mut status_text = status::format_status_text(&status_info);
-			if sleep_start > 0 {
-				let remaining = status::calculate_sleep_timer_remaining(sleep_start, sleep_duration);
-				if remaining > 0 {
-					status_text = status::format_sleep_timer_status(&status_text, remaining);
-				}
+			let mut fields = tab.session.status_fields(position);
+			fields.title = display_title(tab);
+			let mut status_text = status::format_status_text(&self.config.lock().unwrap(), &fields);
+			if tab.session.is_loading_pages() {
+				status_text = status::format_loading_status(&status_text);
 			}
-			self.frame.set_status_text(&status_text, 0);
+			self.frame.set_status_text(&status::with_sleep_timer(status::with_download(status_text)), 0);
 		}
 	}
 
-	fn check_bookmark_sounds(&self) {
-		let config = self.config.lock().unwrap();
-		if !config.get_app_bool("bookmark_sounds", true) {
+	/// Appends pages read in the background to every tab, and asks for more once the caret in the
+	/// active tab nears the end of what is loaded. Returns whether the active tab is still loading.
+	pub fn sync_loaded_pages(&mut self) -> bool {
+		for tab in &mut self.tabs {
+			tab.sync_loaded_pages();
+		}
+		let Some(tab) = self.active_tab() else {
+			return false;
+		};
+		tab.session.request_pages_near(tab.text_ctrl.get_insertion_point());
+		tab.session.is_loading_pages()
+	}
+
+	/// Reads the rest of the active document, counting the pages in the status bar as it goes.
+	pub fn load_active_document_fully(&mut self) {
+		let frame = self.frame;
+		let Some(tab) = self.active_tab_mut() else {
 			return;
+		};
+		let total = tab.session.page_count();
+		while !tab.session.is_fully_loaded() {
+			let loaded = tab.session.loaded_page_count();
+			// TRANSLATORS: Status bar text while every page of a large PDF is read before searching it; the first %d is the pages read so far, the second the total
+			let progress = t("Loading pages: %d of %d").replacen("%d", &loaded.to_string(), 1);
+			let progress = progress.replacen("%d", &total.to_string(), 1);
+			frame.set_status_text(&progress, 0);
+			tab.load_through_page(i32::try_from(loaded + PAGE_LOAD_STEP).unwrap_or(i32::MAX));
 		}
+		self.update_status_bar();
+	}
+
+	/// Plays the bookmark sound when the caret moves into a bookmark and, with `announce`, speaks its
+	/// note or text. `announce` is false for mouse clicks, and for keys such as the bookmark commands
+	/// that already say where they landed.
+	fn check_bookmark_entry(&self, announce: bool) {
 		let Some(tab) = self.active_tab() else {
 			return;
 		};
@@ -464,27 +1137,22 @@ impl DocumentManager {
 		if prev == position {
 			return;
 		}
-		let path_str = tab.file_path.to_string_lossy().to_string();
-		let bookmarks = config.get_bookmarks(&path_str);
+		let config = self.config.lock().unwrap();
+		let sounds = config.get_app_bool("bookmark_sounds", true);
+		let announcements = announce && config.get_app_bool("announce_bookmarks", true);
+		if !sounds && !announcements {
+			return;
+		}
+		let ranges = tab.bookmark_ranges(&config);
 		drop(config);
-
-		let mut has_note = false;
-		let mut has_bookmark = false;
-		for bm in &bookmarks {
-			let was_inside = if bm.start == bm.end { prev == bm.start } else { prev >= bm.start && prev < bm.end };
-			let is_inside =
-				if bm.start == bm.end { position == bm.start } else { position >= bm.start && position < bm.end };
-			let triggered = is_inside && !was_inside;
-			if triggered {
-				if bm.note.is_empty() {
-					has_bookmark = true;
-				} else {
-					has_note = true;
-				}
-			}
+		let Some(bookmark) = ranges.entered(prev, position) else {
+			return;
+		};
+		if sounds {
+			super::sounds::play_bookmark_sound(!bookmark.note.is_empty());
 		}
-		if has_note || has_bookmark {
-			super::sounds::play_bookmark_sound(has_note);
+		if announcements {
+			speech::announce(self.live_region_label, &bookmark_entry_message(&tab.session, bookmark));
 		}
 	}
 
@@ -492,34 +1160,156 @@ impl DocumentManager {
 		self.last_sound_position.set(None);
 	}
 
-	pub fn apply_font(&self, font: &Font) {
-		for tab in &self.tabs {
-			tab.text_ctrl.set_font(font);
-			tab.text_ctrl.refresh(true, None);
+	/// Speaks the list, table, block quote or code block the caret has just moved into or out of.
+	/// With `announce` false (mouse clicks, and keys such as Go menu shortcuts that already say
+	/// where they landed) the new region is only remembered for the next comparison.
+	fn update_structure_context(&self, announce: bool) {
+		let Some(tab) = self.active_tab() else {
+			return;
+		};
+		let current = tab.session.structure_region(tab.text_ctrl.get_insertion_point());
+		if !announce || !self.config.lock().unwrap().get_app_bool("context_announcements", false) {
+			self.announced_structure.set(current);
+			return;
+		}
+		let Some(transition) = structure_transition(self.announced_structure.get(), current) else {
+			return;
+		};
+		let now = Instant::now();
+		if self
+			.last_structure_announcement
+			.get()
+			.is_some_and(|last| now.saturating_duration_since(last) < STRUCTURE_ANNOUNCEMENT_INTERVAL)
+		{
+			return;
 		}
+		self.announced_structure.set(current);
+		self.last_structure_announcement.set(Some(now));
+		speech::announce(self.live_region_label, &structure_transition_message(transition));
 	}
 
-	pub fn apply_color(&self, color: i32) {
+	pub fn reset_structure_context(&self) {
+		self.update_structure_context(false);
+	}
+
+	pub fn is_auto_scrolling(&self) -> bool {
+		self.auto_scrolling.get()
+	}
+
+	/// Milliseconds between auto-scroll steps at the configured speed.
+	pub fn auto_scroll_interval(&self) -> i32 {
+		60_000 / self.auto_scroll_speed()
+	}
+
+	fn auto_scroll_speed(&self) -> i32 {
+		self.config
+			.lock()
+			.unwrap()
+			.get_app_int("auto_scroll_lines_per_minute", AUTO_SCROLL_DEFAULT_SPEED)
+			.clamp(AUTO_SCROLL_MIN_SPEED, AUTO_SCROLL_MAX_SPEED)
+	}
+
+	/// Starts auto-scroll from the line the caret is on, bringing it to the top of the view.
+	/// Returns the speed in lines per minute, or `None` when no document is open.
+	pub fn start_auto_scroll(&self) -> Option<i32> {
+		let tab = self.active_tab()?;
+		let position = tab.text_ctrl.get_insertion_point().max(0);
+		let line_start =
+			tab.text_ctrl.position_to_xy(position).map_or(position, |(_, line)| tab.text_ctrl.xy_to_position(0, line));
+		let line_start = if line_start < 0 { position } else { line_start };
+		tab.text_ctrl.set_insertion_point(line_start);
+		scroll_position_to_top(tab.text_ctrl, line_start);
+		self.auto_scrolling.set(true);
+		Some(self.auto_scroll_speed())
+	}
+
+	/// Stops auto-scroll, returning whether it was running.
+	pub fn stop_auto_scroll(&self) -> bool {
+		self.auto_scrolling.replace(false)
+	}
+
+	/// Stops auto-scroll because the user took over (navigation, Find, switching tabs).
+	pub fn pause_auto_scroll(&self) {
+		if self.stop_auto_scroll() {
+			// TRANSLATORS: Announced when auto-scroll stops because the user moved the caret, searched or switched tabs
+			speech::announce(self.live_region_label, &t("Auto-scroll paused."));
+		}
+	}
+
+	/// Moves the caret to the start of the next line and scrolls it to the top of the view. Works
+	/// from the caret each time rather than a remembered line number, so re-wrapping the text mid-
+	/// scroll doesn't make it jump. Returns false once scrolling has stopped, either because it was
+	/// paused or because the end of the document was reached.
+	pub fn auto_scroll_step(&self) -> bool {
+		if !self.auto_scrolling.get() {
+			return false;
+		}
+		let Some(tab) = self.active_tab() else {
+			self.auto_scrolling.set(false);
+			return false;
+		};
+		let position = tab.text_ctrl.get_insertion_point().max(0);
+		let next =
+			tab.text_ctrl.position_to_xy(position).map_or(-1, |(_, line)| tab.text_ctrl.xy_to_position(0, line + 1));
+		if next <= position {
+			self.auto_scrolling.set(false);
+			// TRANSLATORS: Announced when auto-scroll reaches the end of the document and stops
+			speech::announce(self.live_region_label, &t("End of document."));
+			return false;
+		}
+		tab.text_ctrl.set_insertion_point(next);
+		scroll_position_to_top(tab.text_ctrl, next);
+		self.update_status_bar();
+		self.save_position_throttled();
+		true
+	}
+
+	fn change_auto_scroll_speed(&self, faster: bool) {
+		let step = if faster { AUTO_SCROLL_SPEED_STEP } else { -AUTO_SCROLL_SPEED_STEP };
+		let speed = (self.auto_scroll_speed() + step).clamp(AUTO_SCROLL_MIN_SPEED, AUTO_SCROLL_MAX_SPEED);
+		self.config.lock().unwrap().set_app_int("auto_scroll_lines_per_minute", speed);
+		// TRANSLATORS: Announced when the auto-scroll speed changes; %d is the new speed
+		speech::announce(self.live_region_label, &t("%d lines per minute").replace("%d", &speed.to_string()));
+	}
+
+	pub fn apply_font(&self, font: &Font) {
 		for tab in &self.tabs {
-			apply_foreground_color_to_ctrl(tab.text_ctrl, color);
+			tab.text_ctrl.set_font(font);
 			tab.text_ctrl.refresh(true, None);
 		}
 	}
 
-	pub fn apply_bg_color(&self, color: i32) {
+	pub fn apply_reading_colors(&self, colors: ReadingColors) {
 		for tab in &self.tabs {
-			apply_bg_color_to_ctrl(tab.text_ctrl, color);
+			apply_reading_colors(&tab.text_ctrl, colors);
 			tab.text_ctrl.refresh(true, None);
 		}
 	}
 
 	pub fn apply_text_alignment(&self, alignment: i32) {
+		let cfg = self.config.lock().unwrap();
 		for tab in &self.tabs {
 			apply_text_alignment_to_ctrl(tab.text_ctrl, alignment);
+			let direction = text_direction_for(&cfg, &tab.session);
+			if direction == TextDirection::RightToLeft {
+				apply_text_direction_to_ctrl(tab.text_ctrl, direction, alignment);
+			}
 			tab.text_ctrl.refresh(true, None);
 		}
 	}
 
+	/// Sets the active document's text direction, `None` going back to the detected one, and lays
+	/// its text out to match. Returns the direction now in effect.
+	pub fn set_text_direction(&self, direction: Option<TextDirection>) -> Option<TextDirection> {
+		let tab = self.active_tab()?;
+		let cfg = self.config.lock().unwrap();
+		cfg.set_document_text_direction(&tab.file_path.to_string_lossy(), direction);
+		let effective = text_direction_for(&cfg, &tab.session);
+		apply_text_direction_to_ctrl(tab.text_ctrl, effective, cfg.get_text_alignment());
+		tab.text_ctrl.refresh(true, None);
+		Some(effective)
+	}
+
 	pub fn apply_letter_spacing(&self, spacing: i32) {
 		for tab in &self.tabs {
 			apply_letter_spacing_to_ctrl(tab.text_ctrl, spacing);
@@ -541,22 +1331,91 @@ impl DocumentManager {
 		}
 	}
 
+	/// Text directions the reader chose, by the path of each open document that has one.
+	fn text_direction_overrides(&self, cfg: &ConfigManager) -> HashMap<PathBuf, TextDirection> {
+		self.tabs
+			.iter()
+			.filter_map(|tab| {
+				cfg.get_document_text_direction(&tab.file_path.to_string_lossy())
+					.map(|direction| (tab.file_path.clone(), direction))
+			})
+			.collect()
+	}
+
+	/// Takes Find's match highlights off every tab.
+	pub fn clear_find_highlights(&mut self) {
+		for tab in &mut self.tabs {
+			find::clear_find_highlights(tab);
+		}
+	}
+
 	pub fn apply_word_wrap(&mut self, self_rc: &Rc<Mutex<Self>>, word_wrap: bool) {
-		let (rf, line_spacing, bg_color, text_alignment, letter_spacing, paragraph_spacing) = {
+		self.rebuild_text_ctrls(self_rc, word_wrap, None);
+	}
+
+	/// Sets how the active document lays out its lines, `None` going back to the detected layout, and
+	/// returns the layout it ends up with.
+	pub fn set_line_layout(&mut self, self_rc: &Rc<Mutex<Self>>, layout: Option<LineLayout>) -> Option<LineLayout> {
+		let index = self.active_tab_index()?;
+		let tab = self.tabs.get(index)?;
+		let word_wrap = {
+			let config = self.config.lock().unwrap();
+			config.set_document_line_layout(&tab.file_path.to_string_lossy(), layout);
+			config.get_app_bool("word_wrap", false)
+		};
+		let effective = layout.unwrap_or(tab.line_layout);
+		self.rebuild_text_ctrls(self_rc, word_wrap, Some(index));
+		Some(effective)
+	}
+
+	/// Whether the active document is laid out preformatted, whether detected or chosen.
+	pub fn active_is_preformatted(&self) -> bool {
+		self.active_tab().is_some_and(|tab| {
+			let config = self.config.lock().unwrap();
+			effective_line_layout(&config, &tab.file_path, tab.line_layout) == LineLayout::Preformatted
+		})
+	}
+
+	/// Rebuilds the text controls of every tab, or just the one at `only_index`, whose wrapping no
+	/// longer matches the `word_wrap` setting and the document's line layout.
+	fn rebuild_text_ctrls(&mut self, self_rc: &Rc<Mutex<Self>>, word_wrap: bool, only_index: Option<usize>) {
+		let (
+			rf,
+			line_spacing,
+			colors,
+			text_alignment,
+			letter_spacing,
+			paragraph_spacing,
+			expose_structure,
+			directions,
+			layouts,
+		) = {
 			let cfg = self.config.lock().unwrap();
+			let layouts: Vec<LineLayout> =
+				self.tabs.iter().map(|tab| effective_line_layout(&cfg, &tab.file_path, tab.line_layout)).collect();
 			(
 				cfg.get_readability_font(),
 				cfg.get_line_spacing(),
-				cfg.get_bg_color(),
+				ReadingColors::for_document(&cfg),
 				cfg.get_text_alignment(),
 				cfg.get_letter_spacing(),
 				cfg.get_paragraph_spacing(),
+				cfg.get_app_bool("expose_document_structure", false),
+				self.text_direction_overrides(&cfg),
+				layouts,
 			)
 		};
-		for tab in &mut self.tabs {
+		for (index, (tab, layout)) in self.tabs.iter_mut().zip(layouts).enumerate() {
+			let word_wrap = word_wrap && layout == LineLayout::Wrapped;
+			if only_index.is_some_and(|only| only != index) || word_wrap == tab.word_wrap {
+				continue;
+			}
 			let old_ctrl = tab.text_ctrl;
-			let current_pos = old_ctrl.get_insertion_point();
-			let content = old_ctrl.get_value();
+			// Positions are offsets into the document's text, so they carry over to the rebuilt control
+			// whatever the wrapping does to its lines; the text itself comes from the session too.
+			let current_pos = tab.session.clamp_position(old_ctrl.get_insertion_point());
+			let (selection_start, selection_end) = old_ctrl.get_selection();
+			let first_visible = first_visible_position(old_ctrl);
 			#[cfg(target_os = "linux")]
 			let text_ctrl =
 				Self::build_text_ctrl(tab.panel, word_wrap, self_rc, self.frame, Rc::clone(&self.navigation_key_map));
@@ -565,12 +1424,11 @@ impl DocumentManager {
 			let sizer = BoxSizer::builder(Orientation::Vertical).build();
 			sizer.add(&text_ctrl, 1, SizerFlag::Expand | SizerFlag::All, 0);
 			tab.panel.set_sizer(sizer, true);
-			fill_text_ctrl_with_formatting(text_ctrl, &tab.session, &content);
+			fill_text_ctrl_with_formatting(text_ctrl, &tab.session, tab.session.content_ref(), expose_structure);
 			if let Some(font) = build_font_from_readability(&rf) {
 				text_ctrl.set_font(&font);
 			}
-			apply_foreground_color_to_ctrl(text_ctrl, rf.color);
-			apply_bg_color_to_ctrl(text_ctrl, bg_color);
+			apply_reading_colors(&text_ctrl, colors);
 			apply_readability_format_to_ctrl(
 				text_ctrl,
 				line_spacing,
@@ -578,46 +1436,102 @@ impl DocumentManager {
 				letter_spacing,
 				text_alignment,
 			);
+			if directions.get(&tab.file_path).copied().unwrap_or_else(|| tab.session.text_direction())
+				== TextDirection::RightToLeft
+			{
+				apply_text_direction_to_ctrl(text_ctrl, TextDirection::RightToLeft, text_alignment);
+			}
+			find::paint_find_highlights(text_ctrl, &tab.find_highlights, colors);
 			let max_pos = text_ctrl.get_last_position();
 			let pos = current_pos.clamp(0, max_pos);
 			tab.panel.layout();
-			text_ctrl.set_insertion_point(pos);
-			text_ctrl.show_position(pos);
+			match first_visible {
+				Some(top) => scroll_position_to_top(text_ctrl, top.clamp(0, max_pos)),
+				None => text_ctrl.show_position(pos),
+			}
+			if selection_start == selection_end {
+				text_ctrl.set_insertion_point(pos);
+			} else {
+				let start = tab.session.clamp_position(selection_start).min(max_pos);
+				let end = tab.session.clamp_position(selection_end).min(max_pos);
+				text_ctrl.set_selection(start, end);
+			}
 			old_ctrl.destroy();
 			tab.text_ctrl = text_ctrl;
+			tab.word_wrap = word_wrap;
 		}
 	}
 
+	pub fn announce_word_wrap(&self, word_wrap: bool) {
+		// TRANSLATORS: Announced when toggling word wrap; the message reflects the new state
+		let message = if word_wrap { t("Word wrap on.") } else { t("Word wrap off.") };
+		speech::announce(self.live_region_label, &message);
+	}
+
 	/// Re-parses every open document with the new `render_tables_inline` setting and refills its
 	/// text control. Re-parsing (rather than transforming in place) keeps every format's table
 	/// rendering identical via the shared parse-time helper. A tab whose re-parse fails is left
 	/// unchanged.
+	/// Rebuild the single-key navigation map after the user changes shortcuts.
+	#[cfg(target_os = "linux")]
+	pub fn reload_navigation_keys(&self) {
+		*self.navigation_key_map.borrow_mut() = build_navigation_key_map();
+	}
+
 	pub fn apply_render_tables_inline(&mut self, render_tables_inline: bool) {
-		// Read readability settings and collect each tab's parse inputs (path, password, forced
-		// format) under a single config lock, so we don't re-lock per tab while mutating the tabs.
-		let (rf, line_spacing, bg_color, text_alignment, letter_spacing, paragraph_spacing, parse_inputs) = {
+		self.reparse_tabs(render_tables_inline, None);
+	}
+
+	/// Re-parses the active document, picking up changes to its per-document parse options.
+	pub fn reparse_active_document(&mut self) {
+		let Some(index) = self.active_tab_index() else {
+			return;
+		};
+		let render_tables_inline = self.config.lock().unwrap().get_app_bool("render_tables_inline", true);
+		self.reparse_tabs(render_tables_inline, Some(index));
+	}
+
+	fn reparse_tabs(&mut self, render_tables_inline: bool, only_index: Option<usize>) {
+		// Read readability settings and build each tab's parser context under a single config lock,
+		// so we don't re-lock per tab while mutating the tabs.
+		let (
+			rf,
+			line_spacing,
+			colors,
+			text_alignment,
+			letter_spacing,
+			paragraph_spacing,
+			expose_structure,
+			directions,
+			contexts,
+		) = {
 			let cfg = self.config.lock().unwrap();
-			let parse_inputs: Vec<(String, String, String)> = self
+			let contexts: Vec<ParserContext> = self
 				.tabs
 				.iter()
 				.map(|tab| {
-					let path_str = tab.file_path.to_string_lossy().to_string();
-					let password = cfg.get_document_password(&path_str);
-					let forced_extension = cfg.get_document_format(&path_str);
-					(path_str, password, forced_extension)
+					document_parser_context(&cfg, &tab.file_path.to_string_lossy())
+						.with_render_tables_inline(render_tables_inline)
 				})
 				.collect();
 			(
 				cfg.get_readability_font(),
 				cfg.get_line_spacing(),
-				cfg.get_bg_color(),
+				ReadingColors::for_document(&cfg),
 				cfg.get_text_alignment(),
 				cfg.get_letter_spacing(),
 				cfg.get_paragraph_spacing(),
-				parse_inputs,
+				cfg.get_app_bool("expose_document_structure", false),
+				self.text_direction_overrides(&cfg),
+				contexts,
 			)
 		};
-		for (tab, (path_str, password, forced_extension)) in self.tabs.iter_mut().zip(parse_inputs) {
+		for (index, (tab, context)) in self.tabs.iter_mut().zip(contexts).enumerate() {
+			// Clipboard documents have no file to read again, and pending ones pick the change up when
+			// they're first parsed.
+			if only_index.is_some_and(|only| only != index) || is_clipboard_path(&context.file_path) || tab.pending {
+				continue;
+			}
 			let current_pos = tab.text_ctrl.get_insertion_point();
 			let pos = usize::try_from(current_pos.max(0)).unwrap_or(0);
 
@@ -636,22 +1550,23 @@ impl DocumentManager {
 			};
 			let fallback_percent = tab.session.get_status_info(current_pos).percentage;
 
-			let new_session = match DocumentSession::new(&path_str, &password, &forced_extension, render_tables_inline)
-			{
+			let new_session = match DocumentSession::from_context(&context) {
 				Ok(session) => session,
 				Err(err) => {
-					tracing::error!(path = %path_str, error = %err, "failed to re-parse document for render_tables_inline toggle");
+					tracing::error!(path = %context.file_path, error = %err, "failed to re-parse document");
 					continue;
 				}
 			};
+			let furthest = tab.session.furthest_position();
 			tab.session = new_session;
-			let content = tab.session.content();
-			fill_text_ctrl_with_formatting(tab.text_ctrl, &tab.session, &content);
+			tab.session.set_furthest_position(furthest);
+			// Refilling the control drops the highlights, and the matches may have moved anyway.
+			tab.find_highlights = FindHighlights::default();
+			fill_text_ctrl_with_formatting(tab.text_ctrl, &tab.session, tab.session.content_ref(), expose_structure);
 			if let Some(font) = build_font_from_readability(&rf) {
 				tab.text_ctrl.set_font(&font);
 			}
-			apply_foreground_color_to_ctrl(tab.text_ctrl, rf.color);
-			apply_bg_color_to_ctrl(tab.text_ctrl, bg_color);
+			apply_reading_colors(&tab.text_ctrl, colors);
 			apply_readability_format_to_ctrl(
 				tab.text_ctrl,
 				line_spacing,
@@ -659,7 +1574,13 @@ impl DocumentManager {
 				letter_spacing,
 				text_alignment,
 			);
+			if directions.get(&tab.file_path).copied().unwrap_or_else(|| tab.session.text_direction())
+				== TextDirection::RightToLeft
+			{
+				apply_text_direction_to_ctrl(tab.text_ctrl, TextDirection::RightToLeft, text_alignment);
+			}
 			tab.panel.layout();
+			tab.load_through_position(current_pos);
 			let max_pos = tab.text_ctrl.get_last_position();
 
 			let restored_pos = if let Some((ref key, within)) = stable_anchor {
@@ -682,7 +1603,7 @@ impl DocumentManager {
 		word_wrap: bool,
 		self_rc: &Rc<Mutex<Self>>,
 		#[cfg(target_os = "linux")] frame: Frame,
-		#[cfg(target_os = "linux")] navigation_key_map: Rc<HashMap<(i32, bool), i32>>,
+		#[cfg(target_os = "linux")] navigation_key_map: Rc<RefCell<HashMap<(i32, bool), i32>>>,
 	) -> TextCtrl {
 		let style = TextCtrlStyle::MultiLine
 			| TextCtrlStyle::ReadOnly
@@ -692,6 +1613,13 @@ impl DocumentManager {
 		let dm_for_enter = Rc::clone(self_rc);
 		text_ctrl.on_char(move |event| {
 			if let WindowEventData::Keyboard(kbd) = event {
+				if let Some(key @ (KEY_PLUS | KEY_MINUS)) = kbd.get_key_code()
+					&& let Ok(dm) = dm_for_enter.try_lock()
+					&& dm.is_auto_scrolling()
+				{
+					dm.change_auto_scroll_speed(key == KEY_PLUS);
+					return;
+				}
 				if kbd.get_key_code() == Some(13) || kbd.get_key_code() == Some(32) {
 					// 13 is KEY_RETURN, 32 is space
 					let table_html = {
@@ -714,20 +1642,37 @@ impl DocumentManager {
 		let dm_for_key_up = Rc::clone(self_rc);
 		text_ctrl.bind_internal(EventType::KEY_UP, move |event| {
 			event.skip(true);
+			let key = event.get_key_code().unwrap_or(0);
+			let caret_key = CARET_MOVEMENT_KEYS.contains(&key);
+			// Speed keys and bare modifiers leave auto-scroll running; so do shortcuts such as the
+			// word wrap toggle. Any other key is the reader taking over.
+			let manual_key = !AUTO_SCROLL_SPEED_KEYS.contains(&key)
+				&& !matches!(key, WXK_SHIFT | WXK_ALT | WXK_CONTROL)
+				&& (caret_key || !(event.control_down() || event.alt_down()));
 			if let Ok(dm) = dm_for_key_up.try_lock() {
+				if manual_key {
+					dm.pause_auto_scroll();
+				}
+				dm.note_reading_activity();
 				dm.update_status_bar();
+				dm.refresh_section_title(false);
 				dm.save_position_throttled();
-				dm.check_bookmark_sounds();
+				dm.check_bookmark_entry(caret_key);
+				dm.update_structure_context(caret_key);
 			}
 		});
 		let dm_for_mouse = Rc::clone(self_rc);
 		text_ctrl.bind_internal(wxdragon::event::EventType::LEFT_UP, move |event| {
 			event.skip(true);
 			if let Ok(dm) = dm_for_mouse.try_lock() {
+				dm.note_reading_activity();
 				dm.preferred_column.set(None);
+				dm.pause_auto_scroll();
 				dm.update_status_bar();
+				dm.refresh_section_title(false);
 				dm.save_position_throttled();
-				dm.check_bookmark_sounds();
+				dm.check_bookmark_entry(false);
+				dm.update_structure_context(false);
 			}
 		});
 		let text_ctrl_for_menu = text_ctrl;
@@ -748,7 +1693,8 @@ impl DocumentManager {
 				}
 				#[cfg(target_os = "linux")]
 				if !kbd.control_down() && !kbd.alt_down() {
-					if let Some(&menu_id) = key_map.get(&(key, kbd.shift_down())) {
+					let menu_id = key_map.borrow().get(&(key, kbd.shift_down())).copied();
+					if let Some(menu_id) = menu_id {
 						kbd.event.skip(false);
 						frame_for_keys.process_menu_command(menu_id);
 						return;
@@ -789,6 +1735,40 @@ impl DocumentManager {
 	}
 }
 
+/// The position of the first character in view, so the view can be put back after the control is
+/// rebuilt or the document reopened. Only RichEdit reports it.
+#[cfg(target_os = "windows")]
+fn first_visible_position(text_ctrl: TextCtrl) -> Option<i64> {
+	use windows::Win32::{
+		Foundation::{HWND, WPARAM},
+		UI::WindowsAndMessaging::SendMessageW,
+	};
+	const EM_LINEINDEX: u32 = 187;
+	const EM_GETFIRSTVISIBLELINE: u32 = 206;
+	let hwnd_ptr = text_ctrl.get_handle();
+	if hwnd_ptr.is_null() {
+		return None;
+	}
+	let hwnd = HWND(hwnd_ptr);
+	let position = unsafe {
+		let line = usize::try_from(SendMessageW(hwnd, EM_GETFIRSTVISIBLELINE, None, None).0).unwrap_or(0);
+		SendMessageW(hwnd, EM_LINEINDEX, Some(WPARAM(line)), None).0
+	};
+	i64::try_from(position).ok().filter(|&position| position >= 0)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn first_visible_position(_text_ctrl: TextCtrl) -> Option<i64> {
+	None
+}
+
+/// Scrolls `position` to the top of the view: showing the end of the text first means the control
+/// has to scroll back up to reveal `position`, which leaves it on the first visible line.
+fn scroll_position_to_top(text_ctrl: TextCtrl, position: i64) {
+	text_ctrl.show_position(text_ctrl.get_last_position());
+	text_ctrl.show_position(position);
+}
+
 /// Returns (`new_position`, `preferred_column`) for character-column-based vertical navigation.
 /// Uses wxdragon `PositionToXY`, `XYToPosition`, and `GetLineLength` so the cursor lands on the same
 /// character column (not pixel column) on the target visual line.
@@ -810,17 +1790,51 @@ fn navigate_line_by_column(text_ctrl: TextCtrl, going_down: bool, pref_col: Opti
 	Some((new_pos, col))
 }
 
-fn normalized_path_key(path: &Path) -> String {
-	let normalized = dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
-	let value = normalized.to_string_lossy().to_string();
-	#[cfg(target_os = "windows")]
-	{
-		value.to_ascii_lowercase()
-	}
-	#[cfg(not(target_os = "windows"))]
-	{
-		value
-	}
+/// Parse options for `path`: its saved password, format, column and heading detection, archive
+/// entry and running-line stripping, plus the app-wide rendering settings.
+fn document_parser_context(config: &ConfigManager, path: &str) -> ParserContext {
+	// Documents opened from a web address are read from their downloaded copy.
+	let downloaded = url_download::is_url(path).then(|| url_download::cached_file(path)).flatten();
+	let context = match downloaded {
+		Some(file) => ParserContext::new(file.to_string_lossy().into_owned()).with_source_url(path.to_string()),
+		None => ParserContext::new(path.to_string()),
+	};
+	let mut context = context
+		.with_render_tables_inline(config.get_app_bool("render_tables_inline", true))
+		.with_detect_columns(config.get_document_column_detection(path))
+		.with_detect_headings(config.get_document_heading_detection(path))
+		.with_max_detected_headings(
+			usize::try_from(config.get_app_int(
+				"max_detected_headings",
+				i32::try_from(DEFAULT_MAX_DETECTED_HEADINGS).unwrap_or(i32::MAX),
+			))
+			.unwrap_or(DEFAULT_MAX_DETECTED_HEADINGS),
+		)
+		.with_include_speaker_notes(config.get_app_bool("include_speaker_notes", true))
+		.with_include_comments(config.get_app_bool("include_comments", false))
+		.with_skip_unlabeled_images(config.get_app_bool("skip_unlabeled_images", false))
+		.with_normalize_typography(config.get_app_bool("normalize_typography", false))
+		.with_lazy_page_limit(LAZY_PDF_PAGE_LIMIT);
+	let password = config.get_document_password(path);
+	if !password.is_empty() {
+		context = context.with_password(password);
+	}
+	let forced_extension = config.get_document_format(path);
+	if !forced_extension.is_empty() {
+		context = context.with_forced_extension(forced_extension);
+	}
+	let archive_entry = config.get_document_archive_entry(path);
+	if !archive_entry.is_empty() {
+		context = context.with_archive_entry(archive_entry);
+	}
+	let encoding = config.get_document_encoding(path);
+	if !encoding.is_empty() {
+		context = context.with_encoding(encoding);
+	}
+	if let Some(strip) = config.get_document_strip_running_lines(path) {
+		context = context.with_strip_running_lines(strip);
+	}
+	context
 }
 
 fn prompt_for_password(parent: &dyn WxWidget) -> Option<String> {
@@ -839,23 +1853,144 @@ fn show_error_dialog(parent: &dyn WxWidget, message: &str, title: &str) {
 	dialog.show_modal();
 }
 
-fn build_document_load_error_message(path: &Path, error: &str) -> String {
-	let details = error.trim().strip_prefix(PASSWORD_REQUIRED_ERROR_PREFIX).map_or_else(|| error.trim(), str::trim);
-	if details.is_empty() {
-		// TRANSLATORS: Generic error message shown when a document fails to load with no further detail available
-		return t("Failed to load document.");
-	}
-	// TRANSLATORS: "File" label prefix in the document-load error dialog; {} is the file path
-	let file_line = t("File: {}").replace("{}", &path.display().to_string());
-	// TRANSLATORS: "Details" label prefix in the document-load error dialog; {} is the underlying error message
-	let details_line = t("Details: {}").replace("{}", details);
-	format!("{}\n\n{file_line}\n{details_line}", t("Failed to load document."))
+/// Asks which reading position to keep when the document and its synced sidecar both moved since
+/// the last sync. Returns true to use the sidecar's.
+fn confirm_use_sidecar_position(
+	parent: &dyn WxWidget,
+	local_position: i64,
+	sidecar_position: i64,
+	max_pos: i64,
+) -> bool {
+	let percent = |position: i64| {
+		if max_pos > 0 { (position.clamp(0, max_pos) * 100 / max_pos).to_string() } else { "0".to_string() }
+	};
+	// TRANSLATORS: Prompt shown when a document's reading position changed both on this computer and in its synced .paperback file; the first %d is the percentage read here, the second the percentage in the file
+	let message = t("This document's reading position changed both on this computer (%d%) and in its .paperback file (%d%), probably on another computer.\n\nUse the position from the .paperback file?")
+		.replacen("%d", &percent(local_position), 1)
+		.replacen("%d", &percent(sidecar_position), 1);
+	// TRANSLATORS: Title of the dialog asking which of two conflicting reading positions to keep
+	let title = t("Reading position conflict");
+	let dialog = MessageDialog::builder(parent, &message, &title)
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal() == ID_YES
+}
+
+/// Whether the document was forced to a format through Open As because no parser handles its own
+/// extension, so a failure can offer another format instead of giving up.
+fn opened_as_unknown_type(path: &Path, context: &ParserContext, error: &ParserError) -> bool {
+	context.forced_extension.is_some()
+		&& !matches!(
+			error,
+			ParserError::PasswordRequired(_) | ParserError::ArchiveEntryRequired(_) | ParserError::EmptyFile(_)
+		) && !path.extension().and_then(|ext| ext.to_str()).is_some_and(parser_supports_extension)
+}
+
+fn show_document_load_error(parent: &dyn WxWidget, path: &Path, error: &ParserError) {
+	let (summary, details) = document_load_error_text(path, error);
+	show_load_error_dialog(parent, &summary, &details);
+}
+
+/// A translated explanation of why the document failed to open, and the technical details
+/// (file, error code, cause) shown under the dialog's Details button.
+fn document_load_error_text(path: &Path, error: &ParserError) -> (String, String) {
+	let summary = match error {
+		ParserError::NoExtension(_) | ParserError::UnsupportedFormat(_) => {
+			// TRANSLATORS: Shown when a document fails to open because its file type isn't supported
+			t("Paperback doesn't support this type of file.")
+		}
+		ParserError::PasswordRequired(_) => {
+			// TRANSLATORS: Shown when a password-protected document still fails to open after entering a password
+			t("The password is incorrect, or the document couldn't be decrypted.")
+		}
+		ParserError::Encrypted(_) => {
+			// TRANSLATORS: Shown when a document is protected by DRM or encryption that cannot be removed
+			t("This document is protected by DRM or encryption and can't be opened.")
+		}
+		ParserError::Io(_) => {
+			// TRANSLATORS: Shown when a document's file could not be read from disk
+			t("The file couldn't be read. Check that it still exists and that you have permission to open it.")
+		}
+		// TRANSLATORS: Shown when the file being opened has no content at all (zero bytes)
+		ParserError::EmptyFile(_) => t("The file is empty."),
+		ParserError::CorruptArchive(_) => {
+			// TRANSLATORS: Shown when a document's ZIP container (EPUB, DOCX, etc.) is damaged or incomplete
+			t("The file is damaged or incomplete. Try downloading or copying it again.")
+		}
+		ParserError::Xml { .. } => {
+			// TRANSLATORS: Shown when a document contains malformed XML markup
+			t("The document contains malformed markup and couldn't be read.")
+		}
+		ParserError::UnsupportedVariant(_) => {
+			// TRANSLATORS: Shown when a document uses a variant or feature of its format that isn't supported
+			t("This document uses a variant of its format that Paperback doesn't support.")
+		}
+		ParserError::ArchiveEntryRequired(_) => {
+			// TRANSLATORS: Shown when an archive holds several documents and none was chosen to open
+			t("The archive contains several documents. Choose one to open it.")
+		}
+		// TRANSLATORS: Generic error message shown when a document fails to load
+		ParserError::Other(_) => t("Failed to load document."),
+	};
+	// TRANSLATORS: "File" line in the details of the document load error dialog; {} is the file path
+	let mut details = vec![t("File: {}").replace("{}", &path.display().to_string())];
+	// TRANSLATORS: "Error code" line in the details of the document load error dialog; {} is a short untranslated code such as corrupt_archive
+	details.push(t("Error code: {}").replace("{}", error.code().as_str()));
+	if let ParserError::Xml { line, column, .. } = error {
+		// TRANSLATORS: Position of malformed XML in the details of the document load error dialog; %d are the line and column numbers
+		details.push(t("Line %d, column %d").replacen("%d", &line.to_string(), 1).replacen(
+			"%d",
+			&column.to_string(),
+			1,
+		));
+	}
+	// TRANSLATORS: "Details" line in the document load error dialog; {} is the underlying error message
+	details.push(t("Details: {}").replace("{}", error.to_string().trim()));
+	(summary, details.join("\n"))
 }
 
 fn fill_text_ctrl(text_ctrl: TextCtrl, content: &str) {
 	text_ctrl.set_value(content);
 }
 
+/// Fills a tab's text control with the document and applies the paragraph settings to it.
+fn fill_tab_text(text_ctrl: TextCtrl, session: &DocumentSession, config: &ConfigManager) {
+	let expose_structure = config.get_app_bool("expose_document_structure", false);
+	fill_text_ctrl_with_formatting(text_ctrl, session, session.content_ref(), expose_structure);
+	apply_readability_format_to_ctrl(
+		text_ctrl,
+		config.get_line_spacing(),
+		config.get_paragraph_spacing(),
+		config.get_letter_spacing(),
+		config.get_text_alignment(),
+	);
+	let direction = text_direction_for(config, session);
+	if direction == TextDirection::RightToLeft {
+		apply_text_direction_to_ctrl(text_ctrl, direction, config.get_text_alignment());
+	}
+}
+
+/// The direction the reader chose for the document, or else the one detected from its text.
+/// Guesses whether a document relies on its own line breaks, against the configured line length.
+fn detect_line_layout(config: &ConfigManager, session: &DocumentSession) -> LineLayout {
+	let max_line_length = config.get_app_int("preformatted_line_length", DEFAULT_PREFORMATTED_LINE_LENGTH);
+	session.detect_line_layout(usize::try_from(max_line_length).unwrap_or(0))
+}
+
+/// The layout chosen for the document at `path`, or else the one detected from its lines.
+fn effective_line_layout(config: &ConfigManager, path: &Path, detected: LineLayout) -> LineLayout {
+	config.get_document_line_layout(&path.to_string_lossy()).unwrap_or(detected)
+}
+
+/// Whether the document at `path` wraps: only with word wrap on and when it isn't preformatted.
+fn word_wrap_for(config: &ConfigManager, path: &Path, detected: LineLayout) -> bool {
+	config.get_app_bool("word_wrap", false) && effective_line_layout(config, path, detected) == LineLayout::Wrapped
+}
+
+fn text_direction_for(config: &ConfigManager, session: &DocumentSession) -> TextDirection {
+	config.get_document_text_direction(session.file_path()).unwrap_or_else(|| session.text_direction())
+}
+
 /// Sets `content` on `text_ctrl` and applies its bold/italic/underline markers.
 ///
 /// On Windows this streams a single RTF blob into the native RichEdit control
@@ -866,7 +2001,25 @@ fn fill_text_ctrl(text_ctrl: TextCtrl, content: &str) {
 /// prefix, so it would just store the markup as literal text. If streaming
 /// doesn't round-trip back to the original content, this falls back to the
 /// plain-text + per-segment path used on every other platform.
-fn fill_text_ctrl_with_formatting(text_ctrl: TextCtrl, session: &DocumentSession, content: &str) {
+///
+/// With `expose_structure` on, Windows also tags headings so the
+/// control's UI Automation tree reports them (see `reading_structure`).
+fn fill_text_ctrl_with_formatting(
+	text_ctrl: TextCtrl,
+	session: &DocumentSession,
+	content: &str,
+	expose_structure: bool,
+) {
+	fill_text_and_formatting(text_ctrl, session, content);
+	#[cfg(target_os = "windows")]
+	if expose_structure {
+		reading_structure::expose_document_structure(text_ctrl, &session.get_structure_markers());
+	}
+	#[cfg(not(target_os = "windows"))]
+	let _ = expose_structure;
+}
+
+fn fill_text_and_formatting(text_ctrl: TextCtrl, session: &DocumentSession, content: &str) {
 	let markers = session.get_formatting_markers();
 	let segments = merge_formatting_markers(&markers);
 
@@ -1000,22 +2153,91 @@ pub fn build_font_from_readability(rf: &ReadabilityFont) -> Option<Font> {
 	Some(font)
 }
 
-pub fn apply_foreground_color_to_ctrl(text_ctrl: TextCtrl, color: i32) {
-	if color >= 0 {
-		let r = ((color >> 16) & 0xFF) as u8;
-		let g = ((color >> 8) & 0xFF) as u8;
-		let b = (color & 0xFF) as u8;
-		text_ctrl.set_foreground_color(Colour::rgb(r, g, b));
+/// Foreground and background of the reading view as packed `0xRRGGBB`, with -1 meaning the system color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadingColors {
+	pub foreground: i32,
+	pub background: i32,
+}
+
+impl ReadingColors {
+	pub const SYSTEM: Self = Self { foreground: -1, background: -1 };
+
+	/// Resolves the configured color scheme. High-contrast mode always wins so the OS theme stays legible.
+	pub fn from_config(config: &ConfigManager) -> Self {
+		if is_high_contrast_active() {
+			return Self::SYSTEM;
+		}
+		let font_color = config.get_readability_font().color;
+		match get_color_scheme(config) {
+			ColorScheme::System => Self { foreground: font_color, background: -1 },
+			ColorScheme::Light => Self { foreground: 0x00_0000, background: 0xFF_FFFF },
+			ColorScheme::Dark => Self { foreground: 0xE6_E6E6, background: 0x1E_1E1E },
+			ColorScheme::Custom => {
+				let text_color = get_text_color(config);
+				Self {
+					foreground: if text_color >= 0 { text_color } else { font_color },
+					background: config.get_bg_color(),
+				}
+			}
+		}
+	}
+
+	/// Used by the dark reading view, whatever the system appearance or color scheme.
+	pub const DARK_READING_VIEW: Self = Self { foreground: 0xD4_D4D4, background: 0x12_1212 };
+
+	/// Colors for document text: the dark reading view while it's on, otherwise the configured scheme.
+	/// Dialogs keep using [`Self::from_config`].
+	pub fn for_document(config: &ConfigManager) -> Self {
+		if config.get_app_bool("dark_reading_view", false) && !is_high_contrast_active() {
+			return Self::DARK_READING_VIEW;
+		}
+		Self::from_config(config)
 	}
+
+	/// Whether applying these colors needs a fresh control, since a set color can't be unset in place.
+	pub const fn needs_rebuild_from(self, previous: Self) -> bool {
+		(self.foreground < 0 && previous.foreground >= 0) || (self.background < 0 && previous.background >= 0)
+	}
+}
+
+fn unpack_colour(color: i32) -> Colour {
+	Colour::rgb(((color >> 16) & 0xFF) as u8, ((color >> 8) & 0xFF) as u8, (color & 0xFF) as u8)
 }
 
-pub fn apply_bg_color_to_ctrl(text_ctrl: TextCtrl, color: i32) {
-	if color >= 0 {
-		let r = ((color >> 16) & 0xFF) as u8;
-		let g = ((color >> 8) & 0xFF) as u8;
-		let b = (color & 0xFF) as u8;
-		text_ctrl.set_background_color(Colour::rgb(r, g, b));
+pub fn apply_reading_colors(window: &impl WxWidget, colors: ReadingColors) {
+	if colors.foreground >= 0 {
+		window.set_foreground_color(unpack_colour(colors.foreground));
 	}
+	if colors.background >= 0 {
+		window.set_background_color(unpack_colour(colors.background));
+	}
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_high_contrast_active() -> bool {
+	use std::mem::size_of;
+
+	use windows::Win32::UI::{
+		Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+		WindowsAndMessaging::{SPI_GETHIGHCONTRAST, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, SystemParametersInfoW},
+	};
+	let mut info = HIGHCONTRASTW { cbSize: size_of::<HIGHCONTRASTW>() as u32, ..Default::default() };
+	let ok = unsafe {
+		SystemParametersInfoW(
+			SPI_GETHIGHCONTRAST,
+			info.cbSize,
+			Some(addr_of_mut!(info).cast()),
+			SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+		)
+	}
+	.is_ok();
+	ok && info.dwFlags.contains(HCF_HIGHCONTRASTON)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub const fn is_high_contrast_active() -> bool {
+	false
 }
 
 pub fn apply_text_alignment_to_ctrl(text_ctrl: TextCtrl, alignment: i32) {
@@ -1029,6 +2251,51 @@ pub fn apply_text_alignment_to_ctrl(text_ctrl: TextCtrl, alignment: i32) {
 	text_ctrl.set_style(0, text_ctrl.get_last_position(), &attr);
 }
 
+/// Lays `text_ctrl` out for `direction`. RichEdit gets genuine right-to-left paragraphs, which
+/// start at the right edge and keep trailing punctuation at the end of the sentence; the GTK and
+/// Cocoa controls already order each paragraph by its own characters, so there only the alignment
+/// follows. An alignment chosen in Options is left alone.
+#[cfg(target_os = "windows")]
+pub fn apply_text_direction_to_ctrl(text_ctrl: TextCtrl, direction: TextDirection, _alignment: i32) {
+	use windows::Win32::{
+		Foundation::{HWND, LPARAM, WPARAM},
+		UI::{
+			Controls::RichEdit::{PARAFORMAT2, PFM_RTLPARA},
+			WindowsAndMessaging::SendMessageW,
+		},
+	};
+	const EM_GETSEL: u32 = 176;
+	const EM_SETSEL: u32 = 177;
+	const EM_SETPARAFORMAT: u32 = 1095;
+	const PFE_RTLPARA: u16 = 1;
+	let hwnd_ptr = text_ctrl.get_handle();
+	if hwnd_ptr.is_null() {
+		return;
+	}
+	let hwnd = HWND(hwnd_ptr);
+	unsafe {
+		let mut caret: u32 = 0;
+		SendMessageW(hwnd, EM_GETSEL, Some(WPARAM(addr_of_mut!(caret) as usize)), None);
+		SendMessageW(hwnd, EM_SETSEL, Some(WPARAM(0)), Some(LPARAM(-1_isize)));
+		let mut pf = PARAFORMAT2::default();
+		pf.Base.cbSize = size_of::<PARAFORMAT2>() as u32;
+		pf.Base.dwMask = PFM_RTLPARA;
+		pf.Base.Anonymous.wEffects = if direction == TextDirection::RightToLeft { PFE_RTLPARA } else { 0 };
+		SendMessageW(hwnd, EM_SETPARAFORMAT, Some(WPARAM(0)), Some(LPARAM(&raw const pf as isize)));
+		SendMessageW(hwnd, EM_SETSEL, Some(WPARAM(caret as usize)), Some(LPARAM(caret as isize)));
+	}
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn apply_text_direction_to_ctrl(text_ctrl: TextCtrl, direction: TextDirection, alignment: i32) {
+	if alignment != 0 {
+		return;
+	}
+	let mut attr = wxdragon::widgets::textctrl::TextAttr::new();
+	attr.set_alignment(if direction == TextDirection::RightToLeft { 3 } else { 1 });
+	text_ctrl.set_style(0, text_ctrl.get_last_position(), &attr);
+}
+
 #[cfg(target_os = "windows")]
 pub fn apply_letter_spacing_to_ctrl(text_ctrl: TextCtrl, spacing: i32) {
 	use windows::Win32::{
@@ -1341,7 +2608,10 @@ fn show_reader_context_menu(text_ctrl: TextCtrl) {
 /// Parses shortcut strings from menu entry labels to stay in sync with menu definitions.
 #[cfg(target_os = "linux")]
 fn build_navigation_key_map() -> HashMap<(i32, bool), i32> {
-	use super::menu::{self, MenuEntry};
+	use super::{
+		menu::{self, MenuEntry},
+		shortcuts,
+	};
 
 	let mut map = HashMap::new();
 	let all_entries = [
@@ -1351,6 +2621,8 @@ fn build_navigation_key_map() -> HashMap<(i32, bool), i32> {
 		menu::links_entries(),
 		menu::tables_entries(),
 		menu::separators_entries(),
+		menu::block_quotes_entries(),
+		menu::code_blocks_entries(),
 		menu::lists_entries(),
 		menu::containers_entries(),
 		menu::bookmarks_entries(),
@@ -1358,7 +2630,7 @@ fn build_navigation_key_map() -> HashMap<(i32, bool), i32> {
 	for entries in &all_entries {
 		for entry in entries {
 			if let MenuEntry::Item(spec) = entry {
-				if let Some((key, shift)) = parse_single_key_shortcut(&spec.label) {
+				if let Some((key, shift)) = parse_single_key_shortcut(&shortcuts::apply(spec.id, &spec.label)) {
 					map.insert((key, shift), spec.id);
 				}
 			}
@@ -1387,11 +2659,56 @@ fn parse_single_key_shortcut(label: &str) -> Option<(i32, bool)> {
 	None
 }
 
+/// "Bookmark: " and the bookmark's note, or the text it marks when it has none.
+fn bookmark_entry_message(session: &DocumentSession, bookmark: &Bookmark) -> String {
+	let text = if bookmark.note.is_empty() {
+		if bookmark.start == bookmark.end {
+			session.get_line_text(bookmark.start)
+		} else {
+			session.get_text_range(bookmark.start, bookmark.end)
+		}
+	} else {
+		bookmark.note.clone()
+	};
+	// TRANSLATORS: Spoken when the caret moves into a bookmark; {} is the bookmark's note, or the text it marks
+	t("Bookmark: {}").replace("{}", &ellipsize_words(text.trim(), BOOKMARK_SNIPPET_CHARS))
+}
+
+fn structure_transition_message(transition: StructureTransition) -> String {
+	match transition {
+		StructureTransition::Entered(region) => match region.span.mtype {
+			// TRANSLATORS: Spoken when the arrow keys move into a list; %d is the number of items in it
+			MarkerType::List => t("List with %d items").replace("%d", &region.item_count.to_string()),
+			// TRANSLATORS: Spoken when the arrow keys move into a table
+			MarkerType::Table => t("Table"),
+			// TRANSLATORS: Spoken when the arrow keys move into a block quote
+			MarkerType::BlockQuote => t("Block quote"),
+			// TRANSLATORS: Spoken when the arrow keys move into a code block
+			_ => t("Code block"),
+		},
+		StructureTransition::Left(mtype) => match mtype {
+			// TRANSLATORS: Spoken when the arrow keys move out of a list
+			MarkerType::List => t("Out of list"),
+			// TRANSLATORS: Spoken when the arrow keys move out of a table
+			MarkerType::Table => t("Out of table"),
+			// TRANSLATORS: Spoken when the arrow keys move out of a block quote
+			MarkerType::BlockQuote => t("Out of block quote"),
+			// TRANSLATORS: Spoken when the arrow keys move out of a code block
+			_ => t("Out of code block"),
+		},
+	}
+}
+
 #[cfg(test)]
 mod tests {
+	use std::{
+		path::Path,
+		time::{Duration, Instant},
+	};
+
 	use paperback_core::session::{LineMarker, MarkerTypeFfi};
 
-	use super::{FormatSegment, merge_formatting_markers};
+	use super::{FormatSegment, READING_IDLE_TIMEOUT, ReadingClock, merge_formatting_markers};
 
 	fn marker(mtype: MarkerTypeFfi, position: i64, length: i64) -> LineMarker {
 		LineMarker { mtype, position, text: String::new(), reference: String::new(), level: 0, length }
@@ -1458,4 +2775,31 @@ mod tests {
 			vec![FormatSegment { start: 0, end: 6, bold: true, italic: true, underline: true }]
 		);
 	}
+
+	#[test]
+	fn reading_clock_credits_the_previous_document_on_switch() {
+		let start = Instant::now();
+		let mut clock = ReadingClock::default();
+		assert!(clock.start(Path::new("a.epub"), start).is_none());
+		clock.touch(start + Duration::from_secs(30));
+		let (path, elapsed) = clock.start(Path::new("b.epub"), start + Duration::from_secs(40)).unwrap();
+		assert_eq!(path, Path::new("a.epub"));
+		assert_eq!(elapsed, Duration::from_secs(40));
+		let (path, elapsed) = clock.stop(start + Duration::from_secs(50)).unwrap();
+		assert_eq!(path, Path::new("b.epub"));
+		assert_eq!(elapsed, Duration::from_secs(10));
+		assert!(clock.stop(start + Duration::from_secs(60)).is_none());
+	}
+
+	#[test]
+	fn reading_clock_pauses_after_idle_timeout() {
+		let start = Instant::now();
+		let mut clock = ReadingClock::default();
+		clock.start(Path::new("a.epub"), start);
+		clock.touch(start + Duration::from_secs(8 * 60 * 60));
+		let (_, elapsed) = clock.take(start + Duration::from_secs(8 * 60 * 60 + 20)).unwrap();
+		assert_eq!(elapsed, READING_IDLE_TIMEOUT + Duration::from_secs(20));
+		let (_, elapsed) = clock.take(start + Duration::from_secs(8 * 60 * 60 + 25)).unwrap();
+		assert_eq!(elapsed, Duration::from_secs(5));
+	}
 }
@@ -1,24 +1,36 @@
 use std::{cell::Cell, rc::Rc, sync::Mutex};
 
 use bitflags::bitflags;
-use paperback_core::{config::ConfigManager, reader_core, util::text::display_len};
+use paperback_core::{
+	config::{ConfigManager, FindScope, FindSettings},
+	reader_core::{self, SearchPatternError},
+	util::text::display_len,
+};
 use patois::t;
-use wxdragon::prelude::*;
+use wxdragon::{color::Colour, prelude::*, widgets::textctrl::TextAttr};
 
-use super::document_manager::DocumentManager;
+use super::{
+	document_manager::{DocumentManager, DocumentTab, ReadingColors, apply_reading_colors, display_title},
+	speech,
+};
+use crate::translation_manager::{format_message, format_number};
 
 const DIALOG_PADDING: i32 = 10;
 const MAX_FIND_HISTORY_SIZE: usize = 10;
+/// Matches painted at most by "Highlight all matches", to keep restyling a huge book quick.
+const MAX_HIGHLIGHTED_MATCHES: usize = 5_000;
 
 #[derive(Clone, Debug, Default)]
 pub struct SearchResult {
 	pub found: bool,
 	pub wrapped: bool,
 	pub position: i64,
+	/// Display length of the matched text, which can differ from the query's.
+	pub length: i64,
 }
 
 bitflags! {
-	#[derive(Copy, Clone, Default)]
+	#[derive(Copy, Clone, Default, PartialEq, Eq)]
 	pub struct FindOptions: u8 {
 		const NONE = 0;
 		const FORWARD = 1 << 0;
@@ -28,10 +40,7 @@ bitflags! {
 	}
 }
 
-pub fn find_text_with_wrap(haystack: &str, needle: &str, start: i64, options: FindOptions) -> SearchResult {
-	if needle.is_empty() {
-		return SearchResult::default();
-	}
+fn to_search_options(options: FindOptions) -> reader_core::SearchOptions {
 	let mut search_options = reader_core::SearchOptions::empty();
 	if options.contains(FindOptions::FORWARD) {
 		search_options |= reader_core::SearchOptions::FORWARD;
@@ -45,17 +54,151 @@ pub fn find_text_with_wrap(haystack: &str, needle: &str, start: i64, options: Fi
 	if options.contains(FindOptions::USE_REGEX) {
 		search_options |= reader_core::SearchOptions::REGEX;
 	}
-	let result = reader_core::reader_search_with_wrap(haystack, needle, start, search_options);
-	SearchResult { found: result.found, wrapped: result.wrapped, position: result.position }
+	search_options
+}
+
+pub fn find_text_with_wrap(
+	haystack: &str,
+	needle: &str,
+	start: i64,
+	options: FindOptions,
+) -> Result<SearchResult, SearchPatternError> {
+	if needle.is_empty() {
+		return Ok(SearchResult::default());
+	}
+	let result = reader_core::reader_search_with_wrap(haystack, needle, start, to_search_options(options))?;
+	Ok(SearchResult { found: result.found, wrapped: result.wrapped, position: result.position, length: result.length })
+}
+
+/// The matches "Highlight all matches" painted in a tab, and the query they were found with.
+#[derive(Clone, Default)]
+pub struct FindHighlights {
+	query: String,
+	options: FindOptions,
+	ranges: Vec<(i64, i64)>,
+}
+
+impl FindHighlights {
+	/// Whether these are the highlights of `query`, so there's nothing to redo.
+	pub fn is_for(&self, query: &str) -> bool {
+		!self.ranges.is_empty() && self.query == query
+	}
+}
+
+/// Background for highlighted matches, dimmed on dark reading colors so the text stays legible.
+fn highlight_colour(colors: ReadingColors) -> Colour {
+	let [_, red, green, blue] = colors.background.to_be_bytes();
+	let dark = colors.background >= 0 && u16::from(red) + u16::from(green) + u16::from(blue) < 0x180;
+	if dark { Colour::rgb(0x6B, 0x55, 0x00) } else { Colour::rgb(0xFF, 0xE0, 0x66) }
+}
+
+fn set_background(text_ctrl: TextCtrl, ranges: &[(i64, i64)], colour: Colour) {
+	if ranges.is_empty() {
+		return;
+	}
+	let last_pos = text_ctrl.get_last_position();
+	let mut attr = TextAttr::new();
+	attr.set_background_color(colour);
+	text_ctrl.freeze();
+	for &(start, length) in ranges.iter().filter(|&&(start, _)| start < last_pos) {
+		text_ctrl.set_style(start, (start + length).min(last_pos), &attr);
+	}
+	text_ctrl.thaw();
+}
+
+/// Paints `highlights` onto `text_ctrl`, which is how they survive the control being rebuilt.
+pub fn paint_find_highlights(text_ctrl: TextCtrl, highlights: &FindHighlights, colors: ReadingColors) {
+	set_background(text_ctrl, &highlights.ranges, highlight_colour(colors));
+}
+
+/// Takes the highlights off `tab`, giving the matches the control's own background again.
+pub fn clear_find_highlights(tab: &mut DocumentTab) {
+	let ranges = std::mem::take(&mut tab.find_highlights).ranges;
+	set_background(tab.text_ctrl, &ranges, tab.text_ctrl.get_background_color());
+}
+
+/// Highlights every match of `query` in the active tab, unless they already are. Only the first
+/// [`MAX_HIGHLIGHTED_MATCHES`] are painted, which is announced.
+fn highlight_all_matches(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	query: &str,
+	options: FindOptions,
+	live_region_label: StaticText,
+) {
+	let options = options.difference(FindOptions::FORWARD);
+	let colors = ReadingColors::for_document(&config.lock().unwrap());
+	let mut dm = doc_manager.lock().unwrap();
+	let Some(tab) = dm.active_tab_mut() else {
+		return;
+	};
+	if tab.find_highlights.is_for(query) && tab.find_highlights.options == options {
+		return;
+	}
+	clear_find_highlights(tab);
+	let Ok((ranges, truncated)) = reader_core::reader_search_all(
+		tab.session.content_ref(),
+		query,
+		to_search_options(options),
+		MAX_HIGHLIGHTED_MATCHES,
+	) else {
+		return;
+	};
+	tab.find_highlights = FindHighlights { query: query.to_string(), options, ranges };
+	paint_find_highlights(tab.text_ctrl, &tab.find_highlights, colors);
+	if truncated {
+		// TRANSLATORS: Announced when Find has more matches than it highlights; {} is how many were highlighted
+		let message = t("Highlighted the first {} matches.");
+		speech::announce(live_region_label, &message.replace("{}", &format_number(MAX_HIGHLIGHTED_MATCHES)));
+	}
+}
+
+/// Highlights the matches around the one just found, or takes old highlights away when the option
+/// is off.
+fn update_highlights(
+	state: &FindDialogState,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	query: &str,
+	options: FindOptions,
+	live_region_label: StaticText,
+) {
+	if state.highlight_all.is_checked() {
+		highlight_all_matches(doc_manager, config, query, options, live_region_label);
+	} else {
+		doc_manager.lock().unwrap().clear_find_highlights();
+	}
+}
+
+pub fn search_pattern_error_message(error: &SearchPatternError) -> String {
+	match error {
+		// TRANSLATORS: Announced when a Find pattern, such as the regular expression "a*", would match empty text
+		SearchPatternError::EmptyMatch => t("Pattern matches empty text."),
+		// TRANSLATORS: Announced when a regular expression in the Find dialog is too large to search with
+		SearchPatternError::TooComplex => t("Pattern is too complex."),
+		SearchPatternError::Syntax { position: Some(position), message } => {
+			// TRANSLATORS: Announced when a regular expression in the Find dialog is invalid; the first {} is the character position, the second the error
+			t("Invalid pattern at position {}: {}")
+				.replacen("{}", &(position + 1).to_string(), 1)
+				.replacen("{}", message, 1)
+		}
+		SearchPatternError::Syntax { position: None, message } => {
+			// TRANSLATORS: Announced when a regular expression in the Find dialog is invalid; {} is the error
+			t("Invalid pattern: {}").replace("{}", message)
+		}
+	}
 }
 
 #[derive(Clone)]
 pub struct FindDialogState {
 	pub dialog: Dialog,
 	find_combo: ComboBox,
+	search_in: Choice,
 	match_case: CheckBox,
 	whole_word: CheckBox,
 	use_regex: CheckBox,
+	all_documents: CheckBox,
+	highlight_all: CheckBox,
 	in_progress: Rc<Cell<bool>>,
 }
 
@@ -71,9 +214,12 @@ impl FindDialogState {
 		let dialog = Dialog::builder(frame, &t("Find")).build();
 		let FindDialogWidgets {
 			find_combo,
+			search_in,
 			match_case,
 			whole_word,
 			use_regex,
+			all_documents,
+			highlight_all,
 			find_prev_btn,
 			find_next_btn,
 			cancel_btn,
@@ -90,8 +236,17 @@ impl FindDialogState {
 			find_dialog: Rc::clone(find_dialog),
 			live_region_label,
 		});
-		let state =
-			Self { dialog, find_combo, match_case, whole_word, use_regex, in_progress: Rc::new(Cell::new(false)) };
+		let state = Self {
+			dialog,
+			find_combo,
+			search_in,
+			match_case,
+			whole_word,
+			use_regex,
+			all_documents,
+			highlight_all,
+			in_progress: Rc::new(Cell::new(false)),
+		};
 		state.reload_history(config);
 		state.save_settings(config);
 		state
@@ -109,13 +264,22 @@ impl FindDialogState {
 		self.match_case.set_value(settings.match_case);
 		self.whole_word.set_value(settings.whole_word);
 		self.use_regex.set_value(settings.use_regex);
+		self.all_documents.set_value(settings.all_documents);
+		self.highlight_all.set_value(settings.highlight_all);
+		self.search_in.set_selection(match settings.scope {
+			FindScope::Text => 0,
+			FindScope::Headings => 1,
+		});
 	}
 
 	pub fn save_settings(&self, config: &Rc<Mutex<ConfigManager>>) {
-		let settings = paperback_core::config::FindSettings {
+		let settings = FindSettings {
 			match_case: self.match_case.is_checked(),
 			whole_word: self.whole_word.is_checked(),
 			use_regex: self.use_regex.is_checked(),
+			all_documents: self.all_documents.is_checked(),
+			highlight_all: self.highlight_all.is_checked(),
+			scope: self.scope(),
 		};
 		config.lock().unwrap().set_find_settings(settings);
 	}
@@ -126,6 +290,10 @@ impl FindDialogState {
 		self.find_combo.set_value(text);
 	}
 
+	fn scope(&self) -> FindScope {
+		if self.search_in.get_selection() == Some(1) { FindScope::Headings } else { FindScope::Text }
+	}
+
 	pub fn find_text(&self) -> String {
 		self.find_combo.get_value()
 	}
@@ -152,9 +320,12 @@ impl FindDialogState {
 
 struct FindDialogWidgets {
 	find_combo: ComboBox,
+	search_in: Choice,
 	match_case: CheckBox,
 	whole_word: CheckBox,
 	use_regex: CheckBox,
+	all_documents: CheckBox,
+	highlight_all: CheckBox,
 	find_prev_btn: Button,
 	find_next_btn: Button,
 	cancel_btn: Button,
@@ -183,6 +354,17 @@ fn build_find_dialog_ui(dialog: Dialog) -> FindDialogWidgets {
 		.with_style(ComboBoxStyle::ProcessEnter)
 		.with_size(Size::new(combo_width, -1))
 		.build();
+	// TRANSLATORS: Label for the choice of what the Find dialog searches: the document text or its headings
+	let search_in_label_text = t("&Search in:");
+	let search_in_label = StaticText::builder(&dialog).with_label(&search_in_label_text).build();
+	let search_in = Choice::builder(&dialog).build();
+	// TRANSLATORS: Choice in the Find dialog's "Search in" list to search the whole text of the document
+	search_in.append(&t("Document text"));
+	// TRANSLATORS: Choice in the Find dialog's "Search in" list to search only headings and table of contents entries
+	search_in.append(&t("Headings and contents"));
+	search_in.set_selection(0);
+	#[cfg(target_os = "macos")]
+	search_in.set_accessibility_label(search_in_label_text.replace('&', "").trim_end_matches(':').trim());
 	let options_box = StaticBoxSizerBuilder::new_with_label(Orientation::Vertical, &dialog, &t("Options")).build();
 	// TRANSLATORS: Checkbox to make the search case-sensitive
 	let match_case = CheckBox::builder(&dialog).with_label(&t("&Match case")).build();
@@ -192,7 +374,13 @@ fn build_find_dialog_ui(dialog: Dialog) -> FindDialogWidgets {
 	let use_regex = CheckBox::builder(&dialog).with_label(&t("Use &regular expressions")).build();
 	options_box.add(&match_case, 0, SizerFlag::All, option_padding);
 	options_box.add(&whole_word, 0, SizerFlag::All, option_padding);
+	// TRANSLATORS: Checkbox to continue the search into the other open documents when the current one has no more matches
+	let all_documents = CheckBox::builder(&dialog).with_label(&t("Find in &all open documents")).build();
+	// TRANSLATORS: Checkbox to highlight every match in the document once Find lands on one
+	let highlight_all = CheckBox::builder(&dialog).with_label(&t("&Highlight all matches")).build();
 	options_box.add(&use_regex, 0, SizerFlag::All, option_padding);
+	options_box.add(&all_documents, 0, SizerFlag::All, option_padding);
+	options_box.add(&highlight_all, 0, SizerFlag::All, option_padding);
 	// TRANSLATORS: Button to search backward for the previous match
 	let find_prev_btn = Button::builder(&dialog).with_label(&t("Find &Previous")).build();
 	// TRANSLATORS: Button to search forward for the next match
@@ -203,6 +391,9 @@ fn build_find_dialog_ui(dialog: Dialog) -> FindDialogWidgets {
 	let find_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	find_sizer.add(&find_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
 	find_sizer.add(&find_combo, 1, SizerFlag::Expand, 0);
+	let search_in_sizer = BoxSizer::builder(Orientation::Horizontal).build();
+	search_in_sizer.add(&search_in_label, 0, SizerFlag::AlignCenterVertical | SizerFlag::Right, DIALOG_PADDING);
+	search_in_sizer.add(&search_in, 1, SizerFlag::Expand, 0);
 	let button_sizer = BoxSizer::builder(Orientation::Horizontal).build();
 	button_sizer.add(&find_prev_btn, 0, SizerFlag::Right, button_spacing);
 	button_sizer.add(&find_next_btn, 0, SizerFlag::Right, button_spacing);
@@ -210,6 +401,12 @@ fn build_find_dialog_ui(dialog: Dialog) -> FindDialogWidgets {
 	button_sizer.add(&cancel_btn, 0, SizerFlag::All, 0);
 	let main_sizer = BoxSizer::builder(Orientation::Vertical).build();
 	main_sizer.add_sizer(&find_sizer, 0, SizerFlag::Expand | SizerFlag::All, DIALOG_PADDING);
+	main_sizer.add_sizer(
+		&search_in_sizer,
+		0,
+		SizerFlag::Expand | SizerFlag::Left | SizerFlag::Right | SizerFlag::Bottom,
+		DIALOG_PADDING,
+	);
 	main_sizer.add_sizer(
 		&options_box,
 		0,
@@ -224,7 +421,18 @@ fn build_find_dialog_ui(dialog: Dialog) -> FindDialogWidgets {
 	);
 	dialog.set_sizer_and_fit(main_sizer, true);
 	dialog.centre();
-	FindDialogWidgets { find_combo, match_case, whole_word, use_regex, find_prev_btn, find_next_btn, cancel_btn }
+	FindDialogWidgets {
+		find_combo,
+		search_in,
+		match_case,
+		whole_word,
+		use_regex,
+		all_documents,
+		highlight_all,
+		find_prev_btn,
+		find_next_btn,
+		cancel_btn,
+	}
 }
 
 fn bind_find_dialog_actions(params: FindDialogActionParams) {
@@ -271,11 +479,13 @@ fn bind_find_dialog_actions(params: FindDialogActionParams) {
 	let dialog_for_cancel = dialog;
 	let find_dialog_for_cancel = Rc::clone(&find_dialog);
 	let config_for_cancel = Rc::clone(&config);
+	let doc_manager_for_cancel = Rc::clone(&doc_manager);
 	cancel_btn.on_click(move |_| {
 		if let Some(state) = find_dialog_for_cancel.lock().unwrap().as_ref() {
 			state.save_settings(&config_for_cancel);
 			dialog_for_cancel.show(false);
 		}
+		doc_manager_for_cancel.lock().unwrap().clear_find_highlights();
 	});
 	let frame_for_enter = frame;
 	let find_dialog_for_enter = Rc::clone(&find_dialog);
@@ -292,14 +502,27 @@ fn bind_find_dialog_actions(params: FindDialogActionParams) {
 		);
 		event.skip(false);
 	});
+	// Highlights of the old query would be misleading once it's edited. Filling the box in from the
+	// history keeps the query, so those stay.
+	let doc_manager_for_text = Rc::clone(&doc_manager);
+	find_combo.on_text_updated(move |_| {
+		let query = find_combo.get_value();
+		if let Ok(mut dm) = doc_manager_for_text.try_lock()
+			&& dm.active_tab().is_some_and(|tab| !tab.find_highlights.is_for(&query))
+		{
+			dm.clear_find_highlights();
+		}
+	});
 	let dialog_for_close = dialog;
 	let find_dialog_for_close = Rc::clone(&find_dialog);
 	let config_for_close = Rc::clone(&config);
+	let doc_manager_for_close = Rc::clone(&doc_manager);
 	dialog.on_close(move |event| {
 		if let Some(state) = find_dialog_for_close.lock().unwrap().as_ref() {
 			state.save_settings(&config_for_close);
 		}
 		dialog_for_close.show(false);
+		doc_manager_for_close.lock().unwrap().clear_find_highlights();
 		event.skip(false);
 	});
 }
@@ -329,6 +552,32 @@ pub fn ensure_find_dialog(
 	*dialog_guard = Some(state);
 }
 
+/// Builds the Find dialog again in the current language, keeping the text being looked for and
+/// whether it was showing. Its options are saved first, so the new one starts with them.
+pub fn rebuild_find_dialog(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	find_dialog: &Rc<Mutex<Option<FindDialogState>>>,
+	live_region_label: StaticText,
+) {
+	let Some(old) = find_dialog.lock().unwrap().take() else {
+		return;
+	};
+	old.save_settings(config);
+	let text = old.find_text();
+	let shown = old.dialog.is_shown();
+	old.dialog.destroy();
+	ensure_find_dialog(frame, doc_manager, config, find_dialog, live_region_label);
+	let Some(state) = find_dialog.lock().unwrap().as_ref().cloned() else {
+		return;
+	};
+	state.find_combo.set_value(&text);
+	if shown {
+		state.dialog.show(true);
+	}
+}
+
 pub fn show_find_dialog(
 	frame: &Frame,
 	doc_manager: &Rc<Mutex<DocumentManager>>,
@@ -344,6 +593,8 @@ pub fn show_find_dialog(
 	let Some(state) = state else {
 		return;
 	};
+	let colors = ReadingColors::from_config(&config.lock().unwrap());
+	apply_reading_colors(&state.find_combo, colors);
 	let text_ctrl = {
 		let dm = doc_manager.lock().unwrap();
 		dm.active_tab().map(|tab| tab.text_ctrl)
@@ -396,6 +647,48 @@ pub fn handle_find_action(
 	do_find(forward, &state, doc_manager, config, live_region_label);
 }
 
+/// Runs `search` over the active tab's text while the manager is locked, so the text is searched in
+/// place rather than copied. `None` when no document is open.
+fn search_active_text<T>(doc_manager: &Rc<Mutex<DocumentManager>>, search: impl FnOnce(&str) -> T) -> Option<T> {
+	let dm = doc_manager.lock().unwrap();
+	dm.active_tab().map(|tab| search(tab.session.content_ref()))
+}
+
+/// Searches the other open documents in notebook order (wrapping), starting after `active_index`.
+/// Returns the tab index, its text control, its title and the match position and length of the first hit.
+fn find_in_other_documents(
+	dm: &DocumentManager,
+	active_index: usize,
+	query: &str,
+	options: FindOptions,
+) -> Option<(usize, TextCtrl, String, (i64, i64))> {
+	let count = dm.tab_count();
+	let forward = options.contains(FindOptions::FORWARD);
+	(1..count).find_map(|step| {
+		let index = if forward { (active_index + step) % count } else { (active_index + count - step) % count };
+		let tab = dm.get_tab(index)?;
+		let text = tab.session.content_ref();
+		let start = if forward { 0 } else { i64::try_from(display_len(text)).unwrap_or(i64::MAX) };
+		let found = reader_core::reader_search_match(text, query, start, to_search_options(options))?;
+		Some((index, tab.text_ctrl, display_title(tab), found))
+	})
+}
+
+fn select_match(text_ctrl: TextCtrl, position: i64, len: i64) {
+	if position < 0 {
+		return;
+	}
+	let last_pos = text_ctrl.get_last_position();
+	if last_pos <= 0 {
+		return;
+	}
+	let start = position.clamp(0, last_pos);
+	let end = (start + len).min(last_pos);
+	text_ctrl.set_focus();
+	text_ctrl.set_selection(start, end);
+	text_ctrl.show_position(start);
+}
+
 fn do_find(
 	forward: bool,
 	state: &FindDialogState,
@@ -403,20 +696,17 @@ fn do_find(
 	config: &Rc<Mutex<ConfigManager>>,
 	live_region_label: StaticText,
 ) {
-	let (text_ctrl, raw_text) = {
-		let dm = doc_manager.lock().unwrap();
-		match dm.active_tab() {
-			Some(tab) => (tab.text_ctrl, tab.session.content()),
-			None => return,
+	let load_all_pages = config.lock().unwrap().get_app_bool("find_loads_all_pages", true);
+	let (text_ctrl, active_index, partial) = {
+		let mut dm = doc_manager.lock().unwrap();
+		if load_all_pages && dm.active_tab().is_some_and(|tab| !tab.session.is_fully_loaded()) {
+			dm.load_active_document_fully();
+		}
+		match (dm.active_tab(), dm.active_tab_index()) {
+			(Some(tab), Some(index)) => (tab.text_ctrl, index, !tab.session.is_fully_loaded()),
+			_ => return,
 		}
 	};
-	// On Windows the Rich Edit control normalizes line endings to \n, so
-	// positions from get_selection() / set_selection() are in \n-only space.
-	// session.content() may contain raw \r\n (e.g. Windows .txt files), which
-	// would cause search positions to drift by one per newline after the first
-	// match.  Normalize to \n so the haystack coordinate system matches the
-	// text control's coordinate system.
-	let text = if raw_text.contains('\r') { raw_text.replace("\r\n", "\n").replace('\r', "\n") } else { raw_text };
 	if !text_ctrl.is_valid() {
 		return;
 	}
@@ -442,34 +732,113 @@ fn do_find(
 	if state.use_regex.is_checked() {
 		options |= FindOptions::USE_REGEX;
 	}
+	if let Err(error) = reader_core::build_search_regex(&query, to_search_options(options)) {
+		speech::announce(live_region_label, &search_pattern_error_message(&error));
+		state.dialog.show(true);
+		state.dialog.raise();
+		state.focus_find_text();
+		return;
+	}
 	let (sel_start, sel_end) = text_ctrl.get_selection();
 	let start_pos = if forward { sel_end } else { sel_start };
-	let result = find_text_with_wrap(&text, &query, start_pos, options);
+	if state.scope() == FindScope::Headings {
+		find_heading(state, doc_manager, text_ctrl, &query, start_pos, options, live_region_label);
+		return;
+	}
+	if state.all_documents.is_checked() {
+		let found = search_active_text(doc_manager, |text| {
+			reader_core::reader_search_match(text, &query, start_pos, to_search_options(options))
+		})
+		.flatten();
+		if let Some((position, length)) = found {
+			select_match(text_ctrl, position, length);
+			update_highlights(state, doc_manager, config, &query, options, live_region_label);
+			state.dialog.show(false);
+			return;
+		}
+		let hit = find_in_other_documents(&doc_manager.lock().unwrap(), active_index, &query, options);
+		if let Some((index, other_ctrl, title, (position, length))) = hit {
+			tracing::debug!(query = %query, forward, index, "find continued in another document");
+			// Switch with the manager unlocked so the page-change handler can update the title.
+			let notebook = *doc_manager.lock().unwrap().notebook();
+			notebook.set_selection(index);
+			// TRANSLATORS: Announced when Find continues into another open document; {} is that document's title
+			speech::announce(live_region_label, &t("Found in {}").replace("{}", &title));
+			select_match(other_ctrl, position, length);
+			update_highlights(state, doc_manager, config, &query, options, live_region_label);
+			state.dialog.show(false);
+			return;
+		}
+	}
+	let result = search_active_text(doc_manager, |text| find_text_with_wrap(text, &query, start_pos, options))
+		.and_then(Result::ok)
+		.unwrap_or_default();
 	tracing::debug!(query = %query, forward, found = result.found, wrapped = result.wrapped, "find search");
 	if !result.found {
-		// TRANSLATORS: Announced when a search finds no matches in the document
-		live_region::announce(live_region_label, &t("Not found."));
+		let message = if partial {
+			// TRANSLATORS: Announced when a search of a large PDF that has not been fully read finds no matches in the pages read so far
+			t("Not found in the pages loaded so far.")
+		} else {
+			// TRANSLATORS: Announced when a search finds no matches in the document
+			t("Not found.")
+		};
+		speech::announce(live_region_label, &message);
 		state.dialog.show(true);
 		state.dialog.raise();
 		state.focus_find_text();
 		return;
 	}
 	if result.wrapped {
-		// TRANSLATORS: Announced when a search reaches the end of the document and wraps back to the start
-		live_region::announce(live_region_label, &t("No more results. Wrapping search."));
-	}
-	if result.position < 0 {
-		return;
+		let message = if partial {
+			// TRANSLATORS: Announced when a search reaches the end of the pages of a large PDF read so far and wraps back to the start
+			t("No more results in the pages loaded so far. Wrapping search.")
+		} else {
+			// TRANSLATORS: Announced when a search reaches the end of the document and wraps back to the start
+			t("No more results. Wrapping search.")
+		};
+		speech::announce(live_region_label, &message);
 	}
-	let len = i64::try_from(display_len(&query)).unwrap_or(i64::MAX);
-	let last_pos = text_ctrl.get_last_position();
-	if last_pos <= 0 {
+	select_match(text_ctrl, result.position, result.length);
+	update_highlights(state, doc_manager, config, &query, options, live_region_label);
+	state.dialog.show(false);
+}
+
+/// Find in "Headings and contents": moves the caret to the next heading or table of contents entry
+/// whose text matches, and announces it with its level.
+fn find_heading(
+	state: &FindDialogState,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	text_ctrl: TextCtrl,
+	query: &str,
+	start_pos: i64,
+	options: FindOptions,
+	live_region_label: StaticText,
+) {
+	let found = {
+		let mut dm = doc_manager.lock().unwrap();
+		dm.clear_find_highlights();
+		dm.active_tab().and_then(|tab| tab.session.search_headings(query, start_pos, to_search_options(options)).ok())
+	};
+	tracing::debug!(query = %query, found = found.as_ref().is_some_and(Option::is_some), "find heading search");
+	let Some(Some(heading)) = found else {
+		// TRANSLATORS: Announced when a search finds no matches in the document
+		speech::announce(live_region_label, &t("Not found."));
+		state.dialog.show(true);
+		state.dialog.raise();
+		state.focus_find_text();
 		return;
-	}
-	let start = result.position.clamp(0, last_pos);
-	let end = (start + len).min(last_pos);
+	};
+	let position = heading.position.clamp(0, text_ctrl.get_last_position());
 	text_ctrl.set_focus();
-	text_ctrl.set_selection(start, end);
-	text_ctrl.show_position(start);
+	text_ctrl.set_insertion_point(position);
+	text_ctrl.show_position(position);
+	let mut message =
+		// TRANSLATORS: Announcement when landing on a heading; %s is the heading text, %d is the heading level number
+		format_message(&t("%s Heading level %d"), &[("%s", &heading.text), ("%d", &heading.level.to_string())]);
+	if heading.wrapped {
+		// TRANSLATORS: Announced when a search reaches the end of the document and wraps back to the start
+		message = format!("{} {message}", t("No more results. Wrapping search."));
+	}
+	speech::announce(live_region_label, &message);
 	state.dialog.show(false);
 }
@@ -18,16 +18,29 @@ use ship_shape::{UpdateChannel as ShipChannel, UpdaterConfig};
 use wx_utils::show_error;
 use wxdragon::prelude::*;
 
+#[cfg(target_os = "windows")]
+use super::update;
 use super::{dialogs, document_manager::DocumentManager};
-use crate::{config_ext::UpdateChannel, translation_manager::TranslationManager};
+use crate::{config_ext::UpdateChannel, shell, translation_manager::TranslationManager};
 
 pub static MAIN_WINDOW_PTR: AtomicUsize = AtomicUsize::new(0);
 
 const PAPERBACK_GITHUB_REPO: &str = "trypsynth/paperback";
-const PAPERBACK_MINISIGN_KEY: &str = "RWQasnbWXwK2dhno9ThUm8HONEIo85iiDBZvw3jlNs574QJHEkoRiGX7";
+/// The public key release downloads are signed with.
+pub const PAPERBACK_MINISIGN_KEY: &str = "RWQasnbWXwK2dhno9ThUm8HONEIo85iiDBZvw3jlNs574QJHEkoRiGX7";
 
 pub fn run_update_check(silent: bool, channel: UpdateChannel) {
 	tracing::info!(channel = %channel, silent, "checking for updates");
+	#[cfg(target_os = "windows")]
+	if !silent && channel == UpdateChannel::Stable {
+		update::check_for_update(is_installer_distribution());
+		return;
+	}
+	run_ship_shape_check(silent, channel);
+}
+
+/// Checks for updates through ship-shape, which reports a newer version and links to it.
+pub fn run_ship_shape_check(silent: bool, channel: UpdateChannel) {
 	let config = Arc::new(UpdaterConfig::new(
 		PAPERBACK_GITHUB_REPO,
 		"paperback",
@@ -83,18 +96,12 @@ pub fn handle_reveal_file_in_folder(frame: &Frame, doc_manager: &Rc<Mutex<Docume
 		return;
 	};
 
-	#[cfg(target_os = "windows")]
-	{
-		std::process::Command::new("explorer").arg(format!("/select,{}", file_path.display())).spawn().ok();
-	}
-	#[cfg(not(target_os = "windows"))]
-	{
-		if let Some(dir) = file_path.parent() {
-			let url = format!("file://{}", dir.to_string_lossy());
-			if !wxdragon::utils::launch_default_browser(&url, wxdragon::utils::BrowserLaunchFlags::Default) {
-				show_error(frame, t("Failed to reveal file in folder."), &t("Error"));
-			}
-		}
+	if let Err(err) = shell::reveal_in_file_manager(&file_path) {
+		tracing::warn!(path = %file_path.display(), error = %err, "failed to reveal file in folder");
+		let message =
+			// TRANSLATORS: Error shown when the file manager couldn't be opened at a document; {} is the document's path
+			t("Couldn't show {} in the file manager.").replace("{}", &file_path.to_string_lossy());
+		show_error(frame, message, &t("Error"));
 	}
 }
 
@@ -160,19 +167,10 @@ fn ensure_parser_for_unknown_file(parent: &Frame, path: &Path, config: &ConfigMa
 	if !saved_format.is_empty() && parser::parser_supports_extension(&saved_format) {
 		return true;
 	}
-	let Some(format) = dialogs::show_open_as_dialog(parent, path) else {
+	let Some(choice) = dialogs::show_open_as_dialog(parent, path, None, None) else {
 		return false;
 	};
-	if !parser::parser_supports_extension(&format) {
-		// TRANSLATORS: Error shown when the user picks a file format from the "Open As" dialog that this parser build doesn't support
-		let message = t("Unsupported format selected.");
-		let title = t("Error");
-		let dialog = MessageDialog::builder(parent, &message, &title)
-			.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
-			.build();
-		dialog.show_modal();
-		return false;
-	}
-	config.set_document_format(&path_str, &format);
+	config.set_document_format(&path_str, &choice.format);
+	config.set_document_encoding(&path_str, &choice.encoding);
 	true
 }
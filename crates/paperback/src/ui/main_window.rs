@@ -3,47 +3,62 @@ use std::cell::RefCell;
 use std::{
 	cell::Cell,
 	env,
-	path::Path,
+	path::{Path, PathBuf},
 	process,
 	rc::Rc,
-	sync::{
-		Mutex,
-		atomic::{AtomicI32, AtomicI64, Ordering},
-	},
-	time::{SystemTime, UNIX_EPOCH},
+	sync::{Mutex, atomic::Ordering},
 };
 
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use paperback_core::{
+	config::Bookmark,
+	ipc_api::{self, ApiHost, ApiRequest, ApiResponse, DocumentState},
+	session::TocEntry,
+};
 use paperback_core::{
-	config::ConfigManager,
+	config::{BackupError, ConfigManager, ReadabilityFont},
+	document::{LineLayout, TextDirection},
 	parser::{build_file_filter_string, parser_supports_extension},
+	reader_core::{
+		BrailleOptions, OutlineOptions,
+		import_annotations::{AnnotationFormat, AnnotationImport},
+		lookup,
+	},
 	types::BookmarkFilterType,
 };
 use patois::t;
-use wxdragon::{prelude::*, timer::Timer};
+use wxdragon::{clipboard::Clipboard, prelude::*, timer::Timer};
 
 #[cfg(target_os = "windows")]
 use super::tray;
 use super::{
 	dialogs,
-	document_manager::{DocumentManager, build_font_from_readability, display_title},
+	document_manager::{DocumentManager, ReadingColors, build_font_from_readability, display_title},
 	find::{self, FindDialogState},
 	help::{self, MAIN_WINDOW_PTR},
-	menu, menu_ids,
-	navigation::{self, MarkerNavTarget},
-	status,
+	menu, menu_ids, navigation, shortcuts,
+	sleep_timer::{self, SleepTimerEvent},
+	speech, status,
+	url_download::{self, DownloadError},
 };
 #[cfg(any(target_os = "linux", target_os = "windows"))]
-use crate::ipc::IpcCommand;
+use crate::ipc::{IpcCommand, normalize_cli_path};
 use crate::{
-	config_ext::{UpdateChannel, get_update_channel, set_update_channel},
-	translation_manager::TranslationManager,
+	config_ext::{
+		SleepAction, UpdateChannel, get_autosave_interval, get_sleep_action, get_update_channel,
+		is_bookmark_category_navigation_enabled, mark_session_ended, mark_session_started, set_autosave_interval,
+		set_bookmark_category_navigation_enabled, set_color_scheme, set_sleep_action, set_text_color,
+		set_update_channel,
+	},
+	controller::{MarkerNavTarget, TextUnit},
+	shell,
+	translation_manager::{self, TranslationManager, plural},
 };
 
 const KEY_DELETE: i32 = 127;
 const KEY_NUMPAD_DELETE: i32 = 330;
-
-pub static SLEEP_TIMER_START_MS: AtomicI64 = AtomicI64::new(0);
-pub static SLEEP_TIMER_DURATION_MINUTES: AtomicI32 = AtomicI32::new(0);
+/// How often pages read in the background are moved into the text control.
+const PAGE_LOAD_POLL_INTERVAL_MS: i32 = 250;
 
 #[derive(Default)]
 struct RestoreState {
@@ -57,7 +72,7 @@ pub struct MainWindow {
 	config: Rc<Mutex<ConfigManager>>,
 	#[cfg(target_os = "windows")]
 	_tray_state: Rc<Mutex<Option<tray::TrayState>>>,
-	_live_region_label: StaticText,
+	live_region_label: StaticText,
 	_find_dialog: Rc<Mutex<Option<FindDialogState>>>,
 	#[cfg(target_os = "windows")]
 	_hotkey_handle: Rc<RefCell<Option<HotkeyHandle>>>,
@@ -66,6 +81,28 @@ pub struct MainWindow {
 #[cfg(target_os = "windows")]
 static HIDDEN_POPUP: std::sync::atomic::AtomicIsize = std::sync::atomic::AtomicIsize::new(0);
 
+/// Windows only lets the process owning the foreground window hand it over, so the input of the
+/// current foreground thread is shared for a moment while `hwnd` is brought forward.
+#[cfg(target_os = "windows")]
+fn force_foreground(hwnd: windows::Win32::Foundation::HWND) {
+	use windows::Win32::{
+		System::Threading::{AttachThreadInput, GetCurrentThreadId},
+		UI::WindowsAndMessaging::{
+			BringWindowToTop, GetForegroundWindow, GetWindowThreadProcessId, SetForegroundWindow,
+		},
+	};
+	let foreground_thread = unsafe { GetWindowThreadProcessId(GetForegroundWindow(), None) };
+	let current_thread = unsafe { GetCurrentThreadId() };
+	let attached = foreground_thread != 0
+		&& foreground_thread != current_thread
+		&& unsafe { AttachThreadInput(current_thread, foreground_thread, true) }.as_bool();
+	let _ = unsafe { BringWindowToTop(hwnd) };
+	let _ = unsafe { SetForegroundWindow(hwnd) };
+	if attached {
+		let _ = unsafe { AttachThreadInput(current_thread, foreground_thread, false) };
+	}
+}
+
 impl MainWindow {
 	pub fn new(config: Rc<Mutex<ConfigManager>>) -> Self {
 		// TRANSLATORS: Main window title when no document is open
@@ -75,6 +112,13 @@ impl MainWindow {
 		frame.create_status_bar(1, 0, -1, "statusbar");
 		// TRANSLATORS: Default status bar text when no document is open
 		frame.set_status_text(&t("Ready"), 0);
+		// A second instance shares the first one's config, so the running flag it finds is the first
+		// one's rather than a sign of a crash, and the documents to restore are the first one's too.
+		let second_instance = config.lock().unwrap().is_app_read_only();
+		let crashed = !second_instance && mark_session_started(&config.lock().unwrap());
+		if crashed {
+			tracing::warn!("previous session did not exit cleanly");
+		}
 		let menu_bar = menu::create_menu_bar(&config.lock().unwrap());
 		frame.set_menu_bar(menu_bar);
 		menu::update_menu_item_states(&frame, false);
@@ -115,16 +159,26 @@ impl MainWindow {
 				&& let Ok(new_index) = usize::try_from(new_index)
 				&& let Some(tab) = dm_ref.get_tab(new_index)
 			{
-				live_region::announce(live_region_label, &display_title(tab));
+				speech::announce(live_region_label, &display_title(tab));
 			}
 		});
 		let dm = Rc::clone(&doc_manager);
 		notebook.on_page_changed(move |_event| {
-			let Ok(dm_ref) = dm.try_lock() else {
+			let Ok(mut dm_ref) = dm.try_lock() else {
 				return;
 			};
+			if dm_ref.active_tab().is_some_and(|tab| tab.pending) {
+				dm_ref.load_active_tab(&dm);
+				if !dm_ref.notebook().has_focus() {
+					dm_ref.restore_focus();
+				}
+				menu::update_menu_item_states(&frame_copy, dm_ref.tab_count() > 0);
+			}
 			update_title_from_manager(&frame_copy, &dm_ref);
 			dm_ref.reset_sound_line();
+			dm_ref.reset_structure_context();
+			dm_ref.pause_auto_scroll();
+			dm_ref.start_reading_clock();
 		});
 		let dm = Rc::clone(&doc_manager);
 		let frame_copy = frame;
@@ -170,6 +224,7 @@ impl MainWindow {
 					cfg.set_app_string("active_document", &path);
 					cfg.flush();
 				}
+				dm.stop_reading_clock();
 				dm.save_all_positions();
 				#[cfg(target_os = "macos")]
 				if let WindowEventData::General(ref ev) = event {
@@ -180,6 +235,7 @@ impl MainWindow {
 						return;
 					}
 				}
+				mark_session_ended(&config_for_close.lock().unwrap());
 				#[cfg(target_os = "windows")]
 				if let Some(state) = tray_for_close.lock().unwrap().as_ref() {
 					state.icon.remove_icon();
@@ -199,6 +255,13 @@ impl MainWindow {
 				event.skip(true);
 			});
 		}
+		let dm_for_activate = Rc::clone(&doc_manager);
+		frame.bind_internal(EventType::ACTIVATE, move |event| {
+			if let Ok(dm) = dm_for_activate.try_lock() {
+				dm.autosave_positions();
+			}
+			event.skip(true);
+		});
 		#[cfg(target_os = "windows")]
 		{
 			let tray_for_destroy = Rc::clone(&tray_state);
@@ -208,14 +271,32 @@ impl MainWindow {
 				}
 			});
 		}
-		Self::schedule_restore_documents(frame, Rc::clone(&doc_manager), Rc::clone(&config));
+		{
+			let dm = Rc::clone(&doc_manager);
+			let config = Rc::clone(&config);
+			let find_dialog = Rc::clone(&find_dialog);
+			#[cfg(target_os = "windows")]
+			let tray_state = Rc::clone(&tray_state);
+			translation_manager::on_language_changed(move || {
+				apply_layout_direction(&frame);
+				rebuild_menu_bar(&frame, &config, &dm);
+				update_title_from_manager(&frame, &dm.lock().unwrap());
+				find::rebuild_find_dialog(&frame, &dm, &config, &find_dialog, live_region_label);
+				#[cfg(target_os = "windows")]
+				tray::rebuild_tray_menu(frame, &dm, &tray_state);
+			});
+		}
+		apply_layout_direction(&frame);
+		if !second_instance {
+			Self::schedule_restore_documents(frame, Rc::clone(&doc_manager), Rc::clone(&config), crashed);
+		}
 		Self {
 			frame,
 			doc_manager,
 			config,
 			#[cfg(target_os = "windows")]
 			_tray_state: tray_state,
-			_live_region_label: live_region_label,
+			live_region_label,
 			_find_dialog: find_dialog,
 			#[cfg(target_os = "windows")]
 			_hotkey_handle: hotkey_handle,
@@ -242,6 +323,11 @@ impl MainWindow {
 	}
 
 	pub fn open_file(&self, path: &Path) -> bool {
+		let path_str = path.to_string_lossy();
+		if url_download::is_url(&path_str) {
+			open_url(&self.frame, &self.doc_manager, path_str.trim());
+			return true;
+		}
 		if !self.ensure_parser_ready(path) {
 			return false;
 		}
@@ -254,6 +340,42 @@ impl MainWindow {
 		result
 	}
 
+	/// Opens a document downloaded with Open URL, or reports why the download failed.
+	pub fn finish_url_download(&self, url: &str, result: Result<PathBuf, DownloadError>) {
+		self.refresh_status_bar();
+		let file = match result {
+			Ok(file) => file,
+			Err(DownloadError::Cancelled) => {
+				speech::announce(self.live_region_label, &DownloadError::Cancelled.message());
+				return;
+			}
+			Err(err) => {
+				let dialog = MessageDialog::builder(&self.frame, &err.message(), &t("Error"))
+					.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
+					.build();
+				dialog.show_modal();
+				return;
+			}
+		};
+		// The address rather than the downloaded copy carries the format picked in Open As.
+		if !parser_supports_extension(&parser_extension_for_path(&file))
+			&& !ensure_parser_for_unknown_file(&self.frame, Path::new(url), &self.config.lock().unwrap())
+		{
+			return;
+		}
+		if self.doc_manager.lock().unwrap().open_url(&self.doc_manager, url) {
+			self.update_title();
+			self.update_recent_documents_menu();
+			self.doc_manager.lock().unwrap().restore_focus();
+		}
+	}
+
+	pub fn refresh_status_bar(&self) {
+		if let Ok(dm) = self.doc_manager.try_lock() {
+			dm.update_status_bar();
+		}
+	}
+
 	#[cfg(any(target_os = "linux", target_os = "windows"))]
 	pub fn handle_ipc_command(&self, command: IpcCommand) {
 		tracing::info!(command = ?command, "received IPC command");
@@ -292,6 +414,13 @@ impl MainWindow {
 		}
 	}
 
+	/// Answers a request from the external tools API. Runs on the UI thread.
+	#[cfg(any(target_os = "linux", target_os = "windows"))]
+	pub fn handle_api_request(&self, request: ApiRequest) -> ApiResponse {
+		tracing::debug!(request = ?request, "received API request");
+		ipc_api::dispatch(&mut WindowApiHost(self), request)
+	}
+
 	#[cfg(any(target_os = "linux", target_os = "windows"))]
 	fn toggle_visibility(&self) {
 		let is_shown = self.frame.is_shown();
@@ -327,6 +456,8 @@ impl MainWindow {
 
 	#[cfg(any(target_os = "linux", target_os = "windows"))]
 	fn activate_from_ipc(&self) {
+		#[cfg(target_os = "windows")]
+		let in_tray = !self.frame.is_shown();
 		self.frame.show(true);
 		self.frame.iconize(false);
 		self.frame.request_user_attention(UserAttentionFlag::Info);
@@ -338,7 +469,7 @@ impl MainWindow {
 		{
 			use windows::Win32::{
 				Foundation::HWND,
-				UI::WindowsAndMessaging::{GetLastActivePopup, SW_SHOW, SetForegroundWindow, ShowWindow},
+				UI::WindowsAndMessaging::{GetLastActivePopup, SW_SHOW, ShowWindow},
 			};
 			let handle = self.frame.get_handle();
 			if !handle.is_null() {
@@ -348,13 +479,13 @@ impl MainWindow {
 				if hidden != 0 {
 					let active_popup = HWND(hidden as _);
 					let _ = unsafe { ShowWindow(active_popup, SW_SHOW) };
-					let _ = unsafe { SetForegroundWindow(active_popup) };
+					force_foreground(active_popup);
 					has_popup = true;
 				} else {
 					let active_popup = unsafe { GetLastActivePopup(frame_hwnd) };
 					has_popup = active_popup != frame_hwnd;
 
-					let _ = unsafe { SetForegroundWindow(active_popup) };
+					force_foreground(active_popup);
 				}
 			}
 		}
@@ -363,9 +494,11 @@ impl MainWindow {
 			self.doc_manager.lock().unwrap().restore_focus();
 		}
 
-		#[cfg(not(target_os = "linux"))]
+		#[cfg(target_os = "windows")]
 		if let Some(state) = self._tray_state.lock().unwrap().as_mut() {
-			if let Some(bundle) =
+			if in_tray {
+				state.icon.remove_icon();
+			} else if let Some(bundle) =
 				ArtProvider::get_bitmap_bundle(ArtId::Information, ArtClient::MessageBox, Some(Size::new(32, 32)))
 			{
 				state.icon.set_icon_bundle(&bundle, "Paperback");
@@ -412,12 +545,11 @@ impl MainWindow {
 			return;
 		}
 		if let Some(tab) = dm.active_tab() {
-			// TRANSLATORS: Window title when a document is open; {} is the document title
-			let template = t("Paperback - {}");
-			self.frame.set_title(&template.replace("{}", &display_title(tab)));
-			// TRANSLATORS: Status bar character count; {} is the number of characters
-			let chars_label = t("{} chars");
-			self.frame.set_status_text(&chars_label.replace("{}", &tab.session.content().len().to_string()), 0);
+			dm.refresh_section_title(true);
+			// TRANSLATORS: Status bar character count; %d is the number of characters. Give one variant per plural form of your language, separated by |
+			let chars_label =
+				plural(&t("%d char|%d chars"), u64::try_from(tab.session.char_length()).unwrap_or(u64::MAX));
+			self.frame.set_status_text(&chars_label, 0);
 		}
 	}
 
@@ -445,9 +577,13 @@ impl MainWindow {
 		frame: Frame,
 		doc_manager: Rc<Mutex<DocumentManager>>,
 		config: Rc<Mutex<ConfigManager>>,
+		crashed: bool,
 	) {
-		let restore = config.lock().unwrap().get_app_bool("restore_previous_documents", true);
-		if !restore {
+		let (restore, relink) = {
+			let cfg = config.lock().unwrap();
+			(cfg.get_app_bool("restore_previous_documents", true), cfg.get_app_bool("relink_moved_documents", false))
+		};
+		if !restore && !crashed && !relink {
 			return;
 		}
 		let state = Rc::new(Mutex::new(RestoreState::default()));
@@ -467,24 +603,41 @@ impl MainWindow {
 			}
 			state.restored = true;
 			drop(state);
+			if relink {
+				relink_moved_documents(&frame, &config);
+			}
+			resolve_unfinished_document(&frame, &doc_manager, &config);
+			if !restore && !crashed {
+				return;
+			}
 			let pre_restore_active = doc_manager.lock().unwrap().active_tab_index();
 			let active_path = config.lock().unwrap().get_app_string("active_document", "");
 			let paths = config.lock().unwrap().get_opened_documents_existing();
+			if !restore && (paths.is_empty() || !confirm_crash_restore(&frame)) {
+				return;
+			}
 			tracing::info!(count = paths.len(), "restoring previously open documents");
+			// Every tab is added straight away, but only the one shown is parsed now; the rest are
+			// parsed when first switched to, or one by one in the background.
 			for path in paths {
 				let path = Path::new(&path);
 				if !ensure_parser_ready_for_path(&frame, path, &config) {
 					continue;
 				}
-				let _ = doc_manager.lock().unwrap().open_file_restore(&doc_manager, path);
-			}
-			let mut target_idx = pre_restore_active;
-			if target_idx.is_none() && !active_path.is_empty() {
-				target_idx = doc_manager.lock().unwrap().find_tab_by_path(Path::new(&active_path));
+				doc_manager.lock().unwrap().add_pending_tab(&doc_manager, path);
 			}
+			// The document read last comes back to the front; if it's gone, the first tab does instead.
+			let target_idx = pre_restore_active.or_else(|| {
+				let dm = doc_manager.lock().unwrap();
+				(!active_path.is_empty())
+					.then(|| dm.find_tab_by_path(Path::new(&active_path)))
+					.flatten()
+					.or_else(|| (dm.tab_count() > 0).then_some(0))
+			});
 			if let Some(idx) = target_idx {
 				doc_manager.lock().unwrap().notebook().set_selection(idx);
 			}
+			doc_manager.lock().unwrap().load_active_tab(&doc_manager);
 			let dm_ref = doc_manager.lock().unwrap();
 			update_title_from_manager(&frame, &dm_ref);
 			let has_docs = dm_ref.tab_count() > 0;
@@ -524,6 +677,58 @@ impl MainWindow {
 		}
 	}
 
+	fn handle_open_url(frame: &Frame, doc_manager: &Rc<Mutex<DocumentManager>>) {
+		if url_download::is_downloading() {
+			// TRANSLATORS: Question asked when Open URL is used while an earlier download is still running
+			let message = t("A document is still downloading. Cancel the download?");
+			let dialog = MessageDialog::builder(frame, &message, &t("Open URL"))
+				.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+				.build();
+			if dialog.show_modal() == ID_YES {
+				url_download::cancel();
+			}
+			return;
+		}
+		let clipboard_text = Clipboard::get().get_text().unwrap_or_default();
+		let suggested = if url_download::is_url(&clipboard_text) { clipboard_text.trim() } else { "" };
+		// TRANSLATORS: Label of the address field in the Open URL dialog
+		let prompt = t("Web &address:");
+		// TRANSLATORS: Title of the dialog for opening a document from an http or https address
+		let title = t("Open URL");
+		let dialog = TextEntryDialog::builder(frame, &prompt, &title).with_default_value(suggested).build();
+		if dialog.show_modal() != ID_OK {
+			return;
+		}
+		let Some(url) = dialog.get_value().map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+		else {
+			return;
+		};
+		if !url_download::is_url(&url) {
+			// TRANSLATORS: Error shown when the text entered in Open URL isn't an http or https address
+			let message = t("Enter an address starting with http:// or https://.");
+			let dialog = MessageDialog::builder(frame, &message, &t("Error"))
+				.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
+				.build();
+			dialog.show_modal();
+			return;
+		}
+		open_url(frame, doc_manager, &url);
+	}
+
+	fn handle_new_from_clipboard(frame: &Frame, doc_manager: &Rc<Mutex<DocumentManager>>) {
+		// wxdragon only exposes the plain-text flavor of the clipboard, so there is no HTML to prefer.
+		let text = Clipboard::get().get_text().unwrap_or_default();
+		if doc_manager.lock().unwrap().open_clipboard(doc_manager, &text, None) {
+			let Ok(dm_ref) = doc_manager.try_lock() else {
+				return;
+			};
+			update_title_from_manager(frame, &dm_ref);
+			dm_ref.restore_focus();
+			drop(dm_ref);
+			menu::update_menu_item_states(frame, true);
+		}
+	}
+
 	#[allow(clippy::too_many_lines)]
 	fn bind_menu_events(
 		frame: &Frame,
@@ -539,66 +744,111 @@ impl MainWindow {
 		let find_dialog = Rc::clone(find_dialog);
 		#[cfg(target_os = "windows")]
 		let hotkey_handle_for_options = Rc::clone(hotkey_handle);
-		let sleep_timer = Rc::new(Timer::new(frame));
-		let sleep_timer_running = Rc::new(Cell::new(false));
-		let sleep_timer_start_time = Rc::new(Cell::new(0i64));
-		let sleep_timer_duration_minutes = Rc::new(Cell::new(0i32));
-		let sleep_timer_for_tick = Rc::clone(&sleep_timer);
-		let sleep_timer_running_for_tick = Rc::clone(&sleep_timer_running);
-		let frame_for_timer = *frame;
-		let dm_for_timer = Rc::clone(doc_manager);
-		let config_for_timer = Rc::clone(&config);
-		sleep_timer.on_tick(move |_| {
-			tracing::info!("sleep timer fired, closing application");
-			sleep_timer_running_for_tick.set(false);
-			sleep_timer_for_tick.stop();
-			SLEEP_TIMER_START_MS.store(0, Ordering::SeqCst);
-			SLEEP_TIMER_DURATION_MINUTES.store(0, Ordering::SeqCst);
-			{
-				let dm = dm_for_timer.lock().unwrap();
-				let cfg = config_for_timer.lock().unwrap();
-				for i in 0..dm.tab_count() {
-					if let Some(tab) = dm.get_tab(i) {
-						let current_pos = tab.text_ctrl.get_insertion_point();
-						let path_str = tab.file_path.to_string_lossy();
-						cfg.set_document_position(&path_str, current_pos);
-					}
-				}
-				cfg.flush();
-			}
-			frame_for_timer.close(true);
-		});
+		let sleep_warning: Rc<Cell<Option<Dialog>>> = Rc::new(Cell::new(None));
 		let status_update_timer = Rc::new(Timer::new(frame));
-		let sleep_timer_running_for_status = Rc::clone(&sleep_timer_running);
-		let sleep_timer_start_for_status = Rc::clone(&sleep_timer_start_time);
-		let sleep_timer_duration_for_status = Rc::clone(&sleep_timer_duration_minutes);
 		let dm_for_status = Rc::clone(doc_manager);
+		let config_for_status = Rc::clone(&config);
+		let sleep_warning_for_status = Rc::clone(&sleep_warning);
 		let frame_for_status = *frame;
 		status_update_timer.on_tick(move |_| {
-			if !sleep_timer_running_for_status.get() {
+			let fallback = config_for_status.try_lock().ok().and_then(|cfg| cfg.take_fallback_notice());
+			if let Some(pending_path) = fallback {
+				warn_config_saved_elsewhere(&frame_for_status, &pending_path);
+			}
+			let event = sleep_timer::controller().tick(sleep_timer::now_ms());
+			match event {
+				Some(SleepTimerEvent::Warn) => {
+					warn_sleep_timer_ending(
+						&frame_for_status,
+						&dm_for_status,
+						&sleep_warning_for_status,
+						live_region_label,
+					);
+				}
+				Some(SleepTimerEvent::Expire) => {
+					close_sleep_timer_warning(&sleep_warning_for_status);
+					expire_sleep_timer(&frame_for_status, &dm_for_status, &config_for_status);
+					return;
+				}
+				None => {}
+			}
+			if let Ok(mut dm) = dm_for_status.try_lock() {
+				dm.track_furthest_read();
+			}
+			if !sleep_timer::controller().is_running() {
 				return;
 			}
 			let Ok(dm) = dm_for_status.try_lock() else {
 				return;
 			};
-			status::update_status_bar_with_sleep_timer(
-				&frame_for_status,
-				&dm,
-				sleep_timer_start_for_status.get(),
-				sleep_timer_duration_for_status.get(),
-			);
+			dm.update_status_bar();
 		});
 		status_update_timer.start(1000, false);
-		let sleep_timer_for_menu = Rc::clone(&sleep_timer);
-		let sleep_timer_running_for_menu = Rc::clone(&sleep_timer_running);
-		let sleep_timer_start_for_menu = Rc::clone(&sleep_timer_start_time);
-		let sleep_timer_duration_for_menu = Rc::clone(&sleep_timer_duration_minutes);
+		let autosave_timer = Rc::new(Timer::new(frame));
+		let dm_for_autosave = Rc::clone(doc_manager);
+		autosave_timer.on_tick(move |_| {
+			if let Ok(dm) = dm_for_autosave.try_lock() {
+				dm.autosave_positions();
+			}
+		});
+		let restart_autosave_timer = {
+			let timer = Rc::clone(&autosave_timer);
+			move |seconds: i32| {
+				timer.stop();
+				if seconds > 0 {
+					timer.start(seconds.saturating_mul(1000), false);
+				}
+			}
+		};
+		restart_autosave_timer(get_autosave_interval(&config.lock().unwrap()));
+		let page_load_timer = Rc::new(Timer::new(frame));
+		let page_load_was_busy = Rc::new(Cell::new(false));
+		let dm_for_page_load = Rc::clone(doc_manager);
+		page_load_timer.on_tick(move |_| {
+			let Ok(mut dm) = dm_for_page_load.try_lock() else {
+				return;
+			};
+			let busy = dm.sync_loaded_pages();
+			// Refresh once more after loading stops, to clear the notice.
+			if busy || page_load_was_busy.get() {
+				dm.update_status_bar();
+			}
+			page_load_was_busy.set(busy);
+		});
+		page_load_timer.start(PAGE_LOAD_POLL_INTERVAL_MS, false);
+		let auto_scroll_timer = Rc::new(Timer::new(frame));
+		let auto_scroll_interval = Rc::new(Cell::new(0));
+		let auto_scroll_timer_for_tick = Rc::clone(&auto_scroll_timer);
+		let auto_scroll_interval_for_tick = Rc::clone(&auto_scroll_interval);
+		let dm_for_auto_scroll = Rc::clone(doc_manager);
+		auto_scroll_timer.on_tick(move |_| {
+			let Ok(dm) = dm_for_auto_scroll.try_lock() else {
+				return;
+			};
+			if !dm.auto_scroll_step() {
+				auto_scroll_timer_for_tick.stop();
+				return;
+			}
+			// Pick up speed changes made with + and - since the last step.
+			let interval = dm.auto_scroll_interval();
+			if interval != auto_scroll_interval_for_tick.get() {
+				auto_scroll_interval_for_tick.set(interval);
+				auto_scroll_timer_for_tick.start(interval, false);
+			}
+		});
+		let sleep_warning_for_menu = Rc::clone(&sleep_warning);
 		frame.on_menu(move |event| {
 			let id = event.get_id();
 			match id {
 				menu_ids::OPEN => {
 					Self::handle_open(&frame_copy, &dm, &config);
 				}
+				menu_ids::NEW_FROM_CLIPBOARD => {
+					Self::handle_new_from_clipboard(&frame_copy, &dm);
+				}
+				menu_ids::OPEN_URL => {
+					Self::handle_open_url(&frame_copy, &dm);
+				}
 				menu_ids::CLOSE => {
 					let mut dm = dm.lock().unwrap();
 					close_active_document_announced(&mut dm, live_region_label);
@@ -625,6 +875,12 @@ impl MainWindow {
 				menu_ids::REOPEN_LAST_CLOSED => {
 					let path = dm.lock().unwrap().pop_recently_closed();
 					if let Some(path) = path {
+						if url_download::is_url(&path.to_string_lossy()) {
+							open_url(&frame_copy, &dm, &path.to_string_lossy());
+							let has_reopen = dm.lock().unwrap().has_recently_closed();
+							menu::update_reopen_state(&frame_copy, has_reopen);
+							return;
+						}
 						if !ensure_parser_ready_for_path(&frame_copy, &path, &config) {
 							dm.lock().unwrap().push_recently_closed(path);
 							return;
@@ -643,10 +899,14 @@ impl MainWindow {
 					}
 				}
 				menu_ids::EXIT => {
-					dm.lock().unwrap().save_all_positions();
+					let dm_ref = dm.lock().unwrap();
+					dm_ref.stop_reading_clock();
+					dm_ref.save_all_positions();
+					drop(dm_ref);
 					process::exit(0);
 				}
 				menu_ids::FIND => {
+					dm.lock().unwrap().pause_auto_scroll();
 					find::show_find_dialog(&frame_copy, &dm, &config, &find_dialog, live_region_label);
 				}
 				menu_ids::FIND_NEXT => {
@@ -686,8 +946,7 @@ impl MainWindow {
 								tab.text_ctrl.set_insertion_point(target_pos);
 								tab.text_ctrl.show_position(target_pos);
 								tab.session.check_and_record_history(target_pos);
-								let (history, history_index) = tab.session.get_history();
-								let history = history.to_vec();
+								let (history, history_index) = tab.session.saved_history();
 								let path_str = tab.file_path.to_string_lossy().to_string();
 								(history, history_index, path_str)
 							};
@@ -699,40 +958,49 @@ impl MainWindow {
 					}
 				}
 				menu_ids::GO_TO_PAGE => {
-					let (current_page, max_page) = {
+					let (current_page, max_page, labels) = {
 						let mut dm_guard = dm.lock().unwrap();
-						let (current_page, max_page) = {
+						let (current_page, max_page, labels) = {
 							let Some(tab) = dm_guard.active_tab_mut() else {
 								return;
 							};
 							let page_count = tab.session.page_count();
 							if page_count == 0 {
 								// TRANSLATORS: Announced when "Go to Page" is used on a document that has no page numbers
-								live_region::announce(live_region_label, &t("No pages."));
+								speech::announce(live_region_label, &t("No pages."));
 								return;
 							}
 							let current_pos = tab.text_ctrl.get_insertion_point();
 							let current_page = tab.session.current_page(current_pos);
 							let max_page = i32::try_from(page_count.max(1)).unwrap_or(i32::MAX);
-							(current_page, max_page)
+							(current_page, max_page, tab.session.page_labels().to_vec())
 						};
 						drop(dm_guard);
-						(current_page, max_page)
+						(current_page, max_page, labels)
 					};
-					if let Some(page) = dialogs::show_go_to_page_dialog(&frame_copy, current_page, max_page) {
+					if let Some(input) = dialogs::show_go_to_page_dialog(&frame_copy, current_page, max_page, &labels) {
 						let (history, history_index, path_str) = {
 							let mut dm_guard = dm.lock().unwrap();
 							let (history, history_index, path_str) = {
 								let Some(tab) = dm_guard.active_tab_mut() else {
 									return;
 								};
+								let Some(page) = tab.session.page_for_input(&input) else {
+									// TRANSLATORS: Announced when the page typed into Go to Page doesn't exist; %s is what was typed
+									let message = t("No page %s.").replace("%s", &input);
+									speech::announce(live_region_label, &message);
+									return;
+								};
+								if !tab.session.is_fully_loaded() {
+									frame_copy.set_status_text(&status::loading_page_label(), 0);
+									tab.load_through_page(page);
+								}
 								let target_pos = tab.session.page_offset(page);
 								tab.text_ctrl.set_focus();
 								tab.text_ctrl.set_insertion_point(target_pos);
 								tab.text_ctrl.show_position(target_pos);
 								tab.session.check_and_record_history(target_pos);
-								let (history, history_index) = tab.session.get_history();
-								let history = history.to_vec();
+								let (history, history_index) = tab.session.saved_history();
 								let path_str = tab.file_path.to_string_lossy().to_string();
 								(history, history_index, path_str)
 							};
@@ -764,13 +1032,17 @@ impl MainWindow {
 								let Some(tab) = dm_guard.active_tab_mut() else {
 									return;
 								};
+								// Percentages are of the whole text, so every page has to be read first.
+								if !tab.session.is_fully_loaded() {
+									frame_copy.set_status_text(&status::loading_page_label(), 0);
+									tab.load_all_pages();
+								}
 								let target_pos = tab.session.position_from_percent(percent);
 								tab.text_ctrl.set_focus();
 								tab.text_ctrl.set_insertion_point(target_pos);
 								tab.text_ctrl.show_position(target_pos);
 								tab.session.check_and_record_history(target_pos);
-								let (history, history_index) = tab.session.get_history();
-								let history = history.to_vec();
+								let (history, history_index) = tab.session.saved_history();
 								let path_str = tab.file_path.to_string_lossy().to_string();
 								(history, history_index, path_str)
 							};
@@ -781,12 +1053,48 @@ impl MainWindow {
 						cfg.set_navigation_history(&path_str, &history, history_index);
 					}
 				}
+				menu_ids::GO_TO_SECTION_NUMBER => {
+					navigation::handle_go_to_section_number(&frame_copy, &dm, &config, live_region_label);
+				}
+				menu_ids::LANDMARKS => {
+					navigation::handle_landmarks_dialog(&frame_copy, &dm, &config, live_region_label);
+				}
 				menu_ids::GO_BACK => {
 					navigation::handle_history_navigation(&dm, &config, live_region_label, false);
 				}
 				menu_ids::GO_FORWARD => {
 					navigation::handle_history_navigation(&dm, &config, live_region_label, true);
 				}
+				menu_ids::HISTORY_LIST => {
+					navigation::handle_history_dialog(&frame_copy, &dm, &config, live_region_label);
+				}
+				menu_ids::SPEAK_LOCATION => {
+					navigation::handle_speak_location(&dm, live_region_label);
+				}
+				menu_ids::LAST_READ_POSITION => {
+					navigation::handle_last_read_position(&dm, &config, live_region_label);
+				}
+				menu_ids::FURTHEST_READ_POSITION => {
+					navigation::handle_furthest_read_position(&dm, &config, live_region_label);
+				}
+				menu_ids::PREVIOUS_SENTENCE => {
+					navigation::handle_text_unit_navigation(&dm, &config, live_region_label, TextUnit::Sentence, false);
+				}
+				menu_ids::NEXT_SENTENCE => {
+					navigation::handle_text_unit_navigation(&dm, &config, live_region_label, TextUnit::Sentence, true);
+				}
+				menu_ids::PREVIOUS_PARAGRAPH => {
+					navigation::handle_text_unit_navigation(
+						&dm,
+						&config,
+						live_region_label,
+						TextUnit::Paragraph,
+						false,
+					);
+				}
+				menu_ids::NEXT_PARAGRAPH => {
+					navigation::handle_text_unit_navigation(&dm, &config, live_region_label, TextUnit::Paragraph, true);
+				}
 				menu_ids::PREVIOUS_SECTION => {
 					navigation::handle_marker_navigation(
 						&dm,
@@ -805,6 +1113,12 @@ impl MainWindow {
 						true,
 					);
 				}
+				menu_ids::RANDOM_SECTION => {
+					navigation::handle_random_section(&dm, &config, live_region_label);
+				}
+				menu_ids::TOGGLE_SECTION_SHUFFLE => {
+					navigation::handle_toggle_section_shuffle(&dm, live_region_label);
+				}
 				menu_ids::PREVIOUS_HEADING => {
 					navigation::handle_marker_navigation(
 						&dm,
@@ -976,12 +1290,38 @@ impl MainWindow {
 						BookmarkFilterType::NotesOnly,
 					);
 				}
+				menu_ids::TOGGLE_BOOKMARK_CATEGORY_NAVIGATION => {
+					let new_state = {
+						let cfg = config.lock().unwrap();
+						let enabled = !is_bookmark_category_navigation_enabled(&cfg);
+						set_bookmark_category_navigation_enabled(&cfg, enabled);
+						cfg.flush();
+						enabled
+					};
+					if let Some(menu_bar) = frame_copy.get_menu_bar() {
+						menu_bar.check_item(menu_ids::TOGGLE_BOOKMARK_CATEGORY_NAVIGATION, new_state);
+					}
+					let msg = if new_state {
+						// TRANSLATORS: Announced when Next/Previous Bookmark are limited to the selected bookmark category
+						t("Bookmark navigation stays in the selected category.")
+					} else {
+						// TRANSLATORS: Announced when Next/Previous Bookmark visit bookmarks of every category again
+						t("Bookmark navigation includes all categories.")
+					};
+					speech::announce(live_region_label, &msg);
+				}
 				menu_ids::TOGGLE_BOOKMARK => {
 					navigation::handle_toggle_bookmark(&dm, &config, live_region_label);
 				}
 				menu_ids::BOOKMARK_WITH_NOTE => {
 					navigation::handle_bookmark_with_note(&frame_copy, &dm, &config, live_region_label);
 				}
+				menu_ids::DOCUMENT_NOTES => {
+					navigation::handle_document_notes(&frame_copy, &dm, &config, live_region_label);
+				}
+				menu_ids::SEARCH_NOTES => {
+					navigation::handle_search_notes(&frame_copy, &dm, &config, live_region_label);
+				}
 				menu_ids::TOGGLE_WORD_WRAP => {
 					let new_state = {
 						let cfg = config.lock().unwrap();
@@ -994,13 +1334,115 @@ impl MainWindow {
 						let dm_for_wrap = Rc::clone(&dm);
 						let mut dm_ref = dm.lock().unwrap();
 						dm_ref.apply_word_wrap(&dm_for_wrap, new_state);
+						dm_ref.announce_word_wrap(new_state);
 					}
 					if let Some(menu_bar) = frame_copy.get_menu_bar() {
 						menu_bar.check_item(menu_ids::TOGGLE_WORD_WRAP, new_state);
 					}
-					// TRANSLATORS: Announced when toggling word wrap; the message reflects the new state
-					let msg = if new_state { t("Word wrap on.") } else { t("Word wrap off.") };
-					live_region::announce(live_region_label, &msg);
+					dm.lock().unwrap().restore_focus();
+				}
+				menu_ids::TOGGLE_DARK_READING_VIEW => {
+					let (diff, new_state) = {
+						let cfg = config.lock().unwrap();
+						let before = ReadingSettings::from_config(&cfg);
+						let v = !cfg.get_app_bool("dark_reading_view", false);
+						cfg.set_app_bool("dark_reading_view", v);
+						cfg.flush();
+						(SettingsDiff { before, after: ReadingSettings::from_config(&cfg) }, v)
+					};
+					apply_settings(&frame_copy, &dm, &diff);
+					if let Some(menu_bar) = frame_copy.get_menu_bar() {
+						menu_bar.check_item(menu_ids::TOGGLE_DARK_READING_VIEW, new_state);
+					}
+					// TRANSLATORS: Announced when toggling the dark reading view; the message reflects the new state
+					let msg = if new_state { t("Dark reading view on.") } else { t("Dark reading view off.") };
+					speech::announce(live_region_label, &msg);
+					dm.lock().unwrap().restore_focus();
+				}
+				menu_ids::TEXT_DIRECTION_AUTOMATIC => {
+					navigation::handle_text_direction(&dm, live_region_label, None);
+				}
+				menu_ids::TEXT_DIRECTION_LEFT_TO_RIGHT => {
+					navigation::handle_text_direction(&dm, live_region_label, Some(TextDirection::LeftToRight));
+				}
+				menu_ids::TEXT_DIRECTION_RIGHT_TO_LEFT => {
+					navigation::handle_text_direction(&dm, live_region_label, Some(TextDirection::RightToLeft));
+				}
+				menu_ids::LINE_LAYOUT_AUTOMATIC => {
+					navigation::handle_line_layout(&dm, live_region_label, None);
+				}
+				menu_ids::LINE_LAYOUT_WRAP => {
+					navigation::handle_line_layout(&dm, live_region_label, Some(LineLayout::Wrapped));
+				}
+				menu_ids::LINE_LAYOUT_PRESERVE => {
+					navigation::handle_line_layout(&dm, live_region_label, Some(LineLayout::Preformatted));
+				}
+				menu_ids::PRONUNCIATION_RULES => {
+					let path = dm.lock().unwrap().active_tab().map(|tab| tab.file_path.to_string_lossy().to_string());
+					let initial = {
+						let cfg = config.lock().unwrap();
+						dialogs::PronunciationRuleSets {
+							document: path.as_deref().map(|path| cfg.get_document_pronunciation_rules(path)),
+							global: cfg.get_global_pronunciation_rules(),
+						}
+					};
+					if let Some(rules) = dialogs::show_pronunciation_rules_dialog(&frame_copy, initial) {
+						let cfg = config.lock().unwrap();
+						if let (Some(path), Some(document_rules)) = (path.as_deref(), &rules.document) {
+							cfg.set_document_pronunciation_rules(path, document_rules);
+						}
+						cfg.set_global_pronunciation_rules(&rules.global);
+						cfg.flush();
+						speech::load_rules(&cfg, path.as_deref());
+					}
+					dm.lock().unwrap().restore_focus();
+				}
+				menu_ids::LOOK_UP_WORD => {
+					navigation::handle_look_up_word(&frame_copy, &dm, &config, live_region_label);
+				}
+				menu_ids::ENCODING => {
+					let Some((path_str, format)) = ({
+						let dm_ref = dm.lock().unwrap();
+						dm_ref.active_tab().map(|tab| {
+							let path_str = tab.file_path.to_string_lossy().to_string();
+							let forced = config.lock().unwrap().get_document_format(&path_str);
+							let format =
+								if forced.is_empty() { parser_extension_for_path(&tab.file_path) } else { forced };
+							(path_str, format)
+						})
+					}) else {
+						return;
+					};
+					if !paperback_core::parser::text::is_text_format(&format) {
+						// TRANSLATORS: Error shown when "Encoding" is used on a document that isn't plain text
+						let message = t("The encoding can only be changed for plain text documents.");
+						let dialog = MessageDialog::builder(&frame_copy, &message, &t("Error"))
+							.with_style(
+								MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre,
+							)
+							.build();
+						dialog.show_modal();
+						return;
+					}
+					let current = config.lock().unwrap().get_document_encoding(&path_str);
+					if let Some(encoding) = dialogs::show_encoding_dialog(&frame_copy, &current)
+						&& encoding != current
+					{
+						{
+							let cfg = config.lock().unwrap();
+							cfg.set_document_encoding(&path_str, &encoding);
+							cfg.flush();
+						}
+						dm.lock().unwrap().reparse_active_document();
+						let message = if encoding.is_empty() {
+							// TRANSLATORS: Announced after switching a text document back to automatic encoding detection
+							t("Encoding detected automatically.")
+						} else {
+							// TRANSLATORS: Announced after reopening a text document in another encoding; %s is the encoding name, e.g. "windows-1251"
+							t("Reading as %s.").replace("%s", &encoding)
+						};
+						speech::announce(live_region_label, &message);
+					}
 					dm.lock().unwrap().restore_focus();
 				}
 				menu_ids::VIEW_NOTE_TEXT => {
@@ -1012,6 +1454,9 @@ impl MainWindow {
 				menu_ids::NEXT_LINK => {
 					navigation::handle_marker_navigation(&dm, &config, live_region_label, MarkerNavTarget::Link, true);
 				}
+				menu_ids::PREVIEW_LINK => {
+					navigation::handle_preview_link(&frame_copy, &dm, &config, live_region_label);
+				}
 				menu_ids::PREVIOUS_IMAGE => {
 					navigation::handle_marker_navigation(
 						&dm,
@@ -1072,6 +1517,42 @@ impl MainWindow {
 						true,
 					);
 				}
+				menu_ids::PREVIOUS_BLOCK_QUOTE => {
+					navigation::handle_marker_navigation(
+						&dm,
+						&config,
+						live_region_label,
+						MarkerNavTarget::BlockQuote,
+						false,
+					);
+				}
+				menu_ids::NEXT_BLOCK_QUOTE => {
+					navigation::handle_marker_navigation(
+						&dm,
+						&config,
+						live_region_label,
+						MarkerNavTarget::BlockQuote,
+						true,
+					);
+				}
+				menu_ids::PREVIOUS_CODE_BLOCK => {
+					navigation::handle_marker_navigation(
+						&dm,
+						&config,
+						live_region_label,
+						MarkerNavTarget::CodeBlock,
+						false,
+					);
+				}
+				menu_ids::NEXT_CODE_BLOCK => {
+					navigation::handle_marker_navigation(
+						&dm,
+						&config,
+						live_region_label,
+						MarkerNavTarget::CodeBlock,
+						true,
+					);
+				}
 				menu_ids::PREVIOUS_LIST => {
 					navigation::handle_marker_navigation(&dm, &config, live_region_label, MarkerNavTarget::List, false);
 				}
@@ -1214,64 +1695,263 @@ impl MainWindow {
 						}
 					}
 				}
-				menu_ids::EXPORT_DOCUMENT_DATA => {
+				menu_ids::EXPORT_FOR_BRAILLE => {
 					let Ok(dm_ref) = dm.try_lock() else {
 						return;
 					};
 					let Some(tab) = dm_ref.active_tab() else {
 						return;
 					};
+					let defaults = BrailleOptions::default();
+					let initial = {
+						let cfg = config.lock().unwrap();
+						BrailleOptions {
+							line_width: usize::try_from(
+								cfg.get_app_int("braille_line_width", i32::try_from(defaults.line_width).unwrap_or(38)),
+							)
+							.unwrap_or(defaults.line_width),
+							heading_prefix: cfg.get_app_string("braille_heading_prefix", &defaults.heading_prefix),
+							platform_newlines: cfg
+								.get_app_bool("braille_platform_newlines", defaults.platform_newlines),
+						}
+					};
+					let Some(options) = dialogs::show_export_braille_dialog(&frame_copy, &initial) else {
+						return;
+					};
+					{
+						let cfg = config.lock().unwrap();
+						cfg.set_app_int("braille_line_width", i32::try_from(options.line_width).unwrap_or(38));
+						cfg.set_app_string("braille_heading_prefix", &options.heading_prefix);
+						cfg.set_app_bool("braille_platform_newlines", options.platform_newlines);
+					}
 					let default_name =
 						// TRANSLATORS: Fallback file name stem used when the document's path has no file stem
 						tab.file_path.file_stem().map_or_else(|| t("document"), |s| s.to_string_lossy().to_string());
-					let default_file = format!("{default_name}.paperback");
-					// TRANSLATORS: File filter shown in the export/import notes-and-bookmarks (.paperback) dialogs
-					let wildcard = t("Paperback files (*.paperback)|*.paperback");
+					// TRANSLATORS: Suffix appended to the document name for the default braille export file name
+					let default_file = format!("{default_name} {}.txt", t("braille"));
+					// TRANSLATORS: File filter shown in the "Export to plain text" save dialog
+					let wildcard = t("Plain text files (*.txt)|*.txt|All files (*.*)|*.*");
 					let dialog = FileDialog::builder(&frame_copy)
-						// TRANSLATORS: Title of the file save dialog when exporting a document's notes and bookmarks
-						.with_message(&t("Export notes and bookmarks"))
+						// TRANSLATORS: Title of the file save dialog when exporting a document for braille embossing
+						.with_message(&t("Export document for braille"))
 						.with_default_file(&default_file)
 						.with_wildcard(&wildcard)
 						.with_style(FileDialogStyle::Save | FileDialogStyle::OverwritePrompt)
 						.build();
 					if dialog.show_modal() == ID_OK
 						&& let Some(path) = dialog.get_path()
+						&& let Err(e) = tab.session.export_braille(&path, &options)
 					{
-						let path_str = tab.file_path.to_string_lossy();
-						config.lock().unwrap().export_document_settings(&path_str, &path);
-						tracing::info!(doc = %tab.file_path.display(), export = %path, "document data exported");
-						let dialog = MessageDialog::builder(
-							&frame_copy,
-							// TRANSLATORS: Success message shown after exporting a document's notes and bookmarks
-							&t("Notes and bookmarks exported successfully."),
-							// TRANSLATORS: Title of the export-succeeded dialog
-							&t("Export Successful"),
-						)
-						.with_style(
-							MessageDialogStyle::OK | MessageDialogStyle::IconInformation | MessageDialogStyle::Centre,
-						)
-						.build();
+						tracing::error!(path = %path, error = %e, "failed to export document for braille");
+						let dialog =
+							// TRANSLATORS: Error dialog shown when exporting a document to another format fails
+							MessageDialog::builder(&frame_copy, &t("Failed to export document."), &t("Error"))
+								.with_style(
+									MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre,
+								)
+								.build();
 						dialog.show_modal();
 					}
 				}
-				menu_ids::IMPORT_DOCUMENT_DATA => {
+				menu_ids::EXPORT_NOTES_TO_MARKDOWN => {
 					let Ok(dm_ref) = dm.try_lock() else {
 						return;
 					};
 					let Some(tab) = dm_ref.active_tab() else {
 						return;
 					};
-					// TRANSLATORS: File filter shown in the export/import notes-and-bookmarks (.paperback) dialogs
-					let wildcard = t("Paperback files (*.paperback)|*.paperback");
-					let dialog = FileDialog::builder(&frame_copy)
-						// TRANSLATORS: Title of the file open dialog when importing a document's notes and bookmarks
-						.with_message(&t("Import notes and bookmarks"))
-						.with_wildcard(&wildcard)
-						.with_style(FileDialogStyle::Open | FileDialogStyle::FileMustExist)
+					let Some(include_bookmarks) = dialogs::show_export_notes_dialog(&frame_copy) else {
+						return;
+					};
+					let default_name =
+						// TRANSLATORS: Fallback file name stem used when the document's path has no file stem
+						tab.file_path.file_stem().map_or_else(|| t("document"), |s| s.to_string_lossy().to_string());
+					// TRANSLATORS: Suffix appended to the document name for the default exported notes file name
+					let default_file = format!("{default_name} {}.md", t("notes"));
+					// TRANSLATORS: File filter shown in the "Export to Markdown" save dialog
+					let wildcard = t("Markdown files (*.md)|*.md|All files (*.*)|*.*");
+					let dialog = FileDialog::builder(&frame_copy)
+						// TRANSLATORS: Title of the file save dialog when exporting bookmarks and notes to Markdown
+						.with_message(&t("Export notes to Markdown"))
+						.with_default_file(&default_file)
+						.with_wildcard(&wildcard)
+						.with_style(FileDialogStyle::Save | FileDialogStyle::OverwritePrompt)
+						.build();
+					if dialog.show_modal() == ID_OK
+						&& let Some(path) = dialog.get_path()
+					{
+						let result = {
+							let config = config.lock().unwrap();
+							tab.session.export_notes_markdown(&config, &path, include_bookmarks)
+						};
+						if let Err(e) = result {
+							tracing::error!(path = %path, error = %e, "failed to export notes as Markdown");
+							let dialog =
+								// TRANSLATORS: Error dialog shown when exporting bookmarks and notes fails
+								MessageDialog::builder(&frame_copy, &t("Failed to export notes."), &t("Error"))
+									.with_style(
+										MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre,
+									)
+									.build();
+							dialog.show_modal();
+						}
+					}
+				}
+				menu_ids::EXPORT_SECTION_TO_PLAIN_TEXT => {
+					navigation::handle_export_current_section(
+						&frame_copy,
+						&dm,
+						paperback_core::export::ExportFormat::Text,
+					);
+				}
+				menu_ids::EXPORT_SECTION_TO_HTML => {
+					navigation::handle_export_current_section(
+						&frame_copy,
+						&dm,
+						paperback_core::export::ExportFormat::Html,
+					);
+				}
+				menu_ids::COPY_CURRENT_SECTION => {
+					navigation::handle_copy_current_section(&dm, live_region_label);
+				}
+				menu_ids::EXPORT_OUTLINE => {
+					let Ok(dm_ref) = dm.try_lock() else {
+						return;
+					};
+					let Some(tab) = dm_ref.active_tab() else {
+						return;
+					};
+					let initial = {
+						let cfg = config.lock().unwrap();
+						OutlineOptions {
+							include_percent: cfg.get_app_bool("outline_include_percent", false),
+							include_page: cfg.get_app_bool("outline_include_page", false),
+							..OutlineOptions::default()
+						}
+					};
+					let Some(mut options) = dialogs::show_export_outline_dialog(&frame_copy, initial) else {
+						return;
+					};
+					{
+						let cfg = config.lock().unwrap();
+						cfg.set_app_bool("outline_include_percent", options.include_percent);
+						cfg.set_app_bool("outline_include_page", options.include_page);
+					}
+					let default_name =
+						// TRANSLATORS: Fallback file name stem used when the document's path has no file stem
+						tab.file_path.file_stem().map_or_else(|| t("document"), |s| s.to_string_lossy().to_string());
+					// TRANSLATORS: Suffix appended to the document name for the default exported outline file name
+					let default_file = format!("{default_name} {}.md", t("outline"));
+					// TRANSLATORS: File filter shown in the "Export outline" save dialog; the file extension picks the format
+					let wildcard = t("Markdown files (*.md)|*.md|Plain text files (*.txt)|*.txt|All files (*.*)|*.*");
+					let dialog = FileDialog::builder(&frame_copy)
+						// TRANSLATORS: Title of the file save dialog when exporting the document outline
+						.with_message(&t("Export outline"))
+						.with_default_file(&default_file)
+						.with_wildcard(&wildcard)
+						.with_style(FileDialogStyle::Save | FileDialogStyle::OverwritePrompt)
+						.build();
+					if dialog.show_modal() != ID_OK {
+						return;
+					}
+					let Some(path) = dialog.get_path() else {
+						return;
+					};
+					options.markdown = Path::new(&path)
+						.extension()
+						.is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"));
+					if let Err(e) = tab.session.export_outline(&path, options) {
+						tracing::error!(path = %path, error = %e, "failed to export outline");
+						let dialog =
+							// TRANSLATORS: Error dialog shown when exporting the document outline fails
+							MessageDialog::builder(&frame_copy, &t("Failed to export outline."), &t("Error"))
+								.with_style(
+									MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre,
+								)
+								.build();
+						dialog.show_modal();
+					}
+				}
+				menu_ids::EXPORT_DOCUMENT_DATA => {
+					let Ok(dm_ref) = dm.try_lock() else {
+						return;
+					};
+					let Some(tab) = dm_ref.active_tab() else {
+						return;
+					};
+					let default_name =
+						// TRANSLATORS: Fallback file name stem used when the document's path has no file stem
+						tab.file_path.file_stem().map_or_else(|| t("document"), |s| s.to_string_lossy().to_string());
+					let default_file = format!("{default_name}.paperback");
+					// TRANSLATORS: File filter shown in the export/import notes-and-bookmarks (.paperback) dialogs
+					let wildcard = t("Paperback files (*.paperback)|*.paperback");
+					let dialog = FileDialog::builder(&frame_copy)
+						// TRANSLATORS: Title of the file save dialog when exporting a document's notes and bookmarks
+						.with_message(&t("Export notes and bookmarks"))
+						.with_default_file(&default_file)
+						.with_wildcard(&wildcard)
+						.with_style(FileDialogStyle::Save | FileDialogStyle::OverwritePrompt)
 						.build();
 					if dialog.show_modal() == ID_OK
 						&& let Some(path) = dialog.get_path()
 					{
+						let path_str = tab.file_path.to_string_lossy();
+						config.lock().unwrap().export_document_settings(&path_str, &path);
+						tracing::info!(doc = %tab.file_path.display(), export = %path, "document data exported");
+						let dialog = MessageDialog::builder(
+							&frame_copy,
+							// TRANSLATORS: Success message shown after exporting a document's notes and bookmarks
+							&t("Notes and bookmarks exported successfully."),
+							// TRANSLATORS: Title of the export-succeeded dialog
+							&t("Export Successful"),
+						)
+						.with_style(
+							MessageDialogStyle::OK | MessageDialogStyle::IconInformation | MessageDialogStyle::Centre,
+						)
+						.build();
+						dialog.show_modal();
+					}
+				}
+				menu_ids::BACKUP_ALL_DATA => handle_backup_all_data(&frame_copy, &config, &dm),
+				menu_ids::RESTORE_ALL_DATA => handle_restore_all_data(&frame_copy, &config, &dm),
+				menu_ids::IMPORT_DOCUMENT_DATA => {
+					let Ok(dm_ref) = dm.try_lock() else {
+						return;
+					};
+					let Some(tab) = dm_ref.active_tab() else {
+						return;
+					};
+					let wildcard = format!(
+						"{}|{}|{}",
+						// TRANSLATORS: File filter shown in the export/import notes-and-bookmarks (.paperback) dialogs
+						t("Paperback files (*.paperback)|*.paperback"),
+						// TRANSLATORS: File filter for annotations exported from the Calibre e-book viewer
+						t("Calibre annotations (*.calibre_highlights;*.json)|*.calibre_highlights;*.json"),
+						// TRANSLATORS: File filter for the reading position files kept by the Moon+ Reader app
+						t("Moon+ Reader positions (*.po)|*.po"),
+					);
+					let dialog = FileDialog::builder(&frame_copy)
+						// TRANSLATORS: Title of the file open dialog when importing a document's notes and bookmarks
+						.with_message(&t("Import notes and bookmarks"))
+						.with_wildcard(&wildcard)
+						.with_style(FileDialogStyle::Open | FileDialogStyle::FileMustExist)
+						.build();
+					if dialog.show_modal() != ID_OK {
+						return;
+					}
+					let Some(path) = dialog.get_path() else {
+						return;
+					};
+					if AnnotationFormat::from_path(&path).is_some() {
+						let import = {
+							let config = config.lock().unwrap();
+							let import = tab.session.import_annotations(&config, &path);
+							config.flush();
+							import
+						};
+						tracing::info!(doc = %tab.file_path.display(), import = %path, "annotations imported");
+						show_annotation_import_summary(&frame_copy, import.as_ref());
+					} else {
 						let path_str = tab.file_path.to_string_lossy();
 						let pos = {
 							let config = config.lock().unwrap();
@@ -1304,24 +1984,90 @@ impl MainWindow {
 					};
 					if let Some(tab) = dm_ref.active_tab() {
 						let selection = tab.text_ctrl.get_string_selection();
-						let (word_count, is_selection) = if selection.trim().is_empty() {
-							(tab.session.stats().word_count, false)
+						let (stats, is_selection) = if selection.trim().is_empty() {
+							(tab.session.stats().clone(), false)
 						} else {
-							(paperback_core::document::DocumentStats::from_text(&selection).word_count, true)
+							(paperback_core::document::DocumentStats::from_text(&selection), true)
 						};
 						let wpm = config.lock().unwrap().get_app_int("reading_speed_wpm", 150);
-						dialogs::show_word_count_dialog(&frame_copy, word_count, wpm, is_selection);
+						dialogs::show_word_count_dialog(&frame_copy, &stats, wpm, is_selection);
 					}
 				}
 				menu_ids::DOCUMENT_INFO => {
 					let Ok(dm_ref) = dm.try_lock() else {
 						return;
 					};
-					if let Some(tab) = dm_ref.active_tab() {
-						let stats = tab.session.stats();
-						let title = tab.session.title();
-						let author = tab.session.author();
-						dialogs::show_document_info_dialog(&frame_copy, &tab.file_path, &title, &author, stats);
+					dm_ref.commit_reading_time();
+					let Some(tab) = dm_ref.active_tab() else {
+						return;
+					};
+					let title = tab.session.title();
+					let author = tab.session.author();
+					let metadata = tab.session.document_metadata();
+					let language = tab.session.language();
+					let running_headers = tab.session.running_headers();
+					let running_footers = tab.session.running_footers();
+					let preformatted = dm_ref.active_is_preformatted();
+					let path_str = tab.file_path.to_string_lossy().to_string();
+					let (options, reading_stats, furthest_percent, notes_length) = {
+						let cfg = config.lock().unwrap();
+						let format = cfg.get_document_format(&path_str);
+						let extension =
+							if format.is_empty() { parser_extension_for_path(&tab.file_path) } else { format };
+						let is_pdf = extension.eq_ignore_ascii_case("pdf");
+						let options = dialogs::DocumentInfoOptions {
+							language_override: cfg.get_document_language_override(&path_str),
+							detect_columns: is_pdf.then(|| cfg.get_document_column_detection(&path_str)),
+							detect_headings: is_pdf.then(|| cfg.get_document_heading_detection(&path_str)),
+							strip_running_lines: cfg.get_document_strip_running_lines(&path_str).unwrap_or(is_pdf),
+						};
+						(
+							options,
+							cfg.get_document_reading_stats(&path_str),
+							cfg.get_document_furthest_percent(&path_str),
+							cfg.get_document_notes(&path_str).chars().count(),
+						)
+					};
+					let document = dialogs::DocumentInfo {
+						path: &tab.file_path,
+						title: &title,
+						author: &author,
+						metadata: &metadata,
+						stats: tab.session.stats(),
+						char_length: tab.session.char_length(),
+						figure_count: tab.session.figure_list(0).items.len(),
+						detected_language: &language,
+						reading_stats,
+						furthest_percent,
+						notes_length,
+						typography_normalized: tab.session.typography_normalized(),
+						running_headers: &running_headers,
+						running_footers: &running_footers,
+						preformatted,
+					};
+					let Some(updated) = dialogs::show_document_info_dialog(&frame_copy, &document, &options) else {
+						return;
+					};
+					drop(dm_ref);
+					{
+						let cfg = config.lock().unwrap();
+						cfg.set_document_language_override(&path_str, &updated.language_override);
+						if let Some(detect_columns) = updated.detect_columns {
+							cfg.set_document_column_detection(&path_str, detect_columns);
+						}
+						if let Some(detect_headings) = updated.detect_headings {
+							cfg.set_document_heading_detection(&path_str, detect_headings);
+						}
+						if updated.strip_running_lines != options.strip_running_lines {
+							cfg.set_document_strip_running_lines(&path_str, updated.strip_running_lines);
+						}
+						cfg.flush();
+					}
+					if updated.detect_columns != options.detect_columns
+						|| updated.detect_headings != options.detect_headings
+						|| updated.strip_running_lines != options.strip_running_lines
+					{
+						dm.lock().unwrap().reparse_active_document();
 					}
 				}
 				menu_ids::TABLE_OF_CONTENTS => {
@@ -1330,7 +2076,7 @@ impl MainWindow {
 						let toc_items = &tab.session.handle().document().toc_items;
 						if toc_items.is_empty() {
 							// TRANSLATORS: Announced when opening the Table of Contents for a document that has none
-							live_region::announce(live_region_label, &t("No table of contents."));
+							speech::announce(live_region_label, &t("No table of contents."));
 							return;
 						}
 						let current_pos = tab.text_ctrl.get_insertion_point();
@@ -1345,10 +2091,10 @@ impl MainWindow {
 							tab.text_ctrl.set_insertion_point(i64::from(offset));
 							tab.text_ctrl.show_position(i64::from(offset));
 							tab.session.check_and_record_history(i64::from(offset));
-							let (history, history_index) = tab.session.get_history();
+							let (history, history_index) = tab.session.saved_history();
 							let path_str = tab.file_path.to_string_lossy();
 							let cfg = config.lock().unwrap();
-							cfg.set_navigation_history(&path_str, history, history_index);
+							cfg.set_navigation_history(&path_str, &history, history_index);
 						}
 					}
 				}
@@ -1361,10 +2107,10 @@ impl MainWindow {
 							tab.text_ctrl.set_insertion_point(offset);
 							tab.text_ctrl.show_position(offset);
 							tab.session.check_and_record_history(offset);
-							let (history, history_index) = tab.session.get_history();
+							let (history, history_index) = tab.session.saved_history();
 							let path_str = tab.file_path.to_string_lossy();
 							let cfg = config.lock().unwrap();
-							cfg.set_navigation_history(&path_str, history, history_index);
+							cfg.set_navigation_history(&path_str, &history, history_index);
 						}
 					}
 				}
@@ -1381,12 +2127,12 @@ impl MainWindow {
 						let mut url = format!("file:///{}", target.path.replace('\\', "/"));
 						let fragment =
 							target.fragment.or_else(|| tab.session.webview_fragment_for_position(current_pos));
-						if let Some(fragment) = fragment {
+						if let Some(fragment) = &fragment {
 							url.push('#');
-							url.push_str(&fragment);
+							url.push_str(fragment);
 						}
 						drop(dm_ref);
-						dialogs::show_web_view_dialog(
+						let final_url = dialogs::show_web_view_dialog(
 							&frame_copy,
 							// TRANSLATORS: Title of the window that renders a document's content as HTML (e.g. for embedded web pages)
 							&t("Web View"),
@@ -1404,6 +2150,18 @@ impl MainWindow {
 								}
 							})),
 						);
+						// Only follow the web view when an internal link moved it off the anchor it opened on.
+						let final_fragment = final_url.split_once('#').map(|(_, fragment)| fragment);
+						if final_fragment.is_some() && final_fragment != fragment.as_deref() {
+							let mut dm_ref = dm.lock().unwrap();
+							if let Some(tab) = dm_ref.active_tab_mut()
+								&& let Some(offset) = tab.session.webview_url_position(&final_url, current_pos)
+							{
+								tab.text_ctrl.set_insertion_point(offset);
+								tab.text_ctrl.show_position(offset);
+								tab.session.check_and_record_history(offset);
+							}
+						}
 					} else {
 						tracing::warn!(path = %tab.file_path.display(), "could not determine web view content");
 						let dialog = MessageDialog::builder(
@@ -1485,164 +2243,133 @@ impl MainWindow {
 					let Some(options) = options else {
 						return;
 					};
-					let (
-						old_word_wrap,
-						old_render_tables_inline,
-						old_compact_menu,
-						old_readability_font,
-						old_line_spacing,
-						old_bg_color,
-						old_text_alignment,
-						old_letter_spacing,
-						old_paragraph_spacing,
-					) = {
+					let (before, old_autosave_interval) = {
 						let cfg = config.lock().unwrap();
-						(
-							cfg.get_app_bool("word_wrap", false),
-							cfg.get_app_bool("render_tables_inline", true),
-							cfg.get_app_bool("compact_go_menu", true),
-							cfg.get_readability_font(),
-							cfg.get_line_spacing(),
-							cfg.get_bg_color(),
-							cfg.get_text_alignment(),
-							cfg.get_letter_spacing(),
-							cfg.get_paragraph_spacing(),
-						)
+						(ReadingSettings::from_config(&cfg), get_autosave_interval(&cfg))
 					};
 					let cfg = config.lock().unwrap();
 					cfg.set_app_bool("restore_previous_documents", options.restore_previous_documents);
+					cfg.set_app_bool("auto_sync_sidecar", options.auto_sync_sidecar);
+					cfg.set_app_bool("relink_moved_documents", options.relink_moved_documents);
+					cfg.set_app_string("library_folder", &options.library_folder);
 					cfg.set_app_bool("word_wrap", options.word_wrap);
 					cfg.set_app_bool("render_tables_inline", options.render_tables_inline);
+					cfg.set_app_bool("include_speaker_notes", options.include_speaker_notes);
+					cfg.set_app_bool("include_comments", options.include_comments);
+					cfg.set_app_bool("skip_unlabeled_images", options.skip_unlabeled_images);
+					cfg.set_app_bool("normalize_typography", options.normalize_typography);
 					cfg.set_app_bool("minimize_to_tray", options.minimize_to_tray);
 					cfg.set_app_bool("start_maximized", options.start_maximized);
+					cfg.set_app_bool("single_instance", options.single_instance);
 					cfg.set_app_bool("compact_go_menu", options.compact_go_menu);
 					cfg.set_app_bool("navigation_wrap", options.navigation_wrap);
 					cfg.set_app_bool("check_for_updates_on_startup", options.check_for_updates_on_startup);
 					cfg.set_app_bool("bookmark_sounds", options.bookmark_sounds);
+					cfg.set_app_bool("announce_bookmarks", options.announce_bookmarks);
+					cfg.set_app_bool("announce_location_on_navigation", options.announce_location_on_navigation);
+					cfg.set_app_bool("context_announcements", options.context_announcements);
+					cfg.set_app_bool("find_loads_all_pages", options.find_loads_all_pages);
+					cfg.set_app_bool("show_section_in_title", options.show_section_in_title);
 					cfg.set_app_int("recent_documents_to_show", options.recent_documents_to_show);
 					cfg.set_app_int("reading_speed_wpm", options.reading_speed_wpm);
+					if options.status_format != status::status_format(&cfg) {
+						cfg.set_app_string("status_format", &options.status_format);
+					}
+					cfg.set_app_string("dictionary_path", &options.dictionary_path);
+					let lookup_url =
+						if options.lookup_url.is_empty() { lookup::DEFAULT_LOOKUP_URL } else { &options.lookup_url };
+					if lookup_url != cfg.get_app_string("lookup_url", lookup::DEFAULT_LOOKUP_URL) {
+						cfg.set_app_string("lookup_url", lookup_url);
+					}
 					cfg.set_app_string("language", &options.language);
 					set_update_channel(&cfg, options.update_channel);
+					set_autosave_interval(&cfg, options.autosave_interval);
 					cfg.set_hotkey(&options.hotkey);
 					cfg.set_readability_font(&options.readability_font);
 					cfg.set_line_spacing(options.line_spacing);
 					cfg.set_bg_color(options.bg_color);
+					set_color_scheme(&cfg, options.color_scheme);
+					set_text_color(&cfg, options.text_color);
 					cfg.set_text_alignment(options.text_alignment);
 					cfg.set_letter_spacing(options.letter_spacing);
 					cfg.set_paragraph_spacing(options.paragraph_spacing);
+					cfg.set_shortcuts(&shortcuts::to_config(&options.shortcuts));
+					if options.compact_configuration {
+						let removed = cfg.compact_documents();
+						tracing::info!(removed, "compacted configuration");
+					}
 					cfg.flush();
 					tracing::info!("settings saved");
 					#[cfg(target_os = "windows")]
 					{
 						re_register_hotkey(&hotkey_handle_for_options, &options.hotkey);
 					}
+					let diff = SettingsDiff { before, after: ReadingSettings::from_config(&cfg) };
 					drop(cfg);
-					let options_word_wrap = options.word_wrap;
-					let options_render_tables_inline = options.render_tables_inline;
-					let render_tables_inline_changed = old_render_tables_inline != options_render_tables_inline;
-					let font_changed = old_readability_font != options.readability_font;
-					let line_spacing_changed = old_line_spacing != options.line_spacing;
-					let bg_color_changed = old_bg_color != options.bg_color;
-					let text_alignment_changed = old_text_alignment != options.text_alignment;
-					let letter_spacing_changed = old_letter_spacing != options.letter_spacing;
-					let paragraph_spacing_changed = old_paragraph_spacing != options.paragraph_spacing;
-					let needs_rebuild = old_word_wrap != options_word_wrap
-						|| (font_changed && build_font_from_readability(&options.readability_font).is_none())
-						|| (bg_color_changed && options.bg_color < 0)
-						|| (font_changed && options.readability_font.color < 0);
-					if needs_rebuild {
-						let dm_for_wrap = Rc::clone(&dm);
-						let mut dm_ref = dm.lock().unwrap();
-						dm_ref.apply_word_wrap(&dm_for_wrap, options_word_wrap);
-						dm_ref.restore_focus();
-					} else {
-						let dm_ref = dm.lock().unwrap();
-						if font_changed {
-							if let Some(font) = build_font_from_readability(&options.readability_font) {
-								dm_ref.apply_font(&font);
-							}
-							dm_ref.apply_color(options.readability_font.color);
-						}
-						if bg_color_changed {
-							dm_ref.apply_bg_color(options.bg_color);
-						}
-						if line_spacing_changed {
-							dm_ref.apply_line_spacing(options.line_spacing);
-						}
-						if text_alignment_changed {
-							dm_ref.apply_text_alignment(options.text_alignment);
-						}
-						if letter_spacing_changed {
-							dm_ref.apply_letter_spacing(options.letter_spacing);
-						}
-						if paragraph_spacing_changed {
-							dm_ref.apply_paragraph_spacing(options.paragraph_spacing);
-						}
+					if old_autosave_interval != options.autosave_interval {
+						restart_autosave_timer(options.autosave_interval);
 					}
-					if render_tables_inline_changed {
-						let mut dm_ref = dm.lock().unwrap();
-						dm_ref.apply_render_tables_inline(options_render_tables_inline);
-					}
-					let options_compact_menu = options.compact_go_menu;
-					if current_language != options.language || old_compact_menu != options_compact_menu {
-						if current_language != options.language {
-							let _ = TranslationManager::instance().lock().unwrap().set_language(&options.language);
-						}
-						let dm_ref = dm.lock().unwrap();
-						update_title_from_manager(&frame_copy, &dm_ref);
+					apply_settings(&frame_copy, &dm, &diff);
+					let switched =
+						current_language != options.language && translation_manager::switch_language(&options.language);
+					// Switching the language has already refreshed the title and menus, along with the other windows.
+					if !switched {
+						update_title_from_manager(&frame_copy, &dm.lock().unwrap());
+						rebuild_menu_bar(&frame_copy, &config, &dm);
 					}
-					let menu_bar = menu::create_menu_bar(&config.lock().unwrap());
-					frame_copy.set_menu_bar(menu_bar);
+				}
+				menu_ids::SAVE_READING_PROFILE => {
+					handle_save_reading_profile(&frame_copy, &config, &dm, live_region_label);
+				}
+				menu_ids::DELETE_READING_PROFILE => {
+					handle_delete_reading_profile(&frame_copy, &config, &dm, live_region_label);
+				}
+				menu_ids::AUTO_SCROLL => {
 					let dm_ref = dm.lock().unwrap();
-					let has_docs = dm_ref.tab_count() > 0;
-					let has_reopen = dm_ref.has_recently_closed();
-					drop(dm_ref);
-					menu::update_menu_item_states(&frame_copy, has_docs);
-					menu::update_reopen_state(&frame_copy, has_reopen);
+					if dm_ref.stop_auto_scroll() {
+						auto_scroll_timer.stop();
+						// TRANSLATORS: Announced when the user stops auto-scroll
+						speech::announce(live_region_label, &t("Auto-scroll stopped."));
+					} else if let Some(speed) = dm_ref.start_auto_scroll() {
+						let interval = dm_ref.auto_scroll_interval();
+						auto_scroll_interval.set(interval);
+						auto_scroll_timer.start(interval, false);
+						// TRANSLATORS: Announced when auto-scroll starts; %d is the speed. Plus and minus change it while it runs
+						let message =
+							t("Auto-scroll started, %d lines per minute. Press plus or minus to change speed.");
+						speech::announce(live_region_label, &message.replace("%d", &speed.to_string()));
+					}
 				}
 				menu_ids::SLEEP_TIMER => {
-					if sleep_timer_running_for_menu.get() {
-						sleep_timer_for_menu.stop();
-						sleep_timer_running_for_menu.set(false);
-						sleep_timer_start_for_menu.set(0);
-						sleep_timer_duration_for_menu.set(0);
-						SLEEP_TIMER_START_MS.store(0, Ordering::SeqCst);
-						SLEEP_TIMER_DURATION_MINUTES.store(0, Ordering::SeqCst);
+					if sleep_timer::controller().is_running() {
+						sleep_timer::controller().stop();
+						close_sleep_timer_warning(&sleep_warning_for_menu);
 						tracing::info!("sleep timer cancelled");
 						let dm_ref = dm.lock().unwrap();
 						update_title_from_manager(&frame_copy, &dm_ref);
 						// TRANSLATORS: Announced when the user cancels a running sleep timer
-						live_region::announce(live_region_label, &t("Sleep timer cancelled."));
+						speech::announce(live_region_label, &t("Sleep timer cancelled."));
 						return;
 					}
-					let initial_duration = config.lock().unwrap().get_app_int("sleep_timer_duration", 30);
-					if let Some(duration) = dialogs::show_sleep_timer_dialog(&frame_copy, initial_duration) {
+					let (initial_duration, initial_action) = {
+						let cfg = config.lock().unwrap();
+						(cfg.get_app_int("sleep_timer_duration", 30), get_sleep_action(&cfg))
+					};
+					if let Some((duration, action)) =
+						dialogs::show_sleep_timer_dialog(&frame_copy, initial_duration, initial_action)
+					{
 						{
 							let cfg = config.lock().unwrap();
 							cfg.set_app_int("sleep_timer_duration", duration);
+							set_sleep_action(&cfg, action);
 							cfg.flush();
 						}
-						let duration_ms = u64::try_from(duration).unwrap_or(0) * 60 * 1000;
-						sleep_timer_for_menu.start(i32::try_from(duration_ms).unwrap_or(i32::MAX), true);
-						sleep_timer_running_for_menu.set(true);
+						sleep_timer::controller().start(sleep_timer::now_ms(), duration);
 						tracing::info!(duration_minutes = duration, "sleep timer started");
-						let now = SystemTime::now()
-							.duration_since(UNIX_EPOCH)
-							.ok()
-							.and_then(|d| i64::try_from(d.as_millis()).ok())
-							.unwrap_or(0);
-						sleep_timer_start_for_menu.set(now);
-						sleep_timer_duration_for_menu.set(duration);
-						SLEEP_TIMER_START_MS.store(now, Ordering::SeqCst);
-						SLEEP_TIMER_DURATION_MINUTES.store(duration, Ordering::SeqCst);
-						let msg = if duration == 1 {
-							// TRANSLATORS: Announcement when the sleep timer is set for exactly 1 minute
-							t("Sleep timer set for 1 minute.")
-						} else {
-							// TRANSLATORS: Announcement when the sleep timer is set; %d is the number of minutes (always 2 or more)
-							t("Sleep timer set for %d minutes.").replace("%d", &duration.to_string())
-						};
-						live_region::announce(live_region_label, &msg);
+						// TRANSLATORS: Announcement when the sleep timer is set; %d is the number of minutes. Give one variant per plural form of your language, separated by |
+						let msg = t("Sleep timer set for %d minute.|Sleep timer set for %d minutes.");
+						let msg = plural(&msg, u64::try_from(duration).unwrap_or(0));
+						speech::announce(live_region_label, &msg);
 					}
 				}
 				menu_ids::ABOUT => {
@@ -1682,6 +2409,10 @@ impl MainWindow {
 						if let Ok(doc_index) = usize::try_from(doc_index)
 							&& let Some(path) = recent_docs.get(doc_index)
 						{
+							if url_download::is_url(path) {
+								open_url(&frame_copy, &dm, path);
+								return;
+							}
 							let path = Path::new(path);
 							if !ensure_parser_ready_for_path(&frame_copy, path, &config) {
 								return;
@@ -1699,6 +2430,21 @@ impl MainWindow {
 								menu::update_reopen_state(&frame_copy, has_reopen);
 							}
 						}
+					} else if (menu_ids::SET_QUICK_SLOT_BASE..=menu_ids::SET_QUICK_SLOT_MAX).contains(&id) {
+						if let Ok(slot) = u8::try_from(id - menu_ids::SET_QUICK_SLOT_BASE) {
+							navigation::handle_set_quick_slot(&dm, &config, live_region_label, slot);
+						}
+					} else if (menu_ids::GO_TO_QUICK_SLOT_BASE..=menu_ids::GO_TO_QUICK_SLOT_MAX).contains(&id) {
+						if let Ok(slot) = u8::try_from(id - menu_ids::GO_TO_QUICK_SLOT_BASE) {
+							navigation::handle_go_to_quick_slot(&dm, &config, live_region_label, slot);
+						}
+					} else if (menu_ids::READING_PROFILE_BASE..=menu_ids::READING_PROFILE_MAX).contains(&id) {
+						let names = config.lock().unwrap().reading_profile_names();
+						if let Ok(index) = usize::try_from(id - menu_ids::READING_PROFILE_BASE)
+							&& let Some(name) = names.get(index)
+						{
+							switch_reading_profile(&frame_copy, &config, &dm, live_region_label, name);
+						}
 					} else if id == menu_ids::SHOW_ALL_DOCUMENTS {
 						let has_documents = {
 							let config_guard = config.lock().unwrap();
@@ -1706,7 +2452,7 @@ impl MainWindow {
 						};
 						if !has_documents {
 							// TRANSLATORS: Announced when opening "All Documents" while the recent-documents list is empty
-							live_region::announce(live_region_label, &t("No recent documents."));
+							speech::announce(live_region_label, &t("No recent documents."));
 							return;
 						}
 						let open_paths = dm.lock().unwrap().open_paths();
@@ -1726,6 +2472,10 @@ impl MainWindow {
 							}
 						}
 						if let Some(path) = result.open {
+							if url_download::is_url(&path) {
+								open_url(&frame_copy, &dm, &path);
+								return;
+							}
 							let path_buf = Path::new(&path).to_path_buf();
 							let path = path_buf.as_path();
 							if !ensure_parser_ready_for_path(&frame_copy, path, &config) {
@@ -1769,6 +2519,84 @@ impl MainWindow {
 	}
 }
 
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+struct WindowApiHost<'a>(&'a MainWindow);
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+impl ApiHost for WindowApiHost<'_> {
+	fn state(&self) -> Option<DocumentState> {
+		let dm = self.0.doc_manager.lock().unwrap();
+		let tab = dm.active_tab()?;
+		let caret = tab.text_ctrl.get_insertion_point();
+		Some(DocumentState {
+			path: tab.file_path.to_string_lossy().to_string(),
+			title: display_title(tab),
+			caret,
+			percent: tab.session.get_status_info(caret).percentage,
+		})
+	}
+
+	fn toc(&self) -> Option<Vec<TocEntry>> {
+		let dm = self.0.doc_manager.lock().unwrap();
+		dm.active_tab().map(|tab| tab.session.get_toc())
+	}
+
+	fn bookmarks(&self) -> Option<Vec<Bookmark>> {
+		let path = self.0.doc_manager.lock().unwrap().active_tab()?.file_path.to_string_lossy().to_string();
+		Some(self.0.config.lock().unwrap().get_bookmarks(&path))
+	}
+
+	fn goto(&mut self, offset: i64) -> bool {
+		let history_update = {
+			let mut dm = self.0.doc_manager.lock().unwrap();
+			let Some(tab) = dm.active_tab_mut() else {
+				return false;
+			};
+			let offset = offset.min(tab.text_ctrl.get_last_position());
+			tab.text_ctrl.set_insertion_point(offset);
+			tab.text_ctrl.show_position(offset);
+			tab.session.check_and_record_history(offset);
+			let history_update = tab.track.then(|| {
+				let (history, history_index) = tab.session.saved_history();
+				(tab.file_path.to_string_lossy().to_string(), history, history_index)
+			});
+			dm.update_status_bar();
+			history_update
+		};
+		if let Some((path, history, history_index)) = history_update {
+			self.0.config.lock().unwrap().set_navigation_history(&path, &history, history_index);
+		}
+		true
+	}
+
+	fn open(&mut self, path: &str) -> Result<(), String> {
+		let path = normalize_cli_path(Path::new(path));
+		if self.0.open_file(&path) { Ok(()) } else { Err(format!("could not open {}", path.display())) }
+	}
+}
+
+/// Switches to the tab already showing `url`, or downloads it to open once it arrives.
+fn open_url(frame: &Frame, doc_manager: &Rc<Mutex<DocumentManager>>, url: &str) {
+	{
+		let dm = doc_manager.lock().unwrap();
+		if let Some(index) = dm.find_tab_by_path(Path::new(url)) {
+			dm.notebook().set_selection(index);
+			dm.restore_focus();
+			return;
+		}
+	}
+	if url_download::is_downloading() {
+		// TRANSLATORS: Error shown when a document is opened from a web address while another one is still downloading
+		let message = t("Another document is still downloading. Try again when it has finished.");
+		let dialog = MessageDialog::builder(frame, &message, &t("Error"))
+			.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
+			.build();
+		dialog.show_modal();
+		return;
+	}
+	url_download::start(url.to_string());
+}
+
 fn ensure_parser_ready_for_path(frame: &Frame, path: &Path, config: &Rc<Mutex<ConfigManager>>) -> bool {
 	let extension = parser_extension_for_path(path);
 	if extension.is_empty() || parser_supports_extension(&extension) {
@@ -1806,21 +2634,446 @@ fn ensure_parser_for_unknown_file(parent: &Frame, path: &Path, config: &ConfigMa
 	if !saved_format.is_empty() && parser_supports_extension(&saved_format) {
 		return true;
 	}
-	let Some(format) = dialogs::show_open_as_dialog(parent, path) else {
+	let Some(choice) = dialogs::show_open_as_dialog(parent, path, None, None) else {
 		return false;
 	};
-	if !parser_supports_extension(&format) {
-		// TRANSLATORS: Error shown when the user picks a file format from the "Open As" dialog that this parser build doesn't support
-		let message = t("Unsupported format selected.");
-		let title = t("Error");
-		let dialog = MessageDialog::builder(parent, &message, &title)
-			.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
-			.build();
+	config.set_document_format(&path_str, &choice.format);
+	config.set_document_encoding(&path_str, &choice.encoding);
+	true
+}
+
+/// Typography normalization shifts text offsets, so it only takes effect when a document is parsed.
+/// Most unplaced entries listed after an annotation import; the rest are only counted.
+const MAX_UNPLACED_LISTED: usize = 20;
+
+/// Reports how many annotations an import added, listing the ones that couldn't be found in the
+/// document so they aren't lost without a trace.
+fn show_annotation_import_summary(frame: &Frame, import: Option<&AnnotationImport>) {
+	let Some(import) = import else {
+		let dialog = MessageDialog::builder(
+			frame,
+			// TRANSLATORS: Error shown when a chosen Calibre or Moon+ Reader file can't be read as annotations
+			&t("This file doesn't contain annotations Paperback can read."),
+			// TRANSLATORS: Title of the dialog shown when importing annotations fails
+			&t("Import Failed"),
+		)
+		.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
+		.build();
 		dialog.show_modal();
-		return false;
+		return;
+	};
+	// TRANSLATORS: Shown after importing annotations from another reading app; %d is how many were added as bookmarks
+	let mut message = t("Imported %d annotations.").replace("%d", &import.annotations.len().to_string());
+	if !import.unplaced.is_empty() {
+		message.push_str("\n\n");
+		// TRANSLATORS: Followed by a list of imported annotations whose text couldn't be found in the open document; %d is how many
+		message
+			.push_str(&t("%d could not be found in this document:").replace("%d", &import.unplaced.len().to_string()));
+		for entry in import.unplaced.iter().take(MAX_UNPLACED_LISTED) {
+			message.push_str("\n- ");
+			message.push_str(entry);
+		}
+		if import.unplaced.len() > MAX_UNPLACED_LISTED {
+			message.push('\n');
+			// TRANSLATORS: Ends a shortened list of annotations that couldn't be placed; %d is how many weren't listed
+			message
+				.push_str(&t("and %d more").replace("%d", &(import.unplaced.len() - MAX_UNPLACED_LISTED).to_string()));
+		}
 	}
-	config.set_document_format(&path_str, &format);
-	true
+	// TRANSLATORS: Title of the dialog summarizing an annotation import
+	let dialog = MessageDialog::builder(frame, &message, &t("Import Finished"))
+		.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconInformation | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal();
+}
+
+fn handle_backup_all_data(frame: &Frame, config: &Rc<Mutex<ConfigManager>>, dm: &Rc<Mutex<DocumentManager>>) {
+	// TRANSLATORS: File filter shown in the Backup All Data and Restore All Data dialogs
+	let wildcard = t("Paperback backups (*.json)|*.json");
+	let dialog = FileDialog::builder(frame)
+		// TRANSLATORS: Title of the file save dialog when backing up the data of every document
+		.with_message(&t("Backup all data"))
+		.with_default_file("paperback-backup.json")
+		.with_wildcard(&wildcard)
+		.with_style(FileDialogStyle::Save | FileDialogStyle::OverwritePrompt)
+		.build();
+	if dialog.show_modal() != ID_OK {
+		return;
+	}
+	let Some(path) = dialog.get_path() else {
+		return;
+	};
+	// Open documents' positions are only saved now and then, so the backup takes the current ones.
+	if let Ok(dm_ref) = dm.try_lock() {
+		dm_ref.save_all_positions();
+	}
+	let result = config.lock().unwrap().backup_to_file(Path::new(&path));
+	let (message, title, icon) = match result {
+		Ok(()) => {
+			tracing::info!(backup = %path, "all data backed up");
+			(
+				// TRANSLATORS: Success message shown after backing up the data of every document
+				t("All data backed up successfully."),
+				// TRANSLATORS: Title of the backup-succeeded dialog
+				t("Backup Successful"),
+				MessageDialogStyle::IconInformation,
+			)
+		}
+		Err(error) => {
+			tracing::warn!(backup = %path, %error, "failed to back up data");
+			(
+				// TRANSLATORS: Error shown when the backup file can't be written
+				t("The backup file could not be saved."),
+				// TRANSLATORS: Title of the dialog shown when backing up fails
+				t("Backup Failed"),
+				MessageDialogStyle::IconError,
+			)
+		}
+	};
+	let dialog = MessageDialog::builder(frame, &message, &title)
+		.with_style(MessageDialogStyle::OK | icon | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal();
+}
+
+fn handle_restore_all_data(frame: &Frame, config: &Rc<Mutex<ConfigManager>>, dm: &Rc<Mutex<DocumentManager>>) {
+	// TRANSLATORS: File filter shown in the Backup All Data and Restore All Data dialogs
+	let wildcard = t("Paperback backups (*.json)|*.json");
+	let dialog = FileDialog::builder(frame)
+		// TRANSLATORS: Title of the file open dialog when restoring a backup of every document's data
+		.with_message(&t("Restore all data"))
+		.with_wildcard(&wildcard)
+		.with_style(FileDialogStyle::Open | FileDialogStyle::FileMustExist)
+		.build();
+	if dialog.show_modal() != ID_OK {
+		return;
+	}
+	let Some(path) = dialog.get_path() else {
+		return;
+	};
+	// Save what's open first, so reading done since the backup was made counts as the newer copy.
+	if let Ok(dm_ref) = dm.try_lock() {
+		dm_ref.save_all_positions();
+	}
+	let result = config.lock().unwrap().restore_backup_from_file(Path::new(&path));
+	let summary = match result {
+		Ok(summary) => summary,
+		Err(error) => {
+			tracing::warn!(backup = %path, %error, "failed to restore backup");
+			let message = if matches!(error, BackupError::UnsupportedVersion(_)) {
+				// TRANSLATORS: Error shown when a backup was made by a newer version of Paperback than the one restoring it
+				t("This backup was made by a newer version of Paperback. Update Paperback to restore it.")
+			} else {
+				// TRANSLATORS: Error shown when the chosen file can't be read as a Paperback backup
+				t("This file isn't a Paperback backup, or it couldn't be read.")
+			};
+			// TRANSLATORS: Title of the dialog shown when restoring a backup fails
+			let dialog = MessageDialog::builder(frame, &message, &t("Restore Failed"))
+				.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconError | MessageDialogStyle::Centre)
+				.build();
+			dialog.show_modal();
+			return;
+		}
+	};
+	tracing::info!(backup = %path, restored = summary.restored, kept = summary.kept, "backup restored");
+	rebuild_menu_bar(frame, config, dm);
+	// TRANSLATORS: Shown after restoring a backup; %d is how many documents' data was restored
+	let mut message = t("Restored data for %d documents.").replace("%d", &summary.restored.to_string());
+	if summary.kept > 0 {
+		message.push(' ');
+		// TRANSLATORS: Follows the restore summary; %d is how many documents were left alone because they changed on this computer after the backup was made
+		message.push_str(
+			&t("%d were kept as they are, having changed more recently here.").replace("%d", &summary.kept.to_string()),
+		);
+	}
+	message.push_str("\n\n");
+	// TRANSLATORS: Ends the restore summary, since the restored settings aren't applied to the open window
+	message.push_str(&t("Restart Paperback to apply the restored settings."));
+	// TRANSLATORS: Title of the dialog summarizing a restored backup
+	let dialog = MessageDialog::builder(frame, &message, &t("Restore Finished"))
+		.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconInformation | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal();
+}
+
+/// The settings that change how open documents are laid out or parsed.
+struct ReadingSettings {
+	word_wrap: bool,
+	render_tables_inline: bool,
+	include_speaker_notes: bool,
+	include_comments: bool,
+	skip_unlabeled_images: bool,
+	normalize_typography: bool,
+	readability_font: ReadabilityFont,
+	line_spacing: i32,
+	colors: ReadingColors,
+	text_alignment: i32,
+	letter_spacing: i32,
+	paragraph_spacing: i32,
+}
+
+impl ReadingSettings {
+	fn from_config(cfg: &ConfigManager) -> Self {
+		Self {
+			word_wrap: cfg.get_app_bool("word_wrap", false),
+			render_tables_inline: cfg.get_app_bool("render_tables_inline", true),
+			include_speaker_notes: cfg.get_app_bool("include_speaker_notes", true),
+			include_comments: cfg.get_app_bool("include_comments", false),
+			skip_unlabeled_images: cfg.get_app_bool("skip_unlabeled_images", false),
+			normalize_typography: cfg.get_app_bool("normalize_typography", false),
+			readability_font: cfg.get_readability_font(),
+			line_spacing: cfg.get_line_spacing(),
+			colors: ReadingColors::for_document(cfg),
+			text_alignment: cfg.get_text_alignment(),
+			letter_spacing: cfg.get_letter_spacing(),
+			paragraph_spacing: cfg.get_paragraph_spacing(),
+		}
+	}
+}
+
+/// Reading settings before and after a change, already written to the config.
+struct SettingsDiff {
+	before: ReadingSettings,
+	after: ReadingSettings,
+}
+
+/// Pushes a settings change to every open document, rebuilding the reading controls only when a
+/// change can't be applied in place. Shared by the Options dialog and reading profiles.
+fn apply_settings(frame: &Frame, dm: &Rc<Mutex<DocumentManager>>, diff: &SettingsDiff) {
+	let SettingsDiff { before, after } = diff;
+	let font_changed = before.readability_font != after.readability_font;
+	let needs_rebuild = before.word_wrap != after.word_wrap
+		|| (font_changed && build_font_from_readability(&after.readability_font).is_none())
+		|| after.colors.needs_rebuild_from(before.colors);
+	if needs_rebuild {
+		let dm_for_wrap = Rc::clone(dm);
+		let mut dm_ref = dm.lock().unwrap();
+		dm_ref.apply_word_wrap(&dm_for_wrap, after.word_wrap);
+		dm_ref.restore_focus();
+		if before.word_wrap != after.word_wrap {
+			dm_ref.announce_word_wrap(after.word_wrap);
+		}
+	} else {
+		let dm_ref = dm.lock().unwrap();
+		if font_changed && let Some(font) = build_font_from_readability(&after.readability_font) {
+			dm_ref.apply_font(&font);
+		}
+		if before.colors != after.colors {
+			dm_ref.apply_reading_colors(after.colors);
+		}
+		if before.line_spacing != after.line_spacing {
+			dm_ref.apply_line_spacing(after.line_spacing);
+		}
+		if before.text_alignment != after.text_alignment {
+			dm_ref.apply_text_alignment(after.text_alignment);
+		}
+		if before.letter_spacing != after.letter_spacing {
+			dm_ref.apply_letter_spacing(after.letter_spacing);
+		}
+		if before.paragraph_spacing != after.paragraph_spacing {
+			dm_ref.apply_paragraph_spacing(after.paragraph_spacing);
+		}
+	}
+	let typography_changed = before.normalize_typography != after.normalize_typography;
+	let has_open_documents = dm.lock().unwrap().tab_count() > 0;
+	let reload_for_typography = typography_changed && has_open_documents && confirm_reload_for_typography(frame);
+	if before.render_tables_inline != after.render_tables_inline
+		|| before.include_speaker_notes != after.include_speaker_notes
+		|| before.include_comments != after.include_comments
+		|| before.skip_unlabeled_images != after.skip_unlabeled_images
+		|| reload_for_typography
+	{
+		let mut dm_ref = dm.lock().unwrap();
+		dm_ref.apply_render_tables_inline(after.render_tables_inline);
+	}
+}
+
+/// Recreates the menu bar so labels, shortcuts, check marks and the profile list reflect the config.
+fn rebuild_menu_bar(frame: &Frame, config: &Rc<Mutex<ConfigManager>>, dm: &Rc<Mutex<DocumentManager>>) {
+	let menu_bar = menu::create_menu_bar(&config.lock().unwrap());
+	frame.set_menu_bar(menu_bar);
+	let dm_ref = dm.lock().unwrap();
+	#[cfg(target_os = "linux")]
+	dm_ref.reload_navigation_keys();
+	let has_docs = dm_ref.tab_count() > 0;
+	let has_reopen = dm_ref.has_recently_closed();
+	drop(dm_ref);
+	menu::update_menu_item_states(frame, has_docs);
+	menu::update_reopen_state(frame, has_reopen);
+}
+
+fn switch_reading_profile(
+	frame: &Frame,
+	config: &Rc<Mutex<ConfigManager>>,
+	dm: &Rc<Mutex<DocumentManager>>,
+	live_region_label: StaticText,
+	name: &str,
+) {
+	let diff = {
+		let cfg = config.lock().unwrap();
+		let before = ReadingSettings::from_config(&cfg);
+		if cfg.apply_reading_profile(name).is_none() {
+			return;
+		}
+		cfg.flush();
+		SettingsDiff { before, after: ReadingSettings::from_config(&cfg) }
+	};
+	tracing::info!(profile = name, "switched reading profile");
+	apply_settings(frame, dm, &diff);
+	rebuild_menu_bar(frame, config, dm);
+	dm.lock().unwrap().restore_focus();
+	// TRANSLATORS: Announced after switching to a reading profile; {} is the profile name
+	speech::announce(live_region_label, &t("Profile {} active.").replace("{}", name));
+}
+
+fn handle_save_reading_profile(
+	frame: &Frame,
+	config: &Rc<Mutex<ConfigManager>>,
+	dm: &Rc<Mutex<DocumentManager>>,
+	live_region_label: StaticText,
+) {
+	let active = config.lock().unwrap().active_reading_profile();
+	// TRANSLATORS: Prompt asking for the name to save the current reading settings under
+	let prompt = t("Profile &name:");
+	// TRANSLATORS: Title of the dialog that saves the current reading settings as a profile
+	let title = t("Save Reading Profile");
+	let dialog = TextEntryDialog::builder(frame, &prompt, &title).with_default_value(&active).build();
+	if dialog.show_modal() != ID_OK {
+		return;
+	}
+	let Some(name) = dialog.get_value().map(|value| value.trim().to_string()).filter(|value| !value.is_empty()) else {
+		return;
+	};
+	{
+		let cfg = config.lock().unwrap();
+		cfg.save_reading_profile(&name);
+		cfg.flush();
+	}
+	rebuild_menu_bar(frame, config, dm);
+	// TRANSLATORS: Announced after saving the current reading settings as a profile; {} is the profile name
+	speech::announce(live_region_label, &t("Profile {} saved.").replace("{}", &name));
+}
+
+fn handle_delete_reading_profile(
+	frame: &Frame,
+	config: &Rc<Mutex<ConfigManager>>,
+	dm: &Rc<Mutex<DocumentManager>>,
+	live_region_label: StaticText,
+) {
+	let name = config.lock().unwrap().active_reading_profile();
+	if name.is_empty() {
+		// TRANSLATORS: Announced when choosing Delete Profile while no reading profile is active
+		speech::announce(live_region_label, &t("No profile is active."));
+		return;
+	}
+	// TRANSLATORS: Confirmation before deleting the active reading profile; {} is the profile name. The current settings are kept
+	let message = t("Delete the profile {}? Your current settings are kept.").replace("{}", &name);
+	// TRANSLATORS: Title of the dialog confirming that a reading profile should be deleted
+	let title = t("Delete Reading Profile");
+	let dialog = MessageDialog::builder(frame, &message, &title)
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	if dialog.show_modal() != ID_YES {
+		return;
+	}
+	{
+		let cfg = config.lock().unwrap();
+		cfg.delete_reading_profile(&name);
+		cfg.flush();
+	}
+	rebuild_menu_bar(frame, config, dm);
+	// TRANSLATORS: Announced after deleting a reading profile; {} is the profile name
+	speech::announce(live_region_label, &t("Profile {} deleted.").replace("{}", &name));
+}
+
+fn confirm_reload_for_typography(frame: &Frame) -> bool {
+	// TRANSLATORS: Prompt shown after toggling plain quotes and dashes in Options, since the change only applies when documents are loaded
+	let message = t("Reload open documents now to apply the new quote and dash setting?");
+	// TRANSLATORS: Title of the dialog asking to reload documents after changing a parse-time option
+	let title = t("Reload documents");
+	let dialog = MessageDialog::builder(frame, &message, &title)
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal() == ID_YES
+}
+
+/// Asks whether to reopen the documents from a session that ended without a clean exit. Used when
+/// restoring previous documents is turned off, since the crash is the only reason to offer it.
+fn confirm_crash_restore(frame: &Frame) -> bool {
+	// TRANSLATORS: Prompt shown at startup when Paperback crashed or was killed during the previous session
+	let message =
+		t("Paperback didn't close properly last time. Would you like to reopen the documents that were open?");
+	// TRANSLATORS: Title of the dialog offering to restore documents after a crash
+	let title = t("Restore documents");
+	let dialog = MessageDialog::builder(frame, &message, &title)
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal() == ID_YES
+}
+
+/// Asks what to do with the document the previous run crashed while parsing, which restoring leaves
+/// out. Showing its folder comes back to the question; dismissing it asks again at the next start.
+fn resolve_unfinished_document(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+) {
+	let Some(path) = config.lock().unwrap().unfinished_document() else {
+		return;
+	};
+	tracing::warn!(path = %path, "previous session ended while opening a document");
+	let path = PathBuf::from(path);
+	loop {
+		match dialogs::show_unfinished_document_dialog(frame, &path) {
+			Some(dialogs::UnfinishedDocumentChoice::TryAgain) => {
+				config.lock().unwrap().finish_parsing_document();
+				doc_manager.lock().unwrap().open_file(doc_manager, &path);
+				return;
+			}
+			Some(dialogs::UnfinishedDocumentChoice::RemoveFromSession) => {
+				let config = config.lock().unwrap();
+				config.remove_opened_document(&path.to_string_lossy());
+				config.finish_parsing_document();
+				return;
+			}
+			Some(dialogs::UnfinishedDocumentChoice::OpenContainingFolder) => {
+				if let Err(err) = shell::reveal_in_file_manager(&path) {
+					tracing::warn!(path = %path.display(), error = %err, "failed to reveal file in folder");
+				}
+			}
+			None => return,
+		}
+	}
+}
+
+/// Offers to point documents that were moved into the library folder at their new place, so their
+/// positions and bookmarks follow them.
+fn relink_moved_documents(frame: &Frame, config: &Rc<Mutex<ConfigManager>>) {
+	let library = config.lock().unwrap().get_app_string("library_folder", "");
+	if library.is_empty() {
+		return;
+	}
+	let moved = config.lock().unwrap().find_moved_documents(Path::new(&library));
+	if moved.is_empty() {
+		return;
+	}
+	tracing::info!(count = moved.len(), "found moved documents in the library folder");
+	// TRANSLATORS: Prompt shown at startup when documents Paperback remembers were found under a new path in the library folder; %d is how many
+	let message = t("%d documents seem to have moved into your library folder. Update their saved positions and bookmarks to the new locations?")
+		.replacen("%d", &moved.len().to_string(), 1);
+	// TRANSLATORS: Title of the dialog offering to relink moved documents
+	let title = t("Moved documents");
+	let dialog = MessageDialog::builder(frame, &message, &title)
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	if dialog.show_modal() != ID_YES {
+		return;
+	}
+	let cfg = config.lock().unwrap();
+	for (old_path, new_path) in &moved {
+		cfg.rename_document(old_path, new_path);
+	}
+	cfg.flush();
 }
 
 /// Close the active document, announcing the newly focused document for screen readers.
@@ -1835,41 +3088,111 @@ fn close_active_document_announced(dm: &mut DocumentManager, live_region_label:
 	};
 	let next = dm.active_index_after_closing(index).and_then(|i| dm.get_tab(i)).map(display_title);
 	if let Some(next) = &next {
-		live_region::announce(live_region_label, next);
+		speech::announce(live_region_label, next);
 	}
 	dm.close_document(index, true);
 }
 
-fn update_title_from_manager(frame: &Frame, dm: &DocumentManager) {
-	let sleep_start = SLEEP_TIMER_START_MS.load(Ordering::SeqCst);
-	let sleep_duration = SLEEP_TIMER_DURATION_MINUTES.load(Ordering::SeqCst);
-	if dm.tab_count() == 0 {
-		frame.set_title(&t("Paperback"));
-		let mut status_text = t("Ready");
-		if sleep_start > 0 {
-			let remaining = status::calculate_sleep_timer_remaining(sleep_start, sleep_duration);
-			if remaining > 0 {
-				status_text = status::format_sleep_timer_status(&status_text, remaining);
-			}
-		}
-		frame.set_status_text(&status_text, 0);
-		return;
+/// Tells the user, once per session, that their settings and positions are being kept in the
+/// pending file because the config file couldn't be written.
+fn warn_config_saved_elsewhere(frame: &Frame, pending_path: &Path) {
+	// TRANSLATORS: Warning shown when the settings file can't be saved (e.g. it is on a network share that became read-only); {} is the path of the local file used instead
+	let message = t("Paperback couldn't save its settings file, so your reading positions, bookmarks and settings are being saved to {} instead. They will be moved back the next time Paperback starts and can write its settings file.")
+		.replace("{}", &pending_path.display().to_string());
+	// TRANSLATORS: Title of a warning dialog
+	let dialog = MessageDialog::builder(frame, &message, &t("Warning"))
+		.with_style(MessageDialogStyle::OK | MessageDialogStyle::IconWarning | MessageDialogStyle::Centre)
+		.build();
+	dialog.show_modal();
+}
+
+/// Announces that the sleep timer is about to run out and offers to extend or stop it.
+fn warn_sleep_timer_ending(
+	frame: &Frame,
+	dm: &Rc<Mutex<DocumentManager>>,
+	warning: &Cell<Option<Dialog>>,
+	live_region_label: StaticText,
+) {
+	tracing::info!("sleep timer about to run out");
+	// TRANSLATORS: Announced two minutes before the sleep timer runs out
+	speech::announce(live_region_label, &t("Sleep timer: 2 minutes remaining"));
+	let dm_for_extend = Rc::clone(dm);
+	let dm_for_stop = Rc::clone(dm);
+	let dialog = dialogs::show_sleep_timer_warning(
+		frame,
+		move || {
+			sleep_timer::controller().extend(sleep_timer::EXTENSION_MINUTES);
+			tracing::info!("sleep timer extended");
+			// TRANSLATORS: Announced when the user extends a sleep timer that was about to run out
+			speech::announce(live_region_label, &t("Sleep timer extended by 10 minutes."));
+			dm_for_extend.lock().unwrap().update_status_bar();
+		},
+		move || {
+			sleep_timer::controller().stop();
+			tracing::info!("sleep timer cancelled");
+			// TRANSLATORS: Announced when the user cancels a running sleep timer
+			speech::announce(live_region_label, &t("Sleep timer cancelled."));
+			dm_for_stop.lock().unwrap().update_status_bar();
+		},
+	);
+	close_sleep_timer_warning(warning);
+	warning.set(Some(dialog));
+}
+
+fn close_sleep_timer_warning(warning: &Cell<Option<Dialog>>) {
+	if let Some(dialog) = warning.take() {
+		dialog.destroy();
 	}
-	if let Some(tab) = dm.active_tab() {
-		// TRANSLATORS: Window title when a document is open; {} is the document title
-		let template = t("Paperback - {}");
-		frame.set_title(&template.replace("{}", &display_title(tab)));
-		let position = tab.text_ctrl.get_insertion_point();
-		let status_info = tab.session.get_status_info(position);
-		let mut status_text = status::format_status_text(&status_info);
-		if sleep_start > 0 {
-			let remaining = status::calculate_sleep_timer_remaining(sleep_start, sleep_duration);
-			if remaining > 0 {
-				status_text = status::format_sleep_timer_status(&status_text, remaining);
+}
+
+/// Saves every open document's position, then exits or minimizes as the sleep timer was set to.
+fn expire_sleep_timer(frame: &Frame, dm: &Rc<Mutex<DocumentManager>>, config: &Rc<Mutex<ConfigManager>>) {
+	let action = {
+		let dm = dm.lock().unwrap();
+		let cfg = config.lock().unwrap();
+		for i in 0..dm.tab_count() {
+			if let Some(tab) = dm.get_tab(i)
+				&& !tab.pending
+			{
+				tab.save_position(&cfg, tab.text_ctrl.get_insertion_point());
 			}
 		}
-		frame.set_status_text(&status_text, 0);
+		cfg.flush();
+		get_sleep_action(&cfg)
+	};
+	match action {
+		SleepAction::Exit => {
+			tracing::info!("sleep timer fired, closing application");
+			frame.close(true);
+		}
+		SleepAction::Minimize => {
+			tracing::info!("sleep timer fired, minimizing application");
+			dm.lock().unwrap().update_status_bar();
+			// With minimize to tray on, the size handler takes it from here.
+			frame.iconize(true);
+		}
+	}
+}
+
+/// Mirrors the window for a UI language written right to left, and puts it back for one that isn't.
+fn apply_layout_direction(frame: &Frame) {
+	let language = TranslationManager::instance().lock().unwrap().current_language();
+	let direction = if translation_manager::is_right_to_left(&language) {
+		LayoutDirection::RightToLeft
+	} else {
+		LayoutDirection::LeftToRight
+	};
+	frame.set_layout_direction(direction);
+}
+
+fn update_title_from_manager(frame: &Frame, dm: &DocumentManager) {
+	if dm.active_tab().is_some() {
+		dm.refresh_section_title(true);
+	} else {
+		frame.set_title(&t("Paperback"));
 	}
+	dm.update_status_bar();
+	dm.refresh_pronunciation_rules();
 }
 
 #[cfg(test)]
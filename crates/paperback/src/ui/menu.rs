@@ -1,10 +1,11 @@
-use std::path::Path;
+use std::{iter, path::Path};
 
 use paperback_core::config::ConfigManager;
 use patois::t;
 use wxdragon::prelude::*;
 
-use super::menu_ids;
+use super::{menu_ids, shortcuts};
+use crate::config_ext::is_bookmark_category_navigation_enabled;
 
 /// IDs of menu items that require an open document.
 const DOCUMENT_DEPENDENT_IDS: &[i32] = &[
@@ -19,11 +20,24 @@ const DOCUMENT_DEPENDENT_IDS: &[i32] = &[
 	menu_ids::GO_TO_LINE,
 	menu_ids::GO_TO_PERCENT,
 	menu_ids::GO_TO_PAGE,
+	menu_ids::GO_TO_SECTION_NUMBER,
+	menu_ids::LANDMARKS,
+	menu_ids::SPEAK_LOCATION,
+	menu_ids::LAST_READ_POSITION,
+	menu_ids::FURTHEST_READ_POSITION,
 	menu_ids::GO_BACK,
 	menu_ids::GO_FORWARD,
+	menu_ids::HISTORY_LIST,
+	// Sentences / Paragraphs
+	menu_ids::PREVIOUS_SENTENCE,
+	menu_ids::NEXT_SENTENCE,
+	menu_ids::PREVIOUS_PARAGRAPH,
+	menu_ids::NEXT_PARAGRAPH,
 	// Sections
 	menu_ids::PREVIOUS_SECTION,
 	menu_ids::NEXT_SECTION,
+	menu_ids::RANDOM_SECTION,
+	menu_ids::TOGGLE_SECTION_SHUFFLE,
 	// Headings
 	menu_ids::PREVIOUS_HEADING,
 	menu_ids::NEXT_HEADING,
@@ -54,6 +68,7 @@ const DOCUMENT_DEPENDENT_IDS: &[i32] = &[
 	// Links
 	menu_ids::PREVIOUS_LINK,
 	menu_ids::NEXT_LINK,
+	menu_ids::PREVIEW_LINK,
 	// Images
 	menu_ids::PREVIOUS_IMAGE,
 	menu_ids::NEXT_IMAGE,
@@ -66,6 +81,11 @@ const DOCUMENT_DEPENDENT_IDS: &[i32] = &[
 	// Separators
 	menu_ids::PREVIOUS_SEPARATOR,
 	menu_ids::NEXT_SEPARATOR,
+	// Block quotes / Code blocks
+	menu_ids::PREVIOUS_BLOCK_QUOTE,
+	menu_ids::NEXT_BLOCK_QUOTE,
+	menu_ids::PREVIOUS_CODE_BLOCK,
+	menu_ids::NEXT_CODE_BLOCK,
 	// Lists
 	menu_ids::PREVIOUS_LIST,
 	menu_ids::NEXT_LIST,
@@ -82,13 +102,30 @@ const DOCUMENT_DEPENDENT_IDS: &[i32] = &[
 	menu_ids::REVEAL_FILE_IN_FOLDER,
 	menu_ids::OPEN_IN_WEB_VIEW,
 	menu_ids::VIEW_SOURCE,
+	menu_ids::LOOK_UP_WORD,
+	menu_ids::ENCODING,
+	menu_ids::AUTO_SCROLL,
 	// Import/Export
 	menu_ids::IMPORT_DOCUMENT_DATA,
 	menu_ids::EXPORT_DOCUMENT_DATA,
 	menu_ids::EXPORT_TO_PLAIN_TEXT,
+	menu_ids::EXPORT_NOTES_TO_MARKDOWN,
+	menu_ids::EXPORT_FOR_BRAILLE,
+	menu_ids::EXPORT_OUTLINE,
+	menu_ids::EXPORT_SECTION_TO_PLAIN_TEXT,
+	menu_ids::EXPORT_SECTION_TO_HTML,
+	menu_ids::COPY_CURRENT_SECTION,
 	// Bookmark tools
 	menu_ids::TOGGLE_BOOKMARK,
 	menu_ids::BOOKMARK_WITH_NOTE,
+	menu_ids::DOCUMENT_NOTES,
+	menu_ids::SEARCH_NOTES,
+	menu_ids::TEXT_DIRECTION_AUTOMATIC,
+	menu_ids::TEXT_DIRECTION_LEFT_TO_RIGHT,
+	menu_ids::TEXT_DIRECTION_RIGHT_TO_LEFT,
+	menu_ids::LINE_LAYOUT_AUTOMATIC,
+	menu_ids::LINE_LAYOUT_WRAP,
+	menu_ids::LINE_LAYOUT_PRESERVE,
 ];
 
 /// Enable or disable all document-dependent menu items.
@@ -99,6 +136,11 @@ pub fn update_menu_item_states(frame: &Frame, has_document: bool) {
 	for &id in DOCUMENT_DEPENDENT_IDS {
 		menu_bar.enable_item(id, has_document);
 	}
+	for id in (menu_ids::SET_QUICK_SLOT_BASE..=menu_ids::SET_QUICK_SLOT_MAX)
+		.chain(menu_ids::GO_TO_QUICK_SLOT_BASE..=menu_ids::GO_TO_QUICK_SLOT_MAX)
+	{
+		menu_bar.enable_item(id, has_document);
+	}
 }
 
 /// Enable or disable the "Reopen Last Closed" menu item.
@@ -132,7 +174,9 @@ pub fn build_menu(entries: &[MenuEntry]) -> Menu {
 	let mut builder = Menu::builder();
 	for entry in entries {
 		builder = match entry {
-			MenuEntry::Item(spec) => builder.append_item(spec.id, &spec.label, spec.help.as_deref().unwrap_or("")),
+			MenuEntry::Item(spec) => {
+				builder.append_item(spec.id, &shortcuts::bind(spec.id, &spec.label), spec.help.as_deref().unwrap_or(""))
+			}
 			MenuEntry::Separator => builder.append_separator(),
 		};
 	}
@@ -143,7 +187,7 @@ pub fn append_menu_entries(menu: &Menu, entries: &[MenuEntry]) {
 	for entry in entries {
 		match entry {
 			MenuEntry::Item(spec) => {
-				let _ = menu.append(spec.id, &spec.label, "", ItemKind::Normal);
+				let _ = menu.append(spec.id, &shortcuts::bind(spec.id, &spec.label), "", ItemKind::Normal);
 			}
 			MenuEntry::Separator => menu.append_separator(),
 		}
@@ -159,9 +203,19 @@ pub fn sections_entries() -> Vec<MenuEntry> {
 	let next_section_label = t("Next Section\t]");
 	// TRANSLATORS: Status bar help text for the "Next Section" menu item
 	let next_section_help = t("Go to next section");
+	// TRANSLATORS: Menu item label to jump to a randomly chosen section
+	let random_section_label = t("Random Section\tCtrl+Shift+R");
+	// TRANSLATORS: Status bar help text for the "Random Section" menu item
+	let random_section_help = t("Go to a section chosen at random");
+	// TRANSLATORS: Menu item label to turn shuffled section order for Next Section on or off
+	let shuffle_sections_label = t("S&huffle Sections");
+	// TRANSLATORS: Status bar help text for the "Shuffle Sections" menu item
+	let shuffle_sections_help = t("Make Next Section visit every section once in a random order");
 	vec![
 		item_with_help(menu_ids::PREVIOUS_SECTION, prev_section_label, prev_section_help),
 		item_with_help(menu_ids::NEXT_SECTION, next_section_label, next_section_help),
+		item_with_help(menu_ids::RANDOM_SECTION, random_section_label, random_section_help),
+		item_with_help(menu_ids::TOGGLE_SECTION_SHUFFLE, shuffle_sections_label, shuffle_sections_help),
 	]
 }
 
@@ -184,7 +238,13 @@ pub fn links_entries() -> Vec<MenuEntry> {
 	let prev_link_label = t("Previous Lin&k\tShift+K");
 	// TRANSLATORS: Menu item label to go to the next link
 	let next_link_label = t("Next Lin&k\tK");
-	vec![item(menu_ids::PREVIOUS_LINK, prev_link_label), item(menu_ids::NEXT_LINK, next_link_label)]
+	// TRANSLATORS: Menu item label to show where the link at the caret leads without following it
+	let preview_link_label = t("&Preview Link Target\tShift+Enter");
+	vec![
+		item(menu_ids::PREVIOUS_LINK, prev_link_label),
+		item(menu_ids::NEXT_LINK, next_link_label),
+		item(menu_ids::PREVIEW_LINK, preview_link_label),
+	]
 }
 
 pub fn images_entries() -> Vec<MenuEntry> {
@@ -219,6 +279,22 @@ pub fn separators_entries() -> Vec<MenuEntry> {
 	vec![item(menu_ids::PREVIOUS_SEPARATOR, prev_separator_label), item(menu_ids::NEXT_SEPARATOR, next_separator_label)]
 }
 
+pub fn block_quotes_entries() -> Vec<MenuEntry> {
+	// TRANSLATORS: Menu item label to go to the previous block quote
+	let prev_quote_label = t("Previous Block &Quote\tShift+Q");
+	// TRANSLATORS: Menu item label to go to the next block quote
+	let next_quote_label = t("Next Block &Quote\tQ");
+	vec![item(menu_ids::PREVIOUS_BLOCK_QUOTE, prev_quote_label), item(menu_ids::NEXT_BLOCK_QUOTE, next_quote_label)]
+}
+
+pub fn code_blocks_entries() -> Vec<MenuEntry> {
+	// TRANSLATORS: Menu item label to go to the previous code block
+	let prev_code_label = t("Previous C&ode Block\tShift+C");
+	// TRANSLATORS: Menu item label to go to the next code block
+	let next_code_label = t("Next C&ode Block\tC");
+	vec![item(menu_ids::PREVIOUS_CODE_BLOCK, prev_code_label), item(menu_ids::NEXT_CODE_BLOCK, next_code_label)]
+}
+
 pub fn lists_entries() -> Vec<MenuEntry> {
 	// TRANSLATORS: Menu item label to go to the previous list
 	let prev_list_label = t("Previous L&ist\tShift+L");
@@ -251,6 +327,32 @@ pub fn containers_entries() -> Vec<MenuEntry> {
 	]
 }
 
+/// Digit keys in keyboard order, 1 through 9 and then 0, each naming the quick slot it reaches.
+const QUICK_SLOT_DIGITS: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 0];
+
+/// Alt plus a digit jumps to a quick slot and Ctrl+Alt plus the digit saves it, leaving the bare
+/// digits to the heading levels.
+pub fn quick_slots_entries() -> Vec<MenuEntry> {
+	// TRANSLATORS: Menu item label to jump to the position saved in a quick slot; %d is the slot's digit key
+	let go_to_label = t("Go to Slot %d");
+	// TRANSLATORS: Menu item label to save the caret position in a quick slot; %d is the slot's digit key
+	let set_label = t("Set Slot %d");
+	let go_to = QUICK_SLOT_DIGITS.iter().map(|&digit| {
+		let label = format!("{}\tAlt+{digit}", go_to_label.replace("%d", &digit.to_string()));
+		item(menu_ids::GO_TO_QUICK_SLOT_BASE + i32::from(digit), label)
+	});
+	let set = QUICK_SLOT_DIGITS.iter().map(|&digit| {
+		let label = format!("{}\tCtrl+Alt+{digit}", set_label.replace("%d", &digit.to_string()));
+		item(menu_ids::SET_QUICK_SLOT_BASE + i32::from(digit), label)
+	});
+	go_to.chain(iter::once(MenuEntry::Separator)).chain(set).collect()
+}
+
+pub fn create_quick_slots_submenu() -> Menu {
+	let entries = quick_slots_entries();
+	build_menu(&entries)
+}
+
 pub fn headings_entries() -> Vec<MenuEntry> {
 	// TRANSLATORS: Menu item label to go to the next level-1 heading
 	let next_heading1_label = t("Next Heading Level 1\t1");
@@ -410,6 +512,26 @@ pub fn append_figures_items(menu: &Menu) {
 	append_menu_entries(menu, &entries);
 }
 
+pub fn create_block_quotes_submenu() -> Menu {
+	let entries = block_quotes_entries();
+	build_menu(&entries)
+}
+
+pub fn append_block_quotes_items(menu: &Menu) {
+	let entries = block_quotes_entries();
+	append_menu_entries(menu, &entries);
+}
+
+pub fn create_code_blocks_submenu() -> Menu {
+	let entries = code_blocks_entries();
+	build_menu(&entries)
+}
+
+pub fn append_code_blocks_items(menu: &Menu) {
+	let entries = code_blocks_entries();
+	append_menu_entries(menu, &entries);
+}
+
 pub fn create_tables_submenu() -> Menu {
 	let entries = tables_entries();
 	build_menu(&entries)
@@ -462,15 +584,34 @@ pub fn append_headings_items(menu: &Menu) {
 
 pub fn create_bookmarks_submenu() -> Menu {
 	let entries = bookmarks_entries();
-	build_menu(&entries)
+	let menu = build_menu(&entries);
+	append_bookmark_category_toggle(&menu);
+	menu
 }
 
 pub fn append_bookmarks_items(menu: &Menu) {
 	let entries = bookmarks_entries();
 	append_menu_entries(menu, &entries);
+	append_bookmark_category_toggle(menu);
+}
+
+/// Checked by [`create_menu_bar`] once the whole bar exists.
+fn append_bookmark_category_toggle(menu: &Menu) {
+	// TRANSLATORS: Checkable menu item label; when on, Next/Previous Bookmark only visit bookmarks in the category picked in the bookmarks filter
+	let label = t("Stay in Selected &Category");
+	// TRANSLATORS: Status bar help text for the "Stay in Selected Category" menu item
+	let help = t("Limit next and previous bookmark to the category chosen in the bookmarks filter");
+	menu.append_separator();
+	menu.append(
+		menu_ids::TOGGLE_BOOKMARK_CATEGORY_NAVIGATION,
+		&shortcuts::bind(menu_ids::TOGGLE_BOOKMARK_CATEGORY_NAVIGATION, &label),
+		&help,
+		ItemKind::Check,
+	);
 }
 
 pub fn create_menu_bar(config: &ConfigManager) -> MenuBar {
+	shortcuts::load(config);
 	let file_menu = create_file_menu(config);
 	let compact_go_menu = config.get_app_bool("compact_go_menu", true);
 	let go_menu = create_go_menu(compact_go_menu);
@@ -495,7 +636,10 @@ pub fn create_menu_bar(config: &ConfigManager) -> MenuBar {
 		let edit_label = t("&Edit");
 		builder = builder.append(create_edit_menu(), &edit_label);
 	}
-	builder.append(go_menu, &go_label).append(tools_menu, &tools_label).append(help_menu, &help_label).build()
+	let menu_bar =
+		builder.append(go_menu, &go_label).append(tools_menu, &tools_label).append(help_menu, &help_label).build();
+	menu_bar.check_item(menu_ids::TOGGLE_BOOKMARK_CATEGORY_NAVIGATION, is_bookmark_category_navigation_enabled(config));
+	menu_bar
 }
 
 pub fn create_file_menu(config: &ConfigManager) -> Menu {
@@ -503,6 +647,14 @@ pub fn create_file_menu(config: &ConfigManager) -> Menu {
 	let open_label = t("&Open...\tCtrl+O");
 	// TRANSLATORS: Status bar help text for the "Open" menu item
 	let open_help = t("Open a document");
+	// TRANSLATORS: Menu item label to open the text on the clipboard as a new document
+	let clipboard_label = t("New from &Clipboard\tCtrl+N");
+	// TRANSLATORS: Status bar help text for the "New from Clipboard" menu item
+	let clipboard_help = t("Read the text on the clipboard as a document");
+	// TRANSLATORS: Menu item label to open a document from an http or https address
+	let open_url_label = t("Open &URL...\tCtrl+Shift+O");
+	// TRANSLATORS: Status bar help text for the "Open URL" menu item
+	let open_url_help = t("Download a document from the web and open it");
 	// On macOS, Ctrl+ maps to Cmd+, so use Cmd+W / Cmd+Shift+W for close.
 	// On Windows/Linux, keep Ctrl+F4 / Ctrl+Shift+F4.
 	// TRANSLATORS: Menu item label to close the current document
@@ -519,10 +671,20 @@ pub fn create_file_menu(config: &ConfigManager) -> Menu {
 	// TRANSLATORS: Status bar help text for the "Reopen Last Closed" menu item
 	let reopen_help = t("Reopen the last closed document");
 	let file_menu = Menu::builder()
-		.append_item(menu_ids::OPEN, &open_label, &open_help)
-		.append_item(menu_ids::CLOSE, &close_label, &close_help)
-		.append_item(menu_ids::CLOSE_ALL, &close_all_label, &close_all_help)
-		.append_item(menu_ids::REOPEN_LAST_CLOSED, &reopen_label, &reopen_help)
+		.append_item(menu_ids::OPEN, &shortcuts::bind(menu_ids::OPEN, &open_label), &open_help)
+		.append_item(menu_ids::OPEN_URL, &shortcuts::bind(menu_ids::OPEN_URL, &open_url_label), &open_url_help)
+		.append_item(
+			menu_ids::NEW_FROM_CLIPBOARD,
+			&shortcuts::bind(menu_ids::NEW_FROM_CLIPBOARD, &clipboard_label),
+			&clipboard_help,
+		)
+		.append_item(menu_ids::CLOSE, &shortcuts::bind(menu_ids::CLOSE, &close_label), &close_help)
+		.append_item(menu_ids::CLOSE_ALL, &shortcuts::bind(menu_ids::CLOSE_ALL, &close_all_label), &close_all_help)
+		.append_item(
+			menu_ids::REOPEN_LAST_CLOSED,
+			&shortcuts::bind(menu_ids::REOPEN_LAST_CLOSED, &reopen_label),
+			&reopen_help,
+		)
 		.build();
 	let recent_menu = Menu::builder().build();
 	populate_recent_documents_menu(&recent_menu, config);
@@ -539,7 +701,12 @@ pub fn create_file_menu(config: &ConfigManager) -> Menu {
 		let exit_label = t("E&xit\tCtrl+Q");
 		// TRANSLATORS: Status bar help text for the "Exit" menu item
 		let exit_help = t("Exit the application");
-		let _ = file_menu.append(menu_ids::EXIT, &exit_label, &exit_help, ItemKind::Normal);
+		let _ = file_menu.append(
+			menu_ids::EXIT,
+			&shortcuts::bind(menu_ids::EXIT, &exit_label),
+			&exit_help,
+			ItemKind::Normal,
+		);
 	}
 	file_menu
 }
@@ -615,6 +782,28 @@ pub fn create_go_menu(compact: bool) -> Menu {
 	};
 	// TRANSLATORS: Status bar help text for the "Go to percent" menu item
 	let goto_percent_help = t("Go to a percentage of the document");
+	// TRANSLATORS: Menu item label to open the dialog for jumping to a numbered section such as 3.2.1
+	let goto_section_number_label = t("Go to Section N&umber...\tCtrl+Shift+H");
+	// TRANSLATORS: Status bar help text for the "Go to Section Number" menu item
+	let goto_section_number_help = t("Go to a heading by its outline number, such as 3.2.1");
+	// Cmd+Shift+L is Go to Percent on macOS, so the landmarks list uses the physical Control key there.
+	let landmarks_label =
+		// TRANSLATORS: Menu item label to open the list of landmarks such as the preface, bibliography and index
+		if cfg!(target_os = "macos") { t("Land&marks...\tRawCtrl+Shift+L") } else { t("Land&marks...\tCtrl+Shift+L") };
+	// TRANSLATORS: Status bar help text for the "Landmarks" menu item
+	let landmarks_help = t("Jump to a landmark such as the preface, bibliography, glossary or index");
+	// TRANSLATORS: Menu item label to announce the current heading, page, percentage and line
+	let speak_location_label = t("Speak &Location\tCtrl+/");
+	// TRANSLATORS: Status bar help text for the "Speak Location" menu item
+	let speak_location_help = t("Announce the current heading, page, percentage and line");
+	// TRANSLATORS: Menu item label to return to where reading stopped before the document was last closed
+	let last_read_label = t("Last &Read Position\tCtrl+J");
+	// TRANSLATORS: Status bar help text for the "Last Read Position" menu item
+	let last_read_help = t("Go back to where you stopped reading last time and select that sentence");
+	// TRANSLATORS: Menu item label to jump to the furthest point reached in the document, even after going back to re-read
+	let furthest_read_label = t("Furthest Rea&d Position\tCtrl+Shift+J");
+	// TRANSLATORS: Status bar help text for the "Furthest Read Position" menu item
+	let furthest_read_help = t("Go to the furthest point you have read to in this document and select that sentence");
 	// On macOS, Alt+Left/Right are reserved by AppKit for word-by-word caret
 	// movement in text fields; binding history navigation there would shadow
 	// them. Cmd+[ / Cmd+] are the system-standard back/forward (Safari, Finder,
@@ -628,16 +817,89 @@ pub fn create_go_menu(compact: bool) -> Menu {
 		if cfg!(target_os = "macos") { t("Go &Forward\tCtrl+]") } else { t("Go &Forward\tAlt+Right") };
 	// TRANSLATORS: Status bar help text for the "Go Forward" menu item
 	let go_forward_help = t("Go forward in history");
+	// Cmd+H hides the app on macOS, so the history browser uses the physical Control key there.
+	let history_label =
+		// TRANSLATORS: Menu item label to open the list of recently visited positions
+		if cfg!(target_os = "macos") { t("&History...\tRawCtrl+H") } else { t("&History...\tCtrl+H") };
+	// TRANSLATORS: Status bar help text for the "History" menu item
+	let history_help = t("Browse recently visited positions and jump to one");
+	// TRANSLATORS: Menu item label to go to the previous sentence
+	let prev_sentence_label = t("Previous Sen&tence\tCtrl+Alt+Left");
+	// TRANSLATORS: Status bar help text for the "Previous Sentence" menu item
+	let prev_sentence_help = t("Go to the previous sentence and read it");
+	// TRANSLATORS: Menu item label to go to the next sentence
+	let next_sentence_label = t("Next Sente&nce\tCtrl+Alt+Right");
+	// TRANSLATORS: Status bar help text for the "Next Sentence" menu item
+	let next_sentence_help = t("Go to the next sentence and read it");
+	// TRANSLATORS: Menu item label to go to the previous paragraph
+	let prev_paragraph_label = t("Previous P&aragraph\tCtrl+Alt+Up");
+	// TRANSLATORS: Status bar help text for the "Previous Paragraph" menu item
+	let prev_paragraph_help = t("Go to the previous paragraph and read it");
+	// TRANSLATORS: Menu item label to go to the next paragraph
+	let next_paragraph_label = t("Next Paragrap&h\tCtrl+Alt+Down");
+	// TRANSLATORS: Status bar help text for the "Next Paragraph" menu item
+	let next_paragraph_help = t("Go to the next paragraph and read it");
 	let menu = Menu::builder()
-		.append_item(menu_ids::FIND, &find_label, &find_help)
-		.append_item(menu_ids::FIND_NEXT, &find_next_label, &find_next_help)
-		.append_item(menu_ids::FIND_PREVIOUS, &find_prev_label, &find_prev_help)
+		.append_item(menu_ids::FIND, &shortcuts::bind(menu_ids::FIND, &find_label), &find_help)
+		.append_item(menu_ids::FIND_NEXT, &shortcuts::bind(menu_ids::FIND_NEXT, &find_next_label), &find_next_help)
+		.append_item(
+			menu_ids::FIND_PREVIOUS,
+			&shortcuts::bind(menu_ids::FIND_PREVIOUS, &find_prev_label),
+			&find_prev_help,
+		)
+		.append_separator()
+		.append_item(menu_ids::GO_TO_LINE, &shortcuts::bind(menu_ids::GO_TO_LINE, &goto_line_label), &goto_line_help)
+		.append_item(
+			menu_ids::GO_TO_PERCENT,
+			&shortcuts::bind(menu_ids::GO_TO_PERCENT, &goto_percent_label),
+			&goto_percent_help,
+		)
+		.append_item(
+			menu_ids::GO_TO_SECTION_NUMBER,
+			&shortcuts::bind(menu_ids::GO_TO_SECTION_NUMBER, &goto_section_number_label),
+			&goto_section_number_help,
+		)
+		.append_item(menu_ids::LANDMARKS, &shortcuts::bind(menu_ids::LANDMARKS, &landmarks_label), &landmarks_help)
+		.append_item(
+			menu_ids::SPEAK_LOCATION,
+			&shortcuts::bind(menu_ids::SPEAK_LOCATION, &speak_location_label),
+			&speak_location_help,
+		)
+		.append_item(
+			menu_ids::LAST_READ_POSITION,
+			&shortcuts::bind(menu_ids::LAST_READ_POSITION, &last_read_label),
+			&last_read_help,
+		)
+		.append_item(
+			menu_ids::FURTHEST_READ_POSITION,
+			&shortcuts::bind(menu_ids::FURTHEST_READ_POSITION, &furthest_read_label),
+			&furthest_read_help,
+		)
 		.append_separator()
-		.append_item(menu_ids::GO_TO_LINE, &goto_line_label, &goto_line_help)
-		.append_item(menu_ids::GO_TO_PERCENT, &goto_percent_label, &goto_percent_help)
+		.append_item(menu_ids::GO_BACK, &shortcuts::bind(menu_ids::GO_BACK, &go_back_label), &go_back_help)
+		.append_item(menu_ids::GO_FORWARD, &shortcuts::bind(menu_ids::GO_FORWARD, &go_forward_label), &go_forward_help)
+		.append_item(menu_ids::HISTORY_LIST, &shortcuts::bind(menu_ids::HISTORY_LIST, &history_label), &history_help)
 		.append_separator()
-		.append_item(menu_ids::GO_BACK, &go_back_label, &go_back_help)
-		.append_item(menu_ids::GO_FORWARD, &go_forward_label, &go_forward_help)
+		.append_item(
+			menu_ids::PREVIOUS_SENTENCE,
+			&shortcuts::bind(menu_ids::PREVIOUS_SENTENCE, &prev_sentence_label),
+			&prev_sentence_help,
+		)
+		.append_item(
+			menu_ids::NEXT_SENTENCE,
+			&shortcuts::bind(menu_ids::NEXT_SENTENCE, &next_sentence_label),
+			&next_sentence_help,
+		)
+		.append_item(
+			menu_ids::PREVIOUS_PARAGRAPH,
+			&shortcuts::bind(menu_ids::PREVIOUS_PARAGRAPH, &prev_paragraph_label),
+			&prev_paragraph_help,
+		)
+		.append_item(
+			menu_ids::NEXT_PARAGRAPH,
+			&shortcuts::bind(menu_ids::NEXT_PARAGRAPH, &next_paragraph_label),
+			&next_paragraph_help,
+		)
 		.append_separator()
 		.build();
 	if compact {
@@ -686,6 +948,16 @@ pub fn create_go_menu(compact: bool) -> Menu {
 		// TRANSLATORS: Status bar help text for the "Separators" submenu
 		let separators_help = t("Navigate by separators");
 		menu.append_submenu(create_separators_submenu(), &separators_label, &separators_help);
+		// TRANSLATORS: Submenu label containing block quote navigation commands
+		let block_quotes_label = t("Block &Quotes");
+		// TRANSLATORS: Status bar help text for the "Block Quotes" submenu
+		let block_quotes_help = t("Navigate by block quotes");
+		menu.append_submenu(create_block_quotes_submenu(), &block_quotes_label, &block_quotes_help);
+		// TRANSLATORS: Submenu label containing code block navigation commands
+		let code_blocks_label = t("C&ode Blocks");
+		// TRANSLATORS: Status bar help text for the "Code Blocks" submenu
+		let code_blocks_help = t("Navigate by code blocks");
+		menu.append_submenu(create_code_blocks_submenu(), &code_blocks_label, &code_blocks_help);
 		// TRANSLATORS: Submenu label containing list navigation commands
 		let lists_label = t("&Lists");
 		// TRANSLATORS: Status bar help text for the "Lists" submenu
@@ -715,10 +987,20 @@ pub fn create_go_menu(compact: bool) -> Menu {
 		menu.append_separator();
 		append_separators_items(&menu);
 		menu.append_separator();
+		append_block_quotes_items(&menu);
+		menu.append_separator();
+		append_code_blocks_items(&menu);
+		menu.append_separator();
 		append_lists_items(&menu);
 		menu.append_separator();
 		append_containers_items(&menu);
 	}
+	menu.append_separator();
+	// TRANSLATORS: Submenu label containing the commands that save and jump to quick slots
+	let quick_slots_label = t("Quic&k Slots");
+	// TRANSLATORS: Status bar help text for the "Quick Slots" submenu
+	let quick_slots_help = t("Save up to ten positions and jump back to them with Alt and a digit");
+	menu.append_submenu(create_quick_slots_submenu(), &quick_slots_label, &quick_slots_help);
 	menu
 }
 
@@ -743,13 +1025,107 @@ pub fn create_tools_menu(config: &ConfigManager) -> Menu {
 	let export_markdown_label = t("Export to &Markdown...");
 	// TRANSLATORS: Status bar help text for the "Export to Markdown" menu item
 	let export_markdown_help = t("Export document as Markdown");
+	// TRANSLATORS: Menu item label to export the section holding the caret as plain text
+	let export_section_text_label = t("Export Current &Section to Plain Text...");
+	// TRANSLATORS: Status bar help text for the "Export Current Section to Plain Text" menu item
+	let export_section_text_help = t("Export the section or chapter you're reading as plain text");
+	// TRANSLATORS: Menu item label to export the section holding the caret as HTML
+	let export_section_html_label = t("Export Current Section to H&TML...");
+	// TRANSLATORS: Status bar help text for the "Export Current Section to HTML" menu item
+	let export_section_html_help = t("Export the section or chapter you're reading as HTML");
+	// TRANSLATORS: Menu item label to copy the text of the section holding the caret to the clipboard
+	let copy_section_label = t("&Copy Current Section");
+	// TRANSLATORS: Status bar help text for the "Copy Current Section" menu item
+	let copy_section_help = t("Copy the text of the section or chapter you're reading to the clipboard");
+	// TRANSLATORS: Menu item label to export the document's bookmarks and notes as Markdown
+	let export_notes_label = t("Export &Notes to Markdown...");
+	// TRANSLATORS: Status bar help text for the "Export Notes to Markdown" menu item
+	let export_notes_help = t("Export bookmarks and notes as Markdown");
+	// TRANSLATORS: Menu item label to export the document as plain text laid out for braille embossing
+	let export_braille_label = t("Export for &Braille...");
+	// TRANSLATORS: Status bar help text for the "Export for Braille" menu item
+	let export_braille_help =
+		t("Export document as wrapped plain text with marked headings and page numbers for embossing");
+	// TRANSLATORS: Menu item label to export the document's table of contents or headings as an outline
+	let export_outline_label = t("Export &Outline...");
+	// TRANSLATORS: Status bar help text for the "Export Outline" menu item
+	let export_outline_help = t("Export the table of contents or headings as an indented outline");
+	// TRANSLATORS: Menu item label to save the positions, bookmarks, notes and settings of every document to one file
+	let backup_label = t("&Backup All Data...");
+	// TRANSLATORS: Status bar help text for the "Backup All Data" menu item
+	let backup_help = t("Save positions, bookmarks and notes for all documents, with your settings, to a backup file");
+	// TRANSLATORS: Menu item label to bring back the data saved with "Backup All Data"
+	let restore_label = t("&Restore All Data...");
+	// TRANSLATORS: Status bar help text for the "Restore All Data" menu item
+	let restore_help = t("Restore positions, bookmarks, notes and settings from a backup file");
 	let import_export_menu = Menu::builder()
-		.append_item(menu_ids::IMPORT_DOCUMENT_DATA, &import_label, &import_help)
-		.append_item(menu_ids::EXPORT_DOCUMENT_DATA, &export_label, &export_help)
+		.append_item(
+			menu_ids::IMPORT_DOCUMENT_DATA,
+			&shortcuts::bind(menu_ids::IMPORT_DOCUMENT_DATA, &import_label),
+			&import_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_DOCUMENT_DATA,
+			&shortcuts::bind(menu_ids::EXPORT_DOCUMENT_DATA, &export_label),
+			&export_help,
+		)
+		.append_separator()
+		.append_item(
+			menu_ids::EXPORT_TO_PLAIN_TEXT,
+			&shortcuts::bind(menu_ids::EXPORT_TO_PLAIN_TEXT, &export_text_label),
+			&export_text_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_TO_HTML,
+			&shortcuts::bind(menu_ids::EXPORT_TO_HTML, &export_html_label),
+			&export_html_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_TO_MARKDOWN,
+			&shortcuts::bind(menu_ids::EXPORT_TO_MARKDOWN, &export_markdown_label),
+			&export_markdown_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_SECTION_TO_PLAIN_TEXT,
+			&shortcuts::bind(menu_ids::EXPORT_SECTION_TO_PLAIN_TEXT, &export_section_text_label),
+			&export_section_text_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_SECTION_TO_HTML,
+			&shortcuts::bind(menu_ids::EXPORT_SECTION_TO_HTML, &export_section_html_label),
+			&export_section_html_help,
+		)
+		.append_item(
+			menu_ids::COPY_CURRENT_SECTION,
+			&shortcuts::bind(menu_ids::COPY_CURRENT_SECTION, &copy_section_label),
+			&copy_section_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_FOR_BRAILLE,
+			&shortcuts::bind(menu_ids::EXPORT_FOR_BRAILLE, &export_braille_label),
+			&export_braille_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_NOTES_TO_MARKDOWN,
+			&shortcuts::bind(menu_ids::EXPORT_NOTES_TO_MARKDOWN, &export_notes_label),
+			&export_notes_help,
+		)
+		.append_item(
+			menu_ids::EXPORT_OUTLINE,
+			&shortcuts::bind(menu_ids::EXPORT_OUTLINE, &export_outline_label),
+			&export_outline_help,
+		)
 		.append_separator()
-		.append_item(menu_ids::EXPORT_TO_PLAIN_TEXT, &export_text_label, &export_text_help)
-		.append_item(menu_ids::EXPORT_TO_HTML, &export_html_label, &export_html_help)
-		.append_item(menu_ids::EXPORT_TO_MARKDOWN, &export_markdown_label, &export_markdown_help)
+		.append_item(
+			menu_ids::BACKUP_ALL_DATA,
+			&shortcuts::bind(menu_ids::BACKUP_ALL_DATA, &backup_label),
+			&backup_help,
+		)
+		.append_item(
+			menu_ids::RESTORE_ALL_DATA,
+			&shortcuts::bind(menu_ids::RESTORE_ALL_DATA, &restore_label),
+			&restore_help,
+		)
 		.build();
 	// On macOS, Cmd+W is close, so use Ctrl+W (raw Control key) for word count.
 	let word_count_label =
@@ -781,16 +1157,51 @@ pub fn create_tools_menu(config: &ConfigManager) -> Menu {
 	let view_source_label = t("View &Source\tCtrl+U");
 	// TRANSLATORS: Status bar help text for the "View Source" menu item
 	let view_source_help = t("Open the document source in a new tab");
+	let look_up_label = if cfg!(target_os = "macos") {
+		// TRANSLATORS: Menu item label to look up the selected word, or the word at the cursor, in a dictionary
+		t("&Look Up Word\tRawCtrl+L")
+	} else {
+		// TRANSLATORS: Menu item label to look up the selected word, or the word at the cursor, in a dictionary
+		t("&Look Up Word\tCtrl+L")
+	};
+	// TRANSLATORS: Status bar help text for the "Look Up Word" menu item
+	let look_up_help = t("Look up the selected word in a dictionary");
+	// TRANSLATORS: Menu item label to reopen a plain text document in a chosen character encoding
+	let encoding_label = t("&Encoding...");
+	// TRANSLATORS: Status bar help text for the "Encoding" menu item
+	let encoding_help = t("Reopen the text document in another character encoding");
 	let menu = Menu::builder()
-		.append_item(menu_ids::WORD_COUNT, &word_count_label, &word_count_help)
-		.append_item(menu_ids::DOCUMENT_INFO, &doc_info_label, &doc_info_help)
+		.append_item(menu_ids::WORD_COUNT, &shortcuts::bind(menu_ids::WORD_COUNT, &word_count_label), &word_count_help)
+		.append_item(
+			menu_ids::DOCUMENT_INFO,
+			&shortcuts::bind(menu_ids::DOCUMENT_INFO, &doc_info_label),
+			&doc_info_help,
+		)
 		.append_separator()
-		.append_item(menu_ids::TABLE_OF_CONTENTS, &toc_label, &toc_help)
-		.append_item(menu_ids::ELEMENTS_LIST, &elements_label, &elements_help)
+		.append_item(menu_ids::TABLE_OF_CONTENTS, &shortcuts::bind(menu_ids::TABLE_OF_CONTENTS, &toc_label), &toc_help)
+		.append_item(
+			menu_ids::ELEMENTS_LIST,
+			&shortcuts::bind(menu_ids::ELEMENTS_LIST, &elements_label),
+			&elements_help,
+		)
 		.append_separator()
-		.append_item(menu_ids::REVEAL_FILE_IN_FOLDER, &open_folder_label, &open_folder_help)
-		.append_item(menu_ids::OPEN_IN_WEB_VIEW, &web_view_label, &web_view_help)
-		.append_item(menu_ids::VIEW_SOURCE, &view_source_label, &view_source_help)
+		.append_item(
+			menu_ids::REVEAL_FILE_IN_FOLDER,
+			&shortcuts::bind(menu_ids::REVEAL_FILE_IN_FOLDER, &open_folder_label),
+			&open_folder_help,
+		)
+		.append_item(
+			menu_ids::OPEN_IN_WEB_VIEW,
+			&shortcuts::bind(menu_ids::OPEN_IN_WEB_VIEW, &web_view_label),
+			&web_view_help,
+		)
+		.append_item(
+			menu_ids::VIEW_SOURCE,
+			&shortcuts::bind(menu_ids::VIEW_SOURCE, &view_source_label),
+			&view_source_help,
+		)
+		.append_item(menu_ids::LOOK_UP_WORD, &shortcuts::bind(menu_ids::LOOK_UP_WORD, &look_up_label), &look_up_help)
+		.append_item(menu_ids::ENCODING, &shortcuts::bind(menu_ids::ENCODING, &encoding_label), &encoding_help)
 		.append_separator()
 		.build();
 	// TRANSLATORS: Submenu label containing import/export commands
@@ -803,15 +1214,86 @@ pub fn create_tools_menu(config: &ConfigManager) -> Menu {
 	let toggle_bookmark_label = t("Toggle &Bookmark\tCtrl+Shift+B");
 	// TRANSLATORS: Menu item label to add a bookmark with an attached note
 	let bookmark_note_label = t("Bookmark with &Note\tCtrl+Shift+N");
-	menu.append(menu_ids::TOGGLE_BOOKMARK, &toggle_bookmark_label, "", ItemKind::Normal);
-	menu.append(menu_ids::BOOKMARK_WITH_NOTE, &bookmark_note_label, "", ItemKind::Normal);
+	menu.append(
+		menu_ids::TOGGLE_BOOKMARK,
+		&shortcuts::bind(menu_ids::TOGGLE_BOOKMARK, &toggle_bookmark_label),
+		"",
+		ItemKind::Normal,
+	);
+	menu.append(
+		menu_ids::BOOKMARK_WITH_NOTE,
+		&shortcuts::bind(menu_ids::BOOKMARK_WITH_NOTE, &bookmark_note_label),
+		"",
+		ItemKind::Normal,
+	);
+	// TRANSLATORS: Menu item label to edit the free-form notes kept for the whole document
+	let document_notes_label = t("Document No&tes...\tCtrl+Alt+N");
+	// TRANSLATORS: Status bar help text for the "Document Notes" menu item
+	let document_notes_help = t("Edit notes on the whole document");
+	menu.append(
+		menu_ids::DOCUMENT_NOTES,
+		&shortcuts::bind(menu_ids::DOCUMENT_NOTES, &document_notes_label),
+		&document_notes_help,
+		ItemKind::Normal,
+	);
+	// TRANSLATORS: Menu item label to search the bookmark notes and Document Notes of the current document
+	let search_notes_label = t("Searc&h Notes...");
+	// TRANSLATORS: Status bar help text for the "Search Notes" menu item
+	let search_notes_help = t("Find text in this document's bookmark notes and Document Notes");
+	menu.append(
+		menu_ids::SEARCH_NOTES,
+		&shortcuts::bind(menu_ids::SEARCH_NOTES, &search_notes_label),
+		&search_notes_help,
+		ItemKind::Normal,
+	);
 	menu.append_separator();
 	// TRANSLATORS: Checkable menu item label to toggle word wrap
 	let word_wrap_label = t("Word w&rap\tCtrl+Alt+W");
 	// TRANSLATORS: Status bar help text for the "Word wrap" menu item
 	let word_wrap_help = t("Toggle word wrap");
-	menu.append(menu_ids::TOGGLE_WORD_WRAP, &word_wrap_label, &word_wrap_help, ItemKind::Check);
+	menu.append(
+		menu_ids::TOGGLE_WORD_WRAP,
+		&shortcuts::bind(menu_ids::TOGGLE_WORD_WRAP, &word_wrap_label),
+		&word_wrap_help,
+		ItemKind::Check,
+	);
 	menu.check_item(menu_ids::TOGGLE_WORD_WRAP, config.get_app_bool("word_wrap", false));
+	// TRANSLATORS: Checkable menu item label to show documents as light text on a dark background, whatever the system appearance
+	let dark_view_label = t("&Dark Reading View\tCtrl+Shift+D");
+	// TRANSLATORS: Status bar help text for the "Dark Reading View" menu item
+	let dark_view_help = t("Show documents in light text on a dark background");
+	menu.append(
+		menu_ids::TOGGLE_DARK_READING_VIEW,
+		&shortcuts::bind(menu_ids::TOGGLE_DARK_READING_VIEW, &dark_view_label),
+		&dark_view_help,
+		ItemKind::Check,
+	);
+	menu.check_item(menu_ids::TOGGLE_DARK_READING_VIEW, config.get_app_bool("dark_reading_view", false));
+	// TRANSLATORS: Submenu label for choosing whether the document's text runs left to right or right to left
+	let direction_label = t("Text D&irection");
+	// TRANSLATORS: Status bar help text for the "Text Direction" submenu
+	let direction_help = t("Choose the direction of the document's text, for Hebrew, Arabic and mixed works");
+	menu.append_submenu(create_text_direction_menu(), &direction_label, &direction_help);
+	// TRANSLATORS: Submenu label for choosing whether the document's lines are wrapped or kept as written
+	let layout_label = t("Line La&yout");
+	// TRANSLATORS: Status bar help text for the "Line Layout" submenu
+	let layout_help = t("Choose whether the document's lines wrap like prose or keep their breaks like poetry");
+	menu.append_submenu(create_line_layout_menu(), &layout_label, &layout_help);
+	// TRANSLATORS: Submenu label listing saved reading profiles (bundles of reading settings)
+	let profiles_label = t("Reading &Profiles");
+	// TRANSLATORS: Status bar help text for the "Reading Profiles" submenu
+	let profiles_help = t("Switch between saved bundles of reading settings");
+	menu.append_submenu(create_reading_profiles_menu(config), &profiles_label, &profiles_help);
+	// TRANSLATORS: Menu item label to edit the replacements applied to text before it's spoken, such as "et al." read as "and others"
+	let pronunciation_label = t("Pron&unciation Rules...");
+	// TRANSLATORS: Status bar help text for the "Pronunciation Rules" menu item
+	let pronunciation_help = t("Edit how abbreviations and symbols are spoken");
+	menu.append(
+		menu_ids::PRONUNCIATION_RULES,
+		&shortcuts::bind(menu_ids::PRONUNCIATION_RULES, &pronunciation_label),
+		&pronunciation_help,
+		ItemKind::Normal,
+	);
 	menu.append_separator();
 	// TRANSLATORS: Menu item label to open the application options/preferences dialog
 	let options_label = t("&Options\tCtrl+,");
@@ -819,8 +1301,90 @@ pub fn create_tools_menu(config: &ConfigManager) -> Menu {
 	let sleep_label = t("&Sleep Timer...\tCtrl+Shift+S");
 	// On macOS, use wxID_PREFERENCES so wxWidgets puts it in the app menu.
 	let options_id = if cfg!(target_os = "macos") { menu_ids::PREFERENCES } else { menu_ids::OPTIONS };
-	menu.append(options_id, &options_label, "", ItemKind::Normal);
-	menu.append(menu_ids::SLEEP_TIMER, &sleep_label, "", ItemKind::Normal);
+	menu.append(options_id, &shortcuts::bind(options_id, &options_label), "", ItemKind::Normal);
+	menu.append(menu_ids::SLEEP_TIMER, &shortcuts::bind(menu_ids::SLEEP_TIMER, &sleep_label), "", ItemKind::Normal);
+	// TRANSLATORS: Menu item label to start or stop scrolling the document automatically, one line at a time
+	let auto_scroll_label = t("&Auto-Scroll\tCtrl+Shift+A");
+	menu.append(
+		menu_ids::AUTO_SCROLL,
+		&shortcuts::bind(menu_ids::AUTO_SCROLL, &auto_scroll_label),
+		"",
+		ItemKind::Normal,
+	);
+	menu
+}
+
+fn create_text_direction_menu() -> Menu {
+	// TRANSLATORS: Menu item label to let the text direction follow what is detected from the document
+	let automatic_label = t("&Automatic");
+	// TRANSLATORS: Status bar help text for the "Automatic" text direction menu item
+	let automatic_help = t("Use the direction detected from the document's text");
+	// TRANSLATORS: Menu item label to show the document's text left to right
+	let ltr_label = t("&Left to Right");
+	// TRANSLATORS: Status bar help text for the "Left to Right" text direction menu item
+	let ltr_help = t("Show this document's text left to right");
+	// TRANSLATORS: Menu item label to show the document's text right to left, as for Hebrew or Arabic
+	let rtl_label = t("&Right to Left");
+	// TRANSLATORS: Status bar help text for the "Right to Left" text direction menu item
+	let rtl_help = t("Show this document's text right to left");
+	build_menu(&[
+		item_with_help(menu_ids::TEXT_DIRECTION_AUTOMATIC, automatic_label, automatic_help),
+		item_with_help(menu_ids::TEXT_DIRECTION_LEFT_TO_RIGHT, ltr_label, ltr_help),
+		item_with_help(menu_ids::TEXT_DIRECTION_RIGHT_TO_LEFT, rtl_label, rtl_help),
+	])
+}
+
+fn create_line_layout_menu() -> Menu {
+	// TRANSLATORS: Menu item label to let the line layout follow what is detected from the document
+	let automatic_label = t("&Automatic");
+	// TRANSLATORS: Status bar help text for the "Automatic" line layout menu item
+	let automatic_help = t("Keep the line breaks of documents made of short lines, such as poetry or code");
+	// TRANSLATORS: Menu item label to wrap the document's lines to the window like prose
+	let wrap_label = t("&Wrap Long Lines");
+	// TRANSLATORS: Status bar help text for the "Wrap Long Lines" line layout menu item
+	let wrap_help = t("Wrap this document's lines to the window when word wrap is on");
+	// TRANSLATORS: Menu item label to keep the document's own line breaks, as for poetry or code
+	let preserve_label = t("&Preserve Original Lines");
+	// TRANSLATORS: Status bar help text for the "Preserve Original Lines" line layout menu item
+	let preserve_help = t("Never wrap this document's lines, keeping each one as it was written");
+	build_menu(&[
+		item_with_help(menu_ids::LINE_LAYOUT_AUTOMATIC, automatic_label, automatic_help),
+		item_with_help(menu_ids::LINE_LAYOUT_WRAP, wrap_label, wrap_help),
+		item_with_help(menu_ids::LINE_LAYOUT_PRESERVE, preserve_label, preserve_help),
+	])
+}
+
+fn create_reading_profiles_menu(config: &ConfigManager) -> Menu {
+	let menu = Menu::builder().build();
+	let active = config.active_reading_profile();
+	let max_profiles = usize::try_from(menu_ids::READING_PROFILE_MAX - menu_ids::READING_PROFILE_BASE + 1).unwrap_or(0);
+	let names = config.reading_profile_names();
+	for (index, name) in names.iter().take(max_profiles).enumerate() {
+		let id = menu_ids::READING_PROFILE_BASE + i32::try_from(index).unwrap_or(0);
+		menu.append(id, &name.replace('&', "&&"), "", ItemKind::Check);
+		menu.check_item(id, *name == active);
+	}
+	if !names.is_empty() {
+		menu.append_separator();
+	}
+	// TRANSLATORS: Menu item label to save the current reading settings as a named profile
+	let save_label = t("&Save Current Settings as Profile...");
+	// TRANSLATORS: Status bar help text for the "Save Current Settings as Profile" menu item
+	let save_help = t("Save word wrap, text size, navigation wrap, context announcements and typography as a profile");
+	menu.append(
+		menu_ids::SAVE_READING_PROFILE,
+		&shortcuts::bind(menu_ids::SAVE_READING_PROFILE, &save_label),
+		&save_help,
+		ItemKind::Normal,
+	);
+	// TRANSLATORS: Menu item label to delete the active reading profile
+	let delete_label = t("&Delete Active Profile...");
+	menu.append(
+		menu_ids::DELETE_READING_PROFILE,
+		&shortcuts::bind(menu_ids::DELETE_READING_PROFILE, &delete_label),
+		"",
+		ItemKind::Normal,
+	);
 	menu
 }
 
@@ -846,13 +1410,25 @@ pub fn create_help_menu() -> Menu {
 	// TRANSLATORS: Status bar help text for the "Donate" menu item
 	let donate_help = t("Support Paperback development");
 	Menu::builder()
-		.append_item(menu_ids::ABOUT, &about_label, &about_help)
-		.append_item(menu_ids::VIEW_HELP_BROWSER, &help_browser_label, &help_browser_help)
-		.append_item(menu_ids::VIEW_HELP_PAPERBACK, &help_paperback_label, &help_paperback_help)
+		.append_item(menu_ids::ABOUT, &shortcuts::bind(menu_ids::ABOUT, &about_label), &about_help)
+		.append_item(
+			menu_ids::VIEW_HELP_BROWSER,
+			&shortcuts::bind(menu_ids::VIEW_HELP_BROWSER, &help_browser_label),
+			&help_browser_help,
+		)
+		.append_item(
+			menu_ids::VIEW_HELP_PAPERBACK,
+			&shortcuts::bind(menu_ids::VIEW_HELP_PAPERBACK, &help_paperback_label),
+			&help_paperback_help,
+		)
 		.append_separator()
-		.append_item(menu_ids::CHECK_FOR_UPDATES, &updates_label, &updates_help)
+		.append_item(
+			menu_ids::CHECK_FOR_UPDATES,
+			&shortcuts::bind(menu_ids::CHECK_FOR_UPDATES, &updates_label),
+			&updates_help,
+		)
 		.append_separator()
-		.append_item(menu_ids::DONATE, &donate_label, &donate_help)
+		.append_item(menu_ids::DONATE, &shortcuts::bind(menu_ids::DONATE, &donate_label), &donate_help)
 		.build()
 }
 
@@ -878,7 +1454,12 @@ pub fn populate_recent_documents_menu(menu: &Menu, config: &ConfigManager) {
 	menu.append_separator();
 	// TRANSLATORS: Menu item label to open the full recent-documents list dialog
 	let show_all_label = t("Show All...\tCtrl+R");
-	let _ = menu.append(menu_ids::SHOW_ALL_DOCUMENTS, &show_all_label, "", ItemKind::Normal);
+	let _ = menu.append(
+		menu_ids::SHOW_ALL_DOCUMENTS,
+		&shortcuts::bind(menu_ids::SHOW_ALL_DOCUMENTS, &show_all_label),
+		"",
+		ItemKind::Normal,
+	);
 }
 
 pub fn recent_documents_for_menu(config: &ConfigManager) -> Vec<String> {
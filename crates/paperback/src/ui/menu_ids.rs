@@ -33,7 +33,7 @@ mod edit_ids {
 const BASE: i32 = 5000;
 
 // File menu (BASE + 0..99)
-seq_ids!(BASE => OPEN, CLOSE, CLOSE_ALL, SHOW_ALL_DOCUMENTS, REOPEN_LAST_CLOSED);
+seq_ids!(BASE => OPEN, CLOSE, CLOSE_ALL, SHOW_ALL_DOCUMENTS, REOPEN_LAST_CLOSED, NEW_FROM_CLIPBOARD, OPEN_URL);
 
 // Recent documents - reserved range (BASE + 100..199)
 pub const RECENT_DOCUMENT_BASE: i32 = BASE + 100;
@@ -43,13 +43,13 @@ pub const RECENT_DOCUMENT_MAX: i32 = BASE + 199;
 seq_ids!(BASE + 200 => FIND, FIND_NEXT, FIND_PREVIOUS);
 
 // Go menu: Go to (BASE + 210..219)
-seq_ids!(BASE + 210 => GO_TO_LINE, GO_TO_PERCENT, GO_TO_PAGE);
+seq_ids!(BASE + 210 => GO_TO_LINE, GO_TO_PERCENT, GO_TO_PAGE, GO_TO_SECTION_NUMBER, LANDMARKS);
 
 // Go menu: History (BASE + 220..229)
-seq_ids!(BASE + 220 => GO_BACK, GO_FORWARD);
+seq_ids!(BASE + 220 => GO_BACK, GO_FORWARD, HISTORY_LIST);
 
 // Go menu: Section navigation (BASE + 230..239)
-seq_ids!(BASE + 230 => PREVIOUS_SECTION, NEXT_SECTION);
+seq_ids!(BASE + 230 => PREVIOUS_SECTION, NEXT_SECTION, RANDOM_SECTION, TOGGLE_SECTION_SHUFFLE);
 
 // Go menu: Heading navigation (BASE + 240..269)
 seq_ids!(BASE + 240 => PREVIOUS_HEADING, NEXT_HEADING);
@@ -70,11 +70,11 @@ seq_ids!(BASE + 280 =>
 	PREVIOUS_BOOKMARK, NEXT_BOOKMARK,
 	PREVIOUS_NOTE, NEXT_NOTE,
 	JUMP_TO_ALL_BOOKMARKS, JUMP_TO_BOOKMARKS_ONLY, JUMP_TO_NOTES_ONLY,
-	VIEW_NOTE_TEXT,
+	VIEW_NOTE_TEXT, TOGGLE_BOOKMARK_CATEGORY_NAVIGATION,
 );
 
-// Go menu: Link navigation (BASE + 290..299)
-seq_ids!(BASE + 290 => PREVIOUS_LINK, NEXT_LINK);
+// Go menu: Link navigation (BASE + 290..294)
+seq_ids!(BASE + 290 => PREVIOUS_LINK, NEXT_LINK, PREVIEW_LINK);
 
 // Go menu: Image navigation (BASE + 295..299)
 seq_ids!(BASE + 295 => PREVIOUS_IMAGE, NEXT_IMAGE);
@@ -87,23 +87,58 @@ seq_ids!(BASE + 305 => PREVIOUS_TABLE, NEXT_TABLE, PREVIOUS_SEPARATOR, NEXT_SEPA
 seq_ids!(BASE + 310 => PREVIOUS_LIST, NEXT_LIST, PREVIOUS_LIST_ITEM, NEXT_LIST_ITEM);
 seq_ids!(BASE + 314 => CONTAINER_START, CONTAINER_END);
 
+// Go menu: Sentence and paragraph navigation (BASE + 320..329)
+seq_ids!(BASE + 320 => PREVIOUS_SENTENCE, NEXT_SENTENCE, PREVIOUS_PARAGRAPH, NEXT_PARAGRAPH);
+
+// Go menu: Location (BASE + 330..339)
+seq_ids!(BASE + 330 => SPEAK_LOCATION, LAST_READ_POSITION, FURTHEST_READ_POSITION);
+
+// Go menu: Block quote and code block navigation (BASE + 340..349)
+seq_ids!(BASE + 340 => PREVIOUS_BLOCK_QUOTE, NEXT_BLOCK_QUOTE, PREVIOUS_CODE_BLOCK, NEXT_CODE_BLOCK);
+
+// Go menu: Quick slots, one ID per digit key (BASE + 350..369)
+pub const SET_QUICK_SLOT_BASE: i32 = BASE + 350;
+pub const SET_QUICK_SLOT_MAX: i32 = BASE + 359;
+pub const GO_TO_QUICK_SLOT_BASE: i32 = BASE + 360;
+pub const GO_TO_QUICK_SLOT_MAX: i32 = BASE + 369;
+
 // Tools menu: Document info (BASE + 400..409)
 seq_ids!(BASE + 400 =>
 	WORD_COUNT, DOCUMENT_INFO, TABLE_OF_CONTENTS, ELEMENTS_LIST,
-	REVEAL_FILE_IN_FOLDER, OPEN_IN_WEB_VIEW, VIEW_SOURCE,
+	REVEAL_FILE_IN_FOLDER, OPEN_IN_WEB_VIEW, VIEW_SOURCE, LOOK_UP_WORD, ENCODING,
 );
 
 // Tools menu: Import/Export (BASE + 410..419)
-seq_ids!(BASE + 410 => IMPORT_DOCUMENT_DATA, EXPORT_DOCUMENT_DATA, EXPORT_TO_PLAIN_TEXT, EXPORT_TO_HTML, EXPORT_TO_MARKDOWN);
+seq_ids!(BASE + 410 =>
+	IMPORT_DOCUMENT_DATA, EXPORT_DOCUMENT_DATA, EXPORT_TO_PLAIN_TEXT, EXPORT_TO_HTML, EXPORT_TO_MARKDOWN,
+	EXPORT_NOTES_TO_MARKDOWN, EXPORT_FOR_BRAILLE, EXPORT_OUTLINE, BACKUP_ALL_DATA, RESTORE_ALL_DATA,
+);
 
 // Tools menu: Bookmarks (BASE + 420..429)
-seq_ids!(BASE + 420 => TOGGLE_BOOKMARK, BOOKMARK_WITH_NOTE);
+seq_ids!(BASE + 420 => TOGGLE_BOOKMARK, BOOKMARK_WITH_NOTE, DOCUMENT_NOTES, SEARCH_NOTES);
 
 // Tools menu: Settings (BASE + 430..439)
-seq_ids!(BASE + 430 => OPTIONS, SLEEP_TIMER);
+seq_ids!(BASE + 430 => OPTIONS, SLEEP_TIMER, AUTO_SCROLL, PRONUNCIATION_RULES);
 
 // Tools menu: View toggles (BASE + 440..449)
-seq_ids!(BASE + 440 => TOGGLE_WORD_WRAP);
+seq_ids!(BASE + 440 =>
+	TOGGLE_WORD_WRAP,
+	TOGGLE_DARK_READING_VIEW,
+	TEXT_DIRECTION_AUTOMATIC,
+	TEXT_DIRECTION_LEFT_TO_RIGHT,
+	TEXT_DIRECTION_RIGHT_TO_LEFT,
+	LINE_LAYOUT_AUTOMATIC,
+	LINE_LAYOUT_WRAP,
+	LINE_LAYOUT_PRESERVE,
+);
+
+// Tools menu: Reading profiles (BASE + 450..489)
+seq_ids!(BASE + 450 => SAVE_READING_PROFILE, DELETE_READING_PROFILE);
+pub const READING_PROFILE_BASE: i32 = BASE + 460;
+pub const READING_PROFILE_MAX: i32 = BASE + 489;
+
+// Tools menu: Import/Export for the current section (BASE + 490..499)
+seq_ids!(BASE + 490 => EXPORT_SECTION_TO_PLAIN_TEXT, EXPORT_SECTION_TO_HTML, COPY_CURRENT_SECTION);
 
 // Help menu (BASE + 500..599)
 seq_ids!(BASE + 500 => VIEW_HELP_BROWSER, VIEW_HELP_PAPERBACK, CHECK_FOR_UPDATES, DONATE);
@@ -1,377 +1,458 @@
-use std::{rc::Rc, sync::Mutex};
+use std::{path::Path, rc::Rc, sync::Mutex};
 
-use paperback_core::{config::ConfigManager, reader_core, session::NavigationResult, types::BookmarkFilterType};
+use paperback_core::{
+	config::ConfigManager,
+	document::{LineLayout, TextDirection},
+	export::ExportFormat,
+	reader_core::{self, NoteSource, SearchOptions, lookup},
+	session::DocumentSession,
+	types::BookmarkFilterType,
+};
 use patois::t;
-use wxdragon::prelude::*;
-
-use super::{dialogs, document_manager::DocumentManager};
-
-#[derive(Clone, Copy)]
-pub enum MarkerNavTarget {
-	Section,
-	Page,
-	Heading(i32),
-	Link,
-	Table,
-	Separator,
-	List,
-	ListItem,
-	Image,
-	Figure,
-}
-
-enum NavFoundFormat {
-	TextOnly,
-	TextWithLevel,
-	PageFormat,
-	LinkFormat,
-	ImageFormat,
-}
-
-struct NavAnnouncements {
-	not_supported: String,
-	not_found_next: String,
-	not_found_prev: String,
-	format: NavFoundFormat,
-}
-
-fn nav_announcements(target: MarkerNavTarget, level_filter: i32) -> NavAnnouncements {
-	match target {
-		MarkerNavTarget::Section => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no sections to navigate
-			not_supported: t("No sections."),
-			// TRANSLATORS: Announced when there is no next section from the current position
-			not_found_next: t("No next section"),
-			// TRANSLATORS: Announced when there is no previous section from the current position
-			not_found_prev: t("No previous section"),
-			format: NavFoundFormat::TextOnly,
-		},
-		MarkerNavTarget::Heading(level) => {
-			if level_filter > 0 {
-				// TRANSLATORS: Announced when the document has no headings at the given level; %d is the heading level number
-				let no_headings = t("No headings at level %d.");
-				// TRANSLATORS: Announced when there is no next heading at the given level; %d is the heading level number
-				let no_next = t("No next heading at level %d.");
-				// TRANSLATORS: Announced when there is no previous heading at the given level; %d is the heading level number
-				let no_prev = t("No previous heading at level %d.");
-				NavAnnouncements {
-					not_supported: no_headings.replacen("%d", &level.to_string(), 1),
-					not_found_next: no_next.replacen("%d", &level.to_string(), 1),
-					not_found_prev: no_prev.replacen("%d", &level.to_string(), 1),
-					format: NavFoundFormat::TextWithLevel,
-				}
-			} else {
-				NavAnnouncements {
-					// TRANSLATORS: Announced when the document has no headings at all (no level filter applied)
-					not_supported: t("No headings."),
-					// TRANSLATORS: Announced when there is no next heading (no level filter applied)
-					not_found_next: t("No next heading."),
-					// TRANSLATORS: Announced when there is no previous heading (no level filter applied)
-					not_found_prev: t("No previous heading."),
-					format: NavFoundFormat::TextWithLevel,
-				}
-			}
-		}
-		MarkerNavTarget::Page => NavAnnouncements {
-			// TRANSLATORS: Announced when "Go to Page" is used on a document that has no page numbers
-			not_supported: t("No pages."),
-			// TRANSLATORS: Announced when there is no next page from the current position
-			not_found_next: t("No next page."),
-			// TRANSLATORS: Announced when there is no previous page from the current position
-			not_found_prev: t("No previous page."),
-			format: NavFoundFormat::PageFormat,
-		},
-		MarkerNavTarget::Link => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no links to navigate
-			not_supported: t("No links."),
-			// TRANSLATORS: Announced when there is no next link from the current position
-			not_found_next: t("No next link."),
-			// TRANSLATORS: Announced when there is no previous link from the current position
-			not_found_prev: t("No previous link."),
-			format: NavFoundFormat::LinkFormat,
-		},
-		MarkerNavTarget::List => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no lists to navigate
-			not_supported: t("No lists."),
-			// TRANSLATORS: Announced when there is no next list from the current position
-			not_found_next: t("No next list."),
-			// TRANSLATORS: Announced when there is no previous list from the current position
-			not_found_prev: t("No previous list."),
-			format: NavFoundFormat::TextOnly,
-		},
-		MarkerNavTarget::ListItem => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no list items to navigate
-			not_supported: t("No list items."),
-			// TRANSLATORS: Announced when there is no next list item from the current position
-			not_found_next: t("No next list item."),
-			// TRANSLATORS: Announced when there is no previous list item from the current position
-			not_found_prev: t("No previous list item."),
-			format: NavFoundFormat::TextOnly,
-		},
-		MarkerNavTarget::Table => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no tables to navigate
-			not_supported: t("No tables."),
-			// TRANSLATORS: Announced when there is no next table from the current position
-			not_found_next: t("No next table."),
-			// TRANSLATORS: Announced when there is no previous table from the current position
-			not_found_prev: t("No previous table."),
-			format: NavFoundFormat::TextOnly,
-		},
-		MarkerNavTarget::Separator => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no separators to navigate
-			not_supported: t("No separators."),
-			// TRANSLATORS: Announced when there is no next separator from the current position
-			not_found_next: t("No next separator."),
-			// TRANSLATORS: Announced when there is no previous separator from the current position
-			not_found_prev: t("No previous separator."),
-			format: NavFoundFormat::TextOnly,
-		},
-		MarkerNavTarget::Image => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no images to navigate
-			not_supported: t("No images."),
-			// TRANSLATORS: Announced when there is no next image from the current position
-			not_found_next: t("No next image."),
-			// TRANSLATORS: Announced when there is no previous image from the current position
-			not_found_prev: t("No previous image."),
-			format: NavFoundFormat::ImageFormat,
-		},
-		MarkerNavTarget::Figure => NavAnnouncements {
-			// TRANSLATORS: Announced when the document has no figures to navigate
-			not_supported: t("No figures."),
-			// TRANSLATORS: Announced when there is no next figure from the current position
-			not_found_next: t("No next figure."),
-			// TRANSLATORS: Announced when there is no previous figure from the current position
-			not_found_prev: t("No previous figure."),
-			format: NavFoundFormat::ImageFormat,
-		},
+use wx_utils::show_error;
+use wxdragon::{clipboard::Clipboard, prelude::*};
+
+use super::{
+	dialogs,
+	document_manager::{DocumentManager, DocumentTab, ReadingColors, display_title},
+	find, speech, url_download,
+};
+use crate::controller::{
+	Announcer, Caret, MarkerNavTarget, ReaderController, TextUnit, marker_ahead, text_unit_ahead,
+	truncate_for_announcement,
+};
+
+/// The active tab's text control as the controller's caret.
+struct TextCtrlCaret(TextCtrl);
+
+impl Caret for TextCtrlCaret {
+	fn position(&self) -> i64 {
+		self.0.get_insertion_point()
+	}
+
+	fn set_position(&mut self, position: i64) {
+		self.0.set_focus();
+		self.0.set_insertion_point(position);
+		self.0.show_position(position);
 	}
 }
 
-fn format_nav_found_message(
-	ann: &NavAnnouncements,
-	context_text: &str,
-	context_index: i32,
-	wrapped: bool,
-	next: bool,
-) -> String {
-	let wrap_prefix =
-		// TRANSLATORS: Prefix announced when navigation wraps around past the end/start of the document; the trailing space is significant
-		if wrapped { if next { t("Wrapping to start. ") } else { t("Wrapping to end. ") } } else { String::new() };
-	match ann.format {
-		NavFoundFormat::TextOnly => format!("{wrap_prefix}{context_text}"),
-		NavFoundFormat::TextWithLevel => {
-			// TRANSLATORS: Announcement when landing on a heading; %s is the heading text, %d is the heading level number
-			let template = t("%s Heading level %d");
-			let message = template.replacen("%s", context_text, 1).replacen("%d", &context_index.to_string(), 1);
-			format!("{wrap_prefix}{message}")
-		}
-		NavFoundFormat::PageFormat => {
-			// TRANSLATORS: Announcement when landing on a page; %d is the page number, %s is the page text
-			let template = t("Page %d: %s");
-			let page_text = (context_index + 1).to_string();
-			let message = template.replacen("%d", &page_text, 1).replacen("%s", context_text, 1);
-			format!("{wrap_prefix}{message}")
-		}
-		NavFoundFormat::LinkFormat => {
-			// TRANSLATORS: Suffix appended after a link's text when announcing navigation to a link; the leading space is significant
-			let message = format!("{context_text}{}", t(" link"));
-			format!("{wrap_prefix}{message}")
-		}
-		NavFoundFormat::ImageFormat => {
-			let message = context_text.to_string();
-			format!("{wrap_prefix}{message}")
-		}
+/// The window's live region, speaking the controller's announcements.
+struct LiveRegion(StaticText);
+
+impl Announcer for LiveRegion {
+	fn announce(&mut self, message: &str) {
+		speech::announce(self.0, message);
 	}
 }
 
-fn apply_navigation_result(
-	tab: &super::document_manager::DocumentTab,
-	result: &NavigationResult,
-	target: MarkerNavTarget,
-	next: bool,
+/// Runs `navigate` on the active tab through a [`ReaderController`] and saves the tab's history
+/// when the caret moved.
+fn navigate_active_tab(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
 	live_region_label: StaticText,
-) -> bool {
-	let level_filter = match target {
-		MarkerNavTarget::Heading(level) => level,
-		_ => 0,
-	};
-	let ann = nav_announcements(target, level_filter);
-	if result.not_supported {
-		live_region::announce(live_region_label, &ann.not_supported);
-		return false;
-	}
-	if !result.found {
-		let message = if next { &ann.not_found_next } else { &ann.not_found_prev };
-		live_region::announce(live_region_label, message);
-		return false;
+	navigate: impl FnOnce(&mut ReaderController<'_>) -> bool,
+) {
+	let mut dm = doc_manager.lock().unwrap();
+	let history_update = {
+		let Some(tab) = dm.active_tab_mut() else {
+			return;
+		};
+		let mut caret = TextCtrlCaret(tab.text_ctrl);
+		let mut announcer = LiveRegion(live_region_label);
+		let moved = navigate(&mut ReaderController::new(&mut tab.session, &mut caret, &mut announcer));
+		if moved && tab.track {
+			let (history, history_index) = tab.session.saved_history();
+			Some((tab.file_path.to_string_lossy().to_string(), history, history_index))
+		} else {
+			None
+		}
+	};
+	drop(dm);
+	if let Some((path_str, history, history_index)) = history_update {
+		let cfg = config.lock().unwrap();
+		cfg.set_navigation_history(&path_str, &history, history_index);
 	}
-	let mut context_text = result.marker_text.clone();
-	if context_text.is_empty() {
-		context_text = tab.session.get_line_text(result.offset);
+}
+
+/// Reads more of a partly loaded active document until `found_ahead` sees a hit after the caret,
+/// so a forward move from the end of the loaded text reaches the pages still to come.
+fn load_until_found(doc_manager: &Rc<Mutex<DocumentManager>>, found_ahead: impl Fn(&DocumentSession, i64) -> bool) {
+	let mut dm = doc_manager.lock().unwrap();
+	let Some(tab) = dm.active_tab_mut() else {
+		return;
+	};
+	let position = tab.text_ctrl.get_insertion_point();
+	while !tab.session.is_fully_loaded() && !found_ahead(&tab.session, position) {
+		let loaded_end = i64::try_from(tab.session.char_length()).unwrap_or(i64::MAX);
+		tab.load_through_position(loaded_end);
 	}
-	let context_index = match target {
-		MarkerNavTarget::Heading(_) => result.marker_level,
-		MarkerNavTarget::Page => result.marker_index,
-		MarkerNavTarget::Image => result.marker_index,
-		MarkerNavTarget::Figure => result.marker_index,
-		_ => 0,
-	};
-	let message = format_nav_found_message(&ann, &context_text, context_index, result.wrapped, next);
-	live_region::announce(live_region_label, &message);
-	let offset = result.offset;
-	tab.text_ctrl.set_focus();
-	tab.text_ctrl.set_insertion_point(offset);
-	tab.text_ctrl.show_position(offset);
-	true
 }
 
-pub fn handle_history_navigation(
+/// Announces where the caret is: the enclosing headings, page, percentage and line.
+pub fn handle_speak_location(doc_manager: &Rc<Mutex<DocumentManager>>, live_region_label: StaticText) {
+	let message = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		tab.session.location_announcement(tab.text_ctrl.get_insertion_point())
+	};
+	speech::announce(live_region_label, &message);
+}
+
+/// Returns to the position restored when the document was opened and selects the sentence there, so
+/// the reader can pick up the context they left off in.
+pub fn handle_last_read_position(
 	doc_manager: &Rc<Mutex<DocumentManager>>,
 	config: &Rc<Mutex<ConfigManager>>,
 	live_region_label: StaticText,
-	forward: bool,
+) {
+	// TRANSLATORS: Announced when the document has no reading position saved from a previous session
+	let missing = t("No last read position.");
+	go_to_reading_position(doc_manager, config, live_region_label, &missing, |tab| tab.last_read);
+}
+
+/// Jumps to the furthest point reached in the document, however far back the reader has gone to
+/// re-read since, and selects the sentence there.
+pub fn handle_furthest_read_position(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	// TRANSLATORS: Announced when nothing of the document has been read yet, so there is no furthest position to go to
+	let missing = t("No furthest read position.");
+	go_to_reading_position(doc_manager, config, live_region_label, &missing, |tab| {
+		let position = tab.session.furthest_position();
+		if position <= 0 {
+			return None;
+		}
+		tab.load_through_position(position);
+		Some((tab.session.clamp_position(position), false))
+	});
+}
+
+/// Selects the sentence at the position `target` picks for the active tab and announces it, with a
+/// note when the position is only approximate. Announces `missing` when there is no such position.
+fn go_to_reading_position(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	missing: &str,
+	target: impl FnOnce(&mut DocumentTab) -> Option<(i64, bool)>,
 ) {
 	let mut dm = doc_manager.lock().unwrap();
 	let (message, history_update) = {
 		let Some(tab) = dm.active_tab_mut() else {
 			return;
 		};
-		let current_pos = tab.text_ctrl.get_insertion_point();
-		let result = if forward {
-			tab.session.history_go_forward(current_pos)
-		} else {
-			tab.session.history_go_back(current_pos)
-		};
-		if result.found {
-			// TRANSLATORS: Announced when moving forward/backward through the caret position history
-			let message = if forward { t("Navigated to next position.") } else { t("Navigated to previous position.") };
+		if let Some((position, approximate)) = target(tab) {
+			let (start, end, sentence) =
+				tab.session.sentence_at(position).unwrap_or((position, position, String::new()));
 			tab.text_ctrl.set_focus();
-			tab.text_ctrl.set_insertion_point(result.offset);
-			tab.text_ctrl.show_position(result.offset);
-			tab.session.set_stable_position(result.offset);
+			tab.text_ctrl.set_selection(start, end);
+			tab.text_ctrl.show_position(start);
+			tab.session.check_and_record_history(start);
+			let mut message = truncate_for_announcement(&sentence);
+			if approximate {
+				// TRANSLATORS: Announced before the sentence at the last read position when the document changed since it was saved
+				message = format!("{} {message}", t("Position approximate."));
+			}
 			let history_update = if tab.track {
-				let (history, history_index) = tab.session.get_history();
-				let path_str = tab.file_path.to_string_lossy().to_string();
-				Some((path_str, history.to_vec(), history_index))
+				let (history, history_index) = tab.session.saved_history();
+				Some((tab.file_path.to_string_lossy().to_string(), history, history_index))
 			} else {
 				None
 			};
 			(message, history_update)
 		} else {
-			// TRANSLATORS: Announced when there is no next/previous position in the caret position history
-			let message = if forward { t("No next position.") } else { t("No previous position.") };
-			(message, None)
+			(missing.to_string(), None)
 		}
 	};
 	drop(dm);
-	live_region::announce(live_region_label, &message);
+	speech::announce(live_region_label, message.trim());
 	if let Some((path_str, history, history_index)) = history_update {
 		let cfg = config.lock().unwrap();
 		cfg.set_navigation_history(&path_str, &history, history_index);
 	}
 }
 
-pub fn handle_marker_navigation(
+pub fn handle_history_navigation(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	forward: bool,
+) {
+	navigate_active_tab(doc_manager, config, live_region_label, |nav| nav.navigate_history(forward));
+}
+
+/// Opens the history browser and jumps to the chosen entry, appending the jump to the history
+/// rather than discarding the entries after the current one.
+pub fn handle_history_dialog(
+	frame: &Frame,
 	doc_manager: &Rc<Mutex<DocumentManager>>,
 	config: &Rc<Mutex<ConfigManager>>,
 	live_region_label: StaticText,
-	target: MarkerNavTarget,
-	next: bool,
 ) {
-	let wrap = config.lock().unwrap().get_app_bool("navigation_wrap", false);
 	let mut dm = doc_manager.lock().unwrap();
-	let history_update = {
+	let (message, history_update) = {
 		let Some(tab) = dm.active_tab_mut() else {
 			return;
 		};
-		let current_pos = tab.text_ctrl.get_insertion_point();
-		let result = match target {
-			MarkerNavTarget::Section => tab.session.navigate_section(current_pos, wrap, next),
-			MarkerNavTarget::Page => tab.session.navigate_page(current_pos, wrap, next),
-			MarkerNavTarget::Heading(level) => tab.session.navigate_heading(current_pos, wrap, next, level),
-			MarkerNavTarget::Link => tab.session.navigate_link(current_pos, wrap, next),
-			MarkerNavTarget::Table => tab.session.navigate_table(current_pos, wrap, next),
-			MarkerNavTarget::Separator => tab.session.navigate_separator(current_pos, wrap, next),
-			MarkerNavTarget::List => tab.session.navigate_list(current_pos, wrap, next),
-			MarkerNavTarget::ListItem => tab.session.navigate_list_item(current_pos, wrap, next),
-			MarkerNavTarget::Image => tab.session.navigate_image(current_pos, wrap, next),
-			MarkerNavTarget::Figure => tab.session.navigate_figure(current_pos, wrap, next),
+		let entries = tab.session.history_entries();
+		if entries.is_empty() {
+			drop(dm);
+			// TRANSLATORS: Announced when opening the history browser before any positions were recorded
+			speech::announce(live_region_label, &t("No history yet."));
+			return;
+		}
+		let (_, history_index) = tab.session.get_history();
+		let Some(entry) = dialogs::show_history_dialog(frame, &entries, history_index) else {
+			return;
 		};
-		let target_offset = result.offset;
-		if apply_navigation_result(tab, &result, target, next, live_region_label) {
-			tab.session.check_and_record_history(target_offset);
-			if tab.track {
-				let (history, history_index) = tab.session.get_history();
-				let path_str = tab.file_path.to_string_lossy().to_string();
-				Some((path_str, history.to_vec(), history_index))
-			} else {
-				None
-			}
+		let current_pos = tab.text_ctrl.get_insertion_point();
+		let result = tab.session.history_go_to(current_pos, entry);
+		if !result.found {
+			return;
+		}
+		tab.text_ctrl.set_focus();
+		tab.text_ctrl.set_insertion_point(result.offset);
+		tab.text_ctrl.show_position(result.offset);
+		tab.session.set_stable_position(result.offset);
+		let message = truncate_for_announcement(tab.session.get_line_text(result.offset).trim());
+		let history_update = if tab.track {
+			let (history, history_index) = tab.session.saved_history();
+			let path_str = tab.file_path.to_string_lossy().to_string();
+			Some((path_str, history, history_index))
 		} else {
 			None
-		}
+		};
+		(message, history_update)
 	};
 	drop(dm);
+	speech::announce(live_region_label, &message);
 	if let Some((path_str, history, history_index)) = history_update {
 		let cfg = config.lock().unwrap();
 		cfg.set_navigation_history(&path_str, &history, history_index);
 	}
 }
 
-/// Navigate relative to the container (list/table) the caret is currently inside: `to_end` jumps
-/// just past its end, otherwise to its start. Announces "Not in a container." when the caret is
-/// not inside any container.
-pub fn handle_container_navigation(
+/// Asks for a dotted section number and jumps to the heading with that outline number.
+pub fn handle_go_to_section_number(
+	frame: &Frame,
 	doc_manager: &Rc<Mutex<DocumentManager>>,
 	config: &Rc<Mutex<ConfigManager>>,
 	live_region_label: StaticText,
-	to_end: bool,
 ) {
+	if doc_manager.lock().unwrap().active_tab().is_none() {
+		return;
+	}
+	let Some(number) = dialogs::show_go_to_section_number_dialog(frame) else {
+		return;
+	};
 	let mut dm = doc_manager.lock().unwrap();
-	let history_update = {
+	let (message, history_update) = {
 		let Some(tab) = dm.active_tab_mut() else {
 			return;
 		};
-		let current_pos = tab.text_ctrl.get_insertion_point();
-		let result = tab.session.navigate_container(current_pos, to_end);
-		if result.not_supported {
-			// TRANSLATORS: Announced when the document has no containers (lists/tables) to navigate
-			live_region::announce(live_region_label, &t("No containers."));
-			None
-		} else if !result.found {
-			// TRANSLATORS: Announced when the caret is not currently inside a container (list/table)
-			live_region::announce(live_region_label, &t("Not in a container."));
-			None
+		let Some(target_pos) = tab.session.section_number_position(&number) else {
+			drop(dm);
+			// TRANSLATORS: Announced when no heading has the section number entered in "Go to Section Number"; {} is the number, e.g. 3.2.1
+			speech::announce(live_region_label, &t("No section {}.").replace("{}", &number));
+			return;
+		};
+		tab.text_ctrl.set_focus();
+		tab.text_ctrl.set_insertion_point(target_pos);
+		tab.text_ctrl.show_position(target_pos);
+		tab.session.check_and_record_history(target_pos);
+		let message = tab.session.heading_breadcrumb(target_pos);
+		let history_update = if tab.track {
+			let (history, history_index) = tab.session.saved_history();
+			let path_str = tab.file_path.to_string_lossy().to_string();
+			Some((path_str, history, history_index))
 		} else {
-			let offset = result.offset;
-			let line = tab.session.get_line_text(offset);
-			let message = if line.trim().is_empty() {
-				// TRANSLATORS: Announced when jumping to the start/end of the container (list/table) the caret is inside, and the target line is blank
-				if to_end { t("Past end of container.") } else { t("Start of container.") }
-			} else {
-				line
-			};
-			live_region::announce(live_region_label, &message);
-			tab.text_ctrl.set_focus();
-			tab.text_ctrl.set_insertion_point(offset);
-			tab.text_ctrl.show_position(offset);
-			tab.session.check_and_record_history(offset);
-			if tab.track {
-				let (history, history_index) = tab.session.get_history();
-				let path_str = tab.file_path.to_string_lossy().to_string();
-				Some((path_str, history.to_vec(), history_index))
-			} else {
-				None
-			}
+			None
+		};
+		(message, history_update)
+	};
+	drop(dm);
+	speech::announce(live_region_label, &message);
+	if let Some((path_str, history, history_index)) = history_update {
+		let cfg = config.lock().unwrap();
+		cfg.set_navigation_history(&path_str, &history, history_index);
+	}
+}
+
+/// Lists the document's landmarks and jumps to the chosen one, recording the jump in history.
+pub fn handle_landmarks_dialog(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	let mut dm = doc_manager.lock().unwrap();
+	let (message, history_update) = {
+		let Some(tab) = dm.active_tab_mut() else {
+			return;
+		};
+		let landmarks = tab.session.landmarks();
+		if landmarks.is_empty() {
+			drop(dm);
+			// TRANSLATORS: Announced when opening the landmarks list for a document that marks none
+			speech::announce(live_region_label, &t("No landmarks."));
+			return;
 		}
+		let current_pos = usize::try_from(tab.text_ctrl.get_insertion_point()).unwrap_or(0);
+		let Some(index) = dialogs::show_landmarks_dialog(frame, &landmarks, current_pos) else {
+			return;
+		};
+		let target_pos = i64::try_from(landmarks[index].offset).unwrap_or(0);
+		tab.text_ctrl.set_focus();
+		tab.text_ctrl.set_insertion_point(target_pos);
+		tab.text_ctrl.show_position(target_pos);
+		tab.session.check_and_record_history(target_pos);
+		let message = truncate_for_announcement(tab.session.get_line_text(target_pos).trim());
+		let history_update = if tab.track {
+			let (history, history_index) = tab.session.saved_history();
+			let path_str = tab.file_path.to_string_lossy().to_string();
+			Some((path_str, history, history_index))
+		} else {
+			None
+		};
+		(message, history_update)
 	};
 	drop(dm);
+	speech::announce(live_region_label, &message);
 	if let Some((path_str, history, history_index)) = history_update {
 		let cfg = config.lock().unwrap();
 		cfg.set_navigation_history(&path_str, &history, history_index);
 	}
 }
 
+pub fn handle_marker_navigation(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	target: MarkerNavTarget,
+	next: bool,
+) {
+	let (wrap, with_location) = {
+		let cfg = config.lock().unwrap();
+		(cfg.get_app_bool("navigation_wrap", false), cfg.get_app_bool("announce_location_on_navigation", false))
+	};
+	if next {
+		load_until_found(doc_manager, |session, position| marker_ahead(session, target, position));
+	}
+	navigate_active_tab(doc_manager, config, live_region_label, |nav| {
+		nav.navigate_marker(target, next, wrap, with_location)
+	});
+}
+
+/// Jumps to a section other than the current one, chosen at random. Announces "Not enough
+/// sections." when the document has fewer than two.
+pub fn handle_random_section(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	let with_location = config.lock().unwrap().get_app_bool("announce_location_on_navigation", false);
+	navigate_active_tab(doc_manager, config, live_region_label, |nav| nav.random_section(with_location));
+}
+
+/// Turns shuffled section order for Next Section on or off for the current document.
+pub fn handle_toggle_section_shuffle(doc_manager: &Rc<Mutex<DocumentManager>>, live_region_label: StaticText) {
+	let mut dm = doc_manager.lock().unwrap();
+	let Some(tab) = dm.active_tab_mut() else {
+		return;
+	};
+	let enabled = !tab.session.section_shuffle_enabled();
+	tab.session.set_section_shuffle(enabled);
+	drop(dm);
+	let message = if enabled {
+		// TRANSLATORS: Announced when Next Section starts visiting sections in a random order
+		t("Section shuffle on.")
+	} else {
+		// TRANSLATORS: Announced when Next Section goes back to reading order
+		t("Section shuffle off.")
+	};
+	speech::announce(live_region_label, &message);
+}
+
+/// Sets the active document's text direction, `None` going back to the detected one, and says
+/// which direction is now in effect.
+pub fn handle_text_direction(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	live_region_label: StaticText,
+	direction: Option<TextDirection>,
+) {
+	let dm = doc_manager.lock().unwrap();
+	let Some(effective) = dm.set_text_direction(direction) else {
+		return;
+	};
+	let message = match (direction, effective) {
+		// TRANSLATORS: Announced after choosing Text Direction > Automatic when the document's text was detected as right to left
+		(None, TextDirection::RightToLeft) => t("Automatic direction: right to left."),
+		// TRANSLATORS: Announced after choosing Text Direction > Automatic when the document's text was detected as left to right
+		(None, TextDirection::LeftToRight) => t("Automatic direction: left to right."),
+		// TRANSLATORS: Announced after choosing Text Direction > Right to Left
+		(Some(_), TextDirection::RightToLeft) => t("Right to left."),
+		// TRANSLATORS: Announced after choosing Text Direction > Left to Right
+		(Some(_), TextDirection::LeftToRight) => t("Left to right."),
+	};
+	speech::announce(live_region_label, &message);
+	dm.restore_focus();
+}
+
+pub fn handle_line_layout(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	live_region_label: StaticText,
+	layout: Option<LineLayout>,
+) {
+	let mut dm = doc_manager.lock().unwrap();
+	let Some(effective) = dm.set_line_layout(doc_manager, layout) else {
+		return;
+	};
+	let message = match (layout, effective) {
+		// TRANSLATORS: Announced after choosing Line Layout > Automatic when the document was detected as preformatted, like poetry or code
+		(None, LineLayout::Preformatted) => t("Automatic layout: preformatted, original lines kept."),
+		// TRANSLATORS: Announced after choosing Line Layout > Automatic when the document was detected as prose
+		(None, LineLayout::Wrapped) => t("Automatic layout: prose."),
+		// TRANSLATORS: Announced after choosing Line Layout > Preserve Original Lines
+		(Some(_), LineLayout::Preformatted) => t("Original lines kept."),
+		// TRANSLATORS: Announced after choosing Line Layout > Wrap Long Lines
+		(Some(_), LineLayout::Wrapped) => t("Laid out as prose."),
+	};
+	speech::announce(live_region_label, &message);
+	dm.restore_focus();
+}
+
+/// Navigate relative to the container (list/table) the caret is currently inside: `to_end` jumps
+/// just past its end, otherwise to its start. Announces "Not in a container." when the caret is
+/// not inside any container.
+pub fn handle_container_navigation(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	to_end: bool,
+) {
+	navigate_active_tab(doc_manager, config, live_region_label, |nav| nav.navigate_container(to_end));
+}
+
+/// Move to the next or previous sentence or paragraph and announce its (truncated) text.
+pub fn handle_text_unit_navigation(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	unit: TextUnit,
+	next: bool,
+) {
+	if next {
+		load_until_found(doc_manager, |session, position| text_unit_ahead(session, unit, position));
+	}
+	navigate_active_tab(doc_manager, config, live_region_label, |nav| nav.navigate_text_unit(unit, next));
+}
+
 pub fn selected_range(text_ctrl: TextCtrl) -> (i64, i64) {
 	let (start, end) = text_ctrl.get_selection();
 	if start == end {
@@ -392,76 +473,76 @@ pub fn handle_bookmark_navigation(
 	notes_only: bool,
 ) {
 	let wrap = config.lock().unwrap().get_app_bool("navigation_wrap", false);
+	navigate_active_tab(doc_manager, config, live_region_label, |nav| {
+		let cfg = config.lock().unwrap();
+		let landed = nav.navigate_bookmark(&cfg, next, notes_only, wrap);
+		let sounds = cfg.get_app_bool("bookmark_sounds", true);
+		drop(cfg);
+		if let Some(has_note) = landed.filter(|_| sounds) {
+			super::sounds::play_bookmark_sound(has_note);
+		}
+		landed.is_some()
+	});
+}
+
+/// Saves the caret position in quick slot `slot`, replacing whatever it held.
+pub fn handle_set_quick_slot(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	slot: u8,
+) {
+	let (position, path_str) = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		(tab.text_ctrl.get_insertion_point(), tab.file_path.to_string_lossy().to_string())
+	};
+	let cfg = config.lock().unwrap();
+	cfg.set_document_quick_slot(&path_str, slot, position);
+	cfg.flush();
+	drop(cfg);
+	// TRANSLATORS: Announced after saving the caret position in a quick slot; %d is the slot's digit key
+	speech::announce(live_region_label, &t("Slot %d set").replace("%d", &slot.to_string()));
+}
+
+/// Jumps to the position saved in quick slot `slot` and reads the line there.
+pub fn handle_go_to_quick_slot(
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+	slot: u8,
+) {
 	let mut dm = doc_manager.lock().unwrap();
 	let (message, history_update) = {
 		let Some(tab) = dm.active_tab_mut() else {
 			return;
 		};
-		let current_pos = tab.text_ctrl.get_insertion_point();
 		let path_str = tab.file_path.to_string_lossy().to_string();
-		let (result, has_items) = {
-			let cfg = config.lock().unwrap();
-			let bookmarks = cfg.get_bookmarks(&path_str);
-			let has_items =
-				if notes_only { bookmarks.iter().any(|bm| !bm.note.is_empty()) } else { !bookmarks.is_empty() };
-			let result = if notes_only {
-				tab.session.navigate_note(&cfg, current_pos, wrap, next)
-			} else {
-				tab.session.navigate_bookmark(&cfg, current_pos, wrap, next)
-			};
-			drop(cfg);
-			(result, has_items)
-		};
-		if result.found {
+		let saved = config.lock().unwrap().get_document_quick_slot(&path_str, slot);
+		if let Some(position) = saved {
+			tab.load_through_position(position);
+			let position = tab.session.clamp_position(position);
 			tab.text_ctrl.set_focus();
-			tab.text_ctrl.set_insertion_point(result.offset);
-			tab.text_ctrl.show_position(result.offset);
-			tab.session.check_and_record_history(result.offset);
-			if config.lock().unwrap().get_app_bool("bookmark_sounds", true) {
-				super::sounds::play_bookmark_sound(!result.marker_text.is_empty());
-			}
-			let note_text = result.marker_text;
-			let line_text = tab.session.get_line_text(result.offset);
-			let content_text = if note_text.is_empty() { line_text } else { format!("{note_text}, {line_text}") };
-			let wrap_prefix = if result.wrapped {
-				// TRANSLATORS: Prefix announced when navigation wraps around past the end/start of the document; the trailing space is significant
-				if next { t("Wrapping to start. ") } else { t("Wrapping to end. ") }
-			} else {
-				String::new()
-			};
-			// TRANSLATORS: Announcement when landing on a bookmark; %s is the bookmark/line text, %d is the bookmark's 1-based index
-			let bookmark_text = t("%s - Bookmark %d").replacen("%s", &content_text, 1).replacen(
-				"%d",
-				&(result.marker_index + 1).to_string(),
-				1,
-			);
-			let message = format!("{wrap_prefix}{bookmark_text}");
+			tab.text_ctrl.set_insertion_point(position);
+			tab.text_ctrl.show_position(position);
+			tab.session.check_and_record_history(position);
+			let message = tab.session.get_line_text(position);
 			let history_update = if tab.track {
-				let (history, history_index) = tab.session.get_history();
-				Some((path_str, history.to_vec(), history_index))
+				let (history, history_index) = tab.session.saved_history();
+				Some((path_str, history, history_index))
 			} else {
 				None
 			};
 			(message, history_update)
 		} else {
-			let message = if !has_items {
-				// TRANSLATORS: Announced when there are no bookmarks/notes at all to navigate to
-				if notes_only { t("No notes.") } else { t("No bookmarks.") }
-			} else if next {
-				// TRANSLATORS: Announced when there is no next bookmark/note from the current position
-				if notes_only { t("No next note.") } else { t("No next bookmark.") }
-			} else if notes_only {
-				// TRANSLATORS: Announced when there is no previous note from the current position
-				t("No previous note.")
-			} else {
-				// TRANSLATORS: Announced when there is no previous bookmark from the current position
-				t("No previous bookmark.")
-			};
-			(message, None)
+			// TRANSLATORS: Announced when jumping to a quick slot that has no position saved in it; %d is the slot's digit key
+			(t("Slot %d empty").replace("%d", &slot.to_string()), None)
 		}
 	};
 	drop(dm);
-	live_region::announce(live_region_label, &message);
+	speech::announce(live_region_label, message.trim());
 	if let Some((path_str, history, history_index)) = history_update {
 		let cfg = config.lock().unwrap();
 		cfg.set_navigation_history(&path_str, &history, history_index);
@@ -505,16 +586,16 @@ pub fn handle_bookmark_dialog(
 			t("Bookmark.")
 		};
 		let history_update = if tab.track {
-			let (history, history_index) = tab.session.get_history();
+			let (history, history_index) = tab.session.saved_history();
 			let path_str = tab.file_path.to_string_lossy().to_string();
-			Some((path_str, history.to_vec(), history_index))
+			Some((path_str, history, history_index))
 		} else {
 			None
 		};
 		(message, history_update)
 	};
 	drop(dm);
-	live_region::announce(live_region_label, &message);
+	speech::announce(live_region_label, &message);
 	if let Some((path_str, history, history_index)) = history_update {
 		let cfg = config.lock().unwrap();
 		cfg.set_navigation_history(&path_str, &history, history_index);
@@ -546,7 +627,7 @@ pub fn handle_toggle_bookmark(
 	drop(cfg);
 	// TRANSLATORS: Announced after toggling a bookmark at the current selection off/on
 	let message = if existed { t("Bookmark removed.") } else { t("Bookmark added.") };
-	live_region::announce(live_region_label, &message);
+	speech::announce(live_region_label, &message);
 }
 
 pub fn handle_bookmark_with_note(
@@ -568,26 +649,135 @@ pub fn handle_bookmark_with_note(
 		drop(dm);
 		(start, end, path_str)
 	};
-	let existing = {
+	let (existing, categories) = {
 		let cfg = config.lock().unwrap();
-		cfg.get_bookmarks(&path_str).into_iter().find(|bm| bm.start == start && bm.end == end)
+		let existing = cfg.get_bookmarks(&path_str).into_iter().find(|bm| bm.start == start && bm.end == end);
+		(existing, cfg.get_bookmark_categories(&path_str))
 	};
-	let existing_note = existing.as_ref().map(|bm| bm.note.clone()).unwrap_or_default();
-	let Some(note) =
-		dialogs::show_note_entry_dialog(frame, &t("Bookmark Note"), &t("Enter bookmark note:"), &existing_note)
+	let found = existing.is_some();
+	let existing = existing.map_or(dialogs::BookmarkNoteEntry { note: String::new(), category: String::new() }, |bm| {
+		dialogs::BookmarkNoteEntry { note: bm.note, category: bm.category }
+	});
+	let Some(entry) =
+		dialogs::show_note_entry_dialog(frame, &t("Bookmark Note"), &t("Enter bookmark note:"), &existing, &categories)
 	else {
 		return;
 	};
 	let cfg = config.lock().unwrap();
-	if existing.is_some() {
-		cfg.update_bookmark_note(&path_str, start, end, &note);
+	if found {
+		cfg.update_bookmark_note(&path_str, start, end, &entry.note);
 	} else {
-		cfg.add_bookmark(&path_str, start, end, &note);
+		cfg.add_bookmark(&path_str, start, end, &entry.note);
 	}
+	cfg.update_bookmark_category(&path_str, start, end, &entry.category);
 	cfg.flush();
 	drop(cfg);
 	// TRANSLATORS: Announced after saving a bookmark's note text
-	live_region::announce(live_region_label, &t("Bookmark saved."));
+	speech::announce(live_region_label, &t("Bookmark saved."));
+}
+
+/// Opens the document's notes page and saves whatever it holds when the dialog closes.
+pub fn handle_document_notes(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	let (path_str, title) = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		(tab.file_path.to_string_lossy().to_string(), display_title(tab))
+	};
+	let notes = config.lock().unwrap().get_document_notes(&path_str);
+	let updated = dialogs::show_document_notes_dialog(frame, &title, &notes, None);
+	if updated != notes {
+		let cfg = config.lock().unwrap();
+		cfg.set_document_notes(&path_str, &updated);
+		cfg.flush();
+		drop(cfg);
+		// TRANSLATORS: Announced after closing the Document Notes dialog with changes
+		speech::announce(live_region_label, &t("Notes saved."));
+	}
+	doc_manager.lock().unwrap().restore_focus();
+}
+
+/// Searches the active document's notes and goes to the match chosen: a bookmark's match moves the
+/// caret to the bookmark, a Document Notes match opens them with the match selected.
+pub fn handle_search_notes(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	let (path_str, title) = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		(tab.file_path.to_string_lossy().to_string(), display_title(tab))
+	};
+	let initial_options = {
+		let settings = config.lock().unwrap().get_find_settings();
+		let mut options = SearchOptions::empty();
+		options.set(SearchOptions::MATCH_CASE, settings.match_case);
+		options.set(SearchOptions::WHOLE_WORD, settings.whole_word);
+		options.set(SearchOptions::REGEX, settings.use_regex);
+		options
+	};
+	let config_for_search = Rc::clone(config);
+	let path_for_search = path_str.clone();
+	let result = dialogs::show_search_notes_dialog(frame, initial_options, move |query, options, ignore_diacritics| {
+		let cfg = config_for_search.lock().unwrap();
+		reader_core::search_notes(&cfg, &path_for_search, query, options, ignore_diacritics)
+			.map_err(|error| find::search_pattern_error_message(&error))
+	});
+	let Some(result) = result else {
+		doc_manager.lock().unwrap().restore_focus();
+		return;
+	};
+	match result.source {
+		NoteSource::Bookmark { start, .. } => {
+			let mut dm = doc_manager.lock().unwrap();
+			let Some(tab) = dm.active_tab_mut() else {
+				return;
+			};
+			tab.text_ctrl.set_focus();
+			tab.text_ctrl.set_insertion_point(start);
+			tab.text_ctrl.show_position(start);
+			tab.session.check_and_record_history(start);
+			let cfg = config.lock().unwrap();
+			if tab.track {
+				let (history, history_index) = tab.session.saved_history();
+				cfg.set_navigation_history(&path_str, &history, history_index);
+			}
+			let info = tab.session.bookmark_display_at_position(&cfg, start);
+			drop(cfg);
+			drop(dm);
+			let message = if info.found && !info.note.is_empty() {
+				info.note
+			} else {
+				// TRANSLATORS: Fallback announcement when viewing a bookmark that has no note text or line snippet
+				t("Bookmark.")
+			};
+			speech::announce(live_region_label, &message);
+		}
+		NoteSource::DocumentNotes => {
+			let notes = config.lock().unwrap().get_document_notes(&path_str);
+			let selection = (result.position, result.position + result.length);
+			let updated = dialogs::show_document_notes_dialog(frame, &title, &notes, Some(selection));
+			if updated != notes {
+				let cfg = config.lock().unwrap();
+				cfg.set_document_notes(&path_str, &updated);
+				cfg.flush();
+				drop(cfg);
+				// TRANSLATORS: Announced after closing the Document Notes dialog with changes
+				speech::announce(live_region_label, &t("Notes saved."));
+			}
+			doc_manager.lock().unwrap().restore_focus();
+		}
+	}
 }
 
 pub fn handle_view_note_text(
@@ -608,9 +798,9 @@ pub fn handle_view_note_text(
 		drop(dm);
 		(current_pos, path_str)
 	};
-	let note = {
+	let (note, colors) = {
 		let cfg = config.lock().unwrap();
-		reader_core::bookmark_note_at_position(&cfg, &path_str, current_pos)
+		(reader_core::bookmark_note_at_position(&cfg, &path_str, current_pos), ReadingColors::from_config(&cfg))
 	};
 	if note.is_empty() {
 		// TRANSLATORS: Message shown when trying to view a bookmark note but the current position has none
@@ -620,5 +810,180 @@ pub fn handle_view_note_text(
 		dialog.show_modal();
 		return;
 	}
-	dialogs::show_view_note_dialog(frame, &note);
+	dialogs::show_view_note_dialog(frame, &note, colors);
+}
+
+/// Shows where the link at the caret leads, leaving the caret where it is.
+pub fn handle_preview_link(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	let preview = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		tab.session.link_preview(tab.text_ctrl.get_insertion_point())
+	};
+	let Some(preview) = preview else {
+		// TRANSLATORS: Announced when previewing a link target but the caret is not on a link that leads anywhere
+		speech::announce(live_region_label, &t("Not on a link."));
+		return;
+	};
+	let colors = ReadingColors::from_config(&config.lock().unwrap());
+	let url = (!preview.url.is_empty()).then_some(preview.url.as_str());
+	dialogs::show_link_preview_dialog(frame, &preview.title, &preview.text, url, colors);
+	doc_manager.lock().unwrap().restore_focus();
+}
+
+/// Looks up the selection, or the word at the caret, in the offline dictionary chosen in Options,
+/// or opens the lookup page in the browser when none is set. Selections are looked up verbatim.
+pub fn handle_look_up_word(
+	frame: &Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	config: &Rc<Mutex<ConfigManager>>,
+	live_region_label: StaticText,
+) {
+	let text = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		let (start, end) = tab.text_ctrl.get_selection();
+		if start == end {
+			tab.session.word_at(tab.text_ctrl.get_insertion_point())
+		} else {
+			tab.text_ctrl.get_string_selection()
+		}
+	};
+	let text = text.trim();
+	if text.is_empty() {
+		// TRANSLATORS: Announced when Look Up Word is used with the cursor on no word and nothing selected
+		speech::announce(live_region_label, &t("No word to look up."));
+		return;
+	}
+	let (dictionary_path, url_template, colors) = {
+		let cfg = config.lock().unwrap();
+		(
+			cfg.get_app_string("dictionary_path", ""),
+			cfg.get_app_string("lookup_url", lookup::DEFAULT_LOOKUP_URL),
+			ReadingColors::from_config(&cfg),
+		)
+	};
+	if dictionary_path.is_empty() {
+		let url = lookup::lookup_url(&url_template, text);
+		if !launch_default_browser(&url, BrowserLaunchFlags::Default) {
+			tracing::warn!(url = %url, "failed to launch default browser for word lookup");
+			// TRANSLATORS: Error shown when the web browser could not be opened to look up a word
+			show_error(frame, t("Failed to launch default browser."), &t("Error"));
+		}
+		return;
+	}
+	match lookup::cached_dictionary(Path::new(&dictionary_path)) {
+		Ok(dictionary) => match dictionary.lookup(text) {
+			Some(definition) => dialogs::show_text_dialog(frame, text, definition, colors),
+			// TRANSLATORS: Announced when the word being looked up isn't in the offline dictionary
+			None => speech::announce(live_region_label, &t("No definition found.")),
+		},
+		Err(error) => {
+			tracing::warn!(path = %dictionary_path, %error, "failed to read dictionary");
+			// TRANSLATORS: Error shown when the dictionary file chosen in Options can't be read; {} is its path
+			let message = t("Couldn't read the dictionary file {}.").replace("{}", &dictionary_path);
+			show_error(frame, message, &t("Error"));
+		}
+	}
+}
+
+/// Exports the section holding the caret. A document without sections is exported whole, once the
+/// user agrees to that.
+pub fn handle_export_current_section(frame: &Frame, doc_manager: &Rc<Mutex<DocumentManager>>, format: ExportFormat) {
+	let dm = doc_manager.lock().unwrap();
+	let Some(tab) = dm.active_tab() else {
+		return;
+	};
+	let section = tab.session.current_section_range(tab.text_ctrl.get_insertion_point());
+	if section.whole_document {
+		// TRANSLATORS: Question asked by Export Current Section when the document isn't divided into sections
+		let message = t("This document has no sections or chapters. Export the whole document instead?");
+		// TRANSLATORS: Title of the question asked by Export Current Section when the document has no sections
+		let dialog = MessageDialog::builder(frame, &message, &t("Export Current Section"))
+			.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+			.build();
+		if dialog.show_modal() != ID_YES {
+			return;
+		}
+	}
+	let stem = url_download::sanitize_file_name(&section.title.replace(['/', '\\'], "-"));
+	let stem = if stem.is_empty() {
+		// TRANSLATORS: Fallback file name stem used when the document's path has no file stem
+		tab.file_path.file_stem().map_or_else(|| t("document"), |s| s.to_string_lossy().to_string())
+	} else {
+		stem
+	};
+	let (extension, wildcard, title) = match format {
+		ExportFormat::Text => (
+			"txt",
+			// TRANSLATORS: File filter shown in the "Export to plain text" save dialog
+			t("Plain text files (*.txt)|*.txt|All files (*.*)|*.*"),
+			// TRANSLATORS: Title of the file save dialog when exporting the current section to plain text
+			t("Export current section to plain text"),
+		),
+		ExportFormat::Html => (
+			"html",
+			// TRANSLATORS: File filter shown in the "Export to HTML" save dialog
+			t("HTML files (*.html)|*.html|All files (*.*)|*.*"),
+			// TRANSLATORS: Title of the file save dialog when exporting the current section to HTML
+			t("Export current section to HTML"),
+		),
+		ExportFormat::Markdown => (
+			"md",
+			// TRANSLATORS: File filter shown in the "Export to Markdown" save dialog
+			t("Markdown files (*.md)|*.md|All files (*.*)|*.*"),
+			// TRANSLATORS: Title of the file save dialog when exporting the current section to Markdown
+			t("Export current section to Markdown"),
+		),
+	};
+	let dialog = FileDialog::builder(frame)
+		.with_message(&title)
+		.with_default_file(&format!("{stem}.{extension}"))
+		.with_wildcard(&wildcard)
+		.with_style(FileDialogStyle::Save | FileDialogStyle::OverwritePrompt)
+		.build();
+	if dialog.show_modal() != ID_OK {
+		return;
+	}
+	let Some(path) = dialog.get_path() else {
+		return;
+	};
+	if let Err(e) = tab.session.export_section_as(&path, format, &section) {
+		tracing::error!(path = %path, error = %e, "failed to export current section");
+		// TRANSLATORS: Error dialog shown when exporting the current section fails
+		show_error(frame, t("Failed to export the section."), &t("Error"));
+	}
+}
+
+/// Puts the text of the section holding the caret on the clipboard, or the whole document when it
+/// has no sections.
+pub fn handle_copy_current_section(doc_manager: &Rc<Mutex<DocumentManager>>, live_region_label: StaticText) {
+	let message = {
+		let dm = doc_manager.lock().unwrap();
+		let Some(tab) = dm.active_tab() else {
+			return;
+		};
+		let section = tab.session.current_section_range(tab.text_ctrl.get_insertion_point());
+		Clipboard::get().set_text(&tab.session.get_text_range(section.start, section.end));
+		if section.whole_document {
+			// TRANSLATORS: Announced after Copy Current Section in a document that isn't divided into sections
+			t("This document has no sections, so the whole document was copied.")
+		} else if section.title.is_empty() {
+			// TRANSLATORS: Announced after Copy Current Section copies a section without a heading
+			t("Section copied.")
+		} else {
+			// TRANSLATORS: Announced after Copy Current Section; {} is the section's heading
+			t("Copied {}.").replace("{}", &section.title)
+		}
+	};
+	speech::announce(live_region_label, &message);
 }
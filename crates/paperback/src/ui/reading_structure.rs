@@ -0,0 +1,102 @@
+//! Exposes headings in the reading control to UI Automation.
+//!
+//! The reading view is a native RichEdit control, and RichEdit's own UIA provider already reports
+//! paragraph styles. Tagging heading paragraphs with the built-in `tomHeading1`..`tomHeading6`
+//! styles makes them show up as `StyleId_Heading1`..`StyleId_Heading6`, so screen readers' own
+//! heading navigation works without a custom provider.
+//!
+//! Links are left alone: `ITextRange2::SetURL` stores the target as hidden text in front of the
+//! link, which would move every later character off the offsets the caret and markers use.
+
+use std::ffi::c_void;
+
+use paperback_core::session::{LineMarker, MarkerTypeFfi};
+use windows::{
+	Win32::{
+		Foundation::{HWND, LPARAM, WPARAM},
+		UI::{
+			Controls::RichEdit::{
+				EM_GETOLEINTERFACE, IRichEditOle, ITextDocument2, tomHeading1, tomResume, tomSuspend,
+			},
+			WindowsAndMessaging::SendMessageW,
+		},
+	},
+	core::Interface,
+};
+use wxdragon::prelude::*;
+
+/// Tags the heading `markers` in `text_ctrl`, which must already hold the document text.
+///
+/// Positions are the same character offsets used for the caret, so this has to run after the
+/// control is filled. Failures are logged and otherwise ignored; the text stays readable either way.
+pub fn expose_document_structure(text_ctrl: TextCtrl, markers: &[LineMarker]) {
+	if markers.is_empty() {
+		return;
+	}
+	let Some(document) = text_document(text_ctrl) else {
+		tracing::warn!("reading control has no TOM document; structure not exposed");
+		return;
+	};
+	unsafe {
+		let _ = document.Undo(tomSuspend.0);
+		let _ = document.Freeze();
+	}
+	let mut failures = 0usize;
+	for marker in markers {
+		if apply_marker(&document, marker).is_err() {
+			failures += 1;
+		}
+	}
+	unsafe {
+		let _ = document.Unfreeze();
+		let _ = document.Undo(tomResume.0);
+	}
+	if failures > 0 {
+		tracing::warn!(failures, total = markers.len(), "some structure markers could not be exposed");
+	}
+}
+
+fn text_document(text_ctrl: TextCtrl) -> Option<ITextDocument2> {
+	let hwnd_ptr = text_ctrl.get_handle();
+	if hwnd_ptr.is_null() {
+		return None;
+	}
+	let mut ole: *mut c_void = std::ptr::null_mut();
+	let ok = unsafe {
+		SendMessageW(
+			HWND(hwnd_ptr),
+			EM_GETOLEINTERFACE,
+			Some(WPARAM(0)),
+			Some(LPARAM(std::ptr::addr_of_mut!(ole) as isize)),
+		)
+	};
+	if ok.0 == 0 || ole.is_null() {
+		return None;
+	}
+	// EM_GETOLEINTERFACE hands back an AddRef'd pointer, which `from_raw` takes ownership of.
+	let rich_edit = unsafe { IRichEditOle::from_raw(ole) };
+	rich_edit.cast::<ITextDocument2>().ok()
+}
+
+fn apply_marker(document: &ITextDocument2, marker: &LineMarker) -> windows::core::Result<()> {
+	let Some(level) = heading_level(marker.mtype) else {
+		return Ok(());
+	};
+	let start = i32::try_from(marker.position).unwrap_or(i32::MAX);
+	let range = unsafe { document.Range2(start, start)? };
+	// tomHeading1 is -2, and each deeper level counts down by one.
+	unsafe { range.GetPara()?.SetStyle(tomHeading1.0 - (level - 1))? };
+	Ok(())
+}
+
+const fn heading_level(mtype: MarkerTypeFfi) -> Option<i32> {
+	match mtype {
+		MarkerTypeFfi::Heading1 => Some(1),
+		MarkerTypeFfi::Heading2 => Some(2),
+		MarkerTypeFfi::Heading3 => Some(3),
+		MarkerTypeFfi::Heading4 => Some(4),
+		MarkerTypeFfi::Heading5 => Some(5),
+		MarkerTypeFfi::Heading6 => Some(6),
+		_ => None,
+	}
+}
@@ -0,0 +1,244 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use paperback_core::config::ConfigManager;
+
+thread_local! {
+	/// User-assigned accelerators by menu ID. An empty string removes the default shortcut.
+	static OVERRIDES: RefCell<BTreeMap<i32, String>> = const { RefCell::new(BTreeMap::new()) };
+	/// Every command that went through `bind` while the current menu bar was built, in menu order,
+	/// with its default (translated) label.
+	static COMMANDS: RefCell<Vec<(i32, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+const WXK_BACK: i32 = 8;
+const WXK_TAB: i32 = 9;
+const WXK_RETURN: i32 = 13;
+const WXK_ESCAPE: i32 = 27;
+const WXK_SPACE: i32 = 32;
+const WXK_DELETE: i32 = 127;
+const WXK_SHIFT: i32 = 306;
+const WXK_ALT: i32 = 307;
+const WXK_CONTROL: i32 = 308;
+const WXK_END: i32 = 312;
+const WXK_HOME: i32 = 313;
+const WXK_LEFT: i32 = 314;
+const WXK_UP: i32 = 315;
+const WXK_RIGHT: i32 = 316;
+const WXK_DOWN: i32 = 317;
+const WXK_INSERT: i32 = 322;
+const WXK_F1: i32 = 340;
+const WXK_F24: i32 = 363;
+const WXK_PAGEUP: i32 = 366;
+const WXK_PAGEDOWN: i32 = 367;
+
+/// A menu command that can be given a custom shortcut.
+#[derive(Clone, Debug)]
+pub struct ShortcutCommand {
+	pub id: i32,
+	/// Menu label without mnemonics or shortcut, e.g. "Next Heading".
+	pub name: String,
+	pub default_shortcut: String,
+}
+
+/// Replace the in-memory overrides with the ones stored in the config and forget the commands
+/// registered by the previous menu bar.
+pub fn load(config: &ConfigManager) {
+	let overrides = config.get_shortcuts().into_iter().filter_map(|(id, accel)| Some((id.parse().ok()?, accel)));
+	OVERRIDES.with(|map| *map.borrow_mut() = overrides.collect());
+	COMMANDS.with(|commands| commands.borrow_mut().clear());
+}
+
+/// Convert edited bindings back into the config representation.
+pub fn to_config(overrides: &BTreeMap<i32, String>) -> BTreeMap<String, String> {
+	overrides.iter().map(|(id, accel)| (id.to_string(), accel.clone())).collect()
+}
+
+/// Record `label` as the default for `id` and return it with the user's shortcut, if any, applied.
+pub fn bind(id: i32, label: &str) -> String {
+	COMMANDS.with(|commands| {
+		let mut commands = commands.borrow_mut();
+		if !commands.iter().any(|(existing, _)| *existing == id) {
+			commands.push((id, label.to_string()));
+		}
+	});
+	apply(id, label)
+}
+
+/// Return `label` with the user's shortcut for `id` swapped in after the tab.
+pub fn apply(id: i32, label: &str) -> String {
+	OVERRIDES.with(|map| map.borrow().get(&id).map_or_else(|| label.to_string(), |accel| with_shortcut(label, accel)))
+}
+
+/// Commands known from the last menu bar build, in menu order.
+pub fn commands() -> Vec<ShortcutCommand> {
+	COMMANDS.with(|commands| {
+		commands
+			.borrow()
+			.iter()
+			.map(|(id, label)| ShortcutCommand {
+				id: *id,
+				name: display_name(label),
+				default_shortcut: shortcut_of(label).to_string(),
+			})
+			.collect()
+	})
+}
+
+pub fn overrides() -> BTreeMap<i32, String> {
+	OVERRIDES.with(|map| map.borrow().clone())
+}
+
+fn with_shortcut(label: &str, accel: &str) -> String {
+	let text = label.split('\t').next().unwrap_or(label);
+	if accel.is_empty() { text.to_string() } else { format!("{text}\t{accel}") }
+}
+
+fn shortcut_of(label: &str) -> &str {
+	label.split_once('\t').map_or("", |(_, accel)| accel)
+}
+
+fn display_name(label: &str) -> String {
+	let text = label.split('\t').next().unwrap_or(label);
+	text.replace("&&", "\u{0}").replace('&', "").replace('\u{0}', "&").trim_end_matches("...").to_string()
+}
+
+/// Canonical form of an accelerator string, used to detect two commands sharing a key.
+/// Modifier order and letter case don't matter: "shift+ctrl+x" and "Ctrl+Shift+X" are equal.
+pub fn normalize(accel: &str) -> String {
+	let accel = accel.trim();
+	if accel.is_empty() {
+		return String::new();
+	}
+	let (modifiers, key) = match accel.strip_suffix("++") {
+		Some(rest) => (rest, "+"),
+		None => accel.rsplit_once('+').unwrap_or(("", accel)),
+	};
+	let mut ctrl = false;
+	let mut raw_ctrl = false;
+	let mut alt = false;
+	let mut shift = false;
+	for modifier in modifiers.split('+').map(str::trim) {
+		match modifier.to_ascii_lowercase().as_str() {
+			"ctrl" | "control" | "cmd" => ctrl = true,
+			"rawctrl" => raw_ctrl = true,
+			"alt" => alt = true,
+			"shift" => shift = true,
+			_ => {}
+		}
+	}
+	let mut parts = Vec::new();
+	if raw_ctrl {
+		parts.push("RawCtrl".to_string());
+	}
+	if ctrl {
+		parts.push("Ctrl".to_string());
+	}
+	if alt {
+		parts.push("Alt".to_string());
+	}
+	if shift {
+		parts.push("Shift".to_string());
+	}
+	let key = key.trim();
+	let mut chars = key.chars();
+	let key = match (chars.next(), chars.next()) {
+		(Some(c), None) => c.to_ascii_uppercase().to_string(),
+		_ => {
+			let mut name = key.to_ascii_lowercase();
+			if let Some(first) = name.get_mut(..1) {
+				first.make_ascii_uppercase();
+			}
+			name
+		}
+	};
+	parts.push(key);
+	parts.join("+")
+}
+
+/// Find another command already using `accel` among the effective bindings.
+pub fn find_conflict(bindings: &[(i32, String)], id: i32, accel: &str) -> Option<i32> {
+	let wanted = normalize(accel);
+	if wanted.is_empty() {
+		return None;
+	}
+	bindings.iter().find(|(other, existing)| *other != id && normalize(existing) == wanted).map(|(other, _)| *other)
+}
+
+/// Build an accelerator string from a key press in the shortcut capture field.
+/// Returns None for lone modifiers, for Tab and Escape so the dialog stays keyboard navigable, and for keys
+/// that can't be used in a menu accelerator.
+pub fn accelerator_from_key(key: i32, ctrl: bool, alt: bool, shift: bool) -> Option<String> {
+	let name = match key {
+		WXK_SHIFT | WXK_ALT | WXK_CONTROL | WXK_TAB | WXK_ESCAPE => return None,
+		WXK_BACK => "Back".to_string(),
+		WXK_RETURN => "Enter".to_string(),
+		WXK_SPACE => "Space".to_string(),
+		WXK_DELETE => "Del".to_string(),
+		WXK_END => "End".to_string(),
+		WXK_HOME => "Home".to_string(),
+		WXK_LEFT => "Left".to_string(),
+		WXK_UP => "Up".to_string(),
+		WXK_RIGHT => "Right".to_string(),
+		WXK_DOWN => "Down".to_string(),
+		WXK_INSERT => "Ins".to_string(),
+		WXK_PAGEUP => "PgUp".to_string(),
+		WXK_PAGEDOWN => "PgDn".to_string(),
+		WXK_F1..=WXK_F24 => format!("F{}", key - WXK_F1 + 1),
+		33..=126 => char::from(u8::try_from(key).ok()?).to_ascii_uppercase().to_string(),
+		_ => return None,
+	};
+	let mut accel = String::new();
+	if ctrl {
+		accel.push_str("Ctrl+");
+	}
+	if alt {
+		accel.push_str("Alt+");
+	}
+	if shift {
+		accel.push_str("Shift+");
+	}
+	accel.push_str(&name);
+	Some(accel)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn override_replaces_only_the_shortcut_suffix() {
+		assert_eq!(with_shortcut("&Next Heading\tH", "F6"), "&Next Heading\tF6");
+		assert_eq!(with_shortcut("&Next Heading\tH", ""), "&Next Heading");
+		assert_eq!(with_shortcut("E&xport to HTML...", "Ctrl+E"), "E&xport to HTML...\tCtrl+E");
+	}
+
+	#[test]
+	fn display_name_strips_mnemonics_and_shortcut() {
+		assert_eq!(display_name("&Next Heading\tH"), "Next Heading");
+		assert_eq!(display_name("&Find...\tCtrl+F"), "Find");
+		assert_eq!(display_name("Save && Close"), "Save & Close");
+	}
+
+	#[test]
+	fn normalize_ignores_modifier_order_and_case() {
+		assert_eq!(normalize("shift+ctrl+x"), normalize("Ctrl+Shift+X"));
+		assert_eq!(normalize("f6"), "F6");
+		assert_eq!(normalize("Ctrl++"), "Ctrl++");
+		assert_ne!(normalize("Shift+H"), normalize("H"));
+	}
+
+	#[test]
+	fn conflict_is_reported_for_another_command_only() {
+		let bindings = vec![(1, "H".to_string()), (2, "Shift+H".to_string()), (3, String::new())];
+		assert_eq!(find_conflict(&bindings, 2, "h"), Some(1));
+		assert_eq!(find_conflict(&bindings, 1, "H"), None);
+		assert_eq!(find_conflict(&bindings, 2, ""), None);
+	}
+
+	#[test]
+	fn key_presses_become_accelerators() {
+		assert_eq!(accelerator_from_key(WXK_F1 + 5, false, false, false).as_deref(), Some("F6"));
+		assert_eq!(accelerator_from_key(i32::from(b'h'), true, false, true).as_deref(), Some("Ctrl+Shift+H"));
+		assert_eq!(accelerator_from_key(WXK_CONTROL, true, false, false), None);
+	}
+}
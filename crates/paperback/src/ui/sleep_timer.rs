@@ -0,0 +1,155 @@
+use std::{
+	sync::{Mutex, MutexGuard, PoisonError},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How long before the timer runs out the reader is warned and offered more time.
+pub const WARNING_SECONDS: i64 = 2 * 60;
+/// Minutes added by the warning dialog's extend button.
+pub const EXTENSION_MINUTES: i32 = 10;
+
+/// The one sleep timer, shared by the menu, the status bar and the countdown tick.
+pub static SLEEP_TIMER: Mutex<SleepTimerController> = Mutex::new(SleepTimerController::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepTimerEvent {
+	/// The timer has just crossed into its last two minutes.
+	Warn,
+	/// The timer has run out and stopped itself.
+	Expire,
+}
+
+/// Countdown state for the sleep timer. Every method takes the current time so the logic does not
+/// depend on the clock; [`now_ms`] supplies it in the app.
+#[derive(Debug, Default)]
+pub struct SleepTimerController {
+	/// When the timer runs out, in milliseconds since the Unix epoch. `None` while it is off.
+	deadline_ms: Option<i64>,
+	warned: bool,
+}
+
+impl SleepTimerController {
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { deadline_ms: None, warned: false }
+	}
+
+	#[must_use]
+	pub const fn is_running(&self) -> bool {
+		self.deadline_ms.is_some()
+	}
+
+	pub fn start(&mut self, now_ms: i64, minutes: i32) {
+		let deadline = now_ms + minutes_to_ms(minutes);
+		self.deadline_ms = Some(deadline);
+		// A timer set for two minutes or less has nothing useful to warn about.
+		self.warned = deadline - now_ms <= WARNING_SECONDS * 1000;
+	}
+
+	pub const fn stop(&mut self) {
+		self.deadline_ms = None;
+		self.warned = false;
+	}
+
+	/// Pushes the deadline back, so the warning can fire again before the new one.
+	pub fn extend(&mut self, minutes: i32) {
+		if let Some(deadline) = self.deadline_ms.as_mut() {
+			*deadline += minutes_to_ms(minutes);
+			self.warned = false;
+		}
+	}
+
+	/// Whole seconds left, or `None` when the timer is off or has run out.
+	#[must_use]
+	pub fn remaining_seconds(&self, now_ms: i64) -> Option<i32> {
+		let remaining_ms = self.deadline_ms? - now_ms;
+		if remaining_ms <= 0 {
+			return None;
+		}
+		Some(i32::try_from(remaining_ms / 1000).unwrap_or(i32::MAX))
+	}
+
+	/// Advances the timer to `now_ms`, returning the warning once when the last two minutes begin
+	/// and the expiry once when time is up.
+	pub fn tick(&mut self, now_ms: i64) -> Option<SleepTimerEvent> {
+		let remaining_ms = self.deadline_ms? - now_ms;
+		if remaining_ms <= 0 {
+			self.stop();
+			return Some(SleepTimerEvent::Expire);
+		}
+		if !self.warned && remaining_ms <= WARNING_SECONDS * 1000 {
+			self.warned = true;
+			return Some(SleepTimerEvent::Warn);
+		}
+		None
+	}
+}
+
+/// Locks the shared timer, recovering it if a panic poisoned the lock.
+pub fn controller() -> MutexGuard<'static, SleepTimerController> {
+	SLEEP_TIMER.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Seconds left on the shared timer, if it is running.
+pub fn remaining_seconds() -> Option<i32> {
+	controller().remaining_seconds(now_ms())
+}
+
+pub fn now_ms() -> i64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).ok().and_then(|d| i64::try_from(d.as_millis()).ok()).unwrap_or(0)
+}
+
+fn minutes_to_ms(minutes: i32) -> i64 {
+	i64::from(minutes) * 60 * 1000
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MINUTE: i64 = 60 * 1000;
+
+	#[test]
+	fn warns_once_two_minutes_before_expiring() {
+		let mut timer = SleepTimerController::new();
+		timer.start(0, 10);
+		assert_eq!(timer.tick(7 * MINUTE), None);
+		assert_eq!(timer.tick(8 * MINUTE), Some(SleepTimerEvent::Warn));
+		assert_eq!(timer.tick(8 * MINUTE + 1000), None);
+		assert_eq!(timer.remaining_seconds(9 * MINUTE), Some(60));
+		assert_eq!(timer.tick(10 * MINUTE), Some(SleepTimerEvent::Expire));
+		assert!(!timer.is_running());
+		assert_eq!(timer.tick(11 * MINUTE), None);
+	}
+
+	#[test]
+	fn extending_moves_the_deadline_and_rearms_the_warning() {
+		let mut timer = SleepTimerController::new();
+		timer.start(0, 10);
+		assert_eq!(timer.tick(9 * MINUTE), Some(SleepTimerEvent::Warn));
+		timer.extend(EXTENSION_MINUTES);
+		assert_eq!(timer.remaining_seconds(9 * MINUTE), Some(11 * 60));
+		assert_eq!(timer.tick(10 * MINUTE), None);
+		assert_eq!(timer.tick(18 * MINUTE), Some(SleepTimerEvent::Warn));
+		assert_eq!(timer.tick(20 * MINUTE), Some(SleepTimerEvent::Expire));
+	}
+
+	#[test]
+	fn short_timers_skip_the_warning() {
+		let mut timer = SleepTimerController::new();
+		timer.start(0, 1);
+		assert_eq!(timer.tick(30 * 1000), None);
+		assert_eq!(timer.tick(MINUTE), Some(SleepTimerEvent::Expire));
+	}
+
+	#[test]
+	fn stopped_timer_reports_nothing() {
+		let mut timer = SleepTimerController::new();
+		timer.start(0, 5);
+		timer.stop();
+		assert_eq!(timer.remaining_seconds(MINUTE), None);
+		assert_eq!(timer.tick(10 * MINUTE), None);
+		timer.extend(5);
+		assert!(!timer.is_running());
+	}
+}
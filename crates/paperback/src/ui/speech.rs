@@ -0,0 +1,29 @@
+//! Text on its way to speech, with the reader's pronunciation rules applied. The document itself is
+//! never changed, so offsets and Find are unaffected.
+
+use std::cell::RefCell;
+
+use paperback_core::{config::ConfigManager, reader_core::pronunciation::PronunciationRules};
+use wxdragon::prelude::*;
+
+thread_local! {
+	/// Rules for the active document, rebuilt when it or the rules change.
+	static RULES: RefCell<PronunciationRules> = RefCell::new(PronunciationRules::default());
+}
+
+/// Compiles the rules for the document at `path`, or only the global ones when none is open.
+pub fn load_rules(config: &ConfigManager, path: Option<&str>) {
+	let rules =
+		path.map_or_else(|| config.get_global_pronunciation_rules(), |path| config.get_pronunciation_rules(path));
+	RULES.with(|current| *current.borrow_mut() = PronunciationRules::new(&rules));
+}
+
+/// `text` as it should be spoken.
+pub fn speakable(text: &str) -> String {
+	RULES.with(|rules| rules.borrow().apply(text))
+}
+
+/// Announces `text` through the live region, pronunciation rules applied.
+pub fn announce(label: StaticText, text: &str) {
+	live_region::announce(label, &speakable(text));
+}
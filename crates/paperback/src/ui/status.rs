@@ -1,33 +1,34 @@
-use std::time::{self, SystemTime};
-
-use paperback_core::session::StatusInfo;
+use paperback_core::{
+	config::ConfigManager,
+	reader_core::{StatusFields, default_status_format, format_status},
+};
 use patois::t;
-use wxdragon::prelude::*;
 
-use super::document_manager::DocumentManager;
+use super::{sleep_timer, url_download};
+
+/// The user's status bar template, or the default one when they haven't set their own.
+pub fn status_format(config: &ConfigManager) -> String {
+	config.get_app_string("status_format", &default_status_format())
+}
+
+/// Fills in the configured status bar template for the caret's place in a document.
+pub fn format_status_text(config: &ConfigManager, fields: &StatusFields) -> String {
+	format_status(&status_format(config), fields, i64::from(config.get_app_int("reading_speed_wpm", 150)))
+}
 
-pub fn format_status_text(info: &StatusInfo) -> String {
-	// TRANSLATORS: Status bar label for the current line number, e.g. "Line 5, Character 120, Reading 45%"
-	let line_label = t("Line");
-	// TRANSLATORS: Status bar label for the current character offset within the line
-	let char_label = t("Character");
-	let reading_label = t("Reading");
-	format!(
-		"{} {}, {} {}, {} {}%",
-		line_label, info.line_number, char_label, info.character_number, reading_label, info.percentage
-	)
+/// Joins a trailing notice onto the status text, without a separator when the template left it empty.
+fn append_notice(base_status: &str, notice: &str) -> String {
+	if base_status.is_empty() { notice.to_string() } else { format!("{base_status} | {notice}") }
 }
 
-pub fn calculate_sleep_timer_remaining(start_ms: i64, duration_minutes: i32) -> i32 {
-	let now = SystemTime::now()
-		.duration_since(time::UNIX_EPOCH)
-		.ok()
-		.and_then(|d| i64::try_from(d.as_millis()).ok())
-		.unwrap_or(0);
-	let elapsed_ms = now - start_ms;
-	let duration_ms = i64::from(duration_minutes) * 60 * 1000;
-	let remaining_ms = duration_ms - elapsed_ms;
-	if remaining_ms < 0 { 0 } else { i32::try_from(remaining_ms / 1000).unwrap_or(i32::MAX) }
+/// Appends the notice shown while a large PDF reads the pages the reader is heading towards.
+pub fn format_loading_status(base_status: &str) -> String {
+	append_notice(base_status, &loading_page_label())
+}
+
+pub fn loading_page_label() -> String {
+	// TRANSLATORS: Status bar text while pages of a large PDF are still being read in the background
+	t("Loading page…")
 }
 
 pub fn format_sleep_timer_status(base_status: &str, remaining_seconds: i32) -> String {
@@ -35,38 +36,21 @@ pub fn format_sleep_timer_status(base_status: &str, remaining_seconds: i32) -> S
 	let seconds = remaining_seconds % 60;
 	// TRANSLATORS: Status bar label prefixed before the remaining sleep timer countdown, e.g. "Sleep timer: 04:32"
 	let sleep_label = t("Sleep timer");
-	format!("{base_status} | {sleep_label}: {minutes:02}:{seconds:02}")
+	append_notice(base_status, &format!("{sleep_label}: {minutes:02}:{seconds:02}"))
 }
 
-pub fn update_status_bar_with_sleep_timer(
-	frame: &Frame,
-	dm: &DocumentManager,
-	sleep_timer_start_ms: i64,
-	sleep_timer_duration_minutes: i32,
-) {
-	if dm.tab_count() == 0 {
-		if sleep_timer_start_ms > 0 {
-			let remaining = calculate_sleep_timer_remaining(sleep_timer_start_ms, sleep_timer_duration_minutes);
-			if remaining > 0 {
-				// TRANSLATORS: Default status bar text when no document is open
-				let status_text = format_sleep_timer_status(&t("Ready"), remaining);
-				frame.set_status_text(&status_text, 0);
-				return;
-			}
-		}
-		frame.set_status_text(&t("Ready"), 0);
-		return;
+/// Appends the sleep timer countdown when a timer is running.
+pub fn with_sleep_timer(status_text: String) -> String {
+	match sleep_timer::remaining_seconds() {
+		Some(remaining) => format_sleep_timer_status(&status_text, remaining),
+		None => status_text,
 	}
-	if let Some(tab) = dm.active_tab() {
-		let position = tab.text_ctrl.get_insertion_point();
-		let status_info = tab.session.get_status_info(position);
-		let mut status_text = format_status_text(&status_info);
-		if sleep_timer_start_ms > 0 {
-			let remaining = calculate_sleep_timer_remaining(sleep_timer_start_ms, sleep_timer_duration_minutes);
-			if remaining > 0 {
-				status_text = format_sleep_timer_status(&status_text, remaining);
-			}
-		}
-		frame.set_status_text(&status_text, 0);
+}
+
+/// Appends how far the document being downloaded has got, when there is one.
+pub fn with_download(status_text: String) -> String {
+	match url_download::status_notice() {
+		Some(notice) => append_notice(&status_text, &notice),
+		None => status_text,
 	}
 }
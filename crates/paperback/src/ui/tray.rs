@@ -66,6 +66,24 @@ fn handle_minimize_to_tray(
 	frame.show(false);
 }
 
+/// Builds the tray menu again in the current language. An icon that isn't showing is just dropped,
+/// to be built when the window next goes to the tray.
+pub fn rebuild_tray_menu(
+	frame: Frame,
+	doc_manager: &Rc<Mutex<DocumentManager>>,
+	tray_state: &Rc<Mutex<Option<TrayState>>>,
+) {
+	let mut tray_state_guard = tray_state.lock().unwrap();
+	let Some(old) = tray_state_guard.take() else {
+		return;
+	};
+	old.icon.remove_icon();
+	old.icon.destroy();
+	if !frame.is_shown() {
+		*tray_state_guard = Some(create_tray_state(frame, Rc::clone(doc_manager), Rc::clone(tray_state)));
+	}
+}
+
 fn create_tray_state(
 	frame: Frame,
 	doc_manager: Rc<Mutex<DocumentManager>>,
@@ -0,0 +1,421 @@
+//! Downloading and installing a new release from inside Paperback, offered when the user checks for
+//! updates themselves. The silent check at startup only ever tells them about one.
+
+use std::{
+	env, fs,
+	io::{self, Read},
+	os::windows::process::CommandExt,
+	path::{Path, PathBuf},
+	process::Command,
+	sync::{Arc, Mutex, PoisonError, atomic::AtomicBool},
+	thread,
+};
+
+use minisign_verify::{PublicKey, Signature};
+use paperback_core::util::zip::extract_zip_to_dir;
+use patois::t;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use wxdragon::prelude::*;
+
+use super::{
+	app::main_window_from_ptr,
+	dialogs::{self, DownloadProgress},
+	help,
+	url_download::{self, DownloadError},
+};
+use crate::config_ext::UpdateChannel;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/trypsynth/paperback/releases/latest";
+const INSTALLER_ASSET: &str = "paperback_setup.exe";
+const ZIP_ASSET: &str = "paperback.zip";
+/// Added to an asset's name for the minisign signature published alongside it.
+const SIGNATURE_SUFFIX: &str = ".minisig";
+/// Added to the name of each file an update replaces. Windows lets the files of a running program
+/// be renamed but not overwritten, so they are moved aside and removed at the next start.
+const REPLACED_SUFFIX: &str = ".replaced";
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Release {
+	version: String,
+	asset: ReleaseAsset,
+}
+
+/// The file of a release for this kind of install, with the SHA-256 GitHub publishes for it and
+/// the address of its minisign signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ReleaseAsset {
+	name: String,
+	url: String,
+	size: Option<u64>,
+	sha256: String,
+	signature_url: String,
+}
+
+/// Looks for a newer stable release on a worker thread, then offers to download and install it.
+/// Falls back to the regular update check when the release can't be read, or publishes no file,
+/// checksum and signature for this kind of install.
+pub fn check_for_update(installer: bool) {
+	thread::spawn(move || {
+		let release = latest_release(installer);
+		wxdragon::call_after(Box::new(move || offer_update(release, installer)));
+		wxdragon::wake_up_idle();
+	});
+}
+
+fn offer_update(release: Result<Option<Release>, DownloadError>, installer: bool) {
+	let Some(window) = main_window_from_ptr() else {
+		return;
+	};
+	let frame = *window.frame();
+	let release = match release {
+		Ok(Some(release)) => release,
+		Ok(None) => {
+			tracing::info!("latest release has no signed download for this install, using the regular check");
+			help::run_ship_shape_check(false, UpdateChannel::Stable);
+			return;
+		}
+		Err(err) => {
+			tracing::warn!(error = ?err, "couldn't read the latest release, using the regular check");
+			help::run_ship_shape_check(false, UpdateChannel::Stable);
+			return;
+		}
+	};
+	let current = env!("CARGO_PKG_VERSION");
+	if !is_newer(&release.version, current) {
+		// TRANSLATORS: Shown after checking for updates when there is no newer version; %s is the running version
+		let message = t("You are running the latest version of Paperback (%s).").replace("%s", current);
+		// TRANSLATORS: Title of the messages shown by Check for Updates
+		show_message(frame, &message, &t("Paperback Update"), MessageDialogStyle::IconInformation);
+		return;
+	}
+	// TRANSLATORS: Asked before downloading an update; the first %s is the new version, the second the running one
+	let template = t("Paperback %s is available. You have %s. Download and install it now?");
+	let question = template.replacen("%s", &release.version, 1).replacen("%s", current, 1);
+	let confirm = MessageDialog::builder(&frame, &question, &t("Paperback Update"))
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	if confirm.show_modal() != ID_YES {
+		return;
+	}
+	tracing::info!(version = %release.version, asset = %release.asset.name, "downloading update");
+	let progress = Arc::new(Mutex::new(DownloadProgress::default()));
+	let cancel = Arc::new(AtomicBool::new(false));
+	{
+		let (progress, cancel, asset) = (Arc::clone(&progress), Arc::clone(&cancel), release.asset.clone());
+		thread::spawn(move || {
+			let result = download_asset(&asset, &progress, &cancel);
+			progress.lock().unwrap_or_else(PoisonError::into_inner).result = Some(result);
+		});
+	}
+	// TRANSLATORS: Shown above the progress bar while an update downloads; %s is the new version
+	let message = t("Downloading Paperback %s…").replace("%s", &release.version);
+	match dialogs::show_download_progress_dialog(&frame, &t("Paperback Update"), &message, &progress, &cancel) {
+		Ok(path) => install(frame, &path, installer, &release.version),
+		Err(DownloadError::Cancelled) => tracing::info!("update download cancelled"),
+		Err(err) => {
+			tracing::warn!(error = ?err, "update download failed");
+			show_message(frame, &error_message(&err), &t("Error"), MessageDialogStyle::IconError);
+		}
+	}
+}
+
+fn show_message(frame: Frame, message: &str, title: &str, icon: MessageDialogStyle) {
+	MessageDialog::builder(&frame, message, title)
+		.with_style(MessageDialogStyle::OK | icon | MessageDialogStyle::Centre)
+		.build()
+		.show_modal();
+}
+
+/// What went wrong with an update download, worded for an update rather than a document.
+fn error_message(err: &DownloadError) -> String {
+	match err {
+		// TRANSLATORS: Error shown when an update fails to download; %s is the technical reason
+		DownloadError::Network(reason) => t("Could not download the update: %s").replace("%s", reason),
+		// TRANSLATORS: Error shown when a downloaded update can't be saved; %s is the technical reason
+		DownloadError::Io(err) => t("Could not save the update: %s").replace("%s", &err.to_string()),
+		other => other.message(),
+	}
+}
+
+fn latest_release(installer: bool) -> Result<Option<Release>, DownloadError> {
+	let json = get_text(LATEST_RELEASE_URL)?;
+	Ok(parse_release(&json, if installer { INSTALLER_ASSET } else { ZIP_ASSET }))
+}
+
+fn get_text(url: &str) -> Result<String, DownloadError> {
+	let mut text = String::new();
+	url_download::get(url)?
+		.into_body()
+		.into_reader()
+		.read_to_string(&mut text)
+		.map_err(|err| DownloadError::Network(err.to_string()))?;
+	Ok(text)
+}
+
+/// The version and the file named `asset_name` in GitHub's description of a release, or `None`
+/// when the file is missing, or has no SHA-256 or `.minisig` signature to check it against.
+fn parse_release(json: &str, asset_name: &str) -> Option<Release> {
+	let release: Value = serde_json::from_str(json).ok()?;
+	let version = release.get("tag_name")?.as_str()?.trim_start_matches('v').to_string();
+	let assets = release.get("assets")?.as_array()?;
+	let find = |name: &str| assets.iter().find(|asset| asset.get("name").and_then(Value::as_str) == Some(name));
+	let asset = find(asset_name)?;
+	let signature_url =
+		find(&format!("{asset_name}{SIGNATURE_SUFFIX}"))?.get("browser_download_url")?.as_str()?.to_string();
+	let sha256 = asset.get("digest")?.as_str()?.strip_prefix("sha256:")?.to_ascii_lowercase();
+	let url = asset.get("browser_download_url")?.as_str()?.to_string();
+	let size = asset.get("size").and_then(Value::as_u64);
+	Some(Release { version, asset: ReleaseAsset { name: asset_name.to_string(), url, size, sha256, signature_url } })
+}
+
+/// Whether the dotted version `candidate` comes after `current`. Anything after a `-` or `+` is
+/// ignored, and missing parts count as zero, so "1.0" and "1.0.0" are the same.
+fn is_newer(candidate: &str, current: &str) -> bool {
+	let parts = |version: &str| {
+		let mut parts: Vec<u64> = version
+			.split(['-', '+'])
+			.next()
+			.unwrap_or_default()
+			.split('.')
+			.map(|part| part.trim().parse().unwrap_or(0))
+			.collect();
+		while parts.last() == Some(&0) {
+			parts.pop();
+		}
+		parts
+	};
+	parts(candidate) > parts(current)
+}
+
+/// Downloads `asset` into the temp folder and checks it against its published SHA-256 and its
+/// minisign signature. The checksum only catches damage, since it comes from the same place as the
+/// file; the signature, checked with the key built into Paperback, is what shows the release is
+/// genuine. A file that fails either is deleted; one cut short is already gone.
+fn download_asset(
+	asset: &ReleaseAsset,
+	progress: &Mutex<DownloadProgress>,
+	cancel: &AtomicBool,
+) -> Result<PathBuf, DownloadError> {
+	let dir = env::temp_dir().join("paperback-update");
+	fs::create_dir_all(&dir)?;
+	let path = dir.join(&asset.name);
+	let response = url_download::get(&asset.url)?;
+	let received = url_download::stream_to_file(response.into_body().into_reader(), &path, cancel, |received| {
+		let mut state = progress.lock().unwrap_or_else(PoisonError::into_inner);
+		state.received = received;
+		state.total = asset.size;
+	})?;
+	let matches = sha256_file(&path).map(|digest| digest == asset.sha256);
+	if !matches.as_ref().is_ok_and(|&matches| matches) {
+		tracing::warn!(received, expected = %asset.sha256, "update failed its checksum");
+		let _ = fs::remove_file(&path);
+		matches?;
+		return Err(DownloadError::ChecksumMismatch);
+	}
+	let signature = get_text(&asset.signature_url);
+	let verified = signature.and_then(|signature| {
+		let contents = fs::read(&path)?;
+		verify_signature(&contents, &signature).map_err(|_| DownloadError::SignatureMismatch)
+	});
+	if let Err(err) = verified {
+		tracing::warn!(error = ?err, "update failed its signature check");
+		let _ = fs::remove_file(&path);
+		return Err(err);
+	}
+	Ok(path)
+}
+
+/// Checks `contents` against the text of its `.minisig` file, signed with Paperback's release key.
+fn verify_signature(contents: &[u8], signature: &str) -> Result<(), minisign_verify::Error> {
+	let key = PublicKey::from_base64(help::PAPERBACK_MINISIGN_KEY)?;
+	let signature = Signature::decode(signature)?;
+	key.verify(contents, &signature, false)
+}
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+	let mut file = fs::File::open(path)?;
+	let mut hasher = Sha256::new();
+	let mut buffer = vec![0; 64 * 1024];
+	loop {
+		let read = file.read(&mut buffer)?;
+		if read == 0 {
+			break;
+		}
+		hasher.update(&buffer[..read]);
+	}
+	Ok(hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Runs the installer, which needs Paperback closed to replace it, or unpacks the zip over this
+/// copy and offers to restart into the new version.
+fn install(frame: Frame, path: &Path, installer: bool, version: &str) {
+	if installer {
+		match Command::new(path).spawn() {
+			Ok(_) => {
+				frame.close(true);
+			}
+			Err(err) => {
+				tracing::warn!(path = %path.display(), error = %err, "failed to start the update installer");
+				// TRANSLATORS: Error shown when a downloaded update installer can't be started; %s is the technical reason
+				let message = t("Could not start the installer: %s").replace("%s", &err.to_string());
+				show_message(frame, &message, &t("Error"), MessageDialogStyle::IconError);
+			}
+		}
+		return;
+	}
+	let Some(app_dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) else {
+		return;
+	};
+	let result = extract_zip_to_dir(path, &app_dir, move_aside);
+	let _ = fs::remove_file(path);
+	if let Err(err) = result {
+		tracing::warn!(dir = %app_dir.display(), error = %err, "failed to unpack the update");
+		// TRANSLATORS: Error shown when a downloaded update can't be unpacked next to Paperback; %s is the technical reason
+		let message = t("Could not install the update: %s").replace("%s", &err.to_string());
+		show_message(frame, &message, &t("Error"), MessageDialogStyle::IconError);
+		return;
+	}
+	tracing::info!(version, "update installed");
+	// TRANSLATORS: Asked once an update has been unpacked; %s is the new version
+	let question = t("Paperback has been updated to %s. Restart now to use it?").replace("%s", version);
+	let restart_now = MessageDialog::builder(&frame, &question, &t("Paperback Update"))
+		.with_style(MessageDialogStyle::YesNo | MessageDialogStyle::IconQuestion | MessageDialogStyle::Centre)
+		.build();
+	if restart_now.show_modal() == ID_YES {
+		restart(frame);
+	}
+}
+
+/// Renames a file the update is about to replace, since Windows won't overwrite a running program
+/// or a library it has loaded.
+fn move_aside(path: &Path) -> io::Result<()> {
+	if !path.exists() {
+		return Ok(());
+	}
+	let mut aside = path.as_os_str().to_owned();
+	aside.push(REPLACED_SUFFIX);
+	let _ = fs::remove_file(&aside);
+	fs::rename(path, aside)
+}
+
+/// Removes the files an update moved aside, now that the copy using them has exited.
+pub fn remove_replaced_files() {
+	fn remove_in(dir: &Path) {
+		let Ok(entries) = fs::read_dir(dir) else {
+			return;
+		};
+		for entry in entries.flatten() {
+			let path = entry.path();
+			if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+				remove_in(&path);
+			} else if path.to_string_lossy().ends_with(REPLACED_SUFFIX)
+				&& let Err(err) = fs::remove_file(&path)
+			{
+				tracing::warn!(path = %path.display(), error = %err, "failed to remove a file replaced by an update");
+			}
+		}
+	}
+	if let Some(dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+		remove_in(&dir);
+	}
+}
+
+/// Starts the new version once this one has exited, so it isn't turned away as a second instance.
+fn restart(frame: Frame) {
+	let Ok(exe) = env::current_exe() else {
+		return;
+	};
+	// Pinging waits a couple of seconds without needing a console, which `timeout` does.
+	let command = format!("/C ping -n 3 127.0.0.1 >NUL & start \"\" \"{}\"", exe.display());
+	match Command::new("cmd").raw_arg(command).creation_flags(CREATE_NO_WINDOW).spawn() {
+		Ok(_) => {
+			frame.close(true);
+		}
+		Err(err) => tracing::warn!(error = %err, "failed to schedule the restart after updating"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	const RELEASE_JSON: &str = r#"{
+		"tag_name": "v0.9.0",
+		"assets": [
+			{
+				"name": "paperback.zip",
+				"size": 1234,
+				"digest": "sha256:ABCDEF0123",
+				"browser_download_url": "https://github.com/trypsynth/paperback/releases/download/v0.9.0/paperback.zip"
+			},
+			{
+				"name": "paperback.zip.minisig",
+				"size": 300,
+				"browser_download_url": "https://github.com/trypsynth/paperback/releases/download/v0.9.0/paperback.zip.minisig"
+			},
+			{
+				"name": "paperback_setup.exe",
+				"size": 5678,
+				"digest": null,
+				"browser_download_url": "https://github.com/trypsynth/paperback/releases/download/v0.9.0/paperback_setup.exe"
+			}
+		]
+	}"#;
+
+	#[test]
+	fn parse_release_finds_the_asset_and_its_checksum() {
+		let release = parse_release(RELEASE_JSON, ZIP_ASSET).unwrap();
+		assert_eq!(release.version, "0.9.0");
+		assert_eq!(
+			release.asset,
+			ReleaseAsset {
+				name: "paperback.zip".to_string(),
+				url: "https://github.com/trypsynth/paperback/releases/download/v0.9.0/paperback.zip".to_string(),
+				size: Some(1234),
+				sha256: "abcdef0123".to_string(),
+				signature_url: "https://github.com/trypsynth/paperback/releases/download/v0.9.0/paperback.zip.minisig"
+					.to_string(),
+			}
+		);
+	}
+
+	#[test]
+	fn parse_release_skips_an_asset_without_a_checksum_or_signature() {
+		assert_eq!(parse_release(RELEASE_JSON, INSTALLER_ASSET), None);
+		let unsigned = RELEASE_JSON.replace("paperback.zip.minisig", "paperback.zip.sig");
+		assert_eq!(parse_release(&unsigned, ZIP_ASSET), None);
+		assert_eq!(parse_release(RELEASE_JSON, "paperback.dmg"), None);
+		assert_eq!(parse_release("not json", ZIP_ASSET), None);
+	}
+
+	#[rstest]
+	#[case("0.9.0", "0.8.5", true)]
+	#[case("0.10.0", "0.9.0", true)]
+	#[case("0.9.0", "0.9.0", false)]
+	#[case("0.9", "0.9.0", false)]
+	#[case("0.8.5", "0.9.0", false)]
+	#[case("1.0.0-beta", "0.9.9", true)]
+	fn is_newer_compares_each_part_as_a_number(#[case] candidate: &str, #[case] current: &str, #[case] newer: bool) {
+		assert_eq!(is_newer(candidate, current), newer);
+	}
+
+	#[test]
+	fn verify_signature_rejects_anything_but_a_release_signature() {
+		assert!(verify_signature(b"abc", "not a signature").is_err());
+		let forged = "untrusted comment: signature\nRWQasnbWXwK2dgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==\ntrusted comment: timestamp:0\nAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==\n";
+		assert!(verify_signature(b"abc", forged).is_err());
+	}
+
+	#[test]
+	fn sha256_file_matches_the_published_form() {
+		let path = env::temp_dir().join(format!("paperback_update_hash_{}", std::process::id()));
+		fs::write(&path, b"abc").unwrap();
+		let digest = sha256_file(&path).unwrap();
+		let _ = fs::remove_file(&path);
+		assert_eq!(digest, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+	}
+}
@@ -0,0 +1,501 @@
+use std::{
+	fs,
+	io::{self, Read, Write},
+	path::{Path, PathBuf},
+	sync::{
+		Arc, Mutex, PoisonError,
+		atomic::{AtomicBool, Ordering},
+	},
+	time::{Duration, Instant, SystemTime},
+};
+
+use paperback_core::{parser::parser_supports_extension, util::text::url_decode, version};
+use patois::t;
+use ureq::ResponseExt;
+
+use super::app::main_window_from_ptr;
+use crate::config_ext::url_cache_dir;
+
+/// Downloads are dropped, least recently opened first, once the cache holds more than this.
+const CACHE_MAX_BYTES: u64 = 512 * 1024 * 1024;
+/// How often the status bar is refreshed while a download runs.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Name a download is written under until it is complete.
+const PARTIAL_FILE_NAME: &str = ".download.part";
+
+/// The download running in the background. Only one runs at a time.
+static ACTIVE: Mutex<Option<ActiveDownload>> = Mutex::new(None);
+
+struct ActiveDownload {
+	received: u64,
+	total: Option<u64>,
+	cancel: Arc<AtomicBool>,
+}
+
+#[derive(Debug)]
+pub enum DownloadError {
+	Cancelled,
+	/// The server answered with an HTTP error status.
+	Status(u16),
+	Network(String),
+	/// The connection broke off partway through, after this many bytes had arrived.
+	Interrupted {
+		received: u64,
+		reason: String,
+	},
+	Io(io::Error),
+	/// The file arrived, but doesn't match the checksum published for it.
+	ChecksumMismatch,
+	/// The file arrived, but its signature is missing, malformed or not made with Paperback's key.
+	SignatureMismatch,
+	/// The link points at something other than a document, with the content type it was served as.
+	Unsupported(String),
+}
+
+impl DownloadError {
+	pub fn message(&self) -> String {
+		match self {
+			// TRANSLATORS: Announced when a download started with Open URL is cancelled
+			Self::Cancelled => t("Download cancelled."),
+			// TRANSLATORS: Error shown when the server refuses a download; %d is the HTTP status code, e.g. 404
+			Self::Status(code) => t("The server returned an error (HTTP %d).").replace("%d", &code.to_string()),
+			// TRANSLATORS: Error shown when a download fails to connect or breaks off; %s is the technical reason
+			Self::Network(reason) => t("Could not download the document: %s").replace("%s", reason),
+			Self::Interrupted { received, reason } => {
+				// TRANSLATORS: Error shown when a download breaks off partway; the first %s is how much arrived, e.g. "3.2 MB", the second the technical reason
+				t("The download stopped after %s: %s")
+					.replacen("%s", &format_size(*received), 1)
+					.replacen("%s", reason, 1)
+			}
+			// TRANSLATORS: Error shown when a downloaded document can't be saved to the cache folder; %s is the technical reason
+			Self::Io(err) => t("Could not save the downloaded document: %s").replace("%s", &err.to_string()),
+			Self::Unsupported(content_type) => {
+				// TRANSLATORS: Error shown when a link doesn't lead to a document Paperback can read; %s is the content type the server sent, e.g. "image/png"
+				t("The link does not point to a supported document (%s).").replace("%s", content_type)
+			}
+			// TRANSLATORS: Error shown when a downloaded file doesn't match the checksum published with it
+			Self::ChecksumMismatch => t("The downloaded file is damaged: it doesn't match its published checksum."),
+			// TRANSLATORS: Error shown when a downloaded update isn't signed with Paperback's release key, so it isn't installed
+			Self::SignatureMismatch => t("The downloaded file failed its signature check, so it wasn't installed."),
+		}
+	}
+}
+
+impl From<io::Error> for DownloadError {
+	fn from(err: io::Error) -> Self {
+		Self::Io(err)
+	}
+}
+
+/// Whether `text` is an http or https address.
+pub fn is_url(text: &str) -> bool {
+	let text = text.trim();
+	["http://", "https://"]
+		.iter()
+		.any(|scheme| text.get(..scheme.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme)))
+}
+
+pub fn is_downloading() -> bool {
+	ACTIVE.lock().unwrap_or_else(PoisonError::into_inner).is_some()
+}
+
+/// Asks the running download to stop. It finishes with [`DownloadError::Cancelled`].
+pub fn cancel() {
+	if let Some(active) = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner).as_ref() {
+		active.cancel.store(true, Ordering::SeqCst);
+	}
+}
+
+/// Status bar notice for the running download, e.g. "Downloading: 42%".
+pub fn status_notice() -> Option<String> {
+	let guard = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+	let active = guard.as_ref()?;
+	// TRANSLATORS: Status bar label before the progress of a download started with Open URL, e.g. "Downloading: 42%"
+	let label = t("Downloading");
+	Some(match active.total.filter(|&total| total > 0) {
+		Some(total) => format!("{label}: {}%", active.received.min(total) * 100 / total),
+		None => format!("{label}: {}", format_size(active.received)),
+	})
+}
+
+/// Downloads `url` on a worker thread, then hands the result to the main window to open.
+pub fn start(url: String) {
+	let cancel = Arc::new(AtomicBool::new(false));
+	*ACTIVE.lock().unwrap_or_else(PoisonError::into_inner) =
+		Some(ActiveDownload { received: 0, total: None, cancel: Arc::clone(&cancel) });
+	refresh_status_bar();
+	std::thread::spawn(move || {
+		tracing::info!(url = %url, "downloading document");
+		let mut last_refresh = Instant::now();
+		let result = download(&url, &url_cache_dir(), &cancel, |received, total| {
+			if let Some(active) = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner).as_mut() {
+				active.received = received;
+				active.total = total;
+			}
+			if last_refresh.elapsed() >= PROGRESS_INTERVAL {
+				last_refresh = Instant::now();
+				refresh_status_bar();
+			}
+		});
+		if let Err(err) = &result {
+			tracing::warn!(url = %url, error = ?err, "download failed");
+		}
+		*ACTIVE.lock().unwrap_or_else(PoisonError::into_inner) = None;
+		wxdragon::call_after(Box::new(move || {
+			if let Some(window) = main_window_from_ptr() {
+				window.finish_url_download(&url, result);
+			}
+		}));
+		wxdragon::wake_up_idle();
+	});
+}
+
+fn refresh_status_bar() {
+	wxdragon::call_after(Box::new(|| {
+		if let Some(window) = main_window_from_ptr() {
+			window.refresh_status_bar();
+		}
+	}));
+	wxdragon::wake_up_idle();
+}
+
+/// The downloaded copy of `url`, if it is still in the cache. Marks it as recently used.
+pub fn cached_file(url: &str) -> Option<PathBuf> {
+	let entry_dir = url_cache_dir().join(entry_name(url));
+	let file = fs::read_dir(entry_dir)
+		.ok()?
+		.flatten()
+		.find(|entry| entry.file_name() != PARTIAL_FILE_NAME && entry.file_type().is_ok_and(|kind| kind.is_file()))?
+		.path();
+	if let Ok(handle) = fs::File::options().append(true).open(&file) {
+		let _ = handle.set_modified(SystemTime::now());
+	}
+	Some(file)
+}
+
+/// Folder in the cache holding the copy of `url`, named by an FNV-1a hash so it stays the same
+/// across runs and Rust versions.
+fn entry_name(url: &str) -> String {
+	let hash = url
+		.bytes()
+		.fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+	format!("{hash:016x}")
+}
+
+/// Downloads `url` into its folder under `cache_dir`, following redirects, and returns the file
+/// written. Anything already cached for the address is replaced once the new copy is complete.
+fn download(
+	url: &str,
+	cache_dir: &Path,
+	cancel: &AtomicBool,
+	mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, DownloadError> {
+	let response = get(url)?;
+	let header = |name: &str| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+	let final_url = response.get_uri().to_string();
+	let content_type = header("content-type").unwrap_or_default();
+	let total = header("content-length").and_then(|length| length.trim().parse().ok());
+	let name = document_file_name(&final_url, header("content-disposition").as_deref(), &content_type)?;
+	let entry_dir = cache_dir.join(entry_name(url));
+	fs::create_dir_all(&entry_dir)?;
+	let partial = entry_dir.join(PARTIAL_FILE_NAME);
+	stream_to_file(response.into_body().into_reader(), &partial, cancel, |received| on_progress(received, total))?;
+	for entry in fs::read_dir(&entry_dir)?.flatten() {
+		if entry.file_name() != PARTIAL_FILE_NAME {
+			let _ = fs::remove_file(entry.path());
+		}
+	}
+	let path = entry_dir.join(name);
+	fs::rename(&partial, &path)?;
+	prune_cache(cache_dir, CACHE_MAX_BYTES, &entry_dir);
+	Ok(path)
+}
+
+/// Sends a GET for `url` as Paperback, following redirects.
+pub fn get(url: &str) -> Result<ureq::http::Response<ureq::Body>, DownloadError> {
+	ureq::get(url).header("User-Agent", &version::user_agent()).call().map_err(|err| match err {
+		ureq::Error::StatusCode(code) => DownloadError::Status(code),
+		other => DownloadError::Network(other.to_string()),
+	})
+}
+
+/// Writes everything `reader` gives into a new file at `path`, calling `on_progress` with the bytes
+/// received so far after each chunk, and returns how many there were. The file is removed again
+/// when the download is cancelled or fails, so nothing half-written is left behind.
+pub fn stream_to_file(
+	mut reader: impl Read,
+	path: &Path,
+	cancel: &AtomicBool,
+	mut on_progress: impl FnMut(u64),
+) -> Result<u64, DownloadError> {
+	let mut file = fs::File::create(path)?;
+	let mut buffer = vec![0; CHUNK_SIZE];
+	let mut received = 0;
+	let result = loop {
+		if cancel.load(Ordering::SeqCst) {
+			break Err(DownloadError::Cancelled);
+		}
+		let read = match reader.read(&mut buffer) {
+			Ok(0) => break Ok(received),
+			Ok(read) => read,
+			Err(err) => break Err(DownloadError::Interrupted { received, reason: err.to_string() }),
+		};
+		if let Err(err) = file.write_all(&buffer[..read]) {
+			break Err(err.into());
+		}
+		received += read as u64;
+		on_progress(received);
+	};
+	drop(file);
+	if result.is_err() {
+		let _ = fs::remove_file(path);
+	}
+	result
+}
+
+/// Name to save a download under: the one the server suggests, else the last part of the address,
+/// given the extension its content type calls for when the name has none Paperback reads. Fails
+/// for content that is plainly not a document, such as images; text and unlabelled data are let
+/// through so the Open As dialog can ask what they are.
+fn document_file_name(final_url: &str, disposition: Option<&str>, content_type: &str) -> Result<String, DownloadError> {
+	let name = disposition
+		.and_then(file_name_from_disposition)
+		.or_else(|| file_name_from_url(final_url))
+		.map(|name| sanitize_file_name(&name))
+		.filter(|name| !name.is_empty())
+		.unwrap_or_else(|| "document".to_string());
+	let extension = Path::new(&name).extension().map(|ext| ext.to_string_lossy().into_owned()).unwrap_or_default();
+	if parser_supports_extension(&extension) {
+		return Ok(name);
+	}
+	let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+	if let Some(extension) = extension_for_content_type(&mime) {
+		return Ok(Path::new(&name).with_extension(extension).to_string_lossy().into_owned());
+	}
+	if mime.is_empty() || mime.starts_with("text/") || mime == "application/octet-stream" {
+		return Ok(name);
+	}
+	Err(DownloadError::Unsupported(mime))
+}
+
+fn extension_for_content_type(mime: &str) -> Option<&'static str> {
+	let extension = match mime {
+		"application/pdf" => "pdf",
+		"application/epub+zip" => "epub",
+		"text/html" => "html",
+		"application/xhtml+xml" => "xhtml",
+		"text/plain" => "txt",
+		"text/markdown" | "text/x-markdown" => "md",
+		"application/rtf" | "text/rtf" => "rtf",
+		"application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+		"application/msword" => "doc",
+		"application/vnd.openxmlformats-officedocument.presentationml.presentation" => "pptx",
+		"application/vnd.ms-powerpoint" => "ppt",
+		"application/vnd.oasis.opendocument.text" => "odt",
+		"application/vnd.oasis.opendocument.presentation" => "odp",
+		"application/x-fictionbook+xml" => "fb2",
+		"application/x-mobipocket-ebook" => "mobi",
+		"application/vnd.ms-htmlhelp" | "application/x-chm" => "chm",
+		"application/zip" | "application/x-zip-compressed" => "zip",
+		_ => return None,
+	};
+	parser_supports_extension(extension).then_some(extension)
+}
+
+/// The file name in a `Content-Disposition` header, preferring the UTF-8 `filename*` form.
+fn file_name_from_disposition(header: &str) -> Option<String> {
+	let mut plain = None;
+	for param in header.split(';').map(str::trim) {
+		let Some((key, value)) = param.split_once('=') else {
+			continue;
+		};
+		match key.trim().to_ascii_lowercase().as_str() {
+			"filename*" => {
+				let encoded = value.trim().trim_matches('"');
+				let name = encoded.split_once("''").map_or(encoded, |(_, name)| name);
+				return Some(url_decode(name)).filter(|name| !name.is_empty());
+			}
+			"filename" => plain = Some(value.trim().trim_matches('"').to_string()),
+			_ => {}
+		}
+	}
+	plain.filter(|name| !name.is_empty())
+}
+
+/// The last segment of an address's path, without the query or fragment.
+fn file_name_from_url(url: &str) -> Option<String> {
+	let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+	let path = without_scheme.split(['?', '#']).next()?;
+	let (_, path) = path.split_once('/')?;
+	let segment = path.rsplit('/').next()?;
+	Some(url_decode(segment)).filter(|name| !name.is_empty())
+}
+
+/// Drops characters a file name can't hold on any platform, along with any folder part.
+pub fn sanitize_file_name(name: &str) -> String {
+	let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+	let cleaned: String =
+		name.chars().filter(|&ch| !ch.is_control() && !matches!(ch, '<' | '>' | ':' | '"' | '|' | '?' | '*')).collect();
+	cleaned.trim().trim_matches('.').to_string()
+}
+
+/// Removes cached downloads, least recently used first, until the cache fits in `max_bytes`.
+/// `keep` is never removed, even when it alone is over the limit.
+fn prune_cache(cache_dir: &Path, max_bytes: u64, keep: &Path) {
+	let Ok(entries) = fs::read_dir(cache_dir) else {
+		return;
+	};
+	let mut cached: Vec<(PathBuf, u64, SystemTime)> = entries
+		.flatten()
+		.filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+		.map(|entry| {
+			let files: Vec<fs::Metadata> = fs::read_dir(entry.path())
+				.into_iter()
+				.flatten()
+				.flatten()
+				.filter_map(|file| file.metadata().ok())
+				.collect();
+			let size = files.iter().map(fs::Metadata::len).sum();
+			let used = files.iter().filter_map(|file| file.modified().ok()).max().unwrap_or(SystemTime::UNIX_EPOCH);
+			(entry.path(), size, used)
+		})
+		.collect();
+	let mut total: u64 = cached.iter().map(|(_, size, _)| size).sum();
+	cached.sort_by_key(|(_, _, used)| *used);
+	for (dir, size, _) in cached {
+		if total <= max_bytes {
+			break;
+		}
+		if dir == keep {
+			continue;
+		}
+		if fs::remove_dir_all(&dir).is_ok() {
+			total -= size;
+		}
+	}
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: u64) -> String {
+	if bytes >= 1024 * 1024 {
+		format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+	} else {
+		format!("{} KB", bytes.div_ceil(1024))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	#[rstest]
+	#[case("https://example.com/book.epub", true)]
+	#[case("  HTTP://example.com/", true)]
+	#[case("ftp://example.com/book.epub", false)]
+	#[case("C:\\books\\book.epub", false)]
+	#[case("clipboard://2024-05-03-14-22", false)]
+	fn is_url_accepts_web_addresses_only(#[case] text: &str, #[case] expected: bool) {
+		assert_eq!(is_url(text), expected);
+	}
+
+	#[rstest]
+	#[case("attachment; filename=\"report.pdf\"", Some("report.pdf"))]
+	#[case("attachment; filename=plain.txt", Some("plain.txt"))]
+	#[case(
+		"attachment; filename=\"fallback.pdf\"; filename*=UTF-8''%D0%BA%D0%BD%D0%B8%D0%B3%D0%B0.epub",
+		Some("книга.epub")
+	)]
+	#[case("inline", None)]
+	fn file_name_from_disposition_prefers_the_encoded_name(#[case] header: &str, #[case] expected: Option<&str>) {
+		assert_eq!(file_name_from_disposition(header).as_deref(), expected);
+	}
+
+	#[rstest]
+	#[case("https://example.com/files/My%20Book.epub?download=1#top", Some("My Book.epub"))]
+	#[case("https://example.com/", None)]
+	#[case("https://example.com", None)]
+	fn file_name_from_url_takes_the_last_path_segment(#[case] url: &str, #[case] expected: Option<&str>) {
+		assert_eq!(file_name_from_url(url).as_deref(), expected);
+	}
+
+	#[test]
+	fn document_file_name_uses_the_content_type_when_the_name_has_no_document_extension() {
+		let name = document_file_name("https://example.com/get.php?id=3", None, "application/pdf").unwrap();
+		assert_eq!(name, "get.pdf");
+		let name = document_file_name("https://example.com/article", None, "text/html; charset=utf-8").unwrap();
+		assert_eq!(name, "article.html");
+		let name = document_file_name("https://example.com/get", Some("attachment; filename=\"../x/novel.epub\""), "")
+			.unwrap();
+		assert_eq!(name, "novel.epub");
+	}
+
+	#[test]
+	fn document_file_name_rejects_content_that_is_not_a_document() {
+		let err = document_file_name("https://example.com/photo", None, "image/png").unwrap_err();
+		assert!(matches!(err, DownloadError::Unsupported(mime) if mime == "image/png"));
+		assert_eq!(document_file_name("https://example.com/data", None, "application/octet-stream").unwrap(), "data");
+	}
+
+	/// Gives `data`, then fails the way a dropped connection does.
+	struct BrokenReader<'a>(&'a [u8]);
+
+	impl Read for BrokenReader<'_> {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			if self.0.is_empty() {
+				return Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"));
+			}
+			let read = self.0.len().min(buf.len());
+			buf[..read].copy_from_slice(&self.0[..read]);
+			self.0 = &self.0[read..];
+			Ok(read)
+		}
+	}
+
+	#[test]
+	fn stream_to_file_removes_the_file_and_reports_the_bytes_reached_when_the_connection_drops() {
+		let path = std::env::temp_dir().join(format!("paperback_stream_{}.part", std::process::id()));
+		let mut progress = Vec::new();
+		let err = stream_to_file(BrokenReader(&[7; 100]), &path, &AtomicBool::new(false), |received| {
+			progress.push(received);
+		})
+		.unwrap_err();
+		assert!(matches!(err, DownloadError::Interrupted { received: 100, .. }));
+		assert_eq!(progress, [100]);
+		assert!(!path.exists());
+		let err = stream_to_file(&b"data"[..], &path, &AtomicBool::new(true), |_| {}).unwrap_err();
+		assert!(matches!(err, DownloadError::Cancelled));
+		assert!(!path.exists());
+		assert_eq!(stream_to_file(&b"data"[..], &path, &AtomicBool::new(false), |_| {}).unwrap(), 4);
+		assert_eq!(fs::read(&path).unwrap(), b"data");
+		let _ = fs::remove_file(&path);
+	}
+
+	#[test]
+	fn entry_names_are_stable_per_address() {
+		assert_eq!(entry_name("https://example.com/a.pdf"), entry_name("https://example.com/a.pdf"));
+		assert_ne!(entry_name("https://example.com/a.pdf"), entry_name("https://example.com/b.pdf"));
+	}
+
+	#[test]
+	fn prune_cache_drops_the_least_recently_used_entries() {
+		let cache = std::env::temp_dir().join(format!("paperback_url_cache_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&cache);
+		let now = SystemTime::now();
+		for (name, age) in [("old", 300), ("middle", 200), ("new", 100)] {
+			let dir = cache.join(name);
+			fs::create_dir_all(&dir).unwrap();
+			let file = dir.join("doc.txt");
+			fs::write(&file, vec![b'x'; 100]).unwrap();
+			let handle = fs::File::options().append(true).open(&file).unwrap();
+			handle.set_modified(now - Duration::from_secs(age)).unwrap();
+		}
+		prune_cache(&cache, 150, &cache.join("middle"));
+		assert!(!cache.join("old").exists());
+		assert!(cache.join("middle").exists());
+		assert!(!cache.join("new").exists());
+		let _ = fs::remove_dir_all(&cache);
+	}
+}
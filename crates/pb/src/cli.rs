@@ -5,7 +5,7 @@ use clap::{Parser, ValueEnum};
 #[derive(Parser)]
 #[command(name = "pb", about = "Convert any document to text, HTML, or Markdown")]
 pub struct Cli {
-	/// Input document file
+	/// Input document file, or a folder holding a multi-file HTML book
 	pub input: PathBuf,
 	/// Output format
 	#[arg(short, long, default_value = "text")]
@@ -22,6 +22,9 @@ pub struct Cli {
 	/// Exit with code 2 instead of prompting for a password (useful for batch processing)
 	#[arg(long)]
 	pub no_prompt: bool,
+	/// Maximum number of files to read when following links through a multi-file HTML book
+	#[arg(long)]
+	pub max_files: Option<usize>,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -1,11 +1,11 @@
-use std::{fs, process};
+use std::{borrow::Cow, fs, process};
 
 use anyhow::{Context, Result, bail};
 use clap::Parser;
 use paperback_core::{
 	document::{Document, ParserContext},
 	export::{self, ExportFormat},
-	parser::{self, PASSWORD_REQUIRED_ERROR_PREFIX, parse_document},
+	parser::{self, error::ParserError, html::HTML_BOOK_FORMAT, parse_document},
 };
 
 mod cli;
@@ -14,8 +14,9 @@ use cli::{Cli, Format};
 
 fn main() -> Result<()> {
 	let cli = Cli::parse();
+	let is_folder = cli.input.is_dir();
 	let ext = cli.input.extension().and_then(|e| e.to_str()).unwrap_or("");
-	if !parser::parser_supports_extension(ext) {
+	if !is_folder && !parser::parser_supports_extension(ext) {
 		bail!("unsupported file format: .{ext}");
 	}
 	let file_path = cli.input.to_string_lossy().into_owned();
@@ -31,12 +32,18 @@ fn main() -> Result<()> {
 		};
 	}
 	let mut context = ParserContext::new(file_path).with_render_tables_inline(true);
+	if is_folder {
+		context = context.with_forced_extension(HTML_BOOK_FORMAT.to_string());
+	}
+	if let Some(max_files) = cli.max_files {
+		context = context.with_max_linked_files(max_files);
+	}
 	if let Some(password) = cli.password {
 		context = context.with_password(password);
 	}
 	let doc = match parse_document(&context) {
 		Ok(doc) => doc,
-		Err(e) if e.to_string().starts_with(PASSWORD_REQUIRED_ERROR_PREFIX) => {
+		Err(ParserError::PasswordRequired(_)) => {
 			if cli.no_prompt {
 				eprintln!("pb: document requires a password; skipping (use -p to supply one)");
 				process::exit(2);
@@ -45,12 +52,12 @@ fn main() -> Result<()> {
 			context.password = Some(password);
 			parse_document(&context).with_context(|| format!("failed to parse {}", cli.input.display()))?
 		}
-		Err(e) => return Err(e.context(format!("failed to parse {}", cli.input.display()))),
+		Err(e) => return Err(e).with_context(|| format!("failed to parse {}", cli.input.display())),
 	};
 	let handle = paperback_core::document::DocumentHandle::new(doc);
 	let is_markdown = !cli.metadata && matches!(cli.format, Format::Markdown);
 	let result = if cli.metadata {
-		metadata(handle.document())
+		Cow::Owned(metadata(handle.document()))
 	} else {
 		let format = match cli.format {
 			Format::Text => ExportFormat::Text,
@@ -67,7 +74,7 @@ fn main() -> Result<()> {
 				bytes.extend_from_slice(result.as_bytes());
 				fs::write(&path, &bytes)
 			} else {
-				fs::write(&path, &result)
+				fs::write(&path, result.as_bytes())
 			}
 			.with_context(|| format!("failed to write {}", path.display()))
 		}
@@ -86,6 +93,10 @@ fn metadata(doc: &Document) -> String {
 	if !doc.author.is_empty() {
 		out.push_str(&format!("Author: {}\n", doc.author));
 	}
+	for (field, value) in doc.metadata_entries() {
+		let key = field.key();
+		out.push_str(&format!("{}{}: {value}\n", key[..1].to_uppercase(), &key[1..]));
+	}
 	out.push_str(&format!("Words: {}\n", doc.stats.word_count));
 	out.push_str(&format!("Characters: {}\n", doc.stats.char_count));
 	out.push_str(&format!("Lines: {}\n", doc.stats.line_count));